@@ -59,7 +59,23 @@ impl<K: AsPrimitive<usize> + FromPrimitive + Zero> StringDictionary<K> {
 
     /// Returns the id corresponding to value, adding an entry for the
     /// id if it is not yet present in the dictionary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dictionary is full, see
+    /// [`Self::try_lookup_value_or_insert()`].
     pub fn lookup_value_or_insert(&mut self, value: &str) -> K {
+        self.try_lookup_value_or_insert(value)
+            .expect("failed to fit string index into dictionary key")
+    }
+
+    /// Returns the id corresponding to value, adding an entry for the
+    /// id if it is not yet present in the dictionary.
+    ///
+    /// Returns [`None`] if `value` is not present and the dictionary is full,
+    /// i.e. either the id of `value` or the offset of its end in the string
+    /// storage does not fit into `K`.
+    pub fn try_lookup_value_or_insert(&mut self, value: &str) -> Option<K> {
         use hashbrown::hash_map::RawEntryMut;
 
         let hasher = &self.hash;
@@ -72,17 +88,18 @@ impl<K: AsPrimitive<usize> + FromPrimitive + Zero> StringDictionary<K> {
             .from_hash(hash, |key| value == storage.get(key.as_()).unwrap());
 
         match entry {
-            RawEntryMut::Occupied(entry) => *entry.into_key(),
+            RawEntryMut::Occupied(entry) => Some(*entry.into_key()),
             RawEntryMut::Vacant(entry) => {
-                let index = storage.append(value);
-                let key =
-                    K::from_usize(index).expect("failed to fit string index into dictionary key");
-                *entry
-                    .insert_with_hasher(hash, key, (), |key| {
-                        let string = storage.get(key.as_()).unwrap();
-                        hash_str(hasher, string)
-                    })
-                    .0
+                let key = K::from_usize(storage.len())?;
+                storage.try_append(value)?;
+                Some(
+                    *entry
+                        .insert_with_hasher(hash, key, (), |key| {
+                            let string = storage.get(key.as_()).unwrap();
+                            hash_str(hasher, string)
+                        })
+                        .0,
+                )
             }
         }
     }
@@ -244,6 +261,29 @@ mod test {
         assert_eq!(arrow_expected, arrow_actual);
     }
 
+    #[test]
+    fn test_full_dictionary() {
+        // The string storage of an i8 dictionary holds at most 127 bytes.
+        let mut dictionary = StringDictionary::<i8>::new();
+
+        let a = dictionary.try_lookup_value_or_insert(&"a".repeat(100));
+        assert_eq!(a, Some(0));
+
+        // Values that do not fit are rejected, without modifying the
+        // dictionary.
+        assert_eq!(dictionary.try_lookup_value_or_insert(&"b".repeat(28)), None);
+        assert_eq!(dictionary.values().len(), 1);
+        assert!(dictionary.lookup_value(&"b".repeat(28)).is_none());
+
+        // Existing values can still be looked up, and smaller values still
+        // fit.
+        assert_eq!(dictionary.try_lookup_value_or_insert(&"a".repeat(100)), a);
+        assert_eq!(
+            dictionary.try_lookup_value_or_insert(&"b".repeat(27)),
+            Some(1)
+        );
+    }
+
     #[test]
     fn from_string_array() {
         let mut data = PackedStringArray::<u64>::new();
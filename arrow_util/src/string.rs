@@ -53,15 +53,25 @@ impl<K: AsPrimitive<usize> + FromPrimitive + Zero> PackedStringArray<K> {
     ///
     /// Returns the index of the appended data
     pub fn append(&mut self, data: &str) -> usize {
+        self.try_append(data)
+            .expect("failed to fit into offset type")
+    }
+
+    /// Append a value, unless the offset of its end does not fit into the
+    /// offset type.
+    ///
+    /// Returns the index of the appended data, or [`None`] if it was not
+    /// appended.
+    pub fn try_append(&mut self, data: &str) -> Option<usize> {
         let id = self.offsets.len() - 1;
 
         let offset = self.storage.len() + data.len();
-        let offset = K::from_usize(offset).expect("failed to fit into offset type");
+        let offset = K::from_usize(offset)?;
 
         self.offsets.push(offset);
         self.storage.push_str(data);
 
-        id
+        Some(id)
     }
 
     /// Extends this [`PackedStringArray`] by the contents of `other`
@@ -235,7 +235,8 @@ static REGEX_TIMING: Lazy<Regex> =
 ///
 /// Should be used in combination w/ [`REGEX_TIME_OP`].
 static REGEX_FILTER: Lazy<Regex> = Lazy::new(|| {
-    Regex::new("(?P<prefix>(FilterExec)|(ParquetExec): )(?P<expr>.*)").expect("filter regex")
+    Regex::new("(?P<prefix>((FilterExec)|(ParquetExec)|(RecordBatchesExec)): )(?P<expr>.*)")
+        .expect("filter regex")
 });
 
 /// Matches things like `time@3 < -9223372036854775808` and `time_min@2 > 1641031200399937022`
@@ -372,10 +373,12 @@ impl Normalizer {
         // Converts:
         // FilterExec: time@2 < -9223372036854775808 OR time@2 > 1640995204240217000
         // ParquetExec: limit=None, partitions={...}, predicate=time@2 > 1640995204240217000, pruning_predicate=time@2 > 1640995204240217000, output_ordering=[...], projection=[...]
+        // RecordBatchesExec: chunks=1, predicate=time@0 > 1640995204240217000
         //
         // to
         // FilterExec: time@2 < <REDACTED> OR time@2 > <REDACTED>
         // ParquetExec: limit=None, partitions={...}, predicate=time@2 > <REDACTED>, pruning_predicate=time@2 > <REDACTED>, output_ordering=[...], projection=[...]
+        // RecordBatchesExec: chunks=1, predicate=time@0 > <REDACTED>
         if self.normalized_filters {
             current_results = current_results
                 .into_iter()
@@ -5,7 +5,8 @@ use std::sync::Arc;
 
 use arrow::{
     array::{new_null_array, ArrayRef, StringArray},
-    datatypes::SchemaRef,
+    compute::{is_not_null, kernels::zip::zip},
+    datatypes::{Field, Schema, SchemaRef},
     error::ArrowError,
     record_batch::RecordBatch,
 };
@@ -55,3 +56,87 @@ pub fn ensure_schema(
 
     RecordBatch::try_new(Arc::clone(output_schema), batch_output_columns)
 }
+
+/// Renames the columns of `batch` to the names returned by `rename`, dropping the columns it returns `None` for.
+///
+/// Columns renamed to the same name are merged into one column (at the position of the first of them), which holds
+/// the first non-null value of these columns for each row. Merged columns must have the same data type.
+pub fn rename_columns<F>(batch: &RecordBatch, rename: F) -> Result<RecordBatch, ArrowError>
+where
+    F: Fn(&Field) -> Option<String>,
+{
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = vec![];
+    let mut columns: Vec<ArrayRef> = vec![];
+
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        let Some(name) = rename(field) else {
+            continue;
+        };
+
+        match fields.iter().position(|f| f.name() == &name) {
+            Some(idx) => {
+                columns[idx] = zip(&is_not_null(&columns[idx])?, &columns[idx], column)?;
+                fields[idx] = fields[idx].clone().with_nullable(true);
+            }
+            None => {
+                fields.push(Field::clone(field).with_name(name));
+                columns.push(Arc::clone(column));
+            }
+        }
+    }
+
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Int64Array;
+
+    use super::*;
+    use crate::assert_batches_eq;
+
+    #[test]
+    fn test_rename_columns() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "a",
+                Arc::new(Int64Array::from(vec![Some(1), None, None])) as ArrayRef,
+            ),
+            (
+                "b",
+                Arc::new(StringArray::from(vec!["x", "y", "z"])) as ArrayRef,
+            ),
+            (
+                "c",
+                Arc::new(Int64Array::from(vec![Some(4), Some(5), None])) as ArrayRef,
+            ),
+            ("d", Arc::new(Int64Array::from(vec![7, 8, 9])) as ArrayRef),
+        ])
+        .unwrap();
+
+        let renamed = rename_columns(&batch, |field| match field.name().as_str() {
+            "a" => Some("a".to_string()),
+            "b" => Some("s".to_string()),
+            "c" => Some("a".to_string()),
+            _ => None,
+        })
+        .unwrap();
+
+        assert_batches_eq!(
+            [
+                "+---+---+",
+                "| a | s |",
+                "+---+---+",
+                "| 1 | x |",
+                "| 5 | y |",
+                "|   | z |",
+                "+---+---+",
+            ],
+            &[renamed]
+        );
+    }
+}
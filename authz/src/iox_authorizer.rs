@@ -76,7 +76,8 @@ impl Authorizer for IoxAuthorizer {
             })
             .collect();
 
-        if intersected_perms.is_empty() {
+        // tag filter policies only restrict access
+        if !intersected_perms.iter().any(Permission::grants_access) {
             return Err(Error::Forbidden);
         }
         Ok(intersected_perms)
@@ -208,6 +209,47 @@ mod test {
         want = Ok(_)
     );
 
+    #[tokio::test]
+    async fn test_tag_filter_policies() {
+        let mut authz_server = AuthorizerServer::create().await;
+        let authz = IoxAuthorizer::connect_lazy(authz_server.addr())
+            .expect("Failed to create IoxAuthorizer client.");
+
+        let token = authz_server.create_token_for(NAMESPACE, &["ACTION_READ"]);
+        authz_server.add_tag_filter(&token, NAMESPACE, "tenant_id", &["acme"]);
+
+        let got = authz
+            .permissions(
+                Some(token.as_bytes().to_vec()),
+                &[Permission::ResourceAction(
+                    Resource::Database(NAMESPACE.to_string()),
+                    Action::Read,
+                )],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            crate::tag_filter_policies(&got, NAMESPACE),
+            vec![crate::TagFilterPolicy {
+                database: NAMESPACE.to_string(),
+                tag: "tenant_id".to_string(),
+                values: vec!["acme".to_string()],
+            }]
+        );
+
+        // the policy alone does not grant access
+        let got = authz
+            .permissions(
+                Some(token.as_bytes().to_vec()),
+                &[Permission::ResourceAction(
+                    Resource::Database(NAMESPACE.to_string()),
+                    Action::Write,
+                )],
+            )
+            .await;
+        assert_matches!(got, Err(Error::Forbidden));
+    }
+
     #[tokio::test]
     async fn test_invalid_token() {
         let authz_server = AuthorizerServer::create().await;
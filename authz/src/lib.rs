@@ -32,7 +32,7 @@ pub use iox_authorizer::{Error, IoxAuthorizer};
 mod instrumentation;
 pub use instrumentation::AuthorizerInstrumentation;
 mod permission;
-pub use permission::{Action, Permission, Resource};
+pub use permission::{tag_filter_policies, Action, Permission, Resource, TagFilterPolicy};
 
 #[cfg(feature = "http")]
 pub mod http;
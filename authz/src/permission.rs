@@ -66,6 +66,54 @@ pub enum Permission {
     /// ResourceAction is a permission in the form of a reasource and an
     /// action.
     ResourceAction(Resource, Action),
+    /// TagFilter restricts the rows of a database that can be accessed.
+    /// It is attached to a token by the authorizer and never grants access
+    /// by itself.
+    TagFilter(TagFilterPolicy),
+}
+
+impl Permission {
+    /// Returns true if this permission grants access to a resource, i.e.
+    /// it is not just a restriction.
+    pub fn grants_access(&self) -> bool {
+        match self {
+            Self::ResourceAction(_, _) => true,
+            Self::TagFilter(_) => false,
+        }
+    }
+}
+
+/// A row-level restriction attached to a token: only rows of `database`
+/// whose tag `tag` has one of `values` may be read or written. Rows
+/// without the tag are inaccessible.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagFilterPolicy {
+    /// Name of the database the policy applies to.
+    pub database: String,
+    /// Name of the filtered tag.
+    pub tag: String,
+    /// Allowed values of the tag.
+    pub values: Vec<String>,
+}
+
+impl TagFilterPolicy {
+    /// Returns true if `value` of the tag is allowed by this policy.
+    pub fn allows(&self, value: &str) -> bool {
+        self.values.iter().any(|v| v == value)
+    }
+}
+
+/// The [`TagFilterPolicy`]s of `perms` that apply to `database`.
+///
+/// All returned policies must be satisfied by a row to be accessible.
+pub fn tag_filter_policies(perms: &[Permission], database: &str) -> Vec<TagFilterPolicy> {
+    perms
+        .iter()
+        .filter_map(|p| match p {
+            Permission::TagFilter(policy) if policy.database == database => Some(policy.clone()),
+            _ => None,
+        })
+        .collect()
 }
 
 impl TryFrom<proto::Permission> for Permission {
@@ -85,6 +133,16 @@ impl TryFrom<proto::Permission> for Permission {
                 )?;
                 Ok(Self::ResourceAction(r, a))
             }
+            Some(proto::permission::PermissionOneOf::TagFilter(tf)) => {
+                if tf.database.is_empty() || tf.tag.is_empty() {
+                    return Err(IncompatiblePermissionError {});
+                }
+                Ok(Self::TagFilter(TagFilterPolicy {
+                    database: tf.database,
+                    tag: tf.tag,
+                    values: tf.values,
+                }))
+            }
             _ => Err(IncompatiblePermissionError {}),
         }
     }
@@ -108,6 +166,15 @@ impl TryFrom<Permission> for proto::Permission {
                     )),
                 })
             }
+            Permission::TagFilter(policy) => Ok(Self {
+                permission_one_of: Some(proto::permission::PermissionOneOf::TagFilter(
+                    proto::TagFilterPolicy {
+                        database: policy.database,
+                        tag: policy.tag,
+                        values: policy.values,
+                    },
+                )),
+            }),
         }
     }
 }
@@ -117,6 +184,9 @@ impl TryFrom<Permission> for proto::Permission {
 pub enum Resource {
     /// A database is a named IOx database.
     Database(String),
+    /// A tenant is a named owner of IOx databases. A permission on a tenant
+    /// applies to all of its databases.
+    Tenant(String),
 }
 
 impl Resource {
@@ -128,6 +198,9 @@ impl Resource {
             (proto::resource_action_permission::ResourceType::Database, Some(s)) => {
                 Ok(Self::Database(s))
             }
+            (proto::resource_action_permission::ResourceType::Tenant, Some(s)) => {
+                Ok(Self::Tenant(s))
+            }
             _ => Err(IncompatiblePermissionError {}),
         }
     }
@@ -146,6 +219,10 @@ impl Resource {
                 proto::resource_action_permission::ResourceType::Database,
                 Some(s),
             )),
+            Self::Tenant(s) => Ok((
+                proto::resource_action_permission::ResourceType::Tenant,
+                Some(s),
+            )),
         }
     }
 }
@@ -224,6 +301,22 @@ mod tests {
             )
             .unwrap_err()
         );
+        assert_eq!(
+            Resource::Tenant("acme".into()),
+            Resource::try_from_proto(
+                proto::resource_action_permission::ResourceType::Tenant,
+                Some("acme".into())
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            IncompatiblePermissionError {},
+            Resource::try_from_proto(
+                proto::resource_action_permission::ResourceType::Tenant,
+                None
+            )
+            .unwrap_err()
+        );
         assert_eq!(
             IncompatiblePermissionError {},
             Resource::try_from_proto(
@@ -243,6 +336,13 @@ mod tests {
             ),
             Resource::Database("ns1".into()).try_into_proto().unwrap(),
         );
+        assert_eq!(
+            (
+                proto::resource_action_permission::ResourceType::Tenant,
+                Some("acme".into())
+            ),
+            Resource::Tenant("acme".into()).try_into_proto().unwrap(),
+        );
     }
 
     #[test]
@@ -288,6 +388,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tag_filter_try_from_proto() {
+        assert_eq!(
+            Permission::TagFilter(TagFilterPolicy {
+                database: "ns1".into(),
+                tag: "tenant_id".into(),
+                values: vec!["acme".into()],
+            }),
+            Permission::try_from(proto::Permission {
+                permission_one_of: Some(proto::permission::PermissionOneOf::TagFilter(
+                    proto::TagFilterPolicy {
+                        database: "ns1".into(),
+                        tag: "tenant_id".into(),
+                        values: vec!["acme".into()],
+                    }
+                ))
+            })
+            .unwrap()
+        );
+        assert_eq!(
+            IncompatiblePermissionError {},
+            Permission::try_from(proto::Permission {
+                permission_one_of: Some(proto::permission::PermissionOneOf::TagFilter(
+                    proto::TagFilterPolicy {
+                        database: "ns1".into(),
+                        tag: "".into(),
+                        values: vec!["acme".into()],
+                    }
+                ))
+            })
+            .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn tag_filter_policies_for_database() {
+        let policy = |database: &str, tag: &str| TagFilterPolicy {
+            database: database.into(),
+            tag: tag.into(),
+            values: vec!["acme".into()],
+        };
+        let perms = vec![
+            Permission::ResourceAction(Resource::Database("ns1".into()), Action::Read),
+            Permission::TagFilter(policy("ns1", "tenant_id")),
+            Permission::TagFilter(policy("ns2", "tenant_id")),
+            Permission::TagFilter(policy("ns1", "region")),
+        ];
+
+        assert_eq!(
+            tag_filter_policies(&perms, "ns1"),
+            vec![policy("ns1", "tenant_id"), policy("ns1", "region")]
+        );
+        assert_eq!(tag_filter_policies(&perms, "ns3"), vec![]);
+        assert!(policy("ns1", "tenant_id").allows("acme"));
+        assert!(!policy("ns1", "tenant_id").allows("globex"));
+    }
+
     #[test]
     fn permission_try_into_proto() {
         assert_eq!(
@@ -595,6 +595,13 @@ where
                 "Number of entries that were evicted from a given LRU cache pool member",
             )
             .recorder(&[("pool", pool.name), ("member", id)]);
+        let metric_evicted_last_used = pool
+            .metric_registry
+            .register_metric::<U64Gauge>(
+                "cache_lru_member_evicted_last_used_seconds",
+                "Time (in seconds since the UNIX epoch) the most recently used entry evicted from a given LRU cache pool member was last used",
+            )
+            .recorder(&[("pool", pool.name), ("member", id)]);
 
         move |mut callback_handle| {
             callback_handle.execute_requests(vec![ChangeRequest::ensure_empty()]);
@@ -603,6 +610,7 @@ where
                 id,
                 last_used: Arc::new(Mutex::new(AddressableHeap::new())),
                 metric_evicted,
+                metric_evicted_last_used,
                 callback_handle: Mutex::new(callback_handle),
             });
 
@@ -740,10 +748,10 @@ trait PoolMember: Debug + Send + Sync + 'static {
     /// Elements are returned in order of the "last used" timestamp, in increasing order.
     fn could_remove(&self) -> PoolMemberCouldRemove<Self::S>;
 
-    /// Remove given set of keys.
+    /// Remove given set of keys, the most recently used of which was last used at `last_used`.
     ///
     /// The keys MUST be a result of [`could_remove`](Self::could_remove), otherwise the downcasting may not work and panic.
-    fn remove_keys(&self, keys: Vec<Box<dyn Any>>);
+    fn remove_keys(&self, keys: Vec<Box<dyn Any>>, last_used: Time);
 }
 
 /// The only implementation of [`PoolMember`].
@@ -762,6 +770,11 @@ where
     /// Count number of evicted items.
     metric_evicted: U64Counter,
 
+    /// "Last used" timestamp of the most recently used evicted item.
+    ///
+    /// If this is close to "now", entries are evicted shortly after being used and the pool is likely too small.
+    metric_evicted_last_used: U64Gauge,
+
     /// Tracks usage of the last used elements.
     ///
     /// See documentation of [`callback_handle`](Self::callback_handle) for a reasoning about locking.
@@ -796,7 +809,7 @@ where
         it::build_it(self.last_used.lock_arc())
     }
 
-    fn remove_keys(&self, keys: Vec<Box<dyn Any>>) {
+    fn remove_keys(&self, keys: Vec<Box<dyn Any>>, last_used: Time) {
         let keys = keys
             .into_iter()
             .map(|k| *k.downcast::<K>().expect("wrong type"))
@@ -808,6 +821,8 @@ where
             "evicting cache entries due to LRU pressure",
         );
         self.metric_evicted.inc(keys.len() as u64);
+        self.metric_evicted_last_used
+            .set(last_used.timestamp().max(0) as u64);
 
         let combined = ChangeRequest::from_fn(move |backend| {
             for k in keys {
@@ -932,7 +947,9 @@ async fn clean_up_loop<S>(
         }
 
         // select victims
-        let mut victims: BTreeMap<&'static str, Vec<Box<dyn Any>>> = Default::default();
+        // Candidates are returned in order of their "last used" timestamp, so the timestamp of the last victim of each
+        // member is the most recent one.
+        let mut victims: BTreeMap<&'static str, (Time, Vec<Box<dyn Any>>)> = Default::default();
         {
             trace!(
                 current = current.into(),
@@ -960,7 +977,10 @@ async fn clean_up_loop<S>(
                             "found victim"
                         );
                         current = current - s;
-                        victims.entry(candidate.id).or_default().push(k);
+                        let (last_used, keys) =
+                            victims.entry(candidate.id).or_insert_with(|| (t, vec![]));
+                        *last_used = t;
+                        keys.push(k);
                     }
                     None => {
                         // The custom `Ord` implementation ensures that we prefer iterators with data over iterators
@@ -981,9 +1001,9 @@ async fn clean_up_loop<S>(
             trace!("done selecting eviction victims");
         }
 
-        for (id, keys) in victims {
+        for (id, (last_used, keys)) in victims {
             let member = members.get(id).expect("did get this ID from this map");
-            member.remove_keys(keys);
+            member.remove_keys(keys, last_used);
         }
     }
 }
@@ -1630,7 +1650,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_metrics() {
-        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp(1, 0).unwrap()));
         let metric_registry = Arc::new(metric::Registry::new());
         let pool = Arc::new(ResourcePool::new(
             "pool",
@@ -1659,7 +1679,7 @@ mod tests {
             &Observation::U64Gauge(0)
         );
 
-        let mut backend = PolicyBackend::hashmap_backed(time_provider);
+        let mut backend = PolicyBackend::hashmap_backed(Arc::clone(&time_provider) as _);
         backend.add_policy(LruPolicy::new(
             Arc::clone(&pool),
             "id",
@@ -1708,9 +1728,18 @@ mod tests {
                 .unwrap(),
             &Observation::U64Counter(0)
         );
+        assert_eq!(
+            reporter
+                .metric("cache_lru_member_evicted_last_used_seconds")
+                .unwrap()
+                .observation(&[("pool", "pool"), ("member", "id")])
+                .unwrap(),
+            &Observation::U64Gauge(0)
+        );
 
         backend.set(String::from("a"), 1usize); // usage = 1
         pool.wait_converged().await;
+        time_provider.inc(Duration::from_secs(1));
         backend.set(String::from("b"), 2usize); // usage = 3
         pool.wait_converged().await;
         backend.set(String::from("b"), 3usize); // usage = 4
@@ -1764,6 +1793,15 @@ mod tests {
                 .unwrap(),
             &Observation::U64Counter(1)
         );
+        assert_eq!(
+            reporter
+                .metric("cache_lru_member_evicted_last_used_seconds")
+                .unwrap()
+                .observation(&[("pool", "pool"), ("member", "id")])
+                .unwrap(),
+            // "a" was last used at 1s, before the clock advanced
+            &Observation::U64Gauge(1)
+        );
     }
 
     /// A note regarding the test flavor:
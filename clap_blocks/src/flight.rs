@@ -0,0 +1,102 @@
+//! CLI config for the Arrow Flight query API.
+
+use std::{num::NonZeroUsize, time::Duration};
+
+use crate::memory_size::MemorySize;
+
+/// CLI config for the Arrow Flight query API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::Parser)]
+pub struct FlightConfig {
+    /// How long the unread results of a paginated Flight query are kept, waiting for the client
+    /// to fetch the next page.
+    ///
+    /// The query is cancelled if the next page is not fetched within this time.
+    #[clap(
+        long = "flight-cursor-ttl",
+        env = "INFLUXDB_IOX_FLIGHT_CURSOR_TTL",
+        default_value = "5m",
+        value_parser = humantime::parse_duration,
+        action
+    )]
+    pub cursor_ttl: Duration,
+
+    /// Limit the memory used by the unread results of paginated Flight queries, in bytes.
+    ///
+    /// Includes the results buffered between pages and the memory reported as used by the
+    /// still running queries.
+    ///
+    /// Pages of paginated queries that would exceed this limit fail with a "resource
+    /// exhausted" error.
+    ///
+    /// Can be given as absolute value or in percentage of the total available memory (e.g. `10%`).
+    #[clap(
+        long = "flight-cursor-memory-bytes",
+        env = "INFLUXDB_IOX_FLIGHT_CURSOR_MEMORY_BYTES",
+        default_value = "268435456",  // 256MB
+        action
+    )]
+    pub cursor_memory_bytes: MemorySize,
+
+    /// Limit the number of rows returned by a single Flight query.
+    ///
+    /// Results exceeding this limit are truncated instead of failing the query: the rows up to
+    /// the limit are returned and flagged as partial. Unlimited if not set.
+    #[clap(
+        long = "flight-max-result-rows",
+        env = "INFLUXDB_IOX_FLIGHT_MAX_RESULT_ROWS",
+        action
+    )]
+    pub max_result_rows: Option<NonZeroUsize>,
+
+    /// Limit the size of the results returned by a single Flight query, in bytes.
+    ///
+    /// Results exceeding this limit are truncated instead of failing the query: the results up
+    /// to the limit are returned and flagged as partial. Unlimited if not set.
+    ///
+    /// Can be given as absolute value or in percentage of the total available memory (e.g. `10%`).
+    #[clap(
+        long = "flight-max-result-bytes",
+        env = "INFLUXDB_IOX_FLIGHT_MAX_RESULT_BYTES",
+        action
+    )]
+    pub max_result_bytes: Option<MemorySize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_default() {
+        let actual = FlightConfig::try_parse_from(["my_binary"]).unwrap();
+
+        assert_eq!(actual.cursor_ttl, Duration::from_secs(5 * 60));
+        assert_eq!(actual.cursor_memory_bytes.bytes(), 256 * 1024 * 1024);
+        assert_eq!(actual.max_result_rows, None);
+        assert_eq!(actual.max_result_bytes, None);
+    }
+
+    #[test]
+    fn test_cursor_ttl() {
+        let actual =
+            FlightConfig::try_parse_from(["my_binary", "--flight-cursor-ttl", "30s"]).unwrap();
+
+        assert_eq!(actual.cursor_ttl, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_max_result() {
+        let actual = FlightConfig::try_parse_from([
+            "my_binary",
+            "--flight-max-result-rows",
+            "1000",
+            "--flight-max-result-bytes",
+            "1048576",
+        ])
+        .unwrap();
+
+        assert_eq!(actual.max_result_rows, NonZeroUsize::new(1000));
+        assert_eq!(actual.max_result_bytes.map(|b| b.bytes()), Some(1048576));
+    }
+}
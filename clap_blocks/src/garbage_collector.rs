@@ -82,4 +82,19 @@ pub struct GarbageCollectorConfig {
         env = "INFLUXDB_IOX_GC_RETENTION_SLEEP_INTERVAL_MINUTES"
     )]
     pub retention_sleep_interval_minutes: u64,
+
+    /// If this flag is specified, periodically read every parquet file referenced by the catalog
+    /// and compare its content with the hash recorded when it was created, logging an error for
+    /// each corrupted or modified file.
+    #[clap(long, env = "INFLUXDB_IOX_GC_CHECKSUM_VERIFICATION")]
+    pub checksum_verification: bool,
+
+    /// Number of minutes to sleep between iterations of the checksum verification code.
+    /// Defaults to 1 day, as every pass reads all the parquet files in object storage.
+    #[clap(
+        long,
+        default_value_t = 1440,
+        env = "INFLUXDB_IOX_GC_CHECKSUM_SLEEP_INTERVAL_MINUTES"
+    )]
+    pub checksum_sleep_interval_minutes: u64,
 }
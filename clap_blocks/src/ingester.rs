@@ -1,9 +1,28 @@
 //! CLI config for the ingester using the RPC write path
 
-use std::{num::NonZeroUsize, path::PathBuf};
+use std::{
+    num::{NonZeroU32, NonZeroUsize},
+    path::PathBuf,
+};
 
 use crate::gossip::GossipConfig;
 
+/// How the ingester responds to writes while applying backpressure.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackpressureResponse {
+    /// Reject writes with a retryable "resource exhausted" error (the
+    /// equivalent of an HTTP 429).
+    #[default]
+    Throttle,
+
+    /// Reject writes with an "unavailable" error.
+    Reject,
+
+    /// Hold writes until the backpressure is lifted, up to the configured
+    /// maximum delay, before throttling them.
+    Delay,
+}
+
 /// CLI config for the ingester using the RPC write path
 #[derive(Debug, Clone, clap::Parser)]
 #[allow(missing_copy_implementations)]
@@ -35,6 +54,65 @@ pub struct IngesterConfig {
     )]
     pub wal_rotation_period_seconds: u64,
 
+    /// The size in bytes at which the open WAL segment file is rotated and
+    /// the buffered data persisted, before the rotation period elapses.
+    ///
+    /// This bounds the WAL data replayed after a crash when writes arrive
+    /// faster than the rotation period can absorb. Disabled by default.
+    #[clap(
+        long = "wal-max-segment-bytes",
+        env = "INFLUXDB_IOX_WAL_MAX_SEGMENT_BYTES"
+    )]
+    pub wal_max_segment_bytes: Option<u64>,
+
+    /// The maximum number of WAL ops applied per second when replaying the
+    /// WAL at startup.
+    ///
+    /// Limiting the replay rate reduces the CPU and memory pressure of a large
+    /// replay, at the cost of a longer startup. Unlimited by default.
+    #[clap(
+        long = "wal-replay-max-ops-per-second",
+        env = "INFLUXDB_IOX_WAL_REPLAY_MAX_OPS_PER_SECOND"
+    )]
+    pub wal_replay_max_ops_per_second: Option<NonZeroU32>,
+
+    /// Where this ingester instance should write periodic snapshots of its
+    /// buffered data, used to speed up WAL replay at startup.
+    ///
+    /// Each ingester instance must have its own directory. Disabled by
+    /// default.
+    #[clap(
+        long = "buffer-snapshot-directory",
+        env = "INFLUXDB_IOX_BUFFER_SNAPSHOT_DIRECTORY",
+        action
+    )]
+    pub buffer_snapshot_directory: Option<PathBuf>,
+
+    /// The number of seconds between snapshots of the buffered data, when
+    /// "--buffer-snapshot-directory" is set.
+    #[clap(
+        long = "buffer-snapshot-period-seconds",
+        env = "INFLUXDB_IOX_BUFFER_SNAPSHOT_PERIOD_SECONDS",
+        default_value = "60",
+        action
+    )]
+    pub buffer_snapshot_period_seconds: u64,
+
+    /// Encrypt the WAL and buffer snapshot files with the keys in this file.
+    ///
+    /// The file contains one `<key ID>=<hex encoded 256 bit key>` entry per
+    /// line. New files are encrypted with the key in the last entry; the
+    /// other keys are only used to read files written before the key was
+    /// rotated. Lines starting with `#` are ignored.
+    ///
+    /// Files written before encryption was enabled remain readable.
+    #[clap(
+        long = "wal-encryption-key-file",
+        env = "INFLUXDB_IOX_WAL_ENCRYPTION_KEY_FILE",
+        action
+    )]
+    pub wal_encryption_key_file: Option<PathBuf>,
+
     /// Sets how many queries the ingester will handle simultaneously before
     /// rejecting further incoming requests.
     #[clap(
@@ -85,4 +163,121 @@ pub struct IngesterConfig {
         env = "INFLUXDB_IOX_MAX_PARTITIONS_PER_NAMESPACE"
     )]
     pub max_partitions_per_namespace: Option<NonZeroUsize>,
+
+    /// The estimated number of bytes of data buffered for a single namespace
+    /// (across all tables) at which all of its partitions are queued for
+    /// persistence.
+    ///
+    /// This limit is disabled by default.
+    #[clap(
+        long = "namespace-buffer-soft-limit-bytes",
+        env = "INFLUXDB_IOX_NAMESPACE_BUFFER_SOFT_LIMIT_BYTES"
+    )]
+    pub namespace_buffer_soft_limit_bytes: Option<usize>,
+
+    /// The estimated number of bytes of data buffered for a single namespace
+    /// (across all tables) at which writes to it are rejected, until
+    /// persistence brings it back under the limit.
+    ///
+    /// Requires a lower `--namespace-buffer-soft-limit-bytes`, which persists
+    /// the namespace. This limit is disabled by default.
+    #[clap(
+        long = "namespace-buffer-hard-limit-bytes",
+        env = "INFLUXDB_IOX_NAMESPACE_BUFFER_HARD_LIMIT_BYTES",
+        requires = "namespace_buffer_soft_limit_bytes"
+    )]
+    pub namespace_buffer_hard_limit_bytes: Option<usize>,
+
+    /// The estimated number of bytes of data buffered (and not yet
+    /// persisting) across all namespaces at which backpressure is applied to
+    /// writes, and the largest partitions are queued for persistence.
+    ///
+    /// If IOx is built with the "heap_tracking" feature, the measured heap
+    /// usage of the buffered data (including data being persisted) is used
+    /// instead of the estimate.
+    ///
+    /// This limit is disabled by default.
+    #[clap(
+        long = "buffer-hard-limit-bytes",
+        env = "INFLUXDB_IOX_BUFFER_HARD_LIMIT_BYTES"
+    )]
+    pub buffer_hard_limit_bytes: Option<usize>,
+
+    /// How writes are handled while the ingester is applying backpressure,
+    /// because it is over the buffer limit, the persist queue is saturated or
+    /// the disk is full.
+    #[clap(
+        value_enum,
+        long = "backpressure-response",
+        env = "INFLUXDB_IOX_BACKPRESSURE_RESPONSE",
+        default_value = "throttle",
+        action
+    )]
+    pub backpressure_response: BackpressureResponse,
+
+    /// The maximum number of milliseconds a write is held for when the
+    /// backpressure response is "delay", before it is throttled.
+    #[clap(
+        long = "backpressure-max-delay-millis",
+        env = "INFLUXDB_IOX_BACKPRESSURE_MAX_DELAY_MILLIS",
+        default_value = "1000",
+        action
+    )]
+    pub backpressure_max_delay_millis: u64,
+
+    /// The number of writes applied but not yet persisted above which the
+    /// ingester reports itself as degraded, failing its readiness check and
+    /// marking its write service as not serving in the gRPC health service.
+    ///
+    /// This threshold is disabled by default.
+    #[clap(
+        long = "persist-lag-max-unpersisted-writes",
+        env = "INFLUXDB_IOX_PERSIST_LAG_MAX_UNPERSISTED_WRITES"
+    )]
+    pub persist_lag_max_unpersisted_writes: Option<usize>,
+
+    /// The age in seconds of the oldest write applied but not yet persisted
+    /// above which the ingester reports itself as degraded.
+    ///
+    /// This threshold is disabled by default.
+    #[clap(
+        long = "persist-lag-max-age-seconds",
+        env = "INFLUXDB_IOX_PERSIST_LAG_MAX_AGE_SECONDS"
+    )]
+    pub persist_lag_max_age_seconds: Option<u64>,
+
+    /// A URL to POST a JSON notification to whenever a Parquet file is
+    /// persisted, describing its namespace, table, partition, object store
+    /// path, time range, size and row count.
+    ///
+    /// Notifications are delivered asynchronously and at most once.
+    /// Notifications are disabled by default.
+    #[clap(
+        long = "persist-notification-webhook-url",
+        env = "INFLUXDB_IOX_PERSIST_NOTIFICATION_WEBHOOK_URL"
+    )]
+    pub persist_notification_webhook_url: Option<String>,
+
+    /// Cache the last value of every field of every series written to each
+    /// partition, for up to this many series per partition.
+    ///
+    /// The cached values are retained after the buffered data is persisted
+    /// and can be queried in place of the buffered data. Values of series
+    /// beyond this limit are not cached.
+    ///
+    /// The cache is disabled by default.
+    #[clap(
+        long = "last-value-cache-max-series",
+        env = "INFLUXDB_IOX_LAST_VALUE_CACHE_MAX_SERIES"
+    )]
+    pub last_value_cache_max_series: Option<NonZeroUsize>,
+
+    /// Also cache the first value of every field of every series, when the
+    /// last value cache is enabled.
+    #[clap(
+        long = "last-value-cache-first-values",
+        env = "INFLUXDB_IOX_LAST_VALUE_CACHE_FIRST_VALUES",
+        action
+    )]
+    pub last_value_cache_first_values: bool,
 }
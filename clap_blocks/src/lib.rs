@@ -21,6 +21,7 @@ use workspace_hack as _;
 pub mod catalog_dsn;
 pub mod compactor;
 pub mod compactor_scheduler;
+pub mod flight;
 pub mod garbage_collector;
 pub mod gossip;
 pub mod ingester;
@@ -28,7 +29,9 @@ pub mod ingester_address;
 pub mod memory_size;
 pub mod object_store;
 pub mod querier;
+pub mod query_router;
 pub mod router;
 pub mod run_config;
+pub mod server_limits;
 pub mod single_tenant;
 pub mod socket_addr;
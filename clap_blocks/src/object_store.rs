@@ -1,12 +1,16 @@
 //! CLI handling for object store config (via CLI arguments and environment variables).
 
 use futures::TryStreamExt;
+use iox_time::TimeProvider;
 use object_store::memory::InMemory;
 use object_store::path::Path;
 use object_store::throttle::ThrottledStore;
 use object_store::{throttle::ThrottleConfig, DynObjectStore};
+use object_store_encryption::{EncryptedObjectStore, KeyError, StaticKeyProvider};
+use object_store_hedging::HedgedObjectStore;
+use object_store_replication::ReplicatedObjectStore;
 use observability_deps::tracing::{info, warn};
-use snafu::{ResultExt, Snafu};
+use snafu::{OptionExt, ResultExt, Snafu};
 use std::sync::Arc;
 use std::{fs, num::NonZeroUsize, path::PathBuf, time::Duration};
 use uuid::Uuid;
@@ -47,6 +51,12 @@ pub enum ParseError {
 
     #[snafu(display("Error configuring Microsoft Azure: {}", source))]
     InvalidAzureConfig { source: object_store::Error },
+
+    #[snafu(display("Error loading object store encryption keys: {}", source))]
+    InvalidEncryptionKeys { source: KeyError },
+
+    #[snafu(display("Cannot use the replica object store, --object-store-replica is not set"))]
+    MissingReplicaConfig,
 }
 
 /// The AWS region to use for Amazon S3 based object storage if none is
@@ -161,6 +171,48 @@ pub struct ObjectStoreConfig {
     #[clap(long = "aws-allow-http", env = "AWS_ALLOW_HTTP", action)]
     pub aws_allow_http: bool,
 
+    /// When using Amazon S3 as the object store, assume this IAM role to
+    /// access the bucket.
+    ///
+    /// The role is assumed using STS with the credentials given by
+    /// `--aws-access-key-id` and `--aws-secret-access-key` if set, or the
+    /// instance profile credentials otherwise. The temporary role credentials
+    /// are refreshed before they expire.
+    ///
+    /// Without this option, and without static credentials, credentials are
+    /// loaded (and refreshed) from the web identity token of an EKS service
+    /// account (IRSA, using `AWS_WEB_IDENTITY_TOKEN_FILE` and `AWS_ROLE_ARN`),
+    /// or the instance profile.
+    #[clap(long = "aws-role-arn", env = "INFLUXDB_IOX_AWS_ROLE_ARN", action)]
+    pub aws_role_arn: Option<String>,
+
+    /// The external ID to pass when assuming `--aws-role-arn`, if required by
+    /// the trust policy of the role.
+    #[clap(
+        long = "aws-role-external-id",
+        env = "INFLUXDB_IOX_AWS_ROLE_EXTERNAL_ID",
+        action
+    )]
+    pub aws_role_external_id: Option<String>,
+
+    /// The session name to use when assuming `--aws-role-arn`.
+    #[clap(
+        long = "aws-role-session-name",
+        env = "INFLUXDB_IOX_AWS_ROLE_SESSION_NAME",
+        default_value = "influxdb-iox",
+        action
+    )]
+    pub aws_role_session_name: String,
+
+    /// The STS endpoint to use when assuming `--aws-role-arn`. Defaults to the
+    /// regional endpoint of `--aws-default-region`.
+    #[clap(
+        long = "aws-sts-endpoint",
+        env = "INFLUXDB_IOX_AWS_STS_ENDPOINT",
+        action
+    )]
+    pub aws_sts_endpoint: Option<String>,
+
     /// When using Google Cloud Storage as the object store, set this to the
     /// path to the JSON file that contains the Google credentials.
     ///
@@ -203,6 +255,131 @@ pub struct ObjectStoreConfig {
         action
     )]
     pub object_store_connection_limit: NonZeroUsize,
+
+    /// Encrypt data written to the object store with the keys in this file.
+    ///
+    /// The file contains one `<key ID>=<hex encoded 256 bit key>` entry per
+    /// line. New data is encrypted with the key in the last entry; the other
+    /// keys are only used to read data written before the key was rotated.
+    /// Lines starting with `#` are ignored.
+    ///
+    /// Existing unencrypted data stays readable. Data written with encryption
+    /// enabled can only be read by servers configured with the same keys.
+    #[clap(
+        long = "object-store-encryption-key-file",
+        env = "INFLUXDB_IOX_OBJECT_STORE_ENCRYPTION_KEY_FILE",
+        action
+    )]
+    pub object_store_encryption_key_file: Option<PathBuf>,
+
+    /// Replicate newly written objects (e.g. persisted parquet files) to a
+    /// second object store, e.g. a bucket in another region.
+    ///
+    /// Objects are copied asynchronously after they were written to the
+    /// primary object store; deletes are replicated as well. Only the
+    /// ingester, compactor and garbage collector replicate writes. Progress
+    /// is reported by the `object_store_replication_*` metrics.
+    ///
+    /// The replica uses the same credentials as the primary object store.
+    /// Possible values are the same as for `--object-store`.
+    #[clap(
+        value_enum,
+        long = "object-store-replica",
+        env = "INFLUXDB_IOX_OBJECT_STORE_REPLICA",
+        ignore_case = true,
+        action
+    )]
+    pub object_store_replica: Option<ObjectStoreType>,
+
+    /// Name of the bucket of the replica object store.
+    ///
+    /// Must also set `--object-store-replica` to a cloud object storage.
+    #[clap(
+        long = "object-store-replica-bucket",
+        env = "INFLUXDB_IOX_OBJECT_STORE_REPLICA_BUCKET",
+        action
+    )]
+    pub object_store_replica_bucket: Option<String>,
+
+    /// Where to store files of the replica object store.
+    ///
+    /// Must also set `--object-store-replica=file`.
+    #[clap(
+        long = "object-store-replica-data-dir",
+        env = "INFLUXDB_IOX_OBJECT_STORE_REPLICA_DATA_DIR",
+        action
+    )]
+    pub object_store_replica_data_dir: Option<PathBuf>,
+
+    /// The AWS region of the replica bucket, if different from
+    /// `--aws-default-region`.
+    #[clap(
+        long = "object-store-replica-aws-region",
+        env = "INFLUXDB_IOX_OBJECT_STORE_REPLICA_AWS_REGION",
+        action
+    )]
+    pub object_store_replica_aws_region: Option<String>,
+
+    /// The S3 compatible endpoint of the replica, if different from
+    /// `--aws-endpoint`.
+    #[clap(
+        long = "object-store-replica-aws-endpoint",
+        env = "INFLUXDB_IOX_OBJECT_STORE_REPLICA_AWS_ENDPOINT",
+        action
+    )]
+    pub object_store_replica_aws_endpoint: Option<String>,
+
+    /// Limit the number of writes queued for replication to the replica.
+    ///
+    /// Writes to the primary object store never wait for the replication:
+    /// writes exceeding this limit are not replicated, and are reported by
+    /// the `object_store_replication_dropped` metric.
+    #[clap(
+        long = "object-store-replica-max-queue-length",
+        env = "INFLUXDB_IOX_OBJECT_STORE_REPLICA_MAX_QUEUE_LENGTH",
+        default_value = "100000",
+        action
+    )]
+    pub object_store_replica_max_queue_length: NonZeroUsize,
+
+    /// How long a failed replication is retried before it is given up on.
+    ///
+    /// Writes given up on are logged and reported by the
+    /// `object_store_replication_dropped` metric.
+    #[clap(
+        long = "object-store-replica-retry-deadline",
+        env = "INFLUXDB_IOX_OBJECT_STORE_REPLICA_RETRY_DEADLINE",
+        default_value = "1h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub object_store_replica_retry_deadline: Duration,
+
+    /// Fail over to the replica: read and write the replica object store
+    /// configured by `--object-store-replica` instead of the primary one.
+    ///
+    /// Writes are not replicated while failed over. Set this on all servers
+    /// of a cluster at the same time, after the replication queue was drained
+    /// or the primary object store became unavailable.
+    #[clap(
+        long = "object-store-use-replica",
+        env = "INFLUXDB_IOX_OBJECT_STORE_USE_REPLICA",
+        action
+    )]
+    pub object_store_use_replica: bool,
+
+    /// Hedge slow parquet file reads of queries: if a read from the object
+    /// store takes longer than this, the same read is issued a second time
+    /// and whichever request completes first is used.
+    ///
+    /// This trims the tail latency of queries at the cost of some duplicate
+    /// requests. Only the querier hedges reads. Hedged reads are reported by
+    /// the `object_store_hedged_reads*` metrics. Disabled if not set.
+    #[clap(
+        long = "object-store-hedge-threshold",
+        env = "INFLUXDB_IOX_OBJECT_STORE_HEDGE_THRESHOLD",
+        value_parser = humantime::parse_duration,
+    )]
+    pub object_store_hedge_threshold: Option<Duration>,
 }
 
 impl ObjectStoreConfig {
@@ -220,8 +397,12 @@ impl ObjectStoreConfig {
             aws_allow_http: Default::default(),
             aws_default_region: Default::default(),
             aws_endpoint: Default::default(),
+            aws_role_arn: Default::default(),
+            aws_role_external_id: Default::default(),
+            aws_role_session_name: Default::default(),
             aws_secret_access_key: Default::default(),
             aws_session_token: Default::default(),
+            aws_sts_endpoint: Default::default(),
             azure_storage_access_key: Default::default(),
             azure_storage_account: Default::default(),
             bucket: Default::default(),
@@ -229,6 +410,16 @@ impl ObjectStoreConfig {
             google_service_account: Default::default(),
             object_store,
             object_store_connection_limit: NonZeroUsize::new(16).unwrap(),
+            object_store_encryption_key_file: Default::default(),
+            object_store_hedge_threshold: Default::default(),
+            object_store_replica: Default::default(),
+            object_store_replica_aws_endpoint: Default::default(),
+            object_store_replica_aws_region: Default::default(),
+            object_store_replica_bucket: Default::default(),
+            object_store_replica_data_dir: Default::default(),
+            object_store_replica_max_queue_length: NonZeroUsize::new(100_000).unwrap(),
+            object_store_replica_retry_deadline: Duration::from_secs(60 * 60),
+            object_store_use_replica: Default::default(),
         }
     }
 }
@@ -309,6 +500,9 @@ fn new_s3(config: &ObjectStoreConfig) -> Result<Arc<DynObjectStore>, ParseError>
     if let Some(endpoint) = &config.aws_endpoint {
         builder = builder.with_endpoint(endpoint);
     }
+    if let Some(role_arn) = &config.aws_role_arn {
+        builder = builder.with_credentials(Arc::new(assume_role_provider(config, role_arn)));
+    }
 
     Ok(Arc::new(LimitStore::new(
         builder.build().context(InvalidS3ConfigSnafu)?,
@@ -316,6 +510,41 @@ fn new_s3(config: &ObjectStoreConfig) -> Result<Arc<DynObjectStore>, ParseError>
     )))
 }
 
+#[cfg(feature = "aws")]
+fn assume_role_provider(
+    config: &ObjectStoreConfig,
+    role_arn: &str,
+) -> object_store_aws_credentials::AssumeRoleCredentialProvider {
+    use object_store::aws::AwsCredential;
+    use object_store_aws_credentials::{
+        AssumeRoleConfig, AssumeRoleCredentialProvider, SourceCredentials,
+        DEFAULT_METADATA_ENDPOINT,
+    };
+
+    let source = match (&config.aws_access_key_id, &config.aws_secret_access_key) {
+        (Some(key_id), Some(secret_key)) => SourceCredentials::Static(Arc::new(AwsCredential {
+            key_id: key_id.clone(),
+            secret_key: secret_key.clone(),
+            token: config.aws_session_token.clone(),
+        })),
+        _ => SourceCredentials::InstanceMetadata {
+            endpoint: DEFAULT_METADATA_ENDPOINT.to_string(),
+        },
+    };
+
+    AssumeRoleCredentialProvider::new(
+        AssumeRoleConfig {
+            role_arn: role_arn.to_string(),
+            external_id: config.aws_role_external_id.clone(),
+            session_name: config.aws_role_session_name.clone(),
+            session_duration: Duration::from_secs(60 * 60),
+            region: config.aws_default_region.clone(),
+            sts_endpoint: config.aws_sts_endpoint.clone(),
+        },
+        source,
+    )
+}
+
 #[cfg(not(feature = "aws"))]
 fn new_s3(_: &ObjectStoreConfig) -> Result<Arc<DynObjectStore>, ParseError> {
     panic!("S3 support not enabled, recompile with the aws feature enabled")
@@ -354,6 +583,103 @@ fn new_azure(_: &ObjectStoreConfig) -> Result<Arc<DynObjectStore>, ParseError> {
 
 /// Create config-dependant object store.
 pub fn make_object_store(config: &ObjectStoreConfig) -> Result<Arc<DynObjectStore>, ParseError> {
+    let store = make_primary_object_store(config)?;
+    maybe_encrypt(config, store)
+}
+
+/// Create config-dependant object store that replicates writes to the replica
+/// object store, if one is configured.
+///
+/// Must be called from within a tokio runtime.
+pub fn make_replicated_object_store(
+    config: &ObjectStoreConfig,
+    time_provider: Arc<dyn TimeProvider>,
+    metric_registry: &metric::Registry,
+) -> Result<Arc<DynObjectStore>, ParseError> {
+    let store = make_primary_object_store(config)?;
+
+    let store = match replica_config(config) {
+        Some(replica_config) if !config.object_store_use_replica => {
+            let replica = make_inner_object_store(&replica_config)?;
+            info!(%replica, "Object store replication enabled");
+            Arc::new(ReplicatedObjectStore::new(
+                store,
+                replica,
+                config.object_store_connection_limit,
+                config.object_store_replica_max_queue_length,
+                config.object_store_replica_retry_deadline,
+                time_provider,
+                metric_registry,
+            ))
+        }
+        _ => store,
+    };
+
+    // replicate the encrypted objects
+    maybe_encrypt(config, store)
+}
+
+/// Hedge the slow range reads of `store`, if configured.
+pub fn maybe_hedge_reads(
+    config: &ObjectStoreConfig,
+    store: Arc<DynObjectStore>,
+    metric_registry: &metric::Registry,
+) -> Arc<DynObjectStore> {
+    match config.object_store_hedge_threshold {
+        Some(threshold) => {
+            info!(?threshold, "Object store read hedging enabled");
+            Arc::new(HedgedObjectStore::new(store, threshold, metric_registry))
+        }
+        None => store,
+    }
+}
+
+/// The store data is read from and written to, i.e. the replica when failed
+/// over.
+fn make_primary_object_store(
+    config: &ObjectStoreConfig,
+) -> Result<Arc<DynObjectStore>, ParseError> {
+    if config.object_store_use_replica {
+        let replica_config = replica_config(config).context(MissingReplicaConfigSnafu)?;
+        warn!("Failed over to the replica object store");
+        make_inner_object_store(&replica_config)
+    } else {
+        make_inner_object_store(config)
+    }
+}
+
+/// The config of the replica object store, if any.
+fn replica_config(config: &ObjectStoreConfig) -> Option<ObjectStoreConfig> {
+    let object_store = config.object_store_replica?;
+
+    let mut replica_config = config.clone();
+    replica_config.object_store = Some(object_store);
+    replica_config.bucket = config.object_store_replica_bucket.clone();
+    replica_config.database_directory = config.object_store_replica_data_dir.clone();
+    if let Some(region) = &config.object_store_replica_aws_region {
+        replica_config.aws_default_region = region.clone();
+    }
+    if let Some(endpoint) = &config.object_store_replica_aws_endpoint {
+        replica_config.aws_endpoint = Some(endpoint.clone());
+    }
+    Some(replica_config)
+}
+
+fn maybe_encrypt(
+    config: &ObjectStoreConfig,
+    store: Arc<DynObjectStore>,
+) -> Result<Arc<DynObjectStore>, ParseError> {
+    match &config.object_store_encryption_key_file {
+        Some(path) => {
+            let keys = StaticKeyProvider::from_file(path).context(InvalidEncryptionKeysSnafu)?;
+            info!(?path, "Object store encryption enabled");
+            Ok(Arc::new(EncryptedObjectStore::new(store, Arc::new(keys))))
+        }
+        None => Ok(store),
+    }
+}
+
+fn make_inner_object_store(config: &ObjectStoreConfig) -> Result<Arc<DynObjectStore>, ParseError> {
     if let Some(data_dir) = &config.database_directory {
         if !matches!(&config.object_store, Some(ObjectStoreType::File)) {
             warn!(?data_dir, object_store_type=?config.object_store,
@@ -484,6 +810,26 @@ mod tests {
         assert_eq!(&object_store.to_string(), "AmazonS3(mybucket)")
     }
 
+    #[test]
+    #[cfg(feature = "aws")]
+    fn valid_s3_config_with_role() {
+        let config = ObjectStoreConfig::try_parse_from([
+            "server",
+            "--object-store",
+            "s3",
+            "--bucket",
+            "mybucket",
+            "--aws-role-arn",
+            "arn:aws:iam::123456789012:role/iox",
+            "--aws-role-external-id",
+            "NotARealExternalId",
+        ])
+        .unwrap();
+
+        let object_store = make_object_store(&config).unwrap();
+        assert_eq!(&object_store.to_string(), "AmazonS3(mybucket)")
+    }
+
     #[test]
     #[cfg(feature = "aws")]
     fn s3_config_missing_params() {
@@ -614,4 +960,134 @@ mod tests {
             data-dir"
         );
     }
+
+    #[test]
+    fn encrypted_object_store() {
+        let root = TempDir::new().unwrap();
+        let key_file = root.path().join("keys");
+        fs::write(&key_file, format!("1={}\n", "ab".repeat(32))).unwrap();
+
+        let config = ObjectStoreConfig::try_parse_from([
+            "server",
+            "--object-store-encryption-key-file",
+            key_file.to_str().unwrap(),
+        ])
+        .unwrap();
+
+        let object_store = make_object_store(&config).unwrap();
+        assert_eq!(&object_store.to_string(), "EncryptedObjectStore(InMemory)")
+    }
+
+    #[test]
+    fn encrypted_object_store_invalid_keys() {
+        let root = TempDir::new().unwrap();
+        let key_file = root.path().join("keys");
+        fs::write(&key_file, "1=not-a-key\n").unwrap();
+
+        let config = ObjectStoreConfig::try_parse_from([
+            "server",
+            "--object-store-encryption-key-file",
+            key_file.to_str().unwrap(),
+        ])
+        .unwrap();
+
+        let err = make_object_store(&config).unwrap_err().to_string();
+        assert!(
+            err.starts_with("Error loading object store encryption keys"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn replicated_object_store() {
+        let root = TempDir::new().unwrap();
+        let replica_dir = root.path().to_str().unwrap();
+
+        let config = ObjectStoreConfig::try_parse_from([
+            "server",
+            "--object-store-replica",
+            "file",
+            "--object-store-replica-data-dir",
+            replica_dir,
+        ])
+        .unwrap();
+
+        let object_store = make_replicated_object_store(
+            &config,
+            Arc::new(iox_time::SystemProvider::new()),
+            &metric::Registry::default(),
+        )
+        .unwrap()
+        .to_string();
+        assert!(
+            object_store.starts_with("ReplicatedObjectStore(InMemory, LocalFileSystem"),
+            "{}",
+            object_store
+        );
+
+        // only replicating stores are replicated
+        let object_store = make_object_store(&config).unwrap();
+        assert_eq!(&object_store.to_string(), "InMemory")
+    }
+
+    #[tokio::test]
+    async fn failover_to_replica() {
+        let root = TempDir::new().unwrap();
+        let replica_dir = root.path().to_str().unwrap();
+
+        let config = ObjectStoreConfig::try_parse_from([
+            "server",
+            "--object-store-replica",
+            "file",
+            "--object-store-replica-data-dir",
+            replica_dir,
+            "--object-store-use-replica",
+        ])
+        .unwrap();
+
+        let object_store = make_replicated_object_store(
+            &config,
+            Arc::new(iox_time::SystemProvider::new()),
+            &metric::Registry::default(),
+        )
+        .unwrap()
+        .to_string();
+        assert!(
+            object_store.starts_with("LocalFileSystem"),
+            "{}",
+            object_store
+        );
+    }
+
+    #[test]
+    fn failover_without_replica() {
+        let config =
+            ObjectStoreConfig::try_parse_from(["server", "--object-store-use-replica"]).unwrap();
+
+        let err = make_object_store(&config).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Cannot use the replica object store, --object-store-replica is not set"
+        );
+    }
+
+    #[test]
+    fn hedged_object_store() {
+        let config = ObjectStoreConfig::try_parse_from(["server"]).unwrap();
+        let object_store = make_object_store(&config).unwrap();
+        let object_store = maybe_hedge_reads(&config, object_store, &metric::Registry::default());
+        assert_eq!(&object_store.to_string(), "InMemory");
+
+        let config =
+            ObjectStoreConfig::try_parse_from(["server", "--object-store-hedge-threshold", "50ms"])
+                .unwrap();
+        assert_eq!(
+            config.object_store_hedge_threshold,
+            Some(Duration::from_millis(50))
+        );
+        let object_store = make_object_store(&config).unwrap();
+        let object_store = maybe_hedge_reads(&config, object_store, &metric::Registry::default());
+        assert_eq!(&object_store.to_string(), "HedgedObjectStore(InMemory)");
+    }
 }
@@ -1,15 +1,20 @@
 //! Querier-related configs.
 
 use crate::{
+    flight::FlightConfig,
     ingester_address::IngesterAddress,
     memory_size::MemorySize,
     single_tenant::{CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG},
 };
-use std::{collections::HashMap, num::NonZeroUsize};
+use std::{collections::HashMap, num::NonZeroUsize, path::PathBuf, time::Duration};
 
 /// CLI config for querier configuration
 #[derive(Debug, Clone, PartialEq, Eq, clap::Parser)]
 pub struct QuerierConfig {
+    /// Arrow Flight config.
+    #[clap(flatten)]
+    pub flight_config: FlightConfig,
+
     /// Addr for connection to authz
     #[clap(long = CONFIG_AUTHZ_FLAG, env = CONFIG_AUTHZ_ENV_NAME)]
     pub authz_address: Option<String>,
@@ -79,6 +84,52 @@ pub struct QuerierConfig {
     )]
     pub ram_pool_data_bytes: MemorySize,
 
+    /// Read parquet files that only contain data older than this directly
+    /// from object store, instead of through the RAM data cache.
+    ///
+    /// This keeps recent data in RAM while queries over old data are served
+    /// from object store, without evicting the recent data from the cache.
+    ///
+    /// Disabled by default (all data is read through the cache).
+    #[clap(
+        long = "cold-data-threshold",
+        env = "INFLUXDB_IOX_COLD_DATA_THRESHOLD",
+        value_parser = humantime::parse_duration,
+        action
+    )]
+    pub cold_data_threshold: Option<Duration>,
+
+    /// Limit the number of concurrent object store requests reading data
+    /// older than `--cold-data-threshold`. Must be greater than 0.
+    #[clap(
+        long = "max-concurrent-cold-reads",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_COLD_READS",
+        default_value = "10",
+        action
+    )]
+    pub max_concurrent_cold_reads: NonZeroUsize,
+
+    /// Directory on local disk (ideally NVMe) used as a second cache tier for
+    /// data evicted from, or not yet loaded into, the RAM data cache.
+    ///
+    /// Any existing content of the directory is removed at startup. Disabled
+    /// by default (data missing from RAM is fetched from object store).
+    #[clap(
+        long = "disk-cache-directory",
+        env = "INFLUXDB_IOX_DISK_CACHE_DIRECTORY",
+        action
+    )]
+    pub disk_cache_directory: Option<PathBuf>,
+
+    /// Maximum size of the data stored in `--disk-cache-directory` in bytes.
+    #[clap(
+        long = "disk-cache-bytes",
+        env = "INFLUXDB_IOX_DISK_CACHE_BYTES",
+        default_value = "10737418240",  // 10GB
+        action
+    )]
+    pub disk_cache_bytes: u64,
+
     /// Limit the number of concurrent queries.
     #[clap(
         long = "max-concurrent-queries",
@@ -88,6 +139,73 @@ pub struct QuerierConfig {
     )]
     pub max_concurrent_queries: usize,
 
+    /// Limit the number of series a single InfluxRPC `read_filter`, `read_group` or
+    /// `read_window_aggregate` request may return.
+    ///
+    /// Requests that would return more series fail with a "resource exhausted" error asking for
+    /// a narrower predicate, instead of streaming an unbounded result. Unlimited if not set.
+    #[clap(
+        long = "max-series-per-query",
+        env = "INFLUXDB_IOX_MAX_SERIES_PER_QUERY",
+        action
+    )]
+    pub max_series_per_query: Option<NonZeroUsize>,
+
+    /// Limit the number of points a single InfluxRPC `read_filter`, `read_group` or
+    /// `read_window_aggregate` request may return.
+    ///
+    /// Results exceeding this limit are truncated instead of failing the request: the points up
+    /// to the limit are returned and the response ends with the `iox-partial-results: true`
+    /// trailer. Unlimited if not set.
+    #[clap(
+        long = "storage-max-result-points",
+        env = "INFLUXDB_IOX_STORAGE_MAX_RESULT_POINTS",
+        action
+    )]
+    pub storage_max_result_points: Option<NonZeroUsize>,
+
+    /// Limit the size of the results a single InfluxRPC `read_filter`, `read_group` or
+    /// `read_window_aggregate` request may return, in bytes.
+    ///
+    /// Results exceeding this limit are truncated instead of failing the request: the results up
+    /// to the limit are returned and the response ends with the `iox-partial-results: true`
+    /// trailer. Unlimited if not set.
+    ///
+    /// Can be given as absolute value or in percentage of the total available memory (e.g. `10%`).
+    #[clap(
+        long = "storage-max-result-bytes",
+        env = "INFLUXDB_IOX_STORAGE_MAX_RESULT_BYTES",
+        action
+    )]
+    pub storage_max_result_bytes: Option<MemorySize>,
+
+    /// Log queries taking at least this long to complete.
+    ///
+    /// Each slow query is logged with its predicate, the number of chunks it scanned, pruning
+    /// statistics and a compact representation of its plan, and retained in the
+    /// `system.slow_queries` table. Disabled if not set.
+    #[clap(
+        long = "slow-query-threshold",
+        env = "INFLUXDB_IOX_SLOW_QUERY_THRESHOLD",
+        value_parser = humantime::parse_duration,
+        action
+    )]
+    pub slow_query_threshold: Option<Duration>,
+
+    /// Verify the content of parquet files against the hash recorded in the catalog before
+    /// reading them.
+    ///
+    /// Each file is read in full the first time it is queried. Files that fail verification are
+    /// quarantined and queries touching them return an error naming the file, instead of
+    /// reading corrupted data. Files created before content hashes were recorded are not
+    /// verified.
+    #[clap(
+        long = "verify-parquet-checksums",
+        env = "INFLUXDB_IOX_VERIFY_PARQUET_CHECKSUMS",
+        action
+    )]
+    pub verify_parquet_checksums: bool,
+
     /// After how many ingester query errors should the querier enter circuit breaker mode?
     ///
     /// The querier normally contacts the ingester for any unpersisted data during query planning.
@@ -166,6 +284,56 @@ mod tests {
         assert_eq!(actual.num_query_threads, None);
         assert!(actual.ingester_addresses.is_empty());
         assert!(actual.datafusion_config.is_empty());
+        assert_eq!(actual.cold_data_threshold, None);
+        assert_eq!(actual.disk_cache_directory, None);
+    }
+
+    #[test]
+    fn test_cold_data_threshold() {
+        let actual =
+            QuerierConfig::try_parse_from(["my_binary", "--cold-data-threshold", "1d"]).unwrap();
+
+        assert_eq!(
+            actual.cold_data_threshold,
+            Some(Duration::from_secs(24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn test_max_concurrent_cold_reads() {
+        let actual = QuerierConfig::try_parse_from(["my_binary"]).unwrap();
+        assert_eq!(
+            actual.max_concurrent_cold_reads,
+            NonZeroUsize::new(10).unwrap()
+        );
+
+        // 0 would block every cold read forever
+        let actual =
+            QuerierConfig::try_parse_from(["my_binary", "--max-concurrent-cold-reads", "0"])
+                .unwrap_err()
+                .to_string();
+        assert_contains!(
+            actual,
+            "invalid value '0' for '--max-concurrent-cold-reads <MAX_CONCURRENT_COLD_READS>'"
+        );
+    }
+
+    #[test]
+    fn test_storage_max_result() {
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--storage-max-result-points",
+            "1000",
+            "--storage-max-result-bytes",
+            "1048576",
+        ])
+        .unwrap();
+
+        assert_eq!(actual.storage_max_result_points, NonZeroUsize::new(1000));
+        assert_eq!(
+            actual.storage_max_result_bytes.map(|b| b.bytes()),
+            Some(1048576)
+        );
     }
 
     #[test]
@@ -0,0 +1,228 @@
+//! Query router-related configs.
+
+use crate::{
+    flight::FlightConfig,
+    ingester_address::{Error as AddressError, IngesterAddress},
+    memory_size::MemorySize,
+    single_tenant::{CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG},
+};
+use snafu::{ResultExt, Snafu};
+use std::{fmt::Display, num::NonZeroUsize, str::FromStr};
+
+/// CLI config for the query router.
+#[derive(Debug, Clone, PartialEq, Eq, clap::Parser)]
+pub struct QueryRouterConfig {
+    /// Arrow Flight config.
+    #[clap(flatten)]
+    pub flight_config: FlightConfig,
+
+    /// Addr for connection to authz
+    #[clap(long = CONFIG_AUTHZ_FLAG, env = CONFIG_AUTHZ_ENV_NAME)]
+    pub authz_address: Option<String>,
+
+    /// The IOx servers (queriers) that queries are sent to, given as `NAME=ADDRESS`. For
+    /// example:
+    ///
+    /// "hot=http://querier-hot:8082,cold=http://querier-cold:8082"
+    ///
+    /// Every query is sent to all backends that have the queried tables and the results are
+    /// merged. If the same row (same tags and timestamp) is returned by multiple backends, the
+    /// row of the backend listed last wins.
+    #[clap(
+        long = "backends",
+        env = "INFLUXDB_IOX_QUERY_ROUTER_BACKENDS",
+        required = true,
+        num_args = 1..,
+        value_delimiter = ','
+    )]
+    pub backends: Vec<QueryBackend>,
+
+    /// Return the results of the remaining backends if some backends fail or have an
+    /// incompatible table schema, instead of failing the query.
+    ///
+    /// The failed backends are logged and the results are flagged as partial: in the
+    /// `app_metadata` of the last Flight message (`AppMetadata.partial`), and with the
+    /// `iox-partial-results: true` trailer of InfluxRPC responses.
+    #[clap(
+        long = "allow-partial-results",
+        env = "INFLUXDB_IOX_QUERY_ROUTER_ALLOW_PARTIAL_RESULTS",
+        action
+    )]
+    pub allow_partial_results: bool,
+
+    /// The number of threads to use for queries.
+    ///
+    /// If not specified, defaults to the number of cores on the system
+    #[clap(
+        long = "num-query-threads",
+        env = "INFLUXDB_IOX_NUM_QUERY_THREADS",
+        action
+    )]
+    pub num_query_threads: Option<NonZeroUsize>,
+
+    /// Size of memory pool used during query exec, in bytes.
+    ///
+    /// If queries attempt to allocate more than this many bytes
+    /// during execution, they will error with "ResourcesExhausted".
+    ///
+    /// Can be given as absolute value or in percentage of the total available memory (e.g. `10%`).
+    #[clap(
+        long = "exec-mem-pool-bytes",
+        env = "INFLUXDB_IOX_EXEC_MEM_POOL_BYTES",
+        default_value = "8589934592",  // 8GB
+        action
+    )]
+    pub exec_mem_pool_bytes: MemorySize,
+
+    /// Limit the number of concurrent queries.
+    #[clap(
+        long = "max-concurrent-queries",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_QUERIES",
+        default_value = "10",
+        action
+    )]
+    pub max_concurrent_queries: usize,
+
+    /// Limit the number of series a single InfluxRPC `read_filter`, `read_group` or
+    /// `read_window_aggregate` request may return.
+    ///
+    /// Unlimited if not set.
+    #[clap(
+        long = "max-series-per-query",
+        env = "INFLUXDB_IOX_MAX_SERIES_PER_QUERY",
+        action
+    )]
+    pub max_series_per_query: Option<NonZeroUsize>,
+
+    /// Limit the number of points a single InfluxRPC `read_filter`, `read_group` or
+    /// `read_window_aggregate` request may return.
+    ///
+    /// Results exceeding this limit are truncated instead of failing the request: the points up
+    /// to the limit are returned and the response ends with the `iox-partial-results: true`
+    /// trailer. Unlimited if not set.
+    #[clap(
+        long = "storage-max-result-points",
+        env = "INFLUXDB_IOX_STORAGE_MAX_RESULT_POINTS",
+        action
+    )]
+    pub storage_max_result_points: Option<NonZeroUsize>,
+
+    /// Limit the size of the results a single InfluxRPC `read_filter`, `read_group` or
+    /// `read_window_aggregate` request may return, in bytes.
+    ///
+    /// Results exceeding this limit are truncated instead of failing the request: the results up
+    /// to the limit are returned and the response ends with the `iox-partial-results: true`
+    /// trailer. Unlimited if not set.
+    ///
+    /// Can be given as absolute value or in percentage of the total available memory (e.g. `10%`).
+    #[clap(
+        long = "storage-max-result-bytes",
+        env = "INFLUXDB_IOX_STORAGE_MAX_RESULT_BYTES",
+        action
+    )]
+    pub storage_max_result_bytes: Option<MemorySize>,
+}
+
+/// A named backend of the query router. Create by using `QueryBackend::from_str`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryBackend {
+    /// Name of the backend, used in logs and error messages.
+    pub name: String,
+
+    /// gRPC address of the backend.
+    pub address: IngesterAddress,
+}
+
+/// Why a specified backend might be invalid
+#[allow(missing_docs)]
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Invalid backend - expected 'NAME=ADDRESS' got '{value}'"))]
+    Format { value: String },
+
+    #[snafu(display("Invalid address for backend '{name}': {source}"))]
+    Address { name: String, source: AddressError },
+}
+
+impl FromStr for QueryBackend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((name, address)) = s.split_once('=') else {
+            return FormatSnafu { value: s }.fail();
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return FormatSnafu { value: s }.fail();
+        }
+
+        let address = IngesterAddress::from_str(address.trim()).context(AddressSnafu { name })?;
+
+        Ok(Self {
+            name: name.to_string(),
+            address,
+        })
+    }
+}
+
+impl Display for QueryBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{error::ErrorKind, Parser};
+    use test_helpers::{assert_contains, assert_error};
+
+    #[test]
+    fn test_backends() {
+        let actual = QueryRouterConfig::try_parse_from([
+            "my_binary",
+            "--backends",
+            "hot=http://querier-hot:8082, cold = querier-cold:8082",
+        ])
+        .unwrap();
+
+        let backends: Vec<_> = actual.backends.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            backends,
+            vec![
+                "hot=http://querier-hot:8082/",
+                "cold=http://querier-cold:8082/"
+            ]
+        );
+        assert!(!actual.allow_partial_results);
+        assert_eq!(actual.max_concurrent_queries, 10);
+    }
+
+    #[test]
+    fn backends_required() {
+        assert_error!(
+            QueryRouterConfig::try_parse_from(["my_binary"]),
+            ref e if e.kind() == ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn bad_backends() {
+        let actual =
+            QueryRouterConfig::try_parse_from(["my_binary", "--backends", "http://querier:8082"])
+                .unwrap_err()
+                .to_string();
+        assert_contains!(
+            actual,
+            "Invalid backend - expected 'NAME=ADDRESS' got 'http://querier:8082'"
+        );
+
+        let actual = QueryRouterConfig::try_parse_from(["my_binary", "--backends", "hot=querier"])
+            .unwrap_err()
+            .to_string();
+        assert_contains!(
+            actual,
+            "Invalid address for backend 'hot': Port is required; no port found in `querier`"
+        );
+    }
+}
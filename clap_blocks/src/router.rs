@@ -9,6 +9,7 @@ use crate::{
 };
 use std::{
     num::{NonZeroUsize, ParseIntError},
+    path::PathBuf,
     time::Duration,
 };
 
@@ -40,6 +41,17 @@ pub struct RouterConfig {
     )]
     pub single_tenant_deployment: bool,
 
+    /// Accept InfluxDB 1.x `/write?db=...&rp=...` requests in multi-tenant
+    /// deployments, writing to the buckets of this org.
+    ///
+    /// The `db` and `rp` parameters are mapped to the bucket named `db` for
+    /// the default (or `autogen`) retention policy and `db/rp` otherwise,
+    /// so a write to `db=telegraf` goes to the namespace `<org>_telegraf`.
+    /// V1 writes are rejected if this is not set. Single tenant deployments
+    /// always accept V1 writes and ignore this setting.
+    #[clap(long = "v1-write-org", env = "INFLUXDB_IOX_V1_WRITE_ORG", action)]
+    pub v1_write_org: Option<String>,
+
     /// The maximum number of simultaneous requests the HTTP server is
     /// configured to accept.
     ///
@@ -141,6 +153,46 @@ pub struct RouterConfig {
         default_value = "10"
     )]
     pub rpc_write_health_num_probes: u64,
+
+    /// Path of a JSON file with the rules normalizing the tag values of
+    /// writes, per namespace.
+    ///
+    /// The file maps namespace names to the rules of their tags, e.g.
+    /// `{"my_ns": {"host": {"trim": true, "lowercase": true,
+    /// "map": {"srv01": "server01"}}}}`. Tag values are normalized before
+    /// they form the series keys of the written rows.
+    ///
+    /// The rules are router configuration, not namespace properties stored
+    /// in the catalog: every router must be given the same file, changes
+    /// take effect on restart, and the rules of a namespace are not returned
+    /// by the namespace API.
+    #[clap(
+        long = "tag-normalization-rules-file",
+        env = "INFLUXDB_IOX_TAG_NORMALIZATION_RULES_FILE",
+        action
+    )]
+    pub tag_normalization_rules_file: Option<PathBuf>,
+
+    /// Path of a JSON file with the rules deriving tags of writes from
+    /// other tags or the measurement name, per namespace and table.
+    ///
+    /// The file maps namespace names to the rules of their tables, each rule
+    /// matching a regex against a tag (or the measurement name if no
+    /// `source` is given) and adding a tag for each named capture group, e.g.
+    /// `{"my_ns": {"cpu": [{"source": "host",
+    /// "pattern": "^(?P<datacenter>[a-z]+)-"}]}}`. Tags are derived before
+    /// the rows are partitioned.
+    ///
+    /// Like the tag normalization rules, these rules are router
+    /// configuration rather than table properties stored in the catalog:
+    /// every router must be given the same file, and changes take effect on
+    /// restart.
+    #[clap(
+        long = "derived-tags-file",
+        env = "INFLUXDB_IOX_DERIVED_TAGS_FILE",
+        action
+    )]
+    pub derived_tags_file: Option<PathBuf>,
 }
 
 /// Map a string containing an integer number of seconds into a [`Duration`].
@@ -2,7 +2,9 @@
 use trace_exporters::TracingConfig;
 use trogging::cli::LoggingConfig;
 
-use crate::{object_store::ObjectStoreConfig, socket_addr::SocketAddr};
+use crate::{
+    object_store::ObjectStoreConfig, server_limits::ServerLimitsConfig, socket_addr::SocketAddr,
+};
 
 /// The default bind address for the HTTP API.
 pub const DEFAULT_API_BIND_ADDR: &str = "127.0.0.1:8080";
@@ -48,6 +50,22 @@ pub struct RunConfig {
     )]
     pub max_http_request_size: usize,
 
+    /// gRPC and HTTP server limits
+    #[clap(flatten)]
+    pub server_limits: ServerLimitsConfig,
+
+    /// Serve the gRPC API to change the log filter at runtime.
+    ///
+    /// The API is not authorized, only enable it on servers not reachable by
+    /// untrusted clients.
+    #[clap(
+        long = "log-filter-api",
+        env = "INFLUXDB_IOX_LOG_FILTER_API",
+        default_value = "false",
+        action
+    )]
+    pub log_filter_api: bool,
+
     /// object store config
     #[clap(flatten)]
     pub(crate) object_store_config: ObjectStoreConfig,
@@ -93,6 +111,8 @@ impl RunConfig {
         http_bind_address: SocketAddr,
         grpc_bind_address: SocketAddr,
         max_http_request_size: usize,
+        server_limits: ServerLimitsConfig,
+        log_filter_api: bool,
         object_store_config: ObjectStoreConfig,
     ) -> Self {
         Self {
@@ -101,6 +121,8 @@ impl RunConfig {
             http_bind_address,
             grpc_bind_address,
             max_http_request_size,
+            server_limits,
+            log_filter_api,
             object_store_config,
         }
     }
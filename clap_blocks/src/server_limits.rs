@@ -0,0 +1,105 @@
+//! Limits of the gRPC and HTTP servers of all `run` commands.
+use std::time::Duration;
+
+/// Limits of the gRPC and HTTP servers.
+///
+/// Unset limits keep the defaults of the underlying server implementation.
+#[derive(Debug, Clone, Copy, Default, clap::Parser)]
+pub struct ServerLimitsConfig {
+    /// Maximum size of a gRPC message sent or received by the Flight
+    /// service, in bytes.
+    ///
+    /// Received messages are limited to 4 MiB if not set.
+    #[clap(
+        long = "grpc-max-message-size",
+        env = "INFLUXDB_IOX_GRPC_MAX_MESSAGE_SIZE",
+        action
+    )]
+    pub grpc_max_message_size: Option<usize>,
+
+    /// Maximum number of concurrent HTTP/2 streams (gRPC calls) of a single
+    /// client connection.
+    #[clap(
+        long = "max-concurrent-streams",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_STREAMS",
+        action
+    )]
+    pub max_concurrent_streams: Option<u32>,
+
+    /// Interval at which HTTP/2 keep-alive pings are sent to idle clients of
+    /// the gRPC and HTTP servers.
+    ///
+    /// No pings are sent if not set.
+    #[clap(
+        long = "http2-keep-alive-interval",
+        env = "INFLUXDB_IOX_HTTP2_KEEP_ALIVE_INTERVAL",
+        value_parser = humantime::parse_duration,
+    )]
+    pub http2_keep_alive_interval: Option<Duration>,
+
+    /// Time to wait for the acknowledgement of a HTTP/2 keep-alive ping
+    /// before closing the connection.
+    #[clap(
+        long = "http2-keep-alive-timeout",
+        env = "INFLUXDB_IOX_HTTP2_KEEP_ALIVE_TIMEOUT",
+        value_parser = humantime::parse_duration,
+    )]
+    pub http2_keep_alive_timeout: Option<Duration>,
+
+    /// Idle time after which TCP keep-alive probes are sent on the
+    /// connections of the gRPC and HTTP servers.
+    ///
+    /// TCP keep-alive is disabled if not set.
+    #[clap(
+        long = "tcp-keep-alive",
+        env = "INFLUXDB_IOX_TCP_KEEP_ALIVE",
+        value_parser = humantime::parse_duration,
+    )]
+    pub tcp_keep_alive: Option<Duration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        let config = ServerLimitsConfig::try_parse_from(["server"]).unwrap();
+        assert_eq!(config.grpc_max_message_size, None);
+        assert_eq!(config.max_concurrent_streams, None);
+        assert_eq!(config.http2_keep_alive_interval, None);
+        assert_eq!(config.http2_keep_alive_timeout, None);
+        assert_eq!(config.tcp_keep_alive, None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = ServerLimitsConfig::try_parse_from([
+            "server",
+            "--grpc-max-message-size",
+            "104857600",
+            "--max-concurrent-streams",
+            "100",
+            "--http2-keep-alive-interval",
+            "30s",
+            "--http2-keep-alive-timeout",
+            "10s",
+            "--tcp-keep-alive",
+            "1m",
+        ])
+        .unwrap();
+        assert_eq!(config.grpc_max_message_size, Some(104857600));
+        assert_eq!(config.max_concurrent_streams, Some(100));
+        assert_eq!(
+            config.http2_keep_alive_interval,
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            config.http2_keep_alive_timeout,
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(config.tcp_keep_alive, Some(Duration::from_secs(60)));
+    }
+}
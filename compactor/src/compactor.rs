@@ -1,16 +1,22 @@
 //! Main compactor entry point.
 use std::sync::Arc;
 
+use data_types::job::Job;
 use futures::{
     future::{BoxFuture, Shared},
     FutureExt, TryFutureExt,
 };
 use generated_types::influxdata::iox::gossip::{v1::CompactionEvent, Topic};
 use gossip::{NopDispatcher, TopicInterests};
+use iox_catalog::interface::Catalog;
+use iox_query::exec::Executor;
+use iox_time::TimeProvider;
 use observability_deps::tracing::{info, warn};
+use parking_lot::Mutex;
+use parquet_file::storage::ParquetStorage;
 use tokio::task::{JoinError, JoinHandle};
 use tokio_util::sync::CancellationToken;
-use tracker::AsyncSemaphoreMetrics;
+use tracker::{AsyncSemaphoreMetrics, TaskRegistry, TaskTracker, TrackedFutureExt};
 
 use crate::{
     components::{
@@ -18,9 +24,15 @@ use crate::{
         report::{log_components, log_config},
     },
     config::Config,
-    driver::compact,
+    driver::{compact, JobRegistry},
+    namespace_merge::{self, NamespaceMerge, NamespaceMerger},
+    table_rewrite::{self, TableRewrite, TableRewriter},
 };
 
+/// The number of completed compaction jobs retained for inspection through
+/// [`Compactor::jobs`].
+const JOB_HISTORY_SIZE: usize = 1000;
+
 /// A [`JoinHandle`] that can be cloned
 type SharedJoinHandle = Shared<BoxFuture<'static, Result<(), Arc<JoinError>>>>;
 
@@ -34,6 +46,11 @@ fn shared_handle(handle: JoinHandle<()>) -> SharedJoinHandle {
 pub struct Compactor {
     shutdown: CancellationToken,
     worker: SharedJoinHandle,
+    jobs: Arc<Mutex<JobRegistry>>,
+    catalog: Arc<dyn Catalog>,
+    store: ParquetStorage,
+    exec: Arc<Executor>,
+    time_provider: Arc<dyn TimeProvider>,
 }
 
 impl Compactor {
@@ -54,6 +71,17 @@ impl Compactor {
         ));
         let df_semaphore = Arc::new(semaphore_metrics.new_semaphore(config.df_concurrency.get()));
 
+        let jobs = Arc::new(Mutex::new(JobRegistry::new(
+            TaskRegistry::new(Arc::clone(&config.time_provider)),
+            JOB_HISTORY_SIZE,
+        )));
+        let jobs_captured = Arc::clone(&jobs);
+
+        let catalog = Arc::clone(&config.catalog);
+        let store = config.parquet_store_real.clone();
+        let exec = Arc::clone(&config.exec);
+        let time_provider = Arc::clone(&config.time_provider);
+
         // Initialise the gossip subsystem, if configured.
         let gossip = match config.gossip_bind_address {
             Some(bind) => {
@@ -89,6 +117,7 @@ impl Compactor {
                         Arc::clone(&df_semaphore),
                         &components,
                         gossip,
+                        &jobs_captured,
                     ).await;
 
                     info!("compactor done");
@@ -97,7 +126,86 @@ impl Compactor {
         });
         let worker = shared_handle(worker);
 
-        Self { shutdown, worker }
+        Self {
+            shutdown,
+            worker,
+            jobs,
+            catalog,
+            store,
+            exec,
+            time_provider,
+        }
+    }
+
+    /// The registry of running and recently completed compaction jobs.
+    ///
+    /// Cancelling a job abandons the compaction of its partition, keeping the
+    /// output of any branches that were already committed.
+    pub fn jobs(&self) -> Arc<Mutex<JobRegistry>> {
+        Arc::clone(&self.jobs)
+    }
+
+    /// Start rewriting the data of a table into another table in the
+    /// background, returning the tracker of the job.
+    ///
+    /// The rewrite is validated, and the target table and its columns are
+    /// created, before this returns.
+    pub async fn rewrite_table(
+        &self,
+        rewrite: TableRewrite,
+    ) -> Result<TaskTracker<Job>, table_rewrite::Error> {
+        let rewriter = TableRewriter::try_new(
+            Arc::clone(&self.catalog),
+            self.store.clone(),
+            Arc::clone(&self.exec),
+            Arc::clone(&self.time_provider),
+            rewrite,
+        )
+        .await?;
+
+        let job = rewriter.job();
+        let (tracker, registration) = self.jobs.lock().register(job.clone());
+        let progress = registration.progress_reporter();
+
+        info!(%job, "table rewrite starting");
+        tokio::spawn(async move {
+            match rewriter.run(progress).track(registration).await {
+                Ok(Ok(())) => info!(%job, "table rewrite done"),
+                Ok(Err(e)) => warn!(%job, %e, "table rewrite failed"),
+                Err(_) => info!(%job, "table rewrite cancelled"),
+            }
+        });
+
+        Ok(tracker)
+    }
+
+    /// Start merging the tables of a namespace into another namespace in the
+    /// background, returning the tracker of the job.
+    ///
+    /// The schemas of both namespaces are checked for conflicts, and the
+    /// missing tables and columns of the target namespace are created, before
+    /// this returns.
+    pub async fn merge_namespace(
+        &self,
+        merge: NamespaceMerge,
+    ) -> Result<TaskTracker<Job>, namespace_merge::Error> {
+        let merger =
+            NamespaceMerger::try_new(Arc::clone(&self.catalog), self.store.clone(), merge).await?;
+
+        let job = merger.job();
+        let (tracker, registration) = self.jobs.lock().register(job.clone());
+        let progress = registration.progress_reporter();
+
+        info!(%job, "namespace merge starting");
+        tokio::spawn(async move {
+            match merger.run(progress).track(registration).await {
+                Ok(Ok(())) => info!(%job, "namespace merge done"),
+                Ok(Err(e)) => warn!(%job, %e, "namespace merge failed"),
+                Err(_) => info!(%job, "namespace merge cancelled"),
+            }
+        });
+
+        Ok(tracker)
     }
 
     /// Trigger shutdown. You should [join](Self::join) afterwards.
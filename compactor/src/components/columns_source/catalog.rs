@@ -2,7 +2,7 @@ use std::{fmt::Display, sync::Arc};
 
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig};
-use data_types::{Column, TableId};
+use data_types::{Column, ColumnRename, TableId};
 use iox_catalog::interface::Catalog;
 
 use super::ColumnsSource;
@@ -43,4 +43,18 @@ impl ColumnsSource for CatalogColumnsSource {
             .await
             .expect("retry forever")
     }
+
+    async fn fetch_renames(&self, table: TableId) -> Vec<ColumnRename> {
+        Backoff::new(&self.backoff_config)
+            .retry_all_errors("column_renames_of_given_table_id", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .columns()
+                    .list_renames_by_table_id(table)
+                    .await
+            })
+            .await
+            .expect("retry forever")
+    }
 }
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, fmt::Display};
 
 use async_trait::async_trait;
-use data_types::{Column, TableId};
+use data_types::{Column, ColumnRename, TableId};
 
 use super::ColumnsSource;
 
@@ -28,6 +28,11 @@ impl ColumnsSource for MockColumnsSource {
     async fn fetch(&self, table: TableId) -> Vec<Column> {
         self.tables.get(&table).cloned().unwrap_or_default()
     }
+
+    async fn fetch_renames(&self, _table: TableId) -> Vec<ColumnRename> {
+        // columns are never renamed
+        vec![]
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,7 @@
 use std::fmt::{Debug, Display};
 
 use async_trait::async_trait;
-use data_types::{Column, TableId};
+use data_types::{Column, ColumnRename, TableId};
 
 pub mod catalog;
 pub mod mock;
@@ -12,4 +12,9 @@ pub trait ColumnsSource: Debug + Display + Send + Sync {
     ///
     /// This method performs retries.
     async fn fetch(&self, table: TableId) -> Vec<Column>;
+
+    /// Get the column renames of a given table
+    ///
+    /// This method performs retries.
+    async fn fetch_renames(&self, table: TableId) -> Vec<ColumnRename>;
 }
@@ -57,6 +57,7 @@ where
                     SchedulerErrorKind::OutOfMemory => SchedulerErrorKind::OutOfMemory,
                     SchedulerErrorKind::ObjectStore => SchedulerErrorKind::ObjectStore,
                     SchedulerErrorKind::Timeout => SchedulerErrorKind::Timeout,
+                    SchedulerErrorKind::Cancelled => SchedulerErrorKind::Cancelled,
                     SchedulerErrorKind::Unknown(_) => SchedulerErrorKind::Unknown(e.to_string()),
                 };
 
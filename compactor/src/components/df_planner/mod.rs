@@ -8,7 +8,7 @@ use datafusion::{error::DataFusionError, physical_plan::ExecutionPlan};
 
 pub mod panic;
 pub mod planner_v1;
-mod query_chunk;
+pub(crate) mod query_chunk;
 
 use crate::{partition_info::PartitionInfo, plan_ir::PlanIR};
 
@@ -1,7 +1,11 @@
 //! QueryableParquetChunk for building query plan
-use std::{any::Any, sync::Arc};
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
-use data_types::{ChunkId, ChunkOrder, TransitionPartitionId};
+use data_types::{column_names_at, ChunkId, ChunkOrder, TransitionPartitionId};
 use datafusion::physical_plan::Statistics;
 use iox_query::{util::create_basic_summary, QueryChunk, QueryChunkData};
 use observability_deps::tracing::debug;
@@ -169,6 +173,38 @@ fn to_queryable_parquet_chunk(
         "built parquet chunk from metadata"
     );
 
-    let parquet_chunk = ParquetChunk::new(Arc::new(file.file.clone()), schema, store);
+    // columns renamed after the file was created are stored under their old name
+    let renamed_columns = column_names_at(&partition_info.column_renames, file.file.created_at)
+        .into_iter()
+        .filter_map(|(id, name_in_file)| {
+            let name = column_id_lookup.get(&id)?;
+            selection
+                .contains(name)
+                .then(|| (name.to_string(), name_in_file.to_string()))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    // columns that are not in the file must not be read by name if the file may contain a
+    // different column of that name, i.e. one that was renamed or dropped since
+    let has_dropped_columns = file
+        .file
+        .column_set
+        .iter()
+        .any(|id| !column_id_lookup.contains_key(id));
+    let absent_columns = column_id_lookup
+        .iter()
+        .filter(|(id, _name)| !file.file.column_set.contains(*id))
+        .filter(|(_id, name)| {
+            has_dropped_columns
+                || renamed_columns
+                    .values()
+                    .any(|name_in_file| name_in_file.as_str() == **name)
+        })
+        .map(|(_id, name)| name.to_string())
+        .collect::<BTreeSet<_>>();
+
+    let parquet_chunk = ParquetChunk::new(Arc::new(file.file.clone()), schema, store)
+        .with_renamed_columns(renamed_columns)
+        .with_absent_columns(absent_columns);
     QueryableParquetChunk::new(partition_id, Arc::new(parquet_chunk), sort_key, file.order)
 }
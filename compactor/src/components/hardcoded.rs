@@ -142,7 +142,7 @@ fn make_jobs_source_commit_jobs_sink(
                     // use explicit match statement so we never forget to add new variants
                     match kind {
                         ErrorKind::OutOfMemory | ErrorKind::Timeout | ErrorKind::Unknown => true,
-                        ErrorKind::ObjectStore => false,
+                        ErrorKind::ObjectStore | ErrorKind::Cancelled => false,
                     }
                 })
                 .copied()
@@ -310,6 +310,10 @@ fn make_parquet_files_sink(config: &Config) -> Arc<dyn ParquetFilesSink> {
                     config.exec.pool(),
                     config.parquet_store_scratchpad.clone(),
                     Arc::clone(&config.time_provider),
+                    Arc::new(CatalogColumnsSource::new(
+                        config.backoff_config.clone(),
+                        Arc::clone(&config.catalog),
+                    )),
                 ),
                 Arc::clone(&config.exec),
             ),
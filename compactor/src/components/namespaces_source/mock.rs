@@ -190,6 +190,8 @@ mod tests {
                         retention_period_ns: None,
                         deleted_at: None,
                         partition_template: Default::default(),
+                        tenant_id: None,
+                        template_id: None,
                     },
                     schema: NamespaceSchema {
                         id,
@@ -80,6 +80,7 @@ impl ParquetFileSink for MockParquetFileSink {
             created_at: Timestamp::new(1),
             column_set: ColumnSet::new(vec![]),
             max_l0_created_at: max_l0_created_at.into(),
+            content_hash: None,
         });
         guard.push(StoredFile {
             batches,
@@ -176,6 +177,7 @@ mod tests {
                 created_at: Timestamp::new(1),
                 column_set: ColumnSet::new([]),
                 max_l0_created_at: max_l0_created_at.into(),
+                content_hash: None,
             }),
         );
 
@@ -239,6 +241,7 @@ mod tests {
                 created_at: Timestamp::new(1),
                 column_set: ColumnSet::new([]),
                 max_l0_created_at: max_l0_created_at.into(),
+                content_hash: None,
             }),
         );
 
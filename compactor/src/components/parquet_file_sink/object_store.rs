@@ -1,7 +1,7 @@
-use std::{fmt::Display, sync::Arc};
+use std::{collections::HashMap, fmt::Display, sync::Arc};
 
 use async_trait::async_trait;
-use data_types::{CompactionLevel, ParquetFileParams};
+use data_types::{file_column_names, ColumnId, CompactionLevel, ParquetFileParams, Timestamp};
 use datafusion::{
     error::DataFusionError, execution::memory_pool::MemoryPool,
     physical_plan::SendableRecordBatchStream,
@@ -10,11 +10,11 @@ use iox_time::{Time, TimeProvider};
 use parquet_file::{
     metadata::IoxMetadata,
     serialize::CodecError,
-    storage::{ParquetStorage, UploadError},
+    storage::{rename_sort_key_columns, rename_stream_columns, ParquetStorage, UploadError},
 };
 use uuid::Uuid;
 
-use crate::partition_info::PartitionInfo;
+use crate::{components::columns_source::ColumnsSource, partition_info::PartitionInfo};
 
 use super::ParquetFileSink;
 
@@ -24,6 +24,8 @@ pub struct ObjectStoreParquetFileSink {
     pool: Arc<dyn MemoryPool>,
     store: ParquetStorage,
     time_provider: Arc<dyn TimeProvider>,
+    // source of the column renames at the time the file is created
+    columns_source: Arc<dyn ColumnsSource>,
 }
 
 impl ObjectStoreParquetFileSink {
@@ -31,11 +33,13 @@ impl ObjectStoreParquetFileSink {
         pool: Arc<dyn MemoryPool>,
         store: ParquetStorage,
         time_provider: Arc<dyn TimeProvider>,
+        columns_source: Arc<dyn ColumnsSource>,
     ) -> Self {
         Self {
             pool,
             store,
             time_provider,
+            columns_source,
         }
     }
 }
@@ -55,16 +59,41 @@ impl ParquetFileSink for ObjectStoreParquetFileSink {
         level: CompactionLevel,
         max_l0_created_at: Time,
     ) -> Result<Option<ParquetFileParams>, DataFusionError> {
+        // Readers expect each column under the name it had when the file was
+        // created, so the renames must be read after the creation time is
+        // taken. Renames that happened since the partition info was fetched
+        // are then stored under the old name.
+        let creation_timestamp = self.time_provider.now();
+        let renames = self.columns_source.fetch_renames(partition.table.id).await;
+        let renamed_columns = file_column_names(
+            &partition.table_schema.columns,
+            &renames,
+            Timestamp::from(creation_timestamp),
+        );
+        let stream = rename_stream_columns(stream, &renamed_columns);
+        let column_ids: HashMap<&str, ColumnId> = partition
+            .table_schema
+            .columns
+            .iter()
+            .map(|(name, column)| {
+                let name = renamed_columns.get(name).unwrap_or(name);
+                (name.as_str(), column.id)
+            })
+            .collect();
+
         let meta = IoxMetadata {
             object_store_id: Uuid::new_v4(),
-            creation_timestamp: self.time_provider.now(),
+            creation_timestamp,
             namespace_id: partition.namespace_id,
             namespace_name: partition.namespace_name.clone().into(),
             table_id: partition.table.id,
             table_name: partition.table.name.clone().into(),
             partition_key: partition.partition_key.clone(),
             compaction_level: level,
-            sort_key: partition.sort_key.clone(),
+            sort_key: partition
+                .sort_key
+                .as_ref()
+                .map(|sort_key| rename_sort_key_columns(sort_key, &renamed_columns)),
             max_l0_created_at,
         };
 
@@ -72,7 +101,7 @@ impl ParquetFileSink for ObjectStoreParquetFileSink {
         // them, and directly upload the resulting Parquet files to
         // object storage.
         let pool = Arc::clone(&self.pool);
-        let (parquet_meta, file_size) = match self
+        let (parquet_meta, file_size, content_hash) = match self
             .store
             .upload(stream, &partition.partition_id(), &meta, pool)
             .await
@@ -91,15 +120,13 @@ impl ParquetFileSink for ObjectStoreParquetFileSink {
             }
         };
 
-        let parquet_file =
-            meta.to_parquet_file(partition.partition_id(), file_size, &parquet_meta, |name| {
-                partition
-                    .table_schema
-                    .columns
-                    .get(name)
-                    .expect("unknown column")
-                    .id
-            });
+        let parquet_file = meta.to_parquet_file(
+            partition.partition_id(),
+            file_size,
+            content_hash,
+            &parquet_meta,
+            |name| *column_ids.get(name).expect("unknown column"),
+        );
 
         Ok(Some(parquet_file))
     }
@@ -110,6 +110,7 @@ where
 
         // fetch table columns to get column names for the partition's sort_key_ids
         let columns = self.columns_source.fetch(table.id).await;
+        let column_renames = self.columns_source.fetch_renames(table.id).await;
 
         // sort_key_ids of the partition
         let sort_key_ids = partition.sort_key_ids_none_if_empty();
@@ -154,6 +155,7 @@ where
             table_schema: Arc::new(table_schema.clone()),
             sort_key,
             partition_key: partition.partition_key,
+            column_renames,
         }))
     }
 }
@@ -2,16 +2,20 @@ use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use chrono::Utc;
 use compactor_scheduler::CompactionJob;
-use data_types::{CompactionLevel, ParquetFile, ParquetFileParams, PartitionId};
-use futures::{stream, StreamExt, TryStreamExt};
+use data_types::{job::Job, CompactionLevel, ParquetFile, ParquetFileParams, PartitionId};
+use futures::{stream, StreamExt, TryFutureExt, TryStreamExt};
 use gossip_compaction::tx::CompactionEventTx;
 use iox_query::exec::query_tracing::send_metrics_to_tracing;
 use observability_deps::tracing::info;
+use parking_lot::Mutex;
 use parquet_file::ParquetFilePath;
 use tokio::sync::watch::Sender;
 use trace::span::Span;
 use trace::span::SpanRecorder;
-use tracker::InstrumentedAsyncSemaphore;
+use tracker::{
+    AbstractTaskRegistry, InstrumentedAsyncSemaphore, ProgressReporter, TaskRegistry,
+    TaskRegistryWithHistory, TrackedFutureExt,
+};
 
 use crate::{
     components::{
@@ -27,8 +31,13 @@ use crate::{
     PlanIR, RoundInfo,
 };
 
+/// The registry tracking the partitions being compacted, and a history of
+/// recently compacted ones.
+pub type JobRegistry = TaskRegistryWithHistory<Job, TaskRegistry<Job>>;
+
 /// Tries to compact all eligible partitions, up to
 /// partition_concurrency at a time.
+#[allow(clippy::too_many_arguments)]
 pub async fn compact(
     trace_collector: Option<Arc<dyn trace::TraceCollector>>,
     partition_concurrency: NonZeroUsize,
@@ -36,6 +45,7 @@ pub async fn compact(
     df_semaphore: Arc<InstrumentedAsyncSemaphore>,
     components: &Arc<Components>,
     gossip_handle: Option<Arc<CompactionEventTx>>,
+    jobs: &Arc<Mutex<JobRegistry>>,
 ) {
     components
         .compaction_job_stream
@@ -58,6 +68,7 @@ pub async fn compact(
                 Arc::clone(&df_semaphore),
                 components,
                 gossip_handle.clone(),
+                Arc::clone(jobs),
             )
         })
         .buffer_unordered(partition_concurrency.get())
@@ -72,19 +83,24 @@ async fn compact_partition(
     df_semaphore: Arc<InstrumentedAsyncSemaphore>,
     components: Arc<Components>,
     gossip_handle: Option<Arc<CompactionEventTx>>,
+    jobs: Arc<Mutex<JobRegistry>>,
 ) {
     let partition_id = job.partition_id;
     info!(partition_id = partition_id.get(), timeout = ?partition_timeout, "compact partition",);
     span.set_metadata("partition_id", partition_id.get().to_string());
     let scratchpad = components.scratchpad_gen.pad();
 
+    let (_tracker, registration) = jobs.lock().register(Job::CompactPartition { partition_id });
+    let progress = registration.progress_reporter();
+
     info!(partition_id = partition_id.get(), "compaction job starting");
 
     let res = timeout_with_progress_checking(partition_timeout, |transmit_progress_signal| {
         let components = Arc::clone(&components);
         let scratchpad = Arc::clone(&scratchpad);
         async {
-            try_compact_partition(
+            // errors detected in the CompactionJob update_job_status(), will be handled in the timeout_with_progress_checking
+            let res = try_compact_partition(
                 span,
                 job.clone(),
                 df_semaphore,
@@ -92,8 +108,24 @@ async fn compact_partition(
                 scratchpad,
                 transmit_progress_signal,
                 gossip_handle,
+                progress,
             )
-            .await // errors detected in the CompactionJob update_job_status(), will be handled in the timeout_with_progress_checking
+            .track(registration)
+            .await;
+
+            // A cancelled compaction keeps the branches it already committed. It is
+            // reported as its own error kind so it is neither counted as a success nor
+            // causes the partition to be skipped.
+            res.unwrap_or_else(|_| {
+                info!(
+                    partition_id = partition_id.get(),
+                    "compaction job cancelled"
+                );
+                Err(Box::new(SimpleError::new(
+                    ErrorKind::Cancelled,
+                    "compaction job cancelled",
+                )) as _)
+            })
         }
     })
     .await;
@@ -120,6 +152,7 @@ async fn compact_partition(
     let _ = components.compaction_job_done_sink.record(job, res).await;
 
     scratchpad.clean().await;
+    jobs.lock().reclaim();
     info!(partition_id = partition_id.get(), "compaction job done",);
 }
 
@@ -209,6 +242,7 @@ async fn compact_partition(
 /// Note:
 ///   . If there are no L0s files in the partition, the first round can just compact L1s and L2s to L2s
 ///   . Round 2 happens or not depends on the stop condition
+#[allow(clippy::too_many_arguments)]
 async fn try_compact_partition(
     span: SpanRecorder,
     job: CompactionJob,
@@ -217,6 +251,7 @@ async fn try_compact_partition(
     scratchpad_ctx: Arc<dyn Scratchpad>,
     transmit_progress_signal: Sender<bool>,
     gossip_handle: Option<Arc<CompactionEventTx>>,
+    progress: ProgressReporter,
 ) -> Result<(), DynError> {
     let partition_id = job.partition_id;
     let mut files = components.partition_files_source.fetch(partition_id).await;
@@ -291,6 +326,7 @@ async fn try_compact_partition(
             if branches.is_none() {
                 continue;
             }
+            progress.add_total(branches_cnt);
 
             // concurrently run the branches.
             let branches_output: Vec<Vec<ParquetFile>> =
@@ -321,6 +357,7 @@ async fn try_compact_partition(
                             )
                             .await
                         }
+                        .inspect_ok(|_| progress.add_done(1))
                     })
                     .buffer_unordered(df_semaphore.total_permits())
                     .try_collect()
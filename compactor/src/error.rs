@@ -24,6 +24,11 @@ pub enum ErrorKind {
     /// Partition took too long.
     Timeout,
 
+    /// The compaction job was cancelled through the job registry.
+    ///
+    /// Branches committed before the cancellation are kept and the partition is retried later.
+    Cancelled,
+
     /// Unknown/unexpected error.
     ///
     /// This will likely mark the affected partition as "skipped" and the compactor will no longer touch it.
@@ -37,6 +42,7 @@ impl ErrorKind {
             Self::ObjectStore,
             Self::OutOfMemory,
             Self::Timeout,
+            Self::Cancelled,
             Self::Unknown,
         ]
     }
@@ -47,6 +53,7 @@ impl ErrorKind {
             Self::ObjectStore => "object_store",
             Self::OutOfMemory => "out_of_memory",
             Self::Timeout => "timeout",
+            Self::Cancelled => "cancelled",
             Self::Unknown => "unknown",
         }
     }
@@ -58,6 +65,7 @@ impl From<ErrorKind> for SchedulerErrorKind {
             ErrorKind::ObjectStore => Self::ObjectStore,
             ErrorKind::OutOfMemory => Self::OutOfMemory,
             ErrorKind::Timeout => Self::Timeout,
+            ErrorKind::Cancelled => Self::Cancelled,
             ErrorKind::Unknown => Self::Unknown("".into()),
         }
     }
@@ -214,17 +214,19 @@ pub mod config;
 mod driver;
 mod error;
 mod file_classification;
+pub mod namespace_merge;
 pub mod object_store;
 mod partition_info;
 mod plan_ir;
 mod round_info;
+pub mod table_rewrite;
 
 // publically expose items needed for testing
 pub use components::{
     df_planner::panic::PanicDataFusionPlanner, hardcoded::hardcoded_components,
     namespaces_source::mock::NamespaceWrapper, parquet_files_sink::ParquetFilesSink, Components,
 };
-pub use driver::compact;
+pub use driver::{compact, JobRegistry};
 pub use error::DynError;
 pub use partition_info::PartitionInfo;
 pub use plan_ir::PlanIR;
@@ -0,0 +1,952 @@
+//! Merge the tables of a namespace into another namespace.
+//!
+//! A merge moves all persisted data of the source namespace into the target
+//! namespace, without rewriting it: each table of the source namespace is
+//! merged into the table with the same name of the target namespace, which
+//! is created if it does not exist, and each partition into the partition
+//! with the same key. The parquet files of a partition are copied in the
+//! object store and then adopted by the target partition in a single catalog
+//! transaction, which also marks the files of the source partition for
+//! deletion, so queries never see the data twice.
+//!
+//! The schemas of both namespaces are compared before anything is changed,
+//! and all conflicts are reported at once: tables with the same name must
+//! have the same partition template, columns with the same name must have
+//! the same type, the tables of the source namespace must not have renamed
+//! or dropped columns, and partitions with the same key must sort their
+//! common columns in the same order, as the adopted files are not sorted
+//! again.
+//!
+//! Only persisted data is merged, so the buffered data of the source
+//! namespace should be persisted first, and writes to it stopped. Merging
+//! again moves the data persisted to the source namespace since the previous
+//! merge. The source namespace is not deleted.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    sync::Arc,
+};
+
+use data_types::{
+    job::Job, Column, ColumnId, ColumnSet, ColumnType, ColumnsByName, CompactionLevel, Namespace,
+    ParquetFileParams, Partition, Table, TableId,
+};
+use iox_catalog::interface::{CasFailure, Catalog, SoftDeletedRows};
+use observability_deps::tracing::debug;
+use parquet_file::{storage::ParquetStorage, ParquetFilePath};
+use schema::sort::adjust_sort_key_columns;
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use tracker::ProgressReporter;
+use uuid::Uuid;
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("namespace {name} not found"))]
+    NamespaceNotFound { name: String },
+
+    #[snafu(display("cannot merge namespace {name} into itself"))]
+    SameNamespace { name: String },
+
+    #[snafu(display(
+        "cannot merge namespace {source_namespace} into {target_namespace}: {}",
+        conflicts.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    ))]
+    SchemaConflicts {
+        source_namespace: String,
+        target_namespace: String,
+        conflicts: Vec<SchemaConflict>,
+    },
+
+    #[snafu(display("catalog error: {source}"))]
+    Catalog {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("cannot copy parquet file: {source}"))]
+    Copy { source: object_store::Error },
+}
+
+/// A specialized `Result` for namespace merge errors
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A difference between the schemas of two namespaces preventing a merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaConflict {
+    /// The table has a different partition template in each namespace
+    PartitionTemplate {
+        /// The name of the table
+        table: String,
+    },
+
+    /// The column has a different type in each namespace
+    ColumnType {
+        /// The name of the table
+        table: String,
+        /// The name of the column
+        column: String,
+        /// The type of the column in the source namespace
+        source_type: ColumnType,
+        /// The type of the column in the target namespace
+        target_type: ColumnType,
+    },
+
+    /// The table has renamed or dropped columns in the source namespace,
+    /// whose parquet files still use the old columns
+    ChangedColumns {
+        /// The name of the table
+        table: String,
+    },
+
+    /// The partition has a sort key in each namespace, and the files of the
+    /// source partition are not sorted by the sort key the target partition
+    /// would have after the merge
+    SortKey {
+        /// The name of the table
+        table: String,
+        /// The key of the partition
+        partition_key: String,
+        /// The sort key of the partition in the source namespace
+        source_sort_key: Vec<String>,
+        /// The sort key of the partition in the target namespace
+        target_sort_key: Vec<String>,
+    },
+}
+
+impl Display for SchemaConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PartitionTemplate { table } => {
+                write!(f, "table {table} has a different partition template")
+            }
+            Self::ColumnType {
+                table,
+                column,
+                source_type,
+                target_type,
+            } => write!(
+                f,
+                "column {column} of table {table} is a {source_type} in the source namespace \
+                 and a {target_type} in the target namespace"
+            ),
+            Self::ChangedColumns { table } => {
+                write!(f, "table {table} has renamed or dropped columns")
+            }
+            Self::SortKey {
+                table,
+                partition_key,
+                source_sort_key,
+                target_sort_key,
+            } => write!(
+                f,
+                "partition {partition_key} of table {table} is sorted by {source_sort_key:?} in \
+                 the source namespace and by {target_sort_key:?} in the target namespace"
+            ),
+        }
+    }
+}
+
+/// A merge of the tables of a namespace into another namespace.
+#[derive(Debug, Clone)]
+pub struct NamespaceMerge {
+    /// The namespace the data is moved from
+    pub source_namespace: String,
+
+    /// The namespace the data is moved into
+    pub target_namespace: String,
+}
+
+/// A table of the source namespace, and the table of the target namespace
+/// it is merged into.
+#[derive(Debug)]
+struct TableMerge {
+    source: Table,
+    target: Table,
+    /// The ids of the columns of the target table, by the id of the source
+    /// table column with the same name
+    column_ids: HashMap<ColumnId, ColumnId>,
+    target_columns: ColumnsByName,
+}
+
+/// Returns the conflicts between the schemas of the `source` tables and
+/// the `target` tables with the same name, with their columns.
+///
+/// `changed_tables` are the ids of the source tables with renamed or dropped
+/// columns, and `partitions` the partitions of the tables by table id.
+fn schema_conflicts(
+    source: &BTreeMap<String, (Table, Vec<Column>)>,
+    target: &BTreeMap<String, (Table, Vec<Column>)>,
+    changed_tables: &[TableId],
+    partitions: &HashMap<TableId, Vec<Partition>>,
+) -> Vec<SchemaConflict> {
+    let mut conflicts = vec![];
+
+    for (name, (source_table, source_columns)) in source {
+        if changed_tables.contains(&source_table.id) {
+            conflicts.push(SchemaConflict::ChangedColumns {
+                table: name.clone(),
+            });
+        }
+
+        let Some((target_table, target_columns)) = target.get(name) else {
+            continue;
+        };
+
+        if source_table.partition_template != target_table.partition_template {
+            conflicts.push(SchemaConflict::PartitionTemplate {
+                table: name.clone(),
+            });
+        }
+
+        for source_column in source_columns {
+            let Some(target_column) = target_columns.iter().find(|c| c.name == source_column.name)
+            else {
+                continue;
+            };
+            if source_column.column_type != target_column.column_type {
+                conflicts.push(SchemaConflict::ColumnType {
+                    table: name.clone(),
+                    column: source_column.name.clone(),
+                    source_type: source_column.column_type,
+                    target_type: target_column.column_type,
+                });
+            }
+        }
+
+        let target_partitions: HashMap<_, _> = partitions
+            .get(&target_table.id)
+            .into_iter()
+            .flatten()
+            .map(|p| (&p.partition_key, p))
+            .collect();
+        for source_partition in partitions.get(&source_table.id).into_iter().flatten() {
+            let Some(target_partition) = target_partitions.get(&source_partition.partition_key)
+            else {
+                continue;
+            };
+            if let Some(conflict) = sort_key_conflict(name, source_partition, target_partition) {
+                conflicts.push(conflict);
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Returns a conflict if the files of the `source` partition, which are
+/// sorted by its sort key, are not sorted by the sort key of the `target`
+/// partition once it is extended with the columns of the source sort key.
+fn sort_key_conflict(
+    table: &str,
+    source: &Partition,
+    target: &Partition,
+) -> Option<SchemaConflict> {
+    let (Some(source_sort_key), Some(target_sort_key)) = (source.sort_key(), target.sort_key())
+    else {
+        return None;
+    };
+
+    let source_columns: Vec<_> = source_sort_key.to_columns().collect();
+    let (file_sort_key, _catalog_update) =
+        adjust_sort_key_columns(&target_sort_key, &source_columns);
+    if file_sort_key.to_columns().eq(source_columns) {
+        return None;
+    }
+
+    Some(SchemaConflict::SortKey {
+        table: table.to_string(),
+        partition_key: source.partition_key.to_string(),
+        source_sort_key: source.sort_key.clone(),
+        target_sort_key: target.sort_key.clone(),
+    })
+}
+
+/// Runs a validated [`NamespaceMerge`].
+#[derive(Debug)]
+pub(crate) struct NamespaceMerger {
+    catalog: Arc<dyn Catalog>,
+    store: ParquetStorage,
+    source: Namespace,
+    target: Namespace,
+    tables: Vec<TableMerge>,
+}
+
+impl NamespaceMerger {
+    /// Validate `merge` and prepare the target namespace, creating the
+    /// tables and columns of the source namespace it does not have.
+    pub(crate) async fn try_new(
+        catalog: Arc<dyn Catalog>,
+        store: ParquetStorage,
+        merge: NamespaceMerge,
+    ) -> Result<Self> {
+        let NamespaceMerge {
+            source_namespace,
+            target_namespace,
+        } = merge;
+
+        ensure!(
+            source_namespace != target_namespace,
+            SameNamespaceSnafu {
+                name: source_namespace
+            }
+        );
+
+        let mut repos = catalog.repositories().await;
+
+        let mut namespaces = vec![];
+        for name in [source_namespace, target_namespace] {
+            let namespace = repos
+                .namespaces()
+                .get_by_name(&name, SoftDeletedRows::ExcludeDeleted)
+                .await
+                .context(CatalogSnafu)?
+                .context(NamespaceNotFoundSnafu { name })?;
+
+            let mut columns: HashMap<_, Vec<_>> = HashMap::new();
+            for column in repos
+                .columns()
+                .list_by_namespace_id(namespace.id)
+                .await
+                .context(CatalogSnafu)?
+            {
+                columns.entry(column.table_id).or_default().push(column);
+            }
+            let tables: BTreeMap<_, _> = repos
+                .tables()
+                .list_by_namespace_id(namespace.id)
+                .await
+                .context(CatalogSnafu)?
+                .into_iter()
+                .map(|table| {
+                    let table_columns = columns.remove(&table.id).unwrap_or_default();
+                    (table.name.clone(), (table, table_columns))
+                })
+                .collect();
+
+            namespaces.push((namespace, tables));
+        }
+        let (target, mut target_tables) = namespaces.pop().expect("two namespaces");
+        let (source, source_tables) = namespaces.pop().expect("two namespaces");
+
+        let mut changed_tables: Vec<_> = repos
+            .columns()
+            .list_renames_by_namespace_id(source.id)
+            .await
+            .context(CatalogSnafu)?
+            .into_iter()
+            .map(|r| r.table_id)
+            .collect();
+        changed_tables.extend(
+            repos
+                .columns()
+                .list_drops_by_namespace_id(source.id)
+                .await
+                .context(CatalogSnafu)?
+                .into_iter()
+                .map(|d| d.table_id),
+        );
+
+        // the partitions of the tables that exist in both namespaces
+        let mut partitions = HashMap::new();
+        for (name, (source_table, _)) in &source_tables {
+            let Some((target_table, _)) = target_tables.get(name) else {
+                continue;
+            };
+            for table_id in [source_table.id, target_table.id] {
+                let table_partitions = repos
+                    .partitions()
+                    .list_by_table_id(table_id)
+                    .await
+                    .context(CatalogSnafu)?;
+                partitions.insert(table_id, table_partitions);
+            }
+        }
+
+        let conflicts =
+            schema_conflicts(&source_tables, &target_tables, &changed_tables, &partitions);
+        ensure!(
+            conflicts.is_empty(),
+            SchemaConflictsSnafu {
+                source_namespace: &source.name,
+                target_namespace: &target.name,
+                conflicts,
+            }
+        );
+
+        let mut tables = Vec::with_capacity(source_tables.len());
+        for (name, (source_table, source_columns)) in source_tables {
+            let target_table = match target_tables.remove(&name) {
+                Some((table, _)) => table,
+                None => repos
+                    .tables()
+                    .create(&name, source_table.partition_template.clone(), target.id)
+                    .await
+                    .context(CatalogSnafu)?,
+            };
+
+            // Like the router, the column limit of the namespace is not
+            // checked here.
+            repos
+                .columns()
+                .create_or_get_many_unchecked(
+                    target_table.id,
+                    source_columns
+                        .iter()
+                        .map(|c| (c.name.as_str(), c.column_type))
+                        .collect(),
+                )
+                .await
+                .context(CatalogSnafu)?;
+            let target_columns = ColumnsByName::new(
+                repos
+                    .columns()
+                    .list_by_table_id(target_table.id)
+                    .await
+                    .context(CatalogSnafu)?,
+            );
+            let column_ids = source_columns
+                .iter()
+                .filter_map(|c| Some((c.id, target_columns.get(&c.name)?.id)))
+                .collect();
+
+            tables.push(TableMerge {
+                source: source_table,
+                target: target_table,
+                column_ids,
+                target_columns,
+            });
+        }
+
+        Ok(Self {
+            catalog,
+            store,
+            source,
+            target,
+            tables,
+        })
+    }
+
+    /// The job tracking this merge
+    pub(crate) fn job(&self) -> Job {
+        Job::MergeNamespace {
+            source_namespace_id: self.source.id,
+            target_namespace_id: self.target.id,
+        }
+    }
+
+    /// Merge all partitions of the source namespace, one at a time.
+    pub(crate) async fn run(self, progress: ProgressReporter) -> Result<()> {
+        let mut partitions = vec![];
+        for table in &self.tables {
+            let table_partitions = self
+                .catalog
+                .repositories()
+                .await
+                .partitions()
+                .list_by_table_id(table.source.id)
+                .await
+                .context(CatalogSnafu)?;
+            partitions.extend(table_partitions.into_iter().map(|p| (table, p)));
+        }
+        progress.add_total(partitions.len());
+
+        for (table, partition) in partitions {
+            self.merge_partition(table, partition).await?;
+            progress.add_done(1);
+        }
+
+        Ok(())
+    }
+
+    async fn merge_partition(&self, table: &TableMerge, partition: Partition) -> Result<()> {
+        let mut repos = self.catalog.repositories().await;
+
+        let files = repos
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition.transition_partition_id())
+            .await
+            .context(CatalogSnafu)?;
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let mut target_partition = repos
+            .partitions()
+            .create_or_get(partition.partition_key.clone(), table.target.id)
+            .await
+            .context(CatalogSnafu)?;
+        let target_partition_id = target_partition.transition_partition_id();
+
+        // extend the sort key of the target partition with the columns of
+        // the sort key of the source partition
+        let source_sort_key: Vec<_> = partition.sort_key.iter().map(|c| c.as_str()).collect();
+        loop {
+            // the sort key of the target partition may have changed since
+            // the merge was validated
+            if let Some(conflict) =
+                sort_key_conflict(&table.target.name, &partition, &target_partition)
+            {
+                return SchemaConflictsSnafu {
+                    source_namespace: &self.source.name,
+                    target_namespace: &self.target.name,
+                    conflicts: vec![conflict],
+                }
+                .fail();
+            }
+
+            let new_sort_key = match target_partition.sort_key() {
+                Some(catalog_sort_key) => {
+                    adjust_sort_key_columns(&catalog_sort_key, &source_sort_key).1
+                }
+                None => partition.sort_key(),
+            };
+            let Some(new_sort_key) = new_sort_key else {
+                break;
+            };
+
+            let new_sort_key: Vec<_> = new_sort_key.to_columns().collect();
+            let new_sort_key_ids = table.target_columns.ids_for_names(&new_sort_key);
+            match repos
+                .partitions()
+                .cas_sort_key(
+                    &target_partition_id,
+                    Some(target_partition.sort_key.clone()),
+                    Some(target_partition.sort_key_ids.clone()),
+                    &new_sort_key,
+                    &new_sort_key_ids,
+                )
+                .await
+            {
+                Ok(_) => break,
+                Err(CasFailure::ValueMismatch(_)) => {
+                    // the sort key was changed concurrently, adjust the new one
+                    target_partition = repos
+                        .partitions()
+                        .create_or_get(partition.partition_key.clone(), table.target.id)
+                        .await
+                        .context(CatalogSnafu)?;
+                }
+                Err(CasFailure::QueryError(e)) => return Err(Error::Catalog { source: e }),
+            }
+        }
+
+        // the compacted files of the source partition may overlap the
+        // compacted files of the target partition, so they are adopted as L0
+        // files unless the target partition has no files
+        let keep_levels = repos
+            .parquet_files()
+            .list_by_partition_not_to_delete(&target_partition_id)
+            .await
+            .context(CatalogSnafu)?
+            .is_empty();
+        drop(repos);
+
+        let mut delete = Vec::with_capacity(files.len());
+        let mut create = Vec::with_capacity(files.len());
+        for file in files {
+            let object_store_id = Uuid::new_v4();
+            let from = ParquetFilePath::from(&file).object_store_path();
+            let to = ParquetFilePath::new(
+                self.target.id,
+                table.target.id,
+                &target_partition_id,
+                object_store_id,
+            )
+            .object_store_path();
+            self.store
+                .object_store()
+                .copy(&from, &to)
+                .await
+                .context(CopySnafu)?;
+
+            create.push(ParquetFileParams {
+                namespace_id: self.target.id,
+                table_id: table.target.id,
+                partition_id: target_partition_id.clone(),
+                object_store_id,
+                min_time: file.min_time,
+                max_time: file.max_time,
+                file_size_bytes: file.file_size_bytes,
+                row_count: file.row_count,
+                compaction_level: if keep_levels {
+                    file.compaction_level
+                } else {
+                    CompactionLevel::Initial
+                },
+                created_at: file.created_at,
+                column_set: ColumnSet::new(
+                    file.column_set
+                        .iter()
+                        .filter_map(|id| table.column_ids.get(id).copied()),
+                ),
+                max_l0_created_at: file.max_l0_created_at,
+                content_hash: file.content_hash,
+            });
+            delete.push(file.id);
+        }
+
+        self.catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .create_upgrade_delete(&delete, &[], &create, CompactionLevel::Initial)
+            .await
+            .context(CatalogSnafu)?;
+
+        debug!(
+            source_partition_id = partition.id.get(),
+            target_partition_id = target_partition.id.get(),
+            n_files = delete.len(),
+            "merged partition"
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::{
+        partition_template::{test_table_partition_override, TemplatePart},
+        NamespaceId,
+    };
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use tracker::{AbstractTaskRegistry, TaskRegistry};
+
+    use super::*;
+
+    fn table(
+        id: i64,
+        name: &str,
+        columns: &[(&str, ColumnType)],
+    ) -> (String, (Table, Vec<Column>)) {
+        let table = Table {
+            id: TableId::new(id),
+            namespace_id: NamespaceId::new(id),
+            name: name.to_string(),
+            partition_template: Default::default(),
+            retention_period_ns: None,
+        };
+        let columns = columns
+            .iter()
+            .enumerate()
+            .map(|(i, (name, column_type))| Column {
+                id: ColumnId::new(id * 100 + i as i64),
+                table_id: table.id,
+                name: name.to_string(),
+                column_type: *column_type,
+            })
+            .collect();
+        (name.to_string(), (table, columns))
+    }
+
+    #[test]
+    fn test_schema_conflicts() {
+        let source = BTreeMap::from([
+            table(
+                1,
+                "cpu",
+                &[
+                    ("host", ColumnType::Tag),
+                    ("usage", ColumnType::F64),
+                    ("time", ColumnType::Time),
+                ],
+            ),
+            table(2, "mem", &[("free", ColumnType::I64)]),
+            table(3, "disk", &[("used", ColumnType::I64)]),
+        ]);
+        let mut target = BTreeMap::from([
+            table(
+                4,
+                "cpu",
+                &[
+                    ("host", ColumnType::Tag),
+                    ("usage", ColumnType::I64),
+                    ("region", ColumnType::Tag),
+                ],
+            ),
+            table(5, "mem", &[("free", ColumnType::I64)]),
+        ]);
+
+        assert_eq!(
+            schema_conflicts(&source, &target, &[], &HashMap::new()),
+            vec![SchemaConflict::ColumnType {
+                table: "cpu".to_string(),
+                column: "usage".to_string(),
+                source_type: ColumnType::F64,
+                target_type: ColumnType::I64,
+            }]
+        );
+
+        target.get_mut("mem").unwrap().0.partition_template =
+            test_table_partition_override(vec![TemplatePart::TimeFormat("%Y")]);
+        assert_eq!(
+            schema_conflicts(&source, &target, &[TableId::new(3)], &HashMap::new()),
+            vec![
+                SchemaConflict::ColumnType {
+                    table: "cpu".to_string(),
+                    column: "usage".to_string(),
+                    source_type: ColumnType::F64,
+                    target_type: ColumnType::I64,
+                },
+                SchemaConflict::ChangedColumns {
+                    table: "disk".to_string()
+                },
+                SchemaConflict::PartitionTemplate {
+                    table: "mem".to_string()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_namespace() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+
+        let source = catalog.create_namespace_1hr_retention("team_a").await;
+        let cpu = source.create_table("cpu").await;
+        cpu.create_column("host", ColumnType::Tag).await;
+        cpu.create_column("usage", ColumnType::F64).await;
+        cpu.create_column("time", ColumnType::Time).await;
+        let partition = cpu.create_partition("1970-01-01").await;
+        partition
+            .create_parquet_file(
+                TestParquetFileBuilder::default()
+                    .with_line_protocol("cpu,host=a usage=1 10\ncpu,host=b usage=2 10"),
+            )
+            .await;
+        let mem = source.create_table("mem").await;
+        mem.create_column("free", ColumnType::I64).await;
+        mem.create_column("time", ColumnType::Time).await;
+        mem.create_partition("1970-01-01")
+            .await
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("mem free=1i 10"),
+            )
+            .await;
+
+        let target = catalog.create_namespace_1hr_retention("team_b").await;
+        let target_cpu = target.create_table("cpu").await;
+        target_cpu.create_column("region", ColumnType::Tag).await;
+        target_cpu.create_column("usage", ColumnType::F64).await;
+        target_cpu.create_column("time", ColumnType::Time).await;
+        target_cpu
+            .create_partition("1970-01-01")
+            .await
+            .create_parquet_file(
+                TestParquetFileBuilder::default()
+                    .with_line_protocol("cpu,region=west usage=3 10")
+                    .with_compaction_level(CompactionLevel::FileNonOverlapped),
+            )
+            .await;
+
+        let merger = NamespaceMerger::try_new(
+            catalog.catalog(),
+            catalog.parquet_store.clone(),
+            NamespaceMerge {
+                source_namespace: "team_a".to_string(),
+                target_namespace: "team_b".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut registry = TaskRegistry::new(catalog.time_provider());
+        let (_tracker, registration) = registry.register(merger.job());
+        merger.run(registration.progress_reporter()).await.unwrap();
+
+        // the files of the source namespace are adopted by the target
+        // namespace, as L0 files where the target partition has files
+        let files = catalog
+            .list_by_table_not_to_delete(target_cpu.table.id)
+            .await;
+        assert_eq!(files.len(), 2);
+        let adopted = files
+            .iter()
+            .find(|f| f.compaction_level == CompactionLevel::Initial)
+            .unwrap();
+        assert_eq!(adopted.row_count, 2);
+        let columns = target_cpu.catalog_schema().await.columns;
+        let mut names: Vec<_> = adopted
+            .column_set
+            .iter()
+            .map(|id| {
+                columns
+                    .iter()
+                    .find(|(_, c)| c.id == *id)
+                    .unwrap()
+                    .0
+                    .as_str()
+            })
+            .collect();
+        names.sort_unstable();
+        assert_eq!(names, ["host", "time", "usage"]);
+
+        let target_partition = catalog
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_by_table_id(target_cpu.table.id)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(target_partition.sort_key, ["region", "host", "time"]);
+
+        // the object store holds a copy of the adopted file
+        let path = ParquetFilePath::from(adopted).object_store_path();
+        catalog.object_store.head(&path).await.unwrap();
+
+        // tables missing from the target namespace are created
+        let target_mem = catalog
+            .catalog
+            .repositories()
+            .await
+            .tables()
+            .get_by_namespace_and_name(target.namespace.id, "mem")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            catalog
+                .list_by_table_not_to_delete(target_mem.id)
+                .await
+                .len(),
+            1
+        );
+
+        // the source namespace no longer has files
+        assert!(catalog
+            .list_by_table_not_to_delete(cpu.table.id)
+            .await
+            .is_empty());
+        assert!(catalog
+            .list_by_table_not_to_delete(mem.table.id)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_namespace_sort_key_conflict() {
+        let catalog = TestCatalog::new();
+
+        let source = catalog.create_namespace_1hr_retention("team_a").await;
+        let cpu = source.create_table("cpu").await;
+        let host = cpu.create_column("host", ColumnType::Tag).await;
+        let region = cpu.create_column("region", ColumnType::Tag).await;
+        cpu.create_column("usage", ColumnType::F64).await;
+        let time = cpu.create_column("time", ColumnType::Time).await;
+        cpu.create_partition_with_sort_key(
+            "1970-01-01",
+            &["host", "region", "time"],
+            &[
+                host.column.id.get(),
+                region.column.id.get(),
+                time.column.id.get(),
+            ],
+        )
+        .await
+        .create_parquet_file(
+            TestParquetFileBuilder::default()
+                .with_line_protocol("cpu,host=a,region=west usage=1 10"),
+        )
+        .await;
+
+        let target = catalog.create_namespace_1hr_retention("team_b").await;
+        let target_cpu = target.create_table("cpu").await;
+        let region = target_cpu.create_column("region", ColumnType::Tag).await;
+        let host = target_cpu.create_column("host", ColumnType::Tag).await;
+        let time = target_cpu.create_column("time", ColumnType::Time).await;
+        target_cpu
+            .create_partition_with_sort_key(
+                "1970-01-01",
+                &["region", "host", "time"],
+                &[
+                    region.column.id.get(),
+                    host.column.id.get(),
+                    time.column.id.get(),
+                ],
+            )
+            .await;
+
+        // the files of the source partition are sorted by host first, while
+        // the files of the target partition are sorted by region first
+        let err = NamespaceMerger::try_new(
+            catalog.catalog(),
+            catalog.parquet_store.clone(),
+            NamespaceMerge {
+                source_namespace: "team_a".to_string(),
+                target_namespace: "team_b".to_string(),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_matches!(
+            err,
+            Error::SchemaConflicts { conflicts, .. } if conflicts == [SchemaConflict::SortKey {
+                table: "cpu".to_string(),
+                partition_key: "1970-01-01".to_string(),
+                source_sort_key: vec!["host".to_string(), "region".to_string(), "time".to_string()],
+                target_sort_key: vec!["region".to_string(), "host".to_string(), "time".to_string()],
+            }]
+        );
+
+        // nothing was merged
+        assert_eq!(
+            catalog
+                .list_by_table_not_to_delete(cpu.table.id)
+                .await
+                .len(),
+            1
+        );
+        assert!(catalog
+            .list_by_table_not_to_delete(target_cpu.table.id)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_namespace_errors() {
+        let catalog = TestCatalog::new();
+        let source = catalog.create_namespace_1hr_retention("team_a").await;
+        source
+            .create_table("cpu")
+            .await
+            .create_column("usage", ColumnType::F64)
+            .await;
+        let target = catalog.create_namespace_1hr_retention("team_b").await;
+        target
+            .create_table("cpu")
+            .await
+            .create_column("usage", ColumnType::I64)
+            .await;
+
+        let try_new = |source_namespace: &str, target_namespace: &str| {
+            NamespaceMerger::try_new(
+                catalog.catalog(),
+                catalog.parquet_store.clone(),
+                NamespaceMerge {
+                    source_namespace: source_namespace.to_string(),
+                    target_namespace: target_namespace.to_string(),
+                },
+            )
+        };
+
+        assert_matches!(
+            try_new("team_a", "team_a").await,
+            Err(Error::SameNamespace { name }) if name == "team_a"
+        );
+        assert_matches!(
+            try_new("team_c", "team_a").await,
+            Err(Error::NamespaceNotFound { name }) if name == "team_c"
+        );
+        assert_matches!(
+            try_new("team_a", "team_b").await,
+            Err(Error::SchemaConflicts { conflicts, .. }) if conflicts.len() == 1
+        );
+    }
+}
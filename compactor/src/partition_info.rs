@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use data_types::{
-    NamespaceId, PartitionHashId, PartitionId, PartitionKey, Table, TableSchema,
+    ColumnRename, NamespaceId, PartitionHashId, PartitionId, PartitionKey, Table, TableSchema,
     TransitionPartitionId,
 };
 use schema::sort::SortKey;
@@ -34,6 +34,9 @@ pub struct PartitionInfo {
 
     /// partition_key
     pub partition_key: PartitionKey,
+
+    /// Renames of the table's columns, to read files written before a rename
+    pub column_renames: Vec<ColumnRename>,
 }
 
 impl PartitionInfo {
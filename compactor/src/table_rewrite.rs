@@ -0,0 +1,1063 @@
+//! Rewrite the data of a table into another table.
+//!
+//! A rewrite copies the rows of a table passing a predicate into another
+//! table of the same namespace, optionally adding, removing or renaming tags
+//! on the way. This is used to fix bad schemas in place, e.g. to split a
+//! table or to move a value that was written as part of the table name into
+//! a tag.
+//!
+//! Each partition of the source table is rewritten into the partition with
+//! the same key of the target table, which therefore must use the same
+//! partition template, and the tags used by the partition template cannot
+//! be changed. The rows of a partition are deduplicated, filtered and
+//! rewritten in memory and then written as a single L0 file, which is
+//! compacted as usual afterwards.
+//!
+//! The source table is not modified. Data written to the source table while
+//! the rewrite runs may or may not be copied.
+
+use std::{any::Any, collections::HashSet, sync::Arc};
+
+use data_types::{
+    job::Job, partition_template::TemplatePart, ChunkId, ChunkOrder, Column, ColumnRename,
+    ColumnType, ColumnsByName, CompactionLevel, Namespace, ParquetFile, Partition, PartitionKey,
+    Table, TableSchema, TransitionPartitionId,
+};
+use datafusion::{
+    arrow::{datatypes::DataType, record_batch::RecordBatch},
+    error::DataFusionError,
+    logical_expr::LogicalPlanBuilder,
+    physical_plan::Statistics,
+    prelude::{cast, lit, Column as DFColumn, Expr},
+};
+use iox_catalog::interface::{CasFailure, Catalog, SoftDeletedRows};
+use iox_query::{
+    exec::{Executor, ExecutorType},
+    frontend::reorg::ReorgPlanner,
+    util::create_basic_summary,
+    QueryChunk, QueryChunkData,
+};
+use iox_time::TimeProvider;
+use observability_deps::tracing::debug;
+use parquet_file::{
+    metadata::IoxMetadata,
+    serialize::CodecError,
+    storage::{ParquetStorage, UploadError},
+    ParquetFilePath,
+};
+use predicate::Predicate;
+use schema::{
+    sort::{adjust_sort_key_columns, compute_sort_key, SortKey},
+    Schema,
+};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use tracker::ProgressReporter;
+use uuid::Uuid;
+
+use crate::{
+    components::df_planner::query_chunk::to_query_chunks, partition_info::PartitionInfo,
+    plan_ir::FileIR,
+};
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("namespace {name} not found"))]
+    NamespaceNotFound { name: String },
+
+    #[snafu(display("table {name} not found"))]
+    TableNotFound { name: String },
+
+    #[snafu(display("cannot rewrite table {name} into itself"))]
+    SameTable { name: String },
+
+    #[snafu(display(
+        "table {target_table} does not have the partition template of table {source_table}"
+    ))]
+    PartitionTemplateMismatch {
+        source_table: String,
+        target_table: String,
+    },
+
+    #[snafu(display("tag {name} not found"))]
+    TagNotFound { name: String },
+
+    #[snafu(display("cannot change tag {name}: it is used by the partition template"))]
+    PartitionTag { name: String },
+
+    #[snafu(display("column {name} already exists"))]
+    ColumnExists { name: String },
+
+    #[snafu(display("catalog error: {source}"))]
+    Catalog {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("cannot plan rewrite: {source}"))]
+    Planning {
+        source: iox_query::frontend::reorg::Error,
+    },
+
+    #[snafu(display("cannot execute rewrite: {source}"))]
+    Execution { source: DataFusionError },
+
+    #[snafu(display("cannot upload parquet file: {source}"))]
+    Upload { source: UploadError },
+}
+
+/// A specialized `Result` for table rewrite errors
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A change of the tags of the rewritten rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagOp {
+    /// Set the tag `name` to `value` in every row, adding the tag if it does
+    /// not exist
+    Add {
+        /// The name of the tag
+        name: String,
+        /// The value of the tag
+        value: String,
+    },
+
+    /// Remove the tag `name`.
+    ///
+    /// Rows that only differ by this tag become duplicates, of which only
+    /// the most recently written one is kept.
+    Remove {
+        /// The name of the tag
+        name: String,
+    },
+
+    /// Rename the tag `from` to `to`
+    Rename {
+        /// The current name of the tag
+        from: String,
+        /// The new name of the tag
+        to: String,
+    },
+}
+
+impl TagOp {
+    /// The names of the tags changed by this operation
+    fn tag_names(&self) -> Vec<&str> {
+        match self {
+            Self::Add { name, .. } | Self::Remove { name } => vec![name],
+            Self::Rename { from, to } => vec![from, to],
+        }
+    }
+}
+
+/// A rewrite of the data of a table into another table.
+#[derive(Debug, Clone)]
+pub struct TableRewrite {
+    /// The namespace of both tables
+    pub namespace: String,
+
+    /// The table the data is read from
+    pub source_table: String,
+
+    /// The table the data is written to.
+    ///
+    /// The table is created with the partition template of the source table
+    /// if it does not exist.
+    pub target_table: String,
+
+    /// Only the rows passing this predicate are copied. The predicate refers
+    /// to the columns of the source table.
+    pub predicate: Predicate,
+
+    /// The changes of the tags of the copied rows, applied in order
+    pub tag_ops: Vec<TagOp>,
+}
+
+/// Where the values of a column of the rewritten data come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ColumnSource {
+    /// The column of the source table with this name
+    Column(String),
+    /// A constant tag value
+    Constant(String),
+}
+
+/// A column of the rewritten data
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OutputColumn {
+    name: String,
+    column_type: ColumnType,
+    source: ColumnSource,
+}
+
+/// Apply `tag_ops` to the `columns` of the `source` table, returning the
+/// columns of the rewritten data.
+fn output_columns(
+    source: &Table,
+    columns: &[Column],
+    tag_ops: &[TagOp],
+) -> Result<Vec<OutputColumn>> {
+    let partition_tags: HashSet<&str> = source
+        .partition_template
+        .parts()
+        .filter_map(|part| match part {
+            TemplatePart::TagValue(tag) => Some(tag),
+            TemplatePart::TimeFormat(_) => None,
+        })
+        .collect();
+
+    let mut output: Vec<_> = columns
+        .iter()
+        .map(|c| OutputColumn {
+            name: c.name.clone(),
+            column_type: c.column_type,
+            source: ColumnSource::Column(c.name.clone()),
+        })
+        .collect();
+
+    let find_tag = |output: &[OutputColumn], name: &str| {
+        output
+            .iter()
+            .position(|c| c.name == name && c.column_type == ColumnType::Tag)
+            .context(TagNotFoundSnafu { name })
+    };
+
+    for op in tag_ops {
+        for name in op.tag_names() {
+            ensure!(!partition_tags.contains(name), PartitionTagSnafu { name });
+        }
+
+        match op {
+            TagOp::Add { name, value } => match output.iter_mut().find(|c| &c.name == name) {
+                Some(c) if c.column_type == ColumnType::Tag => {
+                    c.source = ColumnSource::Constant(value.clone());
+                }
+                Some(_) => return ColumnExistsSnafu { name }.fail(),
+                None => output.push(OutputColumn {
+                    name: name.clone(),
+                    column_type: ColumnType::Tag,
+                    source: ColumnSource::Constant(value.clone()),
+                }),
+            },
+            TagOp::Remove { name } => {
+                let idx = find_tag(&output, name)?;
+                output.remove(idx);
+            }
+            TagOp::Rename { from, to } => {
+                ensure!(
+                    !output.iter().any(|c| &c.name == to),
+                    ColumnExistsSnafu { name: to }
+                );
+                let idx = find_tag(&output, from)?;
+                output[idx].name = to.clone();
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Runs a validated [`TableRewrite`].
+#[derive(Debug)]
+pub(crate) struct TableRewriter {
+    catalog: Arc<dyn Catalog>,
+    store: ParquetStorage,
+    exec: Arc<Executor>,
+    time_provider: Arc<dyn TimeProvider>,
+    namespace: Namespace,
+    source: Table,
+    source_schema: Arc<TableSchema>,
+    source_renames: Vec<ColumnRename>,
+    target: Table,
+    target_columns: ColumnsByName,
+    output: Vec<OutputColumn>,
+    predicate: Predicate,
+}
+
+impl TableRewriter {
+    /// Validate `rewrite` and prepare the target table, creating the table
+    /// and its columns as needed.
+    pub(crate) async fn try_new(
+        catalog: Arc<dyn Catalog>,
+        store: ParquetStorage,
+        exec: Arc<Executor>,
+        time_provider: Arc<dyn TimeProvider>,
+        rewrite: TableRewrite,
+    ) -> Result<Self> {
+        let TableRewrite {
+            namespace,
+            source_table,
+            target_table,
+            predicate,
+            tag_ops,
+        } = rewrite;
+
+        ensure!(
+            source_table != target_table,
+            SameTableSnafu { name: source_table }
+        );
+
+        let mut repos = catalog.repositories().await;
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .context(CatalogSnafu)?
+            .context(NamespaceNotFoundSnafu { name: namespace })?;
+
+        let source = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &source_table)
+            .await
+            .context(CatalogSnafu)?
+            .context(TableNotFoundSnafu { name: source_table })?;
+        let source_columns = repos
+            .columns()
+            .list_by_table_id(source.id)
+            .await
+            .context(CatalogSnafu)?;
+        let source_renames = repos
+            .columns()
+            .list_renames_by_table_id(source.id)
+            .await
+            .context(CatalogSnafu)?;
+
+        let output = output_columns(&source, &source_columns, &tag_ops)?;
+
+        let target = match repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &target_table)
+            .await
+            .context(CatalogSnafu)?
+        {
+            Some(target) => {
+                ensure!(
+                    target.partition_template == source.partition_template,
+                    PartitionTemplateMismatchSnafu {
+                        source_table: &source.name,
+                        target_table: &target.name,
+                    }
+                );
+                target
+            }
+            None => repos
+                .tables()
+                .create(
+                    &target_table,
+                    source.partition_template.clone(),
+                    namespace.id,
+                )
+                .await
+                .context(CatalogSnafu)?,
+        };
+
+        // Like the router, the column limit of the namespace is not checked
+        // here.
+        let target_columns = repos
+            .columns()
+            .create_or_get_many_unchecked(
+                target.id,
+                output
+                    .iter()
+                    .map(|c| (c.name.as_str(), c.column_type))
+                    .collect(),
+            )
+            .await
+            .context(CatalogSnafu)?;
+        drop(repos);
+
+        let source_schema = Arc::new(TableSchema {
+            id: source.id,
+            partition_template: source.partition_template.clone(),
+            columns: ColumnsByName::new(source_columns),
+        });
+
+        Ok(Self {
+            catalog,
+            store,
+            exec,
+            time_provider,
+            namespace,
+            source,
+            source_schema,
+            source_renames,
+            target,
+            target_columns: ColumnsByName::new(target_columns),
+            output,
+            predicate,
+        })
+    }
+
+    /// The job tracking this rewrite
+    pub(crate) fn job(&self) -> Job {
+        Job::RewriteTable {
+            source_table_id: self.source.id,
+            target_table_id: self.target.id,
+        }
+    }
+
+    /// Rewrite all partitions of the source table, one at a time.
+    pub(crate) async fn run(self, progress: ProgressReporter) -> Result<()> {
+        let partitions = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_by_table_id(self.source.id)
+            .await
+            .context(CatalogSnafu)?;
+        progress.add_total(partitions.len());
+
+        for partition in partitions {
+            self.rewrite_partition(partition).await?;
+            progress.add_done(1);
+        }
+
+        Ok(())
+    }
+
+    async fn rewrite_partition(&self, partition: Partition) -> Result<()> {
+        let mut files = self
+            .catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition.transition_partition_id())
+            .await
+            .context(CatalogSnafu)?;
+        if let Some(range) = self.predicate.range {
+            files.retain(|f| f.min_time.get() < range.end() && f.max_time.get() >= range.start());
+        }
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let batches = self.read_partition(&partition, files).await?;
+        if batches.iter().all(|b| b.num_rows() == 0) {
+            debug!(
+                partition_id = partition.id.get(),
+                "no rows to rewrite in partition"
+            );
+            return Ok(());
+        }
+
+        self.write_partition(partition.partition_key, batches).await
+    }
+
+    /// Read the deduplicated rows of `files` passing the predicate, with the
+    /// columns of the target table.
+    async fn read_partition(
+        &self,
+        partition: &Partition,
+        files: Vec<ParquetFile>,
+    ) -> Result<Vec<RecordBatch>> {
+        // read the columns of the files, and the columns the predicate
+        // refers to
+        let filter = self.predicate.filter_expr();
+        let mut columns: HashSet<String> = filter
+            .iter()
+            .flat_map(|expr| expr.to_columns().unwrap_or_default())
+            .map(|c| c.name)
+            .filter(|name| self.source_schema.columns.contains_column_name(name))
+            .collect();
+        let column_names = self.source_schema.column_id_map();
+        columns.extend(
+            files
+                .iter()
+                .flat_map(|f| f.column_set.iter())
+                .filter_map(|id| column_names.get(id))
+                .map(|name| name.to_string()),
+        );
+        let columns: Vec<_> = columns.iter().map(|c| c.as_str()).collect();
+        let source_schema = Schema::try_from(self.source_schema.columns.clone())
+            .expect("table schema is broken")
+            .select_by_names(&columns)
+            .expect("schema in-sync");
+
+        // the sort key of the partition may not cover the columns the
+        // predicate refers to
+        let sort_key = adjust_sort_key_columns(
+            &partition.sort_key().unwrap_or_else(SortKey::empty),
+            &source_schema.primary_key(),
+        )
+        .0;
+
+        let partition_info = PartitionInfo {
+            partition_id: partition.id,
+            partition_hash_id: partition.hash_id().cloned(),
+            namespace_id: self.namespace.id,
+            namespace_name: self.namespace.name.clone(),
+            table: Arc::new(self.source.clone()),
+            table_schema: Arc::clone(&self.source_schema),
+            sort_key: Some(sort_key.clone()),
+            partition_key: partition.partition_key.clone(),
+            column_renames: self.source_renames.clone(),
+        };
+
+        let files: Vec<_> = files
+            .into_iter()
+            .map(|file| FileIR {
+                path: ParquetFilePath::from(&file),
+                order: ChunkOrder::new(file.max_l0_created_at.get()),
+                file,
+            })
+            .collect();
+        let chunks = to_query_chunks(&files, &partition_info, self.store.clone());
+
+        let plan = ReorgPlanner::new()
+            .compact_plan(
+                Arc::from(self.source.name.as_str()),
+                &source_schema,
+                chunks,
+                sort_key,
+            )
+            .context(PlanningSnafu)?;
+
+        let mut builder = LogicalPlanBuilder::from(plan);
+        if let Some(filter) = filter {
+            builder = builder.filter(filter).context(ExecutionSnafu)?;
+        }
+        let plan = builder
+            .project(
+                self.output
+                    .iter()
+                    .filter_map(|c| self.output_expr(c, &source_schema)),
+            )
+            .context(ExecutionSnafu)?
+            .build()
+            .context(ExecutionSnafu)?;
+
+        let ctx = self.exec.new_context(ExecutorType::Reorg);
+        let physical_plan = ctx
+            .create_physical_plan(&plan)
+            .await
+            .context(ExecutionSnafu)?;
+        ctx.collect(physical_plan).await.context(ExecutionSnafu)
+    }
+
+    /// The expression computing the output column `c`, if the data read
+    /// from the partition has a value for it.
+    fn output_expr(&self, c: &OutputColumn, source_schema: &Schema) -> Option<Expr> {
+        let expr = match &c.source {
+            ColumnSource::Column(name) => {
+                source_schema.find_index_of(name)?;
+                Expr::Column(DFColumn::from_name(name))
+            }
+            ColumnSource::Constant(value) => cast(
+                lit(value.as_str()),
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            ),
+        };
+        Some(expr.alias(&c.name))
+    }
+
+    /// Write the rewritten `batches` into the partition `partition_key` of
+    /// the target table.
+    async fn write_partition(
+        &self,
+        partition_key: PartitionKey,
+        batches: Vec<RecordBatch>,
+    ) -> Result<()> {
+        let column_names: Vec<_> = batches[0]
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect();
+        let column_names: Vec<_> = column_names.iter().map(|c| c.as_str()).collect();
+        let schema = Schema::try_from(self.target_columns.clone())
+            .expect("table schema is broken")
+            .select_by_names(&column_names)
+            .expect("schema in-sync");
+
+        // restore the schema metadata lost by the projection
+        let batches = batches
+            .into_iter()
+            .map(|batch| RecordBatch::try_new(schema.as_arrow(), batch.columns().to_vec()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| DataFusionError::ArrowError(e, None))
+            .context(ExecutionSnafu)?;
+
+        let mut repos = self.catalog.repositories().await;
+        let mut partition = repos
+            .partitions()
+            .create_or_get(partition_key.clone(), self.target.id)
+            .await
+            .context(CatalogSnafu)?;
+
+        // extend the sort key of the target partition with the new columns,
+        // as the ingester does when persisting
+        let sort_key = loop {
+            let (sort_key, catalog_update) = match partition.sort_key() {
+                Some(catalog_sort_key) => {
+                    adjust_sort_key_columns(&catalog_sort_key, &schema.primary_key())
+                }
+                None => {
+                    let sort_key = compute_sort_key(&schema, batches.iter());
+                    (sort_key.clone(), Some(sort_key))
+                }
+            };
+            let Some(new_sort_key) = catalog_update else {
+                break sort_key;
+            };
+
+            let new_sort_key: Vec<_> = new_sort_key.to_columns().collect();
+            let new_sort_key_ids = self.target_columns.ids_for_names(&new_sort_key);
+            match repos
+                .partitions()
+                .cas_sort_key(
+                    &partition.transition_partition_id(),
+                    Some(partition.sort_key.clone()),
+                    Some(partition.sort_key_ids.clone()),
+                    &new_sort_key,
+                    &new_sort_key_ids,
+                )
+                .await
+            {
+                Ok(_) => break sort_key,
+                Err(CasFailure::ValueMismatch(_)) => {
+                    // the sort key was changed concurrently, adjust the new one
+                    partition = repos
+                        .partitions()
+                        .create_or_get(partition_key.clone(), self.target.id)
+                        .await
+                        .context(CatalogSnafu)?;
+                }
+                Err(CasFailure::QueryError(e)) => return Err(Error::Catalog { source: e }),
+            }
+        };
+        drop(repos);
+
+        let partition_id = partition.transition_partition_id();
+        let chunk = Arc::new(RewrittenChunk::new(
+            partition_id.clone(),
+            schema.clone(),
+            batches,
+        ));
+        let plan = ReorgPlanner::new()
+            .compact_plan(
+                Arc::from(self.target.name.as_str()),
+                &schema,
+                [chunk as Arc<dyn QueryChunk>],
+                sort_key.clone(),
+            )
+            .context(PlanningSnafu)?;
+        let ctx = self.exec.new_context(ExecutorType::Reorg);
+        let physical_plan = ctx
+            .create_physical_plan(&plan)
+            .await
+            .context(ExecutionSnafu)?;
+        let stream = ctx
+            .execute_stream(physical_plan)
+            .await
+            .context(ExecutionSnafu)?;
+
+        let now = self.time_provider.now();
+        let meta = IoxMetadata {
+            object_store_id: Uuid::new_v4(),
+            creation_timestamp: now,
+            namespace_id: self.namespace.id,
+            namespace_name: self.namespace.name.clone().into(),
+            table_id: self.target.id,
+            table_name: self.target.name.clone().into(),
+            partition_key,
+            compaction_level: CompactionLevel::Initial,
+            sort_key: Some(sort_key),
+            max_l0_created_at: now,
+        };
+        let (parquet_meta, file_size, content_hash) = match self
+            .store
+            .upload(stream, &partition_id, &meta, self.exec.pool())
+            .await
+        {
+            Ok(v) => v,
+            // all rows were removed by deduplication
+            Err(UploadError::Serialise(CodecError::NoRows | CodecError::NoRecordBatches)) => {
+                return Ok(())
+            }
+            Err(e) => return Err(Error::Upload { source: e }),
+        };
+
+        let params = meta.to_parquet_file(
+            partition_id,
+            file_size,
+            content_hash,
+            &parquet_meta,
+            |name| self.target_columns.get(name).expect("unknown column").id,
+        );
+        let file = self
+            .catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .create(params)
+            .await
+            .context(CatalogSnafu)?;
+
+        debug!(
+            parquet_file_id = file.id.get(),
+            target_table_id = self.target.id.get(),
+            "rewrote partition"
+        );
+
+        Ok(())
+    }
+}
+
+/// The rewritten rows of a partition, which may contain duplicates.
+#[derive(Debug)]
+struct RewrittenChunk {
+    id: ChunkId,
+    partition_id: TransitionPartitionId,
+    schema: Schema,
+    batches: Vec<RecordBatch>,
+    stats: Arc<Statistics>,
+}
+
+impl RewrittenChunk {
+    fn new(partition_id: TransitionPartitionId, schema: Schema, batches: Vec<RecordBatch>) -> Self {
+        let rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        let stats = Arc::new(create_basic_summary(rows as u64, &schema, None));
+        Self {
+            id: ChunkId::new(),
+            partition_id,
+            schema,
+            batches,
+            stats,
+        }
+    }
+}
+
+impl QueryChunk for RewrittenChunk {
+    fn stats(&self) -> Arc<Statistics> {
+        Arc::clone(&self.stats)
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn partition_id(&self) -> &TransitionPartitionId {
+        &self.partition_id
+    }
+
+    fn sort_key(&self) -> Option<&SortKey> {
+        None
+    }
+
+    fn id(&self) -> ChunkId {
+        self.id
+    }
+
+    fn may_contain_pk_duplicates(&self) -> bool {
+        // removing or renaming tags may turn different series into the same
+        true
+    }
+
+    fn data(&self) -> QueryChunkData {
+        QueryChunkData::in_mem(self.batches.clone(), Arc::clone(self.schema.inner()))
+    }
+
+    fn chunk_type(&self) -> &str {
+        "RewrittenChunk"
+    }
+
+    fn order(&self) -> ChunkOrder {
+        ChunkOrder::new(0)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_util::assert_batches_sorted_eq;
+    use assert_matches::assert_matches;
+    use data_types::{partition_template::test_table_partition_override, ColumnId, TableId};
+    use datafusion::prelude::col;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder, TestTable};
+    use tracker::{AbstractTaskRegistry, TaskRegistry};
+
+    use super::*;
+
+    fn table() -> Table {
+        Table {
+            id: TableId::new(1),
+            namespace_id: data_types::NamespaceId::new(1),
+            name: "cpu".to_string(),
+            partition_template: test_table_partition_override(vec![
+                TemplatePart::TagValue("region"),
+                TemplatePart::TimeFormat("%Y"),
+            ]),
+            retention_period_ns: None,
+        }
+    }
+
+    fn columns() -> Vec<Column> {
+        [
+            ("host", ColumnType::Tag),
+            ("region", ColumnType::Tag),
+            ("usage", ColumnType::F64),
+            ("time", ColumnType::Time),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(id, (name, column_type))| Column {
+            id: ColumnId::new(id as i64),
+            table_id: TableId::new(1),
+            name: name.to_string(),
+            column_type,
+        })
+        .collect()
+    }
+
+    fn column(name: &str, column_type: ColumnType, source: ColumnSource) -> OutputColumn {
+        OutputColumn {
+            name: name.to_string(),
+            column_type,
+            source,
+        }
+    }
+
+    fn from(name: &str) -> ColumnSource {
+        ColumnSource::Column(name.to_string())
+    }
+
+    fn constant(value: &str) -> ColumnSource {
+        ColumnSource::Constant(value.to_string())
+    }
+
+    #[test]
+    fn test_output_columns() {
+        let output = output_columns(
+            &table(),
+            &columns(),
+            &[
+                TagOp::Rename {
+                    from: "host".to_string(),
+                    to: "hostname".to_string(),
+                },
+                TagOp::Add {
+                    name: "dc".to_string(),
+                    value: "dc1".to_string(),
+                },
+                TagOp::Add {
+                    name: "dc".to_string(),
+                    value: "dc2".to_string(),
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            vec![
+                column("hostname", ColumnType::Tag, from("host")),
+                column("region", ColumnType::Tag, from("region")),
+                column("usage", ColumnType::F64, from("usage")),
+                column("time", ColumnType::Time, from("time")),
+                column("dc", ColumnType::Tag, constant("dc2")),
+            ]
+        );
+
+        // overwrite an existing tag, then remove it
+        let output = output_columns(
+            &table(),
+            &columns(),
+            &[
+                TagOp::Add {
+                    name: "host".to_string(),
+                    value: "a".to_string(),
+                },
+                TagOp::Remove {
+                    name: "host".to_string(),
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            vec![
+                column("region", ColumnType::Tag, from("region")),
+                column("usage", ColumnType::F64, from("usage")),
+                column("time", ColumnType::Time, from("time")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_columns_errors() {
+        let err = |op: TagOp| output_columns(&table(), &columns(), &[op]).unwrap_err();
+
+        assert_matches!(
+            err(TagOp::Remove { name: "region".to_string() }),
+            Error::PartitionTag { name } if name == "region"
+        );
+        assert_matches!(
+            err(TagOp::Rename {
+                from: "host".to_string(),
+                to: "region".to_string()
+            }),
+            Error::PartitionTag { name } if name == "region"
+        );
+        assert_matches!(
+            err(TagOp::Remove { name: "usage".to_string() }),
+            Error::TagNotFound { name } if name == "usage"
+        );
+        assert_matches!(
+            err(TagOp::Rename {
+                from: "bananas".to_string(),
+                to: "host2".to_string()
+            }),
+            Error::TagNotFound { name } if name == "bananas"
+        );
+        assert_matches!(
+            err(TagOp::Rename {
+                from: "host".to_string(),
+                to: "usage".to_string()
+            }),
+            Error::ColumnExists { name } if name == "usage"
+        );
+        assert_matches!(
+            err(TagOp::Add {
+                name: "usage".to_string(),
+                value: "a".to_string()
+            }),
+            Error::ColumnExists { name } if name == "usage"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_table() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("cpu").await;
+        table.create_column("host", ColumnType::Tag).await;
+        table.create_column("region", ColumnType::Tag).await;
+        table.create_column("usage", ColumnType::F64).await;
+        table.create_column("time", ColumnType::Time).await;
+        let partition = table.create_partition("1970-01-01").await;
+        partition
+            .create_parquet_file(TestParquetFileBuilder::default().with_line_protocol(
+                "cpu,host=a,region=west usage=1 10\n\
+                 cpu,host=b,region=west usage=2 10\n\
+                 cpu,host=c,region=east usage=3 10",
+            ))
+            .await;
+        // a newer value of host a
+        partition
+            .create_parquet_file(
+                TestParquetFileBuilder::default()
+                    .with_line_protocol("cpu,host=a,region=west usage=4 10")
+                    .with_max_l0_created_at(iox_time::Time::from_timestamp_nanos(2)),
+            )
+            .await;
+
+        let rewrite = TableRewrite {
+            namespace: "ns".to_string(),
+            source_table: "cpu".to_string(),
+            target_table: "cpu_west".to_string(),
+            predicate: Predicate::new().with_expr(col("region").eq(lit("west"))),
+            tag_ops: vec![
+                TagOp::Rename {
+                    from: "host".to_string(),
+                    to: "hostname".to_string(),
+                },
+                TagOp::Remove {
+                    name: "region".to_string(),
+                },
+                TagOp::Add {
+                    name: "dc".to_string(),
+                    value: "dc1".to_string(),
+                },
+            ],
+        };
+        let rewriter = TableRewriter::try_new(
+            catalog.catalog(),
+            catalog.parquet_store.clone(),
+            catalog.exec(),
+            catalog.time_provider(),
+            rewrite,
+        )
+        .await
+        .unwrap();
+
+        let mut registry = TaskRegistry::new(catalog.time_provider());
+        let (_tracker, registration) = registry.register(rewriter.job());
+        rewriter
+            .run(registration.progress_reporter())
+            .await
+            .unwrap();
+
+        let target = catalog
+            .catalog
+            .repositories()
+            .await
+            .tables()
+            .get_by_namespace_and_name(ns.namespace.id, "cpu_west")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.partition_template, table.table.partition_template);
+
+        let files = catalog.list_by_table_not_to_delete(target.id).await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].compaction_level, CompactionLevel::Initial);
+
+        let target = Arc::new(TestTable {
+            catalog: Arc::clone(&catalog),
+            namespace: Arc::clone(&ns),
+            table: target,
+        });
+        let batches = target.read_parquet_file(files[0].clone()).await;
+        assert_batches_sorted_eq!(
+            [
+                "+-----+----------+--------------------------------+-------+",
+                "| dc  | hostname | time                           | usage |",
+                "+-----+----------+--------------------------------+-------+",
+                "| dc1 | a        | 1970-01-01T00:00:00.000000010Z | 4.0   |",
+                "| dc1 | b        | 1970-01-01T00:00:00.000000010Z | 2.0   |",
+                "+-----+----------+--------------------------------+-------+",
+            ],
+            &batches
+        );
+
+        // the source table is left alone
+        let files = catalog.list_by_table_not_to_delete(table.table.id).await;
+        assert_eq!(files.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_table_errors() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        ns.create_table("cpu").await;
+
+        let try_new = |source_table: &str, target_table: &str| {
+            TableRewriter::try_new(
+                catalog.catalog(),
+                catalog.parquet_store.clone(),
+                catalog.exec(),
+                catalog.time_provider(),
+                TableRewrite {
+                    namespace: "ns".to_string(),
+                    source_table: source_table.to_string(),
+                    target_table: target_table.to_string(),
+                    predicate: Predicate::default(),
+                    tag_ops: vec![],
+                },
+            )
+        };
+
+        assert_matches!(
+            try_new("cpu", "cpu").await,
+            Err(Error::SameTable { name }) if name == "cpu"
+        );
+        assert_matches!(
+            try_new("mem", "cpu").await,
+            Err(Error::TableNotFound { name }) if name == "mem"
+        );
+    }
+}
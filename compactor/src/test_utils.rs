@@ -23,6 +23,7 @@ impl PartitionInfoBuilder {
             namespace_id,
             name: String::from("table"),
             partition_template: Default::default(),
+            retention_period_ns: None,
         });
         let table_schema = Arc::new(TableSchema::new_empty_from(&table));
 
@@ -36,6 +37,7 @@ impl PartitionInfoBuilder {
                 table_schema,
                 sort_key: None,
                 partition_key,
+                column_renames: vec![],
             },
         }
     }
@@ -26,6 +26,9 @@ pub enum ErrorKind {
     /// Partition took too long.
     Timeout,
 
+    /// The compaction job was cancelled.
+    Cancelled,
+
     /// Unknown/unexpected error.
     ///
     /// This will likely mark the affected partition as "skipped" and the compactor will no longer touch it.
@@ -39,6 +42,7 @@ impl ErrorKind {
             Self::ObjectStore => "object_store",
             Self::OutOfMemory => "out_of_memory",
             Self::Timeout => "timeout",
+            Self::Cancelled => "cancelled",
             Self::Unknown(_) => "unknown",
         }
     }
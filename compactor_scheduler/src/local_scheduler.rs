@@ -38,7 +38,7 @@ use self::{
         catalog_to_compact::CatalogToCompactPartitionsSource,
         filter::FilterPartitionsSourceWrapper, never_skipped::NeverSkippedPartitionsSource,
     },
-    partitions_subset_source::skipped::SkippedPartitionsSource,
+    partitions_subset_source::{frozen::FrozenPartitionsSource, skipped::SkippedPartitionsSource},
 };
 
 /// Configuration specific to the local scheduler.
@@ -144,10 +144,17 @@ impl LocalScheduler {
         if !config.ignore_partition_skip_marker {
             partitions_source = Arc::new(NeverSkippedPartitionsSource::new(
                 partitions_source,
-                SkippedPartitionsSource::new(backoff_config, Arc::clone(&catalog)),
+                SkippedPartitionsSource::new(backoff_config.clone(), Arc::clone(&catalog)),
             ));
         };
 
+        // Partitions frozen for maintenance are never compacted, even if the
+        // skip marker is ignored.
+        partitions_source = Arc::new(NeverSkippedPartitionsSource::new(
+            partitions_source,
+            FrozenPartitionsSource::new(backoff_config, Arc::clone(&catalog)),
+        ));
+
         let mut id_only_partition_filters: Vec<Arc<dyn IdOnlyPartitionFilter>> = vec![];
         if let Some(shard_config) = &shard_config {
             // add shard filter before performing any catalog IO
@@ -0,0 +1,50 @@
+use std::{fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use data_types::PartitionId;
+use iox_catalog::interface::Catalog;
+
+use super::PartitionsSubsetSource;
+
+/// Finds the partitions frozen for maintenance, which must not be compacted.
+#[derive(Debug)]
+pub(crate) struct FrozenPartitionsSource {
+    backoff_config: BackoffConfig,
+    catalog: Arc<dyn Catalog>,
+}
+
+impl FrozenPartitionsSource {
+    pub(crate) fn new(backoff_config: BackoffConfig, catalog: Arc<dyn Catalog>) -> Self {
+        Self {
+            backoff_config,
+            catalog,
+        }
+    }
+}
+
+impl Display for FrozenPartitionsSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "frozen_partitions_catalog")
+    }
+}
+
+#[async_trait]
+impl PartitionsSubsetSource for FrozenPartitionsSource {
+    async fn fetch(&self, partitions: &[PartitionId]) -> Vec<PartitionId> {
+        Backoff::new(&self.backoff_config)
+            .retry_all_errors("frozen_partitions_of_given_partitions", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .get_in_frozen(partitions)
+                    .await
+            })
+            .await
+            .expect("retry forever")
+            .iter()
+            .map(|f| f.partition_id)
+            .collect()
+    }
+}
@@ -1,3 +1,4 @@
+pub(crate) mod frozen;
 pub(crate) mod mock;
 pub(crate) mod skipped;
 
@@ -102,3 +102,42 @@ async fn test_will_throttle_partition_if_no_commits() {
         jobs
     );
 }
+
+#[tokio::test]
+async fn test_will_not_fetch_frozen_partitions() {
+    test_helpers::maybe_start_logging();
+
+    let test_scheduler = TestLocalScheduler::builder().await;
+    let expected_partition = test_scheduler.get_partition_id();
+    let scheduler = Arc::clone(&test_scheduler.scheduler);
+    let catalog = test_scheduler.catalog.catalog();
+
+    catalog
+        .repositories()
+        .await
+        .partitions()
+        .freeze(expected_partition, "maintenance")
+        .await
+        .unwrap();
+
+    // TEST: frozen partitions are not returned
+    let jobs = scheduler.get_jobs().await;
+    assert_matches!(
+        jobs[..],
+        [],
+        "expect frozen partition is not returned from get_jobs(), instead found {:?}",
+        jobs
+    );
+
+    catalog
+        .repositories()
+        .await
+        .partitions()
+        .unfreeze(expected_partition)
+        .await
+        .unwrap();
+
+    // TEST: the partition is returned once unfrozen
+    let jobs = scheduler.get_jobs().await;
+    test_scheduler.assert_matches_seeded_hot_partition(&jobs);
+}
@@ -38,7 +38,7 @@ use crate::{
 use async_trait::async_trait;
 use backoff::BackoffConfig;
 use compactor::{
-    compact, config::Config, hardcoded_components, Components, PanicDataFusionPlanner,
+    compact, config::Config, hardcoded_components, Components, JobRegistry, PanicDataFusionPlanner,
     PartitionInfo,
 };
 use compactor_scheduler::SchedulerConfig;
@@ -60,7 +60,7 @@ use object_store::{path::Path, DynObjectStore};
 use parquet_file::storage::{ParquetStorage, StorageId};
 use schema::sort::SortKey;
 use trace::{RingBufferTraceCollector, TraceCollector};
-use tracker::AsyncSemaphoreMetrics;
+use tracker::{AsyncSemaphoreMetrics, TaskRegistry};
 
 // Default values for the test setup builder
 const MAX_DESIRE_FILE_SIZE: u64 = 100 * 1024;
@@ -639,6 +639,7 @@ impl<const WITH_FILES: bool> TestSetupBuilder<WITH_FILES> {
             table_schema: Arc::new(self.table.catalog_schema().await),
             sort_key: self.partition.partition.sort_key(),
             partition_key: self.partition.partition.partition_key.clone(),
+            column_renames: vec![],
         });
 
         TestSetup {
@@ -768,6 +769,11 @@ impl TestSetup {
             Arc::clone(config.parquet_store_scratchpad.object_store()),
         );
 
+        let jobs = Arc::new(parking_lot::Mutex::new(JobRegistry::new(
+            TaskRegistry::new(Arc::clone(&config.time_provider)),
+            10,
+        )));
+
         compact(
             trace_collector,
             NonZeroUsize::new(10).unwrap(),
@@ -775,6 +781,7 @@ impl TestSetup {
             df_semaphore,
             &components,
             None,
+            &jobs,
         )
         .await;
 
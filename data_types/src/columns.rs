@@ -1,6 +1,6 @@
 //! Types having to do with columns.
 
-use super::TableId;
+use super::{TableId, Timestamp};
 use generated_types::influxdata::iox::{gossip, schema::v1 as proto};
 use influxdb_line_protocol::FieldValue;
 use schema::{builder::SchemaBuilder, InfluxColumnType, InfluxFieldType, Schema};
@@ -177,6 +177,112 @@ impl Column {
     }
 }
 
+/// A rename of a column, recorded so that data written before the rename
+/// can still be read using the name the column had at the time.
+#[derive(Debug, Clone, sqlx::FromRow, Eq, PartialEq)]
+pub struct ColumnRename {
+    /// the table id the column is in
+    pub table_id: TableId,
+    /// the renamed column
+    pub column_id: ColumnId,
+    /// the name of the column before the rename
+    pub old_name: String,
+    /// the name of the column after the rename
+    pub new_name: String,
+    /// when the column was renamed
+    pub renamed_at: Timestamp,
+}
+
+/// A column that was dropped from a table.
+#[derive(Debug, Clone, sqlx::FromRow, Eq, PartialEq)]
+pub struct ColumnDrop {
+    /// the table id the column was in
+    pub table_id: TableId,
+    /// the id the column had
+    pub column_id: ColumnId,
+    /// the name the column had
+    pub name: String,
+    /// when the column was dropped
+    pub dropped_at: Timestamp,
+}
+
+/// Returns the names that columns had at `time`, for the columns that
+/// were renamed after `time`.
+///
+/// Columns that were not renamed after `time` still have their current name
+/// and are not part of the returned map.
+pub fn column_names_at(renames: &[ColumnRename], time: Timestamp) -> HashMap<ColumnId, &str> {
+    let mut renames = renames
+        .iter()
+        .filter(|rename| rename.renamed_at > time)
+        .collect::<Vec<_>>();
+
+    // the first rename after `time` knows the name at `time`, so insert it
+    // last
+    renames.sort_by_key(|rename| std::cmp::Reverse(rename.renamed_at));
+    renames
+        .into_iter()
+        .map(|rename| (rename.column_id, rename.old_name.as_str()))
+        .collect()
+}
+
+/// Returns the names that the columns of a parquet file created at `created_at` are stored under, from the name in
+/// `columns` to the name within the file, for the columns where the two differ.
+///
+/// Readers expect a column under the name it had at `created_at`, see [`column_names_at`]. `columns` may be older
+/// than that (e.g. the columns used to plan the data of the file), but `renames` MUST be read after `created_at`,
+/// otherwise a rename that happened in between is missed and the file uses a name that readers do not expect.
+pub fn file_column_names(
+    columns: &ColumnsByName,
+    renames: &[ColumnRename],
+    created_at: Timestamp,
+) -> BTreeMap<String, String> {
+    columns
+        .iter()
+        .filter_map(|(name, column)| {
+            let mut renames = renames
+                .iter()
+                .filter(|rename| rename.column_id == column.id)
+                .collect::<Vec<_>>();
+            renames.sort_by_key(|rename| rename.renamed_at);
+
+            let name_in_file = match renames.iter().find(|rename| rename.renamed_at > created_at) {
+                Some(rename) => &rename.old_name,
+                None => renames
+                    .last()
+                    .map(|rename| &rename.new_name)
+                    .unwrap_or(name),
+            };
+            (name != name_in_file).then(|| (name.clone(), name_in_file.clone()))
+        })
+        .collect()
+}
+
+/// Returns the current name and schema of the column that is (or was) called `name`, e.g. in data that was written
+/// before the column was renamed.
+///
+/// A column that currently has the name takes precedence over a column that had it before a rename. Returns `None`
+/// for names of dropped and unknown columns.
+pub fn resolve_column_name<'a>(
+    columns: &'a ColumnsByName,
+    renames: &[ColumnRename],
+    name: &str,
+) -> Option<(&'a str, ColumnSchema)> {
+    if let Some((name, column)) = columns.0.get_key_value(name) {
+        return Some((name.as_str(), *column));
+    }
+
+    let column_id = renames
+        .iter()
+        .filter(|rename| rename.old_name == name)
+        .max_by_key(|rename| rename.renamed_at)?
+        .column_id;
+    columns
+        .iter()
+        .find(|(_name, column)| column.id == column_id)
+        .map(|(name, column)| (name.as_str(), *column))
+}
+
 /// The column id and its type for a column
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct ColumnSchema {
@@ -474,6 +580,123 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_column_names_at() {
+        let renames = [
+            rename(1, "b", "c", 20),
+            rename(1, "a", "b", 10),
+            rename(2, "x", "y", 30),
+        ];
+
+        assert_eq!(
+            column_names_at(&renames, Timestamp::new(5)),
+            HashMap::from([(ColumnId::new(1), "a"), (ColumnId::new(2), "x")])
+        );
+        assert_eq!(
+            column_names_at(&renames, Timestamp::new(10)),
+            HashMap::from([(ColumnId::new(1), "b"), (ColumnId::new(2), "x")])
+        );
+        assert_eq!(
+            column_names_at(&renames, Timestamp::new(25)),
+            HashMap::from([(ColumnId::new(2), "x")])
+        );
+        assert_eq!(
+            column_names_at(&renames, Timestamp::new(30)),
+            HashMap::new()
+        );
+    }
+
+    #[test]
+    fn test_file_column_names() {
+        let columns = ColumnsByName::new([
+            Column {
+                id: ColumnId::new(1),
+                table_id: TableId::new(1),
+                name: "c".to_string(),
+                column_type: ColumnType::F64,
+            },
+            Column {
+                id: ColumnId::new(2),
+                table_id: TableId::new(1),
+                name: "z".to_string(),
+                column_type: ColumnType::Tag,
+            },
+        ]);
+        let renames = [
+            rename(1, "b", "c", 20),
+            rename(1, "a", "b", 10),
+            // column 3 was dropped
+            rename(3, "q", "r", 10),
+        ];
+
+        assert_eq!(
+            file_column_names(&columns, &renames, Timestamp::new(5)),
+            BTreeMap::from([("c".to_string(), "a".to_string())])
+        );
+        assert_eq!(
+            file_column_names(&columns, &renames, Timestamp::new(15)),
+            BTreeMap::from([("c".to_string(), "b".to_string())])
+        );
+        assert_eq!(
+            file_column_names(&columns, &renames, Timestamp::new(20)),
+            BTreeMap::new()
+        );
+
+        // the columns were read before the last rename
+        let columns = ColumnsByName::new([Column {
+            id: ColumnId::new(1),
+            table_id: TableId::new(1),
+            name: "b".to_string(),
+            column_type: ColumnType::F64,
+        }]);
+        assert_eq!(
+            file_column_names(&columns, &renames, Timestamp::new(25)),
+            BTreeMap::from([("b".to_string(), "c".to_string())])
+        );
+        assert_eq!(
+            file_column_names(&columns, &renames, Timestamp::new(15)),
+            BTreeMap::new()
+        );
+    }
+
+    #[test]
+    fn test_resolve_column_name() {
+        let columns = ColumnsByName::new([
+            Column {
+                id: ColumnId::new(1),
+                table_id: TableId::new(1),
+                name: "c".to_string(),
+                column_type: ColumnType::F64,
+            },
+            Column {
+                id: ColumnId::new(2),
+                table_id: TableId::new(1),
+                name: "a".to_string(),
+                column_type: ColumnType::I64,
+            },
+        ]);
+        let renames = [rename(1, "b", "c", 20), rename(1, "a", "b", 10)];
+        let resolve = |name| {
+            resolve_column_name(&columns, &renames, name).map(|(name, column)| (name, column.id))
+        };
+
+        assert_eq!(resolve("c"), Some(("c", ColumnId::new(1))));
+        assert_eq!(resolve("b"), Some(("c", ColumnId::new(1))));
+        // the current column of that name wins
+        assert_eq!(resolve("a"), Some(("a", ColumnId::new(2))));
+        assert_eq!(resolve("x"), None);
+    }
+
+    fn rename(column_id: i64, old_name: &str, new_name: &str, renamed_at: i64) -> ColumnRename {
+        ColumnRename {
+            table_id: TableId::new(1),
+            column_id: ColumnId::new(column_id),
+            old_name: old_name.to_string(),
+            new_name: new_name.to_string(),
+            renamed_at: Timestamp::new(renamed_at),
+        }
+    }
+
     #[test]
     #[should_panic = "set contains duplicates"]
     fn test_column_set_duplicates() {
@@ -0,0 +1,131 @@
+//! The hash of the content of a parquet file.
+
+use sha2::Digest;
+use thiserror::Error;
+
+const CONTENT_HASH_SIZE_BYTES: usize = 32;
+
+/// The SHA-256 of the bytes of a parquet file as written to object storage.
+///
+/// Recorded in the catalog when the file is created, so that corrupted or
+/// modified files can be detected when they are read back.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ContentHash([u8; CONTENT_HASH_SIZE_BYTES]);
+
+impl ContentHash {
+    /// Compute the hash of `data`.
+    pub fn of(data: &[u8]) -> Self {
+        Self(sha2::Sha256::digest(data).into())
+    }
+
+    /// Read access to the bytes of the hash.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ContentHash({self})")
+    }
+}
+
+/// Reasons bytes specified aren't a valid [`ContentHash`].
+#[derive(Debug, Error)]
+#[allow(missing_copy_implementations)]
+pub enum ContentHashError {
+    /// The bytes specified were not valid
+    #[error("Could not interpret bytes as `ContentHash`: {data:?}")]
+    InvalidBytes {
+        /// The bytes used in the attempt to create a `ContentHash`
+        data: Vec<u8>,
+    },
+}
+
+impl TryFrom<&[u8]> for ContentHash {
+    type Error = ContentHashError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let data: [u8; CONTENT_HASH_SIZE_BYTES] =
+            data.try_into()
+                .map_err(|_| ContentHashError::InvalidBytes {
+                    data: data.to_vec(),
+                })?;
+
+        Ok(Self(data))
+    }
+}
+
+impl<'q> sqlx::encode::Encode<'q, sqlx::Postgres> for &'q ContentHash {
+    fn encode_by_ref(&self, buf: &mut sqlx::postgres::PgArgumentBuffer) -> sqlx::encode::IsNull {
+        buf.extend_from_slice(&self.0);
+
+        sqlx::encode::IsNull::No
+    }
+}
+
+impl<'q> sqlx::encode::Encode<'q, sqlx::Sqlite> for &'q ContentHash {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
+    ) -> sqlx::encode::IsNull {
+        args.push(sqlx::sqlite::SqliteArgumentValue::Blob(
+            std::borrow::Cow::Borrowed(&self.0),
+        ));
+
+        sqlx::encode::IsNull::No
+    }
+}
+
+impl<'r, DB: ::sqlx::Database> ::sqlx::decode::Decode<'r, DB> for ContentHash
+where
+    &'r [u8]: sqlx::Decode<'r, DB>,
+{
+    fn decode(
+        value: <DB as ::sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> ::std::result::Result<
+        Self,
+        ::std::boxed::Box<
+            dyn ::std::error::Error + 'static + ::std::marker::Send + ::std::marker::Sync,
+        >,
+    > {
+        let data = <&[u8] as ::sqlx::decode::Decode<'r, DB>>::decode(value)?;
+        Ok(Self::try_from(data)?)
+    }
+}
+
+impl<'r, DB: ::sqlx::Database> ::sqlx::Type<DB> for ContentHash
+where
+    &'r [u8]: ::sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <&[u8] as ::sqlx::Type<DB>>::type_info()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash() {
+        let hash = ContentHash::of(b"bananas");
+        assert_eq!(
+            hash.to_string(),
+            "e4ba5cbd251c98e6cd1c23f126a3b81d8d8328abc95387229850952b3ef9f904"
+        );
+        assert_eq!(ContentHash::try_from(hash.as_bytes()).unwrap(), hash);
+        assert_ne!(ContentHash::of(b"bananas!"), hash);
+
+        ContentHash::try_from(&[1, 2, 3][..]).unwrap_err();
+    }
+}
@@ -0,0 +1,225 @@
+//! Types describing the background jobs run by IOx servers.
+
+use std::fmt::Display;
+
+use generated_types::influxdata::iox::operations::v1 as proto;
+
+use crate::{NamespaceId, NamespaceName, PartitionId, PartitionKey, TableId};
+
+/// A background job, used as the metadata of the task tracking it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Job {
+    /// Compact the parquet files of a partition
+    CompactPartition {
+        /// The partition being compacted
+        partition_id: PartitionId,
+    },
+
+    /// Persist buffered data of a partition to parquet
+    PersistPartition {
+        /// The table the partition belongs to
+        table_id: TableId,
+        /// The key of the partition being persisted
+        partition_key: PartitionKey,
+    },
+
+    /// Replay the write-ahead log
+    WalReplay,
+
+    /// Rebuild the catalog from the parquet files in object storage
+    RebuildCatalog,
+
+    /// Import parquet files into a namespace
+    ImportFiles {
+        /// The namespace the files are imported into, if not the namespace
+        /// they were exported from
+        namespace_name: Option<NamespaceName<'static>>,
+    },
+
+    /// Rewrite the data of a table into another table
+    RewriteTable {
+        /// The table the data is read from
+        source_table_id: TableId,
+        /// The table the data is written to
+        target_table_id: TableId,
+    },
+
+    /// Merge the tables of a namespace into another namespace
+    MergeNamespace {
+        /// The namespace the data is moved from
+        source_namespace_id: NamespaceId,
+        /// The namespace the data is moved into
+        target_namespace_id: NamespaceId,
+    },
+}
+
+impl Job {
+    /// Returns a human readable description of the job
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::CompactPartition { .. } => "Compacting partition",
+            Self::PersistPartition { .. } => "Persisting partition",
+            Self::WalReplay => "Replaying write-ahead log",
+            Self::RebuildCatalog => "Rebuilding catalog",
+            Self::ImportFiles { .. } => "Importing files",
+            Self::RewriteTable { .. } => "Rewriting table",
+            Self::MergeNamespace { .. } => "Merging namespace",
+        }
+    }
+
+    /// Returns true if the job can be abandoned part way through.
+    ///
+    /// Persisting a partition and replaying the write-ahead log must run to
+    /// completion, as the data they hold would be lost otherwise.
+    pub fn is_cancellable(&self) -> bool {
+        match self {
+            Self::PersistPartition { .. } | Self::WalReplay => false,
+            Self::CompactPartition { .. }
+            | Self::RebuildCatalog
+            | Self::ImportFiles { .. }
+            | Self::RewriteTable { .. }
+            | Self::MergeNamespace { .. } => true,
+        }
+    }
+}
+
+impl Display for Job {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CompactPartition { partition_id } => {
+                write!(f, "compact partition {partition_id}")
+            }
+            Self::PersistPartition {
+                table_id,
+                partition_key,
+            } => write!(f, "persist partition {partition_key} of table {table_id}"),
+            Self::WalReplay => write!(f, "wal replay"),
+            Self::RebuildCatalog => write!(f, "rebuild catalog"),
+            Self::ImportFiles {
+                namespace_name: Some(namespace_name),
+            } => write!(f, "import into {namespace_name}"),
+            Self::ImportFiles {
+                namespace_name: None,
+            } => write!(f, "import"),
+            Self::RewriteTable {
+                source_table_id,
+                target_table_id,
+            } => write!(f, "rewrite table {source_table_id} into {target_table_id}"),
+            Self::MergeNamespace {
+                source_namespace_id,
+                target_namespace_id,
+            } => write!(
+                f,
+                "merge namespace {source_namespace_id} into {target_namespace_id}"
+            ),
+        }
+    }
+}
+
+impl From<&Job> for proto::operation_metadata::Job {
+    fn from(job: &Job) -> Self {
+        match job {
+            Job::CompactPartition { partition_id } => {
+                Self::CompactPartition(proto::CompactPartition {
+                    partition_id: partition_id.get(),
+                })
+            }
+            Job::PersistPartition {
+                table_id,
+                partition_key,
+            } => Self::PersistPartition(proto::PersistPartition {
+                table_id: table_id.get(),
+                partition_key: partition_key.to_string(),
+            }),
+            Job::WalReplay => Self::WalReplay(proto::WalReplay {}),
+            Job::RebuildCatalog => Self::RebuildCatalog(proto::RebuildCatalog {}),
+            Job::ImportFiles { namespace_name } => Self::ImportFiles(proto::ImportFiles {
+                namespace_name: namespace_name.as_ref().map(ToString::to_string),
+            }),
+            Job::RewriteTable {
+                source_table_id,
+                target_table_id,
+            } => Self::RewriteTable(proto::RewriteTable {
+                source_table_id: source_table_id.get(),
+                target_table_id: target_table_id.get(),
+            }),
+            Job::MergeNamespace {
+                source_namespace_id,
+                target_namespace_id,
+            } => Self::MergeNamespace(proto::MergeNamespace {
+                source_namespace_id: source_namespace_id.get(),
+                target_namespace_id: target_namespace_id.get(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<proto::operation_metadata::Job> for Job {
+    type Error = crate::NamespaceNameError;
+
+    fn try_from(job: proto::operation_metadata::Job) -> Result<Self, Self::Error> {
+        use proto::operation_metadata::Job as P;
+
+        Ok(match job {
+            P::CompactPartition(v) => Self::CompactPartition {
+                partition_id: PartitionId::new(v.partition_id),
+            },
+            P::PersistPartition(v) => Self::PersistPartition {
+                table_id: TableId::new(v.table_id),
+                partition_key: PartitionKey::from(v.partition_key),
+            },
+            P::WalReplay(_) => Self::WalReplay,
+            P::RebuildCatalog(_) => Self::RebuildCatalog,
+            P::ImportFiles(v) => Self::ImportFiles {
+                namespace_name: v.namespace_name.map(NamespaceName::try_from).transpose()?,
+            },
+            P::RewriteTable(v) => Self::RewriteTable {
+                source_table_id: TableId::new(v.source_table_id),
+                target_table_id: TableId::new(v.target_table_id),
+            },
+            P::MergeNamespace(v) => Self::MergeNamespace {
+                source_namespace_id: NamespaceId::new(v.source_namespace_id),
+                target_namespace_id: NamespaceId::new(v.target_namespace_id),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proto_round_trip() {
+        let jobs = [
+            Job::CompactPartition {
+                partition_id: PartitionId::new(42),
+            },
+            Job::PersistPartition {
+                table_id: TableId::new(1),
+                partition_key: PartitionKey::from("2023-01-01"),
+            },
+            Job::WalReplay,
+            Job::RebuildCatalog,
+            Job::ImportFiles {
+                namespace_name: Some(NamespaceName::try_from("bananas").unwrap()),
+            },
+            Job::ImportFiles {
+                namespace_name: None,
+            },
+            Job::RewriteTable {
+                source_table_id: TableId::new(1),
+                target_table_id: TableId::new(2),
+            },
+            Job::MergeNamespace {
+                source_namespace_id: NamespaceId::new(1),
+                target_namespace_id: NamespaceId::new(2),
+            },
+        ];
+
+        for job in jobs {
+            let proto = proto::operation_metadata::Job::from(&job);
+            assert_eq!(Job::try_from(proto).unwrap(), job);
+        }
+    }
+}
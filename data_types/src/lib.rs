@@ -21,8 +21,15 @@ use workspace_hack as _;
 
 mod columns;
 pub use columns::*;
+mod content_hash;
+pub use content_hash::*;
 mod namespace_name;
 pub use namespace_name::*;
+mod namespace_template;
+pub use namespace_template::*;
+mod read_lease;
+pub use read_lease::*;
+pub mod job;
 pub mod partition_template;
 use partition_template::*;
 pub mod partition;
@@ -30,6 +37,8 @@ pub use partition::*;
 pub mod sequence_number_set;
 pub mod service_limits;
 pub use service_limits::*;
+mod tenant;
+pub use tenant::*;
 
 use observability_deps::tracing::warn;
 use schema::TIME_COLUMN_NAME;
@@ -292,6 +301,10 @@ pub struct Namespace {
     /// The partition template to use for new tables in this namespace either created implicitly or
     /// created without specifying a partition template.
     pub partition_template: NamespacePartitionTemplateOverride,
+    /// The tenant owning this namespace, if any.
+    pub tenant_id: Option<TenantId>,
+    /// The template this namespace was created from, if any.
+    pub template_id: Option<NamespaceTemplateId>,
 }
 
 /// Schema collection for a namespace. This is an in-memory object useful for a schema
@@ -395,6 +408,38 @@ pub struct Table {
     pub name: String,
     /// The partition template to use for writes in this table.
     pub partition_template: TablePartitionTemplateOverride,
+    /// The retention period of the table in nanoseconds, if it expires its
+    /// data faster than the namespace retention period.
+    pub retention_period_ns: Option<i64>,
+}
+
+impl Table {
+    /// Returns the retention period that applies to the data of this table
+    /// given the `namespace_retention_period_ns`, i.e. the shorter of the two.
+    ///
+    /// [`None`] means infinite retention.
+    pub fn effective_retention_period_ns(
+        &self,
+        namespace_retention_period_ns: Option<i64>,
+    ) -> Option<i64> {
+        effective_retention_period_ns(namespace_retention_period_ns, self.retention_period_ns)
+    }
+}
+
+/// Returns the retention period that applies to the data of a table with the
+/// `table_retention_period_ns` override in a namespace with the
+/// `namespace_retention_period_ns`, i.e. the shorter of the two.
+///
+/// A table retention period can only expire data faster than its namespace;
+/// [`None`] means infinite retention.
+pub fn effective_retention_period_ns(
+    namespace_retention_period_ns: Option<i64>,
+    table_retention_period_ns: Option<i64>,
+) -> Option<i64> {
+    match (namespace_retention_period_ns, table_retention_period_ns) {
+        (Some(ns), Some(table)) => Some(ns.min(table)),
+        (ns, table) => ns.or(table),
+    }
 }
 
 /// Serialise a [`Table`] object into its protobuf representation.
@@ -405,6 +450,7 @@ impl From<Table> for generated_types::influxdata::iox::table::v1::Table {
             name: value.name,
             namespace_id: value.namespace_id.get(),
             partition_template: value.partition_template.as_proto().cloned(),
+            retention_period_ns: value.retention_period_ns,
         }
     }
 }
@@ -542,6 +588,42 @@ impl From<SkippedCompaction> for compactor_proto::SkippedCompaction {
     }
 }
 
+/// A partition frozen for maintenance. The files of a frozen partition are not
+/// compacted, and ingesters reject writes to it, until it is unfrozen.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::FromRow)]
+pub struct FrozenPartition {
+    /// the partition
+    pub partition_id: PartitionId,
+    /// the table of the partition
+    pub table_id: TableId,
+    /// the key of the partition
+    pub partition_key: PartitionKey,
+    /// the reason the partition was frozen
+    pub reason: String,
+    /// when the partition was frozen
+    pub frozen_at: Timestamp,
+}
+
+impl From<FrozenPartition> for compactor_proto::FrozenPartition {
+    fn from(frozen: FrozenPartition) -> Self {
+        let FrozenPartition {
+            partition_id,
+            table_id,
+            partition_key,
+            reason,
+            frozen_at,
+        } = frozen;
+
+        Self {
+            partition_id: partition_id.get(),
+            table_id: table_id.get(),
+            partition_key: partition_key.to_string(),
+            reason,
+            frozen_at: frozen_at.get(),
+        }
+    }
+}
+
 /// Data for a parquet file reference that has been inserted in the catalog.
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
 pub struct ParquetFile {
@@ -599,6 +681,10 @@ pub struct ParquetFile {
     pub column_set: ColumnSet,
     /// the max of created_at of all L0 files needed for file/chunk ordering for deduplication
     pub max_l0_created_at: Timestamp,
+    /// The hash of the content of the file, used to detect corrupted or modified files.
+    ///
+    /// `None` for files created before content hashes were recorded.
+    pub content_hash: Option<ContentHash>,
 }
 
 impl ParquetFile {
@@ -621,6 +707,7 @@ impl ParquetFile {
             created_at: params.created_at,
             column_set: params.column_set,
             max_l0_created_at: params.max_l0_created_at,
+            content_hash: params.content_hash,
         }
     }
 
@@ -680,6 +767,7 @@ impl From<ParquetFile> for generated_types::influxdata::iox::catalog::v1::Parque
             created_at: v.created_at.get(),
             column_set: v.column_set.iter().map(|v| v.get()).collect(),
             max_l0_created_at: v.max_l0_created_at.get(),
+            content_hash: v.content_hash.map(|h| h.as_bytes().to_vec()),
         }
     }
 }
@@ -702,6 +790,10 @@ pub enum ParquetFileProtoError {
     /// The specified compaction level value is invalid.
     #[error("invalid compaction level: {0}")]
     InvalidCompactionLevel(Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The specified content hash is invalid.
+    #[error(transparent)]
+    InvalidContentHash(#[from] ContentHashError),
 }
 
 impl TryFrom<generated_types::influxdata::iox::catalog::v1::ParquetFile> for ParquetFile {
@@ -732,6 +824,10 @@ impl TryFrom<generated_types::influxdata::iox::catalog::v1::ParquetFile> for Par
             created_at: Timestamp::new(v.created_at),
             column_set: ColumnSet::new(v.column_set.into_iter().map(ColumnId::new)),
             max_l0_created_at: Timestamp::new(v.max_l0_created_at),
+            content_hash: v
+                .content_hash
+                .map(|h| ContentHash::try_from(h.as_slice()))
+                .transpose()?,
         })
     }
 }
@@ -763,6 +859,8 @@ pub struct ParquetFileParams {
     pub column_set: ColumnSet,
     /// the max of created_at of all L0 files
     pub max_l0_created_at: Timestamp,
+    /// the hash of the content of the file, if known
+    pub content_hash: Option<ContentHash>,
 }
 
 impl From<ParquetFile> for ParquetFileParams {
@@ -780,6 +878,7 @@ impl From<ParquetFile> for ParquetFileParams {
             created_at: value.created_at,
             column_set: value.column_set,
             max_l0_created_at: value.max_l0_created_at,
+            content_hash: value.content_hash,
         }
     }
 }
@@ -1728,6 +1827,16 @@ mod tests {
     use ordered_float::OrderedFloat;
     use proptest::{prelude::*, proptest};
 
+    #[test]
+    fn test_effective_retention_period_ns() {
+        assert_eq!(effective_retention_period_ns(None, None), None);
+        assert_eq!(effective_retention_period_ns(Some(42), None), Some(42));
+        assert_eq!(effective_retention_period_ns(None, Some(42)), Some(42));
+        // a table retention period can only shorten the namespace retention
+        assert_eq!(effective_retention_period_ns(Some(42), Some(24)), Some(24));
+        assert_eq!(effective_retention_period_ns(Some(24), Some(42)), Some(24));
+    }
+
     #[test]
     fn test_chunk_id_new() {
         // `ChunkId::new()` create new random ID
@@ -2768,6 +2877,7 @@ mod tests {
             created_at in arbitrary_timestamp(),
             column_set in prop::collection::vec(any::<i64>(), 0..10),
             max_l0_created_at in arbitrary_timestamp(),
+            content_hash in prop::option::of(any::<[u8; 32]>()),
         ) -> ParquetFile {
             let column_set = ColumnSet::new(column_set.into_iter().map(ColumnId::new));
 
@@ -2786,6 +2896,7 @@ mod tests {
                 created_at,
                 column_set,
                 max_l0_created_at,
+                content_hash: content_hash.map(|h| ContentHash::try_from(&h[..]).unwrap()),
             }
         }
     }
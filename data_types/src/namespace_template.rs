@@ -0,0 +1,59 @@
+//! Named namespace settings used to provision namespaces consistently.
+
+use crate::{
+    partition_template::NamespacePartitionTemplateOverride, MaxColumnsPerTable, MaxTables,
+    NamespaceServiceProtectionLimitsOverride,
+};
+
+/// Unique ID for a [`NamespaceTemplate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct NamespaceTemplateId(i64);
+
+#[allow(missing_docs)]
+impl NamespaceTemplateId {
+    pub const fn new(v: i64) -> Self {
+        Self(v)
+    }
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for NamespaceTemplateId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Data object for a namespace template.
+///
+/// Namespaces created from a template record it in
+/// [`Namespace::template_id`](crate::Namespace::template_id), so later changes
+/// to the template can be propagated to them.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct NamespaceTemplate {
+    /// The id of the template
+    pub id: NamespaceTemplateId,
+    /// The unique name of the template
+    pub name: String,
+    /// The retention period in ns. None represents infinite duration (i.e. never drop data).
+    pub retention_period_ns: Option<i64>,
+    /// The maximum number of tables, or `None` for the default
+    pub max_tables: Option<MaxTables>,
+    /// The maximum number of columns per table, or `None` for the default
+    pub max_columns_per_table: Option<MaxColumnsPerTable>,
+    /// The partition template of the namespaces created from this template
+    pub partition_template: NamespacePartitionTemplateOverride,
+}
+
+impl NamespaceTemplate {
+    /// The service protection limits of the namespaces created from this
+    /// template.
+    pub fn service_protection_limits(&self) -> NamespaceServiceProtectionLimitsOverride {
+        NamespaceServiceProtectionLimitsOverride {
+            max_tables: self.max_tables,
+            max_columns_per_table: self.max_columns_per_table,
+        }
+    }
+}
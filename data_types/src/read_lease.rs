@@ -0,0 +1,44 @@
+//! Leases protecting the parquet files of a table from garbage collection
+//! while they are read by an external reader.
+
+use crate::{TableId, Timestamp};
+
+/// Unique ID for a [`ReadLease`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct ReadLeaseId(i64);
+
+#[allow(missing_docs)]
+impl ReadLeaseId {
+    pub const fn new(v: i64) -> Self {
+        Self(v)
+    }
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ReadLeaseId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Data object for a read lease.
+///
+/// A lease taken at [`created_at`](Self::created_at) guarantees that the
+/// parquet files of the table that were not flagged for deletion at that time
+/// remain readable in object storage until the lease is released or reaches
+/// [`expires_at`](Self::expires_at), even if they are compacted away in the
+/// meantime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::FromRow)]
+pub struct ReadLease {
+    /// The id of the lease
+    pub id: ReadLeaseId,
+    /// The table whose files are protected by the lease
+    pub table_id: TableId,
+    /// When the lease was taken
+    pub created_at: Timestamp,
+    /// When the lease expires, if not released before
+    pub expires_at: Timestamp,
+}
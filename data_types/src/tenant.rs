@@ -0,0 +1,62 @@
+//! Tenants, which own namespaces and bound the resources they use in
+//! aggregate.
+
+/// Unique ID for a [`Tenant`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct TenantId(i64);
+
+#[allow(missing_docs)]
+impl TenantId {
+    pub const fn new(v: i64) -> Self {
+        Self(v)
+    }
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for TenantId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Data object for a tenant.
+///
+/// A tenant owns zero or more namespaces (see
+/// [`Namespace::tenant_id`](crate::Namespace::tenant_id)), and its
+/// [`TenantQuotas`] apply to the sum of the resources used by all of them.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct Tenant {
+    /// The id of the tenant
+    pub id: TenantId,
+    /// The unique name of the tenant
+    pub name: String,
+    /// The limits on the resources used by the namespaces of the tenant
+    #[sqlx(flatten)]
+    pub quotas: TenantQuotas,
+}
+
+/// Limits on the resources used by all namespaces of a [`Tenant`] together.
+///
+/// `None` means the resource is not limited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, sqlx::FromRow)]
+pub struct TenantQuotas {
+    /// The maximum total size, in bytes, of the parquet files of the tenant's
+    /// namespaces.
+    pub max_storage_bytes: Option<i64>,
+    /// The maximum number of distinct series written to the tenant's
+    /// namespaces.
+    pub max_series: Option<i64>,
+    /// The maximum number of lines per second written to the tenant's
+    /// namespaces.
+    pub max_write_lines_per_second: Option<i64>,
+}
+
+impl TenantQuotas {
+    /// Returns true if none of the resources are limited.
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+}
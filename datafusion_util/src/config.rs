@@ -20,6 +20,9 @@ pub fn iox_session_config() -> SessionConfig {
     let mut options = ConfigOptions::new();
     options.execution.parquet.pushdown_filters = true;
     options.execution.parquet.reorder_filters = true;
+    // Prune row groups using bloom filters, if the file has them, for
+    // equality and IN list predicates
+    options.execution.parquet.bloom_filter_enabled = true;
     options.optimizer.repartition_sorts = true;
 
     SessionConfig::from(options)
@@ -38,6 +38,7 @@ use std::{
 };
 use tokio::sync::oneshot::{error::RecvError, Receiver};
 use tokio_util::sync::CancellationToken;
+use tracker::{set_thread_subsystem, MemorySubsystem};
 
 use futures::{
     future::{BoxFuture, Shared},
@@ -248,7 +249,12 @@ impl DedicatedExecutor {
                         )
                     })
                     .worker_threads(num_threads.get())
-                    .on_thread_start(move || set_current_thread_priority(WORKER_PRIORITY))
+                    .on_thread_start(move || {
+                        set_current_thread_priority(WORKER_PRIORITY);
+                        // Everything run on a dedicated executor is plan
+                        // execution.
+                        set_thread_subsystem(MemorySubsystem::QueryExec);
+                    })
                     .build()
                     .expect("Creating tokio runtime");
 
@@ -0,0 +1,2 @@
+/// Logic for verifying the content of parquet files against their recorded hashes
+pub(crate) mod verifier;
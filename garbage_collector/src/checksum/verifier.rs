@@ -0,0 +1,210 @@
+use iox_catalog::interface::Catalog;
+use object_store::DynObjectStore;
+use observability_deps::tracing::*;
+use parquet_file::verify::{verify_file, Verification, VerifyError};
+use snafu::prelude::*;
+use std::{sync::Arc, time::Duration};
+use tokio::{select, time::sleep};
+use tokio_util::sync::CancellationToken;
+
+pub(crate) async fn perform(
+    shutdown: CancellationToken,
+    catalog: Arc<dyn Catalog>,
+    object_store: Arc<DynObjectStore>,
+    enabled: bool,
+    sleep_interval_minutes: u64,
+) -> Result<()> {
+    if !enabled {
+        debug!("parquet file checksum verification disabled");
+        return Ok(());
+    }
+
+    loop {
+        let summary = verify_all(&catalog, &object_store).await?;
+        info!(
+            verified = summary.verified,
+            no_content_hash = summary.no_content_hash,
+            corrupted = summary.corrupted,
+            unreadable = summary.unreadable,
+            "parquet file checksum verification complete"
+        );
+
+        select! {
+            _ = shutdown.cancelled() => {
+                break
+            },
+            _ = sleep(Duration::from_secs(60 * sleep_interval_minutes)) => (),
+        }
+    }
+    Ok(())
+}
+
+/// The number of files in each state found by a pass of [`verify_all`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Summary {
+    verified: usize,
+    no_content_hash: usize,
+    corrupted: usize,
+    unreadable: usize,
+}
+
+/// Verify every parquet file in the catalog that is not flagged for deletion
+/// against the content in object storage, logging an error for each file that
+/// does not match.
+async fn verify_all(
+    catalog: &Arc<dyn Catalog>,
+    object_store: &Arc<DynObjectStore>,
+) -> Result<Summary> {
+    let mut summary = Summary::default();
+
+    let tables = catalog
+        .repositories()
+        .await
+        .tables()
+        .list()
+        .await
+        .context(ListingSnafu)?;
+
+    for table in tables {
+        let files = catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_table_not_to_delete(table.id)
+            .await
+            .context(ListingSnafu)?;
+
+        for file in files {
+            match verify_file(object_store.as_ref(), &file).await {
+                Ok(Verification::Verified) => summary.verified += 1,
+                Ok(Verification::NoContentHash) => summary.no_content_hash += 1,
+                Err(e @ VerifyError::Corrupted { .. }) => {
+                    error!(
+                        error = %e,
+                        table_id = %file.table_id,
+                        partition_id = %file.partition_id,
+                        parquet_file_id = %file.id,
+                        "corrupted parquet file"
+                    );
+                    summary.corrupted += 1;
+                }
+                Err(e @ VerifyError::ObjectStore { .. }) => {
+                    // the file may have been deleted since it was listed
+                    warn!(
+                        error = %e,
+                        parquet_file_id = %file.id,
+                        "failed to read parquet file for verification"
+                    );
+                    summary.unreadable += 1;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Failed to list parquet files to verify"))]
+    Listing {
+        source: iox_catalog::interface::Error,
+    },
+}
+
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use data_types::{
+        ColumnId, ColumnSet, CompactionLevel, ContentHash, NamespaceId, ParquetFile,
+        ParquetFileParams, Partition, Timestamp,
+    };
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
+    use object_store::memory::InMemory;
+    use parquet_file::ParquetFilePath;
+    use uuid::Uuid;
+
+    async fn create_file(
+        catalog: &Arc<dyn Catalog>,
+        partition: &Partition,
+        content_hash: Option<ContentHash>,
+    ) -> ParquetFile {
+        catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .create(ParquetFileParams {
+                namespace_id: NamespaceId::new(1),
+                table_id: partition.table_id,
+                partition_id: partition.transition_partition_id(),
+                object_store_id: Uuid::new_v4(),
+                min_time: Timestamp::new(1),
+                max_time: Timestamp::new(10),
+                file_size_bytes: 1337,
+                row_count: 0,
+                compaction_level: CompactionLevel::Initial,
+                created_at: Timestamp::new(1),
+                column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
+                max_l0_created_at: Timestamp::new(1),
+                content_hash,
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn put(object_store: &Arc<DynObjectStore>, file: &ParquetFile, data: &'static [u8]) {
+        let path = ParquetFilePath::from(file).object_store_path();
+        object_store
+            .put(&path, Bytes::from_static(data))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_all_files() {
+        let metric_registry = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metric_registry));
+        let object_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+
+        let partition = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "namespace_checksum_test").await;
+            let table = arbitrary_table(&mut *repos, "test_table", &namespace).await;
+            repos
+                .partitions()
+                .create_or_get("one".into(), table.id)
+                .await
+                .unwrap()
+        };
+
+        let good = create_file(&catalog, &partition, Some(ContentHash::of(b"good"))).await;
+        put(&object_store, &good, b"good").await;
+
+        let corrupted = create_file(&catalog, &partition, Some(ContentHash::of(b"good"))).await;
+        put(&object_store, &corrupted, b"bad").await;
+
+        let unhashed = create_file(&catalog, &partition, None).await;
+        put(&object_store, &unhashed, b"whatever").await;
+
+        // never written to object storage
+        create_file(&catalog, &partition, Some(ContentHash::of(b"missing"))).await;
+
+        let summary = verify_all(&catalog, &object_store).await.unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                verified: 1,
+                no_content_hash: 1,
+                corrupted: 1,
+                unreadable: 1,
+            }
+        );
+    }
+}
@@ -24,6 +24,7 @@ use clap as _;
 use workspace_hack as _;
 
 use crate::{
+    checksum::verifier as checksum_verifier,
     objectstore::{checker as os_checker, deleter as os_deleter, lister as os_lister},
     parquetfile::deleter as pf_deleter,
     retention::flagger as retention_flagger,
@@ -39,6 +40,8 @@ use std::{fmt::Debug, sync::Arc};
 use tokio::{select, sync::mpsc};
 use tokio_util::sync::CancellationToken;
 
+/// Logic for verifying the content of parquet files in object storage
+mod checksum;
 /// Logic for listing, checking and deleting files in object storage
 mod objectstore;
 /// Logic for deleting parquet files from the catalog
@@ -61,6 +64,7 @@ pub struct GarbageCollector {
     os_deleter: tokio::task::JoinHandle<Result<(), os_deleter::Error>>,
     pf_deleter: tokio::task::JoinHandle<Result<(), pf_deleter::Error>>,
     retention_flagger: tokio::task::JoinHandle<Result<(), retention_flagger::Error>>,
+    checksum_verifier: tokio::task::JoinHandle<Result<(), checksum_verifier::Error>>,
 }
 
 impl Debug for GarbageCollector {
@@ -85,6 +89,8 @@ impl GarbageCollector {
             objectstore_sleep_interval_minutes = %sub_config.objectstore_sleep_interval_minutes,
             parquetfile_sleep_interval_minutes = %sub_config.parquetfile_sleep_interval_minutes,
             retention_sleep_interval_minutes = %sub_config.retention_sleep_interval_minutes,
+            checksum_verification = %sub_config.checksum_verification,
+            checksum_sleep_interval_minutes = %sub_config.checksum_sleep_interval_minutes,
             "GarbageCollector starting"
         );
 
@@ -145,6 +151,16 @@ impl GarbageCollector {
             }
         });
 
+        // Initialise the checksum verifier, which is just one thread that reads every parquet file
+        // in the catalog, compares it with its recorded content hash, then sleeps.
+        let checksum_verifier = tokio::spawn(checksum_verifier::perform(
+            shutdown.clone(),
+            Arc::clone(&catalog),
+            Arc::clone(&object_store),
+            sub_config.checksum_verification,
+            sub_config.checksum_sleep_interval_minutes,
+        ));
+
         let os_deleter = tokio::spawn(os_deleter::perform(
             shutdown.clone(),
             object_store,
@@ -178,6 +194,7 @@ impl GarbageCollector {
             os_deleter,
             pf_deleter,
             retention_flagger,
+            checksum_verifier,
         })
     }
 
@@ -197,17 +214,20 @@ impl GarbageCollector {
             os_deleter,
             pf_deleter,
             retention_flagger,
+            checksum_verifier,
             shutdown: _,
         } = self;
 
-        let (os_lister, os_checker, os_deleter, pf_deleter, retention_flagger) = futures::join!(
+        let (os_lister, os_checker, os_deleter, pf_deleter, retention_flagger, checksum_verifier) = futures::join!(
             os_lister,
             os_checker,
             os_deleter,
             pf_deleter,
-            retention_flagger
+            retention_flagger,
+            checksum_verifier
         );
 
+        checksum_verifier.context(ChecksumVerifierPanicSnafu)??;
         retention_flagger.context(ParquetFileDeleterPanicSnafu)??;
         pf_deleter.context(ParquetFileDeleterPanicSnafu)??;
         os_deleter.context(ObjectStoreDeleterPanicSnafu)??;
@@ -274,6 +294,12 @@ pub enum Error {
     ParquetFileRetentionFlagger { source: retention_flagger::Error },
     #[snafu(display("The parquet file retention flagger task panicked"))]
     ParquetFileRetentionFlaggerPanic { source: tokio::task::JoinError },
+
+    #[snafu(display("The parquet file checksum verifier task failed"))]
+    #[snafu(context(false))]
+    ChecksumVerifier { source: checksum_verifier::Error },
+    #[snafu(display("The parquet file checksum verifier task panicked"))]
+    ChecksumVerifierPanic { source: tokio::task::JoinError },
 }
 
 #[allow(missing_docs)]
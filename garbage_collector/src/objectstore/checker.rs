@@ -2,6 +2,7 @@ use chrono::{DateTime, Duration, Utc};
 use iox_catalog::interface::{Catalog, ParquetFileRepo};
 use object_store::ObjectMeta;
 use observability_deps::tracing::*;
+use parquet_file::DELTA_LOG_DIR;
 use snafu::prelude::*;
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -141,6 +142,21 @@ async fn should_delete(
             continue;
         }
 
+        if candidate
+            .location
+            .parts()
+            .any(|part| part.as_ref() == DELTA_LOG_DIR)
+        {
+            debug!(
+                location = %candidate.location,
+                deleting = false,
+                reason = "delta lake transaction log",
+                "Ignoring object",
+            );
+            // written by a table export, not by IOx; do not delete
+            continue;
+        }
+
         // extract the file suffix, delete it if it isn't a parquet file
         if let Some(uuid) = file_name.unwrap().as_ref().strip_suffix(".parquet") {
             if let Ok(object_store_id) = uuid.parse::<Uuid>() {
@@ -284,6 +300,7 @@ mod tests {
             created_at: Timestamp::new(1),
             column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
             max_l0_created_at: Timestamp::new(1),
+            content_hash: None,
         };
 
         let parquet_file = repos
@@ -452,6 +469,27 @@ mod tests {
         assert_eq!(results[0], item);
     }
 
+    #[tokio::test]
+    async fn dont_delete_old_delta_log() {
+        let metric_registry = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metric_registry)));
+        let mut repositories = catalog.repositories().await;
+        let parquet_files = repositories.parquet_files();
+
+        let cutoff = *NEWER_TIME;
+        let last_modified = *OLDER_TIME;
+
+        let item = ObjectMeta {
+            location: Path::from("1/2/_delta_log/00000000000000000000.json"),
+            last_modified,
+            size: 0,
+            e_tag: None,
+        };
+
+        let results = should_delete(vec![item], cutoff, parquet_files).await;
+        assert_eq!(results.len(), 0);
+    }
+
     /// The garbage collector checks the catalog for files it _should not delete_. If we can't reach
     /// the catalog (some error), assume we are keeping all the files we are checking.
     /// [do_not_delete_on_catalog_error] tests that.
@@ -539,6 +577,14 @@ mod tests {
             self.inner.list_by_table_not_to_delete(table_id).await
         }
 
+        async fn list_by_table_at(
+            &mut self,
+            table_id: TableId,
+            at: Timestamp,
+        ) -> iox_catalog::interface::Result<Vec<ParquetFile>> {
+            self.inner.list_by_table_at(table_id, at).await
+        }
+
         async fn delete_old_ids_only(
             &mut self,
             older_than: Timestamp,
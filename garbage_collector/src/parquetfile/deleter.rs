@@ -13,16 +13,34 @@ pub(crate) async fn perform(
     sleep_interval_minutes: u64,
 ) -> Result<()> {
     loop {
-        let older_than = Timestamp::from(catalog.time_provider().now() - cutoff);
-        // do the delete, returning the deleted files
-        let deleted = catalog
-            .repositories()
+        let mut repos = catalog.repositories().await;
+
+        // Files flagged for deletion after the oldest active read lease was taken may still be
+        // read by its holder, so they are kept until the lease is released or expires.
+        let expired = repos
+            .read_leases()
+            .delete_expired()
+            .await
+            .context(LeasesSnafu)?;
+        debug!(%expired, "deleted expired read leases");
+        let mut older_than = Timestamp::from(catalog.time_provider().now() - cutoff);
+        if let Some(oldest_lease) = repos
+            .read_leases()
+            .oldest_active()
             .await
+            .context(LeasesSnafu)?
+        {
+            older_than = older_than.min(oldest_lease);
+        }
+
+        // do the delete, returning the deleted files
+        let deleted = repos
             .parquet_files()
             .delete_old_ids_only(older_than) // read/write
             .await
             .context(DeletingSnafu)?;
         info!(delete_count = %deleted.len(), "iox_catalog::delete_old()");
+        drop(repos);
 
         select! {
             _ = shutdown.cancelled() => {
@@ -41,6 +59,11 @@ pub enum Error {
     Deleting {
         source: iox_catalog::interface::Error,
     },
+
+    #[snafu(display("Failed to read the read leases in catalog"))]
+    Leases {
+        source: iox_catalog::interface::Error,
+    },
 }
 
 pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
@@ -16,6 +16,10 @@
 //! [`AlreadyExists`], [`NotFound`], [`PreconditionViolation`] etc... are provided that can be
 //! converted to a `tonic::Status` with `Into::into`.
 //!
+//! Services that need to combine several payloads in a single status, for example a
+//! service-specific error alongside a `google.rpc.RetryInfo`, can encode them individually with
+//! [`encode_bad_request`], [`encode_retry_info`] etc... and pass them to [`encode_status_details`].
+//!
 //! Unfortunately client support for details payloads is patchy. Therefore, whilst IOx does
 //! provide these payloads, they should be viewed as an optional extension and not mandatory
 //! functionality for a workable client implementation
@@ -76,11 +80,11 @@ pub mod longrunning {
 use self::protobuf::Any;
 use observability_deps::tracing::error;
 use prost::{bytes::BytesMut, Message};
-use std::convert::TryInto;
+use std::{convert::TryInto, time::Duration};
 
-// A newtype struct to provide conversion into tonic::Status
+/// A newtype struct to provide conversion into tonic::Status
 #[derive(Debug)]
-struct EncodeError(prost::EncodeError);
+pub struct EncodeError(prost::EncodeError);
 
 impl From<EncodeError> for tonic::Status {
     fn from(error: EncodeError) -> Self {
@@ -96,12 +100,21 @@ impl From<prost::EncodeError> for EncodeError {
 }
 
 pub fn encode_status(code: tonic::Code, message: String, details: Any) -> tonic::Status {
+    encode_status_details(code, message, vec![details])
+}
+
+/// Returns a [`tonic::Status`] carrying all of the provided details payloads
+pub fn encode_status_details(
+    code: tonic::Code,
+    message: String,
+    details: Vec<Any>,
+) -> tonic::Status {
     let mut buffer = BytesMut::new();
 
     let status = rpc::Status {
         code: code as i32,
         message: message.clone(),
-        details: vec![details],
+        details,
     };
 
     match status.encode(&mut buffer) {
@@ -162,7 +175,8 @@ impl std::fmt::Display for FieldViolation {
     }
 }
 
-fn encode_bad_request(violation: Vec<FieldViolation>) -> Result<Any, EncodeError> {
+/// Encodes the violations as a `google.rpc.BadRequest` details payload
+pub fn encode_bad_request(violation: Vec<FieldViolation>) -> Result<Any, EncodeError> {
     let mut buffer = BytesMut::new();
 
     rpc::BadRequest {
@@ -193,6 +207,23 @@ impl From<FieldViolation> for tonic::Status {
     }
 }
 
+/// Returns an `InvalidArgument` status for an invalid value of `field`.
+///
+/// Unlike converting a [`FieldViolation`] into a status, the message of the returned status is
+/// just `description`, so this can be used where clients already expect a particular message.
+pub fn invalid_argument(field: impl Into<String>, description: impl Into<String>) -> tonic::Status {
+    let violation = FieldViolation {
+        field: field.into(),
+        description: description.into(),
+    };
+    let message = violation.description.clone();
+
+    match encode_bad_request(vec![violation]) {
+        Ok(details) => encode_status(tonic::Code::InvalidArgument, message, details),
+        Err(e) => e.into(),
+    }
+}
+
 impl From<rpc::bad_request::FieldViolation> for FieldViolation {
     fn from(v: rpc::bad_request::FieldViolation) -> Self {
         Self {
@@ -590,19 +621,94 @@ where
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct QuotaFailure {
     pub subject: String,
     pub description: String,
 }
 
+/// Encodes the quota failures as a `google.rpc.QuotaFailure` details payload
+pub fn encode_quota_failure(failures: Vec<QuotaFailure>) -> Result<Any, EncodeError> {
+    let mut buffer = BytesMut::new();
+
+    rpc::QuotaFailure {
+        violations: failures
+            .into_iter()
+            .map(|f| rpc::quota_failure::Violation {
+                subject: f.subject,
+                description: f.description,
+            })
+            .collect(),
+    }
+    .encode(&mut buffer)?;
+
+    Ok(Any {
+        type_url: "type.googleapis.com/google.rpc.QuotaFailure".to_string(),
+        value: buffer.freeze(),
+    })
+}
+
 impl From<QuotaFailure> for tonic::Status {
     fn from(quota_failure: QuotaFailure) -> Self {
-        tonic::Status::new(
-            tonic::Code::ResourceExhausted,
-            format!("{}: {}", quota_failure.subject, quota_failure.description),
-        )
+        let message = format!("{}: {}", quota_failure.subject, quota_failure.description);
+
+        match encode_quota_failure(vec![quota_failure]) {
+            Ok(details) => encode_status(tonic::Code::ResourceExhausted, message, details),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+impl From<rpc::quota_failure::Violation> for QuotaFailure {
+    fn from(v: rpc::quota_failure::Violation) -> Self {
+        Self {
+            subject: v.subject,
+            description: v.description,
+        }
+    }
+}
+
+/// Returns an iterator over the [`QuotaFailure`] payloads in the provided [`tonic::Status`]
+pub fn decode_quota_failure(status: &tonic::Status) -> impl Iterator<Item = QuotaFailure> {
+    get_details(status)
+        .filter(|details| details.type_url == "type.googleapis.com/google.rpc.QuotaFailure")
+        .flat_map(|details| rpc::QuotaFailure::decode(details.value).ok())
+        .flat_map(|failure| failure.violations)
+        .map(Into::into)
+}
+
+/// Encodes a `google.rpc.RetryInfo` details payload, telling the client to wait at least `delay`
+/// before retrying the request
+pub fn encode_retry_info(delay: Duration) -> Result<Any, EncodeError> {
+    let mut buffer = BytesMut::new();
+
+    rpc::RetryInfo {
+        retry_delay: Some(protobuf::Duration {
+            seconds: delay.as_secs() as i64,
+            nanos: delay.subsec_nanos() as i32,
+        }),
     }
+    .encode(&mut buffer)?;
+
+    Ok(Any {
+        type_url: "type.googleapis.com/google.rpc.RetryInfo".to_string(),
+        value: buffer.freeze(),
+    })
+}
+
+/// Returns the retry delay of the `google.rpc.RetryInfo` payload in the provided
+/// [`tonic::Status`], if any
+pub fn decode_retry_info(status: &tonic::Status) -> Option<Duration> {
+    get_details(status)
+        .filter(|details| details.type_url == "type.googleapis.com/google.rpc.RetryInfo")
+        .flat_map(|details| rpc::RetryInfo::decode(details.value).ok())
+        .flat_map(|info| info.retry_delay)
+        .find_map(|delay| {
+            Some(Duration::new(
+                delay.seconds.try_into().ok()?,
+                delay.nanos.try_into().ok()?,
+            ))
+        })
 }
 
 /// An extension trait that adds the method `field` to any type implementing
@@ -756,6 +862,48 @@ mod tests {
         let status = tonic::Status::from(precondition.clone());
         let collected: Vec<_> = decode_precondition_violation(&status).collect();
         assert_eq!(collected, vec![precondition]);
+
+        let quota_failure = QuotaFailure {
+            subject: "series".to_string(),
+            description: "too many".to_string(),
+        };
+        let status = tonic::Status::from(quota_failure.clone());
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        let collected: Vec<_> = decode_quota_failure(&status).collect();
+        assert_eq!(collected, vec![quota_failure]);
+
+        let status = invalid_argument("name", "bad name");
+        assert_eq!(status.message(), "bad name");
+        let collected: Vec<_> = decode_field_violation(&status).collect();
+        assert_eq!(
+            collected,
+            vec![FieldViolation {
+                field: "name".to_string(),
+                description: "bad name".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_combined_details() {
+        let delay = Duration::from_millis(1500);
+        let status = encode_status_details(
+            tonic::Code::Unavailable,
+            "try again".to_string(),
+            vec![
+                encode_retry_info(delay).unwrap(),
+                encode_bad_request(vec![FieldViolation::required("foo")]).unwrap(),
+            ],
+        );
+
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+        assert_eq!(status.message(), "try again");
+        assert_eq!(decode_retry_info(&status), Some(delay));
+        let collected: Vec<_> = decode_field_violation(&status).collect();
+        assert_eq!(collected, vec![FieldViolation::required("foo")]);
+
+        let status = tonic::Status::unavailable("no details");
+        assert_eq!(decode_retry_info(&status), None);
     }
 
     #[test]
@@ -151,6 +151,16 @@ pub mod influxdata {
             }
         }
 
+        pub mod logging {
+            pub mod v1 {
+                include!(concat!(env!("OUT_DIR"), "/influxdata.iox.logging.v1.rs"));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.logging.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod namespace {
             pub mod v1 {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.namespace.v1.rs"));
@@ -174,6 +184,16 @@ pub mod influxdata {
             }
         }
 
+        pub mod operations {
+            pub mod v1 {
+                include!(concat!(env!("OUT_DIR"), "/influxdata.iox.operations.v1.rs"));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.operations.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod partition_template {
             pub mod v1 {
                 include!(concat!(
@@ -206,6 +206,7 @@ mod tests {
             created_at: 12344321,
             column_set: vec![1, 2, 3, 4, 5],
             max_l0_created_at: 123455555,
+            content_hash: None,
         };
 
         // Broadcast the event from A
@@ -0,0 +1,532 @@
+//! Export of IOx tables as [Delta Lake] tables.
+//!
+//! IOx stores the Parquet files of a table under
+//! `<namespace_id>/<table_id>/<partition>/<uuid>.parquet` in the object store.
+//! [`DeltaExporter`] maintains a Delta Lake transaction log in
+//! `<namespace_id>/<table_id>/_delta_log/` referencing these files, so engines
+//! able to read Delta Lake tables (such as Spark or Trino) can query IOx data
+//! directly from the object store, without copying it.
+//!
+//! Each export compares the files referenced by the transaction log with the
+//! files of the table in the catalog, and commits a new log entry adding the
+//! files persisted or compacted since the last export and removing the files
+//! compacted away or deleted since. Exports are incremental and should be
+//! repeated to keep the Delta Lake tables up to date. Only a single exporter
+//! may write to the transaction log of a table at a time.
+//!
+//! [Delta Lake]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use data_types::{Column, ColumnType, Namespace, ParquetFile, Table};
+use futures_util::TryStreamExt;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use object_store::{path::Path, DynObjectStore};
+use observability_deps::tracing::*;
+use parquet_file::{ParquetFilePath, DELTA_LOG_DIR};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DeltaExportError {
+    #[error("Catalog error: {0}")]
+    Catalog(#[from] iox_catalog::interface::Error),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+
+    #[error("Invalid transaction log entry {path}: {source}")]
+    InvalidLogEntry {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[error("Namespace {0} not found")]
+    NamespaceNotFound(String),
+
+    #[error("Table {0} not found")]
+    TableNotFound(String),
+}
+
+type Result<T, E = DeltaExportError> = std::result::Result<T, E>;
+
+/// The outcome of exporting a single table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableExport {
+    /// The name of the exported table.
+    pub table_name: String,
+    /// The version of the transaction log entry committed by this export, or
+    /// [`None`] if the Delta Lake table was already up to date.
+    pub version: Option<u64>,
+    /// The number of files added to the Delta Lake table.
+    pub added: usize,
+    /// The number of files removed from the Delta Lake table.
+    pub removed: usize,
+}
+
+/// Maintains Delta Lake transaction logs for the tables of IOx namespaces.
+#[derive(Debug)]
+pub struct DeltaExporter {
+    catalog: Arc<dyn Catalog>,
+    object_store: Arc<DynObjectStore>,
+}
+
+impl DeltaExporter {
+    /// Create an exporter for the files recorded in `catalog` and stored in
+    /// `object_store`.
+    pub fn new(catalog: Arc<dyn Catalog>, object_store: Arc<DynObjectStore>) -> Self {
+        Self {
+            catalog,
+            object_store,
+        }
+    }
+
+    /// Export the tables of `namespace_name`, or only `table_name` if
+    /// specified.
+    pub async fn export(
+        &self,
+        namespace_name: &str,
+        table_name: Option<&str>,
+    ) -> Result<Vec<TableExport>> {
+        let mut repos = self.catalog.repositories().await;
+        let namespace = repos
+            .namespaces()
+            .get_by_name(namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await?
+            .ok_or_else(|| DeltaExportError::NamespaceNotFound(namespace_name.to_string()))?;
+
+        let tables = match table_name {
+            Some(table_name) => vec![repos
+                .tables()
+                .get_by_namespace_and_name(namespace.id, table_name)
+                .await?
+                .ok_or_else(|| DeltaExportError::TableNotFound(table_name.to_string()))?],
+            None => repos.tables().list_by_namespace_id(namespace.id).await?,
+        };
+        drop(repos);
+
+        let mut exports = Vec::with_capacity(tables.len());
+        for table in tables {
+            exports.push(self.export_table(&namespace, &table).await?);
+        }
+
+        Ok(exports)
+    }
+
+    /// Bring the transaction log of `table` up to date with the catalog.
+    async fn export_table(&self, namespace: &Namespace, table: &Table) -> Result<TableExport> {
+        let (columns, files) = {
+            let mut repos = self.catalog.repositories().await;
+            let columns = repos.columns().list_by_table_id(table.id).await?;
+            let files = repos
+                .parquet_files()
+                .list_by_table_not_to_delete(table.id)
+                .await?;
+            (columns, files)
+        };
+
+        let root = Path::from_iter([namespace.id.to_string(), table.id.to_string()]);
+        let log = self.read_log(&root).await?;
+
+        let schema = delta_schema(&columns);
+        let files: BTreeMap<String, ParquetFile> = files
+            .into_iter()
+            .map(|f| (relative_path(&root, &f), f))
+            .collect();
+
+        let added: Vec<&ParquetFile> = files
+            .iter()
+            .filter(|(path, _)| !log.files.contains(*path))
+            .map(|(_, f)| f)
+            .collect();
+        let removed: Vec<&String> = log
+            .files
+            .iter()
+            .filter(|path| !files.contains_key(*path))
+            .collect();
+        let schema_changed = log.schema.as_deref() != Some(schema.as_str());
+
+        let mut export = TableExport {
+            table_name: table.name.clone(),
+            version: None,
+            added: added.len(),
+            removed: removed.len(),
+        };
+        if added.is_empty() && removed.is_empty() && !schema_changed {
+            debug!(table_name = %table.name, "delta table up to date");
+            return Ok(export);
+        }
+
+        let now = now_millis();
+        let version = log.version.map_or(0, |v| v + 1);
+
+        let mut actions = vec![json!({
+            "commitInfo": {
+                "timestamp": now,
+                "operation": "WRITE",
+                "operationParameters": {"mode": "Append"},
+                "engineInfo": "influxdb_iox",
+            }
+        })];
+        if log.version.is_none() {
+            actions.push(json!({
+                "protocol": {"minReaderVersion": 1, "minWriterVersion": 2}
+            }));
+        }
+        if schema_changed {
+            actions.push(json!({
+                "metaData": {
+                    "id": log.table_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                    "name": table.name,
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": schema,
+                    "partitionColumns": [],
+                    "configuration": {},
+                    "createdTime": now,
+                }
+            }));
+        }
+        for file in &added {
+            actions.push(json!({
+                "add": {
+                    "path": relative_path(&root, file),
+                    "partitionValues": {},
+                    "size": file.file_size_bytes,
+                    "modificationTime": file.created_at.get() / 1_000_000,
+                    "dataChange": true,
+                    "stats": json!({"numRecords": file.row_count}).to_string(),
+                }
+            }));
+        }
+        for path in &removed {
+            actions.push(json!({
+                "remove": {
+                    "path": path,
+                    "deletionTimestamp": now,
+                    "dataChange": true,
+                }
+            }));
+        }
+
+        let body = actions
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.object_store
+            .put(&log_entry_path(&root, version), Bytes::from(body))
+            .await?;
+
+        info!(
+            table_name = %table.name,
+            version,
+            added = export.added,
+            removed = export.removed,
+            "committed delta log entry"
+        );
+        export.version = Some(version);
+
+        Ok(export)
+    }
+
+    /// Replay the transaction log under `root`.
+    async fn read_log(&self, root: &Path) -> Result<LogState> {
+        let prefix = root.child(DELTA_LOG_DIR);
+        let mut entries: Vec<(u64, Path)> = self
+            .object_store
+            .list(Some(&prefix))
+            .await?
+            .try_filter_map(|meta| async move {
+                let version = meta
+                    .location
+                    .filename()
+                    .and_then(|f| f.strip_suffix(".json"))
+                    .and_then(|v| v.parse::<u64>().ok());
+                Ok(version.map(|v| (v, meta.location)))
+            })
+            .try_collect()
+            .await?;
+        entries.sort_unstable_by_key(|(v, _)| *v);
+
+        let mut state = LogState::default();
+        for (version, path) in entries {
+            let data = self.object_store.get(&path).await?.bytes().await?;
+            for line in data.split(|b| *b == b'\n').filter(|l| !l.is_empty()) {
+                let action: Value = serde_json::from_slice(line).map_err(|source| {
+                    DeltaExportError::InvalidLogEntry {
+                        path: path.to_string(),
+                        source,
+                    }
+                })?;
+                state.apply(&action);
+            }
+            state.version = Some(version);
+        }
+
+        Ok(state)
+    }
+}
+
+/// The state of a Delta Lake table, as reconstructed from its transaction log.
+#[derive(Debug, Default)]
+struct LogState {
+    /// The version of the most recent log entry.
+    version: Option<u64>,
+    /// The unique ID of the Delta Lake table.
+    table_id: Option<String>,
+    /// The most recent schema.
+    schema: Option<String>,
+    /// The files currently part of the table.
+    files: BTreeSet<String>,
+}
+
+impl LogState {
+    fn apply(&mut self, action: &Value) {
+        if let Some(meta) = action.get("metaData") {
+            self.table_id = meta.get("id").and_then(Value::as_str).map(String::from);
+            self.schema = meta
+                .get("schemaString")
+                .and_then(Value::as_str)
+                .map(String::from);
+        }
+        if let Some(path) = action.pointer("/add/path").and_then(Value::as_str) {
+            self.files.insert(path.to_string());
+        }
+        if let Some(path) = action.pointer("/remove/path").and_then(Value::as_str) {
+            self.files.remove(path);
+        }
+    }
+}
+
+/// The path of `file` relative to the table `root`.
+fn relative_path(root: &Path, file: &ParquetFile) -> String {
+    let path = ParquetFilePath::from(file).object_store_path();
+    path.prefix_match(root)
+        .expect("parquet file must be stored under its table")
+        .map(|part| part.as_ref().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn log_entry_path(root: &Path, version: u64) -> Path {
+    root.child(DELTA_LOG_DIR)
+        .child(format!("{version:020}.json"))
+}
+
+/// Return the Delta Lake schema of a table with `columns`, in the order of
+/// their names.
+///
+/// Tags are strings, and unsigned integers are exported as signed 64 bit
+/// integers as Delta Lake has no unsigned types.
+fn delta_schema(columns: &[Column]) -> String {
+    let mut columns: Vec<&Column> = columns.iter().collect();
+    columns.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let fields: Vec<Value> = columns
+        .into_iter()
+        .map(|c| {
+            let (data_type, nullable) = match c.column_type {
+                ColumnType::I64 | ColumnType::U64 => ("long", true),
+                ColumnType::F64 => ("double", true),
+                ColumnType::Bool => ("boolean", true),
+                ColumnType::String | ColumnType::Tag => ("string", true),
+                ColumnType::Time => ("timestamp", false),
+            };
+            json!({
+                "name": c.name,
+                "type": data_type,
+                "nullable": nullable,
+                "metadata": {},
+            })
+        })
+        .collect();
+
+    json!({"type": "struct", "fields": fields}).to_string()
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before unix epoch")
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::{
+        ColumnId, ColumnSet, CompactionLevel, NamespaceName, ParquetFileParams, PartitionKey,
+        Timestamp,
+    };
+    use iox_catalog::mem::MemCatalog;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    async fn create_file(catalog: &Arc<dyn Catalog>, table: &Table) -> ParquetFile {
+        let mut repos = catalog.repositories().await;
+        let partition = repos
+            .partitions()
+            .create_or_get(PartitionKey::from("1970-01-01"), table.id)
+            .await
+            .unwrap();
+        repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                namespace_id: table.namespace_id,
+                table_id: table.id,
+                partition_id: partition.transition_partition_id(),
+                object_store_id: uuid::Uuid::new_v4(),
+                min_time: Timestamp::new(1),
+                max_time: Timestamp::new(10),
+                file_size_bytes: 1_337,
+                row_count: 42,
+                compaction_level: CompactionLevel::Initial,
+                created_at: Timestamp::new(1),
+                column_set: ColumnSet::new([ColumnId::new(1)]),
+                max_l0_created_at: Timestamp::new(1),
+                content_hash: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn read_actions(store: &Arc<DynObjectStore>, root: &Path, version: u64) -> Vec<Value> {
+        let data = store
+            .get(&log_entry_path(root, version))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        data.split(|b| *b == b'\n')
+            .map(|l| serde_json::from_slice(l).unwrap())
+            .collect()
+    }
+
+    fn action_count(actions: &[Value], kind: &str) -> usize {
+        actions.iter().filter(|a| a.get(kind).is_some()).count()
+    }
+
+    #[tokio::test]
+    async fn test_export() {
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Default::default()));
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let exporter = DeltaExporter::new(Arc::clone(&catalog), Arc::clone(&store));
+
+        let (namespace, table) = {
+            let mut repos = catalog.repositories().await;
+            let namespace = repos
+                .namespaces()
+                .create(&NamespaceName::new("bananas").unwrap(), None, None, None)
+                .await
+                .unwrap();
+            let table = repos
+                .tables()
+                .create("platanos", Default::default(), namespace.id)
+                .await
+                .unwrap();
+            repos
+                .columns()
+                .create_or_get("time", table.id, ColumnType::Time)
+                .await
+                .unwrap();
+            (namespace, table)
+        };
+        let root = Path::from_iter([namespace.id.to_string(), table.id.to_string()]);
+
+        let unknown = exporter.export("unknown", None).await;
+        assert!(matches!(
+            unknown,
+            Err(DeltaExportError::NamespaceNotFound(_))
+        ));
+
+        // The first export creates the table, with its protocol and schema.
+        let a = create_file(&catalog, &table).await;
+        let got = exporter.export("bananas", None).await.unwrap();
+        assert_eq!(
+            got,
+            [TableExport {
+                table_name: "platanos".to_string(),
+                version: Some(0),
+                added: 1,
+                removed: 0,
+            }]
+        );
+        let actions = read_actions(&store, &root, 0).await;
+        assert_eq!(action_count(&actions, "protocol"), 1);
+        assert_eq!(action_count(&actions, "metaData"), 1);
+        assert_eq!(action_count(&actions, "add"), 1);
+        let path = actions[3]["add"]["path"].as_str().unwrap().to_string();
+        assert_eq!(path, relative_path(&root, &a));
+
+        // Exporting again without changes commits nothing.
+        let got = exporter.export("bananas", Some("platanos")).await.unwrap();
+        assert_eq!(got[0].version, None);
+
+        // Compacting the file into another adds one file and removes the
+        // other, without repeating the protocol or unchanged schema.
+        let b = create_file(&catalog, &table).await;
+        catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .create_upgrade_delete(&[a.id], &[], &[], CompactionLevel::FileNonOverlapped)
+            .await
+            .unwrap();
+        let got = exporter.export("bananas", Some("platanos")).await.unwrap();
+        assert_eq!(
+            got,
+            [TableExport {
+                table_name: "platanos".to_string(),
+                version: Some(1),
+                added: 1,
+                removed: 1,
+            }]
+        );
+        let actions = read_actions(&store, &root, 1).await;
+        assert_eq!(action_count(&actions, "protocol"), 0);
+        assert_eq!(action_count(&actions, "metaData"), 0);
+        assert_eq!(
+            actions[1]["add"]["path"].as_str().unwrap(),
+            relative_path(&root, &b)
+        );
+        assert_eq!(actions[2]["remove"]["path"].as_str().unwrap(), path);
+
+        // A new column updates the schema.
+        catalog
+            .repositories()
+            .await
+            .columns()
+            .create_or_get("region", table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let got = exporter.export("bananas", Some("platanos")).await.unwrap();
+        assert_eq!(got[0].version, Some(2));
+        let actions = read_actions(&store, &root, 2).await;
+        assert_eq!(action_count(&actions, "metaData"), 1);
+    }
+
+    #[test]
+    fn test_delta_schema() {
+        let column = |name: &str, column_type| Column {
+            id: ColumnId::new(1),
+            table_id: data_types::TableId::new(1),
+            name: name.to_string(),
+            column_type,
+        };
+        let schema = delta_schema(&[
+            column("time", ColumnType::Time),
+            column("region", ColumnType::Tag),
+            column("count", ColumnType::U64),
+        ]);
+        assert_eq!(
+            schema,
+            r#"{"fields":[{"metadata":{},"name":"count","nullable":true,"type":"long"},{"metadata":{},"name":"region","nullable":true,"type":"string"},{"metadata":{},"name":"time","nullable":false,"type":"timestamp"}],"type":"struct"}"#
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use data_types::{PartitionHashId, PartitionId, TransitionPartitionId};
+use data_types::{
+    ContentHash, ContentHashError, PartitionHashId, PartitionId, TransitionPartitionId,
+};
 use futures_util::TryStreamExt;
 use influxdb_iox_client::{
     catalog::{
@@ -26,6 +28,23 @@ pub enum ExportError {
 
     #[error("Writing file: {0}")]
     File(#[from] std::io::Error),
+
+    #[error("Invalid content hash in catalog: {0}")]
+    ContentHash(#[from] ContentHashError),
+
+    #[error("Checksum mismatch for {path:?}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: ContentHash,
+        actual: ContentHash,
+    },
+
+    #[error("Size mismatch for {path:?}: expected {expected} bytes, got {actual}")]
+    SizeMismatch {
+        path: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
 }
 
 type Result<T, E = ExportError> = std::result::Result<T, E>;
@@ -92,6 +111,50 @@ impl RemoteExporter {
         Ok(())
     }
 
+    /// Exports the data and metadata of the chunk (the parquet files) of the
+    /// partition with catalog id `partition_id` to local files.
+    ///
+    /// If `output_directory` is specified, all files are written there,
+    /// otherwise files are exported to a directory named
+    /// `partition_<partition_id>`.
+    ///
+    /// The checksum of each parquet file is verified and written next to it,
+    /// and interrupted downloads are resumed when the export is repeated
+    /// with the same output directory. See [`RemoteImporter`] to import the
+    /// exported files into a catalog.
+    ///
+    /// [`RemoteImporter`]: crate::file::RemoteImporter
+    pub async fn export_partition(
+        &mut self,
+        output_directory: Option<PathBuf>,
+        partition_id: i64,
+    ) -> Result<()> {
+        let output_directory =
+            output_directory.unwrap_or_else(|| PathBuf::from(format!("partition_{partition_id}")));
+        fs::create_dir_all(&output_directory).await?;
+
+        let parquet_files = self
+            .catalog_client
+            .get_parquet_files_by_partition_id(partition_id)
+            .await?;
+
+        if let Some(parquet_file) = parquet_files.get(0) {
+            self.export_table_metadata(&output_directory, parquet_file.table_id)
+                .await?;
+        }
+
+        let num_parquet_files = parquet_files.len();
+        println!("found {num_parquet_files} Parquet files, exporting...");
+
+        for (index, parquet_file) in parquet_files.iter().enumerate() {
+            self.export_parquet_file(&output_directory, index, num_parquet_files, parquet_file)
+                .await?;
+        }
+        println!("Done.");
+
+        Ok(())
+    }
+
     /// Exports table and partition information for the specified
     /// table. Overwrites existing files, if any, to ensure it has the
     /// latest catalog information.
@@ -137,6 +200,14 @@ impl RemoteExporter {
     /// 1. `<output_directory>/<uuid>.parquet`: The parquet bytes
     ///
     /// 2. `<output_directory>/<uuid>.parquet.json`: pbjson encoded `ParquetFile` metadata
+    ///
+    /// 3. `<output_directory>/<uuid>.parquet.sha256`: The SHA-256 checksum of
+    /// the parquet bytes, in the format of `sha256sum`
+    ///
+    /// The parquet bytes are downloaded to `<uuid>.parquet.partial` first,
+    /// and a download is resumed from the end of an existing partial file.
+    /// If the catalog recorded a content hash for the file, the downloaded
+    /// bytes are verified against it.
     async fn export_parquet_file(
         &mut self,
         output_directory: &Path,
@@ -170,25 +241,78 @@ impl RemoteExporter {
                 index + 1
             );
         } else {
-            // scope to close files
-            {
-                println!(
-                    "downloading file {} of {num_parquet_files} ({filename})...",
-                    index + 1
-                );
+            let partial_path = output_directory.join(format!("{filename}.partial"));
+            let offset = match fs::metadata(&partial_path).await {
+                Ok(metadata) if metadata.len() <= file_size_bytes => metadata.len(),
+                Ok(_) => {
+                    // The partial file is larger than the file it is a
+                    // download of, so start again.
+                    fs::remove_file(&partial_path).await?;
+                    0
+                }
+                Err(_) => 0,
+            };
+
+            if offset < file_size_bytes {
+                if offset == 0 {
+                    println!(
+                        "downloading file {} of {num_parquet_files} ({filename})...",
+                        index + 1
+                    );
+                } else {
+                    println!(
+                        "resuming download of file {} of {num_parquet_files} ({filename}) at byte {offset}...",
+                        index + 1
+                    );
+                }
+
                 let mut response = self
                     .store_client
-                    .get_parquet_file_by_object_store_id(uuid.clone())
+                    .get_parquet_file_by_object_store_id_from_offset(uuid.clone(), offset)
                     .await?
                     .map_ok(|res| res.data)
                     .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
                     .into_async_read()
                     .compat();
-                let mut file = File::create(&file_path).await?;
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&partial_path)
+                    .await?;
                 io::copy(&mut response, &mut file).await?;
+                file.flush().await?;
+            }
+
+            fs::rename(&partial_path, &file_path).await?;
+        }
+
+        let data = fs::read(&file_path).await?;
+        let actual_size = data.len() as u64;
+        if actual_size != file_size_bytes {
+            fs::remove_file(&file_path).await?;
+            return Err(ExportError::SizeMismatch {
+                path: file_path,
+                expected: file_size_bytes,
+                actual: actual_size,
+            });
+        }
+
+        let checksum = ContentHash::of(&data);
+        if let Some(expected) = &parquet_file.content_hash {
+            let expected = ContentHash::try_from(expected.as_slice())?;
+            if expected != checksum {
+                fs::remove_file(&file_path).await?;
+                return Err(ExportError::ChecksumMismatch {
+                    path: file_path,
+                    expected,
+                    actual: checksum,
+                });
             }
         }
 
+        let checksum_path = output_directory.join(format!("{filename}.sha256"));
+        write_string_to_file(&format!("{checksum}  {filename}\n"), &checksum_path).await?;
+
         Ok(())
     }
 }
@@ -6,7 +6,7 @@ use data_types::{
     partition_template::{
         NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, PARTITION_BY_DAY_PROTO,
     },
-    ColumnSet, ColumnType, ColumnsByName, CompactionLevel, Namespace, NamespaceName,
+    ColumnSet, ColumnType, ColumnsByName, CompactionLevel, ContentHash, Namespace, NamespaceName,
     NamespaceNameError, ParquetFileParams, Partition, PartitionKey, SortedColumnSet, Statistics,
     Table, TableId, Timestamp,
 };
@@ -26,6 +26,7 @@ use std::{
     sync::Arc,
 };
 use thiserror::Error;
+use tracker::ProgressReporter;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -78,6 +79,19 @@ pub enum Error {
 
     #[error("Object store error: {0}")]
     ObjectStore(#[from] object_store::Error),
+
+    #[error("Checksum mismatch for {path:?}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: String,
+        actual: ContentHash,
+    },
+
+    #[error("Import cancelled after {files_done} of {total_files} files")]
+    Cancelled {
+        files_done: usize,
+        total_files: usize,
+    },
 }
 
 impl Error {
@@ -107,6 +121,9 @@ pub struct ExportedContents {
     /// partition .json files
     partition_json_files: Vec<PathBuf>,
 
+    /// .parquet.sha256 files (checksums of the parquet files)
+    checksum_files: Vec<PathBuf>,
+
     /// Decoded partition metadata,  found in the export
     partition_metadata: Vec<proto::Partition>,
 
@@ -160,6 +177,9 @@ impl ExportedContents {
                 } else {
                     warn!(?path, "IGNORING unknown JSON file");
                 }
+            } else if extension == "sha256" {
+                // names like "<UUID>.parquet.sha256"
+                new_self.checksum_files.push(path);
             } else {
                 warn!(?path, "IGNORING unknown file");
             }
@@ -231,6 +251,28 @@ impl ExportedContents {
         self.partition_json_files.as_ref()
     }
 
+    pub fn checksum_files(&self) -> &[PathBuf] {
+        self.checksum_files.as_ref()
+    }
+
+    /// Returns the exported checksum of the parquet file at `parquet_path`,
+    /// if any, as a hex encoded SHA-256 hash
+    pub fn checksum(&self, parquet_path: &Path) -> Result<Option<String>> {
+        let checksum_name = format!("{}.sha256", file_name(parquet_path));
+        let Some(path) = self
+            .checksum_files
+            .iter()
+            .find(|p| file_name(p) == checksum_name)
+        else {
+            return Ok(None);
+        };
+
+        let contents = std::fs::read_to_string(path).map_err(|e| Error::reading(path, e))?;
+
+        // the format of `sha256sum`: "<hash>  <file name>"
+        Ok(contents.split_whitespace().next().map(ToString::to_string))
+    }
+
     /// Returns partition information retrieved from the exported
     /// catalog, if any, with the given table id and partition key
     pub fn partition_metadata(
@@ -267,6 +309,13 @@ pub struct RemoteImporter {
     exported_contents: ExportedContents,
     catalog: Arc<dyn Catalog>,
     object_store: Arc<dyn ObjectStore>,
+
+    /// The namespace to import into, if not the namespace the files were
+    /// exported from.
+    namespace_name: Option<String>,
+
+    /// Where to report the number of files imported, if anywhere.
+    progress: Option<ProgressReporter>,
 }
 
 impl RemoteImporter {
@@ -279,9 +328,25 @@ impl RemoteImporter {
             exported_contents,
             catalog,
             object_store,
+            namespace_name: None,
+            progress: None,
         }
     }
 
+    /// Import the files into the namespace named `namespace_name`, rather
+    /// than the namespace they were exported from.
+    pub fn with_namespace(mut self, namespace_name: impl Into<String>) -> Self {
+        self.namespace_name = Some(namespace_name.into());
+        self
+    }
+
+    /// Report the number of files imported to `progress`, stopping the
+    /// import between files once its task is cancelled.
+    pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
     /// Performs the import, reporting status to observer and erroring
     /// if a failure occurs
     pub async fn import(&self) -> Result<()> {
@@ -289,8 +354,23 @@ impl RemoteImporter {
 
         let total_files = parquet_files.len();
         info!(%total_files, "Begin importing files");
+        if let Some(progress) = &self.progress {
+            progress.add_total(total_files);
+        }
         for (files_done, file) in parquet_files.iter().enumerate() {
+            if let Some(progress) = &self.progress {
+                if progress.is_cancelled() {
+                    return Err(Error::Cancelled {
+                        files_done,
+                        total_files,
+                    });
+                }
+            }
+
             self.import_parquet(file).await?;
+            if let Some(progress) = &self.progress {
+                progress.add_done(1);
+            }
 
             // print a log message every 50 files
             if files_done % 50 == 0 {
@@ -317,6 +397,18 @@ impl RemoteImporter {
             .map_err(|e| Error::reading(file_path, e))?;
         let bytes = Bytes::from(file_bytes);
         let file_size_bytes = bytes.len();
+        let content_hash = ContentHash::of(&bytes);
+
+        if let Some(expected) = self.exported_contents.checksum(file_path)? {
+            if expected != content_hash.to_string() {
+                return Err(Error::ChecksumMismatch {
+                    path: PathBuf::from(file_path),
+                    expected,
+                    actual: content_hash,
+                });
+            }
+            debug!(?file_path, %content_hash, "verified checksum");
+        }
 
         let Some(iox_parquet_metadata) = IoxParquetMetaData::from_file_bytes(bytes.clone())? else {
             return Err(Error::ParquetMetadataNotFound {
@@ -331,7 +423,10 @@ impl RemoteImporter {
         debug!(?iox_metadata, "read metadata");
 
         // step 2: Add the appropriate entry to the catalog
-        let namespace_name = iox_metadata.namespace_name.as_ref();
+        let namespace_name = self
+            .namespace_name
+            .as_deref()
+            .unwrap_or_else(|| iox_metadata.namespace_name.as_ref());
         let mut repos = self.catalog.repositories().await;
 
         let namespace = repos
@@ -401,6 +496,7 @@ impl RemoteImporter {
                 &iox_metadata,
                 &decoded_iox_parquet_metadata,
                 file_size_bytes,
+                content_hash,
             )
             .await?;
 
@@ -611,6 +707,7 @@ impl RemoteImporter {
         iox_metadata: &IoxMetadata,
         decoded_iox_parquet_metadata: &DecodedIoxParquetMetaData,
         file_size_bytes: usize,
+        content_hash: ContentHash,
     ) -> Result<ParquetFileParams> {
         let object_store_id = iox_metadata.object_store_id;
 
@@ -636,6 +733,7 @@ impl RemoteImporter {
                 created_at: Timestamp::new(proto_parquet_file.created_at),
                 column_set,
                 max_l0_created_at: Timestamp::new(proto_parquet_file.max_l0_created_at),
+                content_hash: Some(content_hash),
             }
         } else {
             warn!("Could not read parquet file metadata, reconstructing based on encoded metadata");
@@ -659,6 +757,7 @@ impl RemoteImporter {
                 created_at,
                 column_set,
                 max_l0_created_at: created_at,
+                content_hash: Some(content_hash),
             }
         };
         debug!(?params, "Created ParquetFileParams");
@@ -15,3 +15,6 @@ use workspace_hack as _;
 
 /// Import/Export data to files
 pub mod file;
+
+/// Export of IOx tables as Delta Lake tables
+pub mod delta;
@@ -3,7 +3,10 @@
 use clap_blocks::catalog_dsn::CatalogDsnConfig;
 use thiserror::Error;
 
-use crate::process_info::setup_metric_registry;
+use crate::{commands::output::OutputFormat, process_info::setup_metric_registry};
+
+mod delta;
+mod tenant;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
@@ -13,6 +16,12 @@ pub enum Error {
 
     #[error("Catalog DSN error: {0}")]
     CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Delta export error: {0}")]
+    Delta(#[from] delta::Error),
+
+    #[error("Tenant error: {0}")]
+    Tenant(#[from] tenant::Error),
 }
 
 /// Various commands for catalog manipulation
@@ -34,9 +43,15 @@ struct Setup {
 enum Command {
     /// Run database migrations
     Setup(Setup),
+
+    /// Manage tenants
+    Tenant(tenant::Config),
+
+    /// Export the tables of a namespace as Delta Lake tables
+    DeltaExport(delta::Config),
 }
 
-pub async fn command(config: Config) -> Result<(), Error> {
+pub async fn command(config: Config, format: Option<OutputFormat>) -> Result<(), Error> {
     match config.command {
         Command::Setup(command) => {
             let metrics = setup_metric_registry();
@@ -44,6 +59,8 @@ pub async fn command(config: Config) -> Result<(), Error> {
             catalog.setup().await?;
             println!("OK");
         }
+        Command::Tenant(config) => tenant::command(config, format).await?,
+        Command::DeltaExport(config) => delta::command(config, format).await?,
     }
 
     Ok(())
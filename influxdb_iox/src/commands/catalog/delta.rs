@@ -0,0 +1,126 @@
+//! This module implements the `catalog delta-export` CLI command
+
+use std::sync::Arc;
+
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_object_store, ObjectStoreConfig},
+};
+use comfy_table::{Cell, Table};
+use import_export::delta::{DeltaExportError, DeltaExporter, TableExport};
+use iox_catalog::interface::Catalog;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    commands::output::{self, OutputFormat},
+    process_info::setup_metric_registry,
+};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] clap_blocks::object_store::ParseError),
+
+    #[error("Export error: {0}")]
+    Export(#[from] DeltaExportError),
+
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Maintain Delta Lake transaction logs for the tables of a namespace, so
+/// their parquet files can be queried in place by Delta Lake readers.
+///
+/// Each run adds the files persisted or compacted since the previous run to
+/// the log, and removes the files that were compacted away or deleted. Run it
+/// periodically to keep the Delta Lake tables up to date. Only one export may
+/// run against a namespace at a time.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    object_store: ObjectStoreConfig,
+
+    /// The name of the namespace to export
+    #[clap(action)]
+    namespace: String,
+
+    /// Only export this table. If omitted, all tables of the namespace are
+    /// exported.
+    #[clap(long, action)]
+    table: Option<String>,
+}
+
+pub async fn command(config: Config, format: Option<OutputFormat>) -> Result<(), Error> {
+    let metrics = setup_metric_registry();
+    let catalog: Arc<dyn Catalog> = config.catalog_dsn.get_catalog("cli", metrics).await?;
+    let object_store = make_object_store(&config.object_store)?;
+
+    let exports = DeltaExporter::new(catalog, object_store)
+        .export(&config.namespace, config.table.as_deref())
+        .await?;
+    match format.unwrap_or(OutputFormat::Pretty) {
+        OutputFormat::Pretty => println!("{}", create_table(&exports)),
+        format => {
+            let summaries: Vec<_> = exports.iter().map(Summary::from).collect();
+            output::print(format, &summaries)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A flattened view of a table export. `version` is `null` if the table was
+/// already up to date.
+#[derive(Debug, Serialize)]
+struct Summary<'a> {
+    table: &'a str,
+    version: Option<u64>,
+    added: usize,
+    removed: usize,
+}
+
+impl<'a> From<&'a TableExport> for Summary<'a> {
+    fn from(export: &'a TableExport) -> Self {
+        Self {
+            table: &export.table_name,
+            version: export.version,
+            added: export.added,
+            removed: export.removed,
+        }
+    }
+}
+
+/// Turn table exports into a table
+fn create_table(exports: &[TableExport]) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+
+    let headers: Vec<_> = ["table", "version", "added", "removed"]
+        .into_iter()
+        .map(Cell::new)
+        .collect();
+    table.set_header(headers);
+
+    for export in exports {
+        table.add_row(vec![
+            Cell::new(&export.table_name),
+            Cell::new(
+                export
+                    .version
+                    .map_or_else(|| "unchanged".to_string(), |v| v.to_string()),
+            ),
+            Cell::new(export.added.to_string()),
+            Cell::new(export.removed.to_string()),
+        ]);
+    }
+
+    table
+}
@@ -0,0 +1,221 @@
+//! This module implements the `catalog tenant` CLI command
+
+use std::sync::Arc;
+
+use clap_blocks::catalog_dsn::CatalogDsnConfig;
+use comfy_table::{Cell, Table};
+use data_types::{Tenant, TenantQuotas};
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    commands::output::{self, OutputFormat},
+    process_info::setup_metric_registry,
+};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Catalog error: {0}")]
+    Catalog(#[from] iox_catalog::interface::Error),
+
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Namespace {0} not found")]
+    NamespaceNotFound(String),
+
+    #[error("Tenant {0} not found")]
+    TenantNotFound(String),
+}
+
+/// Manage tenants, which own namespaces and limit the resources they use in
+/// aggregate
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// The quotas of a tenant. Omitted quotas are unlimited.
+#[derive(Debug, clap::Parser)]
+struct Quotas {
+    /// The maximum total size of the parquet files of the tenant's namespaces
+    #[clap(long, action)]
+    max_storage_bytes: Option<i64>,
+
+    /// The maximum number of distinct series written to the tenant's
+    /// namespaces
+    #[clap(long, action)]
+    max_series: Option<i64>,
+
+    /// The maximum number of lines per second written to the tenant's
+    /// namespaces, per router
+    #[clap(long, action)]
+    max_write_lines_per_second: Option<i64>,
+}
+
+impl From<Quotas> for TenantQuotas {
+    fn from(q: Quotas) -> Self {
+        Self {
+            max_storage_bytes: q.max_storage_bytes,
+            max_series: q.max_series,
+            max_write_lines_per_second: q.max_write_lines_per_second,
+        }
+    }
+}
+
+/// All possible subcommands for tenants
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Create a tenant
+    Create {
+        /// The name of the tenant
+        name: String,
+
+        #[clap(flatten)]
+        quotas: Quotas,
+    },
+
+    /// List all tenants
+    List,
+
+    /// Replace the quotas of a tenant
+    Update {
+        /// The name of the tenant
+        name: String,
+
+        #[clap(flatten)]
+        quotas: Quotas,
+    },
+
+    /// Set the tenant owning a namespace
+    Assign {
+        /// The name of the namespace
+        namespace: String,
+
+        /// The name of the tenant. If omitted, the namespace is removed from
+        /// its tenant.
+        #[clap(long, action)]
+        tenant: Option<String>,
+    },
+}
+
+pub async fn command(config: Config, format: Option<OutputFormat>) -> Result<(), Error> {
+    let metrics = setup_metric_registry();
+    let catalog: Arc<dyn Catalog> = config.catalog_dsn.get_catalog("cli", metrics).await?;
+    let mut repos = catalog.repositories().await;
+
+    match config.command {
+        Command::Create { name, quotas } => {
+            let tenant = repos.tenants().create(&name, quotas.into()).await?;
+            print(format, &[tenant])?;
+        }
+        Command::List => {
+            let tenants = repos.tenants().list().await?;
+            print(format, &tenants)?;
+        }
+        Command::Update { name, quotas } => {
+            let tenant = repos.tenants().update_quotas(&name, quotas.into()).await?;
+            print(format, &[tenant])?;
+        }
+        Command::Assign { namespace, tenant } => {
+            let tenant_id = match tenant {
+                Some(name) => Some(
+                    repos
+                        .tenants()
+                        .get_by_name(&name)
+                        .await?
+                        .ok_or(Error::TenantNotFound(name))?
+                        .id,
+                ),
+                None => None,
+            };
+            repos
+                .namespaces()
+                .get_by_name(&namespace, SoftDeletedRows::ExcludeDeleted)
+                .await?
+                .ok_or_else(|| Error::NamespaceNotFound(namespace.clone()))?;
+            repos
+                .namespaces()
+                .update_tenant(&namespace, tenant_id)
+                .await?;
+            println!("OK");
+        } // Deliberately not adding _ => so the compiler will direct people here to impl new
+          // commands
+    }
+
+    Ok(())
+}
+
+/// Print tenants as a table, unless another format is requested
+fn print(format: Option<OutputFormat>, tenants: &[Tenant]) -> Result<(), Error> {
+    match format.unwrap_or(OutputFormat::Pretty) {
+        OutputFormat::Pretty => println!("{}", create_table(tenants)),
+        format => {
+            let summaries: Vec<_> = tenants.iter().map(Summary::from).collect();
+            output::print(format, &summaries)?;
+        }
+    }
+    Ok(())
+}
+
+/// A flattened view of a tenant and its quotas. Unlimited quotas are `null`.
+#[derive(Debug, Serialize)]
+struct Summary<'a> {
+    id: i64,
+    name: &'a str,
+    max_storage_bytes: Option<i64>,
+    max_series: Option<i64>,
+    max_write_lines_per_second: Option<i64>,
+}
+
+impl<'a> From<&'a Tenant> for Summary<'a> {
+    fn from(tenant: &'a Tenant) -> Self {
+        Self {
+            id: tenant.id.get(),
+            name: &tenant.name,
+            max_storage_bytes: tenant.quotas.max_storage_bytes,
+            max_series: tenant.quotas.max_series,
+            max_write_lines_per_second: tenant.quotas.max_write_lines_per_second,
+        }
+    }
+}
+
+/// Turn tenants into a table
+fn create_table(tenants: &[Tenant]) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+
+    let headers: Vec<_> = [
+        "id",
+        "name",
+        "max_storage_bytes",
+        "max_series",
+        "max_write_lines_per_second",
+    ]
+    .into_iter()
+    .map(Cell::new)
+    .collect();
+    table.set_header(headers);
+
+    let limit = |v: Option<i64>| v.map_or_else(|| "unlimited".to_string(), |v| v.to_string());
+    for tenant in tenants {
+        table.add_row(vec![
+            Cell::new(tenant.id.to_string()),
+            Cell::new(&tenant.name),
+            Cell::new(limit(tenant.quotas.max_storage_bytes)),
+            Cell::new(limit(tenant.quotas.max_series)),
+            Cell::new(limit(tenant.quotas.max_write_lines_per_second)),
+        ]);
+    }
+
+    table
+}
@@ -1,4 +1,5 @@
 //! This module implements the `build_catalog` CLI command
+use data_types::job::Job;
 use import_export::file::{ExportedContents, RemoteImporter};
 use iox_catalog::interface::Catalog;
 use metric::Registry;
@@ -10,6 +11,8 @@ use std::{
     sync::Arc,
 };
 
+use crate::commands::operation::track_local_job;
+
 #[derive(Debug, Snafu)]
 pub enum Error {
     #[snafu(display("Not yet implemented"))]
@@ -70,7 +73,8 @@ pub async fn command(config: Config) -> Result<(), Error> {
 
     info!("Initialized catalog, object store, and input path ...");
 
-    let importer = RemoteImporter::new(exported_contents, catalog, object_store);
+    let importer = RemoteImporter::new(exported_contents, catalog, object_store)
+        .with_progress(track_local_job(Job::RebuildCatalog));
 
     info!(
         ?input_dir,
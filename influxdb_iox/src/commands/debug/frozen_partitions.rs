@@ -0,0 +1,125 @@
+//! This module implements the `frozen-partitions` CLI command
+
+use comfy_table::{Cell, Table};
+use influxdb_iox_client::{
+    compactor::{self, generated_types::FrozenPartition},
+    connection::Connection,
+};
+use iox_time::Time;
+use thiserror::Error;
+
+use crate::commands::output::{self, OutputFormat};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    Client(#[from] influxdb_iox_client::error::Error),
+}
+
+/// Various commands for freezing partitions for maintenance
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// All possible subcommands for frozen partitions
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// List all frozen partitions
+    List,
+
+    /// Freeze the requested partition, rejecting writes to it and skipping
+    /// its compaction until it is unfrozen
+    Freeze {
+        partition_id: i64,
+
+        /// Why the partition is frozen
+        #[clap(long, default_value = "maintenance")]
+        reason: String,
+    },
+
+    /// Unfreeze the requested partition
+    Unfreeze { partition_id: i64 },
+}
+
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: OutputFormat,
+) -> Result<(), Error> {
+    let mut client = compactor::Client::new(connection);
+    match config.command {
+        Command::List => {
+            let frozen_partitions = client.frozen_partitions().await?;
+            print(format, &frozen_partitions)?;
+        }
+
+        Command::Freeze {
+            partition_id,
+            reason,
+        } => {
+            let frozen_partition = client.freeze_partition(partition_id, reason).await?;
+            print(format, std::slice::from_ref(&frozen_partition))?;
+        }
+
+        Command::Unfreeze { partition_id } => {
+            let unfrozen_partition = client.unfreeze_partition(partition_id).await?;
+
+            let unfrozen_partition = unfrozen_partition
+                .as_ref()
+                .map(std::slice::from_ref)
+                .unwrap_or_default();
+
+            print(format, unfrozen_partition)?;
+        } // Deliberately not adding _ => so the compiler will direct people here to impl new
+          // commands
+    }
+
+    Ok(())
+}
+
+/// Print frozen partition records as a table or JSON
+#[allow(clippy::result_large_err)]
+fn print(format: OutputFormat, frozen_partitions: &[FrozenPartition]) -> Result<(), Error> {
+    match format {
+        OutputFormat::Pretty => println!("{}", create_table(frozen_partitions)),
+        OutputFormat::Json => output::print(format, &frozen_partitions)?,
+    }
+    Ok(())
+}
+
+/// Turn frozen partition records into a table
+fn create_table(frozen_partitions: &[FrozenPartition]) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+
+    let headers: Vec<_> = [
+        "partition_id",
+        "table_id",
+        "partition_key",
+        "reason",
+        "frozen_at",
+    ]
+    .into_iter()
+    .map(Cell::new)
+    .collect();
+    table.set_header(headers);
+
+    for frozen_partition in frozen_partitions {
+        let frozen_at = Time::from_timestamp_nanos(frozen_partition.frozen_at);
+
+        table.add_row(vec![
+            Cell::new(frozen_partition.partition_id.to_string()),
+            Cell::new(frozen_partition.table_id.to_string()),
+            Cell::new(&frozen_partition.partition_key),
+            Cell::new(&frozen_partition.reason),
+            Cell::new(frozen_at.to_rfc3339()),
+        ]);
+    }
+
+    table
+}
@@ -2,7 +2,10 @@ use futures::Future;
 use influxdb_iox_client::connection::Connection;
 use snafu::prelude::*;
 
+use crate::commands::output::OutputFormat;
+
 mod build_catalog;
+mod frozen_partitions;
 mod parquet_to_lp;
 mod print_cpu;
 mod schema;
@@ -19,6 +22,10 @@ pub enum Error {
     #[snafu(display("Error in build_catalog subcommand: {}", source))]
     BuildCatalog { source: build_catalog::Error },
 
+    #[snafu(context(false))]
+    #[snafu(display("Error in frozen-partitions subcommand: {}", source))]
+    FrozenPartitions { source: frozen_partitions::Error },
+
     #[snafu(context(false))]
     #[snafu(display("Error in parquet_to_lp subcommand: {}", source))]
     ParquetToLp { source: parquet_to_lp::Error },
@@ -66,6 +73,9 @@ enum Command {
     #[clap(verbatim_doc_comment)]
     BuildCatalog(build_catalog::Config),
 
+    /// Freeze partitions for maintenance, and inspect frozen partitions
+    FrozenPartitions(frozen_partitions::Config),
+
     /// Convert IOx Parquet files back into line protocol format
     ParquetToLp(parquet_to_lp::Config),
 
@@ -76,7 +86,11 @@ enum Command {
     Wal(wal::Config),
 }
 
-pub async fn command<C, CFut>(connection: C, config: Config) -> Result<()>
+pub async fn command<C, CFut>(
+    connection: C,
+    config: Config,
+    format: Option<OutputFormat>,
+) -> Result<()>
 where
     C: Send + FnOnce() -> CFut,
     CFut: Send + Future<Output = Connection>,
@@ -85,13 +99,19 @@ where
         Command::PrintCpu => print_cpu::main(),
         Command::Schema(config) => {
             let connection = connection().await;
-            schema::command(connection, config).await?
+            schema::command(connection, config, format.unwrap_or(OutputFormat::Json)).await?
         }
         Command::BuildCatalog(config) => build_catalog::command(config).await?,
+        Command::FrozenPartitions(config) => {
+            let connection = connection().await;
+            frozen_partitions::command(connection, config, format.unwrap_or(OutputFormat::Pretty))
+                .await?
+        }
         Command::ParquetToLp(config) => parquet_to_lp::command(config).await?,
         Command::SkippedCompactions(config) => {
             let connection = connection().await;
-            skipped_compactions::command(connection, config).await?
+            skipped_compactions::command(connection, config, format.unwrap_or(OutputFormat::Pretty))
+                .await?
         }
         Command::Wal(config) => wal::command(connection, config).await?,
     }
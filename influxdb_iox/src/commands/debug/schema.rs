@@ -3,6 +3,8 @@
 use influxdb_iox_client::{connection::Connection, schema};
 use thiserror::Error;
 
+use crate::commands::output::{self, OutputFormat};
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum Error {
@@ -35,12 +37,16 @@ enum Command {
     Get(Get),
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: OutputFormat,
+) -> Result<(), Error> {
     match config.command {
         Command::Get(command) => {
             let mut client = schema::Client::new(connection);
             let schema = client.get_schema(&command.namespace, None).await?;
-            println!("{}", serde_json::to_string_pretty(&schema)?);
+            output::print(format, &schema)?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
           // commands
     }
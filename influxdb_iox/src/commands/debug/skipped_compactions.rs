@@ -8,6 +8,8 @@ use influxdb_iox_client::{
 use iox_time::Time;
 use thiserror::Error;
 
+use crate::commands::output::{self, OutputFormat};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("JSON Serialization error: {0}")]
@@ -37,12 +39,16 @@ enum Command {
     Delete { partition_id: i64 },
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: OutputFormat,
+) -> Result<(), Error> {
     let mut client = compactor::Client::new(connection);
     match config.command {
         Command::List => {
             let skipped_compactions = client.skipped_compactions().await?;
-            println!("{}", create_table(&skipped_compactions)?);
+            print(format, &skipped_compactions)?;
         }
 
         Command::Delete { partition_id } => {
@@ -54,7 +60,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<(), Error
                 .map(std::slice::from_ref)
                 .unwrap_or_default();
 
-            println!("{}", create_table(deleted_skipped_compactions)?);
+            print(format, deleted_skipped_compactions)?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
           // commands
     }
@@ -62,6 +68,16 @@ pub async fn command(connection: Connection, config: Config) -> Result<(), Error
     Ok(())
 }
 
+/// Print skipped compaction records as a table or JSON
+#[allow(clippy::result_large_err)]
+fn print(format: OutputFormat, skipped_compactions: &[SkippedCompaction]) -> Result<(), Error> {
+    match format {
+        OutputFormat::Pretty => println!("{}", create_table(skipped_compactions)?),
+        OutputFormat::Json => output::print(format, &skipped_compactions)?,
+    }
+    Ok(())
+}
+
 /// Turn skipped compaction records into a table
 #[allow(clippy::result_large_err)]
 fn create_table(skipped_compactions: &[SkippedCompaction]) -> Result<Table, Error> {
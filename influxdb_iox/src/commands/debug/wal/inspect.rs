@@ -4,7 +4,7 @@ use std::{io::Write, ops::RangeInclusive, path::PathBuf};
 use itertools::Itertools;
 use wal::SequencedWalOp;
 
-use super::Error;
+use super::{open_segment, Error};
 
 #[derive(Debug, clap::Parser)]
 pub struct Config {
@@ -17,6 +17,11 @@ pub struct Config {
     /// within the range (inclusive) will be displayed
     #[clap(long, short, value_parser = parse_sequence_number_range)]
     sequence_number_range: Option<RangeInclusive<u64>>,
+
+    /// The file containing the keys the WAL file was encrypted with, in the
+    /// format of the ingester's "--wal-encryption-key-file"
+    #[clap(long, value_parser)]
+    encryption_key_file: Option<PathBuf>,
 }
 
 fn parse_sequence_number_range(s: &str) -> Result<RangeInclusive<u64>, String> {
@@ -41,8 +46,7 @@ fn parse_sequence_number_range(s: &str) -> Result<RangeInclusive<u64>, String> {
 }
 
 pub fn command(config: Config) -> Result<(), Error> {
-    let reader = wal::ClosedSegmentFileReader::from_path(&config.input)
-        .map_err(Error::UnableToReadWalFile)?;
+    let reader = open_segment(&config.input, config.encryption_key_file.as_deref())?;
 
     inspect(config.sequence_number_range, &mut std::io::stdout(), reader)
 }
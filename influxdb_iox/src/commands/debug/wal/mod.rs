@@ -1,9 +1,13 @@
 //! This module implements CLI commands for debugging the ingester WAL.
 
+use std::{path::Path, sync::Arc};
+
 use futures::Future;
 
 use influxdb_iox_client::connection::Connection;
+use object_store_encryption::{KeyError, StaticKeyProvider};
 use thiserror::Error;
+use wal::ClosedSegmentFileReader;
 
 mod inspect;
 mod regenerate_lp;
@@ -31,6 +35,9 @@ pub enum Error {
 
     #[error("errors occurred during inspection of the WAL file: {sources:?}")]
     IncompleteInspection { sources: Vec<wal::Error> },
+
+    #[error("could not load WAL encryption keys: {0}")]
+    InvalidEncryptionKeys(#[from] KeyError),
 }
 
 /// A set of non-fatal errors which can occur during the regeneration of write
@@ -60,6 +67,18 @@ enum Command {
     RegenerateLp(regenerate_lp::Config),
 }
 
+/// Open the WAL file at `path`, decrypting its entries with the keys in
+/// `key_file`, if specified.
+fn open_segment(path: &Path, key_file: Option<&Path>) -> Result<ClosedSegmentFileReader, Error> {
+    let reader = ClosedSegmentFileReader::from_path(path)?;
+    Ok(match key_file {
+        Some(key_file) => {
+            reader.with_encryption_keys(Arc::new(StaticKeyProvider::from_file(key_file)?))
+        }
+        None => reader,
+    })
+}
+
 /// Executes a WAL debugging subcommand as directed by the config
 pub async fn command<C, CFut>(connection: C, config: Config) -> Result<(), Error>
 where
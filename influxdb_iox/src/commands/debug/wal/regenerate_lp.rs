@@ -9,10 +9,10 @@ use hashbrown::HashMap;
 use influxdb_iox_client::connection::Connection;
 use influxdb_iox_client::schema::Client as SchemaClient;
 use observability_deps::tracing::{debug, error, info};
-use wal::{ClosedSegmentFileReader, WriteOpEntry, WriteOpEntryDecoder};
+use wal::{WriteOpEntry, WriteOpEntryDecoder};
 use wal_inspect::{LineProtoWriter, NamespaceDemultiplexer, TableBatchWriter, WriteError};
 
-use super::{Error, RegenerateError};
+use super::{open_segment, Error, RegenerateError};
 
 /// The set of errors which may occur when trying to look up a table name
 /// index for a namespace.
@@ -110,6 +110,11 @@ pub struct Config {
     /// ID for each measurement, rather than the original name
     #[clap(long, short)]
     skip_measurement_lookup: bool,
+
+    /// The file containing the keys the WAL file was encrypted with, in the
+    /// format of the ingester's "--wal-encryption-key-file"
+    #[clap(long, value_parser)]
+    encryption_key_file: Option<PathBuf>,
 }
 
 /// Executes the `regenerate-lp` command with the provided configuration, reading
@@ -119,9 +124,10 @@ where
     C: Send + FnOnce() -> CFut,
     CFut: Send + Future<Output = Connection>,
 {
-    let decoder = WriteOpEntryDecoder::from(
-        ClosedSegmentFileReader::from_path(&config.input).map_err(Error::UnableToReadWalFile)?,
-    );
+    let decoder = WriteOpEntryDecoder::from(open_segment(
+        &config.input,
+        config.encryption_key_file.as_deref(),
+    )?);
 
     let table_name_indexer = if config.skip_measurement_lookup {
         Ok(None)
@@ -0,0 +1,93 @@
+//! This module implements the `log-filter` CLI command
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use influxdb_iox_client::{
+    connection::Connection,
+    logging::{self, generated_types::LogFilter},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::commands::output::{self, OutputFormat};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Inspect and change the log filter of a running server, without
+/// restarting it
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// All possible subcommands for log-filter
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Show the active log filter
+    Get,
+
+    /// Replace the log filter
+    Set {
+        /// The new filter, using the same syntax as `--log-filter`, for
+        /// example `info,iox_query=debug`
+        filter: String,
+
+        /// Revert to the default filter after this long
+        #[clap(long, value_parser = humantime::parse_duration, action)]
+        expiry: Option<Duration>,
+    },
+
+    /// Restore the log filter the server was started with
+    Reset,
+}
+
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: Option<OutputFormat>,
+) -> Result<()> {
+    let format = format.unwrap_or(OutputFormat::Pretty);
+    let mut client = logging::Client::new(connection);
+
+    let filter = match config.command {
+        Command::Get => client.get_log_filter().await?,
+        Command::Set { filter, expiry } => client.set_log_filter(filter, expiry).await?,
+        Command::Reset => client.reset_log_filter().await?,
+    };
+
+    output::print(format, &Summary::from(filter))?;
+
+    Ok(())
+}
+
+/// The log filter state, with the expiry rendered as a timestamp
+#[derive(Debug, Serialize)]
+struct Summary {
+    filter: String,
+    default_filter: String,
+    expires_at: Option<String>,
+}
+
+impl From<LogFilter> for Summary {
+    fn from(f: LogFilter) -> Self {
+        Self {
+            filter: f.filter,
+            default_filter: f.default_filter,
+            expires_at: f.expires_at_ns.map(|ns| {
+                let t = UNIX_EPOCH + Duration::from_nanos(ns.max(0) as u64);
+                humantime::format_rfc3339_seconds(t).to_string()
+            }),
+        }
+    }
+}
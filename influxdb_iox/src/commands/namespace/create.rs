@@ -1,6 +1,10 @@
 use influxdb_iox_client::connection::Connection;
 
-use crate::commands::{namespace::Result, partition_template::PartitionTemplateConfig};
+use crate::commands::{
+    namespace::Result,
+    output::{self, OutputFormat},
+    partition_template::PartitionTemplateConfig,
+};
 use influxdb_iox_client::namespace::generated_types::ServiceProtectionLimits;
 
 /// Write data into the specified database
@@ -11,7 +15,8 @@ pub struct Config {
     namespace: String,
 
     /// Num of hours of the retention period of this namespace.
-    /// If not specified, an infinite retention period will be used.
+    /// If not specified, an infinite retention period will be used, or the
+    /// retention period of the namespace template if one is given.
     #[clap(
         action,
         long = "retention-hours",
@@ -27,6 +32,17 @@ pub struct Config {
     /// Partition template
     #[clap(flatten)]
     partition_template_config: PartitionTemplateConfig,
+
+    /// The name of the namespace template to create the namespace from.
+    /// Settings specified on the command line override those of the template.
+    #[clap(action, long = "template")]
+    template: Option<String>,
+
+    /// Only check the settings of the namespace, printing the errors that would
+    /// cause its creation to fail and warnings about settings that are likely to
+    /// behave unexpectedly, without creating it
+    #[clap(action, long = "dry-run")]
+    dry_run: bool,
 }
 
 #[derive(Debug, clap::Args)]
@@ -56,12 +72,14 @@ impl From<ServiceProtectionLimitsArgs> for Option<ServiceProtectionLimits> {
     }
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<()> {
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
     let Config {
         namespace,
         retention_hours,
         service_protection_limits,
         partition_template_config,
+        template,
+        dry_run,
     } = config;
 
     let mut client = influxdb_iox_client::namespace::Client::new(connection);
@@ -74,15 +92,44 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         // internally
         Some(retention_hours as i64 * 60 * 60 * 1_000_000_000)
     };
-    let namespace = client
-        .create_namespace(
-            &namespace,
-            retention,
-            service_protection_limits.into(),
-            partition_template_config.partition_template,
-        )
-        .await?;
-    println!("{}", serde_json::to_string_pretty(&namespace)?);
+    if dry_run {
+        let report = client
+            .validate_namespace(
+                &namespace,
+                template.as_deref(),
+                retention,
+                service_protection_limits.into(),
+                partition_template_config.partition_template,
+            )
+            .await?;
+        output::print(format, &report)?;
+        return Ok(());
+    }
+
+    let namespace = match template {
+        Some(template) => {
+            client
+                .create_namespace_from_template(
+                    &namespace,
+                    &template,
+                    retention,
+                    service_protection_limits.into(),
+                    partition_template_config.partition_template,
+                )
+                .await?
+        }
+        None => {
+            client
+                .create_namespace(
+                    &namespace,
+                    retention,
+                    service_protection_limits.into(),
+                    partition_template_config.partition_template,
+                )
+                .await?
+        }
+    };
+    output::print(format, &namespace)?;
 
     Ok(())
 }
@@ -100,6 +147,18 @@ mod tests {
 
         assert_eq!(config.namespace, "namespace");
         assert_eq!(config.partition_template_config.partition_template, None);
+        assert_eq!(config.template, None);
+        assert!(!config.dry_run);
+    }
+
+    // Valid config with a namespace template
+    #[test]
+    fn valid_namespace_template() {
+        let config =
+            Config::try_parse_from(["server", "namespace", "--template", "standard"]).unwrap();
+
+        assert_eq!(config.namespace, "namespace");
+        assert_eq!(config.template.as_deref(), Some("standard"));
     }
 
     // Valid config with partition template
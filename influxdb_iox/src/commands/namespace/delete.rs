@@ -1,6 +1,9 @@
 use influxdb_iox_client::connection::Connection;
 
-use crate::commands::namespace::Result;
+use crate::commands::{
+    namespace::Result,
+    output::{self, OutputFormat},
+};
 
 #[derive(Debug, clap::Parser)]
 pub struct Config {
@@ -9,13 +12,16 @@ pub struct Config {
     namespace: String,
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<()> {
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
     let Config { namespace } = config;
 
     let mut client = influxdb_iox_client::namespace::Client::new(connection);
 
     client.delete_namespace(&namespace).await?;
-    println!("Deleted namespace {namespace:?}");
+    match format {
+        OutputFormat::Pretty => println!("Deleted namespace {namespace:?}"),
+        OutputFormat::Json => output::print(format, &serde_json::json!({ "deleted": namespace }))?,
+    }
 
     Ok(())
 }
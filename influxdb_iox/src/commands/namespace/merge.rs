@@ -0,0 +1,57 @@
+use influxdb_iox_client::connection::{Builder, Connection};
+
+use crate::commands::{
+    namespace::Result,
+    operation::Summary,
+    output::{self, OutputFormat},
+};
+
+/// Move the persisted data of a namespace into another namespace in the
+/// background, e.g. to consolidate per-team namespaces without re-ingesting
+/// their data.
+///
+/// The command must be sent to a compactor. Tables with the same name in
+/// both namespaces are merged, and the command fails without changing
+/// anything if their schemas conflict. Only persisted data is moved: stop
+/// writes to the source namespace, and pass the addresses of the ingesters
+/// with `--persist-ingester` to persist their buffered data first. Use
+/// `operation wait` to wait for the merge to complete.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace the data is moved from
+    #[clap(action)]
+    source_namespace: String,
+
+    /// The namespace the data is moved into
+    #[clap(action)]
+    target_namespace: String,
+
+    /// The gRPC address of an ingester to persist the data of the source
+    /// namespace on before the merge. May be repeated.
+    #[clap(long = "persist-ingester", action)]
+    persist_ingesters: Vec<String>,
+}
+
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
+    let Config {
+        source_namespace,
+        target_namespace,
+        persist_ingesters,
+    } = config;
+
+    for address in persist_ingesters {
+        let ingester = Builder::default().build(&address).await?;
+        influxdb_iox_client::ingester::Client::new(ingester)
+            .persist(source_namespace.clone())
+            .await?;
+        eprintln!("persisted {source_namespace} on ingester {address}");
+    }
+
+    let mut client = influxdb_iox_client::compactor::Client::new(connection);
+    let operation = client
+        .merge_namespace(source_namespace, target_namespace)
+        .await?;
+    output::print(format, &Summary::from(&operation))?;
+
+    Ok(())
+}
@@ -1,11 +1,15 @@
 //! This module implements the `namespace` CLI command
 
 use influxdb_iox_client::{connection::Connection, namespace};
+
+use crate::commands::output::{self, OutputFormat};
 use thiserror::Error;
 
 mod create;
 mod delete;
+mod merge;
 mod retention;
+mod template;
 mod update_limit;
 
 #[allow(clippy::enum_variant_names)]
@@ -16,6 +20,9 @@ pub enum Error {
 
     #[error("Client error: {0}")]
     ClientError(#[from] influxdb_iox_client::error::Error),
+
+    #[error("Connection error: {0}")]
+    ConnectionError(#[from] influxdb_iox_client::connection::Error),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -44,26 +51,47 @@ enum Command {
 
     /// Delete a namespace
     Delete(delete::Config),
+
+    /// Manage namespace templates
+    Template(template::Config),
+
+    /// Move the data of a namespace into another namespace
+    Merge(merge::Config),
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<()> {
+/// Namespace commands print JSON unless another format is requested
+const DEFAULT_FORMAT: OutputFormat = OutputFormat::Json;
+
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: Option<OutputFormat>,
+) -> Result<()> {
+    let format = format.unwrap_or(DEFAULT_FORMAT);
+
     match config.command {
         Command::Create(config) => {
-            create::command(connection, config).await?;
+            create::command(connection, config, format).await?;
         }
         Command::List => {
             let mut client = namespace::Client::new(connection);
             let namespaces = client.get_namespaces().await?;
-            println!("{}", serde_json::to_string_pretty(&namespaces)?);
+            output::print(format, &namespaces)?;
         }
         Command::Retention(config) => {
-            retention::command(connection, config).await?;
+            retention::command(connection, config, format).await?;
         }
         Command::UpdateLimit(config) => {
-            update_limit::command(connection, config).await?;
+            update_limit::command(connection, config, format).await?;
         }
         Command::Delete(config) => {
-            delete::command(connection, config).await?;
+            delete::command(connection, config, format).await?;
+        }
+        Command::Template(config) => {
+            template::command(connection, config, format).await?;
+        }
+        Command::Merge(config) => {
+            merge::command(connection, config, format).await?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
           // commands
     }
@@ -1,6 +1,9 @@
 use influxdb_iox_client::connection::Connection;
 
-use crate::commands::namespace::Result;
+use crate::commands::{
+    namespace::Result,
+    output::{self, OutputFormat},
+};
 
 /// Update the specified namespace's data retention period
 #[derive(Debug, clap::Parser)]
@@ -15,7 +18,7 @@ pub struct Config {
     retention_hours: u32,
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<()> {
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
     let Config {
         namespace,
         retention_hours,
@@ -33,7 +36,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
     let namespace = client
         .update_namespace_retention(&namespace, retention)
         .await?;
-    println!("{}", serde_json::to_string_pretty(&namespace)?);
+    output::print(format, &namespace)?;
 
     Ok(())
 }
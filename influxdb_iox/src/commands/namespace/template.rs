@@ -0,0 +1,154 @@
+use influxdb_iox_client::{connection::Connection, namespace::generated_types::NamespaceTemplate};
+
+use crate::commands::{
+    namespace::{create::ServiceProtectionLimitsArgs, Result},
+    output::{self, OutputFormat},
+    partition_template::PartitionTemplateConfig,
+};
+
+/// Manage the namespace templates new namespaces can be created from
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// The settings of a namespace template
+#[derive(Debug, clap::Args)]
+struct TemplateArgs {
+    /// The name of the namespace template
+    #[clap(action)]
+    name: String,
+
+    /// Num of hours of the retention period of namespaces created from this
+    /// template. If not specified, an infinite retention period will be used.
+    #[clap(action, long = "retention-hours", short = 'r', default_value = "0")]
+    retention_hours: u32,
+
+    #[clap(flatten)]
+    service_protection_limits: ServiceProtectionLimitsArgs,
+
+    /// Partition template
+    #[clap(flatten)]
+    partition_template_config: PartitionTemplateConfig,
+}
+
+impl From<TemplateArgs> for NamespaceTemplate {
+    fn from(value: TemplateArgs) -> Self {
+        let TemplateArgs {
+            name,
+            retention_hours,
+            service_protection_limits,
+            partition_template_config,
+        } = value;
+
+        // retention_hours = 0 means infinite retention. Make it None/Null in the request.
+        let retention_period_ns = if retention_hours == 0 {
+            None
+        } else {
+            // we take retention from the user in hours, for ease of use, but it's stored as
+            // nanoseconds internally
+            Some(retention_hours as i64 * 60 * 60 * 1_000_000_000)
+        };
+
+        Self {
+            name,
+            retention_period_ns,
+            partition_template: partition_template_config.partition_template,
+            service_protection_limits: service_protection_limits.into(),
+        }
+    }
+}
+
+/// All possible subcommands for namespace templates
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Create a new namespace template
+    Create(TemplateArgs),
+
+    /// Fetch namespace templates
+    List,
+
+    /// Replace all settings of an existing namespace template
+    Update {
+        #[clap(flatten)]
+        template: TemplateArgs,
+
+        /// Also apply the new retention period and service protection limits
+        /// to the namespaces created from the template. Their partition
+        /// templates cannot be changed and are left as they are.
+        #[clap(action, long = "propagate")]
+        propagate: bool,
+    },
+}
+
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    match config.command {
+        Command::Create(template) => {
+            let template = client.create_namespace_template(template.into()).await?;
+            output::print(format, &template)?;
+        }
+        Command::List => {
+            let templates = client.get_namespace_templates().await?;
+            output::print(format, &templates)?;
+        }
+        Command::Update {
+            template,
+            propagate,
+        } => {
+            let response = client
+                .update_namespace_template(template.into(), propagate)
+                .await?;
+            output::print(format, &response)?;
+        } // Deliberately not adding _ => so the compiler will direct people here to impl new
+          // commands
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn valid_update_with_propagate() {
+        let config = Config::try_parse_from([
+            "template",
+            "update",
+            "standard",
+            "--retention-hours",
+            "24",
+            "--max-tables",
+            "42",
+            "--propagate",
+        ])
+        .unwrap();
+
+        let Command::Update {
+            template,
+            propagate,
+        } = config.command
+        else {
+            panic!("unexpected command {:?}", config.command);
+        };
+        assert!(propagate);
+
+        let template = NamespaceTemplate::from(template);
+        assert_eq!(template.name, "standard");
+        assert_eq!(
+            template.retention_period_ns,
+            Some(24 * 60 * 60 * 1_000_000_000)
+        );
+        assert_eq!(
+            template
+                .service_protection_limits
+                .and_then(|l| l.max_tables),
+            Some(42)
+        );
+        assert_eq!(template.partition_template, None);
+    }
+}
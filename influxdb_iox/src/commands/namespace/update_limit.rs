@@ -1,7 +1,10 @@
 use influxdb_iox_client::connection::Connection;
 use influxdb_iox_client::namespace::generated_types::LimitUpdate;
 
-use crate::commands::namespace::Result;
+use crate::commands::{
+    namespace::Result,
+    output::{self, OutputFormat},
+};
 
 #[derive(Debug, clap::Parser)]
 pub struct Config {
@@ -52,7 +55,7 @@ impl From<Args> for LimitUpdate {
     }
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<()> {
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
     let mut client = influxdb_iox_client::namespace::Client::new(connection);
 
     let namespace = client
@@ -61,11 +64,14 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
             LimitUpdate::from(config.args),
         )
         .await?;
-    println!("{}", serde_json::to_string_pretty(&namespace)?);
+    output::print(format, &namespace)?;
 
-    println!(
-        r"
-NOTE: This change will NOT take effect until all router instances have been restarted!"
-    );
+    // Keep stdout machine readable when JSON output is requested
+    let note = r"
+NOTE: This change will NOT take effect until all router instances have been restarted!";
+    match format {
+        OutputFormat::Pretty => println!("{note}"),
+        OutputFormat::Json => eprintln!("{note}"),
+    }
     Ok(())
 }
@@ -0,0 +1,169 @@
+//! This module implements the `operation` CLI command
+
+use std::{sync::Arc, time::Duration};
+
+use data_types::job::Job;
+use influxdb_iox_client::{
+    connection::Connection,
+    operations::{self, generated_types::operation::Result as OperationResult, IoxOperation},
+};
+use iox_time::SystemProvider;
+use observability_deps::tracing::info;
+use serde::Serialize;
+use thiserror::Error;
+use tracker::{ProgressReporter, TaskRegistration};
+
+use crate::commands::output::{self, OutputFormat};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Inspect and cancel the background jobs (compactions, persistence, ...)
+/// of a server
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// All possible subcommands for operation
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// List running and recently completed operations
+    List,
+
+    /// Get the state of an operation
+    Get {
+        /// The id of the operation
+        id: usize,
+    },
+
+    /// Cancel a running operation
+    Cancel {
+        /// The id of the operation
+        id: usize,
+    },
+
+    /// Wait for an operation to complete
+    Wait {
+        /// The id of the operation
+        id: usize,
+
+        /// Stop waiting after this long, even if the operation has not
+        /// completed
+        #[clap(long, value_parser = humantime::parse_duration, action)]
+        timeout: Option<Duration>,
+    },
+}
+
+/// Operations are listed as a table unless another format is requested
+const DEFAULT_FORMAT: OutputFormat = OutputFormat::Pretty;
+
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: Option<OutputFormat>,
+) -> Result<()> {
+    let format = format.unwrap_or(DEFAULT_FORMAT);
+    let mut client = operations::Client::new(connection);
+
+    match config.command {
+        Command::List => {
+            let operations = client.list_operations().await?;
+            let summaries: Vec<_> = operations.iter().map(Summary::from).collect();
+            output::print(format, &summaries)?;
+        }
+        Command::Get { id } => {
+            let operation = client.get_operation(id).await?;
+            output::print(format, &Summary::from(&operation))?;
+        }
+        Command::Cancel { id } => {
+            client.cancel_operation(id).await?;
+            eprintln!("Cancellation of operation {id} requested");
+        }
+        Command::Wait { id, timeout } => {
+            let operation = client.wait_operation(id, timeout).await?;
+            output::print(format, &Summary::from(&operation))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A flattened view of an operation and its metadata
+#[derive(Debug, Serialize)]
+pub(crate) struct Summary {
+    id: String,
+    job: String,
+    status: String,
+    progress: String,
+    items_done: u64,
+    items_total: Option<u64>,
+    cpu_nanos: u64,
+    wall_nanos: u64,
+}
+
+impl From<&IoxOperation> for Summary {
+    fn from(op: &IoxOperation) -> Self {
+        let metadata = &op.metadata;
+
+        let job = match metadata.job.clone().map(Job::try_from) {
+            Some(Ok(job)) => job.to_string(),
+            Some(Err(_)) | None => "unknown".to_string(),
+        };
+
+        let status = match &op.operation.result {
+            None => "running".to_string(),
+            Some(OperationResult::Response(_)) => "success".to_string(),
+            Some(OperationResult::Error(status)) => status.message.clone(),
+        };
+
+        let progress = match metadata.items_total {
+            Some(total) if total > 0 => format!(
+                "{:.0}%",
+                metadata.items_done.min(total) as f64 * 100.0 / total as f64
+            ),
+            _ => "-".to_string(),
+        };
+
+        Self {
+            id: op.operation.name.clone(),
+            job,
+            status,
+            progress,
+            items_done: metadata.items_done,
+            items_total: metadata.items_total,
+            cpu_nanos: metadata.cpu_nanos,
+            wall_nanos: metadata.wall_nanos,
+        }
+    }
+}
+
+/// Track `job`, run by this process rather than by a server, returning the
+/// handle it reports its progress to.
+///
+/// The job is cancelled on Ctrl-C, and stops at the next point it checks
+/// [`ProgressReporter::is_cancelled`].
+pub(crate) fn track_local_job(job: Job) -> ProgressReporter {
+    let registration = TaskRegistration::new(Arc::new(SystemProvider::new()));
+    let progress = registration.progress_reporter();
+    let tracker = registration.into_tracker(job);
+
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            info!(job = %tracker.metadata(), "cancelling job");
+            tracker.cancel();
+        }
+    });
+
+    progress
+}
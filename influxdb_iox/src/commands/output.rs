@@ -0,0 +1,156 @@
+//! Output formatting shared by the management commands
+
+use comfy_table::{Cell, Table};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// The format management commands print their results in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum OutputFormat {
+    /// Human readable tables
+    Pretty,
+
+    /// Machine readable JSON, suitable for scripting
+    Json,
+}
+
+/// Print `value` in the requested `format`
+pub fn print<T: Serialize>(format: OutputFormat, value: &T) -> Result<(), serde_json::Error> {
+    println!("{}", render(format, value)?);
+    Ok(())
+}
+
+/// Render `value` in the requested `format`
+pub fn render<T: Serialize>(format: OutputFormat, value: &T) -> Result<String, serde_json::Error> {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(value),
+        OutputFormat::Pretty => Ok(json_table(&serde_json::to_value(value)?)),
+    }
+}
+
+/// Render a JSON value as a table.
+///
+/// A list of objects is rendered with one row per object and one column
+/// per field, a single object with one row per field, and anything else
+/// as-is. Nested values are rendered as compact JSON.
+fn json_table(value: &Value) -> String {
+    let rows: Vec<&Map<String, Value>> = match value {
+        Value::Array(values) => match values
+            .iter()
+            .map(Value::as_object)
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(rows) => rows,
+            None => return value.to_string(),
+        },
+        Value::Object(fields) => {
+            let mut table = new_table(["field", "value"]);
+            for (k, v) in fields {
+                table.add_row([Cell::new(k), Cell::new(cell_value(v))]);
+            }
+            return table.to_string();
+        }
+        _ => return cell_value(value),
+    };
+
+    // Not all rows necessarily have the same fields (default values are
+    // omitted when serialising protobuf messages) so use the union of all
+    // of them, in order of first appearance.
+    let mut columns: Vec<&str> = vec![];
+    for row in &rows {
+        for k in row.keys() {
+            if !columns.contains(&k.as_str()) {
+                columns.push(k);
+            }
+        }
+    }
+
+    let mut table = new_table(columns.iter().copied());
+    for row in rows {
+        table.add_row(
+            columns
+                .iter()
+                .map(|c| Cell::new(row.get(*c).map(cell_value).unwrap_or_default())),
+        );
+    }
+    table.to_string()
+}
+
+fn cell_value(v: &Value) -> String {
+    match v {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+fn new_table<'a>(headers: impl IntoIterator<Item = &'a str>) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+    table.set_header(headers.into_iter().map(Cell::new));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json() {
+        let value = json!([{"id": 1, "name": "foo"}]);
+        assert_eq!(
+            render(OutputFormat::Json, &value).unwrap(),
+            serde_json::to_string_pretty(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pretty_list() {
+        let value = json!([
+            {"id": 1, "name": "foo"},
+            {"id": 2, "retention": {"hours": 3}},
+        ]);
+        let rendered = render(OutputFormat::Pretty, &value).unwrap();
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "+----+------+-------------+",
+                "| id | name | retention   |",
+                "+----+------+-------------+",
+                "| 1  | foo  |             |",
+                "| 2  |      | {\"hours\":3} |",
+                "+----+------+-------------+",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pretty_object() {
+        let value = json!({"id": 1, "name": "foo"});
+        let rendered = render(OutputFormat::Pretty, &value).unwrap();
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "+-------+-------+",
+                "| field | value |",
+                "+-------+-------+",
+                "| id    | 1     |",
+                "| name  | foo   |",
+                "+-------+-------+",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pretty_scalar() {
+        assert_eq!(render(OutputFormat::Pretty, &"foo").unwrap(), "foo");
+        assert_eq!(
+            render(OutputFormat::Pretty, &json!([1, 2])).unwrap(),
+            "[1,2]"
+        );
+    }
+}
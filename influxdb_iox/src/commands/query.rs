@@ -47,6 +47,25 @@ pub struct Config {
     /// Query type used
     #[clap(short = 'l', long = "lang", default_value = "sql")]
     query_lang: QueryLanguage,
+
+    /// Run the query against the catalog state at this point in time (RFC3339), i.e. only read
+    /// the parquet files that existed then. Unpersisted data is not included.
+    #[clap(long = "as-of", action)]
+    as_of: Option<String>,
+
+    /// Only read a random sample of this fraction (between 0 and 1) of the rows, returning
+    /// approximate results faster. Deduplication happens before sampling.
+    #[clap(long = "sample", action)]
+    sample: Option<f64>,
+
+    /// Only read the last (and, if enabled, first) value of every field of every series held in
+    /// the last value caches of the ingesters, instead of the stored data.
+    #[clap(long = "last-values", action)]
+    last_values: bool,
+
+    /// Precision of the returned timestamps: `s`, `ms`, `us` or `ns` (the default).
+    #[clap(long = "precision", action)]
+    precision: Option<String>,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -82,7 +101,15 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         format,
         query,
         query_lang,
+        as_of,
+        sample,
+        last_values,
+        precision,
     } = config;
+    client.set_as_of(as_of);
+    client.set_sample_fraction(sample);
+    client.set_last_value_cache(last_values);
+    client.set_timestamp_precision(precision);
 
     let mut query_results = match query_lang {
         QueryLanguage::Sql => client.sql(namespace, query).await,
@@ -114,5 +141,9 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         }
     }
 
+    if let Some(sample) = sample {
+        eprintln!("Results are approximate, only {sample} of the rows were sampled");
+    }
+
     Ok(())
 }
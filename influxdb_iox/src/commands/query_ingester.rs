@@ -47,6 +47,11 @@ pub struct Config {
     #[clap(long = "predicate-base64", action)]
     predicate_base64: Option<String>,
 
+    /// Return the values held in the last value cache of the ingester instead
+    /// of the buffered data (the predicate is ignored)
+    #[clap(long = "last-values", action)]
+    last_values: bool,
+
     /// Optional format ('pretty', 'json', or 'csv')
     #[clap(short, long, default_value = "pretty", action)]
     format: String,
@@ -60,6 +65,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         table_id,
         columns,
         predicate_base64,
+        last_values,
     } = config;
 
     let format = QueryOutputFormat::from_str(&format)?;
@@ -75,6 +81,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         columns,
         predicate,
         namespace_id,
+        last_values,
     };
 
     // send the message directly encoded as bytes to the ingester.
@@ -3,6 +3,8 @@
 use influxdb_iox_client::connection::Connection;
 use thiserror::Error;
 
+use crate::commands::output::OutputFormat;
+
 mod partition;
 mod store;
 
@@ -38,10 +40,14 @@ enum Command {
     Store(store::Config),
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: Option<OutputFormat>,
+) -> Result<(), Error> {
     match config.command {
         Command::Partition(config) => {
-            partition::command(connection, config).await?;
+            partition::command(connection, config, format).await?;
         }
         Command::Store(config) => {
             store::command(connection, config).await?;
@@ -1,11 +1,28 @@
 //! This module implements the `remote partition` CLI subcommand
 
+use std::{path::PathBuf, sync::Arc};
+
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_object_store, ObjectStoreConfig},
+};
+use data_types::{job::Job, NamespaceName};
+use import_export::file::{ExportedContents, RemoteExporter, RemoteImporter};
 use influxdb_iox_client::{
     catalog::{self},
     connection::Connection,
 };
+use iox_catalog::interface::Catalog;
 use thiserror::Error;
 
+use crate::{
+    commands::{
+        operation::track_local_job,
+        output::{self, OutputFormat},
+    },
+    process_info::setup_metric_registry,
+};
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum Error {
@@ -26,6 +43,15 @@ pub enum Error {
 
     #[error("Object store error: {0}")]
     ObjectStore(#[from] object_store::Error),
+
+    #[error("Exporting: {0}")]
+    Export(#[from] import_export::file::ExportError),
+
+    #[error("Importing: {0}")]
+    Import(#[from] import_export::file::Error),
+
+    #[error("Invalid namespace name: {0}")]
+    NamespaceName(#[from] data_types::NamespaceNameError),
 }
 
 /// Manage IOx chunks
@@ -43,20 +69,94 @@ struct Show {
     id: i64,
 }
 
+/// Download the chunk (the parquet files) of a partition and its metadata
+/// into a local directory.
+///
+/// The SHA-256 checksum of each parquet file is verified and written next to
+/// it. If the export is interrupted, run it again with the same output
+/// directory to resume the download. See `import-chunk` to register the
+/// exported files with another catalog.
+#[derive(Debug, clap::Parser)]
+struct ExportChunk {
+    /// The id of the partition
+    #[clap(action)]
+    id: i64,
+
+    /// The output directory to use. If not specified, files will be placed in a directory named
+    /// `partition_<id>` in the current working directory.
+    #[clap(action, short)]
+    output_directory: Option<PathBuf>,
+}
+
+/// Import a chunk exported by `export-chunk` into a catalog and object store,
+/// verifying the checksums of its parquet files.
+#[derive(Debug, clap::Parser)]
+struct ImportChunk {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    object_store: ObjectStoreConfig,
+
+    /// The directory containing the output of `export-chunk`
+    #[clap(action)]
+    input_directory: PathBuf,
+
+    /// The namespace to import the chunk into. If not specified, the chunk is
+    /// imported into the namespace it was exported from.
+    #[clap(action, long)]
+    namespace: Option<String>,
+}
+
 /// All possible subcommands for partition
 #[derive(Debug, clap::Parser)]
 enum Command {
     Show(Show),
+
+    ExportChunk(ExportChunk),
+
+    ImportChunk(ImportChunk),
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: Option<OutputFormat>,
+) -> Result<(), Error> {
     match config.command {
         Command::Show(show) => {
             let mut client = catalog::Client::new(connection);
             let files = client.get_parquet_files_by_partition_id(show.id).await?;
-            println!("{}", serde_json::to_string_pretty(&files)?);
+            output::print(format.unwrap_or(OutputFormat::Json), &files)?;
 
             Ok(())
         }
+        Command::ExportChunk(ExportChunk {
+            id,
+            output_directory,
+        }) => {
+            let mut exporter = RemoteExporter::new(connection);
+            Ok(exporter.export_partition(output_directory, id).await?)
+        }
+        Command::ImportChunk(import) => {
+            let metrics = setup_metric_registry();
+            let catalog: Arc<dyn Catalog> = import.catalog_dsn.get_catalog("cli", metrics).await?;
+            let object_store = make_object_store(&import.object_store)?;
+
+            let namespace_name = import
+                .namespace
+                .clone()
+                .map(NamespaceName::try_from)
+                .transpose()?;
+
+            let exported_contents = ExportedContents::try_new(&import.input_directory)?;
+            let mut importer = RemoteImporter::new(exported_contents, catalog, object_store)
+                .with_progress(track_local_job(Job::ImportFiles { namespace_name }));
+            if let Some(namespace) = import.namespace {
+                importer = importer.with_namespace(namespace);
+            }
+
+            Ok(importer.import().await?)
+        }
     }
 }
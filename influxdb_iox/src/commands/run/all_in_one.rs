@@ -7,6 +7,7 @@ use clap_blocks::{
     catalog_dsn::CatalogDsnConfig,
     compactor::CompactorConfig,
     compactor_scheduler::CompactorSchedulerConfig,
+    flight::FlightConfig,
     gossip::GossipConfig,
     ingester::IngesterConfig,
     ingester_address::IngesterAddress,
@@ -15,6 +16,7 @@ use clap_blocks::{
     querier::QuerierConfig,
     router::RouterConfig,
     run_config::RunConfig,
+    server_limits::ServerLimitsConfig,
     single_tenant::{
         CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG, CONFIG_CST_ENV_NAME, CONFIG_CST_FLAG,
     },
@@ -202,6 +204,21 @@ pub struct Config {
     )]
     pub max_http_request_size: usize,
 
+    #[clap(flatten)]
+    server_limits: ServerLimitsConfig,
+
+    /// Serve the gRPC API to change the log filter at runtime.
+    ///
+    /// The API is not authorized, only enable it on servers not reachable by
+    /// untrusted clients.
+    #[clap(
+        long = "log-filter-api",
+        env = "INFLUXDB_IOX_LOG_FILTER_API",
+        default_value = "false",
+        action
+    )]
+    pub log_filter_api: bool,
+
     #[clap(flatten)]
     object_store_config: ObjectStoreConfig,
 
@@ -223,6 +240,17 @@ pub struct Config {
     )]
     pub wal_rotation_period_seconds: u64,
 
+    /// The size in bytes at which the open WAL segment file is rotated and
+    /// the buffered data persisted, before the rotation period elapses.
+    ///
+    /// This bounds the WAL data replayed after a crash when writes arrive
+    /// faster than the rotation period can absorb. Disabled by default.
+    #[clap(
+        long = "wal-max-segment-bytes",
+        env = "INFLUXDB_IOX_WAL_MAX_SEGMENT_BYTES"
+    )]
+    pub wal_max_segment_bytes: Option<u64>,
+
     /// Sets how many queries the ingester will handle simultaneously before
     /// rejecting further incoming requests.
     #[clap(
@@ -350,6 +378,9 @@ pub struct Config {
     )]
     pub querier_max_concurrent_queries: usize,
 
+    #[clap(flatten)]
+    querier_flight_config: FlightConfig,
+
     /// Size of memory pool used during query exec, in bytes.
     ///
     /// Can be given as absolute value or in percentage of the total available memory (e.g. `10%`).
@@ -371,10 +402,13 @@ impl Config {
             logging_config,
             tracing_config,
             max_http_request_size,
+            server_limits,
+            log_filter_api,
             object_store_config,
             wal_directory,
             mut catalog_dsn,
             wal_rotation_period_seconds,
+            wal_max_segment_bytes,
             concurrent_query_limit,
             persist_max_parallelism,
             persist_queue_depth,
@@ -388,6 +422,7 @@ impl Config {
             querier_ram_pool_metadata_bytes,
             querier_ram_pool_data_bytes,
             querier_max_concurrent_queries,
+            querier_flight_config,
             exec_mem_pool_bytes,
             single_tenant_deployment,
         } = self;
@@ -461,6 +496,8 @@ impl Config {
             router_http_bind_address,
             router_grpc_bind_address,
             max_http_request_size,
+            server_limits,
+            log_filter_api,
             object_store_config,
         );
 
@@ -479,6 +516,11 @@ impl Config {
         let ingester_config = IngesterConfig {
             wal_directory,
             wal_rotation_period_seconds,
+            wal_max_segment_bytes,
+            wal_replay_max_ops_per_second: None,
+            buffer_snapshot_directory: None,
+            buffer_snapshot_period_seconds: 60,
+            wal_encryption_key_file: None,
             concurrent_query_limit,
             persist_max_parallelism,
             persist_queue_depth,
@@ -486,11 +528,22 @@ impl Config {
             rpc_write_max_incoming_bytes: 1024 * 1024 * 1024, // 1GiB
             gossip_config: GossipConfig::disabled(),
             max_partitions_per_namespace: None,
+            namespace_buffer_soft_limit_bytes: None,
+            namespace_buffer_hard_limit_bytes: None,
+            buffer_hard_limit_bytes: None,
+            backpressure_response: Default::default(),
+            backpressure_max_delay_millis: 1_000,
+            persist_lag_max_unpersisted_writes: None,
+            persist_lag_max_age_seconds: None,
+            persist_notification_webhook_url: None,
+            last_value_cache_max_series: None,
+            last_value_cache_first_values: false,
         };
 
         let router_config = RouterConfig {
             authz_address: authz_address.clone(),
             single_tenant_deployment,
+            v1_write_org: None,
             http_request_limit: 1_000,
             ingester_addresses: ingester_addresses.clone(),
             new_namespace_retention_hours: None, // infinite retention
@@ -500,6 +553,8 @@ impl Config {
             rpc_write_max_outgoing_bytes: ingester_config.rpc_write_max_incoming_bytes,
             rpc_write_health_num_probes: 10,
             gossip_config: GossipConfig::disabled(),
+            tag_normalization_rules_file: None,
+            derived_tags_file: None,
         };
 
         // create a CompactorConfig for the all in one server based on
@@ -527,12 +582,22 @@ impl Config {
         };
 
         let querier_config = QuerierConfig {
+            flight_config: querier_flight_config,
             authz_address,
             num_query_threads: None, // will be ignored
             ingester_addresses,
             ram_pool_metadata_bytes: querier_ram_pool_metadata_bytes,
             ram_pool_data_bytes: querier_ram_pool_data_bytes,
+            cold_data_threshold: None,
+            max_concurrent_cold_reads: NonZeroUsize::new(10).unwrap(),
+            disk_cache_directory: None,
+            disk_cache_bytes: 0,
             max_concurrent_queries: querier_max_concurrent_queries,
+            max_series_per_query: None,
+            storage_max_result_points: None,
+            storage_max_result_bytes: None,
+            slow_query_threshold: None,
+            verify_parquet_checksums: false,
             exec_mem_pool_bytes,
             ingester_circuit_breaker_threshold: u64::MAX, // never for all-in-one-mode
             datafusion_config: Default::default(),
@@ -3,8 +3,8 @@
 use super::main;
 use crate::process_info::setup_metric_registry;
 use clap_blocks::{
-    catalog_dsn::CatalogDsnConfig, compactor::CompactorConfig, object_store::make_object_store,
-    run_config::RunConfig,
+    catalog_dsn::CatalogDsnConfig, compactor::CompactorConfig,
+    object_store::make_replicated_object_store, run_config::RunConfig,
 };
 use compactor::object_store::metrics::MetricsStore;
 use iox_query::exec::{Executor, ExecutorConfig};
@@ -76,8 +76,12 @@ pub async fn command(config: Config) -> Result<(), Error> {
         .get_catalog("compactor", Arc::clone(&metric_registry))
         .await?;
 
-    let object_store = make_object_store(config.run_config.object_store_config())
-        .map_err(Error::ObjectStoreParsing)?;
+    let object_store = make_replicated_object_store(
+        config.run_config.object_store_config(),
+        Arc::clone(&time_provider),
+        &metric_registry,
+    )
+    .map_err(Error::ObjectStoreParsing)?;
 
     // Decorate the object store with a metric recorder.
     let object_store: Arc<DynObjectStore> = Arc::new(ObjectStoreMetrics::new(
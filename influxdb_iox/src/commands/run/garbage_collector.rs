@@ -1,8 +1,8 @@
 use clap_blocks::{
     catalog_dsn::CatalogDsnConfig, garbage_collector::GarbageCollectorConfig,
-    object_store::make_object_store, run_config::RunConfig,
+    object_store::make_replicated_object_store, run_config::RunConfig,
 };
-use iox_time::SystemProvider;
+use iox_time::{SystemProvider, TimeProvider};
 use ioxd_common::{
     server_type::{CommonServerState, CommonServerStateError},
     Service,
@@ -31,7 +31,7 @@ pub struct Config {
 }
 
 pub async fn command(config: Config) -> Result<()> {
-    let time_provider = Arc::new(SystemProvider::new());
+    let time_provider = Arc::new(SystemProvider::new()) as Arc<dyn TimeProvider>;
     let metric_registry = setup_metric_registry();
 
     let catalog = config
@@ -39,7 +39,11 @@ pub async fn command(config: Config) -> Result<()> {
         .get_catalog("garbage-collector", Arc::clone(&metric_registry))
         .await?;
 
-    let object_store = make_object_store(config.run_config.object_store_config())?;
+    let object_store = make_replicated_object_store(
+        config.run_config.object_store_config(),
+        Arc::clone(&time_provider),
+        &metric_registry,
+    )?;
 
     // Decorate the object store with a metric recorder.
     let object_store: Arc<DynObjectStore> = Arc::new(ObjectStoreMetrics::new(
@@ -3,8 +3,8 @@
 use std::{num::NonZeroUsize, sync::Arc};
 
 use clap_blocks::{
-    catalog_dsn::CatalogDsnConfig, ingester::IngesterConfig, object_store::make_object_store,
-    run_config::RunConfig,
+    catalog_dsn::CatalogDsnConfig, ingester::IngesterConfig,
+    object_store::make_replicated_object_store, run_config::RunConfig,
 };
 use iox_query::exec::Executor;
 use iox_time::{SystemProvider, TimeProvider};
@@ -118,8 +118,12 @@ pub async fn command(config: Config) -> Result<()> {
         config.exec_mem_pool_bytes,
         Arc::clone(&metric_registry),
     ));
-    let object_store = make_object_store(config.run_config.object_store_config())
-        .map_err(Error::ObjectStoreParsing)?;
+    let object_store = make_replicated_object_store(
+        config.run_config.object_store_config(),
+        Arc::clone(&time_provider),
+        &metric_registry,
+    )
+    .map_err(Error::ObjectStoreParsing)?;
 
     // Decorate the object store with a metric recorder.
     let object_store: Arc<DynObjectStore> = Arc::new(ObjectStoreMetrics::new(
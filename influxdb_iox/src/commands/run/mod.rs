@@ -7,6 +7,7 @@ mod garbage_collector;
 mod ingester;
 mod main;
 mod querier;
+mod query_router;
 mod router;
 mod test;
 
@@ -22,6 +23,9 @@ pub enum Error {
     #[snafu(display("Error in querier subcommand: {}", source))]
     QuerierError { source: querier::Error },
 
+    #[snafu(display("Error in query router subcommand: {}", source))]
+    QueryRouterError { source: query_router::Error },
+
     #[snafu(display("Error in router subcommand: {}", source))]
     RouterError { source: router::Error },
 
@@ -54,6 +58,7 @@ impl Config {
             Some(Command::Compactor(config)) => config.run_config.logging_config(),
             Some(Command::GarbageCollector(config)) => config.run_config.logging_config(),
             Some(Command::Querier(config)) => config.run_config.logging_config(),
+            Some(Command::QueryRouter(config)) => config.run_config.logging_config(),
             Some(Command::Router(config)) => config.run_config.logging_config(),
             Some(Command::Ingester(config)) => config.run_config.logging_config(),
             Some(Command::AllInOne(config)) => &config.logging_config,
@@ -71,6 +76,9 @@ enum Command {
     /// Run the server in querier mode
     Querier(querier::Config),
 
+    /// Run the server in query router mode
+    QueryRouter(query_router::Config),
+
     /// Run the server in router mode
     #[clap(alias = "router2")]
     Router(router::Config),
@@ -101,6 +109,9 @@ pub async fn command(config: Config) -> Result<()> {
             .await
             .context(GarbageCollectorSnafu),
         Some(Command::Querier(config)) => querier::command(config).await.context(QuerierSnafu),
+        Some(Command::QueryRouter(config)) => query_router::command(config)
+            .await
+            .context(QueryRouterSnafu),
         Some(Command::Router(config)) => router::command(config).await.context(RouterSnafu),
         Some(Command::Ingester(config)) => ingester::command(config).await.context(IngesterSnafu),
         Some(Command::AllInOne(config)) => all_in_one::command(config).await.context(AllInOneSnafu),
@@ -4,7 +4,9 @@ use crate::process_info::setup_metric_registry;
 
 use super::main;
 use clap_blocks::{
-    catalog_dsn::CatalogDsnConfig, object_store::make_object_store, querier::QuerierConfig,
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_object_store, maybe_hedge_reads},
+    querier::QuerierConfig,
     run_config::RunConfig,
 };
 use iox_query::exec::Executor;
@@ -83,6 +85,11 @@ pub async fn command(config: Config) -> Result<(), Error> {
 
     let object_store = make_object_store(config.run_config.object_store_config())
         .map_err(Error::ObjectStoreParsing)?;
+    let object_store = maybe_hedge_reads(
+        config.run_config.object_store_config(),
+        object_store,
+        &metric_registry,
+    );
     // Decorate the object store with a metric recorder.
     let object_store: Arc<DynObjectStore> = Arc::new(ObjectStoreMetrics::new(
         object_store,
@@ -0,0 +1,85 @@
+//! Implementation of command line option for running the query router
+
+use crate::process_info::setup_metric_registry;
+
+use super::main;
+use clap_blocks::{query_router::QueryRouterConfig, run_config::RunConfig};
+use iox_query::exec::Executor;
+use ioxd_common::{
+    server_type::{CommonServerState, CommonServerStateError},
+    Service,
+};
+use ioxd_query_router::{create_query_router_server_type, QueryRouterServerTypeArgs};
+use observability_deps::tracing::*;
+use std::{num::NonZeroUsize, sync::Arc};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Run: {0}")]
+    Run(#[from] main::Error),
+
+    #[error("Invalid config: {0}")]
+    InvalidConfigCommon(#[from] CommonServerStateError),
+
+    #[error("Query router error: {0}")]
+    QueryRouter(#[from] ioxd_query_router::Error),
+}
+
+#[derive(Debug, clap::Parser)]
+#[clap(
+    name = "run",
+    about = "Runs in query router mode",
+    long_about = "Run the IOx query router server.\n\nThe query router answers queries by \
+    sending them to multiple IOx servers (e.g. shards or hot/cold tiers) and merging the \
+    results.\n\nThe configuration options below can be \
+    set either with the command line flags or with the specified environment \
+    variable. If there is a file named '.env' in the current working directory, \
+    it is sourced before loading the configuration.
+
+Configuration is loaded from the following sources (highest precedence first):
+        - command line arguments
+        - user set environment variables
+        - .env file contents
+        - pre-configured default values"
+)]
+pub struct Config {
+    #[clap(flatten)]
+    pub(crate) run_config: RunConfig,
+
+    #[clap(flatten)]
+    pub(crate) query_router_config: QueryRouterConfig,
+}
+
+pub async fn command(config: Config) -> Result<(), Error> {
+    let common_state = CommonServerState::from_config(config.run_config.clone())?;
+    let metric_registry = setup_metric_registry();
+
+    let num_query_threads = config.query_router_config.num_query_threads;
+    let num_threads = num_query_threads.unwrap_or_else(|| {
+        NonZeroUsize::new(num_cpus::get()).unwrap_or_else(|| NonZeroUsize::new(1).unwrap())
+    });
+    info!(%num_threads, "using specified number of threads per thread pool");
+
+    let backends = &config.query_router_config.backends;
+    info!(?backends, "using backends");
+
+    let exec = Arc::new(Executor::new(
+        num_threads,
+        config.query_router_config.exec_mem_pool_bytes.bytes(),
+        Arc::clone(&metric_registry),
+    ));
+
+    let server_type = create_query_router_server_type(QueryRouterServerTypeArgs {
+        common_state: &common_state,
+        metric_registry: Arc::clone(&metric_registry),
+        exec,
+        query_router_config: config.query_router_config,
+    })
+    .await?;
+
+    info!("starting query router");
+
+    let services = vec![Service::create(server_type, common_state.run_config())];
+    Ok(main::main(common_state, services, metric_registry).await?)
+}
@@ -10,19 +10,25 @@ mod repl_command;
 
 /// Start IOx interactive SQL REPL loop
 ///
-/// Supports command history and interactive editing. History is
-/// stored in $HOME/.iox_sql_history.
+/// Supports multi-line editing, command history and `\d`-style
+/// metadata commands. History is stored in $HOME/.iox_sql_history.
 #[derive(Debug, clap::Parser)]
 pub struct Config {
-    // TODO add an option to avoid saving history
-
-    // TODO add an option to specify the default database (rather than having to set it via USE DATABASE)
     /// Format to use for output. Can be overridden using
     /// `SET FORMAT` command
     ///
     /// Optional format ('pretty', 'json', or 'csv')
     #[clap(short, long, default_value = "pretty", action)]
     format: String,
+
+    /// Namespace to query on startup. Can be changed using the
+    /// `USE NAMESPACE` command
+    #[clap(long, action)]
+    namespace: Option<String>,
+
+    /// Do not save the command history of this session
+    #[clap(long, action)]
+    no_history: bool,
 }
 
 #[derive(Debug, Snafu)]
@@ -51,10 +57,14 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
 
     println!("Connected to IOx Server");
 
-    let mut repl = repl::Repl::new(connection).context(ReplSnafu)?;
+    let mut repl = repl::Repl::new(connection, !config.no_history).context(ReplSnafu)?;
 
     repl.set_output_format(config.format).context(ReplSnafu)?;
 
+    if let Some(namespace) = config.namespace {
+        repl.use_namespace(namespace);
+    }
+
     repl.run().await.context(ReplSnafu)
 }
 
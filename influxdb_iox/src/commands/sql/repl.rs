@@ -1,4 +1,12 @@
-use std::{borrow::Cow, convert::TryInto, path::PathBuf, sync::Arc, time::Instant};
+use std::{
+    borrow::Cow,
+    convert::TryInto,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::Arc,
+    time::Instant,
+};
 
 use arrow::{
     array::{ArrayRef, Int64Array, StringArray},
@@ -44,6 +52,12 @@ pub enum Error {
 
     #[snafu(display("Cannot create REPL: {}", source))]
     ReplCreation { source: ReadlineError },
+
+    #[snafu(display("Error running pager '{}': {}", pager, source))]
+    RunningPager {
+        pager: String,
+        source: std::io::Error,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -76,7 +90,7 @@ impl rustyline::validate::Validator for RustylineHelper {
     ) -> rustyline::Result<rustyline::validate::ValidationResult> {
         let input = ctx.input();
 
-        if input.trim_end().ends_with(';') {
+        if ReplCommand::is_meta_command(input) || input.trim_end().ends_with(';') {
             match ReplCommand::try_from(input) {
                 Ok(_) => Ok(rustyline::validate::ValidationResult::Valid(None)),
                 Err(err) => Ok(rustyline::validate::ValidationResult::Invalid(Some(err))),
@@ -172,6 +186,15 @@ pub struct Repl {
 
     /// Formatter to use to format query results
     output_format: QueryOutputFormat,
+
+    /// Whether to print the elapsed time after each query
+    timing: bool,
+
+    /// Whether to send query output through the pager
+    paging: bool,
+
+    /// Whether to save the command history on exit
+    save_history: bool,
 }
 
 impl Repl {
@@ -180,7 +203,10 @@ impl Repl {
     }
 
     /// Create a new Repl instance, connected to the specified URL
-    pub fn new(connection: Connection) -> Result<Self> {
+    ///
+    /// If `save_history` is false, previously saved history is still
+    /// loaded but the history of this session is not written back.
+    pub fn new(connection: Connection, save_history: bool) -> Result<Self> {
         let namespace_client = influxdb_iox_client::namespace::Client::new(connection.clone());
         let flight_client = influxdb_iox_client::flight::Client::new(connection);
 
@@ -203,6 +229,9 @@ impl Repl {
             flight_client,
             query_engine: None,
             output_format,
+            timing: true,
+            paging: false,
+            save_history,
         })
     }
 
@@ -222,6 +251,26 @@ impl Repl {
                         .map_err(|e| println!("{e}"))
                         .ok();
                 }
+                ReplCommand::ShowTables => {
+                    self.run_sql(SHOW_TABLES_SQL.to_string())
+                        .await
+                        .map_err(|e| println!("{e}"))
+                        .ok();
+                }
+                ReplCommand::DescribeTable { table_name } => {
+                    self.run_sql(describe_table_sql(&table_name))
+                        .await
+                        .map_err(|e| println!("{e}"))
+                        .ok();
+                }
+                ReplCommand::ToggleTiming => {
+                    self.timing = !self.timing;
+                    println!("Timing is {}", on_off(self.timing));
+                }
+                ReplCommand::TogglePager => {
+                    self.paging = !self.paging;
+                    println!("Pager is {} (using '{}')", on_off(self.paging), pager());
+                }
                 ReplCommand::UseNamespace { db_name } => {
                     self.use_namespace(db_name);
                 }
@@ -313,11 +362,15 @@ impl Repl {
         let end = Instant::now();
         self.print_results(&batches)?;
 
-        println!(
-            "Returned {} in {:?}",
-            Self::row_summary(&batches),
-            end - start
-        );
+        if self.timing {
+            println!(
+                "Returned {} in {:?}",
+                Self::row_summary(&batches),
+                end - start
+            );
+        } else {
+            println!("Returned {}", Self::row_summary(&batches));
+        }
         Ok(())
     }
 
@@ -333,7 +386,8 @@ impl Repl {
         }
     }
 
-    fn use_namespace(&mut self, db_name: String) {
+    /// Selects the namespace to run queries against
+    pub fn use_namespace(&mut self, db_name: String) {
         info!(%db_name, "setting current namespace");
         println!("You are now in remote mode, querying namespace {db_name}");
         self.set_query_engine(QueryEngine::Remote(db_name));
@@ -359,19 +413,29 @@ impl Repl {
         Ok(())
     }
 
-    /// Prints to the specified output format
+    /// Prints to the specified output format, through the pager if
+    /// paging is enabled
     fn print_results(&self, batches: &[RecordBatch]) -> Result<()> {
         let formatted_results = self
             .output_format
             .format(batches)
             .context(FormattingResultsSnafu)?;
-        println!("{formatted_results}");
-        Ok(())
+
+        if self.paging {
+            page_output(&formatted_results)
+        } else {
+            println!("{formatted_results}");
+            Ok(())
+        }
     }
 }
 
 impl Drop for Repl {
     fn drop(&mut self) {
+        if !self.save_history {
+            return;
+        }
+
         let history_file = history_file();
 
         if let Err(e) = self.rl.save_history(&history_file) {
@@ -380,6 +444,65 @@ impl Drop for Repl {
     }
 }
 
+/// Lists the user tables of the current namespace
+const SHOW_TABLES_SQL: &str = "SELECT table_name, table_type \
+    FROM information_schema.tables \
+    WHERE table_schema = 'iox' \
+    ORDER BY table_name";
+
+/// Describes the columns of `table_name` in the current namespace
+fn describe_table_sql(table_name: &str) -> String {
+    // escape embedded quotes so the name is always treated as a literal
+    let table_name = table_name.replace('\'', "''");
+    format!(
+        "SELECT column_name, data_type, is_nullable \
+         FROM information_schema.columns \
+         WHERE table_schema = 'iox' AND table_name = '{table_name}' \
+         ORDER BY ordinal_position"
+    )
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Return the pager command (defaults to `less -FRX` if $PAGER is not set)
+fn pager() -> String {
+    std::env::var("PAGER").unwrap_or_else(|_| "less -FRX".to_string())
+}
+
+/// Writes `output` to the stdin of the pager and waits for the user to
+/// quit it
+fn page_output(output: &str) -> Result<()> {
+    let pager = pager();
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else {
+        println!("{output}");
+        return Ok(());
+    };
+
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+        .context(RunningPagerSnafu { pager: &pager })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // the pager may exit before reading all its input (e.g. the user
+        // quit early), which is not an error
+        if let Err(e) = writeln!(stdin, "{output}") {
+            debug!(%e, "error writing to pager");
+        }
+    }
+
+    child.wait().context(RunningPagerSnafu { pager: &pager })?;
+    Ok(())
+}
+
 fn is_exit_command(line: &str) -> bool {
     let line = line.trim_end().to_lowercase();
     line == "quit" || line == "exit"
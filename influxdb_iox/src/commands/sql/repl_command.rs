@@ -5,6 +5,10 @@ use observability_deps::tracing::{debug, warn};
 pub enum ReplCommand {
     Help,
     ShowNamespaces,
+    ShowTables,
+    DescribeTable { table_name: String },
+    ToggleTiming,
+    TogglePager,
     SetFormat { format: String },
     UseNamespace { db_name: String },
     SqlCommand { sql: String },
@@ -30,6 +34,10 @@ impl TryFrom<&str> for ReplCommand {
             return Err("No command specified".to_string());
         }
 
+        if let Some(meta_command) = input.trim().strip_prefix('\\') {
+            return Self::try_from_meta_command(meta_command);
+        }
+
         // tokenized commands, normalized whitespace but original case
         let raw_commands = input
             .trim()
@@ -90,6 +98,42 @@ impl TryFrom<&str> for ReplCommand {
 }
 
 impl ReplCommand {
+    /// Parses a psql-style backslash command such as `\d my_table`
+    /// (without the leading backslash). These do not require a
+    /// trailing semicolon.
+    fn try_from_meta_command(input: &str) -> Result<Self, String> {
+        let tokens = input
+            .strip_suffix(';')
+            .unwrap_or(input)
+            .split_whitespace()
+            .collect::<Vec<_>>();
+
+        match tokens.as_slice() {
+            ["?"] => Ok(Self::Help),
+            ["q"] => Ok(Self::Exit),
+            ["l"] => Ok(Self::ShowNamespaces),
+            ["d"] | ["dt"] => Ok(Self::ShowTables),
+            ["d", table_name] => Ok(Self::DescribeTable {
+                table_name: table_name.to_string(),
+            }),
+            ["c", db_name] => Ok(Self::UseNamespace {
+                db_name: db_name.to_string(),
+            }),
+            ["timing"] => Ok(Self::ToggleTiming),
+            ["pager"] => Ok(Self::TogglePager),
+            _ => Err(format!(
+                "Unknown command '\\{}'. Hint: try '\\?' for help",
+                input.trim()
+            )),
+        }
+    }
+
+    /// Returns true if this is a backslash command, which is complete
+    /// without a trailing semicolon
+    pub fn is_meta_command(input: &str) -> bool {
+        input.trim_start().starts_with('\\')
+    }
+
     /// Information for each command
     pub fn help() -> &'static str {
         r#"
@@ -104,6 +148,16 @@ SET FORMAT <format>: Set the output format to Pretty, csv or json
 
 [EXIT | QUIT]: Quit this session and exit the program
 
+Metadata commands (no trailing semicolon required):
+\l            List namespaces (same as SHOW NAMESPACES)
+\c <name>     Set the current remote namespace (same as USE NAMESPACE)
+\d            List tables in the current namespace
+\d <table>    Describe the columns of a table
+\timing       Toggle display of query timing
+\pager        Toggle paging of query output through $PAGER
+\?            Show this help
+\q            Quit
+
 # Examples: use remote namespace foo
 SHOW NAMESPACES;
 USE foo;
@@ -228,6 +282,37 @@ mod tests {
         assert_eq!("blah".try_into(), expected);
     }
 
+    #[test]
+    fn meta_commands() {
+        assert_eq!(r"\l".try_into(), Ok(ReplCommand::ShowNamespaces));
+        assert_eq!(r"  \l  ".try_into(), Ok(ReplCommand::ShowNamespaces));
+        assert_eq!(r"\d".try_into(), Ok(ReplCommand::ShowTables));
+        assert_eq!(r"\dt;".try_into(), Ok(ReplCommand::ShowTables));
+        assert_eq!(
+            r"\d MyTable".try_into(),
+            Ok(ReplCommand::DescribeTable {
+                table_name: "MyTable".to_string()
+            })
+        );
+        assert_eq!(
+            r"\c foo".try_into(),
+            Ok(ReplCommand::UseNamespace {
+                db_name: "foo".to_string()
+            })
+        );
+        assert_eq!(r"\timing".try_into(), Ok(ReplCommand::ToggleTiming));
+        assert_eq!(r"\pager".try_into(), Ok(ReplCommand::TogglePager));
+        assert_eq!(r"\?".try_into(), Ok(ReplCommand::Help));
+        assert_eq!(r"\q".try_into(), Ok(ReplCommand::Exit));
+
+        let expected: Result<ReplCommand, String> =
+            Err(r"Unknown command '\x'. Hint: try '\?' for help".to_string());
+        assert_eq!(r"\x".try_into(), expected);
+
+        assert!(ReplCommand::is_meta_command(r"  \d"));
+        assert!(!ReplCommand::is_meta_command("select 1"));
+    }
+
     #[test]
     fn exit() {
         let expected = Ok(ReplCommand::Exit);
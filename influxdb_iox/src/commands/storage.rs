@@ -1,12 +1,18 @@
+pub(crate) mod load;
 pub(crate) mod request;
 pub(crate) mod response;
 
 use crate::commands::storage::response::{BinaryTagSchema, TextTagSchema};
+use futures::Future;
 use generated_types::{
-    aggregate::AggregateType, influxdata::platform::storage::read_group_request::Group, Predicate,
+    aggregate::AggregateType,
+    influxdata::platform::storage::{
+        read_group_request::Group, read_window_aggregate_request::Fill,
+    },
+    AggregateExpression, Predicate,
 };
 use influxdb_storage_client::{connection::Connection, Client, OrgAndBucket};
-use influxrpc_parser::predicate;
+use influxrpc_parser::{aggregate_expr, predicate};
 use iox_time;
 use observability_deps::tracing::info;
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
@@ -42,11 +48,29 @@ pub enum ParseError {
     #[snafu(display("unsupported aggregate type: '{:?}'", agg))]
     Aggregate { agg: String },
 
+    #[snafu(display(
+        "unable to parse aggregate expression '{}': expected 'name=expression'",
+        expr
+    ))]
+    AggregateExpressionName { expr: String },
+
+    #[snafu(display("unable to parse aggregate expression: {:?}", source))]
+    AggregateExpression { source: aggregate_expr::Error },
+
     #[snafu(display(
         "unsupported group. Expected '0', 'none', '2', or 'by': got '{:?}'",
         group
     ))]
     Group { group: String },
+
+    #[snafu(display(
+        "unsupported fill. Expected 'none', 'previous' or 'linear': got '{:?}'",
+        fill
+    ))]
+    Fill { fill: String },
+
+    #[snafu(display("load test failed: {}", source))]
+    Load { source: load::Error },
 }
 
 pub type Result<T, E = ParseError> = std::result::Result<T, E>;
@@ -123,6 +147,16 @@ fn parse_predicate(expr: &str) -> Result<Predicate, ParseError> {
     predicate::expr_to_rpc_predicate(expr).context(PredicateSnafu)
 }
 
+// Attempts to parse a `name=expression` aggregate expression.
+fn parse_aggregate_expression(s: &str) -> Result<AggregateExpression, ParseError> {
+    let (name, expr) = s
+        .split_once('=')
+        .context(AggregateExpressionNameSnafu { expr: s })?;
+
+    aggregate_expr::expr_to_rpc_aggregate_expression(name.trim(), expr)
+        .context(AggregateExpressionSnafu)
+}
+
 // Attempts to parse the namespace name into and org and bucket ID.
 fn parse_namespace_name(db_name: &str) -> Result<OrgAndBucket, ParseError> {
     let parts = db_name.split('_').collect::<Vec<_>>();
@@ -170,6 +204,7 @@ enum Command {
     ReadGroup(ReadGroup),
     ReadWindowAggregate(ReadWindowAggregate),
     TagValues(TagValues),
+    Load(load::Load),
 }
 
 #[derive(Debug, clap::Parser)]
@@ -221,6 +256,22 @@ struct ReadWindowAggregate {
         value_parser = parse_aggregate,
     )]
     aggregate: Vec<AggregateType>,
+
+    /// Arithmetic over the aggregates of fields to calculate for each window,
+    /// as `name=expression` (e.g. `ratio=sum(bytes)/sum(requests)`). Replaces
+    /// `--aggregate`.
+    #[clap(
+        long = "expression",
+        value_parser = parse_aggregate_expression,
+    )]
+    expressions: Vec<AggregateExpression>,
+
+    #[clap(
+        long,
+        value_parser = parse_fill,
+        default_value = "none",
+    )]
+    fill: Fill,
 }
 
 // Attempts to parse the optional format.
@@ -234,10 +285,25 @@ fn parse_aggregate(aggs: &str) -> Result<AggregateType, ParseError> {
         "mean" => Ok(AggregateType::Mean),
         "first" => Ok(AggregateType::First),
         "last" => Ok(AggregateType::Last),
+        "rate" => Ok(AggregateType::Rate),
+        "derivative" => Ok(AggregateType::Derivative),
+        "non_negative_derivative" => Ok(AggregateType::NonNegativeDerivative),
+        "increase" => Ok(AggregateType::Increase),
+        "any" => Ok(AggregateType::Any),
+        "all" => Ok(AggregateType::All),
         _ => AggregateSnafu { agg: aggs }.fail(),
     }
 }
 
+fn parse_fill(f: &str) -> Result<Fill, ParseError> {
+    match f.to_lowercase().as_str() {
+        "none" => Ok(Fill::None),
+        "previous" => Ok(Fill::Previous),
+        "linear" => Ok(Fill::Linear),
+        _ => FillSnafu { fill: f }.fail(),
+    }
+}
+
 fn parse_group(g: &str) -> Result<Group, ParseError> {
     match g.to_lowercase().as_str() {
         "0" | "none" => Ok(Group::None),
@@ -254,12 +320,37 @@ struct TagValues {
 }
 
 /// Create and issue read request
-pub async fn command(connection: Connection, config: Config) -> Result<()> {
-    let mut client = influxdb_storage_client::Client::new(connection);
-
+///
+/// `write_connection` is only called by the `load` command, which also writes
+/// to the server.
+pub async fn command<C, CFut>(
+    connection: Connection,
+    write_connection: C,
+    config: Config,
+) -> Result<()>
+where
+    C: Send + FnOnce() -> CFut,
+    CFut: Send + Future<Output = Connection>,
+{
     // convert predicate with no root node into None.
     let predicate = config.predicate.root.is_some().then_some(config.predicate);
 
+    if let Command::Load(load) = config.command {
+        return load::load(
+            connection,
+            write_connection,
+            &config.db_name,
+            config.start,
+            config.stop,
+            predicate,
+            load,
+        )
+        .await
+        .context(LoadSnafu);
+    }
+
+    let mut client = influxdb_storage_client::Client::new(connection);
+
     let source = Client::read_source(&config.db_name, 0);
     let now = std::time::Instant::now();
     match config.command {
@@ -354,7 +445,9 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
                 window_every,
                 offset,
                 rwa.aggregate,
+                rwa.expressions,
                 Some(window),
+                rwa.fill,
             )
             .context(RequestSnafu)?;
             info!(?request, "read_window_aggregate");
@@ -379,6 +472,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
                 Format::Quiet => {}
             }
         }
+        Command::Load(_) => unreachable!("handled above"),
     };
     println!("Query execution: {:?}", now.elapsed());
     Ok(())
@@ -407,4 +501,17 @@ mod test_super {
             );
         }
     }
+
+    #[test]
+    fn test_parse_aggregate_expression() {
+        let got = parse_aggregate_expression("ratio = sum(bytes) / sum(requests)").unwrap();
+        assert_eq!(got.name, "ratio");
+        assert!(got.expr.is_some());
+
+        let err = parse_aggregate_expression("sum(bytes)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unable to parse aggregate expression 'sum(bytes)': expected 'name=expression'"
+        );
+    }
 }
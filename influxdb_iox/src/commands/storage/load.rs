@@ -0,0 +1,500 @@
+//! Drive concurrent writes and storage queries against a server at fixed
+//! rates and report latency percentiles and error rates.
+
+use futures::Future;
+use generated_types::Predicate;
+use hdrhistogram::Histogram;
+use influxdb_iox_client::{connection::Connection, write};
+use influxdb_storage_client::{Client, OrgAndBucket};
+use observability_deps::tracing::{debug, info};
+use parking_lot::Mutex;
+use serde::Serialize;
+use snafu::{ensure, ResultExt, Snafu};
+use std::{
+    fmt::Write as _,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    sync::Semaphore,
+    time::{interval, MissedTickBehavior},
+};
+
+use super::request;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("{} must be greater than zero", name))]
+    NotPositive { name: &'static str },
+
+    #[snafu(display("{} must not be negative", name))]
+    Negative { name: &'static str },
+
+    #[snafu(display("nothing to do, both --write-rate and --query-rate are 0"))]
+    NoLoad,
+
+    #[snafu(display("error serializing results: {}", source))]
+    Serialize { source: serde_json::Error },
+
+    #[snafu(display("error writing results to {:?}: {}", path, source))]
+    WriteOutput {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Send writes and storage queries at fixed rates, then print their latency
+/// percentiles and error rates.
+///
+/// Requests are sent on a fixed schedule, independent of how fast the server
+/// responds. When the maximum number of requests is already in flight the
+/// request is skipped and counted, so a server that cannot keep up shows as
+/// skipped requests instead of silently lowering the rate.
+#[derive(Debug, clap::Parser)]
+pub struct Load {
+    /// How long to generate load for.
+    #[clap(long, default_value = "60s", value_parser = humantime::parse_duration)]
+    duration: Duration,
+
+    /// Write requests to send per second. 0 disables writes.
+    #[clap(long, default_value = "10")]
+    write_rate: f64,
+
+    /// The maximum number of write requests in flight.
+    #[clap(long, default_value = "4")]
+    write_concurrency: usize,
+
+    /// Lines of line protocol in each write request.
+    #[clap(long, default_value = "1000")]
+    lines_per_write: usize,
+
+    /// The number of distinct series the writes are spread over.
+    #[clap(long, default_value = "1000")]
+    series: usize,
+
+    /// The measurement to write to.
+    #[clap(long, default_value = "load")]
+    measurement: String,
+
+    /// Queries to send per second. 0 disables queries.
+    #[clap(long, default_value = "10")]
+    query_rate: f64,
+
+    /// The maximum number of queries in flight.
+    #[clap(long, default_value = "4")]
+    query_concurrency: usize,
+
+    /// The storage request to send as the query. The time range and predicate
+    /// of the `storage` command apply.
+    #[clap(long, value_enum, default_value = "read-filter")]
+    query: QueryKind,
+
+    /// Also write the results as JSON to this file.
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+/// The storage request sent by [`Load`].
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum QueryKind {
+    /// A `read_filter` request.
+    ReadFilter,
+    /// A `tag_values` request for the `series` tag written by the writes.
+    TagValues,
+    /// A `measurement_fields` request for the measurement written by the
+    /// writes.
+    MeasurementFields,
+}
+
+/// Run the load test described by `load`, writing to `write_connection` and
+/// querying through `connection`.
+pub async fn load<C, CFut>(
+    connection: Connection,
+    write_connection: C,
+    db_name: &OrgAndBucket,
+    start: i64,
+    stop: i64,
+    predicate: Option<Predicate>,
+    load: Load,
+) -> Result<()>
+where
+    C: Send + FnOnce() -> CFut,
+    CFut: Send + Future<Output = Connection>,
+{
+    ensure!(
+        load.write_rate >= 0.0,
+        NegativeSnafu {
+            name: "--write-rate"
+        }
+    );
+    ensure!(
+        load.query_rate >= 0.0,
+        NegativeSnafu {
+            name: "--query-rate"
+        }
+    );
+    ensure!(load.write_rate > 0.0 || load.query_rate > 0.0, NoLoadSnafu);
+    for (name, value) in [
+        ("--write-concurrency", load.write_concurrency),
+        ("--query-concurrency", load.query_concurrency),
+        ("--lines-per-write", load.lines_per_write),
+        ("--series", load.series),
+    ] {
+        ensure!(value > 0, NotPositiveSnafu { name });
+    }
+
+    let deadline = Instant::now() + load.duration;
+    info!(?load, "starting load test");
+
+    let writes = if load.write_rate > 0.0 {
+        let write_connection = write_connection().await;
+        let namespace = db_name.db_name().to_string();
+        let measurement = load.measurement.clone();
+        let (lines_per_write, series) = (load.lines_per_write, load.series);
+
+        Some(tokio::spawn(drive(
+            load.write_rate,
+            load.write_concurrency,
+            deadline,
+            move |n| {
+                let mut client = write::Client::new(write_connection.clone());
+                let namespace = namespace.clone();
+                let lp = line_protocol(&measurement, n, lines_per_write, series);
+                async move {
+                    client
+                        .write_lp(namespace, lp)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| e.to_string())
+                }
+            },
+        )))
+    } else {
+        None
+    };
+
+    let queries = if load.query_rate > 0.0 {
+        let source = Client::read_source(db_name, 0);
+        let measurement = load.measurement.clone();
+        let query = load.query;
+
+        Some(tokio::spawn(drive(
+            load.query_rate,
+            load.query_concurrency,
+            deadline,
+            move |_| {
+                let mut client = Client::new(connection.clone());
+                let source = source.clone();
+                let predicate = predicate.clone();
+                let measurement = measurement.clone();
+                async move {
+                    match query {
+                        QueryKind::ReadFilter => client
+                            .read_filter(request::read_filter(source, start, stop, predicate))
+                            .await
+                            .map(|_| ()),
+                        QueryKind::TagValues => client
+                            .tag_values(request::tag_values(
+                                source,
+                                start,
+                                stop,
+                                predicate,
+                                "series".to_string(),
+                            ))
+                            .await
+                            .map(|_| ()),
+                        QueryKind::MeasurementFields => client
+                            .measurement_fields(request::measurement_fields(
+                                source,
+                                measurement,
+                                start,
+                                stop,
+                                predicate,
+                            ))
+                            .await
+                            .map(|_| ()),
+                    }
+                    .map_err(|e| e.to_string())
+                }
+            },
+        )))
+    } else {
+        None
+    };
+
+    let mut report = Report {
+        duration_secs: load.duration.as_secs_f64(),
+        writes: None,
+        queries: None,
+    };
+    if let Some(writes) = writes {
+        let stats = writes.await.expect("write driver panicked");
+        report.writes = Some(stats.summary(load.duration));
+    }
+    if let Some(queries) = queries {
+        let stats = queries.await.expect("query driver panicked");
+        report.queries = Some(stats.summary(load.duration));
+    }
+
+    print!("{}", report.display());
+
+    if let Some(path) = load.output {
+        let json = serde_json::to_string_pretty(&report).context(SerializeSnafu)?;
+        std::fs::write(&path, json).context(WriteOutputSnafu { path })?;
+    }
+
+    Ok(())
+}
+
+/// The latencies and outcomes of the requests of one kind.
+#[derive(Debug)]
+struct Stats {
+    /// Request latencies, in microseconds.
+    latencies: Histogram<u64>,
+    errors: u64,
+    skipped: u64,
+    /// The first few distinct errors, to give an idea of what went wrong.
+    sample_errors: Vec<String>,
+}
+
+/// The number of distinct errors kept in [`Stats::sample_errors`].
+const MAX_SAMPLE_ERRORS: usize = 5;
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            // Track latencies of up to an hour with 3 significant digits.
+            latencies: Histogram::new_with_bounds(1, 3_600_000_000, 3)
+                .expect("valid histogram bounds"),
+            errors: 0,
+            skipped: 0,
+            sample_errors: vec![],
+        }
+    }
+}
+
+impl Stats {
+    fn record(&mut self, latency: Duration, result: Result<(), String>) {
+        self.latencies.saturating_record(latency.as_micros() as u64);
+        if let Err(e) = result {
+            self.errors += 1;
+            if self.sample_errors.len() < MAX_SAMPLE_ERRORS && !self.sample_errors.contains(&e) {
+                self.sample_errors.push(e);
+            }
+        }
+    }
+
+    fn summary(&self, duration: Duration) -> Summary {
+        let requests = self.latencies.len();
+        let percentile_ms = |q: f64| self.latencies.value_at_quantile(q) as f64 / 1000.0;
+
+        Summary {
+            requests,
+            errors: self.errors,
+            skipped: self.skipped,
+            error_rate: if requests == 0 {
+                0.0
+            } else {
+                self.errors as f64 / requests as f64
+            },
+            requests_per_sec: requests as f64 / duration.as_secs_f64(),
+            latency_ms: Percentiles {
+                p50: percentile_ms(0.5),
+                p90: percentile_ms(0.9),
+                p99: percentile_ms(0.99),
+                p999: percentile_ms(0.999),
+                max: self.latencies.max() as f64 / 1000.0,
+            },
+            sample_errors: self.sample_errors.clone(),
+        }
+    }
+}
+
+/// Call `op` with an increasing request number `rate` times per second until
+/// `deadline`, with at most `concurrency` calls in flight, and wait for the
+/// calls in flight at the deadline to complete.
+async fn drive<F, Fut>(rate: f64, concurrency: usize, deadline: Instant, mut op: F) -> Stats
+where
+    F: FnMut(u64) -> Fut + Send,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    let stats = Arc::new(Mutex::new(Stats::default()));
+    let permits = Arc::new(Semaphore::new(concurrency));
+
+    let mut ticks = interval(Duration::from_secs_f64(1.0 / rate));
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
+    let mut n = 0;
+    loop {
+        ticks.tick().await;
+        if Instant::now() >= deadline {
+            break;
+        }
+
+        let Ok(permit) = Arc::clone(&permits).try_acquire_owned() else {
+            debug!(n, "all permits in use, skipping request");
+            stats.lock().skipped += 1;
+            continue;
+        };
+
+        let fut = op(n);
+        let stats = Arc::clone(&stats);
+        tokio::spawn(async move {
+            let start = Instant::now();
+            let result = fut.await;
+            stats.lock().record(start.elapsed(), result);
+            drop(permit);
+        });
+        n += 1;
+    }
+
+    // Wait for the requests in flight.
+    let _all = permits
+        .acquire_many(concurrency as u32)
+        .await
+        .expect("semaphore is never closed");
+
+    std::mem::take(&mut *stats.lock())
+}
+
+/// The line protocol of write number `n`, cycling through `series` series.
+fn line_protocol(measurement: &str, n: u64, lines: usize, series: usize) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_nanos() as i64;
+
+    let mut lp = String::with_capacity(lines * 64);
+    for i in 0..lines {
+        let line = n as usize * lines + i;
+        // Offset the timestamps of the lines so a series written more than
+        // once per write does not overwrite itself.
+        writeln!(
+            lp,
+            "{measurement},series=s{} value={line}i {}",
+            line % series,
+            now + i as i64
+        )
+        .expect("writing to a String cannot fail");
+    }
+    lp
+}
+
+/// The results of a load test.
+#[derive(Debug, Serialize)]
+struct Report {
+    duration_secs: f64,
+    writes: Option<Summary>,
+    queries: Option<Summary>,
+}
+
+/// The results of the requests of one kind.
+#[derive(Debug, Serialize, PartialEq)]
+struct Summary {
+    requests: u64,
+    errors: u64,
+    skipped: u64,
+    error_rate: f64,
+    requests_per_sec: f64,
+    latency_ms: Percentiles,
+    sample_errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct Percentiles {
+    p50: f64,
+    p90: f64,
+    p99: f64,
+    p999: f64,
+    max: f64,
+}
+
+impl Report {
+    fn display(&self) -> String {
+        let mut out = String::new();
+        for (name, summary) in [("writes", &self.writes), ("queries", &self.queries)] {
+            let Some(s) = summary else { continue };
+            writeln!(
+                out,
+                "{name}: {} requests ({:.1}/sec), {} errors ({:.2}%), {} skipped",
+                s.requests,
+                s.requests_per_sec,
+                s.errors,
+                s.error_rate * 100.0,
+                s.skipped,
+            )
+            .unwrap();
+            let l = &s.latency_ms;
+            writeln!(
+                out,
+                "  latency ms: p50 {:.2}, p90 {:.2}, p99 {:.2}, p99.9 {:.2}, max {:.2}",
+                l.p50, l.p90, l.p99, l.p999, l.max
+            )
+            .unwrap();
+            for e in &s.sample_errors {
+                writeln!(out, "  error: {e}").unwrap();
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_protocol() {
+        let lp = line_protocol("m", 1, 3, 4);
+        let lines: Vec<_> = lp.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("m,series=s3 value=3i "), "{lp}");
+        assert!(lines[1].starts_with("m,series=s0 value=4i "), "{lp}");
+        assert!(lines[2].starts_with("m,series=s1 value=5i "), "{lp}");
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut stats = Stats::default();
+        for ms in 1..=100 {
+            let result = if ms % 10 == 0 {
+                Err(format!("error {}", ms % 20))
+            } else {
+                Ok(())
+            };
+            stats.record(Duration::from_millis(ms), result);
+        }
+        stats.skipped = 3;
+
+        let summary = stats.summary(Duration::from_secs(10));
+        assert_eq!(summary.requests, 100);
+        assert_eq!(summary.errors, 10);
+        assert_eq!(summary.skipped, 3);
+        assert_eq!(summary.error_rate, 0.1);
+        assert_eq!(summary.requests_per_sec, 10.0);
+        // 3 significant digits
+        assert!((summary.latency_ms.p50 - 50.0).abs() < 0.1, "{summary:?}");
+        assert!((summary.latency_ms.p99 - 99.0).abs() < 0.1, "{summary:?}");
+        assert!((summary.latency_ms.max - 100.0).abs() < 0.1, "{summary:?}");
+        assert_eq!(summary.sample_errors, vec!["error 10", "error 0"]);
+    }
+
+    #[tokio::test]
+    async fn test_drive_skips_when_saturated() {
+        let deadline = Instant::now() + Duration::from_millis(200);
+        // A single request in flight at a time that never completes before the
+        // deadline, so all but the first request are skipped.
+        let stats = drive(100.0, 1, deadline, |_| async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok(())
+        })
+        .await;
+
+        assert_eq!(stats.latencies.len(), 1);
+        assert!(stats.skipped > 0);
+        assert_eq!(stats.errors, 0);
+    }
+}
@@ -60,6 +60,9 @@ pub fn read_filter(
         range: Some(TimestampRange { start, end: stop }),
         key_sort: read_filter_request::KeySort::Unspecified as i32, // IOx doesn't support any other sort
         tag_key_meta_names: TagKeyMetaNames::Binary as i32,
+        series_frame_encoding: SeriesFrameEncoding::SharedTagPrefix as i32,
+        timestamp_precision: TimestampPrecision::Nanoseconds as i32,
+        point_order: read_filter_request::PointOrder::Ascending as i32,
     }
 }
 
@@ -76,9 +79,13 @@ pub fn read_group(
         predicate,
         read_source: Some(org_bucket),
         range: Some(TimestampRange { start, end: stop }),
-        aggregate: aggregate.map(|a| Aggregate { r#type: a as i32 }),
+        aggregate: aggregate.map(|a| Aggregate {
+            r#type: a as i32,
+            n: 0,
+        }),
         group: group as i32,
         group_keys,
+        timestamp_precision: TimestampPrecision::Nanoseconds as i32,
     }
 }
 
@@ -91,7 +98,9 @@ pub fn read_window_aggregate(
     every: std::time::Duration,
     offset: std::time::Duration,
     aggregates: Vec<AggregateType>,
+    aggregate_expressions: Vec<AggregateExpression>,
     window: std::option::Option<Window>,
+    fill: read_window_aggregate_request::Fill,
 ) -> Result<ReadWindowAggregateRequest, Error> {
     let window_every = if every.as_nanos() > i64::MAX as u128 {
         return DurationSnafu { d: every }.fail();
@@ -108,7 +117,10 @@ pub fn read_window_aggregate(
     // wrap in the PB message type for aggregates.
     let aggregate = aggregates
         .into_iter()
-        .map(|a| Aggregate { r#type: a as i32 })
+        .map(|a| Aggregate {
+            r#type: a as i32,
+            n: 0,
+        })
         .collect::<Vec<_>>();
 
     Ok(generated_types::ReadWindowAggregateRequest {
@@ -120,6 +132,9 @@ pub fn read_window_aggregate(
         aggregate,
         window,
         tag_key_meta_names: TagKeyMetaNames::Text as i32,
+        fill: fill as i32,
+        timestamp_precision: TimestampPrecision::Nanoseconds as i32,
+        aggregate_expressions,
     })
 }
 
@@ -180,7 +195,9 @@ mod test_super {
             std::time::Duration::from_millis(3),
             std::time::Duration::from_millis(2),
             vec![],
+            vec![],
             None,
+            read_window_aggregate_request::Fill::None,
         )
         .unwrap();
 
@@ -195,7 +212,9 @@ mod test_super {
             std::time::Duration::from_secs(u64::MAX),
             std::time::Duration::from_millis(2),
             vec![],
+            vec![],
             None,
+            read_window_aggregate_request::Fill::None,
         );
         assert!(got.is_err());
 
@@ -207,7 +226,9 @@ mod test_super {
             std::time::Duration::from_secs(3),
             std::time::Duration::from_secs(u64::MAX),
             vec![],
+            vec![],
             None,
+            read_window_aggregate_request::Fill::None,
         );
         assert!(got.is_err());
     }
@@ -672,6 +672,7 @@ mod test_super {
                     (K::FIELD_KEY, "temp"),
                 ]),
                 data_type: DataType::Float as i32,
+                shared_tag_prefix: 0,
             }),
             Data::FloatPoints(FloatPointsFrame {
                 timestamps: vec![1, 2, 3, 4],
@@ -689,6 +690,7 @@ mod test_super {
                     (K::FIELD_KEY, "voltage"),
                 ]),
                 data_type: DataType::Integer as i32,
+                shared_tag_prefix: 0,
             }),
             Data::IntegerPoints(IntegerPointsFrame {
                 timestamps: vec![1, 2],
@@ -702,6 +704,7 @@ mod test_super {
                     (K::FIELD_KEY, "voltage"),
                 ]),
                 data_type: DataType::Integer as i32,
+                shared_tag_prefix: 0,
             }),
             Data::IntegerPoints(IntegerPointsFrame {
                 timestamps: vec![100, 200],
@@ -713,6 +716,7 @@ mod test_super {
                     (K::FIELD_KEY, "voltage"),
                 ]),
                 data_type: DataType::String as i32,
+                shared_tag_prefix: 0,
             }),
             Data::StringPoints(StringPointsFrame {
                 timestamps: vec![200, 201],
@@ -725,6 +729,7 @@ mod test_super {
                     (K::FIELD_KEY, "voltage"),
                 ]),
                 data_type: DataType::String as i32,
+                shared_tag_prefix: 0,
             }),
             Data::StringPoints(StringPointsFrame {
                 timestamps: vec![302, 304],
@@ -737,6 +742,7 @@ mod test_super {
                     (K::FIELD_KEY, "bool_field"),
                 ]),
                 data_type: DataType::Boolean as i32,
+                shared_tag_prefix: 0,
             }),
             Data::BooleanPoints(BooleanPointsFrame {
                 timestamps: vec![1000],
@@ -749,6 +755,7 @@ mod test_super {
                     (K::FIELD_KEY, "unsigned_field"),
                 ]),
                 data_type: DataType::Unsigned as i32,
+                shared_tag_prefix: 0,
             }),
             Data::UnsignedPoints(UnsignedPointsFrame {
                 timestamps: vec![2000],
@@ -768,6 +775,7 @@ mod test_super {
                 (BinaryKeyNames::FIELD_KEY, "temp"),
             ]),
             data_type: DataType::Float as i32,
+            shared_tag_prefix: 0,
         });
 
         let exp = make_table_columns(&[TableColumnInput::new(
@@ -810,6 +818,7 @@ mod test_super {
                 (b"_field", "temp"),
             ]),
             data_type: DataType::Float as i32,
+            shared_tag_prefix: 0,
         });
 
         let exp = make_table_columns(&[TableColumnInput::new(
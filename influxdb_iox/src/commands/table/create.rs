@@ -1,4 +1,8 @@
-use crate::commands::{partition_template::PartitionTemplateConfig, table::Result};
+use crate::commands::{
+    output::{self, OutputFormat},
+    partition_template::PartitionTemplateConfig,
+    table::Result,
+};
 use influxdb_iox_client::connection::Connection;
 
 /// Write data into the specified database
@@ -17,7 +21,7 @@ pub struct Config {
     partition_template_config: PartitionTemplateConfig,
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<()> {
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
     let Config {
         database,
         table,
@@ -33,7 +37,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
             partition_template_config.partition_template,
         )
         .await?;
-    println!("{}", serde_json::to_string_pretty(&table)?);
+    output::print(format, &table)?;
 
     Ok(())
 }
@@ -0,0 +1,29 @@
+use crate::commands::table::Error;
+use influxdb_iox_client::connection::Connection;
+
+/// Drop a field column of a table
+#[derive(Debug, clap::Parser, Default, Clone)]
+pub struct Config {
+    /// The database the table is in
+    #[clap(action)]
+    database: String,
+
+    /// The table the column is in
+    #[clap(action)]
+    table: String,
+
+    /// The name of the field column to drop
+    #[clap(action)]
+    name: String,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+
+    client
+        .drop_column(&config.database, &config.table, &config.name)
+        .await?;
+    println!("Dropped column {}", config.name);
+
+    Ok(())
+}
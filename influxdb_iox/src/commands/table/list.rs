@@ -1,4 +1,7 @@
-use crate::commands::table::Error;
+use crate::commands::{
+    output::{self, OutputFormat},
+    table::Error,
+};
 use influxdb_iox_client::connection::Connection;
 
 /// List tables within the specified database
@@ -9,11 +12,15 @@ pub struct Config {
     database: String,
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: OutputFormat,
+) -> Result<(), Error> {
     let mut client = influxdb_iox_client::table::Client::new(connection);
 
     let tables = client.get_tables(&config.database).await?;
-    println!("{}", serde_json::to_string_pretty(&tables)?);
+    output::print(format, &tables)?;
 
     Ok(())
 }
@@ -4,8 +4,15 @@ use influxdb_iox_client::connection::Connection;
 use observability_deps::tracing::info;
 use thiserror::Error;
 
+use crate::commands::output::OutputFormat;
+
 mod create;
+mod drop_column;
 mod list;
+mod rename;
+mod rename_column;
+mod retention;
+mod rewrite;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
@@ -33,16 +40,40 @@ enum Command {
     List(list::Config),
     /// Create a new table
     Create(create::Config),
+    /// Rename a table
+    Rename(rename::Config),
+    /// Rename a column of a table
+    RenameColumn(rename_column::Config),
+    /// Drop a field column of a table
+    DropColumn(drop_column::Config),
+    /// Update the retention period of a table
+    Retention(retention::Config),
+    /// Copy the data of a table into another table
+    Rewrite(rewrite::Config),
 }
 
-pub async fn command(connection: Connection, config: Config) -> Result<()> {
+/// Table commands print JSON unless another format is requested
+const DEFAULT_FORMAT: OutputFormat = OutputFormat::Json;
+
+pub async fn command(
+    connection: Connection,
+    config: Config,
+    format: Option<OutputFormat>,
+) -> Result<()> {
+    let format = format.unwrap_or(DEFAULT_FORMAT);
     match config.command {
-        Command::List(config) => list::command(connection, config).await?,
+        Command::List(config) => list::command(connection, config, format).await?,
         Command::Create(config) => {
             info!("Creating table with config: {:?}", config);
-            create::command(connection, config).await?;
-        } // Deliberately not adding _ => so the compiler will direct people here to impl new
-          // commands
+            create::command(connection, config, format).await?;
+        }
+        Command::Rename(config) => rename::command(connection, config, format).await?,
+        Command::RenameColumn(config) => rename_column::command(connection, config).await?,
+        Command::DropColumn(config) => drop_column::command(connection, config).await?,
+        Command::Retention(config) => retention::command(connection, config, format).await?,
+        Command::Rewrite(config) => rewrite::command(connection, config, format).await?,
+        // Deliberately not adding _ => so the compiler will direct people here to impl new
+        // commands
     }
     Ok(())
 }
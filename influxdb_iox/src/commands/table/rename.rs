@@ -0,0 +1,32 @@
+use crate::commands::{
+    output::{self, OutputFormat},
+    table::Result,
+};
+use influxdb_iox_client::connection::Connection;
+
+/// Rename a table
+#[derive(Debug, clap::Parser, Default, Clone)]
+pub struct Config {
+    /// The database the table is in
+    #[clap(action)]
+    database: String,
+
+    /// The current name of the table
+    #[clap(action)]
+    name: String,
+
+    /// The new name of the table
+    #[clap(action)]
+    new_name: String,
+}
+
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+
+    let table = client
+        .rename_table(&config.database, &config.name, &config.new_name)
+        .await?;
+    output::print(format, &table)?;
+
+    Ok(())
+}
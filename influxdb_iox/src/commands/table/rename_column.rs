@@ -0,0 +1,38 @@
+use crate::commands::table::Error;
+use influxdb_iox_client::connection::Connection;
+
+/// Rename a column of a table
+#[derive(Debug, clap::Parser, Default, Clone)]
+pub struct Config {
+    /// The database the table is in
+    #[clap(action)]
+    database: String,
+
+    /// The table the column is in
+    #[clap(action)]
+    table: String,
+
+    /// The current name of the column
+    #[clap(action)]
+    name: String,
+
+    /// The new name of the column
+    #[clap(action)]
+    new_name: String,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+
+    client
+        .rename_column(
+            &config.database,
+            &config.table,
+            &config.name,
+            &config.new_name,
+        )
+        .await?;
+    println!("Renamed column {} to {}", config.name, config.new_name);
+
+    Ok(())
+}
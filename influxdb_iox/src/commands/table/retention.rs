@@ -0,0 +1,49 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::{
+    output::{self, OutputFormat},
+    table::Result,
+};
+
+/// Update the retention period of a table, to expire its data faster than the
+/// retention period of its database
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The database the table is in
+    #[clap(action)]
+    database: String,
+
+    /// The name of the table
+    #[clap(action)]
+    table: String,
+
+    /// Num of hours of the retention period of this table. Default is 0, which
+    /// removes the table retention period so only the retention period of the
+    /// database applies
+    #[clap(action, long = "retention-hours", short = 'r', default_value = "0")]
+    retention_hours: u32,
+}
+
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
+    let Config {
+        database,
+        table,
+        retention_hours,
+    } = config;
+
+    // retention_hours = 0 removes the table retention period. Make it None/Null in the request.
+    let retention: Option<i64> = if retention_hours == 0 {
+        None
+    } else {
+        // we take retention from the user in hours, for ease of use, but it's stored as nanoseconds
+        // internally
+        Some(retention_hours as i64 * 60 * 60 * 1_000_000_000)
+    };
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+    let table = client
+        .update_table_retention(&database, &table, retention)
+        .await?;
+    output::print(format, &table)?;
+
+    Ok(())
+}
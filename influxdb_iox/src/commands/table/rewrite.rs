@@ -0,0 +1,113 @@
+use crate::commands::{
+    operation::Summary,
+    output::{self, OutputFormat},
+    table::Result,
+};
+use influxdb_iox_client::{
+    compactor::generated_types::{
+        tag_op::Op, AddTag, RemoveTag, RenameTag, RewriteTableRequest, TagOp,
+    },
+    connection::Connection,
+};
+
+/// Copy the data of a table into another table in the background, e.g. to
+/// split a table or to move values into a tag.
+///
+/// The command must be sent to a compactor. Renamed tags are renamed first,
+/// then tags are removed, then tags are added. Use `operation wait` to wait
+/// for the rewrite to complete.
+#[derive(Debug, clap::Parser, Default, Clone)]
+pub struct Config {
+    /// The database of both tables
+    #[clap(action)]
+    database: String,
+
+    /// The table the data is read from
+    #[clap(action)]
+    source_table: String,
+
+    /// The table the data is written to, which is created if it does not
+    /// exist
+    #[clap(action)]
+    target_table: String,
+
+    /// Only copy rows at or after this time (nanoseconds since the epoch or
+    /// RFC3339)
+    #[clap(long, default_value = "", action)]
+    start: String,
+
+    /// Only copy rows before this time (nanoseconds since the epoch or
+    /// RFC3339)
+    #[clap(long, default_value = "", action)]
+    stop: String,
+
+    /// Only copy rows matching this predicate, in the syntax of delete
+    /// predicates, e.g. `region="west"`
+    #[clap(long, default_value = "", action)]
+    predicate: String,
+
+    /// Set the tag `name` to `value` in every row, as `name=value`
+    #[clap(long = "add-tag", value_parser = parse_key_value)]
+    add_tags: Vec<(String, String)>,
+
+    /// Remove the tag `name`
+    #[clap(long = "remove-tag", action)]
+    remove_tags: Vec<String>,
+
+    /// Rename the tag `from` to `to`, as `from=to`
+    #[clap(long = "rename-tag", value_parser = parse_key_value)]
+    rename_tags: Vec<(String, String)>,
+}
+
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .ok_or_else(|| format!("invalid KEY=VALUE: no `=` found in `{s}`"))
+}
+
+pub async fn command(connection: Connection, config: Config, format: OutputFormat) -> Result<()> {
+    let mut client = influxdb_iox_client::compactor::Client::new(connection);
+
+    let Config {
+        database,
+        source_table,
+        target_table,
+        start,
+        stop,
+        predicate,
+        add_tags,
+        remove_tags,
+        rename_tags,
+    } = config;
+
+    let tag_ops = rename_tags
+        .into_iter()
+        .map(|(from, to)| Op::Rename(RenameTag { from, to }))
+        .chain(
+            remove_tags
+                .into_iter()
+                .map(|name| Op::Remove(RemoveTag { name })),
+        )
+        .chain(
+            add_tags
+                .into_iter()
+                .map(|(name, value)| Op::Add(AddTag { name, value })),
+        )
+        .map(|op| TagOp { op: Some(op) })
+        .collect();
+
+    let operation = client
+        .rewrite_table(RewriteTableRequest {
+            namespace: database,
+            source_table,
+            target_table,
+            start,
+            stop,
+            predicate,
+            tag_ops,
+        })
+        .await?;
+    output::print(format, &Summary::from(&operation))?;
+
+    Ok(())
+}
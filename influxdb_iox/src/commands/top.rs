@@ -0,0 +1,281 @@
+//! This module implements the `top` CLI command
+
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use comfy_table::{Cell, Table};
+use influxdb_iox_client::{connection::Connection, metrics};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Client error: {0}")]
+    Client(#[from] influxdb_iox_client::error::Error),
+
+    #[error("Error writing to terminal: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Render a live view of the server's write, persist, memory and query
+/// activity, refreshed periodically from its `/metrics` endpoint
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// How often to refresh the view
+    #[clap(
+        long,
+        default_value = "2s",
+        value_parser = humantime::parse_duration,
+        action
+    )]
+    interval: Duration,
+
+    /// Exit after this many refreshes (runs until interrupted if not set)
+    #[clap(long, action)]
+    iterations: Option<usize>,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let client = metrics::Client::new(connection);
+
+    let mut previous: Option<Sample> = None;
+    let mut iteration = 0;
+    loop {
+        let sample = Sample::new(&client.get_metrics().await?);
+
+        let mut stdout = std::io::stdout();
+        // clear the screen and move the cursor to the top left corner
+        write!(stdout, "\x1b[2J\x1b[H")?;
+        writeln!(stdout, "{}", render(&sample, previous.as_ref()))?;
+        stdout.flush()?;
+
+        previous = Some(sample);
+        iteration += 1;
+        if config.iterations.is_some_and(|n| iteration >= n) {
+            return Ok(());
+        }
+
+        tokio::time::sleep(config.interval).await;
+    }
+}
+
+/// The value of every metric series at a point in time.
+#[derive(Debug)]
+struct Sample {
+    taken_at: Instant,
+
+    /// Map of metric name to the (labels, value) of each of its series
+    metrics: BTreeMap<String, Vec<(String, f64)>>,
+}
+
+impl Sample {
+    /// Parse the Prometheus text exposition format
+    fn new(text: &str) -> Self {
+        let mut metrics: BTreeMap<String, Vec<(String, f64)>> = BTreeMap::new();
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((series, value)) = line.rsplit_once(' ') else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f64>() else {
+                continue;
+            };
+
+            let (name, labels) = match series.split_once('{') {
+                Some((name, labels)) => (name, labels.trim_end_matches('}')),
+                None => (series, ""),
+            };
+
+            metrics
+                .entry(name.to_string())
+                .or_default()
+                .push((labels.to_string(), value));
+        }
+
+        Self {
+            taken_at: Instant::now(),
+            metrics,
+        }
+    }
+
+    /// The sum of all series of the named metric
+    fn total(&self, name: &str) -> Option<f64> {
+        self.metrics
+            .get(name)
+            .map(|series| series.iter().map(|(_, v)| v).sum())
+    }
+
+    /// The value of each series of the named metric, keyed by the value of
+    /// `label`
+    fn by_label(&self, name: &str, label: &str) -> BTreeMap<String, f64> {
+        let mut values = BTreeMap::new();
+        for (labels, value) in self.metrics.get(name).into_iter().flatten() {
+            let key = label_value(labels, label).unwrap_or_default();
+            *values.entry(key.to_string()).or_default() += value;
+        }
+        values
+    }
+
+    /// The per-second rate of change of a counter since `previous`
+    fn rate(&self, previous: Option<&Self>, name: &str) -> Option<f64> {
+        let previous = previous?;
+        let elapsed = self
+            .taken_at
+            .duration_since(previous.taken_at)
+            .as_secs_f64();
+        if elapsed == 0.0 {
+            return None;
+        }
+
+        // counters reset when the server restarts
+        let delta = self.total(name)? - previous.total(name).unwrap_or_default();
+        Some(delta.max(0.0) / elapsed)
+    }
+}
+
+/// Extract the value of `label` from a Prometheus label set such as
+/// `a="1",b="2"`
+fn label_value<'a>(labels: &'a str, label: &str) -> Option<&'a str> {
+    labels.split(',').find_map(|pair| {
+        let (k, v) = pair.split_once('=')?;
+        (k.trim() == label).then(|| v.trim().trim_matches('"'))
+    })
+}
+
+fn render(sample: &Sample, previous: Option<&Sample>) -> String {
+    let rate = |name: &str| match sample.rate(previous, name) {
+        Some(v) => format!("{v:.1}/s"),
+        None => "-".to_string(),
+    };
+    let total = |name: &str| match sample.total(name) {
+        Some(v) => format!("{v}"),
+        None => "-".to_string(),
+    };
+
+    let mut out = String::new();
+
+    let mut writes = new_table(["writes", "lines", "fields", "bytes", "rejected requests"]);
+    writes.add_row([
+        Cell::new("rate"),
+        Cell::new(rate("http_write_lines_total")),
+        Cell::new(rate("http_write_fields_total")),
+        Cell::new(rate("http_write_body_bytes_total")),
+        Cell::new(rate("http_request_limit_rejected_total")),
+    ]);
+    out.push_str(&format!("{writes}\n"));
+
+    let mut ingester = new_table([
+        "ingester",
+        "namespaces",
+        "tables",
+        "persist jobs",
+        "files persisted",
+        "bytes persisted",
+        "inactive wal files",
+    ]);
+    ingester.add_row([
+        Cell::new("now"),
+        Cell::new(total("ingester_namespaces_total")),
+        Cell::new(total("ingester_tables_total")),
+        Cell::new(rate("ingester_persist_enqueued_jobs_total")),
+        Cell::new(rate("ingester_persist_parquet_file_size_bytes_count")),
+        Cell::new(rate("ingester_persist_parquet_file_size_bytes_sum")),
+        Cell::new(total("ingester_wal_inactive_file_count")),
+    ]);
+    out.push_str(&format!("{ingester}\n"));
+
+    let memory = sample.by_label("jemalloc_memstats_bytes", "stat");
+    if !memory.is_empty() {
+        let mut table = new_table(["memory", "bytes"]);
+        for (stat, bytes) in memory {
+            table.add_row([Cell::new(stat), Cell::new(bytes)]);
+        }
+        out.push_str(&format!("{table}\n"));
+    }
+
+    let acquired = sample.by_label("iox_async_semaphore_holders_acquired", "semaphore");
+    let pending = sample.by_label("iox_async_semaphore_holders_pending", "semaphore");
+    if !acquired.is_empty() {
+        let mut table = new_table(["active", "running", "waiting"]);
+        for (semaphore, running) in acquired {
+            let waiting = pending.get(&semaphore).copied().unwrap_or_default();
+            table.add_row([Cell::new(semaphore), Cell::new(running), Cell::new(waiting)]);
+        }
+        out.push_str(&format!("{table}\n"));
+    }
+
+    out
+}
+
+fn new_table<const N: usize>(headers: [&str; N]) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+    table.set_header(headers.into_iter().map(Cell::new));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: &str = r#"
+# HELP http_write_lines_total cumulative number of line protocol lines successfully routed
+# TYPE http_write_lines_total counter
+http_write_lines_total 100
+# HELP iox_async_semaphore_holders_acquired Number of currently acquired semaphore holders
+# TYPE iox_async_semaphore_holders_acquired gauge
+iox_async_semaphore_holders_acquired{semaphore="query_execution"} 2
+iox_async_semaphore_holders_acquired{semaphore="job"} 1
+"#;
+
+    #[test]
+    fn test_parse() {
+        let sample = Sample::new(METRICS);
+
+        assert_eq!(sample.total("http_write_lines_total"), Some(100.0));
+        assert_eq!(
+            sample.total("iox_async_semaphore_holders_acquired"),
+            Some(3.0)
+        );
+        assert_eq!(sample.total("missing"), None);
+
+        let by_semaphore = sample.by_label("iox_async_semaphore_holders_acquired", "semaphore");
+        assert_eq!(by_semaphore.get("query_execution"), Some(&2.0));
+        assert_eq!(by_semaphore.get("job"), Some(&1.0));
+    }
+
+    #[test]
+    fn test_rate() {
+        let mut previous = Sample::new(METRICS);
+        let current = Sample::new(&METRICS.replace("total 100", "total 300"));
+        previous.taken_at = current.taken_at - Duration::from_secs(2);
+
+        assert_eq!(current.rate(None, "http_write_lines_total"), None);
+        assert_eq!(
+            current.rate(Some(&previous), "http_write_lines_total"),
+            Some(100.0)
+        );
+
+        // a counter reset does not produce a negative rate
+        let mut reset = Sample::new(&METRICS.replace("total 100", "total 5"));
+        reset.taken_at = current.taken_at + Duration::from_secs(1);
+        assert_eq!(
+            reset.rate(Some(&current), "http_write_lines_total"),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_label_value() {
+        assert_eq!(label_value(r#"a="1",b="2""#, "b"), Some("2"));
+        assert_eq!(label_value(r#"a="1""#, "b"), None);
+        assert_eq!(label_value("", "b"), None);
+    }
+}
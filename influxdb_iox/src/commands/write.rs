@@ -1,5 +1,8 @@
 use futures::{stream::BoxStream, StreamExt};
-use influxdb_iox_client::{connection::Connection, write};
+use influxdb_iox_client::{
+    connection::Connection,
+    write::{self, Precision},
+};
 use observability_deps::tracing::{debug, info};
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use std::{
@@ -43,6 +46,9 @@ pub enum Error {
 
     #[snafu(display("Max concurrent uploads must be greater than zero"))]
     MaxConcurrentUploadsVerfication,
+
+    #[snafu(display("Invalid precision '{}'. Expected 's', 'ms', 'us' or 'ns'", precision))]
+    InvalidPrecision { precision: String },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -59,6 +65,11 @@ pub struct Config {
     #[clap(action, long, short = 'c', default_value = "10")]
     max_concurrent_uploads: usize,
 
+    /// Precision of the timestamps in the line protocol: `s`, `ms`, `us` or `ns`.
+    /// Defaults to nanoseconds
+    #[clap(long, default_value = "ns", value_parser = parse_precision)]
+    precision: Precision,
+
     /// The namespace into which to write, in the form <org_id>_<bucket_id>
     #[clap(action)]
     namespace: String,
@@ -69,6 +80,16 @@ pub struct Config {
     file_names: Vec<PathBuf>,
 }
 
+fn parse_precision(precision: &str) -> Result<Precision> {
+    match precision {
+        "s" => Ok(Precision::Seconds),
+        "ms" => Ok(Precision::Milliseconds),
+        "us" => Ok(Precision::Microseconds),
+        "ns" => Ok(Precision::Nanoseconds),
+        _ => InvalidPrecisionSnafu { precision }.fail(),
+    }
+}
+
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
     let start = Instant::now();
 
@@ -77,6 +98,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         file_names,
         max_request_payload_size_bytes,
         max_concurrent_uploads,
+        precision,
     } = config;
 
     let max_concurrent_uploads =
@@ -148,7 +170,8 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
 
     let mut client = write::Client::new(connection)
         .with_max_concurrent_uploads(max_concurrent_uploads)
-        .with_max_request_payload_size_bytes(Some(max_request_payload_size_bytes));
+        .with_max_request_payload_size_bytes(Some(max_request_payload_size_bytes))
+        .with_precision(precision);
 
     let total_bytes = client
         .write_lp_stream(namespace, lp_stream)
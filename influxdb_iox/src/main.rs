@@ -39,7 +39,10 @@ use trace_exporters::{
 mod commands {
     pub mod catalog;
     pub mod debug;
+    pub mod log_filter;
     pub mod namespace;
+    pub mod operation;
+    pub mod output;
     pub mod partition_template;
     pub mod query;
     pub mod query_ingester;
@@ -48,6 +51,7 @@ mod commands {
     pub mod sql;
     pub mod storage;
     pub mod table;
+    pub mod top;
     pub mod tracing;
     pub mod write;
 }
@@ -68,6 +72,18 @@ enum ReturnCode {
 ))]
 compile_error!("heappy and jemalloc_replacing_malloc features are mutually exclusive");
 
+#[cfg(all(feature = "heappy", feature = "heap_tracking", not(feature = "clippy")))]
+compile_error!("heappy and heap_tracking features are mutually exclusive");
+
+/// Attribute heap allocations to IOx subsystems.
+///
+/// This wraps the system allocator, which is jemalloc if the
+/// `jemalloc_replacing_malloc` feature is enabled.
+#[cfg(all(feature = "heap_tracking", not(feature = "heappy")))]
+#[global_allocator]
+static ALLOC: tracker::TrackingAllocator<std::alloc::System> =
+    tracker::TrackingAllocator::new(std::alloc::System);
+
 #[derive(Debug, clap::Parser)]
 #[clap(
     name = "influxdb_iox",
@@ -179,6 +195,16 @@ struct Config {
     #[clap(long, global = true, env = "INFLUX_TOKEN", action)]
     token: Option<String>,
 
+    /// Output format of management commands such as `namespace list`
+    ///
+    /// `json` is suitable for scripting. If not specified, each command
+    /// uses its own default.
+    ///
+    /// This is distinct from the `--format` option of the `storage`
+    /// commands, which is also global within them.
+    #[clap(id = "output_format", long, global = true, value_enum, action)]
+    output_format: Option<commands::output::OutputFormat>,
+
     /// Set the maximum number of threads to use. Defaults to the number of
     /// cores on the system
     #[clap(long, action)]
@@ -227,6 +253,15 @@ enum Command {
 
     /// Various commands for table manipulation
     Table(commands::table::Config),
+
+    /// Inspect and cancel background operations
+    Operation(commands::operation::Config),
+
+    /// Inspect and change the log filter of a running server
+    LogFilter(commands::log_filter::Config),
+
+    /// Live view of server activity, refreshed from its metrics
+    Top(commands::top::Config),
 }
 
 fn main() -> Result<(), std::io::Error> {
@@ -245,6 +280,7 @@ fn main() -> Result<(), std::io::Error> {
             .logging_config
             .log_verbose_count;
         let rpc_timeout = global_config.rpc_timeout;
+        let format = global_config.output_format;
 
         let connection = |host| async move {
             let mut builder = headers.into_iter().fold(Builder::default(), |builder, kv| {
@@ -308,7 +344,7 @@ fn main() -> Result<(), std::io::Error> {
             Some(Command::Remote(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection(grpc_host).await;
-                if let Err(e) = commands::remote::command(connection, config).await {
+                if let Err(e) = commands::remote::command(connection, config, format).await {
                     eprintln!("{e}");
                     std::process::exit(ReturnCode::Failure as _)
                 }
@@ -331,22 +367,27 @@ fn main() -> Result<(), std::io::Error> {
             }
             Some(Command::Storage(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
-                let connection = connection(grpc_host).await;
-                if let Err(e) = commands::storage::command(connection, config).await {
+                let grpc_connection = connection(grpc_host).await;
+                if let Err(e) =
+                    commands::storage::command(grpc_connection, || connection(http_host), config)
+                        .await
+                {
                     eprintln!("{e}");
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
             Some(Command::Catalog(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
-                if let Err(e) = commands::catalog::command(config).await {
+                if let Err(e) = commands::catalog::command(config, format).await {
                     eprintln!("{e}");
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
             Some(Command::Debug(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
-                if let Err(e) = commands::debug::command(|| connection(grpc_host), config).await {
+                if let Err(e) =
+                    commands::debug::command(|| connection(grpc_host), config, format).await
+                {
                     eprintln!("{e}");
                     std::process::exit(ReturnCode::Failure as _)
                 }
@@ -378,7 +419,7 @@ fn main() -> Result<(), std::io::Error> {
             Some(Command::Namespace(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection(grpc_host).await;
-                if let Err(e) = commands::namespace::command(connection, config).await {
+                if let Err(e) = commands::namespace::command(connection, config, format).await {
                     eprintln!("{e}");
                     std::process::exit(ReturnCode::Failure as _)
                 }
@@ -386,7 +427,31 @@ fn main() -> Result<(), std::io::Error> {
             Some(Command::Table(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection(grpc_host).await;
-                if let Err(e) = commands::table::command(connection, config).await {
+                if let Err(e) = commands::table::command(connection, config, format).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
+            Some(Command::Operation(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection(grpc_host).await;
+                if let Err(e) = commands::operation::command(connection, config, format).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
+            Some(Command::LogFilter(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection(grpc_host).await;
+                if let Err(e) = commands::log_filter::command(connection, config, format).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
+            Some(Command::Top(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection(http_host).await;
+                if let Err(e) = commands::top::command(connection, config).await {
                     eprintln!("{e}");
                     std::process::exit(ReturnCode::Failure as _)
                 }
@@ -570,6 +635,42 @@ where
 mod tests {
     use std::io::Write;
 
+    use clap::{CommandFactory, Parser};
+
+    use super::*;
+
+    #[test]
+    fn verify_cli() {
+        Config::command().debug_assert();
+    }
+
+    #[test]
+    fn storage_and_output_format() {
+        let config = Config::try_parse_from([
+            "influxdb_iox",
+            "storage",
+            "bananas",
+            "read-filter",
+            "--format",
+            "quiet",
+        ])
+        .unwrap();
+        assert_eq!(config.output_format, None);
+
+        let config = Config::try_parse_from([
+            "influxdb_iox",
+            "namespace",
+            "list",
+            "--output-format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.output_format,
+            Some(commands::output::OutputFormat::Json)
+        );
+    }
+
     #[test]
     // ensures that dependabot doesn't update dotenvy until https://github.com/allan2/dotenvy/issues/12 is fixed
     fn dotenvy_regression() {
@@ -54,6 +54,10 @@ pub fn setup_metric_registry() -> Arc<metric::Registry> {
     #[cfg(all(not(feature = "heappy"), feature = "jemalloc_replacing_malloc"))]
     registry.register_instrument("jemalloc_metrics", crate::jemalloc::JemallocMetrics::new);
 
+    // Register per-subsystem heap metrics
+    #[cfg(feature = "heap_tracking")]
+    registry.register_instrument("heap_tracking_metrics", tracker::HeapTrackingMetrics::new);
+
     // Register tokio metric for main runtime
     #[cfg(tokio_unstable)]
     setup_tokio_metrics(
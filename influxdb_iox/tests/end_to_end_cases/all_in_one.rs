@@ -1,5 +1,6 @@
 use arrow_util::assert_batches_sorted_eq;
 use http::StatusCode;
+use influxdb_iox_client::{error::Error, logging::Client as LoggingClient};
 use iox_time::{SystemProvider, TimeProvider};
 use test_helpers_end_to_end::{
     maybe_skip_integration, rand_name, run_sql, write_to_router, ServerFixture, TestConfig,
@@ -104,3 +105,28 @@ async fn ephemeral_mode() {
     ];
     assert_batches_sorted_eq!(&expected, &batches);
 }
+
+#[tokio::test]
+async fn log_filter_api_opt_in() {
+    // this test does not require / use a posgres database
+    test_helpers::maybe_start_logging();
+
+    // The log filter API is not served by default
+    let all_in_one = ServerFixture::create(TestConfig::new_all_in_one(None)).await;
+    let err = LoggingClient::new(all_in_one.router_grpc_connection())
+        .get_log_filter()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Unimplemented(_)), "{err:?}");
+
+    let all_in_one =
+        ServerFixture::create(TestConfig::new_all_in_one(None).with_log_filter_api()).await;
+    let mut client = LoggingClient::new(all_in_one.router_grpc_connection());
+    let got = client
+        .set_log_filter("info,iox_query=debug", None)
+        .await
+        .unwrap();
+    assert_eq!(got.filter, "info,iox_query=debug");
+    let got = client.reset_log_filter().await.unwrap();
+    assert_eq!(got.filter, got.default_filter);
+}
@@ -359,6 +359,58 @@ async fn query_error_handling() {
     .await
 }
 
+/// Test the storage CLI commands, whose `--format` option must not clash with
+/// the global `--output-format` option
+#[tokio::test]
+async fn storage_read_filter() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::WriteLineProtocol("storage_cli,tag=A val=42i 123456".into()),
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let querier_addr = state.cluster().querier().querier_grpc_base().to_string();
+                    let namespace = state.cluster().namespace();
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&querier_addr)
+                        .arg("storage")
+                        .arg(namespace)
+                        .arg("read-filter")
+                        .arg("--format")
+                        .arg("pretty")
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains("_measurement: storage_cli"));
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&querier_addr)
+                        .arg("storage")
+                        .arg(namespace)
+                        .arg("read-filter")
+                        .arg("--format")
+                        .arg("quiet")
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::is_empty());
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
+
 /// Test error handling for the query CLI command for InfluxQL queries
 #[tokio::test]
 async fn influxql_error_handling() {
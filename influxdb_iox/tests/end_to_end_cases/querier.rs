@@ -805,6 +805,8 @@ async fn iox_debug_header() {
                     "+---------------+--------------+------------+------------+",
                     "| table_catalog | table_schema | table_name | table_type |",
                     "+---------------+--------------+------------+------------+",
+                    "| public        | system       | memory     | BASE TABLE |",
+                    "| public        | system       | partitions | BASE TABLE |",
                     "| public        | system       | queries    | BASE TABLE |",
                     "+---------------+--------------+------------+------------+",
                 ],
@@ -834,6 +836,8 @@ async fn iox_debug_header() {
                     "| public        | information_schema | tables      | VIEW       |",
                     "| public        | information_schema | views       | VIEW       |",
                     "| public        | iox                | the_table   | BASE TABLE |",
+                    "| public        | system             | memory      | BASE TABLE |",
+                    "| public        | system             | partitions  | BASE TABLE |",
                     "| public        | system             | queries     | BASE TABLE |",
                     "+---------------+--------------------+-------------+------------+",
                 ],
@@ -6,7 +6,7 @@ mod read_filter;
 mod read_group;
 mod read_window_aggregate;
 
-use crate::query_tests::setups::SETUPS;
+use crate::query_tests::{scenarios::Scenario, setups::SETUPS};
 use async_trait::async_trait;
 use futures::FutureExt;
 use observability_deps::tracing::*;
@@ -64,6 +64,9 @@ pub(crate) fn read_group_data() -> Vec<&'static str> {
 /// under test, runs a setup defined in [`SETUPS`] and specified by the implementation of
 /// `setup_name`, then performs actions and assertions defined by the implementation of
 /// `request_and_assert` with the [`MiniCluster`].
+///
+/// If the setup only writes and persists data, the test is then repeated with the same data laid
+/// out in every [`Scenario`].
 #[async_trait]
 trait InfluxRpcTest: Send + Sync + 'static {
     /// The name of the setup in [`SETUPS`] that should be run on the cluster before running
@@ -87,27 +90,44 @@ trait InfluxRpcTest: Send + Sync + 'static {
         let database_url = maybe_skip_integration!();
         let setup_name = self.setup_name();
 
+        let setup_steps = SETUPS
+            .get(setup_name)
+            .unwrap_or_else(|| panic!("Could not find setup with key `{setup_name}`"));
+
         info!("Using setup {setup_name}");
+        run_setup_and_test(&self, database_url.clone(), setup_steps).await;
 
-        // Set up the cluster  ====================================
-        let mut cluster = MiniCluster::create_shared_never_persist(database_url.clone()).await;
+        for scenario in Scenario::all() {
+            let Some(scenario_steps) = scenario.steps(setup_steps) else {
+                break;
+            };
 
-        let setup_steps = SETUPS
-            .get(setup_name)
-            .unwrap_or_else(|| panic!("Could not find setup with key `{setup_name}`"))
-            .iter();
-
-        let cloned_self = Arc::clone(&self);
-
-        let test_step = Step::Custom(Box::new(move |state: &mut StepTestState| {
-            let cloned_self = Arc::clone(&cloned_self);
-            async move {
-                cloned_self.request_and_assert(state.cluster()).await;
-            }
-            .boxed()
-        }));
-        StepTest::new(&mut cluster, setup_steps.chain(std::iter::once(&test_step)))
-            .run()
-            .await;
+            info!("Using setup {setup_name} in scenario: {scenario}");
+            run_setup_and_test(&self, database_url.clone(), &scenario_steps).await;
+        }
     }
 }
+
+/// Run the `setup_steps` on a new namespace of a shared [`MiniCluster`] that never persists on its
+/// own, followed by a custom step calling `request_and_assert` of `test`.
+async fn run_setup_and_test<T>(test: &Arc<T>, database_url: String, setup_steps: &[Step])
+where
+    T: InfluxRpcTest + ?Sized,
+{
+    let mut cluster = MiniCluster::create_shared_never_persist(database_url).await;
+
+    let cloned_test = Arc::clone(test);
+    let test_step = Step::Custom(Box::new(move |state: &mut StepTestState| {
+        let cloned_test = Arc::clone(&cloned_test);
+        async move {
+            cloned_test.request_and_assert(state.cluster()).await;
+        }
+        .boxed()
+    }));
+    StepTest::new(
+        &mut cluster,
+        setup_steps.iter().chain(std::iter::once(&test_step)),
+    )
+    .run()
+    .await;
+}
@@ -12,7 +12,9 @@ pub(crate) fn dump_data_frames(frames: &[Data]) -> Vec<String> {
 
 fn dump_data(data: &Data) -> String {
     match Some(data) {
-        Some(Data::Series(SeriesFrame { tags, data_type })) => format!(
+        Some(Data::Series(SeriesFrame {
+            tags, data_type, ..
+        })) => format!(
             "SeriesFrame, tags: {}, type: {:?}",
             dump_tags(tags),
             data_type
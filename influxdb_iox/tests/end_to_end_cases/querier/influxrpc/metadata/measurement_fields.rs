@@ -2,7 +2,10 @@ use super::{run_data_test, InfluxRpcTest};
 use async_trait::async_trait;
 use data_types::{MAX_NANO_TIME, MIN_NANO_TIME};
 use futures::{prelude::*, FutureExt};
-use generated_types::measurement_fields_response::{FieldType, MessageField};
+use generated_types::{
+    measurement_fields_response::{FieldType, MessageField},
+    FieldsGroupedByMeasurementResponse,
+};
 use std::sync::Arc;
 use test_helpers_end_to_end::{DataGenerator, GrpcRequestBuilder, MiniCluster, StepTestState};
 
@@ -70,16 +73,22 @@ async fn field_columns_existing_table_with_predicate() {
                 key: "moisture".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 100_000,
+                row_count: Some(3),
+                null_count: Some(2),
             },
             MessageField {
                 key: "other_temp".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 250,
+                row_count: Some(3),
+                null_count: Some(2),
             },
             MessageField {
                 key: "temp".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 100_000,
+                row_count: Some(3),
+                null_count: Some(1),
             },
         ],
     })
@@ -98,16 +107,22 @@ async fn field_columns_timestamp_range_predicate() {
                 key: "moisture".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 100_000,
+                row_count: Some(4),
+                null_count: Some(3),
             },
             MessageField {
                 key: "other_temp".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 350,
+                row_count: Some(4),
+                null_count: Some(2),
             },
             MessageField {
                 key: "temp".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 100_000,
+                row_count: Some(4),
+                null_count: Some(2),
             },
         ],
     })
@@ -126,16 +141,22 @@ async fn field_columns_no_predicate_full_time_range_returns_zero_timestamp() {
                 key: "moisture".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 0,
+                row_count: Some(4),
+                null_count: None,
             },
             MessageField {
                 key: "other_temp".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 0,
+                row_count: Some(4),
+                null_count: None,
             },
             MessageField {
                 key: "temp".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 0,
+                row_count: Some(4),
+                null_count: None,
             },
         ],
     })
@@ -155,6 +176,8 @@ async fn field_columns_tag_and_timestamp_range_predicate() {
             key: "other_temp".into(),
             r#type: FieldType::Float.into(),
             timestamp: 250,
+            row_count: Some(1),
+            null_count: Some(0),
         }],
     })
     .run()
@@ -172,21 +195,29 @@ async fn field_names() {
                 key: "field1".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 1_000,
+                row_count: Some(2),
+                null_count: Some(0),
             },
             MessageField {
                 key: "field2".into(),
                 r#type: FieldType::String.into(),
                 timestamp: 100,
+                row_count: Some(2),
+                null_count: Some(1),
             },
             MessageField {
                 key: "field3".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 100,
+                row_count: Some(2),
+                null_count: Some(1),
             },
             MessageField {
                 key: "field4".into(),
                 r#type: FieldType::Boolean.into(),
                 timestamp: 1_000,
+                row_count: Some(2),
+                null_count: Some(1),
             },
         ],
     })
@@ -204,6 +235,8 @@ async fn list_field_columns_all_time() {
             key: "value".into(),
             r#type: FieldType::Float.into(),
             timestamp: 0,
+            row_count: Some(1),
+            null_count: None,
         }],
     })
     .run()
@@ -223,6 +256,8 @@ async fn list_field_columns_max_time_included() {
             key: "value".into(),
             r#type: FieldType::Float.into(),
             timestamp: MAX_NANO_TIME,
+            row_count: Some(1),
+            null_count: Some(0),
         }],
     })
     .run()
@@ -253,11 +288,72 @@ async fn list_field_columns_with_periods() {
                 key: "field.one".into(),
                 r#type: FieldType::Float.into(),
                 timestamp: 1_609_459_201_000_000_002,
+                row_count: Some(2),
+                null_count: Some(0),
             },
             MessageField {
                 key: "field.two".into(),
                 r#type: FieldType::Boolean.into(),
                 timestamp: 1_609_459_201_000_000_002,
+                row_count: Some(2),
+                null_count: Some(0),
+            },
+        ],
+    })
+    .run()
+    .await;
+}
+
+#[tokio::test]
+async fn fields_grouped_by_measurement() {
+    Arc::new(FieldsGroupedByMeasurementTest {
+        setup_name: "TwoMeasurementsManyFields",
+        request: GrpcRequestBuilder::new().tag_predicate("state", "MA"),
+        expected: vec![
+            FieldsGroupedByMeasurementResponse {
+                measurement: "h2o".into(),
+                fields: vec![
+                    MessageField {
+                        key: "moisture".into(),
+                        r#type: FieldType::Float.into(),
+                        timestamp: 100_000,
+                        row_count: Some(3),
+                        null_count: Some(2),
+                    },
+                    MessageField {
+                        key: "other_temp".into(),
+                        r#type: FieldType::Float.into(),
+                        timestamp: 250,
+                        row_count: Some(3),
+                        null_count: Some(2),
+                    },
+                    MessageField {
+                        key: "temp".into(),
+                        r#type: FieldType::Float.into(),
+                        timestamp: 100_000,
+                        row_count: Some(3),
+                        null_count: Some(1),
+                    },
+                ],
+            },
+            FieldsGroupedByMeasurementResponse {
+                measurement: "o2".into(),
+                fields: vec![
+                    MessageField {
+                        key: "reading".into(),
+                        r#type: FieldType::Float.into(),
+                        timestamp: 50,
+                        row_count: Some(1),
+                        null_count: Some(0),
+                    },
+                    MessageField {
+                        key: "temp".into(),
+                        r#type: FieldType::Float.into(),
+                        timestamp: 50,
+                        row_count: Some(1),
+                        null_count: Some(0),
+                    },
+                ],
             },
         ],
     })
@@ -301,3 +397,38 @@ impl InfluxRpcTest for MeasurementFieldsTest {
         assert_eq!(responses[0].fields, self.expected_fields);
     }
 }
+
+#[derive(Debug)]
+struct FieldsGroupedByMeasurementTest {
+    setup_name: &'static str,
+    request: GrpcRequestBuilder,
+    expected: Vec<FieldsGroupedByMeasurementResponse>,
+}
+
+#[async_trait]
+impl InfluxRpcTest for FieldsGroupedByMeasurementTest {
+    fn setup_name(&self) -> &'static str {
+        self.setup_name
+    }
+
+    async fn request_and_assert(&self, cluster: &MiniCluster) {
+        let mut storage_client = cluster.querier_storage_client();
+
+        let request = self
+            .request
+            .clone()
+            .source(cluster)
+            .build_fields_grouped_by_measurement();
+
+        let responses: Vec<_> = storage_client
+            .fields_grouped_by_measurement(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(responses, self.expected);
+    }
+}
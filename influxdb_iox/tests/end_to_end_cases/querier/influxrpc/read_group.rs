@@ -701,6 +701,55 @@ async fn grouped_series_set_plan_max() {
     .await;
 }
 
+#[tokio::test]
+async fn grouped_series_set_plan_top() {
+    Arc::new(ReadGroupTest {
+        setup_name: "MeasurementForMax",
+        aggregate_type: AggregateType::Top,
+        group_keys: vec!["state"],
+        request: GrpcRequestBuilder::new()
+            .aggregate_n(2)
+            // fiter out first row (ts 1000)
+            .timestamp_range(1001, 4001),
+        expected_results: vec![
+            "GroupFrame, tag_keys: _field,_measurement,city,state, partition_key_vals: MA",
+            "SeriesFrame, tags: _field=b,_measurement=h2o,city=Cambridge,state=MA, type: 3",
+            "BooleanPointsFrame, timestamps: [2000, 3000], values: false,true",
+            "SeriesFrame, tags: _field=f,_measurement=h2o,city=Cambridge,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [2000, 3000], values: \"7,6\"",
+            "SeriesFrame, tags: _field=i,_measurement=h2o,city=Cambridge,state=MA, type: 1",
+            "IntegerPointsFrame, timestamps: [2000, 3000], values: \"7,6\"",
+            "SeriesFrame, tags: _field=s,_measurement=h2o,city=Cambridge,state=MA, type: 4",
+            "StringPointsFrame, timestamps: [2000, 4000], values: d,z",
+        ],
+    })
+    .run()
+    .await;
+}
+
+#[tokio::test]
+async fn grouped_series_set_plan_bottom() {
+    Arc::new(ReadGroupTest {
+        setup_name: "MeasurementForMax",
+        aggregate_type: AggregateType::Bottom,
+        group_keys: vec!["state"],
+        request: GrpcRequestBuilder::new().aggregate_n(2),
+        expected_results: vec![
+            "GroupFrame, tag_keys: _field,_measurement,city,state, partition_key_vals: MA",
+            "SeriesFrame, tags: _field=b,_measurement=h2o,city=Cambridge,state=MA, type: 3",
+            "BooleanPointsFrame, timestamps: [2000, 4000], values: false,false",
+            "SeriesFrame, tags: _field=f,_measurement=h2o,city=Cambridge,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [3000, 4000], values: \"6,5\"",
+            "SeriesFrame, tags: _field=i,_measurement=h2o,city=Cambridge,state=MA, type: 1",
+            "IntegerPointsFrame, timestamps: [3000, 4000], values: \"6,5\"",
+            "SeriesFrame, tags: _field=s,_measurement=h2o,city=Cambridge,state=MA, type: 4",
+            "StringPointsFrame, timestamps: [1000, 3000], values: c,a",
+        ],
+    })
+    .run()
+    .await;
+}
+
 #[tokio::test]
 async fn grouped_series_set_plan_group_by_state_city() {
     Arc::new(ReadGroupTest {
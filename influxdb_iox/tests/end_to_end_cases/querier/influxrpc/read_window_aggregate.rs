@@ -1,7 +1,7 @@
 use super::{dump::dump_data_frames, InfluxRpcTest};
 use async_trait::async_trait;
 use futures::{prelude::*, FutureExt};
-use generated_types::aggregate::AggregateType;
+use generated_types::{aggregate::AggregateType, read_window_aggregate_request::Fill};
 use std::sync::Arc;
 use test_helpers_end_to_end::{
     maybe_skip_integration, GrpcRequestBuilder, MiniCluster, Step, StepTest, StepTestState,
@@ -68,6 +68,52 @@ pub async fn read_window_aggregate_test_with_periods() {
     .await
 }
 
+#[tokio::test]
+pub async fn read_window_aggregate_test_fill() {
+    let input_lines = vec![
+        "h2o,state=MA,city=Boston temp=70.0 100",
+        "h2o,state=MA,city=Boston temp=76.0 700",
+    ];
+    let request_builder = GrpcRequestBuilder::new()
+        .timestamp_range(0, 1000)
+        .window_every(200)
+        .offset(0)
+        .aggregate_type(AggregateType::Mean);
+
+    // windows [200, 800) have no data
+    do_read_window_aggregate_test(
+        input_lines.clone(),
+        request_builder.clone(),
+        vec![
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Boston,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [200, 800], values: \"70,76\"",
+        ],
+    )
+    .await;
+
+    // the value of the last window is used until the end of the range
+    do_read_window_aggregate_test(
+        input_lines.clone(),
+        request_builder.clone().fill(Fill::Previous),
+        vec![
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Boston,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [200, 400, 600, 800, 1000], values: \"70,70,70,76,76\"",
+        ],
+    )
+    .await;
+
+    // only windows between windows with data are interpolated
+    do_read_window_aggregate_test(
+        input_lines,
+        request_builder.fill(Fill::Linear),
+        vec![
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Boston,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [200, 400, 600, 800], values: \"70,72,74,76\"",
+        ],
+    )
+    .await;
+}
+
 /// Sends the specified line protocol to a server, runs a read_window_aggregate
 /// gRPC request, and compares it against expected frames
 async fn do_read_window_aggregate_test(
@@ -189,6 +235,28 @@ async fn nanoseconds_measurement_count() {
     .await;
 }
 
+#[tokio::test]
+async fn nanoseconds_measurement_increase() {
+    Arc::new(ReadWindowAggregateTest {
+        setup_name: "MeasurementForWindowAggregate",
+        aggregate_type: AggregateType::Increase,
+        every: 300,
+        offset: 0,
+        request: GrpcRequestBuilder::new().timestamp_range(100, 450),
+        expected_results: vec![
+            // Cambridge is spread over two parquet files
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Boston,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [300, 600], values: \"1,1\"",
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Cambridge,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [300, 600], values: \"1,1\"",
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=LA,state=CA, type: 0",
+            "FloatPointsFrame, timestamps: [300, 600], values: \"1,1\"",
+        ],
+    })
+    .run()
+    .await;
+}
+
 // See <https://github.com/influxdata/influxdb_iox/issues/2697>
 #[tokio::test]
 async fn min_defect_2697() {
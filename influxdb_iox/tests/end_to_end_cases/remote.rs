@@ -316,3 +316,115 @@ async fn remote_partition_and_get_from_store_and_pull() {
     .run()
     .await
 }
+
+/// Export the chunk of a partition with `remote partition export-chunk`,
+/// resume an interrupted export, and import it into another catalog and
+/// namespace with `remote partition import-chunk`
+#[tokio::test]
+async fn remote_partition_export_import_chunk() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    // The test below assumes a specific partition id, so use a
+    // non-shared one here so concurrent tests don't interfere with
+    // each other
+    let mut cluster = MiniCluster::create_non_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::RecordNumParquetFiles,
+            Step::WriteLineProtocol(String::from(
+                "my_awesome_table,tag1=A,tag2=B val=42i 123456",
+            )),
+            Step::WaitForPersisted {
+                expected_increase: 1,
+            },
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let router_addr = state.cluster().router().router_grpc_base().to_string();
+
+                    let dir = tempdir().unwrap();
+                    let export_dir = dir.path().join("export");
+
+                    let export = || {
+                        Command::cargo_bin("influxdb_iox")
+                            .unwrap()
+                            .arg("-h")
+                            .arg(&router_addr)
+                            .arg("remote")
+                            .arg("partition")
+                            .arg("export-chunk")
+                            .arg("1")
+                            .arg("-o")
+                            .arg(&export_dir)
+                            .assert()
+                            .success()
+                            .stdout(predicate::str::contains("Done."));
+                    };
+                    export();
+
+                    let contents = assert_one_parquet_file_and_meta(&export_dir);
+                    assert_eq!(contents.checksum_files().len(), 1);
+                    let parquet_path = contents.parquet_files()[0].clone();
+                    let parquet_bytes = fs::read(&parquet_path).await.unwrap();
+
+                    // Simulate an interrupted download, which is resumed
+                    let mut partial_path = parquet_path.clone().into_os_string();
+                    partial_path.push(".partial");
+                    fs::remove_file(&parquet_path).await.unwrap();
+                    fs::write(&partial_path, &parquet_bytes[..parquet_bytes.len() / 2])
+                        .await
+                        .unwrap();
+                    export();
+                    assert_eq!(fs::read(&parquet_path).await.unwrap(), parquet_bytes);
+                    assert!(!Path::new(&partial_path).exists());
+
+                    let catalog_dsn = format!(
+                        "sqlite://{}",
+                        dir.path().join("catalog.sqlite").to_str().unwrap()
+                    );
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("catalog")
+                        .arg("setup")
+                        .arg("--catalog-dsn")
+                        .arg(&catalog_dsn)
+                        .assert()
+                        .success();
+
+                    let import = || {
+                        Command::cargo_bin("influxdb_iox")
+                            .unwrap()
+                            .arg("remote")
+                            .arg("partition")
+                            .arg("import-chunk")
+                            .arg("--catalog-dsn")
+                            .arg(&catalog_dsn)
+                            .arg("--object-store")
+                            .arg("file")
+                            .arg("--data-dir")
+                            .arg(dir.path().join("object_store"))
+                            .arg("--namespace")
+                            .arg("imported")
+                            .arg(&export_dir)
+                            .assert()
+                    };
+                    import().success();
+
+                    // A corrupted file is not imported
+                    let mut corrupted = parquet_bytes.clone();
+                    let last = corrupted.len() - 1;
+                    corrupted[last] ^= 0xff;
+                    fs::write(&parquet_path, corrupted).await.unwrap();
+                    import()
+                        .failure()
+                        .stderr(predicate::str::contains("Checksum mismatch"));
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
@@ -1,4 +1,5 @@
 mod cases;
 pub mod framework;
+pub mod scenarios;
 pub mod setups;
 mod sql_errors;
@@ -0,0 +1,274 @@
+//! Generate variants of the [`SETUPS`][super::setups::SETUPS] that lay the same data out
+//! differently, so a test written against one setup is run against every combination of where
+//! the data lives and whether its chunks overlap.
+//!
+//! Only setups made entirely of writes and persistence steps can be rearranged; any other step
+//! (retention changes, compaction, custom steps) may depend on the exact layout of the original
+//! setup, so [`Scenario::steps`] returns `None` for such setups.
+//!
+//! There is no delete support, so scenarios with and without deletes are not generated.
+
+use iox_time::{SystemProvider, TimeProvider};
+use std::{collections::HashSet, fmt::Display};
+use test_helpers_end_to_end::Step;
+
+/// The number of nanoseconds in a day, the width of the default partitions.
+const NANOS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Where the data of a setup is when the test runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChunkLayout {
+    /// All data is buffered in the ingester.
+    Ingester,
+
+    /// All data is persisted to Parquet.
+    Parquet,
+
+    /// The first half of the data is persisted to Parquet, the rest is buffered in the ingester.
+    ParquetAndIngester,
+}
+
+/// How the data of a setup is split between the two chunks it is written as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overlap {
+    /// Each line is written to exactly one chunk.
+    Disjoint,
+
+    /// The second chunk rewrites the lines of the first chunk's second half, so the chunks contain
+    /// duplicate rows that have to be deduplicated.
+    Overlapping,
+}
+
+/// A combination of [`ChunkLayout`] and [`Overlap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Scenario {
+    pub layout: ChunkLayout,
+    pub overlap: Overlap,
+}
+
+impl Display for Scenario {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let layout = match self.layout {
+            ChunkLayout::Ingester => "ingester",
+            ChunkLayout::Parquet => "parquet",
+            ChunkLayout::ParquetAndIngester => "parquet and ingester",
+        };
+        let overlap = match self.overlap {
+            Overlap::Disjoint => "disjoint",
+            Overlap::Overlapping => "overlapping",
+        };
+        write!(f, "{layout}, {overlap} chunks")
+    }
+}
+
+impl Scenario {
+    /// Every combination of [`ChunkLayout`] and [`Overlap`].
+    pub fn all() -> impl Iterator<Item = Self> {
+        [
+            ChunkLayout::Ingester,
+            ChunkLayout::Parquet,
+            ChunkLayout::ParquetAndIngester,
+        ]
+        .into_iter()
+        .flat_map(|layout| {
+            [Overlap::Disjoint, Overlap::Overlapping]
+                .into_iter()
+                .map(move |overlap| Self { layout, overlap })
+        })
+    }
+
+    /// Rearrange the data written by `setup` into this scenario, returning `None` if the setup
+    /// contains steps other than writes and persistence.
+    ///
+    /// The steps are meant to be run on a cluster that never persists on its own, such as
+    /// [`MiniCluster::create_shared_never_persist`][test_helpers_end_to_end::MiniCluster::create_shared_never_persist].
+    pub fn steps(&self, setup: &[Step]) -> Option<Vec<Step>> {
+        let mut lines = vec![];
+        for step in setup {
+            match step {
+                Step::WriteLineProtocol(lp) => lines.extend(
+                    lp.lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with('#')),
+                ),
+                Step::RecordNumParquetFiles | Step::Persist | Step::WaitForPersisted { .. } => {}
+                _ => return None,
+            }
+        }
+        if lines.is_empty() {
+            return None;
+        }
+
+        // Later lines overwrite earlier ones with the same primary key, so every chunk has to
+        // keep the lines in their original order. Rewriting the tail of the lines in the second
+        // chunk does not change the deduplicated result, as the tail already was the latest data.
+        let mid = lines.len() / 2;
+        let (first, second) = match self.overlap {
+            Overlap::Disjoint => (&lines[..mid], &lines[mid..]),
+            Overlap::Overlapping => (&lines[..], &lines[mid..]),
+        };
+
+        let (persist_first, persist_second) = match self.layout {
+            ChunkLayout::Ingester => (false, false),
+            ChunkLayout::Parquet => (true, true),
+            ChunkLayout::ParquetAndIngester => (true, false),
+        };
+
+        let mut steps = vec![];
+        for (chunk, persist) in [(first, persist_first), (second, persist_second)] {
+            if chunk.is_empty() {
+                continue;
+            }
+            if persist {
+                steps.push(Step::RecordNumParquetFiles);
+            }
+            steps.push(Step::WriteLineProtocol(chunk.join("\n")));
+            if persist {
+                steps.push(Step::Persist);
+                steps.push(Step::WaitForPersisted {
+                    expected_increase: num_partitions(chunk),
+                });
+            }
+        }
+
+        Some(steps)
+    }
+}
+
+/// The number of partitions, and so Parquet files persisted, for `lines` with the default
+/// partition template, which partitions each table by day.
+fn num_partitions(lines: &[&str]) -> usize {
+    let now = SystemProvider::new().now().timestamp_nanos();
+
+    lines
+        .iter()
+        .map(|line| {
+            let day = timestamp(line).unwrap_or(now).div_euclid(NANOS_PER_DAY);
+            (measurement(line), day)
+        })
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// The measurement of `line`, up to the first unescaped comma or space.
+fn measurement(line: &str) -> &str {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' | ' ' => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// The timestamp of `line`, if it has one.
+fn timestamp(line: &str) -> Option<i64> {
+    line.rsplit_once(' ')
+        .and_then(|(_, timestamp)| timestamp.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_scenarios() {
+        let scenarios: HashSet<_> = Scenario::all().collect();
+        assert_eq!(scenarios.len(), 6);
+    }
+
+    #[test]
+    fn test_line_parsing() {
+        assert_eq!(measurement("cpu,host=a usage=1 10"), "cpu");
+        assert_eq!(measurement(r"my\ cpu,host=a usage=1 10"), r"my\ cpu");
+        assert_eq!(measurement("cpu usage=1"), "cpu");
+
+        assert_eq!(timestamp("cpu,host=a usage=1 10"), Some(10));
+        assert_eq!(timestamp("cpu,host=a usage=1"), None);
+        assert_eq!(timestamp(r#"cpu,host=a s="a b""#), None);
+
+        assert_eq!(
+            num_partitions(&[
+                "cpu usage=1 10",
+                "cpu usage=2 20",
+                "disk bytes=1 10",
+                &format!("cpu usage=3 {NANOS_PER_DAY}"),
+            ]),
+            3
+        );
+    }
+
+    #[test]
+    fn test_steps() {
+        let setup = [
+            Step::RecordNumParquetFiles,
+            Step::WriteLineProtocol("cpu usage=1 10\ncpu usage=2 20".into()),
+            Step::Persist,
+            Step::WaitForPersisted {
+                expected_increase: 1,
+            },
+            Step::WriteLineProtocol("cpu usage=3 30".into()),
+        ];
+
+        let written = |steps: &[Step]| {
+            steps
+                .iter()
+                .filter_map(|step| match step {
+                    Step::WriteLineProtocol(lp) => Some(lp.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let persists = |steps: &[Step]| {
+            steps
+                .iter()
+                .filter(|step| matches!(step, Step::Persist))
+                .count()
+        };
+
+        let disjoint = Scenario {
+            layout: ChunkLayout::ParquetAndIngester,
+            overlap: Overlap::Disjoint,
+        }
+        .steps(&setup)
+        .unwrap();
+        assert_eq!(
+            written(&disjoint),
+            ["cpu usage=1 10", "cpu usage=2 20\ncpu usage=3 30"]
+        );
+        assert_eq!(persists(&disjoint), 1);
+
+        let overlapping = Scenario {
+            layout: ChunkLayout::Parquet,
+            overlap: Overlap::Overlapping,
+        }
+        .steps(&setup)
+        .unwrap();
+        assert_eq!(
+            written(&overlapping),
+            [
+                "cpu usage=1 10\ncpu usage=2 20\ncpu usage=3 30",
+                "cpu usage=2 20\ncpu usage=3 30"
+            ]
+        );
+        assert_eq!(persists(&overlapping), 2);
+
+        let ingester = Scenario {
+            layout: ChunkLayout::Ingester,
+            overlap: Overlap::Disjoint,
+        }
+        .steps(&setup)
+        .unwrap();
+        assert_eq!(persists(&ingester), 0);
+
+        // setups with other steps can't be rearranged
+        let setup = [
+            Step::WriteLineProtocol("cpu usage=1 10".into()),
+            Step::Compact,
+        ];
+        assert!(Scenario::all().all(|scenario| scenario.steps(&setup).is_none()));
+    }
+}
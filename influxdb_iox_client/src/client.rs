@@ -24,9 +24,18 @@ pub mod health;
 /// Client for the ingester API
 pub mod ingester;
 
+/// Client for the logging API
+pub mod logging;
+
+/// Client for the HTTP metrics endpoint
+pub mod metrics;
+
 /// Client for namespace API
 pub mod namespace;
 
+/// Client for the long running operations API
+pub mod operations;
+
 /// Client for schema API
 pub mod schema;
 
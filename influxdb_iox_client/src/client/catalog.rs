@@ -88,4 +88,41 @@ impl Client {
 
         Ok(response.into_inner().parquet_files)
     }
+
+    /// Get the Parquet files holding the data of a table in an optional time range, and take a
+    /// lease keeping them readable for `lease_duration_seconds` (or the server default if
+    /// `None`).
+    ///
+    /// The lease should be released with [`Self::release_table_manifest`] once the files have
+    /// been read.
+    pub async fn get_table_manifest(
+        &mut self,
+        namespace_name: impl Into<String> + Send,
+        table_name: impl Into<String> + Send,
+        min_time: Option<i64>,
+        max_time: Option<i64>,
+        lease_duration_seconds: Option<u64>,
+    ) -> Result<GetTableManifestResponse, Error> {
+        let response = self
+            .inner
+            .get_table_manifest(GetTableManifestRequest {
+                namespace_name: namespace_name.into(),
+                table_name: table_name.into(),
+                min_time,
+                max_time,
+                lease_duration_seconds: lease_duration_seconds.unwrap_or_default(),
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    /// Release the lease taken by [`Self::get_table_manifest`]
+    pub async fn release_table_manifest(&mut self, lease_id: i64) -> Result<(), Error> {
+        self.inner
+            .release_table_manifest(ReleaseTableManifestRequest { lease_id })
+            .await?;
+
+        Ok(())
+    }
 }
@@ -1,5 +1,7 @@
+use ::generated_types::google::FieldViolation;
+
 use self::generated_types::{compaction_service_client::CompactionServiceClient, *};
-use crate::{connection::Connection, error::Error};
+use crate::{client::operations::IoxOperation, connection::Connection, error::Error};
 use client_util::connection::GrpcConnection;
 
 /// Re-export generated_types
@@ -43,4 +45,87 @@ impl Client {
 
         Ok(response.into_inner().skipped_compaction)
     }
+
+    /// Start moving the persisted data of the namespace `source_namespace`
+    /// into the namespace `target_namespace`, returning the operation
+    /// tracking the merge
+    pub async fn merge_namespace(
+        &mut self,
+        source_namespace: impl Into<String> + Send,
+        target_namespace: impl Into<String> + Send,
+    ) -> Result<IoxOperation, Error> {
+        let response = self
+            .inner
+            .merge_namespace(MergeNamespaceRequest {
+                source_namespace: source_namespace.into(),
+                target_namespace: target_namespace.into(),
+            })
+            .await?;
+
+        Ok(response
+            .into_inner()
+            .operation
+            .ok_or_else(|| FieldViolation::required("operation"))?
+            .try_into()?)
+    }
+
+    /// Freeze the requested partition, preventing writes to and compaction
+    /// of it until it is unfrozen
+    pub async fn freeze_partition(
+        &mut self,
+        partition_id: i64,
+        reason: impl Into<String> + Send,
+    ) -> Result<FrozenPartition, Error> {
+        let response = self
+            .inner
+            .freeze_partition(FreezePartitionRequest {
+                partition_id,
+                reason: reason.into(),
+            })
+            .await?;
+
+        Ok(response
+            .into_inner()
+            .frozen_partition
+            .ok_or_else(|| FieldViolation::required("frozen_partition"))?)
+    }
+
+    /// Unfreeze the requested partition, returning its freeze record if it
+    /// was frozen
+    pub async fn unfreeze_partition(
+        &mut self,
+        partition_id: i64,
+    ) -> Result<Option<FrozenPartition>, Error> {
+        let response = self
+            .inner
+            .unfreeze_partition(UnfreezePartitionRequest { partition_id })
+            .await?;
+
+        Ok(response.into_inner().frozen_partition)
+    }
+
+    /// List all frozen partitions
+    pub async fn frozen_partitions(&mut self) -> Result<Vec<FrozenPartition>, Error> {
+        let response = self
+            .inner
+            .list_frozen_partitions(ListFrozenPartitionsRequest {})
+            .await?;
+
+        Ok(response.into_inner().frozen_partitions)
+    }
+
+    /// Start rewriting the data of a table into another table, returning
+    /// the operation tracking the rewrite
+    pub async fn rewrite_table(
+        &mut self,
+        request: RewriteTableRequest,
+    ) -> Result<IoxOperation, Error> {
+        let response = self.inner.rewrite_table(request).await?;
+
+        Ok(response
+            .into_inner()
+            .operation
+            .ok_or_else(|| FieldViolation::required("operation"))?
+            .try_into()?)
+    }
 }
@@ -1,5 +1,7 @@
-use generated_types::google::{AlreadyExists, FieldViolation, NotFound, PreconditionViolation};
-use std::fmt::Debug;
+use generated_types::google::{
+    AlreadyExists, FieldViolation, NotFound, PreconditionViolation, QuotaFailure,
+};
+use std::{fmt::Debug, time::Duration};
 use thiserror::Error;
 use tonic::{Code, Status};
 
@@ -14,12 +16,15 @@ pub struct ServerError<D> {
     pub message: String,
     /// An optional machine-readable error
     pub details: Option<D>,
+    /// How long the server asked to wait before retrying the request, if it is worth retrying
+    pub retry_delay: Option<Duration>,
 }
 
 fn parse_status<D: ServerErrorDetails>(status: tonic::Status) -> ServerError<D> {
     ServerError {
         message: status.message().to_string(),
         details: D::try_decode(&status),
+        retry_delay: generated_types::google::decode_retry_info(&status),
     }
 }
 
@@ -57,6 +62,12 @@ impl ServerErrorDetails for PreconditionViolation {
     }
 }
 
+impl ServerErrorDetails for QuotaFailure {
+    fn try_decode(status: &tonic::Status) -> Option<Self> {
+        generated_types::google::decode_quota_failure(status).next()
+    }
+}
+
 /// The errors returned by this client
 #[derive(Error, Debug)]
 #[allow(missing_docs)]
@@ -83,7 +94,7 @@ pub enum Error {
     PermissionDenied(ServerError<()>),
 
     #[error("Some resource has been exhausted: {0}")]
-    ResourceExhausted(ServerError<()>),
+    ResourceExhausted(ServerError<QuotaFailure>),
 
     #[error("The system is not in a state required for the operation's execution: {0}")]
     FailedPrecondition(ServerError<PreconditionViolation>),
@@ -146,6 +157,7 @@ impl Error {
         Self::Unknown(ServerError {
             message: message.into(),
             details: None,
+            retry_delay: None,
         })
     }
 
@@ -154,6 +166,7 @@ impl Error {
         Self::Internal(ServerError {
             message: message.into(),
             details: None,
+            retry_delay: None,
         })
     }
 
@@ -176,6 +189,7 @@ impl Error {
                 field: field_name,
                 description,
             }),
+            retry_delay: None,
         })
     }
 }
@@ -137,6 +137,18 @@ impl From<tonic::Status> for Error {
 #[derive(Debug)]
 pub struct Client {
     inner: FlightClient,
+
+    /// Catalog state that queries run against, see [`Client::set_as_of`].
+    as_of: Option<String>,
+
+    /// Fraction of rows that queries sample, see [`Client::set_sample_fraction`].
+    sample_fraction: Option<f64>,
+
+    /// Read the ingester last value caches, see [`Client::set_last_value_cache`].
+    last_value_cache: bool,
+
+    /// Precision of the returned timestamps, see [`Client::set_timestamp_precision`].
+    timestamp_precision: Option<String>,
 }
 
 impl Client {
@@ -159,7 +171,13 @@ impl Client {
             inner.metadata_mut().insert(name, value);
         }
 
-        Self { inner }
+        Self {
+            inner,
+            as_of: None,
+            sample_fraction: None,
+            last_value_cache: false,
+            timestamp_precision: None,
+        }
     }
 
     /// Return the inner arrow flight client
@@ -185,6 +203,34 @@ impl Client {
         Ok(self.inner.add_header(key, value)?)
     }
 
+    /// Run all subsequent queries against the catalog state at `as_of`
+    /// (an RFC3339 timestamp), i.e. only read the parquet files that
+    /// existed at that time. `None` queries the current state.
+    pub fn set_as_of(&mut self, as_of: Option<String>) {
+        self.as_of = as_of;
+    }
+
+    /// Run all subsequent queries on a random sample of this fraction
+    /// (between 0 and 1) of the rows, returning approximate results
+    /// faster. `None` reads all rows.
+    pub fn set_sample_fraction(&mut self, sample_fraction: Option<f64>) {
+        self.sample_fraction = sample_fraction;
+    }
+
+    /// Run all subsequent queries against the last (and, if enabled,
+    /// the first) value of every field of every series held in the
+    /// last value cache of the ingesters, instead of the stored data.
+    pub fn set_last_value_cache(&mut self, last_value_cache: bool) {
+        self.last_value_cache = last_value_cache;
+    }
+
+    /// Return the timestamp columns of all subsequent queries in this
+    /// precision (`s`, `ms`, `us` or `ns`) instead of nanoseconds.
+    /// `None` returns nanoseconds.
+    pub fn set_timestamp_precision(&mut self, timestamp_precision: Option<String>) {
+        self.timestamp_precision = timestamp_precision;
+    }
+
     /// Query the given database with the given SQL query, returning
     /// a struct that can stream Arrow [`RecordBatch`] results.
     pub async fn sql(
@@ -198,6 +244,12 @@ impl Client {
             query_type: QueryType::Sql.into(),
             flightsql_command: vec![],
             is_debug: false,
+            as_of: self.as_of.clone().unwrap_or_default(),
+            sample_fraction: self.sample_fraction.unwrap_or_default(),
+            last_value_cache: self.last_value_cache,
+            timestamp_precision: self.timestamp_precision.clone().unwrap_or_default(),
+            page_size: 0,
+            cursor: String::new(),
         };
 
         self.do_get_with_read_info(request).await
@@ -216,6 +268,12 @@ impl Client {
             query_type: QueryType::InfluxQl.into(),
             flightsql_command: vec![],
             is_debug: false,
+            as_of: self.as_of.clone().unwrap_or_default(),
+            sample_fraction: self.sample_fraction.unwrap_or_default(),
+            last_value_cache: self.last_value_cache,
+            timestamp_precision: self.timestamp_precision.clone().unwrap_or_default(),
+            page_size: 0,
+            cursor: String::new(),
         };
 
         self.do_get_with_read_info(request).await
@@ -1,6 +1,8 @@
 use self::generated_types::{persist_service_client::PersistServiceClient, *};
 use crate::{connection::Connection, error::Error};
 use client_util::connection::GrpcConnection;
+use futures_util::stream::BoxStream;
+use tonic::Status;
 
 /// Re-export generated_types
 pub mod generated_types {
@@ -29,4 +31,20 @@ impl Client {
 
         Ok(())
     }
+
+    /// Stream an event for each Parquet file persisted by the ingester from now on, optionally
+    /// limited to the namespace named `namespace`.
+    pub async fn watch_persist_events(
+        &mut self,
+        namespace: Option<String>,
+    ) -> Result<BoxStream<'static, Result<PersistEvent, Status>>, Error> {
+        let response = self
+            .inner
+            .watch_persist_events(WatchPersistEventsRequest {
+                namespace: namespace.unwrap_or_default(),
+            })
+            .await?;
+
+        Ok(Box::pin(response.into_inner()))
+    }
 }
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use ::generated_types::google::OptionalField;
+
+use self::generated_types::{logging_service_client::LoggingServiceClient, *};
+use crate::{connection::Connection, error::Error};
+use client_util::connection::GrpcConnection;
+
+/// Re-export generated_types
+pub mod generated_types {
+    pub use generated_types::influxdata::iox::logging::v1::*;
+}
+
+/// A basic client for inspecting and changing the log filter of a running
+/// server.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: LoggingServiceClient<GrpcConnection>,
+}
+
+impl Client {
+    /// Creates a new client with the provided connection
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            inner: LoggingServiceClient::new(connection.into_grpc_connection()),
+        }
+    }
+
+    /// Get the active log filter
+    pub async fn get_log_filter(&mut self) -> Result<LogFilter, Error> {
+        let response = self.inner.get_log_filter(GetLogFilterRequest {}).await?;
+
+        Ok(response
+            .into_inner()
+            .log_filter
+            .unwrap_field("log_filter")?)
+    }
+
+    /// Replace the log filter with `filter`, reverting to the default filter
+    /// after `expiry` elapses (if specified)
+    pub async fn set_log_filter(
+        &mut self,
+        filter: impl Into<String> + Send,
+        expiry: Option<Duration>,
+    ) -> Result<LogFilter, Error> {
+        let response = self
+            .inner
+            .set_log_filter(SetLogFilterRequest {
+                filter: filter.into(),
+                expiry_seconds: expiry.map(|d| d.as_secs().max(1)),
+            })
+            .await?;
+
+        Ok(response
+            .into_inner()
+            .log_filter
+            .unwrap_field("log_filter")?)
+    }
+
+    /// Restore the log filter the server was started with
+    pub async fn reset_log_filter(&mut self) -> Result<LogFilter, Error> {
+        let response = self
+            .inner
+            .reset_log_filter(ResetLogFilterRequest {})
+            .await?;
+
+        Ok(response
+            .into_inner()
+            .log_filter
+            .unwrap_field("log_filter")?)
+    }
+}
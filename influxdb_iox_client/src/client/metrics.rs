@@ -0,0 +1,67 @@
+use client_util::connection::HttpConnection;
+use reqwest::Method;
+
+use crate::{
+    connection::Connection,
+    error::{translate_response, Error},
+};
+
+/// A client for fetching the Prometheus metrics exposed by an IOx server
+/// on its HTTP `/metrics` endpoint.
+///
+/// ```no_run
+/// #[tokio::main]
+/// # async fn main() {
+/// use influxdb_iox_client::{
+///     metrics::Client,
+///     connection::Builder,
+/// };
+///
+/// let connection = Builder::default()
+///     .build("http://127.0.0.1:8080")
+///     .await
+///     .unwrap();
+///
+/// let client = Client::new(connection);
+///
+/// // fetch the metrics in the Prometheus text exposition format
+/// let metrics = client.get_metrics().await.expect("failed to fetch metrics");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: HttpConnection,
+}
+
+impl Client {
+    /// Creates a new client with the provided connection
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            inner: connection.into_http_connection(),
+        }
+    }
+
+    /// Fetch the current value of all metrics, in the Prometheus text
+    /// exposition format
+    pub async fn get_metrics(&self) -> Result<String, Error> {
+        let metrics_url = format!("{}metrics", self.inner.uri());
+
+        let response = self
+            .inner
+            .client()
+            .request(Method::GET, &metrics_url)
+            .send()
+            .await
+            .map_err(Error::client)?;
+
+        let status = response.status();
+        if status.is_success() {
+            return response.text().await.map_err(Error::client);
+        }
+
+        // `translate_response` always returns an error for non-success
+        // statuses, including the response body in the message
+        translate_response(response).await?;
+        Err(Error::unknown(format!("unexpected status {status}")))
+    }
+}
@@ -55,12 +55,67 @@ impl Client {
                 retention_period_ns,
                 partition_template,
                 service_protection_limits,
+                template: None,
             })
             .await?;
 
         Ok(response.into_inner().namespace.unwrap_field("namespace")?)
     }
 
+    /// Create a namespace from the namespace template `template`.
+    ///
+    /// The retention period, service protection limits and partition template
+    /// of the template are used unless specified here.
+    pub async fn create_namespace_from_template(
+        &mut self,
+        namespace: &str,
+        template: &str,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<ServiceProtectionLimits>,
+        partition_template: Option<PartitionTemplate>,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .create_namespace(CreateNamespaceRequest {
+                name: namespace.to_string(),
+                retention_period_ns,
+                partition_template,
+                service_protection_limits,
+                template: Some(template.to_string()),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Check the settings of a namespace without creating it.
+    ///
+    /// The arguments are those of [`Self::create_namespace_from_template`],
+    /// with `template` being optional.
+    pub async fn validate_namespace(
+        &mut self,
+        namespace: &str,
+        template: Option<&str>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<ServiceProtectionLimits>,
+        partition_template: Option<PartitionTemplate>,
+    ) -> Result<ValidateNamespaceResponse, Error> {
+        let response = self
+            .inner
+            .validate_namespace(ValidateNamespaceRequest {
+                namespace: Some(CreateNamespaceRequest {
+                    name: namespace.to_string(),
+                    retention_period_ns,
+                    partition_template,
+                    service_protection_limits,
+                    template: template.map(ToString::to_string),
+                }),
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
     /// Update retention for a namespace
     ///
     /// `retention_period_ns` is the the retention period in nanoseconds,
@@ -118,4 +173,50 @@ impl Client {
 
         Ok(())
     }
+
+    /// Get the namespace templates
+    pub async fn get_namespace_templates(&mut self) -> Result<Vec<NamespaceTemplate>, Error> {
+        let response = self
+            .inner
+            .get_namespace_templates(GetNamespaceTemplatesRequest {})
+            .await?;
+
+        Ok(response.into_inner().templates)
+    }
+
+    /// Create a namespace template
+    pub async fn create_namespace_template(
+        &mut self,
+        template: NamespaceTemplate,
+    ) -> Result<NamespaceTemplate, Error> {
+        let response = self
+            .inner
+            .create_namespace_template(CreateNamespaceTemplateRequest {
+                template: Some(template),
+            })
+            .await?;
+
+        Ok(response.into_inner().template.unwrap_field("template")?)
+    }
+
+    /// Replace the settings of the namespace template named `template.name`.
+    ///
+    /// If `propagate` is true, the new retention period and service protection
+    /// limits are also applied to the namespaces created from the template,
+    /// which are returned.
+    pub async fn update_namespace_template(
+        &mut self,
+        template: NamespaceTemplate,
+        propagate: bool,
+    ) -> Result<UpdateNamespaceTemplateResponse, Error> {
+        let response = self
+            .inner
+            .update_namespace_template(UpdateNamespaceTemplateRequest {
+                template: Some(template),
+                propagate,
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
 }
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use generated_types::google::FieldViolation;
+use prost::Message;
+
+use self::generated_types::{operations_client::OperationsClient, *};
+use crate::{connection::Connection, error::Error};
+use client_util::connection::GrpcConnection;
+
+/// Re-export generated_types
+pub mod generated_types {
+    pub use generated_types::google::longrunning::*;
+    pub use generated_types::influxdata::iox::operations::v1::{
+        operation_metadata::Job, OperationMetadata,
+    };
+}
+
+/// An [`Operation`] with its metadata decoded
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoxOperation {
+    /// The operation as returned by the server
+    pub operation: Operation,
+
+    /// The decoded `metadata` of the operation
+    pub metadata: OperationMetadata,
+}
+
+impl TryFrom<Operation> for IoxOperation {
+    type Error = FieldViolation;
+
+    fn try_from(operation: Operation) -> Result<Self, Self::Error> {
+        let metadata = operation
+            .metadata
+            .as_ref()
+            .ok_or_else(|| FieldViolation::required("metadata"))?;
+
+        let metadata =
+            OperationMetadata::decode(metadata.value.clone()).map_err(|e| FieldViolation {
+                field: "metadata".to_string(),
+                description: e.to_string(),
+            })?;
+
+        Ok(Self {
+            operation,
+            metadata,
+        })
+    }
+}
+
+/// A basic client for listing, inspecting and cancelling the background
+/// jobs of a server.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: OperationsClient<GrpcConnection>,
+}
+
+impl Client {
+    /// Creates a new client with the provided connection
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            inner: OperationsClient::new(connection.into_grpc_connection()),
+        }
+    }
+
+    /// List the running and recently completed operations
+    pub async fn list_operations(&mut self) -> Result<Vec<IoxOperation>, Error> {
+        let response = self
+            .inner
+            .list_operations(ListOperationsRequest::default())
+            .await?;
+
+        Ok(response
+            .into_inner()
+            .operations
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Get the current state of the operation with ID `id`
+    pub async fn get_operation(&mut self, id: usize) -> Result<IoxOperation, Error> {
+        let response = self
+            .inner
+            .get_operation(GetOperationRequest {
+                name: id.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().try_into()?)
+    }
+
+    /// Request cancellation of the operation with ID `id`
+    pub async fn cancel_operation(&mut self, id: usize) -> Result<(), Error> {
+        self.inner
+            .cancel_operation(CancelOperationRequest {
+                name: id.to_string(),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Wait for the operation with ID `id` to complete, or for `timeout` to
+    /// elapse, and return its state
+    pub async fn wait_operation(
+        &mut self,
+        id: usize,
+        timeout: Option<Duration>,
+    ) -> Result<IoxOperation, Error> {
+        let response = self
+            .inner
+            .wait_operation(WaitOperationRequest {
+                name: id.to_string(),
+                timeout: timeout.map(Into::into),
+            })
+            .await?;
+
+        Ok(response.into_inner().try_into()?)
+    }
+}
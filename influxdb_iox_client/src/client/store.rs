@@ -31,10 +31,25 @@ impl Client {
         &mut self,
         uuid: String,
     ) -> Result<BoxStream<'static, Result<GetParquetFileByObjectStoreIdResponse, Status>>, Error>
+    {
+        self.get_parquet_file_by_object_store_id_from_offset(uuid, 0)
+            .await
+    }
+
+    /// Get the parquet file data by its object store uuid, starting at byte
+    /// `offset` of the file
+    pub async fn get_parquet_file_by_object_store_id_from_offset(
+        &mut self,
+        uuid: String,
+        offset: u64,
+    ) -> Result<BoxStream<'static, Result<GetParquetFileByObjectStoreIdResponse, Status>>, Error>
     {
         let response = self
             .inner
-            .get_parquet_file_by_object_store_id(GetParquetFileByObjectStoreIdRequest { uuid })
+            .get_parquet_file_by_object_store_id(GetParquetFileByObjectStoreIdRequest {
+                uuid,
+                offset,
+            })
             .await?;
 
         Ok(Box::pin(response.into_inner()))
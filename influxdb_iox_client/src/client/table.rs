@@ -57,4 +57,80 @@ impl Client {
 
         Ok(response.into_inner().table.unwrap_field("table")?)
     }
+
+    /// Rename a table
+    pub async fn rename_table(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        new_name: &str,
+    ) -> Result<Table, Error> {
+        let response = self
+            .inner
+            .rename_table(RenameTableRequest {
+                namespace: namespace.to_string(),
+                name: table.to_string(),
+                new_name: new_name.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().table.unwrap_field("table")?)
+    }
+
+    /// Update the retention period of a table, or remove it with [`None`]
+    pub async fn update_table_retention(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table, Error> {
+        let response = self
+            .inner
+            .update_table_retention(UpdateTableRetentionRequest {
+                namespace: namespace.to_string(),
+                name: table.to_string(),
+                retention_period_ns,
+            })
+            .await?;
+
+        Ok(response.into_inner().table.unwrap_field("table")?)
+    }
+
+    /// Rename a column of a table
+    pub async fn rename_column(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        name: &str,
+        new_name: &str,
+    ) -> Result<(), Error> {
+        self.inner
+            .rename_column(RenameColumnRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                name: name.to_string(),
+                new_name: new_name.to_string(),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drop a field column of a table
+    pub async fn drop_column(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        self.inner
+            .drop_column(DropColumnRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                name: name.to_string(),
+            })
+            .await?;
+
+        Ok(())
+    }
 }
@@ -12,6 +12,34 @@ use reqwest::{Body, Method};
 /// The default value for the maximum size of each request, in bytes
 pub const DEFAULT_MAX_REQUEST_PAYLOAD_SIZE_BYTES: Option<usize> = Some(1024 * 1024);
 
+/// Precision of the timestamps of written line protocol.
+///
+/// The server converts the timestamps to nanoseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Precision {
+    /// Seconds since the epoch.
+    Seconds,
+    /// Milliseconds since the epoch.
+    Milliseconds,
+    /// Microseconds since the epoch.
+    Microseconds,
+    /// Nanoseconds since the epoch.
+    #[default]
+    Nanoseconds,
+}
+
+impl Precision {
+    /// The value of the `precision` parameter of the write API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Seconds => "s",
+            Self::Milliseconds => "ms",
+            Self::Microseconds => "us",
+            Self::Nanoseconds => "ns",
+        }
+    }
+}
+
 /// An IOx Write API client.
 ///
 /// ```no_run
@@ -58,6 +86,10 @@ pub struct Client {
 
     /// Makes this many concurrent requests at a time. Defaults to 1
     max_concurrent_uploads: NonZeroUsize,
+
+    /// Precision of the timestamps of the written line protocol.
+    /// Defaults to nanoseconds.
+    precision: Precision,
 }
 
 impl Client {
@@ -72,6 +104,7 @@ impl Client {
             inner,
             max_request_payload_size_bytes: DEFAULT_MAX_REQUEST_PAYLOAD_SIZE_BYTES,
             max_concurrent_uploads: NonZeroUsize::new(1).unwrap(),
+            precision: Precision::default(),
         }
     }
 
@@ -98,6 +131,12 @@ impl Client {
         }
     }
 
+    /// Interpret the timestamps of the written line protocol in the
+    /// given precision. Defaults to nanoseconds.
+    pub fn with_precision(self, precision: Precision) -> Self {
+        Self { precision, ..self }
+    }
+
     /// Write the [LineProtocol] formatted string in `lp_data` to
     /// namespace `namespace`.
     ///
@@ -136,6 +175,7 @@ impl Client {
 
         let max_concurrent_uploads: usize = self.max_concurrent_uploads.into();
         let max_request_payload_size_bytes = self.max_request_payload_size_bytes;
+        let precision = self.precision;
 
         // make a stream and process in parallel
         let results = sources
@@ -153,9 +193,11 @@ impl Client {
                 let bucket_id = bucket_id.to_string();
                 let inner = Arc::clone(&self.inner);
 
-                tokio::task::spawn(
-                    async move { inner.write_source(org_id, bucket_id, source).await },
-                )
+                tokio::task::spawn(async move {
+                    inner
+                        .write_source(org_id, bucket_id, precision, source)
+                        .await
+                })
             })
             // Do the uploads in parallel
             .buffered(max_concurrent_uploads)
@@ -174,14 +216,16 @@ impl Client {
 /// Something that knows how to send http data. Exists so it can be
 /// mocked out for testing
 trait RequestMaker: Debug + Send + Sync {
-    /// Write the body data to the specified org, bucket, and
-    /// returning the number of bytes written
+    /// Write the body data with timestamps of the given precision to
+    /// the specified org, bucket, and returning the number of bytes
+    /// written
     ///
     /// (this is implemented manually to avoid `async_trait`)
     fn write_source(
         &self,
         org_id: String,
         bucket_id: String,
+        precision: Precision,
         body: String,
     ) -> BoxFuture<'_, Result<usize, Error>>;
 }
@@ -191,6 +235,7 @@ impl RequestMaker for HttpConnection {
         &self,
         org_id: String,
         bucket_id: String,
+        precision: Precision,
         body: String,
     ) -> BoxFuture<'_, Result<usize, Error>> {
         let write_url = format!("{}api/v2/write", self.uri());
@@ -204,6 +249,7 @@ impl RequestMaker for HttpConnection {
                 .client()
                 .request(Method::POST, &write_url)
                 .query(&[("bucket", bucket_id), ("org", org_id)])
+                .query(&[("precision", precision.as_str())])
                 .body(body)
                 .send()
                 .await
@@ -312,6 +358,7 @@ mod tests {
         let expected = vec![MockRequest {
             org_id: "orgname".into(),
             bucket_id: "bucketname".into(),
+            precision: Precision::Nanoseconds,
             body: data.into(),
         }];
 
@@ -323,6 +370,28 @@ mod tests {
         assert_eq!(num_bytes, 11);
     }
 
+    #[tokio::test]
+    async fn test_precision() {
+        let mock = Arc::new(MockRequestMaker::new());
+
+        let namespace = "orgname_bucketname";
+        let data = "m,t=foo f=4 1690000000";
+
+        let expected = vec![MockRequest {
+            org_id: "orgname".into(),
+            bucket_id: "bucketname".into(),
+            precision: Precision::Seconds,
+            body: data.into(),
+        }];
+
+        Client::new_with_maker(Arc::clone(&mock) as _)
+            .with_precision(Precision::Seconds)
+            .write_lp(namespace, data)
+            .await
+            .unwrap();
+        assert_eq!(expected, mock.requests());
+    }
+
     #[tokio::test]
     async fn test_max_request_payload_size() {
         let mock = Arc::new(MockRequestMaker::new());
@@ -337,11 +406,13 @@ mod tests {
             MockRequest {
                 org_id: "orgname".into(),
                 bucket_id: "bucketname".into(),
+                precision: Precision::Nanoseconds,
                 body: "m,t=foo f=4\nm,t=bar f=3".into(),
             },
             MockRequest {
                 org_id: "orgname".into(),
                 bucket_id: "bucketname".into(),
+                precision: Precision::Nanoseconds,
                 body: "m,t=fooddddddd f=4".into(),
             },
         ];
@@ -372,11 +443,13 @@ mod tests {
             MockRequest {
                 org_id: "orgname".into(),
                 bucket_id: "bucketname".into(),
+                precision: Precision::Nanoseconds,
                 body: "m,t=foo f=4".into(),
             },
             MockRequest {
                 org_id: "orgname".into(),
                 bucket_id: "bucketname".into(),
+                precision: Precision::Nanoseconds,
                 body: "m,t=bar f=3".into(),
             },
         ];
@@ -393,6 +466,7 @@ mod tests {
     struct MockRequest {
         org_id: String,
         bucket_id: String,
+        precision: Precision,
         body: String,
     }
 
@@ -419,6 +493,7 @@ mod tests {
             &self,
             org_id: String,
             bucket_id: String,
+            precision: Precision,
             body: String,
         ) -> BoxFuture<'_, Result<usize, Error>> {
             let sz = body.len();
@@ -426,6 +501,7 @@ mod tests {
             self.requests.lock().unwrap().push(MockRequest {
                 org_id,
                 bucket_id,
+                precision,
                 body,
             });
 
@@ -70,6 +70,9 @@
 pub mod builder;
 pub use builder::LineProtocolBuilder;
 
+pub mod series_key;
+pub use series_key::{series_key, SeriesKeyEscaping};
+
 use fmt::Display;
 use log::debug;
 use nom::{
@@ -83,13 +86,7 @@ use nom::{
 use smallvec::SmallVec;
 use snafu::{ResultExt, Snafu};
 use std::cmp::Ordering;
-use std::{
-    borrow::Cow,
-    char,
-    collections::{btree_map::Entry, BTreeMap},
-    fmt,
-    ops::Deref,
-};
+use std::{borrow::Cow, char, fmt, ops::Deref};
 
 /// Parsing errors that describe how a particular line is invalid line protocol.
 #[derive(Debug, Snafu)]
@@ -288,49 +285,36 @@ impl<'a> Display for Series<'a> {
 }
 
 impl<'a> Series<'a> {
+    /// Returns the canonical series key of this series with
+    /// [`SeriesKeyEscaping::Canonical`] escaping.
+    ///
+    /// See [`series_key()`] for the canonicalization rules.
     pub fn generate_base(self) -> Result<Cow<'a, str>> {
-        match (!self.is_escaped(), self.is_sorted_and_unique()) {
-            (true, true) => Ok(self.raw_input.into()),
-            (_, true) => Ok(self.generate_base_with_escaping().into()),
-            (_, _) => self
-                .generate_base_with_escaping_sorting_deduplicating()
-                .map(Into::into),
-        }
-    }
-
-    fn generate_base_with_escaping(self) -> String {
-        let mut series_base = self.measurement.to_string();
-        for (tag_key, tag_value) in self.tag_set.unwrap_or_default() {
-            use std::fmt::Write;
-            write!(&mut series_base, ",{tag_key}={tag_value}").expect("Could not append string");
+        let tags = self.tag_set.iter().flatten();
+        if self.is_sorted_and_unique()
+            && !self.is_escaped()
+            && SeriesKeyEscaping::Canonical.is_verbatim(
+                self.measurement.as_str(),
+                tags.map(|(tag_key, tag_value)| (tag_key.as_str(), tag_value.as_str())),
+            )
+        {
+            return Ok(self.raw_input.into());
         }
-        series_base
+        self.series_key(SeriesKeyEscaping::Canonical)
+            .map(Into::into)
     }
 
-    fn generate_base_with_escaping_sorting_deduplicating(self) -> Result<String> {
-        let mut unique_sorted_tag_set = BTreeMap::new();
-        for (tag_key, tag_value) in self.tag_set.unwrap_or_default() {
-            match unique_sorted_tag_set.entry(tag_key) {
-                Entry::Vacant(e) => {
-                    e.insert(tag_value);
-                }
-                Entry::Occupied(e) => {
-                    let (tag_key, _) = e.remove_entry();
-                    return DuplicateTagSnafu {
-                        tag_key: tag_key.to_string(),
-                    }
-                    .fail();
-                }
-            }
-        }
-
-        let mut series_base = self.measurement.to_string();
-        for (tag_key, tag_value) in unique_sorted_tag_set {
-            use std::fmt::Write;
-            write!(&mut series_base, ",{tag_key}={tag_value}").expect("Could not append string");
-        }
-
-        Ok(series_base)
+    /// Returns the canonical series key of this series, escaped according to
+    /// `escaping`.
+    ///
+    /// See [`series_key()`] for the canonicalization rules.
+    pub fn series_key(&self, escaping: SeriesKeyEscaping) -> Result<String> {
+        let tags = self.tag_set.iter().flatten();
+        series_key(
+            self.measurement.as_str(),
+            tags.map(|(tag_key, tag_value)| (tag_key.as_str(), tag_value.as_str())),
+            escaping,
+        )
     }
 
     fn is_escaped(&self) -> bool {
@@ -1772,6 +1756,25 @@ mod test {
         assert_eq!(series.generate_base().unwrap(), "foo,tag1=1,tag2=2");
     }
 
+    #[test]
+    fn parse_tag_set_escaping_is_canonical() {
+        // The same series, with and without redundant escaping and sorting.
+        let inputs = [
+            r"foo,tag1=a\ b,tag2=c\=d",
+            r"foo,tag2=c=d,tag1=a\ b",
+            r"foo,tag2=c\=d,tag1=a\ b",
+        ];
+        for input in inputs {
+            let (remaining, series) = series(input).unwrap();
+            assert!(remaining.is_empty());
+            assert_eq!(
+                series.generate_base().unwrap(),
+                r"foo,tag1=a\ b,tag2=c\=d",
+                "input: {input}"
+            );
+        }
+    }
+
     #[test]
     fn parse_tag_set_duplicate_tags() {
         let input = "foo,tag=1,tag=2";
@@ -0,0 +1,183 @@
+//! Canonical series keys.
+//!
+//! A series key identifies a series by its measurement and tag set, written
+//! in line protocol syntax:
+//!
+//! ```text
+//! measurement,tag_key_1=tag_value_1,tag_key_2=tag_value_2
+//! ```
+//!
+//! The same series can be written in many ways in line protocol - tags may
+//! appear in any order and characters may be escaped even when they need not
+//! be. [`series_key()`] maps all of these to a single canonical key:
+//!
+//! 1. Tags are ordered by the bytes of their (unescaped) key.
+//! 2. A tag key appearing more than once is an error.
+//! 3. Measurement, tag keys and tag values are escaped according to the
+//!    [`SeriesKeyEscaping`] rules, and nothing else is escaped.
+
+use std::fmt::Write;
+
+use crate::{DuplicateTagSnafu, Result};
+
+/// The escaping rules of a series key.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesKeyEscaping {
+    /// Escape `,`, ` ` and `\` in measurements, and `,`, `=`, ` ` and `\` in
+    /// tag keys and values.
+    ///
+    /// Escaping backslashes makes the key unambiguous: parsing it as line
+    /// protocol yields the original measurement and tags.
+    #[default]
+    Canonical,
+
+    /// Escape `,` and ` ` in measurements, and `,`, `=` and ` ` in tag keys
+    /// and values, leaving backslashes as they are.
+    ///
+    /// This matches the series keys of InfluxDB 1.x, and the escaping of the
+    /// [`Display`](std::fmt::Display) implementation of
+    /// [`Series`](crate::Series).
+    V1Compat,
+}
+
+impl SeriesKeyEscaping {
+    fn measurement_delimiters(&self) -> &'static [char] {
+        match self {
+            Self::Canonical => &[',', ' ', '\\'],
+            Self::V1Compat => &[',', ' '],
+        }
+    }
+
+    fn tag_delimiters(&self) -> &'static [char] {
+        match self {
+            Self::Canonical => &[',', '=', ' ', '\\'],
+            Self::V1Compat => &[',', '=', ' '],
+        }
+    }
+
+    /// Returns true if a series key with these rules contains `measurement`
+    /// and `tags` verbatim, i.e. none of them contain a character that needs
+    /// escaping.
+    pub(crate) fn is_verbatim<'a>(
+        &self,
+        measurement: &str,
+        mut tags: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> bool {
+        !measurement.contains(self.measurement_delimiters())
+            && tags.all(|(key, value)| {
+                !key.contains(self.tag_delimiters()) && !value.contains(self.tag_delimiters())
+            })
+    }
+}
+
+/// Build the canonical series key of `measurement` and the (unescaped) `tags`
+/// with the given `escaping` rules.
+///
+/// Returns [`Error::DuplicateTag`](crate::Error::DuplicateTag) if `tags`
+/// contains the same key more than once.
+pub fn series_key<'a>(
+    measurement: &str,
+    tags: impl IntoIterator<Item = (&'a str, &'a str)>,
+    escaping: SeriesKeyEscaping,
+) -> Result<String> {
+    let mut tags = tags.into_iter().collect::<Vec<_>>();
+    tags.sort_unstable_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+    if let Some(w) = tags.windows(2).find(|w| w[0].0 == w[1].0) {
+        return DuplicateTagSnafu { tag_key: w[0].0 }.fail();
+    }
+
+    let mut key = String::with_capacity(
+        measurement.len()
+            + tags
+                .iter()
+                .map(|(k, v)| k.len() + v.len() + 2)
+                .sum::<usize>(),
+    );
+    push_escaped(&mut key, measurement, escaping.measurement_delimiters());
+    for (tag_key, tag_value) in tags {
+        key.push(',');
+        push_escaped(&mut key, tag_key, escaping.tag_delimiters());
+        key.push('=');
+        push_escaped(&mut key, tag_value, escaping.tag_delimiters());
+    }
+
+    Ok(key)
+}
+
+fn push_escaped(buf: &mut String, value: &str, delimiters: &[char]) {
+    let mut last = 0;
+    for (idx, delim) in value.match_indices(delimiters) {
+        write!(buf, r"{}\{delim}", &value[last..idx]).expect("writing to a string");
+        last = idx + delim.len();
+    }
+    buf.push_str(&value[last..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::series;
+
+    #[test]
+    fn test_sorted_tags() {
+        let key = series_key(
+            "cpu",
+            [("region", "west"), ("host", "a"), ("Zone", "1")],
+            SeriesKeyEscaping::Canonical,
+        )
+        .unwrap();
+        assert_eq!(key, "cpu,Zone=1,host=a,region=west");
+    }
+
+    #[test]
+    fn test_duplicate_tags() {
+        let err = series_key(
+            "cpu",
+            [("host", "a"), ("region", "west"), ("host", "b")],
+            SeriesKeyEscaping::Canonical,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Must not contain duplicate tags, but "host" was repeated"#
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        let measurement = r"c p,u\";
+        let tags = [("ho=st", r"a,b c\d"), ("region", "x=y")];
+
+        let key = series_key(measurement, tags, SeriesKeyEscaping::Canonical).unwrap();
+        assert_eq!(key, r"c\ p\,u\\,ho\=st=a\,b\ c\\d,region=x\=y");
+
+        let key = series_key(measurement, tags, SeriesKeyEscaping::V1Compat).unwrap();
+        assert_eq!(key, r"c\ p\,u\,ho\=st=a\,b\ c\d,region=x\=y");
+    }
+
+    /// Parsing a canonical series key as line protocol yields the original
+    /// measurement and tags, and the same key.
+    #[test]
+    fn test_round_trip() {
+        let key = series_key(
+            r"c p,u\x",
+            [("ho=st", r"a,b c\d"), ("region", "x=y")],
+            SeriesKeyEscaping::Canonical,
+        )
+        .unwrap();
+
+        let (remaining, parsed) = series(&key).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(parsed.measurement, r"c p,u\x");
+        let tags = parsed.tag_set.as_ref().unwrap();
+        assert_eq!(tags[0].0, "ho=st");
+        assert_eq!(tags[0].1, r"a,b c\d");
+        assert_eq!(tags[1].0, "region");
+        assert_eq!(tags[1].1, "x=y");
+
+        assert_eq!(
+            parsed.series_key(SeriesKeyEscaping::Canonical).unwrap(),
+            key
+        );
+    }
+}
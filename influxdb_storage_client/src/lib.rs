@@ -332,13 +332,51 @@ impl Client {
         Ok(responses)
     }
 
-    /// Extract the data frames from the list of ReadResponse
+    /// Make a request to query::fields_grouped_by_measurement and do the
+    /// required async dance to flatten the resulting stream to Strings
+    pub async fn fields_grouped_by_measurement(
+        &mut self,
+        request: FieldsGroupedByMeasurementRequest,
+    ) -> Result<Vec<String>, tonic::Status> {
+        let request = request.log_trace("fields_grouped_by_measurement request");
+        let responses: Vec<_> = self
+            .inner
+            .fields_grouped_by_measurement(request)
+            .await?
+            .into_inner()
+            .try_collect::<Vec<_>>()
+            .await
+            .log_trace("fields_grouped_by_measurement response")?
+            .into_iter()
+            .flat_map(|r| {
+                let measurement = r.measurement;
+                r.fields.into_iter().map(move |message_field| {
+                    format!(
+                        "measurement: {}, key: {}, type: {}, timestamp: {}",
+                        measurement,
+                        message_field.key,
+                        message_field.r#type,
+                        message_field.timestamp
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(responses)
+    }
+
+    /// Extract the data frames from the list of ReadResponse, restoring any
+    /// tags omitted from series frames
     fn collect_data(responses: Vec<ReadResponse>) -> Vec<read_response::frame::Data> {
-        responses
+        let mut frames = responses
             .into_iter()
             .flat_map(|r| r.frames)
             .flat_map(|f| f.data)
-            .collect()
+            .collect::<Vec<_>>();
+
+        expand_shared_tag_prefixes(&mut frames);
+
+        frames
     }
 
     /// Convert the StringValueResponses into rust Strings, sorting the
@@ -368,6 +406,25 @@ pub fn tag_key_bytes_to_strings(bytes: Vec<u8>) -> String {
     }
 }
 
+/// Restores the leading tags omitted from series frames encoded with
+/// [`SeriesFrameEncoding::SharedTagPrefix`], so every series frame contains
+/// all of its tags. Frames using the full encoding are left unchanged.
+pub fn expand_shared_tag_prefixes(frames: &mut [read_response::frame::Data]) {
+    let mut previous: Vec<Tag> = vec![];
+    for frame in frames {
+        if let read_response::frame::Data::Series(series) = frame {
+            let shared = (series.shared_tag_prefix as usize).min(previous.len());
+            if shared > 0 {
+                let mut tags = previous[..shared].to_vec();
+                tags.append(&mut series.tags);
+                series.tags = tags;
+            }
+            series.shared_tag_prefix = 0;
+            previous.clone_from(&series.tags);
+        }
+    }
+}
+
 /// Logs the specific item
 trait Loggable {
     fn log_trace(self, msg: &'static str) -> Self;
@@ -0,0 +1,250 @@
+//! This module has logic to translate SQL-like arithmetic over aggregates of
+//! fields into RPC aggregate expressions.
+use generated_types::{
+    aggregate::AggregateType as RPCAggregateType,
+    aggregate_expression_node::{
+        FieldAggregate as RPCFieldAggregate, Operator as RPCOperator, Value as RPCValue,
+    },
+    Aggregate as RPCAggregate, AggregateExpression as RPCAggregateExpression,
+    AggregateExpressionNode as RPCAggregateExpressionNode,
+};
+use snafu::{ResultExt, Snafu};
+use sqlparser::{
+    ast::{BinaryOperator, Expr, FunctionArg, FunctionArgExpr, UnaryOperator, Value},
+    parser::Parser,
+    tokenizer::Tokenizer,
+};
+
+/// Parse Error
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("unable to tokenize '{}': {}", expr, source))]
+    TokenizeError {
+        expr: String,
+        source: sqlparser::tokenizer::TokenizerError,
+    },
+
+    #[snafu(display("unable to parse '{:?}' ", source))]
+    ExprParseError {
+        source: sqlparser::parser::ParserError,
+    },
+
+    #[snafu(display("unable to parse '{}' into numerical value", value))]
+    NumericalParseError { value: String },
+
+    #[snafu(display("unsupported aggregate '{}'", name))]
+    UnsupportedAggregate { name: String },
+
+    #[snafu(display("aggregate '{}' requires a single field argument", name))]
+    InvalidAggregateArguments { name: String },
+
+    #[snafu(display("unexpected expression type: '{:?}'", expr))]
+    UnexpectedExprType { expr: Expr },
+
+    #[snafu(display("unexpected operator: '{:?}'", op))]
+    UnexpectedBinaryOperator { op: BinaryOperator },
+}
+
+/// Result type for the aggregate expression parser
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Parses and then converts a SQL expression to an InfluxRPC aggregate
+/// expression, which calculates the field `name`.
+///
+/// Expects expressions like the following:
+///
+///   * sum(bytes_total) / sum(requests)
+///   * 100 * (max("usage") - min("usage"))
+///   * mean(temp) * 1.8 + 32
+///
+/// Notes:
+///
+///   * Field names can be optionally surrounded in double quotes.
+///   * Supported aggregates: sum, count, mean, min, max, rate, derivative,
+///     non_negative_derivative, increase.
+///   * Supported operators: `+`, `-`, `*` and `/`. Use parentheses to denote
+///     precedence.
+///   * All numbers are floats.
+pub fn expr_to_rpc_aggregate_expression(name: &str, expr: &str) -> Result<RPCAggregateExpression> {
+    let dialect = sqlparser::dialect::PostgreSqlDialect {};
+    let mut tokenizer = Tokenizer::new(&dialect, expr);
+    let tokens = tokenizer.tokenize().context(TokenizeSnafu { expr })?;
+    let mut parser = Parser::new(&dialect).with_tokens(tokens);
+
+    Ok(RPCAggregateExpression {
+        name: name.to_owned(),
+        expr: Some(build_node(&parser.parse_expr().context(ExprParseSnafu)?)?),
+    })
+}
+
+fn build_node(expr: &Expr) -> Result<RPCAggregateExpressionNode> {
+    match expr {
+        Expr::Nested(expr) => build_node(expr),
+        Expr::Value(Value::Number(n, _)) => make_literal(parse_number(n)?),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => match &**expr {
+            Expr::Value(Value::Number(n, _)) => make_literal(-parse_number(n)?),
+            _ => UnexpectedExprTypeSnafu {
+                expr: expr.as_ref().clone(),
+            }
+            .fail(),
+        },
+        Expr::Function(function) => {
+            let name = function.name.to_string().to_lowercase();
+            let aggregate_type = match name.as_str() {
+                "sum" => RPCAggregateType::Sum,
+                "count" => RPCAggregateType::Count,
+                "mean" => RPCAggregateType::Mean,
+                "min" => RPCAggregateType::Min,
+                "max" => RPCAggregateType::Max,
+                "rate" => RPCAggregateType::Rate,
+                "derivative" => RPCAggregateType::Derivative,
+                "non_negative_derivative" => RPCAggregateType::NonNegativeDerivative,
+                "increase" => RPCAggregateType::Increase,
+                _ => return UnsupportedAggregateSnafu { name }.fail(),
+            };
+
+            let field = match function.args.as_slice() {
+                [FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(field)))] => {
+                    field.value.clone()
+                }
+                _ => return InvalidAggregateArgumentsSnafu { name }.fail(),
+            };
+
+            Ok(RPCAggregateExpressionNode {
+                value: Some(RPCValue::FieldAggregate(RPCFieldAggregate {
+                    aggregate: Some(RPCAggregate {
+                        r#type: aggregate_type as i32,
+                        n: 0,
+                    }),
+                    field,
+                })),
+                children: vec![],
+            })
+        }
+        Expr::BinaryOp { left, op, right } => {
+            let operator = match op {
+                BinaryOperator::Plus => RPCOperator::Add,
+                BinaryOperator::Minus => RPCOperator::Subtract,
+                BinaryOperator::Multiply => RPCOperator::Multiply,
+                BinaryOperator::Divide => RPCOperator::Divide,
+                _ => return UnexpectedBinaryOperatorSnafu { op: op.clone() }.fail(),
+            };
+
+            Ok(RPCAggregateExpressionNode {
+                value: Some(RPCValue::Operator(operator as i32)),
+                children: vec![build_node(left)?, build_node(right)?],
+            })
+        }
+        _ => UnexpectedExprTypeSnafu {
+            expr: expr.to_owned(),
+        }
+        .fail(),
+    }
+}
+
+fn parse_number(number: &str) -> Result<f64> {
+    number
+        .parse::<f64>()
+        .map_err(|_| Error::NumericalParseError {
+            value: number.to_owned(),
+        })
+}
+
+fn make_literal(value: f64) -> Result<RPCAggregateExpressionNode> {
+    Ok(RPCAggregateExpressionNode {
+        value: Some(RPCValue::FloatValue(value)),
+        children: vec![],
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn field_aggregate(
+        aggregate_type: RPCAggregateType,
+        field: &str,
+    ) -> RPCAggregateExpressionNode {
+        RPCAggregateExpressionNode {
+            value: Some(RPCValue::FieldAggregate(RPCFieldAggregate {
+                aggregate: Some(RPCAggregate {
+                    r#type: aggregate_type as i32,
+                    n: 0,
+                }),
+                field: field.to_owned(),
+            })),
+            children: vec![],
+        }
+    }
+
+    fn binary(
+        operator: RPCOperator,
+        left: RPCAggregateExpressionNode,
+        right: RPCAggregateExpressionNode,
+    ) -> RPCAggregateExpressionNode {
+        RPCAggregateExpressionNode {
+            value: Some(RPCValue::Operator(operator as i32)),
+            children: vec![left, right],
+        }
+    }
+
+    #[test]
+    fn test_expr_to_rpc_aggregate_expression() {
+        let got =
+            expr_to_rpc_aggregate_expression("ratio", "sum(bytes) / SUM(\"requests\")").unwrap();
+        let expected = RPCAggregateExpression {
+            name: "ratio".to_owned(),
+            expr: Some(binary(
+                RPCOperator::Divide,
+                field_aggregate(RPCAggregateType::Sum, "bytes"),
+                field_aggregate(RPCAggregateType::Sum, "requests"),
+            )),
+        };
+        assert_eq!(got, expected);
+
+        // precedence
+        let got = expr_to_rpc_aggregate_expression("f", "mean(temp) * 1.8 + -32").unwrap();
+        let expected = binary(
+            RPCOperator::Add,
+            binary(
+                RPCOperator::Multiply,
+                field_aggregate(RPCAggregateType::Mean, "temp"),
+                make_literal(1.8).unwrap(),
+            ),
+            make_literal(-32.0).unwrap(),
+        );
+        assert_eq!(got.expr, Some(expected));
+
+        let got = expr_to_rpc_aggregate_expression("d", "2 * (max(u) - min(u))").unwrap();
+        let expected = binary(
+            RPCOperator::Multiply,
+            make_literal(2.0).unwrap(),
+            binary(
+                RPCOperator::Subtract,
+                field_aggregate(RPCAggregateType::Max, "u"),
+                field_aggregate(RPCAggregateType::Min, "u"),
+            ),
+        );
+        assert_eq!(got.expr, Some(expected));
+    }
+
+    #[test]
+    fn test_expr_to_rpc_aggregate_expression_errors() {
+        let err = expr_to_rpc_aggregate_expression("f", "first(bytes)").unwrap_err();
+        assert_eq!(err.to_string(), "unsupported aggregate 'first'");
+
+        let err = expr_to_rpc_aggregate_expression("f", "sum(bytes, requests)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "aggregate 'sum' requires a single field argument"
+        );
+
+        let err = expr_to_rpc_aggregate_expression("f", "sum(bytes) % 2").unwrap_err();
+        assert_eq!(err.to_string(), "unexpected operator: 'Modulo'");
+
+        assert!(expr_to_rpc_aggregate_expression("f", "bytes + 1").is_err());
+    }
+}
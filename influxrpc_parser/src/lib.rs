@@ -17,4 +17,5 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+pub mod aggregate_expr;
 pub mod predicate;
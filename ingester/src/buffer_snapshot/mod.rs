@@ -0,0 +1,887 @@
+//! Crash-consistent snapshots of buffered, unpersisted partition data.
+//!
+//! At startup, an ingester replays its WAL to rebuild the buffer, decoding
+//! every write and applying it to the buffer tree. For high-throughput
+//! ingesters, this dominates the time taken to restart.
+//!
+//! When enabled, the buffered data of each partition is periodically written
+//! to a [`BufferSnapshotDir`] in Arrow IPC format, alongside the
+//! [`SequenceNumberSet`] of the writes it was built from. When loaded during
+//! WAL replay, the first WAL write covered by a partition snapshot is replaced
+//! by the snapshot data, and the remaining writes it covers are skipped without
+//! being decoded.
+//!
+//! # Consistency
+//!
+//! The WAL remains the source of truth - a snapshot that is missing, partial
+//! or unreadable causes the writes it covers to be replayed from the WAL. To
+//! ensure snapshots never contain data the WAL does not:
+//!
+//! * Each snapshot is written to a temporary directory that is renamed into
+//!   place once all files are written and synced, so a crash mid-snapshot
+//!   leaves the previous snapshot intact.
+//! * Partitions with data being persisted are not snapshotted, so loading a
+//!   snapshot never orders buffered writes before persisting writes.
+//! * The snapshot file of a partition is deleted when the partition persists
+//!   ([`SnapshotInvalidator`]), so persisted data is not loaded again.
+//! * A snapshot is deleted once loaded at startup, as [`SequenceNumber`]
+//!   values are not stable across ingester restarts.
+//!
+//! # Encryption
+//!
+//! Snapshot files contain raw buffered data. If the WAL is encrypted, the
+//! same keys are used to encrypt the snapshot files
+//! ([`BufferSnapshotDir::with_encryption_keys()`]).
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Cursor, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use arrow::{
+    array::{Array, AsArray},
+    datatypes::{DataType, Float64Type, Int64Type, TimestampNanosecondType, UInt64Type},
+    error::ArrowError,
+    ipc::{reader::FileReader, writer::FileWriter},
+    record_batch::RecordBatch,
+};
+use data_types::{
+    sequence_number_set::SequenceNumberSet, NamespaceId, PartitionKey, SequenceNumber, TableId,
+    TransitionPartitionId,
+};
+use hashbrown::HashSet;
+use mutable_batch::{writer::Writer, MutableBatch};
+use object_store_encryption::{decrypt, encrypt, is_encrypted, FormatError, KeyProvider};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use schema::{InfluxColumnType, InfluxFieldType, Schema};
+use thiserror::Error;
+
+mod observer;
+mod task;
+
+pub(crate) use observer::*;
+pub(crate) use task::*;
+
+/// The schema metadata key of the [`NamespaceId`] of a partition snapshot.
+const NAMESPACE_ID_KEY: &str = "iox::ingester::snapshot::namespace_id";
+/// The schema metadata key of the [`TableId`] of a partition snapshot.
+const TABLE_ID_KEY: &str = "iox::ingester::snapshot::table_id";
+/// The schema metadata key of the [`PartitionKey`] of a partition snapshot.
+const PARTITION_KEY_KEY: &str = "iox::ingester::snapshot::partition_key";
+/// The schema metadata key of the [`SequenceNumberSet`] of a partition
+/// snapshot.
+const SEQUENCE_NUMBERS_KEY: &str = "iox::ingester::snapshot::sequence_numbers";
+
+/// The file extension of partition snapshot files.
+const SNAPSHOT_FILE_EXTENSION: &str = "arrow";
+/// The directory extension of incomplete snapshots.
+const TMP_EXTENSION: &str = "tmp";
+
+/// Configuration of periodic buffer snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BufferSnapshotConfig {
+    /// The directory snapshots are stored in.
+    ///
+    /// Each ingester instance must have its own directory, separate from its
+    /// WAL directory.
+    pub directory: PathBuf,
+
+    /// The duration of time between snapshots.
+    pub period: Duration,
+}
+
+/// Errors reading or writing buffer snapshots.
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    /// An I/O error accessing the snapshot directory.
+    #[error("buffer snapshot i/o error for {}: {source}", path.display())]
+    Io {
+        /// The path being accessed.
+        path: PathBuf,
+        /// The underlying error.
+        source: std::io::Error,
+    },
+
+    /// An error encoding or decoding a snapshot file.
+    #[error("buffer snapshot arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+
+    /// The snapshot file does not contain a valid IOx schema.
+    #[error("invalid buffer snapshot schema: {0}")]
+    Schema(#[from] schema::Error),
+
+    /// The snapshot data could not be converted into a [`MutableBatch`].
+    #[error("failed to buffer snapshot data: {0}")]
+    Buffer(#[from] mutable_batch::writer::Error),
+
+    /// A snapshot file is missing, or has an invalid, metadata `key`.
+    #[error("buffer snapshot file has missing or invalid metadata {0}")]
+    Metadata(&'static str),
+
+    /// A snapshot file could not be encrypted or decrypted.
+    #[error("buffer snapshot encryption error: {0}")]
+    Encryption(#[from] FormatError),
+
+    /// A snapshot file is encrypted, but no encryption keys are configured.
+    #[error("buffer snapshot file is encrypted, but no encryption keys are configured")]
+    MissingEncryptionKeys,
+}
+
+impl SnapshotError {
+    fn io(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { path, source }
+    }
+}
+
+/// The buffered data of a single partition.
+#[derive(Debug)]
+pub(crate) struct PartitionSnapshot {
+    pub(crate) namespace_id: NamespaceId,
+    pub(crate) table_id: TableId,
+    pub(crate) partition_id: TransitionPartitionId,
+    pub(crate) partition_key: PartitionKey,
+    pub(crate) sequence_numbers: SequenceNumberSet,
+    pub(crate) batch: RecordBatch,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The generation of the most recently completed snapshot, if any.
+    current: Option<u64>,
+
+    /// The names of the partition files invalidated while a snapshot is being
+    /// written, if a snapshot is being written.
+    pending_invalidations: Option<HashSet<String>>,
+}
+
+/// A directory of buffer snapshots.
+///
+/// Each snapshot is a generation directory containing one Arrow IPC file per
+/// partition, named by the [`TransitionPartitionId`] of the partition. Only
+/// the most recent generation is retained.
+#[derive(Debug)]
+pub(crate) struct BufferSnapshotDir {
+    root: PathBuf,
+    state: Mutex<State>,
+
+    /// The keys used to encrypt and decrypt partition files, if any.
+    keys: Option<Arc<dyn KeyProvider>>,
+}
+
+impl BufferSnapshotDir {
+    /// Open (creating if necessary) the snapshot directory at `root`,
+    /// discarding any incomplete snapshots.
+    pub(crate) fn new(root: impl Into<PathBuf>) -> Result<Self, SnapshotError> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(SnapshotError::io(&root))?;
+
+        let mut generations = Vec::new();
+        for entry in std::fs::read_dir(&root).map_err(SnapshotError::io(&root))? {
+            let path = entry.map_err(SnapshotError::io(&root))?.path();
+            match parse_generation(&path) {
+                Some(generation) => generations.push(generation),
+                None => {
+                    info!(path=%path.display(), "removing incomplete buffer snapshot");
+                    remove_path(&path)?;
+                }
+            }
+        }
+
+        // Only the most recent complete generation is retained.
+        generations.sort_unstable();
+        let current = generations.pop();
+        let dir = Self {
+            root,
+            state: Mutex::new(State {
+                current,
+                pending_invalidations: None,
+            }),
+            keys: None,
+        };
+        for generation in generations {
+            remove_path(&dir.generation_path(generation))?;
+        }
+
+        Ok(dir)
+    }
+
+    /// Encrypt snapshot files written from now on with the current key of
+    /// `keys`, and decrypt encrypted snapshot files when loaded.
+    pub(crate) fn with_encryption_keys(self, keys: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            keys: Some(keys),
+            ..self
+        }
+    }
+
+    fn generation_path(&self, generation: u64) -> PathBuf {
+        self.root.join(format!("{generation:020}"))
+    }
+
+    /// Atomically replace the current snapshot with one containing
+    /// `partitions`.
+    ///
+    /// This call performs blocking I/O.
+    pub(crate) fn write(&self, partitions: &[PartitionSnapshot]) -> Result<(), SnapshotError> {
+        let generation = {
+            let mut state = self.state.lock();
+            assert!(
+                state.pending_invalidations.is_none(),
+                "concurrent buffer snapshot writes"
+            );
+            state.pending_invalidations = Some(HashSet::new());
+            state.current.map(|v| v + 1).unwrap_or_default()
+        };
+
+        let tmp_path = self
+            .generation_path(generation)
+            .with_extension(TMP_EXTENSION);
+        if let Err(e) = write_generation(&tmp_path, partitions, self.keys.as_deref()) {
+            self.state.lock().pending_invalidations = None;
+            // Best-effort removal, the directory is cleaned up on startup
+            // otherwise.
+            let _ = std::fs::remove_dir_all(&tmp_path);
+            return Err(e);
+        }
+
+        // Move the snapshot into place, removing the files of partitions that
+        // were persisted while it was being written.
+        let path = self.generation_path(generation);
+        let previous = {
+            let mut state = self.state.lock();
+            let invalidated = state
+                .pending_invalidations
+                .take()
+                .expect("buffer snapshot write in progress");
+
+            std::fs::rename(&tmp_path, &path).map_err(SnapshotError::io(&path))?;
+            sync_dir(&self.root)?;
+
+            for name in invalidated {
+                remove_path(&path.join(name))?;
+            }
+
+            state.current.replace(generation)
+        };
+
+        if let Some(previous) = previous {
+            remove_path(&self.generation_path(previous))?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete the snapshot of the partition identified by `partition_id`, if
+    /// any.
+    pub(crate) fn invalidate(&self, partition_id: &TransitionPartitionId) {
+        let name = file_name(partition_id);
+        let mut state = self.state.lock();
+
+        if let Some(pending) = &mut state.pending_invalidations {
+            pending.insert(name.clone());
+        }
+
+        if let Some(generation) = state.current {
+            if let Err(e) = remove_path(&self.generation_path(generation).join(&name)) {
+                // The partition data MUST NOT be loaded from this snapshot
+                // again, so discard it entirely.
+                error!(error=%e, %partition_id, "failed to invalidate partition buffer snapshot");
+                if let Err(e) = remove_path(&self.generation_path(generation)) {
+                    error!(error=%e, "failed to remove buffer snapshot");
+                    return;
+                }
+                state.current = None;
+            }
+        }
+    }
+
+    /// Load and remove the current snapshot, if any.
+    ///
+    /// Snapshot files that cannot be read are skipped, and the writes they
+    /// contain are replayed from the WAL instead.
+    pub(crate) fn take(&self) -> Result<LoadedSnapshot, SnapshotError> {
+        let mut loaded = LoadedSnapshot::default();
+
+        let Some(generation) = self.state.lock().current.take() else {
+            return Ok(loaded);
+        };
+        let path = self.generation_path(generation);
+
+        for entry in std::fs::read_dir(&path).map_err(SnapshotError::io(&path))? {
+            let file = entry.map_err(SnapshotError::io(&path))?.path();
+            match read_partition(&file, self.keys.as_deref()) {
+                Ok((table_id, partition_key, partition)) => {
+                    loaded
+                        .partitions
+                        .entry(table_id)
+                        .or_default()
+                        .insert(partition_key, partition);
+                }
+                Err(e) => {
+                    warn!(error=%e, path=%file.display(), "skipping unreadable partition buffer snapshot");
+                }
+            }
+        }
+
+        remove_path(&path)?;
+
+        info!(
+            n_partitions = loaded.partitions.values().map(|v| v.len()).sum::<usize>(),
+            "loaded buffer snapshot"
+        );
+
+        Ok(loaded)
+    }
+}
+
+/// The result of looking up a WAL write in a [`LoadedSnapshot`].
+#[derive(Debug)]
+pub(crate) enum SnapshotCoverage {
+    /// The write is not contained in the snapshot, and must be replayed.
+    Uncovered,
+    /// The write is contained in the snapshot data of the partition, which has
+    /// already been returned.
+    Covered,
+    /// The write is contained in the snapshot data of the partition, and is
+    /// the first such write - the snapshot data must be applied in its place.
+    CoveredFirst(SequenceNumber, MutableBatch),
+}
+
+#[derive(Debug)]
+struct LoadedPartition {
+    sequence_numbers: SequenceNumberSet,
+    /// The snapshot data, until returned by [`LoadedSnapshot::coverage()`].
+    data: Option<MutableBatch>,
+}
+
+/// A buffer snapshot loaded into memory for WAL replay.
+#[derive(Debug, Default)]
+pub(crate) struct LoadedSnapshot {
+    partitions: HashMap<TableId, HashMap<PartitionKey, LoadedPartition>>,
+}
+
+impl LoadedSnapshot {
+    /// Returns the [`SnapshotCoverage`] of the WAL write of `sequence_number`
+    /// to the `table_id` partition identified by `partition_key`.
+    pub(crate) fn coverage(
+        &mut self,
+        table_id: TableId,
+        partition_key: &PartitionKey,
+        sequence_number: SequenceNumber,
+    ) -> SnapshotCoverage {
+        let Some(partition) = self
+            .partitions
+            .get_mut(&table_id)
+            .and_then(|v| v.get_mut(partition_key))
+        else {
+            return SnapshotCoverage::Uncovered;
+        };
+
+        if !partition.sequence_numbers.contains(sequence_number) {
+            return SnapshotCoverage::Uncovered;
+        }
+
+        match partition.data.take() {
+            Some(data) => SnapshotCoverage::CoveredFirst(
+                partition
+                    .sequence_numbers
+                    .iter()
+                    .max()
+                    .expect("snapshot contains sequence_number"),
+                data,
+            ),
+            None => SnapshotCoverage::Covered,
+        }
+    }
+}
+
+fn file_name(partition_id: &TransitionPartitionId) -> String {
+    format!("{partition_id}.{SNAPSHOT_FILE_EXTENSION}")
+}
+
+/// Returns the generation of the complete snapshot at `path`, or [`None`] if
+/// `path` is not a complete snapshot.
+fn parse_generation(path: &Path) -> Option<u64> {
+    if !path.is_dir() || path.extension().is_some() {
+        return None;
+    }
+    path.file_name()?.to_str()?.parse().ok()
+}
+
+fn remove_path(path: &Path) -> Result<(), SnapshotError> {
+    let res = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+
+    match res {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(SnapshotError::io(path)(e)),
+    }
+}
+
+fn sync_dir(path: &Path) -> Result<(), SnapshotError> {
+    File::open(path)
+        .and_then(|f| f.sync_all())
+        .map_err(SnapshotError::io(path))
+}
+
+fn write_generation(
+    path: &Path,
+    partitions: &[PartitionSnapshot],
+    keys: Option<&dyn KeyProvider>,
+) -> Result<(), SnapshotError> {
+    std::fs::create_dir_all(path).map_err(SnapshotError::io(path))?;
+    for partition in partitions {
+        write_partition(path, partition, keys)?;
+    }
+    sync_dir(path)
+}
+
+fn write_partition(
+    dir: &Path,
+    partition: &PartitionSnapshot,
+    keys: Option<&dyn KeyProvider>,
+) -> Result<(), SnapshotError> {
+    let mut metadata = partition.batch.schema().metadata().clone();
+    metadata.extend([
+        (
+            NAMESPACE_ID_KEY.to_string(),
+            partition.namespace_id.get().to_string(),
+        ),
+        (
+            TABLE_ID_KEY.to_string(),
+            partition.table_id.get().to_string(),
+        ),
+        (
+            PARTITION_KEY_KEY.to_string(),
+            partition.partition_key.inner().to_string(),
+        ),
+        (
+            SEQUENCE_NUMBERS_KEY.to_string(),
+            encode_sequence_numbers(&partition.sequence_numbers),
+        ),
+    ]);
+    let schema = Arc::new(
+        partition
+            .batch
+            .schema()
+            .as_ref()
+            .clone()
+            .with_metadata(metadata),
+    );
+    let batch = partition.batch.clone().with_schema(Arc::clone(&schema))?;
+
+    let mut writer = FileWriter::try_new(Vec::new(), &schema)?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    let mut data = writer.into_inner()?;
+    if let Some(keys) = keys {
+        data = encrypt(keys, &data)?;
+    }
+
+    let path = dir.join(file_name(&partition.partition_id));
+    File::create(&path)
+        .and_then(|mut f| {
+            f.write_all(&data)?;
+            f.sync_all()
+        })
+        .map_err(SnapshotError::io(&path))
+}
+
+fn read_partition(
+    path: &Path,
+    keys: Option<&dyn KeyProvider>,
+) -> Result<(TableId, PartitionKey, LoadedPartition), SnapshotError> {
+    let mut data = std::fs::read(path).map_err(SnapshotError::io(path))?;
+    if is_encrypted(&data) {
+        let keys = keys.ok_or(SnapshotError::MissingEncryptionKeys)?;
+        data = decrypt(keys, &data)?;
+    }
+    let reader = FileReader::try_new(Cursor::new(data), None)?;
+
+    let schema = reader.schema();
+    let metadata = schema.metadata();
+    let get = |key: &'static str| {
+        metadata
+            .get(key)
+            .map(String::as_str)
+            .ok_or(SnapshotError::Metadata(key))
+    };
+    let parse_id = |key: &'static str| {
+        get(key)?
+            .parse::<i64>()
+            .map_err(|_| SnapshotError::Metadata(key))
+    };
+
+    // Validate the namespace ID is present, though it is only needed to
+    // construct the replayed write.
+    parse_id(NAMESPACE_ID_KEY)?;
+    let table_id = TableId::new(parse_id(TABLE_ID_KEY)?);
+    let partition_key = PartitionKey::from(get(PARTITION_KEY_KEY)?);
+    let sequence_numbers = decode_sequence_numbers(get(SEQUENCE_NUMBERS_KEY)?)
+        .filter(|v| !v.is_empty())
+        .ok_or(SnapshotError::Metadata(SEQUENCE_NUMBERS_KEY))?;
+
+    let mut data = MutableBatch::new();
+    for batch in reader {
+        write_record_batch(&mut data, &batch?)?;
+    }
+
+    Ok((
+        table_id,
+        partition_key,
+        LoadedPartition {
+            sequence_numbers,
+            data: Some(data),
+        },
+    ))
+}
+
+/// Append the rows of `batch` to `mb`, using the IOx column types in the
+/// schema of `batch`.
+fn write_record_batch(mb: &mut MutableBatch, batch: &RecordBatch) -> Result<(), SnapshotError> {
+    let schema = Schema::try_from(batch.schema())?;
+    let mut writer = Writer::new(mb, batch.num_rows());
+
+    for (idx, column) in batch.columns().iter().enumerate() {
+        let (influx_type, field) = schema.field(idx);
+        let name = field.name();
+
+        match influx_type {
+            InfluxColumnType::Tag => {
+                let column = arrow::compute::cast(column, &DataType::Utf8)?;
+                let values = column.as_string::<i32>();
+                let mask = column.nulls().map(|v| v.inner().sliced());
+                writer.write_tag(name, mask.as_deref(), values.iter().flatten())?;
+            }
+            InfluxColumnType::Field(InfluxFieldType::Float) => {
+                let values = column.as_primitive::<Float64Type>();
+                let mask = column.nulls().map(|v| v.inner().sliced());
+                writer.write_f64(name, mask.as_deref(), values.iter().flatten())?;
+            }
+            InfluxColumnType::Field(InfluxFieldType::Integer) => {
+                let values = column.as_primitive::<Int64Type>();
+                let mask = column.nulls().map(|v| v.inner().sliced());
+                writer.write_i64(name, mask.as_deref(), values.iter().flatten())?;
+            }
+            InfluxColumnType::Field(InfluxFieldType::UInteger) => {
+                let values = column.as_primitive::<UInt64Type>();
+                let mask = column.nulls().map(|v| v.inner().sliced());
+                writer.write_u64(name, mask.as_deref(), values.iter().flatten())?;
+            }
+            InfluxColumnType::Field(InfluxFieldType::String) => {
+                let values = column.as_string::<i32>();
+                let mask = column.nulls().map(|v| v.inner().sliced());
+                writer.write_string(name, mask.as_deref(), values.iter().flatten())?;
+            }
+            InfluxColumnType::Field(InfluxFieldType::Boolean) => {
+                let values = column.as_boolean();
+                let mask = column.nulls().map(|v| v.inner().sliced());
+                writer.write_bool(name, mask.as_deref(), values.iter().flatten())?;
+            }
+            InfluxColumnType::Timestamp => {
+                let values = column.as_primitive::<TimestampNanosecondType>();
+                writer.write_time(name, values.values().iter().copied())?;
+            }
+        }
+    }
+
+    writer.commit();
+    Ok(())
+}
+
+/// Encode `set` as a comma separated list of inclusive ranges, such as
+/// `1-5,7,9-10`.
+fn encode_sequence_numbers(set: &SequenceNumberSet) -> String {
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for n in set.iter().map(|v| v.get()) {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == n => *end = n,
+            _ => ranges.push((n, n)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| match start == end {
+            true => start.to_string(),
+            false => format!("{start}-{end}"),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Decode a set encoded by [`encode_sequence_numbers()`].
+fn decode_sequence_numbers(s: &str) -> Option<SequenceNumberSet> {
+    let mut set = SequenceNumberSet::default();
+    for range in s.split(',').filter(|v| !v.is_empty()) {
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+            None => {
+                let n = range.parse().ok()?;
+                (n, n)
+            }
+        };
+        if start > end {
+            return None;
+        }
+        set.extend((start..=end).map(SequenceNumber::new));
+    }
+    Some(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_util::assert_batches_eq;
+    use assert_matches::assert_matches;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use object_store_encryption::{EncryptionKey, StaticKeyProvider};
+
+    use super::*;
+    use crate::test_util::{
+        ARBITRARY_NAMESPACE_ID, ARBITRARY_PARTITION_KEY, ARBITRARY_TABLE_ID,
+        ARBITRARY_TRANSITION_PARTITION_ID,
+    };
+
+    fn arbitrary_snapshot(lp: &str, sequence_numbers: &[u64]) -> PartitionSnapshot {
+        let (_, mb) = lp_to_mutable_batch(lp);
+        PartitionSnapshot {
+            namespace_id: ARBITRARY_NAMESPACE_ID,
+            table_id: ARBITRARY_TABLE_ID,
+            partition_id: ARBITRARY_TRANSITION_PARTITION_ID.clone(),
+            partition_key: ARBITRARY_PARTITION_KEY.clone(),
+            sequence_numbers: sequence_numbers
+                .iter()
+                .copied()
+                .map(SequenceNumber::new)
+                .collect(),
+            batch: mb.to_arrow(schema::Projection::All).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_sequence_number_encoding() {
+        for set in [vec![], vec![1], vec![1, 2, 3, 5, 7, 8, 42]] {
+            let set = set
+                .into_iter()
+                .map(SequenceNumber::new)
+                .collect::<SequenceNumberSet>();
+            let encoded = encode_sequence_numbers(&set);
+            assert_eq!(decode_sequence_numbers(&encoded), Some(set));
+        }
+
+        assert_eq!(
+            encode_sequence_numbers(
+                &[1, 2, 3, 5, 7, 8]
+                    .map(SequenceNumber::new)
+                    .into_iter()
+                    .collect()
+            ),
+            "1-3,5,7-8"
+        );
+        assert_eq!(decode_sequence_numbers("3-1"), None);
+        assert_eq!(decode_sequence_numbers("bananas"), None);
+    }
+
+    #[test]
+    fn test_write_take() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = BufferSnapshotDir::new(dir.path()).unwrap();
+
+        // Nothing to load from an empty directory.
+        let mut loaded = snapshots.take().unwrap();
+        assert_matches!(
+            loaded.coverage(
+                ARBITRARY_TABLE_ID,
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(1)
+            ),
+            SnapshotCoverage::Uncovered
+        );
+
+        snapshots
+            .write(&[arbitrary_snapshot(
+                "bananas,city=London people=2,pigeons=\"millions\" 10\n\
+                bananas,city=Madrid people=4,pigeons=\"none\",good=true 20",
+                &[1, 2],
+            )])
+            .unwrap();
+
+        // A newer snapshot replaces the older one.
+        snapshots
+            .write(&[arbitrary_snapshot(
+                "bananas,city=London people=2,pigeons=\"millions\" 10\n\
+                bananas,city=Madrid people=4,good=true 20\n\
+                bananas,city=Paris people=6 30",
+                &[1, 2, 4],
+            )])
+            .unwrap();
+
+        // Reopening the directory finds the snapshot.
+        drop(snapshots);
+        let snapshots = BufferSnapshotDir::new(dir.path()).unwrap();
+        let mut loaded = snapshots.take().unwrap();
+
+        // Sequence numbers outside of the snapshot are not covered.
+        assert_matches!(
+            loaded.coverage(
+                ARBITRARY_TABLE_ID,
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(3)
+            ),
+            SnapshotCoverage::Uncovered
+        );
+        assert_matches!(
+            loaded.coverage(
+                TableId::new(ARBITRARY_TABLE_ID.get() + 1),
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(1)
+            ),
+            SnapshotCoverage::Uncovered
+        );
+
+        // The first covered write returns the data.
+        let data = assert_matches!(
+            loaded.coverage(
+                ARBITRARY_TABLE_ID,
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(2)
+            ),
+            SnapshotCoverage::CoveredFirst(n, data) => {
+                assert_eq!(n, SequenceNumber::new(4));
+                data
+            }
+        );
+        let expected = [
+            "+--------+------+--------+----------+--------------------------------+",
+            "| city   | good | people | pigeons  | time                           |",
+            "+--------+------+--------+----------+--------------------------------+",
+            "| London |      | 2.0    | millions | 1970-01-01T00:00:00.000000010Z |",
+            "| Madrid | true | 4.0    |          | 1970-01-01T00:00:00.000000020Z |",
+            "| Paris  |      | 6.0    |          | 1970-01-01T00:00:00.000000030Z |",
+            "+--------+------+--------+----------+--------------------------------+",
+        ];
+        assert_batches_eq!(expected, &[data.to_arrow(schema::Projection::All).unwrap()]);
+
+        // And subsequent writes are covered without data.
+        assert_matches!(
+            loaded.coverage(
+                ARBITRARY_TABLE_ID,
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(1)
+            ),
+            SnapshotCoverage::Covered
+        );
+
+        // The snapshot was removed once loaded.
+        let mut loaded = BufferSnapshotDir::new(dir.path()).unwrap().take().unwrap();
+        assert_matches!(
+            loaded.coverage(
+                ARBITRARY_TABLE_ID,
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(1)
+            ),
+            SnapshotCoverage::Uncovered
+        );
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = BufferSnapshotDir::new(dir.path()).unwrap();
+
+        snapshots
+            .write(&[arbitrary_snapshot("bananas,city=London people=2 10", &[1])])
+            .unwrap();
+        snapshots.invalidate(&ARBITRARY_TRANSITION_PARTITION_ID);
+
+        let mut loaded = snapshots.take().unwrap();
+        assert_matches!(
+            loaded.coverage(
+                ARBITRARY_TABLE_ID,
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(1)
+            ),
+            SnapshotCoverage::Uncovered
+        );
+    }
+
+    #[test]
+    fn test_encrypted_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys: Arc<dyn KeyProvider> = Arc::new(StaticKeyProvider::new(
+            (1, EncryptionKey::new([42; 32])),
+            [],
+        ));
+
+        let snapshots = BufferSnapshotDir::new(dir.path())
+            .unwrap()
+            .with_encryption_keys(Arc::clone(&keys));
+        snapshots
+            .write(&[arbitrary_snapshot("bananas,city=London people=2 10", &[1])])
+            .unwrap();
+
+        // The partition file is encrypted.
+        let path = snapshots
+            .generation_path(0)
+            .join(file_name(&ARBITRARY_TRANSITION_PARTITION_ID));
+        assert!(is_encrypted(&std::fs::read(&path).unwrap()));
+
+        // And cannot be loaded without the keys.
+        assert_matches!(
+            read_partition(&path, None),
+            Err(SnapshotError::MissingEncryptionKeys)
+        );
+
+        drop(snapshots);
+        let snapshots = BufferSnapshotDir::new(dir.path())
+            .unwrap()
+            .with_encryption_keys(keys);
+        let mut loaded = snapshots.take().unwrap();
+        assert_matches!(
+            loaded.coverage(
+                ARBITRARY_TABLE_ID,
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(1)
+            ),
+            SnapshotCoverage::CoveredFirst(..)
+        );
+    }
+
+    #[test]
+    fn test_incomplete_snapshot_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = BufferSnapshotDir::new(dir.path()).unwrap();
+        snapshots
+            .write(&[arbitrary_snapshot("bananas,city=London people=2 10", &[1])])
+            .unwrap();
+
+        // Emulate a crash while writing the next snapshot.
+        let tmp = snapshots.generation_path(1).with_extension(TMP_EXTENSION);
+        std::fs::create_dir_all(&tmp).unwrap();
+        std::fs::write(tmp.join("42.arrow"), b"bananas").unwrap();
+        drop(snapshots);
+
+        // The incomplete snapshot is removed, and the previous one loaded.
+        let snapshots = BufferSnapshotDir::new(dir.path()).unwrap();
+        assert!(!tmp.exists());
+        let mut loaded = snapshots.take().unwrap();
+        assert_matches!(
+            loaded.coverage(
+                ARBITRARY_TABLE_ID,
+                &ARBITRARY_PARTITION_KEY,
+                SequenceNumber::new(1)
+            ),
+            SnapshotCoverage::CoveredFirst(..)
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::BufferSnapshotDir;
+use crate::persist::completion_observer::{CompletedPersist, PersistCompletionObserver};
+
+/// A [`PersistCompletionObserver`] decorator deleting the buffer snapshot of
+/// each persisted partition, before passing the notification to the inner
+/// observer.
+///
+/// This ensures data is never loaded from a snapshot once it has been
+/// persisted.
+#[derive(Debug)]
+pub(crate) struct SnapshotInvalidator<T> {
+    inner: T,
+    snapshots: Arc<BufferSnapshotDir>,
+}
+
+impl<T> SnapshotInvalidator<T> {
+    pub(crate) fn new(inner: T, snapshots: Arc<BufferSnapshotDir>) -> Self {
+        Self { inner, snapshots }
+    }
+}
+
+#[async_trait]
+impl<T> PersistCompletionObserver for SnapshotInvalidator<T>
+where
+    T: PersistCompletionObserver,
+{
+    async fn persist_complete(&self, note: Arc<CompletedPersist>) {
+        self.snapshots.invalidate(note.partition_id());
+        self.inner.persist_complete(note).await;
+    }
+}
@@ -0,0 +1,100 @@
+use std::{sync::Arc, time::Duration};
+
+use observability_deps::tracing::*;
+
+use super::{BufferSnapshotDir, PartitionSnapshot};
+use crate::partition_iter::PartitionIter;
+
+/// Write a snapshot of the buffered data in `buffer` to `snapshots` every
+/// `period` duration of time.
+///
+/// A failure to write a snapshot is logged, and does not affect ingest.
+pub(crate) async fn periodic_snapshot<T>(
+    snapshots: Arc<BufferSnapshotDir>,
+    period: Duration,
+    buffer: T,
+) where
+    T: PartitionIter + Sync + 'static,
+{
+    let mut interval = tokio::time::interval(period);
+
+    // The first tick completes immediately, and there is nothing to snapshot
+    // yet.
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let partitions = snapshot_partitions(&buffer);
+        let n_partitions = partitions.len();
+
+        let snapshots = Arc::clone(&snapshots);
+        let res = tokio::task::spawn_blocking(move || snapshots.write(&partitions))
+            .await
+            .expect("buffer snapshot task panicked");
+
+        match res {
+            Ok(()) => debug!(n_partitions, "wrote buffer snapshot"),
+            Err(e) => error!(error=%e, n_partitions, "failed to write buffer snapshot"),
+        }
+    }
+}
+
+/// Copy the buffered data of each partition in `buffer` that is not currently
+/// persisting.
+fn snapshot_partitions<T>(buffer: &T) -> Vec<PartitionSnapshot>
+where
+    T: PartitionIter,
+{
+    buffer
+        .partition_iter()
+        .filter_map(|p| {
+            let mut p = p.lock();
+            let (batch, sequence_numbers) = p.buffer_snapshot()?;
+            Some(PartitionSnapshot {
+                namespace_id: p.namespace_id(),
+                table_id: p.table_id(),
+                partition_id: p.partition_id().clone(),
+                partition_key: p.partition_key().clone(),
+                sequence_numbers,
+                batch,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::SequenceNumber;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::test_util::{PartitionDataBuilder, ARBITRARY_PARTITION_KEY, ARBITRARY_TABLE_ID};
+
+    #[test]
+    fn test_snapshot_partitions() {
+        let mut empty = PartitionDataBuilder::new()
+            .with_partition_key("empty".into())
+            .build();
+        assert!(empty.buffer_snapshot().is_none());
+
+        let mut p = PartitionDataBuilder::new().build();
+        p.buffer_write(
+            lp_to_mutable_batch("bananas,city=London people=2 10").1,
+            SequenceNumber::new(1),
+        )
+        .unwrap();
+
+        let buffer = vec![Arc::new(Mutex::new(empty)), Arc::new(Mutex::new(p))];
+        let got = snapshot_partitions(&buffer);
+
+        assert_matches!(got.as_slice(), [p] => {
+            assert_eq!(p.table_id, ARBITRARY_TABLE_ID);
+            assert_eq!(p.partition_key, *ARBITRARY_PARTITION_KEY);
+            assert_eq!(p.batch.num_rows(), 1);
+            assert!(p.sequence_numbers.contains(SequenceNumber::new(1)));
+        });
+    }
+}
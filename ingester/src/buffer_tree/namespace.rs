@@ -11,7 +11,10 @@ use predicate::Predicate;
 use trace::span::Span;
 
 use super::{
-    partition::{counter::PartitionCounter, resolver::PartitionProvider},
+    partition::{
+        counter::PartitionCounter, dictionary_metrics::DictionaryMetrics,
+        resolver::PartitionProvider,
+    },
     post_write::PostWriteObserver,
     table::{metadata_resolver::TableProvider, TableData},
     BufferWriteError,
@@ -88,6 +91,9 @@ pub(crate) struct NamespaceData<O> {
     /// consistent when enforced.
     partition_count: Arc<PartitionCounter>,
 
+    /// Tag dictionary metrics shared by all partitions of this namespace.
+    dictionary_metrics: DictionaryMetrics,
+
     post_write_observer: Arc<O>,
 }
 
@@ -109,6 +115,8 @@ impl<O> NamespaceData<O> {
             )
             .recorder(&[]);
 
+        let dictionary_metrics = DictionaryMetrics::new(metrics);
+
         Self {
             namespace_id,
             namespace_name,
@@ -118,6 +126,7 @@ impl<O> NamespaceData<O> {
             partition_provider,
             post_write_observer,
             partition_count: Arc::new(partition_counter),
+            dictionary_metrics,
         }
     }
 
@@ -171,6 +180,7 @@ where
                             Arc::clone(&self.namespace_name),
                             Arc::clone(&self.partition_provider),
                             Arc::clone(&self.partition_count),
+                            self.dictionary_metrics.clone(),
                             Arc::clone(&self.post_write_observer),
                         ))
                     });
@@ -225,6 +235,29 @@ where
                 .await?,
         ))
     }
+
+    async fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        projection: OwnedProjection,
+        span: Option<Span>,
+    ) -> Result<Self::Response, QueryError> {
+        assert_eq!(
+            self.namespace_id, namespace_id,
+            "buffer tree index inconsistency"
+        );
+
+        let inner = self
+            .table(table_id)
+            .ok_or(QueryError::TableNotFound(namespace_id, table_id))?;
+
+        Ok(QueryResponse::new(
+            QueryExecTracing::new(inner, "table")
+                .query_last_values(namespace_id, table_id, projection, span)
+                .await?,
+        ))
+    }
 }
 
 #[cfg(test)]
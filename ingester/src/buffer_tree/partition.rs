@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use arrow::record_batch::RecordBatch;
 use data_types::{
     sequence_number_set::SequenceNumberSet, NamespaceId, PartitionKey, SequenceNumber,
     SortedColumnSet, TableId, TimestampMinMax, TransitionPartitionId,
@@ -9,10 +10,12 @@ use data_types::{
 use mutable_batch::MutableBatch;
 use observability_deps::tracing::*;
 use schema::{merge::SchemaMerger, sort::SortKey, Schema};
+use tracker::{enter_subsystem, MemorySubsystem};
 
 use self::{
     buffer::{traits::Queryable, DataBuffer},
     counter::PartitionCounter,
+    last_values::LastValueCache,
     persisting::{BatchIdent, PersistingData},
     persisting_list::PersistingList,
 };
@@ -23,6 +26,8 @@ use crate::{
 
 mod buffer;
 pub(crate) mod counter;
+pub(crate) mod dictionary_metrics;
+pub(crate) mod last_values;
 pub(crate) mod persisting;
 mod persisting_list;
 pub(crate) mod resolver;
@@ -129,6 +134,13 @@ pub struct PartitionData {
     /// data is dropped, transitioning the [`PartitionData`] from non-empty to
     /// empty.
     partition_counter: Arc<PartitionCounter>,
+
+    /// The last (and optionally first) value of every field of every series
+    /// written to this partition, if enabled.
+    ///
+    /// Unlike the buffered data, the cached values are retained after
+    /// persistence.
+    last_values: Option<LastValueCache>,
 }
 
 impl PartitionData {
@@ -158,6 +170,17 @@ impl PartitionData {
             completed_persistence_count: 0,
             partition_counter,
             is_empty: true,
+            last_values: None,
+        }
+    }
+
+    /// Maintain a [`LastValueCache`] for the writes buffered in this partition
+    /// from now on.
+    ///
+    /// A no-op if the cache is already enabled.
+    pub(crate) fn enable_last_value_cache(&mut self, cache: LastValueCache) {
+        if self.last_values.is_none() {
+            self.last_values = Some(cache);
         }
     }
 
@@ -181,8 +204,22 @@ impl PartitionData {
         // point because this partition is non-empty.
         debug_assert_ne!(self.partition_counter.read(), 0);
 
-        // Buffer the write.
-        self.buffer.buffer_write(mb, sequence_number)?;
+        // Update the last value cache before the write is moved into the
+        // buffer.
+        //
+        // Column types are validated against the catalog schema before
+        // reaching the ingester, so the buffer is not expected to reject a
+        // write the cache accepted.
+        if let Some(last_values) = &mut self.last_values {
+            last_values.observe(&mb);
+        }
+
+        // Buffer the write, attributing any memory it allocates (i.e. growing
+        // the buffer) to the mutable buffer.
+        {
+            let _guard = enter_subsystem(MemorySubsystem::MutableBuffer);
+            self.buffer.buffer_write(mb, sequence_number)?;
+        }
 
         // Invariant: if the partition contains a buffered write, it must report
         // non-empty.
@@ -206,6 +243,20 @@ impl PartitionData {
         self.buffer.persist_cost_estimate()
     }
 
+    /// Return the number of values in the largest tag dictionary of the
+    /// mutable buffer of this [`PartitionData`].
+    pub(crate) fn max_dictionary_values(&self) -> usize {
+        self.buffer.max_dictionary_values()
+    }
+
+    /// Return the number of times the current mutable buffer of this
+    /// [`PartitionData`] was closed early because a tag dictionary was full.
+    ///
+    /// This count is reset when the buffer is snapshot for persistence.
+    pub(crate) fn buffer_closed_early(&self) -> usize {
+        self.buffer.closed_early()
+    }
+
     /// Returns the number of rows currently buffered in this [`PartitionData`].
     ///
     /// The returned value will always match the row count of the data returned
@@ -286,6 +337,22 @@ impl PartitionData {
         )
     }
 
+    /// Return the values held in the [`LastValueCache`] of this partition,
+    /// with `projection` applied.
+    ///
+    /// Returns [`None`] if the cache is not enabled for this partition, and an
+    /// empty set of batches if it holds no values.
+    pub(crate) fn last_values(&self, projection: &OwnedProjection) -> Option<Vec<RecordBatch>> {
+        let cache = self.last_values.as_ref()?;
+        if cache.is_empty() {
+            return Some(vec![]);
+        }
+
+        Some(vec![
+            projection.project_mutable_batches(&cache.to_mutable_batch())
+        ])
+    }
+
     /// Return all data for this partition, ordered by the calls to
     /// [`PartitionData::buffer_write()`].
     pub(crate) fn get_query_data(&mut self, projection: &OwnedProjection) -> Option<QueryAdaptor> {
@@ -359,6 +426,26 @@ impl PartitionData {
         Some(q)
     }
 
+    /// Return a copy of the buffered data, and the [`SequenceNumberSet`] of
+    /// the writes it contains, for a buffer snapshot.
+    ///
+    /// This method returns [`None`] if no data is buffered in [`Self`], or if
+    /// [`Self`] has data being persisted - the snapshot would otherwise order
+    /// the buffered writes before the persisting writes when loaded.
+    pub(crate) fn buffer_snapshot(&mut self) -> Option<(RecordBatch, SequenceNumberSet)> {
+        if !self.persisting.is_empty() {
+            return None;
+        }
+
+        // The buffer yields at most one batch when not persisting.
+        let batch = self
+            .buffer
+            .get_query_data(&OwnedProjection::default())
+            .pop()?;
+
+        Some((batch, self.buffer.sequence_number_set().clone()))
+    }
+
     /// Snapshot and mark all buffered data as persisting.
     ///
     /// This method returns [`None`] if no data is buffered in [`Self`].
@@ -715,6 +802,49 @@ mod tests {
         assert!(p.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_buffer_snapshot() {
+        let mut p = PartitionDataBuilder::new().build();
+
+        assert!(p.buffer_snapshot().is_none());
+
+        let mb = lp_to_mutable_batch(r#"bananas,city=London people=2,pigeons="millions" 10"#).1;
+        p.buffer_write(mb, SequenceNumber::new(1))
+            .expect("write should succeed");
+        let mb = lp_to_mutable_batch(r#"bananas,city=Madrid people=4,pigeons="none" 20"#).1;
+        p.buffer_write(mb, SequenceNumber::new(2))
+            .expect("write should succeed");
+
+        let (batch, set) = p.buffer_snapshot().expect("must contain buffered data");
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [1, 2].map(SequenceNumber::new)
+        );
+        let expected = [
+            "+--------+--------+----------+--------------------------------+",
+            "| city   | people | pigeons  | time                           |",
+            "+--------+--------+----------+--------------------------------+",
+            "| London | 2.0    | millions | 1970-01-01T00:00:00.000000010Z |",
+            "| Madrid | 4.0    | none     | 1970-01-01T00:00:00.000000020Z |",
+            "+--------+--------+----------+--------------------------------+",
+        ];
+        assert_batches_eq!(expected, &[batch]);
+
+        // Taking a snapshot does not change the buffered data.
+        assert_eq!(p.rows(), 2);
+
+        // Partitions with data being persisted are not snapshotted.
+        let persisting = p.mark_persisting().expect("must contain existing data");
+        let mb = lp_to_mutable_batch(r#"bananas,city=Paris people=6,pigeons="few" 30"#).1;
+        p.buffer_write(mb, SequenceNumber::new(3))
+            .expect("write should succeed");
+        assert!(p.buffer_snapshot().is_none());
+
+        let _ = p.mark_persisted(persisting);
+        let (_, set) = p.buffer_snapshot().expect("must contain buffered data");
+        assert_eq!(set.iter().collect::<Vec<_>>(), [SequenceNumber::new(3)]);
+    }
+
     // Ensure the ordering of snapshots & persisting data is preserved such that
     // updates resolve correctly, and batch identifiers are correctly allocated
     // and validated in mark_persisted() calls which return the correct
@@ -1259,4 +1389,52 @@ mod tests {
         assert!(p.get_query_data(&OwnedProjection::default()).is_none());
         assert!(p.is_empty());
     }
+
+    // Ensure the last value cache is populated by buffered writes and retains
+    // the values after the buffered data is persisted.
+    #[tokio::test]
+    async fn test_last_values_retained_after_persist() {
+        use std::num::NonZeroUsize;
+
+        use last_values::LastValueCacheConfig;
+
+        let mut p = PartitionDataBuilder::new().build();
+
+        // The cache is disabled by default.
+        assert!(p.last_values(&OwnedProjection::default()).is_none());
+
+        p.enable_last_value_cache(LastValueCache::new(
+            LastValueCacheConfig {
+                max_series: NonZeroUsize::new(10).unwrap(),
+                first_values: false,
+            },
+            Default::default(),
+        ));
+        assert_eq!(p.last_values(&OwnedProjection::default()), Some(vec![]));
+
+        let mb = lp_to_mutable_batch(r#"bananas,city=London people=2 10"#).1;
+        p.buffer_write(mb, SequenceNumber::new(1))
+            .expect("write should succeed");
+        let mb = lp_to_mutable_batch(r#"bananas,city=London people=3 20"#).1;
+        p.buffer_write(mb, SequenceNumber::new(2))
+            .expect("write should succeed");
+
+        // Persist all buffered data, leaving the partition empty.
+        let data = p.mark_persisting().expect("must contain existing data");
+        let _ = p.mark_persisted(data);
+        assert!(p.is_empty());
+
+        let expected = [
+            "+--------+--------+--------------------------------+",
+            "| city   | people | time                           |",
+            "+--------+--------+--------------------------------+",
+            "| London | 3.0    | 1970-01-01T00:00:00.000000020Z |",
+            "+--------+--------+--------------------------------+",
+        ];
+        assert_batches_eq!(
+            expected,
+            &p.last_values(&OwnedProjection::default())
+                .expect("cache is enabled")
+        );
+    }
 }
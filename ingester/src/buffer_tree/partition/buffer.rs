@@ -1,5 +1,5 @@
 use arrow::record_batch::RecordBatch;
-use data_types::{SequenceNumber, TimestampMinMax};
+use data_types::{sequence_number_set::SequenceNumberSet, SequenceNumber, TimestampMinMax};
 use mutable_batch::MutableBatch;
 
 mod always_some;
@@ -62,6 +62,22 @@ impl DataBuffer {
         }
     }
 
+    /// Return the number of values in the largest tag dictionary of the
+    /// mutable buffer.
+    pub(crate) fn max_dictionary_values(&self) -> usize {
+        match self.0.get() {
+            FsmState::Buffering(b) => b.max_dictionary_values(),
+        }
+    }
+
+    /// Return the number of times the mutable buffer was closed early because
+    /// a tag dictionary was full.
+    pub(crate) fn closed_early(&self) -> usize {
+        match self.0.get() {
+            FsmState::Buffering(b) => b.closed_early(),
+        }
+    }
+
     /// Return all data for this buffer, ordered by the [`SequenceNumber`] from
     /// which it was buffered with.
     pub(crate) fn get_query_data(&mut self, projection: &OwnedProjection) -> Vec<RecordBatch> {
@@ -89,6 +105,13 @@ impl DataBuffer {
         }
     }
 
+    /// Returns the set of [`SequenceNumber`] of the writes in this buffer.
+    pub(crate) fn sequence_number_set(&self) -> &SequenceNumberSet {
+        match self.0.get() {
+            FsmState::Buffering(v) => v.sequence_number_set(),
+        }
+    }
+
     /// Returns the [`Schema`] for the buffered data.
     pub(crate) fn schema(&self) -> Option<Schema> {
         match self.0.get() {
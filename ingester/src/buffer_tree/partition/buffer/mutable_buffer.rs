@@ -1,5 +1,6 @@
 use arrow::record_batch::RecordBatch;
-use mutable_batch::MutableBatch;
+use mutable_batch::{column::ColumnData, writer, MutableBatch};
+use observability_deps::tracing::*;
 use schema::Projection;
 
 /// A [`Buffer`] is an internal mutable buffer wrapper over a [`MutableBatch`]
@@ -10,52 +11,150 @@ use schema::Projection;
 /// [`BufferState`]: super::BufferState
 #[derive(Debug, Default)]
 pub(super) struct Buffer {
+    /// Snapshots of previous buffer contents that were closed early, because
+    /// a tag dictionary of the [`MutableBatch`] could not hold any more values.
+    closed: Vec<RecordBatch>,
+
     buffer: Option<MutableBatch>,
 }
 
 impl Buffer {
     /// Apply `batch` to the in-memory buffer.
     ///
+    /// If a tag dictionary of the buffer cannot hold the values of `batch`,
+    /// the buffer is closed early (converted to an immutable [`RecordBatch`])
+    /// and `batch` is applied to a new, empty buffer instead.
+    ///
     /// # Data Loss
     ///
     /// If this method returns an error, the data in `batch` is problematic and
     /// has been discarded.
     pub(super) fn buffer_write(&mut self, batch: MutableBatch) -> Result<(), mutable_batch::Error> {
-        match self.buffer {
-            Some(ref mut b) => b.extend_from(&batch)?,
-            None => self.buffer = Some(batch),
+        let Some(ref mut b) = self.buffer else {
+            self.buffer = Some(batch);
+            return Ok(());
         };
 
-        Ok(())
+        match b.extend_from(&batch) {
+            Ok(()) => Ok(()),
+            Err(mutable_batch::Error::WriterError {
+                source: writer::Error::DictionaryOverflow { column },
+            }) => {
+                warn!(%column, "tag dictionary full, closing partition buffer early");
+                self.close(batch);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
     }
 
-    /// Generates a [`RecordBatch`] from the data in this [`Buffer`].
+    /// Close the current buffer, and start a new buffer containing `batch`.
+    fn close(&mut self, batch: MutableBatch) {
+        if let Some(full) = self.buffer.replace(batch) {
+            self.closed.push(
+                full.to_arrow(Projection::All)
+                    .expect("failed to snapshot buffer data"),
+            );
+        }
+    }
+
+    /// Generates [`RecordBatch`] from the data in this [`Buffer`], ordered by
+    /// the time they were buffered.
     ///
     /// If this [`Buffer`] is empty when this method is called, the call is a
-    /// NOP and [`None`] is returned.
+    /// NOP and an empty [`Vec`] is returned.
     ///
     /// # Panics
     ///
     /// If generating the snapshot fails, this method panics.
-    pub(super) fn snapshot(self) -> Option<RecordBatch> {
-        Some(
-            self.buffer?
-                .to_arrow(Projection::All)
-                .expect("failed to snapshot buffer data"),
-        )
+    pub(super) fn snapshot(self) -> Vec<RecordBatch> {
+        let mut batches = self.closed;
+        batches.extend(self.buffer.map(|b| {
+            b.to_arrow(Projection::All)
+                .expect("failed to snapshot buffer data")
+        }));
+        batches
     }
 
     pub(super) fn is_empty(&self) -> bool {
-        self.buffer.is_none()
+        self.closed.is_empty() && self.buffer.is_none()
     }
 
-    /// Returns the underlying buffer if this [`Buffer`] contains data,
-    /// otherwise returns [`None`].
+    /// Returns the snapshots of the buffers that were closed early.
+    pub(super) fn closed(&self) -> &[RecordBatch] {
+        &self.closed
+    }
+
+    /// Returns the underlying buffer if this [`Buffer`] contains data that is
+    /// not part of a [closed](Self::closed) snapshot, otherwise returns
+    /// [`None`].
     pub(super) fn buffer(&self) -> Option<&MutableBatch> {
         self.buffer.as_ref()
     }
 
+    /// Returns the number of values in the largest tag dictionary of the
+    /// underlying buffer.
+    pub(super) fn max_dictionary_values(&self) -> usize {
+        self.buffer()
+            .into_iter()
+            .flat_map(|b| b.columns())
+            .filter_map(|(_, col)| match col.data() {
+                ColumnData::Tag(_, dictionary, _) => Some(dictionary.values().len()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
     pub(crate) fn persist_cost_estimate(&self) -> usize {
-        self.buffer().map(|v| v.size_data()).unwrap_or_default()
+        self.closed
+            .iter()
+            .map(|b| b.get_array_memory_size())
+            .sum::<usize>()
+            + self.buffer().map(|v| v.size_data()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_util::assert_batches_eq;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+
+    use super::*;
+
+    #[test]
+    fn test_close_early() {
+        let mut buffer = Buffer::default();
+
+        buffer
+            .buffer_write(lp_to_mutable_batch(r#"bananas,tag=A v=1 1"#).1)
+            .expect("write should succeed");
+        assert_eq!(buffer.max_dictionary_values(), 1);
+
+        // Closing the buffer retains the data, and starts a new buffer with
+        // the next write.
+        buffer.close(lp_to_mutable_batch(r#"bananas,tag=B v=2 2"#).1);
+        assert!(!buffer.is_empty());
+        assert_eq!(buffer.closed().len(), 1);
+        assert_eq!(buffer.buffer().unwrap().rows(), 1);
+
+        buffer
+            .buffer_write(lp_to_mutable_batch(r#"bananas,tag=C v=3 3"#).1)
+            .expect("write should succeed");
+        assert_eq!(buffer.max_dictionary_values(), 2);
+
+        let batches = buffer.snapshot();
+        assert_eq!(batches.len(), 2);
+
+        let expected = [
+            "+-----+--------------------------------+-----+",
+            "| tag | time                           | v   |",
+            "+-----+--------------------------------+-----+",
+            "| A   | 1970-01-01T00:00:00.000000001Z | 1.0 |",
+            "| B   | 1970-01-01T00:00:00.000000002Z | 2.0 |",
+            "| C   | 1970-01-01T00:00:00.000000003Z | 3.0 |",
+            "+-----+--------------------------------+-----+",
+        ];
+        assert_batches_eq!(expected, &batches);
     }
 }
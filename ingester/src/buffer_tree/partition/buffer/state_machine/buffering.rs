@@ -2,8 +2,9 @@
 
 use arrow::record_batch::RecordBatch;
 use data_types::{StatValues, TimestampMinMax};
+use iox_query::util::compute_timenanosecond_min_max;
 use mutable_batch::{column::ColumnData, MutableBatch};
-use schema::{Projection, TIME_COLUMN_NAME};
+use schema::{merge::SchemaMerger, Projection, TIME_COLUMN_NAME};
 
 use super::{snapshot::Snapshot, BufferState, Transition};
 use crate::{
@@ -21,6 +22,9 @@ pub(crate) struct Buffering {
     ///
     /// This buffer MAY be empty when no writes have occured since transitioning
     /// to this state.
+    ///
+    /// The buffer MAY contain snapshots of data that was closed early because
+    /// its tag dictionaries were full, see [`Buffer::buffer_write()`].
     buffer: Buffer,
 }
 
@@ -38,17 +42,30 @@ pub(crate) struct Buffering {
 /// [`RecordBatch`] fails (a non-transient error).
 impl Queryable for Buffering {
     fn get_query_data(&self, projection: &OwnedProjection) -> Vec<RecordBatch> {
-        self.buffer
-            .buffer()
-            .map(|v| vec![projection.project_mutable_batches(v)])
-            .unwrap_or_default()
+        let mut batches = projection.project_record_batch(self.buffer.closed());
+        batches.extend(
+            self.buffer
+                .buffer()
+                .map(|v| projection.project_mutable_batches(v)),
+        );
+        batches
     }
 
     fn rows(&self) -> usize {
-        self.buffer.buffer().map(|v| v.rows()).unwrap_or_default()
+        self.buffer
+            .closed()
+            .iter()
+            .map(|v| v.num_rows())
+            .sum::<usize>()
+            + self.buffer.buffer().map(|v| v.rows()).unwrap_or_default()
     }
 
     fn timestamp_stats(&self) -> Option<TimestampMinMax> {
+        let closed = (!self.buffer.closed().is_empty()).then(|| {
+            compute_timenanosecond_min_max(self.buffer.closed())
+                .expect("non-empty batch must contain timestamps")
+        });
+
         self.buffer
             .buffer()
             .map(extract_timestamp_summary)
@@ -58,13 +75,32 @@ impl Queryable for Buffering {
                 min: v.min.unwrap(),
                 max: v.max.unwrap(),
             })
+            .into_iter()
+            .chain(closed)
+            .reduce(|a, b| TimestampMinMax {
+                min: a.min.min(b.min),
+                max: a.max.max(b.max),
+            })
     }
 
     fn schema(&self) -> Option<schema::Schema> {
-        self.buffer.buffer().map(|v| {
-            v.schema(Projection::All)
-                .expect("failed to construct batch schema")
-        })
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let mut merger = SchemaMerger::new();
+        for batch in self.buffer.closed() {
+            let schema =
+                schema::Schema::try_from(batch.schema()).expect("failed to construct batch schema");
+            merger = merger.merge(&schema).expect("schemas compatible");
+        }
+        if let Some(v) = self.buffer.buffer() {
+            let schema = v
+                .schema(Projection::All)
+                .expect("failed to construct batch schema");
+            merger = merger.merge(&schema).expect("schemas compatible");
+        }
+        Some(merger.build())
     }
 }
 
@@ -85,19 +121,27 @@ impl BufferState<Buffering> {
         }
 
         // Generate a snapshot from the buffer.
-        let snap = self
-            .state
-            .buffer
-            .snapshot()
-            .expect("snapshot of non-empty buffer should succeed");
+        let snap = self.state.buffer.snapshot();
 
         // And transition to the WithSnapshot state.
-        Transition::ok(Snapshot::new(vec![snap]), self.sequence_numbers)
+        Transition::ok(Snapshot::new(snap), self.sequence_numbers)
     }
 
     pub(crate) fn persist_cost_estimate(&self) -> usize {
         self.state.buffer.persist_cost_estimate()
     }
+
+    /// Returns the number of values in the largest tag dictionary of the
+    /// mutable buffer.
+    pub(crate) fn max_dictionary_values(&self) -> usize {
+        self.state.buffer.max_dictionary_values()
+    }
+
+    /// Returns the number of times the mutable buffer was closed early because
+    /// a tag dictionary was full.
+    pub(crate) fn closed_early(&self) -> usize {
+        self.state.buffer.closed().len()
+    }
 }
 
 /// Perform an O(1) extraction of the timestamp column statistics.
@@ -0,0 +1,67 @@
+//! Metrics describing the tag dictionaries of partition buffers.
+
+use metric::{U64Counter, U64Histogram, U64HistogramOptions};
+
+use super::PartitionData;
+
+/// Metrics tracking the size of the tag dictionaries of [`PartitionData`]
+/// buffers, and how often a buffer is closed early because a dictionary is
+/// full.
+#[derive(Debug, Clone)]
+pub(crate) struct DictionaryMetrics {
+    /// The number of values in the largest tag dictionary of a partition
+    /// buffer, observed after each write.
+    dictionary_values: U64Histogram,
+
+    /// The number of partition buffers closed early because a tag dictionary
+    /// could not hold any more values.
+    closed_early: U64Counter,
+}
+
+impl DictionaryMetrics {
+    pub(crate) fn new(metrics: &metric::Registry) -> Self {
+        let dictionary_values = metrics
+            .register_metric_with_options::<U64Histogram, _>(
+                "ingester_buffer_tag_dictionary_values",
+                "distribution of the number of values in the largest tag dictionary of a \
+                 partition buffer, observed after each write",
+                || {
+                    U64HistogramOptions::new([
+                        4_u64.pow(3),  // 64
+                        4_u64.pow(5),  // 1,024
+                        4_u64.pow(7),  // 16,384
+                        4_u64.pow(9),  // 262,144
+                        4_u64.pow(11), // 4,194,304
+                        4_u64.pow(13), // 67,108,864
+                        4_u64.pow(15), // 1,073,741,824
+                        u64::MAX,
+                    ])
+                },
+            )
+            .recorder(&[]);
+
+        let closed_early = metrics
+            .register_metric::<U64Counter>(
+                "ingester_buffer_closed_early",
+                "number of partition buffers closed early because a tag dictionary was full",
+            )
+            .recorder(&[]);
+
+        Self {
+            dictionary_values,
+            closed_early,
+        }
+    }
+
+    /// Observe the buffer of `partition` after a write, given the number of
+    /// times the buffer had been closed early before the write.
+    pub(crate) fn observe_write(&self, partition: &PartitionData, closed_early_before: usize) {
+        self.dictionary_values
+            .record(partition.max_dictionary_values() as _);
+        self.closed_early.inc(
+            partition
+                .buffer_closed_early()
+                .saturating_sub(closed_early_before) as _,
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! A cache of the most recent (and optionally the earliest) value of every
+//! field of every series written to a partition.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    iter,
+    num::NonZeroUsize,
+};
+
+use metric::U64Counter;
+use mutable_batch::{column::ColumnData, writer::Writer, MutableBatch};
+use schema::{InfluxColumnType, InfluxFieldType, TIME_COLUMN_NAME};
+
+/// Configuration of the [`LastValueCache`] maintained for every partition.
+#[derive(Debug, Clone, Copy)]
+pub struct LastValueCacheConfig {
+    /// The maximum number of series cached per partition.
+    ///
+    /// Values of series written once this limit is reached are not cached.
+    pub max_series: NonZeroUsize,
+
+    /// Cache the first value of every field, in addition to the last value.
+    pub first_values: bool,
+}
+
+/// The tag values identifying a series, ordered by tag name.
+///
+/// Tags that are NULL for a row are not part of its series key.
+type SeriesKey = Vec<(String, String)>;
+
+/// A single field value.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldValue {
+    F64(f64),
+    I64(i64),
+    U64(u64),
+    String(String),
+    Bool(bool),
+}
+
+impl FieldValue {
+    /// Read the value of row `idx` of `data`, which must be non-NULL.
+    fn from_column(data: &ColumnData, idx: usize) -> Option<Self> {
+        match data {
+            ColumnData::F64(v, _) => Some(Self::F64(v[idx])),
+            ColumnData::I64(v, _) => Some(Self::I64(v[idx])),
+            ColumnData::U64(v, _) => Some(Self::U64(v[idx])),
+            ColumnData::String(v, _) => v.get(idx).map(|s| Self::String(s.to_string())),
+            ColumnData::Bool(v, _) => Some(Self::Bool(v.get(idx))),
+            ColumnData::Tag(_, _, _) => None,
+        }
+    }
+
+    fn write(
+        &self,
+        writer: &mut Writer<'_>,
+        name: &str,
+    ) -> Result<(), mutable_batch::writer::Error> {
+        match self {
+            Self::F64(v) => writer.write_f64(name, None, iter::once(*v)),
+            Self::I64(v) => writer.write_i64(name, None, iter::once(*v)),
+            Self::U64(v) => writer.write_u64(name, None, iter::once(*v)),
+            Self::String(v) => writer.write_string(name, None, iter::once(v.as_str())),
+            Self::Bool(v) => writer.write_bool(name, None, iter::once(*v)),
+        }
+    }
+}
+
+/// The timestamped field values cached for a single series, keyed by field
+/// name.
+#[derive(Debug, Default)]
+struct SeriesValues {
+    last: HashMap<String, (i64, FieldValue)>,
+    first: HashMap<String, (i64, FieldValue)>,
+}
+
+/// A cache of the most recent value of every field of every series written to
+/// a partition, and optionally of the earliest value.
+///
+/// The cache is updated as writes are buffered and is NOT cleared when the
+/// buffered data is persisted, so it answers "current value" queries without
+/// reading the buffered data or any Parquet files. It only contains the values
+/// written to this ingester since it started (including replayed writes).
+///
+/// Like deduplication, a later write of the same series, field and timestamp
+/// replaces the cached value.
+#[derive(Debug)]
+pub(crate) struct LastValueCache {
+    config: LastValueCacheConfig,
+
+    /// The type of every cached field, as first observed.
+    ///
+    /// Values of a field with a conflicting type are ignored.
+    field_types: HashMap<String, InfluxFieldType>,
+
+    series: HashMap<SeriesKey, SeriesValues>,
+
+    /// Incremented for every row of a series that is not cached because the
+    /// cache is full.
+    series_dropped: U64Counter,
+}
+
+impl LastValueCache {
+    pub(crate) fn new(config: LastValueCacheConfig, series_dropped: U64Counter) -> Self {
+        Self {
+            config,
+            field_types: Default::default(),
+            series: Default::default(),
+            series_dropped,
+        }
+    }
+
+    /// Update the cache with the rows of `batch`.
+    pub(crate) fn observe(&mut self, batch: &MutableBatch) {
+        let Ok(ColumnData::I64(timestamps, _)) = batch.column(TIME_COLUMN_NAME).map(|c| c.data())
+        else {
+            return;
+        };
+
+        let mut tags = vec![];
+        let mut fields = vec![];
+        for (name, column) in batch.columns() {
+            match column.influx_type() {
+                InfluxColumnType::Tag => tags.push((name, column)),
+                InfluxColumnType::Field(field_type) => {
+                    let known = *self.field_types.entry(name.clone()).or_insert(field_type);
+                    if known == field_type {
+                        fields.push((name, column));
+                    }
+                }
+                InfluxColumnType::Timestamp => {}
+            }
+        }
+        tags.sort_unstable_by_key(|(name, _)| *name);
+
+        let first_values = self.config.first_values;
+        for (idx, &ts) in timestamps.iter().enumerate() {
+            let key = tags
+                .iter()
+                .filter(|(_, column)| column.valid_mask().get(idx))
+                .filter_map(|(name, column)| match column.data() {
+                    ColumnData::Tag(keys, dictionary, _) => dictionary
+                        .lookup_id(keys[idx])
+                        .map(|value| (name.to_string(), value.to_string())),
+                    _ => None,
+                })
+                .collect::<SeriesKey>();
+
+            if !self.series.contains_key(&key) && self.series.len() >= self.config.max_series.get()
+            {
+                self.series_dropped.inc(1);
+                continue;
+            }
+            let series = self.series.entry(key).or_default();
+
+            for (name, column) in &fields {
+                if !column.valid_mask().get(idx) {
+                    continue;
+                }
+                let Some(value) = FieldValue::from_column(column.data(), idx) else {
+                    continue;
+                };
+
+                replace_if(&mut series.last, name, ts, &value, |cached| cached <= ts);
+                if first_values {
+                    replace_if(&mut series.first, name, ts, &value, |cached| ts <= cached);
+                }
+            }
+        }
+    }
+
+    /// Return the cached values as a [`MutableBatch`].
+    ///
+    /// Every series has one row per distinct timestamp of its cached values.
+    /// Fields without a cached value at that timestamp are NULL, so the row
+    /// with the greatest timestamp at which a field is not NULL holds its last
+    /// value (and the row with the smallest timestamp its first value).
+    pub(crate) fn to_mutable_batch(&self) -> MutableBatch {
+        let mut batch = MutableBatch::new();
+
+        let mut series = self.series.iter().collect::<Vec<_>>();
+        series.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        for (key, values) in series {
+            let mut rows: BTreeMap<i64, BTreeMap<&str, &FieldValue>> = BTreeMap::new();
+            for (name, (ts, value)) in values.first.iter().chain(values.last.iter()) {
+                rows.entry(*ts).or_default().insert(name, value);
+            }
+
+            for (ts, fields) in rows {
+                let mut writer = Writer::new(&mut batch, 1);
+                for (tag, value) in key {
+                    writer
+                        .write_tag(tag, None, iter::once(value.as_str()))
+                        .expect("cached tag column has a consistent type");
+                }
+                for (name, value) in fields {
+                    value
+                        .write(&mut writer, name)
+                        .expect("cached field column has a consistent type");
+                }
+                writer
+                    .write_time(TIME_COLUMN_NAME, iter::once(ts))
+                    .expect("cached time column has a consistent type");
+                writer.commit();
+            }
+        }
+
+        batch
+    }
+
+    /// Returns true if no values are cached.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+}
+
+/// Cache `value` for `field` if no value is cached yet or `replace` returns
+/// true for the timestamp of the cached value.
+fn replace_if(
+    values: &mut HashMap<String, (i64, FieldValue)>,
+    field: &str,
+    ts: i64,
+    value: &FieldValue,
+    replace: impl Fn(i64) -> bool,
+) {
+    match values.get_mut(field) {
+        Some((cached_ts, cached)) => {
+            if replace(*cached_ts) {
+                *cached_ts = ts;
+                *cached = value.clone();
+            }
+        }
+        None => {
+            values.insert(field.to_string(), (ts, value.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_util::assert_batches_eq;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use schema::Projection;
+
+    use super::*;
+
+    fn cache(max_series: usize, first_values: bool) -> LastValueCache {
+        LastValueCache::new(
+            LastValueCacheConfig {
+                max_series: NonZeroUsize::new(max_series).unwrap(),
+                first_values,
+            },
+            U64Counter::default(),
+        )
+    }
+
+    fn observe(cache: &mut LastValueCache, lp: &str) {
+        cache.observe(&lp_to_mutable_batch(lp).1);
+    }
+
+    fn to_record_batch(cache: &LastValueCache) -> arrow::record_batch::RecordBatch {
+        cache
+            .to_mutable_batch()
+            .to_arrow(Projection::All)
+            .expect("valid batch")
+    }
+
+    #[test]
+    fn test_last_values() {
+        let mut cache = cache(10, false);
+        assert!(cache.is_empty());
+
+        observe(
+            &mut cache,
+            "cpu,host=a usage=1,temp=10i 1\n\
+             cpu,host=a usage=2 3\n\
+             cpu,host=b usage=5 2\n\
+             cpu,host=a usage=0 2",
+        );
+        // a later write with the same timestamp replaces the cached value
+        observe(&mut cache, "cpu,host=b usage=6 2\ncpu usage=7 1");
+        assert!(!cache.is_empty());
+
+        assert_batches_eq!(
+            [
+                "+------+------+--------------------------------+-------+",
+                "| host | temp | time                           | usage |",
+                "+------+------+--------------------------------+-------+",
+                "|      |      | 1970-01-01T00:00:00.000000001Z | 7.0   |",
+                "| a    | 10   | 1970-01-01T00:00:00.000000001Z |       |",
+                "| a    |      | 1970-01-01T00:00:00.000000003Z | 2.0   |",
+                "| b    |      | 1970-01-01T00:00:00.000000002Z | 6.0   |",
+                "+------+------+--------------------------------+-------+",
+            ],
+            &[to_record_batch(&cache)]
+        );
+    }
+
+    #[test]
+    fn test_first_values() {
+        let mut cache = cache(10, true);
+
+        observe(
+            &mut cache,
+            "cpu,host=a usage=1 5\n\
+             cpu,host=a usage=2 3\n\
+             cpu,host=a usage=3 7",
+        );
+
+        assert_batches_eq!(
+            [
+                "+------+--------------------------------+-------+",
+                "| host | time                           | usage |",
+                "+------+--------------------------------+-------+",
+                "| a    | 1970-01-01T00:00:00.000000003Z | 2.0   |",
+                "| a    | 1970-01-01T00:00:00.000000007Z | 3.0   |",
+                "+------+--------------------------------+-------+",
+            ],
+            &[to_record_batch(&cache)]
+        );
+    }
+
+    #[test]
+    fn test_max_series() {
+        let metric = U64Counter::default();
+        let mut cache = LastValueCache::new(
+            LastValueCacheConfig {
+                max_series: NonZeroUsize::new(1).unwrap(),
+                first_values: false,
+            },
+            metric.clone(),
+        );
+
+        observe(&mut cache, "cpu,host=a usage=1 1\ncpu,host=b usage=2 1");
+        // values of cached series are still updated
+        observe(&mut cache, "cpu,host=a usage=3 2");
+
+        assert_batches_eq!(
+            [
+                "+------+--------------------------------+-------+",
+                "| host | time                           | usage |",
+                "+------+--------------------------------+-------+",
+                "| a    | 1970-01-01T00:00:00.000000002Z | 3.0   |",
+                "+------+--------------------------------+-------+",
+            ],
+            &[to_record_batch(&cache)]
+        );
+        assert_eq!(metric.fetch(), 1);
+    }
+
+    #[test]
+    fn test_conflicting_field_type() {
+        let mut cache = cache(10, false);
+
+        observe(&mut cache, "cpu,host=a usage=1 1");
+        observe(&mut cache, "cpu,host=a usage=2i 2");
+
+        assert_batches_eq!(
+            [
+                "+------+--------------------------------+-------+",
+                "| host | time                           | usage |",
+                "+------+--------------------------------+-------+",
+                "| a    | 1970-01-01T00:00:00.000000001Z | 1.0   |",
+                "+------+--------------------------------+-------+",
+            ],
+            &[to_record_batch(&cache)]
+        );
+    }
+}
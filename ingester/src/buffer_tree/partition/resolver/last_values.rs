@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use data_types::{NamespaceId, PartitionKey, TableId};
+use metric::U64Counter;
+use parking_lot::Mutex;
+
+use super::PartitionProvider;
+use crate::{
+    buffer_tree::{
+        namespace::NamespaceName,
+        partition::{
+            counter::PartitionCounter,
+            last_values::{LastValueCache, LastValueCacheConfig},
+            PartitionData,
+        },
+        table::metadata::TableMetadata,
+    },
+    deferred_load::DeferredLoad,
+};
+
+/// A [`PartitionProvider`] decorator enabling a [`LastValueCache`] for every
+/// [`PartitionData`] returned by the inner provider.
+///
+/// This decorator MUST wrap any caching [`PartitionProvider`] so that the
+/// cache is enabled for all partitions, regardless of how they were resolved.
+#[derive(Debug)]
+pub(crate) struct LastValueCacheResolver<T> {
+    inner: T,
+    config: LastValueCacheConfig,
+
+    /// The number of rows of series not cached because the cache of their
+    /// partition is full, shared by all partitions.
+    series_dropped: U64Counter,
+}
+
+impl<T> LastValueCacheResolver<T> {
+    pub(crate) fn new(inner: T, config: LastValueCacheConfig, metrics: &metric::Registry) -> Self {
+        let series_dropped = metrics
+            .register_metric::<U64Counter>(
+                "ingester_last_value_cache_series_dropped",
+                "number of rows not added to a partition last value cache because the cache \
+                 reached its maximum number of series",
+            )
+            .recorder(&[]);
+
+        Self {
+            inner,
+            config,
+            series_dropped,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> PartitionProvider for LastValueCacheResolver<T>
+where
+    T: PartitionProvider,
+{
+    async fn get_partition(
+        &self,
+        partition_key: PartitionKey,
+        namespace_id: NamespaceId,
+        namespace_name: Arc<DeferredLoad<NamespaceName>>,
+        table_id: TableId,
+        table: Arc<DeferredLoad<TableMetadata>>,
+        partition_counter: Arc<PartitionCounter>,
+    ) -> Arc<Mutex<PartitionData>> {
+        let partition = self
+            .inner
+            .get_partition(
+                partition_key,
+                namespace_id,
+                namespace_name,
+                table_id,
+                table,
+                partition_counter,
+            )
+            .await;
+
+        partition
+            .lock()
+            .enable_last_value_cache(LastValueCache::new(
+                self.config,
+                self.series_dropped.clone(),
+            ));
+
+        partition
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use data_types::SequenceNumber;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+
+    use super::*;
+    use crate::{
+        buffer_tree::partition::resolver::mock::MockPartitionProvider,
+        query::projection::OwnedProjection,
+        test_util::{
+            defer_namespace_name_1_sec, defer_table_metadata_1_sec, PartitionDataBuilder,
+            ARBITRARY_NAMESPACE_ID, ARBITRARY_PARTITION_KEY, ARBITRARY_TABLE_ID,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_enables_cache() {
+        let inner = MockPartitionProvider::default().with_partition(PartitionDataBuilder::new());
+        let metrics = metric::Registry::default();
+
+        let resolver = LastValueCacheResolver::new(
+            inner,
+            LastValueCacheConfig {
+                max_series: NonZeroUsize::new(1).unwrap(),
+                first_values: false,
+            },
+            &metrics,
+        );
+
+        let partition = resolver
+            .get_partition(
+                ARBITRARY_PARTITION_KEY.clone(),
+                ARBITRARY_NAMESPACE_ID,
+                defer_namespace_name_1_sec(),
+                ARBITRARY_TABLE_ID,
+                defer_table_metadata_1_sec(),
+                Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+            )
+            .await;
+
+        let mut p = partition.lock();
+        assert_eq!(p.last_values(&OwnedProjection::default()), Some(vec![]));
+
+        // The second series exceeds the configured limit.
+        let mb =
+            lp_to_mutable_batch("bananas,city=London people=2 10\nbananas,city=Paris people=3 10")
+                .1;
+        p.buffer_write(mb, SequenceNumber::new(1))
+            .expect("write should succeed");
+
+        let batches = p
+            .last_values(&OwnedProjection::default())
+            .expect("cache is enabled");
+        assert_eq!(batches[0].num_rows(), 1);
+
+        let dropped = metrics
+            .get_instrument::<metric::Metric<U64Counter>>(
+                "ingester_last_value_cache_series_dropped",
+            )
+            .expect("metric is registered")
+            .recorder(&[])
+            .fetch();
+        assert_eq!(dropped, 1);
+    }
+}
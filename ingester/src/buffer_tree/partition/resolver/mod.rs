@@ -20,5 +20,8 @@ pub(crate) use coalesce::*;
 mod old_filter;
 pub(crate) use old_filter::*;
 
+mod last_values;
+pub(crate) use last_values::*;
+
 #[cfg(test)]
 pub(crate) mod mock;
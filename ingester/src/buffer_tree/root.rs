@@ -236,6 +236,22 @@ where
             .query_exec(namespace_id, table_id, projection, span, predicate)
             .await
     }
+
+    async fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        projection: OwnedProjection,
+        span: Option<Span>,
+    ) -> Result<Self::Response, QueryError> {
+        let inner = self
+            .namespace(namespace_id)
+            .ok_or(QueryError::NamespaceNotFound(namespace_id))?;
+
+        QueryExecTracing::new(inner, "namespace")
+            .query_last_values(namespace_id, table_id, projection, span)
+            .await
+    }
 }
 
 impl<O> PartitionIter for crate::buffer_tree::BufferTree<O>
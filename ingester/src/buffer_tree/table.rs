@@ -25,7 +25,10 @@ use self::metadata::TableMetadata;
 
 use super::{
     namespace::NamespaceName,
-    partition::{counter::PartitionCounter, resolver::PartitionProvider, PartitionData},
+    partition::{
+        counter::PartitionCounter, dictionary_metrics::DictionaryMetrics,
+        resolver::PartitionProvider, PartitionData,
+    },
     post_write::PostWriteObserver,
     BufferWriteError,
 };
@@ -63,6 +66,9 @@ pub(crate) struct TableData<O> {
     /// consistent when enforced.
     partition_count: Arc<PartitionCounter>,
 
+    /// Tag dictionary metrics, recorded for each buffered write.
+    dictionary_metrics: DictionaryMetrics,
+
     post_write_observer: Arc<O>,
 }
 
@@ -79,6 +85,7 @@ impl<O> TableData<O> {
         namespace_name: Arc<DeferredLoad<NamespaceName>>,
         partition_provider: Arc<dyn PartitionProvider>,
         partition_count: Arc<PartitionCounter>,
+        dictionary_metrics: DictionaryMetrics,
         post_write_observer: Arc<O>,
     ) -> Self {
         Self {
@@ -89,6 +96,7 @@ impl<O> TableData<O> {
             partition_data: Default::default(),
             partition_provider,
             partition_count,
+            dictionary_metrics,
             post_write_observer,
         }
     }
@@ -174,7 +182,9 @@ where
         let mut p = partition_data.lock();
 
         // Enqueue the write, returning any error.
+        let closed_early = p.buffer_closed_early();
         p.buffer_write(batch, sequence_number)?;
+        self.dictionary_metrics.observe_write(&p, closed_early);
 
         // If successful, allow the observer to inspect the partition.
         self.post_write_observer
@@ -271,6 +281,48 @@ where
 
         Ok(PartitionStream::new(futures::stream::iter(partitions)))
     }
+
+    async fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        projection: OwnedProjection,
+        span: Option<Span>,
+    ) -> Result<Self::Response, QueryError> {
+        assert_eq!(self.table_id, table_id, "buffer tree index inconsistency");
+        assert_eq!(
+            self.namespace_id, namespace_id,
+            "buffer tree index inconsistency"
+        );
+
+        let mut span = SpanRecorder::new(span).child("last value cache read");
+
+        // Read the cached values eagerly so that a partition without a cache
+        // fails the query, rather than returning partial results.
+        let partitions = self
+            .partitions()
+            .into_iter()
+            .map(|p| {
+                let p = p.lock();
+                let batches = p
+                    .last_values(&projection)
+                    .ok_or(QueryError::LastValueCacheDisabled(namespace_id, table_id))?;
+
+                Ok(PartitionResponse::new(
+                    batches,
+                    p.partition_id().clone(),
+                    p.completed_persistence_count(),
+                ))
+            })
+            .collect::<Result<Vec<_>, QueryError>>();
+
+        match &partitions {
+            Ok(_) => span.ok("read last value caches"),
+            Err(e) => span.error(e.to_string()),
+        }
+
+        Ok(PartitionStream::new(futures::stream::iter(partitions?)))
+    }
 }
 
 /// Return true if `data` contains one or more rows matching `predicate`,
@@ -412,6 +464,7 @@ mod tests {
             defer_namespace_name_1_sec(),
             partition_provider,
             Arc::clone(&partition_counter),
+            DictionaryMetrics::new(&metric::Registry::default()),
             Arc::new(MockPostWriteObserver::default()),
         );
 
@@ -464,6 +517,7 @@ mod tests {
             defer_namespace_name_1_sec(),
             partition_provider,
             Arc::clone(&partition_counter),
+            DictionaryMetrics::new(&metric::Registry::default()),
             Arc::new(MockPostWriteObserver::default()),
         );
 
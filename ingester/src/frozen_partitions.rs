@@ -0,0 +1,153 @@
+//! Tracking of partitions frozen for maintenance.
+
+use std::{sync::Arc, time::Duration};
+
+use data_types::{FrozenPartition, PartitionKey, TableId};
+use hashbrown::{HashMap, HashSet};
+use iox_catalog::interface::Catalog;
+use observability_deps::tracing::*;
+use parking_lot::RwLock;
+
+/// The interval at which the set of frozen partitions is refreshed from the
+/// catalog.
+pub(crate) const FROZEN_PARTITIONS_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The set of partitions frozen for maintenance in the catalog, to which
+/// writes are rejected.
+///
+/// The set is refreshed periodically by [`refresh_frozen_partitions()`], so a
+/// partition is frozen (or unfrozen) in the ingester up to a refresh interval
+/// after it is in the catalog.
+#[derive(Debug, Default)]
+pub(crate) struct FrozenPartitions {
+    partitions: RwLock<HashMap<TableId, HashSet<PartitionKey>>>,
+}
+
+impl FrozenPartitions {
+    /// Returns true if the partition identified by `table_id` and
+    /// `partition_key` is frozen.
+    pub(crate) fn is_frozen(&self, table_id: TableId, partition_key: &PartitionKey) -> bool {
+        self.partitions
+            .read()
+            .get(&table_id)
+            .map(|keys| keys.contains(partition_key))
+            .unwrap_or_default()
+    }
+
+    /// Replace the set of frozen partitions with `frozen`.
+    pub(crate) fn set(&self, frozen: impl IntoIterator<Item = FrozenPartition>) {
+        let mut partitions: HashMap<_, HashSet<_>> = HashMap::new();
+        for f in frozen {
+            partitions
+                .entry(f.table_id)
+                .or_default()
+                .insert(f.partition_key);
+        }
+
+        *self.partitions.write() = partitions;
+    }
+}
+
+/// Periodically load the partitions frozen in `catalog` into `frozen`.
+///
+/// If loading fails, the previously loaded set is retained until the next
+/// attempt.
+pub(crate) async fn refresh_frozen_partitions(
+    frozen: Arc<FrozenPartitions>,
+    catalog: Arc<dyn Catalog>,
+    period: Duration,
+) {
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+
+        match catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_frozen()
+            .await
+        {
+            Ok(v) => {
+                debug!(n_frozen = v.len(), "refreshed frozen partitions");
+                frozen.set(v);
+            }
+            Err(error) => warn!(%error, "failed to refresh frozen partitions"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
+    use test_helpers::timeout::FutureTimeout;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_refresh_frozen_partitions() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+
+        let (partition, other) = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "bananas").await;
+            let table = arbitrary_table(&mut *repos, "platanos", &namespace).await;
+            let partition = repos
+                .partitions()
+                .create_or_get("one".into(), table.id)
+                .await
+                .unwrap();
+            let other = repos
+                .partitions()
+                .create_or_get("two".into(), table.id)
+                .await
+                .unwrap();
+            repos
+                .partitions()
+                .freeze(partition.id, "maintenance")
+                .await
+                .unwrap();
+            (partition, other)
+        };
+
+        let frozen = Arc::new(FrozenPartitions::default());
+        assert!(!frozen.is_frozen(partition.table_id, &partition.partition_key));
+
+        let task = tokio::spawn(refresh_frozen_partitions(
+            Arc::clone(&frozen),
+            Arc::clone(&catalog),
+            Duration::from_millis(1),
+        ));
+
+        async {
+            while !frozen.is_frozen(partition.table_id, &partition.partition_key) {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+        assert!(!frozen.is_frozen(other.table_id, &other.partition_key));
+
+        // Unfreezing the partition in the catalog is reflected after a refresh.
+        catalog
+            .repositories()
+            .await
+            .partitions()
+            .unfreeze(partition.id)
+            .await
+            .unwrap();
+        async {
+            while frozen.is_frozen(partition.table_id, &partition.partition_key) {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        task.abort();
+    }
+}
@@ -4,7 +4,10 @@
 //! state to other subsystems. Concretely, it is used to reject writes when the
 //! ingester is unable to process them.
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
 
 use crossbeam_utils::CachePadded;
 use thiserror::Error;
@@ -28,9 +31,23 @@ pub enum IngestStateError {
     /// writes.
     #[error("ingester disk full - persisting write-ahead log")]
     DiskFull = 1 << 2,
+
+    /// Set when the ingester is buffering more (not yet persisting) data than
+    /// its configured limit allows.
+    #[error("ingester overloaded - persisting buffered data")]
+    BufferFull = 1 << 3,
 }
 
 impl IngestStateError {
+    /// Returns true if this error state is applying backpressure - it is
+    /// expected to clear once persistence catches up with ingest.
+    pub(crate) fn is_backpressure(&self) -> bool {
+        match self {
+            Self::PersistSaturated | Self::DiskFull | Self::BufferFull => true,
+            Self::GracefulStop => false,
+        }
+    }
+
     #[inline(always)]
     fn as_bits(self) -> usize {
         // Map the user-friendly enum to a u64 bitmap.
@@ -41,6 +58,27 @@ impl IngestStateError {
     }
 }
 
+/// How the write path responds to writes while an [`IngestStateError`] that
+/// [applies backpressure](IngestStateError::is_backpressure) is set.
+///
+/// Writes are always rejected with a `FAILED_PRECONDITION` error once the
+/// ingester is shutting down, regardless of this configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackpressureResponse {
+    /// Reject the write with a `RESOURCE_EXHAUSTED` error, the gRPC equivalent
+    /// of an HTTP 429, asking the caller to retry later.
+    #[default]
+    Throttle,
+
+    /// Reject the write with an `UNAVAILABLE` error.
+    Reject,
+
+    /// Hold the write for up to the given duration, waiting for the
+    /// backpressure to be lifted. If it is not, the write is rejected as for
+    /// [`BackpressureResponse::Throttle`].
+    Delay(Duration),
+}
+
 /// A thread-safe, cheap-to-read "ingest error" state to propagate errors from
 /// subsystems to the ingest code path. Many error states can be set at any one
 /// time, but at most one error is returned by a call to
@@ -142,7 +180,8 @@ impl IngestState {
     ///
     ///   1. [`IngestStateError::GracefulStop`]
     ///   2. [`IngestStateError::DiskFull`]
-    ///   3. [`IngestStateError::PersistSaturated`].
+    ///   3. [`IngestStateError::PersistSaturated`]
+    ///   4. [`IngestStateError::BufferFull`].
     ///
     pub(crate) fn read(&self) -> Result<(), IngestStateError> {
         let current = self.state.load(Ordering::Relaxed);
@@ -201,6 +240,10 @@ fn as_err(state: usize) -> Result<(), IngestStateError> {
         return Err(IngestStateError::PersistSaturated);
     }
 
+    if state & IngestStateError::BufferFull.as_bits() != 0 {
+        return Err(IngestStateError::BufferFull);
+    }
+
     unreachable!()
 }
 
@@ -286,6 +329,12 @@ mod tests {
             state.read_with_exceptions([]),
             Err(IngestStateError::PersistSaturated)
         );
+
+        // The buffer full state has the lowest precedence.
+        state.set(IngestStateError::BufferFull);
+        assert_matches!(state.read(), Err(IngestStateError::PersistSaturated));
+        state.unset(IngestStateError::PersistSaturated);
+        assert_matches!(state.read(), Err(IngestStateError::BufferFull));
     }
 
     /// A hand-rolled strategy to enumerate [`IngestStateError`] variants.
@@ -294,7 +343,8 @@ mod tests {
         prop_oneof![
             Just(IngestStateError::PersistSaturated),
             Just(IngestStateError::GracefulStop),
-            Just(IngestStateError::DiskFull)
+            Just(IngestStateError::DiskFull),
+            Just(IngestStateError::BufferFull)
         ]
     }
 
@@ -303,6 +353,7 @@ mod tests {
             IngestStateError::PersistSaturated,
             IngestStateError::GracefulStop,
             IngestStateError::DiskFull,
+            IngestStateError::BufferFull,
         ]
         .into_iter()
         .filter(|v| !not.iter().any(|w| discriminant(v) == discriminant(w)))
@@ -318,6 +369,7 @@ mod tests {
                 IngestStateError::PersistSaturated => {}
                 IngestStateError::GracefulStop => {}
                 IngestStateError::DiskFull => {}
+                IngestStateError::BufferFull => {}
             }
         }
 
@@ -1,4 +1,4 @@
-use data_types::ParquetFile;
+use data_types::{job::Job, ParquetFile};
 use gossip::{NopDispatcher, TopicInterests};
 
 use gossip_parquet_file::tx::ParquetFileTx;
@@ -10,7 +10,13 @@ mod graceful_shutdown;
 #[cfg(not(feature = "benches"))]
 mod wal_replay;
 
-use std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    net::SocketAddr,
+    num::{NonZeroU32, NonZeroUsize},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
 use arrow_flight::flight_service_server::FlightService;
 use backoff::BackoffConfig;
@@ -22,31 +28,43 @@ use generated_types::influxdata::iox::{
 };
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
+use iox_time::SystemProvider;
+use object_store_encryption::KeyProvider;
 use observability_deps::tracing::*;
+use parking_lot::Mutex;
 use parquet_file::storage::ParquetStorage;
 use thiserror::Error;
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, watch};
 use tokio_util::sync::CancellationToken;
-use tracker::DiskSpaceMetrics;
+use tracker::{AbstractTaskRegistry, DiskSpaceMetrics, TaskRegistry, TaskRegistryWithHistory};
 use wal::Wal;
 
 use crate::{
+    buffer_snapshot::{periodic_snapshot, BufferSnapshotDir, LoadedSnapshot, SnapshotInvalidator},
     buffer_tree::{
         namespace::name_resolver::{NamespaceNameProvider, NamespaceNameResolver},
         partition::resolver::{
-            CatalogPartitionResolver, CoalescePartitionResolver, OldPartitionBloomFilter,
-            PartitionCache, PartitionProvider,
+            CatalogPartitionResolver, CoalescePartitionResolver, LastValueCacheResolver,
+            OldPartitionBloomFilter, PartitionCache, PartitionProvider,
         },
         table::metadata_resolver::{TableProvider, TableResolver},
         BufferTree,
     },
     dml_sink::{instrumentation::DmlSinkInstrumentation, tracing::DmlSinkTracing},
+    frozen_partitions::{
+        refresh_frozen_partitions, FrozenPartitions, FROZEN_PARTITIONS_REFRESH_INTERVAL,
+    },
     gossip::persist_parquet::ParquetFileNotification,
     ingest_state::IngestState,
     ingester_id::IngesterId,
     persist::{
-        completion_observer::MaybeLayer, file_metrics::ParquetFileInstrumentation,
-        handle::PersistHandle, hot_partitions::HotPartitionPersister,
+        completion_observer::MaybeLayer,
+        events::{PersistEventObserver, PersistEvents},
+        file_metrics::ParquetFileInstrumentation,
+        handle::PersistHandle,
+        hot_partitions::HotPartitionPersister,
+        namespace_memory::{enforce_namespace_memory_limits, NamespaceMemory, ACCOUNTING_INTERVAL},
+        webhook::{PersistNotifier, WebhookSink},
     },
     query::{
         exec_instrumentation::QueryExecInstrumentation,
@@ -60,10 +78,24 @@ use crate::{
         rotate_task::periodic_rotation,
         wal_sink::WalSink,
     },
+    write_lag::{monitor_persist_lag, WriteLagObserver, WriteLagSink, WriteLagTracker},
 };
 
 use self::graceful_shutdown::graceful_shutdown_handler;
 
+pub use crate::buffer_snapshot::{BufferSnapshotConfig, SnapshotError};
+pub use crate::buffer_tree::partition::last_values::LastValueCacheConfig;
+pub use crate::ingest_state::BackpressureResponse;
+pub use crate::persist::namespace_memory::{InvalidNamespaceMemoryLimits, NamespaceMemoryLimits};
+pub use crate::write_lag::{IngesterHealth, PersistLagThreshold};
+
+/// The registry tracking the WAL replay and persist jobs of an ingester, and a
+/// history of recently completed ones.
+pub type JobRegistry = TaskRegistryWithHistory<Job, TaskRegistry<Job>>;
+
+/// The number of completed jobs retained in a [`JobRegistry`].
+pub(crate) const JOB_HISTORY_SIZE: usize = 1000;
+
 /// Acquire opaque handles to the Ingester RPC service implementations.
 ///
 /// This trait serves as the public crate API boundary - callers external to the
@@ -117,6 +149,32 @@ pub struct IngesterGuard<T> {
     /// Aborted on drop.
     disk_metric_task: tokio::task::JoinHandle<()>,
 
+    /// The handle of the periodic per-namespace memory accounting task.
+    ///
+    /// Aborted on drop.
+    namespace_memory_task: tokio::task::JoinHandle<()>,
+
+    /// The handle of the periodic buffer snapshot task, if enabled.
+    ///
+    /// Aborted on drop.
+    snapshot_task: Option<tokio::task::JoinHandle<()>>,
+
+    /// The handle of the periodic persist lag monitoring task.
+    ///
+    /// Aborted on drop.
+    persist_lag_task: tokio::task::JoinHandle<()>,
+
+    /// The handle of the periodic frozen partition refresh task.
+    ///
+    /// Aborted on drop.
+    frozen_partitions_task: tokio::task::JoinHandle<()>,
+
+    /// The health of the ingester, as determined by its persist lag.
+    health: watch::Receiver<IngesterHealth>,
+
+    /// The WAL replay and persist jobs of this ingester.
+    jobs: Arc<Mutex<JobRegistry>>,
+
     /// The task handle executing the graceful shutdown once triggered.
     graceful_shutdown_handler: tokio::task::JoinHandle<()>,
     shutdown_complete: Shared<oneshot::Receiver<()>>,
@@ -131,6 +189,20 @@ where
         &self.rpc
     }
 
+    /// Obtain a handle to observe the health of the ingester.
+    ///
+    /// The ingester is always [`IngesterHealth::Healthy`] unless a
+    /// [`PersistLagThreshold`] is configured.
+    pub fn health(&self) -> watch::Receiver<IngesterHealth> {
+        self.health.clone()
+    }
+
+    /// Obtain the registry of the WAL replay and persist jobs of this ingester,
+    /// to be served by the operations API.
+    pub fn jobs(&self) -> Arc<Mutex<JobRegistry>> {
+        Arc::clone(&self.jobs)
+    }
+
     /// Block and wait until the ingester has gracefully stopped.
     pub async fn join(&self) {
         self.shutdown_complete
@@ -144,6 +216,12 @@ impl<T> Drop for IngesterGuard<T> {
     fn drop(&mut self) {
         self.rotation_task.abort();
         self.disk_metric_task.abort();
+        self.namespace_memory_task.abort();
+        if let Some(t) = &self.snapshot_task {
+            t.abort();
+        }
+        self.persist_lag_task.abort();
+        self.frozen_partitions_task.abort();
         self.graceful_shutdown_handler.abort();
     }
 }
@@ -192,6 +270,18 @@ pub enum InitError {
     /// An error binding the UDP socket for gossip communication.
     #[error("failed to bind udp gossip socket: {0}")]
     GossipBind(std::io::Error),
+
+    /// The persist notification webhook URL is invalid.
+    #[error("invalid persist notification webhook url: {0}")]
+    PersistNotificationWebhook(url::ParseError),
+
+    /// An error opening or loading the buffer snapshot directory.
+    #[error("failed to initialise buffer snapshots: {0}")]
+    BufferSnapshot(SnapshotError),
+
+    /// The namespace memory limits are invalid.
+    #[error("invalid namespace buffer limits: {0}")]
+    NamespaceMemoryLimits(#[from] InvalidNamespaceMemoryLimits),
 }
 
 /// Initialise a new `ingester` instance, returning the gRPC service handler
@@ -209,6 +299,37 @@ pub enum InitError {
 ///
 /// Any error during replay is fatal.
 ///
+/// If `wal_replay_max_ops_per_second` is specified, replay applies at most
+/// that many WAL ops per second, bounding the resources it consumes. The
+/// progress of the replay, and an estimate of the time remaining, is logged as
+/// each WAL segment file is replayed.
+///
+/// ## WAL Rotation
+///
+/// The open WAL segment is rotated every `wal_rotation_period`, and all
+/// buffered partitions are enqueued for persistence. Once persisted, the closed
+/// segment is deleted.
+///
+/// If `wal_max_segment_bytes` is specified, the segment is also rotated (and
+/// the buffer persisted) as soon as the open segment reaches that size,
+/// bounding the amount of data replayed at startup after a crash.
+///
+/// ## Buffer Snapshots
+///
+/// If `buffer_snapshot` is specified, the buffered data of each partition is
+/// periodically written to the snapshot directory. The most recent snapshot is
+/// loaded during WAL replay, replacing the WAL entries it contains and avoiding
+/// the cost of decoding and re-applying them. The WAL remains the source of
+/// truth - any data missing from the snapshot is replayed from the WAL.
+///
+/// ## Encryption at Rest
+///
+/// If `encryption_keys` is specified, WAL entries and buffer snapshot files
+/// are encrypted with the current key before they are written to disk. Files
+/// written with previous keys, or before encryption was enabled, remain
+/// readable as long as `encryption_keys` contains the key they were written
+/// with.
+///
 /// ## Graceful Shutdown
 ///
 /// When `shutdown` completes, the ingester blocks ingest (returning an error to
@@ -265,6 +386,51 @@ pub enum InitError {
 /// Decreasing this value increases the frequency of persist operations, and
 /// usually decreases the size of the resulting parquet files.
 ///
+/// ## Namespace Memory Limits
+///
+/// The same cost estimate is summed across all partitions of each namespace
+/// once a second. A namespace exceeding the soft limit of
+/// `namespace_memory_limits` has all its partitions enqueued for persistence,
+/// and writes to a namespace exceeding the hard limit are rejected until
+/// persistence brings it back under the limit. This stops a single namespace
+/// writing to many partitions from consuming most of the ingester's memory.
+/// A hard limit requires a lower soft limit, so that namespaces over the hard
+/// limit are persisted.
+///
+/// ## Backpressure
+///
+/// If `buffer_limit_bytes` is provided and the sum of the cost estimates of
+/// all namespaces exceeds it, the largest partitions are enqueued for
+/// persistence and backpressure is applied until the ingester is back under
+/// the limit. Writes received while backpressure is applied (including that
+/// caused by a saturated persist queue, or a full disk) are handled according
+/// to `backpressure_response`, while WAL replay waits for the backpressure to
+/// be lifted.
+///
+/// ## Persist Lag
+///
+/// The number of writes applied to the ingester but not yet persisted, and the
+/// age of the oldest of them, are reported once a second. If either exceeds
+/// the limits of `persist_lag_threshold`, the ingester reports itself as
+/// [`IngesterHealth::Degraded`] through [`IngesterGuard::health()`] until it
+/// catches up. A degraded ingester continues to accept writes.
+///
+/// ## Frozen Partitions
+///
+/// Writes to partitions frozen for maintenance in the catalog are rejected.
+/// The set of frozen partitions is refreshed from the catalog every 10
+/// seconds, so a partition may accept writes for up to 10 seconds after it is
+/// frozen.
+///
+/// ## Last Value Cache
+///
+/// If `last_value_cache` is provided, every partition caches the last (and
+/// optionally the first) value of every field of every series written to it.
+/// The cached values survive persistence of the buffered data, and are
+/// returned instead of the buffered data for queries requesting them. The
+/// cache only contains values written since the ingester started (including
+/// those replayed from the WAL).
+///
 /// [`MutableBatch::size_data()`]: mutable_batch::MutableBatch::size_data
 #[allow(clippy::too_many_arguments)]
 pub async fn new<F>(
@@ -273,18 +439,30 @@ pub async fn new<F>(
     persist_background_fetch_time: Duration,
     wal_directory: PathBuf,
     wal_rotation_period: Duration,
+    wal_max_segment_bytes: Option<u64>,
+    wal_replay_max_ops_per_second: Option<NonZeroU32>,
+    buffer_snapshot: Option<BufferSnapshotConfig>,
+    encryption_keys: Option<Arc<dyn KeyProvider>>,
     persist_executor: Arc<Executor>,
     persist_workers: usize,
     persist_queue_depth: usize,
     persist_hot_partition_cost: usize,
+    namespace_memory_limits: NamespaceMemoryLimits,
+    buffer_limit_bytes: Option<usize>,
+    backpressure_response: BackpressureResponse,
+    persist_lag_threshold: PersistLagThreshold,
     object_store: ParquetStorage,
     gossip: GossipConfig,
     max_partitions_per_namespace: NonZeroUsize,
+    persist_notification_webhook: Option<String>,
+    last_value_cache: Option<LastValueCacheConfig>,
     shutdown: F,
 ) -> Result<IngesterGuard<impl IngesterRpcInterface>, InitError>
 where
     F: Future<Output = CancellationToken> + Send + 'static,
 {
+    namespace_memory_limits.validate()?;
+
     // Initialise a random ID for this ingester instance.
     let ingester_id = IngesterId::new();
 
@@ -329,7 +507,7 @@ where
         .map_err(InitError::FetchOldStylePartitions)?;
 
     // Build the partition provider, wrapped in the old partition bloom filter, partition cache,
-    // and request coalescer.
+    // request coalescer, and (if enabled) the last value cache initialiser.
     let partition_provider = CatalogPartitionResolver::new(Arc::clone(&catalog));
     let partition_provider = CoalescePartitionResolver::new(Arc::new(partition_provider));
     let partition_provider = OldPartitionBloomFilter::new(
@@ -348,7 +526,14 @@ where
         BackoffConfig::default(),
         Arc::clone(&metrics),
     );
-    let partition_provider: Arc<dyn PartitionProvider> = Arc::new(partition_provider);
+    let partition_provider: Arc<dyn PartitionProvider> = match last_value_cache {
+        Some(config) => Arc::new(LastValueCacheResolver::new(
+            partition_provider,
+            config,
+            &metrics,
+        )),
+        None => Arc::new(partition_provider),
+    };
 
     // Initialise the ingest pause signal, used to propagate error conditions
     // between subsystems such that they cause an error to be returned in the
@@ -356,10 +541,25 @@ where
     let ingest_state = Arc::new(IngestState::default());
 
     // Initialise the WAL
-    let wal = Wal::new(wal_directory.clone())
+    let wal = Wal::new_with_keys(wal_directory.clone(), encryption_keys.clone())
         .await
         .map_err(InitError::WalInit)?;
 
+    // Open the buffer snapshot directory (if enabled) and load the most recent
+    // snapshot for WAL replay.
+    let (buffer_snapshots, loaded_snapshot) = match &buffer_snapshot {
+        Some(config) => {
+            let mut dir = BufferSnapshotDir::new(config.directory.clone())
+                .map_err(InitError::BufferSnapshot)?;
+            if let Some(keys) = encryption_keys {
+                dir = dir.with_encryption_keys(keys);
+            }
+            let loaded = dir.take().map_err(InitError::BufferSnapshot)?;
+            (Some(Arc::new(dir)), loaded)
+        }
+        None => (None, LoadedSnapshot::default()),
+    };
+
     // Start defining the chain of persist completion observers so it can be
     // layered in gossip handlers if needed.
     //
@@ -368,6 +568,40 @@ where
         WalReferenceHandle::new(Arc::clone(&wal), &metrics);
     // Add file metric instrumentation.
     let persist_observer = ParquetFileInstrumentation::new(wal_reference_handle.clone(), &metrics);
+    // Add write lag instrumentation, shared with the write path below.
+    let write_lag = Arc::new(WriteLagTracker::new(&metrics));
+    let persist_observer = WriteLagObserver::new(persist_observer, Arc::clone(&write_lag));
+    // And report the lag of writes not yet persisted, degrading the ingester
+    // health if it exceeds the configured threshold.
+    let (health_tx, health_rx) = watch::channel(IngesterHealth::default());
+    let persist_lag_task = tokio::spawn(monitor_persist_lag(
+        Arc::clone(&write_lag),
+        persist_lag_threshold,
+        health_tx,
+        Duration::from_secs(1),
+    ));
+    // Publish persist completions to subscribers of the persist events RPC.
+    let persist_events = PersistEvents::default();
+    let persist_observer = PersistEventObserver::new(persist_observer, persist_events.clone());
+    // Delete the buffer snapshot of each persisted partition.
+    let persist_observer = match &buffer_snapshots {
+        Some(dir) => MaybeLayer::With(SnapshotInvalidator::new(persist_observer, Arc::clone(dir))),
+        None => MaybeLayer::Without(persist_observer),
+    };
+    // Optionally deliver a notification for each persisted file to an
+    // external webhook.
+    let persist_observer = match persist_notification_webhook {
+        Some(url) => {
+            let url = reqwest::Url::parse(&url).map_err(InitError::PersistNotificationWebhook)?;
+            MaybeLayer::With(PersistNotifier::new(
+                persist_observer,
+                WebhookSink::new(url),
+                Arc::clone(&catalog),
+                &metrics,
+            ))
+        }
+        None => MaybeLayer::Without(persist_observer),
+    };
 
     // Optionally start the gossip subsystem and layer on the parquet file
     // gossip handler.
@@ -399,6 +633,12 @@ where
         }
     };
 
+    // Initialise the registry tracking the WAL replay and persist jobs.
+    let jobs = Arc::new(Mutex::new(JobRegistry::new(
+        TaskRegistry::new(Arc::new(SystemProvider::new())),
+        JOB_HISTORY_SIZE,
+    )));
+
     // Spawn the persist workers to compact partition data, convert it into
     // Parquet files, and upload them to object storage.
     let persist_handle = PersistHandle::new(
@@ -410,7 +650,8 @@ where
         Arc::clone(&catalog),
         persist_observer,
         &metrics,
-    );
+    )
+    .with_jobs(Arc::clone(&jobs));
     let persist_handle = Arc::new(persist_handle);
 
     // Instantiate a post-write observer for hot partition persistence.
@@ -462,32 +703,60 @@ where
         ),
     ));
 
+    // Spawn a background task to periodically account for the data buffered
+    // per namespace, enforcing the configured limits.
+    //
+    // This is started before replaying the WAL, so that replay is subject to
+    // the ingester-wide buffer limit.
+    let namespace_memory = NamespaceMemory::new(namespace_memory_limits, &metrics);
+    let namespace_memory = Arc::new(match buffer_limit_bytes {
+        Some(v) => namespace_memory.with_buffer_limit(v, Arc::clone(&ingest_state)),
+        None => namespace_memory,
+    });
+    let namespace_memory_task = tokio::spawn(enforce_namespace_memory_limits(
+        Arc::clone(&namespace_memory),
+        Arc::clone(&buffer),
+        Arc::clone(&persist_handle),
+        ACCOUNTING_INTERVAL,
+    ));
+
     // Replay the WAL log files, if any.
-    let max_sequence_number = wal_replay::replay(
+    //
+    // The replay must run to completion before the ingester starts, so the
+    // job reports progress but is not cancellable.
+    let (_tracker, registration) = jobs.lock().register(Job::WalReplay);
+    let max_sequence_number = wal_replay::replay_with_snapshot(
         &wal,
         &buffer,
         Arc::clone(&persist_handle),
         Arc::clone(&ingest_state),
+        loaded_snapshot,
+        wal_replay::ReplayRateLimit::new(wal_replay_max_ops_per_second),
+        &registration.progress_reporter(),
         &metrics,
     )
     .await
     .map_err(|e| InitError::WalReplay(e.into()))?;
+    drop(registration);
 
     // Build the chain of DmlSink that forms the write path.
     let write_path = DmlSinkInstrumentation::new(
         "write_apply",
         DmlSinkTracing::new(
-            DmlSinkTracing::new(
-                WalSink::new(
-                    DmlSinkInstrumentation::new(
-                        "buffer",
-                        DmlSinkTracing::new(Arc::clone(&buffer), "buffer"),
-                        &metrics,
+            WriteLagSink::new(
+                DmlSinkTracing::new(
+                    WalSink::new(
+                        DmlSinkInstrumentation::new(
+                            "buffer",
+                            DmlSinkTracing::new(Arc::clone(&buffer), "buffer"),
+                            &metrics,
+                        ),
+                        Arc::clone(&wal),
+                        wal_reference_handle.clone(),
                     ),
-                    Arc::clone(&wal),
-                    wal_reference_handle.clone(),
+                    "wal",
                 ),
-                "wal",
+                write_lag,
             ),
             "write_apply",
         ),
@@ -502,15 +771,22 @@ where
         &metrics,
     );
 
-    // Spawn a background thread to periodically rotate the WAL segment file.
+    // Spawn a background thread to periodically rotate the WAL segment file,
+    // or rotate it once it exceeds the configured maximum size.
     let rotation_task = tokio::spawn(periodic_rotation(
         Arc::clone(&wal),
         wal_rotation_period,
+        wal_max_segment_bytes,
         wal_reference_handle.clone(),
         Arc::clone(&buffer),
         Arc::clone(&persist_handle),
     ));
 
+    // Spawn a background task to periodically snapshot the buffer, if enabled.
+    let snapshot_task = buffer_snapshots.zip(buffer_snapshot).map(|(dir, config)| {
+        tokio::spawn(periodic_snapshot(dir, config.period, Arc::clone(&buffer)))
+    });
+
     // Restore the highest sequence number from the WAL files, and default to 0
     // if there were no files to replay.
     //
@@ -521,6 +797,15 @@ where
         max_sequence_number.map(|v| v.get()).unwrap_or(0),
     ));
 
+    // Spawn a background task to periodically load the partitions frozen for
+    // maintenance, to which writes are rejected.
+    let frozen_partitions = Arc::new(FrozenPartitions::default());
+    let frozen_partitions_task = tokio::spawn(refresh_frozen_partitions(
+        Arc::clone(&frozen_partitions),
+        Arc::clone(&catalog),
+        FROZEN_PARTITIONS_REFRESH_INTERVAL,
+    ));
+
     let (shutdown_tx, shutdown_rx) = oneshot::channel();
     let shutdown_task = tokio::spawn(graceful_shutdown_handler(
         shutdown,
@@ -543,9 +828,19 @@ where
             metrics,
             buffer,
             persist_handle,
+            namespace_memory,
+            frozen_partitions,
+            backpressure_response,
+            persist_events,
         ),
         rotation_task,
         disk_metric_task,
+        namespace_memory_task,
+        snapshot_task,
+        persist_lag_task,
+        frozen_partitions_task,
+        health: health_rx,
+        jobs,
         graceful_shutdown_handler: shutdown_task,
         shutdown_complete: shutdown_rx.shared(),
     })
@@ -1,17 +1,21 @@
 use std::fmt::Debug;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use data_types::{NamespaceId, PartitionKey, SequenceNumber, TableId};
 use generated_types::influxdata::iox::wal::v1::sequenced_wal_op::Op;
+use iox_time::SystemProvider;
 use metric::U64Counter;
 use mutable_batch_pb::decode::decode_database_batch;
 use observability_deps::tracing::*;
 use thiserror::Error;
+use tracker::{enter_subsystem, MemorySubsystem, ProgressReporter, TaskRegistration};
 use wal::{SegmentId, SequencedWalOp};
 
 use crate::{
+    buffer_snapshot::{LoadedSnapshot, SnapshotCoverage},
     dml_payload::write::{PartitionedData, TableData, WriteOperation},
     dml_payload::IngestOp,
     dml_sink::{DmlError, DmlSink},
@@ -24,6 +28,56 @@ use crate::{
 /// when WAL op replay is blocked on an unhealthy ingest state.
 const OP_REPLAY_BACKPRESSURE_WAIT_DURATION: Duration = Duration::from_millis(500);
 
+/// Paces the application of WAL ops during replay to a maximum number of ops
+/// per second, or not at all if no limit is configured.
+#[derive(Debug, Default)]
+pub(crate) struct ReplayRateLimit {
+    /// The minimum interval between two ops.
+    interval: Option<Duration>,
+    /// The earliest time the next op may be applied.
+    next: Option<tokio::time::Instant>,
+}
+
+impl ReplayRateLimit {
+    pub(crate) fn new(max_ops_per_second: Option<NonZeroU32>) -> Self {
+        Self {
+            interval: max_ops_per_second.map(|v| Duration::from_secs(1) / v.get()),
+            next: None,
+        }
+    }
+
+    /// Wait until the next op may be applied.
+    ///
+    /// Time spent between calls is not banked - a slow op does not permit a
+    /// burst of ops afterwards.
+    async fn wait(&mut self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        let now = tokio::time::Instant::now();
+        let start = match self.next {
+            Some(next) if next > now => {
+                tokio::time::sleep_until(next).await;
+                next
+            }
+            _ => now,
+        };
+        self.next = Some(start + interval);
+    }
+}
+
+/// Estimate the time remaining to replay `remaining_bytes` of WAL segments,
+/// given `replayed_bytes` were replayed in `elapsed`.
+///
+/// Returns [`None`] until some data has been replayed.
+fn replay_eta(elapsed: Duration, replayed_bytes: u64, remaining_bytes: u64) -> Option<Duration> {
+    if replayed_bytes == 0 {
+        return None;
+    }
+    Some(elapsed.mul_f64(remaining_bytes as f64 / replayed_bytes as f64))
+}
+
 /// Errors returned when replaying the write-ahead log.
 #[derive(Debug, Error)]
 pub enum WalReplayError {
@@ -113,6 +167,40 @@ pub async fn replay<W, T, P>(
     ingest_state: Arc<IngestState>,
     metrics: &metric::Registry,
 ) -> Result<Option<SequenceNumber>, WalReplayError>
+where
+    W: WalReader,
+    T: DmlSink + PartitionIter,
+    P: PersistQueue + Clone,
+{
+    replay_with_snapshot(
+        wal,
+        sink,
+        persist,
+        ingest_state,
+        LoadedSnapshot::default(),
+        ReplayRateLimit::default(),
+        &TaskRegistration::new(Arc::new(SystemProvider::new())).progress_reporter(),
+        metrics,
+    )
+    .await
+}
+
+/// Replay all the entries in `wal` to `sink` as [`replay()`] does, applying
+/// the data in `snapshot` in place of the WAL entries it covers, and pacing
+/// the ops applied according to `rate_limit`.
+///
+/// The number of segment files replayed is reported to `progress`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn replay_with_snapshot<W, T, P>(
+    wal: &W,
+    sink: &T,
+    persist: P,
+    ingest_state: Arc<IngestState>,
+    mut snapshot: LoadedSnapshot,
+    mut rate_limit: ReplayRateLimit,
+    progress: &ProgressReporter,
+    metrics: &metric::Registry,
+) -> Result<Option<SequenceNumber>, WalReplayError>
 where
     W: WalReader,
     T: DmlSink + PartitionIter,
@@ -156,9 +244,16 @@ where
     );
     let ok_op_count_metric = op_count_metric.recorder(&[("outcome", "success")]);
     let empty_op_count_metric = op_count_metric.recorder(&[("outcome", "skipped_empty")]);
+    let snapshot_op_count_metric = op_count_metric.recorder(&[("outcome", "skipped_snapshot")]);
 
     let n_files = files.len();
-    info!(n_files, "found wal files for replay");
+    let total_bytes = files.iter().map(|(_, size)| size).sum::<u64>();
+    info!(n_files, total_bytes, "found wal files for replay");
+    progress.add_total(n_files);
+
+    // Track the bytes replayed so far to estimate the time remaining.
+    let replay_start = Instant::now();
+    let mut replayed_bytes = 0;
 
     // Replay each file, keeping track of the last observed sequence number.
     //
@@ -179,11 +274,14 @@ where
 
         // Emit a log entry so progress can be tracked (and a problematic file
         // be identified should an explosion happen during replay).
+        let remaining_bytes = total_bytes - replayed_bytes;
         info!(
             file_number,
             n_files,
             %file_id,
             size = file_size,
+            remaining_bytes,
+            eta = ?replay_eta(replay_start.elapsed(), replayed_bytes, remaining_bytes),
             "replaying wal file"
         );
 
@@ -191,11 +289,15 @@ where
         let replay_result = replay_file(
             reader,
             sink,
+            &mut snapshot,
             &ok_op_count_metric,
             &empty_op_count_metric,
+            &snapshot_op_count_metric,
             &ingest_state,
+            &mut rate_limit,
         )
         .await;
+        replayed_bytes += file_size;
         if replay_result.is_ok() {
             file_count_success_metric.inc(1);
         }
@@ -225,6 +327,7 @@ where
                     );
                 }
 
+                progress.add_done(1);
                 continue;
             }
             // If the replay results in an underlying end of file error when
@@ -271,10 +374,12 @@ where
             size = file_size,
             "dropped persisted wal segment"
         );
+        progress.add_done(1);
     }
 
     info!(
         max_sequence_number = ?max_sequence,
+        elapsed = ?replay_start.elapsed(),
         "wal replay complete"
     );
 
@@ -291,12 +396,16 @@ where
 /// error sourced from an unexpected eof error to mean that there are no more
 /// valid completed writes which can be read from the provided `batches` and
 /// that it is safe to ignore them.
+#[allow(clippy::too_many_arguments)]
 async fn replay_file<T, F>(
     file: F,
     sink: &T,
+    snapshot: &mut LoadedSnapshot,
     ok_op_count_metric: &U64Counter,
     empty_op_count_metric: &U64Counter,
+    snapshot_op_count_metric: &U64Counter,
     ingest_state: &Arc<IngestState>,
+    rate_limit: &mut ReplayRateLimit,
 ) -> Result<Option<SequenceNumber>, WalReplayError>
 where
     T: DmlSink,
@@ -315,7 +424,7 @@ where
                 op,
             } = op;
 
-            let op = match op {
+            let mut op = match op {
                 Op::Write(w) => w,
                 Op::Delete(_) => unreachable!(),
                 Op::Persist(_) => unreachable!(),
@@ -324,17 +433,77 @@ where
             let mut op_min_sequence_number: Option<SequenceNumber> = None;
             let mut op_max_sequence_number = None;
 
-            // Reconstruct the ingest operation
-            let batches = decode_database_batch(&op)?;
             let namespace_id = NamespaceId::new(op.database_id);
-            let partition_key = PartitionKey::from(op.partition_key);
+            let partition_key = PartitionKey::from(std::mem::take(&mut op.partition_key));
 
-            if batches.is_empty() {
+            if op.table_batches.is_empty() {
                 warn!(?segment_id, %namespace_id, "encountered wal op batch containing no table data, skipping replay");
                 empty_op_count_metric.inc(1);
                 continue;
             }
 
+            // Remove the table data covered by the buffer snapshot before it
+            // is decoded, applying the snapshot data of a partition in place
+            // of the first write it covers.
+            let mut snapshot_tables = Vec::new();
+            op.table_batches.retain(|t| {
+                let table_id = TableId::new(t.table_id);
+                let sequence_number = SequenceNumber::new(
+                    *table_write_sequence_numbers
+                        .get(&table_id)
+                        .expect("attempt to apply unsequenced wal op"),
+                );
+
+                match snapshot.coverage(table_id, &partition_key, sequence_number) {
+                    SnapshotCoverage::Uncovered => true,
+                    SnapshotCoverage::Covered => {
+                        max_sequence = max_sequence.max(Some(sequence_number));
+                        false
+                    }
+                    SnapshotCoverage::CoveredFirst(snapshot_sequence_number, data) => {
+                        max_sequence = max_sequence.max(Some(sequence_number));
+                        snapshot_tables.push((
+                            table_id,
+                            TableData::new(
+                                table_id,
+                                PartitionedData::new(snapshot_sequence_number, data),
+                            ),
+                        ));
+                        false
+                    }
+                }
+            });
+
+            if !snapshot_tables.is_empty() {
+                debug!(
+                    %namespace_id,
+                    %partition_key,
+                    n_tables = snapshot_tables.len(),
+                    "apply buffer snapshot"
+                );
+
+                let op = WriteOperation::new(
+                    namespace_id,
+                    snapshot_tables.into_iter().collect(),
+                    partition_key.clone(),
+                    None,
+                );
+                apply_op(sink, op, ingest_state).await?;
+            }
+
+            if op.table_batches.is_empty() {
+                // All the table data in this op was contained in the snapshot.
+                snapshot_op_count_metric.inc(1);
+                continue;
+            }
+
+            // Reconstruct the ingest operation, the batches of which are
+            // moved into (or become) partition buffers.
+            let batches = {
+                let _guard = enter_subsystem(MemorySubsystem::MutableBuffer);
+                decode_database_batch(&op)?
+            };
+
             let op = WriteOperation::new(
                 namespace_id,
                 batches
@@ -364,20 +533,6 @@ where
                 None,
             );
 
-            loop {
-                match ingest_state.read_with_exceptions([IngestStateError::DiskFull]) {
-                    Ok(_) => break,
-                    Err(e) => {
-                        warn!(
-                            ingest_state_error=%e,
-                            wait_duration=?OP_REPLAY_BACKPRESSURE_WAIT_DURATION,
-                            "ingest state is unhealthy, waiting for ingest state to recover before replaying wal op",
-                        );
-                        tokio::time::sleep(OP_REPLAY_BACKPRESSURE_WAIT_DURATION).await;
-                    }
-                }
-            }
-
             debug!(
                 ?op,
                 ?op_min_sequence_number,
@@ -385,10 +540,8 @@ where
                 "apply wal op"
             );
 
-            // Apply the operation to the provided DML sink
-            sink.apply(IngestOp::Write(op))
-                .await
-                .map_err(Into::<DmlError>::into)?;
+            rate_limit.wait().await;
+            apply_op(sink, op, ingest_state).await?;
 
             ok_op_count_metric.inc(1);
         }
@@ -400,6 +553,37 @@ where
     Ok(max_sequence)
 }
 
+/// Apply `op` to `sink` once the ingest state permits it.
+async fn apply_op<T>(
+    sink: &T,
+    op: WriteOperation,
+    ingest_state: &Arc<IngestState>,
+) -> Result<(), WalReplayError>
+where
+    T: DmlSink,
+{
+    loop {
+        match ingest_state.read_with_exceptions([IngestStateError::DiskFull]) {
+            Ok(_) => break,
+            Err(e) => {
+                warn!(
+                    ingest_state_error=%e,
+                    wait_duration=?OP_REPLAY_BACKPRESSURE_WAIT_DURATION,
+                    "ingest state is unhealthy, waiting for ingest state to recover before replaying wal op",
+                );
+                tokio::time::sleep(OP_REPLAY_BACKPRESSURE_WAIT_DURATION).await;
+            }
+        }
+    }
+
+    // Apply the operation to the provided DML sink
+    sink.apply(IngestOp::Write(op))
+        .await
+        .map_err(Into::<DmlError>::into)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::VecDeque, sync::Arc};
@@ -413,7 +597,10 @@ mod tests {
     use test_helpers::timeout::FutureTimeout;
     use wal::Wal;
 
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+
     use crate::{
+        buffer_snapshot::{BufferSnapshotDir, PartitionSnapshot},
         buffer_tree::partition::PartitionData,
         dml_payload::{encode::encode_write_op, IngestOp},
         dml_sink::mock_sink::MockDmlSink,
@@ -860,6 +1047,104 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_replay_with_snapshot() {
+        // Snapshot the data of the first two writes.
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = BufferSnapshotDir::new(dir.path()).unwrap();
+        let (_, data) = lp_to_mutable_batch(&format!(
+            r#"{},region=Belfast temp=14,climate="wet" 4242424242"#,
+            &*ARBITRARY_TABLE_NAME
+        ));
+        snapshots
+            .write(&[PartitionSnapshot {
+                namespace_id: ARBITRARY_NAMESPACE_ID,
+                table_id: ARBITRARY_TABLE_ID,
+                partition_id: ARBITRARY_TRANSITION_PARTITION_ID.clone(),
+                partition_key: ARBITRARY_PARTITION_KEY.clone(),
+                sequence_numbers: [1, 2].map(SequenceNumber::new).into_iter().collect(),
+                batch: data.to_arrow(schema::Projection::All).unwrap(),
+            }])
+            .unwrap();
+        let snapshot = snapshots.take().unwrap();
+
+        let wal = MockWalReader::new(
+            [
+                MockSegmentedWalOpBatchReader::new(SegmentId::new(1)).with_entry_results([Ok(
+                    vec![
+                        arbitrary_sequenced_wal_op(SequenceNumber::new(1)),
+                        arbitrary_sequenced_wal_op(SequenceNumber::new(2)),
+                        arbitrary_sequenced_wal_op(SequenceNumber::new(3)),
+                    ],
+                )]),
+            ],
+            [1],
+        );
+
+        let persist = Arc::new(MockPersistQueue::default());
+        let mock_sink = MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(())]);
+        let mock_iter = MockIter {
+            sink: mock_sink,
+            partitions: vec![],
+        };
+        let metrics = metric::Registry::default();
+        let registration = TaskRegistration::new(Arc::new(SystemProvider::new()));
+
+        let max_sequence_number = replay_with_snapshot(
+            &wal,
+            &mock_iter,
+            Arc::clone(&persist),
+            Arc::new(IngestState::default()),
+            snapshot,
+            ReplayRateLimit::default(),
+            &registration.progress_reporter(),
+            &metrics,
+        )
+        .await
+        .expect("failed to replay WAL");
+        assert_eq!(max_sequence_number, Some(SequenceNumber::new(3)));
+        assert!(wal.closed_segment_ids.lock().is_empty());
+
+        // The segment file is reported as replayed.
+        let progress = registration.into_tracker(()).progress();
+        assert_eq!(progress.done, 1);
+        assert_eq!(progress.total, Some(1));
+
+        // The snapshot data is applied in place of the first two writes.
+        let ops = mock_iter.sink.get_calls();
+        let sequence_numbers = ops
+            .iter()
+            .map(|op| match op {
+                IngestOp::Write(w) => w
+                    .tables()
+                    .map(|(id, t)| (*id, t.partitioned_data().sequence_number()))
+                    .collect::<Vec<_>>(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sequence_numbers,
+            [
+                [(ARBITRARY_TABLE_ID, SequenceNumber::new(2))],
+                [(ARBITRARY_TABLE_ID, SequenceNumber::new(3))],
+            ]
+        );
+
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "ingester_wal_replay_ops",
+            labels = Attributes::from(&[("outcome", "success")]),
+            value = 1,
+        );
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "ingester_wal_replay_ops",
+            labels = Attributes::from(&[("outcome", "skipped_snapshot")]),
+            value = 2,
+        );
+    }
+
     #[tokio::test]
     async fn test_replay_error_for_unknown_corruption() {
         let wal = MockWalReader::new(
@@ -943,9 +1228,12 @@ mod tests {
                 replay_file(
                     reader,
                     &mock_sink,
+                    &mut LoadedSnapshot::default(),
+                    &metric.recorder(&[]),
                     &metric.recorder(&[]),
                     &metric.recorder(&[]),
                     &ingest_state,
+                    &mut ReplayRateLimit::default(),
                 )
                 .await
             })
@@ -990,9 +1278,12 @@ mod tests {
             replay_file(
                 reader,
                 &mock_sink,
+                &mut LoadedSnapshot::default(),
+                &metric.recorder(&[]),
                 &metric.recorder(&[]),
                 &metric.recorder(&[]),
                 &Arc::clone(&ingest_state),
+                &mut ReplayRateLimit::default(),
             )
             .with_timeout_panic(Duration::from_secs(2))
             .await,
@@ -1002,4 +1293,54 @@ mod tests {
         );
         assert_eq!(mock_sink.get_calls().len(), 2);
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_replay_rate_limit() {
+        let metrics = metric::Registry::default();
+        let metric = metrics.register_metric::<U64Counter>("foo", "bar");
+        let reader = MockSegmentedWalOpBatchReader::new(SegmentId::new(1)).with_entry_results([
+            Ok(vec![
+                arbitrary_sequenced_wal_op(SequenceNumber::new(1)),
+                arbitrary_sequenced_wal_op(SequenceNumber::new(2)),
+            ]),
+            Ok(vec![arbitrary_sequenced_wal_op(SequenceNumber::new(3))]),
+        ]);
+        let mock_sink = MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(()), Ok(())]);
+        let ingest_state = Arc::new(IngestState::default());
+
+        // Two ops per second permits the first op immediately, and the next
+        // two after 500ms each.
+        let start = tokio::time::Instant::now();
+        assert_matches!(
+            replay_file(
+                reader,
+                &mock_sink,
+                &mut LoadedSnapshot::default(),
+                &metric.recorder(&[]),
+                &metric.recorder(&[]),
+                &metric.recorder(&[]),
+                &ingest_state,
+                &mut ReplayRateLimit::new(NonZeroU32::new(2)),
+            )
+            .await,
+            Ok(Some(id)) => {
+                assert_eq!(id, SequenceNumber::new(3));
+            }
+        );
+        assert_eq!(mock_sink.get_calls().len(), 3);
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_replay_eta() {
+        assert_eq!(replay_eta(Duration::from_secs(10), 0, 100), None);
+        assert_eq!(
+            replay_eta(Duration::from_secs(10), 100, 300),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            replay_eta(Duration::from_secs(10), 100, 0),
+            Some(Duration::ZERO)
+        );
+    }
 }
@@ -244,11 +244,13 @@ pub mod internal_implementation_details {
 }
 
 mod arcmap;
+mod buffer_snapshot;
 mod buffer_tree;
 mod cancellation_safe;
 mod deferred_load;
 mod dml_payload;
 mod dml_sink;
+mod frozen_partitions;
 mod gossip;
 mod ingest_state;
 mod ingester_id;
@@ -259,6 +261,7 @@ mod query_adaptor;
 pub(crate) mod server;
 mod timestamp_oracle;
 mod wal;
+mod write_lag;
 
 #[cfg(test)]
 mod test_util;
@@ -11,6 +11,7 @@ use tokio::{
     sync::{oneshot, OwnedSemaphorePermit},
     time::Instant,
 };
+use tracker::{ProgressReporter, TaskRegistration};
 
 use crate::{
     buffer_tree::{
@@ -42,15 +43,21 @@ pub(super) struct PersistRequest {
     data: PersistingData,
     enqueued_at: Instant,
     permit: OwnedSemaphorePermit,
+    registration: TaskRegistration,
+    span: Span,
 }
 
 impl PersistRequest {
     /// Construct a [`PersistRequest`] for `data` from `partition`, recording
     /// the current timestamp as the "enqueued at" point.
+    ///
+    /// The current tracing [`Span`] is captured so that the persist job is
+    /// executed within the context of the request that caused it.
     pub(super) fn new(
         partition: Arc<Mutex<PartitionData>>,
         data: PersistingData,
         permit: OwnedSemaphorePermit,
+        registration: TaskRegistration,
         enqueued_at: Instant,
     ) -> (Self, oneshot::Receiver<()>) {
         let (tx, rx) = oneshot::channel();
@@ -61,6 +68,8 @@ impl PersistRequest {
                 data,
                 enqueued_at,
                 permit,
+                registration,
+                span: Span::current(),
             },
             rx,
         )
@@ -126,6 +135,13 @@ pub(super) struct Context {
     /// work, and MUST be released at the end of the persistence AFTER any
     /// references to the persisted data are released.
     permit: OwnedSemaphorePermit,
+
+    /// The registration of the job tracking this persist, released once the
+    /// persist completes.
+    registration: TaskRegistration,
+
+    /// The tracing span active when this persist job was enqueued.
+    span: Span,
 }
 
 impl Context {
@@ -145,6 +161,8 @@ impl Context {
                 data,
                 enqueued_at,
                 permit,
+                registration,
+                span,
             } = req;
 
             let p = Arc::clone(&partition);
@@ -172,6 +190,8 @@ impl Context {
                 enqueued_at,
                 dequeued_at: Instant::now(),
                 permit,
+                registration,
+                span,
             }
         };
 
@@ -283,6 +303,16 @@ impl Context {
         self.enqueued_at
     }
 
+    /// Returns the handle used to report the progress of the job tracking
+    /// this persist.
+    pub(super) fn progress_reporter(&self) -> ProgressReporter {
+        self.registration.progress_reporter()
+    }
+
+    pub(super) fn span(&self) -> &Span {
+        &self.span
+    }
+
     pub(super) fn sort_key(&self) -> &SortKeyState {
         &self.sort_key
     }
@@ -0,0 +1,129 @@
+//! Broadcasting of persist completion events to external subscribers.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use super::completion_observer::{CompletedPersist, PersistCompletionObserver};
+
+/// The number of persist events buffered for each subscriber before the
+/// subscriber is considered lagging.
+const EVENT_BUFFER_SIZE: usize = 1_024;
+
+/// A handle to subscribe to the [`CompletedPersist`] events of this ingester.
+///
+/// Events are only delivered to subscribers that exist at the time of the
+/// event - there is no replay of past events. A subscriber that falls more
+/// than [`EVENT_BUFFER_SIZE`] events behind observes a
+/// [`broadcast::error::RecvError::Lagged`] error.
+#[derive(Debug, Clone)]
+pub(crate) struct PersistEvents {
+    tx: broadcast::Sender<Arc<CompletedPersist>>,
+}
+
+impl Default for PersistEvents {
+    fn default() -> Self {
+        let (tx, _rx) = broadcast::channel(EVENT_BUFFER_SIZE);
+        Self { tx }
+    }
+}
+
+impl PersistEvents {
+    /// Subscribe to all persist events from now on.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<Arc<CompletedPersist>> {
+        self.tx.subscribe()
+    }
+
+    fn publish(&self, note: Arc<CompletedPersist>) {
+        // An error means there are no subscribers, which is fine.
+        let _ = self.tx.send(note);
+    }
+}
+
+/// A [`PersistCompletionObserver`] decorator publishing each persist
+/// completion to the subscribers of [`PersistEvents`], after passing it to the
+/// inner observer.
+#[derive(Debug)]
+pub(crate) struct PersistEventObserver<T> {
+    inner: T,
+    events: PersistEvents,
+}
+
+impl<T> PersistEventObserver<T> {
+    pub(crate) fn new(inner: T, events: PersistEvents) -> Self {
+        Self { inner, events }
+    }
+}
+
+#[async_trait]
+impl<T> PersistCompletionObserver for PersistEventObserver<T>
+where
+    T: PersistCompletionObserver,
+{
+    async fn persist_complete(&self, note: Arc<CompletedPersist>) {
+        self.inner.persist_complete(Arc::clone(&note)).await;
+        self.events.publish(note);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::{
+        sequence_number_set::SequenceNumberSet, ColumnId, ColumnSet, CompactionLevel, ParquetFile,
+        ParquetFileId, Timestamp,
+    };
+
+    use super::*;
+    use crate::{
+        persist::completion_observer::mock::MockCompletionObserver,
+        test_util::{
+            ARBITRARY_NAMESPACE_ID, ARBITRARY_TABLE_ID, ARBITRARY_TRANSITION_PARTITION_ID,
+        },
+    };
+
+    fn arbitrary_note() -> Arc<CompletedPersist> {
+        Arc::new(CompletedPersist::new(
+            ParquetFile {
+                id: ParquetFileId::new(42),
+                namespace_id: ARBITRARY_NAMESPACE_ID,
+                table_id: ARBITRARY_TABLE_ID,
+                partition_id: ARBITRARY_TRANSITION_PARTITION_ID.clone(),
+                object_store_id: Default::default(),
+                min_time: Timestamp::new(1),
+                max_time: Timestamp::new(2),
+                to_delete: None,
+                file_size_bytes: 42_424_242,
+                row_count: 24,
+                compaction_level: CompactionLevel::Initial,
+                created_at: Timestamp::new(1234),
+                column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
+                max_l0_created_at: Timestamp::new(42),
+                content_hash: None,
+            },
+            SequenceNumberSet::default(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_publish() {
+        let inner = Arc::new(MockCompletionObserver::default());
+        let events = PersistEvents::default();
+        let observer = PersistEventObserver::new(Arc::clone(&inner), events.clone());
+
+        // Events without subscribers are dropped.
+        observer.persist_complete(arbitrary_note()).await;
+        assert_eq!(inner.calls().len(), 1);
+
+        let mut a = events.subscribe();
+        let mut b = events.subscribe();
+
+        let note = arbitrary_note();
+        observer.persist_complete(Arc::clone(&note)).await;
+        assert_eq!(inner.calls().len(), 2);
+
+        assert_eq!(a.recv().await.unwrap(), note);
+        assert_eq!(b.recv().await.unwrap(), note);
+        assert!(a.try_recv().is_err());
+    }
+}
@@ -1,8 +1,10 @@
 use std::{sync::Arc, time::Duration};
 
 use async_trait::async_trait;
+use data_types::job::Job;
 use iox_catalog::interface::Catalog;
 use iox_query::{exec::Executor, QueryChunk};
+use iox_time::SystemProvider;
 use metric::{DurationHistogram, DurationHistogramOptions, U64Counter, U64Gauge, DURATION_MAX};
 use observability_deps::tracing::*;
 use parking_lot::Mutex;
@@ -13,6 +15,7 @@ use tokio::{
     sync::{mpsc, oneshot, Semaphore, TryAcquireError},
     time::Instant,
 };
+use tracker::{AbstractTaskRegistry, TaskRegistry};
 
 use super::{
     backpressure::PersistState, completion_observer::PersistCompletionObserver,
@@ -21,7 +24,9 @@ use super::{
 use crate::{
     buffer_tree::partition::{persisting::PersistingData, PartitionData, SortKeyState},
     ingest_state::IngestState,
+    init::JOB_HISTORY_SIZE,
     persist::worker,
+    JobRegistry,
 };
 
 /// A persistence task submission handle.
@@ -164,6 +169,10 @@ pub(crate) struct PersistHandle {
 
     /// A counter tracking the number of enqueued into the persist system.
     enqueued_jobs: U64Counter,
+
+    /// The registry each persist job is registered in, exposing its progress
+    /// through the operations API.
+    jobs: Arc<Mutex<JobRegistry>>,
 }
 
 impl PersistHandle {
@@ -307,9 +316,20 @@ impl PersistHandle {
             worker_tasks,
             persist_state,
             enqueued_jobs,
+            jobs: Arc::new(Mutex::new(JobRegistry::new(
+                TaskRegistry::new(Arc::new(SystemProvider::new())),
+                JOB_HISTORY_SIZE,
+            ))),
         }
     }
 
+    /// Register persist jobs in `jobs`, rather than in a registry private to
+    /// this [`PersistHandle`].
+    pub(crate) fn with_jobs(mut self, jobs: Arc<Mutex<JobRegistry>>) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
     fn assign_worker(&self, r: PersistRequest) {
         debug!(
             partition_id = %r.partition_id(),
@@ -428,9 +448,34 @@ impl PersistQueue for PersistHandle {
             SortKeyState::Provided(v, _) => v.as_ref().cloned(),
         };
 
+        // Register the job tracking this persist now that it holds a place in
+        // the queue.
+        //
+        // A persist cannot be abandoned part way through without losing the
+        // data being persisted, so the job reports progress but is not
+        // cancellable.
+        let job = {
+            let p = partition.lock();
+            Job::PersistPartition {
+                table_id: p.table_id(),
+                partition_key: p.partition_key().clone(),
+            }
+        };
+        let (_tracker, registration) = {
+            let mut jobs = self.jobs.lock();
+            jobs.reclaim();
+            jobs.register(job)
+        };
+
         // Build the persist task request.
         let schema = data.schema().clone();
-        let (r, notify) = PersistRequest::new(Arc::clone(&partition), data, permit, enqueued_at);
+        let (r, notify) = PersistRequest::new(
+            Arc::clone(&partition),
+            data,
+            permit,
+            registration,
+            enqueued_at,
+        );
 
         match sort_key {
             Some(v) => {
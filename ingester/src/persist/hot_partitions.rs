@@ -1,6 +1,6 @@
 use std::{fmt::Debug, sync::Arc};
 
-use observability_deps::tracing::info;
+use observability_deps::tracing::{info, Instrument};
 use parking_lot::{Mutex, MutexGuard};
 
 use crate::buffer_tree::{partition::PartitionData, post_write::PostWriteObserver};
@@ -59,11 +59,16 @@ where
 
         // Perform the enqueue in a separate task, to avoid blocking this
         // writer if the persist system is saturated.
+        //
+        // The task inherits the span of the write that triggered it.
         let persist_handle = self.persist_handle.clone();
-        tokio::spawn(async move {
-            // There is no need to await on the completion handle.
-            persist_handle.enqueue(partition, data).await;
-        });
+        tokio::spawn(
+            async move {
+                // There is no need to await on the completion handle.
+                persist_handle.enqueue(partition, data).await;
+            }
+            .in_current_span(),
+        );
         // Update any exported metrics.
         self.persist_count.inc(1);
     }
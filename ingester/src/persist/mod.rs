@@ -5,10 +5,13 @@ pub(super) mod compact;
 pub(crate) mod completion_observer;
 mod context;
 pub(crate) mod drain_buffer;
+pub(crate) mod events;
 pub(crate) mod file_metrics;
 pub(crate) mod handle;
 pub(crate) mod hot_partitions;
+pub(crate) mod namespace_memory;
 pub mod queue;
+pub(crate) mod webhook;
 mod worker;
 
 #[cfg(test)]
@@ -16,7 +19,7 @@ mod tests {
     use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
     use assert_matches::assert_matches;
-    use data_types::{CompactionLevel, ParquetFile, SortedColumnSet};
+    use data_types::{ColumnSet, CompactionLevel, ParquetFile, SortedColumnSet};
     use futures::TryStreamExt;
     use iox_catalog::{
         interface::{get_schema_by_id, Catalog, SoftDeletedRows},
@@ -29,6 +32,7 @@ mod tests {
     use object_store::{memory::InMemory, ObjectMeta, ObjectStore};
     use parking_lot::Mutex;
     use parquet_file::{
+        metadata::IoxParquetMetaData,
         storage::{ParquetStorage, StorageId},
         ParquetFilePath,
     };
@@ -510,4 +514,113 @@ mod tests {
 
         assert_eq!(file.size, *file_size_bytes as usize);
     }
+
+    /// Columns renamed or dropped while their data is buffered are persisted
+    /// under their current name, or not at all.
+    #[tokio::test]
+    async fn test_persist_renamed_and_dropped_columns() {
+        maybe_start_logging();
+
+        let object_storage: Arc<dyn ObjectStore> = Arc::new(InMemory::default());
+        let storage = ParquetStorage::new(Arc::clone(&object_storage), StorageId::from("iox"));
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metrics)));
+        let ingest_state = Arc::new(IngestState::default());
+        let completion_observer = Arc::new(MockCompletionObserver::default());
+
+        // Initialise the persist system.
+        let handle = PersistHandle::new(
+            1,
+            2,
+            Arc::clone(&ingest_state),
+            Arc::new(Executor::new_testing()),
+            storage,
+            Arc::clone(&catalog),
+            Arc::clone(&completion_observer),
+            &metrics,
+        );
+
+        // Generate a partition with data, and rename and drop its columns
+        let partition = partition_with_write(Arc::clone(&catalog)).await;
+        let table_id = partition.lock().table_id();
+        let partition_id = partition.lock().partition_id().clone();
+        let namespace_id = partition.lock().namespace_id();
+
+        let mut repos = catalog.repositories().await;
+        let region = repos
+            .columns()
+            .rename(table_id, "region", "country")
+            .await
+            .expect("failed to rename column");
+        repos
+            .columns()
+            .delete(table_id, "temp")
+            .await
+            .expect("failed to drop column");
+        let time = repos
+            .columns()
+            .list_by_table_id(table_id)
+            .await
+            .expect("query for columns failed")
+            .into_iter()
+            .find(|c| c.name == "time")
+            .expect("column time not found");
+        drop(repos);
+
+        // Persist the data buffered before the rename and drop
+        let data = partition
+            .lock()
+            .mark_persisting()
+            .expect("partition with write should transition to persisting");
+        handle
+            .enqueue(Arc::clone(&partition), data)
+            .await
+            .with_timeout(Duration::from_secs(10))
+            .await
+            .expect("timeout waiting for completion notification")
+            .expect("worker task failed");
+
+        // The sort key uses the new name
+        assert_matches!(partition.lock().sort_key(), SortKeyState::Provided(Some(sort_key), _) => {
+            let sort_key_columns = sort_key.to_columns().collect::<Vec<_>>();
+            assert_eq!(sort_key_columns, &["country", "time"]);
+        });
+
+        // The file does not contain the dropped column...
+        let files = catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition_id)
+            .await
+            .expect("query for parquet files failed");
+        let file = assert_matches!(&*files, [file] => file);
+        assert_eq!(file.column_set, ColumnSet::new([region.id, time.id]));
+
+        // ...and stores the renamed column under its new name, as it was
+        // created after the rename
+        let path =
+            ParquetFilePath::new(namespace_id, table_id, &partition_id, file.object_store_id)
+                .object_store_path();
+        let bytes = object_storage
+            .get(&path)
+            .await
+            .expect("failed to get file")
+            .bytes()
+            .await
+            .expect("failed to read file");
+        let schema = IoxParquetMetaData::from_file_bytes(bytes)
+            .expect("failed to decode metadata")
+            .expect("file has metadata")
+            .decode()
+            .expect("failed to decode metadata")
+            .read_schema()
+            .expect("failed to read schema");
+        let mut names = schema
+            .iter()
+            .map(|(_t, f)| f.name().as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, &["country", "time"]);
+    }
 }
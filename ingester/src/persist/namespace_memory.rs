@@ -0,0 +1,637 @@
+//! Per-namespace accounting of buffered data, and enforcement of limits on it.
+//!
+//! The hot partition persister and the persist queue depth bound the size of
+//! individual partitions and the ingester as a whole, but a single namespace
+//! writing to many partitions can still consume most of the ingester's memory
+//! without any one partition becoming "hot". This module periodically sums
+//! the data buffered for each namespace and, if configured:
+//!
+//!   * Persists all partitions of a namespace that exceeds the soft limit.
+//!   * Rejects writes to a namespace that exceeds the hard limit, until
+//!     persistence brings it back under the limit.
+//!   * Applies backpressure to all writes (and WAL replay) while the ingester
+//!     as a whole buffers more than the buffer limit, persisting the largest
+//!     partitions until it is back under the limit.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use data_types::NamespaceId;
+use metric::{Attributes, Metric, U64Gauge};
+use observability_deps::tracing::*;
+use parking_lot::{Mutex, RwLock};
+use thiserror::Error;
+use tracker::{subsystem_allocated_bytes, MemorySubsystem};
+
+use crate::{
+    ingest_state::{IngestState, IngestStateError},
+    partition_iter::PartitionIter,
+    persist::{drain_buffer::persist_partitions, queue::PersistQueue},
+};
+
+/// How often the data buffered for each namespace is summed.
+pub(crate) const ACCOUNTING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Limits on the amount of data (as estimated by
+/// [`MutableBatch::size_data()`]) buffered for a single namespace.
+///
+/// Both limits are disabled by default.
+///
+/// [`MutableBatch::size_data()`]: mutable_batch::MutableBatch::size_data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NamespaceMemoryLimits {
+    /// Persist all buffered partitions of a namespace once it buffers more
+    /// than this many bytes.
+    pub soft_limit_bytes: Option<usize>,
+
+    /// Reject writes to a namespace while it buffers more than this many
+    /// bytes.
+    pub hard_limit_bytes: Option<usize>,
+}
+
+impl NamespaceMemoryLimits {
+    fn is_enabled(&self) -> bool {
+        self.soft_limit_bytes.is_some() || self.hard_limit_bytes.is_some()
+    }
+
+    /// Returns an error if a hard limit is set without a lower soft limit.
+    ///
+    /// Without a soft limit, nothing persists a namespace that is over the
+    /// hard limit, so writes to it would be rejected until other triggers
+    /// (e.g. WAL rotation) persist its data.
+    pub fn validate(&self) -> Result<(), InvalidNamespaceMemoryLimits> {
+        match (self.soft_limit_bytes, self.hard_limit_bytes) {
+            (None, Some(_)) => Err(InvalidNamespaceMemoryLimits::HardLimitWithoutSoftLimit),
+            (Some(soft_limit_bytes), Some(hard_limit_bytes))
+                if soft_limit_bytes >= hard_limit_bytes =>
+            {
+                Err(InvalidNamespaceMemoryLimits::SoftLimitNotBelowHardLimit {
+                    soft_limit_bytes,
+                    hard_limit_bytes,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An invalid combination of [`NamespaceMemoryLimits`].
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidNamespaceMemoryLimits {
+    /// A hard limit is set without a soft limit.
+    #[error("a hard limit requires a soft limit")]
+    HardLimitWithoutSoftLimit,
+
+    /// The soft limit is not lower than the hard limit.
+    #[error(
+        "the soft limit ({soft_limit_bytes} bytes) must be lower than the hard limit \
+        ({hard_limit_bytes} bytes)"
+    )]
+    SoftLimitNotBelowHardLimit {
+        /// The configured soft limit.
+        soft_limit_bytes: usize,
+        /// The configured hard limit.
+        hard_limit_bytes: usize,
+    },
+}
+
+/// The error returned for writes to a namespace over its hard limit.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error(
+    "namespace {namespace_id} is buffering {buffered_bytes} bytes, over the limit of \
+    {limit_bytes} bytes - {}",
+    recovery(.persisting)
+)]
+pub(crate) struct NamespaceMemoryLimitError {
+    namespace_id: NamespaceId,
+    buffered_bytes: usize,
+    limit_bytes: usize,
+    /// True if the namespace is being persisted because it is over the soft
+    /// limit.
+    persisting: bool,
+}
+
+fn recovery(persisting: &bool) -> &'static str {
+    if *persisting {
+        "persisting buffered data"
+    } else {
+        "waiting for buffered data to be persisted"
+    }
+}
+
+/// A limit on the amount of data buffered by the ingester as a whole,
+/// enforced by setting [`IngestStateError::BufferFull`].
+#[derive(Debug)]
+struct BufferLimit {
+    limit_bytes: usize,
+    ingest_state: Arc<IngestState>,
+}
+
+/// The most recent per-namespace accounting, shared between the accounting
+/// task and the write path.
+#[derive(Debug)]
+pub(crate) struct NamespaceMemory {
+    limits: NamespaceMemoryLimits,
+    buffer_limit: Option<BufferLimit>,
+
+    /// The namespaces currently over the hard limit, and the number of bytes
+    /// they were observed to buffer.
+    over_hard_limit: RwLock<HashMap<NamespaceId, usize>>,
+
+    /// The number of bytes buffered per namespace.
+    buffered_bytes: Metric<U64Gauge>,
+}
+
+impl NamespaceMemory {
+    pub(crate) fn new(limits: NamespaceMemoryLimits, metrics: &metric::Registry) -> Self {
+        let buffered_bytes = metrics.register_metric::<U64Gauge>(
+            "ingester_namespace_buffered_bytes",
+            "estimated size of the data buffered (and not yet persisting) per namespace",
+        );
+
+        Self {
+            limits,
+            buffer_limit: None,
+            over_hard_limit: Default::default(),
+            buffered_bytes,
+        }
+    }
+
+    /// Apply backpressure by setting [`IngestStateError::BufferFull`] in
+    /// `ingest_state` while more than `limit_bytes` are buffered across all
+    /// namespaces.
+    pub(crate) fn with_buffer_limit(
+        mut self,
+        limit_bytes: usize,
+        ingest_state: Arc<IngestState>,
+    ) -> Self {
+        self.buffer_limit = Some(BufferLimit {
+            limit_bytes,
+            ingest_state,
+        });
+        self
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.limits.is_enabled() || self.buffer_limit.is_some()
+    }
+
+    /// Returns an error if writes to `namespace_id` should be rejected.
+    pub(crate) fn check(&self, namespace_id: NamespaceId) -> Result<(), NamespaceMemoryLimitError> {
+        let Some(limit_bytes) = self.limits.hard_limit_bytes else {
+            return Ok(());
+        };
+
+        match self.over_hard_limit.read().get(&namespace_id) {
+            Some(&buffered_bytes) => Err(NamespaceMemoryLimitError {
+                namespace_id,
+                buffered_bytes,
+                limit_bytes,
+                persisting: self
+                    .limits
+                    .soft_limit_bytes
+                    .is_some_and(|soft_limit| buffered_bytes > soft_limit),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Record the number of bytes buffered per namespace, returning the
+    /// namespaces over the soft limit.
+    ///
+    /// Namespaces with no buffered data are expected to be included with a
+    /// value of 0, so their metrics are reset.
+    pub(crate) fn account(&self, usage: &HashMap<NamespaceId, usize>) -> Vec<NamespaceId> {
+        for (namespace_id, bytes) in usage {
+            self.buffered_bytes
+                .recorder(Attributes::from([(
+                    "namespace_id",
+                    namespace_id.get().to_string().into(),
+                )]))
+                .set(*bytes as u64);
+        }
+
+        if let Some(limit) = self.limits.hard_limit_bytes {
+            let over: HashMap<_, _> = usage
+                .iter()
+                .filter(|(_, bytes)| **bytes > limit)
+                .map(|(id, bytes)| (*id, *bytes))
+                .collect();
+
+            let mut over_hard_limit = self.over_hard_limit.write();
+            for (namespace_id, buffered_bytes) in &over {
+                if !over_hard_limit.contains_key(namespace_id) {
+                    warn!(
+                        %namespace_id,
+                        buffered_bytes,
+                        limit_bytes = limit,
+                        "namespace over buffered data hard limit, rejecting writes"
+                    );
+                }
+            }
+            for namespace_id in over_hard_limit.keys() {
+                if !over.contains_key(namespace_id) {
+                    info!(
+                        %namespace_id,
+                        "namespace back within buffered data hard limit, accepting writes"
+                    );
+                }
+            }
+            *over_hard_limit = over;
+        }
+
+        match self.limits.soft_limit_bytes {
+            Some(limit) => usage
+                .iter()
+                .filter(|(_, bytes)| **bytes > limit)
+                .map(|(id, _)| *id)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Record the total number of bytes buffered across all namespaces,
+    /// returning the number of bytes that must be persisted to bring the
+    /// ingester back under half of the buffer limit, if it is over the limit.
+    pub(crate) fn account_total(&self, total_bytes: usize) -> Option<usize> {
+        let BufferLimit {
+            limit_bytes,
+            ingest_state,
+        } = self.buffer_limit.as_ref()?;
+
+        if total_bytes <= *limit_bytes {
+            if ingest_state.unset(IngestStateError::BufferFull) {
+                info!(
+                    total_bytes,
+                    limit_bytes, "ingester back within buffered data limit, lifting backpressure"
+                );
+            }
+            return None;
+        }
+
+        if ingest_state.set(IngestStateError::BufferFull) {
+            warn!(
+                total_bytes,
+                limit_bytes, "ingester over buffered data limit, applying backpressure"
+            );
+        }
+
+        Some(total_bytes - limit_bytes / 2)
+    }
+}
+
+/// Periodically sum the data buffered for each namespace in `buffer`,
+/// persisting the partitions of namespaces over the soft limit of `memory`
+/// and updating the set of namespaces over the hard limit. A namespace is not
+/// enqueued again while its previous persistence is still in progress.
+///
+/// If the total buffered across all namespaces exceeds the buffer limit of
+/// `memory`, backpressure is applied and the largest partitions are persisted
+/// until the total is back under half the limit. When heap tracking is
+/// enabled, the total is the measured heap usage of the mutable buffer rather
+/// than the sum of the estimates.
+///
+/// This task runs forever, or returns immediately if no limits are
+/// configured.
+pub(crate) async fn enforce_namespace_memory_limits<T, P>(
+    memory: Arc<NamespaceMemory>,
+    buffer: T,
+    persist: P,
+    period: Duration,
+) where
+    T: PartitionIter + Sync + 'static,
+    P: PersistQueue + Clone + 'static,
+{
+    if !memory.is_enabled() {
+        return;
+    }
+
+    // Namespaces seen in previous rounds, so their usage can be reported as 0
+    // once their data is persisted.
+    let mut usage: HashMap<NamespaceId, usize> = HashMap::new();
+
+    // Namespaces whose partitions are being persisted because they exceeded
+    // the soft limit.
+    let persisting: Arc<Mutex<HashSet<NamespaceId>>> = Default::default();
+
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+
+        usage.values_mut().for_each(|v| *v = 0);
+        for partition in buffer.partition_iter() {
+            let p = partition.lock();
+            *usage.entry(p.namespace_id()).or_default() += p.persist_cost_estimate();
+        }
+
+        for namespace_id in memory.account(&usage) {
+            if !persisting.lock().insert(namespace_id) {
+                debug!(
+                    %namespace_id,
+                    buffered_bytes = usage[&namespace_id],
+                    "namespace over buffered data soft limit already persisting"
+                );
+                continue;
+            }
+
+            info!(
+                %namespace_id,
+                buffered_bytes = usage[&namespace_id],
+                "namespace over buffered data soft limit, persisting"
+            );
+
+            let partitions = buffer
+                .partition_iter()
+                .filter(move |p| p.lock().namespace_id() == namespace_id)
+                .collect::<Vec<_>>();
+
+            // Persisting is not awaited in this loop so the hard limit is
+            // lifted as soon as the buffered data is persisting.
+            let persist = persist.clone();
+            let persisting = Arc::clone(&persisting);
+            tokio::spawn(async move {
+                persist_partitions(partitions.into_iter(), &persist).await;
+                persisting.lock().remove(&namespace_id);
+            });
+        }
+
+        // Prefer the measured heap usage of the mutable buffer (which includes
+        // data being persisted) over the estimates, if heap tracking is
+        // enabled.
+        let total = subsystem_allocated_bytes(MemorySubsystem::MutableBuffer)
+            .unwrap_or_else(|| usage.values().sum());
+        if let Some(mut to_persist) = memory.account_total(total) {
+            let mut partitions = buffer
+                .partition_iter()
+                .map(|p| {
+                    let cost = p.lock().persist_cost_estimate();
+                    (cost, p)
+                })
+                .collect::<Vec<_>>();
+            partitions.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+
+            let partitions = partitions
+                .into_iter()
+                .take_while(|(cost, _)| {
+                    let take = to_persist > 0 && *cost > 0;
+                    to_persist = to_persist.saturating_sub(*cost);
+                    take
+                })
+                .map(|(_, p)| p)
+                .collect::<Vec<_>>();
+
+            let persist = persist.clone();
+            tokio::spawn(async move {
+                persist_partitions(partitions.into_iter(), &persist).await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use assert_matches::assert_matches;
+    use async_trait::async_trait;
+    use data_types::SequenceNumber;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use test_helpers::timeout::FutureTimeout;
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::{
+        buffer_tree::partition::{persisting::PersistingData, PartitionData},
+        test_util::{PartitionDataBuilder, ARBITRARY_NAMESPACE_ID, ARBITRARY_TABLE_NAME},
+    };
+
+    const NS_A: NamespaceId = NamespaceId::new(1);
+    const NS_B: NamespaceId = NamespaceId::new(2);
+
+    fn buffered_bytes(metrics: &metric::Registry, namespace_id: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Gauge>>("ingester_namespace_buffered_bytes")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("namespace_id", namespace_id)]))
+            .expect("failed to get observer")
+            .fetch()
+    }
+
+    #[test]
+    fn test_hard_limit() {
+        let metrics = metric::Registry::default();
+        let memory = NamespaceMemory::new(
+            NamespaceMemoryLimits {
+                soft_limit_bytes: None,
+                hard_limit_bytes: Some(100),
+            },
+            &metrics,
+        );
+
+        assert!(memory
+            .account(&[(NS_A, 101), (NS_B, 100)].into())
+            .is_empty());
+        assert_eq!(
+            memory.check(NS_A),
+            Err(NamespaceMemoryLimitError {
+                namespace_id: NS_A,
+                buffered_bytes: 101,
+                limit_bytes: 100,
+                persisting: false,
+            })
+        );
+        assert_eq!(
+            memory.check(NS_A).unwrap_err().to_string(),
+            "namespace 1 is buffering 101 bytes, over the limit of 100 bytes - waiting for \
+            buffered data to be persisted"
+        );
+        assert_eq!(memory.check(NS_B), Ok(()));
+
+        assert_eq!(buffered_bytes(&metrics, "1"), 101);
+
+        // Once persisted, writes are accepted again.
+        memory.account(&[(NS_A, 0), (NS_B, 100)].into());
+        assert_eq!(memory.check(NS_A), Ok(()));
+        assert_eq!(buffered_bytes(&metrics, "1"), 0);
+    }
+
+    #[test]
+    fn test_soft_limit() {
+        let metrics = metric::Registry::default();
+        let memory = NamespaceMemory::new(
+            NamespaceMemoryLimits {
+                soft_limit_bytes: Some(50),
+                hard_limit_bytes: None,
+            },
+            &metrics,
+        );
+
+        assert_eq!(memory.account(&[(NS_A, 51), (NS_B, 50)].into()), [NS_A]);
+
+        // The soft limit never rejects writes.
+        assert_eq!(memory.check(NS_A), Ok(()));
+    }
+
+    #[test]
+    fn test_hard_and_soft_limit() {
+        let memory = NamespaceMemory::new(
+            NamespaceMemoryLimits {
+                soft_limit_bytes: Some(50),
+                hard_limit_bytes: Some(100),
+            },
+            &Default::default(),
+        );
+
+        assert_eq!(memory.account(&[(NS_A, 101)].into()), [NS_A]);
+        assert_eq!(
+            memory.check(NS_A).unwrap_err().to_string(),
+            "namespace 1 is buffering 101 bytes, over the limit of 100 bytes - persisting \
+            buffered data"
+        );
+    }
+
+    #[test]
+    fn test_buffer_limit() {
+        let ingest_state = Arc::new(IngestState::default());
+        let memory = NamespaceMemory::new(NamespaceMemoryLimits::default(), &Default::default())
+            .with_buffer_limit(100, Arc::clone(&ingest_state));
+
+        assert_eq!(memory.account_total(100), None);
+        assert!(ingest_state.read().is_ok());
+
+        // Going over the limit applies backpressure, and asks for enough data
+        // to be persisted to get under half the limit.
+        assert_eq!(memory.account_total(130), Some(80));
+        assert_matches!(ingest_state.read(), Err(IngestStateError::BufferFull));
+
+        // Which is lifted once back under the limit.
+        assert_eq!(memory.account_total(40), None);
+        assert!(ingest_state.read().is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        let limits = |soft_limit_bytes, hard_limit_bytes| NamespaceMemoryLimits {
+            soft_limit_bytes,
+            hard_limit_bytes,
+        };
+
+        assert_eq!(limits(None, None).validate(), Ok(()));
+        assert_eq!(limits(Some(50), None).validate(), Ok(()));
+        assert_eq!(limits(Some(50), Some(100)).validate(), Ok(()));
+        assert_eq!(
+            limits(None, Some(100)).validate(),
+            Err(InvalidNamespaceMemoryLimits::HardLimitWithoutSoftLimit)
+        );
+        assert_eq!(
+            limits(Some(100), Some(100)).validate(),
+            Err(InvalidNamespaceMemoryLimits::SoftLimitNotBelowHardLimit {
+                soft_limit_bytes: 100,
+                hard_limit_bytes: 100,
+            })
+        );
+    }
+
+    type PersistJob = (
+        Arc<Mutex<PartitionData>>,
+        PersistingData,
+        oneshot::Sender<()>,
+    );
+
+    /// A [`PersistQueue`] whose persist jobs only complete once released.
+    #[derive(Debug, Default)]
+    struct BlockingPersistQueue {
+        enqueued: AtomicUsize,
+        jobs: Mutex<Vec<PersistJob>>,
+    }
+
+    impl BlockingPersistQueue {
+        fn enqueued(&self) -> usize {
+            self.enqueued.load(Ordering::Relaxed)
+        }
+
+        /// Complete all outstanding persist jobs.
+        fn release(&self) {
+            for (partition, data, tx) in self.jobs.lock().drain(..) {
+                partition.lock().mark_persisted(data);
+                let _ = tx.send(());
+            }
+        }
+    }
+
+    #[async_trait]
+    impl PersistQueue for BlockingPersistQueue {
+        #[allow(clippy::async_yields_async)]
+        async fn enqueue(
+            &self,
+            partition: Arc<Mutex<PartitionData>>,
+            data: PersistingData,
+        ) -> oneshot::Receiver<()> {
+            let (tx, rx) = oneshot::channel();
+            self.enqueued.fetch_add(1, Ordering::Relaxed);
+            self.jobs.lock().push((partition, data, tx));
+            rx
+        }
+    }
+
+    fn write(partition: &Mutex<PartitionData>, sequence_number: u64) {
+        let mb = lp_to_mutable_batch(&format!(
+            r#"{},city=Hereford people=1 {sequence_number}"#,
+            &*ARBITRARY_TABLE_NAME
+        ))
+        .1;
+        partition
+            .lock()
+            .buffer_write(mb, SequenceNumber::new(sequence_number))
+            .expect("write should succeed");
+    }
+
+    async fn wait_for_enqueued(queue: &BlockingPersistQueue, n: usize) {
+        async {
+            while queue.enqueued() < n {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_soft_limit_skips_persisting_namespace() {
+        let partition = Arc::new(Mutex::new(PartitionDataBuilder::new().build()));
+        assert_eq!(partition.lock().namespace_id(), ARBITRARY_NAMESPACE_ID);
+        let memory = Arc::new(NamespaceMemory::new(
+            NamespaceMemoryLimits {
+                soft_limit_bytes: Some(1),
+                hard_limit_bytes: None,
+            },
+            &Default::default(),
+        ));
+        let queue = Arc::new(BlockingPersistQueue::default());
+
+        let task = tokio::spawn(enforce_namespace_memory_limits(
+            memory,
+            vec![Arc::clone(&partition)],
+            Arc::clone(&queue),
+            Duration::from_millis(10),
+        ));
+
+        write(&partition, 1);
+        wait_for_enqueued(&queue, 1).await;
+
+        // More writes keep the namespace over the soft limit, but it is not
+        // enqueued again while it is persisting.
+        write(&partition, 2);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(queue.enqueued(), 1);
+
+        // Once persisted, the new data is persisted.
+        queue.release();
+        wait_for_enqueued(&queue, 2).await;
+
+        task.abort();
+    }
+}
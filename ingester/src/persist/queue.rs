@@ -177,6 +177,7 @@ pub(crate) mod mock {
                             created_at: Timestamp::new(1234),
                             column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
                             max_l0_created_at: Timestamp::new(42),
+                            content_hash: None,
                         },
                         sequence_numbers,
                     )))
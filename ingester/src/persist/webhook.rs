@@ -0,0 +1,368 @@
+//! Delivery of persist completion notifications to an external webhook.
+
+use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use data_types::NamespaceId;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use metric::U64Counter;
+use observability_deps::tracing::*;
+use parquet_file::ParquetFilePath;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use super::completion_observer::{CompletedPersist, PersistCompletionObserver};
+
+/// The maximum number of notifications waiting for delivery, after which new
+/// notifications are dropped.
+const NOTIFICATION_QUEUE_DEPTH: usize = 1_000;
+
+/// The number of attempts made to deliver a notification.
+const MAX_DELIVERY_ATTEMPTS: usize = 3;
+
+/// The delay between delivery attempts of a notification.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The timeout of a single webhook request.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The record delivered for each persisted Parquet file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct PersistNotification {
+    namespace_id: i64,
+    /// The name of the namespace, if it could be resolved.
+    namespace: Option<String>,
+    table_id: i64,
+    partition_id: String,
+    parquet_file_id: i64,
+    /// The path of the file, relative to the root of the object store.
+    object_store_path: String,
+    min_time: i64,
+    max_time: i64,
+    file_size_bytes: i64,
+    row_count: i64,
+}
+
+impl PersistNotification {
+    fn new(note: &CompletedPersist, namespace: Option<String>) -> Self {
+        let file = note.file_record();
+        Self {
+            namespace_id: file.namespace_id.get(),
+            namespace,
+            table_id: file.table_id.get(),
+            partition_id: file.partition_id.to_string(),
+            parquet_file_id: file.id.get(),
+            object_store_path: ParquetFilePath::from(file).object_store_path().to_string(),
+            min_time: file.min_time.get(),
+            max_time: file.max_time.get(),
+            file_size_bytes: file.file_size_bytes,
+            row_count: file.row_count,
+        }
+    }
+}
+
+/// A destination for [`PersistNotification`] records.
+#[async_trait]
+pub(crate) trait NotificationSink: Debug + Send + Sync + 'static {
+    /// Deliver `notification`, returning an error if it was not accepted.
+    async fn send(
+        &self,
+        notification: &PersistNotification,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// A [`NotificationSink`] POSTing each notification as a JSON object to a
+/// webhook URL.
+#[derive(Debug)]
+pub(crate) struct WebhookSink {
+    client: reqwest::Client,
+    url: reqwest::Url,
+}
+
+impl WebhookSink {
+    pub(crate) fn new(url: reqwest::Url) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for WebhookSink {
+    async fn send(
+        &self,
+        notification: &PersistNotification,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.client
+            .post(self.url.clone())
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(notification)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// A [`PersistCompletionObserver`] decorator delivering a
+/// [`PersistNotification`] for each persisted file to a [`NotificationSink`].
+///
+/// Delivery happens asynchronously in a background task, so a slow or
+/// unavailable sink never delays persistence. Notifications are delivered at
+/// most once: those that cannot be queued because the sink is too far behind,
+/// or that fail [`MAX_DELIVERY_ATTEMPTS`] times, are dropped and counted.
+#[derive(Debug)]
+pub(crate) struct PersistNotifier<T> {
+    inner: T,
+    tx: mpsc::Sender<Arc<CompletedPersist>>,
+    dropped: U64Counter,
+}
+
+impl<T> PersistNotifier<T> {
+    pub(crate) fn new<S>(
+        inner: T,
+        sink: S,
+        catalog: Arc<dyn Catalog>,
+        metrics: &metric::Registry,
+    ) -> Self
+    where
+        S: NotificationSink,
+    {
+        let notifications = metrics.register_metric::<U64Counter>(
+            "ingester_persist_notifications",
+            "number of persist completion notifications by delivery result",
+        );
+        let dropped = notifications.recorder(&[("result", "dropped")]);
+        let delivered = notifications.recorder(&[("result", "delivered")]);
+        let failed = notifications.recorder(&[("result", "failed")]);
+
+        info!(?sink, "delivering persist completion notifications");
+
+        let (tx, rx) = mpsc::channel(NOTIFICATION_QUEUE_DEPTH);
+        tokio::spawn(deliver(rx, sink, catalog, delivered, failed));
+
+        Self { inner, tx, dropped }
+    }
+}
+
+#[async_trait]
+impl<T> PersistCompletionObserver for PersistNotifier<T>
+where
+    T: PersistCompletionObserver,
+{
+    async fn persist_complete(&self, note: Arc<CompletedPersist>) {
+        if self.tx.try_send(Arc::clone(&note)).is_err() {
+            warn!(
+                partition_id = %note.partition_id(),
+                "dropping persist completion notification"
+            );
+            self.dropped.inc(1);
+        }
+
+        // Forward on the notification to the next handler.
+        self.inner.persist_complete(note).await;
+    }
+}
+
+/// Deliver the notifications received from `rx` to `sink` until all senders
+/// are dropped.
+async fn deliver<S>(
+    mut rx: mpsc::Receiver<Arc<CompletedPersist>>,
+    sink: S,
+    catalog: Arc<dyn Catalog>,
+    delivered: U64Counter,
+    failed: U64Counter,
+) where
+    S: NotificationSink,
+{
+    // Namespace names never change, so they are cached indefinitely.
+    let mut names: HashMap<NamespaceId, String> = HashMap::new();
+
+    while let Some(note) = rx.recv().await {
+        let namespace_id = note.namespace_id();
+        let namespace = match names.get(&namespace_id) {
+            Some(name) => Some(name.clone()),
+            None => {
+                let name = match catalog
+                    .repositories()
+                    .await
+                    .namespaces()
+                    .get_by_id(namespace_id, SoftDeletedRows::AllRows)
+                    .await
+                {
+                    Ok(ns) => ns.map(|ns| ns.name),
+                    Err(e) => {
+                        warn!(error=%e, %namespace_id, "failed to resolve namespace name");
+                        None
+                    }
+                };
+                if let Some(name) = &name {
+                    names.insert(namespace_id, name.clone());
+                }
+                name
+            }
+        };
+
+        let notification = PersistNotification::new(&note, namespace);
+
+        let mut attempt = 1;
+        loop {
+            match sink.send(&notification).await {
+                Ok(()) => {
+                    delivered.inc(1);
+                    break;
+                }
+                Err(e) if attempt < MAX_DELIVERY_ATTEMPTS => {
+                    debug!(error=%e, attempt, "retrying persist completion notification");
+                    attempt += 1;
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+                Err(e) => {
+                    warn!(
+                        error=%e,
+                        parquet_file_id = notification.parquet_file_id,
+                        "failed to deliver persist completion notification"
+                    );
+                    failed.inc(1);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use data_types::{
+        sequence_number_set::SequenceNumberSet, ColumnId, ColumnSet, CompactionLevel, ParquetFile,
+        ParquetFileId, Timestamp,
+    };
+    use iox_catalog::mem::MemCatalog;
+    use metric::{assert_counter, Attributes};
+    use parking_lot::Mutex;
+    use test_helpers::timeout::FutureTimeout;
+
+    use super::*;
+    use crate::{
+        persist::completion_observer::mock::MockCompletionObserver,
+        test_util::{ARBITRARY_TABLE_ID, ARBITRARY_TRANSITION_PARTITION_ID},
+    };
+
+    /// A sink recording the notifications it receives, failing the first
+    /// `failures` attempts.
+    #[derive(Debug, Default)]
+    struct MockSink {
+        failures: AtomicUsize,
+        calls: Mutex<Vec<PersistNotification>>,
+    }
+
+    #[async_trait]
+    impl NotificationSink for Arc<MockSink> {
+        async fn send(
+            &self,
+            notification: &PersistNotification,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if self
+                .failures
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| v.checked_sub(1))
+                .is_ok()
+            {
+                return Err("bananas".into());
+            }
+            self.calls.lock().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notification() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metrics)));
+        let namespace = catalog
+            .repositories()
+            .await
+            .namespaces()
+            .create(&"platanos".try_into().unwrap(), None, None, None)
+            .await
+            .unwrap();
+
+        let file = ParquetFile {
+            id: ParquetFileId::new(42),
+            namespace_id: namespace.id,
+            table_id: ARBITRARY_TABLE_ID,
+            partition_id: ARBITRARY_TRANSITION_PARTITION_ID.clone(),
+            object_store_id: Default::default(),
+            min_time: Timestamp::new(1),
+            max_time: Timestamp::new(2),
+            to_delete: None,
+            file_size_bytes: 42_424_242,
+            row_count: 24,
+            compaction_level: CompactionLevel::Initial,
+            created_at: Timestamp::new(1234),
+            column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
+            max_l0_created_at: Timestamp::new(42),
+            content_hash: None,
+        };
+
+        // The first attempt fails, and is retried.
+        let sink = Arc::new(MockSink {
+            failures: AtomicUsize::new(1),
+            ..Default::default()
+        });
+        let inner = Arc::new(MockCompletionObserver::default());
+        let observer = PersistNotifier::new(
+            Arc::clone(&inner),
+            Arc::clone(&sink),
+            Arc::clone(&catalog),
+            &metrics,
+        );
+
+        let note = Arc::new(CompletedPersist::new(
+            file.clone(),
+            SequenceNumberSet::default(),
+        ));
+        observer.persist_complete(Arc::clone(&note)).await;
+
+        // The notification is passed through to the inner observer.
+        assert_eq!(inner.calls(), [Arc::clone(&note)]);
+
+        // And delivered to the sink.
+        async {
+            loop {
+                if !sink.calls.lock().is_empty() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        assert_eq!(
+            *sink.calls.lock(),
+            [PersistNotification {
+                namespace_id: namespace.id.get(),
+                namespace: Some("platanos".to_string()),
+                table_id: ARBITRARY_TABLE_ID.get(),
+                partition_id: ARBITRARY_TRANSITION_PARTITION_ID.to_string(),
+                parquet_file_id: 42,
+                object_store_path: ParquetFilePath::from(&file).object_store_path().to_string(),
+                min_time: 1,
+                max_time: 2,
+                file_size_bytes: 42_424_242,
+                row_count: 24,
+            }]
+        );
+
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "ingester_persist_notifications",
+            labels = Attributes::from(&[("result", "delivered")]),
+            value = 1,
+        );
+    }
+}
@@ -1,19 +1,26 @@
-use std::{ops::ControlFlow, sync::Arc};
+use std::{collections::HashMap, ops::ControlFlow, sync::Arc};
 
+use arrow_util::util::rename_columns;
 use async_channel::RecvError;
 use backoff::Backoff;
-use data_types::{ColumnsByName, CompactionLevel, ParquetFile, ParquetFileParams, SortedColumnSet};
+use data_types::{
+    file_column_names, resolve_column_name, ColumnRename, ColumnsByName, CompactionLevel,
+    ParquetFile, ParquetFileParams, SortedColumnSet, Timestamp,
+};
 use iox_catalog::interface::{get_table_columns_by_id, CasFailure, Catalog};
-use iox_query::exec::Executor;
-use iox_time::{SystemProvider, TimeProvider};
+use iox_query::{exec::Executor, QueryChunk};
+use iox_time::{SystemProvider, Time, TimeProvider};
 use metric::DurationHistogram;
-use observability_deps::tracing::{debug, info, warn};
-use parquet_file::{metadata::IoxMetadata, storage::ParquetStorage};
-use schema::sort::SortKey;
+use observability_deps::tracing::{debug, info, warn, Instrument};
+use parquet_file::{
+    metadata::IoxMetadata,
+    storage::{rename_sort_key_columns, rename_stream_columns, ParquetStorage},
+};
+use schema::sort::{SortKey, SortKeyBuilder};
 use tokio::{sync::mpsc, time::Instant};
 use uuid::Uuid;
 
-use crate::persist::compact::compact_persisting_batch;
+use crate::{persist::compact::compact_persisting_batch, query_adaptor::QueryAdaptor};
 
 use super::{
     compact::CompactedStream,
@@ -115,32 +122,49 @@ pub(super) async fn run_task<O>(
 
         let mut ctx = Context::new(req);
 
+        // Report the progress of the job through its two stages: the upload of
+        // the parquet file, and the catalog update that makes it visible.
+        let progress = ctx.progress_reporter();
+        progress.add_total(2);
+
         // Capture the time spent in the queue.
         let started_at = Instant::now();
         queue_duration.record(started_at.duration_since(ctx.enqueued_at()));
 
-        // Compact the data, generate the parquet file from the result, and
-        // upload it to object storage.
-        //
-        // If this process generated a new sort key that must be added to the
-        // catalog, attempt to update the catalog with a compare-and-swap
-        // operation; if this update fails due to a concurrent sort key update,
-        // the compaction must be redone with the new sort key and uploaded
-        // before continuing.
-        let parquet_table_data = loop {
-            match compact_and_upload(&mut ctx, &worker_state).await {
-                Ok(v) => break v,
-                Err(PersistError::ConcurrentSortKeyUpdate(_sort_key, _sort_key_ids)) => continue,
+        // Run the job within the span of the request that caused it (if any)
+        // so log events are correlated with it.
+        let span = ctx.span().clone();
+        async {
+            // Compact the data, generate the parquet file from the result, and
+            // upload it to object storage.
+            //
+            // If this process generated a new sort key that must be added to the
+            // catalog, attempt to update the catalog with a compare-and-swap
+            // operation; if this update fails due to a concurrent sort key update,
+            // the compaction must be redone with the new sort key and uploaded
+            // before continuing.
+            let parquet_table_data = loop {
+                match compact_and_upload(&mut ctx, &worker_state).await {
+                    Ok(v) => break v,
+                    Err(PersistError::ConcurrentSortKeyUpdate(_sort_key, _sort_key_ids)) => {
+                        continue
+                    }
+                };
             };
-        };
+            progress.add_done(1);
 
-        // Make the newly uploaded parquet file visible to other nodes.
-        let parquet_file = update_catalog_parquet(&ctx, &worker_state, &parquet_table_data).await;
+            // Make the newly uploaded parquet file visible to other nodes.
+            let parquet_file =
+                update_catalog_parquet(&ctx, &worker_state, &parquet_table_data).await;
+            progress.add_done(1);
 
-        // And finally mark the persist job as complete and notify any
-        // observers.
-        ctx.mark_complete(parquet_file, &worker_state.completion_observer)
-            .await;
+            // And finally mark the persist job as complete and notify any
+            // observers.
+            ctx.mark_complete(parquet_file, &worker_state.completion_observer)
+                .await;
+        }
+        .instrument(span)
+        .await;
 
         // Capture the time spent actively persisting.
         let now = Instant::now();
@@ -175,17 +199,36 @@ where
     // sort key MUST be serialised.
     let (sort_key, sort_key_ids) = ctx.sort_key().get().await;
 
-    // Fetch the "column name -> column ID" map.
+    // The creation time of the parquet file, which determines the names that
+    // readers expect for columns renamed after it.
+    //
+    // This MUST be taken before the column renames are read below, so that
+    // the file is written using the names of all renames after this time.
+    let created_at = SystemProvider::new().now();
+
+    // Fetch the "column name -> column ID" map and the column renames.
     //
     // This MUST happen after the sort key has loaded, to ensure all the columns
     // defined in the sort key are present in the map. If the values were
     // fetched in reverse order, a race exists where the sort key could be
     // updated to include a column that does not exist in the column map.
-    let column_map = fetch_column_map(ctx, worker_state, sort_key.as_ref()).await?;
-
-    let compacted = compact(ctx, worker_state, sort_key.as_ref()).await;
-    let (sort_key_update, parquet_table_data) =
-        upload(ctx, worker_state, compacted, &column_map).await;
+    let (column_map, renames) = fetch_column_map(ctx, worker_state, sort_key.as_ref()).await?;
+
+    // The locally cached sort key and the buffered data may use the names
+    // columns had before they were renamed.
+    let sort_key = sort_key.map(|sort_key| current_sort_key(&sort_key, &column_map, &renames));
+    let data = current_column_data(ctx, ctx.data().query_adaptor(), &column_map, &renames);
+
+    let compacted = compact(ctx, worker_state, sort_key.as_ref(), data).await;
+    let (sort_key_update, parquet_table_data) = upload(
+        ctx,
+        worker_state,
+        compacted,
+        &column_map,
+        &renames,
+        created_at,
+    )
+    .await;
 
     if let Some(sort_key_update) = sort_key_update {
         update_catalog_sort_key(
@@ -203,12 +246,78 @@ where
     Ok(parquet_table_data)
 }
 
-/// Compact the data in `ctx` using sorted by the sort key returned from
-/// [`Context::sort_key()`].
+/// Rename the columns of `sort_key` that were renamed since it was read to
+/// their current names.
+fn current_sort_key(
+    sort_key: &SortKey,
+    columns: &ColumnsByName,
+    renames: &[ColumnRename],
+) -> SortKey {
+    sort_key
+        .iter()
+        .fold(
+            SortKeyBuilder::with_capacity(sort_key.len()),
+            |builder, (name, options)| {
+                let (name, _column) = resolve_column_name(columns, renames, name)
+                    .expect("sort key columns are in the column map");
+                builder.with_col_sort_opts(name, *options)
+            },
+        )
+        .build()
+}
+
+/// Rename the columns of the buffered `data` that were renamed since the data
+/// was written to their current names, and remove the data of columns that
+/// were dropped since (including columns re-created with a different type).
+fn current_column_data(
+    ctx: &Context,
+    data: QueryAdaptor,
+    columns: &ColumnsByName,
+    renames: &[ColumnRename],
+) -> QueryAdaptor {
+    let current_names = data
+        .schema()
+        .iter()
+        .map(|(influx_type, field)| {
+            let current_name = resolve_column_name(columns, renames, field.name())
+                .filter(|(_name, column)| column.matches_type(influx_type))
+                .map(|(name, _column)| name.to_string());
+            if current_name.is_none() {
+                warn!(
+                    namespace_id = %ctx.namespace_id(),
+                    table_id = %ctx.table_id(),
+                    partition_id = %ctx.partition_id(),
+                    column = field.name().as_str(),
+                    "discarding buffered data of dropped column"
+                );
+            }
+            (field.name().clone(), current_name)
+        })
+        .collect::<HashMap<_, _>>();
+
+    if current_names
+        .iter()
+        .all(|(name, current_name)| current_name.as_ref() == Some(name))
+    {
+        return data;
+    }
+
+    let partition_id = data.partition_id().clone();
+    let batches = data
+        .into_record_batches()
+        .iter()
+        .map(|batch| rename_columns(batch, |field| current_names[field.name()].clone()))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("buffered columns of the same column have the same type");
+    QueryAdaptor::new(partition_id, batches)
+}
+
+/// Compact `data` sorted by the sort key returned from [`Context::sort_key()`].
 async fn compact<O>(
     ctx: &Context,
     worker_state: &SharedWorkerState<O>,
     sort_key: Option<&SortKey>,
+    data: QueryAdaptor,
 ) -> CompactedStream
 where
     O: Send + Sync,
@@ -224,7 +333,7 @@ where
         "compacting partition"
     );
 
-    assert!(!ctx.data().record_batches().is_empty());
+    assert!(!data.record_batches().is_empty());
 
     // Run a compaction sort the data and resolve any duplicate values.
     //
@@ -234,7 +343,7 @@ where
         &worker_state.exec,
         sort_key,
         ctx.table().get().await.name().clone(),
-        ctx.data().query_adaptor(),
+        data,
     )
     .await
 }
@@ -246,6 +355,8 @@ async fn upload<O>(
     worker_state: &SharedWorkerState<O>,
     compacted: CompactedStream,
     columns: &ColumnsByName,
+    renames: &[ColumnRename],
+    created_at: Time,
 ) -> (Option<SortKey>, ParquetFileParams)
 where
     O: Send + Sync,
@@ -272,11 +383,23 @@ where
         "uploading partition parquet"
     );
 
+    // Columns renamed after the file was created are stored under the name
+    // they had at that time.
+    let renamed_columns = file_column_names(columns, renames, Timestamp::from(created_at));
+    let record_stream = rename_stream_columns(record_stream, &renamed_columns);
+    let data_sort_key = rename_sort_key_columns(&data_sort_key, &renamed_columns);
+    let column_ids = columns
+        .iter()
+        .map(|(name, column)| {
+            let name = renamed_columns.get(name).unwrap_or(name);
+            (name.as_str(), column.id)
+        })
+        .collect::<HashMap<_, _>>();
+
     // Construct the metadata for this parquet file.
-    let time_now = SystemProvider::new().now();
     let iox_metadata = IoxMetadata {
         object_store_id,
-        creation_timestamp: time_now,
+        creation_timestamp: created_at,
         namespace_id: ctx.namespace_id(),
         namespace_name: Arc::clone(&*ctx.namespace_name().get().await),
         table_id: ctx.table_id(),
@@ -284,14 +407,14 @@ where
         partition_key: ctx.partition_key().clone(),
         compaction_level: CompactionLevel::Initial,
         sort_key: Some(data_sort_key),
-        max_l0_created_at: time_now,
+        max_l0_created_at: created_at,
     };
 
     // Save the compacted data to a parquet file in object storage.
     //
     // This call retries until it completes.
     let pool = worker_state.exec.pool();
-    let (md, file_size) = worker_state
+    let (md, file_size, content_hash) = worker_state
         .store
         .upload(record_stream, ctx.partition_id(), &iox_metadata, pool)
         .await
@@ -311,23 +434,26 @@ where
 
     // Build the data that must be inserted into the parquet_files catalog
     // table in order to make the file visible to queriers.
-    let parquet_table_data =
-        iox_metadata.to_parquet_file(ctx.partition_id().clone(), file_size, &md, |name| {
-            columns
-                .get(name)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "unknown column {name} in table ID {table_id}",
-                        table_id = ctx.table_id().get()
-                    )
-                })
-                .id
-        });
+    let parquet_table_data = iox_metadata.to_parquet_file(
+        ctx.partition_id().clone(),
+        file_size,
+        content_hash,
+        &md,
+        |name| {
+            *column_ids.get(name).unwrap_or_else(|| {
+                panic!(
+                    "unknown column {name} in table ID {table_id}",
+                    table_id = ctx.table_id().get()
+                )
+            })
+        },
+    );
 
     (catalog_sort_key_update, parquet_table_data)
 }
 
-/// Fetch the table column map from the catalog and verify if they contain all columns in the sort key
+/// Fetch the table column map and the column renames from the catalog and verify if they contain all columns in the
+/// sort key
 async fn fetch_column_map<O>(
     ctx: &Context,
     worker_state: &SharedWorkerState<O>,
@@ -336,23 +462,32 @@ async fn fetch_column_map<O>(
     // The purpose to put the sort_key as a param here is to make sure the caller has already loaded the sort key
     // and the same sort_key is returned
     sort_key: Option<&SortKey>,
-) -> Result<ColumnsByName, PersistError>
+) -> Result<(ColumnsByName, Vec<ColumnRename>), PersistError>
 where
     O: Send + Sync,
 {
     // Read the table's columns from the catalog to get a map of column name -> column IDs.
-    let column_map = Backoff::new(&Default::default())
+    //
+    // The renames are read after the columns, so that they include all renames
+    // of the read columns.
+    let (column_map, renames) = Backoff::new(&Default::default())
         .retry_all_errors("get table schema", || async {
             let mut repos = worker_state.catalog.repositories().await;
-            get_table_columns_by_id(ctx.table_id(), repos.as_mut()).await
+            let column_map = get_table_columns_by_id(ctx.table_id(), repos.as_mut()).await?;
+            let renames = repos
+                .columns()
+                .list_renames_by_table_id(ctx.table_id())
+                .await?;
+            Ok::<_, iox_catalog::interface::Error>((column_map, renames))
         })
         .await
         .expect("retry forever");
 
-    // Verify that the sort key columns are in the column map
+    // Verify that the sort key columns are in the column map, possibly under
+    // a new name
     if let Some(sort_key) = &sort_key {
         for sort_key_column in sort_key.to_columns() {
-            if !column_map.contains_column_name(sort_key_column) {
+            if resolve_column_name(&column_map, &renames, sort_key_column).is_none() {
                 panic!(
                     "sort key column {} of partition id {} is not in the column map {:?}",
                     sort_key_column,
@@ -363,7 +498,7 @@ where
         }
     }
 
-    Ok(column_map)
+    Ok((column_map, renames))
 }
 
 /// Update the sort key value stored in the catalog for this [`Context`].
@@ -2,7 +2,7 @@
 
 use async_trait::async_trait;
 use data_types::{NamespaceId, TableId};
-use iox_time::{SystemProvider, TimeProvider};
+use iox_time::{SystemProvider, Time, TimeProvider};
 use metric::{DurationHistogram, Metric};
 use predicate::Predicate;
 use trace::span::Span;
@@ -74,16 +74,48 @@ where
             .query_exec(namespace_id, table_id, projection, span, predicate)
             .await;
 
+        self.record(t, &res);
+
+        res
+    }
+
+    #[inline(always)]
+    async fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        projection: OwnedProjection,
+        span: Option<Span>,
+    ) -> Result<Self::Response, QueryError> {
+        let t = self.time_provider.now();
+
+        let res = self
+            .inner
+            .query_last_values(namespace_id, table_id, projection, span)
+            .await;
+
+        self.record(t, &res);
+
+        res
+    }
+}
+
+impl<T, P> QueryExecInstrumentation<T, P>
+where
+    P: TimeProvider,
+{
+    /// Record the duration of a call started at `t` with the given result.
+    fn record<R>(&self, t: Time, res: &Result<R, QueryError>) {
         if let Some(delta) = self.time_provider.now().checked_duration_since(t) {
-            match &res {
+            match res {
                 Ok(_) => self.query_duration_success.record(delta),
-                Err(QueryError::TableNotFound { .. } | QueryError::NamespaceNotFound { .. }) => {
-                    self.query_duration_error_not_found.record(delta)
-                }
+                Err(
+                    QueryError::TableNotFound { .. }
+                    | QueryError::NamespaceNotFound { .. }
+                    | QueryError::LastValueCacheDisabled { .. },
+                ) => self.query_duration_error_not_found.record(delta),
             };
         }
-
-        res
     }
 }
 
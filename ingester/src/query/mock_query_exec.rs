@@ -35,4 +35,17 @@ impl QueryExec for MockQueryExec {
             .take()
             .unwrap_or(Err(QueryError::NamespaceNotFound(NamespaceId::new(42))))
     }
+
+    async fn query_last_values(
+        &self,
+        _namespace_id: NamespaceId,
+        _table_id: TableId,
+        _projection: OwnedProjection,
+        _span: Option<Span>,
+    ) -> Result<Self::Response, QueryError> {
+        self.response
+            .lock()
+            .take()
+            .unwrap_or(Err(QueryError::NamespaceNotFound(NamespaceId::new(42))))
+    }
 }
@@ -216,6 +216,34 @@ where
             .query_exec(namespace_id, table_id, projection, span, predicate)
             .await?;
 
+        Ok(self.instrument(stream, started_at))
+    }
+
+    async fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        projection: OwnedProjection,
+        span: Option<Span>,
+    ) -> Result<Self::Response, QueryError> {
+        let started_at = self.time_provider.now();
+
+        let stream = self
+            .inner
+            .query_last_values(namespace_id, table_id, projection, span)
+            .await?;
+
+        Ok(self.instrument(stream, started_at))
+    }
+}
+
+impl<T, P> QueryResultInstrumentation<T, P>
+where
+    P: TimeProvider + Clone,
+{
+    /// Wrap `stream` to record the metrics of the query started at
+    /// `started_at` as it is consumed.
+    fn instrument(&self, stream: QueryResponse, started_at: Time) -> QueryResponse {
         let stream = QueryMetricContext::new(
             stream.into_partition_stream(),
             started_at,
@@ -227,7 +255,7 @@ where
             self.partition_hist.clone(),
         );
 
-        Ok(QueryResponse::new(PartitionStream::new(stream)))
+        QueryResponse::new(PartitionStream::new(stream))
     }
 }
 
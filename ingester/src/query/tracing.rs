@@ -68,6 +68,32 @@ where
             }
         }
     }
+
+    #[inline(always)]
+    async fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        projection: OwnedProjection,
+        span: Option<Span>,
+    ) -> Result<Self::Response, QueryError> {
+        let mut recorder = SpanRecorder::new(span).child(self.name.clone());
+
+        match self
+            .inner
+            .query_last_values(namespace_id, table_id, projection, recorder.span().cloned())
+            .await
+        {
+            Ok(v) => {
+                recorder.ok("query_last_values complete");
+                Ok(v)
+            }
+            Err(e) => {
+                recorder.error(e.to_string());
+                Err(e)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -16,6 +16,9 @@ pub(crate) enum QueryError {
 
     #[error("table id {1} not found in namespace id {0}")]
     TableNotFound(NamespaceId, TableId),
+
+    #[error("last value cache is not enabled for table id {1} in namespace id {0}")]
+    LastValueCacheDisabled(NamespaceId, TableId),
 }
 
 #[async_trait]
@@ -30,6 +33,16 @@ pub(crate) trait QueryExec: Send + Sync + Debug {
         span: Option<Span>,
         predicate: Option<Predicate>,
     ) -> Result<Self::Response, QueryError>;
+
+    /// Return the values held in the last value cache of every partition of
+    /// the specified table, instead of the buffered data.
+    async fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        projection: OwnedProjection,
+        span: Option<Span>,
+    ) -> Result<Self::Response, QueryError>;
 }
 
 #[async_trait]
@@ -51,4 +64,16 @@ where
             .query_exec(namespace_id, table_id, projection, span, predicate)
             .await
     }
+
+    async fn query_last_values(
+        &self,
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        projection: OwnedProjection,
+        span: Option<Span>,
+    ) -> Result<Self::Response, QueryError> {
+        self.deref()
+            .query_last_values(namespace_id, table_id, projection, span)
+            .await
+    }
 }
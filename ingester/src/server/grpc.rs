@@ -11,11 +11,12 @@ use service_grpc_catalog::CatalogService;
 
 use crate::{
     dml_sink::DmlSink,
-    ingest_state::IngestState,
+    frozen_partitions::FrozenPartitions,
+    ingest_state::{BackpressureResponse, IngestState},
     ingester_id::IngesterId,
     init::IngesterRpcInterface,
     partition_iter::PartitionIter,
-    persist::queue::PersistQueue,
+    persist::{events::PersistEvents, namespace_memory::NamespaceMemory, queue::PersistQueue},
     query::{response::QueryResponse, QueryExec},
     timestamp_oracle::TimestampOracle,
 };
@@ -38,6 +39,10 @@ pub(crate) struct GrpcDelegate<D, Q, T, P> {
     metrics: Arc<metric::Registry>,
     buffer: Arc<T>,
     persist_handle: Arc<P>,
+    namespace_memory: Arc<NamespaceMemory>,
+    frozen_partitions: Arc<FrozenPartitions>,
+    backpressure_response: BackpressureResponse,
+    persist_events: PersistEvents,
 }
 
 impl<D, Q, T, P> GrpcDelegate<D, Q, T, P>
@@ -59,6 +64,10 @@ where
         metrics: Arc<metric::Registry>,
         buffer: Arc<T>,
         persist_handle: Arc<P>,
+        namespace_memory: Arc<NamespaceMemory>,
+        frozen_partitions: Arc<FrozenPartitions>,
+        backpressure_response: BackpressureResponse,
+        persist_events: PersistEvents,
     ) -> Self {
         Self {
             dml_sink,
@@ -70,6 +79,10 @@ where
             metrics,
             buffer,
             persist_handle,
+            namespace_memory,
+            frozen_partitions,
+            backpressure_response,
+            persist_events,
         }
     }
 }
@@ -104,6 +117,9 @@ where
             Arc::clone(&self.timestamp),
             Arc::clone(&self.ingest_state),
         )
+        .with_namespace_memory(Arc::clone(&self.namespace_memory))
+        .with_frozen_partitions(Arc::clone(&self.frozen_partitions))
+        .with_backpressure_response(self.backpressure_response)
     }
 
     /// Return a [`PersistService`] gRPC implementation.
@@ -114,6 +130,7 @@ where
             Arc::clone(&self.buffer),
             Arc::clone(&self.persist_handle),
             Arc::clone(&self.catalog),
+            self.persist_events.clone(),
         )
     }
 
@@ -1,19 +1,25 @@
 use crate::{
     partition_iter::PartitionIter,
-    persist::{drain_buffer::persist_partitions, queue::PersistQueue},
+    persist::{drain_buffer::persist_partitions, events::PersistEvents, queue::PersistQueue},
 };
+use futures::{stream, Stream};
 use generated_types::influxdata::iox::ingester::v1::{
     self as proto, persist_service_server::PersistService,
 };
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
-use std::sync::Arc;
+use observability_deps::tracing::warn;
+use std::{pin::Pin, sync::Arc};
+use tokio::sync::broadcast::error::RecvError;
 use tonic::{Request, Response};
 
+type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send + 'static>>;
+
 #[derive(Debug)]
 pub(crate) struct PersistHandler<T, P> {
     buffer: T,
     persist_handle: P,
     catalog: Arc<dyn Catalog>,
+    events: PersistEvents,
 }
 
 impl<T, P> PersistHandler<T, P>
@@ -21,11 +27,17 @@ where
     T: PartitionIter + Sync + 'static,
     P: PersistQueue + Clone + Sync + 'static,
 {
-    pub(crate) fn new(buffer: T, persist_handle: P, catalog: Arc<dyn Catalog>) -> Self {
+    pub(crate) fn new(
+        buffer: T,
+        persist_handle: P,
+        catalog: Arc<dyn Catalog>,
+        events: PersistEvents,
+    ) -> Self {
         Self {
             buffer,
             persist_handle,
             catalog,
+            events,
         }
     }
 }
@@ -36,6 +48,8 @@ where
     T: PartitionIter + Sync + 'static,
     P: PersistQueue + Clone + Sync + 'static,
 {
+    type WatchPersistEventsStream = TonicStream<proto::PersistEvent>;
+
     /// Handle the RPC request to persist immediately. Will block until the data has persisted,
     /// which is useful in tests asserting on persisted data. May behave in unexpected ways if used
     /// concurrently with writes and ingester WAL rotations.
@@ -65,4 +79,59 @@ where
 
         Ok(Response::new(proto::PersistResponse {}))
     }
+
+    /// Stream the files persisted by this ingester, optionally limited to a
+    /// single namespace, until the subscriber disconnects or lags too far
+    /// behind.
+    async fn watch_persist_events(
+        &self,
+        request: Request<proto::WatchPersistEventsRequest>,
+    ) -> Result<Response<Self::WatchPersistEventsStream>, tonic::Status> {
+        let request = request.into_inner();
+
+        let namespace_id = if request.namespace.is_empty() {
+            None
+        } else {
+            let namespace = self
+                .catalog
+                .repositories()
+                .await
+                .namespaces()
+                .get_by_name(&request.namespace, SoftDeletedRows::ExcludeDeleted)
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?
+                .ok_or_else(|| tonic::Status::not_found(&request.namespace))?;
+            Some(namespace.id)
+        };
+
+        // Subscribe before returning, so no events after this call are missed.
+        let rx = self.events.subscribe();
+
+        let events = stream::unfold(Some(rx), move |rx| async move {
+            let mut rx = rx?;
+            loop {
+                match rx.recv().await {
+                    Ok(note) => {
+                        if namespace_id.is_some_and(|id| id != note.namespace_id()) {
+                            continue;
+                        }
+                        let event = proto::PersistEvent {
+                            file: Some(note.file_record().clone().into()),
+                        };
+                        return Some((Ok(event), Some(rx)));
+                    }
+                    Err(RecvError::Lagged(n)) => {
+                        warn!(skipped = n, "persist event subscriber lagging");
+                        let status = tonic::Status::resource_exhausted(format!(
+                            "subscriber lagging, {n} persist events skipped"
+                        ));
+                        return Some((Err(status), None));
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(events)))
+    }
 }
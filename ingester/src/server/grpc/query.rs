@@ -62,6 +62,7 @@ impl From<QueryError> for tonic::Status {
 
         let code = match e {
             QueryError::TableNotFound(_, _) | QueryError::NamespaceNotFound(_) => Code::NotFound,
+            QueryError::LastValueCacheDisabled(_, _) => Code::FailedPrecondition,
         };
 
         Self::new(code, e.to_string())
@@ -209,17 +210,30 @@ where
 
         let projection = OwnedProjection::from(request.columns);
 
-        let response = match self
-            .query_handler
-            .query_exec(
-                namespace_id,
-                table_id,
-                projection,
-                query_recorder.child_span("query exec"),
-                predicate,
-            )
-            .await
-        {
+        // Queries for the last value cache are served from the cache alone,
+        // ignoring the predicate.
+        let response = if request.last_values {
+            self.query_handler
+                .query_last_values(
+                    namespace_id,
+                    table_id,
+                    projection,
+                    query_recorder.child_span("query last values"),
+                )
+                .await
+        } else {
+            self.query_handler
+                .query_exec(
+                    namespace_id,
+                    table_id,
+                    projection,
+                    query_recorder.child_span("query exec"),
+                    predicate,
+                )
+                .await
+        };
+
+        let response = match response {
             Ok(v) => v,
             Err(e @ (QueryError::TableNotFound(_, _) | QueryError::NamespaceNotFound(_))) => {
                 debug!(
@@ -231,6 +245,7 @@ where
 
                 return Err(e)?;
             }
+            Err(e @ QueryError::LastValueCacheDisabled(_, _)) => return Err(e)?,
         };
 
         let output = encode_response(
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use data_types::{NamespaceId, PartitionKey, TableId};
 use generated_types::influxdata::iox::ingester::v1::{
@@ -13,13 +13,16 @@ use trace::{
     ctx::SpanContext,
     span::{SpanExt, SpanRecorder},
 };
+use tracker::{enter_subsystem, MemorySubsystem};
 
 use crate::{
     buffer_tree::BufferWriteError,
     dml_payload::write::{PartitionedData, TableData, WriteOperation},
     dml_payload::IngestOp,
     dml_sink::{DmlError, DmlSink},
-    ingest_state::{IngestState, IngestStateError},
+    frozen_partitions::FrozenPartitions,
+    ingest_state::{BackpressureResponse, IngestState, IngestStateError},
+    persist::namespace_memory::{NamespaceMemory, NamespaceMemoryLimitError},
     timestamp_oracle::TimestampOracle,
 };
 
@@ -47,6 +50,23 @@ enum RpcError {
     /// set by a subsystem. See [`IngestState`] for documentation.
     #[error(transparent)]
     SystemState(IngestStateError),
+
+    /// The ingester is applying backpressure, and is configured to reject
+    /// writes with [`BackpressureResponse::Reject`].
+    #[error("write rejected: {0}")]
+    Rejected(IngestStateError),
+
+    /// The namespace being written to is buffering more data than its hard
+    /// limit allows.
+    #[error(transparent)]
+    NamespaceMemoryLimit(NamespaceMemoryLimitError),
+
+    /// The partition being written to is frozen for maintenance.
+    #[error("partition {partition_key} of table {table_id} is frozen for maintenance")]
+    PartitionFrozen {
+        table_id: TableId,
+        partition_key: PartitionKey,
+    },
 }
 
 impl From<RpcError> for tonic::Status {
@@ -56,6 +76,10 @@ impl From<RpcError> for tonic::Status {
             RpcError::SystemState(IngestStateError::PersistSaturated) => Code::ResourceExhausted,
             RpcError::SystemState(IngestStateError::DiskFull) => Code::ResourceExhausted,
             RpcError::SystemState(IngestStateError::GracefulStop) => Code::FailedPrecondition,
+            RpcError::SystemState(IngestStateError::BufferFull) => Code::ResourceExhausted,
+            RpcError::Rejected(_) => Code::Unavailable,
+            RpcError::NamespaceMemoryLimit(_) => Code::ResourceExhausted,
+            RpcError::PartitionFrozen { .. } => Code::FailedPrecondition,
         };
 
         Self::new(code, e.to_string())
@@ -101,9 +125,20 @@ fn map_write_error(e: mutable_batch::Error) -> tonic::Status {
             // failure.
             Status::internal(e.to_string())
         }
+        mutable_batch::Error::WriterError {
+            source: writer::Error::DictionaryOverflow { .. },
+        } => {
+            // Partition buffers are closed early rather than overflowing, so
+            // this is only reachable if a single write cannot be buffered.
+            Status::resource_exhausted(e.to_string())
+        }
     }
 }
 
+/// The interval at which the [`IngestState`] is re-evaluated while delaying a
+/// write with [`BackpressureResponse::Delay`].
+const BACKPRESSURE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 /// A gRPC [`WriteService`] handler.
 ///
 /// This handler accepts writes from an upstream, and applies them to the
@@ -113,6 +148,9 @@ pub(crate) struct RpcWrite<T> {
     sink: T,
     timestamp: Arc<TimestampOracle>,
     ingest_state: Arc<IngestState>,
+    backpressure_response: BackpressureResponse,
+    namespace_memory: Option<Arc<NamespaceMemory>>,
+    frozen_partitions: Option<Arc<FrozenPartitions>>,
 }
 
 impl<T> RpcWrite<T> {
@@ -127,6 +165,65 @@ impl<T> RpcWrite<T> {
             sink,
             timestamp,
             ingest_state,
+            backpressure_response: BackpressureResponse::default(),
+            namespace_memory: None,
+            frozen_partitions: None,
+        }
+    }
+
+    /// Respond to writes received while the ingester is applying backpressure
+    /// as configured by `backpressure_response`.
+    pub(crate) fn with_backpressure_response(
+        mut self,
+        backpressure_response: BackpressureResponse,
+    ) -> Self {
+        self.backpressure_response = backpressure_response;
+        self
+    }
+
+    /// Reject writes to namespaces over the hard limit of `namespace_memory`.
+    pub(crate) fn with_namespace_memory(mut self, namespace_memory: Arc<NamespaceMemory>) -> Self {
+        self.namespace_memory = Some(namespace_memory);
+        self
+    }
+
+    /// Reject writes to the partitions in `frozen_partitions`.
+    pub(crate) fn with_frozen_partitions(
+        mut self,
+        frozen_partitions: Arc<FrozenPartitions>,
+    ) -> Self {
+        self.frozen_partitions = Some(frozen_partitions);
+        self
+    }
+
+    /// Return an error if the [`IngestState`] does not permit writes,
+    /// responding to backpressure as configured.
+    async fn check_ingest_state(&self) -> Result<(), RpcError> {
+        let Err(e) = self.ingest_state.read() else {
+            return Ok(());
+        };
+        if !e.is_backpressure() {
+            return Err(RpcError::SystemState(e));
+        }
+
+        let max_delay = match self.backpressure_response {
+            BackpressureResponse::Throttle => return Err(RpcError::SystemState(e)),
+            BackpressureResponse::Reject => return Err(RpcError::Rejected(e)),
+            BackpressureResponse::Delay(v) => v,
+        };
+
+        let deadline = tokio::time::Instant::now() + max_delay;
+        loop {
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return self.ingest_state.read().map_err(RpcError::SystemState);
+            }
+            tokio::time::sleep(BACKPRESSURE_POLL_INTERVAL.min(deadline - now)).await;
+
+            match self.ingest_state.read() {
+                Err(e) if e.is_backpressure() => continue,
+                v => return v.map_err(RpcError::SystemState),
+            }
         }
     }
 }
@@ -164,7 +261,9 @@ where
         //   * Deploy more ingesters to reduce the request load on any single
         //     ingester.
         //
-        self.ingest_state.read().map_err(RpcError::SystemState)?;
+        // Depending on the configured BackpressureResponse, writes may instead
+        // be held for a while in the hope the backpressure is lifted.
+        self.check_ingest_state().await?;
 
         // Extract the remote address for debugging.
         let remote_addr = request
@@ -173,11 +272,38 @@ where
             .unwrap_or_else(|| "<unknown>".to_string());
         let payload = request.into_inner().payload.ok_or(RpcError::NoPayload)?;
 
-        let batches = decode_database_batch(&payload).map_err(RpcError::Decode)?;
+        // The decoded batches are moved into (or become) partition buffers.
+        let batches = {
+            let _guard = enter_subsystem(MemorySubsystem::MutableBuffer);
+            decode_database_batch(&payload).map_err(RpcError::Decode)?
+        };
         let num_tables = batches.len();
         let namespace_id = NamespaceId::new(payload.database_id);
         let partition_key = PartitionKey::from(payload.partition_key);
 
+        // Drop writes to namespaces buffering more data than they are allowed
+        // to, until the persistence triggered by the soft limit catches up.
+        if let Some(namespace_memory) = &self.namespace_memory {
+            namespace_memory
+                .check(namespace_id)
+                .map_err(RpcError::NamespaceMemoryLimit)?;
+        }
+
+        // Drop writes to partitions frozen for maintenance, so their files
+        // can be safely exported or repaired.
+        if let Some(frozen) = &self.frozen_partitions {
+            if let Some(table_id) = batches
+                .keys()
+                .map(|&k| TableId::new(k))
+                .find(|&t| frozen.is_frozen(t, &partition_key))
+            {
+                return Err(RpcError::PartitionFrozen {
+                    table_id,
+                    partition_key,
+                })?;
+            }
+        }
+
         // Never attempt to create a WriteOperation with no tables - doing so causes a
         // panic.
         if num_tables == 0 {
@@ -194,6 +320,10 @@ where
 
         // Construct the corresponding ingester write operation for the RPC payload,
         // independently sequencing the data contained by the write per-partition
+        // The per-table outcome reported to the caller once the write is
+        // applied.
+        let mut tables = Vec::with_capacity(num_tables);
+
         let op = WriteOperation::new(
             namespace_id,
             batches
@@ -201,6 +331,11 @@ where
                 .map(|(k, v)| {
                     let table_id = TableId::new(k);
                     let partition_sequence_number = self.timestamp.next();
+                    tables.push(proto::TableWriteResponse {
+                        table_id: k,
+                        row_count: v.rows() as _,
+                        sequence_number: partition_sequence_number.get(),
+                    });
                     (
                         table_id,
                         TableData::new(
@@ -218,7 +353,7 @@ where
         match self.sink.apply(IngestOp::Write(op)).await {
             Ok(()) => {
                 span_recorder.ok("applied write");
-                Ok(Response::new(proto::WriteResponse {}))
+                Ok(Response::new(proto::WriteResponse { tables }))
             }
             Err(e) => {
                 error!(error=%e, "failed to apply ingest operation");
@@ -232,7 +367,7 @@ where
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
-    use data_types::SequenceNumber;
+    use data_types::{FrozenPartition, PartitionId, SequenceNumber, Timestamp};
     use generated_types::influxdata::pbdata::v1::{
         column::{SemanticType, Values},
         Column, DatabaseBatch, TableBatch,
@@ -243,6 +378,7 @@ mod tests {
     use super::*;
     use crate::{
         dml_payload::IngestOp,
+        persist::namespace_memory::NamespaceMemoryLimits,
         test_util::{ARBITRARY_NAMESPACE_ID, ARBITRARY_TABLE_ID},
     };
     use crate::{dml_sink::mock_sink::MockDmlSink, test_util::ARBITRARY_PARTITION_KEY};
@@ -489,15 +625,17 @@ mod tests {
             }),
         };
 
-        handler
+        let resp1 = handler
             .write(Request::new(req.clone()))
             .await
-            .expect("write should succeed");
+            .expect("write should succeed")
+            .into_inner();
 
-        handler
+        let resp2 = handler
             .write(Request::new(req))
             .await
-            .expect("write should succeed");
+            .expect("write should succeed")
+            .into_inner();
 
         assert_matches!(
             *mock.get_calls(),
@@ -505,6 +643,18 @@ mod tests {
                 let w1 = w1.tables().next().unwrap().1.partitioned_data().sequence_number().get();
                 let w2 = w2.tables().next().unwrap().1.partitioned_data().sequence_number().get();
                 assert!(w1 < w2);
+
+                // The responses report the assigned sequence numbers.
+                for (resp, sequence_number) in [(resp1, w1), (resp2, w2)] {
+                    assert_eq!(
+                        resp.tables,
+                        [proto::TableWriteResponse {
+                            table_id: ARBITRARY_TABLE_ID.get(),
+                            row_count: 1,
+                            sequence_number,
+                        }]
+                    );
+                }
             }
         );
     }
@@ -565,6 +715,225 @@ mod tests {
         assert_matches!(*mock.get_calls(), [IngestOp::Write(_)]);
     }
 
+    /// Validate the configured [`BackpressureResponse`] is applied to writes
+    /// while the ingester is applying backpressure.
+    #[tokio::test]
+    async fn test_rpc_write_backpressure_response() {
+        let mock = Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(())]));
+        let timestamp = Arc::new(TimestampOracle::new(0));
+        let ingest_state = Arc::new(IngestState::default());
+
+        let req = proto::WriteRequest {
+            payload: Some(DatabaseBatch {
+                database_id: ARBITRARY_NAMESPACE_ID.get(),
+                partition_key: ARBITRARY_PARTITION_KEY.to_string(),
+                table_batches: vec![TableBatch {
+                    table_id: ARBITRARY_TABLE_ID.get(),
+                    columns: vec![Column {
+                        column_name: "time".to_string(),
+                        semantic_type: SemanticType::Time.into(),
+                        values: Some(Values {
+                            i64_values: vec![4242],
+                            f64_values: vec![],
+                            u64_values: vec![],
+                            string_values: vec![],
+                            bool_values: vec![],
+                            bytes_values: vec![],
+                            packed_string_values: None,
+                            interned_string_values: None,
+                        }),
+                        null_mask: vec![0],
+                    }],
+                    row_count: 1,
+                }],
+            }),
+        };
+
+        ingest_state.set(IngestStateError::BufferFull);
+
+        // Rejected writes are reported as unavailable.
+        let handler = RpcWrite::new(
+            Arc::clone(&mock),
+            Arc::clone(&timestamp),
+            Arc::clone(&ingest_state),
+        )
+        .with_backpressure_response(BackpressureResponse::Reject);
+        let err = handler
+            .write(Request::new(req.clone()))
+            .await
+            .expect_err("write should fail");
+        assert_eq!(err.code(), Code::Unavailable);
+
+        // Delayed writes that time out are throttled.
+        let handler = RpcWrite::new(
+            Arc::clone(&mock),
+            Arc::clone(&timestamp),
+            Arc::clone(&ingest_state),
+        )
+        .with_backpressure_response(BackpressureResponse::Delay(Duration::from_millis(10)));
+        let err = handler
+            .write(Request::new(req.clone()))
+            .await
+            .expect_err("write should fail");
+        assert_eq!(err.code(), Code::ResourceExhausted);
+        assert_matches!(*mock.get_calls(), []);
+
+        // And delayed writes complete once the backpressure is lifted.
+        let handler = RpcWrite::new(Arc::clone(&mock), timestamp, Arc::clone(&ingest_state))
+            .with_backpressure_response(BackpressureResponse::Delay(Duration::from_secs(60)));
+        let write = tokio::spawn(async move { handler.write(Request::new(req)).await });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!write.is_finished());
+        ingest_state.unset(IngestStateError::BufferFull);
+
+        write
+            .await
+            .expect("write task panicked")
+            .expect("write should succeed");
+        assert_matches!(*mock.get_calls(), [IngestOp::Write(_)]);
+    }
+
+    /// Validate that a namespace over its buffered data hard limit has writes
+    /// rejected, while other namespaces remain writable.
+    #[tokio::test]
+    async fn test_rpc_write_namespace_memory_limit() {
+        let mock = Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(())]));
+        let timestamp = Arc::new(TimestampOracle::new(0));
+        let ingest_state = Arc::new(IngestState::default());
+
+        let namespace_memory = Arc::new(NamespaceMemory::new(
+            NamespaceMemoryLimits {
+                soft_limit_bytes: None,
+                hard_limit_bytes: Some(1),
+            },
+            &metric::Registry::default(),
+        ));
+
+        let handler = RpcWrite::new(Arc::clone(&mock), timestamp, ingest_state)
+            .with_namespace_memory(Arc::clone(&namespace_memory));
+
+        let req = proto::WriteRequest {
+            payload: Some(DatabaseBatch {
+                database_id: ARBITRARY_NAMESPACE_ID.get(),
+                partition_key: ARBITRARY_PARTITION_KEY.to_string(),
+                table_batches: vec![TableBatch {
+                    table_id: ARBITRARY_TABLE_ID.get(),
+                    columns: vec![Column {
+                        column_name: "time".to_string(),
+                        semantic_type: SemanticType::Time.into(),
+                        values: Some(Values {
+                            i64_values: vec![4242],
+                            f64_values: vec![],
+                            u64_values: vec![],
+                            string_values: vec![],
+                            bool_values: vec![],
+                            bytes_values: vec![],
+                            packed_string_values: None,
+                            interned_string_values: None,
+                        }),
+                        null_mask: vec![0],
+                    }],
+                    row_count: 1,
+                }],
+            }),
+        };
+
+        handler
+            .write(Request::new(req.clone()))
+            .await
+            .expect("write should succeed");
+
+        namespace_memory.account(&[(ARBITRARY_NAMESPACE_ID, 2)].into());
+
+        let err = handler
+            .write(Request::new(req.clone()))
+            .await
+            .expect_err("write should fail");
+        assert_eq!(err.code(), Code::ResourceExhausted);
+
+        // Once the buffered data is persisted, writes are accepted again.
+        namespace_memory.account(&[(ARBITRARY_NAMESPACE_ID, 0)].into());
+
+        handler
+            .write(Request::new(req))
+            .await
+            .expect("write should succeed");
+
+        assert_matches!(
+            mock.get_calls().as_slice(),
+            [IngestOp::Write(_), IngestOp::Write(_)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rpc_write_frozen_partition() {
+        let mock = Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(())]));
+        let timestamp = Arc::new(TimestampOracle::new(0));
+        let ingest_state = Arc::new(IngestState::default());
+        let frozen = Arc::new(FrozenPartitions::default());
+
+        let handler = RpcWrite::new(Arc::clone(&mock), timestamp, ingest_state)
+            .with_frozen_partitions(Arc::clone(&frozen));
+
+        let req = proto::WriteRequest {
+            payload: Some(DatabaseBatch {
+                database_id: ARBITRARY_NAMESPACE_ID.get(),
+                partition_key: ARBITRARY_PARTITION_KEY.to_string(),
+                table_batches: vec![TableBatch {
+                    table_id: ARBITRARY_TABLE_ID.get(),
+                    columns: vec![Column {
+                        column_name: "time".to_string(),
+                        semantic_type: SemanticType::Time.into(),
+                        values: Some(Values {
+                            i64_values: vec![4242],
+                            f64_values: vec![],
+                            u64_values: vec![],
+                            string_values: vec![],
+                            bool_values: vec![],
+                            bytes_values: vec![],
+                            packed_string_values: None,
+                            interned_string_values: None,
+                        }),
+                        null_mask: vec![0],
+                    }],
+                    row_count: 1,
+                }],
+            }),
+        };
+
+        handler
+            .write(Request::new(req.clone()))
+            .await
+            .expect("write should succeed");
+
+        // Freezing another partition of the table does not affect writes.
+        let frozen_partition = |partition_key: &PartitionKey| FrozenPartition {
+            partition_id: PartitionId::new(1),
+            table_id: ARBITRARY_TABLE_ID,
+            partition_key: partition_key.clone(),
+            reason: "maintenance".to_string(),
+            frozen_at: Timestamp::new(0),
+        };
+        frozen.set([frozen_partition(&PartitionKey::from("other"))]);
+        handler
+            .write(Request::new(req.clone()))
+            .await
+            .expect("write should succeed");
+
+        frozen.set([frozen_partition(&ARBITRARY_PARTITION_KEY)]);
+        let err = handler
+            .write(Request::new(req))
+            .await
+            .expect_err("write should fail");
+        assert_eq!(err.code(), Code::FailedPrecondition);
+
+        assert_matches!(
+            mock.get_calls().as_slice(),
+            [IngestOp::Write(_), IngestOp::Write(_)]
+        );
+    }
+
     /// Validate that the disk being marked as full prevents the ingester from
     /// accepting new writes (and that clearing the mark allows further writes).
     #[tokio::test]
@@ -627,7 +996,10 @@ mod tests {
             .write(Request::new(req.clone()))
             .await
             .expect("write should succeed");
-        assert_matches!(*mock.get_calls(), [IngestOp::Write(_), IngestOp::Write(_)]);
+        assert_matches!(
+            mock.get_calls().as_slice(),
+            [IngestOp::Write(_), IngestOp::Write(_)]
+        );
     }
 
     /// Validate that the ingester being marked as stopping prevents the
@@ -433,6 +433,7 @@ where
             created_at: Timestamp::new(1234),
             column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
             max_l0_created_at: Timestamp::new(42),
+            content_hash: None,
         },
         sequence_numbers
             .into_iter()
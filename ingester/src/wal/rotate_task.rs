@@ -7,11 +7,22 @@ use crate::{
     wal::reference_tracker::WalReferenceHandle,
 };
 
-/// Rotate the `wal` segment file every `period` duration of time, notifying
-/// the [`WalReferenceHandle`].
+/// The interval at which the size of the open WAL segment is compared against
+/// the configured maximum segment size.
+const SEGMENT_SIZE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Rotate the `wal` segment file every `period` duration of time, or once the
+/// open segment reaches `max_segment_bytes` (if specified), notifying the
+/// [`WalReferenceHandle`] and persisting the buffered partitions.
+///
+/// Rotating the segment early bounds the amount of WAL data that must be
+/// retained on disk and replayed at startup when writes arrive faster than the
+/// rotation `period` can absorb. The rotation period is reset after a
+/// size-triggered rotation.
 pub(crate) async fn periodic_rotation<T, P>(
     wal: Arc<wal::Wal>,
     period: Duration,
+    max_segment_bytes: Option<u64>,
     wal_reference_handle: WalReferenceHandle,
     buffer: T,
     persist: P,
@@ -26,8 +37,15 @@ pub(crate) async fn periodic_rotation<T, P>(
     interval.tick().await;
 
     loop {
-        interval.tick().await;
-        info!("rotating wal file");
+        tokio::select! {
+            _ = interval.tick() => {
+                info!("rotating wal file");
+            }
+            _ = segment_size_exceeded(&wal, max_segment_bytes) => {
+                info!("rotating wal file after exceeding maximum segment size");
+                interval.reset();
+            }
+        }
 
         let (stats, ids) = wal.rotate().expect("failed to rotate WAL");
         debug!(
@@ -86,6 +104,21 @@ pub(crate) async fn periodic_rotation<T, P>(
     }
 }
 
+/// Resolves once the open segment of `wal` is at least `max_segment_bytes` in
+/// size, or never if `max_segment_bytes` is [`None`].
+async fn segment_size_exceeded(wal: &wal::Wal, max_segment_bytes: Option<u64>) {
+    let Some(max_segment_bytes) = max_segment_bytes else {
+        return std::future::pending().await;
+    };
+
+    loop {
+        tokio::time::sleep(SEGMENT_SIZE_CHECK_INTERVAL).await;
+        if wal.open_segment_size() >= max_segment_bytes {
+            return;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -181,6 +214,7 @@ mod tests {
         let rotate_task_handle = tokio::spawn(periodic_rotation(
             Arc::clone(&wal),
             TICK_INTERVAL,
+            None,
             wal_reference_handle.clone(),
             vec![Arc::clone(&p)],
             Arc::clone(&persist_handle),
@@ -239,6 +273,97 @@ mod tests {
         })
     }
 
+    #[tokio::test]
+    async fn test_rotate_on_segment_size() {
+        let metrics = metric::Registry::default();
+
+        let write_op = make_write_op(
+            &ARBITRARY_PARTITION_KEY,
+            ARBITRARY_NAMESPACE_ID,
+            &ARBITRARY_TABLE_NAME,
+            ARBITRARY_TABLE_ID,
+            1,
+            &format!(
+                r#"{},city=London people=2,pigeons="millions" 10"#,
+                &*ARBITRARY_TABLE_NAME
+            ),
+            None,
+        );
+
+        let mut p = PartitionDataBuilder::new().build();
+        for (_, table_data) in write_op.tables() {
+            let partitioned_data = table_data.partitioned_data();
+            p.buffer_write(
+                partitioned_data.data().clone(),
+                partitioned_data.sequence_number(),
+            )
+            .expect("write should succeed");
+        }
+        let p = Arc::new(Mutex::new(p));
+
+        let persist_handle = Arc::new(MockPersistQueue::default());
+
+        let tmp_dir = tempdir().expect("no temp dir available");
+        let wal = wal::Wal::new(tmp_dir.path())
+            .await
+            .expect("failed to initialise WAL");
+
+        wal.append(&IngestOp::Write(write_op))
+            .changed()
+            .await
+            .expect("should be able to get WAL write result");
+
+        let (wal_reference_handle, wal_reference_actor) =
+            WalReferenceHandle::new(Arc::clone(&wal), &metrics);
+        tokio::spawn(wal_reference_actor.run());
+
+        // Start the rotation task with a rotation period that never elapses
+        // during the test, and a maximum segment size the write exceeds.
+        let rotate_task_handle = tokio::spawn(periodic_rotation(
+            Arc::clone(&wal),
+            Duration::from_secs(60 * 60),
+            Some(wal.open_segment_size()),
+            wal_reference_handle,
+            vec![Arc::clone(&p)],
+            Arc::clone(&persist_handle),
+        ));
+
+        tokio::time::pause();
+        tokio::time::advance(SEGMENT_SIZE_CHECK_INTERVAL).await;
+        tokio::time::resume();
+
+        // Wait for the WAL to rotate, causing 1 closed segment to exist.
+        async {
+            loop {
+                if !wal.closed_segments().is_empty() {
+                    return;
+                }
+                tokio::task::yield_now().await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        // And for the buffered partition to be enqueued for persistence.
+        async {
+            loop {
+                if !persist_handle.calls().is_empty() {
+                    return;
+                }
+                tokio::task::yield_now().await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        rotate_task_handle.abort();
+
+        assert_matches!(persist_handle.calls().as_slice(), [got] => {
+            let guard = got.lock();
+            assert_eq!(guard.partition_id(), &*ARBITRARY_TRANSITION_PARTITION_ID);
+        });
+    }
+
     /// A [`PersistQueue`] implementation that never completes a persist task
     /// and therefore never signals completion of any persist task.
     ///
@@ -334,6 +459,7 @@ mod tests {
         let rotate_task_handle = tokio::spawn(periodic_rotation(
             Arc::clone(&wal),
             TICK_INTERVAL,
+            None,
             wal_reference_handle,
             vec![Arc::clone(&p)],
             Arc::clone(&persist_handle),
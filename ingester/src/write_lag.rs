@@ -0,0 +1,631 @@
+//! Instrumentation tracking the latency between a write being accepted by the
+//! ingester, and the data within it becoming queryable and durably persisted.
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use data_types::{sequence_number_set::SequenceNumberSet, SequenceNumber};
+use hashbrown::HashMap;
+use iox_time::{SystemProvider, Time, TimeProvider};
+use metric::{DurationGauge, DurationHistogram, DurationHistogramOptions, U64Gauge, DURATION_MAX};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use tokio::sync::watch;
+
+use crate::{
+    dml_payload::IngestOp,
+    dml_sink::DmlSink,
+    persist::completion_observer::{CompletedPersist, PersistCompletionObserver},
+};
+
+const SECONDS: Duration = Duration::from_secs(1);
+const MINUTES: Duration = Duration::from_secs(60);
+
+/// A shared tracker of the write lag for writes applied to this ingester.
+///
+/// Two lag distributions are recorded:
+///
+///   * `ingester_write_queryable_lag`: the duration between a write being
+///     received by the write path, and the write being successfully applied
+///     (WAL committed & buffered), at which point it is visible to queries.
+///
+///   * `ingester_write_persisted_lag`: the duration between a write being
+///     received by the write path, and the data within it being persisted to
+///     a Parquet file (and added to the catalog).
+///
+/// The writes that are not yet persisted are periodically reported by
+/// [`monitor_persist_lag()`] as the `ingester_unpersisted_writes` count, and
+/// the `ingester_unpersisted_write_age` of the oldest of them.
+///
+/// The receive timestamp of each successfully applied write is retained until
+/// all the data it contains has been persisted. The data of a write is assigned
+/// one sequence number per partition it writes to, and the partitions are
+/// persisted independently, so a write is only persisted once all of its
+/// sequence numbers are. Writes replayed from the WAL at startup are not
+/// tracked, and do not contribute to either distribution.
+#[derive(Debug)]
+pub(crate) struct WriteLagTracker<P = SystemProvider> {
+    time_provider: P,
+
+    /// The applied, not-yet-persisted writes.
+    unpersisted: Mutex<UnpersistedWrites>,
+
+    queryable_lag: DurationHistogram,
+    persisted_lag: DurationHistogram,
+
+    unpersisted_writes: U64Gauge,
+    unpersisted_age: DurationGauge,
+}
+
+impl WriteLagTracker {
+    pub(crate) fn new(metrics: &metric::Registry) -> Self {
+        let queryable_lag = metrics
+            .register_metric::<DurationHistogram>(
+                "ingester_write_queryable_lag",
+                "duration from a write being received to it being queryable",
+            )
+            .recorder(&[]);
+
+        let persisted_lag = metrics
+            .register_metric_with_options::<DurationHistogram, _>(
+                "ingester_write_persisted_lag",
+                "duration from a write being received to it being persisted",
+                || {
+                    DurationHistogramOptions::new([
+                        10 * SECONDS,  // 10s
+                        30 * SECONDS,  // 30s
+                        MINUTES,       // 1m
+                        5 * MINUTES,   // 5m
+                        15 * MINUTES,  // 15m
+                        30 * MINUTES,  // 30m
+                        60 * MINUTES,  // 1h
+                        120 * MINUTES, // 2h
+                        240 * MINUTES, // 4h
+                        480 * MINUTES, // 8h
+                        DURATION_MAX,
+                    ])
+                },
+            )
+            .recorder(&[]);
+
+        let unpersisted_writes = metrics
+            .register_metric::<U64Gauge>(
+                "ingester_unpersisted_writes",
+                "number of applied writes that are not yet persisted",
+            )
+            .recorder(&[]);
+
+        let unpersisted_age = metrics
+            .register_metric::<DurationGauge>(
+                "ingester_unpersisted_write_age",
+                "age of the oldest applied write that is not yet persisted",
+            )
+            .recorder(&[]);
+
+        Self {
+            time_provider: Default::default(),
+            unpersisted: Default::default(),
+            queryable_lag,
+            persisted_lag,
+            unpersisted_writes,
+            unpersisted_age,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_time_provider<U>(self, time_provider: U) -> WriteLagTracker<U>
+    where
+        U: TimeProvider,
+    {
+        WriteLagTracker {
+            time_provider,
+            unpersisted: self.unpersisted,
+            queryable_lag: self.queryable_lag,
+            persisted_lag: self.persisted_lag,
+            unpersisted_writes: self.unpersisted_writes,
+            unpersisted_age: self.unpersisted_age,
+        }
+    }
+}
+
+impl<P> WriteLagTracker<P>
+where
+    P: TimeProvider,
+{
+    /// Record the writes identified by `ids`, received at `received_at`, as
+    /// having been successfully applied and now queryable.
+    fn observe_applied(&self, ids: &SequenceNumberSet, received_at: Time) {
+        if let Some(delta) = self.time_provider.now().checked_duration_since(received_at) {
+            self.queryable_lag.record(delta);
+        }
+
+        self.unpersisted.lock().insert(ids, received_at);
+    }
+
+    /// Record the writes in `note` as having been persisted.
+    fn observe_persisted(&self, note: &CompletedPersist) {
+        let now = self.time_provider.now();
+
+        let mut guard = self.unpersisted.lock();
+        for id in note.sequence_numbers().iter() {
+            // Writes replayed from the WAL have no receive timestamp.
+            let Some(received_at) = guard.remove(id) else {
+                continue;
+            };
+            if let Some(delta) = now.checked_duration_since(received_at) {
+                self.persisted_lag.record(delta);
+            }
+        }
+    }
+
+    /// Report the number of applied writes that are not yet persisted, and
+    /// the age of the oldest of them (zero if there are none).
+    fn observe_unpersisted(&self) -> (usize, Duration) {
+        let (count, oldest) = {
+            let guard = self.unpersisted.lock();
+            (guard.writes.len(), guard.oldest())
+        };
+        let age = oldest
+            .and_then(|t| self.time_provider.now().checked_duration_since(t))
+            .unwrap_or_default();
+
+        self.unpersisted_writes.set(count as _);
+        self.unpersisted_age.set(age);
+
+        (count, age)
+    }
+}
+
+/// A write that is not yet fully persisted.
+#[derive(Debug)]
+struct UnpersistedWrite {
+    received_at: Time,
+
+    /// The number of sequence numbers (partitions) of the write that are not
+    /// yet persisted.
+    remaining: usize,
+}
+
+/// The applied writes that are not yet persisted, keyed by the first sequence
+/// number of each write.
+#[derive(Debug, Default)]
+struct UnpersistedWrites {
+    writes: HashMap<SequenceNumber, UnpersistedWrite>,
+
+    /// The write (first sequence number) each not-yet-persisted sequence
+    /// number belongs to.
+    sequence_numbers: HashMap<SequenceNumber, SequenceNumber>,
+}
+
+impl UnpersistedWrites {
+    /// Track the write consisting of `ids`, received at `received_at`.
+    fn insert(&mut self, ids: &SequenceNumberSet, received_at: Time) {
+        let Some(first) = ids.iter().min() else {
+            return;
+        };
+        for id in ids.iter() {
+            self.sequence_numbers.insert(id, first);
+        }
+        self.writes.insert(
+            first,
+            UnpersistedWrite {
+                received_at,
+                remaining: ids.len() as usize,
+            },
+        );
+    }
+
+    /// Mark `id` as persisted, returning the receive time of its write if
+    /// that completed the write.
+    fn remove(&mut self, id: SequenceNumber) -> Option<Time> {
+        let first = self.sequence_numbers.remove(&id)?;
+        let write = self.writes.get_mut(&first)?;
+        write.remaining -= 1;
+        if write.remaining > 0 {
+            return None;
+        }
+        self.writes.remove(&first).map(|w| w.received_at)
+    }
+
+    /// The receive time of the oldest unpersisted write.
+    fn oldest(&self) -> Option<Time> {
+        self.writes.values().map(|w| w.received_at).min()
+    }
+}
+
+/// Thresholds on the writes applied to an ingester that are not yet persisted,
+/// above which the ingester reports itself as [degraded].
+///
+/// All thresholds are disabled by default.
+///
+/// [degraded]: IngesterHealth::Degraded
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PersistLagThreshold {
+    /// The maximum number of applied writes that are not yet persisted.
+    pub max_unpersisted_writes: Option<usize>,
+
+    /// The maximum age of the oldest applied write that is not yet persisted.
+    pub max_unpersisted_age: Option<Duration>,
+}
+
+impl PersistLagThreshold {
+    fn is_enabled(&self) -> bool {
+        self.max_unpersisted_writes.is_some() || self.max_unpersisted_age.is_some()
+    }
+
+    /// Evaluate the health of an ingester with `count` unpersisted writes, the
+    /// oldest of which is `age` old.
+    fn health(&self, count: usize, age: Duration) -> IngesterHealth {
+        if let Some(max) = self.max_unpersisted_writes.filter(|max| count > *max) {
+            return IngesterHealth::Degraded(format!(
+                "{count} unpersisted writes exceed the limit of {max}"
+            ));
+        }
+        if let Some(max) = self.max_unpersisted_age.filter(|max| age > *max) {
+            return IngesterHealth::Degraded(format!(
+                "oldest unpersisted write is {age:?} old, exceeding the limit of {max:?}"
+            ));
+        }
+        IngesterHealth::Healthy
+    }
+}
+
+/// The health of an ingester, as determined by its [`PersistLagThreshold`].
+///
+/// A degraded ingester continues to accept writes and answer queries, but
+/// persists data more slowly than it is written - the amount of data that is
+/// only durable in the WAL of the ingester is growing.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum IngesterHealth {
+    /// All thresholds are respected.
+    #[default]
+    Healthy,
+
+    /// A threshold is exceeded, for the described reason.
+    Degraded(String),
+}
+
+/// Periodically report the writes in `tracker` that are not yet persisted,
+/// publishing the resulting health of the ingester to `health` if any of the
+/// `threshold` are enabled.
+pub(crate) async fn monitor_persist_lag<P>(
+    tracker: Arc<WriteLagTracker<P>>,
+    threshold: PersistLagThreshold,
+    health: watch::Sender<IngesterHealth>,
+    period: Duration,
+) where
+    P: TimeProvider,
+{
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+
+        let (count, age) = tracker.observe_unpersisted();
+        if !threshold.is_enabled() {
+            continue;
+        }
+
+        let new = threshold.health(count, age);
+        health.send_if_modified(|current| {
+            if *current == new {
+                return false;
+            }
+            match &new {
+                IngesterHealth::Degraded(reason) => warn!(%reason, "ingester degraded"),
+                IngesterHealth::Healthy => info!("ingester healthy"),
+            }
+            *current = new;
+            true
+        });
+    }
+}
+
+/// A [`DmlSink`] decorator recording the receive timestamp of each write in a
+/// [`WriteLagTracker`].
+#[derive(Debug)]
+pub(crate) struct WriteLagSink<T, P = SystemProvider> {
+    inner: T,
+    tracker: Arc<WriteLagTracker<P>>,
+}
+
+impl<T, P> WriteLagSink<T, P> {
+    pub(crate) fn new(inner: T, tracker: Arc<WriteLagTracker<P>>) -> Self {
+        Self { inner, tracker }
+    }
+}
+
+#[async_trait]
+impl<T, P> DmlSink for WriteLagSink<T, P>
+where
+    T: DmlSink,
+    P: TimeProvider,
+{
+    type Error = T::Error;
+
+    async fn apply(&self, op: IngestOp) -> Result<(), Self::Error> {
+        let received_at = self.tracker.time_provider.now();
+
+        // The sequence numbers must be read before the op is consumed.
+        let ids = op.sequence_number_set();
+
+        self.inner.apply(op).await?;
+        self.tracker.observe_applied(&ids, received_at);
+
+        Ok(())
+    }
+}
+
+/// A [`PersistCompletionObserver`] decorator recording the persisted lag of
+/// writes tracked in a [`WriteLagTracker`].
+#[derive(Debug)]
+pub(crate) struct WriteLagObserver<T, P = SystemProvider> {
+    inner: T,
+    tracker: Arc<WriteLagTracker<P>>,
+}
+
+impl<T, P> WriteLagObserver<T, P> {
+    pub(crate) fn new(inner: T, tracker: Arc<WriteLagTracker<P>>) -> Self {
+        Self { inner, tracker }
+    }
+}
+
+#[async_trait]
+impl<T, P> PersistCompletionObserver for WriteLagObserver<T, P>
+where
+    T: PersistCompletionObserver,
+    P: TimeProvider,
+{
+    async fn persist_complete(&self, note: Arc<CompletedPersist>) {
+        self.tracker.observe_persisted(&note);
+
+        // Forward on the notification to the next handler.
+        self.inner.persist_complete(note).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dml_sink::{mock_sink::MockDmlSink, DmlError},
+        persist::completion_observer::mock::MockCompletionObserver,
+        test_util::{
+            make_write_op, ARBITRARY_NAMESPACE_ID, ARBITRARY_PARTITION_KEY, ARBITRARY_TABLE_ID,
+            ARBITRARY_TABLE_NAME, ARBITRARY_TRANSITION_PARTITION_ID,
+        },
+    };
+    use assert_matches::assert_matches;
+    use data_types::{ColumnId, ColumnSet, ParquetFile, ParquetFileId, Timestamp};
+    use iox_time::MockProvider;
+    use metric::assert_histogram;
+
+    fn make_op(sequence_number: u64) -> IngestOp {
+        IngestOp::Write(make_write_op(
+            &ARBITRARY_PARTITION_KEY,
+            ARBITRARY_NAMESPACE_ID,
+            &ARBITRARY_TABLE_NAME,
+            ARBITRARY_TABLE_ID,
+            sequence_number,
+            &format!("{},tag=1 v=2 42424242", &*ARBITRARY_TABLE_NAME),
+            None,
+        ))
+    }
+
+    fn make_note(sequence_numbers: impl IntoIterator<Item = u64>) -> Arc<CompletedPersist> {
+        let meta = ParquetFile {
+            id: ParquetFileId::new(42),
+            to_delete: None,
+            namespace_id: ARBITRARY_NAMESPACE_ID,
+            table_id: ARBITRARY_TABLE_ID,
+            partition_id: ARBITRARY_TRANSITION_PARTITION_ID.clone(),
+            object_store_id: Default::default(),
+            min_time: Timestamp::new(1),
+            max_time: Timestamp::new(2),
+            file_size_bytes: 42,
+            row_count: 1,
+            compaction_level: data_types::CompactionLevel::Initial,
+            created_at: Timestamp::new(1234),
+            column_set: ColumnSet::new([1, 2].into_iter().map(ColumnId::new)),
+            max_l0_created_at: Timestamp::new(42),
+            content_hash: None,
+        };
+
+        Arc::new(CompletedPersist::new(
+            meta,
+            sequence_numbers
+                .into_iter()
+                .map(SequenceNumber::new)
+                .collect::<SequenceNumberSet>(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_write_lag() {
+        let metrics = metric::Registry::default();
+        let mock_time = Arc::new(MockProvider::new(Time::MIN));
+        let tracker =
+            Arc::new(WriteLagTracker::new(&metrics).with_time_provider(Arc::clone(&mock_time)));
+
+        let sink = WriteLagSink::new(
+            MockDmlSink::default().with_apply_return([Ok(()), Err(DmlError::ApplyTimeout)]),
+            Arc::clone(&tracker),
+        );
+        let inner_observer = Arc::new(MockCompletionObserver::default());
+        let observer = WriteLagObserver::new(Arc::clone(&inner_observer), Arc::clone(&tracker));
+
+        // A successful write is queryable immediately.
+        sink.apply(make_op(1)).await.expect("apply should succeed");
+        assert_histogram!(
+            metrics,
+            DurationHistogram,
+            "ingester_write_queryable_lag",
+            samples = 1,
+            sum = Duration::ZERO,
+        );
+
+        // A failed write is not tracked.
+        assert_matches!(sink.apply(make_op(2)).await, Err(DmlError::ApplyTimeout));
+        assert_histogram!(
+            metrics,
+            DurationHistogram,
+            "ingester_write_queryable_lag",
+            samples = 1,
+        );
+
+        mock_time.inc(Duration::from_secs(42));
+
+        // Persisting the successful write (alongside an untracked write, such
+        // as one replayed from the WAL) records only the tracked write.
+        observer.persist_complete(make_note([1, 3])).await;
+        assert_histogram!(
+            metrics,
+            DurationHistogram,
+            "ingester_write_persisted_lag",
+            samples = 1,
+            sum = Duration::from_secs(42),
+        );
+        assert_eq!(inner_observer.calls().len(), 1);
+
+        // A write is only observed as persisted once.
+        observer.persist_complete(make_note([1])).await;
+        assert_histogram!(
+            metrics,
+            DurationHistogram,
+            "ingester_write_persisted_lag",
+            samples = 1,
+        );
+        assert!(tracker.unpersisted.lock().writes.is_empty());
+        assert!(tracker.unpersisted.lock().sequence_numbers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_lag_multiple_partitions() {
+        let metrics = metric::Registry::default();
+        let mock_time = Arc::new(MockProvider::new(Time::MIN));
+        let tracker =
+            Arc::new(WriteLagTracker::new(&metrics).with_time_provider(Arc::clone(&mock_time)));
+        let observer = WriteLagObserver::new(
+            Arc::new(MockCompletionObserver::default()),
+            Arc::clone(&tracker),
+        );
+
+        // A write to two partitions has a sequence number for each.
+        tracker.observe_applied(
+            &[1, 2].into_iter().map(SequenceNumber::new).collect(),
+            mock_time.now(),
+        );
+        mock_time.inc(Duration::from_secs(42));
+
+        // Persisting one partition does not persist the write.
+        observer.persist_complete(make_note([1])).await;
+        assert_histogram!(
+            metrics,
+            DurationHistogram,
+            "ingester_write_persisted_lag",
+            samples = 0,
+        );
+        assert_eq!(tracker.observe_unpersisted(), (1, Duration::from_secs(42)));
+
+        // Persisting the other does.
+        observer.persist_complete(make_note([2])).await;
+        assert_histogram!(
+            metrics,
+            DurationHistogram,
+            "ingester_write_persisted_lag",
+            samples = 1,
+            sum = Duration::from_secs(42),
+        );
+        assert_eq!(tracker.observe_unpersisted(), (0, Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_persist_lag_threshold() {
+        let metrics = metric::Registry::default();
+        let mock_time = Arc::new(MockProvider::new(Time::MIN));
+        let tracker =
+            Arc::new(WriteLagTracker::new(&metrics).with_time_provider(Arc::clone(&mock_time)));
+
+        let sink = WriteLagSink::new(
+            MockDmlSink::default().with_apply_return([Ok(()), Ok(())]),
+            Arc::clone(&tracker),
+        );
+        let observer = WriteLagObserver::new(
+            Arc::new(MockCompletionObserver::default()),
+            Arc::clone(&tracker),
+        );
+
+        assert_eq!(tracker.observe_unpersisted(), (0, Duration::ZERO));
+
+        sink.apply(make_op(1)).await.expect("apply should succeed");
+        mock_time.inc(Duration::from_secs(10));
+        sink.apply(make_op(2)).await.expect("apply should succeed");
+
+        // The age is that of the oldest unpersisted write.
+        assert_eq!(tracker.observe_unpersisted(), (2, Duration::from_secs(10)));
+        assert_eq!(tracker.unpersisted_writes.fetch(), 2);
+        assert_eq!(tracker.unpersisted_age.fetch(), Duration::from_secs(10));
+
+        let health = |max_unpersisted_writes, max_unpersisted_age: Option<u64>| {
+            PersistLagThreshold {
+                max_unpersisted_writes,
+                max_unpersisted_age: max_unpersisted_age.map(Duration::from_secs),
+            }
+            .health(2, Duration::from_secs(10))
+        };
+        assert_eq!(health(None, None), IngesterHealth::Healthy);
+        assert_eq!(health(Some(2), Some(10)), IngesterHealth::Healthy);
+        assert_matches!(health(Some(1), None), IngesterHealth::Degraded(reason) => {
+            assert_eq!(reason, "2 unpersisted writes exceed the limit of 1");
+        });
+        assert_matches!(health(None, Some(5)), IngesterHealth::Degraded(reason) => {
+            assert_eq!(reason, "oldest unpersisted write is 10s old, exceeding the limit of 5s");
+        });
+
+        // Persisting the oldest write reduces the age to that of the next.
+        observer.persist_complete(make_note([1])).await;
+        assert_eq!(tracker.observe_unpersisted(), (1, Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_persist_lag() {
+        let metrics = metric::Registry::default();
+        let tracker = Arc::new(WriteLagTracker::new(&metrics));
+        let sink = WriteLagSink::new(
+            MockDmlSink::default().with_apply_return([Ok(())]),
+            Arc::clone(&tracker),
+        );
+        let observer = WriteLagObserver::new(
+            Arc::new(MockCompletionObserver::default()),
+            Arc::clone(&tracker),
+        );
+
+        let (tx, mut rx) = watch::channel(IngesterHealth::default());
+        let task = tokio::spawn(monitor_persist_lag(
+            Arc::clone(&tracker),
+            PersistLagThreshold {
+                max_unpersisted_writes: Some(0),
+                max_unpersisted_age: None,
+            },
+            tx,
+            Duration::from_millis(1),
+        ));
+
+        // An unpersisted write degrades the ingester...
+        sink.apply(make_op(1)).await.expect("apply should succeed");
+        tokio::time::timeout(Duration::from_secs(5), rx.changed())
+            .await
+            .expect("timeout waiting for health change")
+            .expect("monitor stopped");
+        assert_matches!(*rx.borrow(), IngesterHealth::Degraded(_));
+
+        // ...until it is persisted.
+        observer.persist_complete(make_note([1])).await;
+        tokio::time::timeout(Duration::from_secs(5), rx.changed())
+            .await
+            .expect("timeout waiting for health change")
+            .expect("monitor stopped");
+        assert_eq!(*rx.borrow(), IngesterHealth::Healthy);
+
+        task.abort();
+    }
+}
@@ -1,5 +1,8 @@
+use std::num::NonZeroUsize;
+
 use arrow_util::assert_batches_sorted_eq;
 use data_types::PartitionKey;
+use ingester::LastValueCacheConfig;
 use ingester_query_grpc::influxdata::iox::ingester::v1::IngesterQueryRequest;
 use ingester_test_ctx::TestContextBuilder;
 use metric::{DurationHistogram, U64Histogram};
@@ -50,6 +53,7 @@ async fn write_query() {
             table_id: ctx.table_id(namespace_name, "bananas").await.get(),
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -124,6 +128,7 @@ async fn write_query_projection() {
             table_id: ctx.table_id(namespace_name, "bananas").await.get(),
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -151,6 +156,7 @@ async fn write_query_projection() {
                 "platanos".to_string(),
             ],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -165,3 +171,106 @@ async fn write_query_projection() {
     ];
     assert_batches_sorted_eq!(&expected, &data);
 }
+
+// Write data to an ingester with the last value cache enabled, and query the
+// cached values after the buffered data is persisted.
+#[tokio::test]
+async fn write_query_last_values() {
+    let namespace_name = "write_query_test_namespace";
+    let mut ctx = TestContextBuilder::default()
+        .with_last_value_cache(LastValueCacheConfig {
+            max_series: NonZeroUsize::new(10).unwrap(),
+            first_values: false,
+        })
+        .build()
+        .await;
+    let ns = ctx.ensure_namespace(namespace_name, None, None).await;
+
+    let partition_key = PartitionKey::from("1970-01-01");
+    ctx.write_lp(
+        namespace_name,
+        "bananas,city=London count=1 10\nbananas,city=Madrid count=2 20",
+        partition_key.clone(),
+        0,
+        None,
+    )
+    .await;
+    ctx.write_lp(
+        namespace_name,
+        "bananas,city=London count=3 30",
+        partition_key.clone(),
+        1,
+        None,
+    )
+    .await;
+
+    // Persist the data, emptying the buffer.
+    ctx.persist(namespace_name).await;
+
+    let table_id = ctx.table_id(namespace_name, "bananas").await.get();
+    let data: Vec<_> = ctx
+        .query(IngesterQueryRequest {
+            namespace_id: ns.id.get(),
+            table_id,
+            columns: vec![],
+            predicate: None,
+            last_values: false,
+        })
+        .await
+        .expect("query request failed");
+    assert!(data.is_empty());
+
+    // The cached values remain queryable.
+    let data: Vec<_> = ctx
+        .query(IngesterQueryRequest {
+            namespace_id: ns.id.get(),
+            table_id,
+            columns: vec![],
+            predicate: None,
+            last_values: true,
+        })
+        .await
+        .expect("query request failed");
+
+    let expected = vec![
+        "+--------+-------+--------------------------------+",
+        "| city   | count | time                           |",
+        "+--------+-------+--------------------------------+",
+        "| London | 3.0   | 1970-01-01T00:00:00.000000030Z |",
+        "| Madrid | 2.0   | 1970-01-01T00:00:00.000000020Z |",
+        "+--------+-------+--------------------------------+",
+    ];
+    assert_batches_sorted_eq!(&expected, &data);
+}
+
+// Querying the last value cache of an ingester without it fails.
+#[tokio::test]
+async fn query_last_values_disabled() {
+    let namespace_name = "write_query_test_namespace";
+    let mut ctx = TestContextBuilder::default().build().await;
+    let ns = ctx.ensure_namespace(namespace_name, None, None).await;
+
+    ctx.write_lp(
+        namespace_name,
+        "bananas,city=London count=1 10",
+        PartitionKey::from("1970-01-01"),
+        0,
+        None,
+    )
+    .await;
+
+    let err = ctx
+        .query(IngesterQueryRequest {
+            namespace_id: ns.id.get(),
+            table_id: ctx.table_id(namespace_name, "bananas").await.get(),
+            columns: vec![],
+            predicate: None,
+            last_values: true,
+        })
+        .await
+        .expect_err("query should fail");
+    assert!(
+        err.to_string().contains("last value cache is not enabled"),
+        "{err}"
+    );
+}
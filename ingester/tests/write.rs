@@ -7,6 +7,7 @@ use std::time::Duration;
 use arrow_util::assert_batches_sorted_eq;
 use assert_matches::assert_matches;
 use data_types::{PartitionKey, TableId, Timestamp};
+use futures::StreamExt;
 use ingester_query_grpc::influxdata::iox::ingester::v1::IngesterQueryRequest;
 use ingester_test_ctx::{TestContextBuilder, DEFAULT_MAX_PERSIST_QUEUE_DEPTH};
 use iox_catalog::interface::Catalog;
@@ -43,6 +44,7 @@ async fn write_persist() {
             table_id,
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -66,6 +68,7 @@ async fn write_persist() {
             table_id,
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -174,6 +177,58 @@ async fn write_persist() {
     );
 }
 
+// Persisted files are streamed to subscribers of persist events.
+#[tokio::test]
+async fn write_persist_events() {
+    let namespace_name = "persist_events_test_namespace";
+    let mut ctx = TestContextBuilder::default().build().await;
+    let ns = ctx.ensure_namespace(namespace_name, None, None).await;
+    ctx.ensure_namespace("other_namespace", None, None).await;
+
+    let mut events = ctx.watch_persist_events(Some(namespace_name)).await;
+    let all_events = ctx.watch_persist_events(None).await;
+
+    for namespace in ["other_namespace", namespace_name] {
+        ctx.write_lp(
+            namespace,
+            r#"bananas count=42,greatness="inf" 200"#,
+            PartitionKey::from("1970-01-01"),
+            42,
+            None,
+        )
+        .await;
+        ctx.persist(namespace).await;
+    }
+
+    // Only the file of the requested namespace is streamed.
+    let file = events
+        .next()
+        .with_timeout_panic(Duration::from_secs(5))
+        .await
+        .expect("persist event stream ended")
+        .expect("persist event stream error")
+        .file
+        .expect("persist event without file");
+    assert_eq!(file.namespace_id, ns.id.get());
+    assert_eq!(file.row_count, 1);
+    assert!(file.file_size_bytes > 0);
+
+    // While the unfiltered subscriber observes both files.
+    let got = all_events
+        .take(2)
+        .map(|e| {
+            e.expect("persist event stream error")
+                .file
+                .unwrap()
+                .namespace_id
+        })
+        .collect::<Vec<_>>()
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+    assert_eq!(got.len(), 2);
+    assert_eq!(got[1], ns.id.get());
+}
+
 // Write data to the ingester, which writes it to the WAL, then drop and recreate the WAL and
 // validate the data is replayed from the WAL into memory.
 #[tokio::test]
@@ -232,6 +287,7 @@ async fn wal_replay() {
                 table_id: ctx.table_id(namespace_name, "bananas").await.get(),
                 columns: vec![],
                 predicate: None,
+                last_values: false,
             })
             .await
             .expect("query request failed");
@@ -330,6 +386,7 @@ async fn graceful_shutdown() {
             table_id: ctx.table_id(namespace_name, "bananas").await.get(),
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -435,6 +492,7 @@ async fn wal_reference_dropping() {
             table_id: ctx.table_id(TEST_NAMESPACE_NAME, "bananas").await.get(),
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
@@ -521,6 +579,7 @@ async fn write_tracing() {
             table_id,
             columns: vec![],
             predicate: None,
+            last_values: false,
         })
         .await
         .expect("query request failed");
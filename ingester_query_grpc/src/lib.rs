@@ -61,6 +61,10 @@ pub mod influxdata {
 
 pub mod arrow_serde;
 
+/// Protobuf file descriptor containing all generated types.
+/// Useful in gRPC reflection.
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("proto_descriptor");
+
 /// Error returned if a request field has an invalid value. Includes
 /// machinery to add parent field names for context -- thus it will
 /// report `rules.write_timeout` than simply `write_timeout`.
@@ -133,6 +137,10 @@ pub struct IngesterQueryRequest {
 
     /// Predicate for filtering
     pub predicate: Option<Predicate>,
+
+    /// Return the values of the ingester last value cache instead of the
+    /// buffered data
+    pub last_values: bool,
 }
 
 impl IngesterQueryRequest {
@@ -148,6 +156,16 @@ impl IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            last_values: false,
+        }
+    }
+
+    /// Return the values of the ingester last value cache instead of the
+    /// buffered data.
+    pub fn with_last_values(self, last_values: bool) -> Self {
+        Self {
+            last_values,
+            ..self
         }
     }
 }
@@ -161,13 +179,14 @@ impl TryFrom<proto::IngesterQueryRequest> for IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            last_values,
         } = proto;
 
         let namespace_id = NamespaceId::new(namespace_id);
         let table_id = TableId::new(table_id);
         let predicate = predicate.map(TryInto::try_into).transpose()?;
 
-        Ok(Self::new(namespace_id, table_id, columns, predicate))
+        Ok(Self::new(namespace_id, table_id, columns, predicate).with_last_values(last_values))
     }
 }
 
@@ -180,6 +199,7 @@ impl TryFrom<IngesterQueryRequest> for proto::IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            last_values,
         } = query;
 
         Ok(Self {
@@ -187,6 +207,7 @@ impl TryFrom<IngesterQueryRequest> for proto::IngesterQueryRequest {
             table_id: table_id.get(),
             columns,
             predicate: predicate.map(TryInto::try_into).transpose()?,
+            last_values,
         })
     }
 }
@@ -516,7 +537,8 @@ mod tests {
             TableId::new(1337),
             vec!["usage".into(), "time".into()],
             Some(rust_predicate),
-        );
+        )
+        .with_last_values(true);
 
         let proto_query: proto::IngesterQueryRequest = rust_query.clone().try_into().unwrap();
 
@@ -30,10 +30,13 @@ use futures::{stream::FuturesUnordered, FutureExt, StreamExt, TryStreamExt};
 use generated_types::influxdata::iox::ingester::v1::{
     write_service_server::WriteService, WriteRequest,
 };
-use ingester::{GossipConfig, IngesterGuard, IngesterRpcInterface};
+use ingester::{
+    BackpressureResponse, GossipConfig, IngesterGuard, IngesterRpcInterface, LastValueCacheConfig,
+    NamespaceMemoryLimits, PersistLagThreshold,
+};
 use ingester_query_grpc::influxdata::iox::ingester::v1::IngesterQueryRequest;
 use iox_catalog::{
-    interface::{Catalog, SoftDeletedRows},
+    interface::{get_schema_by_name, Catalog, SoftDeletedRows},
     test_helpers::arbitrary_namespace,
     validate_or_insert_schema,
 };
@@ -41,7 +44,7 @@ use iox_time::TimeProvider;
 use metric::{Attributes, Metric, MetricObserver};
 use mutable_batch_lp::lines_to_batches;
 use mutable_batch_pb::encode::encode_write;
-use object_store::ObjectStore;
+use object_store::{DynObjectStore, ObjectStore};
 use observability_deps::tracing::*;
 use parquet_file::storage::ParquetStorage;
 use tempfile::TempDir;
@@ -72,10 +75,12 @@ pub fn test_context() -> TestContextBuilder {
 pub struct TestContextBuilder {
     wal_dir: Option<Arc<TempDir>>,
     catalog: Option<Arc<dyn Catalog>>,
+    object_store: Option<Arc<DynObjectStore>>,
 
     max_persist_queue_depth: usize,
     persist_hot_partition_cost: usize,
     wal_rotation_period: Duration,
+    last_value_cache: Option<LastValueCacheConfig>,
 }
 
 impl Default for TestContextBuilder {
@@ -83,9 +88,11 @@ impl Default for TestContextBuilder {
         Self {
             wal_dir: None,
             catalog: None,
+            object_store: None,
             max_persist_queue_depth: DEFAULT_MAX_PERSIST_QUEUE_DEPTH,
             persist_hot_partition_cost: DEFAULT_PERSIST_HOT_PARTITION_COST,
             wal_rotation_period: DEFAULT_WAL_ROTATION_PERIOD,
+            last_value_cache: None,
         }
     }
 }
@@ -105,6 +112,13 @@ impl TestContextBuilder {
         self
     }
 
+    /// Set the object store parquet files are persisted to, defaulting to an
+    /// in-memory implementation if not specified.
+    pub fn with_object_store(mut self, object_store: Arc<DynObjectStore>) -> Self {
+        self.object_store = Some(object_store);
+        self
+    }
+
     /// Configure the ingester to reject write requests after this many persist
     /// jobs are queued for persistence. Defaults to
     /// [`DEFAULT_MAX_PERSIST_QUEUE_DEPTH`].
@@ -128,14 +142,22 @@ impl TestContextBuilder {
         self
     }
 
+    /// Enable the last value cache of the ingester. Disabled by default.
+    pub fn with_last_value_cache(mut self, config: LastValueCacheConfig) -> Self {
+        self.last_value_cache = Some(config);
+        self
+    }
+
     /// Initialise the [`ingester`] instance and return a [`TestContext`] for it.
     pub async fn build(self) -> TestContext<impl IngesterRpcInterface> {
         let Self {
             wal_dir,
             catalog,
+            object_store,
             max_persist_queue_depth,
             persist_hot_partition_cost,
             wal_rotation_period,
+            last_value_cache,
         } = self;
 
         test_helpers::maybe_start_logging();
@@ -146,8 +168,8 @@ impl TestContextBuilder {
         let catalog = catalog
             .unwrap_or_else(|| Arc::new(iox_catalog::mem::MemCatalog::new(Arc::clone(&metrics))));
 
-        let object_store: Arc<object_store::DynObjectStore> =
-            Arc::new(object_store::memory::InMemory::default());
+        let object_store =
+            object_store.unwrap_or_else(|| Arc::new(object_store::memory::InMemory::default()));
         let storage =
             ParquetStorage::new(object_store, parquet_file::storage::StorageId::from("iox"));
 
@@ -163,13 +185,23 @@ impl TestContextBuilder {
             persist_background_fetch_time,
             dir.path().to_owned(),
             wal_rotation_period,
+            None,
+            None,
+            None,
+            None,
             persist_executor,
             persist_workers,
             max_persist_queue_depth,
             persist_hot_partition_cost,
+            NamespaceMemoryLimits::default(),
+            None,
+            BackpressureResponse::default(),
+            PersistLagThreshold::default(),
             storage.clone(),
             GossipConfig::default(),
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
+            last_value_cache,
             shutdown_rx.map(|v| v.expect("shutdown sender dropped without calling shutdown")),
         )
         .await
@@ -251,6 +283,35 @@ where
         ns
     }
 
+    /// Make a namespace that already exists in the catalog known to the test
+    /// context, such as one created before the ingester was restarted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the namespace does not exist, or was already created or
+    /// loaded by this [`TestContext`].
+    pub async fn load_namespace(&mut self, name: &str) -> Namespace {
+        let mut repos = self.catalog.repositories().await;
+        let ns = repos
+            .namespaces()
+            .get_by_name(name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .expect("should be able to get namespace by name")
+            .expect("namespace does not exist");
+        let schema = get_schema_by_name(name, &mut *repos, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .expect("should be able to load namespace schema");
+
+        assert!(
+            self.namespaces.insert(ns.id, schema).is_none(),
+            "namespace must not be duplicated"
+        );
+
+        debug!(?ns, "test namespace loaded");
+
+        ns
+    }
+
     /// Construct and submit a RPC request to write the given line protocol to
     /// the specified namespace & partition.
     pub async fn write_lp(
@@ -427,6 +488,31 @@ where
             .expect("failed to invoke persist");
     }
 
+    /// Subscribe to the files persisted for `namespace` (or all namespaces if
+    /// `None`) from now on.
+    pub async fn watch_persist_events(
+        &self,
+        namespace: Option<&str>,
+    ) -> futures::stream::BoxStream<
+        'static,
+        Result<generated_types::influxdata::iox::ingester::v1::PersistEvent, tonic::Status>,
+    > {
+        use generated_types::influxdata::iox::ingester::v1::{
+            self as proto, persist_service_server::PersistService,
+        };
+
+        self.ingester
+            .rpc()
+            .persist_service()
+            .watch_persist_events(Request::new(proto::WatchPersistEventsRequest {
+                namespace: namespace.unwrap_or_default().to_string(),
+            }))
+            .await
+            .expect("failed to watch persist events")
+            .into_inner()
+            .boxed()
+    }
+
     /// Gracefully stop the ingester, blocking until completion.
     pub async fn shutdown(self) {
         self.shutdown_tx
@@ -0,0 +1,25 @@
+-- Record the renames of columns, so that parquet files written before a
+-- rename can be read using the name the column had at the time.
+CREATE TABLE IF NOT EXISTS column_rename (
+    id BIGSERIAL PRIMARY KEY,
+    table_id BIGINT NOT NULL REFERENCES table_name (id) ON DELETE CASCADE,
+    column_id BIGINT NOT NULL REFERENCES column_name (id) ON DELETE CASCADE,
+    old_name TEXT NOT NULL,
+    new_name TEXT NOT NULL,
+    renamed_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS column_rename_table_idx ON column_rename (table_id);
+
+-- Record the columns that were dropped, so that the ids of dropped columns
+-- still referenced by parquet files are known not to be missing from the
+-- catalog.
+CREATE TABLE IF NOT EXISTS column_drop (
+    id BIGSERIAL PRIMARY KEY,
+    table_id BIGINT NOT NULL REFERENCES table_name (id) ON DELETE CASCADE,
+    column_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    dropped_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS column_drop_table_idx ON column_drop (table_id);
@@ -0,0 +1,5 @@
+-- Record the SHA-256 of the content of each parquet file, so that corrupted
+-- or modified files can be detected when they are read back. Files created
+-- before this column was added have no hash.
+ALTER TABLE IF EXISTS parquet_file
+    ADD COLUMN IF NOT EXISTS content_hash BYTEA DEFAULT NULL;
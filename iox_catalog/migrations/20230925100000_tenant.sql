@@ -0,0 +1,16 @@
+-- Tenants own namespaces, and bound the resources used by all of their
+-- namespaces together. A NULL quota is unlimited.
+CREATE TABLE IF NOT EXISTS tenant (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    max_storage_bytes BIGINT,
+    max_series BIGINT,
+    max_write_lines_per_second BIGINT,
+    CONSTRAINT tenant_name_unique UNIQUE (name)
+);
+
+-- Namespaces created before tenants existed are not owned by any tenant.
+ALTER TABLE IF EXISTS namespace
+    ADD COLUMN IF NOT EXISTS tenant_id BIGINT DEFAULT NULL REFERENCES tenant (id);
+
+CREATE INDEX IF NOT EXISTS namespace_tenant_idx ON namespace (tenant_id);
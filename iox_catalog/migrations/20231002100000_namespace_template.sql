@@ -0,0 +1,18 @@
+-- Named namespace settings, used to provision namespaces consistently. A NULL
+-- service protection limit uses the default.
+CREATE TABLE IF NOT EXISTS namespace_template (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    retention_period_ns BIGINT,
+    max_tables INT,
+    max_columns_per_table INT,
+    partition_template JSONB,
+    CONSTRAINT namespace_template_name_unique UNIQUE (name)
+);
+
+-- The template a namespace was created from, used to propagate later changes
+-- to the template.
+ALTER TABLE IF EXISTS namespace
+    ADD COLUMN IF NOT EXISTS template_id BIGINT DEFAULT NULL REFERENCES namespace_template (id);
+
+CREATE INDEX IF NOT EXISTS namespace_template_idx ON namespace (template_id);
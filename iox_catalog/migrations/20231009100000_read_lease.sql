@@ -0,0 +1,11 @@
+-- Leases taken by external readers of a table's parquet files. The garbage
+-- collector does not remove files flagged for deletion after the creation of
+-- the oldest unexpired lease.
+CREATE TABLE IF NOT EXISTS read_lease (
+    id BIGSERIAL PRIMARY KEY,
+    table_id BIGINT NOT NULL REFERENCES table_name (id) ON DELETE CASCADE,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS read_lease_expires_at_idx ON read_lease (expires_at);
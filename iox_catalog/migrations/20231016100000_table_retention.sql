@@ -0,0 +1,4 @@
+-- The retention period of a table, if it expires its data faster than the
+-- retention period of its namespace.
+ALTER TABLE IF EXISTS table_name
+    ADD COLUMN IF NOT EXISTS retention_period_ns BIGINT DEFAULT NULL;
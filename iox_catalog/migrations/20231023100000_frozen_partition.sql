@@ -0,0 +1,8 @@
+-- Partitions frozen for maintenance. The compactor does not compact the files
+-- of a frozen partition, and ingesters reject writes to it.
+CREATE TABLE IF NOT EXISTS frozen_partition (
+    partition_id BIGINT REFERENCES partition (id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    frozen_at BIGINT NOT NULL,
+    PRIMARY KEY (partition_id)
+);
@@ -0,0 +1,39 @@
+-- Record the renames of columns, so that parquet files written before a
+-- rename can be read using the name the column had at the time.
+create table if not exists column_rename
+(
+    id         INTEGER
+        constraint column_rename_pkey
+            primary key autoincrement,
+    table_id   numeric not null
+        references table_name
+            on delete cascade,
+    column_id  numeric not null
+        references column_name
+            on delete cascade,
+    old_name   varchar not null,
+    new_name   varchar not null,
+    renamed_at numeric not null
+);
+
+create index if not exists column_rename_table_idx
+    on column_rename (table_id);
+
+-- Record the columns that were dropped, so that the ids of dropped columns
+-- still referenced by parquet files are known not to be missing from the
+-- catalog.
+create table if not exists column_drop
+(
+    id         INTEGER
+        constraint column_drop_pkey
+            primary key autoincrement,
+    table_id   numeric not null
+        references table_name
+            on delete cascade,
+    column_id  numeric not null,
+    name       varchar not null,
+    dropped_at numeric not null
+);
+
+create index if not exists column_drop_table_idx
+    on column_drop (table_id);
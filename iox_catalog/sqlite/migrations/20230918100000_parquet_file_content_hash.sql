@@ -0,0 +1,4 @@
+-- Record the SHA-256 of the content of each parquet file, so that corrupted
+-- or modified files can be detected when they are read back. Files created
+-- before this column was added have no hash.
+ALTER TABLE parquet_file ADD COLUMN content_hash BLOB;
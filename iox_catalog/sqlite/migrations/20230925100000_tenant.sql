@@ -0,0 +1,20 @@
+-- Tenants own namespaces, and bound the resources used by all of their
+-- namespaces together. A NULL quota is unlimited.
+create table if not exists tenant
+(
+    id                         INTEGER
+        constraint tenant_pkey
+            primary key autoincrement,
+    name                       varchar not null
+        constraint tenant_name_unique
+            unique,
+    max_storage_bytes          numeric,
+    max_series                 numeric,
+    max_write_lines_per_second numeric
+);
+
+-- Namespaces created before tenants existed are not owned by any tenant.
+ALTER TABLE namespace ADD COLUMN tenant_id numeric DEFAULT NULL REFERENCES tenant (id);
+
+create index if not exists namespace_tenant_idx
+    on namespace (tenant_id);
@@ -0,0 +1,22 @@
+-- Named namespace settings, used to provision namespaces consistently. A NULL
+-- service protection limit uses the default.
+create table if not exists namespace_template
+(
+    id                    INTEGER
+        constraint namespace_template_pkey
+            primary key autoincrement,
+    name                  varchar not null
+        constraint namespace_template_name_unique
+            unique,
+    retention_period_ns   numeric,
+    max_tables            integer,
+    max_columns_per_table integer,
+    partition_template    TEXT
+);
+
+-- The template a namespace was created from, used to propagate later changes
+-- to the template.
+ALTER TABLE namespace ADD COLUMN template_id numeric DEFAULT NULL REFERENCES namespace_template (id);
+
+create index if not exists namespace_template_idx
+    on namespace (template_id);
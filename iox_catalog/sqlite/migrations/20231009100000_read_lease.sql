@@ -0,0 +1,17 @@
+-- Leases taken by external readers of a table's parquet files. The garbage
+-- collector does not remove files flagged for deletion after the creation of
+-- the oldest unexpired lease.
+create table if not exists read_lease
+(
+    id         INTEGER
+        constraint read_lease_pkey
+            primary key autoincrement,
+    table_id   numeric not null
+        references table_name (id)
+            on delete cascade,
+    created_at numeric not null,
+    expires_at numeric not null
+);
+
+create index if not exists read_lease_expires_at_idx
+    on read_lease (expires_at);
@@ -0,0 +1,3 @@
+-- The retention period of a table, if it expires its data faster than the
+-- retention period of its namespace.
+ALTER TABLE table_name ADD COLUMN retention_period_ns numeric DEFAULT NULL;
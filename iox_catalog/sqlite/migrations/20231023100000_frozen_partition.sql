@@ -0,0 +1,12 @@
+-- Partitions frozen for maintenance. The compactor does not compact the files
+-- of a frozen partition, and ingesters reject writes to it.
+create table if not exists frozen_partition
+(
+    partition_id INTEGER not null
+        constraint frozen_partition_pkey
+            primary key
+        references partition
+            on delete cascade,
+    reason       text    not null,
+    frozen_at    numeric not null
+);
@@ -3,11 +3,12 @@
 use async_trait::async_trait;
 use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
-    Column, ColumnType, ColumnsByName, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace,
-    NamespaceId, NamespaceName, NamespaceSchema, NamespaceServiceProtectionLimitsOverride,
-    ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId,
-    PartitionKey, SkippedCompaction, SortedColumnSet, Table, TableId, TableSchema, Timestamp,
-    TransitionPartitionId,
+    Column, ColumnDrop, ColumnRename, ColumnType, ColumnsByName, CompactionLevel, FrozenPartition,
+    MaxColumnsPerTable, MaxTables, Namespace, NamespaceId, NamespaceName, NamespaceSchema,
+    NamespaceServiceProtectionLimitsOverride, NamespaceTemplate, NamespaceTemplateId, ParquetFile,
+    ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
+    ReadLease, ReadLeaseId, SkippedCompaction, SortedColumnSet, Table, TableId, TableSchema,
+    Tenant, TenantId, TenantQuotas, Timestamp, TransitionPartitionId,
 };
 use iox_time::TimeProvider;
 use snafu::{OptionExt, Snafu};
@@ -74,6 +75,15 @@ pub enum Error {
     #[snafu(display("namespace {} not found", name))]
     NamespaceNotFoundByName { name: String },
 
+    #[snafu(display("column {} not found in table {}", name, table_id))]
+    ColumnNotFoundByName { name: String, table_id: TableId },
+
+    #[snafu(display("column {} already exists in table {}", name, table_id))]
+    ColumnNameExists { name: String, table_id: TableId },
+
+    #[snafu(display("cannot change column {}: {}", name, reason))]
+    ColumnNotChangeable { name: String, reason: String },
+
     #[snafu(display("namespace {} not found", id))]
     NamespaceNotFoundById { id: NamespaceId },
 
@@ -152,6 +162,18 @@ pub enum Error {
 
     #[snafu(display("could not delete namespace: {source}"))]
     CouldNotDeleteNamespace { source: sqlx::Error },
+
+    #[snafu(display("tenant {} not found", name))]
+    TenantNotFoundByName { name: String },
+
+    #[snafu(display("tenant {} not found", id))]
+    TenantNotFoundById { id: TenantId },
+
+    #[snafu(display("namespace template {} not found", name))]
+    NamespaceTemplateNotFoundByName { name: String },
+
+    #[snafu(display("namespace template {} not found", id))]
+    NamespaceTemplateNotFoundById { id: NamespaceTemplateId },
 }
 
 /// A specialized `Error` for Catalog errors
@@ -235,9 +257,15 @@ pub trait Catalog: Send + Sync + Debug + Display {
 /// should and must not care how these are implemented.
 #[async_trait]
 pub trait RepoCollection: Send + Sync + Debug {
+    /// Repository for [tenants](data_types::Tenant).
+    fn tenants(&mut self) -> &mut dyn TenantRepo;
+
     /// Repository for [namespaces](data_types::Namespace).
     fn namespaces(&mut self) -> &mut dyn NamespaceRepo;
 
+    /// Repository for [namespace templates](data_types::NamespaceTemplate).
+    fn namespace_templates(&mut self) -> &mut dyn NamespaceTemplateRepo;
+
     /// Repository for [tables](data_types::Table).
     fn tables(&mut self) -> &mut dyn TableRepo;
 
@@ -249,6 +277,68 @@ pub trait RepoCollection: Send + Sync + Debug {
 
     /// Repository for [Parquet files](data_types::ParquetFile).
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo;
+
+    /// Repository for [read leases](data_types::ReadLease).
+    fn read_leases(&mut self) -> &mut dyn ReadLeaseRepo;
+}
+
+/// Functions for working with tenants in the catalog
+#[async_trait]
+pub trait TenantRepo: Send + Sync {
+    /// Creates the tenant in the catalog. If one by the same name already exists, an error is
+    /// returned.
+    async fn create(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant>;
+
+    /// Gets the tenant by its ID.
+    async fn get_by_id(&mut self, id: TenantId) -> Result<Option<Tenant>>;
+
+    /// Gets the tenant by its unique name.
+    async fn get_by_name(&mut self, name: &str) -> Result<Option<Tenant>>;
+
+    /// List all tenants.
+    async fn list(&mut self) -> Result<Vec<Tenant>>;
+
+    /// Replace the quotas of the tenant `name`.
+    async fn update_quotas(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant>;
+
+    /// The total size, in bytes, of the parquet files not marked for deletion in the
+    /// namespaces of the tenant.
+    async fn storage_bytes(&mut self, id: TenantId) -> Result<i64>;
+}
+
+/// Functions for working with namespace templates in the catalog
+#[async_trait]
+pub trait NamespaceTemplateRepo: Send + Sync {
+    /// Creates the template in the catalog. If one by the same name already exists, an error is
+    /// returned.
+    /// Specify `None` for `retention_period_ns` to get infinite retention.
+    async fn create(
+        &mut self,
+        name: &str,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<NamespaceTemplate>;
+
+    /// Gets the template by its ID.
+    async fn get_by_id(&mut self, id: NamespaceTemplateId) -> Result<Option<NamespaceTemplate>>;
+
+    /// Gets the template by its unique name.
+    async fn get_by_name(&mut self, name: &str) -> Result<Option<NamespaceTemplate>>;
+
+    /// List all templates.
+    async fn list(&mut self) -> Result<Vec<NamespaceTemplate>>;
+
+    /// Replace the settings of the template `name`.
+    ///
+    /// Namespaces already created from the template are not changed.
+    async fn update(
+        &mut self,
+        name: &str,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<NamespaceTemplate>;
 }
 
 /// Functions for working with namespaces in the catalog
@@ -301,6 +391,22 @@ pub trait NamespaceRepo: Send + Sync {
         name: &str,
         new_max: MaxColumnsPerTable,
     ) -> Result<Namespace>;
+
+    /// Set the tenant owning a namespace, or remove it from its tenant if `tenant_id` is `None`.
+    ///
+    /// Returns [`Error::TenantNotFoundById`] if there is no tenant with the given ID.
+    async fn update_tenant(&mut self, name: &str, tenant_id: Option<TenantId>)
+        -> Result<Namespace>;
+
+    /// Record the template a namespace was created from, or clear it if `template_id` is `None`.
+    ///
+    /// Returns [`Error::NamespaceTemplateNotFoundById`] if there is no template with the given
+    /// ID.
+    async fn update_template(
+        &mut self,
+        name: &str,
+        template_id: Option<NamespaceTemplateId>,
+    ) -> Result<Namespace>;
 }
 
 /// Functions for working with tables in the catalog
@@ -330,6 +436,27 @@ pub trait TableRepo: Send + Sync {
 
     /// List all tables.
     async fn list(&mut self) -> Result<Vec<Table>>;
+
+    /// Rename the table with the given ID to `new_name`.
+    ///
+    /// Parquet files are stored by table ID and therefore not affected by the
+    /// rename. Services caching the namespace schema, such as the router, keep
+    /// using the old name until their cache is refreshed.
+    ///
+    /// Returns [`Error::TableNameExists`] if the namespace already has a table
+    /// named `new_name`.
+    async fn rename(&mut self, table_id: TableId, new_name: &str) -> Result<Table>;
+
+    /// Update the retention period of the table with the given ID.
+    ///
+    /// The data of the table expires after the shorter of this and the
+    /// namespace retention period; [`None`] removes the table retention
+    /// period, leaving only the namespace retention period.
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table>;
 }
 
 /// Functions for working with columns in the catalog
@@ -368,6 +495,78 @@ pub trait ColumnRepo: Send + Sync {
 
     /// List all columns.
     async fn list(&mut self) -> Result<Vec<Column>>;
+
+    /// Rename the column `name` of the given table to `new_name`.
+    ///
+    /// The rename is recorded as a [`ColumnRename`], so that data written
+    /// before the rename (which still uses the old name) can be read under
+    /// the new name. Parquet files store each column under the name it had
+    /// when the file was created. The time column and tags used by the
+    /// partition template of the table cannot be renamed.
+    async fn rename(&mut self, table_id: TableId, name: &str, new_name: &str) -> Result<Column>;
+
+    /// Drop the field column `name` from the given table, returning the
+    /// dropped column.
+    ///
+    /// The drop is recorded as a [`ColumnDrop`]. The data of the column is not
+    /// deleted but no longer read, and it is removed when the parquet files
+    /// are compacted. The name can be reused right away, even with a different
+    /// type, as only columns listed in the column set of a parquet file are
+    /// read from it. Tags and the time column cannot be dropped, as they
+    /// identify the series of the table.
+    async fn delete(&mut self, table_id: TableId, name: &str) -> Result<Column>;
+
+    /// List the column renames of all tables in the passed in namespace id.
+    async fn list_renames_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnRename>>;
+
+    /// List the column renames of the given table ID.
+    async fn list_renames_by_table_id(&mut self, table_id: TableId) -> Result<Vec<ColumnRename>>;
+
+    /// List the dropped columns of all tables in the passed in namespace id.
+    async fn list_drops_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnDrop>>;
+}
+
+/// Check that `column` of `table` may be renamed.
+pub(crate) fn check_column_rename(table: &Table, column: &Column) -> Result<()> {
+    if column.column_type == ColumnType::Time {
+        return ColumnNotChangeableSnafu {
+            name: &column.name,
+            reason: "the time column cannot be renamed",
+        }
+        .fail();
+    }
+
+    let in_partition_template = table.partition_template.parts().any(|part| {
+        matches!(part, data_types::partition_template::TemplatePart::TagValue(tag) if tag == column.name)
+    });
+    if in_partition_template {
+        return ColumnNotChangeableSnafu {
+            name: &column.name,
+            reason: "the column is used by the partition template of the table",
+        }
+        .fail();
+    }
+
+    Ok(())
+}
+
+/// Check that `column` may be dropped.
+pub(crate) fn check_column_delete(column: &Column) -> Result<()> {
+    if matches!(column.column_type, ColumnType::Time | ColumnType::Tag) {
+        return ColumnNotChangeableSnafu {
+            name: &column.name,
+            reason: "only field columns can be dropped",
+        }
+        .fail();
+    }
+
+    Ok(())
 }
 
 /// Functions for working with IOx partitions in the catalog. These are how IOx splits up
@@ -458,6 +657,23 @@ pub trait PartitionRepo: Send + Sync {
         partition_id: PartitionId,
     ) -> Result<Option<SkippedCompaction>>;
 
+    /// Freeze the partition for maintenance for the specified reason, until it
+    /// is unfrozen. Freezing a frozen partition replaces the reason.
+    async fn freeze(&mut self, partition_id: PartitionId, reason: &str) -> Result<FrozenPartition>;
+
+    /// Unfreeze the partition, returning the record of it being frozen if it
+    /// was.
+    async fn unfreeze(&mut self, partition_id: PartitionId) -> Result<Option<FrozenPartition>>;
+
+    /// List the records of all frozen partitions.
+    async fn list_frozen(&mut self) -> Result<Vec<FrozenPartition>>;
+
+    /// Get the records of the frozen partitions among `partition_ids`.
+    async fn get_in_frozen(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<FrozenPartition>>;
+
     /// Return the N most recently created partitions.
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>>;
 
@@ -477,6 +693,31 @@ pub trait PartitionRepo: Send + Sync {
     async fn list_old_style(&mut self) -> Result<Vec<Partition>>;
 }
 
+/// Functions for working with read leases in the catalog
+#[async_trait]
+pub trait ReadLeaseRepo: Send + Sync {
+    /// Take a lease on the files of `table_id` until `expires_at`.
+    ///
+    /// Returns [`Error::TableNotFound`] if there is no table with the given ID.
+    async fn create(&mut self, table_id: TableId, expires_at: Timestamp) -> Result<ReadLease>;
+
+    /// Gets the lease by its ID.
+    async fn get_by_id(&mut self, id: ReadLeaseId) -> Result<Option<ReadLease>>;
+
+    /// Release the lease before it expires. Releasing a lease that does not exist, or has
+    /// already been released, is not an error.
+    async fn release(&mut self, id: ReadLeaseId) -> Result<()>;
+
+    /// Return the creation time of the oldest lease that has not expired, if any.
+    ///
+    /// Parquet files flagged for deletion after this time may still be read, and must not be
+    /// removed.
+    async fn oldest_active(&mut self) -> Result<Option<Timestamp>>;
+
+    /// Delete expired leases, returning the number of leases deleted.
+    async fn delete_expired(&mut self) -> Result<u64>;
+}
+
 /// Functions for working with parquet file pointers in the catalog
 #[async_trait]
 pub trait ParquetFileRepo: Send + Sync {
@@ -504,6 +745,16 @@ pub trait ParquetFileRepo: Send + Sync {
     /// [`to_delete`](ParquetFile::to_delete).
     async fn list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
 
+    /// List all parquet files within a given table that existed at time `at`, i.e. that were
+    /// created at or before `at` and were not yet [marked for deletion](ParquetFile::to_delete).
+    ///
+    /// Files that were already removed by the garbage collector are not returned.
+    async fn list_by_table_at(
+        &mut self,
+        table_id: TableId,
+        at: Timestamp,
+    ) -> Result<Vec<ParquetFile>>;
+
     /// Delete parquet files that were marked to be deleted earlier than the specified time.
     ///
     /// Returns the deleted IDs only.
@@ -790,7 +1041,9 @@ pub(crate) mod test_helpers {
         test_namespace_soft_deletion(clean_state().await).await;
         test_partitions_new_file_between(clean_state().await).await;
         test_column(clean_state().await).await;
+        test_column_rename_and_delete(clean_state().await).await;
         test_partition(clean_state().await).await;
+        test_frozen_partition(clean_state().await).await;
         test_parquet_file(clean_state().await).await;
         test_parquet_file_delete_broken(clean_state().await).await;
         test_update_to_compaction_level_1(clean_state().await).await;
@@ -803,6 +1056,14 @@ pub(crate) mod test_helpers {
         test_namespace(Arc::clone(&catalog)).await;
         assert_metric_hit(&catalog.metrics(), "namespace_create");
 
+        let catalog = clean_state().await;
+        test_tenant(Arc::clone(&catalog)).await;
+        assert_metric_hit(&catalog.metrics(), "tenant_create");
+
+        let catalog = clean_state().await;
+        test_namespace_template(Arc::clone(&catalog)).await;
+        assert_metric_hit(&catalog.metrics(), "namespace_template_create");
+
         let catalog = clean_state().await;
         test_table(Arc::clone(&catalog)).await;
         assert_metric_hit(&catalog.metrics(), "table_create");
@@ -818,6 +1079,10 @@ pub(crate) mod test_helpers {
         let catalog = clean_state().await;
         test_parquet_file(Arc::clone(&catalog)).await;
         assert_metric_hit(&catalog.metrics(), "parquet_create");
+
+        let catalog = clean_state().await;
+        test_read_lease(Arc::clone(&catalog)).await;
+        assert_metric_hit(&catalog.metrics(), "read_lease_create");
     }
 
     async fn test_setup(catalog: Arc<dyn Catalog>) {
@@ -1009,6 +1274,374 @@ pub(crate) mod test_helpers {
             .expect("delete namespace should succeed");
     }
 
+    async fn test_tenant(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+
+        let quotas = TenantQuotas {
+            max_storage_bytes: Some(1_000_000),
+            max_series: None,
+            max_write_lines_per_second: Some(100),
+        };
+        let tenant = repos.tenants().create("acme", quotas).await.unwrap();
+        assert_eq!(tenant.name, "acme");
+        assert_eq!(tenant.quotas, quotas);
+
+        let conflict = repos
+            .tenants()
+            .create("acme", TenantQuotas::default())
+            .await;
+        assert_matches!(conflict.unwrap_err(), Error::NameExists { .. });
+
+        let other = repos
+            .tenants()
+            .create("globex", TenantQuotas::default())
+            .await
+            .unwrap();
+        assert!(other.quotas.is_unlimited());
+        assert_ne!(tenant.id, other.id);
+
+        assert_eq!(
+            repos.tenants().get_by_id(tenant.id).await.unwrap(),
+            Some(tenant.clone())
+        );
+        assert_eq!(
+            repos.tenants().get_by_name("globex").await.unwrap(),
+            Some(other.clone())
+        );
+        assert_eq!(repos.tenants().get_by_name("initech").await.unwrap(), None);
+        assert_eq!(
+            repos.tenants().list().await.unwrap(),
+            vec![tenant.clone(), other.clone()]
+        );
+
+        // Replace the quotas.
+        let quotas = TenantQuotas {
+            max_storage_bytes: None,
+            max_series: Some(10),
+            max_write_lines_per_second: None,
+        };
+        let updated = repos.tenants().update_quotas("acme", quotas).await.unwrap();
+        assert_eq!(updated.quotas, quotas);
+        assert_eq!(
+            repos.tenants().get_by_id(tenant.id).await.unwrap(),
+            Some(updated)
+        );
+        let err = repos
+            .tenants()
+            .update_quotas("initech", quotas)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TenantNotFoundByName { name } if name == "initech");
+
+        // Namespaces are not owned by a tenant until assigned to one.
+        let namespace = arbitrary_namespace(&mut *repos, "tenant_ns").await;
+        assert_eq!(namespace.tenant_id, None);
+        let namespace = repos
+            .namespaces()
+            .update_tenant("tenant_ns", Some(tenant.id))
+            .await
+            .unwrap();
+        assert_eq!(namespace.tenant_id, Some(tenant.id));
+        let found = repos
+            .namespaces()
+            .get_by_name("tenant_ns", SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.tenant_id, Some(tenant.id));
+
+        let err = repos
+            .namespaces()
+            .update_tenant("tenant_ns", Some(TenantId::new(i64::MAX)))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TenantNotFoundById { .. });
+        let err = repos
+            .namespaces()
+            .update_tenant("does_not_exist", Some(tenant.id))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+
+        // The storage used by a tenant is the size of the files of its
+        // namespaces that are not marked for deletion.
+        assert_eq!(repos.tenants().storage_bytes(tenant.id).await.unwrap(), 0);
+
+        let table = arbitrary_table(&mut *repos, "tenant_table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), table.id)
+            .await
+            .unwrap();
+        let f1 = repos
+            .parquet_files()
+            .create(arbitrary_parquet_file_params(
+                &namespace, &table, &partition,
+            ))
+            .await
+            .unwrap();
+        let f2 = repos
+            .parquet_files()
+            .create(arbitrary_parquet_file_params(
+                &namespace, &table, &partition,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            repos.tenants().storage_bytes(tenant.id).await.unwrap(),
+            f1.file_size_bytes + f2.file_size_bytes
+        );
+
+        repos
+            .parquet_files()
+            .create_upgrade_delete(&[f2.id], &[], &[], CompactionLevel::Initial)
+            .await
+            .unwrap();
+        assert_eq!(
+            repos.tenants().storage_bytes(tenant.id).await.unwrap(),
+            f1.file_size_bytes
+        );
+
+        // Files of namespaces of other tenants, or without a tenant, are not
+        // counted.
+        let other_ns = arbitrary_namespace(&mut *repos, "other_ns").await;
+        let other_table = arbitrary_table(&mut *repos, "other_table", &other_ns).await;
+        let other_partition = repos
+            .partitions()
+            .create_or_get("one".into(), other_table.id)
+            .await
+            .unwrap();
+        repos
+            .parquet_files()
+            .create(arbitrary_parquet_file_params(
+                &other_ns,
+                &other_table,
+                &other_partition,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            repos.tenants().storage_bytes(tenant.id).await.unwrap(),
+            f1.file_size_bytes
+        );
+        assert_eq!(repos.tenants().storage_bytes(other.id).await.unwrap(), 0);
+
+        // Removing the namespace from the tenant removes its files from the
+        // tenant's storage.
+        let namespace = repos
+            .namespaces()
+            .update_tenant("tenant_ns", None)
+            .await
+            .unwrap();
+        assert_eq!(namespace.tenant_id, None);
+        assert_eq!(repos.tenants().storage_bytes(tenant.id).await.unwrap(), 0);
+    }
+
+    async fn test_namespace_template(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+
+        let partition_template =
+            NamespacePartitionTemplateOverride::try_from(proto::PartitionTemplate {
+                parts: vec![proto::TemplatePart {
+                    part: Some(proto::template_part::Part::TagValue("region".into())),
+                }],
+            })
+            .unwrap();
+        let limits = NamespaceServiceProtectionLimitsOverride {
+            max_tables: Some(MaxTables::new(10)),
+            max_columns_per_table: None,
+        };
+
+        let template = repos
+            .namespace_templates()
+            .create(
+                "prod-metrics",
+                Some(partition_template.clone()),
+                Some(42),
+                Some(limits),
+            )
+            .await
+            .unwrap();
+        assert_eq!(template.name, "prod-metrics");
+        assert_eq!(template.retention_period_ns, Some(42));
+        assert_eq!(template.max_tables, Some(MaxTables::new(10)));
+        assert_eq!(template.max_columns_per_table, None);
+        assert_eq!(template.partition_template, partition_template);
+
+        let conflict = repos
+            .namespace_templates()
+            .create("prod-metrics", None, None, None)
+            .await;
+        assert_matches!(conflict.unwrap_err(), Error::NameExists { .. });
+
+        let other = repos
+            .namespace_templates()
+            .create("dev", None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(other.retention_period_ns, None);
+        assert_eq!(other.max_tables, None);
+        assert_eq!(
+            other.partition_template,
+            NamespacePartitionTemplateOverride::default()
+        );
+
+        assert_eq!(
+            repos
+                .namespace_templates()
+                .get_by_id(template.id)
+                .await
+                .unwrap(),
+            Some(template.clone())
+        );
+        assert_eq!(
+            repos
+                .namespace_templates()
+                .get_by_name("dev")
+                .await
+                .unwrap(),
+            Some(other.clone())
+        );
+        assert_eq!(
+            repos
+                .namespace_templates()
+                .get_by_name("staging")
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            repos.namespace_templates().list().await.unwrap(),
+            vec![template.clone(), other.clone()]
+        );
+
+        // Replace the settings.
+        let limits = NamespaceServiceProtectionLimitsOverride {
+            max_tables: None,
+            max_columns_per_table: Some(MaxColumnsPerTable::new(7)),
+        };
+        let updated = repos
+            .namespace_templates()
+            .update("prod-metrics", None, None, Some(limits))
+            .await
+            .unwrap();
+        assert_eq!(updated.id, template.id);
+        assert_eq!(updated.retention_period_ns, None);
+        assert_eq!(updated.max_tables, None);
+        assert_eq!(
+            updated.max_columns_per_table,
+            Some(MaxColumnsPerTable::new(7))
+        );
+        assert_eq!(
+            updated.partition_template,
+            NamespacePartitionTemplateOverride::default()
+        );
+        assert_eq!(
+            repos
+                .namespace_templates()
+                .get_by_id(template.id)
+                .await
+                .unwrap(),
+            Some(updated)
+        );
+        let err = repos
+            .namespace_templates()
+            .update("staging", None, None, None)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::NamespaceTemplateNotFoundByName { name } if name == "staging");
+
+        // Namespaces record the template they were created from.
+        let namespace = arbitrary_namespace(&mut *repos, "template_ns").await;
+        assert_eq!(namespace.template_id, None);
+        let namespace = repos
+            .namespaces()
+            .update_template("template_ns", Some(template.id))
+            .await
+            .unwrap();
+        assert_eq!(namespace.template_id, Some(template.id));
+        let found = repos
+            .namespaces()
+            .get_by_name("template_ns", SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.template_id, Some(template.id));
+
+        let err = repos
+            .namespaces()
+            .update_template("template_ns", Some(NamespaceTemplateId::new(i64::MAX)))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::NamespaceTemplateNotFoundById { .. });
+        let err = repos
+            .namespaces()
+            .update_template("does_not_exist", Some(template.id))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+
+        let namespace = repos
+            .namespaces()
+            .update_template("template_ns", None)
+            .await
+            .unwrap();
+        assert_eq!(namespace.template_id, None);
+    }
+
+    async fn test_read_lease(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "lease_ns").await;
+        let table = arbitrary_table(&mut *repos, "lease_table", &namespace).await;
+
+        assert_eq!(repos.read_leases().oldest_active().await.unwrap(), None);
+
+        let now = catalog.time_provider().now();
+        let hour = Timestamp::from(now + Duration::from_secs(60 * 60));
+        let lease = repos.read_leases().create(table.id, hour).await.unwrap();
+        assert_eq!(lease.table_id, table.id);
+        assert_eq!(lease.expires_at, hour);
+        assert!(lease.created_at >= Timestamp::from(now));
+        assert_eq!(
+            repos.read_leases().get_by_id(lease.id).await.unwrap(),
+            Some(lease)
+        );
+        assert_eq!(
+            repos.read_leases().oldest_active().await.unwrap(),
+            Some(lease.created_at)
+        );
+
+        let err = repos
+            .read_leases()
+            .create(TableId::new(i64::MAX), hour)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TableNotFound { .. });
+
+        // An expired lease no longer protects any file.
+        let past = Timestamp::from(now - Duration::from_secs(1));
+        let expired = repos.read_leases().create(table.id, past).await.unwrap();
+        assert_eq!(
+            repos.read_leases().oldest_active().await.unwrap(),
+            Some(lease.created_at)
+        );
+        assert_eq!(repos.read_leases().delete_expired().await.unwrap(), 1);
+        assert_eq!(
+            repos.read_leases().get_by_id(expired.id).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            repos.read_leases().get_by_id(lease.id).await.unwrap(),
+            Some(lease)
+        );
+
+        // Releasing a lease is idempotent.
+        repos.read_leases().release(lease.id).await.unwrap();
+        repos.read_leases().release(lease.id).await.unwrap();
+        assert_eq!(repos.read_leases().get_by_id(lease.id).await.unwrap(), None);
+        assert_eq!(repos.read_leases().oldest_active().await.unwrap(), None);
+    }
+
     /// Construct a set of two namespaces:
     ///
     ///  * deleted-ns: marked as soft-deleted
@@ -1351,6 +1984,44 @@ pub(crate) mod test_helpers {
             .unwrap();
         assert_eq!(templated, lookup_templated);
 
+        // Tables can be renamed, keeping their ID and partition template
+        let renamed = repos
+            .tables()
+            .rename(templated.id, "renamed_template")
+            .await
+            .unwrap();
+        assert_eq!(renamed.id, templated.id);
+        assert_eq!(renamed.name, "renamed_template");
+        assert_eq!(renamed.partition_template, templated.partition_template);
+        assert!(repos
+            .tables()
+            .get_by_namespace_and_name(namespace2.id, "use_a_template")
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            repos
+                .tables()
+                .get_by_namespace_and_name(namespace2.id, "renamed_template")
+                .await
+                .unwrap(),
+            Some(renamed.clone())
+        );
+
+        // ...but not to the name of another table in the namespace
+        let err = repos.tables().rename(renamed.id, "foo").await;
+        assert_error!(
+            err,
+            Error::TableNameExists { ref name, namespace_id }
+                if name == "foo" && namespace_id == namespace2.id
+        );
+
+        let err = repos
+            .tables()
+            .rename(TableId::new(i64::MAX), "bananas")
+            .await;
+        assert_error!(err, Error::TableNotFound { .. });
+
         // Create a namespace with a partition template other than the default
         let custom_namespace_template =
             NamespacePartitionTemplateOverride::try_from(proto::PartitionTemplate {
@@ -1537,6 +2208,156 @@ pub(crate) mod test_helpers {
             .expect("delete namespace should succeed");
     }
 
+    async fn test_column_rename_and_delete(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "namespace_column_rename_test").await;
+        let table = arbitrary_table(&mut *repos, "test_table", &namespace).await;
+        let time = repos
+            .columns()
+            .create_or_get("time", table.id, ColumnType::Time)
+            .await
+            .unwrap();
+        let tag = repos
+            .columns()
+            .create_or_get("tag", table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let field = repos
+            .columns()
+            .create_or_get("feild", table.id, ColumnType::F64)
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), table.id)
+            .await
+            .unwrap();
+        repos
+            .partitions()
+            .cas_sort_key(
+                &partition.transition_partition_id(),
+                None,
+                None,
+                &["tag", "time"],
+                &SortedColumnSet::from([tag.id.get(), time.id.get()]),
+            )
+            .await
+            .unwrap();
+
+        // rename a field
+        let renamed = repos
+            .columns()
+            .rename(table.id, "feild", "field")
+            .await
+            .unwrap();
+        assert_eq!(renamed.id, field.id);
+        assert_eq!(renamed.name, "field");
+        assert_eq!(renamed.column_type, ColumnType::F64);
+
+        // rename a tag of the sort key, which updates the sort key
+        let renamed_tag = repos
+            .columns()
+            .rename(table.id, "tag", "host")
+            .await
+            .unwrap();
+        assert_eq!(renamed_tag.id, tag.id);
+        let partition = repos
+            .partitions()
+            .get_by_id(partition.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(partition.sort_key, vec!["host", "time"]);
+
+        let mut columns = repos.columns().list_by_table_id(table.id).await.unwrap();
+        columns.sort_by_key(|c| c.id);
+        assert_eq!(columns, vec![time.clone(), renamed_tag, renamed.clone()]);
+
+        // the old names are recorded
+        let mut renames = repos
+            .columns()
+            .list_renames_by_table_id(table.id)
+            .await
+            .unwrap();
+        renames.sort_by_key(|r| r.column_id);
+        let renames = renames
+            .iter()
+            .map(|r| (r.column_id, r.old_name.as_str(), r.new_name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            renames,
+            vec![(tag.id, "tag", "host"), (field.id, "feild", "field")]
+        );
+        let namespace_renames = repos
+            .columns()
+            .list_renames_by_namespace_id(namespace.id)
+            .await
+            .unwrap();
+        assert_eq!(namespace_renames.len(), 2);
+
+        // the new name must not exist
+        let err = repos
+            .columns()
+            .rename(table.id, "field", "host")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ColumnNameExists { .. }), "{err}");
+
+        // the column must exist
+        let err = repos
+            .columns()
+            .rename(table.id, "feild", "other")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ColumnNotFoundByName { .. }), "{err}");
+
+        // the time column cannot be renamed
+        let err = repos
+            .columns()
+            .rename(table.id, "time", "timestamp")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ColumnNotChangeable { .. }), "{err}");
+
+        // tags cannot be dropped
+        let err = repos.columns().delete(table.id, "host").await.unwrap_err();
+        assert!(matches!(err, Error::ColumnNotChangeable { .. }), "{err}");
+
+        // fields can be dropped, which also removes their renames
+        let dropped = repos.columns().delete(table.id, "field").await.unwrap();
+        assert_eq!(dropped, renamed);
+        let columns = repos.columns().list_by_table_id(table.id).await.unwrap();
+        assert!(columns.iter().all(|c| c.id != field.id));
+        let renames = repos
+            .columns()
+            .list_renames_by_table_id(table.id)
+            .await
+            .unwrap();
+        assert_eq!(renames.len(), 1);
+
+        let drops = repos
+            .columns()
+            .list_drops_by_namespace_id(namespace.id)
+            .await
+            .unwrap();
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].table_id, table.id);
+        assert_eq!(drops[0].column_id, field.id);
+        assert_eq!(drops[0].name, "field");
+
+        let err = repos.columns().delete(table.id, "field").await.unwrap_err();
+        assert!(matches!(err, Error::ColumnNotFoundByName { .. }), "{err}");
+
+        // the name of the dropped column can be used by a new column
+        let recreated = repos
+            .columns()
+            .create_or_get("field", table.id, ColumnType::I64)
+            .await
+            .unwrap();
+        assert_ne!(recreated.id, field.id);
+        assert_eq!(recreated.column_type, ColumnType::I64);
+    }
+
     async fn test_partition(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
         let namespace = arbitrary_namespace(&mut *repos, "namespace_partition_test").await;
@@ -2135,6 +2956,7 @@ pub(crate) mod test_helpers {
             .await
             .unwrap();
         assert_eq!(parquet_file, pfg.unwrap());
+        assert_eq!(parquet_file.content_hash, parquet_file_params.content_hash);
 
         // verify that trying to create a file with the same UUID throws an error
         let err = repos
@@ -2150,9 +2972,11 @@ pub(crate) mod test_helpers {
             object_store_id: Uuid::new_v4(),
             min_time: Timestamp::new(50),
             max_time: Timestamp::new(60),
+            content_hash: None,
             ..parquet_file_params.clone()
         };
         let other_file = repos.parquet_files().create(other_params).await.unwrap();
+        assert_eq!(other_file.content_hash, None);
 
         let exist_id = parquet_file.id;
         let non_exist_id = ParquetFileId::new(other_file.id.get() + 10);
@@ -2193,6 +3017,30 @@ pub(crate) mod test_helpers {
             .cloned()
             .unwrap();
 
+        // test list_by_table_at: the file existed between its creation and being marked deleted
+        let to_delete = marked_deleted.to_delete.unwrap();
+        let files = repos
+            .parquet_files()
+            .list_by_table_at(
+                table.id,
+                Timestamp::new(marked_deleted.created_at.get() - 1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(files, vec![]);
+        let files = repos
+            .parquet_files()
+            .list_by_table_at(table.id, Timestamp::new(to_delete.get() - 1))
+            .await
+            .unwrap();
+        assert_eq!(files, vec![marked_deleted.clone()]);
+        let files = repos
+            .parquet_files()
+            .list_by_table_at(table.id, to_delete)
+            .await
+            .unwrap();
+        assert_eq!(files, vec![]);
+
         // File is not deleted if it was marked to be deleted after the specified time
         let before_deleted = Timestamp::new(
             (catalog.time_provider().now() - Duration::from_secs(100)).timestamp_nanos(),
@@ -2424,6 +3272,80 @@ pub(crate) mod test_helpers {
             .unwrap();
         assert!(ids.is_empty());
 
+        // 3. a table retention period expires the data of the table faster than the namespace
+        //    retention period, but never slower
+        let other_recent_params = ParquetFileParams {
+            table_id: other_partition.table_id,
+            partition_id: other_partition.transition_partition_id(),
+            object_store_id: Uuid::new_v4(),
+            ..f5_params.clone()
+        };
+        let other_recent = repos
+            .parquet_files()
+            .create(other_recent_params.clone())
+            .await
+            .unwrap();
+        let ids = repos
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+            .unwrap();
+        assert!(ids.is_empty());
+
+        let updated = repos
+            .tables()
+            .update_retention_period(other_table.id, Some(30 * 60 * 1_000_000_000)) // 30 minutes
+            .await
+            .unwrap();
+        assert_eq!(updated.retention_period_ns, Some(30 * 60 * 1_000_000_000));
+        let ids = repos
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![other_recent.id]);
+        let f5 = repos
+            .parquet_files()
+            .get_by_object_store_id(f5.object_store_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_matches!(f5.to_delete, None); // f5 is in a table without retention period
+
+        repos
+            .tables()
+            .update_retention_period(other_table.id, Some(2 * 60 * 60 * 1_000_000_000)) // 2 hours
+            .await
+            .unwrap();
+        let other_old = repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                object_store_id: Uuid::new_v4(),
+                max_time: f4_params.max_time,
+                ..other_recent_params
+            })
+            .await
+            .unwrap();
+        let ids = repos
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![other_old.id]); // the namespace retention period still applies
+
+        let updated = repos
+            .tables()
+            .update_retention_period(other_table.id, None)
+            .await
+            .unwrap();
+        assert_eq!(updated.retention_period_ns, None);
+
+        let err = repos
+            .tables()
+            .update_retention_period(TableId::new(i64::MAX), None)
+            .await;
+        assert_error!(err, Error::TableNotFound { .. });
+
         // test that flag_for_delete_by_retention respects UPDATE LIMIT
         // create limit + the meaning of life parquet files that are all older than the retention (>1hr)
         const LIMIT: usize = 1000;
@@ -2543,6 +3465,72 @@ pub(crate) mod test_helpers {
         assert_eq!(present, expected);
     }
 
+    async fn test_frozen_partition(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "frozen_ns").await;
+        let table = arbitrary_table(&mut *repos, "frozen_table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), table.id)
+            .await
+            .unwrap();
+        let other = repos
+            .partitions()
+            .create_or_get("two".into(), table.id)
+            .await
+            .unwrap();
+
+        assert!(repos.partitions().list_frozen().await.unwrap().is_empty());
+
+        let frozen = repos
+            .partitions()
+            .freeze(partition.id, "repairing files")
+            .await
+            .unwrap();
+        assert_eq!(frozen.partition_id, partition.id);
+        assert_eq!(frozen.table_id, table.id);
+        assert_eq!(frozen.partition_key, partition.partition_key);
+        assert_eq!(frozen.reason, "repairing files");
+
+        // Freezing again replaces the reason, but not the time of freezing.
+        let refrozen = repos
+            .partitions()
+            .freeze(partition.id, "exporting files")
+            .await
+            .unwrap();
+        assert_eq!(refrozen.reason, "exporting files");
+        assert_eq!(refrozen.frozen_at, frozen.frozen_at);
+        assert_eq!(
+            repos.partitions().list_frozen().await.unwrap(),
+            vec![refrozen.clone()]
+        );
+        assert_eq!(
+            repos
+                .partitions()
+                .get_in_frozen(&[partition.id, other.id, PartitionId::new(i64::MAX)])
+                .await
+                .unwrap(),
+            vec![refrozen.clone()]
+        );
+
+        let err = repos
+            .partitions()
+            .freeze(PartitionId::new(i64::MAX), "missing")
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::PartitionNotFound { .. });
+
+        assert_eq!(
+            repos.partitions().unfreeze(partition.id).await.unwrap(),
+            Some(refrozen)
+        );
+        assert_eq!(
+            repos.partitions().unfreeze(partition.id).await.unwrap(),
+            None
+        );
+        assert!(repos.partitions().list_frozen().await.unwrap().is_empty());
+    }
+
     async fn test_parquet_file_delete_broken(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
         let namespace_1 = arbitrary_namespace(&mut *repos, "retention_broken_1").await;
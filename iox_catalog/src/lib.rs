@@ -48,6 +48,11 @@ pub mod sqlite;
 pub struct TableScopedError(String, Error);
 
 impl TableScopedError {
+    /// Scope `err` to the table `table`.
+    pub fn new(table: impl Into<String>, err: Error) -> Self {
+        Self(table.into(), err)
+    }
+
     /// Return the table name for this error.
     pub fn table(&self) -> &str {
         &self.0
@@ -331,7 +336,7 @@ pub mod test_helpers {
     use crate::RepoCollection;
     use data_types::{
         partition_template::TablePartitionTemplateOverride, ColumnId, ColumnSet, CompactionLevel,
-        Namespace, NamespaceName, ParquetFileParams, Partition, Table, Timestamp,
+        ContentHash, Namespace, NamespaceName, ParquetFileParams, Partition, Table, Timestamp,
     };
     use uuid::Uuid;
 
@@ -404,6 +409,7 @@ pub mod test_helpers {
             created_at: Timestamp::new(1),
             column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
             max_l0_created_at: Timestamp::new(1),
+            content_hash: Some(ContentHash::of(b"arbitrary")),
         }
     }
 }
@@ -4,22 +4,27 @@
 use crate::interface::MAX_PARQUET_FILES_SELECTED_ONCE_FOR_DELETE;
 use crate::{
     interface::{
-        CasFailure, Catalog, ColumnRepo, ColumnTypeMismatchSnafu, Error, NamespaceRepo,
-        ParquetFileRepo, PartitionRepo, RepoCollection, Result, SoftDeletedRows, TableRepo,
-        MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION,
+        check_column_delete, check_column_rename, CasFailure, Catalog, ColumnRepo,
+        ColumnTypeMismatchSnafu, Error, NamespaceRepo, NamespaceTemplateNotFoundByIdSnafu,
+        NamespaceTemplateRepo, ParquetFileRepo, PartitionRepo, ReadLeaseRepo, RepoCollection,
+        Result, SoftDeletedRows, TableNotFoundSnafu, TableRepo, TenantNotFoundByIdSnafu,
+        TenantRepo, MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION,
     },
     metrics::MetricDecorator,
 };
 use async_trait::async_trait;
 use data_types::SortedColumnSet;
 use data_types::{
+    effective_retention_period_ns,
     partition_template::{
         NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
     },
-    Column, ColumnId, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace,
-    NamespaceId, NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile,
+    Column, ColumnDrop, ColumnId, ColumnRename, ColumnType, CompactionLevel, FrozenPartition,
+    MaxColumnsPerTable, MaxTables, Namespace, NamespaceId, NamespaceName,
+    NamespaceServiceProtectionLimitsOverride, NamespaceTemplate, NamespaceTemplateId, ParquetFile,
     ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
-    SkippedCompaction, Table, TableId, Timestamp, TransitionPartitionId,
+    ReadLease, ReadLeaseId, SkippedCompaction, Table, TableId, Tenant, TenantId, TenantQuotas,
+    Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use snafu::ensure;
@@ -65,12 +70,28 @@ impl std::fmt::Debug for MemCatalog {
 
 #[derive(Default, Debug, Clone)]
 struct MemCollections {
+    tenants: Vec<Tenant>,
+    namespace_templates: Vec<NamespaceTemplate>,
     namespaces: Vec<Namespace>,
     tables: Vec<Table>,
     columns: Vec<Column>,
+    /// The last column ID handed out, as the IDs of dropped columns must
+    /// not be reused
+    last_column_id: i64,
+    column_renames: Vec<ColumnRename>,
+    column_drops: Vec<ColumnDrop>,
     partitions: Vec<Partition>,
     skipped_compactions: Vec<SkippedCompaction>,
+    frozen_partitions: Vec<FrozenPartition>,
     parquet_files: Vec<ParquetFile>,
+    read_leases: Vec<ReadLease>,
+}
+
+impl MemCollections {
+    fn next_column_id(&mut self) -> ColumnId {
+        self.last_column_id += 1;
+        ColumnId::new(self.last_column_id)
+    }
 }
 
 /// transaction bound to an in-memory catalog.
@@ -121,10 +142,18 @@ impl Catalog for MemCatalog {
 
 #[async_trait]
 impl RepoCollection for MemTxn {
+    fn tenants(&mut self) -> &mut dyn TenantRepo {
+        self
+    }
+
     fn namespaces(&mut self) -> &mut dyn NamespaceRepo {
         self
     }
 
+    fn namespace_templates(&mut self) -> &mut dyn NamespaceTemplateRepo {
+        self
+    }
+
     fn tables(&mut self) -> &mut dyn TableRepo {
         self
     }
@@ -140,6 +169,163 @@ impl RepoCollection for MemTxn {
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
         self
     }
+
+    fn read_leases(&mut self) -> &mut dyn ReadLeaseRepo {
+        self
+    }
+}
+
+#[async_trait]
+impl TenantRepo for MemTxn {
+    async fn create(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant> {
+        let stage = self.stage();
+
+        if stage.tenants.iter().any(|t| t.name == name) {
+            return Err(Error::NameExists {
+                name: name.to_string(),
+            });
+        }
+
+        let tenant = Tenant {
+            id: TenantId::new(stage.tenants.len() as i64 + 1),
+            name: name.to_string(),
+            quotas,
+        };
+        stage.tenants.push(tenant);
+        Ok(stage.tenants.last().unwrap().clone())
+    }
+
+    async fn get_by_id(&mut self, id: TenantId) -> Result<Option<Tenant>> {
+        let stage = self.stage();
+
+        Ok(stage.tenants.iter().find(|t| t.id == id).cloned())
+    }
+
+    async fn get_by_name(&mut self, name: &str) -> Result<Option<Tenant>> {
+        let stage = self.stage();
+
+        Ok(stage.tenants.iter().find(|t| t.name == name).cloned())
+    }
+
+    async fn list(&mut self) -> Result<Vec<Tenant>> {
+        let stage = self.stage();
+
+        Ok(stage.tenants.clone())
+    }
+
+    async fn update_quotas(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant> {
+        let stage = self.stage();
+        match stage.tenants.iter_mut().find(|t| t.name == name) {
+            Some(t) => {
+                t.quotas = quotas;
+                Ok(t.clone())
+            }
+            None => Err(Error::TenantNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn storage_bytes(&mut self, id: TenantId) -> Result<i64> {
+        let stage = self.stage();
+
+        let namespaces: HashSet<_> = stage
+            .namespaces
+            .iter()
+            .filter(|n| n.tenant_id == Some(id))
+            .map(|n| n.id)
+            .collect();
+
+        Ok(stage
+            .parquet_files
+            .iter()
+            .filter(|f| f.to_delete.is_none() && namespaces.contains(&f.namespace_id))
+            .map(|f| f.file_size_bytes)
+            .sum())
+    }
+}
+
+#[async_trait]
+impl NamespaceTemplateRepo for MemTxn {
+    async fn create(
+        &mut self,
+        name: &str,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<NamespaceTemplate> {
+        let stage = self.stage();
+
+        if stage.namespace_templates.iter().any(|t| t.name == name) {
+            return Err(Error::NameExists {
+                name: name.to_string(),
+            });
+        }
+
+        let template = NamespaceTemplate {
+            id: NamespaceTemplateId::new(stage.namespace_templates.len() as i64 + 1),
+            name: name.to_string(),
+            retention_period_ns,
+            max_tables: service_protection_limits.and_then(|l| l.max_tables),
+            max_columns_per_table: service_protection_limits.and_then(|l| l.max_columns_per_table),
+            partition_template: partition_template.unwrap_or_default(),
+        };
+        stage.namespace_templates.push(template);
+        Ok(stage.namespace_templates.last().unwrap().clone())
+    }
+
+    async fn get_by_id(&mut self, id: NamespaceTemplateId) -> Result<Option<NamespaceTemplate>> {
+        let stage = self.stage();
+
+        Ok(stage
+            .namespace_templates
+            .iter()
+            .find(|t| t.id == id)
+            .cloned())
+    }
+
+    async fn get_by_name(&mut self, name: &str) -> Result<Option<NamespaceTemplate>> {
+        let stage = self.stage();
+
+        Ok(stage
+            .namespace_templates
+            .iter()
+            .find(|t| t.name == name)
+            .cloned())
+    }
+
+    async fn list(&mut self) -> Result<Vec<NamespaceTemplate>> {
+        let stage = self.stage();
+
+        Ok(stage.namespace_templates.clone())
+    }
+
+    async fn update(
+        &mut self,
+        name: &str,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<NamespaceTemplate> {
+        let stage = self.stage();
+        match stage
+            .namespace_templates
+            .iter_mut()
+            .find(|t| t.name == name)
+        {
+            Some(t) => {
+                t.retention_period_ns = retention_period_ns;
+                t.max_tables = service_protection_limits.and_then(|l| l.max_tables);
+                t.max_columns_per_table =
+                    service_protection_limits.and_then(|l| l.max_columns_per_table);
+                t.partition_template = partition_template.unwrap_or_default();
+                Ok(t.clone())
+            }
+            None => Err(Error::NamespaceTemplateNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
 }
 
 #[async_trait]
@@ -174,6 +360,8 @@ impl NamespaceRepo for MemTxn {
             retention_period_ns,
             deleted_at: None,
             partition_template: partition_template.unwrap_or_default(),
+            tenant_id: None,
+            template_id: None,
         };
         stage.namespaces.push(namespace);
         Ok(stage.namespaces.last().unwrap().clone())
@@ -274,6 +462,54 @@ impl NamespaceRepo for MemTxn {
             }),
         }
     }
+
+    async fn update_tenant(
+        &mut self,
+        name: &str,
+        tenant_id: Option<TenantId>,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        if let Some(id) = tenant_id {
+            ensure!(
+                stage.tenants.iter().any(|t| t.id == id),
+                TenantNotFoundByIdSnafu { id }
+            );
+        }
+
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.tenant_id = tenant_id;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_template(
+        &mut self,
+        name: &str,
+        template_id: Option<NamespaceTemplateId>,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        if let Some(id) = template_id {
+            ensure!(
+                stage.namespace_templates.iter().any(|t| t.id == id),
+                NamespaceTemplateNotFoundByIdSnafu { id }
+            );
+        }
+
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.template_id = template_id;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
 }
 
 #[async_trait]
@@ -332,6 +568,7 @@ impl TableRepo for MemTxn {
                         namespace_id,
                         name: name.to_string(),
                         partition_template,
+                        retention_period_ns: None,
                     };
                     stage.tables.push(table);
                     stage.tables.last().unwrap()
@@ -392,6 +629,53 @@ impl TableRepo for MemTxn {
         let stage = self.stage();
         Ok(stage.tables.clone())
     }
+
+    async fn rename(&mut self, table_id: TableId, new_name: &str) -> Result<Table> {
+        let stage = self.stage();
+
+        let namespace_id = stage
+            .tables
+            .iter()
+            .find(|t| t.id == table_id)
+            .ok_or(Error::TableNotFound { id: table_id })?
+            .namespace_id;
+
+        if stage
+            .tables
+            .iter()
+            .any(|t| t.id != table_id && t.namespace_id == namespace_id && t.name == new_name)
+        {
+            return Err(Error::TableNameExists {
+                name: new_name.to_string(),
+                namespace_id,
+            });
+        }
+
+        let table = stage
+            .tables
+            .iter_mut()
+            .find(|t| t.id == table_id)
+            .expect("table exists");
+        table.name = new_name.to_string();
+
+        Ok(table.clone())
+    }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        let stage = self.stage();
+        let table = stage
+            .tables
+            .iter_mut()
+            .find(|t| t.id == table_id)
+            .ok_or(Error::TableNotFound { id: table_id })?;
+        table.retention_period_ns = retention_period_ns;
+
+        Ok(table.clone())
+    }
 }
 
 #[async_trait]
@@ -459,7 +743,7 @@ impl ColumnRepo for MemTxn {
             }
             None => {
                 let column = Column {
-                    id: ColumnId::new(stage.columns.len() as i64 + 1),
+                    id: stage.next_column_id(),
                     table_id,
                     name: name.to_string(),
                     column_type,
@@ -504,7 +788,7 @@ impl ColumnRepo for MemTxn {
                     }
                     None => {
                         let new_column = Column {
-                            id: ColumnId::new(stage.columns.len() as i64 + 1),
+                            id: stage.next_column_id(),
                             table_id,
                             name: column_name.to_string(),
                             column_type,
@@ -555,6 +839,140 @@ impl ColumnRepo for MemTxn {
         let stage = self.stage();
         Ok(stage.columns.clone())
     }
+
+    async fn rename(&mut self, table_id: TableId, name: &str, new_name: &str) -> Result<Column> {
+        let renamed_at = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        let table = stage
+            .tables
+            .iter()
+            .find(|t| t.id == table_id)
+            .ok_or(Error::TableNotFound { id: table_id })?;
+        if stage
+            .columns
+            .iter()
+            .any(|c| c.table_id == table_id && c.name == new_name)
+        {
+            return Err(Error::ColumnNameExists {
+                name: new_name.to_string(),
+                table_id,
+            });
+        }
+        let column = stage
+            .columns
+            .iter_mut()
+            .find(|c| c.table_id == table_id && c.name == name)
+            .ok_or_else(|| Error::ColumnNotFoundByName {
+                name: name.to_string(),
+                table_id,
+            })?;
+        check_column_rename(table, column)?;
+
+        column.name = new_name.to_string();
+        let column = column.clone();
+
+        for partition in stage
+            .partitions
+            .iter_mut()
+            .filter(|p| p.table_id == table_id)
+        {
+            for sort_key_column in partition.sort_key.iter_mut() {
+                if sort_key_column == name {
+                    *sort_key_column = new_name.to_string();
+                }
+            }
+        }
+
+        stage.column_renames.push(ColumnRename {
+            table_id,
+            column_id: column.id,
+            old_name: name.to_string(),
+            new_name: new_name.to_string(),
+            renamed_at,
+        });
+
+        Ok(column)
+    }
+
+    async fn delete(&mut self, table_id: TableId, name: &str) -> Result<Column> {
+        let dropped_at = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        let idx = stage
+            .columns
+            .iter()
+            .position(|c| c.table_id == table_id && c.name == name)
+            .ok_or_else(|| Error::ColumnNotFoundByName {
+                name: name.to_string(),
+                table_id,
+            })?;
+        check_column_delete(&stage.columns[idx])?;
+
+        let column = stage.columns.remove(idx);
+        stage.column_renames.retain(|r| r.column_id != column.id);
+        stage.column_drops.push(ColumnDrop {
+            table_id,
+            column_id: column.id,
+            name: column.name.clone(),
+            dropped_at,
+        });
+
+        Ok(column)
+    }
+
+    async fn list_renames_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnRename>> {
+        let stage = self.stage();
+
+        let table_ids: Vec<_> = stage
+            .tables
+            .iter()
+            .filter(|t| t.namespace_id == namespace_id)
+            .map(|t| t.id)
+            .collect();
+
+        Ok(stage
+            .column_renames
+            .iter()
+            .filter(|r| table_ids.contains(&r.table_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn list_renames_by_table_id(&mut self, table_id: TableId) -> Result<Vec<ColumnRename>> {
+        let stage = self.stage();
+
+        Ok(stage
+            .column_renames
+            .iter()
+            .filter(|r| r.table_id == table_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_drops_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnDrop>> {
+        let stage = self.stage();
+
+        let table_ids: Vec<_> = stage
+            .tables
+            .iter()
+            .filter(|t| t.namespace_id == namespace_id)
+            .map(|t| t.id)
+            .collect();
+
+        Ok(stage
+            .column_drops
+            .iter()
+            .filter(|d| table_ids.contains(&d.table_id))
+            .cloned()
+            .collect())
+    }
 }
 
 #[async_trait]
@@ -791,6 +1209,70 @@ impl PartitionRepo for MemTxn {
         }
     }
 
+    async fn freeze(&mut self, partition_id: PartitionId, reason: &str) -> Result<FrozenPartition> {
+        let frozen_at = Timestamp::from(self.time_provider.now());
+
+        let stage = self.stage();
+        let partition = stage
+            .partitions
+            .iter()
+            .find(|p| p.id == partition_id)
+            .ok_or_else(|| Error::PartitionNotFound {
+                id: TransitionPartitionId::Deprecated(partition_id),
+            })?;
+        let (table_id, partition_key) = (partition.table_id, partition.partition_key.clone());
+
+        match stage
+            .frozen_partitions
+            .iter_mut()
+            .find(|f| f.partition_id == partition_id)
+        {
+            Some(f) => {
+                f.reason = reason.to_string();
+                Ok(f.clone())
+            }
+            None => {
+                let f = FrozenPartition {
+                    partition_id,
+                    table_id,
+                    partition_key,
+                    reason: reason.to_string(),
+                    frozen_at,
+                };
+                stage.frozen_partitions.push(f.clone());
+                Ok(f)
+            }
+        }
+    }
+
+    async fn unfreeze(&mut self, partition_id: PartitionId) -> Result<Option<FrozenPartition>> {
+        let stage = self.stage();
+        let idx = stage
+            .frozen_partitions
+            .iter()
+            .position(|f| f.partition_id == partition_id);
+        Ok(idx.map(|idx| stage.frozen_partitions.remove(idx)))
+    }
+
+    async fn list_frozen(&mut self) -> Result<Vec<FrozenPartition>> {
+        let stage = self.stage();
+        Ok(stage.frozen_partitions.clone())
+    }
+
+    async fn get_in_frozen(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<FrozenPartition>> {
+        let stage = self.stage();
+        let find: HashSet<&PartitionId> = partition_ids.iter().collect();
+        Ok(stage
+            .frozen_partitions
+            .iter()
+            .filter(|f| find.contains(&f.partition_id))
+            .cloned()
+            .collect())
+    }
+
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         let stage = self.stage();
         Ok(stage.partitions.iter().rev().take(n).cloned().collect())
@@ -832,6 +1314,60 @@ impl PartitionRepo for MemTxn {
     }
 }
 
+#[async_trait]
+impl ReadLeaseRepo for MemTxn {
+    async fn create(&mut self, table_id: TableId, expires_at: Timestamp) -> Result<ReadLease> {
+        let created_at = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+        ensure!(
+            stage.tables.iter().any(|t| t.id == table_id),
+            TableNotFoundSnafu { id: table_id }
+        );
+
+        let lease = ReadLease {
+            id: ReadLeaseId::new(stage.read_leases.last().map_or(1, |l| l.id.get() + 1)),
+            table_id,
+            created_at,
+            expires_at,
+        };
+        stage.read_leases.push(lease);
+        Ok(lease)
+    }
+
+    async fn get_by_id(&mut self, id: ReadLeaseId) -> Result<Option<ReadLease>> {
+        let stage = self.stage();
+
+        Ok(stage.read_leases.iter().find(|l| l.id == id).copied())
+    }
+
+    async fn release(&mut self, id: ReadLeaseId) -> Result<()> {
+        let stage = self.stage();
+        stage.read_leases.retain(|l| l.id != id);
+        Ok(())
+    }
+
+    async fn oldest_active(&mut self) -> Result<Option<Timestamp>> {
+        let now = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        Ok(stage
+            .read_leases
+            .iter()
+            .filter(|l| l.expires_at > now)
+            .map(|l| l.created_at)
+            .min())
+    }
+
+    async fn delete_expired(&mut self) -> Result<u64> {
+        let now = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        let before = stage.read_leases.len();
+        stage.read_leases.retain(|l| l.expires_at > now);
+        Ok((before - stage.read_leases.len()) as u64)
+    }
+}
+
 #[async_trait]
 impl ParquetFileRepo for MemTxn {
     async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile> {
@@ -854,22 +1390,27 @@ impl ParquetFileRepo for MemTxn {
             // don't flag if already flagged for deletion
             .filter(|f| f.to_delete.is_none())
             .filter_map(|f| {
-                // table retention, if it exists, overrides namespace retention
-                // TODO - include check of table retention period once implemented
-                stage
+                // a table retention period expires the data of the table
+                // faster than the namespace retention period
+                let namespace_retention = stage
                     .namespaces
                     .iter()
-                    .find(|n| n.id == f.namespace_id)
-                    .and_then(|ns| {
-                        ns.retention_period_ns.and_then(|rp| {
-                            if f.max_time < now - rp {
-                                f.to_delete = Some(now);
-                                Some(f.id)
-                            } else {
-                                None
-                            }
-                        })
-                    })
+                    .find(|n| n.id == f.namespace_id)?
+                    .retention_period_ns;
+                let table_retention = stage
+                    .tables
+                    .iter()
+                    .find(|t| t.id == f.table_id)
+                    .and_then(|t| t.retention_period_ns);
+
+                effective_retention_period_ns(namespace_retention, table_retention).and_then(|rp| {
+                    if f.max_time < now - rp {
+                        f.to_delete = Some(now);
+                        Some(f.id)
+                    } else {
+                        None
+                    }
+                })
             })
             .take(MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION as usize)
             .collect())
@@ -907,6 +1448,26 @@ impl ParquetFileRepo for MemTxn {
         Ok(parquet_files)
     }
 
+    async fn list_by_table_at(
+        &mut self,
+        table_id: TableId,
+        at: Timestamp,
+    ) -> Result<Vec<ParquetFile>> {
+        let stage = self.stage();
+
+        let parquet_files: Vec<_> = stage
+            .parquet_files
+            .iter()
+            .filter(|f| {
+                table_id == f.table_id
+                    && f.created_at <= at
+                    && f.to_delete.map_or(true, |to_delete| to_delete > at)
+            })
+            .cloned()
+            .collect();
+        Ok(parquet_files)
+    }
+
     async fn delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>> {
         let stage = self.stage();
 
@@ -1,20 +1,23 @@
 //! Metric instrumentation for catalog implementations.
 
 use crate::interface::{
-    CasFailure, ColumnRepo, NamespaceRepo, ParquetFileRepo, PartitionRepo, RepoCollection, Result,
-    SoftDeletedRows, TableRepo,
+    CasFailure, ColumnRepo, NamespaceRepo, NamespaceTemplateRepo, ParquetFileRepo, PartitionRepo,
+    ReadLeaseRepo, RepoCollection, Result, SoftDeletedRows, TableRepo, TenantRepo,
 };
 use async_trait::async_trait;
 use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
-    Column, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceId,
-    NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
-    SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    Column, ColumnDrop, ColumnRename, ColumnType, CompactionLevel, FrozenPartition,
+    MaxColumnsPerTable, MaxTables, Namespace, NamespaceId, NamespaceName,
+    NamespaceServiceProtectionLimitsOverride, NamespaceTemplate, NamespaceTemplateId, ParquetFile,
+    ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
+    ReadLease, ReadLeaseId, SkippedCompaction, SortedColumnSet, Table, TableId, Tenant, TenantId,
+    TenantQuotas, Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, Metric};
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
+use tracker::{in_subsystem, MemorySubsystem};
 use uuid::Uuid;
 
 /// Decorates a implementation of the catalog's [`RepoCollection`] (and the
@@ -23,6 +26,9 @@ use uuid::Uuid;
 ///
 /// Values are recorded under the `catalog_op_duration` metric, labelled by
 /// operation name and result (success/error).
+///
+/// Heap allocations made by the decorated calls are attributed to
+/// [`MemorySubsystem::Catalog`].
 #[derive(Debug)]
 pub struct MetricDecorator<T, P = SystemProvider> {
     inner: T,
@@ -43,13 +49,29 @@ impl<T> MetricDecorator<T> {
 
 impl<T, P> RepoCollection for MetricDecorator<T, P>
 where
-    T: NamespaceRepo + TableRepo + ColumnRepo + PartitionRepo + ParquetFileRepo + Debug,
+    T: TenantRepo
+        + NamespaceRepo
+        + NamespaceTemplateRepo
+        + TableRepo
+        + ColumnRepo
+        + PartitionRepo
+        + ParquetFileRepo
+        + ReadLeaseRepo
+        + Debug,
     P: TimeProvider,
 {
+    fn tenants(&mut self) -> &mut dyn TenantRepo {
+        self
+    }
+
     fn namespaces(&mut self) -> &mut dyn NamespaceRepo {
         self
     }
 
+    fn namespace_templates(&mut self) -> &mut dyn NamespaceTemplateRepo {
+        self
+    }
+
     fn tables(&mut self) -> &mut dyn TableRepo {
         self
     }
@@ -65,6 +87,10 @@ where
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
         self
     }
+
+    fn read_leases(&mut self) -> &mut dyn ReadLeaseRepo {
+        self
+    }
 }
 
 /// Emit a trait impl for `impl_trait` that delegates calls to the inner
@@ -111,7 +137,10 @@ macro_rules! decorate {
                     );
 
                     let t = self.time_provider.now();
-                    let res = self.inner.$method($($arg),*).await;
+                    let res = in_subsystem(
+                        MemorySubsystem::Catalog,
+                        self.inner.$method($($arg),*),
+                    ).await;
 
                     // Avoid exploding if time goes backwards - simply drop the
                     // measurement if it happens.
@@ -130,6 +159,18 @@ macro_rules! decorate {
     };
 }
 
+decorate!(
+    impl_trait = TenantRepo,
+    methods = [
+        "tenant_create" = create(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant>;
+        "tenant_get_by_id" = get_by_id(&mut self, id: TenantId) -> Result<Option<Tenant>>;
+        "tenant_get_by_name" = get_by_name(&mut self, name: &str) -> Result<Option<Tenant>>;
+        "tenant_list" = list(&mut self) -> Result<Vec<Tenant>>;
+        "tenant_update_quotas" = update_quotas(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant>;
+        "tenant_storage_bytes" = storage_bytes(&mut self, id: TenantId) -> Result<i64>;
+    ]
+);
+
 decorate!(
     impl_trait = NamespaceRepo,
     methods = [
@@ -141,6 +182,19 @@ decorate!(
         "namespace_soft_delete" = soft_delete(&mut self, name: &str) -> Result<()>;
         "namespace_update_table_limit" = update_table_limit(&mut self, name: &str, new_max: MaxTables) -> Result<Namespace>;
         "namespace_update_column_limit" = update_column_limit(&mut self, name: &str, new_max: MaxColumnsPerTable) -> Result<Namespace>;
+        "namespace_update_tenant" = update_tenant(&mut self, name: &str, tenant_id: Option<TenantId>) -> Result<Namespace>;
+        "namespace_update_template" = update_template(&mut self, name: &str, template_id: Option<NamespaceTemplateId>) -> Result<Namespace>;
+    ]
+);
+
+decorate!(
+    impl_trait = NamespaceTemplateRepo,
+    methods = [
+        "namespace_template_create" = create(&mut self, name: &str, partition_template: Option<NamespacePartitionTemplateOverride>, retention_period_ns: Option<i64>, service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>) -> Result<NamespaceTemplate>;
+        "namespace_template_get_by_id" = get_by_id(&mut self, id: NamespaceTemplateId) -> Result<Option<NamespaceTemplate>>;
+        "namespace_template_get_by_name" = get_by_name(&mut self, name: &str) -> Result<Option<NamespaceTemplate>>;
+        "namespace_template_list" = list(&mut self) -> Result<Vec<NamespaceTemplate>>;
+        "namespace_template_update" = update(&mut self, name: &str, partition_template: Option<NamespacePartitionTemplateOverride>, retention_period_ns: Option<i64>, service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>) -> Result<NamespaceTemplate>;
     ]
 );
 
@@ -152,6 +206,8 @@ decorate!(
         "table_get_by_namespace_and_name" = get_by_namespace_and_name(&mut self, namespace_id: NamespaceId, name: &str) -> Result<Option<Table>>;
         "table_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>>;
         "table_list" = list(&mut self) -> Result<Vec<Table>>;
+        "table_rename" = rename(&mut self, table_id: TableId, new_name: &str) -> Result<Table>;
+        "table_update_retention_period" = update_retention_period(&mut self, table_id: TableId, retention_period_ns: Option<i64>) -> Result<Table>;
     ]
 );
 
@@ -163,6 +219,11 @@ decorate!(
         "column_list_by_table_id" = list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Column>>;
         "column_create_or_get_many_unchecked" = create_or_get_many_unchecked(&mut self, table_id: TableId, columns: HashMap<&str, ColumnType>) -> Result<Vec<Column>>;
         "column_list" = list(&mut self) -> Result<Vec<Column>>;
+        "column_rename" = rename(&mut self, table_id: TableId, name: &str, new_name: &str) -> Result<Column>;
+        "column_delete" = delete(&mut self, table_id: TableId, name: &str) -> Result<Column>;
+        "column_list_renames_by_namespace_id" = list_renames_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<ColumnRename>>;
+        "column_list_renames_by_table_id" = list_renames_by_table_id(&mut self, table_id: TableId) -> Result<Vec<ColumnRename>>;
+        "column_list_drops_by_namespace_id" = list_drops_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<ColumnDrop>>;
     ]
 );
 
@@ -180,6 +241,10 @@ decorate!(
         "partition_record_skipped_compaction" = record_skipped_compaction(&mut self, partition_id: PartitionId, reason: &str, num_files: usize, limit_num_files: usize, limit_num_files_first_in_partition: usize, estimated_bytes: u64, limit_bytes: u64) -> Result<()>;
         "partition_list_skipped_compactions" = list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>>;
         "partition_delete_skipped_compactions" = delete_skipped_compactions(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompaction>>;
+        "partition_freeze" = freeze(&mut self, partition_id: PartitionId, reason: &str) -> Result<FrozenPartition>;
+        "partition_unfreeze" = unfreeze(&mut self, partition_id: PartitionId) -> Result<Option<FrozenPartition>>;
+        "partition_list_frozen" = list_frozen(&mut self) -> Result<Vec<FrozenPartition>>;
+        "partition_get_in_frozen" = get_in_frozen(&mut self, partition_ids: &[PartitionId]) -> Result<Vec<FrozenPartition>>;
         "partition_most_recent_n" = most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>>;
         "partition_partitions_new_file_between" = partitions_new_file_between(&mut self, minimum_time: Timestamp, maximum_time: Option<Timestamp>) -> Result<Vec<PartitionId>>;
         "partition_get_in_skipped_compactions" = get_in_skipped_compactions(&mut self, partition_ids: &[PartitionId]) -> Result<Vec<SkippedCompaction>>;
@@ -195,6 +260,7 @@ decorate!(
         "parquet_flag_for_delete_by_retention" = flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>>;
         "parquet_list_by_namespace_not_to_delete" = list_by_namespace_not_to_delete(&mut self, namespace_id: NamespaceId) -> Result<Vec<ParquetFile>>;
         "parquet_list_by_table_not_to_delete" = list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
+        "parquet_list_by_table_at" = list_by_table_at(&mut self, table_id: TableId, at: Timestamp) -> Result<Vec<ParquetFile>>;
         "parquet_delete_old_ids_only" = delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>>;
         "parquet_list_by_partition_not_to_delete" = list_by_partition_not_to_delete(&mut self, partition_id: &TransitionPartitionId) -> Result<Vec<ParquetFile>>;
         "parquet_get_by_object_store_id" = get_by_object_store_id(&mut self, object_store_id: Uuid) -> Result<Option<ParquetFile>>;
@@ -202,3 +268,14 @@ decorate!(
         "parquet_create_upgrade_delete" = create_upgrade_delete(&mut self, delete: &[ParquetFileId], upgrade: &[ParquetFileId], create: &[ParquetFileParams], target_level: CompactionLevel) -> Result<Vec<ParquetFileId>>;
     ]
 );
+
+decorate!(
+    impl_trait = ReadLeaseRepo,
+    methods = [
+        "read_lease_create" = create(&mut self, table_id: TableId, expires_at: Timestamp) -> Result<ReadLease>;
+        "read_lease_get_by_id" = get_by_id(&mut self, id: ReadLeaseId) -> Result<Option<ReadLease>>;
+        "read_lease_release" = release(&mut self, id: ReadLeaseId) -> Result<()>;
+        "read_lease_oldest_active" = oldest_active(&mut self) -> Result<Option<Timestamp>>;
+        "read_lease_delete_expired" = delete_expired(&mut self) -> Result<u64>;
+    ]
+);
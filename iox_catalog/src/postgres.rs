@@ -3,9 +3,10 @@
 use crate::interface::MAX_PARQUET_FILES_SELECTED_ONCE_FOR_DELETE;
 use crate::{
     interface::{
-        self, CasFailure, Catalog, ColumnRepo, ColumnTypeMismatchSnafu, Error, NamespaceRepo,
-        ParquetFileRepo, PartitionRepo, RepoCollection, Result, SoftDeletedRows, TableRepo,
-        MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION,
+        self, check_column_delete, check_column_rename, CasFailure, Catalog, ColumnRepo,
+        ColumnTypeMismatchSnafu, Error, NamespaceRepo, NamespaceTemplateRepo, ParquetFileRepo,
+        PartitionRepo, ReadLeaseRepo, RepoCollection, Result, SoftDeletedRows, TableRepo,
+        TenantRepo, MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION,
     },
     kafkaless_transition::{
         SHARED_QUERY_POOL, SHARED_QUERY_POOL_ID, SHARED_TOPIC_ID, SHARED_TOPIC_NAME,
@@ -20,10 +21,12 @@ use data_types::{
     partition_template::{
         NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
     },
-    Column, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceId,
-    NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
-    Table, TableId, Timestamp, TransitionPartitionId,
+    Column, ColumnDrop, ColumnRename, ColumnType, CompactionLevel, FrozenPartition,
+    MaxColumnsPerTable, MaxTables, Namespace, NamespaceId, NamespaceName,
+    NamespaceServiceProtectionLimitsOverride, NamespaceTemplate, NamespaceTemplateId, ParquetFile,
+    ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
+    ReadLease, ReadLeaseId, SkippedCompaction, Table, TableId, Tenant, TenantId, TenantQuotas,
+    Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{Attributes, Instrument, MetricKind};
@@ -612,10 +615,18 @@ fn get_dsn_file_path(dsn: &str) -> Option<String> {
 
 #[async_trait]
 impl RepoCollection for PostgresTxn {
+    fn tenants(&mut self) -> &mut dyn TenantRepo {
+        self
+    }
+
     fn namespaces(&mut self) -> &mut dyn NamespaceRepo {
         self
     }
 
+    fn namespace_templates(&mut self) -> &mut dyn NamespaceTemplateRepo {
+        self
+    }
+
     fn tables(&mut self) -> &mut dyn TableRepo {
         self
     }
@@ -631,6 +642,10 @@ impl RepoCollection for PostgresTxn {
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
         self
     }
+
+    fn read_leases(&mut self) -> &mut dyn ReadLeaseRepo {
+        self
+    }
 }
 
 async fn insert_column_with_connection<'q, E>(
@@ -687,6 +702,230 @@ RETURNING *;
     Ok(rec)
 }
 
+#[async_trait]
+impl TenantRepo for PostgresTxn {
+    async fn create(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant> {
+        let rec = sqlx::query_as::<_, Tenant>(
+            r#"
+INSERT INTO tenant ( name, max_storage_bytes, max_series, max_write_lines_per_second )
+VALUES ( $1, $2, $3, $4 )
+RETURNING *;
+            "#,
+        )
+        .bind(name) // $1
+        .bind(quotas.max_storage_bytes) // $2
+        .bind(quotas.max_series) // $3
+        .bind(quotas.max_write_lines_per_second) // $4
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                Error::NameExists {
+                    name: name.to_string(),
+                }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        Ok(rec)
+    }
+
+    async fn get_by_id(&mut self, id: TenantId) -> Result<Option<Tenant>> {
+        let rec = sqlx::query_as::<_, Tenant>(r#"SELECT * FROM tenant WHERE id = $1;"#)
+            .bind(id) // $1
+            .fetch_one(&mut self.inner)
+            .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let tenant = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(tenant))
+    }
+
+    async fn get_by_name(&mut self, name: &str) -> Result<Option<Tenant>> {
+        let rec = sqlx::query_as::<_, Tenant>(r#"SELECT * FROM tenant WHERE name = $1;"#)
+            .bind(name) // $1
+            .fetch_one(&mut self.inner)
+            .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let tenant = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(tenant))
+    }
+
+    async fn list(&mut self) -> Result<Vec<Tenant>> {
+        sqlx::query_as::<_, Tenant>(r#"SELECT * FROM tenant ORDER BY id;"#)
+            .fetch_all(&mut self.inner)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn update_quotas(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant> {
+        let rec = sqlx::query_as::<_, Tenant>(
+            r#"
+UPDATE tenant
+SET max_storage_bytes = $1, max_series = $2, max_write_lines_per_second = $3
+WHERE name = $4
+RETURNING *;
+        "#,
+        )
+        .bind(quotas.max_storage_bytes) // $1
+        .bind(quotas.max_series) // $2
+        .bind(quotas.max_write_lines_per_second) // $3
+        .bind(name) // $4
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let tenant = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TenantNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(tenant)
+    }
+
+    async fn storage_bytes(&mut self, id: TenantId) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+SELECT COALESCE(SUM(parquet_file.file_size_bytes), 0)::BIGINT AS storage_bytes
+FROM parquet_file
+INNER JOIN namespace ON namespace.id = parquet_file.namespace_id
+WHERE namespace.tenant_id = $1 AND parquet_file.to_delete IS NULL;
+        "#,
+        )
+        .bind(id) // $1
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(row.get("storage_bytes"))
+    }
+}
+
+#[async_trait]
+impl NamespaceTemplateRepo for PostgresTxn {
+    async fn create(
+        &mut self,
+        name: &str,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<NamespaceTemplate> {
+        let rec = sqlx::query_as::<_, NamespaceTemplate>(
+            r#"
+INSERT INTO namespace_template (
+    name, retention_period_ns, max_tables, max_columns_per_table, partition_template
+)
+VALUES ( $1, $2, $3, $4, $5 )
+RETURNING *;
+            "#,
+        )
+        .bind(name) // $1
+        .bind(retention_period_ns) // $2
+        .bind(service_protection_limits.and_then(|l| l.max_tables)) // $3
+        .bind(service_protection_limits.and_then(|l| l.max_columns_per_table)) // $4
+        .bind(partition_template) // $5
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                Error::NameExists {
+                    name: name.to_string(),
+                }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        Ok(rec)
+    }
+
+    async fn get_by_id(&mut self, id: NamespaceTemplateId) -> Result<Option<NamespaceTemplate>> {
+        let rec = sqlx::query_as::<_, NamespaceTemplate>(
+            r#"SELECT * FROM namespace_template WHERE id = $1;"#,
+        )
+        .bind(id) // $1
+        .fetch_one(&mut self.inner)
+        .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let template = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(template))
+    }
+
+    async fn get_by_name(&mut self, name: &str) -> Result<Option<NamespaceTemplate>> {
+        let rec = sqlx::query_as::<_, NamespaceTemplate>(
+            r#"SELECT * FROM namespace_template WHERE name = $1;"#,
+        )
+        .bind(name) // $1
+        .fetch_one(&mut self.inner)
+        .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let template = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(template))
+    }
+
+    async fn list(&mut self) -> Result<Vec<NamespaceTemplate>> {
+        sqlx::query_as::<_, NamespaceTemplate>(r#"SELECT * FROM namespace_template ORDER BY id;"#)
+            .fetch_all(&mut self.inner)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn update(
+        &mut self,
+        name: &str,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<NamespaceTemplate> {
+        let rec = sqlx::query_as::<_, NamespaceTemplate>(
+            r#"
+UPDATE namespace_template
+SET retention_period_ns = $1, max_tables = $2, max_columns_per_table = $3,
+    partition_template = $4
+WHERE name = $5
+RETURNING *;
+        "#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(service_protection_limits.and_then(|l| l.max_tables)) // $2
+        .bind(service_protection_limits.and_then(|l| l.max_columns_per_table)) // $3
+        .bind(partition_template) // $4
+        .bind(name) // $5
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let template = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceTemplateNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(template)
+    }
+}
+
 #[async_trait]
 impl NamespaceRepo for PostgresTxn {
     async fn create(
@@ -710,7 +949,7 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, $5, $6, $7 )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
             "#,
         )
         .bind(name.as_str()) // $1
@@ -741,7 +980,7 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, tenant_id, template_id
 FROM namespace
 WHERE {v};
                 "#,
@@ -765,7 +1004,7 @@ WHERE {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, tenant_id, template_id
 FROM namespace
 WHERE id=$1 AND {v};
                 "#,
@@ -795,7 +1034,7 @@ WHERE id=$1 AND {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, tenant_id, template_id
 FROM namespace
 WHERE name=$1 AND {v};
                 "#,
@@ -836,7 +1075,7 @@ UPDATE namespace
 SET max_tables = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
         "#,
         )
         .bind(new_max)
@@ -865,7 +1104,7 @@ UPDATE namespace
 SET max_columns_per_table = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
         "#,
         )
         .bind(new_max)
@@ -894,7 +1133,7 @@ UPDATE namespace
 SET retention_period_ns = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
         "#,
         )
         .bind(retention_period_ns) // $1
@@ -911,6 +1150,70 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
 
         Ok(namespace)
     }
+
+    async fn update_tenant(
+        &mut self,
+        name: &str,
+        tenant_id: Option<TenantId>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET tenant_id = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, tenant_id, template_id;
+        "#,
+        )
+        .bind(tenant_id) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ if is_fk_violation(&e) => Error::TenantNotFoundById {
+                id: tenant_id.expect("only a tenant ID can violate the foreign key"),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_template(
+        &mut self,
+        name: &str,
+        template_id: Option<NamespaceTemplateId>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET template_id = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, tenant_id, template_id;
+        "#,
+        )
+        .bind(template_id) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ if is_fk_violation(&e) => Error::NamespaceTemplateNotFoundById {
+                id: template_id.expect("only a template ID can violate the foreign key"),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
 }
 
 #[async_trait]
@@ -1062,6 +1365,63 @@ WHERE namespace_id = $1;
 
         Ok(rec)
     }
+
+    async fn rename(&mut self, table_id: TableId, new_name: &str) -> Result<Table> {
+        let namespace_id = self
+            .get_by_id(table_id)
+            .await?
+            .ok_or(Error::TableNotFound { id: table_id })?
+            .namespace_id;
+
+        sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET name = $1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(new_name) // $1
+        .bind(table_id) // $2
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => {
+                if is_unique_violation(&e) {
+                    Error::TableNameExists {
+                        name: new_name.to_string(),
+                        namespace_id,
+                    }
+                } else {
+                    Error::SqlxError { source: e }
+                }
+            }
+        })
+    }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET retention_period_ns = $1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(table_id) // $2
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })
+    }
 }
 
 #[async_trait]
@@ -1172,6 +1532,215 @@ RETURNING *;
 
         Ok(out)
     }
+
+    async fn rename(&mut self, table_id: TableId, name: &str, new_name: &str) -> Result<Column> {
+        let renamed_at = Timestamp::from(self.time_provider.now());
+
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let table = sqlx::query_as::<_, Table>("SELECT * FROM table_name WHERE id = $1;")
+            .bind(table_id) // $1
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?
+            .ok_or(Error::TableNotFound { id: table_id })?;
+
+        let column = sqlx::query_as::<_, Column>(
+            r#"
+SELECT * FROM column_name
+WHERE table_id = $1 AND name = $2
+FOR UPDATE;
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(name) // $2
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .ok_or_else(|| Error::ColumnNotFoundByName {
+            name: name.to_string(),
+            table_id,
+        })?;
+
+        check_column_rename(&table, &column)?;
+
+        let column = sqlx::query_as::<_, Column>(
+            r#"
+UPDATE column_name
+SET name = $1
+WHERE id = $2
+RETURNING *;
+            "#,
+        )
+        .bind(new_name) // $1
+        .bind(column.id) // $2
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                Error::ColumnNameExists {
+                    name: new_name.to_string(),
+                    table_id,
+                }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        // sort keys still refer to columns by name as well
+        sqlx::query(
+            r#"
+UPDATE partition
+SET sort_key = array_replace(sort_key, $1, $2)
+WHERE table_id = $3 AND $1 = ANY(sort_key);
+            "#,
+        )
+        .bind(name) // $1
+        .bind(new_name) // $2
+        .bind(table_id) // $3
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        sqlx::query(
+            r#"
+INSERT INTO column_rename ( table_id, column_id, old_name, new_name, renamed_at )
+VALUES ( $1, $2, $3, $4, $5 );
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(column.id) // $2
+        .bind(name) // $3
+        .bind(new_name) // $4
+        .bind(renamed_at) // $5
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(column)
+    }
+
+    async fn delete(&mut self, table_id: TableId, name: &str) -> Result<Column> {
+        let dropped_at = Timestamp::from(self.time_provider.now());
+
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let column = sqlx::query_as::<_, Column>(
+            r#"
+SELECT * FROM column_name
+WHERE table_id = $1 AND name = $2
+FOR UPDATE;
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(name) // $2
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .ok_or_else(|| Error::ColumnNotFoundByName {
+            name: name.to_string(),
+            table_id,
+        })?;
+
+        check_column_delete(&column)?;
+
+        sqlx::query(
+            r#"
+INSERT INTO column_drop ( table_id, column_id, name, dropped_at )
+VALUES ( $1, $2, $3, $4 );
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(column.id) // $2
+        .bind(&column.name) // $3
+        .bind(dropped_at) // $4
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        // the renames of the column are deleted by the cascade
+        sqlx::query("DELETE FROM column_name WHERE id = $1;")
+            .bind(column.id) // $1
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(column)
+    }
+
+    async fn list_renames_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnRename>> {
+        let rec = sqlx::query_as::<_, ColumnRename>(
+            r#"
+SELECT column_rename.table_id, column_id, old_name, new_name, renamed_at
+FROM table_name
+INNER JOIN column_rename on column_rename.table_id = table_name.id
+WHERE table_name.namespace_id = $1;
+            "#,
+        )
+        .bind(namespace_id)
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
+
+    async fn list_renames_by_table_id(&mut self, table_id: TableId) -> Result<Vec<ColumnRename>> {
+        let rec = sqlx::query_as::<_, ColumnRename>(
+            r#"
+SELECT table_id, column_id, old_name, new_name, renamed_at
+FROM column_rename
+WHERE table_id = $1;
+            "#,
+        )
+        .bind(table_id)
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
+
+    async fn list_drops_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnDrop>> {
+        let rec = sqlx::query_as::<_, ColumnDrop>(
+            r#"
+SELECT column_drop.table_id, column_id, column_drop.name, dropped_at
+FROM table_name
+INNER JOIN column_drop on column_drop.table_id = table_name.id
+WHERE table_name.namespace_id = $1;
+            "#,
+        )
+        .bind(namespace_id)
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
 }
 
 #[async_trait]
@@ -1498,6 +2067,96 @@ RETURNING *
         .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
     }
 
+    async fn freeze(&mut self, partition_id: PartitionId, reason: &str) -> Result<FrozenPartition> {
+        let frozen_at = Timestamp::from(self.time_provider.now());
+
+        sqlx::query(
+            r#"
+INSERT INTO frozen_partition ( partition_id, reason, frozen_at )
+VALUES ( $1, $2, $3 )
+ON CONFLICT ( partition_id )
+DO UPDATE SET reason = EXCLUDED.reason;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(reason) // $2
+        .bind(frozen_at) // $3
+        .execute(&mut self.inner)
+        .await
+        .map_err(|e| {
+            if is_fk_violation(&e) {
+                Error::PartitionNotFound {
+                    id: TransitionPartitionId::Deprecated(partition_id),
+                }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        sqlx::query_as::<_, FrozenPartition>(
+            r#"
+SELECT frozen_partition.partition_id, partition.table_id, partition.partition_key,
+       frozen_partition.reason, frozen_partition.frozen_at
+FROM frozen_partition
+INNER JOIN partition ON partition.id = frozen_partition.partition_id
+WHERE frozen_partition.partition_id = $1;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn unfreeze(&mut self, partition_id: PartitionId) -> Result<Option<FrozenPartition>> {
+        sqlx::query_as::<_, FrozenPartition>(
+            r#"
+DELETE FROM frozen_partition
+USING partition
+WHERE frozen_partition.partition_id = $1 AND partition.id = frozen_partition.partition_id
+RETURNING frozen_partition.partition_id, partition.table_id, partition.partition_key,
+          frozen_partition.reason, frozen_partition.frozen_at;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .fetch_optional(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn list_frozen(&mut self) -> Result<Vec<FrozenPartition>> {
+        sqlx::query_as::<_, FrozenPartition>(
+            r#"
+SELECT frozen_partition.partition_id, partition.table_id, partition.partition_key,
+       frozen_partition.reason, frozen_partition.frozen_at
+FROM frozen_partition
+INNER JOIN partition ON partition.id = frozen_partition.partition_id;
+        "#,
+        )
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn get_in_frozen(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<FrozenPartition>> {
+        sqlx::query_as::<_, FrozenPartition>(
+            r#"
+SELECT frozen_partition.partition_id, partition.table_id, partition.partition_key,
+       frozen_partition.reason, frozen_partition.frozen_at
+FROM frozen_partition
+INNER JOIN partition ON partition.id = frozen_partition.partition_id
+WHERE frozen_partition.partition_id = ANY($1);
+        "#,
+        )
+        .bind(partition_ids) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         sqlx::query_as(
     // TODO: Carol has confirmed the persisted_sequence_number is not needed anywhere so let us remove it
@@ -1561,6 +2220,85 @@ ORDER BY id DESC;"#,
     }
 }
 
+#[async_trait]
+impl ReadLeaseRepo for PostgresTxn {
+    async fn create(&mut self, table_id: TableId, expires_at: Timestamp) -> Result<ReadLease> {
+        let created_at = Timestamp::from(self.time_provider.now());
+
+        let rec = sqlx::query_as::<_, ReadLease>(
+            r#"
+INSERT INTO read_lease ( table_id, created_at, expires_at )
+VALUES ( $1, $2, $3 )
+RETURNING *;
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(created_at) // $2
+        .bind(expires_at) // $3
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| {
+            if is_fk_violation(&e) {
+                Error::TableNotFound { id: table_id }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        Ok(rec)
+    }
+
+    async fn get_by_id(&mut self, id: ReadLeaseId) -> Result<Option<ReadLease>> {
+        let rec = sqlx::query_as::<_, ReadLease>(r#"SELECT * FROM read_lease WHERE id = $1;"#)
+            .bind(id) // $1
+            .fetch_one(&mut self.inner)
+            .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let lease = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(lease))
+    }
+
+    async fn release(&mut self, id: ReadLeaseId) -> Result<()> {
+        sqlx::query(r#"DELETE FROM read_lease WHERE id = $1;"#)
+            .bind(id) // $1
+            .execute(&mut self.inner)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(())
+    }
+
+    async fn oldest_active(&mut self) -> Result<Option<Timestamp>> {
+        let now = Timestamp::from(self.time_provider.now());
+
+        let rec: Option<Timestamp> =
+            sqlx::query_scalar(r#"SELECT MIN(created_at) FROM read_lease WHERE expires_at > $1;"#)
+                .bind(now) // $1
+                .fetch_one(&mut self.inner)
+                .await
+                .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
+
+    async fn delete_expired(&mut self) -> Result<u64> {
+        let now = Timestamp::from(self.time_provider.now());
+
+        let res = sqlx::query(r#"DELETE FROM read_lease WHERE expires_at <= $1;"#)
+            .bind(now) // $1
+            .execute(&mut self.inner)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(res.rows_affected())
+    }
+}
+
 #[async_trait]
 impl ParquetFileRepo for PostgresTxn {
     async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile> {
@@ -1576,7 +2314,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.content_hash
 FROM parquet_file;
              "#,
         )
@@ -1587,16 +2326,19 @@ FROM parquet_file;
 
     async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>> {
         let flagged_at = Timestamp::from(self.time_provider.now());
-        // TODO - include check of table retention period once implemented
+        // A table retention period expires the data of the table faster than the
+        // namespace retention period. LEAST ignores NULL values.
         let flagged = sqlx::query(
             r#"
 WITH parquet_file_ids as (
     SELECT parquet_file.id
-    FROM namespace, parquet_file
-    WHERE namespace.retention_period_ns IS NOT NULL
+    FROM namespace, table_name, parquet_file
+    WHERE (namespace.retention_period_ns IS NOT NULL
+        OR table_name.retention_period_ns IS NOT NULL)
     AND parquet_file.to_delete IS NULL
-    AND parquet_file.max_time < $1 - namespace.retention_period_ns
+    AND parquet_file.max_time < $1 - LEAST(namespace.retention_period_ns, table_name.retention_period_ns)
     AND namespace.id = parquet_file.namespace_id
+    AND table_name.id = parquet_file.table_id
     LIMIT $2
 )
 UPDATE parquet_file
@@ -1625,7 +2367,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.content_hash
 FROM parquet_file
 INNER JOIN table_name on table_name.id = parquet_file.table_id
 WHERE table_name.namespace_id = $1
@@ -1643,7 +2386,7 @@ WHERE table_name.namespace_id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
        min_time, max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at,
-       column_set, max_l0_created_at
+       column_set, max_l0_created_at, content_hash
 FROM parquet_file
 WHERE table_id = $1 AND to_delete IS NULL;
              "#,
@@ -1654,6 +2397,29 @@ WHERE table_id = $1 AND to_delete IS NULL;
         .map_err(|e| Error::SqlxError { source: e })
     }
 
+    async fn list_by_table_at(
+        &mut self,
+        table_id: TableId,
+        at: Timestamp,
+    ) -> Result<Vec<ParquetFile>> {
+        sqlx::query_as::<_, ParquetFile>(
+            r#"
+SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
+       min_time, max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at,
+       column_set, max_l0_created_at, content_hash
+FROM parquet_file
+WHERE table_id = $1
+  AND created_at <= $2
+  AND (to_delete IS NULL OR to_delete > $2);
+             "#,
+        )
+        .bind(table_id) // $1
+        .bind(at) // $2
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>> {
         // see https://www.crunchydata.com/blog/simulating-update-or-delete-with-limit-in-postgres-ctes-to-the-rescue
         let deleted = sqlx::query(
@@ -1689,7 +2455,7 @@ RETURNING id;
                 r#"
 SELECT parquet_file.id, namespace_id, parquet_file.table_id, partition_id, partition_hash_id,
        object_store_id, min_time, max_time, parquet_file.to_delete, file_size_bytes, row_count,
-       compaction_level, created_at, column_set, max_l0_created_at
+       compaction_level, created_at, column_set, max_l0_created_at, content_hash
 FROM parquet_file
 INNER JOIN partition
 ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
@@ -1702,7 +2468,7 @@ WHERE partition.hash_id = $1
                 r#"
 SELECT parquet_file.id, namespace_id, parquet_file.table_id, partition_id, partition_hash_id,
        object_store_id, min_time, max_time, parquet_file.to_delete, file_size_bytes, row_count,
-       compaction_level, created_at, column_set, max_l0_created_at
+       compaction_level, created_at, column_set, max_l0_created_at, content_hash
 FROM parquet_file
 INNER JOIN partition
 ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
@@ -1727,7 +2493,7 @@ WHERE partition.id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, content_hash
 FROM parquet_file
 WHERE object_store_id = $1;
              "#,
@@ -1827,6 +2593,7 @@ where
         created_at,
         column_set,
         max_l0_created_at,
+        content_hash,
     } = parquet_file_params;
 
     let (partition_id, partition_hash_id) = match partition_id {
@@ -1840,8 +2607,9 @@ where
 INSERT INTO parquet_file (
     shard_id, table_id, partition_id, partition_hash_id, object_store_id,
     min_time, max_time, file_size_bytes,
-    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at )
-VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14 )
+    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at,
+    content_hash )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15 )
 RETURNING id;
         "#,
     )
@@ -1858,7 +2626,8 @@ RETURNING id;
     .bind(created_at) // $11
     .bind(namespace_id) // $12
     .bind(column_set) // $13
-    .bind(max_l0_created_at); // $14
+    .bind(max_l0_created_at) // $14
+    .bind(content_hash.as_ref()); // $15
 
     let parquet_file_id = query.fetch_one(executor).await.map_err(|e| {
         if is_unique_violation(&e) {
@@ -2651,7 +3420,7 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, NULL )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
             "#,
         )
         .bind(namespace_name) // $1
@@ -2,9 +2,10 @@
 
 use crate::{
     interface::{
-        self, CasFailure, Catalog, ColumnRepo, ColumnTypeMismatchSnafu, Error, NamespaceRepo,
-        ParquetFileRepo, PartitionRepo, RepoCollection, Result, SoftDeletedRows, TableRepo,
-        MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION,
+        self, check_column_delete, check_column_rename, CasFailure, Catalog, ColumnRepo,
+        ColumnTypeMismatchSnafu, Error, NamespaceRepo, NamespaceTemplateRepo, ParquetFileRepo,
+        PartitionRepo, ReadLeaseRepo, RepoCollection, Result, SoftDeletedRows, TableRepo,
+        TenantRepo, MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION,
     },
     kafkaless_transition::{
         SHARED_QUERY_POOL, SHARED_QUERY_POOL_ID, SHARED_TOPIC_ID, SHARED_TOPIC_NAME,
@@ -17,10 +18,12 @@ use data_types::{
     partition_template::{
         NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
     },
-    Column, ColumnId, ColumnSet, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables,
-    Namespace, NamespaceId, NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile,
-    ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
-    SkippedCompaction, SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    Column, ColumnDrop, ColumnId, ColumnRename, ColumnSet, ColumnType, CompactionLevel,
+    ContentHash, FrozenPartition, MaxColumnsPerTable, MaxTables, Namespace, NamespaceId,
+    NamespaceName, NamespaceServiceProtectionLimitsOverride, NamespaceTemplate,
+    NamespaceTemplateId, ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionHashId,
+    PartitionId, PartitionKey, ReadLease, ReadLeaseId, SkippedCompaction, SortedColumnSet, Table,
+    TableId, Tenant, TenantId, TenantQuotas, Timestamp, TransitionPartitionId,
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt::Display};
@@ -240,10 +243,18 @@ DO NOTHING;
 
 #[async_trait]
 impl RepoCollection for SqliteTxn {
+    fn tenants(&mut self) -> &mut dyn TenantRepo {
+        self
+    }
+
     fn namespaces(&mut self) -> &mut dyn NamespaceRepo {
         self
     }
 
+    fn namespace_templates(&mut self) -> &mut dyn NamespaceTemplateRepo {
+        self
+    }
+
     fn tables(&mut self) -> &mut dyn TableRepo {
         self
     }
@@ -259,6 +270,234 @@ impl RepoCollection for SqliteTxn {
     fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
         self
     }
+
+    fn read_leases(&mut self) -> &mut dyn ReadLeaseRepo {
+        self
+    }
+}
+
+#[async_trait]
+impl TenantRepo for SqliteTxn {
+    async fn create(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant> {
+        let rec = sqlx::query_as::<_, Tenant>(
+            r#"
+INSERT INTO tenant ( name, max_storage_bytes, max_series, max_write_lines_per_second )
+VALUES ( $1, $2, $3, $4 )
+RETURNING *;
+            "#,
+        )
+        .bind(name) // $1
+        .bind(quotas.max_storage_bytes) // $2
+        .bind(quotas.max_series) // $3
+        .bind(quotas.max_write_lines_per_second) // $4
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                Error::NameExists {
+                    name: name.to_string(),
+                }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        Ok(rec)
+    }
+
+    async fn get_by_id(&mut self, id: TenantId) -> Result<Option<Tenant>> {
+        let rec = sqlx::query_as::<_, Tenant>(r#"SELECT * FROM tenant WHERE id = $1;"#)
+            .bind(id) // $1
+            .fetch_one(self.inner.get_mut())
+            .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let tenant = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(tenant))
+    }
+
+    async fn get_by_name(&mut self, name: &str) -> Result<Option<Tenant>> {
+        let rec = sqlx::query_as::<_, Tenant>(r#"SELECT * FROM tenant WHERE name = $1;"#)
+            .bind(name) // $1
+            .fetch_one(self.inner.get_mut())
+            .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let tenant = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(tenant))
+    }
+
+    async fn list(&mut self) -> Result<Vec<Tenant>> {
+        sqlx::query_as::<_, Tenant>(r#"SELECT * FROM tenant ORDER BY id;"#)
+            .fetch_all(self.inner.get_mut())
+            .await
+            .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn update_quotas(&mut self, name: &str, quotas: TenantQuotas) -> Result<Tenant> {
+        let rec = sqlx::query_as::<_, Tenant>(
+            r#"
+UPDATE tenant
+SET max_storage_bytes = $1, max_series = $2, max_write_lines_per_second = $3
+WHERE name = $4
+RETURNING *;
+        "#,
+        )
+        .bind(quotas.max_storage_bytes) // $1
+        .bind(quotas.max_series) // $2
+        .bind(quotas.max_write_lines_per_second) // $3
+        .bind(name) // $4
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let tenant = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TenantNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(tenant)
+    }
+
+    async fn storage_bytes(&mut self, id: TenantId) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+SELECT COALESCE(SUM(parquet_file.file_size_bytes), 0) AS storage_bytes
+FROM parquet_file
+INNER JOIN namespace ON namespace.id = parquet_file.namespace_id
+WHERE namespace.tenant_id = $1 AND parquet_file.to_delete IS NULL;
+        "#,
+        )
+        .bind(id) // $1
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(row.get("storage_bytes"))
+    }
+}
+
+#[async_trait]
+impl NamespaceTemplateRepo for SqliteTxn {
+    async fn create(
+        &mut self,
+        name: &str,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<NamespaceTemplate> {
+        let rec = sqlx::query_as::<_, NamespaceTemplate>(
+            r#"
+INSERT INTO namespace_template (
+    name, retention_period_ns, max_tables, max_columns_per_table, partition_template
+)
+VALUES ( $1, $2, $3, $4, $5 )
+RETURNING *;
+            "#,
+        )
+        .bind(name) // $1
+        .bind(retention_period_ns) // $2
+        .bind(service_protection_limits.and_then(|l| l.max_tables)) // $3
+        .bind(service_protection_limits.and_then(|l| l.max_columns_per_table)) // $4
+        .bind(partition_template) // $5
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                Error::NameExists {
+                    name: name.to_string(),
+                }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        Ok(rec)
+    }
+
+    async fn get_by_id(&mut self, id: NamespaceTemplateId) -> Result<Option<NamespaceTemplate>> {
+        let rec = sqlx::query_as::<_, NamespaceTemplate>(
+            r#"SELECT * FROM namespace_template WHERE id = $1;"#,
+        )
+        .bind(id) // $1
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let template = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(template))
+    }
+
+    async fn get_by_name(&mut self, name: &str) -> Result<Option<NamespaceTemplate>> {
+        let rec = sqlx::query_as::<_, NamespaceTemplate>(
+            r#"SELECT * FROM namespace_template WHERE name = $1;"#,
+        )
+        .bind(name) // $1
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
+        }
+
+        let template = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(template))
+    }
+
+    async fn list(&mut self) -> Result<Vec<NamespaceTemplate>> {
+        sqlx::query_as::<_, NamespaceTemplate>(r#"SELECT * FROM namespace_template ORDER BY id;"#)
+            .fetch_all(self.inner.get_mut())
+            .await
+            .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn update(
+        &mut self,
+        name: &str,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<NamespaceTemplate> {
+        let rec = sqlx::query_as::<_, NamespaceTemplate>(
+            r#"
+UPDATE namespace_template
+SET retention_period_ns = $1, max_tables = $2, max_columns_per_table = $3,
+    partition_template = $4
+WHERE name = $5
+RETURNING *;
+        "#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(service_protection_limits.and_then(|l| l.max_tables)) // $2
+        .bind(service_protection_limits.and_then(|l| l.max_columns_per_table)) // $3
+        .bind(partition_template) // $4
+        .bind(name) // $5
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let template = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceTemplateNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(template)
+    }
 }
 
 #[async_trait]
@@ -282,7 +521,7 @@ impl NamespaceRepo for SqliteTxn {
 INSERT INTO namespace ( name, topic_id, query_pool_id, retention_period_ns, max_tables, max_columns_per_table, partition_template )
 VALUES ( $1, $2, $3, $4, $5, $6, $7 )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
             "#,
         )
         .bind(name.as_str()) // $1
@@ -313,7 +552,7 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, tenant_id, template_id
 FROM namespace
 WHERE {v};
                 "#,
@@ -337,7 +576,7 @@ WHERE {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, tenant_id, template_id
 FROM namespace
 WHERE id=$1 AND {v};
                 "#,
@@ -367,7 +606,7 @@ WHERE id=$1 AND {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, tenant_id, template_id
 FROM namespace
 WHERE name=$1 AND {v};
                 "#,
@@ -408,7 +647,7 @@ UPDATE namespace
 SET max_tables = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
         "#,
         )
         .bind(new_max)
@@ -437,7 +676,7 @@ UPDATE namespace
 SET max_columns_per_table = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
         "#,
         )
         .bind(new_max)
@@ -466,7 +705,7 @@ UPDATE namespace
 SET retention_period_ns = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
             "#,
         )
         .bind(retention_period_ns) // $1
@@ -483,6 +722,70 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
 
         Ok(namespace)
     }
+
+    async fn update_tenant(
+        &mut self,
+        name: &str,
+        tenant_id: Option<TenantId>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET tenant_id = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, tenant_id, template_id;
+        "#,
+        )
+        .bind(tenant_id) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ if is_fk_violation(&e) => Error::TenantNotFoundById {
+                id: tenant_id.expect("only a tenant ID can violate the foreign key"),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_template(
+        &mut self,
+        name: &str,
+        template_id: Option<NamespaceTemplateId>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET template_id = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, tenant_id, template_id;
+        "#,
+        )
+        .bind(template_id) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ if is_fk_violation(&e) => Error::NamespaceTemplateNotFoundById {
+                id: template_id.expect("only a template ID can violate the foreign key"),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
 }
 
 /// [`TableRepo::create`] needs the ability to create some columns within the same transaction as
@@ -694,6 +997,63 @@ WHERE namespace_id = $1;
 
         Ok(rec)
     }
+
+    async fn rename(&mut self, table_id: TableId, new_name: &str) -> Result<Table> {
+        let namespace_id = self
+            .get_by_id(table_id)
+            .await?
+            .ok_or(Error::TableNotFound { id: table_id })?
+            .namespace_id;
+
+        sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET name = $1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(new_name) // $1
+        .bind(table_id) // $2
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => {
+                if is_unique_violation(&e) {
+                    Error::TableNameExists {
+                        name: new_name.to_string(),
+                        namespace_id,
+                    }
+                } else {
+                    Error::SqlxError { source: e }
+                }
+            }
+        })
+    }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET retention_period_ns = $1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(table_id) // $2
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })
+    }
 }
 
 #[async_trait]
@@ -813,6 +1173,224 @@ RETURNING *;
 
         Ok(out)
     }
+
+    async fn rename(&mut self, table_id: TableId, name: &str, new_name: &str) -> Result<Column> {
+        let renamed_at = Timestamp::from(self.time_provider.now());
+
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let table = sqlx::query_as::<_, Table>("SELECT * FROM table_name WHERE id = $1;")
+            .bind(table_id) // $1
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?
+            .ok_or(Error::TableNotFound { id: table_id })?;
+
+        let column = sqlx::query_as::<_, Column>(
+            r#"
+SELECT * FROM column_name
+WHERE table_id = $1 AND name = $2;
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(name) // $2
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .ok_or_else(|| Error::ColumnNotFoundByName {
+            name: name.to_string(),
+            table_id,
+        })?;
+
+        check_column_rename(&table, &column)?;
+
+        let column = sqlx::query_as::<_, Column>(
+            r#"
+UPDATE column_name
+SET name = $1
+WHERE id = $2
+RETURNING *;
+            "#,
+        )
+        .bind(new_name) // $1
+        .bind(column.id) // $2
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                Error::ColumnNameExists {
+                    name: new_name.to_string(),
+                    table_id,
+                }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        // sort keys still refer to columns by name as well
+        sqlx::query(
+            r#"
+UPDATE partition
+SET sort_key = (
+    SELECT json_group_array(CASE WHEN value = $1 THEN $2 ELSE value END)
+    FROM json_each(partition.sort_key)
+)
+WHERE table_id = $3
+AND EXISTS (SELECT 1 FROM json_each(partition.sort_key) WHERE value = $1);
+            "#,
+        )
+        .bind(name) // $1
+        .bind(new_name) // $2
+        .bind(table_id) // $3
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        sqlx::query(
+            r#"
+INSERT INTO column_rename ( table_id, column_id, old_name, new_name, renamed_at )
+VALUES ( $1, $2, $3, $4, $5 );
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(column.id) // $2
+        .bind(name) // $3
+        .bind(new_name) // $4
+        .bind(renamed_at) // $5
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(column)
+    }
+
+    async fn delete(&mut self, table_id: TableId, name: &str) -> Result<Column> {
+        let dropped_at = Timestamp::from(self.time_provider.now());
+
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let column = sqlx::query_as::<_, Column>(
+            r#"
+SELECT * FROM column_name
+WHERE table_id = $1 AND name = $2;
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(name) // $2
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .ok_or_else(|| Error::ColumnNotFoundByName {
+            name: name.to_string(),
+            table_id,
+        })?;
+
+        check_column_delete(&column)?;
+
+        sqlx::query("DELETE FROM column_rename WHERE column_id = $1;")
+            .bind(column.id) // $1
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        sqlx::query(
+            r#"
+INSERT INTO column_drop ( table_id, column_id, name, dropped_at )
+VALUES ( $1, $2, $3, $4 );
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(column.id) // $2
+        .bind(&column.name) // $3
+        .bind(dropped_at) // $4
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        sqlx::query("DELETE FROM column_name WHERE id = $1;")
+            .bind(column.id) // $1
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(column)
+    }
+
+    async fn list_renames_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnRename>> {
+        let rec = sqlx::query_as::<_, ColumnRename>(
+            r#"
+SELECT column_rename.table_id, column_id, old_name, new_name, renamed_at
+FROM table_name
+INNER JOIN column_rename on column_rename.table_id = table_name.id
+WHERE table_name.namespace_id = $1;
+            "#,
+        )
+        .bind(namespace_id)
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
+
+    async fn list_renames_by_table_id(&mut self, table_id: TableId) -> Result<Vec<ColumnRename>> {
+        let rec = sqlx::query_as::<_, ColumnRename>(
+            r#"
+SELECT table_id, column_id, old_name, new_name, renamed_at
+FROM column_rename
+WHERE table_id = $1;
+            "#,
+        )
+        .bind(table_id)
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
+
+    async fn list_drops_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnDrop>> {
+        let rec = sqlx::query_as::<_, ColumnDrop>(
+            r#"
+SELECT column_drop.table_id, column_id, column_drop.name, dropped_at
+FROM table_name
+INNER JOIN column_drop on column_drop.table_id = table_name.id
+WHERE table_name.namespace_id = $1;
+            "#,
+        )
+        .bind(namespace_id)
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
 }
 
 // We can't use [`Partition`], as uses Vec<String> which the Sqlite
@@ -1193,6 +1771,97 @@ RETURNING *
         .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
     }
 
+    async fn freeze(&mut self, partition_id: PartitionId, reason: &str) -> Result<FrozenPartition> {
+        let frozen_at = Timestamp::from(self.time_provider.now());
+
+        sqlx::query(
+            r#"
+INSERT INTO frozen_partition ( partition_id, reason, frozen_at )
+VALUES ( $1, $2, $3 )
+ON CONFLICT ( partition_id )
+DO UPDATE SET reason = EXCLUDED.reason;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(reason) // $2
+        .bind(frozen_at) // $3
+        .execute(self.inner.get_mut())
+        .await
+        .map_err(|e| {
+            if is_fk_violation(&e) {
+                Error::PartitionNotFound {
+                    id: TransitionPartitionId::Deprecated(partition_id),
+                }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        sqlx::query_as::<_, FrozenPartition>(
+            r#"
+SELECT frozen_partition.partition_id, partition.table_id, partition.partition_key,
+       frozen_partition.reason, frozen_partition.frozen_at
+FROM frozen_partition
+INNER JOIN partition ON partition.id = frozen_partition.partition_id
+WHERE frozen_partition.partition_id = $1;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn unfreeze(&mut self, partition_id: PartitionId) -> Result<Option<FrozenPartition>> {
+        // SQLite does not support joins in the RETURNING clause of a DELETE,
+        // so read the record before deleting it.
+        let Some(frozen) = self.get_in_frozen(&[partition_id]).await?.pop() else {
+            return Ok(None);
+        };
+
+        sqlx::query(r#"DELETE FROM frozen_partition WHERE partition_id = $1;"#)
+            .bind(partition_id) // $1
+            .execute(self.inner.get_mut())
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(frozen))
+    }
+
+    async fn list_frozen(&mut self) -> Result<Vec<FrozenPartition>> {
+        sqlx::query_as::<_, FrozenPartition>(
+            r#"
+SELECT frozen_partition.partition_id, partition.table_id, partition.partition_key,
+       frozen_partition.reason, frozen_partition.frozen_at
+FROM frozen_partition
+INNER JOIN partition ON partition.id = frozen_partition.partition_id;
+        "#,
+        )
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn get_in_frozen(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<FrozenPartition>> {
+        let ids = partition_ids.iter().map(|p| p.get()).collect::<Vec<_>>();
+        sqlx::query_as::<_, FrozenPartition>(
+            r#"
+SELECT frozen_partition.partition_id, partition.table_id, partition.partition_key,
+       frozen_partition.reason, frozen_partition.frozen_at
+FROM frozen_partition
+INNER JOIN partition ON partition.id = frozen_partition.partition_id
+WHERE frozen_partition.partition_id IN (SELECT value FROM json_each($1));
+        "#,
+        )
+        .bind(Json(&ids[..])) // $1
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         Ok(sqlx::query_as::<_, PartitionPod>(
             r#"
@@ -1279,6 +1948,7 @@ struct ParquetFilePod {
     created_at: Timestamp,
     column_set: Json<Vec<i64>>,
     max_l0_created_at: Timestamp,
+    content_hash: Option<ContentHash>,
 }
 
 impl From<ParquetFilePod> for ParquetFile {
@@ -1298,7 +1968,87 @@ impl From<ParquetFilePod> for ParquetFile {
             created_at: value.created_at,
             column_set: to_column_set(&value.column_set),
             max_l0_created_at: value.max_l0_created_at,
+            content_hash: value.content_hash,
+        }
+    }
+}
+
+#[async_trait]
+impl ReadLeaseRepo for SqliteTxn {
+    async fn create(&mut self, table_id: TableId, expires_at: Timestamp) -> Result<ReadLease> {
+        let created_at = Timestamp::from(self.time_provider.now());
+
+        let rec = sqlx::query_as::<_, ReadLease>(
+            r#"
+INSERT INTO read_lease ( table_id, created_at, expires_at )
+VALUES ( $1, $2, $3 )
+RETURNING *;
+            "#,
+        )
+        .bind(table_id) // $1
+        .bind(created_at) // $2
+        .bind(expires_at) // $3
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| {
+            if is_fk_violation(&e) {
+                Error::TableNotFound { id: table_id }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        Ok(rec)
+    }
+
+    async fn get_by_id(&mut self, id: ReadLeaseId) -> Result<Option<ReadLease>> {
+        let rec = sqlx::query_as::<_, ReadLease>(r#"SELECT * FROM read_lease WHERE id = $1;"#)
+            .bind(id) // $1
+            .fetch_one(self.inner.get_mut())
+            .await;
+
+        if let Err(sqlx::Error::RowNotFound) = rec {
+            return Ok(None);
         }
+
+        let lease = rec.map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(Some(lease))
+    }
+
+    async fn release(&mut self, id: ReadLeaseId) -> Result<()> {
+        sqlx::query(r#"DELETE FROM read_lease WHERE id = $1;"#)
+            .bind(id) // $1
+            .execute(self.inner.get_mut())
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(())
+    }
+
+    async fn oldest_active(&mut self) -> Result<Option<Timestamp>> {
+        let now = Timestamp::from(self.time_provider.now());
+
+        let rec: Option<Timestamp> =
+            sqlx::query_scalar(r#"SELECT MIN(created_at) FROM read_lease WHERE expires_at > $1;"#)
+                .bind(now) // $1
+                .fetch_one(self.inner.get_mut())
+                .await
+                .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
+
+    async fn delete_expired(&mut self) -> Result<u64> {
+        let now = Timestamp::from(self.time_provider.now());
+
+        let res = sqlx::query(r#"DELETE FROM read_lease WHERE expires_at <= $1;"#)
+            .bind(now) // $1
+            .execute(self.inner.get_mut())
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(res.rows_affected())
     }
 }
 
@@ -1318,7 +2068,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.content_hash
 FROM parquet_file;
              "#,
         )
@@ -1332,16 +2083,23 @@ FROM parquet_file;
 
     async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>> {
         let flagged_at = Timestamp::from(self.time_provider.now());
-        // TODO - include check of table retention period once implemented
+        // A table retention period expires the data of the table faster than the
+        // namespace retention period. Unlike LEAST in Postgres, MIN returns NULL
+        // if any argument is NULL, hence the COALESCE.
         let flagged = sqlx::query(
             r#"
 WITH parquet_file_ids as (
     SELECT parquet_file.id
-    FROM namespace, parquet_file
-    WHERE namespace.retention_period_ns IS NOT NULL
+    FROM namespace, table_name, parquet_file
+    WHERE (namespace.retention_period_ns IS NOT NULL
+        OR table_name.retention_period_ns IS NOT NULL)
     AND parquet_file.to_delete IS NULL
-    AND parquet_file.max_time < $1 - namespace.retention_period_ns
+    AND parquet_file.max_time < $1 - MIN(
+        COALESCE(namespace.retention_period_ns, table_name.retention_period_ns),
+        COALESCE(table_name.retention_period_ns, namespace.retention_period_ns)
+    )
     AND namespace.id = parquet_file.namespace_id
+    AND table_name.id = parquet_file.table_id
     LIMIT $2
 )
 UPDATE parquet_file
@@ -1372,7 +2130,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.content_hash
 FROM parquet_file
 INNER JOIN table_name on table_name.id = parquet_file.table_id
 WHERE table_name.namespace_id = $1
@@ -1393,7 +2152,7 @@ WHERE table_name.namespace_id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
        min_time, max_time, to_delete, file_size_bytes,
-       row_count, compaction_level, created_at, column_set, max_l0_created_at
+       row_count, compaction_level, created_at, column_set, max_l0_created_at, content_hash
 FROM parquet_file
 WHERE table_id = $1 AND to_delete IS NULL;
              "#,
@@ -1407,6 +2166,32 @@ WHERE table_id = $1 AND to_delete IS NULL;
         .collect())
     }
 
+    async fn list_by_table_at(
+        &mut self,
+        table_id: TableId,
+        at: Timestamp,
+    ) -> Result<Vec<ParquetFile>> {
+        Ok(sqlx::query_as::<_, ParquetFilePod>(
+            r#"
+SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
+       min_time, max_time, to_delete, file_size_bytes,
+       row_count, compaction_level, created_at, column_set, max_l0_created_at, content_hash
+FROM parquet_file
+WHERE table_id = $1
+  AND created_at <= $2
+  AND (to_delete IS NULL OR to_delete > $2);
+             "#,
+        )
+        .bind(table_id) // $1
+        .bind(at) // $2
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+    }
+
     async fn delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>> {
         // see https://www.crunchydata.com/blog/simulating-update-or-delete-with-limit-in-sqlite-ctes-to-the-rescue
         let deleted = sqlx::query(
@@ -1442,7 +2227,7 @@ RETURNING id;
                 r#"
 SELECT parquet_file.id, namespace_id, parquet_file.table_id, partition_id, partition_hash_id,
        object_store_id, min_time, max_time, parquet_file.to_delete, file_size_bytes, row_count,
-       compaction_level, created_at, column_set, max_l0_created_at
+       compaction_level, created_at, column_set, max_l0_created_at, content_hash
 FROM parquet_file
 INNER JOIN partition
 ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
@@ -1455,7 +2240,7 @@ WHERE partition.hash_id = $1
                 r#"
 SELECT parquet_file.id, namespace_id, parquet_file.table_id, partition_id, partition_hash_id,
        object_store_id, min_time, max_time, parquet_file.to_delete, file_size_bytes, row_count,
-       compaction_level, created_at, column_set, max_l0_created_at
+       compaction_level, created_at, column_set, max_l0_created_at, content_hash
 FROM parquet_file
 INNER JOIN partition
 ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
@@ -1483,7 +2268,7 @@ WHERE partition.id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, content_hash
 FROM parquet_file
 WHERE object_store_id = $1;
              "#,
@@ -1591,6 +2376,7 @@ where
         created_at,
         column_set,
         max_l0_created_at,
+        content_hash,
     } = parquet_file_params;
 
     let (partition_id, partition_hash_id) = match partition_id {
@@ -1602,12 +2388,13 @@ where
 INSERT INTO parquet_file (
     shard_id, table_id, partition_id, partition_hash_id, object_store_id,
     min_time, max_time, file_size_bytes,
-    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at )
-VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14 )
+    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at,
+    content_hash )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15 )
 RETURNING
     id, table_id, partition_id, partition_hash_id, object_store_id, min_time, max_time, to_delete,
     file_size_bytes, row_count, compaction_level, created_at, namespace_id, column_set,
-    max_l0_created_at;
+    max_l0_created_at, content_hash;
         "#,
     )
     .bind(TRANSITION_SHARD_ID) // $1
@@ -1624,6 +2411,7 @@ RETURNING
     .bind(namespace_id) // $12
     .bind(from_column_set(&column_set)) // $13
     .bind(max_l0_created_at) // $14
+    .bind(content_hash.as_ref()) // $15
     .fetch_one(executor)
     .await;
 
@@ -2130,7 +2918,7 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, NULL )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, tenant_id, template_id;
             "#,
         )
         .bind(namespace_name) // $1
@@ -12,7 +12,11 @@
 )]
 
 use chrono::prelude::*;
-use iox_data_generator::{specification::DataSpec, write::PointsWriterBuilder};
+use iox_data_generator::{
+    specification::DataSpec,
+    synthetic::{self, SyntheticSpec},
+    write::PointsWriterBuilder,
+};
 use std::{
     fs::File,
     io::{self, BufRead},
@@ -36,6 +40,10 @@ Examples:
     # Generate data points for the 24 hours between midnight 2020-01-01 and 2020-01-02
     iox_data_generator -s spec.toml -o lp --start 2020-01-01 --end 2020-01-02
 
+    # Generate the last hour of data for the series described by the synthetic spec in
+    # `synthetic.toml` and write it to the server at the spec's write rate
+    iox_data_generator --synthetic synthetic.toml --start "1 hr" -h localhost:8080 --org myorg --bucket mybucket --token mytoken
+
     # Generate data points starting from an hour ago until now, generating the historical data as
     # fast as possible. Then generate data according to the sampling interval until terminated.
     iox_data_generator -s spec.toml -o lp --start "1 hr" --continue
@@ -60,8 +68,17 @@ Logging:
 )]
 struct Config {
     /// Path to the specification TOML file describing the data generation
-    #[clap(long, short, action)]
-    specification: String,
+    #[clap(long, short, action, required_unless_present = "synthetic")]
+    specification: Option<String>,
+
+    /// Path to a synthetic specification TOML file describing the series, tag value
+    /// distributions, out-of-order fraction and write rate of a single measurement, used
+    /// instead of --specification.
+    ///
+    /// Synthetic data is only generated between --start and --end; --continue has no
+    /// effect.
+    #[clap(long, action, conflicts_with = "specification")]
+    synthetic: Option<String>,
 
     /// Print the generated line protocol from a single sample collection to the terminal
     #[clap(long, action)]
@@ -159,8 +176,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         if continue_on { " then continuing" } else { "" },
     );
 
-    let data_spec = DataSpec::from_file(&config.specification)?;
-
     let mut points_writer_builder = if let Some(line_protocol_filename) = config.output {
         PointsWriterBuilder::new_file(line_protocol_filename)?
     } else if let Some(parquet_directory) = config.parquet {
@@ -198,18 +213,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         vec![String::from("org_bucket")]
     };
 
-    let result = iox_data_generator::generate(
-        &data_spec,
-        buckets,
-        &mut points_writer_builder,
-        start_datetime,
-        end_datetime,
-        execution_start_time_nanos,
-        continue_on,
-        config.batch_size,
-        config.print,
-    )
-    .await;
+    let result = if let Some(synthetic) = config.synthetic {
+        let spec = SyntheticSpec::from_file(&synthetic)?;
+        let mut total_points = 0;
+        for bucket in buckets {
+            let (org, bucket) = bucket
+                .split_once('_')
+                .expect("database names are <org>_<bucket>");
+            let points_writer =
+                points_writer_builder.build_for_agent(&spec.measurement, org, bucket)?;
+            total_points += synthetic::generate(
+                &spec,
+                &points_writer,
+                start_display,
+                end_display,
+                config.batch_size,
+            )
+            .await?;
+        }
+        Ok(total_points)
+    } else {
+        let data_spec = DataSpec::from_file(
+            config
+                .specification
+                .as_deref()
+                .expect("clap requires --specification without --synthetic"),
+        )?;
+
+        iox_data_generator::generate(
+            &data_spec,
+            buckets,
+            &mut points_writer_builder,
+            start_datetime,
+            end_datetime,
+            execution_start_time_nanos,
+            continue_on,
+            config.batch_size,
+            config.print,
+        )
+        .await
+    };
 
     match result {
         Ok(total_points) => {
@@ -53,6 +53,7 @@ pub mod field;
 pub mod measurement;
 pub mod specification;
 pub mod substitution;
+pub mod synthetic;
 mod tag_pair;
 pub mod tag_set;
 pub mod write;
@@ -0,0 +1,658 @@
+//! Generating line protocol for a fixed number of series with a configurable
+//! tag value distribution, out-of-order timestamps and write rate.
+//!
+//! Where a [`DataSpec`](crate::specification::DataSpec) describes agents and
+//! lets the data's shape follow from them, a [`SyntheticSpec`] describes the
+//! shape of the data directly: how many series there are, how the values of
+//! each tag are distributed across them, the type of each field and what
+//! fraction of points arrive out of order. Generation is deterministic for a
+//! given seed, so the same spec can drive load against a live server with
+//! [`generate`] and seed test scenarios with [`SyntheticSpec::line_protocol`].
+//!
+//! ```toml
+//! measurement = "cpu"
+//! series = 1000
+//! sampling_interval = "10s"
+//! out_of_order_fraction = 0.05
+//! max_lateness = "5m"
+//! lines_per_second = 10000
+//!
+//! [[tags]]
+//! key = "host"
+//! cardinality = 500
+//! distribution = { zipf = { exponent = 1.2 } }
+//!
+//! [[tags]]
+//! key = "region"
+//! cardinality = 4
+//!
+//! [[fields]]
+//! key = "usage"
+//! type = "float"
+//!
+//! [[fields]]
+//! key = "healthy"
+//! type = "bool"
+//! ```
+
+use crate::write::PointsWriter;
+use humantime::parse_duration;
+use influxdb2_client::models::{DataPoint, WriteDataPoint};
+use rand::{distributions::Alphanumeric, rngs::StdRng, Rng, SeedableRng};
+use serde::Deserialize;
+use snafu::{ensure, ResultExt, Snafu};
+use std::{
+    collections::HashSet,
+    fs,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// Errors that may happen while reading or generating from a [`SyntheticSpec`].
+#[derive(Snafu, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display(
+        "Error reading synthetic spec from TOML file at {}: {}",
+        file_name,
+        source
+    ))]
+    ReadFile {
+        file_name: String,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Error parsing synthetic spec from TOML: {}", source))]
+    Parse { source: toml::de::Error },
+
+    #[snafu(display("{} must be a valid duration: {}", name, source))]
+    InvalidDuration {
+        name: &'static str,
+        source: humantime::DurationError,
+    },
+
+    #[snafu(display("sampling_interval must be greater than zero"))]
+    ZeroSamplingInterval,
+
+    #[snafu(display("Tag {} must have a cardinality greater than zero", key))]
+    ZeroCardinality { key: String },
+
+    #[snafu(display(
+        "Tag {} has zipf exponent {}, must be greater than zero",
+        key,
+        exponent
+    ))]
+    InvalidExponent { key: String, exponent: f64 },
+
+    #[snafu(display(
+        "Requested {} series but the tags only allow {} distinct series",
+        series,
+        max_series
+    ))]
+    TooManySeries { series: usize, max_series: usize },
+
+    #[snafu(display("out_of_order_fraction is {}, must be between 0.0 and 1.0", fraction))]
+    InvalidOutOfOrderFraction { fraction: f64 },
+
+    #[snafu(display("lines_per_second is {}, must be greater than zero", rate))]
+    InvalidWriteRate { rate: f64 },
+
+    #[snafu(display("At least one field is required"))]
+    NoFields,
+
+    #[snafu(display("Could not build data point: {}", source))]
+    DataPoint {
+        source: influxdb2_client::models::data_point::DataPointError,
+    },
+
+    #[snafu(display("Could not write points: {}", source))]
+    Write { source: crate::write::Error },
+}
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The specification of a synthetic data set for a single measurement.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SyntheticSpec {
+    /// The name of the measurement every line is written to.
+    pub measurement: String,
+    /// The number of distinct series. Each series writes one line per sampling
+    /// interval.
+    pub series: usize,
+    /// The tags of every series.
+    #[serde(default)]
+    pub tags: Vec<SyntheticTagSpec>,
+    /// The fields of every line.
+    pub fields: Vec<SyntheticFieldSpec>,
+    /// The interval between two lines of the same series, such as `10s`.
+    #[serde(default = "default_sampling_interval")]
+    pub sampling_interval: String,
+    /// The fraction of lines, between 0.0 and 1.0, whose timestamp is moved
+    /// back by up to `max_lateness`, so that they are older than lines
+    /// written before them.
+    #[serde(default)]
+    pub out_of_order_fraction: f64,
+    /// How far back the timestamp of an out-of-order line may be moved.
+    #[serde(default = "default_max_lateness")]
+    pub max_lateness: String,
+    /// The maximum number of lines to write per second. Unlimited if not
+    /// specified.
+    pub lines_per_second: Option<f64>,
+    /// The seed of the random number generator.
+    #[serde(default)]
+    pub seed: u64,
+}
+
+fn default_sampling_interval() -> String {
+    "10s".to_string()
+}
+
+fn default_max_lateness() -> String {
+    "1m".to_string()
+}
+
+/// The specification of a tag and the distribution of its values across the
+/// series.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SyntheticTagSpec {
+    /// The tag key.
+    pub key: String,
+    /// The number of distinct values the tag can take, named `<key>-0`,
+    /// `<key>-1`, ...
+    pub cardinality: usize,
+    /// How the values are distributed across the series.
+    #[serde(default)]
+    pub distribution: Distribution,
+}
+
+/// The distribution of a tag's values across series.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Distribution {
+    /// Every value is equally likely.
+    #[default]
+    Uniform,
+    /// The `k`th value is proportional to `1 / k^exponent` likely, so a few
+    /// values are shared by most series and most values by only a few.
+    Zipf {
+        /// The skew of the distribution; larger values are more skewed.
+        exponent: f64,
+    },
+}
+
+/// The specification of a field.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SyntheticFieldSpec {
+    /// The field key.
+    pub key: String,
+    /// The type of the field's values.
+    #[serde(rename = "type")]
+    pub field_type: SyntheticFieldType,
+}
+
+/// The type of a field's values, all of which are random.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyntheticFieldType {
+    /// A float between 0 and 100.
+    Float,
+    /// An integer between 0 and 1000.
+    Integer,
+    /// An unsigned integer between 0 and 1000.
+    Unsigned,
+    /// A boolean.
+    Bool,
+    /// An alphanumeric string of 8 characters.
+    String,
+}
+
+impl SyntheticSpec {
+    /// Given a filename, read the file and parse the specification.
+    pub fn from_file(file_name: &str) -> Result<Self> {
+        let spec_toml = fs::read_to_string(file_name).context(ReadFileSnafu { file_name })?;
+        Self::from_str(&spec_toml)
+    }
+
+    /// Generate the line protocol for every sampling interval from `start` up
+    /// to and including `end`, in nanoseconds since the epoch, ignoring
+    /// `lines_per_second`.
+    pub fn line_protocol(&self, start: i64, end: i64) -> Result<String> {
+        let mut generator = SyntheticGenerator::new(self, start)?;
+        let mut buf = vec![];
+        while generator.next_timestamp() <= end {
+            for point in generator.next_sample()? {
+                point
+                    .write_data_point_to(&mut buf)
+                    .expect("writing to a Vec cannot fail");
+            }
+        }
+        Ok(String::from_utf8(buf).expect("generated line protocol is valid UTF-8"))
+    }
+}
+
+impl FromStr for SyntheticSpec {
+    type Err = Error;
+
+    fn from_str(spec_toml: &str) -> std::result::Result<Self, <Self as FromStr>::Err> {
+        toml::from_str(spec_toml).context(ParseSnafu)
+    }
+}
+
+/// Generates the lines of a [`SyntheticSpec`], one sampling interval at a
+/// time.
+#[derive(Debug)]
+pub struct SyntheticGenerator {
+    measurement: String,
+    tag_keys: Vec<String>,
+    fields: Vec<SyntheticFieldSpec>,
+    /// The tag values of each series, as indexes into the values of each tag.
+    series: Vec<Vec<usize>>,
+    sampling_interval_ns: i64,
+    out_of_order_fraction: f64,
+    max_lateness_ns: i64,
+    next_timestamp: i64,
+    rng: StdRng,
+}
+
+impl SyntheticGenerator {
+    /// Validate `spec` and pick the tag values of its series. The first
+    /// sample is taken at `start`, in nanoseconds since the epoch.
+    pub fn new(spec: &SyntheticSpec, start: i64) -> Result<Self> {
+        let sampling_interval =
+            parse_duration(&spec.sampling_interval).context(InvalidDurationSnafu {
+                name: "sampling_interval",
+            })?;
+        ensure!(!sampling_interval.is_zero(), ZeroSamplingIntervalSnafu);
+        let max_lateness = parse_duration(&spec.max_lateness).context(InvalidDurationSnafu {
+            name: "max_lateness",
+        })?;
+        ensure!(
+            (0.0..=1.0).contains(&spec.out_of_order_fraction),
+            InvalidOutOfOrderFractionSnafu {
+                fraction: spec.out_of_order_fraction
+            }
+        );
+        ensure!(!spec.fields.is_empty(), NoFieldsSnafu);
+
+        let samplers = spec
+            .tags
+            .iter()
+            .map(ValueSampler::new)
+            .collect::<Result<Vec<_>>>()?;
+
+        let max_series = spec
+            .tags
+            .iter()
+            .try_fold(1_usize, |acc, tag| acc.checked_mul(tag.cardinality))
+            .unwrap_or(usize::MAX);
+        ensure!(
+            spec.series <= max_series,
+            TooManySeriesSnafu {
+                series: spec.series,
+                max_series,
+            }
+        );
+
+        let mut rng = StdRng::seed_from_u64(spec.seed);
+
+        // Draw the tag values of each series from the distributions, redrawing
+        // series that already exist. Series made of the most likely values are
+        // picked first, so skewed tags end up with few values shared by most
+        // series.
+        let mut seen = HashSet::with_capacity(spec.series);
+        let mut series = Vec::with_capacity(spec.series);
+        while series.len() < spec.series {
+            let values: Vec<_> = samplers.iter().map(|s| s.sample(&mut rng)).collect();
+            if seen.insert(values.clone()) {
+                series.push(values);
+            }
+        }
+
+        Ok(Self {
+            measurement: spec.measurement.clone(),
+            tag_keys: spec.tags.iter().map(|t| t.key.clone()).collect(),
+            fields: spec.fields.clone(),
+            series,
+            sampling_interval_ns: sampling_interval.as_nanos() as i64,
+            out_of_order_fraction: spec.out_of_order_fraction,
+            max_lateness_ns: max_lateness.as_nanos() as i64,
+            next_timestamp: start,
+            rng,
+        })
+    }
+
+    /// The timestamp of the sample returned by the next call to
+    /// [`next_sample`](Self::next_sample).
+    pub fn next_timestamp(&self) -> i64 {
+        self.next_timestamp
+    }
+
+    /// Generate one line for every series and advance to the next sampling
+    /// interval.
+    pub fn next_sample(&mut self) -> Result<Vec<DataPoint>> {
+        let timestamp = self.next_timestamp;
+        self.next_timestamp += self.sampling_interval_ns;
+
+        let mut points = Vec::with_capacity(self.series.len());
+        for values in &self.series {
+            let mut point = DataPoint::builder(&self.measurement);
+            for (key, value) in self.tag_keys.iter().zip(values) {
+                point = point.tag(key, format!("{key}-{value}"));
+            }
+            for field in &self.fields {
+                point = match field.field_type {
+                    SyntheticFieldType::Float => {
+                        point.field(&field.key, self.rng.gen_range(0.0..100.0))
+                    }
+                    SyntheticFieldType::Integer => {
+                        point.field(&field.key, self.rng.gen_range(0_i64..1000))
+                    }
+                    SyntheticFieldType::Unsigned => {
+                        point.field(&field.key, self.rng.gen_range(0_u64..1000))
+                    }
+                    SyntheticFieldType::Bool => point.field(&field.key, self.rng.gen_bool(0.5)),
+                    SyntheticFieldType::String => {
+                        let s: String = (&mut self.rng)
+                            .sample_iter(&Alphanumeric)
+                            .take(8)
+                            .map(char::from)
+                            .collect();
+                        point.field(&field.key, s)
+                    }
+                };
+            }
+
+            let mut timestamp = timestamp;
+            if self.max_lateness_ns > 0 && self.rng.gen_bool(self.out_of_order_fraction) {
+                timestamp -= self.rng.gen_range(1..=self.max_lateness_ns);
+            }
+
+            points.push(point.timestamp(timestamp).build().context(DataPointSnafu)?);
+        }
+
+        Ok(points)
+    }
+}
+
+/// Draws value indexes of a tag according to its [`Distribution`].
+#[derive(Debug)]
+struct ValueSampler {
+    /// The cumulative probability of each value, ending with 1.0.
+    cumulative: Vec<f64>,
+}
+
+impl ValueSampler {
+    fn new(tag: &SyntheticTagSpec) -> Result<Self> {
+        ensure!(tag.cardinality > 0, ZeroCardinalitySnafu { key: &tag.key });
+
+        let weights: Vec<f64> = match tag.distribution {
+            Distribution::Uniform => vec![1.0; tag.cardinality],
+            Distribution::Zipf { exponent } => {
+                ensure!(
+                    exponent > 0.0,
+                    InvalidExponentSnafu {
+                        key: &tag.key,
+                        exponent
+                    }
+                );
+                (1..=tag.cardinality)
+                    .map(|k| 1.0 / (k as f64).powf(exponent))
+                    .collect()
+            }
+        };
+
+        let total: f64 = weights.iter().sum();
+        let mut sum = 0.0;
+        let mut cumulative: Vec<f64> = weights
+            .iter()
+            .map(|w| {
+                sum += w;
+                sum / total
+            })
+            .collect();
+        // Guard against rounding leaving the last value out of reach.
+        *cumulative.last_mut().expect("cardinality is non-zero") = 1.0;
+
+        Ok(Self { cumulative })
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> usize {
+        let p: f64 = rng.gen();
+        self.cumulative.partition_point(|&c| c <= p)
+    }
+}
+
+/// Write the lines of `spec` for every sampling interval from `start` up to
+/// and including `end`, in nanoseconds since the epoch, to `points_writer`,
+/// `batch_size` sampling intervals per write. Returns the number of lines
+/// written.
+///
+/// If the spec sets `lines_per_second`, writes are delayed so that the rate
+/// is not exceeded.
+pub async fn generate(
+    spec: &SyntheticSpec,
+    points_writer: &PointsWriter,
+    start: i64,
+    end: i64,
+    batch_size: usize,
+) -> Result<usize> {
+    if let Some(rate) = spec.lines_per_second {
+        ensure!(rate > 0.0, InvalidWriteRateSnafu { rate });
+    }
+
+    let mut generator = SyntheticGenerator::new(spec, start)?;
+    let started = Instant::now();
+    let mut lines = 0;
+
+    while generator.next_timestamp() <= end {
+        let mut batch = vec![];
+        for _ in 0..batch_size.max(1) {
+            if generator.next_timestamp() > end {
+                break;
+            }
+            batch.extend(generator.next_sample()?);
+        }
+
+        let batch_lines = batch.len();
+        points_writer
+            .write_points(batch.into_iter())
+            .await
+            .context(WriteSnafu)?;
+        lines += batch_lines;
+
+        if let Some(rate) = spec.lines_per_second {
+            let due = started + Duration::from_secs_f64(lines as f64 / rate);
+            tokio::time::sleep_until(due.into()).await;
+        }
+    }
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn spec(toml: &str) -> SyntheticSpec {
+        SyntheticSpec::from_str(toml).unwrap()
+    }
+
+    fn lines(spec: &SyntheticSpec, start: i64, end: i64) -> Vec<String> {
+        spec.line_protocol(start, end)
+            .unwrap()
+            .lines()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn timestamp(line: &str) -> i64 {
+        line.rsplit_once(' ').unwrap().1.parse().unwrap()
+    }
+
+    const SPEC: &str = r#"
+measurement = "cpu"
+series = 100
+sampling_interval = "1s"
+
+[[tags]]
+key = "host"
+cardinality = 50
+distribution = { zipf = { exponent = 1.5 } }
+
+[[tags]]
+key = "region"
+cardinality = 4
+
+[[fields]]
+key = "usage"
+type = "float"
+
+[[fields]]
+key = "count"
+type = "integer"
+
+[[fields]]
+key = "bytes"
+type = "unsigned"
+
+[[fields]]
+key = "healthy"
+type = "bool"
+
+[[fields]]
+key = "status"
+type = "string"
+"#;
+
+    #[test]
+    fn generates_series_per_interval() {
+        let spec = spec(SPEC);
+        let lines = lines(&spec, 0, 2_000_000_000);
+
+        // 3 sampling intervals of 100 series
+        assert_eq!(lines.len(), 300);
+        let series: HashSet<_> = lines.iter().map(|l| l.split_once(' ').unwrap().0).collect();
+        assert_eq!(series.len(), 100);
+
+        let line = &lines[0];
+        assert!(line.starts_with("cpu,host=host-"), "{line}");
+        for field in ["usage=", "count=", "bytes=", "healthy=", "status=\""] {
+            assert!(line.contains(field), "{field} missing from {line}");
+        }
+        assert!(line.contains("i,") && line.contains("u,"), "{line}");
+    }
+
+    #[test]
+    fn generation_is_deterministic() {
+        let spec = spec(SPEC);
+        assert_eq!(lines(&spec, 0, 0), lines(&spec, 0, 0));
+
+        let other_seed = SyntheticSpec {
+            seed: 42,
+            ..spec.clone()
+        };
+        assert_ne!(lines(&spec, 0, 0), lines(&other_seed, 0, 0));
+    }
+
+    #[test]
+    fn zipf_skews_tag_values() {
+        let spec = spec(SPEC);
+        let mut hosts: HashMap<String, usize> = HashMap::new();
+        for line in lines(&spec, 0, 0) {
+            let host = line.split(',').nth(1).unwrap().to_string();
+            *hosts.entry(host).or_default() += 1;
+        }
+
+        // The most likely value is shared by many more series than it would
+        // be with a uniform distribution (2 series per host).
+        let most_common = hosts.values().max().unwrap();
+        assert!(*most_common >= 4, "{hosts:?}");
+    }
+
+    #[test]
+    fn out_of_order_lines() {
+        let in_order = spec(SPEC);
+        let out_of_order = SyntheticSpec {
+            out_of_order_fraction: 0.5,
+            max_lateness: "1h".to_string(),
+            ..in_order.clone()
+        };
+
+        let start = 10_000_000_000_000;
+        assert!(lines(&in_order, start, start)
+            .iter()
+            .all(|l| timestamp(l) == start));
+
+        let late: Vec<_> = lines(&out_of_order, start, start)
+            .iter()
+            .map(|l| timestamp(l))
+            .filter(|&t| t != start)
+            .collect();
+        assert!(!late.is_empty());
+        assert!(late
+            .iter()
+            .all(|&t| t < start && t >= start - 3_600_000_000_000));
+    }
+
+    #[test]
+    fn invalid_specs() {
+        let base = spec(SPEC);
+
+        let too_many = SyntheticSpec {
+            series: 201,
+            ..base.clone()
+        };
+        assert!(matches!(
+            SyntheticGenerator::new(&too_many, 0),
+            Err(Error::TooManySeries {
+                series: 201,
+                max_series: 200
+            })
+        ));
+
+        let bad_fraction = SyntheticSpec {
+            out_of_order_fraction: 1.5,
+            ..base.clone()
+        };
+        assert!(matches!(
+            SyntheticGenerator::new(&bad_fraction, 0),
+            Err(Error::InvalidOutOfOrderFraction { .. })
+        ));
+
+        let mut zero_cardinality = base.clone();
+        zero_cardinality.tags[1].cardinality = 0;
+        assert!(matches!(
+            SyntheticGenerator::new(&zero_cardinality, 0),
+            Err(Error::ZeroCardinality { .. })
+        ));
+
+        assert!(matches!(
+            SyntheticSpec::from_str("measurement = \"cpu\"\nseries = 1\nfields = []\nbogus = 1"),
+            Err(Error::Parse { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn write_rate_is_limited() {
+        let spec = SyntheticSpec {
+            series: 10,
+            lines_per_second: Some(100.0),
+            ..spec(SPEC)
+        };
+        let points_writer = crate::write::PointsWriterBuilder::new_no_op(true)
+            .build_for_agent("synthetic", "org", "bucket")
+            .unwrap();
+
+        let started = Instant::now();
+        // 3 intervals of 10 lines at 100 lines/sec
+        let lines = generate(&spec, &points_writer, 0, 2_000_000_000, 1)
+            .await
+            .unwrap();
+        assert_eq!(lines, 30);
+        assert!(started.elapsed() >= Duration::from_millis(300));
+    }
+}
@@ -1,6 +1,5 @@
 //! Writing generated points
 
-use crate::measurement::LineToGenerate;
 use bytes::Bytes;
 use datafusion_util::{unbounded_memory_pool, MemoryStream};
 use futures::stream;
@@ -278,7 +277,10 @@ impl PointsWriter {
     /// Write these points
     pub async fn write_points(
         &self,
-        points: impl Iterator<Item = LineToGenerate> + Send + Sync + 'static,
+        points: impl Iterator<Item = impl WriteDataPoint + Send + Sync + 'static>
+            + Send
+            + Sync
+            + 'static,
     ) -> Result<()> {
         self.inner_writer.write_points(points).await
     }
@@ -309,7 +311,10 @@ enum InnerPointsWriter {
 impl InnerPointsWriter {
     async fn write_points(
         &self,
-        points: impl Iterator<Item = LineToGenerate> + Send + Sync + 'static,
+        points: impl Iterator<Item = impl WriteDataPoint + Send + Sync + 'static>
+            + Send
+            + Sync
+            + 'static,
     ) -> Result<()> {
         match self {
             Self::Api {
@@ -0,0 +1,84 @@
+//! Query data held by another application with the IOx query engine.
+//!
+//! This example loads two overlapping chunks of CPU measurements into an
+//! in-memory [`MemNamespace`], and runs a SQL query over them. Rows with the
+//! same tags and timestamp are deduplicated, keeping the row of the chunk
+//! added last, exactly as the querier does for data from ingesters and
+//! parquet files.
+//!
+//! Run with `cargo run -p iox_query --example embedded`.
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, DictionaryArray, Float64Array, TimestampNanosecondArray},
+    datatypes::Int32Type,
+    record_batch::RecordBatch,
+    util::pretty::pretty_format_batches,
+};
+use iox_query::{exec::Executor, mem::MemNamespace, QueryNamespace};
+use schema::{builder::SchemaBuilder, InfluxFieldType, Schema};
+
+/// Build a batch of the `cpu` table.
+fn cpu(schema: &Schema, hosts: &[&str], usage: &[f64], time: &[i64]) -> RecordBatch {
+    RecordBatch::try_new(
+        schema.as_arrow(),
+        vec![
+            // Tag columns are dictionary encoded strings
+            Arc::new(
+                hosts
+                    .iter()
+                    .copied()
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ) as ArrayRef,
+            Arc::new(Float64Array::from(usage.to_vec())),
+            Arc::new(TimestampNanosecondArray::from(time.to_vec())),
+        ],
+    )
+    .expect("valid batch")
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The IOx schema describes which columns are tags, fields and the
+    // timestamp. Tags and the timestamp form the primary key of a row.
+    let schema = SchemaBuilder::new()
+        .tag("host")
+        .influx_field("usage", InfluxFieldType::Float)
+        .timestamp()
+        .build()?;
+
+    let executor = Arc::new(Executor::new_testing());
+    let namespace = MemNamespace::new(Arc::clone(&executor));
+
+    namespace.add_chunk(
+        "cpu",
+        "1970-01-01",
+        schema.clone(),
+        vec![cpu(
+            &schema,
+            &["server01", "server02", "server01"],
+            &[40.0, 60.0, 45.0],
+            &[1_000, 1_000, 2_000],
+        )],
+    )?;
+
+    // A later chunk correcting the usage of server01 at time 1000.
+    namespace.add_chunk(
+        "cpu",
+        "1970-01-01",
+        schema.clone(),
+        vec![cpu(&schema, &["server01"], &[42.0], &[1_000])],
+    )?;
+
+    let ctx = namespace.new_query_context(None);
+    let plan = ctx
+        .sql_to_physical_plan("SELECT host, usage, time FROM cpu ORDER BY host, time")
+        .await?;
+    let batches = ctx.collect(plan).await?;
+    println!("{}", pretty_format_batches(&batches)?);
+
+    executor.join().await;
+
+    Ok(())
+}
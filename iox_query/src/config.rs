@@ -1,6 +1,7 @@
 use std::{str::FromStr, time::Duration};
 
 use datafusion::{common::extensions_options, config::ConfigExtension};
+use schema::merge::TypePromotion;
 
 /// IOx-specific config extension prefix.
 pub const IOX_CONFIG_PREFIX: &str = "iox";
@@ -41,8 +42,72 @@ extensions_options! {
         /// [`target_partitions`]: datafusion::common::config::ExecutionOptions::target_partitions
         pub max_parquet_fanout: usize, default = 40
 
+        /// Number of parquet row groups fetched from the object store ahead of the row group that is currently
+        /// decoded, overlapping object store I/O with decoding. Setting this to `0` disables prefetching.
+        pub parquet_prefetch_row_groups: usize, default = 1
+
+        /// Target size in bytes of the record batches produced by IOx scan and de-duplication operators.
+        ///
+        /// The number of rows per batch is derived from the estimated row width of the scanned table, so that wide
+        /// tables do not create huge batches and narrow tables do not create many tiny ones. Setting this to `0`
+        /// (the default) uses DataFusion's fixed [`batch_size`] number of rows instead.
+        ///
+        ///
+        /// [`batch_size`]: datafusion::common::config::ExecutionOptions::batch_size
+        pub target_batch_bytes: usize, default = 0
+
         /// Cuttoff date for InfluxQL metadata queries.
         pub influxql_metadata_cutoff: MetadataCutoff, default = MetadataCutoff::Relative(Duration::from_secs(3600 * 24))
+
+        /// Point in time (RFC3339) at which the catalog is read.
+        ///
+        /// Queries only see the parquet files that existed at that time, ignoring files created, compacted or deleted
+        /// later, as well as data that is not persisted yet. Files removed by the garbage collector since then cannot
+        /// be read anymore. Defaults to `latest`, i.e. the current state.
+        pub as_of: AsOf, default = AsOf::Latest
+
+        /// Row-level tag filters that every scanned row must satisfy, given as `tag=value1,value2;tag2=value3`.
+        ///
+        /// Rows of tables without one of the tags are not returned. Used to enforce the tag filter policies of the
+        /// authorization token of a request. `\`, `;`, `=` and `,` in tag names or values are escaped with `\`.
+        pub tag_filters: TagFilters, default = TagFilters::default()
+
+        /// Fraction of the rows of every scanned table to randomly sample, for fast approximate queries.
+        ///
+        /// Rows are sampled after de-duplication, so aggregates, sorts and result transfers only process the sampled
+        /// rows. Results are approximate and vary between runs. `1.0` (the default) disables sampling.
+        pub sample_fraction: f64, default = 1.0
+
+        /// Read the last (and, if enabled, the first) value of every field of every series from the last value cache
+        /// of the ingesters, instead of the stored data.
+        ///
+        /// Serves `last()` / `first()` style queries without reading any parquet files. Only values written since the
+        /// ingesters started are returned, and queries fail if an ingester has no last value cache.
+        pub last_value_cache: bool, default = false
+
+        /// Provide the [tag index] of every chunk, loading the indexes of parquet files that are not cached yet.
+        ///
+        /// Set by the InfluxRPC `tag_keys` and `tag_values` planning, which answers these requests from the tag
+        /// indexes instead of scanning the data where possible. Other queries only use the chunk data, so they do not
+        /// pay for loading the indexes.
+        ///
+        ///
+        /// [tag index]: crate::tag_index::TagIndex
+        pub tag_index: bool, default = false
+
+        /// Precision (`s`, `ms`, `us` or `ns`) of the timestamp columns of query responses.
+        ///
+        /// Nanosecond timestamp columns of the results are converted to this precision when they are sent to the
+        /// client, truncating them towards negative infinity. Queries themselves always use nanoseconds.
+        pub timestamp_precision: TimestampPrecision, default = TimestampPrecision::Nanosecond
+
+        /// Field types that may be promoted when the chunks of a table have different types for the same field, as
+        /// `none` (the default) or a comma separated list of `int_to_float`, `uint_to_int` and `uint_to_float`.
+        ///
+        /// Chunk data of a narrower type than the table schema is only cast to the table type if this policy allows
+        /// it, otherwise the query fails. `uint_to_int` turns values larger than `i64::MAX` into NULLs and
+        /// `uint_to_float` may lose precision. See [`TypePromotion`].
+        pub type_promotion: TypePromotion, default = TypePromotion::default()
     }
 }
 
@@ -92,3 +157,260 @@ impl std::fmt::Display for MetadataCutoff {
         }
     }
 }
+
+/// Point in time at which the catalog is read, see [`IoxConfigExt::as_of`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsOf {
+    Latest,
+    Time(chrono::DateTime<chrono::Utc>),
+}
+
+impl FromStr for AsOf {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() || s.eq_ignore_ascii_case("latest") {
+            Ok(Self::Latest)
+        } else {
+            let dt = chrono::DateTime::<chrono::Utc>::from_str(s)
+                .map_err(|e| ParseError(e.to_string()))?;
+            Ok(Self::Time(dt))
+        }
+    }
+}
+
+impl std::fmt::Display for AsOf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Latest => write!(f, "latest"),
+            Self::Time(dt) => write!(f, "{}", dt.to_rfc3339()),
+        }
+    }
+}
+
+/// Precision of the timestamps of query responses, see [`IoxConfigExt::timestamp_precision`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampPrecision {
+    Second,
+    Millisecond,
+    Microsecond,
+    Nanosecond,
+}
+
+impl TimestampPrecision {
+    /// Number of nanoseconds per unit of this precision.
+    pub fn nanos_per_unit(&self) -> i64 {
+        match self {
+            Self::Second => 1_000_000_000,
+            Self::Millisecond => 1_000_000,
+            Self::Microsecond => 1_000,
+            Self::Nanosecond => 1,
+        }
+    }
+}
+
+impl FromStr for TimestampPrecision {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "s" => Ok(Self::Second),
+            "ms" => Ok(Self::Millisecond),
+            "us" => Ok(Self::Microsecond),
+            "" | "ns" => Ok(Self::Nanosecond),
+            _ => Err(ParseError(format!(
+                "invalid timestamp precision, expected 's', 'ms', 'us' or 'ns' got '{s}'"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for TimestampPrecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Second => "s",
+            Self::Millisecond => "ms",
+            Self::Microsecond => "us",
+            Self::Nanosecond => "ns",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Row-level tag filters, see [`IoxConfigExt::tag_filters`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilters(Vec<TagFilter>);
+
+/// Only rows where the tag `tag` has one of `values` pass the filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagFilter {
+    pub tag: String,
+    pub values: Vec<String>,
+}
+
+impl TagFilters {
+    pub fn new(filters: impl IntoIterator<Item = TagFilter>) -> Self {
+        Self(filters.into_iter().collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TagFilter> {
+        self.0.iter()
+    }
+}
+
+impl FromStr for TagFilters {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+
+        split_unescaped(s, ';')
+            .into_iter()
+            .map(|filter| {
+                let [tag, values] = split_unescaped(filter, '=')[..] else {
+                    return Err(ParseError(format!(
+                        "invalid tag filter, expected 'tag=value1,value2' got '{filter}'"
+                    )));
+                };
+                let tag = unescape(tag);
+                if tag.is_empty() {
+                    return Err(ParseError(format!(
+                        "invalid tag filter, empty tag name in '{filter}'"
+                    )));
+                }
+                let values = if values.is_empty() {
+                    vec![]
+                } else {
+                    split_unescaped(values, ',')
+                        .into_iter()
+                        .map(unescape)
+                        .collect()
+                };
+                Ok(TagFilter { tag, values })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl std::fmt::Display for TagFilters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, filter) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ";")?;
+            }
+            write!(f, "{}=", escape(&filter.tag))?;
+            for (j, value) in filter.values.iter().enumerate() {
+                if j > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", escape(value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split `s` at every `sep` that is not escaped. Escape sequences are kept.
+fn split_unescaped(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == sep {
+            parts.push(&s[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | ';' | '=' | ',') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut escaped = false;
+    for c in s.chars() {
+        if !escaped && c == '\\' {
+            escaped = true;
+            continue;
+        }
+        escaped = false;
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_filters_round_trip() {
+        let filters = TagFilters::new([
+            TagFilter {
+                tag: "tenant_id".into(),
+                values: vec!["acme".into(), "a,b;c=d\\e".into()],
+            },
+            TagFilter {
+                tag: "region".into(),
+                values: vec![],
+            },
+        ]);
+        let s = filters.to_string();
+        assert_eq!(s, r"tenant_id=acme,a\,b\;c\=d\\e;region=");
+        assert_eq!(TagFilters::from_str(&s).unwrap(), filters);
+
+        assert_eq!(TagFilters::from_str("").unwrap(), TagFilters::default());
+        assert_eq!(TagFilters::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_timestamp_precision_round_trip() {
+        for precision in [
+            TimestampPrecision::Second,
+            TimestampPrecision::Millisecond,
+            TimestampPrecision::Microsecond,
+            TimestampPrecision::Nanosecond,
+        ] {
+            assert_eq!(
+                TimestampPrecision::from_str(&precision.to_string()).unwrap(),
+                precision
+            );
+        }
+
+        assert_eq!(
+            TimestampPrecision::from_str("").unwrap(),
+            TimestampPrecision::Nanosecond
+        );
+        assert!(TimestampPrecision::from_str("h").is_err());
+    }
+
+    #[test]
+    fn test_tag_filters_invalid() {
+        assert!(TagFilters::from_str("tenant_id").is_err());
+        assert!(TagFilters::from_str("=acme").is_err());
+        assert!(TagFilters::from_str("a=b=c").is_err());
+    }
+}
@@ -7,6 +7,8 @@ pub mod fieldlist;
 pub mod gapfill;
 mod metrics;
 mod non_null_checker;
+pub mod plan_snapshot;
+pub mod query_profile;
 pub mod query_tracing;
 mod schema_pivot;
 pub mod seriesset;
@@ -32,7 +34,7 @@ use datafusion::{
     logical_expr::{Expr, LogicalPlan},
 };
 
-pub use context::{IOxSessionConfig, IOxSessionContext, SessionContextIOxExt};
+pub use context::{IOxSessionConfig, IOxSessionContext, PartialResults, SessionContextIOxExt};
 use schema_pivot::SchemaPivotNode;
 
 use crate::exec::metrics::DataFusionMemoryPoolMetricsBridge;
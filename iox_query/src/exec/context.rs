@@ -11,8 +11,10 @@ use super::{
 use crate::{
     config::IoxConfigExt,
     exec::{
-        fieldlist::{FieldList, IntoFieldList},
+        fieldlist::{FieldList, IntoFieldList, MeasurementFieldList},
         non_null_checker::NonNullCheckerExec,
+        plan_snapshot::ExecutedPlans,
+        query_profile::ProfileExec,
         query_tracing::TracedStream,
         schema_pivot::{SchemaPivotExec, SchemaPivotNode},
         seriesset::{
@@ -25,7 +27,7 @@ use crate::{
     logical_optimizer::register_iox_logical_optimizers,
     physical_optimizer::register_iox_physical_optimizers,
     plan::{
-        fieldlist::FieldListPlan,
+        fieldlist::{FieldListPlan, MeasurementFieldListPlan},
         seriesset::{SeriesSetPlan, SeriesSetPlans},
         stringset::StringSetPlan,
     },
@@ -51,8 +53,19 @@ use datafusion_util::config::{iox_session_config, DEFAULT_CATALOG};
 use executor::DedicatedExecutor;
 use futures::{Stream, StreamExt, TryStreamExt};
 use observability_deps::tracing::{debug, warn};
-use query_functions::{register_scalar_functions, selectors::register_selector_aggregates};
-use std::{fmt, num::NonZeroUsize, sync::Arc};
+use query_functions::{
+    rate::register_rate_aggregates, register_scalar_functions,
+    selectors::register_selector_aggregates, tdigest::register_tdigest_aggregates,
+    unsigned_sum::register_unsigned_sum,
+};
+use std::{
+    fmt,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 use trace::{
     ctx::SpanContext,
     span::{MetaValue, Span, SpanExt, SpanRecorder},
@@ -79,9 +92,18 @@ impl QueryPlanner for IOxQueryPlanner {
         let physical_planner =
             DefaultPhysicalPlanner::with_extension_planners(vec![Arc::new(IOxExtensionPlanner {})]);
         // Delegate most work of physical planning to the default physical planner
-        physical_planner
+        let physical_plan = physical_planner
             .create_physical_plan(logical_plan, session_state)
-            .await
+            .await?;
+
+        // `EXPLAIN ANALYZE VERBOSE` additionally reports the per-operator
+        // execution metrics of the analyzed plan.
+        match logical_plan {
+            LogicalPlan::Analyze(analyze) if analyze.verbose => {
+                Ok(Arc::new(ProfileExec::new(physical_plan)))
+            }
+            _ => Ok(physical_plan),
+        }
     }
 }
 
@@ -247,10 +269,11 @@ impl IOxSessionConfig {
         let maybe_span = self.span_ctx.child_span("Query Execution");
         let recorder = SpanRecorder::new(maybe_span);
 
-        // attach span to DataFusion session
+        // attach span and partial results flag to DataFusion session
         let session_config = self
             .session_config
-            .with_extension(Arc::new(recorder.span().cloned()));
+            .with_extension(Arc::new(recorder.span().cloned()))
+            .with_extension(Arc::new(PartialResults::default()));
 
         let state = SessionState::with_config_rt(session_config, self.runtime)
             .with_query_planner(Arc::new(IOxQueryPlanner {}));
@@ -259,12 +282,15 @@ impl IOxSessionConfig {
 
         let inner = SessionContext::with_state(state);
         register_selector_aggregates(&inner);
+        register_rate_aggregates(&inner);
+        register_tdigest_aggregates(&inner);
+        register_unsigned_sum(&inner);
         register_scalar_functions(&inner);
         if let Some(default_catalog) = self.default_catalog {
             inner.register_catalog(DEFAULT_CATALOG, default_catalog);
         }
 
-        IOxSessionContext::new(inner, self.exec, recorder)
+        IOxSessionContext::new(inner, self.exec, recorder, ExecutedPlans::default())
     }
 }
 
@@ -293,6 +319,9 @@ pub struct IOxSessionContext {
 
     /// Span context from which to create spans for this query
     recorder: SpanRecorder,
+
+    /// The physical plans executed by this context and all its children
+    executed_plans: ExecutedPlans,
 }
 
 impl fmt::Debug for IOxSessionContext {
@@ -315,6 +344,7 @@ impl IOxSessionContext {
             inner: SessionContext::default(),
             exec: DedicatedExecutor::new_testing(),
             recorder: SpanRecorder::default(),
+            executed_plans: ExecutedPlans::default(),
         }
     }
 
@@ -323,11 +353,13 @@ impl IOxSessionContext {
         inner: SessionContext,
         exec: DedicatedExecutor,
         recorder: SpanRecorder,
+        executed_plans: ExecutedPlans,
     ) -> Self {
         Self {
             inner,
             exec,
             recorder,
+            executed_plans,
         }
     }
 
@@ -336,6 +368,20 @@ impl IOxSessionContext {
         &self.inner
     }
 
+    /// Set DataFusion [config option] for all queries planned with this context (and its children) from now on.
+    ///
+    /// May be used to set [IOx-specific] option as well. In contrast to [`IOxSessionConfig::with_config_option`],
+    /// invalid options are reported as an error.
+    ///
+    /// [config option]: datafusion::common::config::ConfigOptions
+    /// [IOx-specific]: crate::config::IoxConfigExt
+    pub async fn set_config_option(&self, key: &str, value: &str) -> Result<()> {
+        // `SET` is the only way to change the options of an existing session
+        let sql = format!("SET {key} = '{}'", value.replace('\'', "''"));
+        self.inner.sql(&sql).await?;
+        Ok(())
+    }
+
     /// Plan a SQL statement. This assumes that any tables referenced
     /// in the SQL have been registered with this context. Use
     /// `create_physical_plan` to actually execute the query.
@@ -440,6 +486,7 @@ impl IOxSessionContext {
             .map(|span| span.child("execute_stream_partitioned"));
 
         let task_context = Arc::new(TaskContext::from(self.inner()));
+        self.executed_plans.push(&physical_plan);
 
         let stream = self
             .run(async move {
@@ -594,6 +641,31 @@ impl IOxSessionContext {
         })
     }
 
+    /// Executes `plan` and return the resulting FieldList of each
+    /// measurement on the query executor
+    pub async fn to_measurement_field_list(
+        &self,
+        plan: MeasurementFieldListPlan,
+    ) -> Result<MeasurementFieldList> {
+        // Run the plans of all measurements in parallel
+        let field_lists = futures::future::try_join_all(plan.measurements.into_iter().map(
+            |(measurement, plan)| async move {
+                let ctx = self.child_ctx("to_measurement_field_list");
+                let field_list = ctx.to_field_list(plan).await?;
+                Ok::<_, DataFusionError>((measurement, field_list))
+            },
+        ))
+        .await?;
+
+        let measurements = field_lists
+            .into_iter()
+            .filter(|(_, field_list)| !field_list.fields.is_empty())
+            .map(|(measurement, field_list)| (measurement.to_string(), field_list))
+            .collect();
+
+        Ok(MeasurementFieldList { measurements })
+    }
+
     /// Executes this plan on the query pool, and returns the
     /// resulting set of strings
     pub async fn to_string_set(&self, plan: StringSetPlan) -> Result<StringSetRef> {
@@ -666,9 +738,15 @@ impl IOxSessionContext {
             self.inner.clone(),
             self.exec.clone(),
             self.recorder.child(name),
+            self.executed_plans.clone(),
         )
     }
 
+    /// Returns the physical plans executed by this context so far
+    pub fn executed_plans(&self) -> &ExecutedPlans {
+        &self.executed_plans
+    }
+
     /// Record an event on the span recorder
     pub fn record_event(&mut self, name: &'static str) {
         self.recorder.event(name);
@@ -693,6 +771,31 @@ impl IOxSessionContext {
     pub fn tasks(&self) -> usize {
         self.exec.tasks()
     }
+
+    /// Returns the flag for partial results of the queries run with this context (and its children).
+    pub fn partial_results(&self) -> PartialResults {
+        self.inner.state().partial_results()
+    }
+}
+
+/// Flags the results of a query as partial, i.e. as missing some of the data.
+///
+/// Every [`IOxSessionContext`] attaches a flag to its DataFusion session, so that table providers can flag data
+/// they could not read (see [`SessionContextIOxExt::partial_results`]) and the query APIs can report it to the
+/// client. Clones share the same flag.
+#[derive(Debug, Default, Clone)]
+pub struct PartialResults(Arc<AtomicBool>);
+
+impl PartialResults {
+    /// Flag the results as partial.
+    pub fn set(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the results were flagged as partial.
+    pub fn get(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
 }
 
 /// Extension trait to pull IOx spans out of DataFusion contexts.
@@ -702,6 +805,11 @@ pub trait SessionContextIOxExt {
 
     /// Get span context
     fn span_ctx(&self) -> Option<SpanContext>;
+
+    /// Get the flag for partial results of the query.
+    ///
+    /// Returns a flag that is not reported anywhere if the session was not created by an [`IOxSessionContext`].
+    fn partial_results(&self) -> PartialResults;
 }
 
 impl SessionContextIOxExt for SessionState {
@@ -716,4 +824,11 @@ impl SessionContextIOxExt for SessionState {
             .get_extension::<Option<Span>>()
             .and_then(|span| span.as_ref().as_ref().map(|span| span.ctx.clone()))
     }
+
+    fn partial_results(&self) -> PartialResults {
+        self.config()
+            .get_extension::<PartialResults>()
+            .map(|partial| partial.as_ref().clone())
+            .unwrap_or_default()
+    }
 }
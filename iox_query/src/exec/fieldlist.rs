@@ -1,6 +1,6 @@
 //! This module contains the definition of a "FieldList" a set of
-//! records of (field_name, field_type, last_timestamp) and code to
-//! pull them from RecordBatches
+//! records of (field_name, field_type, last_timestamp, row_count,
+//! null_count) and code to pull them from RecordBatches
 use std::{collections::BTreeMap, sync::Arc};
 
 use arrow::{
@@ -41,12 +41,31 @@ pub enum Error {
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Represents a single Field (column)'s metadata: Name, data_type,
-/// and most recent (last) timestamp.
+/// most recent (last) timestamp, and how sparse it is.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Field {
     pub name: String,
     pub data_type: DataType,
     pub last_timestamp: i64,
+    /// The number of rows of the table(s) containing this field, or `None`
+    /// if unknown.
+    ///
+    /// When taken from chunk statistics, rows are counted before
+    /// deduplication.
+    pub row_count: Option<u64>,
+    /// The number of those rows in which this field is null, or `None` if
+    /// unknown.
+    pub null_count: Option<u64>,
+}
+
+impl Field {
+    /// Merge the metadata of the same field seen in another table (or set
+    /// of rows) into this one.
+    pub fn merge(&mut self, other: &Self) {
+        self.last_timestamp = std::cmp::max(self.last_timestamp, other.last_timestamp);
+        self.row_count = self.row_count.zip(other.row_count).map(|(a, b)| a + b);
+        self.null_count = self.null_count.zip(other.null_count).map(|(a, b)| a + b);
+    }
 }
 
 /// A list of `Fields`
@@ -55,6 +74,13 @@ pub struct FieldList {
     pub fields: Vec<Field>,
 }
 
+/// The `FieldList`s of several measurements
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MeasurementFieldList {
+    /// The fields of each measurement with any, keyed by measurement name
+    pub measurements: BTreeMap<String, FieldList>,
+}
+
 /// Trait to convert RecordBatch'y things into `FieldLists`. Assumes
 /// that the input RecordBatch es can each have a single string
 /// column.
@@ -83,8 +109,13 @@ impl IntoFieldList for Vec<RecordBatch> {
 
         // key: fieldname, value: highest value of time column we have seen
         let mut field_times = BTreeMap::new();
+        // key: fieldname, value: number of null values we have seen
+        let mut field_nulls = BTreeMap::<&str, u64>::new();
+        let mut row_count = 0;
+
+        for batch in &self {
+            row_count += batch.num_rows() as u64;
 
-        for batch in self {
             let time_column = batch
                 .column(time_column_index)
                 .as_any()
@@ -96,6 +127,7 @@ impl IntoFieldList for Vec<RecordBatch> {
                     continue;
                 }
                 let array = batch.column(column_index);
+                *field_nulls.entry(arrow_field.name()).or_default() += array.null_count() as u64;
 
                 // walk each value in array, looking for non-null values
                 let mut max_ts: Option<i64> = None;
@@ -128,6 +160,8 @@ impl IntoFieldList for Vec<RecordBatch> {
                         name: field_name.to_string(),
                         data_type: arrow_field.data_type().clone(),
                         last_timestamp: *ts,
+                        row_count: Some(row_count),
+                        null_count: field_nulls.get(field_name.as_str()).copied(),
                     })
                 }
             })
@@ -164,8 +198,7 @@ impl IntoFieldList for Vec<FieldList> {
                         data_type2: new_field.data_type,
                     }
                 );
-                existing_field.last_timestamp =
-                    std::cmp::max(existing_field.last_timestamp, new_field.last_timestamp);
+                existing_field.merge(&new_field);
             }
             // no entry for field yet
             else {
@@ -215,6 +248,8 @@ mod tests {
                 name: "string_field".into(),
                 data_type: ArrowDataType::Utf8,
                 last_timestamp: 4000,
+                row_count: Some(4),
+                null_count: Some(0),
             }],
         };
 
@@ -268,6 +303,8 @@ mod tests {
                 name: "string_field".into(),
                 data_type: ArrowDataType::Utf8,
                 last_timestamp: 4000,
+                row_count: Some(4),
+                null_count: Some(0),
             }],
         };
 
@@ -324,11 +361,15 @@ mod tests {
                     name: "string_field".into(),
                     data_type: ArrowDataType::Utf8,
                     last_timestamp: 4000,
+                    row_count: Some(4),
+                    null_count: Some(0),
                 },
                 Field {
                     name: "int_field".into(),
                     data_type: ArrowDataType::Int64,
                     last_timestamp: 3000,
+                    row_count: Some(4),
+                    null_count: Some(1),
                 },
             ],
         };
@@ -359,11 +400,15 @@ mod tests {
             name: "one".into(),
             data_type: ArrowDataType::Utf8,
             last_timestamp: 4000,
+            row_count: Some(10),
+            null_count: Some(2),
         };
         let field2 = Field {
             name: "two".into(),
             data_type: ArrowDataType::Int64,
             last_timestamp: 3000,
+            row_count: Some(10),
+            null_count: Some(0),
         };
 
         let l1 = FieldList {
@@ -381,16 +426,55 @@ mod tests {
             name: "one".into(),
             data_type: ArrowDataType::Utf8,
             last_timestamp: 5000,
+            row_count: Some(5),
+            null_count: Some(0),
         };
 
         // use something that has a later timestamp and expect the later one takes
-        // precedence
+        // precedence, and the counts to be added up
         let l2 = FieldList {
-            fields: vec![field1_later.clone()],
+            fields: vec![field1_later],
         };
         let actual = vec![l1.clone(), l2.clone()].into_fieldlist().unwrap();
         let expected = FieldList {
-            fields: vec![field1_later, field2],
+            fields: vec![
+                Field {
+                    name: "one".into(),
+                    data_type: ArrowDataType::Utf8,
+                    last_timestamp: 5000,
+                    row_count: Some(15),
+                    null_count: Some(2),
+                },
+                field2.clone(),
+            ],
+        };
+
+        assert_eq!(
+            expected, actual,
+            "Expected:\n{expected:#?}\nActual:\n{actual:#?}"
+        );
+
+        // unknown counts stay unknown when merged
+        let field2_unknown = Field {
+            name: "two".into(),
+            data_type: ArrowDataType::Int64,
+            last_timestamp: 1000,
+            row_count: Some(3),
+            null_count: None,
+        };
+        let l_unknown = FieldList {
+            fields: vec![field2_unknown],
+        };
+        let actual = vec![l1.clone(), l_unknown].into_fieldlist().unwrap();
+        let expected = FieldList {
+            fields: vec![
+                l1.fields[0].clone(),
+                Field {
+                    row_count: Some(13),
+                    null_count: None,
+                    ..field2
+                },
+            ],
         };
 
         assert_eq!(
@@ -404,6 +488,8 @@ mod tests {
             name: "one".into(),
             data_type: ArrowDataType::Int64,
             last_timestamp: 5000,
+            row_count: Some(1),
+            null_count: Some(0),
         };
 
         // use something that has a later timestamp and expect the later one takes
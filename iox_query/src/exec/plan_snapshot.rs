@@ -0,0 +1,276 @@
+//! Compact snapshots of the physical plans executed for a query, used to
+//! describe slow queries after the fact.
+
+use std::{fmt::Write, sync::Arc};
+
+use datafusion::{
+    datasource::physical_plan::ParquetExec,
+    physical_plan::{
+        filter::FilterExec, metrics::MetricValue, visit_execution_plan, ExecutionPlan,
+        ExecutionPlanVisitor,
+    },
+};
+use parking_lot::Mutex;
+
+use crate::{
+    exec::{
+        query_profile::{chunks_scanned, QueryProfile},
+        query_tracing::one_line,
+    },
+    provider::RecordBatchesExec,
+};
+
+/// The maximum length of a single plan node description in a
+/// [`PlanSnapshot`], longer descriptions are truncated.
+const MAX_NODE_LEN: usize = 200;
+
+/// The maximum length of the rendered plans in a [`PlanSnapshot`].
+const MAX_PLAN_LEN: usize = 8 * 1024;
+
+/// The physical plans executed through an [`IOxSessionContext`] (and all
+/// contexts derived from it).
+///
+/// Cheap to clone, all clones share the same set of plans.
+///
+/// [`IOxSessionContext`]: crate::exec::IOxSessionContext
+#[derive(Debug, Clone, Default)]
+pub struct ExecutedPlans(Arc<Mutex<Vec<Arc<dyn ExecutionPlan>>>>);
+
+impl ExecutedPlans {
+    /// Record `plan` as executed.
+    ///
+    /// Executing multiple partitions of the same plan records it once.
+    pub(crate) fn push(&self, plan: &Arc<dyn ExecutionPlan>) {
+        let mut plans = self.0.lock();
+        if !plans.iter().any(|p| Arc::ptr_eq(p, plan)) {
+            plans.push(Arc::clone(plan));
+        }
+    }
+
+    /// Move the plans executed so far into a new, unshared [`ExecutedPlans`].
+    ///
+    /// This allows the resources (such as buffered chunk data) referenced by
+    /// the plans to be released independently of the other clones.
+    pub fn take(&self) -> Self {
+        Self(Arc::new(Mutex::new(std::mem::take(&mut *self.0.lock()))))
+    }
+
+    /// Summarise the plans executed so far, including the execution metrics
+    /// they recorded.
+    pub fn snapshot(&self) -> PlanSnapshot {
+        let plans = self.0.lock().clone();
+
+        let mut visitor = SnapshotVisitor::default();
+        for plan in &plans {
+            // The visitor is infallible.
+            let _ = visit_execution_plan(plan.as_ref(), &mut visitor);
+        }
+        visitor.snapshot.predicate = visitor.predicates.join(" AND ");
+
+        visitor.snapshot
+    }
+
+    /// Return the per-operator execution metrics of the plans executed so
+    /// far.
+    pub fn profile(&self) -> QueryProfile {
+        let plans = self.0.lock().clone();
+
+        let mut profile = QueryProfile::default();
+        for plan in &plans {
+            profile.add_plan(plan.as_ref());
+        }
+        profile
+    }
+}
+
+/// A summary of the physical plans executed for a query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanSnapshot {
+    /// The distinct filter predicates applied by the plans, combined with
+    /// `AND`.
+    pub predicate: String,
+
+    /// The number of chunks (Parquet files and ingester / in-memory data)
+    /// scanned.
+    pub chunks_scanned: usize,
+
+    /// The number of Parquet row groups skipped using their statistics.
+    pub row_groups_pruned: usize,
+
+    /// The number of rows skipped by predicates evaluated during the scan
+    /// (using the Parquet page index or filter pushdown, or the predicate of
+    /// in-memory chunks).
+    pub rows_pruned: usize,
+
+    /// A compact, indented representation of the plans, one node per line.
+    pub plan: String,
+}
+
+#[derive(Debug, Default)]
+struct SnapshotVisitor {
+    snapshot: PlanSnapshot,
+    predicates: Vec<String>,
+    depth: usize,
+}
+
+impl SnapshotVisitor {
+    fn add_predicate(&mut self, predicate: String) {
+        if !self.predicates.contains(&predicate) {
+            self.predicates.push(predicate);
+        }
+    }
+
+    fn add_node(&mut self, plan: &dyn ExecutionPlan) {
+        let plan_text = &mut self.snapshot.plan;
+        if plan_text.len() >= MAX_PLAN_LEN {
+            return;
+        }
+
+        let mut desc = one_line(plan).to_string();
+        truncate(&mut desc, MAX_NODE_LEN);
+
+        writeln!(plan_text, "{:indent$}{desc}", "", indent = self.depth * 2)
+            .expect("writing to a string");
+        if plan_text.len() >= MAX_PLAN_LEN {
+            truncate(plan_text, MAX_PLAN_LEN);
+            plan_text.push_str("\n...");
+        }
+    }
+}
+
+impl ExecutionPlanVisitor for SnapshotVisitor {
+    type Error = std::convert::Infallible;
+
+    fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
+        self.add_node(plan);
+        self.depth += 1;
+
+        self.snapshot.chunks_scanned += chunks_scanned(plan).unwrap_or_default();
+
+        let plan_any = plan.as_any();
+        if let Some(filter_exec) = plan_any.downcast_ref::<FilterExec>() {
+            self.add_predicate(filter_exec.predicate().to_string());
+        } else if let Some(record_batches_exec) = plan_any.downcast_ref::<RecordBatchesExec>() {
+            if let Some(predicate) = record_batches_exec.predicate() {
+                self.add_predicate(predicate.to_string());
+            }
+
+            if let Some(rows_filtered) = plan
+                .metrics()
+                .and_then(|metrics| metrics.sum_by_name("pushdown_rows_filtered"))
+            {
+                self.snapshot.rows_pruned += rows_filtered.as_usize();
+            }
+        } else if let Some(parquet_exec) = plan_any.downcast_ref::<ParquetExec>() {
+            if let Some(predicate) = parquet_exec.predicate() {
+                self.add_predicate(predicate.to_string());
+            }
+
+            if let Some(metrics) = plan.metrics() {
+                for metric in metrics.iter() {
+                    let MetricValue::Count { name, count } = metric.value() else {
+                        continue;
+                    };
+                    match name.as_ref() {
+                        "row_groups_pruned" => self.snapshot.row_groups_pruned += count.value(),
+                        "page_index_rows_filtered" | "pushdown_rows_filtered" => {
+                            self.snapshot.rows_pruned += count.value()
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn post_visit(&mut self, _plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
+        self.depth -= 1;
+        Ok(true)
+    }
+}
+
+/// Truncate `s` to at most `max_len` bytes, on a character boundary.
+fn truncate(s: &mut String, max_len: usize) {
+    if s.len() <= max_len {
+        return;
+    }
+    let mut idx = max_len;
+    while !s.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    s.truncate(idx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        provider::chunks_to_physical_nodes, test::TestChunk, util::df_physical_expr, QueryChunk,
+    };
+    use datafusion::prelude::{col, lit};
+
+    #[test]
+    fn test_snapshot() {
+        let chunk1 = TestChunk::new("t")
+            .with_id(1)
+            .with_tag_column("tag1")
+            .with_time_column()
+            .with_dummy_parquet_file();
+        let chunk2 = TestChunk::new("t")
+            .with_id(2)
+            .with_tag_column("tag1")
+            .with_time_column();
+        let schema = chunk1.schema().as_arrow();
+
+        let plan = chunks_to_physical_nodes(
+            &schema,
+            None,
+            vec![Arc::new(chunk1) as _, Arc::new(chunk2) as _],
+            2,
+        );
+        let plan: Arc<dyn ExecutionPlan> = Arc::new(
+            FilterExec::try_new(
+                df_physical_expr(plan.as_ref(), col("tag1").eq(lit("foo"))).unwrap(),
+                plan,
+            )
+            .unwrap(),
+        );
+
+        let plans = ExecutedPlans::default();
+        assert_eq!(plans.snapshot(), PlanSnapshot::default());
+
+        // A plan pushed for each of its partitions is only recorded once
+        plans.push(&plan);
+        plans.clone().push(&plan);
+
+        let snapshot = plans.snapshot();
+        assert_eq!(snapshot.predicate, "tag1@0 = foo");
+        assert_eq!(snapshot.chunks_scanned, 2);
+        assert_eq!(snapshot.row_groups_pruned, 0);
+        assert_eq!(snapshot.rows_pruned, 0);
+
+        let lines = snapshot.plan.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4, "{}", snapshot.plan);
+        assert!(lines[0].starts_with("FilterExec: "));
+        assert!(lines[1].starts_with("  UnionExec"));
+        assert!(lines[2].starts_with("    RecordBatchesExec: chunks=1"));
+        assert!(lines[3].starts_with("    ParquetExec: "));
+
+        let taken = plans.take();
+        assert_eq!(plans.snapshot(), PlanSnapshot::default());
+        assert_eq!(taken.snapshot(), snapshot);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut s = "aé".to_string();
+        truncate(&mut s, 2);
+        assert_eq!(s, "a");
+
+        let mut s = "abc".to_string();
+        truncate(&mut s, 5);
+        assert_eq!(s, "abc");
+    }
+}
@@ -0,0 +1,379 @@
+//! Per-operator execution metrics of physical plans.
+//!
+//! A [`QueryProfile`] summarises the metrics DataFusion recorded for each
+//! operator of an executed plan (output rows, compute time and spilled bytes),
+//! with the scans of all chunks of a scan node aggregated into a single entry.
+//! It is reported by `EXPLAIN ANALYZE VERBOSE` (see [`ProfileExec`]) and logged
+//! for queries made with the `iox-debug` header.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use arrow::{array::StringArray, datatypes::SchemaRef, record_batch::RecordBatch};
+use datafusion::{
+    datasource::physical_plan::ParquetExec,
+    error::{DataFusionError, Result},
+    execution::context::TaskContext,
+    physical_expr::PhysicalSortExpr,
+    physical_plan::{
+        stream::RecordBatchStreamAdapter, visit_execution_plan, DisplayAs, DisplayFormatType,
+        ExecutionPlan, ExecutionPlanVisitor, Partitioning, SendableRecordBatchStream, Statistics,
+    },
+};
+use futures::StreamExt;
+
+use crate::{exec::query_tracing::one_line, provider::RecordBatchesExec};
+
+/// The `plan_type` of the row [`ProfileExec`] appends to the output of
+/// `EXPLAIN ANALYZE VERBOSE`.
+pub const PROFILE_PLAN_TYPE: &str = "Query Profile";
+
+/// The execution metrics of a single operator in a [`QueryProfile`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperatorProfile {
+    /// The depth of the operator in the plan, the root being at depth 0.
+    pub depth: usize,
+
+    /// The name of the operator, such as `FilterExec`.
+    pub name: String,
+
+    /// The number of rows produced, summed over all partitions.
+    pub output_rows: Option<usize>,
+
+    /// The CPU time spent by the operator, summed over all partitions.
+    pub elapsed_compute: Option<Duration>,
+
+    /// The number of bytes spilled to disk.
+    pub spilled_bytes: Option<usize>,
+
+    /// For scan nodes, the number of chunks scanned.
+    pub chunks: Option<usize>,
+
+    /// For Parquet scans, the number of bytes read from the files.
+    pub bytes_scanned: Option<usize>,
+}
+
+impl fmt::Display for OperatorProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.name)?;
+        if let Some(chunks) = self.chunks {
+            write!(f, " chunks={chunks}")?;
+        }
+        if let Some(output_rows) = self.output_rows {
+            write!(f, " output_rows={output_rows}")?;
+        }
+        if let Some(elapsed_compute) = self.elapsed_compute {
+            write!(f, " elapsed_compute={elapsed_compute:?}")?;
+        }
+        if let Some(spilled_bytes) = self.spilled_bytes {
+            write!(f, " spilled_bytes={spilled_bytes}")?;
+        }
+        if let Some(bytes_scanned) = self.bytes_scanned {
+            write!(f, " bytes_scanned={bytes_scanned}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The per-operator execution metrics of one or more physical plans, in
+/// pre-order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryProfile {
+    pub operators: Vec<OperatorProfile>,
+}
+
+impl QueryProfile {
+    /// Create the profile of `plan`.
+    ///
+    /// Metrics are only available once the plan has been executed.
+    pub fn new(plan: &dyn ExecutionPlan) -> Self {
+        let mut profile = Self::default();
+        profile.add_plan(plan);
+        profile
+    }
+
+    /// Append the profile of `plan`.
+    pub fn add_plan(&mut self, plan: &dyn ExecutionPlan) {
+        let mut visitor = ProfileVisitor {
+            profile: self,
+            depth: 0,
+        };
+        // The visitor is infallible.
+        let _ = visit_execution_plan(plan, &mut visitor);
+    }
+}
+
+impl fmt::Display for QueryProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, op) in self.operators.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:indent$}{op}", "", indent = op.depth * 2)?;
+        }
+        Ok(())
+    }
+}
+
+struct ProfileVisitor<'a> {
+    profile: &'a mut QueryProfile,
+    depth: usize,
+}
+
+impl ExecutionPlanVisitor for ProfileVisitor<'_> {
+    type Error = std::convert::Infallible;
+
+    fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
+        let desc = one_line(plan).to_string();
+        let name = desc
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        let mut op = OperatorProfile {
+            depth: self.depth,
+            name,
+            chunks: chunks_scanned(plan),
+            ..Default::default()
+        };
+
+        if let Some(metrics) = plan.metrics() {
+            op.output_rows = metrics.output_rows();
+            op.elapsed_compute = metrics
+                .elapsed_compute()
+                .map(|nanos| Duration::from_nanos(nanos as u64));
+            op.spilled_bytes = metrics.spilled_bytes();
+            op.bytes_scanned = metrics.sum_by_name("bytes_scanned").map(|v| v.as_usize());
+        }
+
+        self.profile.operators.push(op);
+        self.depth += 1;
+        Ok(true)
+    }
+
+    fn post_visit(&mut self, _plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
+        self.depth -= 1;
+        Ok(true)
+    }
+}
+
+/// The number of chunks (Parquet files and ingester / in-memory data) scanned
+/// by `plan`, or [`None`] if it is not a scan node.
+pub(crate) fn chunks_scanned(plan: &dyn ExecutionPlan) -> Option<usize> {
+    let plan_any = plan.as_any();
+    if let Some(record_batches_exec) = plan_any.downcast_ref::<RecordBatchesExec>() {
+        Some(record_batches_exec.chunks().count())
+    } else {
+        plan_any.downcast_ref::<ParquetExec>().map(|parquet_exec| {
+            parquet_exec
+                .base_config()
+                .file_groups
+                .iter()
+                .map(Vec::len)
+                .sum::<usize>()
+        })
+    }
+}
+
+/// Wraps the `AnalyzeExec` of an `EXPLAIN ANALYZE VERBOSE` plan, appending a
+/// row with the [`QueryProfile`] of the analyzed plan to its output.
+#[derive(Debug)]
+pub(crate) struct ProfileExec {
+    input: Arc<dyn ExecutionPlan>,
+}
+
+impl ProfileExec {
+    pub(crate) fn new(input: Arc<dyn ExecutionPlan>) -> Self {
+        Self { input }
+    }
+}
+
+impl DisplayAs for ProfileExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => write!(f, "ProfileExec"),
+        }
+    }
+}
+
+impl ExecutionPlan for ProfileExec {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(Self::new(Arc::clone(&children[0])))),
+            _ => Err(DataFusionError::Internal(
+                "ProfileExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema();
+        let stream = self.input.execute(partition, context)?;
+
+        // The input of the `AnalyzeExec` is the plan being analyzed. Its
+        // metrics are complete once the `AnalyzeExec` stream is exhausted.
+        let analyzed = self.input.children();
+        let profile_schema = Arc::clone(&schema);
+        let profile = futures::stream::once(async move {
+            let mut profile = QueryProfile::default();
+            for plan in &analyzed {
+                profile.add_plan(plan.as_ref());
+            }
+
+            RecordBatch::try_new(
+                profile_schema,
+                vec![
+                    Arc::new(StringArray::from(vec![PROFILE_PLAN_TYPE])),
+                    Arc::new(StringArray::from(vec![profile.to_string()])),
+                ],
+            )
+            .map_err(DataFusionError::from)
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            stream.chain(profile),
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        exec::{Executor, ExecutorType},
+        provider::chunks_to_physical_nodes,
+        test::TestChunk,
+        util::df_physical_expr,
+        QueryChunk,
+    };
+    use datafusion::physical_plan::filter::FilterExec;
+    use datafusion::prelude::{col, lit};
+
+    #[test]
+    fn test_profile() {
+        let chunk1 = TestChunk::new("t")
+            .with_id(1)
+            .with_tag_column("tag1")
+            .with_time_column()
+            .with_dummy_parquet_file();
+        let chunk2 = TestChunk::new("t")
+            .with_id(2)
+            .with_tag_column("tag1")
+            .with_time_column();
+        let schema = chunk1.schema().as_arrow();
+
+        let plan = chunks_to_physical_nodes(
+            &schema,
+            None,
+            vec![Arc::new(chunk1) as _, Arc::new(chunk2) as _],
+            2,
+        );
+        let plan: Arc<dyn ExecutionPlan> = Arc::new(
+            FilterExec::try_new(
+                df_physical_expr(plan.as_ref(), col("tag1").eq(lit("foo"))).unwrap(),
+                plan,
+            )
+            .unwrap(),
+        );
+
+        let profile = QueryProfile::new(plan.as_ref());
+        let names = profile
+            .operators
+            .iter()
+            .map(|op| (op.depth, op.name.as_str(), op.chunks))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                (0, "FilterExec", None),
+                (1, "UnionExec", None),
+                (2, "RecordBatchesExec", Some(1)),
+                (2, "ParquetExec", Some(1)),
+            ]
+        );
+
+        let lines = profile.to_string();
+        let lines = lines.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("FilterExec:"), "{}", lines[0]);
+        assert!(lines[2].starts_with("    RecordBatchesExec: chunks=1"));
+    }
+
+    #[test]
+    fn test_display_operator() {
+        let op = OperatorProfile {
+            depth: 3,
+            name: "SortExec".to_string(),
+            output_rows: Some(10),
+            elapsed_compute: Some(Duration::from_millis(2)),
+            spilled_bytes: Some(0),
+            chunks: None,
+            bytes_scanned: None,
+        };
+        assert_eq!(
+            op.to_string(),
+            "SortExec: output_rows=10 elapsed_compute=2ms spilled_bytes=0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explain_analyze_verbose() {
+        let exec = Executor::new_testing();
+        let ctx = exec.new_context(ExecutorType::Query);
+
+        let plan = ctx
+            .sql_to_physical_plan("EXPLAIN ANALYZE VERBOSE SELECT 1")
+            .await
+            .unwrap();
+        assert!(plan.as_any().downcast_ref::<ProfileExec>().is_some());
+
+        let batches = ctx.collect(plan).await.unwrap();
+        let last = batches.last().unwrap();
+        let plan_type = last
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(plan_type.value(last.num_rows() - 1), PROFILE_PLAN_TYPE);
+
+        // Only the verbose mode reports the profile
+        let plan = ctx
+            .sql_to_physical_plan("EXPLAIN ANALYZE SELECT 1")
+            .await
+            .unwrap();
+        assert!(plan.as_any().downcast_ref::<ProfileExec>().is_none());
+    }
+}
@@ -35,20 +35,25 @@ use once_cell::sync::Lazy;
 use parquet_file::storage::ParquetExecInput;
 use schema::{sort::SortKey, Projection, Schema};
 use std::{any::Any, fmt::Debug, sync::Arc};
+use tag_index::TagIndex;
 
 pub mod chunk_statistics;
 pub mod config;
 pub mod exec;
 pub mod frontend;
 pub mod logical_optimizer;
+pub mod mem;
 pub mod physical_optimizer;
 pub mod plan;
 pub mod provider;
 pub mod pruning;
 pub mod statistics;
+pub mod tag_index;
 pub mod util;
 
-pub use query_functions::group_by::{Aggregate, WindowDuration};
+pub use query_functions::group_by::{
+    Aggregate, AggregateExpr, ArithmeticOperator, NamedAggregateExpr, WindowDuration, WindowFill,
+};
 
 /// The name of the virtual column that represents the chunk order.
 pub const CHUNK_ORDER_COLUMN_NAME: &str = "__chunk_order";
@@ -94,6 +99,11 @@ pub trait QueryChunk: Debug + Send + Sync + 'static {
     /// Order of this chunk relative to other overlapping chunks.
     fn order(&self) -> ChunkOrder;
 
+    /// Index of the distinct tag values of this chunk, if known without reading the data.
+    fn tag_index(&self) -> Option<Arc<TagIndex>> {
+        None
+    }
+
     /// Return backend as [`Any`] which can be used to downcast to a specific implementation.
     fn as_any(&self) -> &dyn Any;
 }
@@ -180,9 +190,21 @@ pub trait QueryNamespace: Debug + Send + Sync {
     /// Returns `None` if now retention policy was defined.
     fn retention_time_ns(&self) -> Option<i64>;
 
-    /// Record that particular type of query was run / planned
+    /// Retention cutoff time of the table `table_name`, which may expire its
+    /// data faster than the namespace.
+    ///
+    /// Defaults to the [namespace retention cutoff](Self::retention_time_ns).
+    fn table_retention_time_ns(&self, table_name: &str) -> Option<i64> {
+        let _ = table_name;
+        self.retention_time_ns()
+    }
+
+    /// Record that particular type of query was run / planned.
+    ///
+    /// `ctx` is the context the query is planned and executed with.
     fn record_query(
         &self,
+        ctx: &IOxSessionContext,
         span_ctx: Option<&SpanContext>,
         query_type: &'static str,
         query_text: QueryText,
@@ -276,6 +298,10 @@ where
         self.as_ref().order()
     }
 
+    fn tag_index(&self) -> Option<Arc<TagIndex>> {
+        self.as_ref().tag_index()
+    }
+
     fn as_any(&self) -> &dyn Any {
         // present the underlying implementation, not the wrapper
         self.as_ref().as_any()
@@ -319,6 +345,10 @@ impl QueryChunk for Arc<dyn QueryChunk> {
         self.as_ref().order()
     }
 
+    fn tag_index(&self) -> Option<Arc<TagIndex>> {
+        self.as_ref().tag_index()
+    }
+
     fn as_any(&self) -> &dyn Any {
         // present the underlying implementation, not the wrapper
         self.as_ref().as_any()
@@ -0,0 +1,529 @@
+//! A minimal in-memory implementation of [`QueryNamespace`] and [`QueryChunk`].
+//!
+//! This serves as the reference for applications embedding the IOx query
+//! engine over their own storage: a [`MemNamespace`] holds tables of
+//! [`RecordBatch`]es, and exposes them to SQL through the same
+//! [`ChunkTableProvider`] used by the querier, including the deduplication of
+//! rows with the same primary key (tags and time) across chunks.
+//!
+//! See `examples/embedded.rs` for a complete example.
+//!
+//! [`ChunkTableProvider`]: crate::provider::ChunkTableProvider
+
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+};
+
+use arrow::record_batch::RecordBatch;
+use arrow_util::util::ensure_schema;
+use async_trait::async_trait;
+use data_types::{ChunkId, ChunkOrder, PartitionKey, TableId, TransitionPartitionId};
+use datafusion::{
+    catalog::{schema::SchemaProvider, CatalogProvider},
+    datasource::TableProvider,
+    error::DataFusionError,
+    physical_plan::Statistics,
+    prelude::Expr,
+};
+use datafusion_util::config::DEFAULT_SCHEMA;
+use once_cell::sync::OnceCell;
+use parking_lot::RwLock;
+use schema::{
+    merge::{SchemaMerger, TypePromotion},
+    sort::SortKey,
+    Schema,
+};
+use snafu::{ensure, ResultExt, Snafu};
+use trace::ctx::SpanContext;
+
+use crate::{
+    config::IOX_CONFIG_PREFIX,
+    exec::{Executor, ExecutorType, IOxSessionContext},
+    provider::ProviderBuilder,
+    pruning::prune_chunks,
+    util::{compute_timenanosecond_min_max, create_basic_summary},
+    QueryChunk, QueryChunkData, QueryCompletedToken, QueryNamespace, QueryText,
+};
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Chunk for table {table_name} contains no rows"))]
+    EmptyChunk { table_name: String },
+
+    #[snafu(display("Chunk schema incompatible with table {table_name}: {source}"))]
+    IncompatibleSchema {
+        table_name: String,
+        source: schema::merge::Error,
+    },
+}
+
+/// A [`QueryChunk`] over a set of [`RecordBatch`]es held in memory, which may
+/// contain rows with duplicate primary keys.
+#[derive(Debug)]
+pub struct MemChunk {
+    id: ChunkId,
+    partition_id: TransitionPartitionId,
+    order: ChunkOrder,
+    schema: Schema,
+    batches: Vec<RecordBatch>,
+    stats: OnceCell<Arc<Statistics>>,
+}
+
+impl MemChunk {
+    /// Create a chunk of `batches`, all of which must have a schema
+    /// compatible with `schema`.
+    ///
+    /// Among overlapping chunks, rows from chunks with a higher `order` take
+    /// precedence.
+    pub fn new(
+        partition_id: TransitionPartitionId,
+        order: ChunkOrder,
+        schema: Schema,
+        batches: Vec<RecordBatch>,
+    ) -> Self {
+        Self {
+            id: ChunkId::new(),
+            partition_id,
+            order,
+            schema,
+            batches,
+            stats: OnceCell::default(),
+        }
+    }
+
+    fn num_rows(&self) -> u64 {
+        self.batches.iter().map(|b| b.num_rows()).sum::<usize>() as u64
+    }
+}
+
+impl QueryChunk for MemChunk {
+    fn stats(&self) -> Arc<Statistics> {
+        Arc::clone(self.stats.get_or_init(|| {
+            let ts_min_max = compute_timenanosecond_min_max(self.batches.iter()).ok();
+            Arc::new(create_basic_summary(
+                self.num_rows(),
+                &self.schema,
+                ts_min_max,
+            ))
+        }))
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn partition_id(&self) -> &TransitionPartitionId {
+        &self.partition_id
+    }
+
+    fn sort_key(&self) -> Option<&SortKey> {
+        None
+    }
+
+    fn id(&self) -> ChunkId {
+        self.id
+    }
+
+    fn may_contain_pk_duplicates(&self) -> bool {
+        true
+    }
+
+    fn data(&self) -> QueryChunkData {
+        let schema = self.schema.as_arrow();
+
+        QueryChunkData::in_mem(
+            self.batches
+                .iter()
+                .map(|b| ensure_schema(&schema, b).expect("batch schema incompatible with chunk"))
+                .collect(),
+            Arc::clone(self.schema.inner()),
+        )
+    }
+
+    fn chunk_type(&self) -> &str {
+        "mem"
+    }
+
+    fn order(&self) -> ChunkOrder {
+        self.order
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// The chunks of a table, and their merged schema.
+#[derive(Debug, Clone)]
+struct MemTable {
+    id: TableId,
+    schema: Schema,
+    chunks: Vec<Arc<dyn QueryChunk>>,
+}
+
+/// A [`QueryNamespace`] of tables held in memory.
+///
+/// Each call to [`MemNamespace::add_chunk`] adds a chunk that takes precedence
+/// over all chunks added before it, so a row replaces any earlier row of the
+/// same table with the same tag values and timestamp.
+///
+/// Chunks with a different type for a field of the table are rejected, unless
+/// the types are promoted by the policy set with
+/// [`MemNamespace::with_type_promotion`].
+#[derive(Debug)]
+pub struct MemNamespace {
+    executor: Arc<Executor>,
+    tables: RwLock<BTreeMap<Arc<str>, MemTable>>,
+    next_order: AtomicI64,
+    type_promotion: TypePromotion,
+}
+
+impl MemNamespace {
+    /// Create an empty namespace executing queries on `executor`.
+    pub fn new(executor: Arc<Executor>) -> Self {
+        Self {
+            executor,
+            tables: Default::default(),
+            next_order: AtomicI64::new(1),
+            type_promotion: TypePromotion::default(),
+        }
+    }
+
+    /// Promote the field types of chunks according to `type_promotion`, both
+    /// when merging their schemas and when reading them in queries.
+    pub fn with_type_promotion(mut self, type_promotion: TypePromotion) -> Self {
+        self.type_promotion = type_promotion;
+        self
+    }
+
+    /// Add the rows in `batches`, of the given `schema`, to `table_name`.
+    ///
+    /// The table is created if it does not exist, and its schema is extended
+    /// with any new column of `schema`.
+    pub fn add_chunk(
+        &self,
+        table_name: &str,
+        partition_key: &str,
+        schema: Schema,
+        batches: Vec<RecordBatch>,
+    ) -> Result<ChunkId, Error> {
+        ensure!(
+            batches.iter().any(|b| b.num_rows() > 0),
+            EmptyChunkSnafu { table_name }
+        );
+
+        let mut tables = self.tables.write();
+        let next_table_id = TableId::new(tables.len() as i64 + 1);
+        let table = tables
+            .entry(Arc::from(table_name))
+            .or_insert_with(|| MemTable {
+                id: next_table_id,
+                schema: schema.clone(),
+                chunks: vec![],
+            });
+
+        table.schema = SchemaMerger::new()
+            .with_type_promotion(self.type_promotion)
+            .merge(&table.schema)
+            .and_then(|m| m.merge(&schema))
+            .context(IncompatibleSchemaSnafu { table_name })?
+            .build();
+
+        let chunk = MemChunk::new(
+            TransitionPartitionId::new(table.id, &PartitionKey::from(partition_key)),
+            ChunkOrder::new(self.next_order.fetch_add(1, Ordering::Relaxed)),
+            schema,
+            batches,
+        );
+        let id = chunk.id();
+        table.chunks.push(Arc::new(chunk));
+
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl QueryNamespace for MemNamespace {
+    async fn chunks(
+        &self,
+        table_name: &str,
+        filters: &[Expr],
+        _projection: Option<&Vec<usize>>,
+        _ctx: IOxSessionContext,
+    ) -> Result<Vec<Arc<dyn QueryChunk>>, DataFusionError> {
+        let Some(table) = self.tables.read().get(table_name).cloned() else {
+            return Ok(vec![]);
+        };
+
+        // Keep all chunks if they cannot be pruned.
+        let keep = prune_chunks(&table.schema, &table.chunks, filters)
+            .unwrap_or_else(|_| vec![true; table.chunks.len()]);
+
+        Ok(table
+            .chunks
+            .into_iter()
+            .zip(keep)
+            .filter_map(|(c, keep)| keep.then_some(c))
+            .collect())
+    }
+
+    fn retention_time_ns(&self) -> Option<i64> {
+        None
+    }
+
+    fn record_query(
+        &self,
+        _ctx: &IOxSessionContext,
+        _span_ctx: Option<&SpanContext>,
+        _query_type: &'static str,
+        _query_text: QueryText,
+    ) -> QueryCompletedToken {
+        QueryCompletedToken::new(|_| {})
+    }
+
+    fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
+        let catalog = MemCatalogProvider {
+            tables: self.tables.read().clone(),
+        };
+
+        self.executor
+            .new_execution_config(ExecutorType::Query)
+            .with_default_catalog(Arc::new(catalog))
+            .with_span_context(span_ctx)
+            .with_config_option(
+                &format!("{IOX_CONFIG_PREFIX}.type_promotion"),
+                &self.type_promotion.to_string(),
+            )
+            .build()
+    }
+}
+
+/// A snapshot of the tables of a [`MemNamespace`], exposed as the default
+/// schema of a DataFusion catalog.
+#[derive(Debug)]
+struct MemCatalogProvider {
+    tables: BTreeMap<Arc<str>, MemTable>,
+}
+
+impl CatalogProvider for MemCatalogProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        vec![DEFAULT_SCHEMA.to_string()]
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        match name {
+            DEFAULT_SCHEMA => Some(Arc::new(MemSchemaProvider {
+                tables: self.tables.clone(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MemSchemaProvider {
+    tables: BTreeMap<Arc<str>, MemTable>,
+}
+
+#[async_trait]
+impl SchemaProvider for MemSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        self.tables.keys().map(|t| t.to_string()).collect()
+    }
+
+    async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        let (table_name, table) = self.tables.get_key_value(name)?;
+
+        let provider = table
+            .chunks
+            .iter()
+            .fold(
+                ProviderBuilder::new(Arc::clone(table_name), table.schema.clone()),
+                |builder, chunk| builder.add_chunk(Arc::clone(chunk)),
+            )
+            .build()
+            .expect("building a provider is infallible");
+
+        Some(Arc::new(provider))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.tables.contains_key(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{ArrayRef, DictionaryArray, Float64Array, Int64Array, TimestampNanosecondArray},
+        datatypes::Int32Type,
+    };
+    use arrow_util::assert_batches_sorted_eq;
+    use schema::{builder::SchemaBuilder, InfluxFieldType};
+
+    use super::*;
+
+    fn cpu_batch(schema: &Schema, hosts: &[&str], usage: &[f64], time: &[i64]) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.as_arrow(),
+            vec![
+                Arc::new(
+                    hosts
+                        .iter()
+                        .copied()
+                        .collect::<DictionaryArray<Int32Type>>(),
+                ) as ArrayRef,
+                Arc::new(Float64Array::from(usage.to_vec())),
+                Arc::new(TimestampNanosecondArray::from(time.to_vec())),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_query_deduplicates_chunks() {
+        let schema = SchemaBuilder::new()
+            .tag("host")
+            .influx_field("usage", InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap();
+
+        let namespace = MemNamespace::new(Arc::new(Executor::new_testing()));
+        namespace
+            .add_chunk(
+                "cpu",
+                "1970-01-01",
+                schema.clone(),
+                vec![cpu_batch(&schema, &["a", "b"], &[1.0, 2.0], &[10, 10])],
+            )
+            .unwrap();
+        namespace
+            .add_chunk(
+                "cpu",
+                "1970-01-01",
+                schema.clone(),
+                vec![cpu_batch(&schema, &["a"], &[3.0], &[10])],
+            )
+            .unwrap();
+
+        let err = namespace
+            .add_chunk("cpu", "1970-01-01", schema.clone(), vec![])
+            .unwrap_err();
+        assert!(matches!(err, Error::EmptyChunk { .. }));
+
+        let ctx = namespace.new_query_context(None);
+        let plan = ctx
+            .sql_to_physical_plan("SELECT host, usage, time FROM cpu")
+            .await
+            .unwrap();
+        let batches = ctx.collect(plan).await.unwrap();
+
+        assert_batches_sorted_eq!(
+            &[
+                "+------+-------+--------------------------------+",
+                "| host | usage | time                           |",
+                "+------+-------+--------------------------------+",
+                "| a    | 3.0   | 1970-01-01T00:00:00.000000010Z |",
+                "| b    | 2.0   | 1970-01-01T00:00:00.000000010Z |",
+                "+------+-------+--------------------------------+",
+            ],
+            &batches
+        );
+
+        // Chunks of unknown tables are empty.
+        let chunks = namespace
+            .chunks("mem", &[], None, ctx.child_ctx("test"))
+            .await
+            .unwrap();
+        assert!(chunks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_promotes_field_types() {
+        let int_schema = SchemaBuilder::new()
+            .tag("host")
+            .influx_field("usage", InfluxFieldType::Integer)
+            .timestamp()
+            .build()
+            .unwrap();
+        let int_batch = RecordBatch::try_new(
+            int_schema.as_arrow(),
+            vec![
+                Arc::new(["a"].into_iter().collect::<DictionaryArray<Int32Type>>()) as ArrayRef,
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(TimestampNanosecondArray::from(vec![10])),
+            ],
+        )
+        .unwrap();
+        let float_schema = SchemaBuilder::new()
+            .tag("host")
+            .influx_field("usage", InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap();
+        let float_batch = cpu_batch(&float_schema, &["b"], &[2.5], &[10]);
+
+        // without a policy, the field types of the chunks must match
+        let namespace = MemNamespace::new(Arc::new(Executor::new_testing()));
+        namespace
+            .add_chunk(
+                "cpu",
+                "1970-01-01",
+                int_schema.clone(),
+                vec![int_batch.clone()],
+            )
+            .unwrap();
+        let err = namespace
+            .add_chunk(
+                "cpu",
+                "1970-01-01",
+                float_schema.clone(),
+                vec![float_batch.clone()],
+            )
+            .unwrap_err();
+        assert!(matches!(err, Error::IncompatibleSchema { .. }));
+
+        let namespace = MemNamespace::new(Arc::new(Executor::new_testing()))
+            .with_type_promotion(TypePromotion::widening());
+        namespace
+            .add_chunk("cpu", "1970-01-01", int_schema, vec![int_batch])
+            .unwrap();
+        namespace
+            .add_chunk("cpu", "1970-01-01", float_schema, vec![float_batch])
+            .unwrap();
+
+        let ctx = namespace.new_query_context(None);
+        let plan = ctx
+            .sql_to_physical_plan("SELECT host, usage, time FROM cpu")
+            .await
+            .unwrap();
+        let batches = ctx.collect(plan).await.unwrap();
+
+        assert_batches_sorted_eq!(
+            &[
+                "+------+-------+--------------------------------+",
+                "| host | usage | time                           |",
+                "+------+-------+--------------------------------+",
+                "| a    | 1.0   | 1970-01-01T00:00:00.000000010Z |",
+                "| b    | 2.5   | 1970-01-01T00:00:00.000000010Z |",
+                "+------+-------+--------------------------------+",
+            ],
+            &batches
+        );
+    }
+}
@@ -4,17 +4,18 @@ use arrow::datatypes::SchemaRef;
 use datafusion::{
     datasource::physical_plan::ParquetExec,
     error::DataFusionError,
+    physical_expr::expressions::Column,
     physical_plan::{
-        empty::EmptyExec, union::UnionExec, visit_execution_plan, ExecutionPlan,
-        ExecutionPlanVisitor,
+        empty::EmptyExec, projection::ProjectionExec, union::UnionExec, visit_execution_plan,
+        ExecutionPlan, ExecutionPlanVisitor,
     },
 };
 use observability_deps::tracing::debug;
 use schema::sort::SortKey;
 
 use crate::{
-    provider::{PartitionedFileExt, RecordBatchesExec},
-    QueryChunk,
+    provider::{scanned_column_name, PartitionedFileExt, RecordBatchesExec},
+    QueryChunk, QueryChunkData,
 };
 
 /// List of [`QueryChunk`]s.
@@ -30,8 +31,8 @@ pub type QueryChunks = Vec<Arc<dyn QueryChunk>>;
 ///
 /// Note that this only works on the direct output of [`chunks_to_physical_nodes`]. If the plan is wrapped into
 /// additional nodes (like de-duplication, filtering, projection) then NO data will be returned. Also [`ParquetExec`]
-/// MUST NOT have a predicate attached.
-///
+/// and [`RecordBatchesExec`] MUST NOT have a predicate attached. The only [`ProjectionExec`]s accepted are the ones
+/// restoring the renamed and absent columns of parquet files on top of a [`ParquetExec`].
 ///
 /// [`chunks_to_physical_nodes`]: crate::provider::chunks_to_physical_nodes
 pub fn extract_chunks(
@@ -93,6 +94,57 @@ impl ExtractChunksVisitor {
 
         Ok(())
     }
+
+    /// The chunks scanned by `parquet_exec`, adding their sort key.
+    fn parquet_chunks(
+        &mut self,
+        parquet_exec: &ParquetExec,
+    ) -> Result<Vec<Arc<dyn QueryChunk>>, DataFusionError> {
+        if parquet_exec.predicate().is_some() {
+            return Err(DataFusionError::External(
+                String::from("ParquetExec has predicate").into(),
+            ));
+        }
+
+        let mut chunks = vec![];
+        for group in &parquet_exec.base_config().file_groups {
+            for file in group {
+                let ext = file
+                    .extensions
+                    .as_ref()
+                    .and_then(|any| any.downcast_ref::<PartitionedFileExt>())
+                    .ok_or_else(|| {
+                        DataFusionError::External(
+                            String::from("PartitionedFileExt not found").into(),
+                        )
+                    })?;
+                self.add_sort_key(ext.output_sort_key_memo.as_ref())?;
+                chunks.push(Arc::clone(&ext.chunk));
+            }
+        }
+        Ok(chunks)
+    }
+}
+
+/// Checks if `projection_exec` is the projection created by [`chunks_to_physical_nodes`] to restore the renamed and
+/// absent columns of the parquet files of `chunks`, which all have the same renamed and absent columns.
+///
+/// [`chunks_to_physical_nodes`]: crate::provider::chunks_to_physical_nodes
+fn restores_columns(projection_exec: &ProjectionExec, chunks: &[Arc<dyn QueryChunk>]) -> bool {
+    let Some(QueryChunkData::Parquet(parquet_input)) = chunks.first().map(|c| c.data()) else {
+        return false;
+    };
+
+    projection_exec.expr().iter().all(|(expr, name)| {
+        let scanned_name = scanned_column_name(
+            name,
+            &parquet_input.renamed_columns,
+            &parquet_input.absent_columns,
+        );
+        expr.as_any()
+            .downcast_ref::<Column>()
+            .is_some_and(|col| col.name() == scanned_name)
+    })
 }
 
 impl ExecutionPlanVisitor for ExtractChunksVisitor {
@@ -102,6 +154,12 @@ impl ExecutionPlanVisitor for ExtractChunksVisitor {
         let plan_any = plan.as_any();
 
         if let Some(record_batches_exec) = plan_any.downcast_ref::<RecordBatchesExec>() {
+            if record_batches_exec.predicate().is_some() {
+                return Err(DataFusionError::External(
+                    String::from("RecordBatchesExec has predicate").into(),
+                ));
+            }
+
             self.add_schema_from_exec(record_batches_exec)
                 .map_err(|e| {
                     DataFusionError::Context(
@@ -116,31 +174,38 @@ impl ExecutionPlanVisitor for ExtractChunksVisitor {
                 self.add_chunk(Arc::clone(chunk));
             }
         } else if let Some(parquet_exec) = plan_any.downcast_ref::<ParquetExec>() {
-            if parquet_exec.predicate().is_some() {
+            self.add_schema_from_exec(parquet_exec).map_err(|e| {
+                DataFusionError::Context("add schema from ParquetExec".to_owned(), Box::new(e))
+            })?;
+
+            for chunk in self.parquet_chunks(parquet_exec)? {
+                self.add_chunk(chunk);
+            }
+        } else if let Some(projection_exec) = plan_any.downcast_ref::<ProjectionExec>() {
+            let parquet_exec = projection_exec
+                .input()
+                .as_any()
+                .downcast_ref::<ParquetExec>()
+                .ok_or_else(|| {
+                    DataFusionError::External(String::from("Unsupported projection").into())
+                })?;
+            let chunks = self.parquet_chunks(parquet_exec)?;
+            if !restores_columns(projection_exec, &chunks) {
                 return Err(DataFusionError::External(
-                    String::from("ParquetExec has predicate").into(),
+                    String::from("Unsupported projection").into(),
                 ));
             }
 
-            self.add_schema_from_exec(parquet_exec).map_err(|e| {
-                DataFusionError::Context("add schema from ParquetExec".to_owned(), Box::new(e))
+            self.add_schema_from_exec(projection_exec).map_err(|e| {
+                DataFusionError::Context("add schema from ProjectionExec".to_owned(), Box::new(e))
             })?;
 
-            for group in &parquet_exec.base_config().file_groups {
-                for file in group {
-                    let ext = file
-                        .extensions
-                        .as_ref()
-                        .and_then(|any| any.downcast_ref::<PartitionedFileExt>())
-                        .ok_or_else(|| {
-                            DataFusionError::External(
-                                String::from("PartitionedFileExt not found").into(),
-                            )
-                        })?;
-                    self.add_sort_key(ext.output_sort_key_memo.as_ref())?;
-                    self.add_chunk(Arc::clone(&ext.chunk));
-                }
+            for chunk in chunks {
+                self.add_chunk(chunk);
             }
+
+            // the chunks of the ParquetExec were already added, with the schema of the projection
+            return Ok(false);
         } else if let Some(empty_exec) = plan_any.downcast_ref::<EmptyExec>() {
             // should not produce dummy data
             if empty_exec.produce_one_row() {
@@ -221,6 +286,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_roundtrip_renamed_and_absent_columns() {
+        let chunk1 = chunk(1).with_dummy_parquet_file();
+        let chunk2 = chunk(2)
+            .with_dummy_parquet_file()
+            .with_renamed_column("field", "feild");
+        let chunk3 = chunk(3)
+            .with_dummy_parquet_file()
+            .with_absent_column("tag2");
+        let chunk4 = chunk(4);
+        let sort_key = Some(sort_key());
+        assert_roundtrip(
+            chunk1.schema().as_arrow(),
+            vec![
+                Arc::new(chunk1),
+                Arc::new(chunk2),
+                Arc::new(chunk3),
+                Arc::new(chunk4),
+            ],
+            sort_key,
+        );
+    }
+
+    #[test]
+    fn test_stop_at_other_projections() {
+        let chunk1 = chunk(1)
+            .with_dummy_parquet_file()
+            .with_renamed_column("field", "feild");
+        let schema = chunk1.schema().as_arrow();
+        let plan = chunks_to_physical_nodes(&schema, None, vec![Arc::new(chunk1)], 2);
+
+        // swap the names of the tag columns
+        let plan = plan
+            .transform_down(&|plan| {
+                if let Some(exec) = plan.as_any().downcast_ref::<ProjectionExec>() {
+                    let mut exprs = exec.expr().to_vec();
+                    let name1 = exprs[1].1.clone();
+                    exprs[1].1 = exprs[2].1.clone();
+                    exprs[2].1 = name1;
+                    let exec = ProjectionExec::try_new(exprs, Arc::clone(exec.input()))?;
+                    return Ok(Transformed::Yes(Arc::new(exec)));
+                }
+                Ok(Transformed::No(plan))
+            })
+            .unwrap();
+        assert!(extract_chunks(plan.as_ref()).is_none());
+    }
+
     #[test]
     fn test_different_schemas() {
         let some_chunk = chunk(1);
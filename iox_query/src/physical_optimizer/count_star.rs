@@ -0,0 +1,466 @@
+use std::sync::Arc;
+
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    error::Result,
+    logical_expr::{utils::COUNT_STAR_EXPANSION, Operator},
+    physical_expr::{split_conjunction, PhysicalExpr},
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        aggregates::{AggregateExec, AggregateMode},
+        coalesce_batches::CoalesceBatchesExec,
+        coalesce_partitions::CoalescePartitionsExec,
+        empty::EmptyExec,
+        expressions::{lit, BinaryExpr, Column, Count, Literal},
+        filter::FilterExec,
+        projection::ProjectionExec,
+        repartition::RepartitionExec,
+        ExecutionPlan,
+    },
+    scalar::ScalarValue,
+};
+use schema::TIME_COLUMN_NAME;
+
+use crate::{
+    physical_optimizer::chunk_extraction::extract_chunks, provider::overlap::timestamp_min_max,
+};
+
+/// Answers `COUNT(*)` aggregations without grouping from the row counts of
+/// the chunk statistics instead of scanning the data.
+///
+/// This is only possible if:
+///
+/// - the aggregation is only filtered by a time range, and every chunk lies
+///   either completely inside or completely outside of this range; and
+/// - the chunks are not de-duplicated, i.e. the rows of all chunks are
+///   returned as-is.
+///
+/// Plans with de-duplication are left untouched, since the number of rows
+/// after de-duplication cannot be derived from the statistics. Run this
+/// after the de-dup rules, which remove de-duplication wherever the chunks
+/// cannot contain duplicates, and before [`PredicatePushdown`] moves the
+/// filter into the chunk scans.
+///
+/// [`PredicatePushdown`]: super::predicate_pushdown::PredicatePushdown
+#[derive(Debug, Default)]
+pub struct CountStar;
+
+impl PhysicalOptimizerRule for CountStar {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let Some(agg_exec) = plan.as_any().downcast_ref::<AggregateExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+
+            let input = match agg_exec.mode() {
+                AggregateMode::Single => Arc::clone(agg_exec.input()),
+                AggregateMode::Final => {
+                    let mut input = Arc::clone(agg_exec.input());
+                    if let Some(coalesce) = input.as_any().downcast_ref::<CoalescePartitionsExec>()
+                    {
+                        input = Arc::clone(coalesce.input());
+                    }
+                    let Some(partial) = input.as_any().downcast_ref::<AggregateExec>() else {
+                        return Ok(Transformed::No(plan));
+                    };
+                    if partial.mode() != &AggregateMode::Partial || !is_count_star(partial) {
+                        return Ok(Transformed::No(plan));
+                    }
+                    Arc::clone(partial.input())
+                }
+                _ => return Ok(Transformed::No(plan)),
+            };
+            if !is_count_star(agg_exec) {
+                return Ok(Transformed::No(plan));
+            }
+
+            let Some(num_rows) = count_rows(input) else {
+                return Ok(Transformed::No(plan));
+            };
+
+            let projections = agg_exec
+                .aggr_expr()
+                .iter()
+                .map(|expr| {
+                    (
+                        lit(ScalarValue::Int64(Some(num_rows as i64))),
+                        expr.name().to_owned(),
+                    )
+                })
+                .collect();
+
+            Ok(Transformed::Yes(Arc::new(ProjectionExec::try_new(
+                projections,
+                Arc::new(EmptyExec::new(true, plan.schema())),
+            )?)))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "count_star"
+    }
+
+    fn schema_check(&self) -> bool {
+        // the counts become non-nullable literals
+        false
+    }
+}
+
+/// Returns true if `agg_exec` only computes `COUNT(*)` without grouping.
+fn is_count_star(agg_exec: &AggregateExec) -> bool {
+    agg_exec.group_expr().is_empty()
+        && !agg_exec.aggr_expr().is_empty()
+        && agg_exec.filter_expr().iter().all(|f| f.is_none())
+        && agg_exec.aggr_expr().iter().all(|expr| {
+            let Some(count) = expr.as_any().downcast_ref::<Count>() else {
+                return false;
+            };
+            let args = count.expressions();
+            args.len() == 1
+                && args[0]
+                    .as_any()
+                    .downcast_ref::<Literal>()
+                    .map(|l| l.value() == &COUNT_STAR_EXPANSION)
+                    .unwrap_or_default()
+        })
+}
+
+/// Returns the number of rows produced by `plan`, if it can be derived from
+/// the statistics of the chunks it scans.
+fn count_rows(mut plan: Arc<dyn ExecutionPlan>) -> Option<usize> {
+    let mut range = TimeRange::default();
+
+    // Walk down to the chunks through nodes that do not change the number
+    // of rows, collecting the time range of all filters.
+    loop {
+        let plan_any = plan.as_any();
+        if let Some(filter_exec) = plan_any.downcast_ref::<FilterExec>() {
+            for expr in split_conjunction(filter_exec.predicate()) {
+                range.restrict(expr)?;
+            }
+            plan = Arc::clone(filter_exec.input());
+        } else if let Some(projection_exec) = plan_any.downcast_ref::<ProjectionExec>() {
+            // the filters above must still refer to the same time column
+            let renames = projection_exec.expr().iter().any(|(expr, name)| {
+                expr.as_any()
+                    .downcast_ref::<Column>()
+                    .map(|c| c.name() != name)
+                    .unwrap_or(true)
+            });
+            if renames && !range.is_unbounded() {
+                return None;
+            }
+            plan = Arc::clone(projection_exec.input());
+        } else if let Some(coalesce) = plan_any.downcast_ref::<CoalesceBatchesExec>() {
+            plan = Arc::clone(coalesce.input());
+        } else if let Some(repartition) = plan_any.downcast_ref::<RepartitionExec>() {
+            plan = Arc::clone(repartition.input());
+        } else {
+            break;
+        }
+    }
+
+    // fails for anything but plain chunk scans, e.g. de-duplication
+    let (_schema, chunks, _sort_key) = extract_chunks(plan.as_ref())?;
+
+    let mut num_rows = 0;
+    for chunk in chunks {
+        let stats = chunk.stats();
+        if !stats.is_exact {
+            return None;
+        }
+        let chunk_rows = stats.num_rows?;
+
+        if range.is_unbounded() {
+            num_rows += chunk_rows;
+            continue;
+        }
+
+        let ts = timestamp_min_max(chunk.as_ref())?;
+        if range.min <= ts.min && ts.max <= range.max {
+            num_rows += chunk_rows;
+        } else if ts.max < range.min || range.max < ts.min {
+            // no row of the chunk passes the filter
+        } else {
+            // some rows may pass the filter, the data has to be scanned
+            return None;
+        }
+    }
+
+    Some(num_rows)
+}
+
+/// An inclusive range of timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimeRange {
+    min: i64,
+    max: i64,
+}
+
+impl Default for TimeRange {
+    fn default() -> Self {
+        Self {
+            min: i64::MIN,
+            max: i64::MAX,
+        }
+    }
+}
+
+impl TimeRange {
+    fn is_unbounded(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Restrict the range to the rows passing the predicate `expr`.
+    ///
+    /// Returns [`None`] if `expr` is not a comparison of the time column
+    /// with a timestamp.
+    fn restrict(&mut self, expr: &Arc<dyn PhysicalExpr>) -> Option<()> {
+        let binary = expr.as_any().downcast_ref::<BinaryExpr>()?;
+
+        let (op, ts) = match (time_column(binary.left()), timestamp(binary.right())) {
+            (true, Some(ts)) => (*binary.op(), ts),
+            _ => match (timestamp(binary.left()), time_column(binary.right())) {
+                // `ts < time` is `time > ts`
+                (Some(ts), true) => (binary.op().swap()?, ts),
+                _ => return None,
+            },
+        };
+
+        match op {
+            Operator::Gt => self.min = self.min.max(ts.saturating_add(1)),
+            Operator::GtEq => self.min = self.min.max(ts),
+            Operator::Lt => self.max = self.max.min(ts.saturating_sub(1)),
+            Operator::LtEq => self.max = self.max.min(ts),
+            Operator::Eq => {
+                self.min = self.min.max(ts);
+                self.max = self.max.min(ts);
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+}
+
+fn time_column(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    expr.as_any()
+        .downcast_ref::<Column>()
+        .map(|c| c.name() == TIME_COLUMN_NAME)
+        .unwrap_or_default()
+}
+
+fn timestamp(expr: &Arc<dyn PhysicalExpr>) -> Option<i64> {
+    match expr.as_any().downcast_ref::<Literal>()?.value() {
+        ScalarValue::TimestampNanosecond(Some(ts), _) => Some(*ts),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::DataType;
+    use datafusion::{
+        physical_plan::{aggregates::PhysicalGroupBy, AggregateExpr},
+        prelude::{col, lit as logical_lit, Expr},
+    };
+
+    use crate::{
+        physical_optimizer::{dedup::test_util::dedup_plan, test_util::OptimizationTest},
+        provider::chunks_to_physical_nodes,
+        test::{format_execution_plan, TestChunk},
+        util::df_physical_expr,
+        QueryChunk,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_no_filter() {
+        let plan = count_plan(scan(vec![chunk(1, 10, 20, 3), chunk(2, 30, 40, 5)]));
+        let opt = CountStar;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*)]"
+          - "   CoalescePartitionsExec"
+          - "     AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*)]"
+          - "       UnionExec"
+          - "         RecordBatchesExec: chunks=2"
+        output:
+          Ok:
+            - " ProjectionExec: expr=[8 as COUNT(*)]"
+            - "   EmptyExec: produce_one_row=true"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_time_range() {
+        // chunk 3 lies outside of the range
+        let chunks = vec![
+            chunk(1, 10, 20, 3),
+            chunk(2, 30, 40, 5),
+            chunk(3, 50, 60, 7),
+        ];
+        let predicate = col(TIME_COLUMN_NAME)
+            .gt_eq(ts(10))
+            .and(col(TIME_COLUMN_NAME).lt(ts(50)));
+        assert_count(count_plan(filter(scan(chunks), predicate)), 8);
+    }
+
+    #[test]
+    fn test_time_range_partial_overlap() {
+        // chunk 2 is only partially in the range
+        let chunks = vec![chunk(1, 10, 20, 3), chunk(2, 30, 40, 5)];
+        let predicate = col(TIME_COLUMN_NAME).lt(ts(35));
+        assert_unchanged(count_plan(filter(scan(chunks), predicate)));
+    }
+
+    #[test]
+    fn test_non_time_filter() {
+        let chunks = vec![chunk(1, 10, 20, 3)];
+        let predicate = col("tag1").eq(logical_lit("foo"));
+        assert_unchanged(count_plan(filter(scan(chunks), predicate)));
+    }
+
+    #[test]
+    fn test_dedup() {
+        let chunks = vec![chunk(1, 10, 20, 3), chunk(2, 15, 25, 5)];
+        let schema = chunks[0].schema().clone();
+        assert_unchanged(count_plan(dedup_plan(schema, chunks)));
+    }
+
+    #[test]
+    fn test_other_aggregate() {
+        let input = scan(vec![chunk(1, 10, 20, 3)]);
+        let count_field = Arc::new(Count::new(
+            Arc::new(Column::new_with_schema("field", &input.schema()).unwrap()),
+            "COUNT(field)",
+            DataType::Int64,
+        ));
+        assert_unchanged(aggregate(input, vec![count_field]));
+    }
+
+    #[test]
+    fn test_time_range_restrict() {
+        let input = scan(vec![chunk(1, 10, 20, 3)]);
+        let expr = |e| df_physical_expr(input.as_ref(), e).unwrap();
+
+        let mut range = TimeRange::default();
+        assert!(range.is_unbounded());
+
+        range
+            .restrict(&expr(col(TIME_COLUMN_NAME).gt(ts(10))))
+            .unwrap();
+        range
+            .restrict(&expr(ts(20).gt_eq(col(TIME_COLUMN_NAME))))
+            .unwrap();
+        assert_eq!(range, TimeRange { min: 11, max: 20 });
+
+        assert!(range
+            .restrict(&expr(col(TIME_COLUMN_NAME).not_eq(ts(15))))
+            .is_none());
+        assert!(range
+            .restrict(&expr(col("tag1").eq(logical_lit("foo"))))
+            .is_none());
+    }
+
+    fn chunk(id: u128, min: i64, max: i64, rows: u64) -> TestChunk {
+        TestChunk::new("table")
+            .with_id(id)
+            .with_tag_column("tag1")
+            .with_tag_column("tag2")
+            .with_i64_field_column("field")
+            .with_time_column_with_full_stats(Some(min), Some(max), rows, None)
+    }
+
+    fn ts(v: i64) -> Expr {
+        logical_lit(ScalarValue::TimestampNanosecond(Some(v), None))
+    }
+
+    fn scan(chunks: Vec<TestChunk>) -> Arc<dyn ExecutionPlan> {
+        let schema = chunks[0].schema().as_arrow();
+        let chunks = chunks
+            .into_iter()
+            .map(|c| Arc::new(c) as Arc<dyn QueryChunk>)
+            .collect();
+        chunks_to_physical_nodes(&schema, None, chunks, 2)
+    }
+
+    fn filter(input: Arc<dyn ExecutionPlan>, expr: Expr) -> Arc<dyn ExecutionPlan> {
+        let predicate = df_physical_expr(input.as_ref(), expr).unwrap();
+        Arc::new(FilterExec::try_new(predicate, input).unwrap())
+    }
+
+    fn count_plan(input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let count_star = Arc::new(Count::new(
+            lit(COUNT_STAR_EXPANSION.clone()),
+            "COUNT(*)",
+            DataType::Int64,
+        ));
+        aggregate(input, vec![count_star])
+    }
+
+    fn aggregate(
+        input: Arc<dyn ExecutionPlan>,
+        aggr_expr: Vec<Arc<Count>>,
+    ) -> Arc<dyn ExecutionPlan> {
+        let aggr_expr = aggr_expr
+            .into_iter()
+            .map(|e| e as Arc<dyn AggregateExpr>)
+            .collect::<Vec<_>>();
+        let n = aggr_expr.len();
+        let schema = input.schema();
+        let partial = Arc::new(
+            AggregateExec::try_new(
+                AggregateMode::Partial,
+                PhysicalGroupBy::default(),
+                aggr_expr.clone(),
+                vec![None; n],
+                vec![None; n],
+                input,
+                Arc::clone(&schema),
+            )
+            .unwrap(),
+        );
+        Arc::new(
+            AggregateExec::try_new(
+                AggregateMode::Final,
+                PhysicalGroupBy::default(),
+                aggr_expr,
+                vec![None; n],
+                vec![None; n],
+                Arc::new(CoalescePartitionsExec::new(partial)),
+                schema,
+            )
+            .unwrap(),
+        )
+    }
+
+    #[track_caller]
+    fn assert_count(plan: Arc<dyn ExecutionPlan>, expected: usize) {
+        let test = OptimizationTest::new(plan, CountStar);
+        assert_eq!(
+            format_execution_plan(test.output_plan().unwrap()),
+            [
+                format!(" ProjectionExec: expr=[{expected} as COUNT(*)]"),
+                "   EmptyExec: produce_one_row=true".to_string(),
+            ]
+        );
+    }
+
+    #[track_caller]
+    fn assert_unchanged(plan: Arc<dyn ExecutionPlan>) {
+        let input = format_execution_plan(&plan);
+        let test = OptimizationTest::new(plan, CountStar);
+        assert_eq!(format_execution_plan(test.output_plan().unwrap()), input);
+    }
+}
@@ -133,6 +133,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_single_chunk_renamed_column() {
+        let chunk1 = chunk(1)
+            .with_dummy_parquet_file()
+            .with_renamed_column("field", "feild")
+            .with_may_contain_pk_duplicates(false);
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1]);
+        let opt = RemoveDedup;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     ProjectionExec: expr=[feild@0 as field, tag1@1 as tag1, tag2@2 as tag2, time@3 as time]"
+          - "       ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[feild, tag1, tag2, time]"
+        output:
+          Ok:
+            - " UnionExec"
+            - "   ProjectionExec: expr=[feild@0 as field, tag1@1 as tag1, tag2@2 as tag2, time@3 as time]"
+            - "     ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[feild, tag1, tag2, time]"
+        "###
+        );
+    }
+
     #[test]
     fn test_multiple_chunks() {
         let chunk1 = chunk(1).with_may_contain_pk_duplicates(false);
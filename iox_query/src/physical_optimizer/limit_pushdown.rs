@@ -0,0 +1,277 @@
+use std::sync::Arc;
+
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    datasource::physical_plan::{FileScanConfig, ParquetExec},
+    error::Result,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        coalesce_batches::CoalesceBatchesExec,
+        coalesce_partitions::CoalescePartitionsExec,
+        limit::{GlobalLimitExec, LocalLimitExec},
+        projection::ProjectionExec,
+        repartition::RepartitionExec,
+        union::UnionExec,
+        ExecutionPlan,
+    },
+};
+
+use crate::provider::RecordBatchesExec;
+
+/// Push down the fetch of [`GlobalLimitExec`] and [`LocalLimitExec`] nodes into the chunk scans ([`RecordBatchesExec`]
+/// and [`ParquetExec`]), so that the scans stop once enough rows were produced.
+///
+/// The limit is only pushed through nodes that neither remove nor reorder rows relative to a limit (projections,
+/// unions, coalescing and repartitioning), so it is never pushed below filters, de-duplication, sorts or aggregates.
+/// Every partition of a scan is limited on its own, the limit node itself is kept to limit the total number of rows.
+///
+/// This must run after [`PredicatePushdown`], because a predicate must not be pushed into a limited scan.
+///
+/// [`PredicatePushdown`]: super::predicate_pushdown::PredicatePushdown
+#[derive(Debug, Default)]
+pub struct LimitPushdown;
+
+impl PhysicalOptimizerRule for LimitPushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let plan_any = plan.as_any();
+
+            let (input, limit) =
+                if let Some(limit_exec) = plan_any.downcast_ref::<GlobalLimitExec>() {
+                    let Some(fetch) = limit_exec.fetch() else {
+                        return Ok(Transformed::No(plan));
+                    };
+                    (limit_exec.input(), limit_exec.skip() + fetch)
+                } else if let Some(limit_exec) = plan_any.downcast_ref::<LocalLimitExec>() {
+                    (limit_exec.input(), limit_exec.fetch())
+                } else {
+                    return Ok(Transformed::No(plan));
+                };
+
+            match push_limit(input, limit)? {
+                Some(new_input) => Ok(Transformed::Yes(plan.with_new_children(vec![new_input])?)),
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "limit_pushdown"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Limit the scans of `plan` to `limit` rows per partition.
+///
+/// Returns `None` if no scan was limited.
+fn push_limit(
+    plan: &Arc<dyn ExecutionPlan>,
+    limit: usize,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let plan_any = plan.as_any();
+
+    if let Some(record_batches_exec) = plan_any.downcast_ref::<RecordBatchesExec>() {
+        if record_batches_exec
+            .limit()
+            .map_or(false, |existing| existing <= limit)
+        {
+            return Ok(None);
+        }
+
+        let new_node = RecordBatchesExec::new(
+            record_batches_exec.chunks().cloned(),
+            record_batches_exec.schema(),
+            record_batches_exec.output_sort_key_memo().cloned(),
+        )
+        .with_predicate(record_batches_exec.predicate().cloned())
+        .with_limit(Some(limit));
+        Ok(Some(Arc::new(new_node)))
+    } else if let Some(parquet_exec) = plan_any.downcast_ref::<ParquetExec>() {
+        let base_config = parquet_exec.base_config();
+        if base_config
+            .limit
+            .map_or(false, |existing| existing <= limit)
+        {
+            return Ok(None);
+        }
+
+        let base_config = FileScanConfig {
+            limit: Some(limit),
+            ..base_config.clone()
+        };
+        let new_node = ParquetExec::new(base_config, parquet_exec.predicate().cloned(), None);
+        Ok(Some(Arc::new(new_node)))
+    } else if plan_any.is::<ProjectionExec>()
+        || plan_any.is::<UnionExec>()
+        || plan_any.is::<CoalescePartitionsExec>()
+        || plan_any.is::<CoalesceBatchesExec>()
+        || plan_any.is::<RepartitionExec>()
+        || plan_any.is::<LocalLimitExec>()
+    {
+        let mut changed = false;
+        let children = plan
+            .children()
+            .into_iter()
+            .map(|child| {
+                Ok(match push_limit(&child, limit)? {
+                    Some(new_child) => {
+                        changed = true;
+                        new_child
+                    }
+                    None => child,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if changed {
+            Ok(Some(Arc::clone(plan).with_new_children(children)?))
+        } else {
+            Ok(None)
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::{
+        datasource::object_store::ObjectStoreUrl,
+        physical_plan::{expressions::Column, filter::FilterExec, Statistics},
+    };
+    use schema::TIME_COLUMN_NAME;
+
+    use crate::{
+        physical_optimizer::{
+            dedup::test_util::{chunk, dedup_plan},
+            test_util::OptimizationTest,
+        },
+        provider::chunks_to_physical_nodes,
+        test::{format_execution_plan, TestChunk},
+        QueryChunk,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let union = Arc::new(UnionExec::new(vec![
+            scan(vec![chunk(1), chunk(2)]),
+            scan(vec![chunk(3)]),
+        ]));
+        let plan = limit(projection(union), 2, Some(5));
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, LimitPushdown),
+            @r###"
+        ---
+        input:
+          - " GlobalLimitExec: skip=2, fetch=5"
+          - "   ProjectionExec: expr=[time@3 as time]"
+          - "     UnionExec"
+          - "       UnionExec"
+          - "         RecordBatchesExec: chunks=2"
+          - "       UnionExec"
+          - "         RecordBatchesExec: chunks=1"
+        output:
+          Ok:
+            - " GlobalLimitExec: skip=2, fetch=5"
+            - "   ProjectionExec: expr=[time@3 as time]"
+            - "     UnionExec"
+            - "       UnionExec"
+            - "         RecordBatchesExec: chunks=2, limit=7"
+            - "       UnionExec"
+            - "         RecordBatchesExec: chunks=1, limit=7"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_parquet() {
+        let schema = chunk(1).schema().as_arrow();
+        let base_config = FileScanConfig {
+            object_store_url: ObjectStoreUrl::parse("test://").unwrap(),
+            file_schema: Arc::clone(&schema),
+            file_groups: vec![],
+            statistics: Statistics::default(),
+            projection: None,
+            limit: None,
+            table_partition_cols: vec![],
+            output_ordering: vec![],
+            infinite_source: false,
+        };
+        let plan = Arc::new(LocalLimitExec::new(
+            Arc::new(ParquetExec::new(base_config, None, None)),
+            10,
+        ));
+
+        let test = OptimizationTest::new(plan, LimitPushdown);
+        let output = test.output_plan().unwrap();
+        let children = output.children();
+        let parquet_exec = children[0].as_any().downcast_ref::<ParquetExec>().unwrap();
+        assert_eq!(parquet_exec.base_config().limit, Some(10));
+    }
+
+    #[test]
+    fn test_no_fetch() {
+        assert_unchanged(limit(scan(vec![chunk(1)]), 2, None));
+    }
+
+    #[test]
+    fn test_filter() {
+        let input = scan(vec![chunk(1)]);
+        let predicate = Arc::new(Column::new_with_schema("tag1", &input.schema()).unwrap());
+        let filter = Arc::new(FilterExec::try_new(predicate, input).unwrap());
+        assert_unchanged(limit(filter, 0, Some(1)));
+    }
+
+    #[test]
+    fn test_dedup() {
+        let chunk1 = chunk(1);
+        let schema = chunk1.schema().clone();
+        assert_unchanged(limit(
+            dedup_plan(schema, vec![chunk1, chunk(2)]),
+            0,
+            Some(1),
+        ));
+    }
+
+    fn scan(chunks: Vec<TestChunk>) -> Arc<dyn ExecutionPlan> {
+        let schema = chunks[0].schema().as_arrow();
+        let chunks = chunks
+            .into_iter()
+            .map(|c| Arc::new(c) as Arc<dyn QueryChunk>)
+            .collect();
+        chunks_to_physical_nodes(&schema, None, chunks, 2)
+    }
+
+    fn projection(input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let time = Arc::new(Column::new_with_schema(TIME_COLUMN_NAME, &input.schema()).unwrap());
+        Arc::new(
+            ProjectionExec::try_new(vec![(time as _, TIME_COLUMN_NAME.to_string())], input)
+                .unwrap(),
+        )
+    }
+
+    fn limit(
+        input: Arc<dyn ExecutionPlan>,
+        skip: usize,
+        fetch: Option<usize>,
+    ) -> Arc<dyn ExecutionPlan> {
+        Arc::new(GlobalLimitExec::new(input, skip, fetch))
+    }
+
+    #[track_caller]
+    fn assert_unchanged(plan: Arc<dyn ExecutionPlan>) {
+        let input = format_execution_plan(&plan);
+        let test = OptimizationTest::new(plan, LimitPushdown);
+        assert_eq!(format_execution_plan(test.output_plan().unwrap()), input);
+    }
+}
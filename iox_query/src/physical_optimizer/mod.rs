@@ -4,19 +4,25 @@ use datafusion::{execution::context::SessionState, physical_optimizer::PhysicalO
 
 use self::{
     combine_chunks::CombineChunks,
+    count_star::CountStar,
     dedup::{
         dedup_null_columns::DedupNullColumns, dedup_sort_order::DedupSortOrder,
         partition_split::PartitionSplit, remove_dedup::RemoveDedup, time_split::TimeSplit,
     },
+    limit_pushdown::LimitPushdown,
+    parquet_prefetch::ParquetPrefetch,
     predicate_pushdown::PredicatePushdown,
     projection_pushdown::ProjectionPushdown,
-    sort::parquet_sortness::ParquetSortness,
+    sort::{parquet_sortness::ParquetSortness, time_desc_scan::TimeDescScan},
     union::{nested_union::NestedUnion, one_union::OneUnion},
 };
 
 mod chunk_extraction;
 mod combine_chunks;
+mod count_star;
 mod dedup;
+mod limit_pushdown;
+mod parquet_prefetch;
 mod predicate_pushdown;
 mod projection_pushdown;
 mod sort;
@@ -36,13 +42,20 @@ pub fn register_iox_physical_optimizers(state: SessionState) -> SessionState {
         Arc::new(CombineChunks),
         Arc::new(DedupNullColumns),
         Arc::new(DedupSortOrder),
+        Arc::new(CountStar),
         Arc::new(PredicatePushdown),
         Arc::new(ProjectionPushdown),
+        Arc::new(TimeDescScan),
+        Arc::new(LimitPushdown),
         Arc::new(ParquetSortness) as _,
         Arc::new(NestedUnion),
         Arc::new(OneUnion),
     ];
     optimizers.append(&mut state.physical_optimizers().to_vec());
+    // must run last, see `ParquetPrefetch`
+    optimizers.push(Arc::new(ParquetPrefetch::new(Arc::clone(
+        state.runtime_env(),
+    ))));
 
     state.with_physical_optimizer_rules(optimizers)
 }
@@ -0,0 +1,457 @@
+use std::{collections::VecDeque, ops::Range, sync::Arc};
+
+use bytes::Bytes;
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    datasource::physical_plan::{
+        FileMeta, ParquetExec, ParquetFileMetrics, ParquetFileReaderFactory,
+    },
+    error::Result,
+    execution::runtime_env::RuntimeEnv,
+    parquet::{
+        arrow::async_reader::{AsyncFileReader, ParquetObjectReader},
+        errors::ParquetError,
+        file::metadata::{ColumnChunkMetaData, ParquetMetaData},
+    },
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{metrics::ExecutionPlanMetricsSet, ExecutionPlan},
+};
+use futures::{future::BoxFuture, FutureExt};
+use object_store::{path::Path, ObjectStore};
+use tokio::task::JoinHandle;
+
+use crate::config::IoxConfigExt;
+
+/// Read parquet files so that the column chunks of upcoming row groups are fetched from the object store while the
+/// current row group is decoded.
+///
+/// Without this, every row group of a file is fetched only once the previous one is fully decoded, so reading cold
+/// data alternates between waiting for the object store and decoding. The number of row groups fetched ahead is set
+/// by [`IoxConfigExt::parquet_prefetch_row_groups`] (`0` disables prefetching).
+///
+/// This rule MUST run after all rules that re-create [`ParquetExec`] nodes, since those do not retain the reader
+/// factory.
+#[derive(Debug)]
+pub struct ParquetPrefetch {
+    runtime: Arc<RuntimeEnv>,
+}
+
+impl ParquetPrefetch {
+    /// Create rule that looks up the object stores of the scanned files in `runtime`.
+    pub fn new(runtime: Arc<RuntimeEnv>) -> Self {
+        Self { runtime }
+    }
+}
+
+impl PhysicalOptimizerRule for ParquetPrefetch {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let depth = config
+            .extensions
+            .get::<IoxConfigExt>()
+            .cloned()
+            .unwrap_or_default()
+            .parquet_prefetch_row_groups;
+        if depth == 0 {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| {
+            let Some(parquet_exec) = plan.as_any().downcast_ref::<ParquetExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+
+            let Ok(object_store) = self
+                .runtime
+                .object_store(&parquet_exec.base_config().object_store_url)
+            else {
+                // unknown store, leave it to DataFusion to report the error
+                return Ok(Transformed::No(plan));
+            };
+
+            let factory = Arc::new(PrefetchReaderFactory {
+                object_store,
+                depth,
+            });
+            let new_parquet_exec = ParquetExec::new(
+                parquet_exec.base_config().clone(),
+                parquet_exec.predicate().cloned(),
+                None,
+            )
+            .with_parquet_file_reader_factory(factory);
+            Ok(Transformed::Yes(Arc::new(new_parquet_exec)))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "parquet_prefetch"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Creates a [`PrefetchReader`] for every scanned file.
+#[derive(Debug)]
+struct PrefetchReaderFactory {
+    object_store: Arc<dyn ObjectStore>,
+    depth: usize,
+}
+
+impl ParquetFileReaderFactory for PrefetchReaderFactory {
+    fn create_reader(
+        &self,
+        partition_index: usize,
+        file_meta: FileMeta,
+        metadata_size_hint: Option<usize>,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> Result<Box<dyn AsyncFileReader + Send>> {
+        let file_metrics =
+            ParquetFileMetrics::new(partition_index, file_meta.location().as_ref(), metrics);
+
+        Ok(Box::new(PrefetchReader::new(
+            Arc::clone(&self.object_store),
+            file_meta,
+            metadata_size_hint,
+            self.depth,
+            file_metrics,
+        )))
+    }
+}
+
+/// Column chunks of a row group that are fetched in the background.
+#[derive(Debug)]
+struct Prefetch {
+    row_group: usize,
+    ranges: Vec<Range<usize>>,
+    handle: JoinHandle<Result<Vec<Bytes>, object_store::Error>>,
+}
+
+/// [`AsyncFileReader`] that, whenever the column chunks of a row group are requested, starts fetching the same
+/// columns of the next `depth` row groups.
+///
+/// A prefetched row group is only used if the parquet reader later requests exactly the prefetched byte ranges, which
+/// is the case unless pages are skipped using the page index. Otherwise the data is fetched on demand, as without
+/// prefetching.
+#[derive(Debug)]
+struct PrefetchReader {
+    object_store: Arc<dyn ObjectStore>,
+    location: Path,
+    inner: ParquetObjectReader,
+    metadata: Option<Arc<ParquetMetaData>>,
+    depth: usize,
+    prefetched: VecDeque<Prefetch>,
+    file_metrics: ParquetFileMetrics,
+}
+
+impl PrefetchReader {
+    fn new(
+        object_store: Arc<dyn ObjectStore>,
+        file_meta: FileMeta,
+        metadata_size_hint: Option<usize>,
+        depth: usize,
+        file_metrics: ParquetFileMetrics,
+    ) -> Self {
+        let location = file_meta.location().clone();
+        let mut inner = ParquetObjectReader::new(Arc::clone(&object_store), file_meta.object_meta);
+        if let Some(hint) = metadata_size_hint {
+            inner = inner.with_footer_size_hint(hint);
+        }
+
+        Self {
+            object_store,
+            location,
+            inner,
+            metadata: None,
+            depth,
+            prefetched: VecDeque::new(),
+            file_metrics,
+        }
+    }
+
+    /// Take the prefetch for `row_group` if it covers exactly `ranges`, dropping prefetches of earlier row groups
+    /// (that were skipped by the reader).
+    fn take_prefetched(
+        &mut self,
+        row_group: usize,
+        ranges: &[Range<usize>],
+    ) -> Option<JoinHandle<Result<Vec<Bytes>, object_store::Error>>> {
+        while let Some(prefetch) = self.prefetched.front() {
+            if prefetch.row_group > row_group {
+                return None;
+            }
+
+            let prefetch = self.prefetched.pop_front().expect("just checked");
+            if prefetch.row_group == row_group && prefetch.ranges == ranges {
+                return Some(prefetch.handle);
+            }
+            prefetch.handle.abort();
+        }
+        None
+    }
+
+    /// Start fetching `columns` of the row groups following `row_group`, up to the configured depth.
+    fn prefetch_after(&mut self, metadata: &ParquetMetaData, row_group: usize, columns: &[usize]) {
+        let mut next = self
+            .prefetched
+            .back()
+            .map(|prefetch| prefetch.row_group)
+            .unwrap_or(row_group)
+            + 1;
+
+        while self.prefetched.len() < self.depth && next < metadata.num_row_groups() {
+            let row_group_meta = metadata.row_group(next);
+            let ranges = columns
+                .iter()
+                .map(|idx| column_range(row_group_meta.column(*idx)))
+                .collect::<Vec<_>>();
+
+            let object_store = Arc::clone(&self.object_store);
+            let location = self.location.clone();
+            let fetch_ranges = ranges.clone();
+            let handle =
+                tokio::spawn(
+                    async move { object_store.get_ranges(&location, &fetch_ranges).await },
+                );
+
+            self.prefetched.push_back(Prefetch {
+                row_group: next,
+                ranges,
+                handle,
+            });
+            next += 1;
+        }
+    }
+}
+
+impl Drop for PrefetchReader {
+    fn drop(&mut self) {
+        for prefetch in &self.prefetched {
+            prefetch.handle.abort();
+        }
+    }
+}
+
+impl AsyncFileReader for PrefetchReader {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, Result<Bytes, ParquetError>> {
+        self.file_metrics.bytes_scanned.add(range.len());
+        self.inner.get_bytes(range)
+    }
+
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, Result<Vec<Bytes>, ParquetError>> {
+        let total = ranges.iter().map(|r| r.len()).sum::<usize>();
+        self.file_metrics.bytes_scanned.add(total);
+
+        async move {
+            let located = self
+                .metadata
+                .clone()
+                .and_then(|metadata| locate(&metadata, &ranges).map(|loc| (metadata, loc)));
+
+            let prefetched = located
+                .as_ref()
+                .and_then(|(_, (row_group, _))| self.take_prefetched(*row_group, &ranges));
+            if let Some((metadata, (row_group, columns))) = &located {
+                self.prefetch_after(metadata, *row_group, columns);
+            }
+
+            let data = match prefetched {
+                Some(handle) => handle
+                    .await
+                    .map_err(|e| ParquetError::External(Box::new(e)))?,
+                None => self.object_store.get_ranges(&self.location, &ranges).await,
+            };
+            data.map_err(|e| ParquetError::External(Box::new(e)))
+        }
+        .boxed()
+    }
+
+    fn get_metadata(&mut self) -> BoxFuture<'_, Result<Arc<ParquetMetaData>, ParquetError>> {
+        async move {
+            let metadata = self.inner.get_metadata().await?;
+            self.metadata = Some(Arc::clone(&metadata));
+            Ok(metadata)
+        }
+        .boxed()
+    }
+}
+
+/// Byte range of a column chunk within the file.
+fn column_range(column: &ColumnChunkMetaData) -> Range<usize> {
+    let (start, length) = column.byte_range();
+    start as usize..(start + length) as usize
+}
+
+/// Find the row group and the columns within it that `ranges` were requested for.
+fn locate(metadata: &ParquetMetaData, ranges: &[Range<usize>]) -> Option<(usize, Vec<usize>)> {
+    let first = ranges.first()?;
+    let contains = |outer: &Range<usize>, inner: &Range<usize>| {
+        outer.start <= inner.start && inner.end <= outer.end
+    };
+
+    metadata
+        .row_groups()
+        .iter()
+        .enumerate()
+        .find_map(|(row_group, row_group_meta)| {
+            let column_ranges = row_group_meta
+                .columns()
+                .iter()
+                .map(column_range)
+                .collect::<Vec<_>>();
+            if !column_ranges.iter().any(|c| contains(c, first)) {
+                return None;
+            }
+
+            let columns = column_ranges
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| ranges.iter().any(|r| contains(c, r)))
+                .map(|(idx, _)| idx)
+                .collect();
+            Some((row_group, columns))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{ArrayRef, Int64Array, StringArray},
+        record_batch::RecordBatch,
+    };
+    use datafusion::parquet::{
+        arrow::{ArrowWriter, ParquetRecordBatchStreamBuilder},
+        file::properties::WriterProperties,
+    };
+    use futures::TryStreamExt;
+    use object_store::{memory::InMemory, ObjectMeta};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_prefetch_reader() {
+        let (object_store, object_meta, batch) = parquet_file(3).await;
+
+        let mut reader = new_reader(Arc::clone(&object_store), object_meta, 2);
+        let metadata = reader.get_metadata().await.unwrap();
+        assert_eq!(metadata.num_row_groups(), 3);
+
+        // all columns of the first row group
+        let ranges = metadata
+            .row_group(0)
+            .columns()
+            .iter()
+            .map(column_range)
+            .collect::<Vec<_>>();
+        assert_eq!(locate(&metadata, &ranges), Some((0, vec![0, 1])));
+        reader.get_byte_ranges(ranges).await.unwrap();
+        assert_eq!(
+            reader
+                .prefetched
+                .iter()
+                .map(|p| p.row_group)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        // only the second column of the last row group, skipping the prefetched second row group
+        let ranges = vec![column_range(metadata.row_group(2).column(1))];
+        assert_eq!(locate(&metadata, &ranges), Some((2, vec![1])));
+        reader.get_byte_ranges(ranges).await.unwrap();
+        assert!(reader.prefetched.is_empty());
+
+        // the data read through the prefetching reader is complete
+        let reader = new_reader(
+            Arc::clone(&object_store),
+            object_store
+                .head(&Path::from("file.parquet"))
+                .await
+                .unwrap(),
+            1,
+        );
+        let batches = ParquetRecordBatchStreamBuilder::new(reader)
+            .await
+            .unwrap()
+            .build()
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            arrow::compute::concat_batches(&batch.schema(), &batches).unwrap(),
+            batch
+        );
+    }
+
+    #[tokio::test]
+    async fn test_locate_unknown_range() {
+        let (object_store, object_meta, _) = parquet_file(1).await;
+        let metadata = new_reader(object_store, object_meta, 1)
+            .get_metadata()
+            .await
+            .unwrap();
+
+        assert_eq!(locate(&metadata, &[]), None);
+        assert_eq!(locate(&metadata, &[0..4]), None);
+    }
+
+    /// Write a parquet file with two columns and `row_groups` row groups of two rows each.
+    async fn parquet_file(row_groups: usize) -> (Arc<dyn ObjectStore>, ObjectMeta, RecordBatch) {
+        let n = row_groups as i64 * 2;
+        let batch = RecordBatch::try_from_iter([
+            (
+                "i",
+                Arc::new(Int64Array::from_iter_values(0..n)) as ArrayRef,
+            ),
+            (
+                "s",
+                Arc::new(StringArray::from_iter_values(
+                    (0..n).map(|i| format!("value {i}")),
+                )) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut data = vec![];
+        let mut writer = ArrowWriter::try_new(&mut data, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let location = Path::from("file.parquet");
+        object_store.put(&location, data.into()).await.unwrap();
+        let object_meta = object_store.head(&location).await.unwrap();
+
+        (object_store, object_meta, batch)
+    }
+
+    fn new_reader(
+        object_store: Arc<dyn ObjectStore>,
+        object_meta: ObjectMeta,
+        depth: usize,
+    ) -> PrefetchReader {
+        let file_metrics = ParquetFileMetrics::new(
+            0,
+            object_meta.location.as_ref(),
+            &ExecutionPlanMetricsSet::new(),
+        );
+        PrefetchReader::new(
+            object_store,
+            FileMeta::from(object_meta),
+            None,
+            depth,
+            file_metrics,
+        )
+    }
+}
@@ -17,9 +17,16 @@ use datafusion::{
     },
 };
 
-use crate::provider::DeduplicateExec;
+use crate::{
+    provider::{DeduplicateExec, RecordBatchesExec, ScanPredicate},
+    CHUNK_ORDER_COLUMN_NAME,
+};
 
 /// Push down predicates.
+///
+/// Predicates pushed into a [`ParquetExec`] are only used for pruning and late materialization within the Parquet
+/// reader and are kept in a filter above the scan. Predicates pushed into a [`RecordBatchesExec`] are applied exactly
+/// during the scan, so the filter is removed.
 #[derive(Debug, Default)]
 pub struct PredicatePushdown;
 
@@ -33,6 +40,12 @@ impl PhysicalOptimizerRule for PredicatePushdown {
             let plan_any = plan.as_any();
 
             if let Some(filter_exec) = plan_any.downcast_ref::<FilterExec>() {
+                // Predicates without any column (e.g. row sampling using `random()`) cannot be used for pruning
+                // and are not necessarily deterministic, so they are evaluated where they were planned.
+                if collect_columns(filter_exec.predicate()).is_empty() {
+                    return Ok(Transformed::No(plan));
+                }
+
                 let mut children = filter_exec.children();
                 assert_eq!(children.len(), 1);
                 let child = children.remove(0);
@@ -77,6 +90,31 @@ impl PhysicalOptimizerRule for PredicatePushdown {
                         )),
                     )?);
                     return Ok(Transformed::Yes(new_node));
+                } else if let Some(child_record_batches) =
+                    child_any.downcast_ref::<RecordBatchesExec>()
+                {
+                    // the chunk order column is virtual and not visible to the scan predicate
+                    let uses_chunk_order = collect_columns(filter_exec.predicate())
+                        .iter()
+                        .any(|c| c.name() == CHUNK_ORDER_COLUMN_NAME);
+
+                    if !uses_chunk_order {
+                        let mut predicate = ScanPredicate::try_new(
+                            Arc::clone(filter_exec.predicate()),
+                            &child.schema(),
+                        )?;
+                        if let Some(existing) = child_record_batches.predicate() {
+                            predicate = existing.and(&predicate)?;
+                        }
+
+                        let new_node = RecordBatchesExec::new(
+                            child_record_batches.chunks().cloned(),
+                            child.schema(),
+                            child_record_batches.output_sort_key_memo().cloned(),
+                        )
+                        .with_predicate(Some(predicate));
+                        return Ok(Transformed::Yes(Arc::new(new_node)));
+                    }
                 } else if let Some(child_dedup) = child_any.downcast_ref::<DeduplicateExec>() {
                     let dedup_cols = child_dedup.sort_columns();
                     let (pushdown, no_pushdown): (Vec<_>, Vec<_>) =
@@ -344,6 +382,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_record_batches() {
+        let schema = schema();
+        let plan = Arc::new(
+            FilterExec::try_new(
+                predicate_mixed(&schema),
+                Arc::new(
+                    RecordBatchesExec::new(vec![], Arc::clone(&schema), None).with_predicate(Some(
+                        ScanPredicate::try_new(predicate_tag(&schema), &schema).unwrap(),
+                    )),
+                ),
+            )
+            .unwrap(),
+        );
+        let opt = PredicatePushdown;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " FilterExec: tag1@0 = field@2"
+          - "   RecordBatchesExec: chunks=0, predicate=tag1@0 = foo"
+        output:
+          Ok:
+            - " RecordBatchesExec: chunks=0, predicate=tag1@0 = foo AND tag1@0 = field@1"
+        "###
+        );
+    }
+
     #[test]
     fn test_dedup_no_pushdown() {
         let schema = schema();
@@ -448,6 +515,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_no_columns_no_pushdown() {
+        let schema = schema();
+        let plan = Arc::new(
+            FilterExec::try_new(
+                predicate_other(),
+                Arc::new(DeduplicateExec::new(
+                    Arc::new(EmptyExec::new(true, Arc::clone(&schema))),
+                    sort_expr(&schema),
+                    false,
+                )),
+            )
+            .unwrap(),
+        );
+        let opt = PredicatePushdown;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " FilterExec: true"
+          - "   DeduplicateExec: [tag1@0 ASC,tag2@1 ASC]"
+          - "     EmptyExec: produce_one_row=true"
+        output:
+          Ok:
+            - " FilterExec: true"
+            - "   DeduplicateExec: [tag1@0 ASC,tag2@1 ASC]"
+            - "     EmptyExec: produce_one_row=true"
+        "###
+        );
+    }
+
     fn schema() -> SchemaRef {
         Arc::new(Schema::new(vec![
             Field::new("tag1", DataType::Utf8, true),
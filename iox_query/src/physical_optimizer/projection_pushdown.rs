@@ -236,7 +236,9 @@ impl PhysicalOptimizerRule for ProjectionPushdown {
                         child_recordbatches.chunks().cloned(),
                         Arc::new(child_recordbatches.schema().project(&column_indices)?),
                         child_recordbatches.output_sort_key_memo().cloned(),
-                    );
+                    )
+                    .with_predicate(child_recordbatches.predicate().cloned())
+                    .with_limit(child_recordbatches.limit());
                     return Ok(Transformed::Yes(Arc::new(new_child)));
                 }
             }
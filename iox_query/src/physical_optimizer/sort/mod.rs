@@ -4,3 +4,4 @@
 
 pub mod parquet_sortness;
 pub mod push_sort_through_union;
+pub mod time_desc_scan;
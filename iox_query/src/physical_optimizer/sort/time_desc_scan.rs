@@ -0,0 +1,397 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use data_types::TimestampMinMax;
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    datasource::physical_plan::ParquetExec,
+    error::Result,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        coalesce_batches::CoalesceBatchesExec, empty::EmptyExec, expressions::Column,
+        filter::FilterExec, projection::ProjectionExec, repartition::RepartitionExec,
+        sorts::sort::SortExec, union::UnionExec, ExecutionPlan,
+    },
+};
+use schema::TIME_COLUMN_NAME;
+
+use crate::{
+    provider::{
+        overlap::timestamp_min_max, DeduplicateExec, PartitionedFileExt, ProgressiveEvalExec,
+        RecordBatchesExec,
+    },
+    QueryChunk,
+};
+
+/// Answers `ORDER BY time DESC LIMIT n` queries by reading the most recent data first.
+///
+/// If the inputs of a [`UnionExec`] under a [`SortExec`] on `time DESC` with a fetch do not overlap in time, each
+/// input is sorted on its own and the sorted inputs are read one after another, starting with the most recent one,
+/// until `n` rows are found. So a plan like this
+///
+/// ```text
+/// SortExec: fetch=1, expr=[time@3 DESC]
+///   UnionExec
+///     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]
+///       UnionExec
+///         RecordBatchesExec: chunks=2
+///     UnionExec
+///       RecordBatchesExec: chunks=1
+/// ```
+///
+/// where the [`RecordBatchesExec`] with one chunk holds the most recent data becomes:
+///
+/// ```text
+/// ProgressiveEvalExec: fetch=1, expr=[time@3 DESC]
+///   SortExec: fetch=1, expr=[time@3 DESC]
+///     UnionExec
+///       RecordBatchesExec: chunks=1
+///   SortExec: fetch=1, expr=[time@3 DESC]
+///     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]
+///       UnionExec
+///         RecordBatchesExec: chunks=2
+/// ```
+///
+/// The older inputs are never executed if the most recent ones hold at least `n` rows. Instead of a single sort over
+/// all chunks, only `n` rows per input are kept.
+///
+/// The time range of each input is derived from the statistics of its chunks, so this must run after the de-dup rules
+/// (which split de-duplication by time) and after [`ProjectionPushdown`] and [`PredicatePushdown`] (which move
+/// projections and filters into the inputs of the union).
+///
+///
+/// [`PredicatePushdown`]: crate::physical_optimizer::predicate_pushdown::PredicatePushdown
+/// [`ProjectionPushdown`]: crate::physical_optimizer::projection_pushdown::ProjectionPushdown
+#[derive(Debug, Default)]
+pub struct TimeDescScan;
+
+impl PhysicalOptimizerRule for TimeDescScan {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            let Some(sort_exec) = plan.as_any().downcast_ref::<SortExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+
+            let Some(fetch) = sort_exec.fetch() else {
+                // all rows are needed anyways, sorting in parallel is faster
+                return Ok(Transformed::No(plan));
+            };
+            if sort_exec.preserve_partitioning() || !is_time_desc(sort_exec) {
+                return Ok(Transformed::No(plan));
+            }
+
+            let Some(union_exec) = sort_exec.input().as_any().downcast_ref::<UnionExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+
+            let Some(inputs) = union_exec
+                .inputs()
+                .iter()
+                .map(|input| time_range(input.as_ref()).map(|range| (range, Arc::clone(input))))
+                .collect::<Option<Vec<_>>>()
+            else {
+                return Ok(Transformed::No(plan));
+            };
+
+            // inputs without any rows can be skipped
+            let mut inputs = inputs
+                .into_iter()
+                .filter_map(|(range, input)| range.map(|range| (range, input)))
+                .collect::<Vec<_>>();
+            if inputs.len() < 2 {
+                return Ok(Transformed::No(plan));
+            }
+
+            // most recent input first
+            inputs.sort_by_key(|(range, _)| Reverse(range.max));
+            let overlapping = inputs.windows(2).any(|pair| pair[0].0.min < pair[1].0.max);
+            if overlapping {
+                return Ok(Transformed::No(plan));
+            }
+
+            let inputs = inputs
+                .into_iter()
+                .map(|(_, input)| {
+                    Arc::new(
+                        SortExec::new(sort_exec.expr().to_vec(), input).with_fetch(Some(fetch)),
+                    ) as _
+                })
+                .collect();
+
+            Ok(Transformed::Yes(Arc::new(ProgressiveEvalExec::new(
+                inputs,
+                sort_exec.schema(),
+                sort_exec.expr().to_vec(),
+                Some(fetch),
+            ))))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "time_desc_scan"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns true if `sort_exec` only sorts on the time column, descending.
+fn is_time_desc(sort_exec: &SortExec) -> bool {
+    match sort_exec.expr() {
+        [sort_expr] => {
+            sort_expr.options.descending
+                && sort_expr
+                    .expr
+                    .as_any()
+                    .downcast_ref::<Column>()
+                    .map_or(false, |column| column.name() == TIME_COLUMN_NAME)
+        }
+        _ => false,
+    }
+}
+
+/// Returns the time range of the rows of `plan` from the statistics of the chunks it scans, or `Some(None)` if `plan`
+/// produces no rows.
+///
+/// Returns `None` if the range is unknown, e.g. because the plan contains nodes that may change the time column.
+fn time_range(plan: &dyn ExecutionPlan) -> Option<Option<TimestampMinMax>> {
+    let mut range: Option<TimestampMinMax> = None;
+    for chunk in scanned_chunks(plan)? {
+        let chunk_range = timestamp_min_max(chunk.as_ref())?;
+        range = Some(match range {
+            Some(range) => TimestampMinMax::new(
+                range.min.min(chunk_range.min),
+                range.max.max(chunk_range.max),
+            ),
+            None => chunk_range,
+        });
+    }
+    Some(range)
+}
+
+/// Returns the chunks scanned by `plan`, if every node of `plan` passes the time column through unchanged.
+fn scanned_chunks(plan: &dyn ExecutionPlan) -> Option<Vec<Arc<dyn QueryChunk>>> {
+    let plan_any = plan.as_any();
+
+    if let Some(record_batches_exec) = plan_any.downcast_ref::<RecordBatchesExec>() {
+        Some(record_batches_exec.chunks().cloned().collect())
+    } else if let Some(parquet_exec) = plan_any.downcast_ref::<ParquetExec>() {
+        parquet_exec
+            .base_config()
+            .file_groups
+            .iter()
+            .flatten()
+            .map(|file| {
+                file.extensions
+                    .as_ref()
+                    .and_then(|any| any.downcast_ref::<PartitionedFileExt>())
+                    .map(|ext| Arc::clone(&ext.chunk))
+            })
+            .collect()
+    } else if let Some(empty_exec) = plan_any.downcast_ref::<EmptyExec>() {
+        (!empty_exec.produce_one_row()).then(Vec::new)
+    } else if let Some(projection_exec) = plan_any.downcast_ref::<ProjectionExec>() {
+        let time_passed_through = projection_exec.expr().iter().any(|(expr, name)| {
+            name == TIME_COLUMN_NAME
+                && expr
+                    .as_any()
+                    .downcast_ref::<Column>()
+                    .map_or(false, |column| column.name() == TIME_COLUMN_NAME)
+        });
+        if !time_passed_through {
+            return None;
+        }
+        scanned_chunks(projection_exec.input().as_ref())
+    } else if plan_any.is::<UnionExec>()
+        || plan_any.is::<DeduplicateExec>()
+        || plan_any.is::<FilterExec>()
+        || plan_any.is::<SortExec>()
+        || plan_any.is::<CoalesceBatchesExec>()
+        || plan_any.is::<RepartitionExec>()
+    {
+        // these only remove or reorder rows
+        let mut chunks = vec![];
+        for child in plan.children() {
+            chunks.extend(scanned_chunks(child.as_ref())?);
+        }
+        Some(chunks)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::compute::SortOptions;
+    use datafusion::physical_plan::expressions::PhysicalSortExpr;
+
+    use crate::{
+        physical_optimizer::{dedup::test_util::dedup_plan, test_util::OptimizationTest},
+        provider::chunks_to_physical_nodes,
+        test::{format_execution_plan, TestChunk},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_non_overlapping() {
+        let plan = sort_plan(
+            vec![
+                scan(chunk(1, 10, 20)),
+                scan(chunk(2, 50, 60)),
+                scan(chunk(3, 30, 40)),
+            ],
+            true,
+            Some(1),
+        );
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, TimeDescScan),
+            @r###"
+        ---
+        input:
+          - " SortExec: fetch=1, expr=[time@3 DESC]"
+          - "   UnionExec"
+          - "     UnionExec"
+          - "       RecordBatchesExec: chunks=1"
+          - "     UnionExec"
+          - "       RecordBatchesExec: chunks=1"
+          - "     UnionExec"
+          - "       RecordBatchesExec: chunks=1"
+        output:
+          Ok:
+            - " ProgressiveEvalExec: fetch=1, expr=[time@3 DESC]"
+            - "   SortExec: fetch=1, expr=[time@3 DESC]"
+            - "     UnionExec"
+            - "       RecordBatchesExec: chunks=1"
+            - "   SortExec: fetch=1, expr=[time@3 DESC]"
+            - "     UnionExec"
+            - "       RecordBatchesExec: chunks=1"
+            - "   SortExec: fetch=1, expr=[time@3 DESC]"
+            - "     UnionExec"
+            - "       RecordBatchesExec: chunks=1"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        let chunk1 = chunk(1, 10, 20);
+        let schema = chunk1.schema().clone();
+        let plan = sort_plan(
+            vec![
+                dedup_plan(schema, vec![chunk1, chunk(2, 15, 25)]),
+                scan(chunk(3, 30, 40)),
+            ],
+            true,
+            Some(10),
+        );
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, TimeDescScan),
+            @r###"
+        ---
+        input:
+          - " SortExec: fetch=10, expr=[time@3 DESC]"
+          - "   UnionExec"
+          - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "       UnionExec"
+          - "         RecordBatchesExec: chunks=2"
+          - "     UnionExec"
+          - "       RecordBatchesExec: chunks=1"
+        output:
+          Ok:
+            - " ProgressiveEvalExec: fetch=10, expr=[time@3 DESC]"
+            - "   SortExec: fetch=10, expr=[time@3 DESC]"
+            - "     UnionExec"
+            - "       RecordBatchesExec: chunks=1"
+            - "   SortExec: fetch=10, expr=[time@3 DESC]"
+            - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "       UnionExec"
+            - "         RecordBatchesExec: chunks=2"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let plan = sort_plan(
+            vec![scan(chunk(1, 10, 30)), scan(chunk(2, 20, 40))],
+            true,
+            Some(1),
+        );
+        assert_unchanged(plan);
+    }
+
+    #[test]
+    fn test_ascending() {
+        let plan = sort_plan(
+            vec![scan(chunk(1, 10, 20)), scan(chunk(2, 30, 40))],
+            false,
+            Some(1),
+        );
+        assert_unchanged(plan);
+    }
+
+    #[test]
+    fn test_no_fetch() {
+        let plan = sort_plan(
+            vec![scan(chunk(1, 10, 20)), scan(chunk(2, 30, 40))],
+            true,
+            None,
+        );
+        assert_unchanged(plan);
+    }
+
+    #[test]
+    fn test_no_stats() {
+        let no_stats = TestChunk::new("table")
+            .with_id(2)
+            .with_tag_column("tag1")
+            .with_tag_column("tag2")
+            .with_i64_field_column("field")
+            .with_time_column();
+        let plan = sort_plan(vec![scan(chunk(1, 10, 20)), scan(no_stats)], true, Some(1));
+        assert_unchanged(plan);
+    }
+
+    fn chunk(id: u128, min: i64, max: i64) -> TestChunk {
+        TestChunk::new("table")
+            .with_id(id)
+            .with_tag_column("tag1")
+            .with_tag_column("tag2")
+            .with_i64_field_column("field")
+            .with_time_column_with_full_stats(Some(min), Some(max), 10, None)
+    }
+
+    fn scan(chunk: TestChunk) -> Arc<dyn ExecutionPlan> {
+        let schema = chunk.schema().as_arrow();
+        chunks_to_physical_nodes(&schema, None, vec![Arc::new(chunk) as _], 2)
+    }
+
+    fn sort_plan(
+        inputs: Vec<Arc<dyn ExecutionPlan>>,
+        descending: bool,
+        fetch: Option<usize>,
+    ) -> Arc<dyn ExecutionPlan> {
+        let union = Arc::new(UnionExec::new(inputs));
+        let sort_expr = PhysicalSortExpr {
+            expr: Arc::new(Column::new_with_schema(TIME_COLUMN_NAME, &union.schema()).unwrap()),
+            options: SortOptions {
+                descending,
+                nulls_first: descending,
+            },
+        };
+        Arc::new(SortExec::new(vec![sort_expr], union).with_fetch(fetch))
+    }
+
+    #[track_caller]
+    fn assert_unchanged(plan: Arc<dyn ExecutionPlan>) {
+        let input = format_execution_plan(&plan);
+        let test = OptimizationTest::new(plan, TimeDescScan);
+        assert_eq!(format_execution_plan(test.output_plan().unwrap()), input);
+    }
+}
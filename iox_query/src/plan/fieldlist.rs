@@ -1,12 +1,15 @@
 use datafusion::logical_expr::LogicalPlan;
 
 use crate::exec::fieldlist::Field;
-use std::collections::BTreeMap;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::Arc,
+};
 
 pub type FieldSet = BTreeMap<String, Field>;
 
 /// A plan which produces a logical set of Fields (e.g. InfluxDB
-/// Fields with name, and data type, and last_timestamp).
+/// Fields with name, data type, last_timestamp, and row and null counts).
 ///
 /// known_values has a set of pre-computed values to be merged with
 /// the extra_plans.
@@ -46,12 +49,58 @@ impl FieldListPlan {
     /// Append the other plan to ourselves
     pub fn append_other(mut self, other: Self) -> Self {
         self.extra_plans.extend(other.extra_plans);
-        self.known_values.extend(other.known_values);
+        for field in other.known_values.into_values() {
+            self.append_field(field);
+        }
         self
     }
 
-    /// Append a single field to the known set of fields in this builder
+    /// Append a single field to the known set of fields in this builder,
+    /// merging it into any known field of the same name
     pub fn append_field(&mut self, s: Field) {
-        self.known_values.insert(s.name.clone(), s);
+        match self.known_values.entry(s.name.clone()) {
+            Entry::Occupied(mut o) => o.get_mut().merge(&s),
+            Entry::Vacant(v) => {
+                v.insert(s);
+            }
+        }
+    }
+}
+
+/// A plan which produces the Fields of several measurements, grouped by
+/// measurement name, in a single execution.
+#[derive(Debug, Default)]
+pub struct MeasurementFieldListPlan {
+    /// The plan producing the fields of each measurement
+    pub measurements: BTreeMap<Arc<str>, FieldListPlan>,
+}
+
+impl MeasurementFieldListPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a plan producing (some of) the fields of `measurement`
+    pub fn append(&mut self, measurement: Arc<str>, plan: FieldListPlan) {
+        let existing = self.measurements.entry(measurement).or_default();
+        *existing = std::mem::take(existing).append_other(plan);
+    }
+
+    /// Append a single field to the known set of fields of `measurement`
+    pub fn append_field(&mut self, measurement: Arc<str>, field: Field) {
+        self.measurements
+            .entry(measurement)
+            .or_default()
+            .append_field(field);
+    }
+}
+
+impl From<MeasurementFieldListPlan> for FieldListPlan {
+    /// Create a plan producing the fields of all measurements of `plan`
+    /// combined
+    fn from(plan: MeasurementFieldListPlan) -> Self {
+        plan.measurements
+            .into_values()
+            .fold(Self::new(), Self::append_other)
     }
 }
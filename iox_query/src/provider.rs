@@ -17,7 +17,7 @@ use datafusion::{
         expressions::col as physical_col, filter::FilterExec, projection::ProjectionExec,
         ExecutionPlan,
     },
-    prelude::Expr,
+    prelude::{lit, random, Expr},
     sql::TableReference,
 };
 use observability_deps::tracing::trace;
@@ -25,6 +25,7 @@ use schema::{sort::SortKey, Schema};
 
 use crate::{
     chunk_order_field,
+    config::IoxConfigExt,
     util::{arrow_sort_key_exprs, df_physical_expr},
     QueryChunk, CHUNK_ORDER_COLUMN_NAME,
 };
@@ -35,12 +36,14 @@ mod adapter;
 mod deduplicate;
 pub mod overlap;
 mod physical;
+mod progressive_eval;
 mod record_batch_exec;
 pub use self::overlap::group_potential_duplicates;
 pub use deduplicate::{DeduplicateExec, RecordBatchDeduplicator};
-pub(crate) use physical::{chunks_to_physical_nodes, PartitionedFileExt};
+pub(crate) use physical::{chunks_to_physical_nodes, scanned_column_name, PartitionedFileExt};
+pub(crate) use progressive_eval::ProgressiveEvalExec;
 
-pub(crate) use record_batch_exec::RecordBatchesExec;
+pub(crate) use record_batch_exec::{RecordBatchesExec, ScanPredicate};
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -266,6 +269,24 @@ impl TableProvider for ChunkTableProvider {
             plan
         };
 
+        // Randomly sample rows for approximate queries, AFTER de-dup and filters so that each row has the same chance
+        // to be selected.
+        let sample_fraction = ctx
+            .config()
+            .options()
+            .extensions
+            .get::<IoxConfigExt>()
+            .map(|iox_config| iox_config.sample_fraction)
+            .unwrap_or(1.0);
+        let plan = if sample_fraction < 1.0 {
+            Arc::new(FilterExec::try_new(
+                df_physical_expr(plan.as_ref(), random().lt(lit(sample_fraction)))?,
+                plan,
+            )?)
+        } else {
+            plan
+        };
+
         // Project at last because it removes columns and hence other operations may fail. Projection pushdown will
         // optimize that later.
         // Always project because we MUST make sure that chunk order col doesn't leak to the user or to our parquet
@@ -416,6 +437,46 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn provider_scan_sampled() {
+        let table_name = "t";
+        let chunk = Arc::new(
+            TestChunk::new(table_name)
+                .with_id(1)
+                .with_tag_column("tag1")
+                .with_f64_field_column("field")
+                .with_time_column(),
+        ) as Arc<dyn QueryChunk>;
+        let schema = chunk.schema().clone();
+
+        let ctx = IOxSessionContext::with_testing();
+        ctx.set_config_option("iox.sample_fraction", "0.1")
+            .await
+            .unwrap();
+        let state = ctx.inner().state();
+
+        let provider = ProviderBuilder::new(Arc::from(table_name), schema)
+            .add_chunk(chunk)
+            .build()
+            .unwrap();
+
+        // rows are sampled after de-dup and filters
+        let expr = vec![col("tag1").eq(lit("foo"))];
+        let plan = provider.scan(&state, None, &expr, None).await.unwrap();
+        insta::assert_yaml_snapshot!(
+            format_execution_plan(&plan),
+            @r###"
+        ---
+        - " ProjectionExec: expr=[field@0 as field, tag1@1 as tag1, time@2 as time]"
+        - "   FilterExec: random() < 0.1"
+        - "     FilterExec: tag1@1 = foo"
+        - "       DeduplicateExec: [tag1@1 ASC,time@2 ASC]"
+        - "         UnionExec"
+        - "           RecordBatchesExec: chunks=1"
+        "###
+        );
+    }
+
     #[tokio::test]
     async fn provider_scan_no_dedup() {
         let table_name = "t";
@@ -6,6 +6,7 @@ use std::task::{Context, Poll};
 
 use arrow::{
     array::new_null_array,
+    compute::cast,
     datatypes::{DataType, SchemaRef},
     record_batch::RecordBatch,
 };
@@ -14,6 +15,7 @@ use datafusion::physical_plan::{
 };
 use datafusion::{error::DataFusionError, scalar::ScalarValue};
 use futures::Stream;
+use schema::{merge::TypePromotion, InfluxFieldType};
 
 /// Schema creation / validation errors.
 #[allow(clippy::enum_variant_names)]
@@ -61,6 +63,11 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 /// stream would append a column of B / nulls to each record batch
 /// that flowed through it and create a constant column D.
 ///
+/// Input field columns with a narrower type than the output (e.g. an
+/// integer field of a chunk written before the field was promoted to a
+/// float) are cast to the output type if the [`TypePromotion`] policy
+/// allows it.
+///
 /// ```text
 ///
 ///                       ┌────────────────┐                         ┌───────────────────────────────┐
@@ -108,11 +115,13 @@ impl SchemaAdapterStream {
     ///
     /// If the underlying stream produces columns that DO NOT appear
     /// in the output schema, or are different types than the output
-    /// schema, an error will be produced.
+    /// schema that `type_promotion` does not promote to the output type,
+    /// an error will be produced.
     pub(crate) fn try_new(
         input: SendableRecordBatchStream,
         output_schema: SchemaRef,
         virtual_columns: &HashMap<&str, ScalarValue>,
+        type_promotion: TypePromotion,
         baseline_metrics: BaselineMetrics,
     ) -> Result<Self> {
         // record this setup time
@@ -133,7 +142,15 @@ impl SchemaAdapterStream {
                     .map(|(idx, _)| idx);
 
                 if let Some(input_field_index) = input_field_index {
-                    ColumnMapping::FromInput(input_field_index)
+                    let input_type = input_schema.field(input_field_index).data_type();
+                    if is_promotion(type_promotion, input_type, output_field.data_type()) {
+                        ColumnMapping::CastInput(
+                            input_field_index,
+                            output_field.data_type().clone(),
+                        )
+                    } else {
+                        ColumnMapping::FromInput(input_field_index)
+                    }
                 } else if let Some(value) = virtual_columns.get(output_field.name().as_str()) {
                     ColumnMapping::Virtual(value.clone())
                 } else {
@@ -166,6 +183,15 @@ impl SchemaAdapterStream {
                         .fail();
                     }
                 }
+                ColumnMapping::CastInput(input_index, _) => {
+                    let input_field = input_schema.field(*input_index);
+                    if virtual_columns.contains_key(input_field.name().as_str()) {
+                        return InternalColumnBothInInputAndVirtualSnafu {
+                            field_name: input_field.name().clone(),
+                        }
+                        .fail();
+                    }
+                }
                 ColumnMapping::MakeNull(_) => {
                     if !output_field.is_nullable() {
                         return InternalColumnNotNullableSnafu {
@@ -204,11 +230,16 @@ impl SchemaAdapterStream {
             .mappings
             .iter()
             .map(|mapping| match mapping {
-                ColumnMapping::FromInput(input_index) => Arc::clone(batch.column(*input_index)),
-                ColumnMapping::MakeNull(data_type) => new_null_array(data_type, batch.num_rows()),
-                ColumnMapping::Virtual(value) => value.to_array_of_size(batch.num_rows()),
+                ColumnMapping::FromInput(input_index) => Ok(Arc::clone(batch.column(*input_index))),
+                ColumnMapping::CastInput(input_index, data_type) => {
+                    Ok(cast(batch.column(*input_index), data_type)?)
+                }
+                ColumnMapping::MakeNull(data_type) => {
+                    Ok(new_null_array(data_type, batch.num_rows()))
+                }
+                ColumnMapping::Virtual(value) => Ok(value.to_array_of_size(batch.num_rows())),
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, DataFusionError>>()?;
 
         Ok(RecordBatch::try_new(
             Arc::clone(&self.output_schema),
@@ -247,6 +278,10 @@ enum ColumnMapping {
     /// Output column is found at `<index>` column of the input schema
     FromInput(usize),
 
+    /// Output column is found at `<index>` column of the input schema,
+    /// which has a narrower type that is cast to the specified type
+    CastInput(usize, DataType),
+
     /// Output colum should be synthesized with nulls of the specified type
     MakeNull(DataType),
 
@@ -254,19 +289,33 @@ enum ColumnMapping {
     Virtual(ScalarValue),
 }
 
+/// Returns true if values of the `input` field type are promoted to the
+/// `output` field type by `type_promotion`, and hence are cast rather than
+/// rejected.
+fn is_promotion(type_promotion: TypePromotion, input: &DataType, output: &DataType) -> bool {
+    match (
+        InfluxFieldType::try_from(input.clone()),
+        InfluxFieldType::try_from(output.clone()),
+    ) {
+        (Ok(input), Ok(output)) => type_promotion.allows(input, output),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use super::*;
     use arrow::{
-        array::{ArrayRef, Int32Array, StringArray},
+        array::{ArrayRef, Float64Array, Int32Array, Int64Array, StringArray, UInt64Array},
         datatypes::{Field, Schema},
         record_batch::RecordBatch,
     };
     use arrow_util::assert_batches_eq;
     use datafusion::physical_plan::{common::collect, metrics::ExecutionPlanMetricsSet};
     use datafusion_util::stream_from_batch;
+    use schema::merge::UnsignedPromotion;
     use test_helpers::assert_contains;
 
     #[tokio::test]
@@ -279,6 +328,7 @@ mod tests {
             input_stream,
             output_schema,
             &Default::default(),
+            TypePromotion::default(),
             baseline_metrics(),
         )
         .unwrap();
@@ -313,6 +363,7 @@ mod tests {
             input_stream,
             output_schema,
             &Default::default(),
+            TypePromotion::default(),
             baseline_metrics(),
         )
         .unwrap();
@@ -355,6 +406,7 @@ mod tests {
                 ("g", ScalarValue::from(1i32)),
                 ("h", ScalarValue::from(1i32)),
             ]),
+            TypePromotion::default(),
             baseline_metrics(),
         )
         .unwrap();
@@ -388,6 +440,7 @@ mod tests {
             input_stream,
             output_schema,
             &Default::default(),
+            TypePromotion::default(),
             baseline_metrics(),
         )
         .unwrap();
@@ -422,12 +475,109 @@ mod tests {
             input_stream,
             output_schema,
             &Default::default(),
+            TypePromotion::default(),
             baseline_metrics(),
         );
 
         assert_contains!(res.unwrap_err().to_string(), "input field 'c' had type 'Utf8' which is different than output field 'c' which had type 'Float32'");
     }
 
+    #[tokio::test]
+    async fn input_has_promoted_type() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "int_field",
+                Arc::new(Int64Array::from(vec![1, -2])) as ArrayRef,
+            ),
+            ("uint_field", Arc::new(UInt64Array::from(vec![3, u64::MAX]))),
+        ])
+        .unwrap();
+
+        // both fields were promoted in the output
+        let output_schema = Arc::new(Schema::new(vec![
+            Field::new("int_field", DataType::Float64, true),
+            Field::new("uint_field", DataType::Int64, true),
+        ]));
+        let input_stream = stream_from_batch(batch.schema(), batch);
+        let adapter_stream = SchemaAdapterStream::try_new(
+            input_stream,
+            output_schema,
+            &Default::default(),
+            TypePromotion::widening(),
+            baseline_metrics(),
+        )
+        .unwrap();
+
+        let output = collect(Box::pin(adapter_stream))
+            .await
+            .expect("Running plan");
+        // values that do not fit into the output type are NULL
+        let expected = vec![
+            "+-----------+------------+",
+            "| int_field | uint_field |",
+            "+-----------+------------+",
+            "| 1.0       | 3          |",
+            "| -2.0      |            |",
+            "+-----------+------------+",
+        ];
+        assert_batches_eq!(&expected, &output);
+    }
+
+    #[tokio::test]
+    async fn input_has_promoted_type_without_promotion() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "uint_field",
+            Arc::new(UInt64Array::from(vec![3, u64::MAX])) as ArrayRef,
+        )])
+        .unwrap();
+
+        // the output type is only used if the policy allows the promotion
+        let output_schema = Arc::new(Schema::new(vec![Field::new(
+            "uint_field",
+            DataType::Int64,
+            true,
+        )]));
+        let input_stream = stream_from_batch(batch.schema(), batch);
+        let res = SchemaAdapterStream::try_new(
+            input_stream,
+            output_schema,
+            &Default::default(),
+            TypePromotion {
+                integer_to_float: true,
+                unsigned: UnsignedPromotion::ToFloat,
+            },
+            baseline_metrics(),
+        );
+
+        assert_contains!(res.unwrap_err().to_string(), "input field 'uint_field' had type 'UInt64' which is different than output field 'uint_field' which had type 'Int64'");
+    }
+
+    #[tokio::test]
+    async fn input_has_narrower_output_type() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "field",
+            Arc::new(Float64Array::from(vec![1.5])) as ArrayRef,
+        )])
+        .unwrap();
+
+        // float fields are never demoted to integers
+        let output_schema = Arc::new(Schema::new(vec![Field::new(
+            "field",
+            DataType::Int64,
+            true,
+        )]));
+        let input_stream = stream_from_batch(batch.schema(), batch);
+        let res = SchemaAdapterStream::try_new(
+            input_stream,
+            output_schema,
+            &Default::default(),
+            TypePromotion::widening(),
+            baseline_metrics(),
+        );
+
+        assert_contains!(res.unwrap_err().to_string(), "input field 'field' had type 'Float64' which is different than output field 'field' which had type 'Int64'");
+    }
+
     #[tokio::test]
     async fn virtual_col_has_wrong_type() {
         let batch = make_batch();
@@ -443,6 +593,7 @@ mod tests {
             input_stream,
             output_schema,
             &HashMap::from([("d", ScalarValue::from(1u32))]),
+            TypePromotion::default(),
             baseline_metrics(),
         );
 
@@ -467,6 +618,7 @@ mod tests {
                 ("a", ScalarValue::from(1i32)),
                 ("d", ScalarValue::from("foo")),
             ]),
+            TypePromotion::default(),
             baseline_metrics(),
         );
 
@@ -488,6 +640,7 @@ mod tests {
             input_stream,
             output_schema,
             &Default::default(),
+            TypePromotion::default(),
             baseline_metrics(),
         );
 
@@ -1,16 +1,18 @@
 //! Implemention of DeduplicateExec operator (resolves primary key conflicts) plumbing and tests
 mod algo;
 mod key_ranges;
+mod merge;
 
 use std::{collections::HashSet, fmt, sync::Arc};
 
 use arrow::{error::ArrowError, record_batch::RecordBatch};
 use datafusion_util::{watch::WatchedTask, AdapterStream};
 
-use crate::CHUNK_ORDER_COLUMN_NAME;
+use crate::{util::target_batch_rows, CHUNK_ORDER_COLUMN_NAME};
 
 use self::algo::get_col_name;
 pub use self::algo::RecordBatchDeduplicator;
+use self::merge::SortedMerge;
 use datafusion::{
     error::{DataFusionError, Result},
     execution::context::TaskContext,
@@ -24,16 +26,20 @@ use datafusion::{
         SendableRecordBatchStream, Statistics,
     },
 };
-use futures::StreamExt;
 use observability_deps::tracing::{debug, trace};
 use tokio::sync::mpsc;
 
 /// # DeduplicateExec
 ///
-/// This operator takes an input stream of RecordBatches that is
+/// This operator takes input streams of RecordBatches that are each
 /// already sorted on "sort_key" and applies IOx specific deduplication
 /// logic.
 ///
+/// If the input has more than one partition (for example one per
+/// overlapping chunk), the partitions are combined with a k-way merge
+/// using a tournament tree before deduplication, so the input never has
+/// to be concatenated and sorted as a whole.
+///
 /// The output is dependent on the order of the the input rows which
 /// have the same key.
 ///
@@ -236,14 +242,27 @@ impl ExecutionPlan for DeduplicateExec {
         }
         let deduplicate_metrics = DeduplicateMetrics::new(&self.metrics, partition);
 
-        let input_stream = self.input.execute(0, context)?;
+        let input_streams = (0..self.input.output_partitioning().partition_count())
+            .map(|input_partition| self.input.execute(input_partition, Arc::clone(&context)))
+            .collect::<Result<Vec<_>>>()?;
+        let input = SortedMerge::try_new(
+            self.input.schema(),
+            input_streams,
+            self.input_order.clone(),
+            target_batch_rows(&self.input.schema(), context.session_config())
+                .unwrap_or_else(|| context.session_config().batch_size()),
+            deduplicate_metrics
+                .baseline_metrics
+                .elapsed_compute()
+                .clone(),
+        )?;
 
         // the deduplication is performed in a separate task which is
         // then sent via a channel to the output
         let (tx, rx) = mpsc::channel(1);
 
         let fut = deduplicate(
-            input_stream,
+            input,
             self.sort_keys.clone(),
             tx.clone(),
             deduplicate_metrics,
@@ -261,10 +280,10 @@ impl ExecutionPlan for DeduplicateExec {
     }
 
     fn required_input_distribution(&self) -> Vec<Distribution> {
-        // For now use a single input -- it might be helpful
-        // eventually to deduplicate in parallel by hash partitioning
-        // the inputs (based on sort keys)
-        vec![Distribution::SinglePartition]
+        // Sorted input partitions are merged by the operator itself. It
+        // might be helpful eventually to deduplicate in parallel by hash
+        // partitioning the inputs (based on sort keys)
+        vec![Distribution::UnspecifiedDistribution]
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -292,7 +311,7 @@ impl DisplayAs for DeduplicateExec {
 }
 
 async fn deduplicate(
-    mut input_stream: SendableRecordBatchStream,
+    mut input: SortedMerge,
     sort_keys: Vec<PhysicalSortExpr>,
     tx: mpsc::Sender<Result<RecordBatch, DataFusionError>>,
     deduplicate_metrics: DeduplicateMetrics,
@@ -306,9 +325,7 @@ async fn deduplicate(
     let mut deduplicator = RecordBatchDeduplicator::new(sort_keys, num_dupes, None);
 
     // Stream input through the indexer
-    while let Some(batch) = input_stream.next().await {
-        let batch = batch?;
-
+    while let Some(batch) = input.next_batch().await? {
         // First check if this batch has same sort key with its previous batch
         let timer = elapsed_compute.timer();
         if let Some(last_batch) = deduplicator
@@ -812,6 +829,91 @@ mod test {
         assert_eq!(results.num_dupes(), 5 - 3);
     }
 
+    #[tokio::test]
+    async fn test_multi_partition() {
+        // input (each partition sorted on t1):
+        // t1 | f1 | f2
+        // ---+----+----
+        //  a | 1  |
+        //  c | 3  | 3
+        //  ====(next partition)====
+        //  a |    | 2
+        //  b | 4  |
+        //  c | 5  |
+        //
+        // expected output (rows with the same key are merged in
+        // partition order):
+        //
+        // t1 | f1 | f2
+        // ---+----+----
+        //  a | 1  | 2
+        //  b | 4  |
+        //  c | 5  | 3
+
+        let batch1 = RecordBatch::try_from_iter(vec![
+            (
+                "t1",
+                Arc::new(StringArray::from(vec![Some("a"), Some("c")])) as ArrayRef,
+            ),
+            (
+                "f1",
+                Arc::new(Float64Array::from(vec![Some(1.0), Some(3.0)])) as ArrayRef,
+            ),
+            (
+                "f2",
+                Arc::new(Float64Array::from(vec![None, Some(3.0)])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let batch2 = RecordBatch::try_from_iter(vec![
+            (
+                "t1",
+                Arc::new(StringArray::from(vec![Some("a"), Some("b"), Some("c")])) as ArrayRef,
+            ),
+            (
+                "f1",
+                Arc::new(Float64Array::from(vec![None, Some(4.0), Some(5.0)])) as ArrayRef,
+            ),
+            (
+                "f2",
+                Arc::new(Float64Array::from(vec![Some(2.0), None, None])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let schema = batch1.schema();
+        let sort_keys = vec![PhysicalSortExpr {
+            expr: col("t1", &schema).unwrap(),
+            options: SortOptions {
+                descending: false,
+                nulls_first: false,
+            },
+        }];
+
+        let input =
+            Arc::new(MemoryExec::try_new(&[vec![batch1], vec![batch2]], schema, None).unwrap());
+        let exec = Arc::new(DeduplicateExec::new(input, sort_keys, false));
+        assert_eq!(exec.output_partitioning().partition_count(), 1);
+
+        let results = TestResults {
+            output: test_collect(Arc::clone(&exec) as Arc<dyn ExecutionPlan>).await,
+            exec,
+        };
+
+        let expected = vec![
+            "+----+-----+-----+",
+            "| t1 | f1  | f2  |",
+            "+----+-----+-----+",
+            "| a  | 1.0 | 2.0 |",
+            "| b  | 4.0 |     |",
+            "| c  | 5.0 | 3.0 |",
+            "+----+-----+-----+",
+        ];
+        assert_batches_eq!(&expected, &results.output);
+        assert_eq!(results.num_dupes(), 2);
+    }
+
     #[tokio::test]
     async fn test_no_dupes() {
         // special case test for data without duplicates (fast path)
@@ -1166,7 +1268,7 @@ mod test {
         }
 
         fn output_partitioning(&self) -> Partitioning {
-            unimplemented!();
+            Partitioning::UnknownPartitioning(1)
         }
 
         fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
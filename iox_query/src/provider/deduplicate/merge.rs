@@ -0,0 +1,402 @@
+//! K-way merge of sorted record batch streams using a tournament tree
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, ArrayRef},
+    compute::interleave,
+    datatypes::SchemaRef,
+    error::Result as ArrowResult,
+    record_batch::RecordBatch,
+    row::{RowConverter, Rows, SortField},
+};
+use datafusion::{
+    error::Result,
+    physical_plan::{expressions::PhysicalSortExpr, metrics, SendableRecordBatchStream},
+};
+use futures::StreamExt;
+
+/// The position of the next row of one input stream.
+#[derive(Debug)]
+struct Cursor {
+    /// Index of the batch the cursor points into within [`SortedMerge::batches`].
+    batch_idx: usize,
+
+    /// The row-format sort key of each row of the batch.
+    rows: Rows,
+
+    /// The next row to emit.
+    offset: usize,
+}
+
+impl Cursor {
+    fn is_finished(&self) -> bool {
+        self.offset == self.rows.num_rows()
+    }
+}
+
+/// Merges multiple input streams, each sorted on the same `sort_exprs`, into
+/// a single sorted sequence of batches.
+///
+/// The streams are merged using a tournament tree ("loser tree"): each
+/// internal node of the tree stores the loser of the comparison between the
+/// winners of its two subtrees, with the overall winner (the stream holding
+/// the smallest row) stored at the root. After the winning row is emitted only
+/// the path from that stream's leaf to the root is replayed, so producing
+/// each row takes `log2(k)` comparisons for `k` streams, compared to sorting
+/// the concatenation of all inputs.
+///
+/// Rows comparing equal are emitted in the order of their streams, so the
+/// merge is stable with respect to the input order.
+pub(super) struct SortedMerge {
+    schema: SchemaRef,
+    streams: Vec<SendableRecordBatchStream>,
+    sort_exprs: Vec<PhysicalSortExpr>,
+    converter: RowConverter,
+
+    /// The current cursor of each stream, [`None`] once the stream is
+    /// exhausted.
+    cursors: Vec<Option<Cursor>>,
+
+    /// The batches the cursors and `indices` point into.
+    batches: Vec<RecordBatch>,
+
+    /// `losers[0]` is the index of the winning stream, `losers[1..]` are the
+    /// losers stored at the internal nodes of the tree. Only valid after
+    /// `initialized` is set.
+    losers: Vec<usize>,
+    initialized: bool,
+
+    /// The `(batch, row)` indices of the rows of the next output batch.
+    indices: Vec<(usize, usize)>,
+    batch_size: usize,
+
+    /// Time spent merging.
+    elapsed_compute: metrics::Time,
+}
+
+impl SortedMerge {
+    pub(super) fn try_new(
+        schema: SchemaRef,
+        streams: Vec<SendableRecordBatchStream>,
+        sort_exprs: Vec<PhysicalSortExpr>,
+        batch_size: usize,
+        elapsed_compute: metrics::Time,
+    ) -> Result<Self> {
+        let sort_fields = sort_exprs
+            .iter()
+            .map(|e| {
+                Ok(SortField::new_with_options(
+                    e.expr.data_type(&schema)?,
+                    e.options,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let converter = RowConverter::new(sort_fields)?;
+
+        let cursors = streams.iter().map(|_| None).collect();
+
+        Ok(Self {
+            schema,
+            streams,
+            sort_exprs,
+            converter,
+            cursors,
+            batches: vec![],
+            losers: vec![],
+            initialized: false,
+            indices: vec![],
+            batch_size: batch_size.max(1),
+            elapsed_compute,
+        })
+    }
+
+    /// Return the next batch of merged rows, or [`None`] once all inputs
+    /// are exhausted.
+    pub(super) async fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        // Nothing to merge, pass the batches through as-is.
+        if self.streams.len() == 1 {
+            return self.streams[0].next().await.transpose();
+        }
+        if self.streams.is_empty() {
+            return Ok(None);
+        }
+
+        if !self.initialized {
+            for stream_idx in 0..self.streams.len() {
+                self.advance_batch(stream_idx).await?;
+            }
+            let timer = self.elapsed_compute.timer();
+            self.init_loser_tree();
+            timer.done();
+            self.initialized = true;
+        }
+
+        loop {
+            let winner = self.losers[0];
+            let Some(cursor) = self.cursors[winner].as_mut() else {
+                // The winner is only exhausted once all streams are.
+                break;
+            };
+
+            self.indices.push((cursor.batch_idx, cursor.offset));
+            cursor.offset += 1;
+            if cursor.is_finished() {
+                self.advance_batch(winner).await?;
+            }
+
+            let timer = self.elapsed_compute.timer();
+            self.update_loser_tree();
+            timer.done();
+
+            if self.indices.len() >= self.batch_size {
+                return self.build_batch().map(Some);
+            }
+        }
+
+        if self.indices.is_empty() {
+            Ok(None)
+        } else {
+            self.build_batch().map(Some)
+        }
+    }
+
+    /// Point the cursor of `stream_idx` at the next non-empty batch of the
+    /// stream, or clear it if the stream is exhausted.
+    async fn advance_batch(&mut self, stream_idx: usize) -> Result<()> {
+        self.cursors[stream_idx] = None;
+
+        while let Some(batch) = self.streams[stream_idx].next().await.transpose()? {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+
+            let timer = self.elapsed_compute.timer();
+            let columns = self
+                .sort_exprs
+                .iter()
+                .map(|e| Ok(e.expr.evaluate(&batch)?.into_array(batch.num_rows())))
+                .collect::<Result<Vec<ArrayRef>>>()?;
+            let rows = self.converter.convert_columns(&columns)?;
+            timer.done();
+
+            self.batches.push(batch);
+            self.cursors[stream_idx] = Some(Cursor {
+                batch_idx: self.batches.len() - 1,
+                rows,
+                offset: 0,
+            });
+            break;
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the next row of stream `a` must be emitted after the
+    /// next row of stream `b`.
+    ///
+    /// Exhausted streams sort after all others.
+    fn is_gt(&self, a: usize, b: usize) -> bool {
+        match (&self.cursors[a], &self.cursors[b]) {
+            (None, _) => true,
+            (_, None) => false,
+            (Some(ac), Some(bc)) => ac
+                .rows
+                .row(ac.offset)
+                .cmp(&bc.rows.row(bc.offset))
+                .then_with(|| a.cmp(&b))
+                .is_gt(),
+        }
+    }
+
+    /// Build the tree by replaying a match for each stream from its leaf up
+    /// to the first node that does not yet hold a loser.
+    fn init_loser_tree(&mut self) {
+        let k = self.streams.len();
+        self.losers = vec![usize::MAX; k];
+
+        for stream_idx in 0..k {
+            let mut winner = stream_idx;
+            let mut node = self.leaf_parent(stream_idx);
+            while node != 0 && self.losers[node] != usize::MAX {
+                let challenger = self.losers[node];
+                if self.is_gt(winner, challenger) {
+                    self.losers[node] = winner;
+                    winner = challenger;
+                }
+                node /= 2;
+            }
+            self.losers[node] = winner;
+        }
+    }
+
+    /// Replay the matches on the path of the previous winner after its
+    /// cursor advanced.
+    fn update_loser_tree(&mut self) {
+        let mut winner = self.losers[0];
+        let mut node = self.leaf_parent(winner);
+        while node != 0 {
+            let challenger = self.losers[node];
+            if self.is_gt(winner, challenger) {
+                self.losers[node] = winner;
+                winner = challenger;
+            }
+            node /= 2;
+        }
+        self.losers[0] = winner;
+    }
+
+    /// The internal node directly above the leaf of `stream_idx`.
+    fn leaf_parent(&self, stream_idx: usize) -> usize {
+        (self.streams.len() + stream_idx) / 2
+    }
+
+    /// Gather the rows in `indices` into a batch, releasing the batches no
+    /// cursor points into anymore.
+    fn build_batch(&mut self) -> Result<RecordBatch> {
+        let timer = self.elapsed_compute.timer();
+
+        let columns = (0..self.schema.fields().len())
+            .map(|col_idx| {
+                let arrays = self
+                    .batches
+                    .iter()
+                    .map(|b| b.column(col_idx).as_ref())
+                    .collect::<Vec<&dyn Array>>();
+                interleave(&arrays, &self.indices)
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        self.indices.clear();
+
+        let mut retained = Vec::with_capacity(self.cursors.len());
+        for cursor in self.cursors.iter_mut().flatten() {
+            retained.push(self.batches[cursor.batch_idx].clone());
+            cursor.batch_idx = retained.len() - 1;
+        }
+        self.batches = retained;
+
+        let batch = RecordBatch::try_new(Arc::clone(&self.schema), columns)?;
+        timer.done();
+
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Int64Array, StringArray},
+        compute::SortOptions,
+    };
+    use arrow_util::assert_batches_eq;
+    use datafusion::physical_plan::{
+        expressions::col, memory::MemoryStream, metrics::ExecutionPlanMetricsSet,
+    };
+
+    use super::*;
+
+    fn batch(tags: &[&str], values: &[i64]) -> RecordBatch {
+        RecordBatch::try_from_iter(vec![
+            (
+                "tag",
+                Arc::new(StringArray::from(tags.to_vec())) as ArrayRef,
+            ),
+            ("v", Arc::new(Int64Array::from(values.to_vec())) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    async fn merge(inputs: Vec<Vec<RecordBatch>>, batch_size: usize) -> Vec<RecordBatch> {
+        let schema = inputs[0][0].schema();
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("tag", &schema).unwrap(),
+            options: SortOptions::default(),
+        }];
+        let streams = inputs
+            .into_iter()
+            .map(|batches| {
+                Box::pin(MemoryStream::try_new(batches, Arc::clone(&schema), None).unwrap())
+                    as SendableRecordBatchStream
+            })
+            .collect();
+
+        let elapsed_compute =
+            metrics::MetricBuilder::new(&ExecutionPlanMetricsSet::new()).elapsed_compute(0);
+        let mut merge =
+            SortedMerge::try_new(schema, streams, sort_exprs, batch_size, elapsed_compute).unwrap();
+
+        let mut output = vec![];
+        while let Some(batch) = merge.next_batch().await.unwrap() {
+            output.push(batch);
+        }
+        output
+    }
+
+    #[tokio::test]
+    async fn test_merge() {
+        let output = merge(
+            vec![
+                vec![batch(&["a", "c"], &[1, 1]), batch(&["e"], &[1])],
+                vec![batch(&[], &[]), batch(&["b", "c", "d"], &[2, 2, 2])],
+                vec![batch(&["a", "f"], &[3, 3])],
+            ],
+            4,
+        )
+        .await;
+
+        // Equal keys are emitted in stream order
+        let expected = vec![
+            "+-----+---+",
+            "| tag | v |",
+            "+-----+---+",
+            "| a   | 1 |",
+            "| a   | 3 |",
+            "| b   | 2 |",
+            "| c   | 1 |",
+            "| c   | 2 |",
+            "| d   | 2 |",
+            "| e   | 1 |",
+            "| f   | 3 |",
+            "+-----+---+",
+        ];
+        assert_batches_eq!(&expected, &output);
+        assert_eq!(
+            output.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![4, 4]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_single_stream() {
+        let input = vec![batch(&["a", "b"], &[1, 2]), batch(&["c"], &[3])];
+        let output = merge(vec![input.clone()], 1).await;
+        assert_eq!(output, input);
+    }
+
+    #[tokio::test]
+    async fn test_merge_exhausted_streams() {
+        let output = merge(
+            vec![
+                vec![batch(&[], &[])],
+                vec![batch(&["b"], &[2])],
+                vec![batch(&["a"], &[1])],
+                vec![batch(&[], &[])],
+                vec![batch(&["c"], &[3])],
+            ],
+            100,
+        )
+        .await;
+
+        let expected = vec![
+            "+-----+---+",
+            "| tag | v |",
+            "+-----+---+",
+            "| a   | 1 |",
+            "| b   | 2 |",
+            "| c   | 3 |",
+            "+-----+---+",
+        ];
+        assert_batches_eq!(&expected, &output);
+        assert_eq!(output.len(), 1);
+    }
+}
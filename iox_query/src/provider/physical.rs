@@ -4,7 +4,7 @@ use crate::{
     provider::record_batch_exec::RecordBatchesExec, util::arrow_sort_key_exprs, QueryChunk,
     QueryChunkData, CHUNK_ORDER_COLUMN_NAME,
 };
-use arrow::datatypes::{DataType, Fields, Schema as ArrowSchema, SchemaRef};
+use arrow::datatypes::{DataType, Field, Fields, Schema as ArrowSchema, SchemaRef};
 use datafusion::{
     datasource::{
         listing::PartitionedFile,
@@ -13,15 +13,16 @@ use datafusion::{
     },
     physical_expr::PhysicalSortExpr,
     physical_plan::{
-        empty::EmptyExec, expressions::Column, union::UnionExec, ColumnStatistics, ExecutionPlan,
-        Statistics,
+        empty::EmptyExec, expressions::Column, projection::ProjectionExec, union::UnionExec,
+        ColumnStatistics, ExecutionPlan, PhysicalExpr, Statistics,
     },
     scalar::ScalarValue,
 };
 use object_store::ObjectMeta;
+use parquet_file::storage::rename_sort_key_columns;
 use schema::{sort::SortKey, Schema};
 use std::{
-    collections::{hash_map::Entry, HashMap, HashSet},
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     sync::Arc,
 };
 
@@ -31,8 +32,8 @@ pub struct PartitionedFileExt {
     pub output_sort_key_memo: Option<SortKey>,
 }
 
-/// Holds a list of chunks that all have the same "URL" and
-/// will be scanned using the same ParquetExec.
+/// Holds a list of chunks that all have the same "URL" and renamed
+/// columns and will be scanned using the same ParquetExec.
 ///
 /// Also tracks the overall sort key which is provided to DataFusion
 /// plans
@@ -125,6 +126,13 @@ fn combine_sort_key(
 /// Parquet chunks will be turned into a [`ParquetExec`] per store, each of them with
 /// [`target_partitions`](datafusion::execution::context::SessionConfig::target_partitions) file groups.
 ///
+/// Parquet files that store some columns under a different name (see
+/// [`renamed_columns`](parquet_file::storage::ParquetExecInput::renamed_columns)) or that must not be read for some
+/// columns (see [`absent_columns`](parquet_file::storage::ParquetExecInput::absent_columns)) are scanned by a
+/// separate [`ParquetExec`] using the names within the files, followed by a [`ProjectionExec`] that restores the names
+/// of `schema`. Absent columns are scanned under a name that is not used within the files (see
+/// [`absent_column_name`]), so they are read as NULLs.
+///
 /// If this function creates more than one physical node, they will be combined using an [`UnionExec`]. Otherwise, a
 /// single node will be returned directly.
 ///
@@ -135,9 +143,9 @@ fn combine_sort_key(
 /// For empty inputs (i.e. no chunks), this will create a single [`EmptyExec`] node with appropriate schema.
 ///
 /// # Predicates
-/// The give `predicate` will only be applied to [`ParquetExec`] nodes since they are the only node type benifiting from
-/// pushdown ([`RecordBatchesExec`] has NO builtin filter function). Delete predicates are NOT applied at all. The
-/// caller is responsible for wrapping the output node into appropriate filter nodes.
+/// No predicates are applied by the created nodes. The caller is responsible for wrapping the output node into
+/// appropriate filter nodes, which the physical optimizer later pushes into the [`ParquetExec`] (as a pruning hint)
+/// and [`RecordBatchesExec`] (evaluated exactly during the scan) nodes.
 pub fn chunks_to_physical_nodes(
     schema: &SchemaRef,
     output_sort_key: Option<&SortKey>,
@@ -149,7 +157,10 @@ pub fn chunks_to_physical_nodes(
     }
 
     let mut record_batch_chunks: Vec<Arc<dyn QueryChunk>> = vec![];
-    let mut parquet_chunks: HashMap<String, ParquetChunkList> = HashMap::new();
+    let mut parquet_chunks: HashMap<
+        (String, BTreeMap<String, String>, BTreeSet<String>),
+        ParquetChunkList,
+    > = HashMap::new();
 
     for chunk in &chunks {
         match chunk.data() {
@@ -158,7 +169,11 @@ pub fn chunks_to_physical_nodes(
             }
             QueryChunkData::Parquet(parquet_input) => {
                 let url_str = parquet_input.object_store_url.as_str().to_owned();
-                match parquet_chunks.entry(url_str) {
+                match parquet_chunks.entry((
+                    url_str,
+                    parquet_input.renamed_columns,
+                    parquet_input.absent_columns,
+                )) {
                     Entry::Occupied(mut o) => {
                         o.get_mut()
                             .add_parquet_file(chunk, parquet_input.object_meta);
@@ -187,9 +202,9 @@ pub fn chunks_to_physical_nodes(
         )));
     }
     let mut parquet_chunks: Vec<_> = parquet_chunks.into_iter().collect();
-    parquet_chunks.sort_by_key(|(url_str, _)| url_str.clone());
+    parquet_chunks.sort_by(|(a, _), (b, _)| a.cmp(b));
     let has_chunk_order_col = schema.field_with_name(CHUNK_ORDER_COLUMN_NAME).is_ok();
-    for (_url_str, chunk_list) in parquet_chunks {
+    for ((_url_str, renamed_columns, absent_columns), chunk_list) in parquet_chunks {
         let ParquetChunkList {
             object_store_url,
             mut chunks,
@@ -238,13 +253,26 @@ pub fn chunks_to_physical_nodes(
             target_partitions,
         );
 
-        // Tell datafusion about the sort key, if any
-        let output_ordering = sort_key.map(|sort_key| arrow_sort_key_exprs(&sort_key, schema));
+        // The files are scanned using the column names within the files
+        let same_columns = renamed_columns.is_empty() && absent_columns.is_empty();
+        let scan_schema = if same_columns {
+            Arc::clone(schema)
+        } else {
+            scan_fields(schema, &renamed_columns, &absent_columns)
+        };
+
+        // Tell datafusion about the sort key, if any. Absent columns are skipped, they are NULL for all rows.
+        let output_ordering = sort_key.map(|sort_key| {
+            arrow_sort_key_exprs(
+                &rename_sort_key_columns(&sort_key, &renamed_columns),
+                &scan_schema,
+            )
+        });
 
         let (table_partition_cols, file_schema, output_ordering) = if has_chunk_order_col {
             let table_partition_cols = vec![(CHUNK_ORDER_COLUMN_NAME.to_owned(), DataType::Int64)];
             let file_schema = Arc::new(ArrowSchema::new(
-                schema
+                scan_schema
                     .fields
                     .iter()
                     .filter(|f| f.name() != CHUNK_ORDER_COLUMN_NAME)
@@ -257,7 +285,7 @@ pub fn chunks_to_physical_nodes(
                     .into_iter()
                     .chain(std::iter::once(PhysicalSortExpr {
                         expr: Arc::new(
-                            Column::new_with_schema(CHUNK_ORDER_COLUMN_NAME, schema)
+                            Column::new_with_schema(CHUNK_ORDER_COLUMN_NAME, &scan_schema)
                                 .expect("just added col"),
                         ),
                         options: Default::default(),
@@ -266,14 +294,14 @@ pub fn chunks_to_physical_nodes(
             );
             (table_partition_cols, file_schema, output_ordering)
         } else {
-            (vec![], Arc::clone(schema), output_ordering)
+            (vec![], Arc::clone(&scan_schema), output_ordering)
         };
 
         let statistics = Statistics {
             num_rows,
             total_byte_size: None,
             column_statistics: Some(
-                schema
+                scan_schema
                     .fields
                     .iter()
                     .map(|f| {
@@ -319,14 +347,93 @@ pub fn chunks_to_physical_nodes(
         };
         let meta_size_hint = None;
 
-        let parquet_exec = ParquetExec::new(base_config, None, meta_size_hint);
-        output_nodes.push(Arc::new(parquet_exec));
+        let parquet_exec = Arc::new(ParquetExec::new(base_config, None, meta_size_hint));
+        if same_columns {
+            output_nodes.push(parquet_exec);
+        } else {
+            output_nodes.push(restore_columns(
+                parquet_exec,
+                schema,
+                &renamed_columns,
+                &absent_columns,
+            ));
+        }
     }
 
     assert!(!output_nodes.is_empty());
     Arc::new(UnionExec::new(output_nodes))
 }
 
+/// The name an absent column (see [`absent_columns`](parquet_file::storage::ParquetExecInput::absent_columns)) is
+/// scanned under.
+fn absent_column_name(name: &str) -> String {
+    format!("__absent_{name}")
+}
+
+/// The name column `name` of `schema` is scanned under, see [`scan_fields`].
+pub(crate) fn scanned_column_name(
+    name: &str,
+    renamed_columns: &BTreeMap<String, String>,
+    absent_columns: &BTreeSet<String>,
+) -> String {
+    if absent_columns.contains(name) {
+        absent_column_name(name)
+    } else {
+        renamed_columns
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.to_owned())
+    }
+}
+
+/// The fields of `schema` as scanned from the files: renamed to the names given by `renamed_columns`, and to
+/// [`absent_column_name`] for the `absent_columns`.
+fn scan_fields(
+    schema: &SchemaRef,
+    renamed_columns: &BTreeMap<String, String>,
+    absent_columns: &BTreeSet<String>,
+) -> SchemaRef {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|f| {
+            let name = scanned_column_name(f.name(), renamed_columns, absent_columns);
+            if &name == f.name() {
+                Arc::clone(f)
+            } else {
+                Arc::new(Field::clone(f).with_name(name))
+            }
+        })
+        .collect::<Fields>();
+
+    Arc::new(ArrowSchema::new_with_metadata(
+        fields,
+        schema.metadata().clone(),
+    ))
+}
+
+/// Project the output of `input`, which has the fields of `schema` as returned by [`scan_fields`], to `schema`.
+fn restore_columns(
+    input: Arc<dyn ExecutionPlan>,
+    schema: &SchemaRef,
+    renamed_columns: &BTreeMap<String, String>,
+    absent_columns: &BTreeSet<String>,
+) -> Arc<dyn ExecutionPlan> {
+    let input_schema = input.schema();
+    let exprs = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let name = scanned_column_name(field.name(), renamed_columns, absent_columns);
+            let expr: Arc<dyn PhysicalExpr> =
+                Arc::new(Column::new_with_schema(&name, &input_schema).expect("scanned column"));
+            (expr, field.name().clone())
+        })
+        .collect();
+
+    Arc::new(ProjectionExec::try_new(exprs, input).expect("projection only restores columns"))
+}
+
 /// Distribute items from the given iterator into `n` containers.
 ///
 /// This will produce less than `n` containers if the input has less than `n` elements.
@@ -580,4 +687,61 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn test_chunks_to_physical_nodes_parquet_renamed_column() {
+        let chunk1 = TestChunk::new("table")
+            .with_id(0)
+            .with_tag_column("tag")
+            .with_i64_field_column("field")
+            .with_dummy_parquet_file();
+        let chunk2 = TestChunk::new("table")
+            .with_id(1)
+            .with_tag_column("tag")
+            .with_i64_field_column("field")
+            .with_dummy_parquet_file()
+            .with_renamed_column("field", "feild");
+        let schema = chunk1.schema().as_arrow();
+        let plan =
+            chunks_to_physical_nodes(&schema, None, vec![Arc::new(chunk1), Arc::new(chunk2)], 2);
+        insta::assert_yaml_snapshot!(
+            format_execution_plan(&plan),
+            @r###"
+        ---
+        - " UnionExec"
+        - "   ParquetExec: file_groups={1 group: [[0.parquet]]}, projection=[field, tag]"
+        - "   ProjectionExec: expr=[feild@0 as field, tag@1 as tag]"
+        - "     ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[feild, tag]"
+        "###
+        );
+        assert_eq!(plan.schema(), schema);
+    }
+
+    #[test]
+    fn test_chunks_to_physical_nodes_parquet_absent_column() {
+        let chunk1 = TestChunk::new("table")
+            .with_id(0)
+            .with_tag_column("tag")
+            .with_i64_field_column("field")
+            .with_dummy_parquet_file();
+        let chunk2 = TestChunk::new("table")
+            .with_id(1)
+            .with_tag_column("tag")
+            .with_dummy_parquet_file()
+            .with_absent_column("field");
+        let schema = chunk1.schema().as_arrow();
+        let plan =
+            chunks_to_physical_nodes(&schema, None, vec![Arc::new(chunk1), Arc::new(chunk2)], 2);
+        insta::assert_yaml_snapshot!(
+            format_execution_plan(&plan),
+            @r###"
+        ---
+        - " UnionExec"
+        - "   ParquetExec: file_groups={1 group: [[0.parquet]]}, projection=[field, tag]"
+        - "   ProjectionExec: expr=[__absent_field@0 as field, tag@1 as tag]"
+        - "     ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[__absent_field, tag]"
+        "###
+        );
+        assert_eq!(plan.schema(), schema);
+    }
 }
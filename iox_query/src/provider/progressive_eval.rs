@@ -0,0 +1,317 @@
+//! Implementation of the ProgressiveEvalExec operator, which evaluates its inputs one after another
+use std::{
+    collections::VecDeque,
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
+use datafusion::{
+    error::{DataFusionError, Result},
+    execution::context::TaskContext,
+    physical_expr::PhysicalSortRequirement,
+    physical_plan::{
+        expressions::PhysicalSortExpr,
+        metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+        DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning, RecordBatchStream,
+        SendableRecordBatchStream, Statistics,
+    },
+};
+use futures::{ready, Stream, StreamExt};
+use observability_deps::tracing::trace;
+
+/// # ProgressiveEvalExec
+///
+/// Concatenates the output of its inputs, which are each sorted by the same `ordering` and whose value ranges do not
+/// overlap, in the order of the inputs. The output is hence sorted by `ordering` without merging the inputs.
+///
+/// Unlike a [`SortPreservingMergeExec`], the inputs are evaluated one after another: an input is only executed once
+/// the previous input is exhausted, and no further input is executed once `fetch` rows have been produced. This is
+/// used for queries like `ORDER BY time DESC LIMIT 10`, where only the most recent chunks need to be read.
+///
+///
+/// [`SortPreservingMergeExec`]: datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec
+#[derive(Debug)]
+pub(crate) struct ProgressiveEvalExec {
+    inputs: Vec<Arc<dyn ExecutionPlan>>,
+    schema: SchemaRef,
+    ordering: Vec<PhysicalSortExpr>,
+    fetch: Option<usize>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl ProgressiveEvalExec {
+    /// Create an operator concatenating `inputs`, which all have the schema `schema`.
+    pub(crate) fn new(
+        inputs: Vec<Arc<dyn ExecutionPlan>>,
+        schema: SchemaRef,
+        ordering: Vec<PhysicalSortExpr>,
+        fetch: Option<usize>,
+    ) -> Self {
+        Self {
+            inputs,
+            schema,
+            ordering,
+            fetch,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for ProgressiveEvalExec {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        Some(&self.ordering)
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition; self.inputs.len()]
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        vec![Some(PhysicalSortRequirement::from_sort_exprs(&self.ordering)); self.inputs.len()]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true; self.inputs.len()]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false; self.inputs.len()]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.inputs.clone()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        assert_eq!(children.len(), self.inputs.len());
+        Ok(Arc::new(Self::new(
+            children,
+            Arc::clone(&self.schema),
+            self.ordering.clone(),
+            self.fetch,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        trace!(partition, "Start ProgressiveEvalExec::execute");
+
+        if partition != 0 {
+            return Err(DataFusionError::Internal(
+                "ProgressiveEvalExec only supports a single output stream".to_string(),
+            ));
+        }
+        if let Some(input) = self
+            .inputs
+            .iter()
+            .find(|input| input.output_partitioning().partition_count() != 1)
+        {
+            return Err(DataFusionError::Internal(format!(
+                "ProgressiveEvalExec requires single-partition inputs, got {} partitions",
+                input.output_partitioning().partition_count()
+            )));
+        }
+
+        Ok(Box::pin(ProgressiveEvalStream {
+            inputs: self.inputs.iter().cloned().collect(),
+            current: None,
+            context,
+            remaining: self.fetch,
+            schema: self.schema(),
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        // don't know anything about the statistics
+        Statistics::default()
+    }
+}
+
+impl DisplayAs for ProgressiveEvalExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let expr: Vec<String> = self.ordering.iter().map(|e| e.to_string()).collect();
+                write!(f, "ProgressiveEvalExec: ")?;
+                if let Some(fetch) = self.fetch {
+                    write!(f, "fetch={fetch}, ")?;
+                }
+                write!(f, "expr=[{}]", expr.join(","))
+            }
+        }
+    }
+}
+
+/// Stream of the batches of the inputs of a [`ProgressiveEvalExec`].
+struct ProgressiveEvalStream {
+    /// Inputs that were not executed yet.
+    inputs: VecDeque<Arc<dyn ExecutionPlan>>,
+
+    /// The output of the input currently read.
+    current: Option<SendableRecordBatchStream>,
+
+    context: Arc<TaskContext>,
+
+    /// Number of rows still to be produced, if limited.
+    remaining: Option<usize>,
+
+    schema: SchemaRef,
+
+    baseline_metrics: BaselineMetrics,
+}
+
+impl ProgressiveEvalStream {
+    fn poll_next_inner(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<RecordBatch>>> {
+        loop {
+            if self.remaining == Some(0) {
+                // release the inputs early
+                self.inputs.clear();
+                self.current = None;
+                return Poll::Ready(None);
+            }
+
+            if self.current.is_none() {
+                let Some(input) = self.inputs.pop_front() else {
+                    return Poll::Ready(None);
+                };
+                self.current = Some(input.execute(0, Arc::clone(&self.context))?);
+            }
+            let current = self.current.as_mut().expect("just set");
+
+            match ready!(current.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    let batch = match &mut self.remaining {
+                        Some(remaining) => {
+                            let batch = batch.slice(0, batch.num_rows().min(*remaining));
+                            *remaining -= batch.num_rows();
+                            batch
+                        }
+                        None => batch,
+                    };
+                    return Poll::Ready(Some(Ok(batch)));
+                }
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => {
+                    // input exhausted, continue with the next one
+                    self.current = None;
+                }
+            }
+        }
+    }
+}
+
+impl Stream for ProgressiveEvalStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_inner(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for ProgressiveEvalStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{ArrayRef, Int64Array},
+        datatypes::{DataType, Field, Schema},
+    };
+    use arrow_util::assert_batches_eq;
+    use datafusion::physical_plan::{collect, expressions::col, memory::MemoryExec};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_concatenate_in_order() {
+        let inputs = vec![
+            input(&[&[9, 8], &[7]]),
+            input(&[&[5, 4]]),
+            input(&[&[2, 1]]),
+        ];
+
+        let batches = run(inputs, None).await;
+        assert_batches_eq!(
+            &[
+                "+---+", "| v |", "+---+", "| 9 |", "| 8 |", "| 7 |", "| 5 |", "| 4 |", "| 2 |",
+                "| 1 |", "+---+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let inputs = vec![
+            input(&[&[9, 8], &[7]]),
+            input(&[&[5, 4]]),
+            input(&[&[2, 1]]),
+        ];
+
+        let batches = run(inputs, Some(4)).await;
+        assert_batches_eq!(
+            &["+---+", "| v |", "+---+", "| 9 |", "| 8 |", "| 7 |", "| 5 |", "+---+"],
+            &batches
+        );
+    }
+
+    async fn run(inputs: Vec<Arc<dyn ExecutionPlan>>, fetch: Option<usize>) -> Vec<RecordBatch> {
+        let ordering = vec![PhysicalSortExpr {
+            expr: col("v", &schema()).unwrap(),
+            options: arrow::compute::SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let exec = Arc::new(ProgressiveEvalExec::new(inputs, schema(), ordering, fetch));
+        collect(exec, Arc::new(TaskContext::default()))
+            .await
+            .unwrap()
+    }
+
+    fn input(batches: &[&[i64]]) -> Arc<dyn ExecutionPlan> {
+        let batches = batches
+            .iter()
+            .map(|values| {
+                let array: ArrayRef = Arc::new(Int64Array::from(values.to_vec()));
+                RecordBatch::try_new(schema(), vec![array]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        Arc::new(MemoryExec::try_new(&[batches], schema(), None).unwrap())
+    }
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]))
+    }
+}
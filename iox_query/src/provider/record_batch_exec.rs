@@ -1,28 +1,138 @@
 //! Implementation of a DataFusion PhysicalPlan node across partition chunks
 
-use crate::{statistics::DFStatsAggregator, QueryChunk, CHUNK_ORDER_COLUMN_NAME};
+use crate::{
+    config::IoxConfigExt, statistics::DFStatsAggregator, util::target_batch_rows, QueryChunk,
+    CHUNK_ORDER_COLUMN_NAME,
+};
 
 use super::adapter::SchemaAdapterStream;
-use arrow::datatypes::{Schema, SchemaRef};
+use arrow::{
+    array::{new_null_array, ArrayRef, BooleanArray},
+    compute::{cast, filter_record_batch},
+    datatypes::{Schema, SchemaRef},
+    record_batch::{RecordBatch, RecordBatchOptions},
+};
 use datafusion::{
+    common::cast::as_boolean_array,
     error::DataFusionError,
     execution::context::TaskContext,
+    logical_expr::Operator,
+    physical_expr::utils::{collect_columns, reassign_predicate_columns},
     physical_plan::{
-        expressions::{Column, PhysicalSortExpr},
-        metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
-        ColumnStatistics, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning,
+        expressions::{BinaryExpr, Column, PhysicalSortExpr},
+        metrics::{self, BaselineMetrics, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
+        stream::RecordBatchStreamAdapter,
+        ColumnStatistics, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
         SendableRecordBatchStream, Statistics,
     },
     scalar::ScalarValue,
 };
+use futures::{future, StreamExt, TryStreamExt};
 use observability_deps::tracing::trace;
 use schema::sort::SortKey;
 use std::{collections::HashMap, fmt, sync::Arc};
 
-/// Implements the DataFusion physical plan interface for [`RecordBatch`]es with automatic projection and NULL-column creation.
+/// A filter predicate evaluated while scanning the chunks of a [`RecordBatchesExec`].
 ///
+/// The predicate is bound to a schema containing only the columns it references, so it can be evaluated against the
+/// batches of any chunk (which may lack some columns of the table) and does not depend on the projection of the scan.
+#[derive(Debug, Clone)]
+pub(crate) struct ScanPredicate {
+    /// The predicate, bound to `schema`.
+    expr: Arc<dyn PhysicalExpr>,
+
+    /// The columns referenced by `expr`.
+    schema: SchemaRef,
+}
+
+impl ScanPredicate {
+    /// Create a predicate from `expr`, which is bound to `input_schema`.
+    pub(crate) fn try_new(
+        expr: Arc<dyn PhysicalExpr>,
+        input_schema: &Schema,
+    ) -> datafusion::error::Result<Self> {
+        let mut columns = collect_columns(&expr).into_iter().collect::<Vec<_>>();
+        columns.sort_by_key(|c| c.index());
+
+        // Columns missing from a chunk are evaluated as NULLs.
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|c| input_schema.field(c.index()).clone().with_nullable(true))
+                .collect::<Vec<_>>(),
+        ));
+        let expr = reassign_predicate_columns(expr, &schema, false)?;
+
+        Ok(Self { expr, schema })
+    }
+
+    /// Combine `self` and `other` with `AND`.
+    pub(crate) fn and(&self, other: &Self) -> datafusion::error::Result<Self> {
+        let schema = Arc::new(Schema::try_merge([
+            self.schema.as_ref().clone(),
+            other.schema.as_ref().clone(),
+        ])?);
+        let lhs = reassign_predicate_columns(Arc::clone(&self.expr), &schema, false)?;
+        let rhs = reassign_predicate_columns(Arc::clone(&other.expr), &schema, false)?;
+
+        Ok(Self {
+            expr: Arc::new(BinaryExpr::new(lhs, Operator::And, rhs)),
+            schema,
+        })
+    }
+
+    /// The predicate, bound to [`columns`](Self::columns).
+    pub(crate) fn expr(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    /// The columns referenced by the predicate.
+    pub(crate) fn columns(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Evaluate the predicate against `batch`, returning the selection vector of the passing rows.
+    ///
+    /// Only the referenced columns of `batch` are read. Columns of a narrower type than the table are cast to the type
+    /// of the table, which the [`SchemaAdapterStream`] of the scan only accepts if the
+    /// [`type_promotion`](IoxConfigExt::type_promotion) policy allows it.
+    fn evaluate(&self, batch: &RecordBatch) -> datafusion::error::Result<BooleanArray> {
+        let num_rows = batch.num_rows();
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| match batch.column_by_name(field.name()) {
+                Some(column) if column.data_type() != field.data_type() => {
+                    Ok(cast(column, field.data_type())?)
+                }
+                Some(column) => Ok(Arc::clone(column)),
+                None => Ok(new_null_array(field.data_type(), num_rows)),
+            })
+            .collect::<datafusion::error::Result<Vec<ArrayRef>>>()?;
+        let input = RecordBatch::try_new_with_options(
+            Arc::clone(&self.schema),
+            columns,
+            &RecordBatchOptions::new().with_row_count(Some(num_rows)),
+        )?;
+
+        let selection = self.expr.evaluate(&input)?.into_array(num_rows);
+        Ok(as_boolean_array(&selection)?.clone())
+    }
+}
+
+impl fmt::Display for ScanPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+/// Implements the DataFusion physical plan interface for [`RecordBatch`]es with automatic projection and NULL-column creation.
 ///
-/// [`RecordBatch`]: arrow::record_batch::RecordBatch
+/// If a [predicate](Self::with_predicate) is set, it is evaluated for each batch of a chunk before the batch is
+/// projected and padded to the output schema. Only the columns referenced by the predicate are read to compute the
+/// selection vector, and the output columns are then gathered for the selected rows only. This avoids materializing
+/// full batches that are discarded by a filter above the scan.
 #[derive(Debug)]
 pub(crate) struct RecordBatchesExec {
     /// Chunks contained in this exec node.
@@ -47,6 +157,12 @@ pub(crate) struct RecordBatchesExec {
 
     /// Output ordering.
     output_ordering: Option<Vec<PhysicalSortExpr>>,
+
+    /// Predicate evaluated during the scan.
+    predicate: Option<ScanPredicate>,
+
+    /// Maximum number of rows produced per chunk.
+    limit: Option<usize>,
 }
 
 impl RecordBatchesExec {
@@ -111,7 +227,48 @@ impl RecordBatchesExec {
             output_sort_key_memo,
             output_ordering,
             metrics: ExecutionPlanMetricsSet::new(),
+            predicate: None,
+            limit: None,
+        }
+    }
+
+    /// Only return the rows passing `predicate`.
+    ///
+    /// Unlike the predicate of a [`ParquetExec`], this predicate is applied exactly, so no filter is required on top of
+    /// this node.
+    ///
+    ///
+    /// [`ParquetExec`]: datafusion::datasource::physical_plan::ParquetExec
+    pub fn with_predicate(mut self, predicate: Option<ScanPredicate>) -> Self {
+        // The statistics are now an upper bound.
+        if predicate.is_some() {
+            self.statistics.is_exact = false;
+        }
+        self.predicate = predicate;
+        self
+    }
+
+    /// Predicate evaluated during the scan.
+    pub fn predicate(&self) -> Option<&ScanPredicate> {
+        self.predicate.as_ref()
+    }
+
+    /// Stop scanning a chunk once `limit` rows (passing the [predicate](Self::with_predicate)) were produced for it.
+    ///
+    /// Since every chunk is its own partition, this limits the rows per partition. A limit on top of this node is
+    /// still required to limit the total number of rows.
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        // The statistics are now an upper bound.
+        if limit.is_some() {
+            self.statistics.is_exact = false;
         }
+        self.limit = limit;
+        self
+    }
+
+    /// Maximum number of rows produced per chunk.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
     }
 
     /// Chunks that make up this node.
@@ -164,7 +321,7 @@ impl ExecutionPlan for RecordBatchesExec {
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> datafusion::error::Result<SendableRecordBatchStream> {
         trace!(partition, "Start RecordBatchesExec::execute");
 
@@ -182,15 +339,60 @@ impl ExecutionPlan for RecordBatchesExec {
                 )));
             }
         };
+        let stream = match &self.predicate {
+            Some(predicate) => filter_stream(
+                stream,
+                predicate.clone(),
+                &schema,
+                baseline_metrics.elapsed_compute().clone(),
+                MetricBuilder::new(&self.metrics).counter("pushdown_rows_filtered", partition),
+            )?,
+            None => stream,
+        };
         let virtual_columns = HashMap::from([(
             CHUNK_ORDER_COLUMN_NAME,
             ScalarValue::from(chunk.order().get()),
         )]);
-        let adapter = Box::pin(
-            SchemaAdapterStream::try_new(stream, schema, &virtual_columns, baseline_metrics)
-                .map_err(|e| DataFusionError::External(Box::new(e)))?,
+        let type_promotion = context
+            .session_config()
+            .options()
+            .extensions
+            .get::<IoxConfigExt>()
+            .map(|config| config.type_promotion)
+            .unwrap_or_default();
+        let adapter: SendableRecordBatchStream = Box::pin(
+            SchemaAdapterStream::try_new(
+                stream,
+                Arc::clone(&schema),
+                &virtual_columns,
+                type_promotion,
+                baseline_metrics,
+            )
+            .map_err(|e| DataFusionError::External(Box::new(e)))?,
         );
 
+        // Split batches that exceed the configured target size.
+        let adapter = match target_batch_rows(&schema, context.session_config()) {
+            Some(max_rows) => Box::pin(RecordBatchStreamAdapter::new(
+                schema,
+                adapter
+                    .map_ok(move |batch| {
+                        futures::stream::iter(
+                            split_batch(batch, max_rows)
+                                .into_iter()
+                                .map(Ok::<_, DataFusionError>),
+                        )
+                    })
+                    .try_flatten(),
+            )),
+            None => adapter,
+        };
+
+        let adapter = match self.limit {
+            Some(limit) => limit_stream(adapter, limit),
+            None => adapter,
+        };
+
         trace!(partition, "End RecordBatchesExec::execute");
         Ok(adapter)
     }
@@ -208,8 +410,200 @@ impl DisplayAs for RecordBatchesExec {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match t {
             DisplayFormatType::Default | DisplayFormatType::Verbose => {
-                write!(f, "RecordBatchesExec: chunks={}", self.chunks.len(),)
+                write!(f, "RecordBatchesExec: chunks={}", self.chunks.len(),)?;
+                if let Some(predicate) = &self.predicate {
+                    write!(f, ", predicate={predicate}")?;
+                }
+                if let Some(limit) = self.limit {
+                    write!(f, ", limit={limit}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Apply `predicate` to the batches of `input`, keeping only the columns that are part of `output_schema`.
+///
+/// Batches without any selected rows are dropped.
+fn filter_stream(
+    input: SendableRecordBatchStream,
+    predicate: ScanPredicate,
+    output_schema: &Schema,
+    elapsed_compute: metrics::Time,
+    rows_filtered: metrics::Count,
+) -> datafusion::error::Result<SendableRecordBatchStream> {
+    let input_schema = input.schema();
+    let projection = input_schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| output_schema.field_with_name(field.name()).is_ok())
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    let projected_schema = Arc::new(input_schema.project(&projection)?);
+
+    let stream = input
+        .map(move |batch| -> datafusion::error::Result<RecordBatch> {
+            let batch = batch?;
+            let _timer = elapsed_compute.timer();
+
+            let selection = predicate.evaluate(&batch)?;
+            let filtered = filter_record_batch(&batch.project(&projection)?, &selection)?;
+            rows_filtered.add(batch.num_rows() - filtered.num_rows());
+
+            Ok(filtered)
+        })
+        .try_filter(|batch| future::ready(batch.num_rows() > 0));
+
+    Ok(Box::pin(RecordBatchStreamAdapter::new(
+        projected_schema,
+        stream,
+    )))
+}
+
+/// Produce at most `limit` rows of `input`.
+///
+/// `input` is not polled anymore once `limit` rows were produced.
+fn limit_stream(input: SendableRecordBatchStream, limit: usize) -> SendableRecordBatchStream {
+    let schema = input.schema();
+    let stream = futures::stream::unfold((input, limit), |(mut input, remaining)| async move {
+        if remaining == 0 {
+            return None;
+        }
+        match input.next().await? {
+            Ok(batch) => {
+                let batch = batch.slice(0, batch.num_rows().min(remaining));
+                let remaining = remaining - batch.num_rows();
+                Some((Ok(batch), (input, remaining)))
             }
+            Err(e) => Some((Err(e), (input, remaining))),
         }
+    });
+
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// Split `batch` into slices of at most `max_rows` rows.
+fn split_batch(batch: RecordBatch, max_rows: usize) -> Vec<RecordBatch> {
+    if batch.num_rows() <= max_rows {
+        return vec![batch];
+    }
+
+    (0..batch.num_rows())
+        .step_by(max_rows)
+        .map(|offset| batch.slice(offset, max_rows.min(batch.num_rows() - offset)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{exec::query_tracing::one_line, test::TestChunk};
+    use arrow::datatypes::{DataType, Field};
+    use arrow_util::assert_batches_eq;
+    use datafusion::physical_plan::expressions::{col, is_null, lit};
+    use datafusion_util::test_collect;
+
+    #[test]
+    fn test_split_batch() {
+        let batch = RecordBatch::try_from_iter([(
+            "a",
+            Arc::new(arrow::array::Int64Array::from_iter_values(0..5)) as ArrayRef,
+        )])
+        .unwrap();
+
+        let split = split_batch(batch.clone(), 2);
+        assert_eq!(
+            split.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert_eq!(
+            arrow::compute::concat_batches(&batch.schema(), &split).unwrap(),
+            batch
+        );
+
+        assert_eq!(split_batch(batch.clone(), 5), vec![batch]);
+    }
+
+    #[tokio::test]
+    async fn test_predicate() {
+        let chunk = TestChunk::new("t")
+            .with_tag_column("tag1")
+            .with_i64_field_column("field_int")
+            .with_time_column()
+            .with_three_rows_of_data();
+        let chunk_schema = chunk.schema().as_arrow();
+
+        // the table has a column the chunk does not have
+        let table_schema = Arc::new(Schema::new(vec![
+            chunk_schema.field_with_name("tag1").unwrap().clone(),
+            Field::new("tag2", DataType::Utf8, true),
+            chunk_schema.field_with_name("field_int").unwrap().clone(),
+            chunk_schema.field_with_name("time").unwrap().clone(),
+        ]));
+
+        // filter on columns that are not part of the output
+        let predicate: Arc<dyn PhysicalExpr> = Arc::new(BinaryExpr::new(
+            Arc::new(BinaryExpr::new(
+                col("field_int", &table_schema).unwrap(),
+                Operator::Gt,
+                lit(50i64),
+            )),
+            Operator::And,
+            is_null(col("tag2", &table_schema).unwrap()).unwrap(),
+        ));
+        let predicate = ScanPredicate::try_new(predicate, &table_schema).unwrap();
+
+        let output_schema = Arc::new(table_schema.project(&[0, 3]).unwrap());
+        let plan = Arc::new(
+            RecordBatchesExec::new(vec![Arc::new(chunk) as _], output_schema, None)
+                .with_predicate(Some(predicate)),
+        );
+        assert_eq!(
+            one_line(plan.as_ref()).to_string(),
+            "RecordBatchesExec: chunks=1, predicate=field_int@1 > 50 AND tag2@0 IS NULL"
+        );
+        assert!(!plan.statistics().is_exact);
+
+        let batches = test_collect(Arc::clone(&plan) as _).await;
+        let expected = vec![
+            "+------+----------------------------+",
+            "| tag1 | time                       |",
+            "+------+----------------------------+",
+            "| WA   | 1970-01-01T00:00:00.000008 |",
+            "| UT   | 1970-01-01T00:00:00.000020 |",
+            "+------+----------------------------+",
+        ];
+        assert_batches_eq!(&expected, &batches);
+
+        let rows_filtered = plan
+            .metrics()
+            .unwrap()
+            .sum_by_name("pushdown_rows_filtered")
+            .unwrap();
+        assert_eq!(rows_filtered.as_usize(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_limit() {
+        let chunk = TestChunk::new("t")
+            .with_tag_column("tag1")
+            .with_i64_field_column("field_int")
+            .with_time_column()
+            .with_three_rows_of_data();
+        let schema = chunk.schema().as_arrow();
+
+        let plan = Arc::new(
+            RecordBatchesExec::new(vec![Arc::new(chunk) as _], schema, None).with_limit(Some(2)),
+        );
+        assert_eq!(
+            one_line(plan.as_ref()).to_string(),
+            "RecordBatchesExec: chunks=1, limit=2"
+        );
+        assert!(!plan.statistics().is_exact);
+
+        let batches = test_collect(Arc::clone(&plan) as _).await;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
     }
 }
@@ -1,6 +1,6 @@
 //! Implementation of statistics based pruning
 
-use crate::QueryChunk;
+use crate::{config::TagFilter, QueryChunk};
 use arrow::{
     array::{ArrayRef, UInt64Array},
     datatypes::{DataType, SchemaRef},
@@ -9,13 +9,13 @@ use datafusion::{
     physical_expr::execution_props::ExecutionProps,
     physical_optimizer::pruning::PruningStatistics,
     physical_plan::{ColumnStatistics, Statistics},
-    prelude::{col, lit_timestamp_nano, Column, Expr},
+    prelude::{col, lit, lit_timestamp_nano, Column, Expr},
     scalar::ScalarValue,
 };
-use datafusion_util::create_pruning_predicate;
+use datafusion_util::{create_pruning_predicate, lit_dict};
 use observability_deps::tracing::{debug, trace, warn};
 use query_functions::group_by::Aggregate;
-use schema::{Schema, TIME_COLUMN_NAME};
+use schema::{InfluxColumnType, Schema, TIME_COLUMN_NAME};
 use std::sync::Arc;
 
 /// Reason why a chunk could not be pruned.
@@ -212,6 +212,17 @@ pub fn retention_expr(retention_time: i64) -> Expr {
     col(TIME_COLUMN_NAME).gt(lit_timestamp_nano(retention_time))
 }
 
+/// Row-level tag filter expression, "tag IN (values)".
+///
+/// If the table has no such tag, the expression is `false`.
+pub fn tag_filter_expr(filter: &TagFilter, schema: &Schema) -> Expr {
+    match schema.field_type_by_name(&filter.tag) {
+        Some(InfluxColumnType::Tag) if !filter.values.is_empty() => col(filter.tag.as_str())
+            .in_list(filter.values.iter().map(|v| lit_dict(v)).collect(), false),
+        _ => lit(false),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{ops::Not, sync::Arc};
@@ -675,4 +686,35 @@ mod test {
             vec![true, false, false, true, false, true]
         );
     }
+
+    #[test]
+    fn test_tag_filter_expr() {
+        let schema = schema::builder::SchemaBuilder::new()
+            .tag("tenant_id")
+            .influx_field("value", schema::InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap();
+        let filter = |tag: &str, values: &[&str]| TagFilter {
+            tag: tag.to_string(),
+            values: values.iter().map(ToString::to_string).collect(),
+        };
+
+        assert_eq!(
+            tag_filter_expr(&filter("tenant_id", &["acme", "globex"]), &schema),
+            col("tenant_id").in_list(vec![lit_dict("acme"), lit_dict("globex")], false)
+        );
+        assert_eq!(
+            tag_filter_expr(&filter("tenant_id", &[]), &schema),
+            lit(false)
+        );
+        assert_eq!(
+            tag_filter_expr(&filter("region", &["us"]), &schema),
+            lit(false)
+        );
+        assert_eq!(
+            tag_filter_expr(&filter("value", &["1"]), &schema),
+            lit(false)
+        );
+    }
 }
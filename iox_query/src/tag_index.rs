@@ -0,0 +1,223 @@
+//! Index of the distinct tag values of a chunk.
+
+use std::{collections::BTreeMap, mem::size_of};
+
+use arrow::{
+    array::{Array, DictionaryArray, StringArray},
+    compute::cast,
+    datatypes::{DataType, Int32Type},
+    record_batch::RecordBatch,
+};
+use schema::Schema;
+
+use crate::exec::stringset::StringSet;
+
+/// The distinct, non-null values of every tag column of a chunk.
+///
+/// Allows answering `tag_keys` / `tag_values` style metadata queries without planning a scan of
+/// the chunk, as long as the query predicate does not exclude any row of the chunk.
+///
+/// Tags without any non-null value are not part of the index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagIndex {
+    tags: BTreeMap<String, StringSet>,
+}
+
+impl TagIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index the tag columns of `batches`, which have the given `schema`.
+    pub fn from_batches<'a>(
+        schema: &Schema,
+        batches: impl IntoIterator<Item = &'a RecordBatch>,
+    ) -> Self {
+        let mut index = Self::new();
+        for batch in batches {
+            index.insert_batch(schema, batch);
+        }
+        index
+    }
+
+    /// Add the values of the tag columns of `batch`, which has the given `schema`.
+    ///
+    /// Tag columns that are missing from `batch` are ignored.
+    pub fn insert_batch(&mut self, schema: &Schema, batch: &RecordBatch) {
+        for field in schema.tags_iter() {
+            if let Some(array) = batch.column_by_name(field.name()) {
+                self.insert_array(field.name(), array.as_ref());
+            }
+        }
+    }
+
+    /// Add the values of `array` to the values of the tag `tag`.
+    ///
+    /// Dictionary encoded arrays only add the dictionary values that are referenced by a row.
+    pub fn insert_array(&mut self, tag: &str, array: &dyn Array) {
+        let mut values = vec![];
+
+        if let Some(dictionary) = array.as_any().downcast_ref::<DictionaryArray<Int32Type>>() {
+            let mut used = vec![false; dictionary.values().len()];
+            for key in dictionary.keys().iter().flatten() {
+                used[key as usize] = true;
+            }
+
+            let dictionary_values = cast(dictionary.values(), &DataType::Utf8)
+                .expect("tag dictionary values can be cast to strings");
+            let dictionary_values = dictionary_values
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .expect("cast to strings");
+            values.extend(
+                used.into_iter()
+                    .enumerate()
+                    .filter(|(idx, used)| *used && dictionary_values.is_valid(*idx))
+                    .map(|(idx, _)| dictionary_values.value(idx)),
+            );
+            self.insert_values(tag, values);
+            return;
+        }
+
+        let array = cast(array, &DataType::Utf8).expect("tag values can be cast to strings");
+        let array = array
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .expect("cast to strings");
+        values.extend(array.iter().flatten());
+        self.insert_values(tag, values);
+    }
+
+    fn insert_values<'a>(&mut self, tag: &str, values: impl IntoIterator<Item = &'a str>) {
+        let mut values = values.into_iter().peekable();
+        if values.peek().is_none() {
+            return;
+        }
+
+        let known = self.tags.entry(tag.to_string()).or_default();
+        for value in values {
+            if !known.contains(value) {
+                known.insert(value.to_string());
+            }
+        }
+    }
+
+    /// Add all values of `other` to this index.
+    pub fn merge(&mut self, other: &Self) {
+        for (tag, values) in &other.tags {
+            self.insert_values(tag, values.iter().map(String::as_str));
+        }
+    }
+
+    /// The tags with at least one non-null value, ordered by name.
+    pub fn tag_keys(&self) -> impl Iterator<Item = &str> {
+        self.tags.keys().map(String::as_str)
+    }
+
+    /// The distinct values of the tag `tag`, or `None` if the tag has no non-null values.
+    pub fn tag_values(&self, tag: &str) -> Option<&StringSet> {
+        self.tags.get(tag)
+    }
+
+    /// Estimated size of the index in bytes, including `self`.
+    pub fn size(&self) -> usize {
+        size_of::<Self>()
+            + self
+                .tags
+                .iter()
+                .map(|(tag, values)| {
+                    size_of::<(String, StringSet)>()
+                        + tag.capacity()
+                        + values
+                            .iter()
+                            .map(|v| size_of::<String>() + v.capacity())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, TimestampNanosecondArray};
+    use schema::{SchemaBuilder, TIME_COLUMN_NAME};
+
+    use super::*;
+
+    fn schema() -> Schema {
+        SchemaBuilder::new()
+            .tag("host")
+            .tag("region")
+            .timestamp()
+            .build()
+            .unwrap()
+    }
+
+    fn batch(host: Vec<Option<&str>>, region: Vec<Option<&str>>) -> RecordBatch {
+        let time = TimestampNanosecondArray::from(vec![1; host.len()]);
+        let host: DictionaryArray<Int32Type> = host.into_iter().collect();
+        let region: DictionaryArray<Int32Type> = region.into_iter().collect();
+
+        RecordBatch::try_from_iter([
+            ("host", Arc::new(host) as ArrayRef),
+            ("region", Arc::new(region) as ArrayRef),
+            (TIME_COLUMN_NAME, Arc::new(time) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    fn set(values: &[&str]) -> StringSet {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_from_batches() {
+        let batches = [
+            batch(vec![Some("a"), Some("b"), None], vec![None, None, None]),
+            batch(vec![Some("b"), Some("c")], vec![None, Some("west")]),
+        ];
+
+        let index = TagIndex::from_batches(&schema(), &batches);
+
+        assert_eq!(index.tag_keys().collect::<Vec<_>>(), vec!["host", "region"]);
+        assert_eq!(index.tag_values("host"), Some(&set(&["a", "b", "c"])));
+        assert_eq!(index.tag_values("region"), Some(&set(&["west"])));
+        assert_eq!(index.tag_values("time"), None);
+    }
+
+    #[test]
+    fn test_all_null_tag() {
+        let index = TagIndex::from_batches(&schema(), &[batch(vec![Some("a")], vec![None])]);
+
+        assert_eq!(index.tag_keys().collect::<Vec<_>>(), vec!["host"]);
+        assert_eq!(index.tag_values("region"), None);
+    }
+
+    #[test]
+    fn test_unreferenced_dictionary_values() {
+        // the dictionary still contains "b" after the row referencing it was filtered out
+        let host: DictionaryArray<Int32Type> = vec!["a", "b"].into_iter().collect();
+        let host = arrow::compute::filter(&host, &vec![true, false].into()).unwrap();
+
+        let mut index = TagIndex::new();
+        index.insert_array("host", host.as_ref());
+
+        assert_eq!(index.tag_values("host"), Some(&set(&["a"])));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut index = TagIndex::from_batches(&schema(), &[batch(vec![Some("a")], vec![None])]);
+        let other =
+            TagIndex::from_batches(&schema(), &[batch(vec![Some("b")], vec![Some("west")])]);
+
+        index.merge(&other);
+
+        assert_eq!(index.tag_values("host"), Some(&set(&["a", "b"])));
+        assert_eq!(index.tag_values("region"), Some(&set(&["west"])));
+        assert!(index.size() > other.size());
+    }
+}
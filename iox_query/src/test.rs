@@ -8,6 +8,7 @@ use crate::{
         Executor, ExecutorType, IOxSessionContext,
     },
     pruning::prune_chunks,
+    tag_index::TagIndex,
     QueryChunk, QueryChunkData, QueryCompletedToken, QueryNamespace, QueryText,
 };
 use arrow::array::{BooleanArray, Float64Array};
@@ -162,6 +163,7 @@ impl QueryNamespace for TestDatabase {
 
     fn record_query(
         &self,
+        _ctx: &IOxSessionContext,
         _span_ctx: Option<&SpanContext>,
         _query_type: &'static str,
         _query_text: QueryText,
@@ -321,6 +323,9 @@ pub struct TestChunk {
 
     /// Suppress output
     quiet: bool,
+
+    /// Index of the tag values, see [`with_tag_index`](Self::with_tag_index)
+    tag_index: Option<Arc<TagIndex>>,
 }
 
 /// Implements a method for adding a column with default stats
@@ -384,6 +389,7 @@ impl TestChunk {
             sort_key: None,
             partition_id: TransitionPartitionId::arbitrary_for_testing(),
             quiet: false,
+            tag_index: None,
         }
     }
 
@@ -403,6 +409,19 @@ impl TestChunk {
         }
     }
 
+    /// Report the tag values of the record batches added so far as [`QueryChunk::tag_index`].
+    pub fn with_tag_index(self) -> Self {
+        let tag_index = match &self.table_data {
+            TestChunkData::RecordBatches(batches) => TagIndex::from_batches(&self.schema, batches),
+            TestChunkData::Parquet(_) => panic!("chunk is parquet-based"),
+        };
+
+        Self {
+            tag_index: Some(Arc::new(tag_index)),
+            ..self
+        }
+    }
+
     pub fn with_dummy_parquet_file(self) -> Self {
         self.with_dummy_parquet_file_and_store("iox://store")
     }
@@ -424,11 +443,37 @@ impl TestChunk {
                     size: 1,
                     e_tag: None,
                 },
+                renamed_columns: Default::default(),
+                absent_columns: Default::default(),
             }),
             ..self
         }
     }
 
+    /// Mark column `name` of the dummy parquet file as stored under `name_in_file`.
+    pub fn with_renamed_column(mut self, name: &str, name_in_file: &str) -> Self {
+        match &mut self.table_data {
+            TestChunkData::Parquet(parquet_input) => {
+                parquet_input
+                    .renamed_columns
+                    .insert(name.to_string(), name_in_file.to_string());
+            }
+            TestChunkData::RecordBatches(_) => panic!("chunk has no parquet file"),
+        }
+        self
+    }
+
+    /// Mark column `name` as not stored in the dummy parquet file.
+    pub fn with_absent_column(mut self, name: &str) -> Self {
+        match &mut self.table_data {
+            TestChunkData::Parquet(parquet_input) => {
+                parquet_input.absent_columns.insert(name.to_string());
+            }
+            TestChunkData::RecordBatches(_) => panic!("chunk has no parquet file"),
+        }
+        self
+    }
+
     fn parquet_location(chunk_id: ChunkId) -> Path {
         Path::parse(format!("{}.parquet", chunk_id.get().as_u128())).unwrap()
     }
@@ -672,6 +717,19 @@ impl TestChunk {
         self
     }
 
+    /// Prepares this chunk to return `batch`, which must have the schema
+    /// built up by the `with_*_column` methods.
+    pub fn with_record_batch(mut self, batch: RecordBatch) -> Self {
+        assert_eq!(
+            batch.schema(),
+            self.schema.as_arrow(),
+            "record batch schema does not match chunk schema"
+        );
+
+        self.push_record_batch(batch);
+        self
+    }
+
     /// Prepares this chunk to return a specific record batch with one
     /// row of non null data.
     /// tag: MA
@@ -1100,6 +1158,10 @@ impl QueryChunk for TestChunk {
         self.order
     }
 
+    fn tag_index(&self) -> Option<Arc<TagIndex>> {
+        self.tag_index.as_ref().map(Arc::clone)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -6,7 +6,9 @@ use std::{
 };
 
 use arrow::{
-    array::TimestampNanosecondArray, compute::SortOptions, datatypes::Schema as ArrowSchema,
+    array::TimestampNanosecondArray,
+    compute::SortOptions,
+    datatypes::{DataType, Schema as ArrowSchema},
     record_batch::RecordBatch,
 };
 
@@ -16,7 +18,7 @@ use datafusion::{
     common::ToDFSchema,
     datasource::{provider_as_source, MemTable},
     error::DataFusionError,
-    execution::context::ExecutionProps,
+    execution::context::{ExecutionProps, SessionConfig},
     logical_expr::{LogicalPlan, LogicalPlanBuilder},
     optimizer::simplify_expressions::{ExprSimplifier, SimplifyContext},
     physical_expr::create_physical_expr,
@@ -33,6 +35,11 @@ use observability_deps::tracing::trace;
 use schema::{sort::SortKey, InfluxColumnType, Schema, TIME_COLUMN_NAME};
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
 
+use crate::config::IoxConfigExt;
+
+/// Assumed average size in bytes of a variable-width value (e.g. a string), used to estimate the size of a row.
+const ESTIMATED_VARIABLE_VALUE_BYTES: usize = 32;
+
 #[derive(Debug, Snafu)]
 #[allow(missing_copy_implementations, missing_docs)]
 pub enum Error {
@@ -228,6 +235,48 @@ pub fn create_basic_summary(
     }
 }
 
+/// Number of rows that roughly fill a [`RecordBatch`] of [`IoxConfigExt::target_batch_bytes`] for data of the given
+/// `schema`, so that wide tables produce batches with fewer rows and narrow tables batches with more rows.
+///
+/// Returns [`None`] if no target size is configured.
+pub fn target_batch_rows(schema: &ArrowSchema, session_config: &SessionConfig) -> Option<usize> {
+    let target_batch_bytes = session_config
+        .options()
+        .extensions
+        .get::<IoxConfigExt>()
+        .map(|config| config.target_batch_bytes)
+        .unwrap_or_default();
+    if target_batch_bytes == 0 {
+        return None;
+    }
+
+    Some((target_batch_bytes / estimated_row_bytes(schema)).max(1))
+}
+
+/// Estimated in-memory size of one row of `schema` in bytes.
+fn estimated_row_bytes(schema: &ArrowSchema) -> usize {
+    schema
+        .fields()
+        .iter()
+        .map(|field| estimated_value_bytes(field.data_type()))
+        .sum::<usize>()
+        .max(1)
+}
+
+fn estimated_value_bytes(data_type: &DataType) -> usize {
+    match data_type {
+        DataType::Boolean => 1,
+        // dictionary values are shared by many rows, only count the keys
+        DataType::Dictionary(key_type, _) => estimated_value_bytes(key_type),
+        // offsets and data
+        DataType::Utf8 | DataType::Binary => 4 + ESTIMATED_VARIABLE_VALUE_BYTES,
+        DataType::LargeUtf8 | DataType::LargeBinary => 8 + ESTIMATED_VARIABLE_VALUE_BYTES,
+        data_type => data_type
+            .primitive_width()
+            .unwrap_or(ESTIMATED_VARIABLE_VALUE_BYTES),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use datafusion::scalar::ScalarValue;
@@ -308,6 +357,33 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_target_batch_rows() {
+        let schema = full_schema();
+        let schema = schema.as_arrow();
+
+        // not configured
+        let session_config = SessionConfig::new().with_option_extension(IoxConfigExt::default());
+        assert_eq!(target_batch_rows(&schema, &session_config), None);
+        let session_config = SessionConfig::new();
+        assert_eq!(target_batch_rows(&schema, &session_config), None);
+
+        // tag (4) + bool (1) + float (8) + integer (8) + string (36) + uinteger (8) + time (8)
+        assert_eq!(estimated_row_bytes(&schema), 73);
+        let session_config = SessionConfig::new().with_option_extension(IoxConfigExt {
+            target_batch_bytes: 73 * 1000,
+            ..Default::default()
+        });
+        assert_eq!(target_batch_rows(&schema, &session_config), Some(1000));
+
+        // always at least one row
+        let session_config = SessionConfig::new().with_option_extension(IoxConfigExt {
+            target_batch_bytes: 1,
+            ..Default::default()
+        });
+        assert_eq!(target_batch_rows(&schema, &session_config), Some(1));
+    }
+
     fn full_schema() -> Schema {
         SchemaBuilder::new()
             .tag("tag")
@@ -17,30 +17,39 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
-use arrow::datatypes::DataType;
-use data_types::ChunkId;
+use arrow::datatypes::{DataType, IntervalMonthDayNanoType};
+use data_types::{ChunkId, TimestampRange, MAX_NANO_TIME, MIN_NANO_TIME};
 use datafusion::{
     common::DFSchemaRef,
     error::DataFusionError,
-    logical_expr::{utils::exprlist_to_columns, ExprSchemable, LogicalPlan, LogicalPlanBuilder},
-    prelude::{when, Column, Expr},
+    logical_expr::{
+        utils::exprlist_to_columns, BinaryExpr, ExprSchemable, Extension, LogicalPlan,
+        LogicalPlanBuilder, Operator,
+    },
+    prelude::{lit, lit_timestamp_nano, when, Column, Expr},
+    scalar::ScalarValue,
 };
 use datafusion_util::{
     config::{DEFAULT_CATALOG, DEFAULT_SCHEMA},
     AsExpr,
 };
 use futures::{Stream, StreamExt, TryStreamExt};
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use iox_query::{
     exec::{
-        field::FieldColumns, fieldlist::Field, make_non_null_checker, make_schema_pivot,
-        stringset::StringSet, IOxSessionContext,
+        field::FieldColumns,
+        fieldlist::Field,
+        gapfill::{FillStrategy, GapFill, GapFillParams},
+        make_non_null_checker, make_schema_pivot,
+        stringset::StringSet,
+        IOxSessionContext,
     },
     plan::{
-        fieldlist::FieldListPlan,
+        fieldlist::{FieldListPlan, MeasurementFieldListPlan},
         seriesset::{SeriesSetPlan, SeriesSetPlans},
         stringset::{Error as StringSetError, StringSetPlan, StringSetPlanBuilder},
     },
+    tag_index::TagIndex,
     QueryChunk, QueryNamespace,
 };
 use observability_deps::tracing::{debug, warn};
@@ -52,14 +61,17 @@ use predicate::{
     Predicate,
 };
 use query_functions::{
-    group_by::{Aggregate, WindowDuration},
+    group_by::{Aggregate, NamedAggregateExpr, WindowDuration, WindowFill},
     make_window_bound_expr,
-    selectors::{selector_first, selector_last, selector_max, selector_min},
+    selectors::{
+        selector_bottom, selector_first, selector_last, selector_max, selector_min, selector_top,
+    },
+    unsigned_sum::unsigned_sum,
 };
 use schema::{InfluxColumnType, Projection, Schema, TIME_COLUMN_NAME};
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use std::collections::{BTreeMap, HashSet as StdHashSet};
-use std::{cmp::Reverse, collections::BTreeSet, sync::Arc};
+use std::{cmp::Reverse, collections::BTreeSet, ops::Bound, sync::Arc};
 
 use crate::scan_plan::ScanPlanBuilder;
 
@@ -99,6 +111,9 @@ pub enum Error {
     #[snafu(display("gRPC planner got error building plan: {}", source))]
     BuildingPlan { source: DataFusionError },
 
+    #[snafu(display("gRPC planner got error configuring query context: {}", source))]
+    ConfiguringContext { source: DataFusionError },
+
     #[snafu(display("gRPC planner got error reading columns from expression: {}", source))]
     ReadColumns {
         source: datafusion::error::DataFusionError,
@@ -157,6 +172,18 @@ pub enum Error {
         source: DataFusionError,
     },
 
+    #[snafu(display(
+        "Aggregate {:?} not supported for field '{}' of type {}",
+        agg,
+        field_name,
+        data_type
+    ))]
+    UnsupportedAggregateFieldType {
+        agg: Aggregate,
+        field_name: String,
+        data_type: DataType,
+    },
+
     #[snafu(display("Internal error: unexpected aggregate request for None aggregate",))]
     InternalUnexpectedNoneAggregate {},
 
@@ -165,6 +192,12 @@ pub enum Error {
 
     #[snafu(display("Table was removed while planning query: {}", table_name))]
     TableRemoved { table_name: String },
+
+    #[snafu(display("Window fill {:?} not supported: {}", fill, reason))]
+    UnsupportedWindowFill {
+        fill: WindowFill,
+        reason: &'static str,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -177,6 +210,7 @@ impl Error {
             Self::GettingChunks { source, .. }
             | Self::CreatingPredicates { source, .. }
             | Self::BuildingPlan { source, .. }
+            | Self::ConfiguringContext { source, .. }
             | Self::ReadColumns { source, .. }
             | Self::CheckingChunkPredicate { source, .. }
             | Self::CastingAggregates { source, .. } => {
@@ -185,7 +219,9 @@ impl Error {
             Self::TableRemoved { .. }
             | Self::InvalidTagColumn { .. }
             | Self::DuplicateGroupColumn { .. }
-            | Self::GroupColumnNotFound { .. } => DataFusionError::Plan(msg),
+            | Self::GroupColumnNotFound { .. }
+            | Self::UnsupportedAggregateFieldType { .. }
+            | Self::UnsupportedWindowFill { .. } => DataFusionError::Plan(msg),
             e @ (Self::CreatingStringSet { .. }
             | Self::InternalInvalidTagType { .. }
             | Self::CreatingAggregates { .. }
@@ -208,6 +244,19 @@ impl From<DataFusionError> for Error {
     }
 }
 
+/// The order of the points within each series output by
+/// [`InfluxRpcPlanner::read_filter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PointOrder {
+    /// Points are sorted by time, even if the series is stored in
+    /// overlapping chunks.
+    #[default]
+    Ascending,
+
+    /// Points may be in any order, which avoids merging them by time.
+    Unspecified,
+}
+
 /// Plans queries that originate from the InfluxDB Storage gRPC
 /// interface, which are in terms of the InfluxDB Data model (e.g.
 /// `ParsedLine`). The query methods on this trait such as
@@ -317,6 +366,7 @@ impl InfluxRpcPlanner {
     ) -> Result<StringSetPlan> {
         let ctx = self.ctx.child_ctx("tag_keys planning");
         debug!(?rpc_predicate, "planning tag_keys");
+        enable_tag_index(&ctx).await?;
 
         // Special case predicates that span the entire valid timestamp range
         let rpc_predicate = rpc_predicate.clear_timestamp_if_max_range();
@@ -371,6 +421,16 @@ impl InfluxRpcPlanner {
 
                 let chunks = prune_chunks(&table_schema, chunks, &predicate);
                 for chunk in cheap_chunk_first(chunks) {
+                    if let Some(tag_index) = chunk_tag_index(chunk.as_ref(), &predicate) {
+                        debug!(
+                            %table_name,
+                            chunk_id=%chunk.id().get(),
+                            "column names found from tag index",
+                        );
+                        known_columns.extend(tag_index.tag_keys().map(ToString::to_string));
+                        continue;
+                    }
+
                     // get only tag columns from metadata
                     let schema = chunk.schema();
 
@@ -451,6 +511,7 @@ impl InfluxRpcPlanner {
     ) -> Result<StringSetPlan> {
         let ctx = self.ctx.child_ctx("tag_values planning");
         debug!(?rpc_predicate, tag_name, "planning tag_values");
+        enable_tag_index(&ctx).await?;
 
         // The basic algorithm is:
         //
@@ -492,6 +553,7 @@ impl InfluxRpcPlanner {
         )
         .and_then(|(table_name, table_schema, predicate, chunks)| async move {
             let mut chunks_full = vec![];
+            let mut known_values = StringSet::new();
 
             let chunks = prune_chunks(&table_schema, chunks, &predicate);
             for chunk in cheap_chunk_first(chunks) {
@@ -524,6 +586,18 @@ impl InfluxRpcPlanner {
                     }
                 );
 
+                if let Some(tag_index) = chunk_tag_index(chunk.as_ref(), &predicate) {
+                    debug!(
+                        %table_name,
+                        chunk_id=%chunk.id().get(),
+                        "tag values found from tag index"
+                    );
+                    if let Some(values) = tag_index.tag_values(tag_name) {
+                        known_values.extend(values.iter().cloned());
+                    }
+                    continue;
+                }
+
                 debug!(
                     %table_name,
                     chunk_id=%chunk.id().get(),
@@ -532,7 +606,7 @@ impl InfluxRpcPlanner {
                 chunks_full.push(chunk);
             }
 
-            Ok((table_name, predicate, chunks_full))
+            Ok((table_name, predicate, chunks_full, known_values))
         })
         .try_collect()
         .await?;
@@ -542,9 +616,11 @@ impl InfluxRpcPlanner {
         let select_exprs = vec![tag_name.as_expr()];
 
         // At this point, we have a set of tag_values we know at plan
-        // time in `known_columns`, and some tables in chunks that we
+        // time in `known_values`, and some tables in chunks that we
         // need to run a plan to find what values pass the predicate.
-        for (table_name, predicate, chunks_full) in tables {
+        for (table_name, predicate, chunks_full, known_values) in tables {
+            builder = builder.append_other(known_values.into());
+
             if !chunks_full.is_empty() {
                 let schema = self
                     .meta
@@ -594,8 +670,21 @@ impl InfluxRpcPlanner {
         namespace: Arc<dyn QueryNamespace>,
         rpc_predicate: InfluxRpcPredicate,
     ) -> Result<FieldListPlan> {
+        self.measurement_field_columns(namespace, rpc_predicate)
+            .await
+            .map(FieldListPlan::from)
+    }
+
+    /// Returns a plan that produces the same columns as [`Self::field_columns`], but grouped by
+    /// the table (measurement) they are in, so that the fields of all tables can be listed with a
+    /// single execution.
+    pub async fn measurement_field_columns(
+        &self,
+        namespace: Arc<dyn QueryNamespace>,
+        rpc_predicate: InfluxRpcPredicate,
+    ) -> Result<MeasurementFieldListPlan> {
         let ctx = self.ctx.child_ctx("field_columns planning");
-        debug!(?rpc_predicate, "planning field_columns");
+        debug!(?rpc_predicate, "planning measurement_field_columns");
 
         // Special case predicates that span the entire valid timestamp range
         let rpc_predicate = rpc_predicate.clear_timestamp_if_max_range();
@@ -610,31 +699,58 @@ impl InfluxRpcPlanner {
             .table_predicates(self.meta.as_ref())
             .context(CreatingPredicatesSnafu)?;
 
-        // optimization: just get the field columns from metadata.
+        // optimization: just get the field columns from metadata, and their
+        // row and null counts from the chunk statistics.
         // note this both ignores field keys, and sets the timestamp data 'incorrectly'.
-        let mut field_list_plan = FieldListPlan::new();
+        let mut field_list_plan = MeasurementFieldListPlan::new();
+        let mut table_predicates_from_stats = Vec::with_capacity(table_predicates.len());
         let mut table_predicates_need_chunks = Vec::with_capacity(table_predicates.len());
         for (table_name, predicate) in table_predicates {
             if predicate.is_empty() {
-                let schema = self
-                    .meta
-                    .table_schema(&table_name)
-                    .context(TableRemovedSnafu {
-                        table_name: table_name.as_ref(),
-                    })?;
-                let fields = schema.fields_iter().map(|f| Field {
-                    name: f.name().clone(),
-                    data_type: f.data_type().clone(),
-                    last_timestamp: 0,
-                });
-                for field in fields {
-                    field_list_plan.append_field(field);
-                }
+                table_predicates_from_stats.push((table_name, predicate));
             } else {
                 table_predicates_need_chunks.push((table_name, predicate));
             }
         }
 
+        let table_chunks: HashMap<_, _> = table_chunk_stream(
+            Arc::clone(&namespace),
+            true,
+            &table_predicates_from_stats,
+            &ctx,
+            &self.meta,
+        )
+        .map_ok(|(table_name, _, _, chunks)| (Arc::clone(table_name), chunks))
+        .try_collect()
+        .await?;
+
+        for (table_name, _) in &table_predicates_from_stats {
+            let schema = self
+                .meta
+                .table_schema(table_name)
+                .context(TableRemovedSnafu {
+                    table_name: table_name.as_ref(),
+                })?;
+            let chunks = table_chunks
+                .get(table_name)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            for f in schema.fields_iter() {
+                let (row_count, null_count) = field_counts(f.name(), chunks);
+                field_list_plan.append_field(
+                    Arc::clone(table_name),
+                    Field {
+                        name: f.name().clone(),
+                        data_type: f.data_type().clone(),
+                        last_timestamp: 0,
+                        row_count,
+                        null_count,
+                    },
+                );
+            }
+        }
+
         // full scans
         let plans = create_plans(
             namespace,
@@ -642,12 +758,15 @@ impl InfluxRpcPlanner {
             ctx,
             Arc::clone(&self.meta),
             |table_name, predicate, chunks, schema| {
-                Self::field_columns_plan(Arc::from(table_name), schema, predicate, chunks)
+                let table_name: Arc<str> = Arc::from(table_name);
+                let plan =
+                    Self::field_columns_plan(Arc::clone(&table_name), schema, predicate, chunks)?;
+                Ok((table_name, plan))
             },
         )
         .await?;
-        for plan in plans {
-            field_list_plan = field_list_plan.append_other(plan.into());
+        for (table_name, plan) in plans {
+            field_list_plan.append(table_name, plan.into());
         }
 
         Ok(field_list_plan)
@@ -670,14 +789,17 @@ impl InfluxRpcPlanner {
     ///
     /// The data is sorted on (tag_col1, tag_col2, ...) so that all
     /// rows for a particular series (groups where all tags are the
-    /// same) occur together in the plan
+    /// same) occur together in the plan. The rows of each series are
+    /// sorted by time, unless `point_order` is
+    /// [`PointOrder::Unspecified`].
     pub async fn read_filter(
         &self,
         namespace: Arc<dyn QueryNamespace>,
         rpc_predicate: InfluxRpcPredicate,
+        point_order: PointOrder,
     ) -> Result<SeriesSetPlans> {
         let ctx = self.ctx.child_ctx("planning_read_filter");
-        debug!(?rpc_predicate, "planning read_filter");
+        debug!(?rpc_predicate, ?point_order, "planning read_filter");
 
         let table_predicates = rpc_predicate
             .table_predicates(self.meta.as_ref())
@@ -689,7 +811,7 @@ impl InfluxRpcPlanner {
             ctx,
             Arc::clone(&self.meta),
             |table_name, predicate, chunks, schema| {
-                Self::read_filter_plan(table_name, schema, predicate, chunks)
+                Self::read_filter_plan(table_name, schema, predicate, point_order, chunks)
             },
         )
         .await?;
@@ -785,9 +907,13 @@ impl InfluxRpcPlanner {
                 }
 
                 match agg {
-                    Aggregate::None => {
-                        Self::read_filter_plan(table_name, schema, predicate, chunks)
-                    }
+                    Aggregate::None => Self::read_filter_plan(
+                        table_name,
+                        schema,
+                        predicate,
+                        PointOrder::Ascending,
+                        chunks,
+                    ),
                     _ => Self::read_group_plan(table_name, schema, predicate, agg, chunks),
                 }
             },
@@ -806,6 +932,7 @@ impl InfluxRpcPlanner {
         agg: Aggregate,
         every: WindowDuration,
         offset: WindowDuration,
+        fill: WindowFill,
     ) -> Result<SeriesSetPlans> {
         let ctx = self.ctx.child_ctx("read_window_aggregate planning");
         debug!(
@@ -813,6 +940,7 @@ impl InfluxRpcPlanner {
             ?agg,
             ?every,
             ?offset,
+            ?fill,
             "planning read_window_aggregate"
         );
 
@@ -827,7 +955,51 @@ impl InfluxRpcPlanner {
             Arc::clone(&self.meta),
             |table_name, predicate, chunks, schema| {
                 Self::read_window_aggregate_plan(
-                    table_name, schema, predicate, agg, every, offset, chunks,
+                    table_name, schema, predicate, agg, every, offset, fill, chunks,
+                )
+            },
+        )
+        .await?;
+
+        Ok(SeriesSetPlans::new(plans))
+    }
+
+    /// Creates a GroupedSeriesSet plan like [`Self::read_window_aggregate`],
+    /// but with one field per expression in `exprs`, calculated from the
+    /// aggregates of the fields of each window.
+    pub async fn read_window_aggregate_expressions(
+        &self,
+        namespace: Arc<dyn QueryNamespace>,
+        rpc_predicate: InfluxRpcPredicate,
+        exprs: Vec<NamedAggregateExpr>,
+        every: WindowDuration,
+        offset: WindowDuration,
+        fill: WindowFill,
+    ) -> Result<SeriesSetPlans> {
+        let ctx = self
+            .ctx
+            .child_ctx("read_window_aggregate_expressions planning");
+        debug!(
+            ?rpc_predicate,
+            ?exprs,
+            ?every,
+            ?offset,
+            ?fill,
+            "planning read_window_aggregate_expressions"
+        );
+
+        let table_predicates = rpc_predicate
+            .table_predicates(self.meta.as_ref())
+            .context(CreatingPredicatesSnafu)?;
+
+        let plans = create_plans(
+            namespace,
+            &table_predicates,
+            ctx,
+            Arc::clone(&self.meta),
+            |table_name, predicate, chunks, schema| {
+                Self::read_window_aggregate_expressions_plan(
+                    table_name, schema, predicate, &exprs, every, offset, fill, chunks,
                 )
             },
         )
@@ -997,12 +1169,18 @@ impl InfluxRpcPlanner {
     ///
     ///    Projection (select the columns needed)
     ///      Order by (tag_columns, timestamp_column)
-    ///        Filter(predicate)
-    ///          Scan
+    ///        Projection (tag_columns, field columns needed, timestamp_column)
+    ///          Filter(predicate)
+    ///            Scan
+    ///
+    /// The field columns not selected by the predicate are removed before
+    /// sorting, so they are not read from the chunks at all. The timestamp
+    /// column is left out of the sort for [`PointOrder::Unspecified`].
     fn read_filter_plan(
         table_name: &str,
         schema: &Schema,
         predicate: &Predicate,
+        point_order: PointOrder,
         chunks: Vec<Arc<dyn QueryChunk>>,
     ) -> Result<SeriesSetPlan> {
         let scan_and_filter = ScanPlanBuilder::new(Arc::from(table_name), schema)
@@ -1012,19 +1190,33 @@ impl InfluxRpcPlanner {
 
         let schema = scan_and_filter.provider.iox_schema();
 
-        let tags_and_timestamp: Vec<_> = scan_and_filter
+        let timestamp = match point_order {
+            PointOrder::Ascending => Some(scan_and_filter.schema().time_iter()),
+            PointOrder::Unspecified => None,
+        };
+        let sort_exprs: Vec<_> = scan_and_filter
             .schema()
             .tags_iter()
-            .chain(scan_and_filter.schema().time_iter())
+            .chain(timestamp.into_iter().flatten())
             .map(|f| f.name() as &str)
             // Convert to SortExprs to pass to the plan builder
             .map(|n| n.as_sort_expr())
             .collect();
 
+        // Only read the field columns that are output
+        let scan_columns: Vec<Expr> = schema
+            .tags_iter()
+            .map(|field| field.name().as_expr())
+            .chain(filtered_fields_iter(schema, predicate).map(|f| f.name.as_expr()))
+            .chain(schema.time_iter().map(|field| field.name().as_expr()))
+            .collect();
+
         // Order by
         let plan_builder = scan_and_filter
             .plan_builder
-            .sort(tags_and_timestamp)
+            .project(scan_columns)
+            .context(BuildingPlanSnafu)?
+            .sort(sort_exprs)
             .context(BuildingPlanSnafu)?;
 
         // Select away anything that isn't in the influx data model
@@ -1096,6 +1288,10 @@ impl InfluxRpcPlanner {
     /// ORDER BY
     ///   tags
     ///
+    /// The 'selector' functions top and bottom select several rows per
+    /// tagset, which are unnested into one row per selected value (see
+    /// [`unnest_selected_rows`]) and ordered by tags and time.
+    ///
     /// The created plan looks like:
     ///
     ///  OrderBy(gby cols; agg)
@@ -1136,10 +1332,23 @@ impl InfluxRpcPlanner {
             .aggregate(group_exprs, agg_exprs)
             .context(BuildingPlanSnafu)?;
 
+        // top / bottom selectors select several rows per series, which are
+        // ordered by time
+        let (plan_builder, time_sort_exprs) = match agg {
+            Aggregate::Top(_) | Aggregate::Bottom(_) => (
+                unnest_selected_rows(
+                    plan_builder.build().context(BuildingPlanSnafu)?,
+                    tag_columns.len(),
+                )?,
+                selected_time_sort_exprs(&field_columns),
+            ),
+            _ => (plan_builder, vec![]),
+        };
+
         // Reorganize the output so it is ordered and sorted on tag columns
 
         // no columns if there are no tags in the input and no group columns in the query
-        let plan_builder = if !tag_columns.is_empty() {
+        let plan_builder = if !tag_columns.is_empty() || !time_sort_exprs.is_empty() {
             // reorder columns
             let reorder_exprs = tag_columns
                 .iter()
@@ -1149,6 +1358,7 @@ impl InfluxRpcPlanner {
             let sort_exprs = reorder_exprs
                 .iter()
                 .map(|expr| expr.as_sort_expr())
+                .chain(time_sort_exprs)
                 .collect::<Vec<_>>();
 
             let project_exprs = project_exprs_in_schema(&tag_columns, plan_builder.schema());
@@ -1205,6 +1415,14 @@ impl InfluxRpcPlanner {
     ///      GroupBy(gby: tag columns, window_function; agg: aggregate(field))
     ///        Filter(predicate)
     ///          Scan
+    ///
+    /// For the top and bottom selectors, the rows selected in each window
+    /// are unnested into one row per selected value (see
+    /// [`unnest_selected_rows`]).
+    ///
+    /// Unless `fill` is [`WindowFill::None`], a `GapFill` node between the
+    /// `GroupBy` and the `OrderBy` fills the windows without data, see
+    /// [`window_fill`].
     #[allow(clippy::too_many_arguments)]
     fn read_window_aggregate_plan(
         table_name: &str,
@@ -1213,6 +1431,7 @@ impl InfluxRpcPlanner {
         agg: Aggregate,
         every: WindowDuration,
         offset: WindowDuration,
+        fill: WindowFill,
         chunks: Vec<Arc<dyn QueryChunk>>,
     ) -> Result<SeriesSetPlan> {
         let scan_and_filter = ScanPlanBuilder::new(Arc::from(table_name), schema)
@@ -1243,10 +1462,60 @@ impl InfluxRpcPlanner {
             .map(|expr| expr.as_sort_expr())
             .collect::<Vec<_>>();
 
+        let num_group_exprs = group_exprs.len();
         let plan_builder = scan_and_filter
             .plan_builder
-            .aggregate(group_exprs, agg_exprs)?
-            .sort(sort_exprs)?;
+            .aggregate(group_exprs, agg_exprs)?;
+
+        let plan_builder = match fill {
+            WindowFill::None if matches!(agg, Aggregate::Top(_) | Aggregate::Bottom(_)) => {
+                let plan_builder = unnest_selected_rows(plan_builder.build()?, num_group_exprs)?;
+
+                // the selected rows of each series are ordered by time
+                let sort_exprs = schema
+                    .tags_iter()
+                    .map(|field| field.name().as_sort_expr())
+                    .chain(selected_time_sort_exprs(&field_columns))
+                    .collect::<Vec<_>>();
+                plan_builder.sort(sort_exprs)?
+            }
+            WindowFill::None => plan_builder.sort(sort_exprs)?,
+            fill => {
+                ensure!(
+                    !matches!(
+                        agg,
+                        Aggregate::First
+                            | Aggregate::Last
+                            | Aggregate::Min
+                            | Aggregate::Max
+                            | Aggregate::Top(_)
+                            | Aggregate::Bottom(_)
+                    ),
+                    UnsupportedWindowFillSnafu {
+                        fill,
+                        reason:
+                            "selectors report the time of the selected value, not of the window",
+                    }
+                );
+
+                let plan = window_fill(
+                    plan_builder.build()?,
+                    num_group_exprs,
+                    every,
+                    offset,
+                    fill,
+                    predicate.range,
+                )?;
+
+                // the filled plan has the group by expressions as columns
+                let sort_exprs = schema
+                    .tags_iter()
+                    .map(|field| field.name().as_sort_expr())
+                    .chain(std::iter::once(TIME_COLUMN_NAME.as_sort_expr()))
+                    .collect::<Vec<_>>();
+                LogicalPlanBuilder::from(plan).sort(sort_exprs)?
+            }
+        };
 
         let plan_builder = cast_aggregates(plan_builder, agg, &field_columns)?;
 
@@ -1265,6 +1534,152 @@ impl InfluxRpcPlanner {
             field_columns,
         ))
     }
+
+    /// Creates a GroupedSeriesSet plan that calculates `exprs` for each
+    /// window, by first aggregating the fields the expressions refer to:
+    ///
+    ///  Projection(tag columns, time, exprs over the aggregates)
+    ///    OrderBy(gby: tag columns, window_function)
+    ///      GroupBy(gby: tag columns, window_function; agg: aggregate(field), ...)
+    ///        Filter(predicate)
+    ///          Scan
+    ///
+    /// Expressions referring to fields the table does not have, or that
+    /// do not pass the predicate, are not calculated for this table.
+    #[allow(clippy::too_many_arguments)]
+    fn read_window_aggregate_expressions_plan(
+        table_name: &str,
+        schema: &Schema,
+        predicate: &Predicate,
+        exprs: &[NamedAggregateExpr],
+        every: WindowDuration,
+        offset: WindowDuration,
+        fill: WindowFill,
+        chunks: Vec<Arc<dyn QueryChunk>>,
+    ) -> Result<SeriesSetPlan> {
+        let scan_and_filter = ScanPlanBuilder::new(Arc::from(table_name), schema)
+            .with_predicate(predicate)
+            .with_chunks(chunks)
+            .build()?;
+
+        let schema = scan_and_filter.provider.iox_schema();
+
+        // Group by all tag columns and the window bounds
+        let window_bound = make_window_bound_expr(TIME_COLUMN_NAME.as_expr(), every, offset)
+            .alias(TIME_COLUMN_NAME);
+
+        let group_exprs = schema
+            .tags_iter()
+            .map(|field| field.name().as_expr())
+            .chain(std::iter::once(window_bound))
+            .collect::<Vec<_>>();
+        let num_group_exprs = group_exprs.len();
+
+        let fields = filtered_fields_iter(schema, predicate)
+            .map(|field| (field.name, field))
+            .collect::<BTreeMap<_, _>>();
+        let exprs = exprs
+            .iter()
+            .filter(|expr| {
+                expr.expr
+                    .field_aggregates()
+                    .iter()
+                    .all(|(_agg, field)| fields.contains_key(field))
+            })
+            .collect::<Vec<_>>();
+
+        // aggregate every field only once per aggregate, even if several
+        // expressions refer to it
+        let mut agg_names = StdHashSet::new();
+        let mut agg_exprs = vec![];
+        for (agg, field) in exprs.iter().flat_map(|expr| expr.expr.field_aggregates()) {
+            let name = field_aggregate_column_name(agg, field);
+            if !agg_names.insert(name.clone()) {
+                continue;
+            }
+
+            let field = fields[field].clone();
+            ensure!(
+                agg.supports_type(field.data_type),
+                UnsupportedAggregateFieldTypeSnafu {
+                    agg,
+                    field_name: field.name,
+                    data_type: field.data_type.clone(),
+                }
+            );
+
+            agg_exprs.push(make_agg_expr(agg, field)?.unalias().alias(name));
+        }
+
+        let sort_exprs = group_exprs
+            .iter()
+            .map(|expr| expr.as_sort_expr())
+            .collect::<Vec<_>>();
+
+        let plan_builder = scan_and_filter
+            .plan_builder
+            .aggregate(group_exprs, agg_exprs)?;
+
+        let plan_builder = match fill {
+            WindowFill::None => plan_builder.sort(sort_exprs)?,
+            fill => {
+                let plan = window_fill(
+                    plan_builder.build()?,
+                    num_group_exprs,
+                    every,
+                    offset,
+                    fill,
+                    predicate.range,
+                )?;
+
+                // the filled plan has the group by expressions as columns
+                let sort_exprs = schema
+                    .tags_iter()
+                    .map(|field| field.name().as_sort_expr())
+                    .chain(std::iter::once(TIME_COLUMN_NAME.as_sort_expr()))
+                    .collect::<Vec<_>>();
+                LogicalPlanBuilder::from(plan).sort(sort_exprs)?
+            }
+        };
+
+        // calculate the expressions from the aggregates
+        let projection = schema
+            .tags_iter()
+            .map(|field| field.name().as_expr())
+            .chain(std::iter::once(TIME_COLUMN_NAME.as_expr()))
+            .chain(exprs.iter().map(|expr| {
+                expr.expr
+                    .to_datafusion_expr(&|agg, field| {
+                        field_aggregate_column_name(agg, field).as_expr()
+                    })
+                    .alias(&expr.name)
+            }))
+            .collect::<Vec<_>>();
+        let plan = plan_builder.project(projection)?.build()?;
+
+        let tag_columns = schema
+            .tags_iter()
+            .map(|field| Arc::from(field.name().as_str()))
+            .collect();
+        let field_columns = exprs
+            .iter()
+            .map(|expr| Arc::from(expr.name.as_str()))
+            .collect::<Vec<_>>()
+            .into();
+
+        Ok(SeriesSetPlan::new(
+            Arc::from(table_name),
+            plan,
+            tag_columns,
+            field_columns,
+        ))
+    }
+}
+
+/// The name of the column holding the aggregate `agg` of the field
+/// `field_name`, when calculating expressions over aggregates
+fn field_aggregate_column_name(agg: Aggregate, field_name: &str) -> String {
+    format!("{agg:?}({field_name})")
 }
 
 /// Stream of chunks for table predicates.
@@ -1291,22 +1706,43 @@ fn table_chunk_stream<'a>(
         Vec<Arc<dyn QueryChunk>>,
     )>,
 > + 'a {
-    futures::stream::iter(table_predicates)
-        .filter_map(move |(table_name, predicate)| async move {
+    // tables may expire their data faster than the namespace
+    let retention_times_ns = table_predicates
+        .iter()
+        .map(|(table_name, _)| namespace.table_retention_time_ns(table_name))
+        .collect::<Vec<_>>();
+
+    futures::stream::iter(table_predicates.iter().zip(retention_times_ns))
+        .filter_map(move |((table_name, predicate), ret)| async move {
             let Some(table_schema) = meta.table_schema(table_name) else {
                 return None;
             };
+
+            // no row can match, so there is no need to fetch any chunks
+            let unsatisfiable = match ret {
+                Some(ret) => predicate
+                    .clone()
+                    .with_retention(ret)
+                    .canonicalize()
+                    .is_unsatisfiable(),
+                None => predicate.is_unsatisfiable(),
+            };
+            if unsatisfiable {
+                debug!(%table_name, %predicate, "skipping table for unsatisfiable predicate");
+                return None;
+            }
+
             let table_schema = Arc::new(table_schema);
-            Some((table_name, table_schema, predicate))
+            Some((table_name, table_schema, predicate, ret))
         })
-        .map(move |(table_name, table_schema, predicate)| {
+        .map(move |(table_name, table_schema, predicate, ret)| {
             let mut ctx = ctx.child_ctx("table");
             ctx.set_metadata("table", table_name.to_string());
 
             let namespace = Arc::clone(&namespace);
 
             async move {
-                let predicate = match namespace.retention_time_ns() {
+                let predicate = match ret {
                     Some(ret) => predicate.clone().with_retention(ret),
                     None => predicate.clone(),
                 };
@@ -1556,6 +1992,7 @@ pub(crate) struct AggExprs {
 struct FieldExpr<'a> {
     expr: Expr,
     name: &'a str,
+    data_type: &'a DataType,
 }
 
 // Returns an iterator of fields from schema that pass the predicate. If there
@@ -1593,6 +2030,7 @@ fn filtered_fields_iter<'a>(
         Some(FieldExpr {
             expr: expr.alias(f.name()),
             name: f.name(),
+            data_type: f.data_type(),
         })
     })
 }
@@ -1607,13 +2045,24 @@ impl AggExprs {
         schema: &Schema,
         predicate: &Predicate,
     ) -> Result<Self> {
+        check_field_types(agg, schema, predicate)?;
+
         match agg {
-            Aggregate::Sum | Aggregate::Count | Aggregate::Mean => {
-                Self::agg_for_read_group(agg, schema, predicate)
-            }
+            Aggregate::Sum
+            | Aggregate::Count
+            | Aggregate::Mean
+            | Aggregate::Rate
+            | Aggregate::Derivative
+            | Aggregate::NonNegativeDerivative
+            | Aggregate::Increase
+            | Aggregate::Any
+            | Aggregate::All => Self::agg_for_read_group(agg, schema, predicate),
             Aggregate::First | Aggregate::Last | Aggregate::Min | Aggregate::Max => {
                 Self::selector_aggregates(agg, schema, predicate)
             }
+            Aggregate::Top(_) | Aggregate::Bottom(_) => {
+                Self::multi_selector_aggregates(agg, schema, predicate)
+            }
             Aggregate::None => InternalUnexpectedNoneAggregateSnafu.fail(),
         }
     }
@@ -1625,13 +2074,24 @@ impl AggExprs {
         schema: &Schema,
         predicate: &Predicate,
     ) -> Result<Self> {
+        check_field_types(agg, schema, predicate)?;
+
         match agg {
-            Aggregate::Sum | Aggregate::Count | Aggregate::Mean => {
-                Self::agg_for_read_window_aggregate(agg, schema, predicate)
-            }
+            Aggregate::Sum
+            | Aggregate::Count
+            | Aggregate::Mean
+            | Aggregate::Rate
+            | Aggregate::Derivative
+            | Aggregate::NonNegativeDerivative
+            | Aggregate::Increase
+            | Aggregate::Any
+            | Aggregate::All => Self::agg_for_read_window_aggregate(agg, schema, predicate),
             Aggregate::First | Aggregate::Last | Aggregate::Min | Aggregate::Max => {
                 Self::selector_aggregates(agg, schema, predicate)
             }
+            Aggregate::Top(_) | Aggregate::Bottom(_) => {
+                Self::multi_selector_aggregates(agg, schema, predicate)
+            }
             Aggregate::None => InternalUnexpectedNoneAggregateSnafu.fail(),
         }
     }
@@ -1658,7 +2118,7 @@ impl AggExprs {
             let field_name = field.name;
             agg_exprs.push(selector.clone().field("value").alias(field_name));
 
-            let time_column_name = format!("{TIME_COLUMN_NAME}_{field_name}");
+            let time_column_name = selected_time_column_name(field_name);
             agg_exprs.push(selector.field("time").alias(&time_column_name));
 
             field_list.push((
@@ -1674,6 +2134,41 @@ impl AggExprs {
         })
     }
 
+    // Creates "top" / "bottom" selector expressions for the fields in the
+    // provided schema. Each selector returns a list of the selected values and
+    // their times, see `unnest_selected_rows` for how these are turned into
+    // rows with a distinct time column for each field column.
+    //
+    // Equivalent SQL would look like:
+    //
+    //   agg_function(_val1, time, n) as _value1
+    //   ..
+    //   agg_function(_valN, time, n) as _valueN
+    fn multi_selector_aggregates(
+        agg: Aggregate,
+        schema: &Schema,
+        predicate: &Predicate,
+    ) -> Result<Self> {
+        let mut agg_exprs = Vec::new();
+        let mut field_list = Vec::new();
+
+        for field in filtered_fields_iter(schema, predicate) {
+            let field_name = field.name;
+            agg_exprs.push(make_selector_expr(agg, field)?.alias(field_name));
+
+            field_list.push((
+                Arc::from(field_name), // value name
+                Arc::from(selected_time_column_name(field_name).as_str()),
+            ));
+        }
+
+        let field_columns = field_list.into();
+        Ok(Self {
+            agg_exprs,
+            field_columns,
+        })
+    }
+
     // Creates aggregate expressions for use in a read_group plan, which
     // includes the time column.
     //
@@ -1692,6 +2187,7 @@ impl AggExprs {
                     FieldExpr {
                         expr: field.name().as_expr(),
                         name: field.name(),
+                        data_type: field.data_type(),
                     },
                 )
             }))
@@ -1739,6 +2235,115 @@ impl AggExprs {
     }
 }
 
+/// Ensures `agg` can be calculated for all fields of `schema` that pass
+/// `predicate`, e.g. that no boolean field is summed
+fn check_field_types(agg: Aggregate, schema: &Schema, predicate: &Predicate) -> Result<()> {
+    for field in filtered_fields_iter(schema, predicate) {
+        ensure!(
+            agg.supports_type(field.data_type),
+            UnsupportedAggregateFieldTypeSnafu {
+                agg,
+                field_name: field.name,
+                data_type: field.data_type.clone(),
+            }
+        );
+    }
+    Ok(())
+}
+
+/// Fills the windows without data of each series in the output of a
+/// `read_window_aggregate` aggregation, by adding a `GapFill` node on top of
+/// `aggregate_plan`. The first `num_group_exprs` columns of `aggregate_plan`
+/// are the tags and, last, the window bound `time` column.
+///
+/// The window bound is the *end* of each window, so the windows to fill are
+/// those ending within the predicate time range shifted by one window.
+fn window_fill(
+    aggregate_plan: LogicalPlan,
+    num_group_exprs: usize,
+    every: WindowDuration,
+    offset: WindowDuration,
+    fill: WindowFill,
+    range: Option<TimestampRange>,
+) -> Result<LogicalPlan> {
+    let (
+        WindowDuration::Fixed { nanoseconds: every },
+        WindowDuration::Fixed {
+            nanoseconds: offset,
+        },
+    ) = (every, offset)
+    else {
+        return UnsupportedWindowFillSnafu {
+            fill,
+            reason: "window durations in months are not supported",
+        }
+        .fail();
+    };
+
+    // Ranges are clamped to [MIN_NANO_TIME, MAX_NANO_TIME], which is also
+    // what clients send for an open range
+    let range =
+        range
+            .filter(|range| range.end() < MAX_NANO_TIME)
+            .context(UnsupportedWindowFillSnafu {
+                fill,
+                reason: "an upper time bound is required",
+            })?;
+    let start = if range.start() <= MIN_NANO_TIME {
+        // fill from the first window with data of each series
+        Bound::Unbounded
+    } else {
+        Bound::Included(lit_timestamp_nano(range.start().saturating_add(every)))
+    };
+    let end = Bound::Excluded(lit_timestamp_nano(range.end().saturating_add(every)));
+
+    let schema = aggregate_plan.schema();
+    let mut group_expr = schema
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()))
+        .collect::<Vec<_>>();
+    let aggr_expr = group_expr.split_off(num_group_exprs);
+    let time_column = group_expr
+        .last()
+        .cloned()
+        .expect("window bound is a group expression");
+
+    // Only numeric aggregates can be interpolated, the other
+    // aggregates are not filled
+    let fill_strategy = aggr_expr
+        .iter()
+        .zip(&schema.fields()[num_group_exprs..])
+        .map(|(expr, field)| {
+            let strategy = match fill {
+                WindowFill::Linear if field.data_type().is_numeric() => {
+                    FillStrategy::LinearInterpolate
+                }
+                WindowFill::Previous => FillStrategy::PrevNullAsMissing,
+                _ => FillStrategy::Null,
+            };
+            (expr.clone(), strategy)
+        })
+        .collect();
+
+    let params = GapFillParams {
+        stride: lit(ScalarValue::IntervalMonthDayNano(Some(
+            IntervalMonthDayNanoType::make_value(0, 0, every),
+        ))),
+        time_column,
+        origin: Some(lit_timestamp_nano(offset)),
+        time_range: start..end,
+        fill_strategy,
+    };
+
+    let gap_fill = GapFill::try_new(Arc::new(aggregate_plan), group_expr, aggr_expr, params)
+        .context(BuildingPlanSnafu)?;
+
+    Ok(LogicalPlan::Extension(Extension {
+        node: Arc::new(gap_fill),
+    }))
+}
+
 /// Creates a DataFusion expression suitable for calculating an aggregate:
 ///
 /// equivalent to `CAST agg(field) as field`
@@ -1754,6 +2359,12 @@ fn make_agg_expr(agg: Aggregate, field_expr: FieldExpr<'_>) -> Result<Expr> {
     };
 
     let field_name = field_expr.name;
+
+    // The sum of unsigned fields must not silently wrap around
+    if agg == Aggregate::Sum && field_expr.data_type == &DataType::UInt64 {
+        return Ok(unsigned_sum().call(vec![field_expr.expr]).alias(field_name));
+    }
+
     agg.to_datafusion_expr(field_expr.expr)
         .context(CreatingAggregatesSnafu)
         .map(|agg| agg.alias(field_name))
@@ -1775,21 +2386,228 @@ fn make_agg_expr(agg: Aggregate, field_expr: FieldExpr<'_>) -> Result<Expr> {
 /// END) as col_name
 ///
 fn make_selector_expr(agg: Aggregate, field: FieldExpr<'_>) -> Result<Expr> {
+    let mut args = vec![field.expr, TIME_COLUMN_NAME.as_expr()];
     let uda = match agg {
         Aggregate::First => selector_first(),
         Aggregate::Last => selector_last(),
         Aggregate::Min => selector_min(),
         Aggregate::Max => selector_max(),
+        Aggregate::Top(n) => {
+            args.push(lit(n));
+            selector_top()
+        }
+        Aggregate::Bottom(n) => {
+            args.push(lit(n));
+            selector_bottom()
+        }
         _ => return InternalAggregateNotSelectorSnafu { agg }.fail(),
     };
 
-    Ok(uda.call(vec![field.expr, TIME_COLUMN_NAME.as_expr()]))
+    Ok(uda.call(args))
+}
+
+/// The name of the column holding the time of the selected values of
+/// `field_name`
+fn selected_time_column_name(field_name: &str) -> String {
+    format!("{TIME_COLUMN_NAME}_{field_name}")
+}
+
+/// Sort expressions for the time columns of the selected values of each
+/// field, so the rows of a series that were selected for the same field are
+/// ordered by time
+fn selected_time_sort_exprs(field_columns: &FieldColumns) -> Vec<Expr> {
+    match field_columns {
+        FieldColumns::SharedTimestamp(_) => vec![],
+        FieldColumns::DifferentTimestamp(fields_and_timestamps) => fields_and_timestamps
+            .iter()
+            .map(|(_field, timestamp)| timestamp.as_sort_expr())
+            .collect(),
+    }
+}
+
+/// Top / bottom selectors return a list of the selected values and their
+/// times for each field of each group of `aggregate_plan`, of which the first
+/// `num_group_exprs` columns are the group columns. Creates a plan with a row
+/// for each selected value instead, by unnesting the lists of one field at a
+/// time.
+///
+/// Equivalent to this SQL query:
+///
+/// SELECT group columns,
+///   _val1['value'] as _val1, _val1['time'] as time__val1,
+///   NULL as _val2, NULL as time__val2,
+///   ...
+/// FROM unnest(_val1)
+/// UNION ALL
+/// SELECT group columns,
+///   NULL as _val1, NULL as time__val1,
+///   _val2['value'] as _val2, _val2['time'] as time__val2,
+///   ...
+/// FROM unnest(_val2)
+/// ...
+fn unnest_selected_rows(
+    aggregate_plan: LogicalPlan,
+    num_group_exprs: usize,
+) -> Result<LogicalPlanBuilder> {
+    let schema = Arc::clone(aggregate_plan.schema());
+    let (group_fields, selected_fields) = schema.fields().split_at(num_group_exprs);
+
+    // NULL values of the right types for the rows of other fields
+    let selected = selected_fields
+        .iter()
+        .map(|field| {
+            let struct_fields = match field.data_type() {
+                DataType::List(item) => match item.data_type() {
+                    DataType::Struct(struct_fields) if struct_fields.len() == 2 => {
+                        Some(struct_fields)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "expected a list of selected values for field '{}', got {}",
+                    field.name(),
+                    field.data_type()
+                ))
+            })?;
+
+            Ok((
+                field.name().as_str(),
+                ScalarValue::try_from(struct_fields[0].data_type())?,
+                ScalarValue::try_from(struct_fields[1].data_type())?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut union: Option<LogicalPlanBuilder> = None;
+    for (unnested_name, ..) in &selected {
+        let mut exprs = group_fields
+            .iter()
+            .map(|field| field.name().as_expr())
+            .collect::<Vec<_>>();
+        for (name, null_value, null_time) in &selected {
+            let time_column_name = selected_time_column_name(name);
+            if name == unnested_name {
+                exprs.push(name.as_expr().field("value").alias(*name));
+                exprs.push(name.as_expr().field("time").alias(time_column_name));
+            } else {
+                exprs.push(lit(null_value.clone()).alias(*name));
+                exprs.push(lit(null_time.clone()).alias(time_column_name));
+            }
+        }
+
+        let plan = LogicalPlanBuilder::from(aggregate_plan.clone())
+            .unnest_column(*unnested_name)?
+            .project(exprs)?
+            .build()?;
+
+        union = Some(match union {
+            Some(union) => union.union(plan)?,
+            None => LogicalPlanBuilder::from(plan),
+        });
+    }
+
+    // no fields were selected
+    Ok(union.unwrap_or_else(|| LogicalPlanBuilder::from(aggregate_plan)))
 }
 
 /// Orders chunks so it is likely that the ones that already have cached data are pulled first.
 ///
 /// We use the inverse chunk order as a heuristic here. See <https://github.com/influxdata/influxdb_iox/issues/5037> for
 /// a more advanced variant.
+/// Ask the namespace to provide the [`TagIndex`] of every chunk, see
+/// [`IoxConfigExt::tag_index`](iox_query::config::IoxConfigExt::tag_index).
+async fn enable_tag_index(ctx: &IOxSessionContext) -> Result<()> {
+    ctx.set_config_option("iox.tag_index", "true")
+        .await
+        .context(ConfiguringContextSnafu)
+}
+
+/// Returns the [`TagIndex`] of `chunk` if `predicate` selects every row of the chunk, in which
+/// case the index holds exactly the tag keys and values that pass the predicate.
+///
+/// Besides the time range, the only supported expressions are lower time bounds (such as the
+/// retention period of the namespace) that all rows of the chunk satisfy.
+fn chunk_tag_index(chunk: &dyn QueryChunk, predicate: &Predicate) -> Option<Arc<TagIndex>> {
+    if predicate.field_columns.is_some() || !predicate.value_expr.is_empty() {
+        return None;
+    }
+    let tag_index = chunk.tag_index()?;
+
+    if predicate.range.is_none() && predicate.exprs.is_empty() {
+        return Some(tag_index);
+    }
+
+    let (min, max) = chunk_time_range(chunk)?;
+    if let Some(range) = &predicate.range {
+        if !(range.contains(min) && range.contains(max)) {
+            return None;
+        }
+    }
+    for expr in &predicate.exprs {
+        let Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Gt,
+            right,
+        }) = expr
+        else {
+            return None;
+        };
+        match (left.as_ref(), right.as_ref()) {
+            (
+                Expr::Column(column),
+                Expr::Literal(ScalarValue::TimestampNanosecond(Some(ts), _)),
+            ) if column.name == TIME_COLUMN_NAME && min > *ts => {}
+            _ => return None,
+        }
+    }
+
+    Some(tag_index)
+}
+
+/// The number of rows in `chunks`, and the number of those in which the column
+/// `column_name` is null, if known from their statistics.
+fn field_counts(column_name: &str, chunks: &[Arc<dyn QueryChunk>]) -> (Option<u64>, Option<u64>) {
+    chunks
+        .iter()
+        .fold((Some(0), Some(0)), |(row_count, null_count), chunk| {
+            let stats = chunk.stats();
+            let chunk_rows = stats.num_rows.map(|n| n as u64);
+            let chunk_nulls = match chunk.schema().find_index_of(column_name) {
+                Some(idx) => stats
+                    .column_statistics
+                    .as_ref()
+                    .and_then(|columns| columns.get(idx))
+                    .and_then(|column| column.null_count)
+                    .map(|n| n as u64),
+                // the column is null in every row of a chunk without it
+                None => chunk_rows,
+            };
+
+            (
+                row_count.zip(chunk_rows).map(|(a, b)| a + b),
+                null_count.zip(chunk_nulls).map(|(a, b)| a + b),
+            )
+        })
+}
+
+/// The minimum and maximum timestamp of `chunk`, if known from its statistics.
+fn chunk_time_range(chunk: &dyn QueryChunk) -> Option<(i64, i64)> {
+    let idx = chunk.schema().find_index_of(TIME_COLUMN_NAME)?;
+    let stats = chunk.stats();
+    let column = stats.column_statistics.as_ref()?.get(idx)?;
+
+    match (&column.min_value, &column.max_value) {
+        (
+            Some(ScalarValue::TimestampNanosecond(Some(min), _)),
+            Some(ScalarValue::TimestampNanosecond(Some(max), _)),
+        ) => Some((*min, *max)),
+        _ => None,
+    }
+}
+
 fn cheap_chunk_first(mut chunks: Vec<Arc<dyn QueryChunk>>) -> Vec<Arc<dyn QueryChunk>> {
     chunks.sort_by_key(|chunk| Reverse(chunk.order()));
     chunks
@@ -1890,11 +2708,13 @@ impl QueryNamespaceMeta for NamespaceMeta {
 mod tests {
     use datafusion::{
         common::ScalarValue,
+        physical_plan::ExecutionPlan,
         prelude::{col, lit},
     };
     use datafusion_util::lit_dict;
     use futures::{future::BoxFuture, FutureExt};
     use predicate::Predicate;
+    use query_functions::group_by::{AggregateExpr, ArithmeticOperator};
 
     use iox_query::{
         exec::Executor,
@@ -2374,7 +3194,7 @@ mod tests {
             async move {
                 InfluxRpcPlanner::new(test_db.new_query_context(None))
                     .await
-                    .read_filter(test_db, rpc_predicate)
+                    .read_filter(test_db, rpc_predicate, PointOrder::Ascending)
                     .await
                     .expect("creating plan");
             }
@@ -2439,7 +3259,8 @@ mod tests {
         insta::assert_snapshot!(ssplan.plan.display_indent_schema().to_string(), @r###"
         Projection: h2o.foo, CASE WHEN h2o.foo.bar = Float64(1.2) THEN h2o.foo.bar END AS foo.bar, h2o.time [foo:Dictionary(Int32, Utf8);N, foo.bar:Float64;N, time:Timestamp(Nanosecond, None)]
           Sort: h2o.foo ASC NULLS FIRST, h2o.time ASC NULLS FIRST [foo:Dictionary(Int32, Utf8);N, foo.bar:Float64;N, time:Timestamp(Nanosecond, None)]
-            TableScan: h2o [foo:Dictionary(Int32, Utf8);N, foo.bar:Float64;N, time:Timestamp(Nanosecond, None)]
+            Projection: h2o.foo, h2o.foo.bar, h2o.time [foo:Dictionary(Int32, Utf8);N, foo.bar:Float64;N, time:Timestamp(Nanosecond, None)]
+              TableScan: h2o [foo:Dictionary(Int32, Utf8);N, foo.bar:Float64;N, time:Timestamp(Nanosecond, None)]
         "###);
     }
 
@@ -2450,9 +3271,10 @@ mod tests {
                 let agg = Aggregate::First;
                 let every = WindowDuration::from_months(1, false);
                 let offset = WindowDuration::from_months(1, false);
+                let fill = WindowFill::None;
                 InfluxRpcPlanner::new(test_db.new_query_context(None))
                     .await
-                    .read_window_aggregate(test_db, rpc_predicate, agg, every, offset)
+                    .read_window_aggregate(test_db, rpc_predicate, agg, every, offset, fill)
                     .await
                     .expect("creating plan");
             }
@@ -2485,7 +3307,11 @@ mod tests {
 
         let res = InfluxRpcPlanner::new(test_db.new_query_context(None))
             .await
-            .read_filter(Arc::clone(&test_db) as _, rpc_predicate)
+            .read_filter(
+                Arc::clone(&test_db) as _,
+                rpc_predicate,
+                PointOrder::Ascending,
+            )
             .await
             .expect("creating plan");
         assert_eq!(res.plans.len(), 1);
@@ -2496,10 +3322,376 @@ mod tests {
         let ssplan = res.plans.first().unwrap();
         insta::assert_snapshot!(ssplan.plan.display_indent_schema().to_string(), @r###"
         Projection: table.tag, table.field AS field, table.time [tag:Dictionary(Int32, Utf8);N, field:Float64;N, time:Timestamp(Nanosecond, None)]
-          Sort: table.tag ASC NULLS FIRST, table.time ASC NULLS FIRST [field:Float64;N, tag:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None)]
-            Filter: table.tag = Dictionary(Int32, Utf8("MA")) AND table.time > TimestampNanosecond(1, None) [field:Float64;N, tag:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None)]
-              TableScan: table [field:Float64;N, tag:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None)]
+          Sort: table.tag ASC NULLS FIRST, table.time ASC NULLS FIRST [tag:Dictionary(Int32, Utf8);N, field:Float64;N, time:Timestamp(Nanosecond, None)]
+            Projection: table.tag, table.field, table.time [tag:Dictionary(Int32, Utf8);N, field:Float64;N, time:Timestamp(Nanosecond, None)]
+              Filter: table.tag = Dictionary(Int32, Utf8("MA")) AND table.time > TimestampNanosecond(1, None) [field:Float64;N, tag:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None)]
+                TableScan: table [field:Float64;N, tag:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None)]
+        "###);
+    }
+
+    #[tokio::test]
+    async fn test_read_filter_field_projection() {
+        maybe_start_logging();
+
+        let chunk = Arc::new(
+            TestChunk::new("h2o")
+                .with_id(0)
+                .with_tag_column("foo")
+                .with_i64_field_column("i64_field")
+                .with_i64_field_column("i64_field_2")
+                .with_time_column()
+                .with_one_row_of_data(),
+        );
+
+        let executor = Arc::new(Executor::new_testing());
+        let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
+        test_db.add_chunk("my_partition_key", Arc::clone(&chunk));
+
+        let predicate = Predicate::new().with_expr("_field".as_expr().eq(lit("i64_field")));
+        let rpc_predicate = InfluxRpcPredicate::new(None, predicate);
+
+        let ctx = test_db.new_query_context(None);
+        let res = InfluxRpcPlanner::new(ctx.child_ctx("planner"))
+            .await
+            .read_filter(
+                Arc::clone(&test_db) as _,
+                rpc_predicate,
+                PointOrder::Ascending,
+            )
+            .await
+            .expect("creating plan");
+        assert_eq!(res.plans.len(), 1);
+
+        // `i64_field_2` is removed before the sort...
+        let ssplan = res.plans.first().unwrap();
+        insta::assert_snapshot!(ssplan.plan.display_indent_schema().to_string(), @r###"
+        Projection: h2o.foo, h2o.i64_field AS i64_field, h2o.time [foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, time:Timestamp(Nanosecond, None)]
+          Sort: h2o.foo ASC NULLS FIRST, h2o.time ASC NULLS FIRST [foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, time:Timestamp(Nanosecond, None)]
+            Projection: h2o.foo, h2o.i64_field, h2o.time [foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, time:Timestamp(Nanosecond, None)]
+              TableScan: h2o [foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, i64_field_2:Int64;N, time:Timestamp(Nanosecond, None)]
         "###);
+
+        // ...and hence never read from the chunk
+        let physical_plan = ctx
+            .create_physical_plan(&ssplan.plan)
+            .await
+            .expect("creating physical plan");
+        fn leaf_columns(plan: &Arc<dyn ExecutionPlan>, out: &mut Vec<String>) {
+            let children = plan.children();
+            if children.is_empty() {
+                out.extend(plan.schema().fields().iter().map(|f| f.name().clone()));
+            }
+            for child in &children {
+                leaf_columns(child, out);
+            }
+        }
+        let mut columns = vec![];
+        leaf_columns(&physical_plan, &mut columns);
+        assert!(columns.contains(&"i64_field".to_string()), "{columns:?}");
+        assert!(!columns.contains(&"i64_field_2".to_string()), "{columns:?}");
+    }
+
+    #[tokio::test]
+    async fn test_read_window_aggregate_field_types() {
+        maybe_start_logging();
+
+        let chunk = Arc::new(
+            TestChunk::new("h2o")
+                .with_id(0)
+                .with_tag_column("foo")
+                .with_u64_column("u64_field")
+                .with_bool_field_column("bool_field")
+                .with_time_column()
+                .with_one_row_of_data(),
+        );
+
+        let executor = Arc::new(Executor::new_testing());
+        let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
+        test_db.add_chunk("my_partition_key", Arc::clone(&chunk));
+
+        let plan = |agg, field: Option<&'static str>| {
+            let test_db = Arc::clone(&test_db);
+            async move {
+                let predicate = match field {
+                    Some(field) => Predicate::new().with_expr("_field".as_expr().eq(lit(field))),
+                    None => Predicate::new(),
+                };
+                InfluxRpcPlanner::new(test_db.new_query_context(None))
+                    .await
+                    .read_window_aggregate(
+                        test_db as _,
+                        InfluxRpcPredicate::new(None, predicate),
+                        agg,
+                        WindowDuration::from_nanoseconds(10),
+                        WindowDuration::from_nanoseconds(0),
+                        WindowFill::None,
+                    )
+                    .await
+                    .map(|plan| plan.plans[0].plan.display_indent().to_string())
+            }
+        };
+
+        // unsigned sums fail on overflow instead of wrapping around
+        let sum = plan(Aggregate::Sum, Some("u64_field")).await.unwrap();
+        assert!(sum.contains("unsigned_sum(h2o.u64_field)"), "{sum}");
+
+        let count = plan(Aggregate::Count, None).await.unwrap();
+        assert!(count.contains("COUNT(h2o.bool_field)"), "{count}");
+
+        let any = plan(Aggregate::Any, Some("bool_field")).await.unwrap();
+        assert!(any.contains("BOOL_OR(h2o.bool_field)"), "{any}");
+
+        let all = plan(Aggregate::All, Some("bool_field")).await.unwrap();
+        assert!(all.contains("BOOL_AND(h2o.bool_field)"), "{all}");
+
+        let err = plan(Aggregate::Sum, None).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Aggregate Sum not supported for field 'bool_field' of type Boolean"
+        );
+
+        let err = plan(Aggregate::All, None).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Aggregate All not supported for field 'u64_field' of type UInt64"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_window_aggregate_fill_range() {
+        maybe_start_logging();
+
+        let chunk = Arc::new(
+            TestChunk::new("h2o")
+                .with_id(0)
+                .with_tag_column("foo")
+                .with_i64_field_column("i64_field")
+                .with_time_column()
+                .with_one_row_of_data(),
+        );
+
+        let executor = Arc::new(Executor::new_testing());
+        let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
+        test_db.add_chunk("my_partition_key", Arc::clone(&chunk));
+
+        let plan = |start, end| {
+            let test_db = Arc::clone(&test_db);
+            async move {
+                InfluxRpcPlanner::new(test_db.new_query_context(None))
+                    .await
+                    .read_window_aggregate(
+                        test_db as _,
+                        InfluxRpcPredicate::new(None, Predicate::new().with_range(start, end)),
+                        Aggregate::Sum,
+                        WindowDuration::from_nanoseconds(10),
+                        WindowDuration::from_nanoseconds(0),
+                        WindowFill::Linear,
+                    )
+                    .await
+                    .map(|plan| plan.plans[0].plan.display_indent().to_string())
+            }
+        };
+
+        let bounded = plan(100, 200).await.unwrap();
+        assert!(
+            bounded.contains("range=Included(Literal(TimestampNanosecond(110, None)))..Excluded(Literal(TimestampNanosecond(210, None)))"),
+            "{bounded}"
+        );
+
+        // an open start, which is clamped to MIN_NANO_TIME, fills from the
+        // first window with data instead of from 1677
+        for start in [i64::MIN, MIN_NANO_TIME] {
+            let open_start = plan(start, 200).await.unwrap();
+            assert!(
+                open_start
+                    .contains("range=Unbounded..Excluded(Literal(TimestampNanosecond(210, None)))"),
+                "{open_start}"
+            );
+        }
+
+        // an open end is rejected
+        for end in [i64::MAX, MAX_NANO_TIME] {
+            let err = plan(100, end).await.unwrap_err();
+            assert!(
+                err.to_string().contains("an upper time bound is required"),
+                "{err}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_window_aggregate_expressions() {
+        maybe_start_logging();
+
+        let chunk = Arc::new(
+            TestChunk::new("h2o")
+                .with_id(0)
+                .with_tag_column("foo")
+                .with_i64_field_column("bytes")
+                .with_i64_field_column("requests")
+                .with_time_column()
+                .with_one_row_of_data(),
+        );
+
+        let executor = Arc::new(Executor::new_testing());
+        let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
+        test_db.add_chunk("my_partition_key", Arc::clone(&chunk));
+
+        let sum = |field: &str| AggregateExpr::Field {
+            agg: Aggregate::Sum,
+            field: field.to_string(),
+        };
+        let exprs = vec![
+            NamedAggregateExpr {
+                name: "bytes_per_request".to_string(),
+                expr: AggregateExpr::Binary {
+                    left: Box::new(sum("bytes")),
+                    op: ArithmeticOperator::Divide,
+                    right: Box::new(sum("requests")),
+                },
+            },
+            NamedAggregateExpr {
+                name: "total_bytes".to_string(),
+                expr: sum("bytes"),
+            },
+            // the table has no such field
+            NamedAggregateExpr {
+                name: "unknown".to_string(),
+                expr: sum("unknown"),
+            },
+        ];
+
+        let res = InfluxRpcPlanner::new(test_db.new_query_context(None))
+            .await
+            .read_window_aggregate_expressions(
+                Arc::clone(&test_db) as _,
+                InfluxRpcPredicate::default(),
+                exprs,
+                WindowDuration::from_nanoseconds(10),
+                WindowDuration::from_nanoseconds(0),
+                WindowFill::None,
+            )
+            .await
+            .expect("creating plan");
+        assert_eq!(res.plans.len(), 1);
+
+        let ssplan = res.plans.first().unwrap();
+        assert_eq!(
+            ssplan.field_columns,
+            FieldColumns::SharedTimestamp(vec![
+                Arc::from("bytes_per_request"),
+                Arc::from("total_bytes")
+            ])
+        );
+
+        // each field is only aggregated once
+        let plan = ssplan.plan.display_indent().to_string();
+        assert_eq!(plan.matches("SUM(h2o.bytes)").count(), 1, "{plan}");
+        assert_eq!(plan.matches("SUM(h2o.requests)").count(), 1, "{plan}");
+
+        let schema = ssplan.plan.schema();
+        for name in ["bytes_per_request", "total_bytes"] {
+            assert_eq!(
+                schema
+                    .field_with_unqualified_name(name)
+                    .unwrap()
+                    .data_type(),
+                &DataType::Float64
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tag_metadata_from_tag_index() {
+        maybe_start_logging();
+
+        let chunk = Arc::new(
+            TestChunk::new("h2o")
+                .with_id(0)
+                .with_tag_column("tag1")
+                .with_i64_field_column("i64_field")
+                .with_time_column_with_stats(Some(8000), Some(20000))
+                .with_three_rows_of_data()
+                .with_tag_index(),
+        );
+
+        let executor = Arc::new(Executor::new_testing());
+        let test_db = Arc::new(TestDatabase::new(Arc::clone(&executor)));
+        test_db.add_chunk("my_partition_key", Arc::clone(&chunk));
+
+        let plan = |predicate: Predicate| {
+            let test_db = Arc::clone(&test_db);
+            async move {
+                let planner = InfluxRpcPlanner::new(test_db.new_query_context(None)).await;
+                let tag_keys = planner
+                    .tag_keys(
+                        Arc::clone(&test_db) as _,
+                        InfluxRpcPredicate::new(None, predicate.clone()),
+                    )
+                    .await
+                    .expect("creating plan");
+                let tag_values = planner
+                    .tag_values(
+                        test_db as _,
+                        "tag1",
+                        InfluxRpcPredicate::new(None, predicate),
+                    )
+                    .await
+                    .expect("creating plan");
+                (tag_keys, tag_values)
+            }
+        };
+        // the values if known without running a plan
+        let known = |plan: StringSetPlan| match plan {
+            StringSetPlan::Known(values) => Some(values.iter().cloned().collect::<Vec<_>>()),
+            StringSetPlan::Plan(_) => None,
+        };
+
+        // the time range covers all rows of the chunk
+        let (tag_keys, tag_values) = plan(Predicate::new().with_range(0, 30000)).await;
+        assert_eq!(known(tag_keys), Some(vec!["tag1".to_string()]));
+        assert_eq!(
+            known(tag_values),
+            Some(vec!["UT".to_string(), "VT".to_string(), "WA".to_string()])
+        );
+
+        // some rows may be outside of the time range
+        let (tag_keys, tag_values) = plan(Predicate::new().with_range(0, 10000)).await;
+        assert_eq!(known(tag_keys), None);
+        assert_eq!(known(tag_values), None);
+
+        // the tag index does not know which rows pass the expression
+        let (_, tag_values) =
+            plan(Predicate::new().with_expr(col("tag1").eq(lit_dict("WA")))).await;
+        assert_eq!(known(tag_values), None);
+    }
+
+    #[test]
+    fn test_chunk_tag_index() {
+        let chunk = TestChunk::new("h2o")
+            .with_tag_column("tag1")
+            .with_time_column_with_stats(Some(8000), Some(20000))
+            .with_three_rows_of_data()
+            .with_tag_index();
+
+        assert!(chunk_tag_index(&chunk, &Predicate::new()).is_some());
+        assert!(chunk_tag_index(&chunk, &Predicate::new().with_range(8000, 20001)).is_some());
+        assert!(chunk_tag_index(&chunk, &Predicate::new().with_range(8000, 20000)).is_none());
+        assert!(chunk_tag_index(&chunk, &Predicate::new().with_retention(7999)).is_some());
+        assert!(chunk_tag_index(&chunk, &Predicate::new().with_retention(8000)).is_none());
+        assert!(chunk_tag_index(
+            &chunk,
+            &Predicate::new()
+                .with_field_columns(vec!["i64_field".to_string()])
+                .unwrap()
+        )
+        .is_none());
+
+        let chunk = TestChunk::new("h2o")
+            .with_tag_column("tag1")
+            .with_time_column()
+            .with_three_rows_of_data();
+        assert!(chunk_tag_index(&chunk, &Predicate::new()).is_none());
     }
 
     /// Runs func() and checks that predicates are simplified prior to
@@ -2537,8 +3729,8 @@ mod tests {
         let silly_predicate = Predicate::new().with_expr(col("_measurement").eq(lit("foo")));
 
         // verify that the predicate was rewritten to `false` as the
-        // measurement name is `h20`
-        let expected_predicate = vec![lit(false)];
+        // measurement name is `h20`, so no chunks were requested at all
+        let expected_predicate = vec![];
         run_test_with_predicate(&func, silly_predicate, expected_predicate).await;
 
         // ------------- Test 3 ----------------
@@ -2594,4 +3786,35 @@ mod tests {
             "\nActual: {actual_predicate:?}\nExpected: {expected_predicate:?}"
         );
     }
+
+    #[test]
+    fn test_field_counts() {
+        let chunk1 = Arc::new(
+            TestChunk::new("h2o")
+                .with_tag_column_with_nulls_and_full_stats("foo", Some("a"), Some("b"), 3, None, 1)
+                .with_tag_column_with_full_stats("bar", Some("a"), Some("b"), 3, None),
+        ) as Arc<dyn QueryChunk>;
+        let chunk2 = Arc::new(TestChunk::new("h2o").with_tag_column_with_full_stats(
+            "foo",
+            Some("a"),
+            Some("b"),
+            2,
+            None,
+        )) as Arc<dyn QueryChunk>;
+        let chunk_unknown =
+            Arc::new(TestChunk::new("h2o").with_string_field_column_with_stats("foo", None, None))
+                as Arc<dyn QueryChunk>;
+
+        assert_eq!(field_counts("foo", &[]), (Some(0), Some(0)));
+        assert_eq!(
+            field_counts("foo", &[Arc::clone(&chunk1), Arc::clone(&chunk2)]),
+            (Some(5), Some(1))
+        );
+        // "bar" is null in every row of the chunk without it
+        assert_eq!(
+            field_counts("bar", &[Arc::clone(&chunk1), Arc::clone(&chunk2)]),
+            (Some(5), Some(2))
+        );
+        assert_eq!(field_counts("foo", &[chunk1, chunk_unknown]), (None, None));
+    }
 }
@@ -0,0 +1,180 @@
+//! Golden-file tests of the logical plans produced by [`InfluxRpcPlanner`].
+//!
+//! Each [`Case`] in the [`corpus`] is planned against a fresh test database
+//! and the rendered plans are compared with the snapshot checked in under
+//! `tests/snapshots/`, so that plan regressions (predicates no longer pushed
+//! down, extra sorts, columns read that are not needed) show up as test
+//! failures.
+//!
+//! To bless intentional plan changes, run the tests with
+//! `INSTA_UPDATE=always`, or review the changes with `cargo insta review`:
+//!
+//! ```text
+//! INSTA_UPDATE=always cargo test -p iox_query_influxrpc --test plans
+//! ```
+
+use std::sync::Arc;
+
+use datafusion::prelude::lit;
+use datafusion_util::AsExpr;
+use iox_query::{
+    exec::Executor,
+    test::{TestChunk, TestDatabase},
+    QueryNamespace,
+};
+use iox_query_influxrpc::{InfluxRpcPlanner, PointOrder};
+use predicate::{rpc_predicate::InfluxRpcPredicate, Predicate};
+use query_functions::group_by::Aggregate;
+
+/// The planner method under test.
+#[derive(Debug)]
+enum Request {
+    ReadFilter(PointOrder),
+    ReadGroup {
+        agg: Aggregate,
+        group_columns: &'static [&'static str],
+    },
+}
+
+/// A request planned against a database holding a single chunk.
+struct Case {
+    /// The name of the snapshot.
+    name: &'static str,
+    chunk: fn() -> TestChunk,
+    predicate: fn() -> Predicate,
+    request: Request,
+    /// The retention period of the database, if any.
+    retention_time_ns: Option<i64>,
+}
+
+/// A table with one tag and one float field.
+fn table_chunk() -> TestChunk {
+    TestChunk::new("table")
+        .with_id(0)
+        .with_tag_column("tag")
+        .with_f64_field_column("field")
+        .with_time_column()
+        .with_one_row_of_data()
+}
+
+/// A table with one tag and two integer fields.
+fn h2o_chunk() -> TestChunk {
+    TestChunk::new("h2o")
+        .with_id(0)
+        .with_tag_column("foo")
+        .with_i64_field_column("i64_field")
+        .with_i64_field_column("i64_field_2")
+        .with_time_column()
+        .with_one_row_of_data()
+}
+
+/// A table with a field name containing a period.
+fn h2o_period_chunk() -> TestChunk {
+    TestChunk::new("h2o")
+        .with_id(0)
+        .with_tag_column("foo")
+        .with_f64_field_column("foo.bar")
+        .with_time_column()
+}
+
+fn corpus() -> Vec<Case> {
+    vec![
+        Case {
+            name: "read_filter_no_predicate",
+            chunk: table_chunk,
+            predicate: Predicate::new,
+            request: Request::ReadFilter(PointOrder::Ascending),
+            retention_time_ns: None,
+        },
+        Case {
+            name: "read_filter_tag_predicate",
+            chunk: table_chunk,
+            predicate: || Predicate::new().with_expr("tag".as_expr().eq(lit("MA"))),
+            request: Request::ReadFilter(PointOrder::Ascending),
+            retention_time_ns: None,
+        },
+        Case {
+            name: "read_filter_tag_predicate_with_retention",
+            chunk: table_chunk,
+            predicate: || Predicate::new().with_expr("tag".as_expr().eq(lit("MA"))),
+            request: Request::ReadFilter(PointOrder::Ascending),
+            retention_time_ns: Some(1),
+        },
+        Case {
+            name: "read_filter_field_projection",
+            chunk: h2o_chunk,
+            predicate: || Predicate::new().with_expr("_field".as_expr().eq(lit("i64_field"))),
+            request: Request::ReadFilter(PointOrder::Ascending),
+            retention_time_ns: None,
+        },
+        Case {
+            name: "read_filter_unspecified_point_order",
+            chunk: table_chunk,
+            predicate: Predicate::new,
+            request: Request::ReadFilter(PointOrder::Unspecified),
+            retention_time_ns: None,
+        },
+        Case {
+            name: "read_group_field_and_value_predicate",
+            chunk: h2o_period_chunk,
+            predicate: || {
+                Predicate::new().with_expr(
+                    "_field"
+                        .as_expr()
+                        .eq(lit("foo.bar"))
+                        .and("_value".as_expr().eq(lit(1.2))),
+                )
+            },
+            request: Request::ReadGroup {
+                agg: Aggregate::None,
+                group_columns: &["foo"],
+            },
+            retention_time_ns: None,
+        },
+    ]
+}
+
+/// Plan `case` and render the resulting logical plans, one after another.
+async fn plan(case: &Case) -> String {
+    let executor = Arc::new(Executor::new_testing());
+    let test_db = Arc::new(
+        TestDatabase::new(Arc::clone(&executor)).with_retention_time_ns(case.retention_time_ns),
+    );
+    test_db.add_chunk("my_partition_key", Arc::new((case.chunk)()));
+
+    let rpc_predicate = InfluxRpcPredicate::new(None, (case.predicate)());
+    let planner = InfluxRpcPlanner::new(test_db.new_query_context(None)).await;
+
+    let plans = match &case.request {
+        Request::ReadFilter(point_order) => planner
+            .read_filter(Arc::clone(&test_db) as _, rpc_predicate, *point_order)
+            .await
+            .expect("creating plan"),
+        Request::ReadGroup { agg, group_columns } => planner
+            .read_group(
+                Arc::clone(&test_db) as _,
+                rpc_predicate,
+                *agg,
+                group_columns,
+            )
+            .await
+            .expect("creating plan"),
+    };
+
+    plans
+        .plans
+        .iter()
+        .map(|ss_plan| ss_plan.plan.display_indent_schema().to_string())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[tokio::test]
+async fn golden_plans() {
+    test_helpers::maybe_start_logging();
+
+    for case in corpus() {
+        let plan = plan(&case).await;
+        insta::assert_snapshot!(case.name, plan);
+    }
+}
@@ -35,6 +35,7 @@ impl ParquetFileBuilder {
                 created_at: Timestamp::new(0),
                 column_set: ColumnSet::new(vec![]),
                 max_l0_created_at: Timestamp::new(0),
+                content_hash: None,
             },
         }
     }
@@ -95,6 +96,7 @@ impl ParquetFileBuilder {
         Self {
             file: ParquetFile {
                 max_l0_created_at: Timestamp::new(max_l0_created_at),
+                content_hash: None,
                 ..self.file
             },
         }
@@ -172,6 +174,7 @@ impl TableBuilder {
                 namespace_id: NamespaceId::new(0),
                 name: "table".to_string(),
                 partition_template: Default::default(),
+                retention_period_ns: None,
             },
         }
     }
@@ -6,9 +6,9 @@ use arrow::{
 };
 use data_types::{
     partition_template::TablePartitionTemplateOverride, Column, ColumnSet, ColumnType,
-    ColumnsByName, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceName,
-    NamespaceSchema, ParquetFile, ParquetFileParams, Partition, PartitionId, SortedColumnSet,
-    Table, TableId, TableSchema, Timestamp, TransitionPartitionId,
+    ColumnsByName, CompactionLevel, ContentHash, MaxColumnsPerTable, MaxTables, Namespace,
+    NamespaceName, NamespaceSchema, ParquetFile, ParquetFileParams, Partition, PartitionId,
+    SortedColumnSet, Table, TableId, TableSchema, Timestamp, TransitionPartitionId,
 };
 use datafusion::physical_plan::metrics::Count;
 use datafusion_util::{unbounded_memory_pool, MemoryStream};
@@ -506,6 +506,7 @@ impl TestPartition {
             object_store_id,
             row_count,
             max_l0_created_at,
+            content_hash: _,
         } = builder;
 
         let record_batch = record_batch.expect("A record batch is required");
@@ -539,7 +540,7 @@ impl TestPartition {
             sort_key: Some(sort_key.clone()),
             max_l0_created_at: Time::from_timestamp_nanos(max_l0_created_at),
         };
-        let real_file_size_bytes = create_parquet_file(
+        let (real_file_size_bytes, content_hash) = create_parquet_file(
             ParquetStorage::new(
                 Arc::clone(&self.catalog.object_store),
                 StorageId::from("iox"),
@@ -564,6 +565,7 @@ impl TestPartition {
             object_store_id: Some(object_store_id),
             row_count: None, // will be computed from the record batch again
             max_l0_created_at,
+            content_hash: Some(content_hash),
         };
 
         let result = self.create_parquet_file_catalog_record(builder).await;
@@ -595,6 +597,7 @@ impl TestPartition {
             object_store_id,
             row_count,
             max_l0_created_at,
+            content_hash,
             ..
         } = builder;
 
@@ -632,6 +635,7 @@ impl TestPartition {
             compaction_level,
             column_set,
             max_l0_created_at: Timestamp::new(max_l0_created_at),
+            content_hash,
         };
 
         let mut repos = self.catalog.catalog.repositories().await;
@@ -676,6 +680,7 @@ pub struct TestParquetFileBuilder {
     object_store_id: Option<Uuid>,
     row_count: Option<usize>,
     max_l0_created_at: i64,
+    content_hash: Option<ContentHash>,
 }
 
 impl Default for TestParquetFileBuilder {
@@ -694,6 +699,7 @@ impl Default for TestParquetFileBuilder {
             object_store_id: None,
             row_count: None,
             max_l0_created_at: 1,
+            content_hash: None,
         }
     }
 }
@@ -852,19 +858,19 @@ async fn update_catalog_sort_key_if_needed<R>(
     }
 }
 
-/// Create parquet file and return file size.
+/// Create parquet file and return file size and content hash.
 async fn create_parquet_file(
     store: ParquetStorage,
     partition_id: &TransitionPartitionId,
     metadata: &IoxMetadata,
     record_batch: RecordBatch,
-) -> usize {
+) -> (usize, ContentHash) {
     let stream = Box::pin(MemoryStream::new(vec![record_batch]));
-    let (_meta, file_size) = store
+    let (_meta, file_size, content_hash) = store
         .upload(stream, partition_id, metadata, unbounded_memory_pool())
         .await
         .expect("persisting parquet file should succeed");
-    file_size
+    (file_size, content_hash)
 }
 
 /// A test parquet file of the catalog
@@ -0,0 +1,148 @@
+//! Basic statistics in the schema of the InfluxDB 1.x `/debug/vars` endpoint,
+//! derived from the metrics of the server.
+//!
+//! Each statistic is an object with a `name`, `tags` and `values`:
+//!
+//! ```json
+//! {
+//!     "cmdline": ["influxdb_iox", "run", "router"],
+//!     "httpd": {
+//!         "name": "httpd",
+//!         "tags": {},
+//!         "values": { "req": 12, "writeReq": 10, "pointsWrittenOK": 1000, ... }
+//!     },
+//!     "write": { ... }
+//! }
+//! ```
+//!
+//! Statistics of servers that do not accept writes are reported as zero.
+
+use metric::{Attributes, Observation, RawReporter};
+use serde_json::{json, Value};
+
+/// The paths of the write endpoints counted as write requests.
+const WRITE_PATHS: &[&str] = &["/write", "/api/v2/write"];
+
+/// Returns the statistics of the server with the metrics in `registry`.
+pub(crate) fn debug_vars(registry: &metric::Registry) -> Value {
+    let mut reporter = RawReporter::default();
+    registry.report(&mut reporter);
+
+    let requests = |f: &dyn Fn(&Attributes) -> bool| sum_counter(&reporter, "http_requests", f);
+    let is_write = |a: &Attributes| {
+        attribute(a, "path").map_or(false, |path| WRITE_PATHS.iter().any(|p| *p == path))
+    };
+    let status = |a: &Attributes, want: &str| attribute(a, "status") == Some(want);
+
+    let points_written = sum_counter(&reporter, "http_write_lines", &|_| true);
+    let write_bytes = sum_counter(&reporter, "http_write_body_bytes", &|_| true);
+    let write_ok = requests(&|a| is_write(a) && status(a, "ok"));
+    let write_err = requests(&|a| is_write(a) && !status(a, "ok"));
+
+    json!({
+        "cmdline": std::env::args().collect::<Vec<_>>(),
+        "httpd": {
+            "name": "httpd",
+            "tags": {},
+            "values": {
+                "req": requests(&|_| true),
+                "clientError": requests(&|a| status(a, "client_error")),
+                "serverError": requests(&|a| status(a, "server_error")),
+                "pingReq": requests(&|a| attribute(a, "path") == Some("/ping")),
+                "writeReq": write_ok + write_err,
+                "writeReqBytes": write_bytes,
+                "pointsWrittenOK": points_written,
+                "reqRejected": sum_counter(&reporter, "http_request_limit_rejected", &|_| true),
+            },
+        },
+        "write": {
+            "name": "write",
+            "tags": {},
+            "values": {
+                "req": write_ok + write_err,
+                "pointReq": points_written,
+                "writeOk": write_ok,
+                "writeError": write_err,
+            },
+        },
+    })
+}
+
+/// Returns the value of the attribute `key`, if set.
+fn attribute<'a>(attributes: &'a Attributes, key: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.as_ref())
+}
+
+/// Sums the observations of the counter `metric_name` with attributes
+/// matching `filter`, or returns 0 if no such metric is registered.
+fn sum_counter(
+    reporter: &RawReporter,
+    metric_name: &str,
+    filter: &dyn Fn(&Attributes) -> bool,
+) -> u64 {
+    reporter
+        .metric(metric_name)
+        .into_iter()
+        .flat_map(|metric| &metric.observations)
+        .filter(|(attributes, _)| filter(attributes))
+        .map(|(_, observation)| match observation {
+            Observation::U64Counter(v) => *v,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use metric::{Metric, U64Counter};
+
+    use super::*;
+
+    #[test]
+    fn test_debug_vars() {
+        let registry = metric::Registry::default();
+
+        let requests: Metric<U64Counter> = registry.register_metric("http_requests", "requests");
+        requests
+            .recorder(&[("path", "/api/v2/write"), ("status", "ok")])
+            .inc(3);
+        requests
+            .recorder(&[("path", "/write"), ("status", "client_error")])
+            .inc(1);
+        requests
+            .recorder(&[("path", "/ping"), ("status", "ok")])
+            .inc(2);
+        requests.recorder(&[("status", "client_error")]).inc(4);
+        requests
+            .recorder(&[("path", "/api/v2/write"), ("status", "server_error")])
+            .inc(5);
+
+        registry
+            .register_metric::<U64Counter>("http_write_lines", "lines")
+            .recorder(&[])
+            .inc(42);
+
+        let vars = debug_vars(&registry);
+
+        let httpd = &vars["httpd"]["values"];
+        assert_eq!(httpd["req"], 15);
+        assert_eq!(httpd["clientError"], 5);
+        assert_eq!(httpd["serverError"], 5);
+        assert_eq!(httpd["pingReq"], 2);
+        assert_eq!(httpd["writeReq"], 9);
+        assert_eq!(httpd["pointsWrittenOK"], 42);
+        // not registered
+        assert_eq!(httpd["writeReqBytes"], 0);
+
+        let write = &vars["write"]["values"];
+        assert_eq!(write["req"], 9);
+        assert_eq!(write["pointReq"], 42);
+        assert_eq!(write["writeOk"], 3);
+        assert_eq!(write["writeError"], 6);
+
+        assert!(vars["cmdline"].is_array());
+    }
+}
@@ -0,0 +1,71 @@
+//! Heap profiling support using the jemalloc profiler
+//!
+//! Compiled only when the "jemalloc_profiling" feature is enabled. The
+//! profiler must also be activated when the process starts, with
+//! `MALLOC_CONF=prof:true`.
+
+use std::{
+    ffi::{c_char, CString},
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use observability_deps::tracing::info;
+use snafu::{ensure, ResultExt, Snafu};
+use tikv_jemalloc_ctl::raw;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display(
+        "jemalloc heap profiling is not active, start the server with MALLOC_CONF=prof:true"
+    ))]
+    NotActive,
+
+    #[snafu(display("{}", source))]
+    Jemalloc { source: tikv_jemalloc_ctl::Error },
+
+    #[snafu(display("invalid profile path: {}", source))]
+    Path { source: std::ffi::NulError },
+
+    #[snafu(display("cannot read profile {}: {}", path.display(), source))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("{}", source))]
+    JoinError { source: tokio::task::JoinError },
+}
+
+/// Distinguishes the profiles of concurrent requests.
+static DUMP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Dump a profile of the allocations currently live on the heap, in the
+/// jemalloc (`jeprof`) format.
+pub(crate) async fn dump_jemalloc_heap_profile() -> Result<Vec<u8>, Error> {
+    tokio::task::spawn_blocking(|| {
+        // SAFETY: "opt.prof" is a bool
+        let active: bool = unsafe { raw::read(b"opt.prof\0") }.context(JemallocSnafu)?;
+        ensure!(active, NotActiveSnafu);
+
+        let path = std::env::temp_dir().join(format!(
+            "iox-heap-{}-{}.prof",
+            std::process::id(),
+            DUMP_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let c_path = CString::new(path.to_string_lossy().as_bytes()).context(PathSnafu)?;
+
+        info!(path=%path.display(), "dumping jemalloc heap profile");
+
+        // SAFETY: "prof.dump" takes the path of the file to write as a
+        // NUL-terminated string, which outlives the call
+        unsafe { raw::write::<*const c_char>(b"prof.dump\0", c_path.as_ptr()) }
+            .context(JemallocSnafu)?;
+
+        let profile = std::fs::read(&path).context(ReadSnafu { path: path.clone() });
+        let _ = std::fs::remove_file(&path);
+        profile
+    })
+    .await
+    .context(JoinSnafu)?
+}
@@ -1,10 +1,12 @@
-use std::{convert::Infallible, num::NonZeroI32, sync::Arc};
+use std::{convert::Infallible, num::NonZeroI32, sync::Arc, time::Duration};
 
 use authz::http::AuthorizationHeaderExtension;
+use clap_blocks::server_limits::ServerLimitsConfig;
 use hyper::{
+    header::CONTENT_TYPE,
     http::HeaderValue,
     server::conn::{AddrIncoming, AddrStream},
-    Body, Method, Request, Response,
+    Body, Method, Request, Response, StatusCode,
 };
 use observability_deps::tracing::{debug, error};
 use serde::Deserialize;
@@ -18,9 +20,14 @@ use crate::{
     server_type::ServerType,
 };
 
+mod debug_vars;
+
 #[cfg(feature = "heappy")]
 mod heappy;
 
+#[cfg(feature = "jemalloc_profiling")]
+mod jemalloc;
+
 #[cfg(feature = "pprof")]
 mod pprof;
 
@@ -30,6 +37,10 @@ pub mod utils;
 
 pub mod test_utils;
 
+/// The time to wait for the acknowledgement of a HTTP/2 keep-alive ping if
+/// no timeout is configured, matching the default of hyper.
+const DEFAULT_HTTP2_KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(20);
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Snafu)]
 pub enum ApplicationError {
@@ -50,6 +61,10 @@ pub enum ApplicationError {
     #[snafu(display("Heappy error: {}", source))]
     HeappyError { source: heappy::Error },
 
+    #[cfg(feature = "jemalloc_profiling")]
+    #[snafu(display("jemalloc heap profiling error: {}", source))]
+    JemallocProfiling { source: jemalloc::Error },
+
     #[snafu(display("Protobuf error: {}", source))]
     Prost {
         source: Box<dyn std::error::Error + Send + Sync>,
@@ -67,6 +82,9 @@ pub enum ApplicationError {
     #[snafu(display("pprof support is not compiled"))]
     PProfIsNotCompiled,
 
+    #[snafu(display("jemalloc heap profiling support is not compiled"))]
+    JemallocProfilingIsNotCompiled,
+
     #[snafu(display("Route error from run mode: {}", e))]
     RunModeRouteError { e: Box<dyn HttpApiErrorSource> },
 }
@@ -81,18 +99,22 @@ impl HttpApiErrorSource for ApplicationError {
             e @ Self::EmptyFlamegraph => e.empty_value(),
             e @ Self::HeappyIsNotCompiled => e.internal_error(),
             e @ Self::PProfIsNotCompiled => e.internal_error(),
+            e @ Self::JemallocProfilingIsNotCompiled => e.internal_error(),
             #[cfg(feature = "heappy")]
             e @ Self::HeappyError { .. } => e.internal_error(),
+            #[cfg(feature = "jemalloc_profiling")]
+            e @ Self::JemallocProfiling { .. } => e.internal_error(),
             Self::RunModeRouteError { e } => e.to_http_api_error(),
         }
     }
 }
 
 pub async fn serve(
-    addr: AddrIncoming,
+    mut addr: AddrIncoming,
     server_type: Arc<dyn ServerType>,
     shutdown: CancellationToken,
     trace_header_parser: TraceHeaderParser,
+    limits: ServerLimitsConfig,
 ) -> Result<(), hyper::Error> {
     let metric_registry = server_type.metric_registry();
     let trace_collector = server_type.trace_collector();
@@ -105,7 +127,16 @@ pub async fn serve(
         server_type.name(),
     );
 
+    addr.set_keepalive(limits.tcp_keep_alive);
+
     hyper::Server::builder(addr)
+        .http2_max_concurrent_streams(limits.max_concurrent_streams)
+        .http2_keep_alive_interval(limits.http2_keep_alive_interval)
+        .http2_keep_alive_timeout(
+            limits
+                .http2_keep_alive_timeout
+                .unwrap_or(DEFAULT_HTTP2_KEEP_ALIVE_TIMEOUT),
+        )
         .serve(hyper::service::make_service_fn(|_conn: &AddrStream| {
             let server_type = Arc::clone(&server_type);
             let service = hyper::service::service_fn(move |request: Request<_>| {
@@ -138,9 +169,12 @@ async fn route_request(
     let response = match (method.clone(), uri.path()) {
         (Method::GET, "/health") => health(),
         (Method::GET, "/metrics") => handle_metrics(server_type.as_ref()),
+        (Method::GET | Method::HEAD, "/ping") => ping(&req),
+        (Method::GET, "/debug/vars") => handle_debug_vars(server_type.as_ref()),
         (Method::GET, "/debug/pprof") => pprof_home(req).await,
         (Method::GET, "/debug/pprof/profile") => pprof_profile(req).await,
         (Method::GET, "/debug/pprof/allocs") => pprof_heappy_profile(req).await,
+        (Method::GET, "/debug/pprof/heap") => pprof_jemalloc_heap_profile().await,
         _ => server_type
             .route_http_request(req)
             .await
@@ -178,6 +212,50 @@ fn handle_metrics(server_type: &dyn ServerType) -> Result<Response<Body>, Applic
     Ok(Response::new(Body::from(body)))
 }
 
+/// Liveness check compatible with the `/ping` endpoint of InfluxDB 1.x and
+/// 2.x, identifying the server in the `X-Influxdb-Build` and
+/// `X-Influxdb-Version` headers.
+///
+/// Responds with `204 No Content`, or the version as JSON with
+/// `?verbose=true`.
+fn ping(req: &Request<Body>) -> Result<Response<Body>, ApplicationError> {
+    let version = env!("CARGO_PKG_VERSION");
+    let verbose = req
+        .uri()
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .any(|p| p == "verbose=true");
+
+    let (status, body) = match verbose {
+        true => (
+            StatusCode::OK,
+            Body::from(serde_json::json!({ "version": version }).to_string()),
+        ),
+        false => (StatusCode::NO_CONTENT, Body::empty()),
+    };
+
+    let mut builder = Response::builder()
+        .status(status)
+        .header("X-Influxdb-Build", "IOx")
+        .header("X-Influxdb-Version", version);
+    if verbose {
+        builder = builder.header(CONTENT_TYPE, "application/json");
+    }
+    Ok(builder.body(body).unwrap())
+}
+
+/// Basic statistics in the schema of the InfluxDB 1.x `/debug/vars`
+/// endpoint.
+fn handle_debug_vars(server_type: &dyn ServerType) -> Result<Response<Body>, ApplicationError> {
+    let vars = debug_vars::debug_vars(server_type.metric_registry().as_ref());
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(vars.to_string()))
+        .unwrap())
+}
+
 async fn pprof_home(req: Request<Body>) -> Result<Response<Body>, ApplicationError> {
     let default_host = HeaderValue::from_static("localhost");
     let host = req
@@ -194,8 +272,9 @@ async fn pprof_home(req: Request<Body>) -> Result<Response<Body>, ApplicationErr
         "/debug/pprof/allocs?seconds={}",
         PProfAllocsArgs::default_seconds()
     );
+    let heap_cmd = "/debug/pprof/heap";
     Ok(Response::new(Body::from(format!(
-        r#"<a href="{profile_cmd}">http://{host}{profile_cmd}</a><br><a href="{allocs_cmd}">http://{host}{allocs_cmd}</a>"#,
+        r#"<a href="{profile_cmd}">http://{host}{profile_cmd}</a><br><a href="{allocs_cmd}">http://{host}{allocs_cmd}</a><br><a href="{heap_cmd}">http://{host}{heap_cmd}</a>"#,
     ))))
 }
 
@@ -338,3 +417,23 @@ async fn pprof_heappy_profile(req: Request<Body>) -> Result<Response<Body>, Appl
 async fn pprof_heappy_profile(_req: Request<Body>) -> Result<Response<Body>, ApplicationError> {
     HeappyIsNotCompiledSnafu {}.fail()
 }
+
+// If jemalloc heap profiling is enabled, dump the live heap
+#[cfg(feature = "jemalloc_profiling")]
+async fn pprof_jemalloc_heap_profile() -> Result<Response<Body>, ApplicationError> {
+    use snafu::ResultExt;
+
+    // The profile is in the jemalloc format, understood by
+    // `jeprof --svg influxdb_iox heap.prof`.
+    let profile = self::jemalloc::dump_jemalloc_heap_profile()
+        .await
+        .context(JemallocProfilingSnafu)?;
+
+    Ok(Response::new(Body::from(profile)))
+}
+
+//  Return error if jemalloc heap profiling not enabled
+#[cfg(not(feature = "jemalloc_profiling"))]
+async fn pprof_jemalloc_heap_profile() -> Result<Response<Body>, ApplicationError> {
+    JemallocProfilingIsNotCompiledSnafu {}.fail()
+}
@@ -23,6 +23,8 @@ mod service;
 // path to use them from in downstream crates.
 pub mod reexport {
     pub use generated_types;
+    pub use ingester_query_grpc;
+    pub use service_grpc_logging;
     pub use service_grpc_testing;
     pub use tokio_stream;
     pub use tonic;
@@ -137,7 +139,8 @@ pub async fn serve(
                 .run_config()
                 .tracing_config()
                 .traces_jaeger_debug_name,
-        );
+        )
+        .with_sampler(common_state.run_config().tracing_config().sampler());
 
     // Construct and start up gRPC server
     let grpc_server = rpc::serve(
@@ -145,12 +148,15 @@ pub async fn serve(
         Arc::clone(&server_type),
         trace_header_parser.clone(),
         frontend_shutdown.clone(),
+        common_state.run_config().server_limits,
+        common_state.run_config().log_filter_api,
     )
     .fuse();
     info!(?server_type, "gRPC server listening");
 
     let captured_server_type = Arc::clone(&server_type);
     let captured_shutdown = frontend_shutdown.clone();
+    let limits = common_state.run_config().server_limits;
     let http_server = async move {
         if let Some(http_listener) = http_listener {
             info!(server_type=?captured_server_type, "HTTP server listening");
@@ -159,6 +165,7 @@ pub async fn serve(
                 captured_server_type,
                 captured_shutdown,
                 trace_header_parser,
+                limits,
             )
             .await?
         } else {
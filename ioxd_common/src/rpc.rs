@@ -1,6 +1,7 @@
 use std::any::Any;
 use std::sync::Arc;
 
+use clap_blocks::server_limits::ServerLimitsConfig;
 use tokio::net::TcpListener;
 use tokio_util::sync::CancellationToken;
 use tonic::{body::BoxBody, transport::NamedService, Code};
@@ -19,6 +20,8 @@ pub struct RpcBuilderInput {
     pub socket: TcpListener,
     pub trace_header_parser: TraceHeaderParser,
     pub shutdown: CancellationToken,
+    pub limits: ServerLimitsConfig,
+    pub log_filter_api: bool,
 }
 
 #[derive(Debug)]
@@ -27,6 +30,7 @@ pub struct RpcBuilder<T> {
     pub health_reporter: HealthReporter,
     pub shutdown: CancellationToken,
     pub socket: TcpListener,
+    pub limits: ServerLimitsConfig,
 }
 
 /// Adds a gRPC service to the builder, and registers it with the
@@ -47,6 +51,7 @@ macro_rules! add_service {
                     mut health_reporter,
                     shutdown,
                     socket,
+                    limits,
                 } = $builder;
                 let service = $svc;
 
@@ -62,6 +67,7 @@ macro_rules! add_service {
                     health_reporter,
                     shutdown,
                     socket,
+                    limits,
                 }
             }
         };
@@ -82,6 +88,8 @@ macro_rules! setup_builder {
             socket,
             trace_header_parser,
             shutdown,
+            limits,
+            log_filter_api,
         } = $input;
 
         let (health_reporter, health_service) =
@@ -90,10 +98,16 @@ macro_rules! setup_builder {
             .register_encoded_file_descriptor_set(
                 $crate::reexport::generated_types::FILE_DESCRIPTOR_SET,
             )
+            .register_encoded_file_descriptor_set(
+                $crate::reexport::ingester_query_grpc::FILE_DESCRIPTOR_SET,
+            )
             .build()
             .expect("gRPC reflection data broken");
 
-        let builder = $crate::reexport::tonic::transport::Server::builder();
+        let builder = $crate::reexport::tonic::transport::Server::builder()
+            .max_concurrent_streams(limits.max_concurrent_streams)
+            .http2_keepalive_interval(limits.http2_keep_alive_interval)
+            .http2_keepalive_timeout(limits.http2_keep_alive_timeout);
         let builder = builder
             .layer($crate::reexport::trace_http::tower::TraceLayer::new(
                 trace_header_parser,
@@ -113,6 +127,7 @@ macro_rules! setup_builder {
             health_reporter,
             shutdown,
             socket,
+            limits,
         };
 
         add_service!(builder, health_service);
@@ -121,6 +136,36 @@ macro_rules! setup_builder {
             builder,
             $crate::reexport::service_grpc_testing::make_server()
         );
+        // The logging service is not authorized, so it is only mounted when
+        // explicitly enabled.
+        let logging_service =
+            log_filter_api.then($crate::reexport::service_grpc_logging::make_server);
+        let builder = {
+            let RpcBuilder {
+                inner,
+                mut health_reporter,
+                shutdown,
+                socket,
+                limits,
+            } = builder;
+
+            if let Some(service) = &logging_service {
+                health_reporter
+                    .set_service_status(
+                        $crate::rpc::service_name(service),
+                        $crate::reexport::tonic_health::ServingStatus::Serving,
+                    )
+                    .await;
+            }
+
+            RpcBuilder {
+                inner: inner.add_optional_service(logging_service),
+                health_reporter,
+                shutdown,
+                socket,
+                limits,
+            }
+        };
 
         builder
     }};
@@ -136,11 +181,14 @@ macro_rules! serve_builder {
             inner,
             shutdown,
             socket,
+            limits,
             ..
         } = $builder;
 
         let stream = $crate::reexport::tonic::transport::server::TcpIncoming::from_listener(
-            socket, true, None,
+            socket,
+            true,
+            limits.tcp_keep_alive,
         )
         .expect("failed to initialise tcp socket");
         inner
@@ -176,11 +224,15 @@ pub async fn serve(
     server_type: Arc<dyn ServerType>,
     trace_header_parser: TraceHeaderParser,
     shutdown: CancellationToken,
+    limits: ServerLimitsConfig,
+    log_filter_api: bool,
 ) -> Result<(), RpcError> {
     let builder_input = RpcBuilderInput {
         socket,
         trace_header_parser,
         shutdown,
+        limits,
+        log_filter_api,
     };
 
     server_type.server_grpc(builder_input).await
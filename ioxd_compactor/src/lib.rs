@@ -19,6 +19,10 @@ use async_trait::async_trait;
 use backoff::BackoffConfig;
 use clap_blocks::compactor::CompactorConfig;
 use compactor::{compactor::Compactor, config::Config};
+use generated_types::{
+    google::longrunning::operations_server::OperationsServer,
+    influxdata::iox::compactor::v1::compaction_service_server::CompactionServiceServer,
+};
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
@@ -33,6 +37,8 @@ use ioxd_common::{
 };
 use metric::Registry;
 use parquet_file::storage::ParquetStorage;
+use service_grpc_compactor::CompactionService;
+use service_grpc_operations::OperationsService;
 use std::{
     fmt::{Debug, Display},
     sync::Arc,
@@ -44,7 +50,8 @@ use trace::TraceCollector;
 use crate::scheduler_config::convert_scheduler_config;
 
 pub struct CompactorServerType {
-    compactor: Compactor,
+    compactor: Arc<Compactor>,
+    catalog: Arc<dyn Catalog>,
     metric_registry: Arc<Registry>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
 }
@@ -58,11 +65,13 @@ impl std::fmt::Debug for CompactorServerType {
 impl CompactorServerType {
     pub fn new(
         compactor: Compactor,
+        catalog: Arc<dyn Catalog>,
         metric_registry: Arc<metric::Registry>,
         common_state: &CommonServerState,
     ) -> Self {
         Self {
-            compactor,
+            compactor: Arc::new(compactor),
+            catalog,
             metric_registry,
             trace_collector: common_state.trace_collector(),
         }
@@ -98,6 +107,18 @@ impl ServerType for CompactorServerType {
     async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
         let builder = setup_builder!(builder_input, self);
 
+        add_service!(
+            builder,
+            OperationsServer::new(OperationsService::new(self.compactor.jobs()))
+        );
+        add_service!(
+            builder,
+            CompactionServiceServer::new(CompactionService::new(
+                Arc::clone(&self.catalog),
+                Arc::clone(&self.compactor),
+            ))
+        );
+
         serve_builder!(builder);
 
         Ok(())
@@ -161,7 +182,7 @@ pub async fn create_compactor_server_type(
     let compactor = Compactor::start(Config {
         metric_registry: Arc::clone(&metric_registry),
         trace_collector: common_state.trace_collector(),
-        catalog,
+        catalog: Arc::clone(&catalog),
         scheduler_config: convert_scheduler_config(
             compactor_config.compactor_scheduler_config.clone(),
         ),
@@ -199,6 +220,7 @@ pub async fn create_compactor_server_type(
 
     Arc::new(CompactorServerType::new(
         compactor,
+        catalog,
         metric_registry,
         common_state,
     ))
@@ -16,28 +16,37 @@ use workspace_hack as _;
 
 use arrow_flight::flight_service_server::FlightServiceServer;
 use async_trait::async_trait;
-use clap_blocks::ingester::IngesterConfig;
+use clap_blocks::ingester::{BackpressureResponse as BackpressureResponseConfig, IngesterConfig};
 use futures::FutureExt;
-use generated_types::influxdata::iox::{
-    catalog::v1::catalog_service_server::CatalogServiceServer,
-    ingester::v1::{
-        persist_service_server::PersistServiceServer, write_service_server::WriteServiceServer,
+use generated_types::{
+    google::longrunning::operations_server::OperationsServer,
+    influxdata::iox::{
+        catalog::v1::catalog_service_server::CatalogServiceServer,
+        ingester::v1::{
+            persist_service_server::PersistServiceServer, write_service_server::WriteServiceServer,
+        },
     },
 };
-use hyper::{Body, Request, Response};
-use ingester::{GossipConfig, IngesterGuard, IngesterRpcInterface};
+use hyper::{Body, Method, Request, Response};
+use ingester::{
+    BackpressureResponse, BufferSnapshotConfig, GossipConfig, IngesterGuard, IngesterHealth,
+    IngesterRpcInterface, LastValueCacheConfig, NamespaceMemoryLimits, PersistLagThreshold,
+};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
 use ioxd_common::{
     add_service,
     http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
-    rpc::RpcBuilderInput,
+    reexport::tonic_health::{server::HealthReporter, ServingStatus},
+    rpc::{service_name, RpcBuilderInput},
     serve_builder,
     server_type::{CommonServerState, RpcError, ServerType},
     setup_builder,
 };
 use metric::Registry;
+use object_store_encryption::{KeyError, KeyProvider, StaticKeyProvider};
 use parquet_file::storage::ParquetStorage;
+use service_grpc_operations::OperationsService;
 use std::{
     fmt::{Debug, Display},
     num::NonZeroUsize,
@@ -45,7 +54,7 @@ use std::{
     time::Duration,
 };
 use thiserror::Error;
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, watch};
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 
@@ -58,6 +67,9 @@ const MAX_OUTGOING_MSG_BYTES: usize = 1024 * 1024; // 1 MiB
 pub enum Error {
     #[error("error initializing ingester: {0}")]
     Ingester(#[from] ingester::InitError),
+
+    #[error("error loading wal encryption keys: {0}")]
+    EncryptionKeys(#[from] KeyError),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -114,12 +126,25 @@ impl<I: IngesterRpcInterface + Sync + Send + Debug + 'static> ServerType for Ing
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
-    /// Just return "not found".
+    /// Serve the readiness endpoint, and return "not found" for anything else.
+    ///
+    /// The ingester is ready unless it is degraded by its persist lag.
     async fn route_http_request(
         &self,
-        _req: Request<Body>,
+        req: Request<Body>,
     ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>> {
-        Err(Box::new(IoxHttpError::NotFound))
+        match (req.method().clone(), req.uri().path()) {
+            (Method::GET, "/ready") => {
+                let health = self.server.health().borrow().clone();
+                match health {
+                    IngesterHealth::Healthy => Ok(Response::new(Body::from("OK"))),
+                    IngesterHealth::Degraded(reason) => {
+                        Err(Box::new(IoxHttpError::Degraded(reason)))
+                    }
+                }
+            }
+            _ => Err(Box::new(IoxHttpError::NotFound)),
+        }
     }
 
     /// Configure the gRPC services.
@@ -130,24 +155,40 @@ impl<I: IngesterRpcInterface + Sync + Send + Debug + 'static> ServerType for Ing
             builder,
             CatalogServiceServer::new(self.server.rpc().catalog_service())
         );
-        add_service!(
-            builder,
-            WriteServiceServer::new(self.server.rpc().write_service())
-                .max_decoding_message_size(self.max_incoming_msg_bytes)
-                .max_encoding_message_size(MAX_OUTGOING_MSG_BYTES)
-        );
+        let write_service = WriteServiceServer::new(self.server.rpc().write_service())
+            .max_decoding_message_size(self.max_incoming_msg_bytes)
+            .max_encoding_message_size(MAX_OUTGOING_MSG_BYTES);
+        let write_service_name = service_name(&write_service);
+        add_service!(builder, write_service);
         add_service!(
             builder,
             PersistServiceServer::new(self.server.rpc().persist_service())
         );
         add_service!(
             builder,
-            FlightServiceServer::new(
-                self.server
-                    .rpc()
-                    .query_service(self.max_simultaneous_queries)
-            )
+            OperationsServer::new(OperationsService::new(self.server.jobs()))
+        );
+        let flight_server = FlightServiceServer::new(
+            self.server
+                .rpc()
+                .query_service(self.max_simultaneous_queries),
         );
+        let flight_server = match builder.limits.grpc_max_message_size {
+            Some(n) => flight_server
+                .max_decoding_message_size(n)
+                .max_encoding_message_size(n),
+            None => flight_server,
+        };
+        add_service!(builder, flight_server);
+
+        // Stop advertising the write service as serving while the ingester is
+        // degraded.
+        tokio::spawn(report_write_health(
+            self.server.health(),
+            builder.health_reporter.clone(),
+            write_service_name,
+            builder.shutdown.clone(),
+        ));
 
         serve_builder!(builder);
 
@@ -170,23 +211,54 @@ impl<I: IngesterRpcInterface + Sync + Send + Debug + 'static> ServerType for Ing
     }
 }
 
+/// Set the gRPC health status of `service` to reflect the ingester `health`,
+/// until `shutdown` is cancelled or the ingester stops.
+async fn report_write_health(
+    mut health: watch::Receiver<IngesterHealth>,
+    mut reporter: HealthReporter,
+    service: &'static str,
+    shutdown: CancellationToken,
+) {
+    loop {
+        let status = match *health.borrow_and_update() {
+            IngesterHealth::Healthy => ServingStatus::Serving,
+            IngesterHealth::Degraded(_) => ServingStatus::NotServing,
+        };
+        reporter.set_service_status(service, status).await;
+
+        tokio::select! {
+            _ = shutdown.cancelled() => return,
+            res = health.changed() => {
+                if res.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
 /// Simple error struct, we're not really providing an HTTP interface for the ingester.
 #[derive(Debug)]
 pub enum IoxHttpError {
     NotFound,
+    Degraded(String),
 }
 
 impl IoxHttpError {
     fn status_code(&self) -> HttpApiErrorCode {
         match self {
             Self::NotFound => HttpApiErrorCode::NotFound,
+            Self::Degraded(_) => HttpApiErrorCode::Unavailable,
         }
     }
 }
 
 impl Display for IoxHttpError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
+        match self {
+            Self::Degraded(reason) => write!(f, "ingester degraded: {reason}"),
+            _ => write!(f, "{self:?}"),
+        }
     }
 }
 
@@ -219,21 +291,65 @@ pub async fn create_ingester_server_type(
         },
     };
 
+    let encryption_keys = ingester_config
+        .wal_encryption_key_file
+        .as_ref()
+        .map(StaticKeyProvider::from_file)
+        .transpose()?
+        .map(|keys| Arc::new(keys) as Arc<dyn KeyProvider>);
+
+    let backpressure_response = match ingester_config.backpressure_response {
+        BackpressureResponseConfig::Throttle => BackpressureResponse::Throttle,
+        BackpressureResponseConfig::Reject => BackpressureResponse::Reject,
+        BackpressureResponseConfig::Delay => BackpressureResponse::Delay(Duration::from_millis(
+            ingester_config.backpressure_max_delay_millis,
+        )),
+    };
+
     let grpc = ingester::new(
         catalog,
         Arc::clone(&metrics),
         PERSIST_BACKGROUND_FETCH_TIME,
         ingester_config.wal_directory.clone(),
         Duration::from_secs(ingester_config.wal_rotation_period_seconds),
+        ingester_config.wal_max_segment_bytes,
+        ingester_config.wal_replay_max_ops_per_second,
+        ingester_config
+            .buffer_snapshot_directory
+            .clone()
+            .map(|directory| BufferSnapshotConfig {
+                directory,
+                period: Duration::from_secs(ingester_config.buffer_snapshot_period_seconds),
+            }),
+        encryption_keys,
         exec,
         ingester_config.persist_max_parallelism,
         ingester_config.persist_queue_depth,
         ingester_config.persist_hot_partition_cost,
+        NamespaceMemoryLimits {
+            soft_limit_bytes: ingester_config.namespace_buffer_soft_limit_bytes,
+            hard_limit_bytes: ingester_config.namespace_buffer_hard_limit_bytes,
+        },
+        ingester_config.buffer_hard_limit_bytes,
+        backpressure_response,
+        PersistLagThreshold {
+            max_unpersisted_writes: ingester_config.persist_lag_max_unpersisted_writes,
+            max_unpersisted_age: ingester_config
+                .persist_lag_max_age_seconds
+                .map(Duration::from_secs),
+        },
         object_store,
         gossip,
         ingester_config
             .max_partitions_per_namespace
             .unwrap_or_else(|| NonZeroUsize::new(usize::MAX).unwrap()),
+        ingester_config.persist_notification_webhook_url.clone(),
+        ingester_config
+            .last_value_cache_max_series
+            .map(|max_series| LastValueCacheConfig {
+                max_series,
+                first_values: ingester_config.last_value_cache_first_values,
+            }),
         shutdown_rx.map(|v| v.expect("shutdown sender dropped without calling shutdown")),
     )
     .await?;
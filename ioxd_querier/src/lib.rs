@@ -17,6 +17,7 @@ use generated_types::influxdata::iox::{
     schema::v1::schema_service_server::SchemaServiceServer,
 };
 use service_grpc_catalog::CatalogService;
+use service_grpc_flight::{CursorConfig, ResultLimits};
 use service_grpc_object_store::ObjectStoreService;
 use service_grpc_schema::SchemaService;
 // Workaround for "unused crate" lint false positives.
@@ -40,9 +41,13 @@ use ioxd_common::{
 };
 use metric::Registry;
 use object_store::{DynObjectStore, ObjectStore};
-use querier::{create_ingester_connections, QuerierCatalogCache, QuerierDatabase, QuerierServer};
+use querier::{
+    create_ingester_connections, ColdDataConfig, DiskCacheConfig, QuerierCatalogCache,
+    QuerierDatabase, QuerierServer,
+};
 use std::{
     fmt::{Debug, Display},
+    num::NonZeroUsize,
     sync::Arc,
 };
 use thiserror::Error;
@@ -60,6 +65,10 @@ pub struct QuerierServerType {
     object_store: Arc<dyn ObjectStore>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
     authz: Option<Arc<dyn Authorizer>>,
+    max_series_per_query: Option<NonZeroUsize>,
+    flight_cursor_config: CursorConfig,
+    flight_result_limits: ResultLimits,
+    storage_result_limits: service_grpc_influxrpc::ResultLimits,
 }
 
 impl std::fmt::Debug for QuerierServerType {
@@ -96,17 +105,27 @@ impl ServerType for QuerierServerType {
     /// Configure the gRPC services.
     async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
         let builder = setup_builder!(builder_input, self);
+        let flight_server = rpc::query::make_flight_server(
+            Arc::clone(&self.database),
+            self.authz.as_ref().map(Arc::clone),
+            self.flight_cursor_config,
+            self.flight_result_limits,
+        );
+        let flight_server = match builder.limits.grpc_max_message_size {
+            Some(n) => flight_server
+                .max_decoding_message_size(n)
+                .max_encoding_message_size(n),
+            None => flight_server,
+        };
+        add_service!(builder, flight_server);
         add_service!(
             builder,
-            rpc::query::make_flight_server(
+            rpc::query::make_storage_server(
                 Arc::clone(&self.database),
-                self.authz.as_ref().map(Arc::clone)
+                self.max_series_per_query,
+                self.storage_result_limits,
             )
         );
-        add_service!(
-            builder,
-            rpc::query::make_storage_server(Arc::clone(&self.database))
-        );
         add_service!(
             builder,
             rpc::namespace::namespace_service(Arc::clone(&self.database))
@@ -206,22 +225,39 @@ pub async fn create_querier_server_type(
         Arc::clone(&args.object_store),
         args.querier_config.ram_pool_metadata_bytes.bytes(),
         args.querier_config.ram_pool_data_bytes.bytes(),
+        args.querier_config
+            .cold_data_threshold
+            .map(|threshold| ColdDataConfig {
+                threshold,
+                max_concurrent_reads: args.querier_config.max_concurrent_cold_reads,
+            }),
+        args.querier_config
+            .disk_cache_directory
+            .clone()
+            .map(|directory| DiskCacheConfig {
+                directory,
+                max_bytes: args.querier_config.disk_cache_bytes,
+            }),
         &Handle::current(),
     ));
 
-    // register cached object store with the execution context
-    let parquet_store = catalog_cache.parquet_store();
+    // register cached object store (and the uncached one for cold data, if
+    // any) with the execution context
     let runtime_env = args
         .exec
         .new_context(ExecutorType::Query)
         .inner()
         .runtime_env();
-    let existing = register_iox_object_store(
-        runtime_env,
-        parquet_store.id(),
-        Arc::clone(parquet_store.object_store()),
-    );
-    assert!(existing.is_none());
+    for parquet_store in
+        std::iter::once(catalog_cache.parquet_store()).chain(catalog_cache.cold_parquet_store())
+    {
+        let existing = register_iox_object_store(
+            Arc::clone(&runtime_env),
+            parquet_store.id(),
+            Arc::clone(parquet_store.object_store()),
+        );
+        assert!(existing.is_none());
+    }
 
     let authz = match &args.querier_config.authz_address {
         Some(addr) => {
@@ -264,7 +300,9 @@ pub async fn create_querier_server_type(
             args.querier_config.max_concurrent_queries,
             Arc::new(args.querier_config.datafusion_config),
         )
-        .await?,
+        .await?
+        .with_slow_query_threshold(args.querier_config.slow_query_threshold)
+        .with_checksum_verification(args.querier_config.verify_parquet_checksums),
     );
 
     let server = QuerierServer::new(Arc::clone(&database));
@@ -276,5 +314,36 @@ pub async fn create_querier_server_type(
         object_store: args.object_store,
         trace_collector: args.common_state.trace_collector(),
         authz,
+        max_series_per_query: args.querier_config.max_series_per_query,
+        flight_cursor_config: CursorConfig {
+            ttl: args.querier_config.flight_config.cursor_ttl,
+            max_memory_bytes: args
+                .querier_config
+                .flight_config
+                .cursor_memory_bytes
+                .bytes(),
+        },
+        flight_result_limits: ResultLimits {
+            max_rows: args
+                .querier_config
+                .flight_config
+                .max_result_rows
+                .map(NonZeroUsize::get),
+            max_bytes: args
+                .querier_config
+                .flight_config
+                .max_result_bytes
+                .map(|b| b.bytes()),
+        },
+        storage_result_limits: service_grpc_influxrpc::ResultLimits {
+            max_points: args
+                .querier_config
+                .storage_max_result_points
+                .map(NonZeroUsize::get),
+            max_bytes: args
+                .querier_config
+                .storage_max_result_bytes
+                .map(|b| b.bytes()),
+        },
     }))
 }
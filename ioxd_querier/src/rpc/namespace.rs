@@ -95,6 +95,42 @@ impl proto::namespace_service_server::NamespaceService for NamespaceServiceImpl
             "use router instances to manage namespaces",
         ))
     }
+
+    async fn get_namespace_templates(
+        &self,
+        _request: tonic::Request<proto::GetNamespaceTemplatesRequest>,
+    ) -> Result<tonic::Response<proto::GetNamespaceTemplatesResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespace templates",
+        ))
+    }
+
+    async fn create_namespace_template(
+        &self,
+        _request: tonic::Request<proto::CreateNamespaceTemplateRequest>,
+    ) -> Result<tonic::Response<proto::CreateNamespaceTemplateResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespace templates",
+        ))
+    }
+
+    async fn update_namespace_template(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceTemplateRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceTemplateResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespace templates",
+        ))
+    }
+
+    async fn validate_namespace(
+        &self,
+        _request: tonic::Request<proto::ValidateNamespaceRequest>,
+    ) -> Result<tonic::Response<proto::ValidateNamespaceResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to validate namespaces",
+        ))
+    }
 }
 
 #[cfg(test)]
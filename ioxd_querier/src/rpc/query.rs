@@ -1,19 +1,26 @@
 use authz::Authorizer;
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc};
 
 use arrow_flight::flight_service_server::{
     FlightService as Flight, FlightServiceServer as FlightServer,
 };
 use generated_types::storage_server::{Storage, StorageServer};
 use querier::QuerierDatabase;
+use service_grpc_flight::{CursorConfig, ResultLimits};
 
 pub fn make_flight_server(
     server: Arc<QuerierDatabase>,
     authz: Option<Arc<dyn Authorizer>>,
+    cursor_config: CursorConfig,
+    result_limits: ResultLimits,
 ) -> FlightServer<impl Flight> {
-    service_grpc_flight::make_server(server, authz)
+    service_grpc_flight::make_server(server, authz, cursor_config, result_limits)
 }
 
-pub fn make_storage_server(server: Arc<QuerierDatabase>) -> StorageServer<impl Storage> {
-    service_grpc_influxrpc::make_server(server)
+pub fn make_storage_server(
+    server: Arc<QuerierDatabase>,
+    max_series_per_query: Option<NonZeroUsize>,
+    result_limits: service_grpc_influxrpc::ResultLimits,
+) -> StorageServer<impl Storage> {
+    service_grpc_influxrpc::make_server(server, max_series_per_query, result_limits)
 }
@@ -0,0 +1,244 @@
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::todo,
+    clippy::use_self,
+    missing_debug_implementations,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use async_trait::async_trait;
+use authz::{Authorizer, IoxAuthorizer};
+use clap_blocks::query_router::QueryRouterConfig;
+use hyper::{Body, Request, Response};
+use iox_query::exec::Executor;
+use ioxd_common::{
+    add_service,
+    http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
+    rpc::RpcBuilderInput,
+    serve_builder,
+    server_type::{CommonServerState, RpcError, ServerType},
+    setup_builder,
+};
+use metric::Registry;
+use query_federation::{Backend, FederatedDatabase, GrpcBackend};
+use service_grpc_flight::{CursorConfig, ResultLimits};
+use std::{
+    fmt::{Debug, Display},
+    num::NonZeroUsize,
+    sync::Arc,
+};
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+use trace::TraceCollector;
+
+pub struct QueryRouterServerType {
+    database: Arc<FederatedDatabase>,
+    metric_registry: Arc<Registry>,
+    trace_collector: Option<Arc<dyn TraceCollector>>,
+    authz: Option<Arc<dyn Authorizer>>,
+    max_series_per_query: Option<NonZeroUsize>,
+    flight_cursor_config: CursorConfig,
+    flight_result_limits: ResultLimits,
+    storage_result_limits: service_grpc_influxrpc::ResultLimits,
+    shutdown: CancellationToken,
+}
+
+impl std::fmt::Debug for QueryRouterServerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryRouter")
+    }
+}
+
+#[async_trait]
+impl ServerType for QueryRouterServerType {
+    /// Human name for this server type
+    fn name(&self) -> &str {
+        "query_router"
+    }
+
+    /// Return the [`metric::Registry`] used by the query router.
+    fn metric_registry(&self) -> Arc<Registry> {
+        Arc::clone(&self.metric_registry)
+    }
+
+    /// Returns the trace collector for query router traces.
+    fn trace_collector(&self) -> Option<Arc<dyn TraceCollector>> {
+        self.trace_collector.as_ref().map(Arc::clone)
+    }
+
+    /// Just return "not found".
+    async fn route_http_request(
+        &self,
+        _req: Request<Body>,
+    ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>> {
+        Err(Box::new(IoxHttpError::NotFound))
+    }
+
+    /// Configure the gRPC services.
+    async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
+        let builder = setup_builder!(builder_input, self);
+        add_service!(
+            builder,
+            service_grpc_flight::make_server(
+                Arc::clone(&self.database),
+                self.authz.as_ref().map(Arc::clone),
+                self.flight_cursor_config,
+                self.flight_result_limits,
+            )
+        );
+        add_service!(
+            builder,
+            service_grpc_influxrpc::make_server(
+                Arc::clone(&self.database),
+                self.max_series_per_query,
+                self.storage_result_limits,
+            )
+        );
+
+        serve_builder!(builder);
+
+        Ok(())
+    }
+
+    async fn join(self: Arc<Self>) {
+        self.shutdown.cancelled().await;
+    }
+
+    fn shutdown(&self, frontend: CancellationToken) {
+        frontend.cancel();
+        self.shutdown.cancel();
+    }
+}
+
+/// Simple error struct, we're not really providing an HTTP interface for the query router.
+#[derive(Debug)]
+pub enum IoxHttpError {
+    NotFound,
+}
+
+impl IoxHttpError {
+    fn status_code(&self) -> HttpApiErrorCode {
+        match self {
+            Self::NotFound => HttpApiErrorCode::NotFound,
+        }
+    }
+}
+
+impl Display for IoxHttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for IoxHttpError {}
+
+impl HttpApiErrorSource for IoxHttpError {
+    fn to_http_api_error(&self) -> HttpApiError {
+        HttpApiError::new(self.status_code(), self.to_string())
+    }
+}
+
+/// Arguments required to create a [`ServerType`] for the query router.
+#[derive(Debug)]
+pub struct QueryRouterServerTypeArgs<'a> {
+    pub common_state: &'a CommonServerState,
+    pub metric_registry: Arc<metric::Registry>,
+    pub exec: Arc<Executor>,
+    pub query_router_config: QueryRouterConfig,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("authz configuration error for '{addr}': '{source}'")]
+    AuthzConfig {
+        source: Box<dyn std::error::Error>,
+        addr: String,
+    },
+}
+
+/// Instantiate a query router server
+pub async fn create_query_router_server_type(
+    args: QueryRouterServerTypeArgs<'_>,
+) -> Result<Arc<dyn ServerType>, Error> {
+    let authz = match &args.query_router_config.authz_address {
+        Some(addr) => {
+            let authz = IoxAuthorizer::connect_lazy(addr.clone())
+                .map(|c| Arc::new(c) as Arc<dyn Authorizer>)
+                .map_err(|source| Error::AuthzConfig {
+                    source,
+                    addr: addr.clone(),
+                })?;
+            authz.probe().await.expect("Authz connection test failed.");
+
+            Some(authz)
+        }
+        None => None,
+    };
+
+    let backends = args
+        .query_router_config
+        .backends
+        .iter()
+        .map(|backend| {
+            Arc::new(GrpcBackend::new(
+                backend.name.clone(),
+                backend.address.to_string(),
+            )) as Arc<dyn Backend>
+        })
+        .collect();
+
+    let database = Arc::new(FederatedDatabase::new(
+        backends,
+        args.exec,
+        &args.metric_registry,
+        args.query_router_config.max_concurrent_queries,
+        args.query_router_config.allow_partial_results,
+    ));
+
+    Ok(Arc::new(QueryRouterServerType {
+        database,
+        metric_registry: args.metric_registry,
+        trace_collector: args.common_state.trace_collector(),
+        authz,
+        max_series_per_query: args.query_router_config.max_series_per_query,
+        flight_cursor_config: CursorConfig {
+            ttl: args.query_router_config.flight_config.cursor_ttl,
+            max_memory_bytes: args
+                .query_router_config
+                .flight_config
+                .cursor_memory_bytes
+                .bytes(),
+        },
+        flight_result_limits: ResultLimits {
+            max_rows: args
+                .query_router_config
+                .flight_config
+                .max_result_rows
+                .map(NonZeroUsize::get),
+            max_bytes: args
+                .query_router_config
+                .flight_config
+                .max_result_bytes
+                .map(|b| b.bytes()),
+        },
+        storage_result_limits: service_grpc_influxrpc::ResultLimits {
+            max_points: args
+                .query_router_config
+                .storage_max_result_points
+                .map(NonZeroUsize::get),
+            max_bytes: args
+                .query_router_config
+                .storage_max_result_bytes
+                .map(|b| b.bytes()),
+        },
+        shutdown: CancellationToken::new(),
+    }))
+}
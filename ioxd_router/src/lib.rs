@@ -50,9 +50,11 @@ use metric::Registry;
 use mutable_batch::MutableBatch;
 use object_store::DynObjectStore;
 use router::{
+    derived_tags::DerivedTagRules,
     dml_handlers::{
         lazy_connector::LazyConnector, DmlHandler, DmlHandlerChainExt, FanOutAdaptor,
         InstrumentationDecorator, Partitioner, RetentionValidator, RpcWrite, SchemaValidator,
+        TenantQuotaValidator, WriteSummary, DEFAULT_TENANT_USAGE_TTL,
     },
     gossip::{
         anti_entropy::mst::{
@@ -79,6 +81,8 @@ use router::{
         },
         RpcWriteRouterServer,
     },
+    tag_normalization::TagNormalizationRules,
+    tenant_resolver::{TenantResolver, DEFAULT_TENANT_TTL},
 };
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
@@ -101,6 +105,12 @@ pub enum Error {
     /// An error binding the UDP socket for gossip communication.
     #[error("failed to bind udp gossip socket: {0}")]
     GossipBind(std::io::Error),
+
+    #[error("tag normalization rules error: {0}")]
+    TagNormalizationRules(#[from] router::tag_normalization::TagNormalizationError),
+
+    #[error("derived tag rules error: {0}")]
+    DerivedTags(#[from] router::derived_tags::DerivedTagsError),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -130,7 +140,7 @@ impl<D, N> std::fmt::Debug for RpcWriteRouterServerType<D, N> {
 #[async_trait]
 impl<D, N> ServerType for RpcWriteRouterServerType<D, N>
 where
-    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = ()> + 'static,
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = WriteSummary> + 'static,
     N: NamespaceResolver + 'static,
 {
     fn name(&self) -> &str {
@@ -381,6 +391,18 @@ pub async fn create_router_server_type(
     let retention_validator =
         InstrumentationDecorator::new("retention_validator", &metrics, retention_validator);
 
+    // # Tenant quota validator
+    //
+    // Resolve the tenant owning the namespace of each write (shared with the
+    // single-tenant authorizer) and reject writes exceeding its quotas
+    let tenant_resolver = Arc::new(TenantResolver::new(
+        Arc::clone(&catalog),
+        DEFAULT_TENANT_TTL,
+    ));
+    let tenant_quota =
+        TenantQuotaValidator::new(Arc::clone(&tenant_resolver), DEFAULT_TENANT_USAGE_TTL);
+    let tenant_quota = InstrumentationDecorator::new("tenant_quota", &metrics, tenant_quota);
+
     // # Write partitioner
     //
     // Add a write partitioner into the handler stack that splits by the date
@@ -418,7 +440,8 @@ pub async fn create_router_server_type(
     // # Handler stack
     //
     // Build the chain of DML handlers that forms the request processing pipeline
-    let handler_stack = retention_validator
+    let handler_stack = tenant_quota
+        .and_then(retention_validator)
         .and_then(schema_validator)
         .and_then(partitioner)
         // Once writes have been partitioned, they are processed in parallel.
@@ -452,7 +475,9 @@ pub async fn create_router_server_type(
                 })?;
             authz.probe().await.expect("Authz connection test failed.");
 
-            Ok(Box::new(SingleTenantRequestUnifier::new(authz)))
+            Ok(Box::new(
+                SingleTenantRequestUnifier::new(authz).with_tenant_resolver(tenant_resolver),
+            ))
         }
         (true, None) => {
             // Single tenancy was requested, but no auth was provided - the
@@ -461,7 +486,13 @@ pub async fn create_router_server_type(
             // never reach here.
             unreachable!("INFLUXDB_IOX_SINGLE_TENANCY is set, but could not create an authz service. Check the INFLUXDB_IOX_AUTHZ_ADDR")
         }
-        (false, None) => Ok(Box::<MultiTenantRequestUnifier>::default()),
+        (false, None) => {
+            let unifier = match &router_config.v1_write_org {
+                Some(org) => MultiTenantRequestUnifier::default().with_v1_org(org),
+                None => MultiTenantRequestUnifier::default(),
+            };
+            Ok(Box::new(unifier))
+        }
         (false, Some(_)) => {
             // As above, this combination should be prevented by the
             // router's clap flag parse configuration.
@@ -476,6 +507,22 @@ pub async fn create_router_server_type(
         &metrics,
         write_request_unifier?,
     );
+    let http = match &router_config.tag_normalization_rules_file {
+        Some(path) => {
+            let rules = TagNormalizationRules::from_file(path)?;
+            info!(path=%path.display(), "loaded tag normalization rules");
+            http.with_tag_normalization(rules)
+        }
+        None => http,
+    };
+    let http = match &router_config.derived_tags_file {
+        Some(path) => {
+            let rules = DerivedTagRules::from_file(path)?;
+            info!(path=%path.display(), "loaded derived tag rules");
+            http.with_derived_tags(rules)
+        }
+        None => http,
+    };
 
     // Initialize the gRPC API delegate that creates the services relevant to the RPC
     // write router path and use it to create the relevant `RpcWriteRouterServer` and
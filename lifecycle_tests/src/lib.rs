@@ -0,0 +1,680 @@
+//! A deterministic simulation of the data lifecycle.
+//!
+//! A [`Simulation`] runs an in-process ingester, compactor and querier that
+//! share a single catalog, object store and WAL directory, and applies a
+//! sequence of [`Op`]s to them: writes, persistence, compaction, ungraceful
+//! ingester crashes followed by WAL replay, and graceful restarts. The
+//! sequence is derived from a seed by [`ops()`], so a failing run can be
+//! reproduced from its seed alone.
+//!
+//! After every op the state of the system is compared with a [`Model`] that
+//! tracks which rows have been written and persisted:
+//!
+//! * Querying the persisted data through the querier (and so through the
+//!   deduplication of overlapping files) returns exactly the rows the model
+//!   expects - nothing lost and no duplicate rows left after deduplication.
+//! * The ingester buffers exactly the rows written since they were last
+//!   persisted.
+//! * Every parquet file in the catalog matches the content hash recorded for
+//!   it.
+//!
+//! Rows are upserts of a small key space, and each write sets a random subset
+//! of the fields so that the "last non-null value wins" field resolution is
+//! exercised across buffers, files and compaction levels.
+//!
+//! Delete predicates are not modelled: this version of IOx has no delete path
+//! in the ingester, catalog or compactor.
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::Duration,
+};
+
+use arrow::{
+    array::{as_primitive_array, as_string_array, Array},
+    compute::cast,
+    datatypes::{DataType, Int64Type},
+    record_batch::RecordBatch,
+};
+use backoff::BackoffConfig;
+use compactor::{compact, config::Config, hardcoded_components, JobRegistry};
+use compactor_scheduler::SchedulerConfig;
+use data_types::PartitionKey;
+use datafusion_util::config::register_iox_object_store;
+use ingester::IngesterRpcInterface;
+use ingester_query_grpc::influxdata::iox::ingester::v1::IngesterQueryRequest;
+use ingester_test_ctx::{TestContext, TestContextBuilder};
+use iox_catalog::{interface::Catalog, mem::MemCatalog};
+use iox_query::{
+    exec::{DedicatedExecutors, Executor, ExecutorConfig, ExecutorType},
+    QueryNamespace,
+};
+use iox_time::{SystemProvider, TimeProvider};
+use object_store::{memory::InMemory, DynObjectStore};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use parquet_file::{
+    storage::{ParquetStorage, StorageId},
+    verify::verify_file,
+};
+use querier::{QuerierCatalogCache, QuerierDatabase};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use tempfile::TempDir;
+use tokio::runtime::Handle;
+use tracker::{AsyncSemaphoreMetrics, TaskRegistry};
+
+/// The namespace all rows are written to.
+pub const NAMESPACE: &str = "lifecycle";
+
+/// The table all rows are written to.
+pub const TABLE: &str = "cpu";
+
+/// The partition all rows fall into with the default partition template.
+const PARTITION_KEY: &str = "1970-01-01";
+
+/// The values of the `host` tag.
+const HOSTS: &[&str] = &["a", "b", "c"];
+
+/// Rows have timestamps in `0..MAX_TIME`, so with [`HOSTS`] there are few
+/// enough distinct keys that writes frequently overwrite each other.
+const MAX_TIME: i64 = 8;
+
+/// The largest number of rows in a single write.
+const MAX_ROWS_PER_WRITE: usize = 6;
+
+/// A row of [`TABLE`], with the primary key (`host`, `time`) and two nullable
+/// integer fields.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Row {
+    /// The `host` tag.
+    pub host: String,
+    /// The timestamp, in nanoseconds.
+    pub time: i64,
+    /// The `v` field, if set.
+    pub v: Option<i64>,
+    /// The `w` field, if set.
+    pub w: Option<i64>,
+}
+
+impl Row {
+    /// Create a row for `host` at `time` with the given fields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if neither field is set, as line protocol requires at least one.
+    pub fn new(host: &str, time: i64, v: Option<i64>, w: Option<i64>) -> Self {
+        assert!(
+            v.is_some() || w.is_some(),
+            "a row must set at least one field"
+        );
+        Self {
+            host: host.to_string(),
+            time,
+            v,
+            w,
+        }
+    }
+
+    fn to_line_protocol(&self) -> String {
+        let fields = [("v", self.v), ("w", self.w)]
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| format!("{name}={value}i")))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{TABLE},host={} {fields} {}", self.host, self.time)
+    }
+}
+
+/// A step of a [`Simulation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    /// Write the rows to the ingester in a single request.
+    Write(Vec<Row>),
+
+    /// Persist all the data buffered in the ingester.
+    Persist,
+
+    /// Run the compactor over every partition with new files.
+    Compact,
+
+    /// Run the compactor while the ingester persists, so that their catalog
+    /// updates interleave.
+    CompactWhilePersisting,
+
+    /// Run the compactor while the rows are written to the ingester.
+    CompactWhileWriting(Vec<Row>),
+
+    /// Drop the ingester without shutting it down, then start a new one that
+    /// replays (and persists) the WAL.
+    Crash,
+
+    /// Gracefully shut the ingester down, persisting its buffer, then start a
+    /// new one.
+    Restart,
+}
+
+impl Op {
+    /// Generate a random op.
+    fn random(rng: &mut StdRng) -> Self {
+        match rng.gen_range(0..100) {
+            0..=49 => Self::Write(random_rows(rng)),
+            50..=64 => Self::Persist,
+            65..=74 => Self::Compact,
+            75..=79 => Self::CompactWhilePersisting,
+            80..=84 => Self::CompactWhileWriting(random_rows(rng)),
+            85..=92 => Self::Crash,
+            _ => Self::Restart,
+        }
+    }
+}
+
+/// Generate a write of up to [`MAX_ROWS_PER_WRITE`] rows with distinct keys,
+/// each setting one or both fields.
+fn random_rows(rng: &mut StdRng) -> Vec<Row> {
+    let mut keys = HOSTS
+        .iter()
+        .flat_map(|host| (0..MAX_TIME).map(move |time| (*host, time)))
+        .collect::<Vec<_>>();
+    keys.shuffle(rng);
+
+    let n = rng.gen_range(1..=MAX_ROWS_PER_WRITE);
+    keys.into_iter()
+        .take(n)
+        .map(|(host, time)| {
+            let (v, w) = match rng.gen_range(0..3) {
+                0 => (Some(rng.gen_range(0..1000)), None),
+                1 => (None, Some(rng.gen_range(0..1000))),
+                _ => (Some(rng.gen_range(0..1000)), Some(rng.gen_range(0..1000))),
+            };
+            Row::new(host, time, v, w)
+        })
+        .collect()
+}
+
+/// Generate `n` random ops from `seed`.
+///
+/// The first op is always a write, so that the namespace has a table to
+/// query.
+pub fn ops(seed: u64, n: usize) -> Vec<Op> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    std::iter::once(Op::Write(random_rows(&mut rng)))
+        .chain(std::iter::repeat_with(|| Op::random(&mut rng)))
+        .take(n)
+        .collect()
+}
+
+/// The expected state of the system.
+#[derive(Debug, Default)]
+pub struct Model {
+    /// The rows persisted to object storage, in the order they were written.
+    persisted: Vec<Row>,
+
+    /// The rows buffered in the ingester, in the order they were written.
+    buffered: Vec<Row>,
+}
+
+impl Model {
+    /// Record the rows as buffered in the ingester.
+    fn write(&mut self, rows: &[Row]) {
+        self.buffered.extend_from_slice(rows);
+    }
+
+    /// Record all the buffered rows as persisted.
+    fn persist(&mut self) {
+        self.persisted.append(&mut self.buffered);
+    }
+
+    /// The persisted rows after deduplication, ordered by key.
+    fn persisted_deduplicated(&self) -> Vec<Row> {
+        deduplicate(&self.persisted)
+    }
+
+    /// The buffered rows, sorted.
+    fn buffered_sorted(&self) -> Vec<Row> {
+        let mut rows = self.buffered.clone();
+        rows.sort();
+        rows
+    }
+}
+
+/// Merge rows with the same key in the order given, each field taking its
+/// last non-null value.
+pub fn deduplicate(rows: &[Row]) -> Vec<Row> {
+    let mut merged = BTreeMap::new();
+    for row in rows {
+        merged
+            .entry((row.host.clone(), row.time))
+            .and_modify(|existing: &mut Row| {
+                existing.v = row.v.or(existing.v);
+                existing.w = row.w.or(existing.w);
+            })
+            .or_insert_with(|| row.clone());
+    }
+    merged.into_values().collect()
+}
+
+/// Drives an ingester, compactor and querier through a sequence of [`Op`]s,
+/// checking the invariants described in the [crate documentation](crate)
+/// after each one.
+#[derive(Debug)]
+pub struct Simulation {
+    seed: u64,
+    catalog: Arc<dyn Catalog>,
+    parquet_store: ParquetStorage,
+    scratchpad_store: ParquetStorage,
+    wal_dir: Arc<TempDir>,
+    exec: Arc<Executor>,
+    time_provider: Arc<dyn TimeProvider>,
+    model: Model,
+
+    /// The sequence number of the next write, which must keep increasing
+    /// across ingester restarts.
+    next_sequence_number: u64,
+}
+
+impl Simulation {
+    /// Create a simulation with an empty catalog and object store.
+    ///
+    /// The `seed` is only used to identify the run in log messages and
+    /// assertion failures.
+    pub fn new(seed: u64) -> Self {
+        test_helpers::maybe_start_logging();
+
+        let metric_registry = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metric_registry)));
+        let object_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let parquet_store = ParquetStorage::new(object_store, StorageId::from("iox"));
+        let scratchpad_store =
+            ParquetStorage::new(Arc::new(InMemory::new()), StorageId::from("scratchpad"));
+
+        let exec = Arc::new(Executor::new_with_config_and_executors(
+            ExecutorConfig {
+                num_threads: NonZeroUsize::new(1).unwrap(),
+                target_query_partitions: NonZeroUsize::new(1).unwrap(),
+                object_stores: HashMap::from([
+                    (parquet_store.id(), Arc::clone(parquet_store.object_store())),
+                    (
+                        scratchpad_store.id(),
+                        Arc::clone(scratchpad_store.object_store()),
+                    ),
+                ]),
+                metric_registry,
+                mem_pool_size: 1024 * 1024 * 1024,
+            },
+            Arc::new(DedicatedExecutors::new_testing()),
+        ));
+
+        Self {
+            seed,
+            catalog,
+            parquet_store,
+            scratchpad_store,
+            wal_dir: Arc::new(test_helpers::tmp_dir().unwrap()),
+            exec,
+            time_provider: Arc::new(SystemProvider::new()),
+            model: Model::default(),
+            next_sequence_number: 0,
+        }
+    }
+
+    /// Apply `ops` in order, checking the invariants after each one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an invariant does not hold, with a message identifying the
+    /// seed and the op that was applied last.
+    pub async fn run(mut self, ops: Vec<Op>) {
+        let mut ingester = self.start_ingester().await;
+        ingester.ensure_namespace(NAMESPACE, None, None).await;
+
+        for (step, op) in ops.into_iter().enumerate() {
+            info!(seed = self.seed, step, ?op, "applying lifecycle op");
+
+            match &op {
+                Op::Write(rows) => self.write(&mut ingester, rows).await,
+                Op::Persist => {
+                    ingester.persist(NAMESPACE).await;
+                    self.model.persist();
+                }
+                Op::Compact => self.compact().await,
+                Op::CompactWhilePersisting => {
+                    tokio::join!(self.compact(), ingester.persist(NAMESPACE));
+                    self.model.persist();
+                }
+                Op::CompactWhileWriting(rows) => {
+                    let sequence_number = self.next_sequence_number;
+                    self.next_sequence_number += 1;
+                    tokio::join!(
+                        self.compact(),
+                        write_rows(&mut ingester, rows, sequence_number)
+                    );
+                    self.model.write(rows);
+                }
+                Op::Crash => {
+                    drop(ingester);
+                    ingester = self.start_ingester().await;
+                    ingester.load_namespace(NAMESPACE).await;
+                    self.model.persist();
+                }
+                Op::Restart => {
+                    ingester.shutdown().await;
+                    ingester = self.start_ingester().await;
+                    ingester.load_namespace(NAMESPACE).await;
+                    self.model.persist();
+                }
+            }
+
+            self.check(&ingester, step, &op).await;
+        }
+    }
+
+    /// Start an ingester using the shared WAL directory, catalog and object
+    /// store, replaying any WAL left behind by the previous one.
+    async fn start_ingester(&self) -> TestContext<impl IngesterRpcInterface> {
+        TestContextBuilder::default()
+            .with_wal_dir(Arc::clone(&self.wal_dir))
+            .with_catalog(Arc::clone(&self.catalog))
+            .with_object_store(Arc::clone(self.parquet_store.object_store()))
+            .build()
+            .await
+    }
+
+    async fn write<T>(&mut self, ingester: &mut TestContext<T>, rows: &[Row])
+    where
+        T: IngesterRpcInterface,
+    {
+        let sequence_number = self.next_sequence_number;
+        self.next_sequence_number += 1;
+        write_rows(ingester, rows, sequence_number).await;
+        self.model.write(rows);
+    }
+
+    /// Run a single pass of the compactor over all partitions with recently
+    /// created files, panicking on any error.
+    async fn compact(&self) {
+        let metric_registry = Arc::new(metric::Registry::new());
+        let config = Config {
+            metric_registry: Arc::clone(&metric_registry),
+            trace_collector: None,
+            catalog: Arc::clone(&self.catalog),
+            scheduler_config: SchedulerConfig::default(),
+            parquet_store_real: self.parquet_store.clone(),
+            parquet_store_scratchpad: self.scratchpad_store.clone(),
+            exec: Arc::clone(&self.exec),
+            time_provider: Arc::clone(&self.time_provider),
+            backoff_config: BackoffConfig::default(),
+            partition_concurrency: NonZeroUsize::new(1).unwrap(),
+            df_concurrency: NonZeroUsize::new(1).unwrap(),
+            partition_scratchpad_concurrency: NonZeroUsize::new(1).unwrap(),
+            // small enough that compaction also splits files
+            max_desired_file_size_bytes: 10 * 1024,
+            percentage_max_file_size: 5,
+            split_percentage: 80,
+            partition_timeout: Duration::from_secs(60),
+            shadow_mode: false,
+            enable_scratchpad: true,
+            min_num_l1_files_to_compact: 2,
+            process_once: true,
+            simulate_without_object_store: false,
+            parquet_files_sink_override: None,
+            all_errors_are_fatal: true,
+            max_num_columns_per_table: 200,
+            max_num_files_per_plan: 200,
+            max_partition_fetch_queries_per_second: None,
+            gossip_bind_address: None,
+            gossip_seeds: vec![],
+        };
+
+        let runtime_env = self
+            .exec
+            .new_context(ExecutorType::Reorg)
+            .inner()
+            .runtime_env();
+        register_iox_object_store(
+            runtime_env,
+            self.scratchpad_store.id(),
+            Arc::clone(self.scratchpad_store.object_store()),
+        );
+
+        let components = hardcoded_components(&config);
+        let df_semaphore =
+            Arc::new(AsyncSemaphoreMetrics::new(&metric_registry, [])).new_semaphore(1);
+        let jobs = Arc::new(Mutex::new(JobRegistry::new(
+            TaskRegistry::new(Arc::clone(&self.time_provider)),
+            10,
+        )));
+
+        compact(
+            None,
+            config.partition_concurrency,
+            config.partition_timeout,
+            Arc::new(df_semaphore),
+            &components,
+            None,
+            &jobs,
+        )
+        .await;
+    }
+
+    /// Check the state of the system against the model.
+    async fn check<T>(&self, ingester: &TestContext<T>, step: usize, op: &Op)
+    where
+        T: IngesterRpcInterface,
+    {
+        let context = format!("seed {}, step {step} ({op:?})", self.seed);
+
+        let mut persisted = self.query_persisted().await;
+        persisted.sort();
+        assert_eq!(
+            persisted,
+            self.model.persisted_deduplicated(),
+            "persisted data queried through the querier differs from the model at {context}"
+        );
+
+        let mut buffered = self.query_buffered(ingester).await;
+        buffered.sort();
+        assert_eq!(
+            buffered,
+            self.model.buffered_sorted(),
+            "data buffered in the ingester differs from the model at {context}"
+        );
+
+        let mut corrupted = String::new();
+        for file in ingester.catalog_parquet_file_records(NAMESPACE).await {
+            if let Err(e) = verify_file(self.parquet_store.object_store().as_ref(), &file).await {
+                writeln!(corrupted, "{e}").unwrap();
+            }
+        }
+        assert!(
+            corrupted.is_empty(),
+            "parquet files failed verification at {context}:\n{corrupted}"
+        );
+    }
+
+    /// Read all the persisted rows through a new querier, which has no
+    /// ingester connection and so only sees parquet files.
+    async fn query_persisted(&self) -> Vec<Row> {
+        let metric_registry = Arc::new(metric::Registry::new());
+        let catalog_cache = Arc::new(QuerierCatalogCache::new_testing(
+            Arc::clone(&self.catalog),
+            Arc::clone(&self.time_provider),
+            Arc::clone(&metric_registry),
+            Arc::clone(self.parquet_store.object_store()),
+            &Handle::current(),
+        ));
+        let db = QuerierDatabase::new(
+            catalog_cache,
+            metric_registry,
+            Arc::clone(&self.exec),
+            None,
+            1,
+            Arc::new(HashMap::default()),
+        )
+        .await
+        .expect("failed to create querier database");
+
+        let namespace = db
+            .namespace(NAMESPACE, None, false)
+            .await
+            .expect("namespace not found");
+        let ctx = namespace.new_query_context(None);
+        let plan = ctx
+            .sql_to_physical_plan(&format!("SELECT * FROM {TABLE}"))
+            .await
+            .expect("failed to plan query");
+        let batches = ctx.collect(plan).await.expect("failed to run query");
+
+        rows(&batches)
+    }
+
+    /// Read the rows buffered in the ingester.
+    async fn query_buffered<T>(&self, ingester: &TestContext<T>) -> Vec<Row>
+    where
+        T: IngesterRpcInterface,
+    {
+        let request = IngesterQueryRequest {
+            namespace_id: ingester.namespace_id(NAMESPACE).await.get(),
+            table_id: ingester.table_id(NAMESPACE, TABLE).await.get(),
+            columns: vec![],
+            predicate: None,
+            last_values: false,
+        };
+
+        match ingester.query(request).await {
+            Ok(batches) => rows(&batches),
+            Err(e) => {
+                // An ingester that has nothing buffered for the namespace or
+                // table returns a "not found" error; any rows the model
+                // expects will be reported missing by the caller.
+                debug!(error=%e, "ingester query failed");
+                vec![]
+            }
+        }
+    }
+}
+
+async fn write_rows<T>(ingester: &mut TestContext<T>, rows: &[Row], sequence_number: u64)
+where
+    T: IngesterRpcInterface,
+{
+    let lp = rows
+        .iter()
+        .map(Row::to_line_protocol)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ingester
+        .write_lp(
+            NAMESPACE,
+            &lp,
+            PartitionKey::from(PARTITION_KEY),
+            sequence_number,
+            None,
+        )
+        .await;
+}
+
+/// Convert query results to [`Row`]s, treating missing field columns as null.
+fn rows(batches: &[RecordBatch]) -> Vec<Row> {
+    let mut rows = vec![];
+
+    for batch in batches {
+        let host = cast(
+            batch.column_by_name("host").expect("host column"),
+            &DataType::Utf8,
+        )
+        .unwrap();
+        let host = as_string_array(&host);
+        let time = int64_column(batch, "time").expect("time column");
+        let v = int64_column(batch, "v");
+        let w = int64_column(batch, "w");
+
+        let value = |column: &Option<Arc<dyn Array>>, i: usize| {
+            column.as_ref().and_then(|column| {
+                let column = as_primitive_array::<Int64Type>(column);
+                (!column.is_null(i)).then(|| column.value(i))
+            })
+        };
+
+        for i in 0..batch.num_rows() {
+            rows.push(Row {
+                host: host.value(i).to_string(),
+                time: as_primitive_array::<Int64Type>(&time).value(i),
+                v: value(&v, i),
+                w: value(&w, i),
+            });
+        }
+    }
+
+    rows
+}
+
+fn int64_column(batch: &RecordBatch, name: &str) -> Option<Arc<dyn Array>> {
+    batch
+        .column_by_name(name)
+        .map(|column| cast(column, &DataType::Int64).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ops_are_deterministic() {
+        assert_eq!(ops(42, 100), ops(42, 100));
+        assert_ne!(ops(42, 100), ops(43, 100));
+        assert!(matches!(ops(7, 1)[..], [Op::Write(_)]));
+    }
+
+    #[test]
+    fn test_deduplicate() {
+        let rows = [
+            Row::new("a", 1, Some(1), Some(1)),
+            Row::new("b", 1, Some(2), None),
+            Row::new("a", 1, None, Some(3)),
+            Row::new("a", 2, Some(4), None),
+            Row::new("a", 1, Some(5), None),
+        ];
+
+        assert_eq!(
+            deduplicate(&rows),
+            [
+                Row::new("a", 1, Some(5), Some(3)),
+                Row::new("a", 2, Some(4), None),
+                Row::new("b", 1, Some(2), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_protocol() {
+        assert_eq!(
+            Row::new("a", 3, Some(1), None).to_line_protocol(),
+            "cpu,host=a v=1i 3"
+        );
+        assert_eq!(
+            Row::new("b", 4, Some(1), Some(2)).to_line_protocol(),
+            "cpu,host=b v=1i,w=2i 4"
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use lifecycle_tests::{ops, Op, Row, Simulation};
+
+/// The number of ops applied in each seeded run.
+const STEPS: usize = 40;
+
+/// The seeds to run, overridden by setting `LIFECYCLE_SEED` to reproduce a
+/// single failing run.
+fn seeds() -> Vec<u64> {
+    match std::env::var("LIFECYCLE_SEED") {
+        Ok(seed) => vec![seed.parse().expect("LIFECYCLE_SEED must be a u64")],
+        Err(_) => (0..8).collect(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn random_lifecycle() {
+    for seed in seeds() {
+        Simulation::new(seed).run(ops(seed, STEPS)).await;
+    }
+}
+
+// Fields set by different writes to the same key must be merged, whether
+// the writes end up in the same buffer, different files or files at
+// different compaction levels.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn partial_field_overwrites() {
+    Simulation::new(0)
+        .run(vec![
+            Op::Write(vec![
+                Row::new("a", 1, Some(1), None),
+                Row::new("b", 1, Some(1), Some(1)),
+            ]),
+            Op::Write(vec![Row::new("a", 1, None, Some(2))]),
+            Op::Persist,
+            Op::Write(vec![Row::new("b", 1, None, Some(3))]),
+            Op::Persist,
+            Op::Compact,
+            Op::Write(vec![Row::new("a", 1, Some(4), None)]),
+            Op::Crash,
+            Op::Write(vec![Row::new("b", 1, Some(5), None)]),
+            Op::CompactWhilePersisting,
+            Op::CompactWhileWriting(vec![Row::new("a", 1, None, Some(6))]),
+            Op::Restart,
+            Op::Compact,
+        ])
+        .await;
+}
@@ -27,6 +27,9 @@ pub enum Error {
 
     #[snafu(display("Key not found in dictionary: {}", key))]
     KeyNotFound { key: usize },
+
+    #[snafu(display("Dictionary of tag column {} is full", column))]
+    DictionaryOverflow { column: String },
 }
 
 /// A specialized `Error` for [`Writer`] errors
@@ -329,7 +332,11 @@ impl<'a> Writer<'a> {
 
                 for idx in set_position_iterator(valid_mask, to_insert) {
                     let value = values.next().ok_or(Error::InsufficientValues)?;
-                    col_data[initial_rows + idx] = dict.lookup_value_or_insert(value);
+                    col_data[initial_rows + idx] = dict
+                        .try_lookup_value_or_insert(value)
+                        .ok_or_else(|| Error::DictionaryOverflow {
+                            column: name.to_string(),
+                        })?;
                     stats.update(value);
                 }
             }
@@ -385,7 +392,11 @@ impl<'a> Writer<'a> {
                     match maybe_did {
                         Some(did) => col_data[initial_rows + idx] = *did,
                         None => {
-                            let did = dict.lookup_value_or_insert(value);
+                            let did = dict.try_lookup_value_or_insert(value).ok_or_else(|| {
+                                Error::DictionaryOverflow {
+                                    column: name.to_string(),
+                                }
+                            })?;
                             *maybe_did = Some(did);
                             col_data[initial_rows + idx] = did
                         }
@@ -476,11 +487,17 @@ impl<'a> Writer<'a> {
                     ColumnData::Tag(dst_data, dst_dict, _),
                     ColumnData::Tag(src_data, src_dict, stats),
                 ) => {
-                    let mapping: Vec<_> = src_dict
+                    let mapping = src_dict
                         .values()
                         .iter()
-                        .map(|value| dst_dict.lookup_value_or_insert(value))
-                        .collect();
+                        .map(|value| {
+                            dst_dict.try_lookup_value_or_insert(value).ok_or_else(|| {
+                                Error::DictionaryOverflow {
+                                    column: src_col_name.clone(),
+                                }
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
 
                     dst_data.extend(src_data.iter().map(|src_id| match *src_id {
                         INVALID_DID => INVALID_DID,
@@ -565,8 +582,8 @@ impl<'a> Writer<'a> {
                     let mut mapping: Vec<_> = vec![None; src_dict.values().len()];
                     let mut stats = StatValues::new_empty();
                     for range in ranges {
-                        dst_data.extend(src_data[range.clone()].iter().map(
-                            |src_id| match *src_id {
+                        for src_id in &src_data[range.clone()] {
+                            let did = match *src_id {
                                 INVALID_DID => {
                                     stats.update_for_nulls(1);
                                     INVALID_DID
@@ -582,14 +599,19 @@ impl<'a> Writer<'a> {
                                             let value = src_dict.lookup_id(*src_id).unwrap();
                                             stats.update(value);
 
-                                            let did = dst_dict.lookup_value_or_insert(value);
+                                            let did = dst_dict
+                                                .try_lookup_value_or_insert(value)
+                                                .ok_or_else(|| Error::DictionaryOverflow {
+                                                    column: src_col_name.clone(),
+                                                })?;
                                             *maybe_did = Some(did);
                                             did
                                         }
                                     }
                                 }
-                            },
-                        ));
+                            };
+                            dst_data.push(did);
+                        }
                     }
 
                     Statistics::String(stats)
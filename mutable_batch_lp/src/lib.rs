@@ -20,6 +20,8 @@
 use criterion as _;
 use workspace_hack as _;
 
+use std::{fmt::Debug, sync::Arc};
+
 use hashbrown::{hash_map::Entry, HashMap, HashSet};
 use influxdb_line_protocol::{parse_lines, FieldValue, ParsedLine};
 use mutable_batch::writer::Writer;
@@ -58,6 +60,25 @@ pub struct PayloadStatistics {
     pub num_lines: usize,
 }
 
+/// Normalizes tag values as they are converted, before they are written to
+/// a [`MutableBatch`] (and so before they form the series key of a row).
+pub trait TagValueNormalizer: Debug + Send + Sync {
+    /// Returns the normalized form of `value` of the tag `tag` in `table`,
+    /// or [`None`] if `value` is written unchanged.
+    fn normalize(&self, table: &str, tag: &str, value: &str) -> Option<String>;
+}
+
+/// Derives additional tags of rows from their measurement name and tags as
+/// they are converted, e.g. extracting a `datacenter` tag from a `host` tag.
+pub trait TagDeriver: Debug + Send + Sync {
+    /// Returns the names and values of the tags derived for a row of `table`
+    /// with the (normalized) `tags`.
+    ///
+    /// Tags of the row take precedence over derived tags of the same name,
+    /// and derived tags named like a field of the row are skipped.
+    fn derive(&self, table: &str, tags: &[(&str, &str)]) -> Vec<(String, String)>;
+}
+
 /// Converts line protocol to a set of [`MutableBatch`]
 #[derive(Debug)]
 pub struct LinesConverter {
@@ -65,6 +86,10 @@ pub struct LinesConverter {
     default_time: i64,
     /// The multiplier to convert input timestamps to nanoseconds
     timestamp_base: i64,
+    /// The normalizer applied to tag values, if any
+    tag_normalizer: Option<Arc<dyn TagValueNormalizer>>,
+    /// The deriver of additional tags, if any
+    tag_deriver: Option<Arc<dyn TagDeriver>>,
     /// The statistics
     stats: PayloadStatistics,
     /// The current batches
@@ -77,6 +102,8 @@ impl LinesConverter {
         Self {
             default_time,
             timestamp_base: 1,
+            tag_normalizer: None,
+            tag_deriver: None,
             stats: Default::default(),
             batches: Default::default(),
         }
@@ -87,6 +114,17 @@ impl LinesConverter {
         self.timestamp_base = timestamp_base
     }
 
+    /// Normalizes the tag values of subsequently written lines with
+    /// `normalizer`
+    pub fn set_tag_normalizer(&mut self, normalizer: Arc<dyn TagValueNormalizer>) {
+        self.tag_normalizer = Some(normalizer)
+    }
+
+    /// Adds the tags derived by `deriver` to subsequently written lines
+    pub fn set_tag_deriver(&mut self, deriver: Arc<dyn TagDeriver>) {
+        self.tag_deriver = Some(deriver)
+    }
+
     /// Write some line protocol data.
     ///
     /// If a field / tag name appears more than once in a single line, the
@@ -129,8 +167,14 @@ impl LinesConverter {
 
             // TODO: Reuse writer
             let mut writer = Writer::new(batch, 1);
-            write_line(&mut writer, &line, self.default_time)
-                .context(WriteSnafu { line: line_idx + 1 })?;
+            write_line_transformed(
+                &mut writer,
+                &line,
+                self.default_time,
+                self.tag_normalizer.as_deref(),
+                self.tag_deriver.as_deref(),
+            )
+            .context(WriteSnafu { line: line_idx + 1 })?;
             writer.commit();
         }
         Ok(())
@@ -205,6 +249,23 @@ pub fn write_line(
     line: &ParsedLine<'_>,
     default_time: i64,
 ) -> Result<(), LineWriteError> {
+    write_line_transformed(writer, line, default_time, None, None)
+}
+
+/// Writes the [`ParsedLine`] to the [`MutableBatch`] like [`write_line()`],
+/// normalizing the tag values with `tag_normalizer` and adding the tags
+/// derived by `tag_deriver`.
+fn write_line_transformed(
+    writer: &mut Writer<'_>,
+    line: &ParsedLine<'_>,
+    default_time: i64,
+    tag_normalizer: Option<&dyn TagValueNormalizer>,
+    tag_deriver: Option<&dyn TagDeriver>,
+) -> Result<(), LineWriteError> {
+    // The written tags of the line, only collected if tags are derived from
+    // them.
+    let mut written_tags = Vec::new();
+
     // Only allocate the seen tags hashset if there are tags.
     if let Some(tags) = &line.series.tag_set {
         let mut seen = HashSet::with_capacity(tags.len());
@@ -218,9 +279,46 @@ pub fn write_line(
                     name: tag_key.to_string(),
                 });
             }
+            let normalized = tag_normalizer.and_then(|n| {
+                n.normalize(
+                    line.series.measurement.as_str(),
+                    tag_key.as_str(),
+                    tag_value.as_str(),
+                )
+            });
+            let tag_value = normalized.as_deref().unwrap_or(tag_value.as_str());
             writer
-                .write_tag(tag_key.as_str(), None, std::iter::once(tag_value.as_str()))
-                .context(MutableBatchSnafu)?
+                .write_tag(tag_key.as_str(), None, std::iter::once(tag_value))
+                .context(MutableBatchSnafu)?;
+
+            if tag_deriver.is_some() {
+                written_tags.push((tag_key.as_str(), tag_value.to_string()));
+            }
+        }
+    }
+
+    if let Some(deriver) = tag_deriver {
+        let tags = written_tags
+            .iter()
+            .map(|(k, v)| (*k, v.as_str()))
+            .collect::<Vec<_>>();
+        let mut seen = tags.iter().map(|(k, _)| *k).collect::<HashSet<_>>();
+
+        let derived = deriver.derive(line.series.measurement.as_str(), &tags);
+        for (name, value) in &derived {
+            // Tags of the line, and tags derived first, win.
+            if !seen.insert(name.as_str()) {
+                continue;
+            }
+            // A derived tag must not turn the write into a column type
+            // conflict, so it is skipped if the line has a field (or the
+            // time column) of the same name.
+            if name == "time" || line.field_set.iter().any(|(k, _)| k.as_str() == name) {
+                continue;
+            }
+            writer
+                .write_tag(name, None, std::iter::once(value.as_str()))
+                .context(MutableBatchSnafu)?;
         }
     }
 
@@ -452,6 +550,136 @@ m b=t 1639612800000000000
         assert!(!u.is_valid(2));
     }
 
+    #[test]
+    fn test_tag_normalizer() {
+        #[derive(Debug)]
+        struct Lowercase;
+
+        impl TagValueNormalizer for Lowercase {
+            fn normalize(&self, table: &str, tag: &str, value: &str) -> Option<String> {
+                (table == "cpu" && tag == "host").then(|| value.to_lowercase())
+            }
+        }
+
+        let lp = r#"cpu,host=Server01,region=West val=1i 1
+        cpu,host=server01,region=west val=2i 2
+        mem,host=Server01 val=3i 3
+        "#;
+
+        let mut converter = LinesConverter::new(5);
+        converter.set_tag_normalizer(Arc::new(Lowercase));
+        converter.write_lp(lp).unwrap();
+        let (batches, _) = converter.finish().unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+----------+--------+--------------------------------+-----+",
+                "| host     | region | time                           | val |",
+                "+----------+--------+--------------------------------+-----+",
+                "| server01 | West   | 1970-01-01T00:00:00.000000001Z | 1   |",
+                "| server01 | west   | 1970-01-01T00:00:00.000000002Z | 2   |",
+                "+----------+--------+--------------------------------+-----+",
+            ],
+            &[batches["cpu"].to_arrow(Projection::All).unwrap()]
+        );
+        assert_batches_eq!(
+            &[
+                "+----------+--------------------------------+-----+",
+                "| host     | time                           | val |",
+                "+----------+--------------------------------+-----+",
+                "| Server01 | 1970-01-01T00:00:00.000000003Z | 3   |",
+                "+----------+--------------------------------+-----+",
+            ],
+            &[batches["mem"].to_arrow(Projection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_tag_deriver() {
+        #[derive(Debug)]
+        struct Datacenter;
+
+        impl TagDeriver for Datacenter {
+            fn derive(&self, _table: &str, tags: &[(&str, &str)]) -> Vec<(String, String)> {
+                tags.iter()
+                    .filter(|(k, _)| *k == "host")
+                    .filter_map(|(_, v)| v.split_once('-'))
+                    .map(|(dc, _)| ("datacenter".to_string(), dc.to_string()))
+                    .collect()
+            }
+        }
+
+        let lp = r#"cpu,host=ams-web01 val=1i 1
+        cpu,host=web02 val=2i 2
+        cpu,host=fra-web03,datacenter=override val=3i 3
+        "#;
+
+        let mut converter = LinesConverter::new(5);
+        converter.set_tag_deriver(Arc::new(Datacenter));
+        converter.write_lp(lp).unwrap();
+        let (batches, _) = converter.finish().unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+------------+-----------+--------------------------------+-----+",
+                "| datacenter | host      | time                           | val |",
+                "+------------+-----------+--------------------------------+-----+",
+                "| ams        | ams-web01 | 1970-01-01T00:00:00.000000001Z | 1   |",
+                "|            | web02     | 1970-01-01T00:00:00.000000002Z | 2   |",
+                "| override   | fra-web03 | 1970-01-01T00:00:00.000000003Z | 3   |",
+                "+------------+-----------+--------------------------------+-----+",
+            ],
+            &[batches["cpu"].to_arrow(Projection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_tag_deriver_field_collision() {
+        #[derive(Debug)]
+        struct Region;
+
+        impl TagDeriver for Region {
+            fn derive(&self, table: &str, _tags: &[(&str, &str)]) -> Vec<(String, String)> {
+                vec![
+                    ("region".to_string(), table.to_string()),
+                    ("time".to_string(), table.to_string()),
+                ]
+            }
+        }
+
+        // The first line has a field named like the derived tag, which is
+        // skipped for that line only instead of failing the write.
+        let lp = r#"cpu region=1i 1
+        mem val=2i 2
+        "#;
+
+        let mut converter = LinesConverter::new(5);
+        converter.set_tag_deriver(Arc::new(Region));
+        converter.write_lp(lp).unwrap();
+        let (batches, _) = converter.finish().unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+--------+--------------------------------+",
+                "| region | time                           |",
+                "+--------+--------------------------------+",
+                "| 1      | 1970-01-01T00:00:00.000000001Z |",
+                "+--------+--------------------------------+",
+            ],
+            &[batches["cpu"].to_arrow(Projection::All).unwrap()]
+        );
+        assert_batches_eq!(
+            &[
+                "+--------+--------------------------------+-----+",
+                "| region | time                           | val |",
+                "+--------+--------------------------------+-----+",
+                "| mem    | 1970-01-01T00:00:00.000000002Z | 2   |",
+                "+--------+--------------------------------+-----+",
+            ],
+            &[batches["mem"].to_arrow(Projection::All).unwrap()]
+        );
+    }
+
     // https://github.com/influxdata/influxdb_iox/issues/4326
     mod issue4326 {
         use super::*;
@@ -0,0 +1,33 @@
+//! Credential providers for the S3 object store.
+//!
+//! The S3 object store already loads and refreshes credentials from the
+//! environment, an EKS web identity token (IRSA) or the EC2 instance profile
+//! when no static credentials are configured. This crate adds an
+//! [`AssumeRoleCredentialProvider`], which assumes a (possibly cross-account)
+//! IAM role using STS `AssumeRole`.
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![allow(clippy::clone_on_ref_ptr)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+mod assume_role;
+mod sign;
+
+pub use assume_role::{
+    AssumeRoleConfig, AssumeRoleCredentialProvider, Error, SourceCredentials,
+    DEFAULT_METADATA_ENDPOINT,
+};
@@ -0,0 +1,365 @@
+//! Format of encrypted data.
+//!
+//! ```text
+//! +-------+---------+----------+--------+------+---------+-----+---------+
+//! | magic | version | reserved | key ID | salt | block 0 | ... | block N |
+//! +-------+---------+----------+--------+------+---------+-----+---------+
+//!     4        1         3         4       32
+//! ```
+//!
+//! The plaintext is split into blocks of [`BLOCK_SIZE`] bytes (the last one may be shorter or even empty), which are
+//! sealed individually with AES-256-GCM, so that byte ranges can be decrypted without reading the whole object. The
+//! block key is derived from the master key identified by the key ID and the random per-object salt using
+//! HKDF-SHA256, the nonce is the block index. Whether a block is the last one is authenticated as well, so truncated
+//! data is detected.
+
+use std::ops::Range;
+
+use ring::{
+    aead, hkdf,
+    rand::{SecureRandom, SystemRandom},
+};
+use snafu::{ensure, OptionExt, Snafu};
+
+use crate::keys::{EncryptionKey, KeyId, KeyProvider};
+
+const MAGIC: &[u8; 4] = b"IOXE";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 32;
+const TAG_LEN: usize = 16;
+const HKDF_INFO: &[u8] = b"iox block key";
+
+/// Length of the header preceding the encrypted blocks.
+pub const HEADER_LEN: usize = 12 + SALT_LEN;
+
+/// Number of plaintext bytes per encrypted block.
+pub const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Number of bytes per encrypted block (except the last one).
+const ENCRYPTED_BLOCK_SIZE: usize = BLOCK_SIZE + TAG_LEN;
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum FormatError {
+    #[snafu(display("Data is not encrypted"))]
+    NotEncrypted,
+
+    #[snafu(display("Unknown encryption key ID {}", key_id))]
+    UnknownKey { key_id: KeyId },
+
+    #[snafu(display("Invalid encrypted data length {}", len))]
+    InvalidLength { len: usize },
+
+    #[snafu(display("Cannot decrypt data, it was modified or encrypted with a different key"))]
+    Decrypt,
+
+    #[snafu(display("Cannot generate random salt"))]
+    Random,
+}
+
+/// Header of encrypted data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Header {
+    key_id: KeyId,
+    salt: [u8; SALT_LEN],
+}
+
+impl Header {
+    /// Parse header from the start of `data`, returning [`None`] if `data` is not encrypted.
+    pub(crate) fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < HEADER_LEN || &data[..4] != MAGIC || data[4] != VERSION {
+            return None;
+        }
+
+        let key_id = KeyId::from_be_bytes(data[8..12].try_into().expect("4 bytes"));
+        let salt = data[12..HEADER_LEN].try_into().expect("salt length");
+        Some(Self { key_id, salt })
+    }
+
+    fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut out = [0; HEADER_LEN];
+        out[..4].copy_from_slice(MAGIC);
+        out[4] = VERSION;
+        out[8..12].copy_from_slice(&self.key_id.to_be_bytes());
+        out[12..].copy_from_slice(&self.salt);
+        out
+    }
+
+    /// Derive the key of the blocks following this header.
+    pub(crate) fn block_key(&self, keys: &dyn KeyProvider) -> Result<BlockKey, FormatError> {
+        let key = keys.key(self.key_id).context(UnknownKeySnafu {
+            key_id: self.key_id,
+        })?;
+        Ok(BlockKey::derive(&key, &self.salt))
+    }
+}
+
+/// Key used to seal and open the blocks of one object.
+pub(crate) struct BlockKey(aead::LessSafeKey);
+
+impl BlockKey {
+    fn derive(key: &EncryptionKey, salt: &[u8; SALT_LEN]) -> Self {
+        let okm = hkdf::Salt::new(hkdf::HKDF_SHA256, salt)
+            .extract(key.as_bytes())
+            .expand(&[HKDF_INFO], &aead::AES_256_GCM)
+            .expect("valid key length");
+        Self(aead::LessSafeKey::new(aead::UnboundKey::from(okm)))
+    }
+
+    fn nonce(index: usize) -> aead::Nonce {
+        let mut nonce = [0; aead::NONCE_LEN];
+        nonce[4..].copy_from_slice(&(index as u64).to_be_bytes());
+        aead::Nonce::assume_unique_for_key(nonce)
+    }
+
+    fn seal(&self, index: usize, last: bool, block: &mut Vec<u8>) {
+        self.0
+            .seal_in_place_append_tag(Self::nonce(index), aead::Aad::from([last as u8]), block)
+            .expect("block fits into AES-GCM limits");
+    }
+
+    fn open<'a>(
+        &self,
+        index: usize,
+        last: bool,
+        block: &'a mut [u8],
+    ) -> Result<&'a [u8], FormatError> {
+        self.0
+            .open_in_place(Self::nonce(index), aead::Aad::from([last as u8]), block)
+            .map(|plaintext| &*plaintext)
+            .map_err(|_| FormatError::Decrypt)
+    }
+
+    /// Decrypt the consecutive encrypted blocks in `data`, starting with block `first_block` of an object with
+    /// `total_blocks` blocks.
+    pub(crate) fn decrypt_blocks(
+        &self,
+        first_block: usize,
+        total_blocks: usize,
+        data: &[u8],
+    ) -> Result<Vec<u8>, FormatError> {
+        let mut out = Vec::with_capacity(data.len());
+        for (offset, block) in data.chunks(ENCRYPTED_BLOCK_SIZE).enumerate() {
+            let index = first_block + offset;
+            ensure!(
+                index < total_blocks && block.len() >= TAG_LEN,
+                InvalidLengthSnafu { len: data.len() }
+            );
+
+            let mut block = block.to_vec();
+            out.extend_from_slice(self.open(index, index + 1 == total_blocks, &mut block)?);
+        }
+        Ok(out)
+    }
+}
+
+fn block_count(plaintext_len: usize) -> usize {
+    ((plaintext_len + BLOCK_SIZE - 1) / BLOCK_SIZE).max(1)
+}
+
+/// Number of blocks of encrypted data with the given length, including the header.
+pub(crate) fn encrypted_block_count(encrypted_len: usize) -> Result<usize, FormatError> {
+    let invalid = || FormatError::InvalidLength { len: encrypted_len };
+
+    let data_len = encrypted_len.checked_sub(HEADER_LEN).ok_or_else(invalid)?;
+    let last_block_len = data_len % ENCRYPTED_BLOCK_SIZE;
+    if data_len == 0 || (last_block_len != 0 && last_block_len < TAG_LEN) {
+        return Err(invalid());
+    }
+    Ok((data_len + ENCRYPTED_BLOCK_SIZE - 1) / ENCRYPTED_BLOCK_SIZE)
+}
+
+/// Length of the encrypted representation of `plaintext_len` bytes, including the header.
+pub fn encrypted_len(plaintext_len: usize) -> usize {
+    HEADER_LEN + plaintext_len + TAG_LEN * block_count(plaintext_len)
+}
+
+/// Length of the plaintext of encrypted data with the given length, including the header.
+pub fn plaintext_len(encrypted_len: usize) -> Result<usize, FormatError> {
+    let blocks = encrypted_block_count(encrypted_len)?;
+    Ok(encrypted_len - HEADER_LEN - TAG_LEN * blocks)
+}
+
+/// Blocks, and their byte range within encrypted data of length `encrypted_len`, containing the non-empty `range` of
+/// the plaintext.
+pub(crate) fn block_range(
+    range: &Range<usize>,
+    encrypted_len: usize,
+) -> (Range<usize>, Range<usize>) {
+    debug_assert!(!range.is_empty());
+
+    let first = range.start / BLOCK_SIZE;
+    let last = (range.end - 1) / BLOCK_SIZE;
+    let bytes = HEADER_LEN + first * ENCRYPTED_BLOCK_SIZE
+        ..(HEADER_LEN + (last + 1) * ENCRYPTED_BLOCK_SIZE).min(encrypted_len);
+    (first..last + 1, bytes)
+}
+
+/// Returns true if `data` starts with the header of encrypted data.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    Header::parse(data).is_some()
+}
+
+/// Encrypt `plaintext` with the current key of `keys`.
+pub fn encrypt(keys: &dyn KeyProvider, plaintext: &[u8]) -> Result<Vec<u8>, FormatError> {
+    let (key_id, key) = keys.current_key();
+    let mut salt = [0; SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| FormatError::Random)?;
+
+    let header = Header { key_id, salt };
+    let block_key = BlockKey::derive(&key, &salt);
+
+    let mut out = Vec::with_capacity(encrypted_len(plaintext.len()));
+    out.extend_from_slice(&header.encode());
+
+    let total_blocks = block_count(plaintext.len());
+    for index in 0..total_blocks {
+        let start = index * BLOCK_SIZE;
+        let end = (start + BLOCK_SIZE).min(plaintext.len());
+
+        let mut block = Vec::with_capacity(end - start + TAG_LEN);
+        block.extend_from_slice(&plaintext[start..end]);
+        block_key.seal(index, index + 1 == total_blocks, &mut block);
+        out.extend_from_slice(&block);
+    }
+
+    Ok(out)
+}
+
+/// Decrypt `data` encrypted with one of the keys of `keys`.
+pub fn decrypt(keys: &dyn KeyProvider, data: &[u8]) -> Result<Vec<u8>, FormatError> {
+    let header = Header::parse(data).context(NotEncryptedSnafu)?;
+    let total_blocks = encrypted_block_count(data.len())?;
+    header
+        .block_key(keys)?
+        .decrypt_blocks(0, total_blocks, &data[HEADER_LEN..])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keys::StaticKeyProvider;
+
+    use super::*;
+
+    fn keys() -> StaticKeyProvider {
+        StaticKeyProvider::new((1, EncryptionKey::new([1; 32])), [])
+    }
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let keys = keys();
+        for len in [
+            0,
+            1,
+            BLOCK_SIZE - 1,
+            BLOCK_SIZE,
+            BLOCK_SIZE + 1,
+            3 * BLOCK_SIZE + 5,
+        ] {
+            let plaintext = data(len);
+            let encrypted = encrypt(&keys, &plaintext).unwrap();
+
+            assert!(is_encrypted(&encrypted));
+            assert_eq!(encrypted.len(), encrypted_len(len));
+            assert_eq!(plaintext_len(encrypted.len()).unwrap(), len);
+            assert_eq!(decrypt(&keys, &encrypted).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_salt_is_random() {
+        let keys = keys();
+        let a = encrypt(&keys, b"bananas").unwrap();
+        let b = encrypt(&keys, b"bananas").unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_block_range() {
+        let keys = keys();
+        let plaintext = data(3 * BLOCK_SIZE + 5);
+        let encrypted = encrypt(&keys, &plaintext).unwrap();
+        let header = Header::parse(&encrypted).unwrap();
+        let total_blocks = encrypted_block_count(encrypted.len()).unwrap();
+        assert_eq!(total_blocks, 4);
+
+        for range in [
+            0..1,
+            BLOCK_SIZE - 1..BLOCK_SIZE + 1,
+            BLOCK_SIZE..2 * BLOCK_SIZE,
+            2 * BLOCK_SIZE + 7..3 * BLOCK_SIZE + 5,
+        ] {
+            let (blocks, bytes) = block_range(&range, encrypted.len());
+            let decrypted = header
+                .block_key(&keys)
+                .unwrap()
+                .decrypt_blocks(blocks.start, total_blocks, &encrypted[bytes])
+                .unwrap();
+            let offset = blocks.start * BLOCK_SIZE;
+            assert_eq!(
+                &decrypted[range.start - offset..range.end - offset],
+                &plaintext[range]
+            );
+        }
+    }
+
+    #[test]
+    fn test_not_encrypted() {
+        assert!(!is_encrypted(b"PAR1"));
+        assert!(matches!(
+            decrypt(&keys(), &data(100)),
+            Err(FormatError::NotEncrypted)
+        ));
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        let encrypted = encrypt(&keys(), b"bananas").unwrap();
+        let other = StaticKeyProvider::new((2, EncryptionKey::new([2; 32])), []);
+        assert!(matches!(
+            decrypt(&other, &encrypted),
+            Err(FormatError::UnknownKey { key_id: 1 })
+        ));
+
+        // same ID, different key
+        let other = StaticKeyProvider::new((1, EncryptionKey::new([2; 32])), []);
+        assert!(matches!(
+            decrypt(&other, &encrypted),
+            Err(FormatError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let keys = keys();
+        let plaintext = data(2 * BLOCK_SIZE);
+        let encrypted = encrypt(&keys, &plaintext).unwrap();
+
+        let mut modified = encrypted.clone();
+        modified[HEADER_LEN + 10] ^= 1;
+        assert!(matches!(
+            decrypt(&keys, &modified),
+            Err(FormatError::Decrypt)
+        ));
+
+        // drop the last block
+        let truncated = &encrypted[..HEADER_LEN + ENCRYPTED_BLOCK_SIZE];
+        assert!(matches!(
+            decrypt(&keys, truncated),
+            Err(FormatError::Decrypt)
+        ));
+
+        // cut within a tag
+        let truncated = &encrypted[..HEADER_LEN + ENCRYPTED_BLOCK_SIZE + 3];
+        assert!(matches!(
+            decrypt(&keys, truncated),
+            Err(FormatError::InvalidLength { .. })
+        ));
+    }
+}
@@ -0,0 +1,226 @@
+//! Encryption keys and their providers.
+
+use std::{collections::BTreeMap, fmt::Debug, path::PathBuf};
+
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+/// Identifier of an [`EncryptionKey`], stored alongside the data encrypted with it.
+pub type KeyId = u32;
+
+/// Length of an [`EncryptionKey`] in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// A 256 bit master key.
+///
+/// The key is never used to encrypt data directly. Instead, a key is derived from it for every encrypted object.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; KEY_LEN]);
+
+impl EncryptionKey {
+    /// Create key from its raw bytes.
+    pub fn new(key: [u8; KEY_LEN]) -> Self {
+        Self(key)
+    }
+
+    /// Parse key from its hex representation.
+    pub fn from_hex(s: &str) -> Option<Self> {
+        let mut key = [0; KEY_LEN];
+        hex::decode_to_slice(s.trim(), &mut key).ok()?;
+        Some(Self(key))
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // never log the key material
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Source of the master keys used to encrypt and decrypt data.
+///
+/// This is the integration point for key management systems (KMS). Implementations are called for every encrypted or
+/// decrypted object, so they are expected to cache keys fetched from remote systems.
+///
+/// Keys are rotated by changing the [current key](Self::current_key). Keys that were previously current MUST stay
+/// available through [`key`](Self::key) for as long as data encrypted with them exists.
+pub trait KeyProvider: Debug + Send + Sync {
+    /// The key new data is encrypted with.
+    fn current_key(&self) -> (KeyId, EncryptionKey);
+
+    /// Look up the key with the given ID, to decrypt data encrypted with it.
+    fn key(&self, id: KeyId) -> Option<EncryptionKey>;
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum KeyError {
+    #[snafu(display("Cannot read encryption key file {:?}: {}", path, source))]
+    ReadKeyFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Invalid encryption key file, line {}: {}", line, reason))]
+    InvalidKeyLine { line: usize, reason: &'static str },
+
+    #[snafu(display("Duplicate encryption key ID {}", id))]
+    DuplicateKey { id: KeyId },
+
+    #[snafu(display("No encryption keys specified"))]
+    NoKeys,
+}
+
+/// [`KeyProvider`] with a fixed set of keys.
+#[derive(Debug, Clone)]
+pub struct StaticKeyProvider {
+    keys: BTreeMap<KeyId, EncryptionKey>,
+    current: KeyId,
+}
+
+impl StaticKeyProvider {
+    /// Create a provider that encrypts data with the key `current` and can decrypt data encrypted with any of the
+    /// given keys.
+    pub fn new(
+        current: (KeyId, EncryptionKey),
+        previous: impl IntoIterator<Item = (KeyId, EncryptionKey)>,
+    ) -> Self {
+        let mut keys: BTreeMap<_, _> = previous.into_iter().collect();
+        keys.insert(current.0, current.1);
+        Self {
+            keys,
+            current: current.0,
+        }
+    }
+
+    /// Read keys from a file.
+    ///
+    /// The file contains one `<key ID>=<hex encoded 256 bit key>` entry per line. The key in the last entry is
+    /// used to encrypt new data, the others are used to decrypt data written before the key was rotated. Empty lines
+    /// and lines starting with `#` are ignored.
+    pub fn from_file(path: impl Into<PathBuf>) -> Result<Self, KeyError> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path).context(ReadKeyFileSnafu { path })?;
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Result<Self, KeyError> {
+        let mut keys = BTreeMap::new();
+        let mut current = None;
+
+        for (idx, line) in content.lines().enumerate() {
+            let line_no = idx + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (id, key) = line.split_once('=').context(InvalidKeyLineSnafu {
+                line: line_no,
+                reason: "expected <key ID>=<key>",
+            })?;
+            let id = id
+                .trim()
+                .parse::<KeyId>()
+                .ok()
+                .context(InvalidKeyLineSnafu {
+                    line: line_no,
+                    reason: "key ID must be an unsigned 32 bit integer",
+                })?;
+            let key = EncryptionKey::from_hex(key).context(InvalidKeyLineSnafu {
+                line: line_no,
+                reason: "key must be 64 hex characters",
+            })?;
+
+            ensure!(
+                keys.insert(id, key.clone()).is_none(),
+                DuplicateKeySnafu { id }
+            );
+            current = Some((id, key));
+        }
+
+        let current = current.context(NoKeysSnafu)?;
+        Ok(Self::new(current, keys))
+    }
+}
+
+impl KeyProvider for StaticKeyProvider {
+    fn current_key(&self) -> (KeyId, EncryptionKey) {
+        let key = self.keys.get(&self.current).expect("current key exists");
+        (self.current, key.clone())
+    }
+
+    fn key(&self, id: KeyId) -> Option<EncryptionKey> {
+        self.keys.get(&id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_1: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+    const KEY_2: &str = "0000000000000000000000000000000000000000000000000000000000000002";
+
+    #[test]
+    fn test_parse() {
+        let provider =
+            StaticKeyProvider::parse(&format!("# old key\n1={KEY_1}\n\n 7 = {KEY_2} \n")).unwrap();
+
+        assert_eq!(
+            provider.current_key(),
+            (7, EncryptionKey::from_hex(KEY_2).unwrap())
+        );
+        assert_eq!(provider.key(1), EncryptionKey::from_hex(KEY_1));
+        assert_eq!(provider.key(2), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            StaticKeyProvider::parse("# nothing\n"),
+            Err(KeyError::NoKeys)
+        ));
+        assert!(matches!(
+            StaticKeyProvider::parse(KEY_1),
+            Err(KeyError::InvalidKeyLine { line: 1, .. })
+        ));
+        assert!(matches!(
+            StaticKeyProvider::parse(&format!("1={KEY_1}\nx={KEY_2}")),
+            Err(KeyError::InvalidKeyLine { line: 2, .. })
+        ));
+        assert!(matches!(
+            StaticKeyProvider::parse("1=abcd"),
+            Err(KeyError::InvalidKeyLine { line: 1, .. })
+        ));
+        assert!(matches!(
+            StaticKeyProvider::parse(&format!("1={KEY_1}\n1={KEY_2}")),
+            Err(KeyError::DuplicateKey { id: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_from_file() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let path = dir.path().join("keys");
+
+        assert!(matches!(
+            StaticKeyProvider::from_file(&path),
+            Err(KeyError::ReadKeyFile { .. })
+        ));
+
+        std::fs::write(&path, format!("1={KEY_1}\n")).unwrap();
+        let provider = StaticKeyProvider::from_file(&path).unwrap();
+        assert_eq!(provider.current_key().0, 1);
+    }
+
+    #[test]
+    fn test_debug_hides_key() {
+        let key = EncryptionKey::from_hex(KEY_1).unwrap();
+        assert_eq!(format!("{key:?}"), "EncryptionKey(..)");
+    }
+}
@@ -0,0 +1,34 @@
+//! An [`ObjectStore`](object_store::ObjectStore) wrapper that encrypts data at rest.
+//!
+//! Objects are encrypted with AES-256-GCM in fixed size blocks, so that byte ranges (e.g. the footer and the row
+//! groups of a parquet file) can be read and authenticated without fetching the whole object. Every object uses its
+//! own key, derived from a master key supplied by a [`KeyProvider`] and a random salt stored in the object header,
+//! alongside the ID of the master key. Master keys can therefore be rotated without rewriting existing data.
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![allow(clippy::clone_on_ref_ptr)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+mod format;
+mod keys;
+mod store;
+
+pub use format::{
+    decrypt, encrypt, encrypted_len, is_encrypted, plaintext_len, FormatError, BLOCK_SIZE,
+    HEADER_LEN,
+};
+pub use keys::{EncryptionKey, KeyError, KeyId, KeyProvider, StaticKeyProvider, KEY_LEN};
+pub use store::EncryptedObjectStore;
@@ -0,0 +1,408 @@
+//! [`ObjectStore`] wrapper encrypting objects.
+
+use std::{fmt::Display, ops::Range, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{stream::BoxStream, StreamExt};
+use object_store::{
+    path::Path, DynObjectStore, Error, GetOptions, GetResult, GetResultPayload, ListResult,
+    MultipartId, ObjectMeta, ObjectStore, Result,
+};
+use tokio::io::AsyncWrite;
+
+use crate::{
+    format::{
+        block_range, decrypt, encrypt, encrypted_block_count, plaintext_len, FormatError, Header,
+        BLOCK_SIZE, HEADER_LEN,
+    },
+    keys::KeyProvider,
+};
+
+const STORE: &str = "EncryptedObjectStore";
+
+fn format_error(e: FormatError) -> Error {
+    Error::Generic {
+        store: STORE,
+        source: Box::new(e),
+    }
+}
+
+/// An [`ObjectStore`] wrapper that encrypts objects before they are written to the inner store and decrypts them when
+/// they are read.
+///
+/// All sizes and byte ranges refer to the plaintext, so the wrapper is transparent to its users. Objects that are not
+/// encrypted (e.g. because they were written before encryption was enabled) are returned as they are.
+///
+/// # Limitations
+///
+/// - Multipart uploads are not supported.
+/// - Listings report the size of the stored (encrypted) objects.
+/// - Range reads issue two requests to the inner store, one for the header and one for the data.
+#[derive(Debug)]
+pub struct EncryptedObjectStore {
+    inner: Arc<DynObjectStore>,
+    keys: Arc<dyn KeyProvider>,
+}
+
+impl EncryptedObjectStore {
+    /// Wrap `inner`, encrypting objects with the keys provided by `keys`.
+    pub fn new(inner: Arc<DynObjectStore>, keys: Arc<dyn KeyProvider>) -> Self {
+        Self { inner, keys }
+    }
+
+    /// Fetch the header and the stored size of the object at `location`.
+    async fn header(&self, location: &Path) -> Result<(Option<Header>, ObjectMeta)> {
+        let options = GetOptions {
+            range: Some(0..HEADER_LEN),
+            ..Default::default()
+        };
+        match self.inner.get_opts(location, options).await {
+            Ok(res) => {
+                let meta = res.meta.clone();
+                let data = res.bytes().await?;
+                Ok((Header::parse(&data), meta))
+            }
+            Err(e @ Error::NotFound { .. }) => Err(e),
+            Err(e) => {
+                // Stores report a range past the end of the object differently, so check whether the object is
+                // actually shorter than the header (and hence not encrypted) before treating it as such. Any other
+                // error (e.g. a timeout) must not cause the encrypted data to be returned as it is.
+                let meta = self.inner.head(location).await?;
+                if meta.size >= HEADER_LEN {
+                    return Err(e);
+                }
+                Ok((None, meta))
+            }
+        }
+    }
+}
+
+impl Display for EncryptedObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncryptedObjectStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for EncryptedObjectStore {
+    async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+        let encrypted = encrypt(self.keys.as_ref(), &bytes).map_err(format_error)?;
+        self.inner.put(location, encrypted.into()).await
+    }
+
+    async fn put_multipart(
+        &self,
+        _location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        Err(Error::NotImplemented)
+    }
+
+    async fn abort_multipart(&self, _location: &Path, _multipart_id: &MultipartId) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    async fn get_opts(&self, location: &Path, mut options: GetOptions) -> Result<GetResult> {
+        // ranges refer to the plaintext, so fetch the whole object
+        let range = options.range.take();
+        let res = self.inner.get_opts(location, options).await?;
+        let mut meta = res.meta.clone();
+        let data = res.bytes().await?;
+
+        let data = if Header::parse(&data).is_some() {
+            Bytes::from(decrypt(self.keys.as_ref(), &data).map_err(format_error)?)
+        } else {
+            data
+        };
+        meta.size = data.len();
+
+        let range = range.unwrap_or(0..data.len());
+        if range.end > data.len() || range.start > range.end {
+            return Err(Error::Generic {
+                store: STORE,
+                source: format!(
+                    "Invalid range {range:?} for object of length {}",
+                    data.len()
+                )
+                .into(),
+            });
+        }
+        let data = data.slice(range.clone());
+
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(
+                futures::stream::once(async move { Ok(data) }).boxed(),
+            ),
+            meta,
+            range,
+        })
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        if range.is_empty() {
+            return Ok(Bytes::new());
+        }
+
+        let (header, meta) = self.header(location).await?;
+        let Some(header) = header else {
+            return self.inner.get_range(location, range).await;
+        };
+
+        let total_blocks = encrypted_block_count(meta.size).map_err(format_error)?;
+        let (blocks, bytes) = block_range(&range, meta.size);
+        let data = self.inner.get_range(location, bytes).await?;
+        let plaintext = header
+            .block_key(self.keys.as_ref())
+            .and_then(|key| key.decrypt_blocks(blocks.start, total_blocks, &data))
+            .map_err(format_error)?;
+
+        let offset = blocks.start * BLOCK_SIZE;
+        let (start, end) = (range.start - offset, range.end - offset);
+        if end > plaintext.len() {
+            return Err(Error::Generic {
+                store: STORE,
+                source: format!("Range {range:?} out of bounds").into(),
+            });
+        }
+        Ok(Bytes::from(plaintext).slice(start..end))
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let (header, mut meta) = self.header(location).await?;
+        if header.is_some() {
+            meta.size = plaintext_len(meta.size).map_err(format_error)?;
+        }
+        Ok(meta)
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        self.inner.list(prefix).await
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use object_store::memory::InMemory;
+
+    use crate::keys::{EncryptionKey, StaticKeyProvider};
+
+    use super::*;
+
+    fn data(len: usize) -> Bytes {
+        (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>().into()
+    }
+
+    fn store(inner: &Arc<InMemory>, keys: StaticKeyProvider) -> EncryptedObjectStore {
+        EncryptedObjectStore::new(Arc::clone(inner) as _, Arc::new(keys))
+    }
+
+    #[tokio::test]
+    async fn test_roundtrip() {
+        let inner = Arc::new(InMemory::new());
+        let store = store(
+            &inner,
+            StaticKeyProvider::new((1, EncryptionKey::new([1; 32])), []),
+        );
+
+        let path = Path::from("ns/table/file.parquet");
+        let plaintext = data(2 * BLOCK_SIZE + 100);
+        store.put(&path, plaintext.clone()).await.unwrap();
+
+        // stored encrypted
+        let stored = inner.get(&path).await.unwrap().bytes().await.unwrap();
+        assert!(crate::is_encrypted(&stored));
+        assert_ne!(stored.len(), plaintext.len());
+
+        // read as plaintext
+        let got = store.get(&path).await.unwrap();
+        assert_eq!(got.meta.size, plaintext.len());
+        assert_eq!(got.bytes().await.unwrap(), plaintext);
+        assert_eq!(store.head(&path).await.unwrap().size, plaintext.len());
+
+        for range in [
+            0..8,
+            BLOCK_SIZE - 4..BLOCK_SIZE + 4,
+            plaintext.len() - 8..plaintext.len(),
+        ] {
+            assert_eq!(
+                store.get_range(&path, range.clone()).await.unwrap(),
+                plaintext.slice(range.clone())
+            );
+            assert_eq!(
+                store
+                    .get_opts(
+                        &path,
+                        GetOptions {
+                            range: Some(range.clone()),
+                            ..Default::default()
+                        }
+                    )
+                    .await
+                    .unwrap()
+                    .bytes()
+                    .await
+                    .unwrap(),
+                plaintext.slice(range)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_key_rotation() {
+        let inner = Arc::new(InMemory::new());
+        let key_1 = (1, EncryptionKey::new([1; 32]));
+        let key_2 = (2, EncryptionKey::new([2; 32]));
+
+        let old = Path::from("old");
+        store(&inner, StaticKeyProvider::new(key_1.clone(), []))
+            .put(&old, data(10))
+            .await
+            .unwrap();
+
+        let store = store(&inner, StaticKeyProvider::new(key_2, [key_1]));
+        let new = Path::from("new");
+        store.put(&new, data(20)).await.unwrap();
+
+        assert_eq!(store.get_range(&old, 0..10).await.unwrap(), data(10));
+        assert_eq!(store.get_range(&new, 0..20).await.unwrap(), data(20));
+    }
+
+    #[tokio::test]
+    async fn test_unencrypted_objects() {
+        let inner = Arc::new(InMemory::new());
+        let path = Path::from("plain");
+        let plaintext = data(1000);
+        inner.put(&path, plaintext.clone()).await.unwrap();
+
+        let store = store(
+            &inner,
+            StaticKeyProvider::new((1, EncryptionKey::new([1; 32])), []),
+        );
+        assert_eq!(store.head(&path).await.unwrap().size, 1000);
+        assert_eq!(
+            store.get(&path).await.unwrap().bytes().await.unwrap(),
+            plaintext
+        );
+        assert_eq!(
+            store.get_range(&path, 10..20).await.unwrap(),
+            plaintext.slice(10..20)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transient_header_error() {
+        let inner = Arc::new(FlakyStore {
+            inner: InMemory::new(),
+            failures: AtomicUsize::new(0),
+        });
+        let store = EncryptedObjectStore::new(
+            Arc::clone(&inner) as _,
+            Arc::new(StaticKeyProvider::new((1, EncryptionKey::new([1; 32])), [])),
+        );
+
+        let path = Path::from("ns/table/file.parquet");
+        let plaintext = data(1000);
+        store.put(&path, plaintext.clone()).await.unwrap();
+
+        // a failing header read fails the read instead of returning the ciphertext
+        inner.failures.store(1, Ordering::SeqCst);
+        let err = store.get_range(&path, 10..20).await.unwrap_err();
+        assert!(err.to_string().contains("transient"), "{err}");
+        inner.failures.store(1, Ordering::SeqCst);
+        store.head(&path).await.unwrap_err();
+
+        // and succeeds once the inner store recovered
+        assert_eq!(
+            store.get_range(&path, 10..20).await.unwrap(),
+            plaintext.slice(10..20)
+        );
+    }
+
+    /// A store failing the next `failures` ranged reads.
+    #[derive(Debug)]
+    struct FlakyStore {
+        inner: InMemory,
+        failures: AtomicUsize,
+    }
+
+    impl Display for FlakyStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "FlakyStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyStore {
+        async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+            self.inner.put(location, bytes).await
+        }
+
+        async fn put_multipart(
+            &self,
+            location: &Path,
+        ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+            self.inner.put_multipart(location).await
+        }
+
+        async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+            self.inner.abort_multipart(location, multipart_id).await
+        }
+
+        async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+            if options.range.is_some()
+                && self
+                    .failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok()
+            {
+                return Err(Error::Generic {
+                    store: "FlakyStore",
+                    source: "transient failure".into(),
+                });
+            }
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+            self.inner.head(location).await
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+            self.inner.list(prefix).await
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+}
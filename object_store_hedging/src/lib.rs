@@ -0,0 +1,354 @@
+//! An [`ObjectStore`] wrapper that hedges slow reads: if a read does not complete within a threshold, the same read
+//! is issued a second time and whichever request completes first is used.
+//!
+//! This cuts the tail latency of object store reads (e.g. the p99 spikes of S3) at the cost of a few duplicate
+//! requests. Only range reads are hedged, which is how parquet files are read during queries.
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![allow(clippy::clone_on_ref_ptr)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::{fmt::Display, future::Future, ops::Range, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{
+    future::{select, Either},
+    stream::BoxStream,
+};
+use metric::{Metric, U64Counter};
+use object_store::{
+    path::Path, DynObjectStore, GetOptions, GetResult, ListResult, MultipartId, ObjectMeta,
+    ObjectStore, Result,
+};
+use observability_deps::tracing::debug;
+use tokio::io::AsyncWrite;
+
+#[derive(Debug)]
+struct OpMetrics {
+    /// Reads that were hedged.
+    hedged: U64Counter,
+    /// Hedged reads that were served by the second request.
+    hedge_won: U64Counter,
+}
+
+#[derive(Debug)]
+struct Metrics {
+    get_range: OpMetrics,
+    get_ranges: OpMetrics,
+}
+
+impl Metrics {
+    fn new(registry: &metric::Registry) -> Self {
+        let hedged: Metric<U64Counter> = registry.register_metric(
+            "object_store_hedged_reads",
+            "number of object store reads that exceeded the hedging threshold and were issued a second time",
+        );
+        let hedge_won: Metric<U64Counter> = registry.register_metric(
+            "object_store_hedged_reads_won",
+            "number of hedged object store reads that were served by the second request",
+        );
+
+        let op_metrics = |op| OpMetrics {
+            hedged: hedged.recorder(&[("op", op)]),
+            hedge_won: hedge_won.recorder(&[("op", op)]),
+        };
+
+        Self {
+            get_range: op_metrics("get_range"),
+            get_ranges: op_metrics("get_ranges"),
+        }
+    }
+}
+
+/// An [`ObjectStore`] that hedges range reads taking longer than a threshold.
+#[derive(Debug)]
+pub struct HedgedObjectStore {
+    inner: Arc<DynObjectStore>,
+    threshold: Duration,
+    metrics: Metrics,
+}
+
+impl HedgedObjectStore {
+    /// Wrap `inner`, issuing a second request for range reads that did not complete within `threshold`.
+    pub fn new(
+        inner: Arc<DynObjectStore>,
+        threshold: Duration,
+        registry: &metric::Registry,
+    ) -> Self {
+        Self {
+            inner,
+            threshold,
+            metrics: Metrics::new(registry),
+        }
+    }
+
+    /// Run `request`, issuing it a second time if the first request did not complete within the threshold.
+    ///
+    /// The result of the request that completes first is used, unless it failed: then the other request decides.
+    async fn hedge<F, Fut, T>(&self, location: &Path, metrics: &OpMetrics, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut + Send,
+        Fut: Future<Output = Result<T>> + Send,
+        T: Send,
+    {
+        let mut original = Box::pin(request());
+        if let Ok(res) = tokio::time::timeout(self.threshold, &mut original).await {
+            return res;
+        }
+
+        debug!(%location, threshold=?self.threshold, "hedging slow object store read");
+        metrics.hedged.inc(1);
+        let hedge = Box::pin(request());
+
+        let (res, other, hedge_won) = match select(original, hedge).await {
+            Either::Left((res, hedge)) => (res, hedge, false),
+            Either::Right((res, original)) => (res, original, true),
+        };
+        match res {
+            Ok(v) => {
+                if hedge_won {
+                    metrics.hedge_won.inc(1);
+                }
+                Ok(v)
+            }
+            Err(e) => {
+                debug!(%location, %e, "hedged object store read failed, waiting for the other request");
+                other.await
+            }
+        }
+    }
+}
+
+impl Display for HedgedObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HedgedObjectStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for HedgedObjectStore {
+    async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+        self.inner.put(location, bytes).await
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        self.inner.put_multipart(location).await
+    }
+
+    async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+        self.inner.abort_multipart(location, multipart_id).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.hedge(location, &self.metrics.get_range, || {
+            self.inner.get_range(location, range.clone())
+        })
+        .await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
+        self.hedge(location, &self.metrics.get_ranges, || {
+            self.inner.get_ranges(location, ranges)
+        })
+        .await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        self.inner.list(prefix).await
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use metric::Attributes;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    /// A store whose first `slow_reads` range reads hang for a long time.
+    #[derive(Debug)]
+    struct SlowStore {
+        inner: InMemory,
+        slow_reads: usize,
+        reads: AtomicUsize,
+    }
+
+    impl SlowStore {
+        async fn maybe_hang(&self) {
+            if self.reads.fetch_add(1, Ordering::SeqCst) < self.slow_reads {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+        }
+    }
+
+    impl Display for SlowStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "SlowStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for SlowStore {
+        async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+            self.inner.put(location, bytes).await
+        }
+
+        async fn put_multipart(
+            &self,
+            location: &Path,
+        ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+            self.inner.put_multipart(location).await
+        }
+
+        async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+            self.inner.abort_multipart(location, multipart_id).await
+        }
+
+        async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+            self.maybe_hang().await;
+            self.inner.get_range(location, range).await
+        }
+
+        async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+            self.inner.head(location).await
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+            self.inner.list(prefix).await
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    struct TestStore {
+        slow: Arc<SlowStore>,
+        store: HedgedObjectStore,
+        metrics: metric::Registry,
+    }
+
+    impl TestStore {
+        async fn new(slow_reads: usize) -> Self {
+            let slow = Arc::new(SlowStore {
+                inner: InMemory::new(),
+                slow_reads,
+                reads: AtomicUsize::new(0),
+            });
+            slow.put(&path(), Bytes::from_static(b"parquet"))
+                .await
+                .unwrap();
+
+            let metrics = metric::Registry::default();
+            let store =
+                HedgedObjectStore::new(Arc::clone(&slow) as _, Duration::from_millis(10), &metrics);
+            Self {
+                slow,
+                store,
+                metrics,
+            }
+        }
+
+        fn reads(&self) -> usize {
+            self.slow.reads.load(Ordering::SeqCst)
+        }
+
+        fn counter(&self, name: &'static str) -> u64 {
+            self.metrics
+                .get_instrument::<Metric<U64Counter>>(name)
+                .unwrap()
+                .get_observer(&Attributes::from(&[("op", "get_range")]))
+                .unwrap()
+                .fetch()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fast_read_not_hedged() {
+        let t = TestStore::new(0).await;
+
+        assert_eq!(
+            t.store.get_range(&path(), 1..4).await.unwrap(),
+            Bytes::from_static(b"arq")
+        );
+        assert_eq!(t.reads(), 1);
+        assert_eq!(t.counter("object_store_hedged_reads"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_slow_read_hedged() {
+        let t = TestStore::new(1).await;
+
+        let res = tokio::time::timeout(Duration::from_secs(10), t.store.get_range(&path(), 1..4))
+            .await
+            .expect("hedged read did not complete");
+        assert_eq!(res.unwrap(), Bytes::from_static(b"arq"));
+        assert_eq!(t.reads(), 2);
+        assert_eq!(t.counter("object_store_hedged_reads"), 1);
+        assert_eq!(t.counter("object_store_hedged_reads_won"), 1);
+        assert_eq!(t.store.to_string(), "HedgedObjectStore(SlowStore)");
+    }
+
+    fn path() -> Path {
+        Path::from("ns/table/partition/file.parquet")
+    }
+}
@@ -0,0 +1,660 @@
+//! An [`ObjectStore`] wrapper that asynchronously replicates writes to a secondary object store, e.g. a bucket in
+//! another region.
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![allow(clippy::clone_on_ref_ptr)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    ops::Range,
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use iox_time::{Time, TimeProvider};
+use metric::{DurationHistogram, Metric, U64Counter, U64Gauge};
+use object_store::{
+    path::Path, DynObjectStore, Error, GetOptions, GetResult, ListResult, MultipartId, ObjectMeta,
+    ObjectStore, Result,
+};
+use observability_deps::tracing::{debug, warn};
+use tokio::{
+    io::AsyncWrite,
+    sync::mpsc::{self, error::TrySendError},
+};
+
+/// A write to the primary store that is yet to be applied to the replica.
+#[derive(Debug)]
+struct Task {
+    op: Op,
+    location: Path,
+    /// When the write to the primary store completed.
+    written_at: Time,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    /// Copy the object from the primary store to the replica.
+    Put,
+    /// Delete the object from the replica.
+    Delete,
+}
+
+#[derive(Debug)]
+struct OpMetrics {
+    lag: DurationHistogram,
+    errors: U64Counter,
+    /// Writes dropped because the queue of their worker was full.
+    dropped_queue_full: U64Counter,
+    /// Writes given up on once their retry deadline passed.
+    dropped_deadline: U64Counter,
+}
+
+#[derive(Debug)]
+struct Metrics {
+    queue_length: U64Gauge,
+    put: OpMetrics,
+    delete: OpMetrics,
+}
+
+impl Metrics {
+    fn new(registry: &metric::Registry) -> Self {
+        let queue_length = registry
+            .register_metric::<U64Gauge>(
+                "object_store_replication_queue_length",
+                "number of writes to the primary object store not yet applied to the replica",
+            )
+            .recorder(&[]);
+        let lag: Metric<DurationHistogram> = registry.register_metric(
+            "object_store_replication_lag",
+            "time between a write to the primary object store and its replication to the replica",
+        );
+        let errors: Metric<U64Counter> = registry.register_metric(
+            "object_store_replication_errors",
+            "number of failed (and retried) attempts to replicate a write to the replica",
+        );
+        let dropped: Metric<U64Counter> = registry.register_metric(
+            "object_store_replication_dropped",
+            "number of writes to the primary object store that were never applied to the replica",
+        );
+
+        let op_metrics = |op| OpMetrics {
+            lag: lag.recorder(&[("op", op)]),
+            errors: errors.recorder(&[("op", op)]),
+            dropped_queue_full: dropped.recorder(&[("op", op), ("reason", "queue_full")]),
+            dropped_deadline: dropped.recorder(&[("op", op), ("reason", "deadline")]),
+        };
+
+        Self {
+            queue_length,
+            put: op_metrics("put"),
+            delete: op_metrics("delete"),
+        }
+    }
+
+    fn op(&self, op: Op) -> &OpMetrics {
+        match op {
+            Op::Put => &self.put,
+            Op::Delete => &self.delete,
+        }
+    }
+}
+
+/// State shared between the store and its replication workers.
+#[derive(Debug)]
+struct Shared {
+    primary: Arc<DynObjectStore>,
+    replica: Arc<DynObjectStore>,
+    time_provider: Arc<dyn TimeProvider>,
+    metrics: Metrics,
+    backoff_config: BackoffConfig,
+}
+
+impl Shared {
+    /// Apply `task` to the replica, retrying until it succeeds or the retry deadline passes.
+    async fn replicate(&self, task: Task) {
+        let metrics = self.metrics.op(task.op);
+
+        let res = Backoff::new(&self.backoff_config)
+            .retry_all_errors("replicate object", || async {
+                let res = match task.op {
+                    Op::Put => self.copy_to_replica(&task.location).await,
+                    Op::Delete => self.delete_from_replica(&task.location).await,
+                };
+                if res.is_err() {
+                    metrics.errors.inc(1);
+                }
+                res
+            })
+            .await;
+
+        match res {
+            Ok(()) => {
+                debug!(location=%task.location, op=?task.op, "replicated object");
+                if let Some(lag) = self
+                    .time_provider
+                    .now()
+                    .checked_duration_since(task.written_at)
+                {
+                    metrics.lag.record(lag);
+                }
+            }
+            Err(e) => {
+                metrics.dropped_deadline.inc(1);
+                warn!(location=%task.location, op=?task.op, %e, "giving up replicating object");
+            }
+        }
+        self.metrics.queue_length.dec(1);
+    }
+
+    async fn copy_to_replica(&self, location: &Path) -> Result<()> {
+        let data = match self.primary.get(location).await {
+            Ok(res) => res.bytes().await?,
+            // deleted since it was written, the delete is replicated on its own
+            Err(Error::NotFound { .. }) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        self.replica.put(location, data).await
+    }
+
+    async fn delete_from_replica(&self, location: &Path) -> Result<()> {
+        match self.replica.delete(location).await {
+            Ok(()) | Err(Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// An [`ObjectStore`] wrapper that serves all requests from the primary store and replicates successful writes
+/// (puts, copies and deletes) to a replica in the background.
+///
+/// Writes are queued in memory and applied by `concurrency` worker tasks. Writes to the same location are always
+/// applied by the same worker, so they reach the replica in the order they were made to the primary store. Failed
+/// replications are retried with a backoff until they succeed or `retry_deadline` passes.
+///
+/// Writes to the primary store never wait for the replication: at most `max_queue_length` writes are queued, and
+/// writes exceeding that limit are not replicated.
+///
+/// The following metrics are reported:
+///
+/// - `object_store_replication_queue_length`: writes not yet applied to the replica.
+/// - `object_store_replication_lag`: time between a write to the primary store and its replication.
+/// - `object_store_replication_errors`: failed replication attempts.
+/// - `object_store_replication_dropped`: writes never replicated, because the queue was full (`reason=queue_full`)
+///   or the retry deadline passed (`reason=deadline`).
+///
+/// # Limitations
+///
+/// - Queued writes are lost if the process exits before they are replicated.
+/// - Dropped writes are only logged and counted; objects missing from the replica must be copied by other means.
+/// - Multipart uploads are not replicated.
+/// - Objects are buffered in memory while they are copied to the replica.
+#[derive(Debug)]
+pub struct ReplicatedObjectStore {
+    shared: Arc<Shared>,
+    workers: Vec<mpsc::Sender<Task>>,
+}
+
+impl ReplicatedObjectStore {
+    /// Wrap `primary`, replicating writes to `replica`.
+    ///
+    /// # Panics
+    ///
+    /// Must be called from within a tokio runtime, which the replication workers are spawned on.
+    pub fn new(
+        primary: Arc<DynObjectStore>,
+        replica: Arc<DynObjectStore>,
+        concurrency: NonZeroUsize,
+        max_queue_length: NonZeroUsize,
+        retry_deadline: Duration,
+        time_provider: Arc<dyn TimeProvider>,
+        registry: &metric::Registry,
+    ) -> Self {
+        let shared = Arc::new(Shared {
+            primary,
+            replica,
+            time_provider,
+            metrics: Metrics::new(registry),
+            backoff_config: BackoffConfig {
+                deadline: Some(retry_deadline),
+                ..Default::default()
+            },
+        });
+
+        // the queue is split evenly between the workers
+        let worker_queue_length =
+            (max_queue_length.get() + concurrency.get() - 1) / concurrency.get();
+        let workers = (0..concurrency.get())
+            .map(|_| {
+                let (tx, mut rx) = mpsc::channel(worker_queue_length);
+                let shared = Arc::clone(&shared);
+                // the worker exits once the store is dropped and its queue is drained
+                tokio::spawn(async move {
+                    while let Some(task) = rx.recv().await {
+                        shared.replicate(task).await;
+                    }
+                });
+                tx
+            })
+            .collect();
+
+        Self { shared, workers }
+    }
+
+    fn enqueue(&self, op: Op, location: &Path) {
+        let mut hasher = DefaultHasher::new();
+        location.hash(&mut hasher);
+        let worker = &self.workers[hasher.finish() as usize % self.workers.len()];
+
+        let task = Task {
+            op,
+            location: location.clone(),
+            written_at: self.shared.time_provider.now(),
+        };
+        self.shared.metrics.queue_length.inc(1);
+        match worker.try_send(task) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.shared.metrics.queue_length.dec(1);
+                self.shared.metrics.op(op).dropped_queue_full.inc(1);
+                warn!(%location, ?op, "replication queue full, not replicating object");
+            }
+            Err(TrySendError::Closed(_)) => {
+                // only happens if the worker panicked
+                self.shared.metrics.queue_length.dec(1);
+                warn!(%location, ?op, "replication worker gone, not replicating object");
+            }
+        }
+    }
+}
+
+impl Display for ReplicatedObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ReplicatedObjectStore({}, {})",
+            self.shared.primary, self.shared.replica
+        )
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ReplicatedObjectStore {
+    async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+        self.shared.primary.put(location, bytes).await?;
+        self.enqueue(Op::Put, location);
+        Ok(())
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        self.shared.primary.put_multipart(location).await
+    }
+
+    async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+        self.shared
+            .primary
+            .abort_multipart(location, multipart_id)
+            .await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.shared.primary.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.shared.primary.get_range(location, range).await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
+        self.shared.primary.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.shared.primary.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.shared.primary.delete(location).await?;
+        self.enqueue(Op::Delete, location);
+        Ok(())
+    }
+
+    async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        self.shared.primary.list(prefix).await
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.shared.primary.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.shared.primary.copy(from, to).await?;
+        self.enqueue(Op::Put, to);
+        Ok(())
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.shared.primary.copy_if_not_exists(from, to).await?;
+        self.enqueue(Op::Put, to);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use iox_time::SystemProvider;
+    use metric::Attributes;
+    use object_store::{
+        memory::InMemory,
+        throttle::{ThrottleConfig, ThrottledStore},
+    };
+
+    use super::*;
+
+    struct TestStore {
+        primary: Arc<InMemory>,
+        replica: Arc<InMemory>,
+        store: ReplicatedObjectStore,
+        metrics: metric::Registry,
+    }
+
+    impl TestStore {
+        fn new() -> Self {
+            let primary = Arc::new(InMemory::new());
+            let replica = Arc::new(InMemory::new());
+            let metrics = metric::Registry::default();
+            let store = ReplicatedObjectStore::new(
+                Arc::clone(&primary) as _,
+                Arc::clone(&replica) as _,
+                NonZeroUsize::new(2).unwrap(),
+                NonZeroUsize::new(100).unwrap(),
+                Duration::from_secs(60),
+                Arc::new(SystemProvider::new()),
+                &metrics,
+            );
+            Self {
+                primary,
+                replica,
+                store,
+                metrics,
+            }
+        }
+
+        /// Wait until all queued writes were replicated.
+        async fn wait_for_replication(&self) {
+            tokio::time::timeout(Duration::from_secs(10), async {
+                while self.queue_length() > 0 {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("replication timed out");
+        }
+
+        fn queue_length(&self) -> u64 {
+            self.metrics
+                .get_instrument::<Metric<U64Gauge>>("object_store_replication_queue_length")
+                .unwrap()
+                .get_observer(&Attributes::from(&[]))
+                .unwrap()
+                .fetch()
+        }
+
+        fn lag_samples(&self, op: &'static str) -> u64 {
+            self.metrics
+                .get_instrument::<Metric<DurationHistogram>>("object_store_replication_lag")
+                .unwrap()
+                .get_observer(&Attributes::from(&[("op", op)]))
+                .unwrap()
+                .fetch()
+                .sample_count()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replicate_put_and_delete() {
+        let t = TestStore::new();
+        let path = Path::from("ns/table/partition/file.parquet");
+        let data = Bytes::from_static(b"parquet");
+
+        t.store.put(&path, data.clone()).await.unwrap();
+        assert_eq!(
+            t.primary.get(&path).await.unwrap().bytes().await.unwrap(),
+            data
+        );
+
+        t.wait_for_replication().await;
+        assert_eq!(
+            t.replica.get(&path).await.unwrap().bytes().await.unwrap(),
+            data
+        );
+        assert_eq!(t.lag_samples("put"), 1);
+
+        t.store.delete(&path).await.unwrap();
+        t.wait_for_replication().await;
+        assert!(matches!(
+            t.replica.head(&path).await,
+            Err(Error::NotFound { .. })
+        ));
+        assert_eq!(t.lag_samples("delete"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replicate_copy() {
+        let t = TestStore::new();
+        let from = Path::from("from");
+        let to = Path::from("to");
+
+        t.store
+            .put(&from, Bytes::from_static(b"data"))
+            .await
+            .unwrap();
+        t.store.copy(&from, &to).await.unwrap();
+        t.wait_for_replication().await;
+
+        assert_eq!(
+            t.replica.get(&to).await.unwrap().bytes().await.unwrap(),
+            Bytes::from_static(b"data")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deleted_before_replication() {
+        let t = TestStore::new();
+        let path = Path::from("short-lived");
+
+        // the object is gone from the primary by the time it is copied
+        t.store
+            .put(&path, Bytes::from_static(b"data"))
+            .await
+            .unwrap();
+        t.primary.delete(&path).await.unwrap();
+        t.wait_for_replication().await;
+
+        assert!(matches!(
+            t.replica.head(&path).await,
+            Err(Error::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reads_served_by_primary() {
+        let t = TestStore::new();
+        let path = Path::from("only-in-primary");
+        t.primary
+            .put(&path, Bytes::from_static(b"data"))
+            .await
+            .unwrap();
+
+        assert_eq!(t.store.head(&path).await.unwrap().size, 4);
+        assert_eq!(
+            t.store.get_range(&path, 1..3).await.unwrap(),
+            Bytes::from_static(b"at")
+        );
+        assert_eq!(
+            t.store.to_string(),
+            "ReplicatedObjectStore(InMemory, InMemory)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queue_full() {
+        let primary = Arc::new(InMemory::new());
+        // a replica so slow that no write completes during the test
+        let replica = Arc::new(ThrottledStore::new(
+            InMemory::new(),
+            ThrottleConfig {
+                wait_put_per_call: Duration::from_secs(3600),
+                ..Default::default()
+            },
+        ));
+        let metrics = metric::Registry::default();
+        let store = ReplicatedObjectStore::new(
+            primary,
+            replica,
+            NonZeroUsize::new(1).unwrap(),
+            NonZeroUsize::new(1).unwrap(),
+            Duration::from_secs(60),
+            Arc::new(SystemProvider::new()),
+            &metrics,
+        );
+
+        // at most one write is replicated and one queued, the writes to the primary store
+        // never block
+        for i in 0..3 {
+            store
+                .put(
+                    &Path::from(format!("file-{i}")),
+                    Bytes::from_static(b"data"),
+                )
+                .await
+                .unwrap();
+        }
+
+        assert!(dropped(&metrics, "put", "queue_full") >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_deadline() {
+        let primary = Arc::new(InMemory::new());
+        let metrics = metric::Registry::default();
+        let store = ReplicatedObjectStore::new(
+            primary,
+            Arc::new(FailingStore),
+            NonZeroUsize::new(1).unwrap(),
+            NonZeroUsize::new(100).unwrap(),
+            Duration::ZERO,
+            Arc::new(SystemProvider::new()),
+            &metrics,
+        );
+
+        store
+            .put(&Path::from("file"), Bytes::from_static(b"data"))
+            .await
+            .unwrap();
+
+        // the write is given up on after the first failure
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while dropped(&metrics, "put", "deadline") == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("replication not given up");
+    }
+
+    fn dropped(metrics: &metric::Registry, op: &'static str, reason: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>("object_store_replication_dropped")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("op", op), ("reason", reason)]))
+            .unwrap()
+            .fetch()
+    }
+
+    /// A replica that fails all requests.
+    #[derive(Debug)]
+    struct FailingStore;
+
+    impl Display for FailingStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "FailingStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FailingStore {
+        async fn put(&self, _location: &Path, _bytes: Bytes) -> Result<()> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn put_multipart(
+            &self,
+            _location: &Path,
+        ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn abort_multipart(
+            &self,
+            _location: &Path,
+            _multipart_id: &MultipartId,
+        ) -> Result<()> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn get_opts(&self, _location: &Path, _options: GetOptions) -> Result<GetResult> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn head(&self, _location: &Path) -> Result<ObjectMeta> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn delete(&self, _location: &Path) -> Result<()> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn list(&self, _prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn list_with_delimiter(&self, _prefix: Option<&Path>) -> Result<ListResult> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn copy(&self, _from: &Path, _to: &Path) -> Result<()> {
+            Err(Error::NotImplemented)
+        }
+
+        async fn copy_if_not_exists(&self, _from: &Path, _to: &Path) -> Result<()> {
+            Err(Error::NotImplemented)
+        }
+    }
+}
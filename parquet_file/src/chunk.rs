@@ -7,7 +7,11 @@ use crate::{
 };
 use data_types::{ParquetFile, TimestampMinMax};
 use schema::Schema;
-use std::{mem, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    mem,
+    sync::Arc,
+};
 use uuid::Uuid;
 
 /// A abstract representation of a Parquet file in object storage, with
@@ -22,6 +26,12 @@ pub struct ParquetChunk {
 
     /// Persists the parquet file within a namespace's relative path
     store: ParquetStorage,
+
+    /// Columns stored under a different name in the file, see [`ParquetExecInput::renamed_columns`].
+    renamed_columns: BTreeMap<String, String>,
+
+    /// Columns not stored in the file, see [`ParquetExecInput::absent_columns`].
+    absent_columns: BTreeSet<String>,
 }
 
 impl ParquetChunk {
@@ -31,9 +41,31 @@ impl ParquetChunk {
             parquet_file,
             schema,
             store,
+            renamed_columns: BTreeMap::new(),
+            absent_columns: BTreeSet::new(),
         }
     }
 
+    /// Set the columns that are stored under a different name in the file, from the name in the table schema to the
+    /// name within the file.
+    pub fn with_renamed_columns(mut self, renamed_columns: BTreeMap<String, String>) -> Self {
+        self.renamed_columns = renamed_columns;
+        self
+    }
+
+    /// Columns that are stored under a different name in the file, see
+    /// [`with_renamed_columns`](Self::with_renamed_columns).
+    pub fn renamed_columns(&self) -> &BTreeMap<String, String> {
+        &self.renamed_columns
+    }
+
+    /// Set the columns of the table schema that are not stored in the file but whose name may be used by another
+    /// column within the file.
+    pub fn with_absent_columns(mut self, absent_columns: BTreeSet<String>) -> Self {
+        self.absent_columns = absent_columns;
+        self
+    }
+
     /// Store that contains this file.
     pub fn store(&self) -> &ParquetStorage {
         &self.store
@@ -68,7 +100,10 @@ impl ParquetChunk {
     /// [`ParquetExec`]: datafusion::datasource::physical_plan::ParquetExec
     pub fn parquet_exec_input(&self) -> ParquetExecInput {
         let path: ParquetFilePath = self.parquet_file.as_ref().into();
-        self.store.parquet_exec_input(&path, self.file_size_bytes())
+        let mut input = self.store.parquet_exec_input(&path, self.file_size_bytes());
+        input.renamed_columns = self.renamed_columns.clone();
+        input.absent_columns = self.absent_columns.clone();
+        input
     }
 
     /// The total number of rows in all row groups in this chunk.
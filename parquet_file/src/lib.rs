@@ -24,12 +24,20 @@ pub mod chunk;
 pub mod metadata;
 pub mod serialize;
 pub mod storage;
+pub mod verify;
 pub mod writer;
 
 use data_types::{NamespaceId, ParquetFile, ParquetFileParams, TableId, TransitionPartitionId};
 use object_store::path::Path;
 use uuid::Uuid;
 
+/// The directory of the Delta Lake transaction log of an exported table,
+/// relative to the directory of the table in the object store.
+///
+/// The objects in it are not parquet files, but must be kept as long as the
+/// export is used.
+pub const DELTA_LOG_DIR: &str = "_delta_log";
+
 /// Location of a Parquet file within a namespace's object store.
 /// The exact format is an implementation detail and is subject to change.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -89,7 +89,7 @@
 use base64::{prelude::BASE64_STANDARD, Engine};
 use bytes::Bytes;
 use data_types::{
-    ColumnId, ColumnSet, ColumnSummary, CompactionLevel, InfluxDbType, NamespaceId,
+    ColumnId, ColumnSet, ColumnSummary, CompactionLevel, ContentHash, InfluxDbType, NamespaceId,
     ParquetFileParams, PartitionKey, StatValues, Statistics, TableId, Timestamp,
     TransitionPartitionId,
 };
@@ -436,6 +436,7 @@ impl IoxMetadata {
         &self,
         partition_id: TransitionPartitionId,
         file_size_bytes: usize,
+        content_hash: ContentHash,
         metadata: &IoxParquetMetaData,
         column_id_map: F,
     ) -> ParquetFileParams
@@ -495,6 +496,7 @@ impl IoxMetadata {
             created_at: Timestamp::from(self.creation_timestamp),
             column_set: ColumnSet::new(columns),
             max_l0_created_at: Timestamp::from(self.max_l0_created_at),
+            content_hash: Some(content_hash),
         }
     }
 
@@ -7,11 +7,11 @@ use crate::{
     ParquetFilePath,
 };
 use arrow::{
-    datatypes::{Field, SchemaRef},
+    datatypes::{Field, Fields, Schema as ArrowSchema, SchemaRef},
     record_batch::RecordBatch,
 };
 use bytes::Bytes;
-use data_types::TransitionPartitionId;
+use data_types::{ContentHash, TransitionPartitionId};
 use datafusion::{
     datasource::{
         listing::PartitionedFile,
@@ -20,14 +20,22 @@ use datafusion::{
     },
     error::DataFusionError,
     execution::memory_pool::MemoryPool,
-    physical_plan::{ExecutionPlan, SendableRecordBatchStream, Statistics},
+    physical_plan::{
+        stream::RecordBatchStreamAdapter, ExecutionPlan, SendableRecordBatchStream, Statistics,
+    },
     prelude::SessionContext,
 };
 use datafusion_util::config::{iox_session_config, register_iox_object_store};
+use futures::StreamExt;
 use object_store::{DynObjectStore, ObjectMeta};
 use observability_deps::tracing::*;
-use schema::Projection;
+use parquet::arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ProjectionMask};
+use schema::{
+    sort::{SortKey, SortKeyBuilder},
+    Projection,
+};
 use std::{
+    collections::{BTreeMap, BTreeSet},
     fmt::Display,
     sync::Arc,
     time::{Duration, Instant},
@@ -55,6 +63,23 @@ pub enum UploadError {
     Upload(#[from] object_store::Error),
 }
 
+/// Errors returned when reading columns of a Parquet file with
+/// [`ParquetStorage::read_columns`].
+#[derive(Debug, Error)]
+pub enum ReadError {
+    /// Fetching the file from object storage failed.
+    #[error("failed to read from object storage: {0}")]
+    ObjectStore(#[from] object_store::Error),
+
+    /// Decoding the file failed.
+    #[error("failed to decode parquet file: {0}")]
+    Decode(#[from] parquet::errors::ParquetError),
+
+    /// Reading the decoded record batches failed.
+    #[error("failed to read record batches: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+}
+
 impl From<UploadError> for DataFusionError {
     fn from(value: UploadError) -> Self {
         match value {
@@ -102,6 +127,14 @@ pub struct ParquetExecInput {
 
     /// Object metadata.
     pub object_meta: ObjectMeta,
+
+    /// Columns that are stored under a different name in the file than in the table schema (e.g. because they were
+    /// renamed after the file was written), from the table name to the name within the file.
+    pub renamed_columns: BTreeMap<String, String>,
+
+    /// Columns of the table schema that are not stored in the file, although the file may contain a different column
+    /// of the same name (e.g. a column that was dropped or renamed since). These columns are not read from the file.
+    pub absent_columns: BTreeSet<String>,
 }
 
 impl ParquetExecInput {
@@ -219,6 +252,9 @@ impl ParquetStorage {
     /// This method retries forever in the presence of object store errors. All
     /// other errors are returned as they occur.
     ///
+    /// Returns the decoded parquet metadata, the size of the file in bytes and
+    /// the [`ContentHash`] of the uploaded bytes.
+    ///
     /// [`RecordBatch`]: arrow::record_batch::RecordBatch
     pub async fn upload(
         &self,
@@ -226,7 +262,7 @@ impl ParquetStorage {
         partition_id: &TransitionPartitionId,
         meta: &IoxMetadata,
         pool: Arc<dyn MemoryPool>,
-    ) -> Result<(IoxParquetMetaData, usize), UploadError> {
+    ) -> Result<(IoxParquetMetaData, usize, ContentHash), UploadError> {
         let start = Instant::now();
 
         // Stream the record batches into a parquet file.
@@ -251,6 +287,7 @@ impl ParquetStorage {
         let path = ParquetFilePath::from((partition_id, meta)).object_store_path();
 
         let file_size = data.len();
+        let content_hash = ContentHash::of(&data);
         let data = Bytes::from(data);
 
         debug!(
@@ -280,7 +317,45 @@ impl ParquetStorage {
             );
         }
 
-        Ok((parquet_meta, file_size))
+        Ok((parquet_meta, file_size, content_hash))
+    }
+
+    /// Read the columns named `columns` of the file at `path`.
+    ///
+    /// Unlike scanning the file with a [`ParquetExec`], this does not require
+    /// a DataFusion session and is meant for reading small subsets of a file
+    /// outside of query execution. The whole file is fetched from object
+    /// storage. Names in `columns` that are not part of the file are ignored.
+    ///
+    /// [`ParquetExec`]: datafusion::datasource::physical_plan::ParquetExec
+    pub async fn read_columns(
+        &self,
+        path: &ParquetFilePath,
+        columns: &[&str],
+    ) -> Result<Vec<RecordBatch>, ReadError> {
+        let data = self
+            .object_store
+            .get(&path.object_store_path())
+            .await?
+            .bytes()
+            .await?;
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(data)?;
+        let indices = builder
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| columns.contains(&field.name().as_str()))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+
+        let batches = builder
+            .with_projection(mask)
+            .build()?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(batches)
     }
 
     /// Inputs for [`ParquetExec`].
@@ -299,10 +374,65 @@ impl ParquetStorage {
                 size: file_size,
                 e_tag: None,
             },
+            renamed_columns: BTreeMap::new(),
+            absent_columns: BTreeSet::new(),
         }
     }
 }
 
+/// Rename the columns of `stream` to the names they are stored under in a parquet file, given as `renamed_columns`
+/// from the name in `stream` to the name within the file.
+pub fn rename_stream_columns(
+    stream: SendableRecordBatchStream,
+    renamed_columns: &BTreeMap<String, String>,
+) -> SendableRecordBatchStream {
+    if renamed_columns.is_empty() {
+        return stream;
+    }
+
+    let schema = stream.schema();
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|f| match renamed_columns.get(f.name()) {
+            Some(name) => Arc::new(Field::clone(f).with_name(name)),
+            None => Arc::clone(f),
+        })
+        .collect::<Fields>();
+    let schema = Arc::new(ArrowSchema::new_with_metadata(
+        fields,
+        schema.metadata().clone(),
+    ));
+
+    let batch_schema = Arc::clone(&schema);
+    Box::pin(RecordBatchStreamAdapter::new(
+        schema,
+        stream.map(move |batch| -> Result<RecordBatch, DataFusionError> {
+            let batch = batch?;
+            RecordBatch::try_new(Arc::clone(&batch_schema), batch.columns().to_vec())
+                .map_err(|e| DataFusionError::ArrowError(e, None))
+        }),
+    ))
+}
+
+/// Rename the columns of `sort_key` to the names they are stored under in a parquet file, see
+/// [`rename_stream_columns`].
+pub fn rename_sort_key_columns(
+    sort_key: &SortKey,
+    renamed_columns: &BTreeMap<String, String>,
+) -> SortKey {
+    sort_key
+        .iter()
+        .fold(
+            SortKeyBuilder::with_capacity(sort_key.len()),
+            |builder, (name, options)| match renamed_columns.get(name.as_ref()) {
+                Some(name_in_file) => builder.with_col_sort_opts(name_in_file.as_str(), *options),
+                None => builder.with_col_sort_opts(Arc::clone(name), *options),
+            },
+        )
+        .build()
+}
+
 /// Error during projecting parquet file data to an expected schema.
 #[derive(Debug, Error)]
 #[allow(clippy::large_enum_variant)]
@@ -345,7 +475,8 @@ mod tests {
         let batch = RecordBatch::try_from_iter([("a", to_string_array(&["value"]))]).unwrap();
 
         // Serialize & upload the record batches.
-        let (file_meta, _file_size) = upload(&store, &partition_id, &meta, batch.clone()).await;
+        let (file_meta, _file_size, _content_hash) =
+            upload(&store, &partition_id, &meta, batch.clone()).await;
 
         // Extract the various bits of metadata.
         let file_meta = file_meta.decode().expect("should decode parquet metadata");
@@ -366,6 +497,30 @@ mod tests {
         assert_roundtrip(batch.clone(), Projection::All, schema, batch).await;
     }
 
+    #[tokio::test]
+    async fn test_read_columns() {
+        let object_store: Arc<DynObjectStore> = Arc::new(object_store::memory::InMemory::default());
+        let store = ParquetStorage::new(object_store, StorageId::from("iox"));
+
+        let (partition_id, meta) = meta();
+        let batch = RecordBatch::try_from_iter([
+            ("a", to_string_array(&["value", "other"])),
+            ("b", to_int_array(&[1, 2])),
+        ])
+        .unwrap();
+        upload(&store, &partition_id, &meta, batch).await;
+
+        let path = ParquetFilePath::from((&partition_id, &meta));
+        let batches = store.read_columns(&path, &["b", "missing"]).await.unwrap();
+
+        // the file metadata is attached to the schema, so only compare the data
+        assert_eq!(batches.len(), 1);
+        let schema = batches[0].schema();
+        let names = schema.fields().iter().map(|f| f.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["b"]);
+        assert_eq!(batches[0].columns(), &[to_int_array(&[1, 2])]);
+    }
+
     #[tokio::test]
     async fn test_selection() {
         let batch = RecordBatch::try_from_iter([
@@ -491,7 +646,7 @@ mod tests {
         let schema = batch.schema();
 
         // Serialize & upload the record batches.
-        let (_iox_md, file_size) = upload(&store, &partition_id, &meta, batch).await;
+        let (_iox_md, file_size, _content_hash) = upload(&store, &partition_id, &meta, batch).await;
 
         // add metadata to reference schema
         let schema = Arc::new(
@@ -534,7 +689,7 @@ mod tests {
         .unwrap();
 
         // Serialize & upload the record batches.
-        let (_iox_md, file_size) = upload(&store, &partition_id, &meta, batch).await;
+        let (_iox_md, file_size, _content_hash) = upload(&store, &partition_id, &meta, batch).await;
 
         download(
             &store,
@@ -617,7 +772,7 @@ mod tests {
         partition_id: &TransitionPartitionId,
         meta: &IoxMetadata,
         batch: RecordBatch,
-    ) -> (IoxParquetMetaData, usize) {
+    ) -> (IoxParquetMetaData, usize, ContentHash) {
         let stream = Box::pin(MemoryStream::new(vec![batch]));
         store
             .upload(stream, partition_id, meta, unbounded_memory_pool())
@@ -656,7 +811,8 @@ mod tests {
 
         // Serialize & upload the record batches.
         let (partition_id, meta) = meta();
-        let (_iox_md, file_size) = upload(&store, &partition_id, &meta, upload_batch).await;
+        let (_iox_md, file_size, _content_hash) =
+            upload(&store, &partition_id, &meta, upload_batch).await;
 
         // And compare to the original input
         let actual_batch = download(
@@ -682,7 +838,8 @@ mod tests {
         let store = ParquetStorage::new(object_store, StorageId::from("iox"));
 
         let (partition_id, meta) = meta();
-        let (_iox_md, file_size) = upload(&store, &partition_id, &meta, persisted_batch).await;
+        let (_iox_md, file_size, _content_hash) =
+            upload(&store, &partition_id, &meta, persisted_batch).await;
 
         let err = download(
             &store,
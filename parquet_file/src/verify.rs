@@ -0,0 +1,293 @@
+//! Verification of parquet files in object storage against the
+//! [`ContentHash`] recorded in the catalog when they were created.
+//!
+//! A file whose bytes no longer match the recorded hash has been corrupted or
+//! modified after it was written. Reading such a file would at best return
+//! wrong results and at worst panic inside the parquet decoder, so it is
+//! reported with a [`VerifyError::Corrupted`] error instead.
+
+use std::collections::{HashMap, HashSet};
+
+use data_types::{ContentHash, ParquetFile};
+use object_store::DynObjectStore;
+use observability_deps::tracing::{debug, error};
+use parking_lot::Mutex;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{storage::ParquetStorage, ParquetFilePath};
+
+/// Errors returned when verifying the content of a parquet file.
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    /// The content of the file does not match the hash recorded in the
+    /// catalog.
+    #[error(
+        "parquet file {object_store_id} is corrupted or was modified: \
+         expected content hash {expected}, found {actual}"
+    )]
+    Corrupted {
+        /// The object store ID of the file.
+        object_store_id: Uuid,
+        /// The hash recorded in the catalog.
+        expected: ContentHash,
+        /// The hash of the bytes in object storage.
+        actual: ContentHash,
+    },
+
+    /// The file could not be read from object storage.
+    #[error("failed to read parquet file {object_store_id} from object storage: {source}")]
+    ObjectStore {
+        /// The object store ID of the file.
+        object_store_id: Uuid,
+        /// The underlying error.
+        source: object_store::Error,
+    },
+}
+
+/// The outcome of a successful verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    /// The content of the file matches the hash recorded in the catalog.
+    Verified,
+
+    /// The catalog has no hash for the file, so it cannot be verified.
+    ///
+    /// This is the case for files created before content hashes were
+    /// recorded.
+    NoContentHash,
+}
+
+/// Read `file` from `object_store` and compare the hash of its content with
+/// the one recorded in the catalog.
+pub async fn verify_file(
+    object_store: &DynObjectStore,
+    file: &ParquetFile,
+) -> Result<Verification, VerifyError> {
+    let Some(expected) = file.content_hash else {
+        return Ok(Verification::NoContentHash);
+    };
+    let object_store_id = file.object_store_id;
+
+    let path = ParquetFilePath::from(file).object_store_path();
+    let data = match object_store.get(&path).await {
+        Ok(result) => result.bytes().await,
+        Err(e) => Err(e),
+    }
+    .map_err(|source| VerifyError::ObjectStore {
+        object_store_id,
+        source,
+    })?;
+
+    let actual = ContentHash::of(&data);
+    if actual != expected {
+        return Err(VerifyError::Corrupted {
+            object_store_id,
+            expected,
+            actual,
+        });
+    }
+
+    Ok(Verification::Verified)
+}
+
+/// Verifies parquet files before they are read, remembering the outcome.
+///
+/// Files are immutable, so each file is only read and hashed once: files that
+/// were verified successfully are not checked again, and files found to be
+/// corrupted are quarantined, failing every later verification without
+/// touching object storage.
+#[derive(Debug, Default)]
+pub struct ChecksumVerifier {
+    /// Object store IDs of the files that were verified successfully.
+    verified: Mutex<HashSet<Uuid>>,
+
+    /// Quarantined files, keyed by object store ID, with the expected and
+    /// actual hashes.
+    quarantined: Mutex<HashMap<Uuid, (ContentHash, ContentHash)>>,
+}
+
+impl ChecksumVerifier {
+    /// Create a new verifier that has not seen any files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verify `file` stored in `store`.
+    ///
+    /// Files without a recorded hash are accepted without being read. Errors
+    /// reading from object storage are returned but not remembered, so the
+    /// file is checked again next time.
+    pub async fn verify(
+        &self,
+        store: &ParquetStorage,
+        file: &ParquetFile,
+    ) -> Result<(), VerifyError> {
+        let object_store_id = file.object_store_id;
+
+        if let Some((expected, actual)) = self.quarantined.lock().get(&object_store_id) {
+            return Err(VerifyError::Corrupted {
+                object_store_id,
+                expected: *expected,
+                actual: *actual,
+            });
+        }
+        if self.verified.lock().contains(&object_store_id) {
+            return Ok(());
+        }
+
+        match verify_file(store.object_store().as_ref(), file).await {
+            Ok(Verification::Verified) => {
+                self.verified.lock().insert(object_store_id);
+                Ok(())
+            }
+            Ok(Verification::NoContentHash) => {
+                debug!(%object_store_id, "parquet file has no content hash, skipping verification");
+                Ok(())
+            }
+            Err(VerifyError::Corrupted {
+                object_store_id,
+                expected,
+                actual,
+            }) => {
+                error!(
+                    %object_store_id,
+                    %expected,
+                    %actual,
+                    "parquet file content does not match its content hash, quarantining"
+                );
+                self.quarantined
+                    .lock()
+                    .insert(object_store_id, (expected, actual));
+                Err(VerifyError::Corrupted {
+                    object_store_id,
+                    expected,
+                    actual,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns true if `object_store_id` was found to be corrupted.
+    pub fn is_quarantined(&self, object_store_id: &Uuid) -> bool {
+        self.quarantined.lock().contains_key(object_store_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+    use bytes::Bytes;
+    use data_types::{
+        ColumnId, ColumnSet, CompactionLevel, NamespaceId, ParquetFileId, PartitionId, TableId,
+        Timestamp, TransitionPartitionId,
+    };
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::storage::StorageId;
+
+    const CONTENT: &[u8] = b"parquet file bytes";
+
+    fn file(content_hash: Option<ContentHash>) -> ParquetFile {
+        ParquetFile {
+            id: ParquetFileId::new(1),
+            namespace_id: NamespaceId::new(1),
+            table_id: TableId::new(1),
+            partition_id: TransitionPartitionId::Deprecated(PartitionId::new(1)),
+            object_store_id: Uuid::new_v4(),
+            min_time: Timestamp::new(1),
+            max_time: Timestamp::new(2),
+            to_delete: None,
+            file_size_bytes: CONTENT.len() as i64,
+            row_count: 1,
+            compaction_level: CompactionLevel::Initial,
+            created_at: Timestamp::new(1),
+            column_set: ColumnSet::new([ColumnId::new(1)]),
+            max_l0_created_at: Timestamp::new(1),
+            content_hash,
+        }
+    }
+
+    async fn put(store: &ParquetStorage, file: &ParquetFile, data: &'static [u8]) {
+        let path = ParquetFilePath::from(file).object_store_path();
+        store
+            .object_store()
+            .put(&path, Bytes::from_static(data))
+            .await
+            .unwrap();
+    }
+
+    fn storage() -> ParquetStorage {
+        ParquetStorage::new(Arc::new(InMemory::new()), StorageId::from("iox"))
+    }
+
+    #[tokio::test]
+    async fn test_verify_file() {
+        let store = storage();
+
+        let good = file(Some(ContentHash::of(CONTENT)));
+        put(&store, &good, CONTENT).await;
+        let got = verify_file(store.object_store().as_ref(), &good)
+            .await
+            .unwrap();
+        assert_eq!(got, Verification::Verified);
+
+        let unhashed = file(None);
+        let got = verify_file(store.object_store().as_ref(), &unhashed)
+            .await
+            .unwrap();
+        assert_eq!(got, Verification::NoContentHash);
+
+        let corrupted = file(Some(ContentHash::of(CONTENT)));
+        put(&store, &corrupted, b"parquet file bytez").await;
+        let err = verify_file(store.object_store().as_ref(), &corrupted)
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            VerifyError::Corrupted { object_store_id, expected, actual } => {
+                assert_eq!(object_store_id, corrupted.object_store_id);
+                assert_eq!(expected, ContentHash::of(CONTENT));
+                assert_eq!(actual, ContentHash::of(b"parquet file bytez"));
+            }
+        );
+
+        let missing = file(Some(ContentHash::of(CONTENT)));
+        let err = verify_file(store.object_store().as_ref(), &missing)
+            .await
+            .unwrap_err();
+        assert_matches!(err, VerifyError::ObjectStore { .. });
+    }
+
+    #[tokio::test]
+    async fn test_checksum_verifier() {
+        let store = storage();
+        let verifier = ChecksumVerifier::new();
+
+        let good = file(Some(ContentHash::of(CONTENT)));
+        put(&store, &good, CONTENT).await;
+        verifier.verify(&store, &good).await.unwrap();
+
+        // verified files are not read again
+        put(&store, &good, b"overwritten").await;
+        verifier.verify(&store, &good).await.unwrap();
+
+        let corrupted = file(Some(ContentHash::of(CONTENT)));
+        put(&store, &corrupted, b"garbage").await;
+        verifier.verify(&store, &corrupted).await.unwrap_err();
+        assert!(verifier.is_quarantined(&corrupted.object_store_id));
+        assert!(!verifier.is_quarantined(&good.object_store_id));
+
+        // quarantined files keep failing, even if the object is repaired
+        put(&store, &corrupted, CONTENT).await;
+        let err = verifier.verify(&store, &corrupted).await.unwrap_err();
+        assert!(err.to_string().contains("is corrupted or was modified"));
+
+        // files without a hash are accepted
+        verifier.verify(&store, &file(None)).await.unwrap();
+    }
+}
@@ -5,8 +5,8 @@ use arrow::{
     record_batch::RecordBatch,
 };
 use data_types::{
-    ColumnId, CompactionLevel, NamespaceId, PartitionId, PartitionKey, TableId, Timestamp,
-    TransitionPartitionId,
+    ColumnId, CompactionLevel, ContentHash, NamespaceId, PartitionId, PartitionKey, TableId,
+    Timestamp, TransitionPartitionId,
 };
 use datafusion_util::{unbounded_memory_pool, MemoryStream};
 use iox_time::Time;
@@ -15,6 +15,7 @@ use parquet_file::{
     metadata::IoxMetadata,
     serialize::CodecError,
     storage::{ParquetStorage, StorageId, UploadError},
+    ParquetFilePath,
 };
 use schema::{
     builder::SchemaBuilder, sort::SortKey, InfluxColumnType, InfluxFieldType, TIME_COLUMN_NAME,
@@ -82,7 +83,7 @@ async fn test_decoded_iox_metadata() {
     let object_store: Arc<DynObjectStore> = Arc::new(object_store::memory::InMemory::default());
     let storage = ParquetStorage::new(object_store, StorageId::from("iox"));
 
-    let (iox_parquet_meta, file_size) = storage
+    let (iox_parquet_meta, file_size, _content_hash) = storage
         .upload(stream, &partition_id, &meta, unbounded_memory_pool())
         .await
         .expect("failed to serialize & persist record batch");
@@ -315,7 +316,7 @@ async fn test_decoded_many_columns_with_null_cols_iox_metadata() {
     let object_store: Arc<DynObjectStore> = Arc::new(object_store::memory::InMemory::default());
     let storage = ParquetStorage::new(object_store, StorageId::from("iox"));
 
-    let (iox_parquet_meta, file_size) = storage
+    let (iox_parquet_meta, file_size, _content_hash) = storage
         .upload(stream, &partition_id, &meta, unbounded_memory_pool())
         .await
         .expect("failed to serialize & persist record batch");
@@ -401,7 +402,7 @@ async fn test_derive_parquet_file_params() {
     let object_store: Arc<DynObjectStore> = Arc::new(object_store::memory::InMemory::default());
     let storage = ParquetStorage::new(object_store, StorageId::from("iox"));
 
-    let (iox_parquet_meta, file_size) = storage
+    let (iox_parquet_meta, file_size, content_hash) = storage
         .upload(stream, &partition_id, &meta, unbounded_memory_pool())
         .await
         .expect("failed to serialize & persist record batch");
@@ -412,9 +413,13 @@ async fn test_derive_parquet_file_params() {
         ("some_field".into(), ColumnId::new(1)),
         ("time".into(), ColumnId::new(2)),
     ]);
-    let catalog_data = meta.to_parquet_file(partition_id, file_size, &iox_parquet_meta, |name| {
-        *column_id_map.get(name).unwrap()
-    });
+    let catalog_data = meta.to_parquet_file(
+        partition_id.clone(),
+        file_size,
+        content_hash,
+        &iox_parquet_meta,
+        |name| *column_id_map.get(name).unwrap(),
+    );
 
     // And verify the resulting statistics used in the catalog.
     //
@@ -430,6 +435,18 @@ async fn test_derive_parquet_file_params() {
     assert_eq!(catalog_data.min_time, Timestamp::new(1646917692000000000));
     assert_eq!(catalog_data.max_time, Timestamp::new(1653311292000000000));
     assert_eq!(catalog_data.max_l0_created_at, Timestamp::new(1234));
+
+    // The content hash must match the bytes written to object storage.
+    let path = ParquetFilePath::from((&partition_id, &meta)).object_store_path();
+    let data = storage
+        .object_store()
+        .get(&path)
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    assert_eq!(catalog_data.content_hash, Some(ContentHash::of(&data)));
 }
 
 fn to_string_array(strs: &[&str]) -> ArrayRef {
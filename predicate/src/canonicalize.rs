@@ -0,0 +1,278 @@
+//! Canonical form of [`Predicate`]s.
+
+use data_types::TimestampRange;
+use datafusion::{
+    execution::context::ExecutionProps,
+    logical_expr::{utils::split_conjunction_owned, BinaryExpr, Operator},
+    optimizer::simplify_expressions::{ExprSimplifier, SimplifyContext},
+    prelude::{lit, Expr},
+    scalar::ScalarValue,
+};
+use schema::TIME_COLUMN_NAME;
+
+use crate::Predicate;
+
+impl Predicate {
+    /// Return an equivalent predicate in canonical form, so that equivalent
+    /// predicates compare equal and contradictions are detected before any
+    /// data is read:
+    ///
+    /// * constant sub-expressions of `exprs` are folded
+    /// * the conjunctions of `exprs` are flattened, sorted and deduplicated,
+    ///   and literal `true` expressions are removed
+    /// * comparisons of the time column with a timestamp literal are merged
+    ///   into `range`
+    /// * contradictions, such as `time > 10 AND time < 5`, `tag = 'a' AND
+    ///   tag = 'b'` or a literal `false` / `NULL` expression, result in a
+    ///   predicate for which [`is_unsatisfiable`](Self::is_unsatisfiable)
+    ///   returns true
+    ///
+    /// Expressions that cannot be simplified without knowing the schema of
+    /// the table are kept as-is.
+    pub fn canonicalize(mut self) -> Self {
+        let props = ExecutionProps::new();
+        let simplifier = ExprSimplifier::new(SimplifyContext::new(&props));
+
+        let mut exprs = Vec::with_capacity(self.exprs.len());
+        for expr in std::mem::take(&mut self.exprs) {
+            let expr = simplifier.simplify(expr.clone()).unwrap_or(expr);
+            exprs.extend(split_conjunction_owned(expr));
+        }
+
+        let mut range = self.range;
+        let mut contradiction = false;
+        let mut equalities: Vec<(String, ScalarValue)> = vec![];
+
+        exprs.retain(|expr| {
+            if let Some(time_range) = time_range(expr) {
+                range = Some(match range {
+                    Some(range) => intersect(range, time_range),
+                    None => time_range,
+                });
+                return false;
+            }
+
+            match expr {
+                Expr::Literal(ScalarValue::Boolean(Some(true))) => false,
+                Expr::Literal(ScalarValue::Boolean(_)) => {
+                    // `false` or `NULL`, no row passes
+                    contradiction = true;
+                    true
+                }
+                _ => {
+                    if let Some((column, value)) = column_eq_literal(expr) {
+                        let conflicts = equalities.iter().any(|(c, v)| {
+                            c == column && v.data_type() == value.data_type() && v != value
+                        });
+                        contradiction |= conflicts;
+                        equalities.push((column.to_string(), value.clone()));
+                    }
+                    true
+                }
+            }
+        });
+
+        if contradiction || range.is_some_and(|range| range.start() >= range.end()) {
+            self.exprs = vec![lit(false)];
+            self.range = None;
+            return self;
+        }
+
+        exprs.sort_by_cached_key(|expr| expr.to_string());
+        exprs.dedup();
+
+        self.exprs = exprs;
+        self.range = range;
+        self
+    }
+
+    /// Returns true if no row can pass this predicate.
+    ///
+    /// This only detects the contradictions found by
+    /// [`canonicalize`](Self::canonicalize) and hence may return false for
+    /// unsatisfiable predicates that were not canonicalized.
+    pub fn is_unsatisfiable(&self) -> bool {
+        self.range.is_some_and(|range| range.start() >= range.end())
+            || self.exprs.iter().any(|expr| {
+                matches!(
+                    expr,
+                    Expr::Literal(ScalarValue::Boolean(Some(false)))
+                        | Expr::Literal(ScalarValue::Boolean(None))
+                )
+            })
+    }
+}
+
+/// If `expr` compares the time column with a timestamp literal, return the
+/// range of timestamps passing the comparison.
+fn time_range(expr: &Expr) -> Option<TimestampRange> {
+    let Expr::BinaryExpr(BinaryExpr { left, op, right }) = expr else {
+        return None;
+    };
+
+    let (op, ts) = match (left.as_ref(), right.as_ref()) {
+        (Expr::Column(c), Expr::Literal(ScalarValue::TimestampNanosecond(Some(ts), _)))
+            if c.name == TIME_COLUMN_NAME =>
+        {
+            (*op, *ts)
+        }
+        (Expr::Literal(ScalarValue::TimestampNanosecond(Some(ts), _)), Expr::Column(c))
+            if c.name == TIME_COLUMN_NAME =>
+        {
+            (op.swap()?, *ts)
+        }
+        _ => return None,
+    };
+
+    let (start, end) = match op {
+        Operator::Eq => (ts, ts.saturating_add(1)),
+        Operator::Gt => (ts.saturating_add(1), i64::MAX),
+        Operator::GtEq => (ts, i64::MAX),
+        Operator::Lt => (i64::MIN, ts),
+        Operator::LtEq => (i64::MIN, ts.saturating_add(1)),
+        _ => return None,
+    };
+
+    Some(TimestampRange::new(start, end))
+}
+
+fn intersect(a: TimestampRange, b: TimestampRange) -> TimestampRange {
+    TimestampRange::new(a.start().max(b.start()), a.end().min(b.end()))
+}
+
+/// If `expr` is `column = literal` (or `literal = column`) with a non-NULL
+/// literal, return the column name and the literal.
+///
+/// Float literals are skipped: distinct values may compare equal (`0.0` and
+/// `-0.0`), so two of them do not necessarily contradict each other.
+fn column_eq_literal(expr: &Expr) -> Option<(&str, &ScalarValue)> {
+    let Expr::BinaryExpr(BinaryExpr {
+        left,
+        op: Operator::Eq,
+        right,
+    }) = expr
+    else {
+        return None;
+    };
+
+    match (left.as_ref(), right.as_ref()) {
+        (Expr::Column(c), Expr::Literal(v)) | (Expr::Literal(v), Expr::Column(c))
+            if !v.is_null() && !v.data_type().is_floating() =>
+        {
+            Some((c.name.as_str(), v))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::prelude::{col, lit_timestamp_nano};
+
+    #[test]
+    fn test_flatten_sort_dedup() {
+        let p = Predicate::new()
+            .with_expr(col("b").eq(lit(1)).and(col("a").eq(lit(2))))
+            .with_expr(lit(true))
+            .with_expr(col("a").eq(lit(2)));
+        let q = Predicate::new()
+            .with_expr(col("a").eq(lit(2)))
+            .with_expr(col("b").eq(lit(1)));
+
+        let expected = Predicate::new().with_exprs([col("a").eq(lit(2)), col("b").eq(lit(1))]);
+        assert_eq!(p.canonicalize(), expected);
+        assert_eq!(q.canonicalize(), expected);
+    }
+
+    #[test]
+    fn test_constant_folding() {
+        let p = Predicate::new()
+            .with_expr(lit(1).lt(lit(2)))
+            .with_expr(col("a").gt(lit(1) + lit(2)))
+            .canonicalize();
+
+        assert_eq!(p, Predicate::new().with_expr(col("a").gt(lit(3))));
+        assert!(!p.is_unsatisfiable());
+    }
+
+    #[test]
+    fn test_merge_time_range() {
+        let p = Predicate::new()
+            .with_range(0, 100)
+            .with_expr(col(TIME_COLUMN_NAME).gt(lit_timestamp_nano(10)))
+            .with_expr(lit_timestamp_nano(50).gt_eq(col(TIME_COLUMN_NAME)))
+            .with_expr(col("a").eq(lit(1)))
+            .canonicalize();
+
+        assert_eq!(
+            p,
+            Predicate::new()
+                .with_range(11, 51)
+                .with_expr(col("a").eq(lit(1)))
+        );
+    }
+
+    #[test]
+    fn test_time_contradiction() {
+        let p = Predicate::new()
+            .with_expr(col(TIME_COLUMN_NAME).gt(lit_timestamp_nano(10)))
+            .with_expr(col(TIME_COLUMN_NAME).lt(lit_timestamp_nano(5)));
+        assert!(!p.is_unsatisfiable());
+
+        let p = p.canonicalize();
+        assert!(p.is_unsatisfiable());
+        assert_eq!(p, Predicate::new().with_expr(lit(false)));
+
+        // the range excludes the only matching timestamp
+        let p = Predicate::new()
+            .with_range(0, 100)
+            .with_expr(col(TIME_COLUMN_NAME).eq(lit_timestamp_nano(100)))
+            .canonicalize();
+        assert!(p.is_unsatisfiable());
+    }
+
+    #[test]
+    fn test_equality_contradiction() {
+        let p = Predicate::new()
+            .with_expr(col("tag").eq(lit("a")))
+            .with_expr(lit("b").eq(col("tag")))
+            .canonicalize();
+        assert!(p.is_unsatisfiable());
+
+        // different columns do not conflict
+        let p = Predicate::new()
+            .with_expr(col("tag").eq(lit("a")))
+            .with_expr(col("other").eq(lit("b")))
+            .canonicalize();
+        assert!(!p.is_unsatisfiable());
+
+        // 0.0 and -0.0 are equal
+        let p = Predicate::new()
+            .with_expr(col("f").eq(lit(0.0)))
+            .with_expr(col("f").eq(lit(-0.0)))
+            .canonicalize();
+        assert!(!p.is_unsatisfiable());
+    }
+
+    #[test]
+    fn test_literal_contradiction() {
+        let p = Predicate::new()
+            .with_expr(col("a").eq(lit(1)))
+            .with_expr(lit(ScalarValue::Boolean(None)))
+            .canonicalize();
+        assert!(p.is_unsatisfiable());
+        assert_eq!(p, Predicate::new().with_expr(lit(false)));
+    }
+
+    #[test]
+    fn test_field_columns_kept() {
+        let p = Predicate::new()
+            .with_field_columns(["f1"])
+            .unwrap()
+            .with_expr(lit(false))
+            .canonicalize();
+        assert!(p.is_unsatisfiable());
+        assert_eq!(p.field_columns, Some(["f1".to_string()].into()));
+    }
+}
@@ -145,7 +145,7 @@ impl InfluxRpcPredicate {
                         // happen if there is a request for
                         // "measurement fields" for a non existent
                         // measurement, for example
-                        self.inner.clone()
+                        self.inner.clone().canonicalize()
                     }
                 };
                 Ok((Arc::from(table), predicate))
@@ -205,6 +205,10 @@ pub trait QueryNamespaceMeta {
 /// ```text
 /// ("field1" > 34.2 OR "field2" > 34.2 OR "fieldn" > 34.2)
 /// ```
+///
+/// Finally the predicate is brought into its canonical form (see
+/// [`Predicate::canonicalize`]), so that predicates which cannot match any
+/// row for this table are detected before planning.
 fn normalize_predicate(
     table_name: &str,
     schema: Schema,
@@ -272,7 +276,9 @@ fn normalize_predicate(
     predicate.value_expr = field_value_exprs;
 
     // save any field projections
-    field_projections.add_to_predicate(predicate)
+    field_projections
+        .add_to_predicate(predicate)
+        .map(Predicate::canonicalize)
 }
 
 fn log_rewrite(expr: Expr, description: &str) -> Expr {
@@ -286,10 +292,7 @@ mod tests {
 
     use super::*;
     use arrow::datatypes::DataType;
-    use datafusion::{
-        prelude::{col, lit},
-        scalar::ScalarValue,
-    };
+    use datafusion::prelude::{col, lit, when};
     use datafusion_util::lit_dict;
     use test_helpers::assert_contains;
 
@@ -307,6 +310,26 @@ mod tests {
         assert_eq!(predicate, expected);
     }
 
+    #[test]
+    fn test_normalize_predicate_tag_in_list() {
+        // CASE WHEN t1 IS NULL THEN '' ELSE t1 END IN ('a', 'b', 'c')
+        let tag_ref = when(col("t1").is_null(), lit(""))
+            .otherwise(col("t1"))
+            .unwrap();
+        let predicate = normalize_predicate(
+            "table",
+            schema(),
+            &Predicate::new().with_expr(tag_ref.in_list(vec![lit("a"), lit("b"), lit("c")], false)),
+        )
+        .unwrap();
+
+        // the IN list is kept, rather than expanded into ORs
+        let expected = Predicate::new()
+            .with_expr(col("t1").in_list(vec![lit_dict("a"), lit_dict("b"), lit_dict("c")], false));
+
+        assert_eq!(predicate, expected);
+    }
+
     #[test]
     fn test_normalize_predicate_field_rewrite() {
         let predicate = normalize_predicate(
@@ -364,8 +387,10 @@ mod tests {
         )
         .unwrap();
 
-        let expected = Predicate::new().with_expr(lit(ScalarValue::Boolean(None)));
+        // `NULL = 'blarg'` never matches
+        let expected = Predicate::new().with_expr(lit(false));
         assert_eq!(predicate, expected);
+        assert!(predicate.is_unsatisfiable());
     }
 
     #[test]
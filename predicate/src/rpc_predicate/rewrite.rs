@@ -1,9 +1,18 @@
+use std::sync::Arc;
+
 use datafusion::{
     common::tree_node::{Transformed, TreeNode},
     error::Result,
-    logical_expr::{binary_expr, expr::Case, BinaryExpr, Cast, Like, Operator},
+    logical_expr::{
+        binary_expr,
+        expr::{Case, InList, ScalarUDF},
+        BinaryExpr, Cast, Like, Operator,
+    },
     prelude::Expr,
 };
+use query_functions::{
+    CONTAINS_STR_UDF_NAME, EQUAL_FOLD_UDF_NAME, HAS_PREFIX_UDF_NAME, HAS_SUFFIX_UDF_NAME,
+};
 
 /// Special purpose `Expr` rewrite rules for IOx
 ///
@@ -12,6 +21,7 @@ use datafusion::{
 /// only seem to make sense for IOx which are placed here:
 ///
 /// 1. Fold past CASE blocks
+/// 2. Fold IN lists and string comparisons past CASE blocks
 ///
 /// # Fold past CASE blocks / translate to boolean CASE #3585
 ///
@@ -33,6 +43,33 @@ use datafusion::{
 ///  ELSE tag_col = 'cpu'
 /// END
 /// ```
+///
+/// # Fold IN lists and string comparisons past CASE blocks
+///
+/// Similarly, an IN list of literals, or a string comparison function
+/// (such as `equal_fold` or `has_prefix`) with a literal, with a CASE as
+/// its argument:
+///
+/// ```sql
+/// CASE
+///   WHEN tag_col IS NULL THEN ''
+///   ELSE tag_col
+///   END IN ('cpu', 'mem')
+/// ```
+///
+/// Is inlined to each WHEN:
+///
+/// ```sql
+/// CASE
+///  WHEN tag_col IS NULL THEN '' IN ('cpu', 'mem')
+///  ELSE tag_col IN ('cpu', 'mem')
+/// END
+/// ```
+///
+/// So that the IN list can be evaluated (and used for pruning) directly
+/// against the tag column rather than being expanded into a chain of ORs,
+/// and string comparisons can be evaluated against the dictionary of the
+/// tag column rather than each of its values.
 pub fn iox_expr_rewrite(expr: Expr) -> Result<Expr> {
     expr.transform(&iox_expr_rewrite_inner)
 }
@@ -47,6 +84,28 @@ fn iox_expr_rewrite_inner(expr: Expr) -> Result<Transformed<Expr>> {
         {
             Transformed::Yes(inline_case(false, *left, *right, op))
         }
+        Expr::InList(InList {
+            expr,
+            list,
+            negated,
+        }) if is_case(&expr) && list.iter().all(is_lit) => {
+            Transformed::Yes(inline_case_in_list(*expr, list, negated))
+        }
+        Expr::ScalarUDF(ScalarUDF { fun, mut args })
+            if is_string_comparison(&fun.name)
+                && args.len() == 2
+                && is_case(&args[0])
+                && is_lit(&args[1]) =>
+        {
+            let value = args.pop().expect("two args");
+            let case = args.pop().expect("two args");
+            Transformed::Yes(map_case(case, |e| {
+                Expr::ScalarUDF(ScalarUDF {
+                    fun: Arc::clone(&fun),
+                    args: vec![e, value.clone()],
+                })
+            }))
+        }
         expr => Transformed::No(expr),
     })
 }
@@ -215,6 +274,36 @@ fn inline_case(case_on_left: bool, left: Expr, right: Expr, op: Operator) -> Exp
     })
 }
 
+fn inline_case_in_list(case: Expr, list: Vec<Expr>, negated: bool) -> Expr {
+    map_case(case, |e| e.in_list(list.clone(), negated))
+}
+
+/// Apply `f` to each THEN (and the ELSE) of a `CASE WHEN .. THEN .. ELSE
+/// .. END` expression.
+fn map_case(case: Expr, f: impl Fn(Expr) -> Expr) -> Expr {
+    let Expr::Case(Case {
+        expr: None,
+        when_then_expr,
+        else_expr,
+    }) = case
+    else {
+        unreachable!()
+    };
+
+    let when_then_expr = when_then_expr
+        .into_iter()
+        .map(|(when, then)| (when, Box::new(f(*then))))
+        .collect();
+
+    let else_expr = else_expr.map(|else_expr| Box::new(f(*else_expr)));
+
+    Expr::Case(Case {
+        expr: None,
+        when_then_expr,
+        else_expr,
+    })
+}
+
 /// returns the column name for a column expression
 fn is_col(expr: &Expr) -> Option<&str> {
     match expr {
@@ -246,9 +335,28 @@ fn is_lit(expr: &Expr) -> bool {
     matches!(expr, Expr::Literal(_))
 }
 
-/// returns the column name for an expression like `col = <lit>`
+/// returns true if `name` is the name of a function comparing a string
+/// column with a string literal, like `equal_fold(col, <lit>)`
+fn is_string_comparison(name: &str) -> bool {
+    [
+        EQUAL_FOLD_UDF_NAME,
+        HAS_PREFIX_UDF_NAME,
+        HAS_SUFFIX_UDF_NAME,
+        CONTAINS_STR_UDF_NAME,
+    ]
+    .contains(&name)
+}
+
+/// returns the column name for an expression like `col = <lit>`,
+/// `col IN (<lit>, <lit>, ...)` or `equal_fold(col, <lit>)`
 fn is_col_op_lit(expr: &Expr) -> Option<&str> {
     match expr {
+        Expr::ScalarUDF(ScalarUDF { fun, args })
+            if is_string_comparison(&fun.name) && args.len() == 2 && is_lit(&args[1]) =>
+        {
+            is_col(&args[0])
+        }
+        Expr::InList(InList { expr, list, .. }) if list.iter().all(is_lit) => is_col(expr),
         Expr::BinaryExpr(BinaryExpr { left, op: _, right }) if is_lit(right) => is_col(left),
         Expr::BinaryExpr(BinaryExpr { left, op: _, right }) if is_lit(left) => is_col(right),
         Expr::Like(Like { expr, pattern, .. }) if is_lit(pattern) => is_col(expr),
@@ -293,6 +401,69 @@ mod tests {
         assert_eq!(expected, iox_expr_rewrite(expr).unwrap());
     }
 
+    #[test]
+    fn test_fold_case_in_list() {
+        // CASE WHEN tag IS NULL then '' ELSE tag END IN ('foo', 'bar')
+        let list = vec![lit("foo"), lit("bar")];
+        let expr =
+            make_case(col("tag").is_null(), lit(""), col("tag")).in_list(list.clone(), false);
+
+        // CASE WHEN tag IS NULL then '' IN ('foo', 'bar') ELSE tag IN ('foo', 'bar') END
+        let expected = make_case(
+            col("tag").is_null(),
+            lit("").in_list(list.clone(), false),
+            col("tag").in_list(list, false),
+        );
+
+        assert_eq!(expected, iox_expr_rewrite(expr).unwrap());
+    }
+
+    #[test]
+    fn test_fold_case_in_list_non_literal() {
+        // only lists of literals are inlined
+        let expr = make_case(col("tag").is_null(), lit(""), col("tag"))
+            .in_list(vec![lit("foo"), col("other")], false);
+
+        let expected = expr.clone();
+        assert_eq!(expected, iox_expr_rewrite(expr).unwrap());
+    }
+
+    #[test]
+    fn test_fold_case_equal_fold() {
+        // equal_fold(CASE WHEN tag IS NULL then '' ELSE tag END, 'Foo')
+        let expr = query_functions::equal_fold_expr(
+            make_case(col("tag").is_null(), lit(""), col("tag")),
+            "Foo".into(),
+        );
+
+        // CASE WHEN tag IS NULL then equal_fold('', 'Foo') ELSE equal_fold(tag, 'Foo') END
+        let expected = make_case(
+            col("tag").is_null(),
+            query_functions::equal_fold_expr(lit(""), "Foo".into()),
+            query_functions::equal_fold_expr(col("tag"), "Foo".into()),
+        );
+
+        assert_eq!(expected, iox_expr_rewrite(expr).unwrap());
+    }
+
+    #[test]
+    fn test_fold_case_has_prefix() {
+        // has_prefix(CASE WHEN tag IS NULL then '' ELSE tag END, 'Foo')
+        let expr = query_functions::has_prefix_expr(
+            make_case(col("tag").is_null(), lit(""), col("tag")),
+            "Foo".into(),
+        );
+
+        // CASE WHEN tag IS NULL then has_prefix('', 'Foo') ELSE has_prefix(tag, 'Foo') END
+        let expected = make_case(
+            col("tag").is_null(),
+            query_functions::has_prefix_expr(lit(""), "Foo".into()),
+            query_functions::has_prefix_expr(col("tag"), "Foo".into()),
+        );
+
+        assert_eq!(expected, iox_expr_rewrite(expr).unwrap());
+    }
+
     #[test]
     fn test_fold_case_basic_reversed() {
         // test with "foo" = CASE...
@@ -510,6 +681,17 @@ mod tests {
         assert_eq!(expected, simplify_predicate(expr).unwrap());
     }
 
+    #[test]
+    fn test_simplify_predicate_in_list() {
+        let list = vec![lit("bar"), lit("baz")];
+        let expr = col("foo")
+            .is_null()
+            .not()
+            .and(col("foo").in_list(list.clone(), false));
+        let expected = col("foo").in_list(list, false);
+        assert_eq!(expected, simplify_predicate(expr).unwrap());
+    }
+
     fn like(expr: Expr, pattern: Expr) -> Expr {
         let expr = Box::new(expr);
         let pattern = Box::new(pattern);
@@ -0,0 +1,294 @@
+//! Local disk tier of the [`ObjectStoreCache`](super::object_store::ObjectStoreCache).
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use bytes::Bytes;
+use metric::U64Counter;
+use object_store::{path::Path, ObjectMeta};
+use observability_deps::tracing::{debug, warn};
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+/// Configuration of the local disk tier of the object store cache.
+///
+/// Objects evicted from (or not yet loaded into) the RAM data cache are
+/// served from `directory` if present, instead of being fetched from object
+/// store again. This allows nodes with modest amounts of RAM to serve a much
+/// larger set of hot files from local (ideally NVMe) storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskCacheConfig {
+    /// Directory to store cached objects in.
+    ///
+    /// Any existing content is removed at startup.
+    pub directory: PathBuf,
+
+    /// Maximum number of bytes of objects stored in `directory`.
+    pub max_bytes: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    meta: ObjectMeta,
+    tick: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<Path, Entry>,
+
+    /// Cached paths ordered by last use, least recently used first.
+    lru: BTreeMap<u64, Path>,
+
+    used_bytes: u64,
+    next_tick: u64,
+}
+
+impl State {
+    /// Mark `path` as used, returning its metadata if it is cached.
+    fn touch(&mut self, path: &Path) -> Option<ObjectMeta> {
+        let tick = self.next_tick;
+        let entry = self.entries.get_mut(path)?;
+        self.lru.remove(&entry.tick);
+        self.lru.insert(tick, path.clone());
+        entry.tick = tick;
+        self.next_tick += 1;
+        Some(entry.meta.clone())
+    }
+
+    fn insert(&mut self, meta: ObjectMeta) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.used_bytes += meta.size as u64;
+        self.lru.insert(tick, meta.location.clone());
+        self.entries
+            .insert(meta.location.clone(), Entry { meta, tick });
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(entry) = self.entries.remove(path) {
+            self.lru.remove(&entry.tick);
+            self.used_bytes -= entry.meta.size as u64;
+        }
+    }
+
+    /// Remove the least recently used entries until at most `max_bytes` are
+    /// used, returning the removed paths.
+    fn evict(&mut self, max_bytes: u64) -> Vec<Path> {
+        let mut evicted = vec![];
+        while self.used_bytes > max_bytes {
+            let Some((_, path)) = self.lru.pop_first() else {
+                break;
+            };
+            let entry = self.entries.remove(&path).expect("LRU entry must exist");
+            self.used_bytes -= entry.meta.size as u64;
+            evicted.push(path);
+        }
+        evicted
+    }
+}
+
+/// Size-bounded, least-recently-used cache of immutable objects on local disk.
+///
+/// The index of cached objects is only held in memory, so the cache starts
+/// out empty after a restart. Objects are written to a temporary file and
+/// renamed into place before they are added to the index, so a cached object
+/// is never read partially written.
+#[derive(Debug)]
+pub struct DiskCache {
+    root: PathBuf,
+    max_bytes: u64,
+    state: Mutex<State>,
+
+    hit: U64Counter,
+    miss: U64Counter,
+}
+
+impl DiskCache {
+    /// Initialise the disk cache described by `config`, removing any content
+    /// left in its directory.
+    pub fn new(
+        config: DiskCacheConfig,
+        metric_registry: &metric::Registry,
+    ) -> std::io::Result<Self> {
+        let DiskCacheConfig {
+            directory,
+            max_bytes,
+        } = config;
+
+        match std::fs::remove_dir_all(&directory) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        std::fs::create_dir_all(&directory)?;
+
+        let requests = metric_registry.register_metric::<U64Counter>(
+            "cache_disk_requests",
+            "number of object store cache loads served from or missing in the local disk tier",
+        );
+
+        Ok(Self {
+            root: directory,
+            max_bytes,
+            state: Default::default(),
+            hit: requests.recorder(&[("result", "hit")]),
+            miss: requests.recorder(&[("result", "miss")]),
+        })
+    }
+
+    /// Read the object at `location` from disk, if cached.
+    pub(crate) async fn get(&self, location: &Path) -> Option<(ObjectMeta, Bytes)> {
+        let Some(meta) = self.state.lock().touch(location) else {
+            self.miss.inc(1);
+            return None;
+        };
+
+        match tokio::fs::read(self.file_path(location)).await {
+            Ok(data) if data.len() == meta.size => {
+                self.hit.inc(1);
+                Some((meta, Bytes::from(data)))
+            }
+            Ok(_) | Err(_) => {
+                // The file was evicted concurrently, or is damaged.
+                debug!(%location, "failed to read object from disk cache");
+                self.state.lock().remove(location);
+                self.miss.inc(1);
+                None
+            }
+        }
+    }
+
+    /// Store the object described by `meta` on disk, evicting the least
+    /// recently used objects if necessary.
+    ///
+    /// Failures are logged and otherwise ignored; the object is then simply
+    /// not cached.
+    pub(crate) async fn put(&self, meta: ObjectMeta, data: Bytes) {
+        if data.len() as u64 > self.max_bytes
+            || self.state.lock().entries.contains_key(&meta.location)
+        {
+            return;
+        }
+
+        let path = self.file_path(&meta.location);
+        let mut tmp = path.clone();
+        tmp.set_extension(format!("{}.tmp", Uuid::new_v4()));
+
+        let res = async {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&tmp, &data).await?;
+            tokio::fs::rename(&tmp, &path).await
+        }
+        .await;
+        if let Err(e) = res {
+            warn!(location=%meta.location, error=%e, "failed to write object to disk cache");
+            tokio::fs::remove_file(&tmp).await.ok();
+            return;
+        }
+
+        let evicted = {
+            let mut state = self.state.lock();
+            state.remove(&meta.location);
+            state.insert(meta);
+            state.evict(self.max_bytes)
+        };
+
+        for location in evicted {
+            if let Err(e) = tokio::fs::remove_file(self.file_path(&location)).await {
+                warn!(%location, error=%e, "failed to remove evicted object from disk cache");
+            }
+        }
+    }
+
+    fn file_path(&self, location: &Path) -> PathBuf {
+        self.root.join(location.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    fn meta(location: &str, size: usize) -> ObjectMeta {
+        ObjectMeta {
+            location: Path::from(location),
+            last_modified: Default::default(),
+            size,
+            e_tag: None,
+        }
+    }
+
+    fn requests(metric_registry: &metric::Registry, result: &'static str) -> u64 {
+        metric_registry
+            .get_instrument::<Metric<U64Counter>>("cache_disk_requests")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("result", result)]))
+            .unwrap()
+            .fetch()
+    }
+
+    #[tokio::test]
+    async fn test_get_put() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let metric_registry = metric::Registry::new();
+        let cache = DiskCache::new(
+            DiskCacheConfig {
+                directory: dir.path().to_owned(),
+                max_bytes: 10,
+            },
+            &metric_registry,
+        )
+        .unwrap();
+
+        let a = meta("ns/table/a.parquet", 4);
+        assert!(cache.get(&a.location).await.is_none());
+        assert_eq!(requests(&metric_registry, "miss"), 1);
+
+        cache.put(a.clone(), Bytes::from_static(b"aaaa")).await;
+        let (got_meta, got) = cache.get(&a.location).await.unwrap();
+        assert_eq!(got_meta, a);
+        assert_eq!(got, Bytes::from_static(b"aaaa"));
+        assert_eq!(requests(&metric_registry, "hit"), 1);
+
+        // Objects larger than the cache are not stored.
+        let big = meta("big", 11);
+        cache.put(big.clone(), Bytes::from(vec![0; 11])).await;
+        assert!(cache.get(&big.location).await.is_none());
+
+        // Adding "b" and "c" exceeds the limit, evicting the least recently
+        // used object.
+        let b = meta("ns/table/b.parquet", 4);
+        cache.put(b.clone(), Bytes::from_static(b"bbbb")).await;
+        assert!(cache.get(&a.location).await.is_some());
+        let c = meta("ns/table/c.parquet", 4);
+        cache.put(c.clone(), Bytes::from_static(b"cccc")).await;
+
+        assert!(cache.get(&a.location).await.is_some());
+        assert!(cache.get(&b.location).await.is_none());
+        assert!(cache.get(&c.location).await.is_some());
+        assert!(!dir.path().join("ns/table/b.parquet").exists());
+    }
+
+    #[tokio::test]
+    async fn test_new_clears_directory() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        std::fs::write(dir.path().join("stale"), b"bananas").unwrap();
+
+        let _cache = DiskCache::new(
+            DiskCacheConfig {
+                directory: dir.path().to_owned(),
+                max_bytes: 10,
+            },
+            &metric::Registry::new(),
+        )
+        .unwrap();
+
+        assert!(!dir.path().join("stale").exists());
+    }
+}
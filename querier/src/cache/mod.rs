@@ -1,28 +1,53 @@
 //! Caches used by the querier.
-use ::object_store::ObjectStore;
+use ::object_store::{limit::LimitStore, ObjectStore};
 use ::parquet_file::storage::{ParquetStorage, StorageId};
 use backoff::BackoffConfig;
 use cache_system::backend::policy::lru::ResourcePool;
+use data_types::ParquetFile;
 use iox_catalog::interface::Catalog;
 use iox_time::TimeProvider;
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 
 use self::{
-    namespace::NamespaceCache, object_store::ObjectStoreCache, parquet_file::ParquetFileCache,
-    partition::PartitionCache, projected_schema::ProjectedSchemaCache, ram::RamSize,
+    disk::{DiskCache, DiskCacheConfig},
+    namespace::NamespaceCache,
+    object_store::ObjectStoreCache,
+    parquet_file::ParquetFileCache,
+    partition::PartitionCache,
+    projected_schema::ProjectedSchemaCache,
+    ram::RamSize,
+    tag_index::TagIndexCache,
 };
 
+pub mod disk;
 pub mod namespace;
 pub mod object_store;
 pub mod parquet_file;
 pub mod partition;
 pub mod projected_schema;
 mod ram;
+pub mod tag_index;
 
 #[cfg(test)]
 pub(crate) mod test_util;
 
+/// Configuration for reading "cold" data.
+///
+/// Parquet files that only contain data older than `threshold` are read
+/// directly from object store instead of through the RAM data cache, so that
+/// queries over old data neither evict recent data from it nor are limited by
+/// its size. At most `max_concurrent_reads` object store requests for cold
+/// data are in flight at any one time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColdDataConfig {
+    /// Age of the newest data in a file for the file to be considered cold.
+    pub threshold: Duration,
+
+    /// Limit of concurrent object store requests for cold files.
+    pub max_concurrent_reads: NonZeroUsize,
+}
+
 /// Caches request to the [`Catalog`].
 #[derive(Debug)]
 pub struct CatalogCache {
@@ -44,6 +69,13 @@ pub struct CatalogCache {
     /// Object store cache.
     object_store_cache: ObjectStoreCache,
 
+    /// Tag index cache.
+    tag_index_cache: TagIndexCache,
+
+    /// Threshold and parquet store (bypassing [`ObjectStoreCache`]) for cold
+    /// files, if configured.
+    cold_data: Option<(Duration, ParquetStorage)>,
+
     /// Metric registry
     metric_registry: Arc<metric::Registry>,
 
@@ -53,6 +85,11 @@ pub struct CatalogCache {
 
 impl CatalogCache {
     /// Create empty cache.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the `disk_cache` directory cannot be initialised.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         catalog: Arc<dyn Catalog>,
         time_provider: Arc<dyn TimeProvider>,
@@ -60,6 +97,8 @@ impl CatalogCache {
         object_store: Arc<dyn ObjectStore>,
         ram_pool_metadata_bytes: usize,
         ram_pool_data_bytes: usize,
+        cold_data: Option<ColdDataConfig>,
+        disk_cache: Option<DiskCacheConfig>,
         handle: &Handle,
     ) -> Self {
         Self::new_internal(
@@ -69,6 +108,8 @@ impl CatalogCache {
             object_store,
             ram_pool_metadata_bytes,
             ram_pool_data_bytes,
+            cold_data,
+            disk_cache,
             handle,
             false,
         )
@@ -91,6 +132,8 @@ impl CatalogCache {
             object_store,
             usize::MAX,
             usize::MAX,
+            None,
+            None,
             handle,
             true,
         )
@@ -104,11 +147,24 @@ impl CatalogCache {
         object_store: Arc<dyn ObjectStore>,
         ram_pool_metadata_bytes: usize,
         ram_pool_data_bytes: usize,
+        cold_data: Option<ColdDataConfig>,
+        disk_cache: Option<DiskCacheConfig>,
         handle: &Handle,
         testing: bool,
     ) -> Self {
         let backoff_config = BackoffConfig::default();
 
+        let cold_data = cold_data.map(|config| {
+            let object_store: Arc<dyn ObjectStore> = Arc::new(LimitStore::new(
+                Arc::clone(&object_store),
+                config.max_concurrent_reads.get(),
+            ));
+            (
+                config.threshold,
+                ParquetStorage::new(object_store, StorageId::from("iox_cold")),
+            )
+        });
+
         let ram_pool_metadata = Arc::new(ResourcePool::new(
             "ram_metadata",
             RamSize(ram_pool_metadata_bytes),
@@ -153,12 +209,25 @@ impl CatalogCache {
             Arc::clone(&ram_pool_metadata),
             testing,
         );
+        let tag_index_cache = TagIndexCache::new(
+            Arc::clone(&time_provider),
+            &metric_registry,
+            Arc::clone(&ram_pool_metadata),
+            testing,
+        );
+        let disk_cache = disk_cache.map(|config| {
+            Arc::new(
+                DiskCache::new(config, &metric_registry)
+                    .expect("failed to initialise disk cache directory"),
+            )
+        });
         let object_store_cache = ObjectStoreCache::new(
             backoff_config,
             object_store,
             Arc::clone(&time_provider),
             &metric_registry,
             Arc::clone(&ram_pool_data),
+            disk_cache,
             handle,
             testing,
         );
@@ -170,6 +239,8 @@ impl CatalogCache {
             parquet_file_cache,
             projected_schema_cache,
             object_store_cache,
+            tag_index_cache,
+            cold_data,
             metric_registry,
             time_provider,
         }
@@ -210,6 +281,11 @@ impl CatalogCache {
         &self.projected_schema_cache
     }
 
+    /// Tag index cache.
+    pub(crate) fn tag_index(&self) -> &TagIndexCache {
+        &self.tag_index_cache
+    }
+
     /// Parquet store that points to the cached object store.
     pub fn parquet_store(&self) -> ParquetStorage {
         ParquetStorage::new(
@@ -217,4 +293,91 @@ impl CatalogCache {
             StorageId::from("iox_cached"),
         )
     }
+
+    /// Parquet store that points to the uncached object store used for cold
+    /// files, if configured.
+    pub fn cold_parquet_store(&self) -> Option<ParquetStorage> {
+        self.cold_data.as_ref().map(|(_, store)| store.clone())
+    }
+
+    /// Parquet store to read `file` from.
+    ///
+    /// This is the [cold parquet store](Self::cold_parquet_store) if the
+    /// newest data in `file` is older than the configured threshold, and the
+    /// [cached one](Self::parquet_store) otherwise.
+    pub(crate) fn parquet_store_for(&self, file: &ParquetFile) -> ParquetStorage {
+        match &self.cold_data {
+            Some((threshold, store)) if self.is_cold(file, *threshold) => store.clone(),
+            _ => self.parquet_store(),
+        }
+    }
+
+    fn is_cold(&self, file: &ParquetFile, threshold: Duration) -> bool {
+        match self.time_provider.now().checked_sub(threshold) {
+            Some(cutoff) => file.max_time.get() < cutoff.timestamp_nanos(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::{ParquetFileBuilder, TestCatalog};
+    use iox_time::Time;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cold_data() {
+        let catalog = TestCatalog::new();
+        catalog
+            .mock_time_provider()
+            .set(Time::from_timestamp(2 * 3600, 0).unwrap());
+
+        let cache = CatalogCache::new_internal(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            usize::MAX,
+            usize::MAX,
+            Some(ColdDataConfig {
+                threshold: Duration::from_secs(3600),
+                max_concurrent_reads: NonZeroUsize::new(1).unwrap(),
+            }),
+            None,
+            &Handle::current(),
+            true,
+        );
+
+        let cold_file = ParquetFileBuilder::new(1)
+            .with_time_range(0, 3600 * 1_000_000_000 - 1)
+            .build();
+        let hot_file = ParquetFileBuilder::new(2)
+            .with_time_range(0, 3600 * 1_000_000_000)
+            .build();
+
+        assert_eq!(
+            cache.parquet_store_for(&cold_file).id(),
+            StorageId::from("iox_cold")
+        );
+        assert_eq!(
+            cache.parquet_store_for(&hot_file).id(),
+            cache.parquet_store().id()
+        );
+
+        // Without a threshold everything is read through the cache.
+        let cache = CatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            Arc::new(metric::Registry::new()),
+            catalog.object_store(),
+            &Handle::current(),
+        );
+        assert!(cache.cold_parquet_store().is_none());
+        assert_eq!(
+            cache.parquet_store_for(&cold_file).id(),
+            cache.parquet_store().id()
+        );
+    }
 }
@@ -14,8 +14,8 @@ use cache_system::{
     resource_consumption::FunctionEstimator,
 };
 use data_types::{
-    partition_template::TablePartitionTemplateOverride, Column, ColumnId, Namespace, NamespaceId,
-    Table, TableId,
+    partition_template::TablePartitionTemplateOverride, Column, ColumnDrop, ColumnId, ColumnRename,
+    Namespace, NamespaceId, Table, TableId,
 };
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use iox_time::TimeProvider;
@@ -127,7 +127,33 @@ impl NamespaceCache {
                     .await
                     .expect("retry forever");
 
-                Some(Arc::new(CachedNamespace::new(namespace, tables, columns)))
+                let renames = Backoff::new(&backoff_config)
+                    .retry_all_errors("get namespace column renames", || async {
+                        catalog
+                            .repositories()
+                            .await
+                            .columns()
+                            .list_renames_by_namespace_id(namespace.id)
+                            .await
+                    })
+                    .await
+                    .expect("retry forever");
+
+                let drops = Backoff::new(&backoff_config)
+                    .retry_all_errors("get namespace column drops", || async {
+                        catalog
+                            .repositories()
+                            .await
+                            .columns()
+                            .list_drops_by_namespace_id(namespace.id)
+                            .await
+                    })
+                    .await
+                    .expect("retry forever");
+
+                Some(Arc::new(CachedNamespace::new(
+                    namespace, tables, columns, renames, drops,
+                )))
             }
         });
         let loader = Arc::new(MetricsLoader::new(
@@ -209,9 +235,10 @@ impl NamespaceCache {
                     if let Some(namespace) = cached_namespace.as_ref() {
                         should_cover.iter().any(|(table_name, columns)| {
                             if let Some(table) = namespace.tables.get(*table_name) {
-                                columns
-                                    .iter()
-                                    .any(|col| !table.column_id_map.contains_key(col))
+                                columns.iter().any(|col| {
+                                    !table.column_id_map.contains_key(col)
+                                        && !table.dropped_column_ids.contains(col)
+                                })
                             } else {
                                 // table unknown => need to update
                                 true
@@ -236,10 +263,22 @@ pub struct CachedTable {
     pub column_id_map_rev: HashMap<Arc<str>, ColumnId>,
     pub primary_key_column_ids: Box<[ColumnId]>,
     pub partition_template: TablePartitionTemplateOverride,
+    /// Renames of the columns, needed to read parquet files written before a rename.
+    pub column_renames: Box<[ColumnRename]>,
+    /// IDs of dropped columns, which may still be referenced by parquet files.
+    pub dropped_column_ids: HashSet<ColumnId>,
+    /// Retention period of the table, expiring its data faster than the
+    /// retention period of the namespace.
+    pub retention_period: Option<Duration>,
 }
 
 impl CachedTable {
-    fn new(table: Table, mut columns: Vec<Column>) -> Self {
+    fn new(
+        table: Table,
+        mut columns: Vec<Column>,
+        column_renames: Vec<ColumnRename>,
+        drops: Vec<ColumnDrop>,
+    ) -> Self {
         // sort columns by name so that schema is normalized
         // Note: `sort_by_key` doesn't work if we don't wanna clone the strings every time
         columns.sort_by(|x, y| x.name.cmp(&y.name));
@@ -280,9 +319,32 @@ impl CachedTable {
             column_id_map_rev,
             primary_key_column_ids,
             partition_template: table.partition_template,
+            column_renames: column_renames.into(),
+            dropped_column_ids: drops.into_iter().map(|d| d.column_id).collect(),
+            retention_period: table
+                .retention_period_ns
+                .map(|retention| Duration::from_nanos(retention as u64)),
         }
     }
 
+    /// Returns the current name of the column that is (or was) called `name`, e.g. in data the ingester buffered
+    /// before the column was renamed.
+    ///
+    /// A column that currently has the name takes precedence over a column that had it before a rename. Returns
+    /// `None` for names of dropped and unknown columns.
+    pub fn resolve_column_name(&self, name: &str) -> Option<&Arc<str>> {
+        if let Some((name, _id)) = self.column_id_map_rev.get_key_value(name) {
+            return Some(name);
+        }
+
+        let rename = self
+            .column_renames
+            .iter()
+            .filter(|rename| rename.old_name == name)
+            .max_by_key(|rename| rename.renamed_at)?;
+        self.column_id_map.get(&rename.column_id)
+    }
+
     /// RAM-bytes EXCLUDING `self`.
     fn size(&self) -> usize {
         self.schema.estimate_size()
@@ -300,6 +362,12 @@ impl CachedTable {
                 .sum::<usize>()
             + (self.primary_key_column_ids.len() * size_of::<ColumnId>())
             + (self.partition_template.size() - size_of::<TablePartitionTemplateOverride>())
+            + self
+                .column_renames
+                .iter()
+                .map(|r| size_of::<ColumnRename>() + r.old_name.len() + r.new_name.len())
+                .sum::<usize>()
+            + (self.dropped_column_ids.capacity() * size_of::<ColumnId>())
     }
 }
 
@@ -311,22 +379,38 @@ pub struct CachedNamespace {
 }
 
 impl CachedNamespace {
-    pub fn new(namespace: Namespace, tables: Vec<Table>, columns: Vec<Column>) -> Self {
+    pub fn new(
+        namespace: Namespace,
+        tables: Vec<Table>,
+        columns: Vec<Column>,
+        renames: Vec<ColumnRename>,
+        drops: Vec<ColumnDrop>,
+    ) -> Self {
         let mut tables_by_id = tables
             .into_iter()
-            .map(|t| (t.id, (t, vec![])))
+            .map(|t| (t.id, (t, vec![], vec![], vec![])))
             .collect::<HashMap<_, _>>();
         for col in columns {
-            if let Some((_t, tcols)) = tables_by_id.get_mut(&col.table_id) {
+            if let Some((_t, tcols, _, _)) = tables_by_id.get_mut(&col.table_id) {
                 tcols.push(col);
             }
         }
+        for rename in renames {
+            if let Some((_t, _, trenames, _)) = tables_by_id.get_mut(&rename.table_id) {
+                trenames.push(rename);
+            }
+        }
+        for drop in drops {
+            if let Some((_t, _, _, tdrops)) = tables_by_id.get_mut(&drop.table_id) {
+                tdrops.push(drop);
+            }
+        }
 
         let mut tables: HashMap<Arc<str>, Arc<CachedTable>> = tables_by_id
             .into_iter()
-            .map(|(_tid, (t, tcols))| {
+            .map(|(_tid, (t, tcols, trenames, tdrops))| {
                 let name = Arc::from(t.name.clone());
-                let table = Arc::new(CachedTable::new(t, tcols));
+                let table = Arc::new(CachedTable::new(t, tcols, trenames, tdrops));
                 (name, table)
             })
             .collect();
@@ -442,6 +526,9 @@ mod tests {
                         ]),
                         primary_key_column_ids: [col112.column.id, col113.column.id].into(),
                         partition_template: table11.table.partition_template.clone(),
+                        column_renames: Default::default(),
+                        dropped_column_ids: Default::default(),
+                        retention_period: None,
                     }),
                 ),
                 (
@@ -464,6 +551,9 @@ mod tests {
                         ]),
                         primary_key_column_ids: [col122.column.id].into(),
                         partition_template: TablePartitionTemplateOverride::default(),
+                        column_renames: Default::default(),
+                        dropped_column_ids: Default::default(),
+                        retention_period: None,
                     }),
                 ),
             ]),
@@ -497,6 +587,9 @@ mod tests {
                     )]),
                     primary_key_column_ids: [col211.column.id].into(),
                     partition_template: TablePartitionTemplateOverride::default(),
+                    column_renames: Default::default(),
+                    dropped_column_ids: Default::default(),
+                    retention_period: None,
                 }),
             )]),
         };
@@ -23,7 +23,7 @@ use observability_deps::tracing::warn;
 use tokio::{io::AsyncWrite, runtime::Handle, sync::oneshot::channel, task::JoinSet};
 use trace::span::Span;
 
-use super::ram::RamSize;
+use super::{disk::DiskCache, ram::RamSize};
 
 const CACHE_ID: &str = "object_store";
 
@@ -94,6 +94,9 @@ type CacheT = Arc<
 ///
 /// ["Not found"](ObjectStoreError::NotFound) results are cached forever, so make sure to only retrieve objects that
 /// shall exist.
+///
+/// If a [`DiskCache`] is provided, objects that are not in RAM are read from it before falling back to the object
+/// store, and objects fetched from the object store are added to it.
 #[derive(Debug)]
 pub struct ObjectStoreCache {
     // this is the virtual object store
@@ -108,6 +111,7 @@ impl ObjectStoreCache {
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: &metric::Registry,
         ram_pool: Arc<ResourcePool<RamSize>>,
+        disk_cache: Option<Arc<DiskCache>>,
         handle: &Handle,
         testing: bool,
     ) -> Self {
@@ -115,9 +119,16 @@ impl ObjectStoreCache {
         let loader = FunctionLoader::new(move |key: Path, _extra: ()| {
             let backoff_config = backoff_config.clone();
             let object_store = Arc::clone(&object_store_captured);
+            let disk_cache = disk_cache.clone();
 
             async move {
-                Backoff::new(&backoff_config)
+                if let Some(disk_cache) = &disk_cache {
+                    if let Some((meta, bytes)) = disk_cache.get(&key).await {
+                        return Some(CachedRead { bytes, meta });
+                    }
+                }
+
+                let data = Backoff::new(&backoff_config)
                     .retry_all_errors::<_, _, _, ObjectStoreError>(
                         "get object from object store",
                         || async {
@@ -127,7 +138,13 @@ impl ObjectStoreCache {
                         },
                     )
                     .await
-                    .expect("retry forever")
+                    .expect("retry forever");
+
+                if let (Some(disk_cache), Some(data)) = (&disk_cache, &data) {
+                    disk_cache.put(data.meta.clone(), data.bytes.clone()).await;
+                }
+
+                data
             }
         });
         let loader = Arc::new(MetricsLoader::new(
@@ -362,7 +379,7 @@ mod tests {
     use object_store::memory::InMemory;
     use object_store_metrics::ObjectStoreMetrics;
 
-    use crate::cache::ram::test_util::test_ram_pool;
+    use crate::cache::{disk::DiskCacheConfig, ram::test_util::test_ram_pool};
 
     use super::*;
 
@@ -400,6 +417,7 @@ mod tests {
             time_provider,
             &metric_registry,
             test_ram_pool(),
+            None,
             &Handle::current(),
             true,
         );
@@ -464,6 +482,52 @@ mod tests {
         assert_eq!(get_count_miss(&metric_registry), 1);
     }
 
+    #[tokio::test]
+    async fn test_disk_cache() {
+        let inner = Arc::new(InMemory::new());
+        let path = Path::from("foo");
+        let bytes = Bytes::from(b"data_foo" as &'static [u8]);
+        inner.put(&path, bytes.clone()).await.unwrap();
+
+        let dir = test_helpers::tmp_dir().unwrap();
+        let metric_registry = metric::Registry::new();
+        let disk_cache = Arc::new(
+            DiskCache::new(
+                DiskCacheConfig {
+                    directory: dir.path().to_owned(),
+                    max_bytes: 1024,
+                },
+                &metric_registry,
+            )
+            .unwrap(),
+        );
+        let new_cache = || {
+            ObjectStoreCache::new(
+                BackoffConfig::default(),
+                Arc::clone(&inner) as _,
+                Arc::new(SystemProvider::new()),
+                &metric_registry,
+                test_ram_pool(),
+                Some(Arc::clone(&disk_cache)),
+                &Handle::current(),
+                true,
+            )
+        };
+
+        // the first read is fetched from the object store and written to disk
+        let cache = new_cache();
+        let got = cache.object_store().get(&path).await.unwrap();
+        assert_eq!(got.bytes().await.unwrap(), bytes);
+
+        // a cache with empty RAM pool is served from disk, even once the object
+        // is gone from the object store
+        inner.delete(&path).await.unwrap();
+        let cache = new_cache();
+        let got = cache.object_store().get(&path).await.unwrap();
+        assert_eq!(got.meta.location, path);
+        assert_eq!(got.bytes().await.unwrap(), bytes);
+    }
+
     fn get_count_hit(metric_registry: &metric::Registry) -> u64 {
         metric_registry
             .get_instrument::<Metric<DurationHistogram>>("object_store_op_duration")
@@ -503,6 +503,8 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            column_renames: Default::default(),
+            dropped_column_ids: Default::default(),
         });
 
         let cache = PartitionCache::new(
@@ -662,6 +664,8 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id, c3.column.id, c4.column.id].into(),
             partition_template: t.table.partition_template.clone(),
+            column_renames: Default::default(),
+            dropped_column_ids: Default::default(),
         });
 
         let cache = PartitionCache::new(
@@ -913,6 +917,8 @@ mod tests {
             column_id_map_rev: HashMap::from([(Arc::from(c.column.name.clone()), c.column.id)]),
             primary_key_column_ids: [c.column.id].into(),
             partition_template: t.table.partition_template.clone(),
+            column_renames: Default::default(),
+            dropped_column_ids: Default::default(),
         });
 
         let cache = PartitionCache::new(
@@ -978,6 +984,8 @@ mod tests {
             column_id_map_rev: HashMap::from([(Arc::from(c.column.name.clone()), c.column.id)]),
             primary_key_column_ids: [c.column.id].into(),
             partition_template: t.table.partition_template.clone(),
+            column_renames: Default::default(),
+            dropped_column_ids: Default::default(),
         });
 
         let cache = PartitionCache::new(
@@ -1079,6 +1087,8 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            column_renames: Default::default(),
+            dropped_column_ids: Default::default(),
         });
 
         let cache = PartitionCache::new(
@@ -1227,6 +1237,8 @@ mod tests {
             column_id_map_rev: HashMap::default(),
             primary_key_column_ids: [].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            column_renames: Default::default(),
+            dropped_column_ids: Default::default(),
         });
 
         let cache = PartitionCache::new(
@@ -1458,6 +1470,8 @@ mod tests {
                 column_id_map_rev: HashMap::from([(Arc::from(c.column.name.clone()), c.column.id)]),
                 primary_key_column_ids: [c.column.id].into(),
                 partition_template: TablePartitionTemplateOverride::default(),
+                column_renames: Default::default(),
+                dropped_column_ids: Default::default(),
             });
             const N_PARTITIONS: usize = 20;
             let c_id = c.column.id.get();
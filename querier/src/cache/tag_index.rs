@@ -0,0 +1,176 @@
+//! Cache for the tag indexes of parquet files.
+//!
+//! Parquet files are immutable, so the [`TagIndex`] of a file never changes once it was built. New files (e.g.
+//! persisted by an ingester or created by the compactor) are indexed the first time they are part of a metadata
+//! query, so the indexes of a partition are updated incrementally.
+use std::{mem::size_of_val, sync::Arc};
+
+use cache_system::{
+    backend::policy::{
+        lru::{LruPolicy, ResourcePool},
+        PolicyBackend,
+    },
+    cache::{driver::CacheDriver, metrics::CacheWithMetrics, Cache},
+    loader::{metrics::MetricsLoader, FunctionLoader},
+    resource_consumption::FunctionEstimator,
+};
+use data_types::ParquetFileId;
+use iox_query::tag_index::TagIndex;
+use iox_time::TimeProvider;
+use observability_deps::tracing::warn;
+use parquet_file::{chunk::ParquetChunk, ParquetFilePath};
+use trace::span::Span;
+
+use super::ram::RamSize;
+
+const CACHE_ID: &str = "tag_index";
+
+type CacheT = Box<
+    dyn Cache<
+        K = ParquetFileId,
+        V = Option<Arc<TagIndex>>,
+        GetExtra = (Arc<ParquetChunk>, Option<Span>),
+        PeekExtra = ((), Option<Span>),
+    >,
+>;
+
+/// Cache for the [`TagIndex`] of parquet files.
+///
+/// The index of a file is built by reading its tag columns. Files that cannot be read are not indexed, so that
+/// queries scan them instead.
+#[derive(Debug)]
+pub struct TagIndexCache {
+    cache: CacheT,
+}
+
+impl TagIndexCache {
+    /// Create new empty cache.
+    pub fn new(
+        time_provider: Arc<dyn TimeProvider>,
+        metric_registry: &metric::Registry,
+        ram_pool: Arc<ResourcePool<RamSize>>,
+        testing: bool,
+    ) -> Self {
+        let loader = FunctionLoader::new(
+            move |file_id: ParquetFileId, chunk: Arc<ParquetChunk>| async move {
+                assert_eq!(file_id, chunk.parquet_file().id);
+
+                let tags = chunk
+                    .schema()
+                    .tags_iter()
+                    .map(|f| f.name().as_str())
+                    .collect::<Vec<_>>();
+                let path = ParquetFilePath::from(chunk.parquet_file().as_ref());
+
+                match chunk.store().read_columns(&path, &tags).await {
+                    Ok(batches) => Some(Arc::new(TagIndex::from_batches(chunk.schema(), &batches))),
+                    Err(e) => {
+                        warn!(
+                            %file_id,
+                            object_store_id=%chunk.object_store_id(),
+                            %e,
+                            "failed to read tag columns of parquet file, not indexing it",
+                        );
+                        None
+                    }
+                }
+            },
+        );
+        let loader = Arc::new(MetricsLoader::new(
+            loader,
+            CACHE_ID,
+            Arc::clone(&time_provider),
+            metric_registry,
+            testing,
+        ));
+
+        // add to memory pool
+        let mut backend = PolicyBackend::hashmap_backed(Arc::clone(&time_provider));
+        backend.add_policy(LruPolicy::new(
+            Arc::clone(&ram_pool),
+            CACHE_ID,
+            Arc::new(FunctionEstimator::new(
+                |k: &ParquetFileId, v: &Option<Arc<TagIndex>>| {
+                    RamSize(
+                        size_of_val(k)
+                            + size_of_val(v)
+                            + v.as_ref().map(|v| v.size()).unwrap_or_default(),
+                    )
+                },
+            )),
+        ));
+
+        let cache = CacheDriver::new(loader, backend);
+        let cache = Box::new(CacheWithMetrics::new(
+            cache,
+            CACHE_ID,
+            time_provider,
+            metric_registry,
+        ));
+
+        Self { cache }
+    }
+
+    /// Get the tag index of the parquet file backing `chunk`, building it if it is not cached yet.
+    ///
+    /// The index uses the column names of `chunk`, so the columns of the file MUST NOT have been renamed since it
+    /// was written.
+    pub async fn get(&self, chunk: Arc<ParquetChunk>, span: Option<Span>) -> Option<Arc<TagIndex>> {
+        self.cache.get(chunk.parquet_file().id, (chunk, span)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::ColumnType;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use parquet_file::storage::{ParquetStorage, StorageId};
+
+    use crate::cache::ram::test_util::test_ram_pool;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+        table.create_column("tag1", ColumnType::Tag).await;
+        table.create_column("tag2", ColumnType::Tag).await;
+        table.create_column("field", ColumnType::I64).await;
+        table.create_column("time", ColumnType::Time).await;
+        let partition = table.create_partition("part").await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table,tag1=a field=1i 1\ntable,tag1=b,tag2=c field=2i 2");
+        let parquet_file = Arc::new(partition.create_parquet_file(builder).await.parquet_file);
+        let schema = table.schema().await;
+        let store = ParquetStorage::new(catalog.object_store(), StorageId::from("iox"));
+        let chunk = Arc::new(ParquetChunk::new(parquet_file, schema, store));
+
+        let cache = TagIndexCache::new(
+            catalog.time_provider(),
+            &catalog.metric_registry(),
+            test_ram_pool(),
+            true,
+        );
+
+        let tag_index = cache.get(Arc::clone(&chunk), None).await.unwrap();
+        assert_eq!(
+            tag_index.tag_keys().collect::<Vec<_>>(),
+            vec!["tag1", "tag2"]
+        );
+        assert_eq!(
+            tag_index
+                .tag_values("tag1")
+                .unwrap()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+
+        // the second request is served from the cache
+        let tag_index_2 = cache.get(chunk, None).await.unwrap();
+        assert!(Arc::ptr_eq(&tag_index, &tag_index_2));
+    }
+}
@@ -19,6 +19,7 @@ use snafu::Snafu;
 use std::{
     collections::{HashMap, VecDeque},
     sync::Arc,
+    time::Duration,
 };
 use trace::span::{Span, SpanRecorder};
 use tracker::{
@@ -150,6 +151,33 @@ impl QuerierDatabase {
         })
     }
 
+    /// Log queries taking at least `threshold` to complete, retaining a
+    /// summary of their plans in the `system.slow_queries` table.
+    pub fn with_slow_query_threshold(self, threshold: Option<Duration>) -> Self {
+        let query_log = QueryLog::new(QUERY_LOG_SIZE, self.catalog_cache.time_provider())
+            .with_slow_query_threshold(threshold);
+
+        Self {
+            query_log: Arc::new(query_log),
+            ..self
+        }
+    }
+
+    /// Verify the content of parquet files against the hash recorded in the
+    /// catalog before reading them.
+    pub fn with_checksum_verification(self, enabled: bool) -> Self {
+        let chunk_adapter = ChunkAdapter::new(
+            Arc::clone(&self.catalog_cache),
+            self.chunk_adapter.metric_registry(),
+        )
+        .with_checksum_verification(enabled);
+
+        Self {
+            chunk_adapter: Arc::new(chunk_adapter),
+            ..self
+        }
+    }
+
     /// Get namespace if it exists.
     ///
     /// This will await the internal namespace semaphore. Existence of namespaces is checked AFTER
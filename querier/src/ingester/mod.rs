@@ -6,12 +6,16 @@ use data_types::{ChunkId, ChunkOrder, NamespaceId, TransitionPartitionId};
 use datafusion::{physical_plan::Statistics, prelude::Expr};
 use iox_query::{
     chunk_statistics::{create_chunk_statistics, ColumnRanges},
+    tag_index::TagIndex,
     util::compute_timenanosecond_min_max,
     QueryChunk, QueryChunkData,
 };
 use observability_deps::tracing::trace;
 use schema::{sort::SortKey, Schema};
-use std::{any::Any, sync::Arc};
+use std::{
+    any::Any,
+    sync::{Arc, OnceLock},
+};
 use trace::span::Span;
 use uuid::Uuid;
 
@@ -45,12 +49,16 @@ pub type DynError = Box<dyn std::error::Error + Send + Sync>;
 #[async_trait]
 pub trait IngesterConnection: std::fmt::Debug + Send + Sync + 'static {
     /// Returns all partitions ingester(s) know about for the specified table.
+    ///
+    /// If `last_values` is true, the partitions contain the values held in the last value cache
+    /// of the ingesters instead of their buffered data.
     async fn partitions(
         &self,
         namespace_id: NamespaceId,
         cached_table: Arc<CachedTable>,
         columns: Vec<String>,
         filters: &[Expr],
+        last_values: bool,
         span: Option<Span>,
     ) -> Result<Vec<IngesterPartition>, DynError>;
 
@@ -114,6 +122,7 @@ impl IngesterPartition {
             schema,
             batches,
             stats: None,
+            tag_index: OnceLock::new(),
         };
 
         self.chunks.push(chunk);
@@ -132,13 +141,29 @@ impl IngesterPartition {
                 .iter()
                 .map(|batch| batch.num_rows())
                 .sum::<usize>() as u64;
-            let stats = Arc::new(create_chunk_statistics(
+            let mut stats = create_chunk_statistics(
                 row_count,
                 &chunk.schema,
                 Some(ts_min_max),
                 partition_column_ranges,
-            ));
-            chunk.stats = Some(stats);
+            );
+
+            // The null counts of the buffered data are cheap to compute exactly.
+            if let Some(column_statistics) = stats.column_statistics.as_mut() {
+                for ((_, field), column) in chunk.schema.iter().zip(column_statistics) {
+                    let null_count = chunk
+                        .batches
+                        .iter()
+                        .map(|batch| match batch.column_by_name(field.name()) {
+                            Some(array) => array.null_count(),
+                            None => batch.num_rows(),
+                        })
+                        .sum();
+                    column.null_count = Some(null_count);
+                }
+            }
+
+            chunk.stats = Some(Arc::new(stats));
         }
     }
 
@@ -178,6 +203,11 @@ pub struct IngesterChunk {
     ///
     /// Set to `None` if not calculated yet.
     stats: Option<Arc<Statistics>>,
+
+    /// Index of the tag values of `batches`.
+    ///
+    /// Built on first use, since most queries do not need it.
+    tag_index: OnceLock<Arc<TagIndex>>,
 }
 
 impl IngesterChunk {
@@ -234,6 +264,13 @@ impl QueryChunk for IngesterChunk {
         QueryChunkData::in_mem(self.batches.clone(), Arc::clone(self.schema.inner()))
     }
 
+    fn tag_index(&self) -> Option<Arc<TagIndex>> {
+        let tag_index = self
+            .tag_index
+            .get_or_init(|| Arc::new(TagIndex::from_batches(&self.schema, &self.batches)));
+        Some(Arc::clone(tag_index))
+    }
+
     fn chunk_type(&self) -> &str {
         "IngesterPartition"
     }
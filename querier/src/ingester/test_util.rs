@@ -35,6 +35,7 @@ impl IngesterConnection for MockIngesterConnection {
         _cached_table: Arc<CachedTable>,
         columns: Vec<String>,
         _filters: &[Expr],
+        _last_values: bool,
         _span: Option<Span>,
     ) -> Result<Vec<super::IngesterPartition>, DynError> {
         let Some(partitions) = self.next_response.lock().take() else {
@@ -74,6 +75,7 @@ impl IngesterConnection for MockIngesterConnection {
                         super::IngesterChunk {
                             batches,
                             schema,
+                            tag_index: Default::default(),
                             ..ic
                         }
                     })
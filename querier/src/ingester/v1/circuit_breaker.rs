@@ -1256,6 +1256,7 @@ mod tests {
             table_id: TableId::new(0),
             columns: vec![],
             predicate: None,
+            last_values: false,
         }
     }
 
@@ -12,6 +12,7 @@ use arrow::{
     record_batch::RecordBatch,
 };
 use arrow_flight::decode::DecodedPayload;
+use arrow_util::util::rename_columns;
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig, BackoffError};
 use client_util::connection;
@@ -371,6 +372,7 @@ struct GetPartitionForIngester<'a> {
     namespace_id: NamespaceId,
     columns: Vec<String>,
     filters: &'a [Expr],
+    last_values: bool,
     cached_table: Arc<CachedTable>,
 }
 
@@ -386,6 +388,7 @@ async fn execute(
         namespace_id,
         columns,
         filters,
+        last_values,
         cached_table,
     } = request;
 
@@ -394,6 +397,7 @@ async fn execute(
         table_id: cached_table.id,
         columns: columns.clone(),
         predicate: Some(Predicate::default().with_exprs(filters.iter().cloned())),
+        last_values,
     };
 
     let query_res = {
@@ -488,6 +492,9 @@ struct IngesterStreamDecoder {
     finished_partitions: HashMap<TransitionPartitionId, IngesterPartition>,
     current_partition: Option<IngesterPartition>,
     current_chunk: Option<(Schema, Vec<RecordBatch>)>,
+    /// Current names of the columns of the current chunk, if the ingester sent some under the name they had before
+    /// a rename or sent columns that were dropped since.
+    current_column_names: Option<HashMap<String, Option<Arc<str>>>>,
     ingester_address: Arc<str>,
     cached_table: Arc<CachedTable>,
     span_recorder: SpanRecorder,
@@ -500,6 +507,7 @@ impl IngesterStreamDecoder {
             finished_partitions: HashMap::new(),
             current_partition: None,
             current_chunk: None,
+            current_column_names: None,
             ingester_address,
             cached_table,
             span_recorder: SpanRecorder::new(span),
@@ -590,11 +598,37 @@ impl IngesterStreamDecoder {
                     }
                 );
 
+                // the ingester may have buffered data under the name a column had before it was
+                // renamed, and data of columns that were dropped since
+                let current_names = schema
+                    .fields()
+                    .iter()
+                    .map(|f| {
+                        let name = self.cached_table.resolve_column_name(f.name()).cloned();
+                        (f.name().clone(), name)
+                    })
+                    .collect::<HashMap<_, _>>();
+                self.current_column_names = current_names
+                    .iter()
+                    .any(|(name, current)| current.as_deref() != Some(name.as_str()))
+                    .then_some(current_names);
+
                 // don't use the transmitted arrow schema to construct the IOx schema because some
                 // metadata might be missing. Instead select the right columns from the expected
                 // schema.
-                let column_names: Vec<_> =
-                    schema.fields().iter().map(|f| f.name().as_str()).collect();
+                let mut column_names: Vec<_> = vec![];
+                for f in schema.fields() {
+                    let name = match &self.current_column_names {
+                        Some(current_names) => match &current_names[f.name()] {
+                            Some(name) => name.as_ref(),
+                            None => continue,
+                        },
+                        None => f.name().as_str(),
+                    };
+                    if !column_names.contains(&name) {
+                        column_names.push(name);
+                    }
+                }
                 let schema = self
                     .cached_table
                     .schema
@@ -609,6 +643,17 @@ impl IngesterStreamDecoder {
                         .context(BatchWithoutChunkSnafu {
                             ingester_address: self.ingester_address.as_ref(),
                         })?;
+                let batch = match &self.current_column_names {
+                    Some(current_names) => rename_columns(&batch, |f| {
+                        current_names
+                            .get(f.name())
+                            .cloned()
+                            .flatten()
+                            .map(|name| name.to_string())
+                    })
+                    .context(CreatingRecordBatchSnafu)?,
+                    None => batch,
+                };
                 current_chunk.1.push(batch);
             }
         }
@@ -655,6 +700,7 @@ impl IngesterConnection for IngesterConnectionImpl {
         cached_table: Arc<CachedTable>,
         columns: Vec<String>,
         filters: &[Expr],
+        last_values: bool,
         span: Option<Span>,
     ) -> Result<Vec<IngesterPartition>, DynError> {
         let mut span_recorder = SpanRecorder::new(span);
@@ -671,6 +717,7 @@ impl IngesterConnection for IngesterConnectionImpl {
                 cached_table: Arc::clone(&cached_table),
                 columns: columns.clone(),
                 filters,
+                last_values,
             };
 
             let backoff_config = self.backoff_config.clone();
@@ -1190,7 +1237,14 @@ mod tests {
         let ingester_conn = mock_flight_client.ingester_conn().await;
         let columns = vec![String::from("col")];
         let err = ingester_conn
-            .partitions(NamespaceId::new(1), cached_table(), columns, &[], None)
+            .partitions(
+                NamespaceId::new(1),
+                cached_table(),
+                columns,
+                &[],
+                false,
+                None,
+            )
             .await
             .unwrap_err();
         let err = err.downcast_ref::<Error>().unwrap();
@@ -1224,7 +1278,14 @@ mod tests {
         let ingester_conn = mock_flight_client.ingester_conn().await;
         let columns = vec![String::from("col")];
         let err = ingester_conn
-            .partitions(NamespaceId::new(1), cached_table(), columns, &[], None)
+            .partitions(
+                NamespaceId::new(1),
+                cached_table(),
+                columns,
+                &[],
+                false,
+                None,
+            )
             .await
             .unwrap_err();
         let err = err.downcast_ref::<Error>().unwrap();
@@ -1260,7 +1321,14 @@ mod tests {
         let ingester_conn = mock_flight_client.ingester_conn().await;
         let columns = vec![String::from("col")];
         let partitions = ingester_conn
-            .partitions(NamespaceId::new(1), cached_table(), columns, &[], None)
+            .partitions(
+                NamespaceId::new(1),
+                cached_table(),
+                columns,
+                &[],
+                false,
+                None,
+            )
             .await
             .unwrap();
 
@@ -1389,7 +1457,14 @@ mod tests {
     ) -> Result<Vec<IngesterPartition>, DynError> {
         let columns = vec![String::from("col")];
         ingester_conn
-            .partitions(NamespaceId::new(1), cached_table(), columns, &[], span)
+            .partitions(
+                NamespaceId::new(1),
+                cached_table(),
+                columns,
+                &[],
+                false,
+                span,
+            )
             .await
     }
 
@@ -1738,6 +1813,9 @@ mod tests {
             column_id_map_rev: Default::default(),
             primary_key_column_ids: Default::default(),
             partition_template: Default::default(),
+            column_renames: Default::default(),
+            dropped_column_ids: Default::default(),
+            retention_period: None,
         })
     }
 }
@@ -31,7 +31,7 @@ mod table;
 /// This is mostly to fetch per-partition data concurrently.
 const CONCURRENT_CHUNK_CREATION_JOBS: usize = 100;
 
-pub use cache::CatalogCache as QuerierCatalogCache;
+pub use cache::{disk::DiskCacheConfig, CatalogCache as QuerierCatalogCache, ColdDataConfig};
 pub use database::{Error as QuerierDatabaseError, QuerierDatabase};
 pub use ingester::{create_ingester_connection_for_testing, create_ingester_connections};
 pub use namespace::QuerierNamespace;
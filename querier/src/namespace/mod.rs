@@ -62,7 +62,7 @@ pub struct QuerierNamespace {
     /// DataFusion config.
     datafusion_config: Arc<HashMap<String, String>>,
 
-    /// Include debug info tables.
+    /// Include debug info tables, and log the execution profile of queries.
     include_debug_info_tables: bool,
 
     /// Retention period.
@@ -92,6 +92,7 @@ impl QuerierNamespace {
                     namespace_id: ns.id,
                     namespace_name: Arc::clone(&name),
                     namespace_retention_period: ns.retention_period,
+                    table_retention_period: cached_table.retention_period,
                     table_id: cached_table.id,
                     table_name: Arc::clone(table_name),
                     schema: cached_table.schema.clone(),
@@ -4,7 +4,7 @@ use crate::{
     namespace::QuerierNamespace,
     query_log::QueryLog,
     system_tables::{SystemSchemaProvider, SYSTEM_SCHEMA},
-    table::QuerierTable,
+    table::{QuerierTable, ReadMode},
 };
 use async_trait::async_trait;
 use data_types::NamespaceId;
@@ -15,11 +15,13 @@ use datafusion::{
     prelude::Expr,
 };
 use datafusion_util::config::DEFAULT_SCHEMA;
+use iox_catalog::interface::Catalog;
 use iox_query::{
+    config::IoxConfigExt,
     exec::{ExecutorType, IOxSessionContext},
     QueryChunk, QueryCompletedToken, QueryNamespace, QueryText,
 };
-use observability_deps::tracing::{debug, trace};
+use observability_deps::tracing::{debug, info, trace};
 use std::{any::Any, collections::HashMap, sync::Arc};
 use trace::ctx::SpanContext;
 
@@ -43,14 +45,31 @@ impl QueryNamespace for QuerierNamespace {
             }
         };
 
+        let state = ctx.inner().state();
+        let options = state.config().options();
+        let read_mode = ReadMode::from_options(options);
         let chunks = table
             .chunks(
                 filters,
                 ctx.child_span("QuerierNamespace chunks"),
                 projection,
+                read_mode,
             )
             .await?;
 
+        let load_tag_indexes = options
+            .extensions
+            .get::<IoxConfigExt>()
+            .map(|iox_config| iox_config.tag_index)
+            .unwrap_or_default();
+        let chunks = if load_tag_indexes {
+            table
+                .load_tag_indexes(chunks, ctx.child_span("QuerierNamespace tag indexes"))
+                .await
+        } else {
+            chunks
+        };
+
         Ok(chunks)
     }
 
@@ -60,8 +79,18 @@ impl QueryNamespace for QuerierNamespace {
         })
     }
 
+    fn table_retention_time_ns(&self, table_name: &str) -> Option<i64> {
+        let Some(table) = self.tables.get(table_name) else {
+            return self.retention_time_ns();
+        };
+        table.retention_period().map(|d| {
+            self.catalog_cache.time_provider().now().timestamp_nanos() - d.as_nanos() as i64
+        })
+    }
+
     fn record_query(
         &self,
+        ctx: &IOxSessionContext,
         span_ctx: Option<&SpanContext>,
         query_type: &'static str,
         query_text: QueryText,
@@ -70,8 +99,33 @@ impl QueryNamespace for QuerierNamespace {
         // will be set.
         let query_log = Arc::clone(&self.query_log);
         let trace_id = span_ctx.map(|ctx| ctx.trace_id);
-        let entry = query_log.push(self.id, query_type, query_text, trace_id);
-        QueryCompletedToken::new(move |success| query_log.set_completed(entry, success))
+        let entry = query_log.push(
+            self.id,
+            query_type,
+            query_text,
+            trace_id,
+            ctx.executed_plans().clone(),
+        );
+
+        // Debug requests log the per-operator execution metrics of the
+        // query, which must happen before the query log releases the plans.
+        let profile_plans = self
+            .include_debug_info_tables
+            .then(|| ctx.executed_plans().clone());
+
+        QueryCompletedToken::new(move |success| {
+            if let Some(plans) = profile_plans {
+                info!(
+                    namespace_id = entry.namespace_id.get(),
+                    query_type = entry.query_type,
+                    trace_id = trace_id.map(|id| format!("{:x}", id.0)),
+                    success,
+                    profile = %plans.profile(),
+                    "query profile"
+                );
+            }
+            query_log.set_completed(entry, success)
+        })
     }
 
     fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
@@ -99,6 +153,9 @@ pub struct QuerierCatalogProvider {
     /// Query log.
     query_log: Arc<QueryLog>,
 
+    /// Catalog, for the system tables.
+    catalog: Arc<dyn Catalog>,
+
     /// Include debug info tables.
     include_debug_info_tables: bool,
 }
@@ -109,6 +166,7 @@ impl QuerierCatalogProvider {
             namespace_id: namespace.id,
             tables: Arc::clone(&namespace.tables),
             query_log: Arc::clone(&namespace.query_log),
+            catalog: namespace.catalog_cache.catalog(),
             include_debug_info_tables: namespace.include_debug_info_tables,
         }
     }
@@ -130,6 +188,7 @@ impl CatalogProvider for QuerierCatalogProvider {
             })),
             SYSTEM_SCHEMA => Some(Arc::new(SystemSchemaProvider::new(
                 Arc::clone(&self.query_log),
+                Arc::clone(&self.catalog),
                 self.namespace_id,
                 self.include_debug_info_tables,
             ))),
@@ -180,6 +239,8 @@ mod tests {
     use snafu::{ResultExt, Snafu};
     use trace::{span::SpanStatus, RingBufferTraceCollector};
 
+    const HOUR_NS: i64 = 60 * 60 * 1_000_000_000;
+
     #[tokio::test]
     async fn test_query() {
         test_helpers::maybe_start_logging();
@@ -521,14 +582,315 @@ mod tests {
         - "|    |   ParquetExec: file_groups={1 group: [[1/1/1/00000000-0000-0000-0000-000000000001.parquet, 1/1/1/00000000-0000-0000-0000-000000000002.parquet, 1/1/1/00000000-0000-0000-0000-000000000003.parquet]]}, projection=[foo, host, load, time]    |"
         - "|    |   ProjectionExec: expr=[foo@1 as foo, host@2 as host, load@3 as load, time@4 as time]    |"
         - "|    |     DeduplicateExec: [host@2 ASC,time@4 ASC]    |"
-        - "|    |       SortPreservingMergeExec: [host@2 ASC,time@4 ASC,__chunk_order@0 ASC]    |"
-        - "|    |         ParquetExec: file_groups={2 groups: [[1/1/1/00000000-0000-0000-0000-000000000004.parquet], [1/1/1/00000000-0000-0000-0000-000000000005.parquet]]}, projection=[__chunk_order, foo, host, load, time], output_ordering=[host@2 ASC, time@4 ASC, __chunk_order@0 ASC]    |"
+        - "|    |       ParquetExec: file_groups={2 groups: [[1/1/1/00000000-0000-0000-0000-000000000004.parquet], [1/1/1/00000000-0000-0000-0000-000000000005.parquet]]}, projection=[__chunk_order, foo, host, load, time], output_ordering=[host@2 ASC, time@4 ASC, __chunk_order@0 ASC]    |"
         - "|    |    |"
         - "----------"
         "###
         );
     }
 
+    #[tokio::test]
+    async fn test_query_across_tables() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_with_retention("ns", None).await;
+
+        let table_cpu = ns.create_table("cpu").await;
+        let table_mem = ns.create_table("mem").await;
+
+        table_cpu.create_column("host", ColumnType::Tag).await;
+        table_cpu.create_column("time", ColumnType::Time).await;
+        table_cpu.create_column("load", ColumnType::F64).await;
+        table_mem.create_column("host", ColumnType::Tag).await;
+        table_mem.create_column("time", ColumnType::Time).await;
+        table_mem.create_column("perc", ColumnType::F64).await;
+
+        let partition_cpu = table_cpu.create_partition("a").await;
+        let partition_mem = table_mem.create_partition("a").await;
+
+        // overlapping files within each table, the later one wins
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(1))
+            .with_line_protocol("cpu,host=a load=1 10\ncpu,host=b load=2 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        partition_cpu.create_parquet_file(builder).await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(2))
+            .with_line_protocol("cpu,host=a load=3 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        partition_cpu.create_parquet_file(builder).await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(3))
+            .with_line_protocol("cpu,host=c load=9 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        partition_cpu
+            .create_parquet_file(builder)
+            .await
+            .flag_for_delete() // will be pruned because of soft delete
+            .await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(4))
+            .with_line_protocol("mem,host=a perc=50 10\nmem,host=c perc=70 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        partition_mem.create_parquet_file(builder).await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(5))
+            .with_line_protocol("mem,host=a perc=55 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        partition_mem.create_parquet_file(builder).await;
+
+        let querier_namespace = Arc::new(querier_namespace(&ns).await);
+
+        insta::assert_yaml_snapshot!(
+            format_query(
+                &querier_namespace,
+                "SELECT cpu.host, cpu.load, mem.perc FROM cpu JOIN mem ON cpu.host = mem.host AND cpu.time = mem.time",
+            ).await,
+            @r###"
+        ---
+        - +------+------+------+
+        - "| host | load | perc |"
+        - +------+------+------+
+        - "| a    | 3.0  | 55.0 |"
+        - +------+------+------+
+        "###
+        );
+
+        insta::assert_yaml_snapshot!(
+            format_query(
+                &querier_namespace,
+                "SELECT cpu.host, mem.perc FROM cpu LEFT JOIN mem ON cpu.host = mem.host",
+            ).await,
+            @r###"
+        ---
+        - +------+------+
+        - "| host | perc |"
+        - +------+------+
+        - "| a    | 55.0 |"
+        - "| b    |      |"
+        - +------+------+
+        "###
+        );
+
+        insta::assert_yaml_snapshot!(
+            format_query(
+                &querier_namespace,
+                "SELECT host, load FROM cpu WHERE host IN (SELECT host FROM mem)",
+            ).await,
+            @r###"
+        ---
+        - +------+------+
+        - "| host | load |"
+        - +------+------+
+        - "| a    | 3.0  |"
+        - +------+------+
+        "###
+        );
+
+        insta::assert_yaml_snapshot!(
+            format_query(
+                &querier_namespace,
+                "SELECT host FROM mem WHERE perc > (SELECT max(load) FROM cpu)",
+            ).await,
+            @r###"
+        ---
+        - +------+
+        - "| host |"
+        - +------+
+        - "| a    |"
+        - "| c    |"
+        - +------+
+        "###
+        );
+
+        // each table is deduplicated on its own
+        let explain = format_explain(
+            &querier_namespace,
+            "EXPLAIN SELECT cpu.host, mem.perc FROM cpu JOIN mem ON cpu.host = mem.host",
+        )
+        .await;
+        let num_dedup = explain
+            .iter()
+            .filter(|line| line.contains("DeduplicateExec"))
+            .count();
+        assert_eq!(num_dedup, 2, "{explain:#?}");
+    }
+
+    #[tokio::test]
+    async fn test_query_tag_filters() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_with_retention("ns", None).await;
+
+        let table_cpu = ns.create_table("cpu").await;
+        let table_mem = ns.create_table("mem").await;
+
+        table_cpu.create_column("host", ColumnType::Tag).await;
+        table_cpu.create_column("time", ColumnType::Time).await;
+        table_cpu.create_column("load", ColumnType::F64).await;
+        table_mem.create_column("region", ColumnType::Tag).await;
+        table_mem.create_column("time", ColumnType::Time).await;
+        table_mem.create_column("perc", ColumnType::F64).await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("cpu,host=a load=1 10\ncpu,host=b load=2 10\ncpu,host=c load=3 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        table_cpu
+            .create_partition("a")
+            .await
+            .create_parquet_file(builder)
+            .await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("mem,region=a perc=50 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        table_mem
+            .create_partition("a")
+            .await
+            .create_parquet_file(builder)
+            .await;
+
+        let querier_namespace = Arc::new(querier_namespace(&ns).await);
+
+        let run_filtered = |sql: &'static str| {
+            let querier_namespace = Arc::clone(&querier_namespace);
+            async move {
+                let ctx = querier_namespace.new_query_context(None);
+                ctx.set_config_option("iox.tag_filters", "host=a,c")
+                    .await
+                    .unwrap();
+                let physical_plan = SqlQueryPlanner::default().query(sql, &ctx).await.unwrap();
+                batches_to_sorted_lines(&ctx.collect(physical_plan).await.unwrap())
+            }
+        };
+
+        insta::assert_yaml_snapshot!(
+            run_filtered("SELECT host, load FROM cpu WHERE load > 1").await,
+            @r###"
+        ---
+        - +------+------+
+        - "| host | load |"
+        - +------+------+
+        - "| c    | 3.0  |"
+        - +------+------+
+        "###
+        );
+
+        // the filter also applies to subqueries, tables without the tag are empty
+        insta::assert_yaml_snapshot!(
+            run_filtered("SELECT (SELECT COUNT(*) FROM cpu) AS cpu_rows, (SELECT COUNT(*) FROM mem) AS mem_rows").await,
+            @r###"
+        ---
+        - +----------+----------+
+        - "| cpu_rows | mem_rows |"
+        - +----------+----------+
+        - "| 2        | 0        |"
+        - +----------+----------+
+        "###
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_table_retention() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+        catalog
+            .mock_time_provider()
+            .set(Time::from_timestamp_nanos(2 * HOUR_NS));
+
+        // namespace with infinite retention policy, and a table that expires its data after an
+        // hour
+        let ns = catalog.create_namespace_with_retention("ns", None).await;
+
+        let table_cpu = ns.create_table("cpu").await;
+        let table_mem = ns.create_table("mem").await;
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .tables()
+            .update_retention_period(table_cpu.table.id, Some(HOUR_NS))
+            .await
+            .unwrap();
+
+        table_cpu.create_column("host", ColumnType::Tag).await;
+        table_cpu.create_column("time", ColumnType::Time).await;
+        table_cpu.create_column("load", ColumnType::F64).await;
+        table_mem.create_column("host", ColumnType::Tag).await;
+        table_mem.create_column("time", ColumnType::Time).await;
+        table_mem.create_column("perc", ColumnType::F64).await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol(&format!(
+                "cpu,host=a load=1 10\ncpu,host=b load=2 {}",
+                HOUR_NS + 10
+            ))
+            .with_min_time(10)
+            .with_max_time(HOUR_NS + 10);
+        table_cpu
+            .create_partition("a")
+            .await
+            .create_parquet_file(builder)
+            .await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("mem,host=a perc=50 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        table_mem
+            .create_partition("a")
+            .await
+            .create_parquet_file(builder)
+            .await;
+
+        let querier_namespace = Arc::new(querier_namespace(&ns).await);
+
+        assert_eq!(querier_namespace.retention_time_ns(), None);
+        assert_eq!(
+            querier_namespace.table_retention_time_ns("cpu"),
+            Some(HOUR_NS)
+        );
+        assert_eq!(querier_namespace.table_retention_time_ns("mem"), None);
+
+        // only the data of the table with a retention period expires
+        insta::assert_yaml_snapshot!(
+            format_query(&querier_namespace, "SELECT host, load FROM cpu").await,
+            @r###"
+        ---
+        - +------+------+
+        - "| host | load |"
+        - +------+------+
+        - "| b    | 2.0  |"
+        - +------+------+
+        "###
+        );
+        insta::assert_yaml_snapshot!(
+            format_query(&querier_namespace, "SELECT host, perc FROM mem").await,
+            @r###"
+        ---
+        - +------+------+
+        - "| host | perc |"
+        - +------+------+
+        - "| a    | 50.0 |"
+        - +------+------+
+        "###
+        );
+    }
+
     async fn format_query(querier_namespace: &Arc<QuerierNamespace>, sql: &str) -> Vec<String> {
         format_query_with_span_ctx(querier_namespace, sql, None).await
     }
@@ -1,10 +1,19 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
 
-use data_types::{ChunkId, ChunkOrder, ColumnId, ParquetFile, TransitionPartitionId};
-use futures::StreamExt;
+use data_types::{
+    column_names_at, ChunkId, ChunkOrder, ColumnId, ParquetFile, TransitionPartitionId,
+};
+use futures::{StreamExt, TryStreamExt};
 use hashbrown::HashSet;
 use iox_catalog::interface::Catalog;
-use parquet_file::chunk::ParquetChunk;
+use iox_query::QueryChunk;
+use parquet_file::{
+    chunk::ParquetChunk,
+    verify::{ChecksumVerifier, VerifyError},
+};
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use schema::{sort::SortKeyBuilder, Schema};
 use trace::span::{Span, SpanRecorder};
@@ -26,6 +35,10 @@ pub struct ChunkAdapter {
 
     /// Metric registry.
     metric_registry: Arc<metric::Registry>,
+
+    /// Verifies the content of parquet files before they are read, if
+    /// enabled.
+    checksum_verifier: Option<ChecksumVerifier>,
 }
 
 impl ChunkAdapter {
@@ -34,6 +47,20 @@ impl ChunkAdapter {
         Self {
             catalog_cache,
             metric_registry,
+            checksum_verifier: None,
+        }
+    }
+
+    /// Verify the content of parquet files against the hash recorded in the
+    /// catalog before they are read.
+    ///
+    /// Each file is read from object storage in full the first time it is
+    /// queried, so this trades additional object store traffic for detecting
+    /// corrupted files before they reach the parquet decoder.
+    pub fn with_checksum_verification(self, enabled: bool) -> Self {
+        Self {
+            checksum_verifier: enabled.then(ChecksumVerifier::new),
+            ..self
         }
     }
 
@@ -52,6 +79,79 @@ impl ChunkAdapter {
         self.catalog_cache.catalog()
     }
 
+    /// Verify the content of the parquet files backing `chunks`, if checksum
+    /// verification is enabled.
+    ///
+    /// Chunks that are not backed by a parquet file are ignored.
+    pub(crate) async fn verify_chunks(
+        &self,
+        chunks: &[Arc<dyn QueryChunk>],
+        span: Option<Span>,
+    ) -> Result<(), VerifyError> {
+        let Some(verifier) = &self.checksum_verifier else {
+            return Ok(());
+        };
+        let _span_recorder = SpanRecorder::new(span);
+
+        futures::stream::iter(
+            chunks
+                .iter()
+                .filter_map(|c| c.as_any().downcast_ref::<QuerierParquetChunk>()),
+        )
+        .map(|c| async move {
+            let chunk = c.parquet_chunk();
+            verifier.verify(chunk.store(), chunk.parquet_file()).await
+        })
+        .buffer_unordered(CONCURRENT_CHUNK_CREATION_JOBS)
+        .try_collect::<()>()
+        .await
+    }
+
+    /// Attach the [`TagIndex`] of the parquet files backing `chunks`, building
+    /// the indexes that are not cached yet.
+    ///
+    /// Other chunks, and chunks of files with renamed columns, are returned
+    /// unchanged.
+    ///
+    /// [`TagIndex`]: iox_query::tag_index::TagIndex
+    pub(crate) async fn load_tag_indexes(
+        &self,
+        chunks: Vec<Arc<dyn QueryChunk>>,
+        span: Option<Span>,
+    ) -> Vec<Arc<dyn QueryChunk>> {
+        let span_recorder = SpanRecorder::new(span);
+
+        futures::stream::iter(chunks)
+            .map(|chunk| {
+                let span_recorder = &span_recorder;
+                async move {
+                    let Some(c) = chunk.as_any().downcast_ref::<QuerierParquetChunk>() else {
+                        return chunk;
+                    };
+                    let parquet_chunk = c.parquet_chunk();
+                    if !parquet_chunk.renamed_columns().is_empty() {
+                        return chunk;
+                    }
+
+                    let tag_index = self
+                        .catalog_cache
+                        .tag_index()
+                        .get(
+                            Arc::clone(parquet_chunk),
+                            span_recorder.child_span("cache GET tag index"),
+                        )
+                        .await;
+                    match tag_index {
+                        Some(tag_index) => Arc::new(c.with_tag_index(tag_index)) as _,
+                        None => chunk,
+                    }
+                }
+            })
+            .buffered(CONCURRENT_CHUNK_CREATION_JOBS)
+            .collect()
+            .await
+    }
+
     pub(crate) async fn new_chunks(
         &self,
         cached_table: Arc<CachedTable>,
@@ -180,11 +280,44 @@ impl ChunkAdapter {
             partition_id: parquet_file.file.partition_id.clone(),
         });
 
-        let parquet_chunk = Arc::new(ParquetChunk::new(
-            parquet_file.file,
-            schema,
-            self.catalog_cache.parquet_store(),
-        ));
+        // Columns renamed after the file was created are stored under the name they had at that time. The ingester
+        // and the compactor write files that way, also for data they buffered before the rename.
+        let renamed_columns =
+            column_names_at(&cached_table.column_renames, parquet_file.file.created_at)
+                .into_iter()
+                .filter(|(c_id, _name)| parquet_file.col_set.contains(c_id))
+                .filter_map(|(c_id, name_in_file)| {
+                    let name = cached_table.column_id_map.get(&c_id)?;
+                    Some((name.to_string(), name_in_file.to_string()))
+                })
+                .collect::<BTreeMap<_, _>>();
+
+        // Columns that are not in the file must not be read by name if the file may contain a different column of
+        // that name, i.e. a column that was renamed or dropped since.
+        let has_dropped_columns = parquet_file
+            .file
+            .column_set
+            .iter()
+            .any(|c_id| cached_table.dropped_column_ids.contains(c_id));
+        let absent_columns = cached_table
+            .column_id_map
+            .iter()
+            .filter(|(c_id, _name)| !parquet_file.col_set.contains(*c_id))
+            .filter(|(_c_id, name)| {
+                has_dropped_columns
+                    || renamed_columns
+                        .values()
+                        .any(|name_in_file| name_in_file.as_str() == name.as_ref())
+            })
+            .map(|(_c_id, name)| name.to_string())
+            .collect::<BTreeSet<_>>();
+
+        let parquet_store = self.catalog_cache.parquet_store_for(&parquet_file.file);
+        let parquet_chunk = Arc::new(
+            ParquetChunk::new(parquet_file.file, schema, parquet_store)
+                .with_renamed_columns(renamed_columns)
+                .with_absent_columns(absent_columns),
+        );
 
         QuerierParquetChunk::new(
             parquet_chunk,
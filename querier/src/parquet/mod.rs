@@ -2,7 +2,10 @@
 
 use data_types::{ChunkId, ChunkOrder, TransitionPartitionId};
 use datafusion::physical_plan::Statistics;
-use iox_query::chunk_statistics::{create_chunk_statistics, ColumnRanges};
+use iox_query::{
+    chunk_statistics::{create_chunk_statistics, ColumnRanges},
+    tag_index::TagIndex,
+};
 use parquet_file::chunk::ParquetChunk;
 use schema::sort::SortKey;
 use std::sync::Arc;
@@ -55,6 +58,9 @@ pub struct QuerierParquetChunk {
 
     /// Stats
     stats: Arc<Statistics>,
+
+    /// Index of the tag values, if loaded.
+    tag_index: Option<Arc<TagIndex>>,
 }
 
 impl QuerierParquetChunk {
@@ -75,6 +81,17 @@ impl QuerierParquetChunk {
             meta,
             parquet_chunk,
             stats,
+            tag_index: None,
+        }
+    }
+
+    /// Create a copy of this chunk with the given tag index.
+    pub fn with_tag_index(&self, tag_index: Arc<TagIndex>) -> Self {
+        Self {
+            meta: Arc::clone(&self.meta),
+            parquet_chunk: Arc::clone(&self.parquet_chunk),
+            stats: Arc::clone(&self.stats),
+            tag_index: Some(tag_index),
         }
     }
 
@@ -83,6 +100,11 @@ impl QuerierParquetChunk {
         self.meta.as_ref()
     }
 
+    /// Get the parquet file backing this chunk.
+    pub fn parquet_chunk(&self) -> &Arc<ParquetChunk> {
+        &self.parquet_chunk
+    }
+
     pub fn estimate_size(&self) -> usize {
         self.parquet_chunk.parquet_file().file_size_bytes as usize
     }
@@ -94,7 +116,10 @@ impl QuerierParquetChunk {
 
 #[cfg(test)]
 pub mod tests {
-    use std::collections::HashMap;
+    use std::{
+        collections::{BTreeMap, HashMap},
+        time::Duration,
+    };
 
     use crate::cache::{
         namespace::{CachedNamespace, CachedTable},
@@ -109,9 +134,9 @@ pub mod tests {
     use datafusion_util::config::register_iox_object_store;
     use iox_query::{
         exec::{ExecutorType, IOxSessionContext},
-        QueryChunk,
+        QueryChunk, QueryChunkData,
     };
-    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use iox_tests::{TestCatalog, TestNamespace, TestParquetFileBuilder};
     use metric::{Attributes, Observation, RawReporter};
     use schema::{builder::SchemaBuilder, sort::SortKeyBuilder};
     use test_helpers::maybe_start_logging;
@@ -156,6 +181,89 @@ pub mod tests {
         assert_eq!(catalog_metrics1, catalog_metrics2);
     }
 
+    #[tokio::test]
+    async fn test_parquet_chunk_renamed_column() {
+        maybe_start_logging();
+        let mut test_data = TestData::new().await;
+
+        test_data
+            .catalog
+            .mock_time_provider()
+            .inc(Duration::from_secs(1));
+        test_data
+            .catalog
+            .catalog
+            .repositories()
+            .await
+            .columns()
+            .rename(test_data.parquet_file.table_id, "field_int", "field")
+            .await
+            .unwrap();
+        test_data.cached_table = TestData::cached_table(&test_data.ns).await;
+
+        let chunk = test_data.chunk().await;
+
+        // the chunk uses the new name...
+        let expected_schema = SchemaBuilder::new()
+            .tag("tag1")
+            .field("field", DataType::Int64)
+            .unwrap()
+            .timestamp()
+            .build()
+            .unwrap();
+        assert_eq!(chunk.schema(), &expected_schema);
+
+        // ...but reads the column under the name it had when the file was written
+        let QueryChunkData::Parquet(parquet_input) = chunk.data() else {
+            panic!("expected parquet data");
+        };
+        assert_eq!(
+            parquet_input.renamed_columns,
+            BTreeMap::from([("field".to_owned(), "field_int".to_owned())]),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_tag_indexes() {
+        maybe_start_logging();
+        let mut test_data = TestData::new().await;
+
+        let chunk = Arc::new(test_data.chunk().await) as Arc<dyn QueryChunk>;
+        assert!(chunk.tag_index().is_none());
+
+        let chunks = test_data.adapter.load_tag_indexes(vec![chunk], None).await;
+        let tag_index = chunks[0].tag_index().unwrap();
+        assert_eq!(tag_index.tag_keys().collect::<Vec<_>>(), vec!["tag1"]);
+        assert_eq!(
+            tag_index
+                .tag_values("tag1")
+                .unwrap()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["UT", "VT", "WA"],
+        );
+
+        // files with renamed columns are not indexed
+        test_data
+            .catalog
+            .mock_time_provider()
+            .inc(Duration::from_secs(1));
+        test_data
+            .catalog
+            .catalog
+            .repositories()
+            .await
+            .columns()
+            .rename(test_data.parquet_file.table_id, "field_int", "field")
+            .await
+            .unwrap();
+        test_data.cached_table = TestData::cached_table(&test_data.ns).await;
+
+        let chunk = Arc::new(test_data.chunk().await) as Arc<dyn QueryChunk>;
+        let chunks = test_data.adapter.load_tag_indexes(vec![chunk], None).await;
+        assert!(chunks[0].tag_index().is_none());
+    }
+
     /// collect data for the given chunk
     async fn collect_read_filter(
         chunk: &dyn QueryChunk,
@@ -169,6 +277,7 @@ pub mod tests {
 
     struct TestData {
         catalog: Arc<TestCatalog>,
+        ns: Arc<TestNamespace>,
         parquet_file: Arc<ParquetFile>,
         adapter: ChunkAdapter,
         cached_table: Arc<CachedTable>,
@@ -215,7 +324,19 @@ pub mod tests {
                 catalog.metric_registry(),
             );
 
-            let mut repos = catalog.catalog.repositories().await;
+            let cached_table = Self::cached_table(&ns).await;
+
+            Self {
+                catalog,
+                ns,
+                parquet_file,
+                adapter,
+                cached_table,
+            }
+        }
+
+        async fn cached_table(ns: &TestNamespace) -> Arc<CachedTable> {
+            let mut repos = ns.catalog.catalog.repositories().await;
             let tables = repos
                 .tables()
                 .list_by_namespace_id(ns.namespace.id)
@@ -226,16 +347,14 @@ pub mod tests {
                 .list_by_namespace_id(ns.namespace.id)
                 .await
                 .unwrap();
-            let cached_namespace = CachedNamespace::new(ns.namespace.clone(), tables, columns);
-            let cached_table =
-                Arc::clone(cached_namespace.tables.get("table").expect("table exists"));
-
-            Self {
-                catalog,
-                parquet_file,
-                adapter,
-                cached_table,
-            }
+            let renames = repos
+                .columns()
+                .list_renames_by_namespace_id(ns.namespace.id)
+                .await
+                .unwrap();
+            let cached_namespace =
+                CachedNamespace::new(ns.namespace.clone(), tables, columns, renames, vec![]);
+            Arc::clone(cached_namespace.tables.get("table").expect("table exists"))
         }
 
         async fn chunk(&self) -> QuerierParquetChunk {
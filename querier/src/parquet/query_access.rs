@@ -1,7 +1,7 @@
 use crate::parquet::QuerierParquetChunk;
 use data_types::{ChunkId, ChunkOrder, TransitionPartitionId};
 use datafusion::physical_plan::Statistics;
-use iox_query::{QueryChunk, QueryChunkData};
+use iox_query::{tag_index::TagIndex, QueryChunk, QueryChunkData};
 use schema::{sort::SortKey, Schema};
 use std::{any::Any, sync::Arc};
 
@@ -42,6 +42,10 @@ impl QueryChunk for QuerierParquetChunk {
         self.meta().order()
     }
 
+    fn tag_index(&self) -> Option<Arc<TagIndex>> {
+        self.tag_index.as_ref().map(Arc::clone)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
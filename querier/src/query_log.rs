@@ -1,7 +1,10 @@
 //! Ring buffer of queries that have been run with some brief information
 
 use data_types::NamespaceId;
-use iox_query::QueryText;
+use iox_query::{
+    exec::plan_snapshot::{ExecutedPlans, PlanSnapshot},
+    QueryText,
+};
 use iox_time::{Time, TimeProvider};
 use observability_deps::tracing::warn;
 use parking_lot::Mutex;
@@ -15,6 +18,12 @@ use trace::ctx::TraceId;
 /// The query duration used for queries still running.
 const UNCOMPLETED_DURATION: i64 = -1;
 
+/// The number of slow queries retained in a [`QueryLog`].
+///
+/// Kept separately from (and retained longer than) the full log, as slow
+/// queries are rare.
+const SLOW_QUERY_LOG_SIZE: usize = 1_000;
+
 /// Information about a single query that was executed
 pub struct QueryLogEntry {
     /// Namespace ID.
@@ -38,6 +47,9 @@ pub struct QueryLogEntry {
 
     /// If the query completed successfully
     pub success: atomic::AtomicBool,
+
+    /// The physical plans executed for this query.
+    executed_plans: ExecutedPlans,
 }
 
 impl std::fmt::Debug for QueryLogEntry {
@@ -60,6 +72,7 @@ impl QueryLogEntry {
         query_text: QueryText,
         trace_id: Option<TraceId>,
         issue_time: Time,
+        executed_plans: ExecutedPlans,
     ) -> Self {
         Self {
             namespace_id,
@@ -69,6 +82,7 @@ impl QueryLogEntry {
             issue_time,
             query_completed_duration: UNCOMPLETED_DURATION.into(),
             success: atomic::AtomicBool::new(false),
+            executed_plans,
         }
     }
 
@@ -105,6 +119,16 @@ impl QueryLogEntry {
     }
 }
 
+/// A query that took longer than the slow query threshold to complete
+#[derive(Debug)]
+pub struct SlowQuery {
+    /// The log entry of the query
+    pub entry: Arc<QueryLogEntry>,
+
+    /// A summary of the plans executed for the query
+    pub plan: PlanSnapshot,
+}
+
 /// Stores a fixed number `QueryExecutions` -- handles locking
 /// internally so can be shared across multiple
 #[derive(Debug)]
@@ -112,6 +136,11 @@ pub struct QueryLog {
     log: Mutex<VecDeque<Arc<QueryLogEntry>>>,
     max_size: usize,
     time_provider: Arc<dyn TimeProvider>,
+
+    /// Queries taking at least this long to complete are logged and
+    /// retained in `slow_queries`.
+    slow_query_threshold: Option<Duration>,
+    slow_queries: Mutex<VecDeque<Arc<SlowQuery>>>,
 }
 
 impl QueryLog {
@@ -122,6 +151,17 @@ impl QueryLog {
             log: Mutex::new(VecDeque::with_capacity(max_size)),
             max_size,
             time_provider,
+            slow_query_threshold: None,
+            slow_queries: Default::default(),
+        }
+    }
+
+    /// Emit a log entry for, and retain a plan snapshot of, every query
+    /// taking at least `threshold` to complete.
+    pub fn with_slow_query_threshold(self, threshold: Option<Duration>) -> Self {
+        Self {
+            slow_query_threshold: threshold,
+            ..self
         }
     }
 
@@ -131,6 +171,7 @@ impl QueryLog {
         query_type: &'static str,
         query_text: QueryText,
         trace_id: Option<TraceId>,
+        executed_plans: ExecutedPlans,
     ) -> Arc<QueryLogEntry> {
         let entry = Arc::new(QueryLogEntry::new(
             namespace_id,
@@ -138,6 +179,7 @@ impl QueryLog {
             query_text,
             trace_id,
             self.time_provider.now(),
+            executed_plans,
         ));
 
         if self.max_size == 0 {
@@ -160,10 +202,50 @@ impl QueryLog {
         log.clone()
     }
 
+    /// Returns the retained slow queries, oldest first
+    pub fn slow_queries(&self) -> VecDeque<Arc<SlowQuery>> {
+        self.slow_queries.lock().clone()
+    }
+
     /// Marks the provided query entry as completed using the current time.
     /// `success` specifies the query ran successfully
     pub fn set_completed(&self, entry: Arc<QueryLogEntry>, success: bool) {
-        entry.set_completed(self.time_provider.now(), success)
+        entry.set_completed(self.time_provider.now(), success);
+
+        // The plans are only needed to describe slow queries, and must not be
+        // retained for the lifetime of the log entry.
+        let executed_plans = entry.executed_plans.take();
+
+        let (Some(threshold), Some(duration)) =
+            (self.slow_query_threshold, entry.query_completed_duration())
+        else {
+            return;
+        };
+        if duration < threshold {
+            return;
+        }
+
+        let plan = executed_plans.snapshot();
+        warn!(
+            namespace_id = entry.namespace_id.get(),
+            query_type = entry.query_type,
+            query_text = %entry.query_text,
+            trace_id = entry.trace_id.map(|id| format!("{:x}", id.0)),
+            ?duration,
+            success,
+            predicate = %plan.predicate,
+            chunks_scanned = plan.chunks_scanned,
+            row_groups_pruned = plan.row_groups_pruned,
+            rows_pruned = plan.rows_pruned,
+            plan = %plan.plan,
+            "slow query"
+        );
+
+        let mut slow_queries = self.slow_queries.lock();
+        if slow_queries.len() == SLOW_QUERY_LOG_SIZE {
+            slow_queries.pop_front();
+        }
+        slow_queries.push_back(Arc::new(SlowQuery { entry, plan }));
     }
 }
 
@@ -183,6 +265,7 @@ mod test_super {
             Box::new("SELECT 1"),
             None,
             time_provider.now(),
+            ExecutedPlans::default(),
         ));
         // query has not completed
         assert_eq!(entry.query_completed_duration(), None);
@@ -205,4 +288,54 @@ mod test_super {
         );
         assert!(!entry.success());
     }
+
+    #[test]
+    fn test_slow_queries() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_millis(100).unwrap()));
+        let query_log = QueryLog::new(10, Arc::clone(&time_provider) as _)
+            .with_slow_query_threshold(Some(Duration::from_secs(1)));
+
+        let fast = query_log.push(
+            NamespaceId::new(1),
+            "sql",
+            Box::new("SELECT 1"),
+            None,
+            ExecutedPlans::default(),
+        );
+        let slow = query_log.push(
+            NamespaceId::new(1),
+            "sql",
+            Box::new("SELECT 2"),
+            None,
+            ExecutedPlans::default(),
+        );
+
+        time_provider.inc(Duration::from_millis(999));
+        query_log.set_completed(fast, true);
+        assert!(query_log.slow_queries().is_empty());
+
+        time_provider.inc(Duration::from_millis(1));
+        query_log.set_completed(slow, false);
+
+        let slow_queries = query_log.slow_queries();
+        assert_eq!(slow_queries.len(), 1);
+        assert_eq!(slow_queries[0].entry.query_text.to_string(), "SELECT 2");
+        assert_eq!(
+            slow_queries[0].entry.query_completed_duration(),
+            Some(Duration::from_secs(1))
+        );
+
+        // Without a threshold no query is slow
+        let query_log = QueryLog::new(10, Arc::clone(&time_provider) as _);
+        let entry = query_log.push(
+            NamespaceId::new(1),
+            "sql",
+            Box::new("SELECT 3"),
+            None,
+            ExecutedPlans::default(),
+        );
+        time_provider.inc(Duration::from_secs(60));
+        query_log.set_completed(entry, true);
+        assert!(query_log.slow_queries().is_empty());
+    }
 }
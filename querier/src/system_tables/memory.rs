@@ -0,0 +1,99 @@
+use crate::system_tables::{BatchIterator, IoxSystemTable};
+use arrow::{
+    array::{ArrayRef, StringArray, UInt64Array},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    error::Result,
+    record_batch::RecordBatch,
+};
+use std::sync::Arc;
+use tracker::MemorySubsystem;
+
+/// Implementation of system.memory table.
+///
+/// Reports the heap memory currently allocated by each subsystem of this
+/// process, which is only tracked (and the table only has rows) if IOx is
+/// built with the instrumented allocator.
+#[derive(Debug)]
+pub(super) struct MemoryTable {
+    schema: SchemaRef,
+}
+
+impl MemoryTable {
+    pub(super) fn new() -> Self {
+        Self {
+            schema: memory_schema(),
+        }
+    }
+}
+
+impl IoxSystemTable for MemoryTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn scan(&self, _batch_size: usize) -> Result<BatchIterator> {
+        let allocated = tracker::allocated_bytes()
+            .map(Vec::from)
+            .unwrap_or_default();
+
+        let batch = from_allocated_bytes(self.schema(), &allocated)?;
+        Ok(Box::new(std::iter::once(Ok(batch))))
+    }
+}
+
+fn memory_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("subsystem", DataType::Utf8, false),
+        Field::new("allocated_bytes", DataType::UInt64, false),
+    ]))
+}
+
+fn from_allocated_bytes(
+    schema: SchemaRef,
+    allocated: &[(MemorySubsystem, usize)],
+) -> Result<RecordBatch> {
+    let subsystem = allocated
+        .iter()
+        .map(|(s, _)| Some(s.name()))
+        .collect::<StringArray>();
+    let allocated_bytes = allocated
+        .iter()
+        .map(|(_, bytes)| Some(*bytes as u64))
+        .collect::<UInt64Array>();
+
+    RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(subsystem) as ArrayRef,
+            Arc::new(allocated_bytes) as ArrayRef,
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+
+    #[test]
+    fn test_from_allocated_bytes() {
+        let batch = from_allocated_bytes(
+            memory_schema(),
+            &[
+                (MemorySubsystem::Unattributed, 1024),
+                (MemorySubsystem::QueryExec, 42),
+            ],
+        )
+        .unwrap();
+
+        let expected = [
+            "+--------------+-----------------+",
+            "| subsystem    | allocated_bytes |",
+            "+--------------+-----------------+",
+            "| unattributed | 1024            |",
+            "| query_exec   | 42              |",
+            "+--------------+-----------------+",
+        ];
+        assert_batches_eq!(expected, &[batch]);
+    }
+}
@@ -16,6 +16,7 @@ use datafusion::{
     },
     prelude::Expr,
 };
+use iox_catalog::interface::Catalog;
 use std::collections::HashMap;
 use std::{
     any::Any,
@@ -24,12 +25,21 @@ use std::{
     task::{Context, Poll},
 };
 
+mod memory;
+mod partitions;
 mod queries;
+mod slow_queries;
 
 pub const SYSTEM_SCHEMA: &str = "system";
 
 const QUERIES_TABLE: &str = "queries";
 
+const SLOW_QUERIES_TABLE: &str = "slow_queries";
+
+const PARTITIONS_TABLE: &str = "partitions";
+
+const MEMORY_TABLE: &str = "memory";
+
 pub struct SystemSchemaProvider {
     tables: HashMap<&'static str, Arc<dyn TableProvider>>,
 }
@@ -37,6 +47,7 @@ pub struct SystemSchemaProvider {
 impl SystemSchemaProvider {
     pub fn new(
         query_log: Arc<QueryLog>,
+        catalog: Arc<dyn Catalog>,
         namespace_id: NamespaceId,
         include_debug_info: bool,
     ) -> Self {
@@ -44,9 +55,28 @@ impl SystemSchemaProvider {
 
         if include_debug_info {
             let queries = Arc::new(SystemTableProvider {
-                table: Arc::new(queries::QueriesTable::new(query_log, Some(namespace_id))),
+                table: Arc::new(queries::QueriesTable::new(
+                    Arc::clone(&query_log),
+                    Some(namespace_id),
+                )),
             });
             tables.insert(QUERIES_TABLE, queries);
+
+            let slow_queries = Arc::new(SystemTableProvider {
+                table: Arc::new(slow_queries::SlowQueriesTable::new(
+                    query_log,
+                    Some(namespace_id),
+                )),
+            });
+            tables.insert(SLOW_QUERIES_TABLE, slow_queries);
+
+            let partitions = Arc::new(partitions::PartitionsTable::new(catalog, namespace_id));
+            tables.insert(PARTITIONS_TABLE, partitions);
+
+            let memory = Arc::new(SystemTableProvider {
+                table: Arc::new(memory::MemoryTable::new()),
+            });
+            tables.insert(MEMORY_TABLE, memory);
         }
 
         Self { tables }
@@ -0,0 +1,297 @@
+use arrow::{
+    array::{ArrayRef, Int64Array, StringArray, TimestampNanosecondArray, UInt64Array},
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    error::Result,
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use data_types::{
+    CompactionLevel, NamespaceId, ParquetFile, Partition, Table, TransitionPartitionId,
+};
+use datafusion::{
+    datasource::TableProvider,
+    error::{DataFusionError, Result as DataFusionResult},
+    execution::context::SessionState,
+    logical_expr::TableType,
+    physical_plan::{memory::MemoryExec, ExecutionPlan},
+    prelude::Expr,
+};
+use iox_catalog::interface::Catalog;
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+/// Implementation of system.partitions table.
+///
+/// Unlike the other system tables this reads from the catalog (rather than
+/// querier state) on every scan, so the statistics reflect all persisted data
+/// of the namespace, including partitions not yet cached by this querier.
+#[derive(Debug)]
+pub(super) struct PartitionsTable {
+    schema: SchemaRef,
+    catalog: Arc<dyn Catalog>,
+    namespace_id: NamespaceId,
+}
+
+impl PartitionsTable {
+    pub(super) fn new(catalog: Arc<dyn Catalog>, namespace_id: NamespaceId) -> Self {
+        Self {
+            schema: partitions_schema(),
+            catalog,
+            namespace_id,
+        }
+    }
+
+    async fn load(&self) -> DataFusionResult<RecordBatch> {
+        let mut repos = self.catalog.repositories().await;
+
+        let tables = repos
+            .tables()
+            .list_by_namespace_id(self.namespace_id)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        let mut partitions = vec![];
+        for table in &tables {
+            partitions.extend(
+                repos
+                    .partitions()
+                    .list_by_table_id(table.id)
+                    .await
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?,
+            );
+        }
+
+        let files = repos
+            .parquet_files()
+            .list_by_namespace_not_to_delete(self.namespace_id)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        Ok(from_catalog(
+            Arc::clone(&self.schema),
+            &tables,
+            &partitions,
+            &files,
+        )?)
+    }
+}
+
+#[async_trait]
+impl TableProvider for PartitionsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn scan(
+        &self,
+        _ctx: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let batch = self.load().await?;
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![batch]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+}
+
+fn partitions_schema() -> SchemaRef {
+    let ts = DataType::Timestamp(TimeUnit::Nanosecond, None);
+
+    Arc::new(Schema::new(vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("partition_key", DataType::Utf8, false),
+        Field::new("partition_id", DataType::Utf8, false),
+        Field::new("file_count", DataType::UInt64, false),
+        Field::new("l0_file_count", DataType::UInt64, false),
+        Field::new("row_count", DataType::Int64, false),
+        Field::new("size_bytes", DataType::Int64, false),
+        Field::new("min_time", ts.clone(), true),
+        Field::new("max_time", ts.clone(), true),
+        Field::new("new_file_at", ts, true),
+    ]))
+}
+
+/// Statistics of the parquet files of a single partition.
+#[derive(Debug, Default)]
+struct PartitionStats {
+    file_count: u64,
+    l0_file_count: u64,
+    row_count: i64,
+    size_bytes: i64,
+    min_time: Option<i64>,
+    max_time: Option<i64>,
+}
+
+fn from_catalog(
+    schema: SchemaRef,
+    tables: &[Table],
+    partitions: &[Partition],
+    files: &[ParquetFile],
+) -> Result<RecordBatch> {
+    let mut stats: HashMap<TransitionPartitionId, PartitionStats> = HashMap::new();
+    for file in files {
+        let s = stats.entry(file.partition_id.clone()).or_default();
+        s.file_count += 1;
+        if file.compaction_level == CompactionLevel::Initial {
+            s.l0_file_count += 1;
+        }
+        s.row_count += file.row_count;
+        s.size_bytes += file.file_size_bytes;
+        s.min_time = Some(
+            s.min_time
+                .map_or(file.min_time.get(), |t| t.min(file.min_time.get())),
+        );
+        s.max_time = Some(
+            s.max_time
+                .map_or(file.max_time.get(), |t| t.max(file.max_time.get())),
+        );
+    }
+
+    let table_names = tables
+        .iter()
+        .map(|t| (t.id, t.name.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    let mut rows = partitions
+        .iter()
+        .filter_map(|p| {
+            let table_name = *table_names.get(&p.table_id)?;
+            let id = p.transition_partition_id();
+            let stats = stats.remove(&id).unwrap_or_default();
+            Some((table_name, p, id, stats))
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| (a.0, a.1.partition_key.inner()).cmp(&(b.0, b.1.partition_key.inner())));
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(rows.iter().map(|r| Some(r.0)).collect::<StringArray>()),
+        Arc::new(
+            rows.iter()
+                .map(|r| Some(r.1.partition_key.inner()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|r| Some(r.2.to_string()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|r| Some(r.3.file_count))
+                .collect::<UInt64Array>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|r| Some(r.3.l0_file_count))
+                .collect::<UInt64Array>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|r| Some(r.3.row_count))
+                .collect::<Int64Array>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|r| Some(r.3.size_bytes))
+                .collect::<Int64Array>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|r| r.3.min_time)
+                .collect::<TimestampNanosecondArray>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|r| r.3.max_time)
+                .collect::<TimestampNanosecondArray>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|r| r.1.new_file_at.map(|t| t.get()))
+                .collect::<TimestampNanosecondArray>(),
+        ),
+    ];
+
+    RecordBatch::try_new(schema, columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+    use data_types::{PartitionId, PartitionKey, TableId, Timestamp};
+    use iox_tests::{ParquetFileBuilder, TableBuilder};
+
+    #[test]
+    fn test_from_catalog() {
+        let tables = [
+            TableBuilder::new(1).with_name("cpu").build(),
+            TableBuilder::new(2).with_name("mem").build(),
+        ];
+
+        let partition = |id, table_id, key: &str, new_file_at: Option<i64>| {
+            Partition::new_in_memory_only(
+                PartitionId::new(id),
+                TableId::new(table_id),
+                PartitionKey::from(key),
+                vec![],
+                Default::default(),
+                new_file_at.map(Timestamp::new),
+            )
+        };
+        let partitions = [
+            partition(1, 1, "2023-01-02", Some(42)),
+            partition(2, 1, "2023-01-01", Some(10)),
+            partition(3, 2, "2023-01-01", None),
+        ];
+
+        let file = |id, p: &Partition| {
+            ParquetFileBuilder::new(id).with_partition(p.transition_partition_id())
+        };
+        let files = [
+            file(1, &partitions[0])
+                .with_compaction_level(CompactionLevel::Initial)
+                .with_row_count(10)
+                .with_file_size_bytes(100)
+                .with_time_range(5, 20)
+                .build(),
+            file(2, &partitions[0])
+                .with_row_count(5)
+                .with_file_size_bytes(50)
+                .with_time_range(1, 10)
+                .build(),
+            file(3, &partitions[1])
+                .with_row_count(1)
+                .with_file_size_bytes(10)
+                .with_time_range(0, 0)
+                .build(),
+        ];
+
+        let batch = from_catalog(partitions_schema(), &tables, &partitions, &files).unwrap();
+        let batch = batch.project(&[0, 1, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+------------+---------------+------------+---------------+-----------+------------+--------------------------------+--------------------------------+--------------------------------+",
+                "| table_name | partition_key | file_count | l0_file_count | row_count | size_bytes | min_time                       | max_time                       | new_file_at                    |",
+                "+------------+---------------+------------+---------------+-----------+------------+--------------------------------+--------------------------------+--------------------------------+",
+                "| cpu        | 2023-01-01    | 1          | 0             | 1         | 10         | 1970-01-01T00:00:00Z           | 1970-01-01T00:00:00Z           | 1970-01-01T00:00:00.000000010Z |",
+                "| cpu        | 2023-01-02    | 2          | 1             | 15        | 150        | 1970-01-01T00:00:00.000000001Z | 1970-01-01T00:00:00.000000020Z | 1970-01-01T00:00:00.000000042Z |",
+                "| mem        | 2023-01-01    | 0          | 0             | 0         | 0          |                                |                                |                                |",
+                "+------------+---------------+------------+---------------+-----------+------------+--------------------------------+--------------------------------+--------------------------------+",
+            ],
+            &[batch]
+        );
+    }
+}
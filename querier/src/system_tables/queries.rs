@@ -181,6 +181,7 @@ fn from_query_log_entries(
 mod tests {
     use super::*;
     use arrow_util::assert_batches_eq;
+    use iox_query::exec::plan_snapshot::ExecutedPlans;
     use iox_time::{Time, TimeProvider};
     use trace::ctx::TraceId;
 
@@ -196,14 +197,27 @@ mod tests {
             10,
             Arc::clone(&time_provider) as Arc<dyn TimeProvider>,
         ));
-        query_log.push(id1, "sql", Box::new("select * from foo"), None);
+        query_log.push(
+            id1,
+            "sql",
+            Box::new("select * from foo"),
+            None,
+            ExecutedPlans::default(),
+        );
         time_provider.inc(std::time::Duration::from_secs(24 * 60 * 60));
-        let sql2_entry = query_log.push(id1, "sql", Box::new("select * from bar"), None);
+        let sql2_entry = query_log.push(
+            id1,
+            "sql",
+            Box::new("select * from bar"),
+            None,
+            ExecutedPlans::default(),
+        );
         let read_filter_entry = query_log.push(
             id2,
             "read_filter",
             Box::new("json goop"),
             Some(TraceId::new(0x45fe).unwrap()),
+            ExecutedPlans::default(),
         );
 
         let table = QueriesTable::new(Arc::clone(&query_log), None);
@@ -0,0 +1,267 @@
+use crate::{
+    query_log::{QueryLog, SlowQuery},
+    system_tables::{BatchIterator, IoxSystemTable},
+};
+use arrow::{
+    array::{
+        ArrayRef, BooleanArray, DurationNanosecondArray, Int64Array, StringArray,
+        TimestampNanosecondArray, UInt64Array,
+    },
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    error::Result,
+    record_batch::RecordBatch,
+};
+use data_types::NamespaceId;
+use observability_deps::tracing::error;
+use std::sync::Arc;
+
+/// Implementation of system.slow_queries table
+#[derive(Debug)]
+pub(super) struct SlowQueriesTable {
+    schema: SchemaRef,
+    query_log: Arc<QueryLog>,
+    namespace_id_filter: Option<NamespaceId>,
+}
+
+impl SlowQueriesTable {
+    pub(super) fn new(query_log: Arc<QueryLog>, namespace_id_filter: Option<NamespaceId>) -> Self {
+        Self {
+            schema: slow_queries_schema(namespace_id_filter.is_none()),
+            query_log,
+            namespace_id_filter,
+        }
+    }
+}
+
+impl IoxSystemTable for SlowQueriesTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let schema = self.schema();
+
+        let mut queries = self.query_log.slow_queries();
+        if let Some(namespace_id) = self.namespace_id_filter {
+            queries.retain(|q| q.entry.namespace_id == namespace_id);
+        }
+        let queries = Vec::from(queries);
+
+        let include_namespace_id = self.namespace_id_filter.is_none();
+        Ok(Box::new(
+            queries
+                .chunks(batch_size.max(1))
+                .map(|chunk| {
+                    from_slow_queries(Arc::clone(&schema), chunk, include_namespace_id).map_err(
+                        |e| {
+                            error!("Error system.slow_queries table: {:?}", e);
+                            e
+                        },
+                    )
+                })
+                .collect::<Vec<_>>()
+                .into_iter(),
+        ))
+    }
+}
+
+fn slow_queries_schema(include_namespace_id: bool) -> SchemaRef {
+    let mut columns = vec![];
+    if include_namespace_id {
+        columns.push(Field::new("namespace_id", DataType::Int64, false));
+    }
+    columns.append(&mut vec![
+        Field::new(
+            "issue_time",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            false,
+        ),
+        Field::new("query_type", DataType::Utf8, false),
+        Field::new("query_text", DataType::Utf8, false),
+        Field::new(
+            "completed_duration",
+            DataType::Duration(TimeUnit::Nanosecond),
+            true,
+        ),
+        Field::new("success", DataType::Boolean, false),
+        Field::new("trace_id", DataType::Utf8, true),
+        Field::new("predicate", DataType::Utf8, false),
+        Field::new("chunks_scanned", DataType::UInt64, false),
+        Field::new("row_groups_pruned", DataType::UInt64, false),
+        Field::new("rows_pruned", DataType::UInt64, false),
+        Field::new("plan", DataType::Utf8, false),
+    ]);
+
+    Arc::new(Schema::new(columns))
+}
+
+fn from_slow_queries(
+    schema: SchemaRef,
+    queries: &[Arc<SlowQuery>],
+    include_namespace_id: bool,
+) -> Result<RecordBatch> {
+    let mut columns: Vec<ArrayRef> = vec![];
+
+    if include_namespace_id {
+        columns.push(Arc::new(
+            queries
+                .iter()
+                .map(|q| Some(q.entry.namespace_id.get()))
+                .collect::<Int64Array>(),
+        ));
+    }
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.entry.issue_time.timestamp_nanos()))
+            .collect::<TimestampNanosecondArray>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.entry.query_type))
+            .collect::<StringArray>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.entry.query_text.to_string()))
+            .collect::<StringArray>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| {
+                q.entry
+                    .query_completed_duration()
+                    .map(|d| d.as_nanos() as i64)
+            })
+            .collect::<DurationNanosecondArray>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.entry.success()))
+            .collect::<BooleanArray>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| q.entry.trace_id.map(|x| format!("{:x}", x.0)))
+            .collect::<StringArray>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.plan.predicate.as_str()))
+            .collect::<StringArray>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.plan.chunks_scanned as u64))
+            .collect::<UInt64Array>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.plan.row_groups_pruned as u64))
+            .collect::<UInt64Array>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.plan.rows_pruned as u64))
+            .collect::<UInt64Array>(),
+    ));
+
+    columns.push(Arc::new(
+        queries
+            .iter()
+            .map(|q| Some(q.plan.plan.as_str()))
+            .collect::<StringArray>(),
+    ));
+
+    RecordBatch::try_new(schema, columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+    use iox_query::exec::plan_snapshot::ExecutedPlans;
+    use iox_time::{Time, TimeProvider};
+    use std::time::Duration;
+
+    #[test]
+    fn test_from_slow_queries() {
+        let now = Time::from_rfc3339("1996-12-19T16:39:57+00:00").unwrap();
+        let time_provider = Arc::new(iox_time::MockProvider::new(now));
+
+        let id1 = NamespaceId::new(1);
+        let id2 = NamespaceId::new(2);
+
+        let query_log = Arc::new(
+            QueryLog::new(10, Arc::clone(&time_provider) as Arc<dyn TimeProvider>)
+                .with_slow_query_threshold(Some(Duration::from_secs(1))),
+        );
+        let fast = query_log.push(
+            id1,
+            "sql",
+            Box::new("select * from foo"),
+            None,
+            ExecutedPlans::default(),
+        );
+        query_log.set_completed(fast, true);
+
+        let slow1 = query_log.push(
+            id1,
+            "sql",
+            Box::new("select * from bar"),
+            None,
+            ExecutedPlans::default(),
+        );
+        let slow2 = query_log.push(
+            id2,
+            "read_filter",
+            Box::new("json goop"),
+            None,
+            ExecutedPlans::default(),
+        );
+        time_provider.inc(Duration::from_secs(4));
+        query_log.set_completed(slow1, true);
+        query_log.set_completed(slow2, false);
+
+        let table = SlowQueriesTable::new(Arc::clone(&query_log), None);
+
+        let expected = vec![
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+-----------+----------------+-------------------+-------------+------+",
+            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id | predicate | chunks_scanned | row_groups_pruned | rows_pruned | plan |",
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+-----------+----------------+-------------------+-------------+------+",
+            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from bar | 4s                 | true    |          |           | 0              | 0                 | 0           |      |",
+            "| 2            | 1996-12-19T16:39:57Z | read_filter | json goop         | 4s                 | false   |          |           | 0              | 0                 | 0           |      |",
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+-----------+----------------+-------------------+-------------+------+",
+        ];
+
+        let batches = table.scan(1).unwrap().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_batches_eq!(&expected, &batches);
+
+        // test namespace scoping
+        let table = SlowQueriesTable::new(Arc::clone(&query_log), Some(id2));
+        let batches = table.scan(10).unwrap().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 1);
+        assert_eq!(batches[0].num_columns(), 11);
+    }
+}
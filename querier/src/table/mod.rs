@@ -8,17 +8,19 @@ use crate::{
     CONCURRENT_CHUNK_CREATION_JOBS,
 };
 use data_types::{
-    ColumnId, NamespaceId, ParquetFile, TableId, TimestampMinMax, TransitionPartitionId,
+    ColumnId, NamespaceId, ParquetFile, TableId, Timestamp, TimestampMinMax, TransitionPartitionId,
     MAX_NANO_TIME, MIN_NANO_TIME,
 };
-use datafusion::{error::DataFusionError, prelude::Expr};
+use datafusion::{config::ConfigOptions, error::DataFusionError, prelude::Expr};
 use futures::{join, StreamExt};
 use iox_query::{
     chunk_statistics::create_chunk_statistics,
+    config::{AsOf, IoxConfigExt},
     provider,
     pruning::{prune_chunks, prune_summaries},
     QueryChunk,
 };
+use iox_time::Time;
 use observability_deps::tracing::debug;
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use schema::{Schema, TIME_COLUMN_NAME};
@@ -48,6 +50,17 @@ pub enum Error {
 
     #[snafu(display("Chunk pruning failed: {}", source))]
     ChunkPruning { source: provider::Error },
+
+    #[snafu(display("Parquet file verification failed: {}", source))]
+    ParquetFileVerification {
+        source: parquet_file::verify::VerifyError,
+    },
+
+    #[snafu(display("Cannot list parquet files as of {}: {}", as_of, source))]
+    ListParquetFilesAsOf {
+        as_of: Time,
+        source: iox_catalog::interface::Error,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -58,11 +71,55 @@ impl From<Error> for DataFusionError {
     }
 }
 
+/// The data read by a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadMode {
+    /// The unpersisted data of the ingesters and the current parquet files.
+    Current,
+
+    /// The parquet files that existed at the given point in time.
+    ///
+    /// See [`IoxConfigExt::as_of`].
+    AsOf(Time),
+
+    /// The last value caches of the ingesters.
+    ///
+    /// See [`IoxConfigExt::last_value_cache`].
+    LastValues,
+}
+
+impl ReadMode {
+    /// The data read by queries with the given config.
+    ///
+    /// Reading a past catalog state takes precedence over reading the last value caches, which
+    /// only hold current values.
+    pub(crate) fn from_options(options: &ConfigOptions) -> Self {
+        let Some(iox_config) = options.extensions.get::<IoxConfigExt>() else {
+            return Self::Current;
+        };
+
+        match iox_config.as_of {
+            AsOf::Time(dt) => Self::AsOf(Time::from_date_time(dt)),
+            AsOf::Latest if iox_config.last_value_cache => Self::LastValues,
+            AsOf::Latest => Self::Current,
+        }
+    }
+
+    /// Point in time at which the catalog is read, or `None` for the current state.
+    pub(crate) fn as_of(&self) -> Option<Time> {
+        match self {
+            Self::AsOf(t) => Some(*t),
+            Self::Current | Self::LastValues => None,
+        }
+    }
+}
+
 /// Args to create a [`QuerierTable`].
 pub struct QuerierTableArgs {
     pub namespace_id: NamespaceId,
     pub namespace_name: Arc<str>,
     pub namespace_retention_period: Option<Duration>,
+    pub table_retention_period: Option<Duration>,
     pub table_id: TableId,
     pub table_name: Arc<str>,
     pub schema: Schema,
@@ -83,6 +140,10 @@ pub struct QuerierTable {
     /// Namespace retenion
     namespace_retention_period: Option<Duration>,
 
+    /// Table retention, expiring the data of the table faster than the
+    /// namespace retention.
+    table_retention_period: Option<Duration>,
+
     /// Table name.
     table_name: Arc<str>,
 
@@ -109,6 +170,7 @@ impl QuerierTable {
             namespace_id,
             namespace_name,
             namespace_retention_period,
+            table_retention_period,
             table_id,
             table_name,
             schema,
@@ -121,6 +183,7 @@ impl QuerierTable {
             namespace_name,
             namespace_id,
             namespace_retention_period,
+            table_retention_period,
             table_name,
             table_id,
             schema,
@@ -145,15 +208,30 @@ impl QuerierTable {
         &self.schema
     }
 
-    /// Query all chunks within this table.
+    /// The retention period of the data of this table, i.e. the shorter of
+    /// the namespace and the table retention period.
+    ///
+    /// [`None`] means infinite retention.
+    pub fn retention_period(&self) -> Option<Duration> {
+        match (self.namespace_retention_period, self.table_retention_period) {
+            (Some(ns), Some(table)) => Some(ns.min(table)),
+            (ns, table) => ns.or(table),
+        }
+    }
+
+    /// Query all chunks within this table, reading the data selected by `read_mode`.
     pub async fn chunks(
         &self,
         filters: &[Expr],
         span: Option<Span>,
         projection: Option<&Vec<usize>>,
+        read_mode: ReadMode,
     ) -> Result<Vec<Arc<dyn QueryChunk>>> {
         let mut span_recorder = SpanRecorder::new(span);
-        match self.chunks_inner(filters, &span_recorder, projection).await {
+        match self
+            .chunks_inner(filters, &span_recorder, projection, read_mode)
+            .await
+        {
             Ok(chunks) => {
                 span_recorder.ok("got chunks");
                 Ok(chunks)
@@ -165,86 +243,73 @@ impl QuerierTable {
         }
     }
 
+    /// Attach the [`TagIndex`] of their parquet files to `chunks`, see
+    /// [`IoxConfigExt::tag_index`].
+    ///
+    /// [`TagIndex`]: iox_query::tag_index::TagIndex
+    pub async fn load_tag_indexes(
+        &self,
+        chunks: Vec<Arc<dyn QueryChunk>>,
+        span: Option<Span>,
+    ) -> Vec<Arc<dyn QueryChunk>> {
+        self.chunk_adapter.load_tag_indexes(chunks, span).await
+    }
+
     async fn chunks_inner(
         &self,
         filters: &[Expr],
         span_recorder: &SpanRecorder,
         projection: Option<&Vec<usize>>,
+        read_mode: ReadMode,
     ) -> Result<Vec<Arc<dyn QueryChunk>>> {
         debug!(
             ?filters,
+            ?read_mode,
             namespace=%self.namespace_name,
             table_name=%self.table_name(),
             "Fetching all chunks"
         );
 
-        let catalog_cache = self.chunk_adapter.catalog_cache();
-
-        // Ask ingesters for data, also optimistically fetching catalog
-        // contents at the same time to pre-warm cache.
-        //
-        // We don't wanna wait for the cache though because we have a the actual cache request later anyways that might
-        // even invalidate what we did during warm-up. The cache system keeps requests running in the background
-        // anyways, so if the warm-up fetched up-to-date data, we'll get that from the cache later.
-        let ingester_ready = CancellationToken::new();
-        let (partitions, _) = join!(
-            async {
+        let (partitions, parquet_files) = match read_mode {
+            ReadMode::AsOf(as_of) => {
+                // Unpersisted data is not part of the catalog state, so the ingesters are not asked.
+                let parquet_files = self
+                    .chunk_adapter
+                    .catalog_cache()
+                    .catalog()
+                    .repositories()
+                    .await
+                    .parquet_files()
+                    .list_by_table_at(self.id(), Timestamp::from(as_of))
+                    .await
+                    .context(ListParquetFilesAsOfSnafu { as_of })?;
+                (
+                    vec![],
+                    parquet_files
+                        .into_iter()
+                        .map(Arc::new)
+                        .collect::<Arc<[_]>>(),
+                )
+            }
+            ReadMode::LastValues => {
+                // The cached values are served by the ingesters alone, no parquet files are read.
                 let partitions = self
                     .ingester_partitions(
                         filters,
-                        span_recorder.child_span("ingester partitions"),
+                        true,
+                        span_recorder.child_span("ingester last values"),
                         projection,
                     )
-                    .await;
-                ingester_ready.cancel();
-                partitions
-            },
-            async {
-                tokio::select! {
-                    _ = catalog_cache.parquet_file().get(
-                        self.id(),
-                        None,
-                        span_recorder.child_span("cache GET parquet_file (pre-warm)")
-                    ) => {},
-                    _ = ingester_ready.cancelled() => {},
-                }
-            },
-        );
-
-        // handle errors / cache refresh
-        let partitions = partitions?;
-
-        // Determine number of persisted parquet files per ingester UUID seen in the ingester query
-        // responses for cache invalidation. If `persisted_file_counts_by_ingester_uuid` is empty,
-        // then there are no results from the ingesters.
-        let persisted_file_counts_by_ingester_uuid = collect_persisted_file_counts(
-            partitions.len(),
-            partitions
-                .iter()
-                .map(|p| (p.ingester_uuid(), p.completed_persistence_count())),
-        );
-
-        debug!(
-            namespace=%self.namespace_name,
-            table_name=%self.table_name(),
-            num_ingester_partitions=%partitions.len(),
-            "Ingester partitions fetched"
-        );
-
-        // Now fetch the actual contents of the catalog we need
-        // NB: Pass max parquet sequence numbers to `get`
-        //     to ensure cache is refreshed if we learned about new files.
-        let parquet_files = catalog_cache
-            .parquet_file()
-            .get(
-                self.id(),
-                Some(persisted_file_counts_by_ingester_uuid),
-                span_recorder.child_span("cache GET parquet_file"),
-            )
-            .await;
+                    .await?;
+                (partitions, Arc::from([]))
+            }
+            ReadMode::Current => {
+                self.current_partitions_and_files(filters, span_recorder, projection)
+                    .await?
+            }
+        };
 
         let columns: HashSet<ColumnId> = parquet_files
-            .files
             .iter()
             .flat_map(|cached_file| cached_file.column_set.iter().copied())
             .collect();
@@ -268,7 +333,7 @@ impl QuerierTable {
             .fetch_cached_partitions(
                 cached_table,
                 &partitions,
-                &parquet_files.files,
+                &parquet_files,
                 span_recorder.child_span("fetch cached partitions"),
             )
             .await;
@@ -283,7 +348,6 @@ impl QuerierTable {
             )
             .await;
         let parquet_files = parquet_files
-            .files
             .iter()
             .filter(|f| {
                 if cached_partitions.contains_key(&f.partition_id) {
@@ -323,6 +387,15 @@ impl QuerierTable {
         );
         let num_final_parquet_file_chunks = pruned_parquet_file_chunks.len();
 
+        // Only verify the files that survived pruning and will actually be read
+        self.chunk_adapter
+            .verify_chunks(
+                &pruned_parquet_file_chunks,
+                span_recorder.child_span("verify chunks"),
+            )
+            .await
+            .context(ParquetFileVerificationSnafu)?;
+
         // build final chunk list from ingester chunks + pruned parquet file chunks
         let chunks: Vec<_> = partitions
             .into_iter()
@@ -353,6 +426,82 @@ impl QuerierTable {
         Ok(chunks)
     }
 
+    /// Get the current ingester partitions and parquet files of this table.
+    async fn current_partitions_and_files(
+        &self,
+        filters: &[Expr],
+        span_recorder: &SpanRecorder,
+        projection: Option<&Vec<usize>>,
+    ) -> Result<(Vec<IngesterPartition>, Arc<[Arc<ParquetFile>]>)> {
+        let catalog_cache = self.chunk_adapter.catalog_cache();
+
+        // Ask ingesters for data, also optimistically fetching catalog
+        // contents at the same time to pre-warm cache.
+        //
+        // We don't wanna wait for the cache though because we have a the actual cache request later anyways that might
+        // even invalidate what we did during warm-up. The cache system keeps requests running in the background
+        // anyways, so if the warm-up fetched up-to-date data, we'll get that from the cache later.
+        let ingester_ready = CancellationToken::new();
+        let (partitions, _) = join!(
+            async {
+                let partitions = self
+                    .ingester_partitions(
+                        filters,
+                        false,
+                        span_recorder.child_span("ingester partitions"),
+                        projection,
+                    )
+                    .await;
+                ingester_ready.cancel();
+                partitions
+            },
+            async {
+                tokio::select! {
+                    _ = catalog_cache.parquet_file().get(
+                        self.id(),
+                        None,
+                        span_recorder.child_span("cache GET parquet_file (pre-warm)")
+                    ) => {},
+                    _ = ingester_ready.cancelled() => {},
+                }
+            },
+        );
+
+        // handle errors / cache refresh
+        let partitions = partitions?;
+
+        // Determine number of persisted parquet files per ingester UUID seen in the ingester query
+        // responses for cache invalidation. If `persisted_file_counts_by_ingester_uuid` is empty,
+        // then there are no results from the ingesters.
+        let persisted_file_counts_by_ingester_uuid = collect_persisted_file_counts(
+            partitions.len(),
+            partitions
+                .iter()
+                .map(|p| (p.ingester_uuid(), p.completed_persistence_count())),
+        );
+
+        debug!(
+            namespace=%self.namespace_name,
+            table_name=%self.table_name(),
+            num_ingester_partitions=%partitions.len(),
+            "Ingester partitions fetched"
+        );
+
+        // Now fetch the actual contents of the catalog we need
+        // NB: Pass max parquet sequence numbers to `get`
+        //     to ensure cache is refreshed if we learned about new files.
+        let parquet_files = catalog_cache
+            .parquet_file()
+            .get(
+                self.id(),
+                Some(persisted_file_counts_by_ingester_uuid),
+                span_recorder.child_span("cache GET parquet_file"),
+            )
+            .await;
+
+        Ok((partitions, Arc::clone(&parquet_files.files)))
+    }
+
     async fn fetch_cached_partitions(
         &self,
         cached_table: &Arc<CachedTable>,
@@ -506,6 +655,7 @@ impl QuerierTable {
     async fn ingester_partitions(
         &self,
         filters: &[Expr],
+        last_values: bool,
         span: Option<Span>,
         projection: Option<&Vec<usize>>,
     ) -> Result<Vec<IngesterPartition>> {
@@ -516,6 +666,7 @@ impl QuerierTable {
                 .ingester_partitions_inner(
                     Arc::clone(ingester_connection),
                     filters,
+                    last_values,
                     &span_recorder,
                     projection,
                 )
@@ -541,6 +692,7 @@ impl QuerierTable {
         &self,
         ingester_connection: Arc<dyn IngesterConnection>,
         filters: &[Expr],
+        last_values: bool,
         span_recorder: &SpanRecorder,
         projection: Option<&Vec<usize>>,
     ) -> Result<Vec<IngesterPartition>> {
@@ -571,6 +723,7 @@ impl QuerierTable {
                 cached_table,
                 columns,
                 filters,
+                last_values,
                 span_recorder.child_span("IngesterConnection partitions"),
             )
             .await
@@ -653,6 +806,8 @@ mod tests {
     };
     use arrow::datatypes::DataType;
     use arrow_util::assert_batches_eq;
+    use assert_matches::assert_matches;
+    use bytes::Bytes;
     use chrono::{Datelike, TimeZone, Utc};
     use data_types::{ChunkId, ColumnType};
     use datafusion::{
@@ -666,6 +821,7 @@ mod tests {
     use iox_query::{chunk_statistics::ColumnRange, exec::IOxSessionContext};
     use iox_tests::{TestCatalog, TestParquetFileBuilder, TestTable};
     use metric::{Observation, RawReporter};
+    use parquet_file::{verify::VerifyError, ParquetFilePath};
     use schema::{builder::SchemaBuilder, InfluxFieldType, TIME_COLUMN_NAME, TIME_DATA_TIMEZONE};
     use std::sync::Arc;
     use test_helpers::maybe_start_logging;
@@ -875,6 +1031,117 @@ mod tests {
         assert_batches_eq!(&expected, &batches);
     }
 
+    #[tokio::test]
+    async fn test_chunks_as_of() {
+        maybe_start_logging();
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table1").await;
+        let partition = table.create_partition("k").await;
+        let schema = make_schema(&table).await;
+
+        let file1 = partition
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("table1 foo=1 11"),
+            )
+            .await;
+
+        // replace the file at t=100s, like a compaction would
+        catalog
+            .mock_time_provider()
+            .set(Time::from_timestamp(100, 0).unwrap());
+        file1.flag_for_delete().await;
+        let file2 = partition
+            .create_parquet_file(
+                TestParquetFileBuilder::default()
+                    .with_line_protocol("table1 foo=1 11")
+                    .with_creation_time(catalog.time_provider().now()),
+            )
+            .await;
+        catalog
+            .mock_time_provider()
+            .set(Time::from_timestamp(200, 0).unwrap());
+
+        let builder = IngesterPartitionBuilder::new(schema, &partition).with_lp(["table foo=1 1"]);
+        let querier_table = TestQuerierTable::new(&catalog, &table)
+            .await
+            .with_ingester_partition(builder.build());
+
+        let chunk_id = |file: &ParquetFile| ChunkId::from(Uuid::from_u128(file.id.get() as _));
+        let chunk_ids =
+            |chunks: Vec<Arc<dyn QueryChunk>>| chunks.iter().map(|c| c.id()).collect::<Vec<_>>();
+
+        // before the compaction, only the original file existed; ingester data is not included
+        let chunks = querier_table
+            .chunks_as_of(Time::from_timestamp(50, 0).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(chunk_ids(chunks), vec![chunk_id(&file1.parquet_file)]);
+
+        // after the compaction
+        let chunks = querier_table
+            .chunks_as_of(Time::from_timestamp(150, 0).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(chunk_ids(chunks), vec![chunk_id(&file2.parquet_file)]);
+
+        // before the first file was created
+        let chunks = querier_table
+            .chunks_as_of(Time::from_timestamp_nanos(0))
+            .await
+            .unwrap();
+        assert!(chunks.is_empty());
+
+        // the current state includes the ingester data
+        let chunks = querier_table.chunks().await.unwrap();
+        assert_eq!(chunks.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_chunks_last_values() {
+        maybe_start_logging();
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table1").await;
+        let partition = table.create_partition("k").await;
+        let schema = make_schema(&table).await;
+
+        partition
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("table1 foo=1 11"),
+            )
+            .await;
+
+        let builder = IngesterPartitionBuilder::new(schema, &partition).with_lp(["table foo=1 1"]);
+        let querier_table = TestQuerierTable::new(&catalog, &table)
+            .await
+            .with_ingester_partition(builder.build());
+
+        // only the ingester data is read, no parquet files
+        let chunks = querier_table.chunks_last_values().await.unwrap();
+        let chunk_types = chunks.iter().map(|c| c.chunk_type()).collect::<Vec<_>>();
+        assert_eq!(chunk_types, vec!["IngesterPartition"]);
+    }
+
+    #[test]
+    fn test_read_mode() {
+        let mut options = ConfigOptions::new();
+        assert_eq!(ReadMode::from_options(&options), ReadMode::Current);
+
+        options.extensions.insert(IoxConfigExt::default());
+        assert_eq!(ReadMode::from_options(&options), ReadMode::Current);
+
+        options.set("iox.last_value_cache", "true").unwrap();
+        assert_eq!(ReadMode::from_options(&options), ReadMode::LastValues);
+        assert_eq!(ReadMode::from_options(&options).as_of(), None);
+
+        // reading a past catalog state takes precedence
+        options.set("iox.as_of", "1970-01-01T00:01:40Z").unwrap();
+        let as_of = Time::from_timestamp(100, 0).unwrap();
+        assert_eq!(ReadMode::from_options(&options), ReadMode::AsOf(as_of));
+        assert_eq!(ReadMode::from_options(&options).as_of(), Some(as_of));
+    }
+
     #[tokio::test]
     async fn test_parquet_cache_refresh() {
         maybe_start_logging();
@@ -921,6 +1188,49 @@ mod tests {
         assert_eq!(chunks.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_parquet_checksum_verification() {
+        maybe_start_logging();
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table1").await;
+        let partition = table.create_partition("k").await;
+
+        let pf_builder = TestParquetFileBuilder::default().with_line_protocol("table1 foo=1 11");
+        let file = partition.create_parquet_file(pf_builder).await.parquet_file;
+        assert!(file.content_hash.is_some());
+
+        let querier_table = TestQuerierTable::new(&catalog, &table)
+            .await
+            .with_checksum_verification();
+        let chunks = querier_table.chunks().await.unwrap();
+        assert_eq!(chunks.len(), 1);
+
+        // Overwrite the file in object storage
+        let path = ParquetFilePath::from(&file).object_store_path();
+        catalog
+            .object_store()
+            .put(&path, Bytes::from_static(b"not a parquet file"))
+            .await
+            .unwrap();
+
+        // Without verification the chunk is created as usual
+        let querier_table = TestQuerierTable::new(&catalog, &table).await;
+        let chunks = querier_table.chunks().await.unwrap();
+        assert_eq!(chunks.len(), 1);
+
+        let querier_table = TestQuerierTable::new(&catalog, &table)
+            .await
+            .with_checksum_verification();
+        let err = querier_table.chunks().await.unwrap_err();
+        assert_matches!(
+            err,
+            Error::ParquetFileVerification {
+                source: VerifyError::Corrupted { object_store_id, .. }
+            } if object_store_id == file.object_store_id
+        );
+    }
+
     #[tokio::test]
     async fn test_custom_partitioning() {
         maybe_start_logging();
@@ -1236,6 +1546,19 @@ mod tests {
             }
         }
 
+        /// Verify the content of parquet files before they are read.
+        fn with_checksum_verification(mut self) -> Self {
+            let chunk_adapter = &self.querier_table.chunk_adapter;
+            self.querier_table.chunk_adapter = Arc::new(
+                ChunkAdapter::new(
+                    Arc::clone(chunk_adapter.catalog_cache()),
+                    chunk_adapter.metric_registry(),
+                )
+                .with_checksum_verification(true),
+            );
+            self
+        }
+
         /// Return a reference to the inner table
         fn inner(&self) -> &QuerierTable {
             &self.querier_table
@@ -1283,7 +1606,35 @@ mod tests {
                 .next_response(Ok(self.ingester_partitions.clone()));
 
             let span = Some(Span::root("root", Arc::clone(&self.traces) as _));
-            self.querier_table.chunks(filters, span, projection).await
+            self.querier_table
+                .chunks(filters, span, projection, ReadMode::Current)
+                .await
+        }
+
+        /// Invokes querier_table.chunks modeling the ingester sending the partitions in this table
+        /// from its last value cache
+        async fn chunks_last_values(&self) -> Result<Vec<Arc<dyn QueryChunk>>> {
+            self.querier_table
+                .ingester_connection
+                .as_ref()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<MockIngesterConnection>()
+                .unwrap()
+                .next_response(Ok(self.ingester_partitions.clone()));
+
+            let span = Some(Span::root("root", Arc::clone(&self.traces) as _));
+            self.querier_table
+                .chunks(&[], span, None, ReadMode::LastValues)
+                .await
+        }
+
+        /// Invokes querier_table.chunks for the catalog state at `as_of`
+        async fn chunks_as_of(&self, as_of: Time) -> Result<Vec<Arc<dyn QueryChunk>>> {
+            let span = Some(Span::root("root", Arc::clone(&self.traces) as _));
+            self.querier_table
+                .chunks(&[], span, None, ReadMode::AsOf(as_of))
+                .await
         }
     }
 }
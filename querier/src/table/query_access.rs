@@ -10,9 +10,14 @@ use datafusion::{
     physical_plan::ExecutionPlan,
     prelude::Expr,
 };
-use iox_query::{exec::SessionContextIOxExt, provider::ProviderBuilder, pruning::retention_expr};
+use iox_query::{
+    config::IoxConfigExt,
+    exec::SessionContextIOxExt,
+    provider::ProviderBuilder,
+    pruning::{retention_expr, tag_filter_expr},
+};
 
-use super::QuerierTable;
+use super::{QuerierTable, ReadMode};
 
 #[async_trait]
 impl TableProvider for QuerierTable {
@@ -41,13 +46,13 @@ impl TableProvider for QuerierTable {
         let mut builder =
             ProviderBuilder::new(Arc::clone(self.table_name()), self.schema().clone());
 
-        let filters = match self.namespace_retention_period {
+        // apply the retention period relative to the point in time that is queried
+        let read_mode = ReadMode::from_options(ctx.config().options());
+        let filters = match self.retention_period() {
             Some(d) => {
-                let ts = self
-                    .chunk_adapter
-                    .catalog_cache()
-                    .time_provider()
-                    .now()
+                let ts = read_mode
+                    .as_of()
+                    .unwrap_or_else(|| self.chunk_adapter.catalog_cache().time_provider().now())
                     .timestamp_nanos()
                     - d.as_nanos() as i64;
 
@@ -60,8 +65,27 @@ impl TableProvider for QuerierTable {
             None => filters.to_vec(),
         };
 
+        // enforce the row-level tag filters of the request, they are applied exactly like all other filters
+        let filters = match ctx.config().options().extensions.get::<IoxConfigExt>() {
+            Some(iox_config) => filters
+                .into_iter()
+                .chain(
+                    iox_config
+                        .tag_filters
+                        .iter()
+                        .map(|filter| tag_filter_expr(filter, self.schema())),
+                )
+                .collect(),
+            None => filters,
+        };
+
         let chunks = self
-            .chunks(&filters, ctx.child_span("QuerierTable chunks"), projection)
+            .chunks(
+                &filters,
+                ctx.child_span("QuerierTable chunks"),
+                projection,
+                read_mode,
+            )
             .await?;
 
         for chunk in chunks {
@@ -43,10 +43,15 @@ pub async fn querier_table(catalog: &Arc<TestCatalog>, table: &Arc<TestTable>) -
         .namespace
         .retention_period_ns
         .map(|retention| Duration::from_nanos(retention as u64));
+    let table_retention_period = table
+        .table
+        .retention_period_ns
+        .map(|retention| Duration::from_nanos(retention as u64));
     QuerierTable::new(QuerierTableArgs {
         namespace_id: table.namespace.namespace.id,
         namespace_name,
         namespace_retention_period,
+        table_retention_period,
         table_id: table.table.id,
         table_name: table.table.name.clone().into(),
         schema,
@@ -0,0 +1,147 @@
+//! Servers queries are federated to.
+
+use std::{collections::BTreeMap, fmt::Debug};
+
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use client_util::connection::{Builder, Connection};
+use data_types::ColumnType;
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
+use influxdb_iox_client::{error::Error as ClientError, flight, schema::Client as SchemaClient};
+use schema::{builder::SchemaBuilder, InfluxColumnType, Schema};
+use snafu::{ResultExt, Snafu};
+use tokio::sync::OnceCell;
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum BackendError {
+    #[snafu(display("Cannot connect: {}", source))]
+    Connect {
+        source: client_util::connection::Error,
+    },
+
+    #[snafu(display("Cannot fetch schema: {}", source))]
+    Schema { source: ClientError },
+
+    #[snafu(display("Invalid schema for table {}: {}", table, reason))]
+    InvalidSchema { table: String, reason: String },
+
+    #[snafu(display("Incompatible schema for table {}: {}", table, source))]
+    IncompatibleSchema {
+        table: String,
+        source: schema::merge::Error,
+    },
+
+    #[snafu(display("Query failed: {}", source))]
+    Query { source: flight::Error },
+}
+
+/// Results of a query sent to a [`Backend`].
+pub type BackendStream = BoxStream<'static, Result<RecordBatch, BackendError>>;
+
+/// A server that (a part of) the data of a namespace is queried from, e.g. a shard or a storage tier.
+#[async_trait]
+pub trait Backend: Debug + Send + Sync {
+    /// Name of the backend, used in logs and error messages.
+    fn name(&self) -> &str;
+
+    /// Schemas of the tables of `namespace`, keyed by table name.
+    ///
+    /// Returns `None` if the namespace does not exist on this backend.
+    async fn table_schemas(
+        &self,
+        namespace: &str,
+    ) -> Result<Option<BTreeMap<String, Schema>>, BackendError>;
+
+    /// Run the SQL query `sql` against `namespace`.
+    ///
+    /// Returns once the backend accepted the query. The results are streamed, so errors while reading them are
+    /// returned by the stream.
+    async fn query(&self, namespace: &str, sql: &str) -> Result<BackendStream, BackendError>;
+}
+
+/// A [`Backend`] reached via the gRPC API of an IOx querier.
+#[derive(Debug)]
+pub struct GrpcBackend {
+    name: String,
+    address: String,
+    connection: OnceCell<Connection>,
+}
+
+impl GrpcBackend {
+    /// Create a backend for the querier at `address`.
+    ///
+    /// The connection is established when the backend is first used.
+    pub fn new(name: impl Into<String>, address: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            address: address.into(),
+            connection: OnceCell::new(),
+        }
+    }
+
+    async fn connection(&self) -> Result<Connection, BackendError> {
+        self.connection
+            .get_or_try_init(|| Builder::new().build(self.address.clone()))
+            .await
+            .cloned()
+            .context(ConnectSnafu)
+    }
+}
+
+#[async_trait]
+impl Backend for GrpcBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn table_schemas(
+        &self,
+        namespace: &str,
+    ) -> Result<Option<BTreeMap<String, Schema>>, BackendError> {
+        let mut client = SchemaClient::new(self.connection().await?);
+        let schema = match client.get_schema(namespace, None).await {
+            Ok(schema) => schema,
+            Err(ClientError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e).context(SchemaSnafu),
+        };
+
+        schema
+            .tables
+            .into_iter()
+            .map(|(table, table_schema)| {
+                let mut builder = SchemaBuilder::new();
+                for (column, column_schema) in table_schema.columns {
+                    let column_type =
+                        ColumnType::try_from(column_schema.column_type()).map_err(|e| {
+                            BackendError::InvalidSchema {
+                                table: table.clone(),
+                                reason: e.to_string(),
+                            }
+                        })?;
+                    builder.influx_column(column, InfluxColumnType::from(column_type));
+                }
+                let schema = builder
+                    .build()
+                    .map_err(|e| BackendError::InvalidSchema {
+                        table: table.clone(),
+                        reason: e.to_string(),
+                    })?
+                    .sort_fields_by_name();
+                Ok((table, schema))
+            })
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+
+    async fn query(&self, namespace: &str, sql: &str) -> Result<BackendStream, BackendError> {
+        let mut client = flight::Client::new(self.connection().await?);
+        let stream = client
+            .sql(namespace.to_string(), sql.to_string())
+            .await
+            .context(QuerySnafu)?;
+        Ok(stream
+            .map_err(|source| BackendError::Query { source })
+            .boxed())
+    }
+}
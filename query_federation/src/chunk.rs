@@ -0,0 +1,140 @@
+//! Data fetched from a backend.
+
+use std::{any::Any, sync::Arc};
+
+use arrow::{
+    array::new_null_array, compute::cast, datatypes::SchemaRef, error::ArrowError,
+    record_batch::RecordBatch,
+};
+use data_types::{ChunkId, ChunkOrder, PartitionId, TransitionPartitionId};
+use datafusion::{
+    error::DataFusionError,
+    physical_plan::{stream::RecordBatchStreamAdapter, Statistics},
+};
+use futures::{StreamExt, TryStreamExt};
+use iox_query::{QueryChunk, QueryChunkData};
+use schema::{sort::SortKey, Schema};
+
+use crate::{backend::Backend, table::TableScan};
+
+/// A [`QueryChunk`] streaming the results of a scan of one backend.
+///
+/// All data of a backend is treated as a single partition. The chunk order is the position of the backend in the
+/// configuration, so that rows that exist on multiple backends are taken from the backend configured last.
+#[derive(Debug)]
+pub(crate) struct FederatedChunk {
+    id: ChunkId,
+    partition_id: TransitionPartitionId,
+    order: ChunkOrder,
+    schema: Schema,
+    backend: Arc<dyn Backend>,
+    scan: Arc<TableScan>,
+    stats: Arc<Statistics>,
+}
+
+impl FederatedChunk {
+    /// Create a chunk for `scan` of `backend`, the backend at position `backend_idx`.
+    ///
+    /// The query is sent to the backend every time the chunk data is read. The results are converted to `schema`:
+    /// columns are matched by name, cast to the type of the schema and filled with NULLs if they are missing.
+    pub(crate) fn new(
+        backend_idx: usize,
+        backend: Arc<dyn Backend>,
+        schema: Schema,
+        scan: Arc<TableScan>,
+    ) -> Self {
+        Self {
+            id: ChunkId::new(),
+            partition_id: TransitionPartitionId::Deprecated(PartitionId::new(backend_idx as i64)),
+            order: ChunkOrder::new(backend_idx as i64),
+            schema,
+            backend,
+            scan,
+            // nothing is known about the results before they are streamed
+            stats: Arc::new(Statistics::default()),
+        }
+    }
+}
+
+fn align_batch(batch: &RecordBatch, schema: &SchemaRef) -> Result<RecordBatch, ArrowError> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match batch.column_by_name(field.name()) {
+            Some(array) if array.data_type() == field.data_type() => Ok(Arc::clone(array)),
+            Some(array) => cast(array, field.data_type()),
+            None => Ok(new_null_array(field.data_type(), batch.num_rows())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    RecordBatch::try_new(Arc::clone(schema), columns)
+}
+
+impl QueryChunk for FederatedChunk {
+    fn stats(&self) -> Arc<Statistics> {
+        Arc::clone(&self.stats)
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn partition_id(&self) -> &TransitionPartitionId {
+        &self.partition_id
+    }
+
+    fn sort_key(&self) -> Option<&SortKey> {
+        // query results are not sorted by primary key
+        None
+    }
+
+    fn id(&self) -> ChunkId {
+        self.id
+    }
+
+    fn may_contain_pk_duplicates(&self) -> bool {
+        // the backend deduplicated its data, but the other backends may have the same rows
+        false
+    }
+
+    fn data(&self) -> QueryChunkData {
+        let schema = Arc::clone(self.schema.inner());
+        let backend = Arc::clone(&self.backend);
+        let scan = Arc::clone(&self.scan);
+
+        let results = {
+            let backend = Arc::clone(&backend);
+            let scan = Arc::clone(&scan);
+            futures::stream::once(
+                async move { backend.query(&scan.namespace_name, &scan.sql).await },
+            )
+            .try_flatten()
+        };
+
+        // a failed backend ends the stream, with an error unless partial results are allowed
+        let stream = {
+            let schema = Arc::clone(&schema);
+            results.scan((), move |_, res| {
+                let res = match res {
+                    Ok(batch) => Some(align_batch(&batch, &schema).map_err(DataFusionError::from)),
+                    Err(e) => scan.backend_failed(backend.name(), e).err().map(Err),
+                };
+                futures::future::ready(res)
+            })
+        };
+
+        QueryChunkData::RecordBatches(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+
+    fn chunk_type(&self) -> &str {
+        "Federated"
+    }
+
+    fn order(&self) -> ChunkOrder {
+        self.order
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,507 @@
+//! Entry point of the federated query path.
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use iox_query::exec::Executor;
+use observability_deps::tracing::warn;
+use schema::{merge::SchemaMerger, Schema};
+use service_common::QueryNamespaceProvider;
+use trace::span::Span;
+use tracker::{
+    AsyncSemaphoreMetrics, InstrumentedAsyncOwnedSemaphorePermit, InstrumentedAsyncSemaphore,
+};
+
+use crate::{
+    backend::Backend,
+    namespace::FederatedNamespace,
+    table::{BackendFailure, FederatedTable},
+    BackendError,
+};
+
+/// A [`QueryNamespaceProvider`] that answers queries with the data of multiple backends, e.g. the shards of a
+/// namespace or its hot and cold storage tiers.
+///
+/// Table scans are sent to all backends that have the table, as SQL queries selecting the required columns and
+/// filtered by the time and tag predicates of the query. All other processing (deduplication, filtering,
+/// aggregation, ...) happens locally on the merged results, so every query behaves as if the data was stored on a
+/// single server. Rows with the same primary key are deduplicated; the row of the backend configured last wins.
+///
+/// The results of the backends are streamed. If a backend fails, or its schema of a table is incompatible with the
+/// others, queries of the table fail with an error naming the failed backends, unless partial results are allowed.
+/// Then the results of the remaining backends are returned and flagged as partial (see
+/// [`PartialResults`](iox_query::exec::PartialResults)), and the failures are logged.
+#[derive(Debug)]
+pub struct FederatedDatabase {
+    backends: Vec<Arc<dyn Backend>>,
+    exec: Arc<Executor>,
+    allow_partial_results: bool,
+    query_execution_semaphore: Arc<InstrumentedAsyncSemaphore>,
+}
+
+impl FederatedDatabase {
+    /// Create a database federating queries to `backends`.
+    pub fn new(
+        backends: Vec<Arc<dyn Backend>>,
+        exec: Arc<Executor>,
+        metric_registry: &metric::Registry,
+        max_concurrent_queries: usize,
+        allow_partial_results: bool,
+    ) -> Self {
+        let semaphore_metrics = Arc::new(AsyncSemaphoreMetrics::new(
+            metric_registry,
+            &[("semaphore", "query_execution")],
+        ));
+        let query_execution_semaphore =
+            Arc::new(semaphore_metrics.new_semaphore(max_concurrent_queries));
+
+        Self {
+            backends,
+            exec,
+            allow_partial_results,
+            query_execution_semaphore,
+        }
+    }
+
+    /// Get namespace if it exists on at least one backend.
+    ///
+    /// Backends that cannot be reached are assumed to have the namespace, so queries report their failure.
+    pub async fn namespace(&self, name: &str) -> Option<Arc<FederatedNamespace>> {
+        let results = futures::future::join_all(
+            self.backends
+                .iter()
+                .map(|backend| async move { backend.table_schemas(name).await }),
+        )
+        .await;
+
+        let mut found = false;
+        let mut unavailable = vec![];
+        let mut tables: HashMap<Arc<str>, (Schema, Vec<_>, Vec<_>)> = HashMap::new();
+        for (idx, (backend, res)) in self.backends.iter().zip(results).enumerate() {
+            match res {
+                Ok(Some(schemas)) => {
+                    found = true;
+                    for (table_name, schema) in schemas {
+                        let table_name = Arc::<str>::from(table_name);
+                        let Some((merged, backends, failures)) = tables.get_mut(&table_name) else {
+                            tables.insert(
+                                table_name,
+                                (schema, vec![(idx, Arc::clone(backend))], vec![]),
+                            );
+                            continue;
+                        };
+
+                        match SchemaMerger::new()
+                            .merge(merged)
+                            .and_then(|merger| merger.merge(&schema))
+                        {
+                            Ok(merger) => {
+                                *merged = merger.build();
+                                backends.push((idx, Arc::clone(backend)));
+                            }
+                            Err(e) => {
+                                warn!(
+                                    backend=%backend.name(),
+                                    namespace=%name,
+                                    %table_name,
+                                    %e,
+                                    "backend has incompatible table schema"
+                                );
+                                failures.push(BackendFailure {
+                                    backend: backend.name().to_string(),
+                                    error: Arc::new(BackendError::IncompatibleSchema {
+                                        table: table_name.to_string(),
+                                        source: e,
+                                    }),
+                                });
+                            }
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(backend=%backend.name(), namespace=%name, %e, "cannot fetch schema from backend");
+                    unavailable.push(BackendFailure {
+                        backend: backend.name().to_string(),
+                        error: Arc::new(e),
+                    });
+                }
+            }
+        }
+
+        if !found && unavailable.is_empty() {
+            return None;
+        }
+
+        let name = Arc::<str>::from(name);
+        let tables = tables
+            .into_iter()
+            .map(|(table_name, (schema, backends, failures))| {
+                let failures = unavailable.iter().cloned().chain(failures).collect();
+                let table = FederatedTable::new(
+                    Arc::clone(&name),
+                    Arc::clone(&table_name),
+                    schema.sort_fields_by_name(),
+                    backends,
+                    failures,
+                    self.backends.len(),
+                    self.allow_partial_results,
+                );
+                (table_name, Arc::new(table))
+            })
+            .collect();
+
+        Some(Arc::new(FederatedNamespace::new(
+            name,
+            tables,
+            Arc::clone(&self.exec),
+        )))
+    }
+}
+
+#[async_trait]
+impl QueryNamespaceProvider for FederatedDatabase {
+    type Db = FederatedNamespace;
+
+    async fn db(
+        &self,
+        name: &str,
+        _span: Option<Span>,
+        _include_debug_info_tables: bool,
+    ) -> Option<Arc<Self::Db>> {
+        self.namespace(name).await
+    }
+
+    async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit {
+        Arc::clone(&self.query_execution_semaphore)
+            .acquire_owned(span)
+            .await
+            .expect("Semaphore should not be closed by anyone")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use arrow::{
+        array::{ArrayRef, DictionaryArray, Float64Array, TimestampNanosecondArray},
+        datatypes::Int32Type,
+        record_batch::RecordBatch,
+    };
+    use arrow_util::assert_batches_sorted_eq;
+    use datafusion::error::DataFusionError;
+    use futures::StreamExt;
+    use iox_query::QueryNamespace;
+    use schema::{builder::SchemaBuilder, InfluxFieldType};
+
+    use super::*;
+    use crate::backend::BackendStream;
+
+    /// How a [`MockBackend`] fails.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Failure {
+        None,
+        /// Every request fails.
+        Down,
+        /// The query fails after returning the rows.
+        Stream,
+        /// The schema of table "cpu" conflicts with the other backends.
+        Schema,
+    }
+
+    #[derive(Debug)]
+    struct MockBackend {
+        name: &'static str,
+        /// Rows of table "cpu" as `(host, load, time)`, or `None` if the namespace does not exist.
+        rows: Option<Vec<(&'static str, f64, i64)>>,
+        failure: Failure,
+    }
+
+    impl MockBackend {
+        fn new(name: &'static str, rows: Vec<(&'static str, f64, i64)>) -> Arc<dyn Backend> {
+            Self::failing(name, rows, Failure::None)
+        }
+
+        fn failing(
+            name: &'static str,
+            rows: Vec<(&'static str, f64, i64)>,
+            failure: Failure,
+        ) -> Arc<dyn Backend> {
+            Arc::new(Self {
+                name,
+                rows: Some(rows),
+                failure,
+            })
+        }
+
+        fn empty(name: &'static str) -> Arc<dyn Backend> {
+            Arc::new(Self {
+                name,
+                rows: None,
+                failure: Failure::None,
+            })
+        }
+
+        fn error(&self) -> BackendError {
+            BackendError::InvalidSchema {
+                table: "cpu".to_string(),
+                reason: format!("{} is down", self.name),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Backend for MockBackend {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn table_schemas(
+            &self,
+            _namespace: &str,
+        ) -> Result<Option<BTreeMap<String, Schema>>, BackendError> {
+            let load_type = match self.failure {
+                Failure::Down => return Err(self.error()),
+                Failure::Schema => InfluxFieldType::String,
+                Failure::None | Failure::Stream => InfluxFieldType::Float,
+            };
+            let schema = SchemaBuilder::new()
+                .tag("host")
+                .influx_field("load", load_type)
+                .timestamp()
+                .build()
+                .unwrap();
+            Ok(self
+                .rows
+                .as_ref()
+                .map(|_| BTreeMap::from([("cpu".to_string(), schema)])))
+        }
+
+        async fn query(&self, _namespace: &str, _sql: &str) -> Result<BackendStream, BackendError> {
+            if self.failure == Failure::Down {
+                return Err(self.error());
+            }
+            let rows = self.rows.as_ref().unwrap();
+            let batch = RecordBatch::try_from_iter(vec![
+                (
+                    "host",
+                    Arc::new(
+                        rows.iter()
+                            .map(|r| r.0)
+                            .collect::<DictionaryArray<Int32Type>>(),
+                    ) as ArrayRef,
+                ),
+                (
+                    "load",
+                    Arc::new(rows.iter().map(|r| r.1).collect::<Float64Array>()) as ArrayRef,
+                ),
+                (
+                    "time",
+                    Arc::new(TimestampNanosecondArray::from_iter_values(
+                        rows.iter().map(|r| r.2),
+                    )) as ArrayRef,
+                ),
+            ])
+            .unwrap();
+
+            let mut results = vec![Ok(batch)];
+            if self.failure == Failure::Stream {
+                results.push(Err(self.error()));
+            }
+            Ok(futures::stream::iter(results).boxed())
+        }
+    }
+
+    fn database(backends: Vec<Arc<dyn Backend>>, allow_partial_results: bool) -> FederatedDatabase {
+        FederatedDatabase::new(
+            backends,
+            Arc::new(Executor::new_testing()),
+            &metric::Registry::default(),
+            10,
+            allow_partial_results,
+        )
+    }
+
+    /// Run `sql`, returning the results and whether they are partial.
+    async fn run_query(
+        db: &FederatedDatabase,
+        sql: &str,
+    ) -> Result<(Vec<RecordBatch>, bool), DataFusionError> {
+        let ns = db.namespace("ns").await.expect("namespace exists");
+        let ctx = ns.new_query_context(None);
+        let plan = ctx.sql_to_physical_plan(sql).await?;
+        let batches = ctx.collect(plan).await?;
+        Ok((batches, ctx.partial_results().get()))
+    }
+
+    #[tokio::test]
+    async fn test_merge_and_dedup() {
+        let db = database(
+            vec![
+                MockBackend::new("a", vec![("a", 1.0, 1), ("b", 2.0, 1)]),
+                MockBackend::new("b", vec![("a", 10.0, 1), ("c", 3.0, 2)]),
+                MockBackend::empty("c"),
+            ],
+            false,
+        );
+
+        let (batches, partial) = run_query(&db, "SELECT host, load, time FROM cpu")
+            .await
+            .unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+------+------+--------------------------------+",
+                "| host | load | time                           |",
+                "+------+------+--------------------------------+",
+                "| a    | 10.0 | 1970-01-01T00:00:00.000000001Z |",
+                "| b    | 2.0  | 1970-01-01T00:00:00.000000001Z |",
+                "| c    | 3.0  | 1970-01-01T00:00:00.000000002Z |",
+                "+------+------+--------------------------------+",
+            ],
+            &batches
+        );
+        assert!(!partial);
+
+        let (batches, _) = run_query(&db, "SELECT SUM(load) AS total FROM cpu WHERE load > 2.5")
+            .await
+            .unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+-------+",
+                "| total |",
+                "+-------+",
+                "| 13.0  |",
+                "+-------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backend_failure() {
+        let backends = || {
+            vec![
+                MockBackend::new("a", vec![("a", 1.0, 1)]),
+                MockBackend::failing("b", vec![], Failure::Down),
+            ]
+        };
+
+        let err = run_query(&database(backends(), false), "SELECT * FROM cpu")
+            .await
+            .unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("Query failed on 1 of 2 backend(s)"), "{err}");
+        assert!(err.contains("backend 'b': "), "{err}");
+
+        let (batches, partial) =
+            run_query(&database(backends(), true), "SELECT host, load FROM cpu")
+                .await
+                .unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+------+------+",
+                "| host | load |",
+                "+------+------+",
+                "| a    | 1.0  |",
+                "+------+------+",
+            ],
+            &batches
+        );
+        assert!(partial);
+    }
+
+    #[tokio::test]
+    async fn test_stream_failure() {
+        let backends = || {
+            vec![
+                MockBackend::new("a", vec![("a", 1.0, 1)]),
+                MockBackend::failing("b", vec![("b", 2.0, 1)], Failure::Stream),
+            ]
+        };
+
+        let err = run_query(&database(backends(), false), "SELECT * FROM cpu")
+            .await
+            .unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("Query failed on 1 of 2 backend(s)"), "{err}");
+        assert!(err.contains("backend 'b': "), "{err}");
+
+        // the rows streamed before the failure are kept
+        let (batches, partial) =
+            run_query(&database(backends(), true), "SELECT host, load FROM cpu")
+                .await
+                .unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+------+------+",
+                "| host | load |",
+                "+------+------+",
+                "| a    | 1.0  |",
+                "| b    | 2.0  |",
+                "+------+------+",
+            ],
+            &batches
+        );
+        assert!(partial);
+    }
+
+    #[tokio::test]
+    async fn test_incompatible_schema() {
+        let backends = || {
+            vec![
+                MockBackend::new("a", vec![("a", 1.0, 1)]),
+                MockBackend::failing("b", vec![("b", 2.0, 1)], Failure::Schema),
+            ]
+        };
+
+        let err = run_query(&database(backends(), false), "SELECT * FROM cpu")
+            .await
+            .unwrap_err();
+        let err = err.to_string();
+        assert!(
+            err.contains("backend 'b': Incompatible schema for table cpu"),
+            "{err}"
+        );
+
+        let (batches, partial) =
+            run_query(&database(backends(), true), "SELECT host, load FROM cpu")
+                .await
+                .unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+------+------+",
+                "| host | load |",
+                "+------+------+",
+                "| a    | 1.0  |",
+                "+------+------+",
+            ],
+            &batches
+        );
+        assert!(partial);
+    }
+
+    #[tokio::test]
+    async fn test_all_backends_failed() {
+        let db = database(
+            vec![
+                MockBackend::failing("a", vec![], Failure::Stream),
+                MockBackend::failing("b", vec![], Failure::Stream),
+            ],
+            true,
+        );
+
+        let err = run_query(&db, "SELECT * FROM cpu").await.unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("Query failed on 2 of 2 backend(s)"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_namespace_not_found() {
+        let db = database(
+            vec![MockBackend::empty("a"), MockBackend::empty("b")],
+            false,
+        );
+        assert!(db.namespace("ns").await.is_none());
+    }
+}
@@ -0,0 +1,33 @@
+//! Query federation across multiple IOx servers.
+//!
+//! A [`FederatedDatabase`] answers queries for namespaces whose data is spread across multiple backends, e.g. the
+//! shards of a namespace or its hot and cold storage tiers. Since it implements
+//! [`QueryNamespaceProvider`](service_common::QueryNamespaceProvider), it can serve the same query APIs (SQL,
+//! InfluxQL, storage RPC) as a querier.
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+mod backend;
+mod chunk;
+mod database;
+mod namespace;
+mod pushdown;
+mod table;
+
+pub use backend::{Backend, BackendError, BackendStream, GrpcBackend};
+pub use database::FederatedDatabase;
+pub use namespace::FederatedNamespace;
@@ -0,0 +1,134 @@
+//! Namespaces whose data is spread across backends.
+
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use datafusion::{
+    catalog::{schema::SchemaProvider, CatalogProvider},
+    datasource::TableProvider,
+    error::DataFusionError,
+    prelude::Expr,
+};
+use datafusion_util::config::DEFAULT_SCHEMA;
+use iox_query::{
+    exec::{Executor, ExecutorType, IOxSessionContext},
+    QueryChunk, QueryCompletedToken, QueryNamespace, QueryText,
+};
+use observability_deps::tracing::debug;
+use trace::ctx::SpanContext;
+
+use crate::table::FederatedTable;
+
+/// A namespace of a [`FederatedDatabase`](crate::FederatedDatabase).
+#[derive(Debug)]
+pub struct FederatedNamespace {
+    name: Arc<str>,
+    tables: Arc<HashMap<Arc<str>, Arc<FederatedTable>>>,
+    exec: Arc<Executor>,
+}
+
+impl FederatedNamespace {
+    pub(crate) fn new(
+        name: Arc<str>,
+        tables: HashMap<Arc<str>, Arc<FederatedTable>>,
+        exec: Arc<Executor>,
+    ) -> Self {
+        Self {
+            name,
+            tables: Arc::new(tables),
+            exec,
+        }
+    }
+}
+
+#[async_trait]
+impl QueryNamespace for FederatedNamespace {
+    async fn chunks(
+        &self,
+        table_name: &str,
+        filters: &[Expr],
+        projection: Option<&Vec<usize>>,
+        ctx: IOxSessionContext,
+    ) -> Result<Vec<Arc<dyn QueryChunk>>, DataFusionError> {
+        debug!(namespace=%self.name, %table_name, ?filters, "Finding chunks for table");
+        match self.tables.get(table_name) {
+            Some(table) => table.chunks(filters, projection, ctx.partial_results()),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn retention_time_ns(&self) -> Option<i64> {
+        // the backends apply their retention policies
+        None
+    }
+
+    fn record_query(
+        &self,
+        _ctx: &IOxSessionContext,
+        _span_ctx: Option<&SpanContext>,
+        query_type: &'static str,
+        query_text: QueryText,
+    ) -> QueryCompletedToken {
+        debug!(namespace=%self.name, query_type, %query_text, "federated query");
+        QueryCompletedToken::new(|_| {})
+    }
+
+    fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
+        self.exec
+            .new_execution_config(ExecutorType::Query)
+            .with_default_catalog(Arc::new(FederatedCatalogProvider {
+                tables: Arc::clone(&self.tables),
+            }) as _)
+            .with_span_context(span_ctx)
+            .build()
+    }
+}
+
+#[derive(Debug)]
+struct FederatedCatalogProvider {
+    tables: Arc<HashMap<Arc<str>, Arc<FederatedTable>>>,
+}
+
+impl CatalogProvider for FederatedCatalogProvider {
+    fn as_any(&self) -> &dyn Any {
+        self as &dyn Any
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        vec![DEFAULT_SCHEMA.to_string()]
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        (name == DEFAULT_SCHEMA).then(|| {
+            Arc::new(FederatedSchemaProvider {
+                tables: Arc::clone(&self.tables),
+            }) as _
+        })
+    }
+}
+
+#[derive(Debug)]
+struct FederatedSchemaProvider {
+    tables: Arc<HashMap<Arc<str>, Arc<FederatedTable>>>,
+}
+
+#[async_trait]
+impl SchemaProvider for FederatedSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self as &dyn Any
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.tables.keys().map(|s| s.to_string()).collect();
+        names.sort();
+        names
+    }
+
+    async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        self.tables.get(name).map(|t| Arc::clone(t) as _)
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.tables.contains_key(name)
+    }
+}
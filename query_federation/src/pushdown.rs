@@ -0,0 +1,148 @@
+//! Translation of table scans into SQL queries for the backends.
+
+use chrono::SecondsFormat;
+use datafusion::{
+    logical_expr::{expr::BinaryExpr, Operator},
+    optimizer::utils::split_conjunction,
+    prelude::Expr,
+    scalar::ScalarValue,
+};
+use iox_time::Time;
+use schema::{InfluxColumnType, Schema};
+
+/// Build the SQL query that fetches `columns` of `table_name` from a backend.
+///
+/// Filters on the time and tag columns are pushed down to the backend. Other filters are not, since they must only be
+/// applied after the data of all backends was deduplicated. The filters are applied to the merged data again, so the
+/// query may return more rows than necessary, but never fewer.
+pub(crate) fn scan_query(
+    table_name: &str,
+    schema: &Schema,
+    columns: &[String],
+    filters: &[Expr],
+) -> String {
+    let select = columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!("SELECT {select} FROM {}", quote_ident(table_name));
+
+    let conditions = filters
+        .iter()
+        .flat_map(split_conjunction)
+        .filter_map(|expr| pushdown_condition(expr, schema))
+        .collect::<Vec<_>>();
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+
+    sql
+}
+
+/// Translate `expr` into a SQL condition, if it only compares the time or a tag column with a literal.
+fn pushdown_condition(expr: &Expr, schema: &Schema) -> Option<String> {
+    let Expr::BinaryExpr(BinaryExpr { left, op, right }) = expr else {
+        return None;
+    };
+    let (column, op, value) = match (left.as_ref(), right.as_ref()) {
+        (Expr::Column(c), Expr::Literal(v)) => (c, *op, v),
+        (Expr::Literal(v), Expr::Column(c)) => (c, op.swap()?, v),
+        _ => return None,
+    };
+    let op = match op {
+        Operator::Eq => "=",
+        Operator::Lt => "<",
+        Operator::LtEq => "<=",
+        Operator::Gt => ">",
+        Operator::GtEq => ">=",
+        _ => return None,
+    };
+
+    let value = match schema.field_by_name(&column.name)?.0 {
+        InfluxColumnType::Timestamp => match value {
+            ScalarValue::TimestampNanosecond(Some(ts), _) => quote_literal(
+                &Time::from_timestamp_nanos(*ts)
+                    .date_time()
+                    .to_rfc3339_opts(SecondsFormat::Nanos, true),
+            ),
+            _ => return None,
+        },
+        InfluxColumnType::Tag if op == "=" => match value {
+            ScalarValue::Utf8(Some(s)) => quote_literal(s),
+            ScalarValue::Dictionary(_, v) => match v.as_ref() {
+                ScalarValue::Utf8(Some(s)) => quote_literal(s),
+                _ => return None,
+            },
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(format!("{} {op} {value}", quote_ident(&column.name)))
+}
+
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::prelude::{col, lit, lit_timestamp_nano};
+    use schema::{builder::SchemaBuilder, InfluxFieldType};
+
+    use super::*;
+
+    fn schema() -> Schema {
+        SchemaBuilder::new()
+            .tag("host")
+            .influx_field("load", InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap()
+    }
+
+    fn columns() -> Vec<String> {
+        vec!["host".to_string(), "load".to_string(), "time".to_string()]
+    }
+
+    #[test]
+    fn test_no_filters() {
+        assert_eq!(
+            scan_query("cpu", &schema(), &columns(), &[]),
+            r#"SELECT "host", "load", "time" FROM "cpu""#
+        );
+    }
+
+    #[test]
+    fn test_pushdown() {
+        let filters = [
+            col("time")
+                .gt_eq(lit_timestamp_nano(1_000_000_001))
+                .and(col("host").eq(lit("a'b"))),
+            lit_timestamp_nano(2_000_000_000).gt(col("time")),
+            // not pushed down
+            col("load").gt(lit(1.0)),
+            col("host").not_eq(lit("c")),
+            col("host").eq(lit("a")).or(col("host").eq(lit("b"))),
+        ];
+
+        assert_eq!(
+            scan_query("cpu", &schema(), &columns(), &filters),
+            r#"SELECT "host", "load", "time" FROM "cpu" WHERE "time" >= '1970-01-01T00:00:01.000000001Z' AND "host" = 'a''b' AND "time" < '1970-01-01T00:00:02.000000000Z'"#
+        );
+    }
+
+    #[test]
+    fn test_quote_ident() {
+        assert_eq!(
+            scan_query("my \"table\"", &schema(), &["host".to_string()], &[]),
+            r#"SELECT "host" FROM "my ""table""""#
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Tables whose data is spread across backends.
+
+use std::{any::Any, sync::Arc};
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+use datafusion::{
+    datasource::{TableProvider, TableType},
+    error::DataFusionError,
+    execution::context::SessionState,
+    logical_expr::TableProviderFilterPushDown,
+    physical_plan::ExecutionPlan,
+    prelude::Expr,
+};
+use iox_query::{
+    config::IoxConfigExt,
+    exec::{PartialResults, SessionContextIOxExt},
+    provider::ProviderBuilder,
+    pruning::tag_filter_expr,
+    QueryChunk,
+};
+use observability_deps::tracing::{debug, warn};
+use parking_lot::Mutex;
+use schema::Schema;
+use snafu::Snafu;
+
+use crate::{backend::Backend, chunk::FederatedChunk, pushdown::scan_query, BackendError};
+
+/// A backend that could not be queried.
+#[derive(Debug, Clone)]
+pub(crate) struct BackendFailure {
+    pub(crate) backend: String,
+    pub(crate) error: Arc<BackendError>,
+}
+
+impl std::fmt::Display for BackendFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "backend '{}': {}", self.backend, self.error)
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub(crate) enum Error {
+    #[snafu(display(
+        "Query failed on {} of {} backend(s): {}",
+        failures.len(),
+        total,
+        failures.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    ))]
+    BackendsFailed {
+        failures: Vec<BackendFailure>,
+        total: usize,
+    },
+}
+
+/// A table of a federated namespace.
+///
+/// Every scan of the table is turned into a SQL query that is sent to all backends that have the table. The results
+/// are streamed from the backends, then deduplicated and filtered like the chunks of a regular table.
+#[derive(Debug)]
+pub(crate) struct FederatedTable {
+    namespace_name: Arc<str>,
+    table_name: Arc<str>,
+    /// Merged schema of the table across all backends, sorted by column name.
+    schema: Schema,
+    /// Backends that have the table, with their position in the configuration.
+    backends: Vec<(usize, Arc<dyn Backend>)>,
+    /// Backends that may have data of the table but cannot be queried, because they could not be asked for their
+    /// schema or their schema of the table is incompatible.
+    failures: Arc<[BackendFailure]>,
+    /// Total number of backends.
+    total_backends: usize,
+    allow_partial_results: bool,
+}
+
+impl FederatedTable {
+    pub(crate) fn new(
+        namespace_name: Arc<str>,
+        table_name: Arc<str>,
+        schema: Schema,
+        backends: Vec<(usize, Arc<dyn Backend>)>,
+        failures: Arc<[BackendFailure]>,
+        total_backends: usize,
+        allow_partial_results: bool,
+    ) -> Self {
+        Self {
+            namespace_name,
+            table_name,
+            schema,
+            backends,
+            failures,
+            total_backends,
+            allow_partial_results,
+        }
+    }
+
+    /// Create a chunk per backend that streams the data matching `filters`.
+    ///
+    /// Chunks include the PK columns and the columns in `projection` (all columns if `None`). The backends are only
+    /// queried once the chunks are read.
+    pub(crate) fn chunks(
+        &self,
+        filters: &[Expr],
+        projection: Option<&Vec<usize>>,
+        partial: PartialResults,
+    ) -> Result<Vec<Arc<dyn QueryChunk>>, DataFusionError> {
+        let columns = self.schema.select_given_and_pk_columns(projection);
+        let chunk_schema = self
+            .schema
+            .select_by_names(&columns.iter().map(String::as_str).collect::<Vec<_>>())
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let sql = scan_query(&self.table_name, &self.schema, &columns, filters);
+        debug!(namespace=%self.namespace_name, table=%self.table_name, %sql, "federating table scan");
+
+        let scan = Arc::new(TableScan {
+            namespace_name: Arc::clone(&self.namespace_name),
+            table_name: Arc::clone(&self.table_name),
+            sql,
+            failures: Mutex::new(self.failures.to_vec()),
+            candidates: self.failures.len() + self.backends.len(),
+            total_backends: self.total_backends,
+            allow_partial_results: self.allow_partial_results,
+            partial,
+        });
+        scan.check_failures(&scan.failures.lock())?;
+
+        Ok(self
+            .backends
+            .iter()
+            .map(|(idx, backend)| {
+                Arc::new(FederatedChunk::new(
+                    *idx,
+                    Arc::clone(backend),
+                    chunk_schema.clone(),
+                    Arc::clone(&scan),
+                )) as Arc<dyn QueryChunk>
+            })
+            .collect())
+    }
+}
+
+/// A scan of a [`FederatedTable`], shared by the chunks of its backends.
+#[derive(Debug)]
+pub(crate) struct TableScan {
+    pub(crate) namespace_name: Arc<str>,
+    pub(crate) table_name: Arc<str>,
+    /// SQL query sent to the backends.
+    pub(crate) sql: String,
+    /// Backends that failed so far, including those that could not be queried at all.
+    failures: Mutex<Vec<BackendFailure>>,
+    /// Number of backends that may have data of the table.
+    candidates: usize,
+    /// Total number of backends.
+    total_backends: usize,
+    allow_partial_results: bool,
+    partial: PartialResults,
+}
+
+impl TableScan {
+    /// Record that `backend` failed while its results were read.
+    ///
+    /// Returns the error the query fails with, unless partial results are allowed and some backend has not failed.
+    /// Then the results are flagged as partial instead.
+    pub(crate) fn backend_failed(
+        &self,
+        backend: &str,
+        error: BackendError,
+    ) -> Result<(), DataFusionError> {
+        let mut failures = self.failures.lock();
+        failures.push(BackendFailure {
+            backend: backend.to_string(),
+            error: Arc::new(error),
+        });
+        self.check_failures(&failures)
+    }
+
+    fn check_failures(&self, failures: &[BackendFailure]) -> Result<(), DataFusionError> {
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        let err = Error::BackendsFailed {
+            failures: failures.to_vec(),
+            total: self.total_backends,
+        };
+        if !self.allow_partial_results || failures.len() >= self.candidates {
+            return Err(DataFusionError::External(Box::new(err)));
+        }
+        warn!(
+            namespace=%self.namespace_name,
+            table=%self.table_name,
+            %err,
+            "returning partial query results"
+        );
+        self.partial.set();
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TableProvider for FederatedTable {
+    fn as_any(&self) -> &dyn Any {
+        self as &dyn Any
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.as_arrow()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        ctx: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        // enforce the row-level tag filters of the request
+        let filters = match ctx.config().options().extensions.get::<IoxConfigExt>() {
+            Some(iox_config) => filters
+                .iter()
+                .cloned()
+                .chain(
+                    iox_config
+                        .tag_filters
+                        .iter()
+                        .map(|filter| tag_filter_expr(filter, &self.schema)),
+                )
+                .collect(),
+            None => filters.to_vec(),
+        };
+
+        let mut builder = ProviderBuilder::new(Arc::clone(&self.table_name), self.schema.clone());
+        for chunk in self.chunks(&filters, projection, ctx.partial_results())? {
+            builder = builder.add_chunk(chunk);
+        }
+
+        let provider = match builder.build() {
+            Ok(provider) => provider,
+            Err(e) => panic!("unexpected error: {e:?}"),
+        };
+
+        provider.scan(ctx, projection, &filters, limit).await
+    }
+
+    fn supports_filter_pushdown(
+        &self,
+        _filter: &Expr,
+    ) -> Result<TableProviderFilterPushDown, DataFusionError> {
+        Ok(TableProviderFilterPushDown::Exact)
+    }
+}
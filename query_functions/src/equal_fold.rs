@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{as_dictionary_array, as_string_array, ArrayRef, BooleanArray},
+    compute::take,
+    datatypes::{DataType, Int32Type},
+};
+use datafusion::{
+    error::{DataFusionError, Result},
+    logical_expr::{
+        ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF, Signature, TypeSignature,
+        Volatility,
+    },
+    physical_plan::ColumnarValue,
+    scalar::ScalarValue,
+};
+use once_cell::sync::Lazy;
+
+/// The name of the equal_fold UDF given to DataFusion.
+pub const EQUAL_FOLD_UDF_NAME: &str = "equal_fold";
+
+/// Implementation of equal_fold
+pub(crate) static EQUAL_FOLD_UDF: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
+    // Accept dictionary encoded strings (tags) as-is, so they are not
+    // unpacked before the comparison
+    let signature = Signature::one_of(
+        vec![
+            TypeSignature::Exact(vec![DataType::Utf8, DataType::Utf8]),
+            TypeSignature::Exact(vec![
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                DataType::Utf8,
+            ]),
+        ],
+        Volatility::Immutable,
+    );
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Boolean)));
+    let fun: ScalarFunctionImplementation = Arc::new(equal_fold_impl);
+
+    Arc::new(ScalarUDF::new(
+        EQUAL_FOLD_UDF_NAME,
+        &signature,
+        &return_type_fn,
+        &fun,
+    ))
+});
+
+/// Given a column containing string values and a single string value,
+/// `equal_fold` determines which values are equal to the given value
+/// when compared case-insensitively (equivalent to Go's
+/// `strings.EqualFold`, or comparing `strings.toLower` of both sides in
+/// Flux).
+///
+/// For dictionary encoded columns each distinct value of the dictionary
+/// is only compared once.
+///
+/// Returns NULL for NULL inputs.
+fn equal_fold_impl(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    assert_eq!(args.len(), 2); // only works over a single column and value at a time.
+
+    let value = match &args[1] {
+        // second arg was array (not constant)
+        ColumnarValue::Array(_) => {
+            return Err(DataFusionError::NotImplemented(
+                "equal_fold with non scalar values not yet implemented".to_string(),
+            ))
+        }
+        ColumnarValue::Scalar(ScalarValue::Utf8(value)) => value,
+        ColumnarValue::Scalar(arg) => {
+            return Err(DataFusionError::Internal(format!(
+                "Expected string value to equal_fold, got: {arg:?}"
+            )))
+        }
+    };
+
+    let Some(value) = value else {
+        return Ok(ColumnarValue::Scalar(ScalarValue::Boolean(None)));
+    };
+    let value = fold(value);
+
+    match &args[0] {
+        ColumnarValue::Array(arr) => Ok(ColumnarValue::Array(equal_fold_array(arr, &value)?)),
+        ColumnarValue::Scalar(row) => Ok(ColumnarValue::Scalar(ScalarValue::Boolean(
+            equal_fold_scalar(row, &value)?,
+        ))),
+    }
+}
+
+fn equal_fold_array(arr: &ArrayRef, folded: &str) -> Result<ArrayRef> {
+    match arr.data_type() {
+        DataType::Utf8 => {
+            let results = as_string_array(arr)
+                .iter()
+                .map(|row| row.map(|v| is_equal_fold(v, folded)))
+                .collect::<BooleanArray>();
+            Ok(Arc::new(results))
+        }
+        DataType::Dictionary(key_type, _) if key_type.as_ref() == &DataType::Int32 => {
+            // compare the distinct values once and look up the result by key
+            let dict = as_dictionary_array::<Int32Type>(arr);
+            let value_results = equal_fold_array(dict.values(), folded)?;
+            Ok(take(&value_results, dict.keys(), None)?)
+        }
+        other => Err(DataFusionError::Internal(format!(
+            "equal_fold expected first argument to be utf8, got ('{other}')"
+        ))),
+    }
+}
+
+fn equal_fold_scalar(row: &ScalarValue, folded: &str) -> Result<Option<bool>> {
+    match row {
+        ScalarValue::Utf8(row) => Ok(row.as_deref().map(|v| is_equal_fold(v, folded))),
+        ScalarValue::Dictionary(_, row) => equal_fold_scalar(row, folded),
+        other => Err(DataFusionError::Internal(format!(
+            "equal_fold expected first argument to be utf8, got ('{other}')"
+        ))),
+    }
+}
+
+/// Lower case `s` character by character.
+fn fold(s: &str) -> String {
+    s.chars().flat_map(char::to_lowercase).collect()
+}
+
+/// Returns true if `s` lower cased is `folded`, without allocating.
+fn is_equal_fold(s: &str, folded: &str) -> bool {
+    s.chars().flat_map(char::to_lowercase).eq(folded.chars())
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::{DictionaryArray, StringArray},
+        record_batch::RecordBatch,
+        util::pretty::pretty_format_batches,
+    };
+    use datafusion::prelude::{col, lit, Expr};
+    use datafusion_util::context_with_table;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn equal_fold_expr() {
+        let words = vec![
+            Some("Server01"),
+            Some("SERVER01"),
+            None,
+            Some("server02"),
+            Some("Server01 "),
+        ];
+
+        let expected = vec![
+            "+----------+",
+            "| words    |",
+            "+----------+",
+            "| Server01 |",
+            "| SERVER01 |",
+            "+----------+",
+        ];
+
+        let strings: ArrayRef = Arc::new(StringArray::from(words.clone()));
+        let dictionary: ArrayRef =
+            Arc::new(words.into_iter().collect::<DictionaryArray<Int32Type>>());
+
+        for words in [strings, dictionary] {
+            let expr = EQUAL_FOLD_UDF.call(vec![col("words"), lit("server01")]);
+            let actual = run_plan(words.clone(), expr).await;
+            assert_eq!(expected, actual, "input: {words:?}");
+
+            // the value is folded as well
+            let expr = EQUAL_FOLD_UDF.call(vec![col("words"), lit("SeRvEr01")]);
+            let actual = run_plan(words, expr).await;
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[tokio::test]
+    async fn equal_fold_expr_negated() {
+        let words: ArrayRef = Arc::new(StringArray::from(vec![Some("Air"), None, Some("bruce")]));
+        let expr = Expr::Not(Box::new(
+            EQUAL_FOLD_UDF.call(vec![col("words"), lit("AIR")]),
+        ));
+
+        let expected = vec![
+            "+-------+",
+            "| words |",
+            "+-------+",
+            "| bruce |",
+            "+-------+",
+        ];
+        assert_eq!(expected, run_plan(words, expr).await);
+    }
+
+    // Run a plan against a table "t" with the column "words"
+    async fn run_plan(words: ArrayRef, op: Expr) -> Vec<String> {
+        let rb = RecordBatch::try_from_iter(vec![("words", words)]).unwrap();
+
+        let ctx = context_with_table(rb);
+        let df = ctx.table("t").await.unwrap();
+        let df = df.filter(op).unwrap();
+
+        let record_batches = df.collect().await.unwrap();
+
+        pretty_format_batches(&record_batches)
+            .unwrap()
+            .to_string()
+            .split('\n')
+            .map(|s| s.to_owned())
+            .collect()
+    }
+}
@@ -2,10 +2,11 @@
 //! and Aggregate functions in IOx, designed to be compatible with
 //! InfluxDB classic
 
+use arrow::datatypes::DataType;
 use datafusion::prelude::Expr;
 use snafu::Snafu;
 
-use crate::window;
+use crate::{rate, window};
 
 #[allow(missing_docs)]
 #[derive(Debug, Snafu)]
@@ -63,10 +64,142 @@ pub enum Aggregate {
     /// Aggregate: Average (geometric mean) column's value
     Mean,
 
+    /// Aggregate: the average increase per second of a counter,
+    /// handling counter resets
+    Rate,
+
+    /// Aggregate: the change per second between the values with the
+    /// minimum and maximum timestamps
+    Derivative,
+
+    /// Aggregate: like `Derivative`, but null if the value decreased
+    NonNegativeDerivative,
+
+    /// Aggregate: the total increase of a counter, handling counter
+    /// resets
+    Increase,
+
+    /// Selector: Selects the given number of rows with the largest
+    /// values of a column and their timestamps. In the case of
+    /// multiple rows with the same value, the earliest timestamps are
+    /// used
+    Top(i64),
+
+    /// Selector: Selects the given number of rows with the smallest
+    /// values of a column and their timestamps. In the case of
+    /// multiple rows with the same value, the earliest timestamps are
+    /// used
+    Bottom(i64),
+
+    /// Aggregate: whether any value of a boolean column is true
+    Any,
+
+    /// Aggregate: whether all values of a boolean column are true
+    All,
+
     /// No grouping is applied
     None,
 }
 
+/// An arithmetic expression over aggregates of fields, such as
+/// `sum(bytes_total) / sum(requests)`
+///
+/// The aggregates are converted to `Float64` before any arithmetic,
+/// so the result of an expression is always a `Float64`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AggregateExpr {
+    /// The aggregate of a field
+    Field {
+        /// How the values of the field are aggregated
+        agg: Aggregate,
+        /// The name of the field
+        field: String,
+    },
+
+    /// A constant
+    Literal(f64),
+
+    /// `left op right`
+    Binary {
+        /// The left operand
+        left: Box<Self>,
+        /// The operator
+        op: ArithmeticOperator,
+        /// The right operand
+        right: Box<Self>,
+    },
+}
+
+/// Operators of [`AggregateExpr::Binary`]
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticOperator {
+    Plus,
+    Minus,
+    Multiply,
+    /// Division by zero results in `NULL`
+    Divide,
+}
+
+/// An [`AggregateExpr`] and the name of the field holding its result
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedAggregateExpr {
+    /// The name of the resulting field
+    pub name: String,
+    /// The expression
+    pub expr: AggregateExpr,
+}
+
+impl AggregateExpr {
+    /// The aggregated fields of this expression, in order of appearance
+    pub fn field_aggregates(&self) -> Vec<(Aggregate, &str)> {
+        let mut out = vec![];
+        self.collect_field_aggregates(&mut out);
+        out
+    }
+
+    fn collect_field_aggregates<'a>(&'a self, out: &mut Vec<(Aggregate, &'a str)>) {
+        match self {
+            Self::Field { agg, field } => out.push((*agg, field)),
+            Self::Literal(_) => {}
+            Self::Binary { left, right, .. } => {
+                left.collect_field_aggregates(out);
+                right.collect_field_aggregates(out);
+            }
+        }
+    }
+
+    /// Create the DataFusion expression calculating this expression,
+    /// given the expression `aggregate` that refers to the aggregate of
+    /// a field
+    pub fn to_datafusion_expr(&self, aggregate: &dyn Fn(Aggregate, &str) -> Expr) -> Expr {
+        use datafusion::{
+            logical_expr::{binary_expr, Operator},
+            prelude::{cast, lit, when},
+            scalar::ScalarValue,
+        };
+
+        match self {
+            Self::Field { agg, field } => cast(aggregate(*agg, field), DataType::Float64),
+            Self::Literal(value) => lit(*value),
+            Self::Binary { left, op, right } => {
+                let left = left.to_datafusion_expr(aggregate);
+                let right = right.to_datafusion_expr(aggregate);
+                match op {
+                    ArithmeticOperator::Plus => binary_expr(left, Operator::Plus, right),
+                    ArithmeticOperator::Minus => binary_expr(left, Operator::Minus, right),
+                    ArithmeticOperator::Multiply => binary_expr(left, Operator::Multiply, right),
+                    ArithmeticOperator::Divide => {
+                        when(right.clone().eq(lit(0.0)), lit(ScalarValue::Float64(None)))
+                            .otherwise(binary_expr(left, Operator::Divide, right))
+                            .expect("CASE expression with a WHEN clause")
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Represents some duration in time
 #[allow(missing_docs)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -78,10 +211,65 @@ pub enum WindowDuration {
     Fixed { nanoseconds: i64 },
 }
 
+/// How windows without data are filled when aggregating by window
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum WindowFill {
+    /// Windows without data are omitted
+    #[default]
+    None,
+
+    /// Windows without data use the value of the most recent window
+    /// with data in the same series
+    Previous,
+
+    /// Windows without data are linearly interpolated between the
+    /// surrounding windows with data in the same series
+    Linear,
+}
+
 impl Aggregate {
+    /// Can this aggregate be calculated for a column of type `data_type`?
+    ///
+    /// Arithmetic aggregates require numeric values, `Any` and `All`
+    /// require booleans. `Count` and the selectors accept any type.
+    pub fn supports_type(self, data_type: &DataType) -> bool {
+        match self {
+            Self::Sum
+            | Self::Mean
+            | Self::Rate
+            | Self::Derivative
+            | Self::NonNegativeDerivative
+            | Self::Increase => data_type.is_numeric(),
+            Self::Any | Self::All => data_type == &DataType::Boolean,
+            Self::Count
+            | Self::Min
+            | Self::Max
+            | Self::First
+            | Self::Last
+            | Self::Top(_)
+            | Self::Bottom(_)
+            | Self::None => true,
+        }
+    }
+
     /// Create the appropriate DataFusion expression for this aggregate
     pub fn to_datafusion_expr(self, input: Expr) -> Result<Expr> {
-        use datafusion::prelude::{avg, count, max, min, sum};
+        use datafusion::{
+            logical_expr::{expr, AggregateFunction},
+            prelude::{avg, col, count, max, min, sum},
+        };
+        use schema::TIME_COLUMN_NAME;
+
+        let rate_args = |input| vec![input, col(TIME_COLUMN_NAME)];
+        let bool_agg = |fun, input| {
+            Expr::AggregateFunction(expr::AggregateFunction::new(
+                fun,
+                vec![input],
+                false,
+                None,
+                None,
+            ))
+        };
         match self {
             Self::Sum => Ok(sum(input)),
             Self::Count => Ok(count(input)),
@@ -90,6 +278,16 @@ impl Aggregate {
             Self::First => AggregateNotSupportedSnafu { agg: "First" }.fail(),
             Self::Last => AggregateNotSupportedSnafu { agg: "Last" }.fail(),
             Self::Mean => Ok(avg(input)),
+            Self::Rate => Ok(rate::rate().call(rate_args(input))),
+            Self::Derivative => Ok(rate::derivative().call(rate_args(input))),
+            Self::NonNegativeDerivative => {
+                Ok(rate::non_negative_derivative().call(rate_args(input)))
+            }
+            Self::Increase => Ok(rate::increase().call(rate_args(input))),
+            Self::Top(_) => AggregateNotSupportedSnafu { agg: "Top" }.fail(),
+            Self::Bottom(_) => AggregateNotSupportedSnafu { agg: "Bottom" }.fail(),
+            Self::Any => Ok(bool_agg(AggregateFunction::BoolOr, input)),
+            Self::All => Ok(bool_agg(AggregateFunction::BoolAnd, input)),
             Self::None => AggregateNotSupportedSnafu { agg: "None" }.fail(),
         }
     }
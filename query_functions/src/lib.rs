@@ -25,15 +25,30 @@ use window::EncodedWindowDuration;
 
 pub mod coalesce_struct;
 
+/// Case insensitive string comparison
+mod equal_fold;
+
 /// Grouping by structs
 pub mod group_by;
 
+/// Rate Functions
+pub mod rate;
+
 /// Regular Expressions
 mod regex;
 
 /// Selector Functions
 pub mod selectors;
 
+/// Prefix, suffix and substring matching of strings
+mod string_match;
+
+/// Quantile Sketch Functions
+pub mod tdigest;
+
+/// Overflow checked sum of unsigned integers
+pub mod unsigned_sum;
+
 /// window_bounds expressions
 mod window;
 
@@ -42,9 +57,11 @@ pub mod gapfill;
 /// Function registry
 mod registry;
 
+pub use crate::equal_fold::EQUAL_FOLD_UDF_NAME;
 pub use crate::regex::clean_non_meta_escapes;
 pub use crate::regex::REGEX_MATCH_UDF_NAME;
 pub use crate::regex::REGEX_NOT_MATCH_UDF_NAME;
+pub use crate::string_match::{CONTAINS_STR_UDF_NAME, HAS_PREFIX_UDF_NAME, HAS_SUFFIX_UDF_NAME};
 
 /// Return an Expr that invokes a InfluxRPC compatible regex match to
 /// determine which values satisfy the pattern. Equivalent to:
@@ -72,6 +89,46 @@ pub fn regex_not_match_expr(input: Expr, pattern: String) -> Expr {
         .call(vec![input, lit(pattern)])
 }
 
+/// Return an Expr that invokes a case-insensitive comparison of the
+/// string values of `input` with `value`. Equivalent to:
+///
+/// ```text
+/// lower(col) = lower(value)
+/// ```
+pub fn equal_fold_expr(input: Expr, value: String) -> Expr {
+    registry()
+        .udf(equal_fold::EQUAL_FOLD_UDF_NAME)
+        .expect("EqualFold function not registered")
+        .call(vec![input, lit(value)])
+}
+
+/// Return an Expr that determines which string values of `input` start
+/// with `prefix`. Equivalent to Flux's `strings.hasPrefix`.
+pub fn has_prefix_expr(input: Expr, prefix: String) -> Expr {
+    registry()
+        .udf(string_match::HAS_PREFIX_UDF_NAME)
+        .expect("HasPrefix function not registered")
+        .call(vec![input, lit(prefix)])
+}
+
+/// Return an Expr that determines which string values of `input` end
+/// with `suffix`. Equivalent to Flux's `strings.hasSuffix`.
+pub fn has_suffix_expr(input: Expr, suffix: String) -> Expr {
+    registry()
+        .udf(string_match::HAS_SUFFIX_UDF_NAME)
+        .expect("HasSuffix function not registered")
+        .call(vec![input, lit(suffix)])
+}
+
+/// Return an Expr that determines which string values of `input` contain
+/// `substr`. Equivalent to Flux's `strings.containsStr`.
+pub fn contains_str_expr(input: Expr, substr: String) -> Expr {
+    registry()
+        .udf(string_match::CONTAINS_STR_UDF_NAME)
+        .expect("ContainsStr function not registered")
+        .call(vec![input, lit(substr)])
+}
+
 /// Create a DataFusion `Expr` that invokes `window_bounds` with the
 /// appropriate every and offset arguments at runtime
 pub fn make_window_bound_expr(
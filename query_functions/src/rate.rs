@@ -0,0 +1,424 @@
+//! ## Overview
+//!
+//! *Rate functions* are IOx SQL aggregate functions that calculate how
+//! fast a value changes over time within a group, as is commonly
+//! needed for monitoring counters and gauges.
+//!
+//! Like selectors, rate functions take the `time` column as their
+//! second argument, as the rows of a group are not ordered by time:
+//!
+//! ```sql
+//! select host, rate(requests, time) from "http" group by host;
+//! ```
+//!
+//! ## Supported Functions
+//!
+//! IOx supports the following rate functions, each returning a
+//! `Float64`:
+//!
+//! 1. `derivative`: the change of `value` per second between the rows
+//!    with the earliest and the latest `time` of the group
+//! 2. `non_negative_derivative`: like `derivative`, but `NULL` if the
+//!    value decreased
+//! 3. `increase`: the total increase of a counter over the group,
+//!    treating any decrease between consecutive rows as a counter reset
+//!    (i.e. the counter restarted from zero)
+//! 4. `rate`: the average increase of a counter per second, treating
+//!    decreases as counter resets like `increase`
+//!
+//! All functions ignore rows with a `NULL` value and return `NULL` if
+//! the group has less than two rows, or if all rows have the same
+//! `time`.
+use std::sync::Arc;
+
+use arrow::{
+    array::{as_list_array, Array, ArrayRef, Float64Array, Int64Array},
+    compute::{cast, kernels::numeric::mul},
+    datatypes::{DataType, Field, TimeUnit},
+};
+use datafusion::{
+    common::downcast_value,
+    error::{DataFusionError, Result as DataFusionResult},
+    logical_expr::{
+        AccumulatorFactoryFunction, ReturnTypeFunction, Signature, StateTypeFunction, Volatility,
+    },
+    physical_plan::{udaf::AggregateUDF, Accumulator},
+    prelude::SessionContext,
+    scalar::ScalarValue,
+};
+
+/// Nanoseconds per second
+const NANOS_PER_SECOND: f64 = 1_000_000_000.0;
+
+/// registers rate functions so they can be invoked via SQL
+pub fn register_rate_aggregates(ctx: &SessionContext) {
+    ctx.register_udaf(derivative());
+    ctx.register_udaf(non_negative_derivative());
+    ctx.register_udaf(increase());
+    ctx.register_udaf(rate());
+}
+
+/// Returns a DataFusion user defined aggregate function for computing
+/// `derivative(value, time)`: the change of `value` per second between
+/// the first and the last row of the group.
+pub fn derivative() -> AggregateUDF {
+    make_uda("derivative", RateType::Derivative)
+}
+
+/// Returns a DataFusion user defined aggregate function for computing
+/// `non_negative_derivative(value, time)`: the change of `value` per
+/// second between the first and the last row of the group, or `NULL` if
+/// the value decreased.
+pub fn non_negative_derivative() -> AggregateUDF {
+    make_uda("non_negative_derivative", RateType::NonNegativeDerivative)
+}
+
+/// Returns a DataFusion user defined aggregate function for computing
+/// `increase(value, time)`: the total increase of a counter over the
+/// group, handling counter resets.
+pub fn increase() -> AggregateUDF {
+    make_uda("increase", RateType::Increase)
+}
+
+/// Returns a DataFusion user defined aggregate function for computing
+/// `rate(value, time)`: the average increase of a counter per second
+/// over the group, handling counter resets.
+pub fn rate() -> AggregateUDF {
+    make_uda("rate", RateType::Rate)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RateType {
+    Derivative,
+    NonNegativeDerivative,
+    Increase,
+    Rate,
+}
+
+/// Create a User Defined Aggregate Function (UDAF) for datafusion.
+fn make_uda(name: &'static str, rate_type: RateType) -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(move |arg_types| {
+        match arg_types {
+            [value_type, DataType::Timestamp(_, _)] if value_type.is_numeric() => {}
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{name} requires a numeric value and a timestamp argument, got {arg_types:?}"
+                )))
+            }
+        }
+        Ok(Arc::new(DataType::Float64))
+    });
+
+    let accumulator_factory: AccumulatorFactoryFunction =
+        Arc::new(move |_| Ok(Box::new(RateAccumulator::new(rate_type))));
+
+    let state_type_factory: StateTypeFunction = Arc::new(|_| {
+        Ok(Arc::new(vec![
+            DataType::List(Arc::new(Field::new("item", DataType::Int64, true))),
+            DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+        ]))
+    });
+
+    AggregateUDF::new(
+        name,
+        &Signature::any(2, Volatility::Immutable),
+        &return_type_func,
+        &accumulator_factory,
+        &state_type_factory,
+    )
+}
+
+/// Collects the `(time, value)` points of a group, which are only
+/// ordered by time once the result is evaluated.
+#[derive(Debug)]
+struct RateAccumulator {
+    rate_type: RateType,
+    points: Vec<(i64, f64)>,
+}
+
+impl RateAccumulator {
+    fn new(rate_type: RateType) -> Self {
+        Self {
+            rate_type,
+            points: vec![],
+        }
+    }
+
+    fn update(&mut self, values: &ArrayRef, times: &ArrayRef) -> DataFusionResult<()> {
+        let values = cast(values, &DataType::Float64)?;
+        let values = downcast_value!(values, Float64Array);
+        let times = nanos(times)?;
+        let times = downcast_value!(times, Int64Array);
+
+        self.points.extend(
+            values
+                .iter()
+                .zip(times.iter())
+                .filter_map(|(value, time)| Some((time?, value?))),
+        );
+        Ok(())
+    }
+}
+
+impl Accumulator for RateAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DataFusionResult<()> {
+        assert_eq!(values.len(), 2);
+        self.update(&values[0], &values[1])
+    }
+
+    fn evaluate(&self) -> DataFusionResult<ScalarValue> {
+        let mut points = self.points.clone();
+        points.sort_by_key(|(time, _)| *time);
+
+        let (Some(&(first_time, first_value)), Some(&(last_time, last_value))) =
+            (points.first(), points.last())
+        else {
+            return Ok(ScalarValue::Float64(None));
+        };
+        if first_time == last_time {
+            return Ok(ScalarValue::Float64(None));
+        }
+        let seconds = (last_time - first_time) as f64 / NANOS_PER_SECOND;
+
+        let result = match self.rate_type {
+            RateType::Derivative => Some((last_value - first_value) / seconds),
+            RateType::NonNegativeDerivative => {
+                Some((last_value - first_value) / seconds).filter(|d| *d >= 0.0)
+            }
+            RateType::Increase => Some(increase_of(&points)),
+            RateType::Rate => Some(increase_of(&points) / seconds),
+        };
+
+        Ok(ScalarValue::Float64(result))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.points.capacity() * std::mem::size_of::<(i64, f64)>()
+    }
+
+    fn state(&self) -> DataFusionResult<Vec<ScalarValue>> {
+        let (times, values): (Vec<_>, Vec<_>) = self
+            .points
+            .iter()
+            .map(|(time, value)| {
+                (
+                    ScalarValue::Int64(Some(*time)),
+                    ScalarValue::Float64(Some(*value)),
+                )
+            })
+            .unzip();
+
+        Ok(vec![
+            ScalarValue::new_list(Some(times), DataType::Int64),
+            ScalarValue::new_list(Some(values), DataType::Float64),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DataFusionResult<()> {
+        assert_eq!(states.len(), 2);
+
+        let times = as_list_array(&states[0]);
+        let values = as_list_array(&states[1]);
+        for idx in 0..times.len() {
+            if times.is_valid(idx) && values.is_valid(idx) {
+                self.update(&values.value(idx), &times.value(idx))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cast `times` to nanoseconds since the epoch.
+///
+/// `times` is either a timestamp of any unit, or the nanoseconds of the
+/// partial state of an accumulator.
+fn nanos(times: &ArrayRef) -> DataFusionResult<ArrayRef> {
+    let nanos_per_unit = match times.data_type() {
+        DataType::Timestamp(TimeUnit::Second, _) => 1_000_000_000,
+        DataType::Timestamp(TimeUnit::Millisecond, _) => 1_000_000,
+        DataType::Timestamp(TimeUnit::Microsecond, _) => 1_000,
+        _ => 1,
+    };
+
+    let times = cast(times, &DataType::Int64)?;
+    if nanos_per_unit == 1 {
+        return Ok(times);
+    }
+    Ok(mul(&times, &Int64Array::new_scalar(nanos_per_unit))?)
+}
+
+/// The total increase of a counter over time ordered `points`. A
+/// decrease between two consecutive points is a counter reset, after
+/// which the counter increased from zero to the new value.
+fn increase_of(points: &[(i64, f64)]) -> f64 {
+    points
+        .windows(2)
+        .map(|w| {
+            let (previous, current) = (w[0].1, w[1].1);
+            if current >= previous {
+                current - previous
+            } else {
+                current
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::{
+            Float64Array, Int64Array, StringArray, TimestampMillisecondArray,
+            TimestampNanosecondArray, TimestampSecondArray,
+        },
+        record_batch::RecordBatch,
+        util::pretty::pretty_format_batches,
+    };
+    use datafusion::{datasource::MemTable, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn test_increase_of() {
+        assert_eq!(increase_of(&[]), 0.0);
+        assert_eq!(increase_of(&[(1, 5.0)]), 0.0);
+        assert_eq!(increase_of(&[(1, 5.0), (2, 7.0), (3, 10.0)]), 5.0);
+        // reset between 2 and 3: 5 -> 7 (+2), 7 -> 3 (+3), 3 -> 4 (+1)
+        assert_eq!(increase_of(&[(1, 5.0), (2, 7.0), (3, 3.0), (4, 4.0)]), 6.0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_functions() {
+        let actual = run_query(
+            "SELECT \
+               tag, \
+               derivative(f64_value, time) AS d, \
+               non_negative_derivative(f64_value, time) AS nnd, \
+               increase(i64_value, time) AS i, \
+               rate(i64_value, time) AS r \
+             FROM t GROUP BY tag ORDER BY tag",
+        )
+        .await;
+
+        // tag a: f64 10 -> 4 over 2s, i64 counter 10, 30, 5 (reset), 15
+        // tag b: f64 1 -> 3 over 1s, i64 counter 100 -> 110
+        // tag c: a single row
+        let expected = vec![
+            "+-----+------+-----+------+------+",
+            "| tag | d    | nnd | i    | r    |",
+            "+-----+------+-----+------+------+",
+            "| a   | -3.0 |     | 35.0 | 17.5 |",
+            "| b   | 2.0  | 2.0 | 10.0 | 10.0 |",
+            "| c   |      |     |      |      |",
+            "+-----+------+-----+------+------+",
+        ];
+        assert_eq!(
+            expected, actual,
+            "\n\nEXPECTED:\n{expected:#?}\nACTUAL:\n{actual:#?}\n"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_units() {
+        let values: ArrayRef = Arc::new(Float64Array::from(vec![10.0, 4.0]));
+        for times in [
+            Arc::new(TimestampSecondArray::from(vec![1, 3])) as ArrayRef,
+            Arc::new(TimestampMillisecondArray::from(vec![1_000, 3_000])),
+            Arc::new(TimestampNanosecondArray::from(vec![
+                1_000_000_000,
+                3_000_000_000,
+            ])),
+        ] {
+            let mut acc = RateAccumulator::new(RateType::Derivative);
+            acc.update_batch(&[Arc::clone(&values), Arc::clone(&times)])
+                .unwrap();
+            assert_eq!(
+                acc.evaluate().unwrap(),
+                ScalarValue::Float64(Some(-3.0)),
+                "{:?}",
+                times.data_type()
+            );
+
+            // the partial state holds nanoseconds
+            let mut merged = RateAccumulator::new(RateType::Derivative);
+            let state = acc
+                .state()
+                .unwrap()
+                .into_iter()
+                .map(|v| v.to_array())
+                .collect::<Vec<_>>();
+            merged.merge_batch(&state).unwrap();
+            assert_eq!(merged.evaluate().unwrap(), ScalarValue::Float64(Some(-3.0)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let ctx = context();
+        let err = ctx
+            .sql("SELECT rate(tag, time) FROM t")
+            .await
+            .expect_err("expected error");
+        assert!(
+            err.to_string()
+                .contains("rate requires a numeric value and a timestamp argument"),
+            "{err}"
+        );
+    }
+
+    fn context() -> SessionContext {
+        // Rows are not ordered by time
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "tag",
+                Arc::new(StringArray::from(vec!["a", "b", "a", "a", "c", "b", "a"])) as ArrayRef,
+            ),
+            (
+                "f64_value",
+                Arc::new(Float64Array::from(vec![
+                    Some(4.0),
+                    Some(3.0),
+                    Some(10.0),
+                    None,
+                    Some(1.0),
+                    Some(1.0),
+                    Some(7.0),
+                ])),
+            ),
+            (
+                "i64_value",
+                Arc::new(Int64Array::from(vec![15, 110, 10, 5, 1, 100, 30])),
+            ),
+            (
+                "time",
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    3_000_000_000,
+                    2_000_000_000,
+                    1_000_000_000,
+                    2_500_000_000,
+                    1_000_000_000,
+                    1_000_000_000,
+                    2_000_000_000,
+                ])),
+            ),
+        ])
+        .unwrap();
+
+        let provider = MemTable::try_new(batch.schema(), vec![vec![batch]]).unwrap();
+        let ctx = SessionContext::new();
+        ctx.register_table("t", Arc::new(provider)).unwrap();
+        register_rate_aggregates(&ctx);
+        ctx
+    }
+
+    async fn run_query(sql: &str) -> Vec<String> {
+        let batches = context().sql(sql).await.unwrap().collect().await.unwrap();
+
+        pretty_format_batches(&batches)
+            .unwrap()
+            .to_string()
+            .split('\n')
+            .map(|s| s.to_owned())
+            .collect()
+    }
+}
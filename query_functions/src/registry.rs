@@ -7,7 +7,7 @@ use datafusion::{
 };
 use once_cell::sync::Lazy;
 
-use crate::{gapfill, regex, window};
+use crate::{equal_fold, gapfill, regex, string_match, tdigest, window};
 
 static REGISTRY: Lazy<IOxFunctionRegistry> = Lazy::new(IOxFunctionRegistry::new);
 
@@ -24,11 +24,16 @@ impl IOxFunctionRegistry {
 impl FunctionRegistry for IOxFunctionRegistry {
     fn udfs(&self) -> HashSet<String> {
         [
+            equal_fold::EQUAL_FOLD_UDF_NAME,
             gapfill::DATE_BIN_GAPFILL_UDF_NAME,
             gapfill::LOCF_UDF_NAME,
             gapfill::INTERPOLATE_UDF_NAME,
             regex::REGEX_MATCH_UDF_NAME,
             regex::REGEX_NOT_MATCH_UDF_NAME,
+            string_match::CONTAINS_STR_UDF_NAME,
+            string_match::HAS_PREFIX_UDF_NAME,
+            string_match::HAS_SUFFIX_UDF_NAME,
+            tdigest::TDIGEST_QUANTILE_UDF_NAME,
             window::WINDOW_BOUNDS_UDF_NAME,
         ]
         .into_iter()
@@ -38,11 +43,16 @@ impl FunctionRegistry for IOxFunctionRegistry {
 
     fn udf(&self, name: &str) -> DataFusionResult<Arc<ScalarUDF>> {
         match name {
+            equal_fold::EQUAL_FOLD_UDF_NAME => Ok(equal_fold::EQUAL_FOLD_UDF.clone()),
             gapfill::DATE_BIN_GAPFILL_UDF_NAME => Ok(gapfill::DATE_BIN_GAPFILL.clone()),
             gapfill::LOCF_UDF_NAME => Ok(gapfill::LOCF.clone()),
             gapfill::INTERPOLATE_UDF_NAME => Ok(gapfill::INTERPOLATE.clone()),
             regex::REGEX_MATCH_UDF_NAME => Ok(regex::REGEX_MATCH_UDF.clone()),
             regex::REGEX_NOT_MATCH_UDF_NAME => Ok(regex::REGEX_NOT_MATCH_UDF.clone()),
+            string_match::CONTAINS_STR_UDF_NAME => Ok(string_match::CONTAINS_STR_UDF.clone()),
+            string_match::HAS_PREFIX_UDF_NAME => Ok(string_match::HAS_PREFIX_UDF.clone()),
+            string_match::HAS_SUFFIX_UDF_NAME => Ok(string_match::HAS_SUFFIX_UDF.clone()),
+            tdigest::TDIGEST_QUANTILE_UDF_NAME => Ok(tdigest::TDIGEST_QUANTILE_UDF.clone()),
             window::WINDOW_BOUNDS_UDF_NAME => Ok(window::WINDOW_BOUNDS_UDF.clone()),
             _ => Err(DataFusionError::Plan(format!(
                 "IOx FunctionRegistry does not contain function '{name}'"
@@ -84,6 +84,8 @@
 //! 2. `selector_last`: `time` and `value` of the row with latest `time` in the group
 //! 3. `selector_min`: `time` and `value` of the row with smallest `value` in the group
 //! 4. `selector_max`: `time` and `value` of the row with largest `value` in the group
+//! 5. `selector_top`: a list of the `time` and `value` of the `n` rows with the largest `value` in the group
+//! 6. `selector_bottom`: a list of the `time` and `value` of the `n` rows with the smallest `value` in the group
 //!
 //! For `selector_first` / `selector_last`, if there are multiple
 //! rows with same minimum / maximum timestamp, the value returned is
@@ -93,6 +95,15 @@
 //! with the same minimum / maximum value, the value with the smallest
 //! timestamp is chosen.
 //!
+//! `selector_top` / `selector_bottom` take the number of rows to select
+//! as their third argument, and return the rows ordered from the
+//! largest / smallest `value`, again preferring rows with a smaller
+//! timestamp if values are equal:
+//!
+//! ```sql
+//! select selector_top(water_level, time, 2) from "h2o_feet";
+//! ```
+//!
 //! [InfluxQL]: https://docs.influxdata.com/influxdb/v1.8/query_language/
 //! [selector functions]: https://docs.influxdata.com/influxdb/v1.8/query_language/functions/#selectors
 use std::{fmt::Debug, sync::Arc};
@@ -108,6 +119,9 @@ use datafusion::{
 mod internal;
 use internal::{Comparison, Selector, Target};
 
+mod top;
+use top::Direction;
+
 mod type_handling;
 use type_handling::AggType;
 
@@ -117,6 +131,8 @@ pub fn register_selector_aggregates(ctx: &SessionContext) {
     ctx.register_udaf(selector_last());
     ctx.register_udaf(selector_min());
     ctx.register_udaf(selector_max());
+    ctx.register_udaf(selector_top());
+    ctx.register_udaf(selector_bottom());
 }
 
 /// Returns a DataFusion user defined aggregate function for computing
@@ -191,6 +207,42 @@ pub fn selector_max() -> AggregateUDF {
     make_uda("selector_max", FactoryBuilder::new(SelectorType::Max))
 }
 
+/// Returns a DataFusion user defined aggregate function for computing
+/// the top(value, time, n) selector function, returning a list of
+/// structs:
+///
+/// top(value, time, n) -> list [ struct { value, time } ]
+///
+/// ```text
+/// [
+///   {
+///     value: value of one of the `n` rows with the largest values
+///     time: value of time for that row
+///   },
+///   ...
+/// ]
+/// ```
+///
+/// The list is ordered by descending value. If there are multiple
+/// rows with the same value, the rows with the first
+/// (earliest/smallest) timestamps are chosen. The group is not sorted,
+/// only the `n` best rows seen so far are kept.
+pub fn selector_top() -> AggregateUDF {
+    make_top_uda("selector_top", Direction::Top)
+}
+
+/// Returns a DataFusion user defined aggregate function for computing
+/// the bottom(value, time, n) selector function, returning a list of
+/// structs:
+///
+/// bottom(value, time, n) -> list [ struct { value, time } ]
+///
+/// The list is ordered by ascending value. See [`selector_top`] for
+/// details.
+pub fn selector_bottom() -> AggregateUDF {
+    make_top_uda("selector_bottom", Direction::Bottom)
+}
+
 #[derive(Debug, Clone, Copy)]
 enum SelectorType {
     First,
@@ -296,6 +348,18 @@ fn make_uda(name: &str, factory_builder: FactoryBuilder) -> AggregateUDF {
     )
 }
 
+/// Create a User Defined Aggregate Function (UDAF) for the top / bottom
+/// selectors.
+fn make_top_uda(name: &'static str, direction: Direction) -> AggregateUDF {
+    AggregateUDF::new(
+        name,
+        &Signature::any(3, Volatility::Stable),
+        &top::return_type_func(name),
+        &top::accumulator_factory(direction),
+        &top::state_type_func(),
+    )
+}
+
 #[cfg(test)]
 mod test {
     use arrow::{
@@ -310,7 +374,7 @@ mod test {
     use datafusion::{datasource::MemTable, prelude::*};
 
     use super::*;
-    use utils::{run_case, run_case_tz, run_cases_err};
+    use utils::{run_case, run_case_err, run_case_tz, run_cases_err};
 
     mod first {
         use super::*;
@@ -1108,6 +1172,176 @@ mod test {
         }
     }
 
+    mod top {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_f64() {
+            run_case(
+                selector_top().call(vec![col("f64_value"), col("time"), lit(2)]),
+                vec![
+                    "+--------------------------------------------------------------------------------------------------+",
+                    "| selector_top(t.f64_value,t.time,Int32(2))                                                        |",
+                    "+--------------------------------------------------------------------------------------------------+",
+                    "| [{value: 5.0, time: 1970-01-01T00:00:00.000005}, {value: 4.0, time: 1970-01-01T00:00:00.000002}] |",
+                    "+--------------------------------------------------------------------------------------------------+",
+                ],
+            )
+            .await;
+
+            run_case(
+                selector_top().call(vec![col("f64_value"), col("time"), lit(1)]),
+                vec![
+                    "+--------------------------------------------------+",
+                    "| selector_top(t.f64_value,t.time,Int32(1))        |",
+                    "+--------------------------------------------------+",
+                    "| [{value: 5.0, time: 1970-01-01T00:00:00.000005}] |",
+                    "+--------------------------------------------------+",
+                ],
+            )
+            .await;
+        }
+
+        #[tokio::test]
+        async fn test_i64() {
+            run_case(
+                selector_top().call(vec![col("i64_value"), col("time"), lit(10)]),
+                vec![
+                    "+---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+",
+                    "| selector_top(t.i64_value,t.time,Int32(10))                                                                                                                                                                                                  |",
+                    "+---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+",
+                    "| [{value: 50, time: 1970-01-01T00:00:00.000005}, {value: 40, time: 1970-01-01T00:00:00.000002}, {value: 30, time: 1970-01-01T00:00:00.000006}, {value: 20, time: 1970-01-01T00:00:00.000001}, {value: 10, time: 1970-01-01T00:00:00.000004}] |",
+                    "+---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+",
+                ],
+            )
+            .await;
+        }
+
+        #[tokio::test]
+        async fn test_string() {
+            run_case(
+                selector_top().call(vec![col("string_value"), col("time"), lit(2)]),
+                vec![
+                    "+-----------------------------------------------------------------------------------------------------+",
+                    "| selector_top(t.string_value,t.time,Int32(2))                                                        |",
+                    "+-----------------------------------------------------------------------------------------------------+",
+                    "| [{value: z_five, time: 1970-01-01T00:00:00.000005}, {value: two, time: 1970-01-01T00:00:00.000001}] |",
+                    "+-----------------------------------------------------------------------------------------------------+",
+                ],
+            )
+            .await;
+        }
+
+        #[tokio::test]
+        async fn test_bool() {
+            run_case(
+                selector_top().call(vec![col("bool_const"), col("time"), lit(2)]),
+                vec![
+                    "+----------------------------------------------------------------------------------------------------+",
+                    "| selector_top(t.bool_const,t.time,Int32(2))                                                         |",
+                    "+----------------------------------------------------------------------------------------------------+",
+                    "| [{value: true, time: 1970-01-01T00:00:00.000001}, {value: true, time: 1970-01-01T00:00:00.000002}] |",
+                    "+----------------------------------------------------------------------------------------------------+",
+                ],
+            )
+            .await;
+        }
+
+        #[tokio::test]
+        async fn test_err() {
+            run_case_err(
+                selector_top().call(vec![col("f64_value"), col("time"), col("string_value")]),
+                "Error during planning: selector_top third argument must be an integer, but got Utf8",
+            )
+            .await;
+
+            run_case_err(
+                selector_top().call(vec![col("f64_value"), col("f64_value"), lit(1)]),
+                "Error during planning: selector_top second argument must be a timestamp, but got Float64",
+            )
+            .await;
+        }
+    }
+
+    mod bottom {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_f64() {
+            run_case(
+                selector_bottom().call(vec![col("f64_value"), col("time"), lit(2)]),
+                vec![
+                    "+--------------------------------------------------------------------------------------------------+",
+                    "| selector_bottom(t.f64_value,t.time,Int32(2))                                                     |",
+                    "+--------------------------------------------------------------------------------------------------+",
+                    "| [{value: 1.0, time: 1970-01-01T00:00:00.000004}, {value: 2.0, time: 1970-01-01T00:00:00.000001}] |",
+                    "+--------------------------------------------------------------------------------------------------+",
+                ],
+            )
+            .await;
+        }
+
+        #[tokio::test]
+        async fn test_i64() {
+            run_case(
+                selector_bottom().call(vec![col("i64_value"), col("time"), lit(10)]),
+                vec![
+                    "+---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+",
+                    "| selector_bottom(t.i64_value,t.time,Int32(10))                                                                                                                                                                                               |",
+                    "+---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+",
+                    "| [{value: 10, time: 1970-01-01T00:00:00.000004}, {value: 20, time: 1970-01-01T00:00:00.000001}, {value: 30, time: 1970-01-01T00:00:00.000006}, {value: 40, time: 1970-01-01T00:00:00.000002}, {value: 50, time: 1970-01-01T00:00:00.000005}] |",
+                    "+---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+",
+                ],
+            )
+            .await;
+        }
+
+        #[tokio::test]
+        async fn test_string() {
+            run_case(
+                selector_bottom().call(vec![col("string_value"), col("time"), lit(2)]),
+                vec![
+                    "+-----------------------------------------------------------------------------------------------------+",
+                    "| selector_bottom(t.string_value,t.time,Int32(2))                                                     |",
+                    "+-----------------------------------------------------------------------------------------------------+",
+                    "| [{value: a_one, time: 1970-01-01T00:00:00.000004}, {value: four, time: 1970-01-01T00:00:00.000002}] |",
+                    "+-----------------------------------------------------------------------------------------------------+",
+                ],
+            )
+            .await;
+        }
+
+        #[tokio::test]
+        async fn test_bool() {
+            run_case(
+                selector_bottom().call(vec![col("bool_value"), col("time"), lit(2)]),
+                vec![
+                    "+------------------------------------------------------------------------------------------------------+",
+                    "| selector_bottom(t.bool_value,t.time,Int32(2))                                                        |",
+                    "+------------------------------------------------------------------------------------------------------+",
+                    "| [{value: false, time: 1970-01-01T00:00:00.000002}, {value: false, time: 1970-01-01T00:00:00.000005}] |",
+                    "+------------------------------------------------------------------------------------------------------+",
+                ],
+            )
+            .await;
+        }
+
+        #[tokio::test]
+        async fn test_err() {
+            run_case_err(
+                selector_bottom().call(vec![col("f64_value"), col("time"), col("string_value")]),
+                "Error during planning: selector_bottom third argument must be an integer, but got Utf8",
+            )
+            .await;
+
+            run_case_err(
+                selector_bottom().call(vec![col("f64_value"), col("f64_value"), lit(1)]),
+                "Error during planning: selector_bottom second argument must be a timestamp, but got Float64",
+            )
+            .await;
+        }
+    }
+
     mod utils {
         use arrow::datatypes::TimeUnit;
 
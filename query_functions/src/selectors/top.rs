@@ -0,0 +1,292 @@
+//! Internal implementation of the "top" / "bottom" selector functions,
+//! which select the `n` rows with the largest / smallest values of a
+//! group.
+//! Tests are in selector module
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    sync::Arc,
+};
+
+use arrow::{
+    array::{as_list_array, as_struct_array, Array, ArrayRef, Int64Array},
+    compute::cast,
+    datatypes::{DataType, Field},
+};
+use datafusion::{
+    common::downcast_value,
+    error::{DataFusionError, Result as DataFusionResult},
+    logical_expr::{AccumulatorFactoryFunction, ReturnTypeFunction, StateTypeFunction},
+    physical_plan::Accumulator,
+    scalar::ScalarValue,
+};
+
+use super::type_handling::{make_struct_datatype, make_struct_scalar};
+
+/// Which rows to select?
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    /// the rows with the largest values
+    Top,
+    /// the rows with the smallest values
+    Bottom,
+}
+
+/// Returns a function that returns the type of
+/// `selector_top(value, time, n)` / `selector_bottom(value, time, n)`:
+/// a list of `struct { value, time }`
+pub fn return_type_func(name: &'static str) -> ReturnTypeFunction {
+    Arc::new(move |arg_types| {
+        let [value_type, time_type, n_type] = arg_types else {
+            return Err(DataFusionError::Plan(format!(
+                "{name} requires 3 arguments, got {}",
+                arg_types.len()
+            )));
+        };
+
+        if !matches!(time_type, DataType::Timestamp(_, _)) {
+            return Err(DataFusionError::Plan(format!(
+                "{name} second argument must be a timestamp, but got {time_type}"
+            )));
+        }
+
+        if !n_type.is_integer() {
+            return Err(DataFusionError::Plan(format!(
+                "{name} third argument must be an integer, but got {n_type}"
+            )));
+        }
+
+        Ok(Arc::new(list_type(make_struct_datatype(
+            value_type,
+            time_type,
+            [],
+        ))))
+    })
+}
+
+/// The state is the selected rows (in the output format) and `n`
+pub fn state_type_func() -> StateTypeFunction {
+    Arc::new(|return_type| Ok(Arc::new(vec![return_type.clone(), DataType::Int64])))
+}
+
+/// Returns a function that instantiates the accumulator
+pub fn accumulator_factory(direction: Direction) -> AccumulatorFactoryFunction {
+    Arc::new(move |return_type| {
+        let struct_type = match return_type {
+            DataType::List(field) => field.data_type().clone(),
+            _ => {
+                return Err(DataFusionError::Internal(format!(
+                    "Unexpected return type for top/bottom selector: {return_type}"
+                )))
+            }
+        };
+        let time_type = match &struct_type {
+            DataType::Struct(fields) if fields.len() == 2 => fields[1].data_type().clone(),
+            _ => {
+                return Err(DataFusionError::Internal(format!(
+                    "Unexpected return type for top/bottom selector: {return_type}"
+                )))
+            }
+        };
+
+        Ok(Box::new(TopSelector::new(
+            direction,
+            struct_type,
+            time_type,
+        )))
+    })
+}
+
+fn list_type(item_type: DataType) -> DataType {
+    DataType::List(Arc::new(Field::new("item", item_type, true)))
+}
+
+/// A selected row, ordered so that the better candidate is the greater
+#[derive(Debug)]
+struct Candidate {
+    direction: Direction,
+    value: ScalarValue,
+    time: i64,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_value = self
+            .value
+            .partial_cmp(&other.value)
+            .unwrap_or(Ordering::Equal);
+        let by_value = match self.direction {
+            Direction::Top => by_value,
+            Direction::Bottom => by_value.reverse(),
+        };
+
+        // for rows with the same value, the earliest one is selected
+        by_value.then_with(|| other.time.cmp(&self.time))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+/// Keeps the best `n` rows seen so far in a bounded heap, rather than
+/// sorting all the rows of the group.
+#[derive(Debug)]
+pub struct TopSelector {
+    direction: Direction,
+    struct_type: DataType,
+    time_type: DataType,
+    /// The number of rows to select, known once the first batch is seen
+    n: Option<usize>,
+    /// The selected rows, with the worst one at the top
+    heap: BinaryHeap<Reverse<Candidate>>,
+}
+
+impl TopSelector {
+    fn new(direction: Direction, struct_type: DataType, time_type: DataType) -> Self {
+        Self {
+            direction,
+            struct_type,
+            time_type,
+            n: None,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    fn set_n(&mut self, n_arr: &ArrayRef) -> DataFusionResult<()> {
+        if self.n.is_some() {
+            return Ok(());
+        }
+
+        let n_arr = cast(n_arr, &DataType::Int64)?;
+        let n_arr = downcast_value!(n_arr, Int64Array);
+        if let Some(n) = n_arr.iter().flatten().next() {
+            if n <= 0 {
+                return Err(DataFusionError::Execution(format!(
+                    "top/bottom selector requires a positive number of values, got {n}"
+                )));
+            }
+            self.n = Some(n as usize);
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, value_arr: &ArrayRef, time_arr: &ArrayRef) -> DataFusionResult<()> {
+        let Some(n) = self.n else {
+            return Ok(());
+        };
+
+        let time_arr = cast(time_arr, &DataType::Int64)?;
+        let time_arr = downcast_value!(time_arr, Int64Array);
+
+        for idx in 0..value_arr.len() {
+            if value_arr.is_null(idx) || time_arr.is_null(idx) {
+                continue;
+            }
+
+            let candidate = Candidate {
+                direction: self.direction,
+                value: ScalarValue::try_from_array(value_arr, idx)?,
+                time: time_arr.value(idx),
+            };
+
+            if self.heap.len() < n {
+                self.heap.push(Reverse(candidate));
+            } else if self
+                .heap
+                .peek()
+                .map(|Reverse(worst)| &candidate > worst)
+                .unwrap_or(false)
+            {
+                self.heap.pop();
+                self.heap.push(Reverse(candidate));
+            }
+        }
+        Ok(())
+    }
+
+    /// The selected rows, best first
+    fn to_list(&self) -> DataFusionResult<ScalarValue> {
+        let mut candidates = self
+            .heap
+            .iter()
+            .map(|Reverse(candidate)| candidate)
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| b.cmp(a));
+
+        let entries = candidates
+            .into_iter()
+            .map(|candidate| {
+                let time = ScalarValue::Int64(Some(candidate.time)).cast_to(&self.time_type)?;
+                Ok(make_struct_scalar(&candidate.value, &time, []))
+            })
+            .collect::<DataFusionResult<Vec<_>>>()?;
+
+        Ok(ScalarValue::new_list(
+            Some(entries),
+            self.struct_type.clone(),
+        ))
+    }
+}
+
+impl Accumulator for TopSelector {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DataFusionResult<()> {
+        assert_eq!(values.len(), 3);
+        self.set_n(&values[2])?;
+        self.update(&values[0], &values[1])
+    }
+
+    fn evaluate(&self) -> DataFusionResult<ScalarValue> {
+        if self.heap.is_empty() {
+            return Ok(ScalarValue::new_list(None, self.struct_type.clone()));
+        }
+        self.to_list()
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.struct_type)
+            + self.struct_type.size()
+            + self
+                .heap
+                .iter()
+                .map(|Reverse(candidate)| {
+                    std::mem::size_of_val(candidate) - std::mem::size_of_val(&candidate.value)
+                        + candidate.value.size()
+                })
+                .sum::<usize>()
+    }
+
+    fn state(&self) -> DataFusionResult<Vec<ScalarValue>> {
+        Ok(vec![
+            self.to_list()?,
+            ScalarValue::Int64(self.n.map(|n| n as i64)),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DataFusionResult<()> {
+        assert_eq!(states.len(), 2);
+        self.set_n(&states[1])?;
+
+        let lists = as_list_array(&states[0]);
+        for idx in 0..lists.len() {
+            if lists.is_null(idx) {
+                continue;
+            }
+            let entries = lists.value(idx);
+            let entries = as_struct_array(&entries);
+            self.update(entries.column(0), entries.column(1))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{as_dictionary_array, as_string_array, ArrayRef, BooleanArray},
+    compute::take,
+    datatypes::{DataType, Int32Type},
+};
+use datafusion::{
+    error::{DataFusionError, Result},
+    logical_expr::{
+        ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF, Signature, TypeSignature,
+        Volatility,
+    },
+    physical_plan::ColumnarValue,
+    scalar::ScalarValue,
+};
+use once_cell::sync::Lazy;
+
+/// The name of the has_prefix UDF given to DataFusion.
+pub const HAS_PREFIX_UDF_NAME: &str = "has_prefix";
+
+/// The name of the has_suffix UDF given to DataFusion.
+pub const HAS_SUFFIX_UDF_NAME: &str = "has_suffix";
+
+/// The name of the contains_str UDF given to DataFusion.
+pub const CONTAINS_STR_UDF_NAME: &str = "contains_str";
+
+/// Implementation of has_prefix
+pub(crate) static HAS_PREFIX_UDF: Lazy<Arc<ScalarUDF>> =
+    Lazy::new(|| make_udf(StringMatch::Prefix));
+
+/// Implementation of has_suffix
+pub(crate) static HAS_SUFFIX_UDF: Lazy<Arc<ScalarUDF>> =
+    Lazy::new(|| make_udf(StringMatch::Suffix));
+
+/// Implementation of contains_str
+pub(crate) static CONTAINS_STR_UDF: Lazy<Arc<ScalarUDF>> =
+    Lazy::new(|| make_udf(StringMatch::Contains));
+
+/// The ways a string value can be matched against a pattern, named after
+/// the Flux functions `strings.hasPrefix`, `strings.hasSuffix` and
+/// `strings.containsStr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StringMatch {
+    Prefix,
+    Suffix,
+    Contains,
+}
+
+impl StringMatch {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Prefix => HAS_PREFIX_UDF_NAME,
+            Self::Suffix => HAS_SUFFIX_UDF_NAME,
+            Self::Contains => CONTAINS_STR_UDF_NAME,
+        }
+    }
+
+    fn matches(&self, value: &str, pattern: &str) -> bool {
+        match self {
+            Self::Prefix => value.starts_with(pattern),
+            Self::Suffix => value.ends_with(pattern),
+            Self::Contains => value.contains(pattern),
+        }
+    }
+}
+
+fn make_udf(kind: StringMatch) -> Arc<ScalarUDF> {
+    // Accept dictionary encoded strings (tags) as-is, so they are not
+    // unpacked before the comparison
+    let signature = Signature::one_of(
+        vec![
+            TypeSignature::Exact(vec![DataType::Utf8, DataType::Utf8]),
+            TypeSignature::Exact(vec![
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                DataType::Utf8,
+            ]),
+        ],
+        Volatility::Immutable,
+    );
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Boolean)));
+    let fun: ScalarFunctionImplementation =
+        Arc::new(move |args: &[ColumnarValue]| string_match_impl(kind, args));
+
+    Arc::new(ScalarUDF::new(
+        kind.name(),
+        &signature,
+        &return_type_fn,
+        &fun,
+    ))
+}
+
+/// Given a column containing string values and a single string pattern,
+/// determines which values start with, end with or contain the pattern,
+/// depending on `kind`.
+///
+/// For dictionary encoded columns each distinct value of the dictionary
+/// is only matched once.
+///
+/// Returns NULL for NULL inputs.
+fn string_match_impl(kind: StringMatch, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    assert_eq!(args.len(), 2); // only works over a single column and pattern at a time.
+
+    let pattern = match &args[1] {
+        // second arg was array (not constant)
+        ColumnarValue::Array(_) => {
+            return Err(DataFusionError::NotImplemented(format!(
+                "{} with non scalar patterns not yet implemented",
+                kind.name()
+            )))
+        }
+        ColumnarValue::Scalar(ScalarValue::Utf8(pattern)) => pattern,
+        ColumnarValue::Scalar(arg) => {
+            return Err(DataFusionError::Internal(format!(
+                "Expected string pattern to {}, got: {arg:?}",
+                kind.name()
+            )))
+        }
+    };
+
+    let Some(pattern) = pattern else {
+        return Ok(ColumnarValue::Scalar(ScalarValue::Boolean(None)));
+    };
+
+    match &args[0] {
+        ColumnarValue::Array(arr) => Ok(ColumnarValue::Array(string_match_array(
+            kind, arr, pattern,
+        )?)),
+        ColumnarValue::Scalar(row) => Ok(ColumnarValue::Scalar(ScalarValue::Boolean(
+            string_match_scalar(kind, row, pattern)?,
+        ))),
+    }
+}
+
+fn string_match_array(kind: StringMatch, arr: &ArrayRef, pattern: &str) -> Result<ArrayRef> {
+    match arr.data_type() {
+        DataType::Utf8 => {
+            let results = as_string_array(arr)
+                .iter()
+                .map(|row| row.map(|v| kind.matches(v, pattern)))
+                .collect::<BooleanArray>();
+            Ok(Arc::new(results))
+        }
+        DataType::Dictionary(key_type, _) if key_type.as_ref() == &DataType::Int32 => {
+            let dict = as_dictionary_array::<Int32Type>(arr);
+
+            // match the distinct values once and look up the result by key
+            let value_results = string_match_array(kind, dict.values(), pattern)?;
+            Ok(take(&value_results, dict.keys(), None)?)
+        }
+        other => Err(DataFusionError::Internal(format!(
+            "{} expected first argument to be utf8, got ('{other}')",
+            kind.name()
+        ))),
+    }
+}
+
+fn string_match_scalar(
+    kind: StringMatch,
+    row: &ScalarValue,
+    pattern: &str,
+) -> Result<Option<bool>> {
+    match row {
+        ScalarValue::Utf8(row) => Ok(row.as_deref().map(|v| kind.matches(v, pattern))),
+        ScalarValue::Dictionary(_, row) => string_match_scalar(kind, row, pattern),
+        other => Err(DataFusionError::Internal(format!(
+            "{} expected first argument to be utf8, got ('{other}')",
+            kind.name()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::{DictionaryArray, Int32Array, StringArray},
+        record_batch::RecordBatch,
+        util::pretty::pretty_format_batches,
+    };
+    use datafusion::prelude::{col, lit, Expr};
+    use datafusion_util::context_with_table;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn string_match_expr() {
+        let words = vec![
+            Some("server01"),
+            Some("host01"),
+            None,
+            Some("server02"),
+            Some("web-server"),
+        ];
+
+        let strings: ArrayRef = Arc::new(StringArray::from(words.clone()));
+        let dictionary: ArrayRef =
+            Arc::new(words.into_iter().collect::<DictionaryArray<Int32Type>>());
+
+        for words in [strings, dictionary] {
+            let expr = HAS_PREFIX_UDF.call(vec![col("words"), lit("server")]);
+            let expected = vec![
+                "+----------+",
+                "| words    |",
+                "+----------+",
+                "| server01 |",
+                "| server02 |",
+                "+----------+",
+            ];
+            assert_eq!(expected, run_plan(words.clone(), expr).await, "{words:?}");
+
+            let expr = HAS_SUFFIX_UDF.call(vec![col("words"), lit("01")]);
+            let expected = vec![
+                "+----------+",
+                "| words    |",
+                "+----------+",
+                "| server01 |",
+                "| host01   |",
+                "+----------+",
+            ];
+            assert_eq!(expected, run_plan(words.clone(), expr).await, "{words:?}");
+
+            let expr = CONTAINS_STR_UDF.call(vec![col("words"), lit("server")]);
+            let expected = vec![
+                "+------------+",
+                "| words      |",
+                "+------------+",
+                "| server01   |",
+                "| server02   |",
+                "| web-server |",
+                "+------------+",
+            ];
+            assert_eq!(expected, run_plan(words, expr).await, "{words:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn has_prefix_dictionary() {
+        let values: ArrayRef = Arc::new(StringArray::from(vec![
+            "host01", "server", "server01", "server02", "web",
+        ]));
+        let keys = Int32Array::from(vec![Some(4), Some(2), None, Some(0), Some(3), Some(1)]);
+        let words: ArrayRef =
+            Arc::new(DictionaryArray::<Int32Type>::try_new(keys, values).unwrap());
+
+        let expr = HAS_PREFIX_UDF.call(vec![col("words"), lit("server")]);
+        let expected = vec![
+            "+----------+",
+            "| words    |",
+            "+----------+",
+            "| server01 |",
+            "| server02 |",
+            "| server   |",
+            "+----------+",
+        ];
+        assert_eq!(expected, run_plan(Arc::clone(&words), expr).await);
+
+        // no value has the prefix
+        let results = string_match_array(StringMatch::Prefix, &words, "mail").unwrap();
+        let expected = BooleanArray::from(vec![
+            Some(false),
+            Some(false),
+            None,
+            Some(false),
+            Some(false),
+            Some(false),
+        ]);
+        assert_eq!(
+            results.as_any().downcast_ref::<BooleanArray>().unwrap(),
+            &expected
+        );
+    }
+
+    #[tokio::test]
+    async fn string_match_expr_negated() {
+        let words: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("server01"),
+            None,
+            Some("host01"),
+        ]));
+        let expr = Expr::Not(Box::new(
+            HAS_PREFIX_UDF.call(vec![col("words"), lit("server")]),
+        ));
+
+        let expected = vec![
+            "+--------+",
+            "| words  |",
+            "+--------+",
+            "| host01 |",
+            "+--------+",
+        ];
+        assert_eq!(expected, run_plan(words, expr).await);
+    }
+
+    // Run a plan against a table "t" with the column "words"
+    async fn run_plan(words: ArrayRef, op: Expr) -> Vec<String> {
+        let rb = RecordBatch::try_from_iter(vec![("words", words)]).unwrap();
+
+        let ctx = context_with_table(rb);
+        let df = ctx.table("t").await.unwrap();
+        let df = df.filter(op).unwrap();
+
+        let record_batches = df.collect().await.unwrap();
+
+        pretty_format_batches(&record_batches)
+            .unwrap()
+            .to_string()
+            .split('\n')
+            .map(|s| s.to_owned())
+            .collect()
+    }
+}
@@ -0,0 +1,732 @@
+//! ## Overview
+//!
+//! *Quantile sketches* summarize the distribution of a set of values,
+//! such as request latencies, in a small, mergeable form. Sketches
+//! pre-aggregated at the edge can be stored as string field values and
+//! merged at query time to estimate quantiles over any set of rows:
+//!
+//! ```sql
+//! select host, tdigest_quantile(tdigest_merge(latency_sketch), 0.99)
+//! from "http" group by host;
+//! ```
+//!
+//! Sketches are [t-digests], encoded as text so they can be written with
+//! line protocol:
+//!
+//! ```text
+//! tdigest;<max size>;<min>;<max>;<mean>:<weight>,<mean>:<weight>,...
+//! ```
+//!
+//! ## Supported Functions
+//!
+//! 1. `tdigest(value)`: aggregate function returning the sketch of the
+//!    numeric values of the group
+//! 2. `tdigest_merge(sketch)`: aggregate function returning the sketch
+//!    combining all sketches of the group
+//! 3. `tdigest_quantile(sketch, quantile)`: the estimated value at
+//!    `quantile` (between 0 and 1) of a sketch
+//!
+//! All functions ignore `NULL` inputs. The aggregate functions return
+//! `NULL` for groups without values.
+//!
+//! [t-digests]: https://github.com/tdunning/t-digest
+use std::sync::Arc;
+
+use arrow::{
+    array::{as_primitive_array, as_string_array, Array, ArrayRef, Float64Array, StringArray},
+    compute::cast,
+    datatypes::{DataType, Float64Type},
+};
+use datafusion::{
+    common::downcast_value,
+    error::{DataFusionError, Result as DataFusionResult},
+    logical_expr::{
+        AccumulatorFactoryFunction, ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF,
+        Signature, StateTypeFunction, Volatility,
+    },
+    physical_plan::{udaf::AggregateUDF, Accumulator, ColumnarValue},
+    prelude::SessionContext,
+    scalar::ScalarValue,
+};
+use once_cell::sync::Lazy;
+
+/// The name of the tdigest_quantile UDF given to DataFusion.
+pub const TDIGEST_QUANTILE_UDF_NAME: &str = "tdigest_quantile";
+
+/// The maximum number of centroids of the sketches built by `tdigest`
+const DEFAULT_MAX_SIZE: usize = 100;
+
+/// Prefix of encoded sketches, identifying the format
+const ENCODING_PREFIX: &str = "tdigest";
+
+/// registers the sketch aggregate functions so they can be invoked via SQL
+pub fn register_tdigest_aggregates(ctx: &SessionContext) {
+    ctx.register_udaf(tdigest());
+    ctx.register_udaf(tdigest_merge());
+}
+
+/// Returns a DataFusion user defined aggregate function for computing
+/// `tdigest(value)`: the sketch of the values of the group.
+pub fn tdigest() -> AggregateUDF {
+    make_uda("tdigest", Input::Values)
+}
+
+/// Returns a DataFusion user defined aggregate function for computing
+/// `tdigest_merge(sketch)`: the sketch combining the sketches of the
+/// group.
+pub fn tdigest_merge() -> AggregateUDF {
+    make_uda("tdigest_merge", Input::Sketches)
+}
+
+/// Implementation of tdigest_quantile
+pub(crate) static TDIGEST_QUANTILE_UDF: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
+    let signature = Signature::exact(
+        vec![DataType::Utf8, DataType::Float64],
+        Volatility::Immutable,
+    );
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+    let fun: ScalarFunctionImplementation = Arc::new(tdigest_quantile_impl);
+
+    Arc::new(ScalarUDF::new(
+        TDIGEST_QUANTILE_UDF_NAME,
+        &signature,
+        &return_type_fn,
+        &fun,
+    ))
+});
+
+/// What the aggregate functions combine into a sketch
+#[derive(Debug, Clone, Copy)]
+enum Input {
+    /// numeric values
+    Values,
+    /// encoded sketches
+    Sketches,
+}
+
+/// Create a User Defined Aggregate Function (UDAF) for datafusion.
+fn make_uda(name: &'static str, input: Input) -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(move |arg_types| {
+        match (input, arg_types) {
+            (Input::Values, [value_type]) if value_type.is_numeric() => {}
+            (Input::Sketches, [DataType::Utf8]) => {}
+            (Input::Values, _) => {
+                return Err(DataFusionError::Plan(format!(
+                    "{name} requires a numeric argument, got {arg_types:?}"
+                )))
+            }
+            (Input::Sketches, _) => {
+                return Err(DataFusionError::Plan(format!(
+                    "{name} requires a string argument, got {arg_types:?}"
+                )))
+            }
+        }
+        Ok(Arc::new(DataType::Utf8))
+    });
+
+    let accumulator_factory: AccumulatorFactoryFunction =
+        Arc::new(move |_| Ok(Box::new(TDigestAccumulator::new(input))));
+
+    // the state is the encoded sketch
+    let state_type_factory: StateTypeFunction = Arc::new(|_| Ok(Arc::new(vec![DataType::Utf8])));
+
+    AggregateUDF::new(
+        name,
+        &Signature::any(1, Volatility::Immutable),
+        &return_type_func,
+        &accumulator_factory,
+        &state_type_factory,
+    )
+}
+
+/// Given a column of encoded sketches and a quantile, `tdigest_quantile`
+/// estimates the value at the quantile of each sketch.
+///
+/// Returns NULL for NULL inputs.
+fn tdigest_quantile_impl(args: &[ColumnarValue]) -> DataFusionResult<ColumnarValue> {
+    assert_eq!(args.len(), 2);
+
+    let num_rows = args.iter().find_map(|arg| match arg {
+        ColumnarValue::Array(arr) => Some(arr.len()),
+        ColumnarValue::Scalar(_) => None,
+    });
+
+    let sketches = args[0].clone().into_array(num_rows.unwrap_or(1));
+    let sketches = as_string_array(&sketches);
+    let quantiles = args[1].clone().into_array(num_rows.unwrap_or(1));
+    let quantiles = as_primitive_array::<Float64Type>(&quantiles);
+
+    let results = sketches
+        .iter()
+        .zip(quantiles.iter())
+        .map(|(sketch, quantile)| match (sketch, quantile) {
+            (Some(sketch), Some(quantile)) => {
+                if !(0.0..=1.0).contains(&quantile) {
+                    return Err(DataFusionError::Execution(format!(
+                        "{TDIGEST_QUANTILE_UDF_NAME} requires a quantile between 0 and 1, got {quantile}"
+                    )));
+                }
+                Ok(TDigest::decode(sketch)?.quantile(quantile))
+            }
+            _ => Ok(None),
+        })
+        .collect::<DataFusionResult<Float64Array>>()?;
+
+    match num_rows {
+        Some(_) => Ok(ColumnarValue::Array(Arc::new(results))),
+        None => Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+            &results, 0,
+        )?)),
+    }
+}
+
+/// Combines values or sketches into a sketch
+#[derive(Debug)]
+struct TDigestAccumulator {
+    input: Input,
+    digest: TDigest,
+}
+
+impl TDigestAccumulator {
+    fn new(input: Input) -> Self {
+        Self {
+            input,
+            digest: TDigest::new(DEFAULT_MAX_SIZE),
+        }
+    }
+
+    fn merge_sketches(&mut self, sketches: &ArrayRef) -> DataFusionResult<()> {
+        let sketches = downcast_value!(sketches, StringArray);
+
+        let mut digests = sketches
+            .iter()
+            .flatten()
+            .map(TDigest::decode)
+            .collect::<DataFusionResult<Vec<_>>>()?;
+        if !self.digest.is_empty() {
+            digests.push(self.digest.clone());
+        }
+
+        self.digest = TDigest::merge_digests(&digests);
+        Ok(())
+    }
+
+    fn encoded(&self) -> ScalarValue {
+        ScalarValue::Utf8((!self.digest.is_empty()).then(|| self.digest.encode()))
+    }
+}
+
+impl Accumulator for TDigestAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DataFusionResult<()> {
+        assert_eq!(values.len(), 1);
+
+        match self.input {
+            Input::Values => {
+                let values = cast(&values[0], &DataType::Float64)?;
+                let values = downcast_value!(values, Float64Array);
+                let values = values
+                    .iter()
+                    .flatten()
+                    .filter(|v| !v.is_nan())
+                    .collect::<Vec<_>>();
+                self.digest = self.digest.merge_values(&values);
+                Ok(())
+            }
+            Input::Sketches => self.merge_sketches(&values[0]),
+        }
+    }
+
+    fn evaluate(&self) -> DataFusionResult<ScalarValue> {
+        Ok(self.encoded())
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.digest.centroids.capacity() * std::mem::size_of::<Centroid>()
+    }
+
+    fn state(&self) -> DataFusionResult<Vec<ScalarValue>> {
+        Ok(vec![self.encoded()])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DataFusionResult<()> {
+        assert_eq!(states.len(), 1);
+        self.merge_sketches(&states[0])
+    }
+}
+
+/// A group of values, summarized by their mean
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+impl Centroid {
+    fn add(&mut self, other: &Self) {
+        let weight = self.weight + other.weight;
+        self.mean += (other.mean - self.mean) * other.weight / weight;
+        self.weight = weight;
+    }
+}
+
+/// A merging t-digest: the values are summarized by at most `max_size`
+/// centroids, ordered by mean. Centroids near the extreme quantiles
+/// summarize fewer values, so that these are estimated more precisely.
+#[derive(Debug, Clone, PartialEq)]
+struct TDigest {
+    max_size: usize,
+    centroids: Vec<Centroid>,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            centroids: vec![],
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.centroids.is_empty()
+    }
+
+    fn count(&self) -> f64 {
+        self.centroids.iter().map(|c| c.weight).sum()
+    }
+
+    /// Returns a digest of the values of `self` and `values`
+    fn merge_values(&self, values: &[f64]) -> Self {
+        if values.is_empty() {
+            return self.clone();
+        }
+
+        let centroids = self
+            .centroids
+            .iter()
+            .cloned()
+            .chain(values.iter().map(|&mean| Centroid { mean, weight: 1.0 }))
+            .collect();
+        let min = values.iter().copied().fold(self.min, f64::min);
+        let max = values.iter().copied().fold(self.max, f64::max);
+
+        Self::compress(self.max_size, centroids, min, max)
+    }
+
+    /// Returns a digest of the values of all `digests`, using the largest
+    /// size of any of them
+    fn merge_digests(digests: &[Self]) -> Self {
+        let max_size = digests
+            .iter()
+            .map(|d| d.max_size)
+            .max()
+            .unwrap_or(DEFAULT_MAX_SIZE);
+        let centroids = digests
+            .iter()
+            .flat_map(|d| d.centroids.iter().cloned())
+            .collect();
+        let min = digests.iter().map(|d| d.min).fold(f64::INFINITY, f64::min);
+        let max = digests
+            .iter()
+            .map(|d| d.max)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        Self::compress(max_size, centroids, min, max)
+    }
+
+    /// Merges adjacent `centroids` so that at most `max_size` remain
+    fn compress(max_size: usize, mut centroids: Vec<Centroid>, min: f64, max: f64) -> Self {
+        centroids.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let count: f64 = centroids.iter().map(|c| c.weight).sum();
+        let mut compressed = Vec::with_capacity(max_size.min(centroids.len()));
+        let mut centroids = centroids.into_iter();
+
+        if let Some(mut current) = centroids.next() {
+            let mut k_limit = 1.0;
+            let mut q_limit_times_count = k_to_q(k_limit, max_size) * count;
+            k_limit += 1.0;
+
+            let mut weight_so_far = current.weight;
+            for next in centroids {
+                weight_so_far += next.weight;
+                if weight_so_far <= q_limit_times_count {
+                    current.add(&next);
+                } else {
+                    compressed.push(current);
+                    q_limit_times_count = k_to_q(k_limit, max_size) * count;
+                    k_limit += 1.0;
+                    current = next;
+                }
+            }
+            compressed.push(current);
+        }
+
+        Self {
+            max_size,
+            centroids: compressed,
+            min,
+            max,
+        }
+    }
+
+    /// Estimates the value at quantile `q`, assuming the values of each
+    /// centroid are spread around its mean
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let first = self.centroids.first()?;
+        let last = self.centroids.last()?;
+        let rank = q * self.count();
+
+        // interpolate between the extremes and the outer centroids
+        if rank <= first.weight / 2.0 {
+            return Some(interpolate(
+                rank,
+                (0.0, self.min),
+                (first.weight / 2.0, first.mean),
+            ));
+        }
+        if rank >= self.count() - last.weight / 2.0 {
+            return Some(interpolate(
+                rank,
+                (self.count() - last.weight / 2.0, last.mean),
+                (self.count(), self.max),
+            ));
+        }
+
+        // otherwise interpolate between the centroids around the rank
+        let mut position = first.weight / 2.0;
+        for pair in self.centroids.windows(2) {
+            let next_position = position + (pair[0].weight + pair[1].weight) / 2.0;
+            if rank <= next_position {
+                return Some(interpolate(
+                    rank,
+                    (position, pair[0].mean),
+                    (next_position, pair[1].mean),
+                ));
+            }
+            position = next_position;
+        }
+
+        Some(last.mean)
+    }
+
+    fn encode(&self) -> String {
+        let centroids = self
+            .centroids
+            .iter()
+            .map(|c| format!("{}:{}", c.mean, c.weight))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{ENCODING_PREFIX};{};{};{};{centroids}",
+            self.max_size, self.min, self.max
+        )
+    }
+
+    fn decode(s: &str) -> DataFusionResult<Self> {
+        let invalid = |reason: &str| {
+            DataFusionError::Execution(format!("invalid t-digest sketch '{s}': {reason}"))
+        };
+        let parse_f64 = |v: &str| {
+            v.parse::<f64>()
+                .map_err(|_| invalid(&format!("'{v}' is not a number")))
+        };
+
+        let parts = s.split(';').collect::<Vec<_>>();
+        let [ENCODING_PREFIX, max_size, min, max, centroids] = parts.as_slice() else {
+            return Err(invalid(&format!(
+                "expected '{ENCODING_PREFIX};<max size>;<min>;<max>;<centroids>'"
+            )));
+        };
+
+        let max_size = max_size
+            .parse::<usize>()
+            .ok()
+            .filter(|max_size| *max_size > 0)
+            .ok_or_else(|| invalid("the max size must be a positive integer"))?;
+
+        let centroids = centroids
+            .split(',')
+            .filter(|c| !c.is_empty())
+            .map(|c| {
+                let (mean, weight) = c
+                    .split_once(':')
+                    .ok_or_else(|| invalid("expected centroids as '<mean>:<weight>'"))?;
+                let centroid = Centroid {
+                    mean: parse_f64(mean)?,
+                    weight: parse_f64(weight)?,
+                };
+                if !centroid.weight.is_finite() || centroid.weight <= 0.0 {
+                    return Err(invalid("centroid weights must be positive"));
+                }
+                Ok(centroid)
+            })
+            .collect::<DataFusionResult<Vec<_>>>()?;
+
+        // sketches may be written by other systems, so ensure the
+        // centroids are ordered and not more than max_size
+        Ok(Self::compress(
+            max_size,
+            centroids,
+            parse_f64(min)?,
+            parse_f64(max)?,
+        ))
+    }
+}
+
+/// The k1 scale function of the t-digest paper, mapping the centroid
+/// index limit `k` to the quantile limit
+fn k_to_q(k: f64, max_size: usize) -> f64 {
+    let k_div_d = k / max_size as f64;
+    if k_div_d >= 0.5 {
+        let base = 1.0 - k_div_d;
+        1.0 - 2.0 * base * base
+    } else {
+        2.0 * k_div_d * k_div_d
+    }
+}
+
+/// Linearly interpolates the value at `x` between points `a` and `b`
+fn interpolate(x: f64, a: (f64, f64), b: (f64, f64)) -> f64 {
+    if b.0 <= a.0 {
+        return b.1;
+    }
+    a.1 + (x - a.0) / (b.0 - a.0) * (b.1 - a.1)
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::{Int64Array, StringArray},
+        record_batch::RecordBatch,
+        util::pretty::pretty_format_batches,
+    };
+    use datafusion::{datasource::MemTable, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn test_quantile_exact() {
+        let digest = TDigest::new(DEFAULT_MAX_SIZE).merge_values(&[3.0, 1.0, 2.0]);
+
+        assert_eq!(digest.quantile(0.0), Some(1.0));
+        assert_eq!(digest.quantile(0.5), Some(2.0));
+        assert_eq!(digest.quantile(1.0), Some(3.0));
+
+        assert_eq!(TDigest::new(DEFAULT_MAX_SIZE).quantile(0.5), None);
+    }
+
+    #[test]
+    fn test_quantile_estimate() {
+        let values = (1..=10_000).map(|v| v as f64).collect::<Vec<_>>();
+
+        // added in several batches
+        let digest = values
+            .chunks(999)
+            .fold(TDigest::new(DEFAULT_MAX_SIZE), |digest, values| {
+                digest.merge_values(values)
+            });
+        assert!(digest.centroids.len() <= DEFAULT_MAX_SIZE);
+
+        for (q, expected) in [(0.01, 100.0), (0.5, 5000.0), (0.99, 9900.0)] {
+            let actual = digest.quantile(q).unwrap();
+            assert!(
+                (actual - expected).abs() <= expected * 0.01,
+                "quantile {q}: expected {expected}, got {actual}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_digests() {
+        let low = TDigest::new(DEFAULT_MAX_SIZE).merge_values(&[1.0, 2.0]);
+        let high = TDigest::new(DEFAULT_MAX_SIZE).merge_values(&[3.0, 4.0, 5.0]);
+        let merged = TDigest::merge_digests(&[high, low]);
+
+        assert_eq!(merged.count(), 5.0);
+        assert_eq!(merged.min, 1.0);
+        assert_eq!(merged.max, 5.0);
+        assert_eq!(merged.quantile(0.5), Some(3.0));
+    }
+
+    #[test]
+    fn test_encoding() {
+        let digest = TDigest::new(DEFAULT_MAX_SIZE).merge_values(&[1.5, -2.0, 1e300]);
+        let encoded = digest.encode();
+        assert_eq!(
+            encoded,
+            format!("tdigest;100;-2;{};-2:1,1.5:1,{}:1", 1e300, 1e300)
+        );
+        assert_eq!(TDigest::decode(&encoded).unwrap(), digest);
+
+        // centroids are ordered when decoded
+        let decoded = TDigest::decode("tdigest;10;1;9;9:1,1:2").unwrap();
+        assert_eq!(decoded.encode(), "tdigest;10;1;9;1:2,9:1");
+
+        // an empty sketch
+        assert!(TDigest::decode("tdigest;10;inf;-inf;").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        for (sketch, reason) in [
+            ("", "expected 'tdigest;<max size>;<min>;<max>;<centroids>'"),
+            (
+                "other;10;1;9;1:1",
+                "expected 'tdigest;<max size>;<min>;<max>;<centroids>'",
+            ),
+            (
+                "tdigest;0;1;9;1:1",
+                "the max size must be a positive integer",
+            ),
+            ("tdigest;10;a;9;1:1", "'a' is not a number"),
+            (
+                "tdigest;10;1;9;1",
+                "expected centroids as '<mean>:<weight>'",
+            ),
+            ("tdigest;10;1;9;1:0", "centroid weights must be positive"),
+        ] {
+            let err = TDigest::decode(sketch).unwrap_err().to_string();
+            assert_eq!(
+                err,
+                format!("Execution error: invalid t-digest sketch '{sketch}': {reason}")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sketch_functions() {
+        let actual = run_query(
+            "SELECT \
+               host, \
+               tdigest_quantile(tdigest(latency), 0.5) AS median, \
+               tdigest_quantile(tdigest_merge(sketch), 0.5) AS merged_median, \
+               tdigest_quantile(tdigest_merge(sketch), 1.0) AS merged_max \
+             FROM t GROUP BY host ORDER BY host",
+        )
+        .await;
+
+        let expected = vec![
+            "+------+--------+---------------+------------+",
+            "| host | median | merged_median | merged_max |",
+            "+------+--------+---------------+------------+",
+            "| a    | 20.0   | 3.0           | 5.0        |",
+            "| b    | 5.0    |               |            |",
+            "+------+--------+---------------+------------+",
+        ];
+        assert_eq!(
+            expected, actual,
+            "\n\nEXPECTED:\n{expected:#?}\nACTUAL:\n{actual:#?}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quantile_of_sketch_column() {
+        let actual = run_query(
+            "SELECT host, tdigest_quantile(sketch, 0.0) AS min FROM t \
+             WHERE sketch IS NOT NULL ORDER BY host, min",
+        )
+        .await;
+
+        let expected = vec![
+            "+------+-----+",
+            "| host | min |",
+            "+------+-----+",
+            "| a    | 1.0 |",
+            "| a    | 3.0 |",
+            "+------+-----+",
+        ];
+        assert_eq!(
+            expected, actual,
+            "\n\nEXPECTED:\n{expected:#?}\nACTUAL:\n{actual:#?}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let ctx = context();
+        let err = ctx
+            .sql("SELECT tdigest(host) FROM t")
+            .await
+            .expect_err("expected error");
+        assert!(
+            err.to_string()
+                .contains("tdigest requires a numeric argument"),
+            "{err}"
+        );
+
+        let err = ctx
+            .sql("SELECT tdigest_merge(latency) FROM t")
+            .await
+            .expect_err("expected error");
+        assert!(
+            err.to_string()
+                .contains("tdigest_merge requires a string argument"),
+            "{err}"
+        );
+
+        let err = ctx
+            .sql("SELECT tdigest_quantile(sketch, 2.0) FROM t")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .expect_err("expected error");
+        assert!(
+            err.to_string()
+                .contains("tdigest_quantile requires a quantile between 0 and 1, got 2"),
+            "{err}"
+        );
+    }
+
+    fn context() -> SessionContext {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "host",
+                Arc::new(StringArray::from(vec!["a", "a", "b", "a"])) as ArrayRef,
+            ),
+            (
+                "latency",
+                Arc::new(Int64Array::from(vec![
+                    Some(10),
+                    Some(30),
+                    Some(5),
+                    Some(20),
+                ])),
+            ),
+            (
+                "sketch",
+                Arc::new(StringArray::from(vec![
+                    Some("tdigest;100;1;2;1:1,2:1"),
+                    None,
+                    None,
+                    Some("tdigest;100;3;5;3:1,4:1,5:1"),
+                ])),
+            ),
+        ])
+        .unwrap();
+
+        let provider = MemTable::try_new(batch.schema(), vec![vec![batch]]).unwrap();
+        let ctx = SessionContext::new();
+        ctx.register_table("t", Arc::new(provider)).unwrap();
+        register_tdigest_aggregates(&ctx);
+        ctx.register_udf(TDIGEST_QUANTILE_UDF.as_ref().clone());
+        ctx
+    }
+
+    async fn run_query(sql: &str) -> Vec<String> {
+        let batches = context().sql(sql).await.unwrap().collect().await.unwrap();
+
+        pretty_format_batches(&batches)
+            .unwrap()
+            .to_string()
+            .split('\n')
+            .map(|s| s.to_owned())
+            .collect()
+    }
+}
@@ -0,0 +1,192 @@
+//! ## Overview
+//!
+//! `unsigned_sum` is an IOx SQL aggregate function that sums an
+//! unsigned integer (`UInt64`) column, failing the query if the sum
+//! does not fit into a `UInt64`:
+//!
+//! ```sql
+//! select host, unsigned_sum(bytes) from "net" group by host;
+//! ```
+//!
+//! The built-in `sum` silently wraps around on overflow, which turns a
+//! large sum of a counter into a small, plausible looking one.
+//!
+//! Like `sum`, the function ignores `NULL` values and returns `NULL` if
+//! the group has no non-`NULL` value.
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, UInt64Array},
+    datatypes::DataType,
+};
+use datafusion::{
+    common::downcast_value,
+    error::{DataFusionError, Result as DataFusionResult},
+    logical_expr::{
+        AccumulatorFactoryFunction, ReturnTypeFunction, Signature, StateTypeFunction, Volatility,
+    },
+    physical_plan::{udaf::AggregateUDF, Accumulator},
+    prelude::SessionContext,
+    scalar::ScalarValue,
+};
+
+/// The name of the `unsigned_sum` function
+const UNSIGNED_SUM_UDAF_NAME: &str = "unsigned_sum";
+
+/// registers `unsigned_sum` so it can be invoked via SQL
+pub fn register_unsigned_sum(ctx: &SessionContext) {
+    ctx.register_udaf(unsigned_sum());
+}
+
+/// Returns a DataFusion user defined aggregate function for computing
+/// `unsigned_sum(value)`: the sum of an unsigned integer column, which
+/// is an error if it overflows.
+pub fn unsigned_sum() -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::UInt64)));
+
+    let accumulator_factory: AccumulatorFactoryFunction =
+        Arc::new(|_| Ok(Box::<UnsignedSumAccumulator>::default()));
+
+    let state_type_factory: StateTypeFunction = Arc::new(|_| Ok(Arc::new(vec![DataType::UInt64])));
+
+    AggregateUDF::new(
+        UNSIGNED_SUM_UDAF_NAME,
+        &Signature::exact(vec![DataType::UInt64], Volatility::Immutable),
+        &return_type_func,
+        &accumulator_factory,
+        &state_type_factory,
+    )
+}
+
+/// The sum of the values seen so far, `None` if there were no values
+#[derive(Debug, Default)]
+struct UnsignedSumAccumulator {
+    sum: Option<u64>,
+}
+
+impl UnsignedSumAccumulator {
+    fn update(&mut self, values: &ArrayRef) -> DataFusionResult<()> {
+        let values = downcast_value!(values, UInt64Array);
+
+        for value in values.iter().flatten() {
+            let sum = self.sum.unwrap_or_default();
+            let sum = sum.checked_add(value).ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "{UNSIGNED_SUM_UDAF_NAME} overflowed: {sum} + {value} does not fit into an unsigned 64-bit integer"
+                ))
+            })?;
+            self.sum = Some(sum);
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for UnsignedSumAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DataFusionResult<()> {
+        assert_eq!(values.len(), 1);
+        self.update(&values[0])
+    }
+
+    fn evaluate(&self) -> DataFusionResult<ScalarValue> {
+        Ok(ScalarValue::UInt64(self.sum))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+
+    fn state(&self) -> DataFusionResult<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::UInt64(self.sum)])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DataFusionResult<()> {
+        assert_eq!(states.len(), 1);
+        self.update(&states[0])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::StringArray, record_batch::RecordBatch, util::pretty::pretty_format_batches,
+    };
+    use datafusion::datasource::MemTable;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unsigned_sum() {
+        let batches = context()
+            .sql("SELECT tag, unsigned_sum(value) AS s FROM t WHERE tag <> 'c' GROUP BY tag ORDER BY tag")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let actual: Vec<_> = pretty_format_batches(&batches)
+            .unwrap()
+            .to_string()
+            .split('\n')
+            .map(|s| s.to_owned())
+            .collect();
+
+        let expected = vec![
+            "+-----+----------------------+",
+            "| tag | s                    |",
+            "+-----+----------------------+",
+            "| a   | 18446744073709551615 |",
+            "| b   |                      |",
+            "+-----+----------------------+",
+        ];
+        assert_eq!(
+            expected, actual,
+            "\n\nEXPECTED:\n{expected:#?}\nACTUAL:\n{actual:#?}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_sum_overflow() {
+        let err = context()
+            .sql("SELECT unsigned_sum(value) FROM t")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .expect_err("expected error");
+        assert!(err.to_string().contains("unsigned_sum overflowed"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let err = context()
+            .sql("SELECT unsigned_sum(tag) FROM t")
+            .await
+            .expect_err("expected error");
+        assert!(err.to_string().contains("unsigned_sum"), "{err}");
+    }
+
+    fn context() -> SessionContext {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "tag",
+                Arc::new(StringArray::from(vec!["a", "b", "a", "c"])) as ArrayRef,
+            ),
+            (
+                "value",
+                Arc::new(UInt64Array::from(vec![
+                    Some(u64::MAX - 1),
+                    None,
+                    Some(1),
+                    Some(1),
+                ])),
+            ),
+        ])
+        .unwrap();
+
+        let provider = MemTable::try_new(batch.schema(), vec![vec![batch]]).unwrap();
+        let ctx = SessionContext::new();
+        ctx.register_table("t", Arc::new(provider)).unwrap();
+        register_unsigned_sum(&ctx);
+        ctx
+    }
+}
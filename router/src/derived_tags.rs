@@ -0,0 +1,313 @@
+//! Tags derived from other tags, or the measurement name, of writes.
+//!
+//! A derived tag is extracted with a regex from a tag (or the measurement
+//! name) of each row, e.g. the `datacenter` of a `host` tag like
+//! `ams-web01`. The named capture groups of the regex name the derived tags:
+//!
+//! ```json
+//! {
+//!     "my_namespace": {
+//!         "cpu": [
+//!             { "source": "host", "pattern": "^(?P<datacenter>[a-z]+)-" }
+//!         ],
+//!         "eu_disk": [
+//!             { "pattern": "^(?P<region>[a-z]+)_" }
+//!         ]
+//!     }
+//! }
+//! ```
+//!
+//! Rules without a `source` tag match the measurement name. Tags are derived
+//! as line protocol is converted, before the rows are partitioned, so derived
+//! tags can be used by partition templates like any other tag. A tag of the
+//! row takes precedence over a derived tag of the same name, as does a tag
+//! derived by an earlier rule. A derived tag named like a field of the row is
+//! not written.
+//!
+//! The rules are configuration of the router, not table properties stored in
+//! the catalog: all routers must load the same file, and a change only
+//! applies once they are restarted.
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use mutable_batch_lp::TagDeriver;
+use regex::Regex;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Errors loading [`DerivedTagRules`].
+#[derive(Debug, Error)]
+pub enum DerivedTagsError {
+    /// The rules file cannot be read.
+    #[error("cannot read derived tag rules file {path}: {source}")]
+    Read {
+        /// The path of the file.
+        path: String,
+        /// The underlying error.
+        source: std::io::Error,
+    },
+
+    /// The rules file is not valid.
+    #[error("invalid derived tag rules in {path}: {source}")]
+    Parse {
+        /// The path of the file.
+        path: String,
+        /// The underlying error.
+        source: serde_json::Error,
+    },
+
+    /// A pattern is not a valid regex.
+    #[error("invalid pattern for table {table} of namespace {namespace}: {source}")]
+    InvalidPattern {
+        /// The namespace of the rule.
+        namespace: String,
+        /// The table of the rule.
+        table: String,
+        /// The underlying error.
+        source: regex::Error,
+    },
+
+    /// A pattern derives no tags.
+    #[error(
+        "pattern {pattern} for table {table} of namespace {namespace} has no named capture groups"
+    )]
+    NoCaptureGroups {
+        /// The namespace of the rule.
+        namespace: String,
+        /// The table of the rule.
+        table: String,
+        /// The pattern.
+        pattern: String,
+    },
+}
+
+/// A rule as written in the rules file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DerivedTagConfig {
+    #[serde(default)]
+    source: Option<String>,
+    pattern: String,
+}
+
+/// A rule deriving tags from the named capture groups of `pattern`.
+#[derive(Debug, Clone)]
+pub struct DerivedTag {
+    /// The tag matched by `pattern`, or [`None`] for the measurement name.
+    source: Option<String>,
+    pattern: Regex,
+}
+
+impl DerivedTag {
+    /// Initialise a rule deriving tags from the named capture groups of
+    /// `pattern` matched against the tag `source`, or the measurement name if
+    /// `source` is [`None`].
+    pub fn new(source: Option<String>, pattern: Regex) -> Self {
+        Self { source, pattern }
+    }
+
+    /// Push the tags derived from `value` to `derived`.
+    ///
+    /// Capture groups that do not participate in the match, or match an
+    /// empty string, derive no tag.
+    fn derive(&self, value: &str, derived: &mut Vec<(String, String)>) {
+        let Some(captures) = self.pattern.captures(value) else {
+            return;
+        };
+
+        for name in self.pattern.capture_names().flatten() {
+            if let Some(m) = captures.name(name).filter(|m| !m.as_str().is_empty()) {
+                derived.push((name.to_string(), m.as_str().to_string()));
+            }
+        }
+    }
+}
+
+/// The [`DerivedTag`] rules of each table of a namespace.
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceDerivedTags {
+    tables: HashMap<String, Vec<DerivedTag>>,
+}
+
+impl NamespaceDerivedTags {
+    /// Initialise the rules of a namespace from the rules of each table.
+    pub fn new(tables: HashMap<String, Vec<DerivedTag>>) -> Self {
+        Self { tables }
+    }
+}
+
+impl TagDeriver for NamespaceDerivedTags {
+    fn derive(&self, table: &str, tags: &[(&str, &str)]) -> Vec<(String, String)> {
+        let mut derived = vec![];
+        for rule in self.tables.get(table).into_iter().flatten() {
+            let value = match &rule.source {
+                Some(source) => match tags.iter().find(|(k, _)| *k == source.as_str()) {
+                    Some((_, v)) => *v,
+                    None => continue,
+                },
+                None => table,
+            };
+            rule.derive(value, &mut derived);
+        }
+        derived
+    }
+}
+
+/// The derived tag rules of all namespaces.
+///
+/// No tags are derived for writes to namespaces without rules.
+#[derive(Debug, Clone, Default)]
+pub struct DerivedTagRules {
+    namespaces: HashMap<String, Arc<NamespaceDerivedTags>>,
+}
+
+impl DerivedTagRules {
+    /// Initialise the rules from the [`NamespaceDerivedTags`] of each
+    /// namespace.
+    pub fn new(namespaces: impl IntoIterator<Item = (String, NamespaceDerivedTags)>) -> Self {
+        Self {
+            namespaces: namespaces
+                .into_iter()
+                .map(|(name, rules)| (name, Arc::new(rules)))
+                .collect(),
+        }
+    }
+
+    /// Load the rules from the JSON file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, DerivedTagsError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|source| DerivedTagsError::Read {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::from_json(&contents).map_err(|e| match e {
+            DerivedTagsError::Parse { source, .. } => DerivedTagsError::Parse {
+                path: path.display().to_string(),
+                source,
+            },
+            e => e,
+        })
+    }
+
+    fn from_json(contents: &str) -> Result<Self, DerivedTagsError> {
+        let config: HashMap<String, HashMap<String, Vec<DerivedTagConfig>>> =
+            serde_json::from_str(contents).map_err(|source| DerivedTagsError::Parse {
+                path: Default::default(),
+                source,
+            })?;
+
+        let mut namespaces = HashMap::with_capacity(config.len());
+        for (namespace, tables) in config {
+            let mut rules = HashMap::with_capacity(tables.len());
+            for (table, table_rules) in tables {
+                let table_rules = table_rules
+                    .into_iter()
+                    .map(|DerivedTagConfig { source, pattern }| {
+                        let regex = Regex::new(&pattern).map_err(|source| {
+                            DerivedTagsError::InvalidPattern {
+                                namespace: namespace.clone(),
+                                table: table.clone(),
+                                source,
+                            }
+                        })?;
+                        if regex.capture_names().flatten().next().is_none() {
+                            return Err(DerivedTagsError::NoCaptureGroups {
+                                namespace: namespace.clone(),
+                                table: table.clone(),
+                                pattern,
+                            });
+                        }
+                        Ok(DerivedTag::new(source, regex))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                rules.insert(table, table_rules);
+            }
+            namespaces.insert(namespace, NamespaceDerivedTags::new(rules));
+        }
+
+        Ok(Self::new(namespaces))
+    }
+
+    /// Returns the rules of `namespace`, if it has any.
+    pub fn get(&self, namespace: &str) -> Option<Arc<NamespaceDerivedTags>> {
+        self.namespaces.get(namespace).map(Arc::clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_derive() {
+        let rules = DerivedTagRules::from_json(
+            r#"{
+                "bananas": {
+                    "cpu": [
+                        { "source": "host", "pattern": "^(?P<datacenter>[a-z]+)-(?P<rack>r\\d+)?" },
+                        { "pattern": "^(?P<kind>[a-z]+)$" }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let bananas = rules.get("bananas").unwrap();
+        assert_eq!(
+            bananas.derive("cpu", &[("host", "ams-r12-web01"), ("region", "eu")]),
+            [
+                ("datacenter".to_string(), "ams".to_string()),
+                ("rack".to_string(), "r12".to_string()),
+                ("kind".to_string(), "cpu".to_string()),
+            ]
+        );
+
+        // the rack group does not participate in the match
+        assert_eq!(
+            bananas.derive("cpu", &[("host", "fra-web01")]),
+            [
+                ("datacenter".to_string(), "fra".to_string()),
+                ("kind".to_string(), "cpu".to_string()),
+            ]
+        );
+
+        // no host tag, only the measurement name matches
+        assert_eq!(
+            bananas.derive("cpu", &[]),
+            [("kind".to_string(), "cpu".to_string())]
+        );
+
+        // no rules for the table, or the namespace
+        assert!(bananas.derive("mem", &[("host", "ams-web01")]).is_empty());
+        assert!(rules.get("platanos").is_none());
+    }
+
+    #[test]
+    fn test_from_json_invalid() {
+        assert_matches!(
+            DerivedTagRules::from_json(r#"{ "bananas": { "cpu": [ { "source": "host" } ] } }"#),
+            Err(DerivedTagsError::Parse { .. })
+        );
+
+        assert_matches!(
+            DerivedTagRules::from_json(
+                r#"{ "bananas": { "cpu": [ { "source": "host", "pattern": "(?P<dc>" } ] } }"#
+            ),
+            Err(DerivedTagsError::InvalidPattern { namespace, table, .. }) => {
+                assert_eq!(namespace, "bananas");
+                assert_eq!(table, "cpu");
+            }
+        );
+
+        assert_matches!(
+            DerivedTagRules::from_json(
+                r#"{ "bananas": { "cpu": [ { "source": "host", "pattern": "^([a-z]+)-" } ] } }"#
+            ),
+            Err(DerivedTagsError::NoCaptureGroups { pattern, .. }) => {
+                assert_eq!(pattern, "^([a-z]+)-");
+            }
+        );
+    }
+}
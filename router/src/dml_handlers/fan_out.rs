@@ -11,6 +11,9 @@ use super::DmlHandler;
 /// executes them concurrently against the inner handler, returning once all
 /// operations are complete.
 ///
+/// The outputs of the inner handler are merged into a single output by
+/// [`Extend`]ing a [`Default`] instance.
+///
 /// If handling an operation produces an error the remaining in-flight writes
 /// are aborted and the error is immediately returned.
 ///
@@ -36,11 +39,12 @@ impl<T, I> FanOutAdaptor<T, I> {
 impl<T, I, U> DmlHandler for FanOutAdaptor<T, I>
 where
     T: DmlHandler,
+    T::WriteOutput: Default + Extend<T::WriteOutput>,
     I: IntoIterator<IntoIter = U> + Debug + Send + Sync,
     U: Iterator<Item = T::WriteInput> + Send + Sync,
 {
     type WriteInput = I;
-    type WriteOutput = ();
+    type WriteOutput = T::WriteOutput;
     type WriteError = T::WriteError;
 
     /// Concurrently execute the write inputs in `input` against the inner
@@ -66,8 +70,7 @@ where
                 }
             })
             .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
-            .await?;
-        Ok(())
+            .try_collect::<Self::WriteOutput>()
+            .await
     }
 }
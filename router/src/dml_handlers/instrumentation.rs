@@ -97,7 +97,7 @@ mod tests {
 
     use super::*;
     use crate::{
-        dml_handlers::{mock::MockDmlHandler, DmlError},
+        dml_handlers::{mock::MockDmlHandler, DmlError, WriteSummary},
         test_helpers::new_empty_namespace_schema,
     };
 
@@ -143,7 +143,8 @@ mod tests {
     #[tokio::test]
     async fn test_write_ok() {
         let ns = "platanos".try_into().unwrap();
-        let handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let handler =
+            Arc::new(MockDmlHandler::default().with_write_return([Ok(WriteSummary::default())]));
 
         let metrics = Arc::new(metric::Registry::default());
         let traces: Arc<dyn TraceCollector> = Arc::new(RingBufferTraceCollector::new(5));
@@ -5,7 +5,7 @@ use data_types::{NamespaceName, NamespaceSchema};
 use parking_lot::Mutex;
 use trace::ctx::SpanContext;
 
-use super::{DmlError, DmlHandler};
+use super::{DmlError, DmlHandler, WriteSummary};
 
 /// A captured call to a [`MockDmlHandler`], generic over `W`, the captured
 /// [`DmlHandler::WriteInput`] type.
@@ -21,7 +21,7 @@ pub enum MockDmlHandlerCall<W> {
 #[derive(Debug)]
 struct Inner<W> {
     calls: Vec<MockDmlHandlerCall<W>>,
-    write_return: VecDeque<Result<(), DmlError>>,
+    write_return: VecDeque<Result<WriteSummary, DmlError>>,
 }
 
 impl<W> Default for Inner<W> {
@@ -52,7 +52,10 @@ impl<W> MockDmlHandler<W>
 where
     W: Clone,
 {
-    pub fn with_write_return(self, ret: impl Into<VecDeque<Result<(), DmlError>>>) -> Self {
+    pub fn with_write_return(
+        self,
+        ret: impl Into<VecDeque<Result<WriteSummary, DmlError>>>,
+    ) -> Self {
         self.0.lock().write_return = ret.into();
         self
     }
@@ -81,7 +84,7 @@ where
 {
     type WriteError = DmlError;
     type WriteInput = W;
-    type WriteOutput = ();
+    type WriteOutput = WriteSummary;
 
     async fn write(
         &self,
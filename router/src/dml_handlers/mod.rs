@@ -39,6 +39,10 @@
 //! to the catalog and populates the [`NamespaceCache`], converging it to match
 //! the set of [`NamespaceSchema`] in the global catalog.
 //!
+//! The rows accepted and sequence numbers assigned by the ingesters for each
+//! partitioned batch are merged into a [`WriteSummary`] describing the whole
+//! write.
+//!
 //! [`NamespaceCache`]: crate::namespace_cache::NamespaceCache
 //! [`NamespaceSchema`]: data_types::NamespaceSchema
 
@@ -53,6 +57,9 @@ pub mod nop;
 mod retention_validation;
 pub use retention_validation::*;
 
+mod tenant_quota;
+pub use tenant_quota::*;
+
 mod partitioner;
 pub use partitioner::*;
 
@@ -68,5 +75,8 @@ pub use fan_out::*;
 mod rpc_write;
 pub use rpc_write::*;
 
+mod write_summary;
+pub use write_summary::*;
+
 #[cfg(test)]
 pub mod mock;
@@ -11,10 +11,10 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use data_types::{NamespaceName, NamespaceSchema, TableId};
+use data_types::{NamespaceName, NamespaceSchema, SequenceNumber, TableId};
 use dml::{DmlMeta, DmlWrite};
 use futures::{stream::FuturesUnordered, StreamExt};
-use generated_types::influxdata::iox::ingester::v1::WriteRequest;
+use generated_types::influxdata::iox::ingester::v1::{WriteRequest, WriteResponse};
 use hashbrown::HashMap;
 use mutable_batch::MutableBatch;
 use mutable_batch_pb::encode::encode_write;
@@ -29,7 +29,7 @@ use self::{
     client::RpcWriteClientError,
     upstream_snapshot::UpstreamSnapshot,
 };
-use super::{DmlHandler, Partitioned};
+use super::{DmlHandler, Partitioned, WriteSummary};
 use crate::dml_handlers::rpc_write::client::WriteClient;
 
 /// The bound on RPC request duration.
@@ -173,7 +173,7 @@ where
     C: CircuitBreakerState + 'static,
 {
     type WriteInput = Partitioned<HashMap<TableId, (String, MutableBatch)>>;
-    type WriteOutput = Vec<DmlMeta>;
+    type WriteOutput = WriteSummary;
 
     type WriteError = RpcWriteError;
 
@@ -188,10 +188,18 @@ where
         // Extract the partition key & DML writes.
         let (partition_key, writes) = writes.into_parts();
 
-        // Drop the table names from the value tuple.
+        // Record the rows of each table in the write summary, and drop the
+        // table names from the value tuple, retaining them to map the table
+        // IDs in the upstream responses back to names.
+        let mut summary = WriteSummary::default();
+        let mut table_names = HashMap::with_capacity(writes.len());
         let writes = writes
             .into_iter()
-            .map(|(id, (_name, data))| (id, data))
+            .map(|(id, (name, data))| {
+                summary.add_rows(&name, data.rows());
+                table_names.insert(id, name);
+                (id, data)
+            })
             .collect();
 
         // Build the DmlWrite
@@ -269,7 +277,16 @@ where
         // next result in the stream is an already-completed write ACK).
         while let Some((i, res)) = result_stream.next().await {
             match res {
-                Ok(_) => {}
+                Ok(resp) => {
+                    for table in resp.tables {
+                        if let Some(name) = table_names.get(&TableId::new(table.table_id)) {
+                            summary.add_sequence_number(
+                                name,
+                                SequenceNumber::new(table.sequence_number),
+                            );
+                        }
+                    }
+                }
                 Err(_e) if i > 0 => {
                     // In all cases, if at least one write succeeded, then this
                     // becomes a partial write error.
@@ -297,7 +314,7 @@ where
             "dispatched write to ingester"
         );
 
-        Ok(vec![op.meta().clone()])
+        Ok(summary)
     }
 }
 
@@ -317,7 +334,7 @@ async fn write_loop<T>(
     endpoints: &mut UpstreamSnapshot<T>,
     req: &WriteRequest,
     span_ctx: Option<SpanContext>,
-) -> Result<(), RpcWriteError>
+) -> Result<WriteResponse, RpcWriteError>
 where
     T: WriteClient,
 {
@@ -333,9 +350,9 @@ where
             let client = endpoints.next().ok_or(RpcWriteError::NotEnoughReplicas)?;
 
             match client.write(req.clone(), span_ctx.clone()).await {
-                Ok(()) => {
+                Ok(resp) => {
                     endpoints.remove(client);
-                    return Ok(());
+                    return Ok(resp);
                 }
                 Err(e) => {
                     warn!(error=%e, "failed ingester rpc write");
@@ -410,7 +427,7 @@ mod tests {
     async fn make_request<T, C>(
         endpoints: impl IntoIterator<Item = CircuitBreakingClient<T, C>> + Send,
         n_copies: usize,
-    ) -> Result<Vec<DmlMeta>, RpcWriteError>
+    ) -> Result<WriteSummary, RpcWriteError>
     where
         T: WriteClient + 'static,
         C: CircuitBreakerState + 'static,
@@ -481,7 +498,29 @@ mod tests {
                 None,
             )
             .await;
-        assert_matches!(got, Ok(_));
+        let summary = assert_matches!(got, Ok(v) => v);
+
+        // The summary reports the rows of each table, and the sequence number
+        // assigned by the (mock) ingester.
+        let rows = summary
+            .tables()
+            .map(|(name, t)| (name, t.rows()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                ("another", 1),
+                ("bananas", 2),
+                ("platanos", 1),
+                ("table", 1)
+            ]
+        );
+        let mut sequence_numbers = summary
+            .tables()
+            .flat_map(|(_, t)| t.sequence_numbers().iter().copied())
+            .collect::<Vec<_>>();
+        sequence_numbers.sort_unstable();
+        assert_eq!(sequence_numbers, [1, 2, 3, 4]);
 
         // Inspect the resulting RPC call
         let call = {
@@ -778,12 +817,19 @@ mod tests {
         )
         .await;
 
-        assert_matches!(got, Ok(_));
+        let summary = assert_matches!(got, Ok(v) => v);
 
         // Assert each client received one (matching) write each
         let calls_1 = client_1.calls();
         assert_eq!(calls_1.len(), 1);
         assert_eq!(calls_1, client_2.calls());
+
+        // The rows are counted once, but both replicas assigned a sequence
+        // number.
+        assert_matches!(summary.tables().collect::<Vec<_>>().as_slice(), [("bananas", t)] => {
+            assert_eq!(t.rows(), 1);
+            assert_eq!(t.sequence_numbers(), [1, 1]);
+        });
     }
 
     /// Assert that once a request has been sent to an upstream, it is never
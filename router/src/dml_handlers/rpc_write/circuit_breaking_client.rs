@@ -1,7 +1,7 @@
 use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
-use generated_types::influxdata::iox::ingester::v1::WriteRequest;
+use generated_types::influxdata::iox::ingester::v1::{WriteRequest, WriteResponse};
 use trace::ctx::SpanContext;
 
 use super::{
@@ -107,7 +107,7 @@ where
         &self,
         op: WriteRequest,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), RpcWriteClientError> {
+    ) -> Result<WriteResponse, RpcWriteClientError> {
         let res = self.inner.write(op, span_ctx).await;
         self.state.observe(&res);
         res
@@ -4,7 +4,7 @@ use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
 use generated_types::influxdata::iox::ingester::v1::{
-    write_service_client::WriteServiceClient, WriteRequest,
+    write_service_client::WriteServiceClient, TableWriteResponse, WriteRequest, WriteResponse,
 };
 use thiserror::Error;
 use trace::ctx::SpanContext;
@@ -39,7 +39,7 @@ pub(super) trait WriteClient: Send + Sync + std::fmt::Debug {
         &self,
         op: WriteRequest,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), RpcWriteClientError>;
+    ) -> Result<WriteResponse, RpcWriteClientError>;
 }
 
 #[async_trait]
@@ -51,7 +51,7 @@ where
         &self,
         op: WriteRequest,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), RpcWriteClientError> {
+    ) -> Result<WriteResponse, RpcWriteClientError> {
         (**self).write(op, span_ctx).await
     }
 }
@@ -80,14 +80,15 @@ impl<'a> WriteClient for TracePropagatingWriteClient<'a> {
         &self,
         op: WriteRequest,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), RpcWriteClientError> {
+    ) -> Result<WriteResponse, RpcWriteClientError> {
         let req = decorate_request_with_span_context(
             tonic::Request::new(op),
             self.trace_context_header_name,
             span_ctx,
         )?;
-        WriteServiceClient::write(&mut self.inner.clone(), req).await?;
-        Ok(())
+        Ok(WriteServiceClient::write(&mut self.inner.clone(), req)
+            .await?
+            .into_inner())
     }
 }
 
@@ -158,12 +159,17 @@ pub mod mock {
         calls: Vec<WriteRequest>,
         ret: Box<dyn Iterator<Item = Result<(), RpcWriteClientError>> + Send + Sync>,
         returned_oks: usize,
+        sequence_number: u64,
     }
 
     /// A mock implementation of the [`WriteClient`] for testing purposes.
     ///
     /// An instance yielded by the [`Default`] implementation will always return
-    /// [`Ok(())`] for write calls.
+    /// [`Ok`] for write calls.
+    ///
+    /// Successful writes are acknowledged with a response that accepts every
+    /// row of the request, assigning each table the next sequence number of a
+    /// counter starting at 1.
     pub struct MockWriteClient {
         state: Mutex<State>,
     }
@@ -181,6 +187,7 @@ pub mod mock {
                     calls: Default::default(),
                     ret: Box::new(iter::repeat_with(|| Ok(()))),
                     returned_oks: 0,
+                    sequence_number: 0,
                 }),
             }
         }
@@ -217,17 +224,32 @@ pub mod mock {
             &self,
             op: WriteRequest,
             _span_ctx: Option<SpanContext>,
-        ) -> Result<(), RpcWriteClientError> {
+        ) -> Result<WriteResponse, RpcWriteClientError> {
             let mut guard = self.state.lock();
+            let tables = op
+                .payload
+                .iter()
+                .flat_map(|payload| &payload.table_batches)
+                .map(|batch| (batch.table_id, batch.row_count))
+                .collect::<Vec<_>>();
             guard.calls.push(op);
 
-            let ret = guard.ret.next().expect("no mock response");
-
-            if ret.is_ok() {
-                guard.returned_oks += 1;
-            }
-
-            ret
+            guard.ret.next().expect("no mock response")?;
+            guard.returned_oks += 1;
+
+            let tables = tables
+                .into_iter()
+                .map(|(table_id, row_count)| {
+                    guard.sequence_number += 1;
+                    TableWriteResponse {
+                        table_id,
+                        row_count: row_count as _,
+                        sequence_number: guard.sequence_number,
+                    }
+                })
+                .collect();
+
+            Ok(WriteResponse { tables })
         }
     }
 }
@@ -10,7 +10,7 @@ use std::{
 
 use async_trait::async_trait;
 use generated_types::influxdata::iox::ingester::v1::{
-    write_service_client::WriteServiceClient, WriteRequest,
+    write_service_client::WriteServiceClient, WriteRequest, WriteResponse,
 };
 use observability_deps::tracing::*;
 use parking_lot::Mutex;
@@ -107,7 +107,7 @@ impl WriteClient for LazyConnector {
         &self,
         op: WriteRequest,
         span_ctx: Option<SpanContext>,
-    ) -> Result<(), RpcWriteClientError> {
+    ) -> Result<WriteResponse, RpcWriteClientError> {
         let conn = self.connection.lock().clone();
         let conn = conn.ok_or_else(|| {
             RpcWriteClientError::UpstreamNotConnected(self.addr.uri().to_string())
@@ -132,9 +132,9 @@ impl WriteClient for LazyConnector {
                 self.consecutive_errors.fetch_add(1, Ordering::Relaxed);
                 return Err(e);
             }
-            Ok(_) => {
+            Ok(v) => {
                 self.consecutive_errors.store(0, Ordering::Relaxed);
-                Ok(())
+                Ok(v)
             }
         }
     }
@@ -348,7 +348,7 @@ pub enum CachedServiceProtectionLimit {
 /// Evaluate the number of columns/tables that would result if `batches` was
 /// applied to `schema`, and ensure the column/table count does not exceed the
 /// maximum permitted amount cached in the [`NamespaceSchema`].
-fn validate_schema_limits(
+pub(crate) fn validate_schema_limits(
     batches: &HashMap<String, MutableBatch>,
     schema: &NamespaceSchema,
 ) -> Result<(), CachedServiceProtectionLimit> {
@@ -0,0 +1,486 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use data_types::{NamespaceName, NamespaceSchema, Tenant, TenantId};
+use hashbrown::{HashMap, HashSet};
+use iox_time::{SystemProvider, Time, TimeProvider};
+use mutable_batch::{column::ColumnData, MutableBatch};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use thiserror::Error;
+use trace::ctx::SpanContext;
+
+use super::DmlHandler;
+use crate::tenant_resolver::TenantResolver;
+
+/// The default duration the storage used by a tenant is cached for.
+pub const DEFAULT_TENANT_USAGE_TTL: Duration = Duration::from_secs(60);
+
+/// Errors emitted when a write exceeds a quota of the tenant owning the
+/// namespace.
+#[derive(Debug, Error)]
+pub enum TenantQuotaError {
+    /// The tenant has written more lines in the last second than it is
+    /// allowed to.
+    #[error("tenant {tenant} exceeded its write rate quota of {limit} lines per second")]
+    WriteRateExceeded {
+        /// The name of the tenant.
+        tenant: String,
+        /// The configured limit.
+        limit: i64,
+    },
+
+    /// The parquet files of the tenant's namespaces use more storage than the
+    /// tenant is allowed to.
+    #[error("tenant {tenant} exceeded its storage quota of {limit} bytes ({used} bytes used)")]
+    StorageExceeded {
+        /// The name of the tenant.
+        tenant: String,
+        /// The configured limit.
+        limit: i64,
+        /// The storage used by the tenant.
+        used: i64,
+    },
+
+    /// The write would add series beyond the number the tenant is allowed.
+    #[error("write would exceed the series quota of {limit} for tenant {tenant}")]
+    SeriesExceeded {
+        /// The name of the tenant.
+        tenant: String,
+        /// The configured limit.
+        limit: i64,
+    },
+
+    /// A catalog error while resolving the tenant or its usage.
+    #[error("failed to resolve tenant usage: {0}")]
+    Catalog(#[from] iox_catalog::interface::Error),
+}
+
+/// The usage of a single tenant observed by this router.
+#[derive(Debug)]
+struct TenantUsage {
+    /// The storage used by the tenant, and when it was read from the catalog.
+    storage_bytes: Option<(i64, Time)>,
+
+    /// The hashes of the series written to the tenant.
+    series: HashSet<u64>,
+
+    /// The write rate token bucket, and when it was last refilled.
+    ///
+    /// The bucket may go negative when a write is larger than the remaining
+    /// tokens, delaying subsequent writes until the debt is repaid.
+    write_tokens: f64,
+    write_tokens_at: Time,
+}
+
+/// A [`DmlHandler`] implementation that enforces the [`TenantQuotas`] of the
+/// tenant owning the namespace of each write.
+///
+/// Writes to namespaces without a tenant are passed through unchanged.
+///
+/// # Quota accuracy
+///
+/// Quotas are enforced independently by each router, using a local view of
+/// the usage of each tenant:
+///
+/// * The write rate is enforced with a per-router token bucket holding at
+///   most one second of writes. A deployment of N routers therefore admits
+///   up to N times the configured rate.
+/// * The storage used is read from the catalog and cached for a
+///   configurable TTL, and only accounts for persisted data. Writes continue
+///   to be accepted until the next refresh observes the quota is exceeded.
+/// * The series written are tracked in memory since the router started, so
+///   series written through other routers, or before a restart, are not
+///   counted.
+///
+/// [`TenantQuotas`]: data_types::TenantQuotas
+#[derive(Debug)]
+pub struct TenantQuotaValidator<P = SystemProvider> {
+    tenants: Arc<TenantResolver<P>>,
+    usage_ttl: Duration,
+    usage: Mutex<HashMap<TenantId, TenantUsage>>,
+}
+
+impl<P> TenantQuotaValidator<P>
+where
+    P: TimeProvider,
+{
+    /// Initialise a [`TenantQuotaValidator`] resolving tenants through
+    /// `tenants`, and caching their storage usage for `usage_ttl`.
+    pub fn new(tenants: Arc<TenantResolver<P>>, usage_ttl: Duration) -> Self {
+        Self {
+            tenants,
+            usage_ttl,
+            usage: Default::default(),
+        }
+    }
+
+    /// Return the storage used by `tenant`, reading it from the catalog if
+    /// the cached value has expired.
+    async fn storage_bytes(&self, tenant: &Tenant, now: Time) -> Result<i64, TenantQuotaError> {
+        let cached = self
+            .usage
+            .lock()
+            .get(&tenant.id)
+            .and_then(|usage| usage.storage_bytes);
+        if let Some((used, fetched_at)) = cached {
+            if now.checked_duration_since(fetched_at).unwrap_or_default() < self.usage_ttl {
+                return Ok(used);
+            }
+        }
+
+        let used = self
+            .tenants
+            .catalog()
+            .repositories()
+            .await
+            .tenants()
+            .storage_bytes(tenant.id)
+            .await?;
+
+        self.usage
+            .lock()
+            .entry(tenant.id)
+            .or_insert_with(|| TenantUsage::new(tenant, now))
+            .storage_bytes = Some((used, now));
+
+        Ok(used)
+    }
+}
+
+impl TenantUsage {
+    fn new(tenant: &Tenant, now: Time) -> Self {
+        Self {
+            storage_bytes: None,
+            series: Default::default(),
+            write_tokens: tenant.quotas.max_write_lines_per_second.unwrap_or_default() as f64,
+            write_tokens_at: now,
+        }
+    }
+}
+
+#[async_trait]
+impl<P> DmlHandler for TenantQuotaValidator<P>
+where
+    P: TimeProvider,
+{
+    type WriteError = TenantQuotaError;
+
+    type WriteInput = HashMap<String, MutableBatch>;
+    type WriteOutput = Self::WriteInput;
+
+    /// Validate the write against the quotas of the namespace's tenant.
+    async fn write(
+        &self,
+        namespace: &NamespaceName<'static>,
+        _namespace_schema: Arc<NamespaceSchema>,
+        batch: Self::WriteInput,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let tenant = match self.tenants.get(namespace).await? {
+            Some(t) if !t.quotas.is_unlimited() => t,
+            _ => return Ok(batch),
+        };
+        let quotas = tenant.quotas;
+        let now = self.tenants.time_provider().now();
+
+        if let Some(limit) = quotas.max_storage_bytes {
+            let used = self.storage_bytes(&tenant, now).await?;
+            if used >= limit {
+                warn!(%namespace, tenant=%tenant.name, used, limit, "tenant storage quota exceeded");
+                return Err(TenantQuotaError::StorageExceeded {
+                    tenant: tenant.name.clone(),
+                    limit,
+                    used,
+                });
+            }
+        }
+
+        // Hash the series outside of the lock.
+        let series = quotas.max_series.map(|_| series_hashes(&batch));
+        let lines: usize = batch.values().map(|b| b.rows()).sum();
+
+        let mut guard = self.usage.lock();
+        let usage = guard
+            .entry(tenant.id)
+            .or_insert_with(|| TenantUsage::new(&tenant, now));
+
+        // Check every quota before recording the write against any of them,
+        // so rejected writes are not accounted for.
+        if let Some(limit) = quotas.max_write_lines_per_second {
+            let elapsed = now
+                .checked_duration_since(usage.write_tokens_at)
+                .unwrap_or_default();
+            usage.write_tokens =
+                (usage.write_tokens + elapsed.as_secs_f64() * limit as f64).min(limit as f64);
+            usage.write_tokens_at = now;
+
+            if usage.write_tokens <= 0.0 {
+                debug!(%namespace, tenant=%tenant.name, limit, "tenant write rate quota exceeded");
+                return Err(TenantQuotaError::WriteRateExceeded {
+                    tenant: tenant.name.clone(),
+                    limit,
+                });
+            }
+        }
+
+        if let (Some(limit), Some(series)) = (quotas.max_series, &series) {
+            let new = series.iter().filter(|s| !usage.series.contains(*s)).count();
+            if usage.series.len() + new > limit as usize {
+                warn!(%namespace, tenant=%tenant.name, limit, "tenant series quota exceeded");
+                return Err(TenantQuotaError::SeriesExceeded {
+                    tenant: tenant.name.clone(),
+                    limit,
+                });
+            }
+        }
+
+        if quotas.max_write_lines_per_second.is_some() {
+            usage.write_tokens -= lines as f64;
+        }
+        if let Some(series) = series {
+            usage.series.extend(series);
+        }
+
+        Ok(batch)
+    }
+}
+
+/// Return the hashes of the series (the table name and the tag set of a row)
+/// in `batch`.
+fn series_hashes(batch: &HashMap<String, MutableBatch>) -> HashSet<u64> {
+    let mut hashes = HashSet::new();
+
+    for (table, batch) in batch {
+        let mut tags = batch
+            .columns()
+            .filter_map(|(name, col)| match col.data() {
+                ColumnData::Tag(ids, dictionary, _) => Some((name, col, ids, dictionary)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        tags.sort_unstable_by_key(|(name, ..)| *name);
+
+        for row in 0..batch.rows() {
+            let mut hasher = DefaultHasher::new();
+            table.hash(&mut hasher);
+            for (name, col, ids, dictionary) in &tags {
+                if col.valid_mask().get(row) {
+                    name.hash(&mut hasher);
+                    dictionary.lookup_id(ids[row]).hash(&mut hasher);
+                }
+            }
+            hashes.insert(hasher.finish());
+        }
+    }
+
+    hashes
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::TenantQuotas;
+    use iox_catalog::interface::Catalog;
+    use iox_tests::{TestCatalog, TestNamespace, TestParquetFileBuilder};
+    use iox_time::MockProvider;
+    use once_cell::sync::Lazy;
+
+    use super::*;
+
+    static NAMESPACE: Lazy<NamespaceName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
+
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+
+    /// Create [`NAMESPACE`] owned by a tenant with `quotas`, and return a
+    /// validator for it along with the namespace schema.
+    async fn test_setup(
+        quotas: TenantQuotas,
+    ) -> (
+        Arc<TestNamespace>,
+        TenantQuotaValidator<MockProvider>,
+        Arc<NamespaceSchema>,
+    ) {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention(&NAMESPACE).await;
+        {
+            let mut repos = catalog.catalog().repositories().await;
+            let tenant = repos.tenants().create("platanos", quotas).await.unwrap();
+            repos
+                .namespaces()
+                .update_tenant(&NAMESPACE, Some(tenant.id))
+                .await
+                .unwrap();
+        }
+
+        let resolver = TenantResolver::new_with_time_provider(
+            catalog.catalog(),
+            Duration::from_secs(60),
+            MockProvider::new(Time::from_timestamp_nanos(0)),
+        );
+        let handler = TenantQuotaValidator::new(Arc::new(resolver), Duration::from_secs(60));
+        let schema = Arc::new(namespace.schema().await);
+
+        (namespace, handler, schema)
+    }
+
+    #[tokio::test]
+    async fn test_no_tenant() {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention(&NAMESPACE).await;
+        let resolver = TenantResolver::new(catalog.catalog(), Duration::from_secs(60));
+        let handler = TenantQuotaValidator::new(Arc::new(resolver), Duration::from_secs(60));
+
+        let writes = lp_to_writes("bananas,tag1=A val=42i 1");
+        let got = handler
+            .write(&NAMESPACE, Arc::new(namespace.schema().await), writes, None)
+            .await
+            .expect("write without tenant should pass");
+        assert_eq!(got.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_write_rate() {
+        let (_namespace, handler, schema) = test_setup(TenantQuotas {
+            max_write_lines_per_second: Some(2),
+            ..Default::default()
+        })
+        .await;
+
+        let lp = "bananas,tag1=A val=42i 1\nbananas,tag1=B val=42i 1\nbananas,tag1=C val=42i 1";
+
+        // The first write is admitted, and puts the bucket into debt.
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), lp_to_writes(lp), None)
+            .await
+            .expect("first write should pass");
+
+        let err = handler
+            .write(&NAMESPACE, Arc::clone(&schema), lp_to_writes(lp), None)
+            .await
+            .expect_err("second write should be rate limited");
+        assert_matches!(err, TenantQuotaError::WriteRateExceeded { limit: 2, .. });
+
+        // After the debt is repaid, writes are admitted again.
+        handler.tenants.time_provider().inc(Duration::from_secs(1));
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), lp_to_writes(lp), None)
+            .await
+            .expect("write after refill should pass");
+    }
+
+    #[tokio::test]
+    async fn test_series() {
+        let (_namespace, handler, schema) = test_setup(TenantQuotas {
+            max_series: Some(2),
+            ..Default::default()
+        })
+        .await;
+
+        handler
+            .write(
+                &NAMESPACE,
+                Arc::clone(&schema),
+                lp_to_writes("bananas,tag1=A val=1i 1\nbananas,tag1=A val=2i 2"),
+                None,
+            )
+            .await
+            .expect("one series should pass");
+
+        // Adding two series would exceed the quota, and is rejected as a
+        // whole.
+        let err = handler
+            .write(
+                &NAMESPACE,
+                Arc::clone(&schema),
+                lp_to_writes("bananas,tag1=B val=1i 1\nbananas,tag1=C val=1i 1"),
+                None,
+            )
+            .await
+            .expect_err("three series should be rejected");
+        assert_matches!(err, TenantQuotaError::SeriesExceeded { limit: 2, .. });
+
+        // Existing series, and one new one, are still admitted.
+        handler
+            .write(
+                &NAMESPACE,
+                Arc::clone(&schema),
+                lp_to_writes("bananas,tag1=A val=3i 3\nplatanos,tag1=A val=1i 1"),
+                None,
+            )
+            .await
+            .expect("two series should pass");
+    }
+
+    #[tokio::test]
+    async fn test_storage() {
+        let (namespace, handler, schema) = test_setup(TenantQuotas {
+            max_storage_bytes: Some(1),
+            ..Default::default()
+        })
+        .await;
+
+        // Nothing is persisted yet.
+        handler
+            .write(
+                &NAMESPACE,
+                Arc::clone(&schema),
+                lp_to_writes("bananas,tag1=A val=1i 1"),
+                None,
+            )
+            .await
+            .expect("write should pass");
+
+        let partition = namespace
+            .create_table("bananas")
+            .await
+            .create_partition("arbitrary")
+            .await;
+        partition
+            .create_parquet_file(
+                TestParquetFileBuilder::default()
+                    .with_line_protocol("bananas,tag1=A val=1i 1")
+                    .with_file_size_bytes(10),
+            )
+            .await;
+
+        // The cached usage is used until it expires.
+        handler
+            .write(
+                &NAMESPACE,
+                Arc::clone(&schema),
+                lp_to_writes("bananas,tag1=A val=1i 1"),
+                None,
+            )
+            .await
+            .expect("write should pass with cached usage");
+
+        handler.tenants.time_provider().inc(Duration::from_secs(60));
+        let err = handler
+            .write(
+                &NAMESPACE,
+                Arc::clone(&schema),
+                lp_to_writes("bananas,tag1=A val=1i 1"),
+                None,
+            )
+            .await
+            .expect_err("write should exceed the storage quota");
+        assert_matches!(
+            err,
+            TenantQuotaError::StorageExceeded {
+                limit: 1,
+                used: 10,
+                ..
+            }
+        );
+    }
+}
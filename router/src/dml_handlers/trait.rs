@@ -1,5 +1,6 @@
 use super::{
     partitioner::PartitionError, retention_validation::RetentionError, RpcWriteError, SchemaError,
+    TenantQuotaError,
 };
 use async_trait::async_trait;
 use data_types::{NamespaceName, NamespaceSchema};
@@ -32,6 +33,10 @@ pub enum DmlError {
     #[error(transparent)]
     Retention(#[from] RetentionError),
 
+    /// The write exceeds a quota of the tenant owning the namespace.
+    #[error(transparent)]
+    TenantQuota(#[from] TenantQuotaError),
+
     /// An unknown error occured while processing the DML request.
     #[error("internal dml handler error: {0}")]
     Internal(Box<dyn Error + Send + Sync>),
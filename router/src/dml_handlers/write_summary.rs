@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use data_types::SequenceNumber;
+use serde::Serialize;
+
+/// A summary of a successfully applied write, returned to the client that
+/// submitted it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct WriteSummary {
+    /// The outcome of the write for each table, keyed by table name.
+    tables: BTreeMap<String, TableWriteSummary>,
+}
+
+/// The outcome of a write for a single table.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct TableWriteSummary {
+    /// The number of rows accepted.
+    rows: usize,
+
+    /// The sequence numbers assigned to the write, one for each partition of
+    /// the table written to each upstream ingester.
+    ///
+    /// Sequence numbers are assigned independently by each ingester, so the
+    /// same number may appear more than once in a replicated write.
+    sequence_numbers: Vec<u64>,
+}
+
+impl WriteSummary {
+    /// Record `rows` accepted rows for `table`.
+    pub fn add_rows(&mut self, table: &str, rows: usize) {
+        self.table_mut(table).rows += rows;
+    }
+
+    /// Record the [`SequenceNumber`] an ingester assigned to the write of
+    /// `table`.
+    pub fn add_sequence_number(&mut self, table: &str, sequence_number: SequenceNumber) {
+        self.table_mut(table)
+            .sequence_numbers
+            .push(sequence_number.get());
+    }
+
+    /// Returns the per-table summaries, ordered by table name.
+    pub fn tables(&self) -> impl Iterator<Item = (&str, &TableWriteSummary)> {
+        self.tables
+            .iter()
+            .map(|(name, table)| (name.as_str(), table))
+    }
+
+    fn table_mut(&mut self, table: &str) -> &mut TableWriteSummary {
+        self.tables.entry(table.to_string()).or_default()
+    }
+}
+
+/// Merges the summaries of the (partitioned) parts of a single write.
+impl Extend<Self> for WriteSummary {
+    fn extend<T: IntoIterator<Item = Self>>(&mut self, iter: T) {
+        for other in iter {
+            for (name, other) in other.tables {
+                let table = self.tables.entry(name).or_default();
+                table.rows += other.rows;
+                table.sequence_numbers.extend(other.sequence_numbers);
+            }
+        }
+        for table in self.tables.values_mut() {
+            table.sequence_numbers.sort_unstable();
+        }
+    }
+}
+
+impl TableWriteSummary {
+    /// The number of rows accepted.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The sequence numbers assigned to the write.
+    pub fn sequence_numbers(&self) -> &[u64] {
+        &self.sequence_numbers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut a = WriteSummary::default();
+        a.add_rows("bananas", 2);
+        a.add_sequence_number("bananas", SequenceNumber::new(4));
+
+        let mut b = WriteSummary::default();
+        b.add_rows("bananas", 1);
+        b.add_sequence_number("bananas", SequenceNumber::new(3));
+        b.add_rows("platanos", 5);
+        b.add_sequence_number("platanos", SequenceNumber::new(2));
+
+        let mut got = WriteSummary::default();
+        got.extend([a, b]);
+
+        let tables = got
+            .tables()
+            .map(|(name, t)| (name, t.rows(), t.sequence_numbers()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tables,
+            [
+                ("bananas", 3, [3, 4].as_slice()),
+                ("platanos", 5, [2].as_slice())
+            ]
+        );
+
+        assert_eq!(
+            serde_json::to_string(&got).unwrap(),
+            r#"{"tables":{"bananas":{"rows":3,"sequence_numbers":[3,4]},"platanos":{"rows":5,"sequence_numbers":[2]}}}"#
+        );
+    }
+}
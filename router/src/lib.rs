@@ -5,6 +5,7 @@
 //! * Creating IOx namespaces & synchronising them within the catalog.
 //! * Handling writes:
 //!     * Receiving IOx write/delete requests via HTTP
+//!     * Normalizing tag values and deriving tags with per-namespace rules
 //!     * Creating or validating the write's namespace
 //!     * Validating write payloads are within the configured retention period
 //!     * Enforcing schema validation & synchronising it within the catalog
@@ -95,6 +96,7 @@
 //!
 //! See the handler types for further documentation:
 //!
+//! * [`TenantQuotaValidator`]
 //! * [`RetentionValidator`]
 //! * [`SchemaValidator`]
 //! * [`Partitioner`]
@@ -108,6 +110,7 @@
 //! [`NamespaceCache`]: crate::namespace_cache::NamespaceCache
 //! [`NamespaceSchema`]: data_types::NamespaceSchema
 //! [`DmlHandler`]: crate::dml_handlers
+//! [`TenantQuotaValidator`]: crate::dml_handlers::TenantQuotaValidator
 //! [`RetentionValidator`]: crate::dml_handlers::RetentionValidator
 //! [`SchemaValidator`]: crate::dml_handlers::SchemaValidator
 //! [`Partitioner`]: crate::dml_handlers::Partitioner
@@ -133,11 +136,14 @@
 use criterion as _;
 use workspace_hack as _;
 
+pub mod derived_tags;
 pub mod dml_handlers;
 pub mod gossip;
 pub mod namespace_cache;
 pub mod namespace_resolver;
 pub mod server;
+pub mod tag_normalization;
+pub mod tenant_resolver;
 
 #[cfg(test)]
 pub(crate) mod test_helpers {
@@ -33,6 +33,20 @@ pub trait NamespaceResolver: std::fmt::Debug + Send + Sync {
         &self,
         namespace: &NamespaceName<'static>,
     ) -> Result<Arc<NamespaceSchema>, Error>;
+
+    /// Return the [`NamespaceSchema`] for the given [`NamespaceName`] without
+    /// any side effects, i.e. without creating the namespace if it does not
+    /// exist.
+    ///
+    /// Used by writes that are validated but never applied (dry-run writes).
+    /// Defaults to [`NamespaceResolver::get_namespace_schema`], so resolvers
+    /// with side effects must override it.
+    async fn lookup_namespace_schema(
+        &self,
+        namespace: &NamespaceName<'static>,
+    ) -> Result<Arc<NamespaceSchema>, Error> {
+        self.get_namespace_schema(namespace).await
+    }
 }
 
 /// An implementation of [`NamespaceResolver`] that resolves the [`NamespaceSchema`]
@@ -130,6 +130,24 @@ where
 
         self.inner.get_namespace_schema(namespace).await
     }
+
+    /// Look `namespace` up without creating it.
+    ///
+    /// If the namespace does not exist and auto-creation is disabled, this
+    /// fails with the error a write would be rejected with.
+    async fn lookup_namespace_schema(
+        &self,
+        namespace: &NamespaceName<'static>,
+    ) -> Result<Arc<NamespaceSchema>, super::Error> {
+        match self.inner.lookup_namespace_schema(namespace).await {
+            Err(super::Error::Lookup(iox_catalog::interface::Error::NamespaceNotFoundByName {
+                ..
+            })) if matches!(self.action, MissingNamespaceAction::Reject) => {
+                Err(NamespaceCreationError::Reject(namespace.into()).into())
+            }
+            res => res,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -233,6 +251,8 @@ mod tests {
                 retention_period_ns: TEST_RETENTION_PERIOD_NS,
                 deleted_at: None,
                 partition_template: Default::default(),
+                tenant_id: None,
+                template_id: None,
             }
         );
     }
@@ -310,4 +330,54 @@ mod tests {
             .expect("should allow existing namespace from catalog");
         assert_eq!(created_id, id);
     }
+
+    #[tokio::test]
+    async fn test_lookup_does_not_create() {
+        let ns = NamespaceName::try_from("bananas").unwrap();
+
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let cache = Arc::new(ReadThroughCache::new(
+            MemoryNamespaceCache::default(),
+            Arc::clone(&catalog),
+        ));
+
+        let creator = NamespaceAutocreation::new(
+            NamespaceSchemaResolver::new(Arc::clone(&cache)),
+            Arc::clone(&cache),
+            Arc::clone(&catalog),
+            MissingNamespaceAction::AutoCreate(TEST_RETENTION_PERIOD_NS),
+        );
+        assert_matches!(
+            creator.lookup_namespace_schema(&ns).await,
+            Err(crate::namespace_resolver::Error::Lookup(
+                iox_catalog::interface::Error::NamespaceNotFoundByName { .. }
+            ))
+        );
+
+        // The namespace was not created
+        let mut repos = catalog.repositories().await;
+        assert_matches!(
+            repos
+                .namespaces()
+                .get_by_name(ns.as_str(), SoftDeletedRows::ExcludeDeleted)
+                .await,
+            Ok(None)
+        );
+        drop(repos);
+
+        // With auto-creation disabled, the lookup fails like a write
+        let creator = NamespaceAutocreation::new(
+            NamespaceSchemaResolver::new(Arc::clone(&cache)),
+            cache,
+            Arc::clone(&catalog),
+            MissingNamespaceAction::Reject,
+        );
+        assert_matches!(
+            creator.lookup_namespace_schema(&ns).await,
+            Err(crate::namespace_resolver::Error::Create(
+                NamespaceCreationError::Reject(_)
+            ))
+        );
+    }
 }
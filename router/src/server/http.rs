@@ -1,32 +1,44 @@
 //! HTTP service implementations for `router`.
 
+mod dry_run;
+mod table_metrics;
 pub mod write;
 
 use std::{str::Utf8Error, time::Instant};
 
+use authz::TagFilterPolicy;
 use bytes::{Bytes, BytesMut};
 use futures::StreamExt;
 use hashbrown::HashMap;
-use hyper::{header::CONTENT_ENCODING, Body, Method, Request, Response, StatusCode};
+use hyper::{
+    header::{ACCEPT, CONTENT_ENCODING, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, U64Counter};
-use mutable_batch::MutableBatch;
+use mutable_batch::{column::ColumnData, MutableBatch};
 use mutable_batch_lp::LinesConverter;
 use observability_deps::tracing::*;
 use thiserror::Error;
 use tokio::sync::{Semaphore, TryAcquireError};
 use trace::ctx::SpanContext;
 
-use self::write::{
-    multi_tenant::MultiTenantExtractError, single_tenant::SingleTenantExtractError, WriteParams,
-    WriteRequestUnifier,
+use self::{
+    dry_run::{dry_run, DryRunSummary},
+    table_metrics::{TableMetrics, TableWrite, MAX_TRACKED_TABLES},
+    write::{
+        multi_tenant::MultiTenantExtractError, single_tenant::SingleTenantExtractError,
+        WriteParams, WriteRequestUnifier,
+    },
 };
 use crate::{
+    derived_tags::DerivedTagRules,
     dml_handlers::{
         client::RpcWriteClientError, DmlError, DmlHandler, PartitionError, RetentionError,
-        RpcWriteError, SchemaError,
+        RpcWriteError, SchemaError, TenantQuotaError, WriteSummary,
     },
     namespace_resolver::NamespaceResolver,
+    tag_normalization::TagNormalizationRules,
 };
 
 /// Errors returned by the `router` HTTP request handler.
@@ -99,6 +111,11 @@ pub enum Error {
     /// The provided authorization is not sufficient to perform the request.
     #[error("access denied")]
     Forbidden,
+
+    /// The write contains rows to the given table that the tag filter
+    /// policies of the authorization token do not allow.
+    #[error("access denied: rows written to table {0} violate a tag filter policy")]
+    TagFilterViolation(String),
 }
 
 impl Error {
@@ -127,6 +144,7 @@ impl Error {
             Error::RequestLimit => StatusCode::SERVICE_UNAVAILABLE,
             Error::Unauthenticated => StatusCode::UNAUTHORIZED,
             Error::Forbidden => StatusCode::FORBIDDEN,
+            Error::TagFilterViolation(_) => StatusCode::FORBIDDEN,
             Error::SingleTenantError(e) => StatusCode::from(e),
             Error::MultiTenantError(e) => StatusCode::from(e),
         }
@@ -153,6 +171,15 @@ impl From<&DmlError> for StatusCode {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
             DmlError::Retention(RetentionError::OutsideRetention { .. }) => StatusCode::FORBIDDEN,
+            DmlError::TenantQuota(TenantQuotaError::WriteRateExceeded { .. }) => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
+            DmlError::TenantQuota(
+                TenantQuotaError::StorageExceeded { .. } | TenantQuotaError::SeriesExceeded { .. },
+            ) => StatusCode::FORBIDDEN,
+            DmlError::TenantQuota(TenantQuotaError::Catalog(_)) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
             DmlError::RpcWrite(RpcWriteError::Client(RpcWriteClientError::Upstream(_))) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
@@ -188,6 +215,8 @@ pub struct HttpDelegate<D, N, T = SystemProvider> {
     namespace_resolver: N,
     dml_handler: D,
     write_request_mode_handler: Box<dyn WriteRequestUnifier>,
+    tag_normalization: TagNormalizationRules,
+    derived_tags: DerivedTagRules,
 
     // A request limiter to restrict the number of simultaneous requests this
     // router services.
@@ -203,6 +232,7 @@ pub struct HttpDelegate<D, N, T = SystemProvider> {
     write_metric_fields: U64Counter,
     write_metric_tables: U64Counter,
     write_metric_body_size: U64Counter,
+    write_metric_table: TableMetrics,
     request_limit_rejected: U64Counter,
 }
 
@@ -244,6 +274,7 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
                 "cumulative byte size of successfully routed (decompressed) line protocol write requests",
             )
             .recorder(&[]);
+        let write_metric_table = TableMetrics::new(metrics, MAX_TRACKED_TABLES);
         let request_limit_rejected = metrics
             .register_metric::<U64Counter>(
                 "http_request_limit_rejected",
@@ -262,6 +293,8 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
             time_provider: SystemProvider::default(),
             namespace_resolver,
             write_request_mode_handler,
+            tag_normalization: Default::default(),
+            derived_tags: Default::default(),
             dml_handler,
             request_sem: Semaphore::new(max_requests),
             write_metric_lines,
@@ -269,14 +302,29 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
             write_metric_fields,
             write_metric_tables,
             write_metric_body_size,
+            write_metric_table,
             request_limit_rejected,
         }
     }
 }
 
+impl<D, N, T> HttpDelegate<D, N, T> {
+    /// Normalize the tag values of writes with the per-namespace `rules`.
+    pub fn with_tag_normalization(mut self, rules: TagNormalizationRules) -> Self {
+        self.tag_normalization = rules;
+        self
+    }
+
+    /// Add the tags derived by the per-namespace `rules` to writes.
+    pub fn with_derived_tags(mut self, rules: DerivedTagRules) -> Self {
+        self.derived_tags = rules;
+        self
+    }
+}
+
 impl<D, N, T> HttpDelegate<D, N, T>
 where
-    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = ()>,
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = WriteSummary>,
     N: NamespaceResolver,
     T: TimeProvider,
 {
@@ -300,24 +348,34 @@ where
             Err(e) => panic!("request limiter error: {e}"),
         };
 
+        // Clients opt into a JSON summary of the write by accepting it,
+        // otherwise the InfluxDB-compatible empty response is returned.
+        let want_summary = accepts_json(&req);
+
+        // Dry-run writes are validated but not applied.
+        let dry_run = is_dry_run(&req);
+
         // Route the request to a handler.
         match (req.method(), req.uri().path()) {
             (&Method::POST, "/write") => {
                 let dml_info = self.write_request_mode_handler.parse_v1(&req).await?;
-                self.write_handler(req, dml_info).await
+                self.write_handler(req, dml_info, dry_run).await
             }
             (&Method::POST, "/api/v2/write") => {
                 let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
-                self.write_handler(req, dml_info).await
+                self.write_handler(req, dml_info, dry_run).await
             }
             (&Method::POST, "/api/v2/delete") => return Err(Error::DeletesUnsupported),
             _ => return Err(Error::NoHandler),
         }
-        .map(|_summary| {
-            Response::builder()
+        .map(|outcome| match outcome {
+            // the changes of a dry run are always returned
+            WriteOutcome::DryRun(summary) => json_response(&summary),
+            WriteOutcome::Applied(summary) if want_summary => json_response(&summary),
+            WriteOutcome::Applied(_) => Response::builder()
                 .status(StatusCode::NO_CONTENT)
                 .body(Body::empty())
-                .unwrap()
+                .unwrap(),
         })
     }
 
@@ -325,7 +383,8 @@ where
         &self,
         req: Request<Body>,
         write_info: WriteParams,
-    ) -> Result<(), Error> {
+        dry_run: bool,
+    ) -> Result<WriteOutcome, Error> {
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
         trace!(
@@ -344,15 +403,30 @@ where
 
         let mut converter = LinesConverter::new(default_time);
         converter.set_timestamp_base(write_info.precision.timestamp_base());
+        if let Some(rules) = self.tag_normalization.get(write_info.namespace.as_str()) {
+            converter.set_tag_normalizer(rules);
+        }
+        if let Some(rules) = self.derived_tags.get(write_info.namespace.as_str()) {
+            converter.set_tag_deriver(rules);
+        }
         let (batches, stats) = match converter.write_lp(body).and_then(|_| converter.finish()) {
             Ok(v) => v,
             Err(mutable_batch_lp::Error::EmptyPayload) => {
                 debug!("nothing to write");
-                return Ok(());
+                return Ok(if dry_run {
+                    WriteOutcome::DryRun(DryRunSummary::default())
+                } else {
+                    WriteOutcome::Applied(WriteSummary::default())
+                });
             }
             Err(e) => return Err(Error::ParseLineProtocol(e)),
         };
 
+        check_tag_filters(
+            &write_info.tag_filters,
+            batches.iter().map(|(table, batch)| (table.as_str(), batch)),
+        )?;
+
         let num_tables = batches.len();
         let duration = start_instant.elapsed();
         self.http_line_protocol_parse_duration.record(duration);
@@ -367,23 +441,61 @@ where
             "routing write",
         );
 
+        if dry_run {
+            debug!(namespace=%write_info.namespace, "dry-run write, not applying");
+            // Look the namespace up without creating it.
+            let namespace_schema = match self
+                .namespace_resolver
+                .lookup_namespace_schema(&write_info.namespace)
+                .await
+            {
+                Ok(v) => Some(v),
+                Err(crate::namespace_resolver::Error::Lookup(
+                    iox_catalog::interface::Error::NamespaceNotFoundByName { .. },
+                )) => None,
+                Err(e) => return Err(e.into()),
+            };
+            let summary = self::dry_run(&write_info.namespace, namespace_schema, batches).await?;
+            return Ok(WriteOutcome::DryRun(summary));
+        }
+
         // Retrieve the namespace schema for this namespace.
         let namespace_schema = self
             .namespace_resolver
             .get_namespace_schema(&write_info.namespace)
             .await?;
 
-        self.dml_handler
+        let table_writes = batches
+            .iter()
+            .map(|(table, batch)| {
+                let write = TableWrite {
+                    rows: batch.rows(),
+                    bytes: batch.size_data(),
+                };
+                (table.clone(), write)
+            })
+            .collect::<Vec<_>>();
+
+        let res = self
+            .dml_handler
             .write(&write_info.namespace, namespace_schema, batches, span_ctx)
-            .await
-            .map_err(Into::into)?;
+            .await;
+
+        self.write_metric_table.record(
+            &write_info.namespace,
+            table_writes
+                .iter()
+                .map(|(table, write)| (table.as_str(), *write)),
+            res.is_ok(),
+        );
+        let summary = res?;
 
         self.write_metric_lines.inc(stats.num_lines as _);
         self.write_metric_fields.inc(stats.num_fields as _);
         self.write_metric_tables.inc(num_tables as _);
         self.write_metric_body_size.inc(body.len() as _);
 
-        Ok(())
+        Ok(WriteOutcome::Applied(summary))
     }
 
     /// Parse the request's body into raw bytes, applying the configured size
@@ -444,13 +556,93 @@ where
     }
 }
 
+/// Returns an error if any row of `batches` is not allowed by the tag filter
+/// `policies`, i.e. has no value or a disallowed value for a filtered tag.
+fn check_tag_filters<'a>(
+    policies: &[TagFilterPolicy],
+    batches: impl IntoIterator<Item = (&'a str, &'a MutableBatch)>,
+) -> Result<(), Error> {
+    if policies.is_empty() {
+        return Ok(());
+    }
+
+    for (table, batch) in batches {
+        for policy in policies {
+            // the dictionary of a freshly converted batch only contains values
+            // of its rows
+            let allowed = match batch
+                .column(&policy.tag)
+                .map(|c| (c.valid_mask(), c.data()))
+            {
+                Ok((valid, ColumnData::Tag(_, dictionary, _))) => {
+                    valid.is_all_set() && dictionary.values().iter().all(|v| policy.allows(v))
+                }
+                _ => false,
+            };
+            if !allowed {
+                return Err(Error::TagFilterViolation(table.to_string()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The result of a successful write request.
+#[derive(Debug)]
+enum WriteOutcome {
+    /// The write was applied.
+    Applied(WriteSummary),
+    /// The write was validated, but not applied.
+    DryRun(DryRunSummary),
+}
+
+fn json_response<T: serde::Serialize>(summary: &T) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            serde_json::to_vec(summary).expect("write summary serialises"),
+        ))
+        .unwrap()
+}
+
+/// Returns true if `req` sets the `dry_run=true` query parameter.
+fn is_dry_run(req: &Request<Body>) -> bool {
+    req.uri()
+        .query()
+        .and_then(|query| serde_urlencoded::from_str::<Vec<(String, String)>>(query).ok())
+        .map(|params| {
+            params
+                .iter()
+                .any(|(k, v)| k == "dry_run" && v.eq_ignore_ascii_case("true"))
+        })
+        .unwrap_or_default()
+}
+
+/// Returns true if the `Accept` header of `req` lists `application/json`.
+fn accepts_json(req: &Request<Body>) -> bool {
+    req.headers()
+        .get_all(&ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|v| {
+            v.split(';')
+                .next()
+                .map(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+                .unwrap_or_default()
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use std::{io::Write, iter, sync::Arc, time::Duration};
 
     use assert_matches::assert_matches;
     use data_types::{
-        NamespaceId, NamespaceName, NamespaceNameError, OrgBucketMappingError, TableId,
+        NamespaceId, NamespaceName, NamespaceNameError, OrgBucketMappingError, SequenceNumber,
+        TableId,
     };
     use flate2::{write::GzEncoder, Compression};
     use hyper::header::HeaderValue;
@@ -673,7 +865,7 @@ mod tests {
         ok,
         query_string = "?org=bananas&bucket=test",
         body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Ok(_),
         want_dml_calls = [
             MockDmlHandlerCall::Write { namespace, .. }
@@ -686,7 +878,7 @@ mod tests {
         ok_precision_s,
         query_string = "?org=bananas&bucket=test&precision=s",
         body = "platanos,tag1=A,tag2=B val=42i 1647622847".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Ok(_),
         want_dml_calls = [
             MockDmlHandlerCall::Write { namespace, namespace_schema, write_input, .. }
@@ -704,7 +896,7 @@ mod tests {
         ok_precision_ms,
         query_string = "?org=bananas&bucket=test&precision=ms",
         body = "platanos,tag1=A,tag2=B val=42i 1647622847000".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Ok(_),
         want_dml_calls = [
             MockDmlHandlerCall::Write { namespace, namespace_schema, write_input, .. }
@@ -722,7 +914,7 @@ mod tests {
         ok_precision_us,
         query_string = "?org=bananas&bucket=test&precision=us",
         body = "platanos,tag1=A,tag2=B val=42i 1647622847000000".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Ok(_),
         want_dml_calls = [
             MockDmlHandlerCall::Write { namespace, namespace_schema, write_input, .. }
@@ -740,7 +932,7 @@ mod tests {
         ok_precision_ns,
         query_string = "?org=bananas&bucket=test&precision=ns",
         body = "platanos,tag1=A,tag2=B val=42i 1647622847000000000".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Ok(_),
         want_dml_calls = [
             MockDmlHandlerCall::Write { namespace, namespace_schema, write_input, .. }
@@ -759,7 +951,7 @@ mod tests {
         // SECONDS, so multiplies the provided timestamp by 1,000,000,000
         query_string = "?org=bananas&bucket=test&precision=s",
         body = "platanos,tag1=A,tag2=B val=42i 1647622847000000000".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Err(Error::ParseLineProtocol(_)),
         want_dml_calls = []
     );
@@ -768,7 +960,7 @@ mod tests {
         no_query_params,
         query_string = "",
         body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Err(Error::MultiTenantError(
             MultiTenantExtractError::ParseV2Request(V2WriteParseError::NoQueryParams)
         )),
@@ -779,7 +971,7 @@ mod tests {
         no_org_bucket,
         query_string = "?",
         body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Err(Error::MultiTenantError(
             MultiTenantExtractError::InvalidOrgAndBucket(
                 OrgBucketMappingError::NoOrgBucketSpecified
@@ -792,7 +984,7 @@ mod tests {
         empty_org_bucket,
         query_string = "?org=&bucket=",
         body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Err(Error::MultiTenantError(
             MultiTenantExtractError::InvalidOrgAndBucket(
                 OrgBucketMappingError::NoOrgBucketSpecified
@@ -805,7 +997,7 @@ mod tests {
         invalid_org_bucket,
         query_string = format!("?org=test&bucket={}", "A".repeat(1000)),
         body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Err(Error::MultiTenantError(
             MultiTenantExtractError::InvalidOrgAndBucket(
                 OrgBucketMappingError::InvalidNamespaceName(
@@ -820,7 +1012,7 @@ mod tests {
         invalid_line_protocol,
         query_string = "?org=bananas&bucket=test",
         body = "not line protocol".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Err(Error::ParseLineProtocol(_)),
         want_dml_calls = [] // None
     );
@@ -829,7 +1021,7 @@ mod tests {
         non_utf8_body,
         query_string = "?org=bananas&bucket=test",
         body = vec![0xc3, 0x28],
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Err(Error::NonUtf8Body(_)),
         want_dml_calls = [] // None
     );
@@ -857,7 +1049,7 @@ mod tests {
                 .flat_map(|s| s.bytes())
                 .collect::<Vec<u8>>()
         },
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Err(Error::RequestSizeExceeded(_)),
         want_dml_calls = [] // None
     );
@@ -888,7 +1080,7 @@ mod tests {
         field_upsert_within_batch,
         query_string = "?org=bananas&bucket=test",
         body = "test field=1u 100\ntest field=2u 100".as_bytes(),
-        dml_handler = [Ok(())],
+        dml_handler = [Ok(WriteSummary::default())],
         want_result = Ok(_),
         want_dml_calls = [
             MockDmlHandlerCall::Write { namespace, namespace_schema, write_input, .. }
@@ -931,7 +1123,7 @@ mod tests {
             duplicate_fields_same_value,
             query_string = "?org=bananas&bucket=test",
             body = "whydo InputPower=300i,InputPower=300i".as_bytes(),
-            dml_handler = [Ok(())],
+            dml_handler = [Ok(WriteSummary::default())],
             want_result = Ok(_),
             want_dml_calls = [MockDmlHandlerCall::Write { namespace, write_input, .. }] => {
                 assert_eq!(namespace, NAMESPACE_NAME);
@@ -948,7 +1140,7 @@ mod tests {
             duplicate_fields_different_value,
             query_string = "?org=bananas&bucket=test",
             body = "whydo InputPower=300i,InputPower=42i".as_bytes(),
-            dml_handler = [Ok(())],
+            dml_handler = [Ok(WriteSummary::default())],
             want_result = Ok(_),
             want_dml_calls = [MockDmlHandlerCall::Write { namespace, write_input, .. }] => {
                 assert_eq!(namespace, NAMESPACE_NAME);
@@ -1038,6 +1230,119 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_check_tag_filters() {
+        let policies = vec![TagFilterPolicy {
+            database: NAMESPACE_NAME.to_string(),
+            tag: "tenant_id".to_string(),
+            values: vec!["acme".to_string(), "globex".to_string()],
+        }];
+
+        let check = |lp: &str| {
+            let mut converter = LinesConverter::new(42);
+            converter.write_lp(lp).unwrap();
+            let (batches, _) = converter.finish().unwrap();
+            check_tag_filters(
+                &policies,
+                batches.iter().map(|(table, batch)| (table.as_str(), batch)),
+            )
+        };
+
+        assert_matches!(
+            check("cpu,tenant_id=acme v=1 1\ncpu,tenant_id=globex v=2 2\nmem,tenant_id=acme v=3 3"),
+            Ok(())
+        );
+        assert_matches!(
+            check("cpu,tenant_id=acme v=1 1\ncpu,tenant_id=initech v=2 2"),
+            Err(Error::TagFilterViolation(table)) => assert_eq!(table, "cpu")
+        );
+        // rows without the tag are not allowed
+        assert_matches!(
+            check("cpu,tenant_id=acme v=1 1\ncpu v=2 2"),
+            Err(Error::TagFilterViolation(_))
+        );
+        assert_matches!(check("mem v=2 2"), Err(Error::TagFilterViolation(_)));
+        // a field of the same name does not satisfy the policy
+        assert_matches!(
+            check("mem tenant_id=\"acme\" 2"),
+            Err(Error::TagFilterViolation(_))
+        );
+
+        // no policies, no restrictions
+        let mut converter = LinesConverter::new(42);
+        converter.write_lp("mem v=2 2").unwrap();
+        let (batches, _) = converter.finish().unwrap();
+        assert_matches!(
+            check_tag_filters(
+                &[],
+                batches.iter().map(|(table, batch)| (table.as_str(), batch))
+            ),
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tag_normalization() {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler =
+            Arc::new(MockDmlHandler::default().with_write_return([Ok(WriteSummary::default())]));
+        let metrics = Arc::new(metric::Registry::default());
+
+        let rules = TagNormalizationRules::new([(
+            NAMESPACE_NAME.to_string(),
+            crate::tag_normalization::NamespaceTagRules::new(
+                [(
+                    "host".to_string(),
+                    crate::tag_normalization::TagRule {
+                        trim: true,
+                        lowercase: true,
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            ),
+        )]);
+
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::new(
+                MockWriteRequestUnifier::default().with_ret(iter::repeat_with(|| {
+                    Ok(WriteParams {
+                        namespace: NamespaceName::new(NAMESPACE_NAME).unwrap(),
+                        precision: Precision::default(),
+                        tag_filters: vec![],
+                    })
+                })),
+            ),
+        )
+        .with_tag_normalization(rules);
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+            .method("POST")
+            .body(Body::from(
+                "cpu,host=Server01 v=1i 1\ncpu,host=\\ server01 v=2i 2\ncpu,host=SERVER01 v=3i 3",
+            ))
+            .unwrap();
+        delegate.route(request).await.expect("write should succeed");
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { write_input, .. }] => {
+                let column = write_input["cpu"].column("host").expect("host column");
+                assert_matches!(column.data(), ColumnData::Tag(_, dictionary, _) => {
+                    assert_eq!(dictionary.values().iter().collect::<Vec<_>>(), ["server01"]);
+                });
+            }
+        );
+    }
+
     #[derive(Debug, Error)]
     enum MockError {
         #[error("bad stuff")]
@@ -1064,6 +1369,7 @@ mod tests {
                     Ok(WriteParams {
                         namespace: NamespaceName::new(NAMESPACE_NAME).unwrap(),
                         precision: Precision::default(),
+                        tag_filters: vec![],
                     })
                 })),
             ),
@@ -1183,7 +1489,8 @@ mod tests {
         let mock_namespace_resolver =
             MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NamespaceId::new(42));
 
-        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let dml_handler =
+            Arc::new(MockDmlHandler::default().with_write_return([Ok(WriteSummary::default())]));
         let metrics = Arc::new(metric::Registry::default());
         let delegate = HttpDelegate::new(
             MAX_BYTES,
@@ -1204,6 +1511,135 @@ mod tests {
         assert_matches!(got, Err(Error::NoHandler));
     }
 
+    /// Assert the write summary is returned as the response body only when
+    /// the client accepts JSON.
+    #[tokio::test]
+    async fn test_write_summary_response() {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NamespaceId::new(42));
+
+        let mut summary = WriteSummary::default();
+        summary.add_rows("platanos", 1);
+        summary.add_sequence_number("platanos", SequenceNumber::new(42));
+
+        let dml_handler = Arc::new(
+            MockDmlHandler::default().with_write_return([Ok(summary.clone()), Ok(summary)]),
+        );
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        // Without an Accept header the response is empty.
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+            .method("POST")
+            .body(Body::from("platanos,tag1=A val=42i 123456"))
+            .unwrap();
+        let got = delegate.route(request).await.expect("write should succeed");
+        assert_eq!(got.status(), StatusCode::NO_CONTENT);
+
+        // Accepting JSON returns the summary.
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+            .method("POST")
+            .header(ACCEPT, "text/plain, application/json;q=0.9")
+            .body(Body::from("platanos,tag1=A val=42i 123456"))
+            .unwrap();
+        let got = delegate.route(request).await.expect("write should succeed");
+        assert_eq!(got.status(), StatusCode::OK);
+        assert_eq!(
+            got.headers().get(CONTENT_TYPE).unwrap(),
+            &HeaderValue::from_static("application/json")
+        );
+        let body = hyper::body::to_bytes(got.into_body()).await.unwrap();
+        assert_eq!(
+            body.as_ref(),
+            br#"{"tables":{"platanos":{"rows":1,"sequence_numbers":[42]}}}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NamespaceId::new(42));
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        // The changes are returned without an Accept header.
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&dry_run=true")
+            .method("POST")
+            .body(Body::from("platanos,tag1=A val=42i 123456"))
+            .unwrap();
+        let got = delegate
+            .route(request)
+            .await
+            .expect("dry run should succeed");
+        assert_eq!(got.status(), StatusCode::OK);
+        assert_eq!(
+            got.headers().get(CONTENT_TYPE).unwrap(),
+            &HeaderValue::from_static("application/json")
+        );
+        let body = hyper::body::to_bytes(got.into_body()).await.unwrap();
+        assert_eq!(
+            body.as_ref(),
+            br#"{"new_namespace":false,"tables":{"platanos":{"rows":1,"new_table":true,"new_columns":{"tag1":"tag","time":"time","val":"i64"}}}}"#
+        );
+
+        // Nothing was written.
+        assert!(dml_handler.calls().is_empty());
+        assert_metric_hit(&metrics, "http_write_lines", Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_new_namespace() {
+        // The namespace does not exist.
+        let mock_namespace_resolver = MockNamespaceResolver::default();
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&dry_run=true")
+            .method("POST")
+            .body(Body::from("platanos val=42i 123456"))
+            .unwrap();
+        let got = delegate
+            .route(request)
+            .await
+            .expect("dry run should succeed");
+        assert_eq!(got.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(got.into_body()).await.unwrap();
+        assert_eq!(
+            body.as_ref(),
+            br#"{"new_namespace":true,"tables":{"platanos":{"rows":1,"new_table":true,"new_columns":{"time":"time","val":"i64"}}}}"#
+        );
+
+        // Nothing was written.
+        assert!(dml_handler.calls().is_empty());
+    }
+
     /// Assert the router delegates request parsing to the
     /// [`WriteRequestUnifier`] implementation.
     ///
@@ -1220,12 +1656,16 @@ mod tests {
                 Ok(WriteParams {
                     namespace: NamespaceName::new(NAMESPACE_NAME).unwrap(),
                     precision: Precision::default(),
+                    tag_filters: vec![],
                 })
             }),
         ));
 
-        let dml_handler =
-            Arc::new(MockDmlHandler::default().with_write_return([Ok(()), Ok(()), Ok(())]));
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([
+            Ok(WriteSummary::default()),
+            Ok(WriteSummary::default()),
+            Ok(WriteSummary::default()),
+        ]));
         let metrics = Arc::new(metric::Registry::default());
         let delegate = HttpDelegate::new(
             MAX_BYTES,
@@ -1451,6 +1891,11 @@ mod tests {
             "access denied",
         ),
 
+        (
+            TagFilterViolation("bananas".to_string()),
+            "access denied: rows written to table bananas violate a tag filter policy",
+        ),
+
         (
             DmlHandler(DmlError::Schema(SchemaError::ServiceLimit(Box::new(CachedServiceProtectionLimit::Column {
                 table_name: "bananas".to_string(),
@@ -0,0 +1,258 @@
+//! Validation of dry-run writes (`?dry_run=true`), which are checked like a
+//! write but never applied.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use data_types::{ColumnType, NamespaceId, NamespaceName, NamespaceSchema};
+use hashbrown::HashMap;
+use iox_catalog::{interface::Error as CatalogError, TableScopedError};
+use mutable_batch::MutableBatch;
+use serde::Serialize;
+
+use crate::dml_handlers::{
+    validate_schema_limits, DmlError, DmlHandler, RetentionValidator, SchemaError,
+};
+
+/// The changes a write would make to the schema of its namespace, returned
+/// to the client of a dry-run write instead of applying the write.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct DryRunSummary {
+    /// True if the namespace does not exist yet and would be created by the
+    /// write.
+    new_namespace: bool,
+
+    /// The tables written to, keyed by table name.
+    tables: BTreeMap<String, TableDryRunSummary>,
+}
+
+/// The changes a write would make to a single table.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct TableDryRunSummary {
+    /// The number of rows that would be written.
+    rows: usize,
+
+    /// True if the table does not exist yet and would be created.
+    new_table: bool,
+
+    /// The columns that would be created, with their types.
+    new_columns: BTreeMap<String, String>,
+}
+
+/// Validate `batches` against the schema of their namespace like a write,
+/// returning the tables and columns the write would create.
+///
+/// Fails with the error the write would fail with if it exceeds the table or
+/// column limits of the namespace, conflicts with the type of an existing
+/// column or is outside of the retention period. Nothing is written to the
+/// catalog or the ingesters.
+///
+/// If the namespace does not exist (`namespace_schema` is `None`), the write
+/// is validated against an empty namespace with the default limits and no
+/// retention period, and the summary reports that the namespace would be
+/// created.
+pub(crate) async fn dry_run(
+    namespace: &NamespaceName<'static>,
+    namespace_schema: Option<Arc<NamespaceSchema>>,
+    batches: HashMap<String, MutableBatch>,
+) -> Result<DryRunSummary, DmlError> {
+    let mut summary = DryRunSummary {
+        new_namespace: namespace_schema.is_none(),
+        ..Default::default()
+    };
+    let namespace_schema = namespace_schema.unwrap_or_else(|| {
+        Arc::new(NamespaceSchema {
+            id: NamespaceId::new(0),
+            tables: Default::default(),
+            max_tables: Default::default(),
+            max_columns_per_table: Default::default(),
+            retention_period_ns: None,
+            partition_template: Default::default(),
+        })
+    });
+
+    validate_schema_limits(&batches, &namespace_schema)
+        .map_err(|e| SchemaError::ServiceLimit(Box::new(e)))?;
+
+    for (table_name, batch) in &batches {
+        let table = namespace_schema.tables.get(table_name);
+
+        let mut new_columns = BTreeMap::new();
+        for (name, col) in batch.columns() {
+            match table.and_then(|t| t.columns.get(name)) {
+                Some(existing) if existing.matches_type(col.influx_type()) => {}
+                Some(existing) => {
+                    let err = CatalogError::ColumnTypeMismatch {
+                        name: name.clone(),
+                        existing: existing.column_type,
+                        new: ColumnType::from(col.influx_type()),
+                    };
+                    return Err(
+                        SchemaError::Conflict(TableScopedError::new(table_name, err)).into(),
+                    );
+                }
+                None => {
+                    new_columns.insert(
+                        name.clone(),
+                        ColumnType::from(col.influx_type()).to_string(),
+                    );
+                }
+            }
+        }
+
+        summary.tables.insert(
+            table_name.clone(),
+            TableDryRunSummary {
+                rows: batch.rows(),
+                new_table: table.is_none(),
+                new_columns,
+            },
+        );
+    }
+
+    RetentionValidator::new()
+        .write(namespace, namespace_schema, batches, None)
+        .await?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::{
+        Column, ColumnId, ColumnsByName, MaxColumnsPerTable, MaxTables, NamespaceId, TableId,
+        TableSchema,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let batches = lp_to_writes(
+            "bananas,tag1=A val=42i 1\n\
+             platanos,tag1=B val=4.2,new=true 2\n\
+             platanos,tag1=C val=1.0 3",
+        );
+
+        let summary = dry_run(&namespace(), Some(Arc::new(schema(10))), batches)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "new_namespace": false,
+                "tables": {
+                    "bananas": {
+                        "rows": 1,
+                        "new_table": false,
+                        "new_columns": {}
+                    },
+                    "platanos": {
+                        "rows": 2,
+                        "new_table": true,
+                        "new_columns": {
+                            "new": "bool",
+                            "tag1": "tag",
+                            "time": "time",
+                            "val": "f64"
+                        }
+                    }
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_new_namespace() {
+        let batches = lp_to_writes("bananas,tag1=A val=4.2 1");
+
+        let summary = dry_run(&namespace(), None, batches).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "new_namespace": true,
+                "tables": {
+                    "bananas": {
+                        "rows": 1,
+                        "new_table": true,
+                        "new_columns": {
+                            "tag1": "tag",
+                            "time": "time",
+                            "val": "f64"
+                        }
+                    }
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_conflict() {
+        let batches = lp_to_writes("bananas,tag1=A val=4.2 1");
+
+        let err = dry_run(&namespace(), Some(Arc::new(schema(10))), batches)
+            .await
+            .unwrap_err();
+        assert_matches!(err, DmlError::Schema(SchemaError::Conflict(e)) => {
+            assert_eq!(e.table(), "bananas");
+            assert_matches!(e.err(), CatalogError::ColumnTypeMismatch { name, .. } => {
+                assert_eq!(name, "val");
+            });
+        });
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_service_limit() {
+        let batches = lp_to_writes("bananas,tag1=A,tag2=B val=42i 1");
+
+        let err = dry_run(&namespace(), Some(Arc::new(schema(3))), batches)
+            .await
+            .unwrap_err();
+        assert_matches!(err, DmlError::Schema(SchemaError::ServiceLimit(_)));
+    }
+
+    fn namespace() -> NamespaceName<'static> {
+        "bananas".try_into().unwrap()
+    }
+
+    /// A namespace with the table `bananas`, which has the columns `tag1`,
+    /// `val` (an integer) and `time`.
+    fn schema(max_columns_per_table: i32) -> NamespaceSchema {
+        let table_id = TableId::new(1);
+        let mut table = TableSchema {
+            id: table_id,
+            partition_template: Default::default(),
+            columns: ColumnsByName::new([]),
+        };
+        for (id, (name, column_type)) in [
+            ("tag1", ColumnType::Tag),
+            ("val", ColumnType::I64),
+            ("time", ColumnType::Time),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            table.add_column(Column {
+                id: ColumnId::new(id as _),
+                table_id,
+                name: name.to_string(),
+                column_type,
+            });
+        }
+
+        NamespaceSchema {
+            id: NamespaceId::new(42),
+            tables: [("bananas".to_string(), table)].into_iter().collect(),
+            max_tables: MaxTables::new(10),
+            max_columns_per_table: MaxColumnsPerTable::new(max_columns_per_table),
+            retention_period_ns: None,
+            partition_template: Default::default(),
+        }
+    }
+
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+}
@@ -0,0 +1,200 @@
+//! Write metrics broken down by namespace and table.
+
+use hashbrown::HashMap;
+use metric::{Attributes, Metric, U64Counter};
+use parking_lot::Mutex;
+
+/// The maximum number of distinct (namespace, table) pairs metrics are
+/// recorded for.
+pub(crate) const MAX_TRACKED_TABLES: usize = 1_000;
+
+/// The namespace and table label values used for writes once
+/// [`MAX_TRACKED_TABLES`] pairs are tracked.
+pub(crate) const OVERFLOW_LABEL: &str = "_other";
+
+/// The number of rows and the estimated size of the data written to a single
+/// table by a write request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TableWrite {
+    pub(crate) rows: usize,
+    pub(crate) bytes: usize,
+}
+
+#[derive(Debug)]
+struct TableRecorders {
+    rows: U64Counter,
+    bytes: U64Counter,
+    errors: U64Counter,
+}
+
+/// Counts the rows, bytes and rejected writes of each table.
+///
+/// Tables are labelled by namespace and table name, up to a configured
+/// number of distinct tables. Writes to any further tables are accounted to
+/// a single [`OVERFLOW_LABEL`] series so a client writing to an unbounded
+/// number of tables cannot blow up the metric cardinality.
+#[derive(Debug)]
+pub(crate) struct TableMetrics {
+    rows: Metric<U64Counter>,
+    bytes: Metric<U64Counter>,
+    errors: Metric<U64Counter>,
+
+    max_tables: usize,
+
+    /// Map of namespace name -> table name -> recorders, and the total number
+    /// of tables in it.
+    tables: Mutex<(HashMap<String, HashMap<String, TableRecorders>>, usize)>,
+}
+
+impl TableMetrics {
+    pub(crate) fn new(metrics: &metric::Registry, max_tables: usize) -> Self {
+        let rows = metrics.register_metric::<U64Counter>(
+            "http_write_table_rows",
+            "cumulative number of rows successfully routed per table",
+        );
+        let bytes = metrics.register_metric::<U64Counter>(
+            "http_write_table_bytes",
+            "cumulative estimated in-memory size of the data successfully routed per table",
+        );
+        let errors = metrics.register_metric::<U64Counter>(
+            "http_write_table_errors",
+            "number of rejected write requests containing data for the table",
+        );
+
+        Self {
+            rows,
+            bytes,
+            errors,
+            max_tables,
+            tables: Default::default(),
+        }
+    }
+
+    /// Record the outcome of a write request containing `writes` for the
+    /// tables of `namespace`.
+    pub(crate) fn record<'a>(
+        &self,
+        namespace: &str,
+        writes: impl IntoIterator<Item = (&'a str, TableWrite)>,
+        success: bool,
+    ) {
+        let mut guard = self.tables.lock();
+        let (tables, count) = &mut *guard;
+
+        for (table, write) in writes {
+            let known = tables
+                .get(namespace)
+                .map(|t| t.contains_key(table))
+                .unwrap_or_default();
+
+            let (namespace, table) = if known || *count < self.max_tables {
+                (namespace, table)
+            } else {
+                (OVERFLOW_LABEL, OVERFLOW_LABEL)
+            };
+
+            let recorders = tables
+                .entry_ref(namespace)
+                .or_default()
+                .entry_ref(table)
+                .or_insert_with(|| {
+                    *count += 1;
+                    let attributes = Attributes::from([
+                        ("namespace", namespace.to_string().into()),
+                        ("table", table.to_string().into()),
+                    ]);
+                    TableRecorders {
+                        rows: self.rows.recorder(attributes.clone()),
+                        bytes: self.bytes.recorder(attributes.clone()),
+                        errors: self.errors.recorder(attributes),
+                    }
+                });
+
+            if success {
+                recorders.rows.inc(write.rows as _);
+                recorders.bytes.inc(write.bytes as _);
+            } else {
+                recorders.errors.inc(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(
+        metrics: &metric::Registry,
+        name: &'static str,
+        namespace: &'static str,
+        table: &'static str,
+    ) -> Option<u64> {
+        metrics
+            .get_instrument::<Metric<U64Counter>>(name)
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[
+                ("namespace", namespace),
+                ("table", table),
+            ]))
+            .map(|c| c.fetch())
+    }
+
+    #[test]
+    fn test_table_metrics() {
+        let metrics = metric::Registry::default();
+        let table_metrics = TableMetrics::new(&metrics, 2);
+
+        let write = |rows, bytes| TableWrite { rows, bytes };
+
+        table_metrics.record("ns", [("cpu", write(2, 100)), ("mem", write(1, 10))], true);
+        table_metrics.record("ns", [("cpu", write(3, 50))], true);
+        table_metrics.record("ns", [("cpu", write(3, 50))], false);
+
+        assert_eq!(
+            counter(&metrics, "http_write_table_rows", "ns", "cpu"),
+            Some(5)
+        );
+        assert_eq!(
+            counter(&metrics, "http_write_table_bytes", "ns", "cpu"),
+            Some(150)
+        );
+        assert_eq!(
+            counter(&metrics, "http_write_table_errors", "ns", "cpu"),
+            Some(1)
+        );
+        assert_eq!(
+            counter(&metrics, "http_write_table_rows", "ns", "mem"),
+            Some(1)
+        );
+
+        // Further tables are accounted to the overflow series
+        table_metrics.record("ns", [("disk", write(4, 40))], true);
+        table_metrics.record("other_ns", [("cpu", write(1, 10))], true);
+
+        assert_eq!(
+            counter(&metrics, "http_write_table_rows", "ns", "disk"),
+            None
+        );
+        assert_eq!(
+            counter(&metrics, "http_write_table_rows", "other_ns", "cpu"),
+            None
+        );
+        assert_eq!(
+            counter(
+                &metrics,
+                "http_write_table_rows",
+                OVERFLOW_LABEL,
+                OVERFLOW_LABEL
+            ),
+            Some(5)
+        );
+
+        // Tables tracked before the limit was reached still are
+        table_metrics.record("ns", [("mem", write(1, 10))], true);
+        assert_eq!(
+            counter(&metrics, "http_write_table_rows", "ns", "mem"),
+            Some(2)
+        );
+    }
+}
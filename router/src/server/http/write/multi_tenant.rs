@@ -1,13 +1,17 @@
-//! Parsing of HTTP requests that conform to the [V2 Write API] only.
+//! Parsing of HTTP requests that conform to the [V2 Write API], and
+//! optionally the [V1 Write API].
 //!
 //! [V2 Write API]:
 //!     https://docs.influxdata.com/influxdb/v2.6/api/#operation/PostWrite
+//! [V1 Write API]:
+//!     https://docs.influxdata.com/influxdb/v1.8/tools/api/#write-http-endpoint
 
 use async_trait::async_trait;
 use data_types::{NamespaceName, OrgBucketMappingError};
 use hyper::{Body, Request};
 
 use super::{
+    v1::{RetentionPolicy, V1WriteParseError, WriteParamsV1, V1_NAMESPACE_RP_SEPARATOR},
     v2::{V2WriteParseError, WriteParamsV2},
     WriteParams, WriteRequestUnifier,
 };
@@ -20,6 +24,10 @@ pub enum MultiTenantExtractError {
     #[error(transparent)]
     InvalidOrgAndBucket(#[from] OrgBucketMappingError),
 
+    /// A [`WriteParamsV1`] failed to be parsed from the HTTP request.
+    #[error(transparent)]
+    ParseV1Request(#[from] V1WriteParseError),
+
     /// A [`WriteParamsV2`] failed to be parsed from the HTTP request.
     #[error(transparent)]
     ParseV2Request(#[from] V2WriteParseError),
@@ -35,6 +43,9 @@ impl From<&MultiTenantExtractError> for hyper::StatusCode {
         // "catch all" code.
         match value {
             MultiTenantExtractError::InvalidOrgAndBucket(_) => Self::BAD_REQUEST,
+            MultiTenantExtractError::ParseV1Request(
+                V1WriteParseError::NoQueryParams | V1WriteParseError::DecodeFail(_),
+            ) => Self::BAD_REQUEST,
             MultiTenantExtractError::ParseV2Request(
                 V2WriteParseError::NoQueryParams | V2WriteParseError::DecodeFail(_),
             ) => Self::BAD_REQUEST,
@@ -44,18 +55,37 @@ impl From<&MultiTenantExtractError> for hyper::StatusCode {
 
 /// Request parsing for cloud2 / multi-tenant deployments.
 ///
-/// This handler respects the [V2 Write API] without modification, and rejects
-/// any V1 write requests.
+/// This handler respects the [V2 Write API] without modification, and by
+/// default rejects any V1 write requests.
+///
+/// If an org is configured for V1 writes with [`Self::with_v1_org()`], V1
+/// write requests are accepted and mapped to the bucket of that org named
+/// after the `db` and `rp` parameters, following the InfluxDB 2.x DBRP
+/// convention: `db` for the default (or `autogen`) retention policy, or
+/// `db/rp` otherwise.
 ///
 /// [V2 Write API]:
 ///     https://docs.influxdata.com/influxdb/v2.6/api/#operation/PostWrite
 #[derive(Debug, Default)]
-pub struct MultiTenantRequestUnifier;
+pub struct MultiTenantRequestUnifier {
+    v1_org: Option<String>,
+}
+
+impl MultiTenantRequestUnifier {
+    /// Accept V1 write requests, writing to the buckets of `org`.
+    pub fn with_v1_org(mut self, org: impl Into<String>) -> Self {
+        self.v1_org = Some(org.into());
+        self
+    }
+}
 
 #[async_trait]
 impl WriteRequestUnifier for MultiTenantRequestUnifier {
-    async fn parse_v1(&self, _req: &Request<Body>) -> Result<WriteParams, Error> {
-        Err(Error::NoHandler)
+    async fn parse_v1(&self, req: &Request<Body>) -> Result<WriteParams, Error> {
+        match &self.v1_org {
+            Some(org) => Ok(parse_v1(req, org)?),
+            None => Err(Error::NoHandler),
+        }
     }
 
     async fn parse_v2(&self, req: &Request<Body>) -> Result<WriteParams, Error> {
@@ -63,6 +93,30 @@ impl WriteRequestUnifier for MultiTenantRequestUnifier {
     }
 }
 
+// Parse a V1 write request for multi tenant mode, writing to the bucket of
+// `org` mapped from the db and rp.
+fn parse_v1(req: &Request<Body>, org: &str) -> Result<WriteParams, MultiTenantExtractError> {
+    let write_params = WriteParamsV1::try_from(req)?;
+
+    let bucket = match write_params.rp {
+        RetentionPolicy::Unspecified | RetentionPolicy::Autogen => write_params.db,
+        RetentionPolicy::Named(rp) => {
+            format!(
+                "{db}{sep}{rp}",
+                db = write_params.db,
+                sep = V1_NAMESPACE_RP_SEPARATOR
+            )
+        }
+    };
+    let namespace = NamespaceName::from_org_and_bucket(org, bucket)?;
+
+    Ok(WriteParams {
+        namespace,
+        precision: write_params.precision,
+        tag_filters: vec![],
+    })
+}
+
 // Parse a V2 write request for multi tenant mode.
 fn parse_v2(req: &Request<Body>) -> Result<WriteParams, MultiTenantExtractError> {
     let write_params = WriteParamsV2::try_from(req)?;
@@ -72,6 +126,7 @@ fn parse_v2(req: &Request<Body>) -> Result<WriteParams, MultiTenantExtractError>
     Ok(WriteParams {
         namespace,
         precision: write_params.precision,
+        tag_filters: vec![],
     })
 }
 
@@ -85,12 +140,108 @@ mod tests {
 
     #[tokio::test]
     async fn test_parse_v1_always_errors() {
-        let unifier = MultiTenantRequestUnifier;
+        let unifier = MultiTenantRequestUnifier::default();
 
         let got = unifier.parse_v1(&Request::default()).await;
         assert_matches!(got, Err(Error::NoHandler));
     }
 
+    macro_rules! test_parse_v1 {
+        (
+            $name:ident,
+            query_string = $query_string:expr,  // A query string including the ?
+            want = $($want:tt)+                 // A pattern match for assert_matches!
+        ) => {
+            paste::paste! {
+                #[tokio::test]
+                async fn [<test_parse_v1_ $name>]() {
+                    let unifier = MultiTenantRequestUnifier::default().with_v1_org("bananas");
+
+                    let query = $query_string;
+                    let request = Request::builder()
+                        .uri(format!("https://itsallbroken.com/write{query}"))
+                        .method("POST")
+                        .body(Body::from(""))
+                        .unwrap();
+
+                    let got = unifier.parse_v1(&request).await;
+                    assert_matches!(got, $($want)+);
+                }
+            }
+        };
+    }
+
+    test_parse_v1!(
+        no_query_string,
+        query_string = "",
+        want = Err(Error::MultiTenantError(
+            MultiTenantExtractError::ParseV1Request(V1WriteParseError::NoQueryParams)
+        ))
+    );
+
+    test_parse_v1!(
+        no_db,
+        query_string = "?rp=autogen",
+        want = Err(Error::MultiTenantError(
+            MultiTenantExtractError::ParseV1Request(V1WriteParseError::DecodeFail(_))
+        ))
+    );
+
+    test_parse_v1!(
+        db_only,
+        query_string = "?db=telegraf",
+        want = Ok(WriteParams {
+            namespace,
+            precision,
+            ..
+        }) => {
+            assert_eq!(namespace.as_str(), "bananas_telegraf");
+            assert_matches!(precision, Precision::Nanoseconds);
+        }
+    );
+
+    test_parse_v1!(
+        rp_autogen,
+        query_string = "?db=telegraf&rp=autogen",
+        want = Ok(WriteParams { namespace, .. }) => {
+            assert_eq!(namespace.as_str(), "bananas_telegraf");
+        }
+    );
+
+    test_parse_v1!(
+        rp_named,
+        query_string = "?db=telegraf&rp=weekly",
+        want = Ok(WriteParams { namespace, .. }) => {
+            assert_eq!(namespace.as_str(), "bananas_telegraf/weekly");
+        }
+    );
+
+    test_parse_v1!(
+        v1_precision,
+        query_string = "?db=telegraf&precision=u",
+        want = Ok(WriteParams { precision, .. }) => {
+            assert_matches!(precision, Precision::Microseconds);
+        }
+    );
+
+    test_parse_v1!(
+        v1_precision_hours,
+        query_string = "?db=telegraf&precision=h",
+        want = Ok(WriteParams { precision, .. }) => {
+            assert_eq!(precision.timestamp_base(), 3_600_000_000_000);
+        }
+    );
+
+    test_parse_v1!(
+        empty_db,
+        query_string = "?db=",
+        want = Err(Error::MultiTenantError(
+            MultiTenantExtractError::InvalidOrgAndBucket(
+                OrgBucketMappingError::NoOrgBucketSpecified
+            )
+        ))
+    );
+
     macro_rules! test_parse_v2 {
         (
             $name:ident,
@@ -100,7 +251,7 @@ mod tests {
             paste::paste! {
                 #[tokio::test]
                 async fn [<test_parse_v2_ $name>]() {
-                    let unifier = MultiTenantRequestUnifier;
+                    let unifier = MultiTenantRequestUnifier::default();
 
                     let query = $query_string;
                     let request = Request::builder()
@@ -225,7 +376,8 @@ mod tests {
         query_string = "?org=banana&bucket=cool&precision=ms",
         want = Ok(WriteParams {
             namespace,
-            precision
+            precision,
+            ..
         }) => {
             assert_eq!(namespace.as_str(), "banana_cool");
             assert_matches!(precision, Precision::Milliseconds);
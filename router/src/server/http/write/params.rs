@@ -1,21 +1,30 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use authz::TagFilterPolicy;
 use data_types::NamespaceName;
 use hyper::{Body, Request};
 use serde::Deserialize;
 
 use crate::server::http::Error;
 
+/// The precision of the timestamps of a write.
+///
+/// The V1 Write API abbreviations (`n`, `u`, `m` and `h`) are accepted as
+/// well.
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) enum Precision {
+    #[serde(rename = "h")]
+    Hours,
+    #[serde(rename = "m")]
+    Minutes,
     #[serde(rename = "s")]
     Seconds,
     #[serde(rename = "ms")]
     Milliseconds,
-    #[serde(rename = "us")]
+    #[serde(rename = "us", alias = "u")]
     Microseconds,
-    #[serde(rename = "ns")]
+    #[serde(rename = "ns", alias = "n")]
     Nanoseconds,
 }
 
@@ -29,6 +38,8 @@ impl Precision {
     /// Returns the multiplier to convert to nanosecond timestamps
     pub(crate) fn timestamp_base(&self) -> i64 {
         match self {
+            Precision::Hours => 3_600_000_000_000,
+            Precision::Minutes => 60_000_000_000,
             Precision::Seconds => 1_000_000_000,
             Precision::Milliseconds => 1_000_000,
             Precision::Microseconds => 1_000,
@@ -42,6 +53,9 @@ impl Precision {
 pub struct WriteParams {
     pub(crate) namespace: NamespaceName<'static>,
     pub(crate) precision: Precision,
+    /// Row-level restrictions of the request's token, every written row
+    /// must satisfy them.
+    pub(crate) tag_filters: Vec<TagFilterPolicy>,
 }
 
 /// A [`WriteRequestUnifier`] abstraction returns a unified [`WriteParams`]
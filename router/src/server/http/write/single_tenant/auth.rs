@@ -3,18 +3,29 @@
 use std::sync::Arc;
 
 use authz::{
-    self, extract_token, http::AuthorizationHeaderExtension, Action, Authorizer, Error, Permission,
-    Resource,
+    self, extract_token, http::AuthorizationHeaderExtension, tag_filter_policies, Action,
+    Authorizer, Error, Permission, Resource, TagFilterPolicy,
 };
 use data_types::NamespaceName;
 use hyper::{Body, Request};
+use observability_deps::tracing::*;
 
+use crate::tenant_resolver::TenantResolver;
+
+/// Authorize a write to `namespace`.
+///
+/// The token must grant write access to either the namespace itself or, if
+/// `tenants` is provided and the namespace has one, the tenant owning it.
+///
+/// Returns the tag filter policies of the token for `namespace`, which the
+/// written rows must satisfy.
 pub(crate) async fn authorize(
     authz: &Arc<dyn Authorizer>,
+    tenants: Option<&TenantResolver>,
     req: &Request<Body>,
     namespace: &NamespaceName<'_>,
     query_param_token: Option<String>,
-) -> Result<(), Error> {
+) -> Result<Vec<TagFilterPolicy>, Error> {
     let token = extract_token(
         req.extensions()
             .get::<AuthorizationHeaderExtension>()
@@ -22,13 +33,27 @@ pub(crate) async fn authorize(
     )
     .or_else(|| query_param_token.map(|t| t.into_bytes()));
 
-    let perms = [Permission::ResourceAction(
+    let mut perms = vec![Permission::ResourceAction(
         Resource::Database(namespace.to_string()),
         Action::Write,
     )];
 
-    authz.permissions(token, &perms).await?;
-    Ok(())
+    if let Some(tenants) = tenants {
+        match tenants.get(namespace).await {
+            Ok(Some(tenant)) => perms.push(Permission::ResourceAction(
+                Resource::Tenant(tenant.name.clone()),
+                Action::Write,
+            )),
+            Ok(None) => {}
+            Err(e) => {
+                // Fall back to the (stricter) namespace permission alone.
+                warn!(error=%e, %namespace, "failed to resolve namespace tenant for authorization");
+            }
+        }
+    }
+
+    let granted = authz.permissions(token, &perms).await?;
+    Ok(tag_filter_policies(&granted, namespace.as_str()))
 }
 
 #[cfg(test)]
@@ -40,6 +65,7 @@ pub mod mock {
     pub const MOCK_AUTH_VALID_TOKEN: &str = "GOOD";
     pub const MOCK_AUTH_INVALID_TOKEN: &str = "UGLY";
     pub const MOCK_AUTH_NO_PERMS_TOKEN: &str = "BAD";
+    pub const MOCK_AUTH_TAG_FILTER_TOKEN: &str = "ACME";
 
     #[derive(Debug, Default)]
     pub struct MockAuthorizer {}
@@ -54,6 +80,21 @@ pub mod mock {
             match token {
                 Some(token) => match (&token as &dyn AsRef<[u8]>).as_ref() {
                     b"GOOD" => Ok(perms.to_vec()),
+                    // restricted to rows with "tenant_id=acme"
+                    b"ACME" => Ok(perms
+                        .iter()
+                        .cloned()
+                        .chain(perms.iter().filter_map(|p| match p {
+                            Permission::ResourceAction(Resource::Database(database), _) => {
+                                Some(Permission::TagFilter(TagFilterPolicy {
+                                    database: database.clone(),
+                                    tag: "tenant_id".to_string(),
+                                    values: vec!["acme".to_string()],
+                                }))
+                            }
+                            _ => None,
+                        }))
+                        .collect()),
                     b"BAD" => Err(authz::Error::Forbidden),
                     b"UGLY" => Err(authz::Error::verification("test", "test error")),
                     _ => panic!("unexpected token"),
@@ -75,7 +116,10 @@ mod tests {
 
     use super::{mock::*, *};
     use crate::{
-        dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall},
+        dml_handlers::{
+            mock::{MockDmlHandler, MockDmlHandlerCall},
+            WriteSummary,
+        },
         namespace_resolver::mock::MockNamespaceResolver,
         server::http::{
             self,
@@ -92,7 +136,8 @@ mod tests {
         let mock_namespace_resolver =
             MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NamespaceId::new(42));
 
-        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let dml_handler =
+            Arc::new(MockDmlHandler::default().with_write_return([Ok(WriteSummary::default())]));
         let metrics = Arc::new(metric::Registry::default());
         let authz = Arc::new(MockAuthorizer::default());
         let delegate = HttpDelegate::new(
@@ -176,7 +221,8 @@ mod tests {
         static NAMESPACE_NAME: &str = "test";
         let mock_namespace_resolver =
             MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NamespaceId::new(42));
-        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let dml_handler =
+            Arc::new(MockDmlHandler::default().with_write_return([Ok(WriteSummary::default())]));
 
         let metrics = Arc::new(metric::Registry::default());
         let decorator = Arc::new(AuthorizerInstrumentation::new(
@@ -265,7 +311,7 @@ mod tests {
                         .body(Body::from(""))
                         .unwrap();
 
-                    let got = authorize(&authz, &request, &namespace, $query_token).await;
+                    let got = authorize(&authz, None, &request, &namespace, $query_token).await;
                     assert_matches!(got, $($want)+);
                 }
             }
@@ -280,7 +326,23 @@ mod tests {
         token_header_ok,
         header_value = format!("Token {MOCK_AUTH_VALID_TOKEN}").as_str(),
         query_param_token = Some("ignore".to_string()),
-        want = Ok(())
+        want = Ok(_)
+    );
+
+    test_authorize!(
+        token_header_tag_filter,
+        header_value = format!("Token {MOCK_AUTH_TAG_FILTER_TOKEN}").as_str(),
+        query_param_token = None,
+        want = Ok(policies) => {
+            assert_eq!(
+                policies,
+                vec![TagFilterPolicy {
+                    database: "test".to_string(),
+                    tag: "tenant_id".to_string(),
+                    values: vec!["acme".to_string()],
+                }]
+            );
+        }
     );
 
     test_authorize!(
@@ -315,14 +377,14 @@ mod tests {
         token_header_missing_whitespace_match_next,
         header_value = "Token",
         query_param_token = Some(MOCK_AUTH_VALID_TOKEN.to_string()),
-        want = Ok(())
+        want = Ok(_)
     );
 
     test_authorize!(
         bearer_header_ok,
         header_value = format!("Bearer {MOCK_AUTH_VALID_TOKEN}").as_str(),
         query_param_token = Some("ignore".to_string()),
-        want = Ok(())
+        want = Ok(_)
     );
 
     test_authorize!(
@@ -336,7 +398,7 @@ mod tests {
         basic_header_ok,
         header_value = encode_basic_header(format!("ignore:{MOCK_AUTH_VALID_TOKEN}")).as_str(),
         query_param_token = Some("ignore".to_string()),
-        want = Ok(())
+        want = Ok(_)
     );
 
     test_authorize!(
@@ -371,7 +433,7 @@ mod tests {
         query_param_token_ok,
         header_value = "",
         query_param_token = Some(MOCK_AUTH_VALID_TOKEN.to_string()),
-        want = Ok(())
+        want = Ok(_)
     );
 
     test_authorize!(
@@ -394,4 +456,81 @@ mod tests {
         query_param_token = None,
         want = Err(authz::Error::NoToken)
     );
+
+    /// An authorizer granting only write access to the tenant "platanos".
+    #[derive(Debug)]
+    struct TenantAuthorizer;
+
+    #[async_trait::async_trait]
+    impl Authorizer for TenantAuthorizer {
+        async fn permissions(
+            &self,
+            _token: Option<Vec<u8>>,
+            perms: &[Permission],
+        ) -> Result<Vec<Permission>, authz::Error> {
+            let want =
+                Permission::ResourceAction(Resource::Tenant("platanos".to_string()), Action::Write);
+            match perms.contains(&want) {
+                true => Ok(vec![want]),
+                false => Err(authz::Error::Forbidden),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authorize_tenant_token() {
+        use data_types::TenantQuotas;
+        use iox_catalog::{interface::Catalog, mem::MemCatalog};
+
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let namespace = NamespaceName::new("test").unwrap();
+        let other = NamespaceName::new("other").unwrap();
+        {
+            let mut repos = catalog.repositories().await;
+            let tenant = repos
+                .tenants()
+                .create("platanos", TenantQuotas::default())
+                .await
+                .unwrap();
+            repos
+                .namespaces()
+                .create(&namespace, None, None, None)
+                .await
+                .unwrap();
+            repos
+                .namespaces()
+                .update_tenant(&namespace, Some(tenant.id))
+                .await
+                .unwrap();
+            repos
+                .namespaces()
+                .create(&other, None, None, None)
+                .await
+                .unwrap();
+        }
+        let tenants = TenantResolver::new(catalog, std::time::Duration::from_secs(60));
+        let authz: Arc<dyn Authorizer> = Arc::new(TenantAuthorizer);
+
+        let request = Request::builder()
+            .uri("https://any.com/ignored")
+            .method("POST")
+            .extension(AuthorizationHeaderExtension::new(Some(
+                HeaderValue::from_str("Token tenant").unwrap(),
+            )))
+            .body(Body::from(""))
+            .unwrap();
+
+        // The tenant token grants access to the namespaces of the tenant...
+        let got = authorize(&authz, Some(&tenants), &request, &namespace, None).await;
+        assert_matches!(got, Ok(_));
+
+        // ...but not to namespaces of other (or no) tenants.
+        let got = authorize(&authz, Some(&tenants), &request, &other, None).await;
+        assert_matches!(got, Err(authz::Error::Forbidden));
+
+        // Nor when tenants are not resolved.
+        let got = authorize(&authz, None, &request, &namespace, None).await;
+        assert_matches!(got, Err(authz::Error::Forbidden));
+    }
 }
@@ -18,6 +18,8 @@ use data_types::{NamespaceName, NamespaceNameError};
 use hyper::{Body, Request};
 use thiserror::Error;
 
+use crate::tenant_resolver::TenantResolver;
+
 use super::{
     v1::{RetentionPolicy, V1WriteParseError, WriteParamsV1},
     v2::{V2WriteParseError, WriteParamsV2},
@@ -95,23 +97,34 @@ impl From<&SingleTenantExtractError> for hyper::StatusCode {
 #[derive(Debug)]
 pub struct SingleTenantRequestUnifier {
     authz: Arc<dyn Authorizer>,
+    tenants: Option<Arc<TenantResolver>>,
 }
 
 impl SingleTenantRequestUnifier {
     /// Creates a new SingleTenantRequestParser
     pub fn new(authz: Arc<dyn Authorizer>) -> Self {
-        Self { authz }
+        Self {
+            authz,
+            tenants: None,
+        }
+    }
+
+    /// Also accept tokens granting write access to the tenant owning the
+    /// namespace, as resolved by `tenants`.
+    pub fn with_tenant_resolver(mut self, tenants: Arc<TenantResolver>) -> Self {
+        self.tenants = Some(tenants);
+        self
     }
 }
 
 #[async_trait]
 impl WriteRequestUnifier for SingleTenantRequestUnifier {
     async fn parse_v1(&self, req: &Request<Body>) -> Result<WriteParams, Error> {
-        Ok(parse_v1(req, &self.authz).await?)
+        Ok(parse_v1(req, &self.authz, self.tenants.as_deref()).await?)
     }
 
     async fn parse_v2(&self, req: &Request<Body>) -> Result<WriteParams, Error> {
-        Ok(parse_v2(req, &self.authz).await?)
+        Ok(parse_v2(req, &self.authz, self.tenants.as_deref()).await?)
     }
 }
 
@@ -119,6 +132,7 @@ impl WriteRequestUnifier for SingleTenantRequestUnifier {
 async fn parse_v1(
     req: &Request<Body>,
     authz: &Arc<dyn Authorizer>,
+    tenants: Option<&TenantResolver>,
 ) -> Result<WriteParams, SingleTenantExtractError> {
     // Extract the write parameters.
     let write_params = WriteParamsV1::try_from(req)?;
@@ -134,13 +148,14 @@ async fn parse_v1(
             )
         }
     })?;
-    authorize(authz, req, &namespace, write_params.password)
+    let tag_filters = authorize(authz, tenants, req, &namespace, write_params.password)
         .await
         .map_err(SingleTenantExtractError::Authorizer)?;
 
     Ok(WriteParams {
         namespace,
         precision: write_params.precision,
+        tag_filters,
     })
 }
 
@@ -148,6 +163,7 @@ async fn parse_v1(
 async fn parse_v2(
     req: &Request<Body>,
     authz: &Arc<dyn Authorizer>,
+    tenants: Option<&TenantResolver>,
 ) -> Result<WriteParams, SingleTenantExtractError> {
     let write_params = WriteParamsV2::try_from(req)?;
 
@@ -161,13 +177,14 @@ async fn parse_v2(
         return Err(SingleTenantExtractError::NoBucketSpecified);
     }
     let namespace = NamespaceName::new(write_params.bucket)?;
-    authorize(authz, req, &namespace, None)
+    let tag_filters = authorize(authz, tenants, req, &namespace, None)
         .await
         .map_err(SingleTenantExtractError::Authorizer)?;
 
     Ok(WriteParams {
         namespace,
         precision: write_params.precision,
+        tag_filters,
     })
 }
 
@@ -543,7 +560,8 @@ mod tests {
         query_string = "?org=wat&bucket=bananas",
         want = Ok(WriteParams {
             namespace,
-            precision
+            precision,
+            ..
         }) => {
             assert_eq!(namespace.as_str(), "bananas");
             assert_matches!(precision, Precision::Nanoseconds);
@@ -596,7 +614,8 @@ mod tests {
         query_string = "?bucket=bananas&precision=ms",
         want = Ok(WriteParams {
             namespace,
-            precision
+            precision,
+            ..
         }) => {
             assert_eq!(namespace.as_str(), "bananas");
             assert_matches!(precision, Precision::Milliseconds);
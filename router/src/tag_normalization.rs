@@ -0,0 +1,264 @@
+//! Normalization of the tag values of writes, configured per namespace.
+//!
+//! Agents that write the same series inconsistently (`host=Server01` and
+//! `host=server01 `) create a new series for every variant. Normalizing tag
+//! values as line protocol is converted, before the series keys of the rows
+//! are formed, folds the variants into one series.
+//!
+//! The rules are read from a JSON file mapping namespace names to the rules
+//! of their tags:
+//!
+//! ```json
+//! {
+//!     "my_namespace": {
+//!         "host": { "trim": true, "lowercase": true },
+//!         "region": { "map": { "us-west-2": "us-west", "uswest": "us-west" } }
+//!     }
+//! }
+//! ```
+//!
+//! The rules of a tag apply to the tag in all tables of the namespace.
+//!
+//! Although keyed by namespace, the rules are configuration of the router
+//! rather than namespace properties stored in the catalog: all routers must
+//! load the same file, and a change only applies once they are restarted.
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use mutable_batch_lp::TagValueNormalizer;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Errors loading [`TagNormalizationRules`].
+#[derive(Debug, Error)]
+pub enum TagNormalizationError {
+    /// The rules file cannot be read.
+    #[error("cannot read tag normalization rules file {path}: {source}")]
+    Read {
+        /// The path of the file.
+        path: String,
+        /// The underlying error.
+        source: std::io::Error,
+    },
+
+    /// The rules file is not valid.
+    #[error("invalid tag normalization rules in {path}: {source}")]
+    Parse {
+        /// The path of the file.
+        path: String,
+        /// The underlying error.
+        source: serde_json::Error,
+    },
+
+    /// A tag value is mapped to an empty value.
+    #[error("tag {tag} of namespace {namespace} maps {value:?} to an empty value")]
+    EmptyMappedValue {
+        /// The namespace of the rule.
+        namespace: String,
+        /// The tag of the rule.
+        tag: String,
+        /// The mapped value.
+        value: String,
+    },
+}
+
+/// How the values of a single tag are normalized.
+///
+/// The value is first trimmed, then lowercased and finally looked up in
+/// `map`, so the keys of `map` are matched against the trimmed and
+/// lowercased value. Values normalized to an empty string are written
+/// unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TagRule {
+    /// Remove leading and trailing whitespace.
+    #[serde(default)]
+    pub trim: bool,
+
+    /// Convert the value to lowercase.
+    #[serde(default)]
+    pub lowercase: bool,
+
+    /// Replace values found in this lookup table with the mapped value.
+    #[serde(default)]
+    pub map: HashMap<String, String>,
+}
+
+impl TagRule {
+    /// Returns the normalized form of `value`, or [`None`] if it is
+    /// unchanged.
+    fn normalize(&self, value: &str) -> Option<String> {
+        let mut normalized = if self.trim { value.trim() } else { value }.to_string();
+        if self.lowercase {
+            normalized = normalized.to_lowercase();
+        }
+        if let Some(mapped) = self.map.get(&normalized) {
+            normalized = mapped.clone();
+        }
+
+        (!normalized.is_empty() && normalized != value).then_some(normalized)
+    }
+}
+
+/// The [`TagRule`] of each tag of a namespace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceTagRules {
+    tags: HashMap<String, TagRule>,
+}
+
+impl NamespaceTagRules {
+    /// Initialise the rules of a namespace from the [`TagRule`] of each tag.
+    pub fn new(tags: HashMap<String, TagRule>) -> Self {
+        Self { tags }
+    }
+}
+
+impl TagValueNormalizer for NamespaceTagRules {
+    fn normalize(&self, _table: &str, tag: &str, value: &str) -> Option<String> {
+        self.tags.get(tag)?.normalize(value)
+    }
+}
+
+/// The tag normalization rules of all namespaces.
+///
+/// Writes to namespaces without rules are not normalized.
+#[derive(Debug, Clone, Default)]
+pub struct TagNormalizationRules {
+    namespaces: HashMap<String, Arc<NamespaceTagRules>>,
+}
+
+impl TagNormalizationRules {
+    /// Initialise the rules from the [`NamespaceTagRules`] of each namespace.
+    pub fn new(namespaces: impl IntoIterator<Item = (String, NamespaceTagRules)>) -> Self {
+        Self {
+            namespaces: namespaces
+                .into_iter()
+                .map(|(name, rules)| (name, Arc::new(rules)))
+                .collect(),
+        }
+    }
+
+    /// Load the rules from the JSON file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TagNormalizationError> {
+        let path = path.as_ref();
+        let contents =
+            std::fs::read_to_string(path).map_err(|source| TagNormalizationError::Read {
+                path: path.display().to_string(),
+                source,
+            })?;
+        Self::from_json(&contents).map_err(|e| match e {
+            TagNormalizationError::Parse { source, .. } => TagNormalizationError::Parse {
+                path: path.display().to_string(),
+                source,
+            },
+            e => e,
+        })
+    }
+
+    fn from_json(contents: &str) -> Result<Self, TagNormalizationError> {
+        let namespaces: HashMap<String, HashMap<String, TagRule>> = serde_json::from_str(contents)
+            .map_err(|source| TagNormalizationError::Parse {
+                path: Default::default(),
+                source,
+            })?;
+
+        for (namespace, tags) in &namespaces {
+            for (tag, rule) in tags {
+                if let Some(value) = rule.map.iter().find(|(_, v)| v.is_empty()).map(|(k, _)| k) {
+                    return Err(TagNormalizationError::EmptyMappedValue {
+                        namespace: namespace.clone(),
+                        tag: tag.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(Self::new(namespaces.into_iter().map(
+            |(namespace, tags)| (namespace, NamespaceTagRules::new(tags)),
+        )))
+    }
+
+    /// Returns the rules of `namespace`, if it has any.
+    pub fn get(&self, namespace: &str) -> Option<Arc<NamespaceTagRules>> {
+        self.namespaces.get(namespace).map(Arc::clone)
+    }
+
+    /// Returns true if no namespace has rules.
+    pub fn is_empty(&self) -> bool {
+        self.namespaces.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_tag_rule() {
+        let rule = TagRule {
+            trim: true,
+            lowercase: true,
+            map: [("srv01".to_string(), "server01".to_string())]
+                .into_iter()
+                .collect(),
+        };
+
+        assert_eq!(rule.normalize(" Server01 "), Some("server01".to_string()));
+        assert_eq!(rule.normalize("SRV01"), Some("server01".to_string()));
+        assert_eq!(rule.normalize("server01"), None);
+        // normalizing to an empty value keeps the original value
+        assert_eq!(rule.normalize("  "), None);
+
+        let rule = TagRule::default();
+        assert_eq!(rule.normalize(" Server01 "), None);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let rules = TagNormalizationRules::from_json(
+            r#"{
+                "bananas": {
+                    "host": { "trim": true, "lowercase": true },
+                    "region": { "map": { "us-west-2": "us-west" } }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let bananas = rules.get("bananas").unwrap();
+        assert_eq!(
+            bananas.normalize("cpu", "host", "Server01"),
+            Some("server01".to_string())
+        );
+        assert_eq!(
+            bananas.normalize("mem", "region", "us-west-2"),
+            Some("us-west".to_string())
+        );
+        assert_eq!(bananas.normalize("cpu", "region", "US-WEST-2"), None);
+        assert_eq!(bananas.normalize("cpu", "platanos", "Server01"), None);
+
+        assert!(rules.get("platanos").is_none());
+    }
+
+    #[test]
+    fn test_from_json_invalid() {
+        assert_matches!(
+            TagNormalizationRules::from_json(r#"{ "bananas": { "host": { "upper": true } } }"#),
+            Err(TagNormalizationError::Parse { .. })
+        );
+
+        assert_matches!(
+            TagNormalizationRules::from_json(
+                r#"{ "bananas": { "host": { "map": { "srv01": "" } } } }"#
+            ),
+            Err(TagNormalizationError::EmptyMappedValue { namespace, tag, value }) => {
+                assert_eq!(namespace, "bananas");
+                assert_eq!(tag, "host");
+                assert_eq!(value, "srv01");
+            }
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Resolution of the [`Tenant`] owning a namespace.
+
+use std::{sync::Arc, time::Duration};
+
+use data_types::{NamespaceName, Tenant};
+use hashbrown::HashMap;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use iox_time::{SystemProvider, Time, TimeProvider};
+use parking_lot::Mutex;
+
+/// The default duration a resolved [`Tenant`] is cached for.
+pub const DEFAULT_TENANT_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct CachedTenant {
+    tenant: Option<Arc<Tenant>>,
+    fetched_at: Time,
+}
+
+/// Resolves the [`Tenant`] owning a namespace, caching the result for a
+/// configurable TTL.
+///
+/// Changes to the tenant of a namespace, or to the quotas of a tenant, become
+/// visible to this router at most one TTL after they are made in the catalog.
+///
+/// Namespaces that do not exist are not cached, so that the tenant of a
+/// namespace created after a write to it was rejected is picked up
+/// immediately.
+#[derive(Debug)]
+pub struct TenantResolver<P = SystemProvider> {
+    catalog: Arc<dyn Catalog>,
+    ttl: Duration,
+    time_provider: P,
+    cache: Mutex<HashMap<String, CachedTenant>>,
+}
+
+impl TenantResolver {
+    /// Initialise a [`TenantResolver`] reading tenants from `catalog` and
+    /// caching them for `ttl`.
+    pub fn new(catalog: Arc<dyn Catalog>, ttl: Duration) -> Self {
+        Self::new_with_time_provider(catalog, ttl, SystemProvider::default())
+    }
+}
+
+impl<P> TenantResolver<P>
+where
+    P: TimeProvider,
+{
+    /// Initialise a [`TenantResolver`] using `time_provider` to expire cached
+    /// tenants.
+    pub fn new_with_time_provider(
+        catalog: Arc<dyn Catalog>,
+        ttl: Duration,
+        time_provider: P,
+    ) -> Self {
+        Self {
+            catalog,
+            ttl,
+            time_provider,
+            cache: Default::default(),
+        }
+    }
+
+    /// The catalog tenants are read from.
+    pub fn catalog(&self) -> &Arc<dyn Catalog> {
+        &self.catalog
+    }
+
+    /// The time provider used to expire cached tenants.
+    pub fn time_provider(&self) -> &P {
+        &self.time_provider
+    }
+
+    /// Return the [`Tenant`] owning `namespace`, or `None` if the namespace
+    /// does not exist or has no tenant.
+    pub async fn get(
+        &self,
+        namespace: &NamespaceName<'_>,
+    ) -> Result<Option<Arc<Tenant>>, iox_catalog::interface::Error> {
+        let now = self.time_provider.now();
+
+        if let Some(cached) = self.cache.lock().get(namespace.as_str()) {
+            if now
+                .checked_duration_since(cached.fetched_at)
+                .unwrap_or_default()
+                < self.ttl
+            {
+                return Ok(cached.tenant.clone());
+            }
+        }
+
+        let mut repos = self.catalog.repositories().await;
+        let Some(ns) = repos
+            .namespaces()
+            .get_by_name(namespace, SoftDeletedRows::ExcludeDeleted)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let tenant = match ns.tenant_id {
+            Some(id) => repos.tenants().get_by_id(id).await?.map(Arc::new),
+            None => None,
+        };
+
+        self.cache.lock().insert(
+            namespace.to_string(),
+            CachedTenant {
+                tenant: tenant.clone(),
+                fetched_at: now,
+            },
+        );
+
+        Ok(tenant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::TenantQuotas;
+    use iox_catalog::mem::MemCatalog;
+    use iox_time::MockProvider;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_and_expire() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let ns_name = NamespaceName::try_from("bananas").unwrap();
+        let time = MockProvider::new(Time::from_timestamp_nanos(0));
+        let resolver = TenantResolver::new_with_time_provider(
+            Arc::clone(&catalog),
+            Duration::from_secs(10),
+            time,
+        );
+
+        // The namespace does not exist yet.
+        assert_eq!(resolver.get(&ns_name).await.unwrap(), None);
+
+        let tenant = {
+            let mut repos = catalog.repositories().await;
+            repos
+                .namespaces()
+                .create(&ns_name, None, None, None)
+                .await
+                .unwrap();
+            repos
+                .tenants()
+                .create("platanos", TenantQuotas::default())
+                .await
+                .unwrap()
+        };
+
+        // The namespace exists but has no tenant, which is cached.
+        assert_eq!(resolver.get(&ns_name).await.unwrap(), None);
+
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_tenant(&ns_name, Some(tenant.id))
+            .await
+            .unwrap();
+        assert_eq!(resolver.get(&ns_name).await.unwrap(), None);
+
+        // Once the TTL passes, the new tenant is observed.
+        resolver.time_provider().inc(Duration::from_secs(10));
+        let got = resolver.get(&ns_name).await.unwrap().expect("tenant");
+        assert_eq!(*got, tenant);
+    }
+}
@@ -90,6 +90,7 @@ async fn test_namespace_create() {
         retention_period_ns: Some(RETENTION),
         partition_template: None,
         service_protection_limits: None,
+        template: None,
     };
     let got = ctx
         .grpc_delegate()
@@ -164,6 +165,7 @@ async fn test_namespace_delete() {
         retention_period_ns: Some(RETENTION),
         partition_template: None,
         service_protection_limits: None,
+        template: None,
     };
     let got = ctx
         .grpc_delegate()
@@ -295,6 +297,7 @@ async fn test_create_namespace_0_retention_period() {
         retention_period_ns: Some(0), // A zero!
         partition_template: None,
         service_protection_limits: None,
+        template: None,
     };
     let got = ctx
         .grpc_delegate()
@@ -361,6 +364,7 @@ async fn test_create_namespace_negative_retention_period() {
         retention_period_ns: Some(-42),
         partition_template: None,
         service_protection_limits: None,
+        template: None,
     };
     let err = ctx
         .grpc_delegate()
@@ -426,6 +430,7 @@ async fn test_update_namespace_0_retention_period() {
             retention_period_ns: Some(42),
             partition_template: None,
             service_protection_limits: None,
+            template: None,
         }))
         .await
         .expect("failed to create namespace")
@@ -533,6 +538,7 @@ async fn test_update_namespace_negative_retention_period() {
             retention_period_ns: Some(42),
             partition_template: None,
             service_protection_limits: None,
+            template: None,
         }))
         .await
         .expect("failed to create namespace")
@@ -794,6 +800,7 @@ async fn test_update_namespace_limit_0_max_tables_max_columns() {
             retention_period_ns: Some(0),
             partition_template: None,
             service_protection_limits: None,
+            template: None,
         }))
         .await
         .expect("failed to create namespace")
@@ -862,6 +869,7 @@ async fn test_table_create() {
         retention_period_ns: None,
         partition_template: None,
         service_protection_limits: None,
+        template: None,
     };
     let namespace = ctx
         .grpc_delegate()
@@ -950,6 +958,7 @@ async fn test_invalid_strftime_partition_template() {
             }],
         }),
         service_protection_limits: None,
+        template: None,
     };
 
     // Check namespace creation returned an error
@@ -982,6 +991,7 @@ async fn test_invalid_tag_value_partition_template() {
             }],
         }),
         service_protection_limits: None,
+        template: None,
     };
 
     // Check namespace creation returned an error
@@ -1014,6 +1024,7 @@ async fn test_namespace_partition_template_implicit_table_creation() {
             }],
         }),
         service_protection_limits: None,
+        template: None,
     };
     ctx.grpc_delegate()
         .namespace_service()
@@ -1065,6 +1076,7 @@ async fn test_namespace_partition_template_explicit_table_creation_without_parti
             }],
         }),
         service_protection_limits: None,
+        template: None,
     };
     ctx.grpc_delegate()
         .namespace_service()
@@ -1131,6 +1143,7 @@ async fn test_namespace_partition_template_explicit_table_creation_with_partitio
             }],
         }),
         service_protection_limits: None,
+        template: None,
     };
     ctx.grpc_delegate()
         .namespace_service()
@@ -1197,6 +1210,7 @@ async fn test_namespace_without_partition_template_table_with_partition_template
         retention_period_ns: None,
         partition_template: None,
         service_protection_limits: None,
+        template: None,
     };
     ctx.grpc_delegate()
         .namespace_service()
@@ -1,3 +1,5 @@
+use std::{fmt::Display, str::FromStr};
+
 use arrow::{datatypes::Field, record_batch::RecordBatch};
 use hashbrown::hash_map::RawEntryMut;
 use hashbrown::HashMap;
@@ -5,7 +7,7 @@ use snafu::Snafu;
 
 use crate::interner::SchemaInterner;
 
-use super::{InfluxColumnType, Schema};
+use super::{InfluxColumnType, InfluxFieldType, Schema};
 
 /// Namespace schema creation / validation errors.
 #[derive(Debug, Snafu)]
@@ -33,6 +35,13 @@ pub enum Error {
         existing_column_type: InfluxColumnType,
         new_column_type: InfluxColumnType,
     },
+
+    #[snafu(display(
+        "Invalid type promotion '{}', expected 'none' or a comma separated list of \
+         'int_to_float', 'uint_to_int' and 'uint_to_float'",
+        value
+    ))]
+    InvalidTypePromotion { value: String },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -51,6 +60,136 @@ pub fn merge_record_batch_schemas(batches: &[RecordBatch]) -> Schema {
     merger.build()
 }
 
+/// How field columns that have different types in the merged schemas
+/// are combined.
+///
+/// By default no promotion is done and merging fields of different types
+/// fails. The promoted (wider) type is used in the merged schema, and the
+/// narrower values must be cast when the data is read, using the same policy
+/// (see [`TypePromotion::allows`]).
+///
+/// The policy is written as `none` or a comma separated list of
+/// `int_to_float`, `uint_to_int` and `uint_to_float`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypePromotion {
+    /// Promote `Integer` fields to `Float` when merged with a `Float` field.
+    pub integer_to_float: bool,
+
+    /// How `UInteger` fields are promoted when merged with a signed field.
+    pub unsigned: UnsignedPromotion,
+}
+
+/// How `UInteger` fields are promoted when merged with a signed field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnsignedPromotion {
+    /// Never promote unsigned fields.
+    #[default]
+    None,
+
+    /// Promote `UInteger` to `Integer` when merged with an `Integer` field.
+    /// Values larger than `i64::MAX` become NULL.
+    ToInteger,
+
+    /// Promote `UInteger` to `Float` when merged with an `Integer` or a
+    /// `Float` field. Large values may lose precision.
+    ToFloat,
+}
+
+impl TypePromotion {
+    /// Promote all numeric field types, preferring `Integer` for mixed
+    /// signed and unsigned integers.
+    pub fn widening() -> Self {
+        Self {
+            integer_to_float: true,
+            unsigned: UnsignedPromotion::ToInteger,
+        }
+    }
+
+    /// Returns the type that fields of type `a` and `b` are both promoted
+    /// to, if any.
+    pub fn promote(&self, a: InfluxFieldType, b: InfluxFieldType) -> Option<InfluxFieldType> {
+        use InfluxFieldType::*;
+
+        if a == b {
+            return Some(a);
+        }
+
+        match (a, b) {
+            (Integer, Float) | (Float, Integer) if self.integer_to_float => Some(Float),
+            (UInteger, Integer) | (Integer, UInteger) => match self.unsigned {
+                UnsignedPromotion::None => None,
+                UnsignedPromotion::ToInteger => Some(Integer),
+                UnsignedPromotion::ToFloat => Some(Float),
+            },
+            (UInteger, Float) | (Float, UInteger) => match self.unsigned {
+                UnsignedPromotion::None => None,
+                UnsignedPromotion::ToInteger if self.integer_to_float => Some(Float),
+                UnsignedPromotion::ToInteger => None,
+                UnsignedPromotion::ToFloat => Some(Float),
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns true if values of type `from` are promoted to `to` by this
+    /// policy, i.e. if they may be cast when reading data written with the
+    /// narrower type.
+    pub fn allows(&self, from: InfluxFieldType, to: InfluxFieldType) -> bool {
+        from != to && self.promote(from, to) == Some(to)
+    }
+}
+
+impl FromStr for TypePromotion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut type_promotion = Self::default();
+        let s = s.trim();
+        if s.is_empty() || s == "none" {
+            return Ok(type_promotion);
+        }
+
+        for part in s.split(',') {
+            match (part.trim(), type_promotion.unsigned) {
+                ("int_to_float", _) => type_promotion.integer_to_float = true,
+                ("uint_to_int", UnsignedPromotion::None) => {
+                    type_promotion.unsigned = UnsignedPromotion::ToInteger
+                }
+                ("uint_to_float", UnsignedPromotion::None) => {
+                    type_promotion.unsigned = UnsignedPromotion::ToFloat
+                }
+                _ => {
+                    return Err(Error::InvalidTypePromotion {
+                        value: s.to_string(),
+                    })
+                }
+            }
+        }
+
+        Ok(type_promotion)
+    }
+}
+
+impl Display for TypePromotion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        if self.integer_to_float {
+            parts.push("int_to_float");
+        }
+        match self.unsigned {
+            UnsignedPromotion::None => {}
+            UnsignedPromotion::ToInteger => parts.push("uint_to_int"),
+            UnsignedPromotion::ToFloat => parts.push("uint_to_float"),
+        }
+
+        if parts.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", parts.join(","))
+        }
+    }
+}
+
 /// Schema Merger
 ///
 /// The usecase for merging schemas is when different chunks have
@@ -63,12 +202,18 @@ pub fn merge_record_batch_schemas(batches: &[RecordBatch]) -> Schema {
 ///
 /// 2. The measurement names must be consistent: one or both can be
 ///    `None`, or they can both be `Some(name`)
+///
+/// 3. Field columns of different types are promoted to a common type
+///    according to the [`TypePromotion`] policy, if one is set with
+///    [`with_type_promotion`](Self::with_type_promotion)
 #[derive(Debug, Default)]
 pub struct SchemaMerger<'a> {
     /// Maps column names to their definition
     fields: HashMap<String, (Field, InfluxColumnType)>,
     /// The measurement name if any
     measurement: Option<String>,
+    /// How to merge fields of different types
+    type_promotion: TypePromotion,
     /// Interner, if any.
     interner: Option<&'a mut SchemaInterner>,
 }
@@ -84,10 +229,18 @@ impl<'a> SchemaMerger<'a> {
         SchemaMerger {
             fields: self.fields,
             measurement: self.measurement,
+            type_promotion: self.type_promotion,
             interner: Some(interner),
         }
     }
 
+    /// Promote field columns of different types according to
+    /// `type_promotion` rather than failing the merge.
+    pub fn with_type_promotion(mut self, type_promotion: TypePromotion) -> Self {
+        self.type_promotion = type_promotion;
+        self
+    }
+
     /// Appends the schema to the merged schema being built,
     /// validating that no columns are added.
     pub fn merge(mut self, other: &Schema) -> Result<Self> {
@@ -128,18 +281,32 @@ impl<'a> SchemaMerger<'a> {
                 let field = Field::new(field_name, field.data_type().clone(), field.is_nullable());
                 vacant.insert(field_name.clone(), (field, column_type));
             }
-            RawEntryMut::Occupied(occupied) => {
-                let (existing_field, existing_column_type) = occupied.get();
+            RawEntryMut::Occupied(mut occupied) => {
+                let (existing_field, existing_column_type) = occupied.get_mut();
 
-                // for now, insist the types are exactly the same
-                // (e.g. None and Some(..) don't match). We could
-                // consider relaxing this constraint
+                // insist the types are exactly the same (e.g. None and
+                // Some(..) don't match), unless the field types can be
+                // promoted
                 if existing_column_type != &column_type {
-                    return Err(Error::TryMergeBadColumnType {
-                        field_name: field_name.to_string(),
-                        existing_column_type: *existing_column_type,
-                        new_column_type: column_type,
-                    });
+                    let promoted = match (*existing_column_type, column_type) {
+                        (InfluxColumnType::Field(a), InfluxColumnType::Field(b)) => {
+                            self.type_promotion.promote(a, b)
+                        }
+                        _ => None,
+                    };
+
+                    let Some(promoted) = promoted else {
+                        return Err(Error::TryMergeBadColumnType {
+                            field_name: field_name.to_string(),
+                            existing_column_type: *existing_column_type,
+                            new_column_type: column_type,
+                        });
+                    };
+
+                    *existing_field =
+                        Field::new(field_name, promoted.into(), existing_field.is_nullable());
+                    *existing_column_type = InfluxColumnType::Field(promoted);
+                    return Ok(self);
                 }
 
                 // both are valid schemas, so this should always hold
@@ -173,7 +340,7 @@ mod tests {
     use std::sync::Arc;
 
     use crate::builder::SchemaBuilder;
-    use crate::InfluxFieldType::Integer;
+    use crate::InfluxFieldType::{Float, Integer, UInteger};
 
     use super::*;
 
@@ -377,6 +544,183 @@ mod tests {
         assert_eq!(merged_schema_error.to_string(), "Schema Merge Error: Incompatible column type for 'the_tag'. Existing type Tag, new type Field(Integer)");
     }
 
+    #[test]
+    fn test_merge_field_types_without_promotion() {
+        let schema1 = SchemaBuilder::new()
+            .influx_field("the_field", Integer)
+            .build()
+            .unwrap();
+
+        let schema2 = SchemaBuilder::new()
+            .influx_field("the_field", Float)
+            .build()
+            .unwrap();
+
+        let merged_schema_error = SchemaMerger::new()
+            .merge(&schema1)
+            .unwrap()
+            .merge(&schema2)
+            .unwrap_err();
+
+        assert_eq!(merged_schema_error.to_string(), "Schema Merge Error: Incompatible column type for 'the_field'. Existing type Field(Integer), new type Field(Float)");
+    }
+
+    #[test]
+    fn test_merge_field_types_with_promotion() {
+        let cases = [
+            (TypePromotion::widening(), Integer, Float, Some(Float)),
+            (TypePromotion::widening(), Float, Integer, Some(Float)),
+            (TypePromotion::widening(), UInteger, Integer, Some(Integer)),
+            (TypePromotion::widening(), Integer, UInteger, Some(Integer)),
+            (TypePromotion::widening(), UInteger, Float, Some(Float)),
+            (
+                TypePromotion {
+                    integer_to_float: false,
+                    unsigned: UnsignedPromotion::ToFloat,
+                },
+                UInteger,
+                Integer,
+                Some(Float),
+            ),
+            (
+                TypePromotion {
+                    integer_to_float: false,
+                    unsigned: UnsignedPromotion::ToInteger,
+                },
+                UInteger,
+                Float,
+                None,
+            ),
+            (
+                TypePromotion {
+                    integer_to_float: true,
+                    unsigned: UnsignedPromotion::None,
+                },
+                UInteger,
+                Integer,
+                None,
+            ),
+            (
+                TypePromotion::widening(),
+                crate::InfluxFieldType::String,
+                Integer,
+                None,
+            ),
+        ];
+
+        for (type_promotion, existing, new, expected) in cases {
+            let schema1 = SchemaBuilder::new()
+                .tag("the_tag")
+                .influx_field("the_field", existing)
+                .build()
+                .unwrap();
+
+            let schema2 = SchemaBuilder::new()
+                .influx_field("the_field", new)
+                .build()
+                .unwrap();
+
+            let merged_schema = SchemaMerger::new()
+                .with_type_promotion(type_promotion)
+                .merge(&schema1)
+                .unwrap()
+                .merge(&schema2);
+
+            match expected {
+                Some(expected) => {
+                    let expected_schema = SchemaBuilder::new()
+                        .tag("the_tag")
+                        .influx_field("the_field", expected)
+                        .build()
+                        .unwrap()
+                        .sort_fields_by_name();
+                    assert_eq!(
+                        merged_schema.unwrap().build(),
+                        expected_schema,
+                        "{type_promotion:?} {existing:?} {new:?}"
+                    );
+                }
+                None => {
+                    assert!(
+                        merged_schema.is_err(),
+                        "{type_promotion:?} {existing:?} {new:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_type_promotion_allows() {
+        let widening = TypePromotion::widening();
+        assert!(widening.allows(Integer, Float));
+        assert!(widening.allows(UInteger, Integer));
+        assert!(widening.allows(UInteger, Float));
+        assert!(!widening.allows(Float, Integer));
+        assert!(!widening.allows(Integer, Integer));
+
+        let none = TypePromotion::default();
+        assert!(!none.allows(Integer, Float));
+        assert!(!none.allows(UInteger, Integer));
+
+        let to_float = TypePromotion {
+            integer_to_float: false,
+            unsigned: UnsignedPromotion::ToFloat,
+        };
+        assert!(to_float.allows(UInteger, Float));
+        assert!(!to_float.allows(UInteger, Integer));
+        assert!(!to_float.allows(Integer, Float));
+    }
+
+    #[test]
+    fn test_type_promotion_round_trip() {
+        for type_promotion in [
+            TypePromotion::default(),
+            TypePromotion::widening(),
+            TypePromotion {
+                integer_to_float: false,
+                unsigned: UnsignedPromotion::ToFloat,
+            },
+        ] {
+            assert_eq!(
+                TypePromotion::from_str(&type_promotion.to_string()).unwrap(),
+                type_promotion
+            );
+        }
+
+        assert_eq!(TypePromotion::default().to_string(), "none");
+        assert_eq!(
+            TypePromotion::from_str(" int_to_float, uint_to_int ").unwrap(),
+            TypePromotion::widening()
+        );
+        assert_eq!(
+            TypePromotion::from_str("").unwrap(),
+            TypePromotion::default()
+        );
+        assert!(TypePromotion::from_str("uint_to_int,uint_to_float").is_err());
+        assert!(TypePromotion::from_str("float_to_int").is_err());
+    }
+
+    #[test]
+    fn test_merge_tag_and_field_with_promotion() {
+        let schema1 = SchemaBuilder::new().tag("the_tag").build().unwrap();
+
+        let schema2 = SchemaBuilder::new()
+            .influx_field("the_tag", Float)
+            .build()
+            .unwrap();
+
+        // only field columns are promoted
+        let merged_schema_error = SchemaMerger::new()
+            .with_type_promotion(TypePromotion::widening())
+            .merge(&schema1)
+            .unwrap()
+            .merge(&schema2)
+            .unwrap_err();
+
+        assert_eq!(merged_schema_error.to_string(), "Schema Merge Error: Incompatible column type for 'the_tag'. Existing type Tag, new type Field(Float)");
+    }
+
     #[test]
     fn test_interning() {
         let schema_1a = SchemaBuilder::new()
@@ -25,6 +25,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use iox_query::QueryNamespace;
+use tonic::metadata::MetadataMap;
 use trace::span::Span;
 use tracker::InstrumentedAsyncOwnedSemaphorePermit;
 
@@ -52,3 +53,13 @@ pub trait QueryNamespaceProvider: std::fmt::Debug + Send + Sync + 'static {
 }
 
 pub use error::datafusion_error_to_tonic_code;
+
+/// Check if request has IOx debug header set.
+pub fn has_debug_header(metadata: &MetadataMap) -> bool {
+    metadata
+        .get("iox-debug")
+        .and_then(|s| s.to_str().ok())
+        .map(|s| s.to_lowercase())
+        .map(|s| matches!(s.as_str(), "1" | "on" | "yes" | "y" | "true" | "t"))
+        .unwrap_or_default()
+}
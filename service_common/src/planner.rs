@@ -9,13 +9,18 @@ use flightsql::{FlightSQLCommand, FlightSQLPlanner};
 use iox_query::{
     exec::IOxSessionContext,
     frontend::sql::SqlQueryPlanner,
-    plan::{fieldlist::FieldListPlan, seriesset::SeriesSetPlans, stringset::StringSetPlan},
-    Aggregate, QueryNamespace, WindowDuration,
+    plan::{
+        fieldlist::{FieldListPlan, MeasurementFieldListPlan},
+        seriesset::SeriesSetPlans,
+        stringset::StringSetPlan,
+    },
+    Aggregate, NamedAggregateExpr, QueryNamespace, WindowDuration, WindowFill,
 };
 use iox_query_influxrpc::InfluxRpcPlanner;
 
 pub use datafusion::error::{DataFusionError as Error, Result};
 use iox_query_influxql::frontend::planner::InfluxQLQueryPlanner;
+pub use iox_query_influxrpc::PointOrder;
 use predicate::rpc_predicate::InfluxRpcPredicate;
 
 /// Query planner that plans queries on a separate threadpool.
@@ -220,12 +225,37 @@ impl Planner {
             .await
     }
 
+    /// Creates a plan as described on
+    /// [`InfluxRpcPlanner::measurement_field_columns`], on a separate
+    /// threadpool
+    pub async fn measurement_field_columns<N>(
+        &self,
+        namespace: Arc<N>,
+        predicate: InfluxRpcPredicate,
+    ) -> Result<MeasurementFieldListPlan>
+    where
+        N: QueryNamespace + 'static,
+    {
+        let planner =
+            InfluxRpcPlanner::new(self.ctx.child_ctx("planner measurement_field_columns")).await;
+
+        self.ctx
+            .run(async move {
+                planner
+                    .measurement_field_columns(namespace, predicate)
+                    .await
+                    .map_err(|e| e.to_df_error("measurement_field_columns"))
+            })
+            .await
+    }
+
     /// Creates a plan as described on [`InfluxRpcPlanner::read_filter`], on a
     /// separate threadpool
     pub async fn read_filter<N>(
         &self,
         namespace: Arc<N>,
         predicate: InfluxRpcPredicate,
+        point_order: PointOrder,
     ) -> Result<SeriesSetPlans>
     where
         N: QueryNamespace + 'static,
@@ -235,7 +265,7 @@ impl Planner {
         self.ctx
             .run(async move {
                 planner
-                    .read_filter(namespace, predicate)
+                    .read_filter(namespace, predicate, point_order)
                     .await
                     .map_err(|e| e.to_df_error("read_filter"))
             })
@@ -275,6 +305,7 @@ impl Planner {
         agg: Aggregate,
         every: WindowDuration,
         offset: WindowDuration,
+        fill: WindowFill,
     ) -> Result<SeriesSetPlans>
     where
         N: QueryNamespace + 'static,
@@ -285,10 +316,43 @@ impl Planner {
         self.ctx
             .run(async move {
                 planner
-                    .read_window_aggregate(namespace, predicate, agg, every, offset)
+                    .read_window_aggregate(namespace, predicate, agg, every, offset, fill)
                     .await
                     .map_err(|e| e.to_df_error("read_window_aggregate"))
             })
             .await
     }
+
+    /// Creates a plan as described on
+    /// [`InfluxRpcPlanner::read_window_aggregate_expressions`], on a separate
+    /// threadpool
+    pub async fn read_window_aggregate_expressions<N>(
+        &self,
+        namespace: Arc<N>,
+        predicate: InfluxRpcPredicate,
+        exprs: Vec<NamedAggregateExpr>,
+        every: WindowDuration,
+        offset: WindowDuration,
+        fill: WindowFill,
+    ) -> Result<SeriesSetPlans>
+    where
+        N: QueryNamespace + 'static,
+    {
+        let planner = InfluxRpcPlanner::new(
+            self.ctx
+                .child_ctx("planner read_window_aggregate_expressions"),
+        )
+        .await;
+
+        self.ctx
+            .run(async move {
+                planner
+                    .read_window_aggregate_expressions(
+                        namespace, predicate, exprs, every, offset, fill,
+                    )
+                    .await
+                    .map_err(|e| e.to_df_error("read_window_aggregate_expressions"))
+            })
+            .await
+    }
 }
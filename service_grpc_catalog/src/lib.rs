@@ -18,13 +18,21 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
-use data_types::{TableId, TransitionPartitionId};
+use data_types::{ReadLeaseId, TableId, Timestamp, TransitionPartitionId};
 use generated_types::influxdata::iox::catalog::v1::*;
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use observability_deps::tracing::*;
-use std::sync::Arc;
+use parquet_file::ParquetFilePath;
+use std::{sync::Arc, time::Duration};
 use tonic::{Request, Response, Status};
 
+/// The lease duration of a table manifest if the request doesn't specify one.
+const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// The longest lease that can be taken on the files of a table manifest, bounding how long
+/// compacted files can be kept in object storage.
+const MAX_LEASE_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
 /// Implementation of the Catalog gRPC service
 #[derive(Debug)]
 pub struct CatalogService {
@@ -171,6 +179,98 @@ impl catalog_service_server::CatalogService for CatalogService {
 
         Ok(Response::new(response))
     }
+
+    async fn get_table_manifest(
+        &self,
+        request: Request<GetTableManifestRequest>,
+    ) -> Result<Response<GetTableManifestResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let req = request.into_inner();
+
+        let lease_duration = match req.lease_duration_seconds {
+            0 => DEFAULT_LEASE_DURATION,
+            secs => Duration::from_secs(secs),
+        };
+        if lease_duration > MAX_LEASE_DURATION {
+            return Err(Status::invalid_argument(format!(
+                "lease duration exceeds the maximum of {}s",
+                MAX_LEASE_DURATION.as_secs()
+            )));
+        }
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&req.namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Namespace {} not found", req.namespace_name))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &req.table_name)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .ok_or_else(|| Status::not_found(format!("Table {} not found", req.table_name)))?;
+
+        // The lease must be taken before listing the files, so that it covers every file
+        // that is not flagged for deletion at the time of listing.
+        let expires_at = Timestamp::from(self.catalog.time_provider().now() + lease_duration);
+        let lease = repos
+            .read_leases()
+            .create(table.id, expires_at)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?;
+
+        let parquet_files = repos
+            .parquet_files()
+            .list_by_table_not_to_delete(table.id)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error=%e,
+                    %req.namespace_name,
+                    %req.table_name,
+                    "failed to get parquet_files for table manifest"
+                );
+                Status::not_found(e.to_string())
+            })?;
+
+        let files = parquet_files
+            .into_iter()
+            .filter(|f| req.min_time.map_or(true, |t| f.max_time.get() >= t))
+            .filter(|f| req.max_time.map_or(true, |t| f.min_time.get() <= t))
+            .map(|f| ManifestFile {
+                object_store_path: ParquetFilePath::from(&f).object_store_path().to_string(),
+                parquet_file: Some(f.into()),
+            })
+            .collect();
+
+        let response = GetTableManifestResponse {
+            files,
+            lease_id: lease.id.get(),
+            lease_expires_at: lease.expires_at.get(),
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn release_table_manifest(
+        &self,
+        request: Request<ReleaseTableManifestRequest>,
+    ) -> Result<Response<ReleaseTableManifestResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let req = request.into_inner();
+
+        repos
+            .read_leases()
+            .release(ReadLeaseId::new(req.lease_id))
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?;
+
+        Ok(Response::new(ReleaseTableManifestResponse {}))
+    }
 }
 
 // converts the catalog Partition to protobuf
@@ -232,6 +332,7 @@ mod tests {
                 created_at: Timestamp::new(2343),
                 column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
                 max_l0_created_at: Timestamp::new(2343),
+                content_hash: None,
             };
             let p2params = ParquetFileParams {
                 object_store_id: Uuid::new_v4(),
@@ -303,4 +404,113 @@ mod tests {
             .collect();
         assert_eq!(expect, response.partitions);
     }
+    #[tokio::test]
+    async fn get_table_manifest() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog = Arc::new(MemCatalog::new(metrics));
+        let (early, late) = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "catalog_manifest_test").await;
+            let table = arbitrary_table(&mut *repos, "manifest_table", &namespace).await;
+            let partition = repos
+                .partitions()
+                .create_or_get("foo".into(), table.id)
+                .await
+                .unwrap();
+            let early = ParquetFileParams {
+                namespace_id: namespace.id,
+                table_id: table.id,
+                partition_id: partition.transition_partition_id(),
+                object_store_id: Uuid::new_v4(),
+                min_time: Timestamp::new(1),
+                max_time: Timestamp::new(5),
+                file_size_bytes: 2343,
+                row_count: 29,
+                compaction_level: CompactionLevel::Initial,
+                created_at: Timestamp::new(2343),
+                column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
+                max_l0_created_at: Timestamp::new(2343),
+                content_hash: None,
+            };
+            let late = ParquetFileParams {
+                object_store_id: Uuid::new_v4(),
+                min_time: Timestamp::new(10),
+                max_time: Timestamp::new(20),
+                ..early.clone()
+            };
+            let early = repos.parquet_files().create(early).await.unwrap();
+            let late = repos.parquet_files().create(late).await.unwrap();
+            (early, late)
+        };
+
+        let grpc = super::CatalogService::new(Arc::clone(&catalog) as _);
+        let request = |min_time, max_time| GetTableManifestRequest {
+            namespace_name: "catalog_manifest_test".to_string(),
+            table_name: "manifest_table".to_string(),
+            min_time,
+            max_time,
+            lease_duration_seconds: 0,
+        };
+
+        let response = grpc
+            .get_table_manifest(Request::new(request(None, None)))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner();
+        let expect: Vec<_> = [&early, &late]
+            .into_iter()
+            .map(|f| ManifestFile {
+                parquet_file: Some(f.clone().into()),
+                object_store_path: ParquetFilePath::from(f).object_store_path().to_string(),
+            })
+            .collect();
+        assert_eq!(expect, response.files);
+
+        // The files are protected by a lease until released.
+        let lease_id = ReadLeaseId::new(response.lease_id);
+        let lease = catalog
+            .repositories()
+            .await
+            .read_leases()
+            .get_by_id(lease_id)
+            .await
+            .unwrap()
+            .expect("lease should exist");
+        assert_eq!(lease.expires_at.get(), response.lease_expires_at);
+
+        grpc.release_table_manifest(Request::new(ReleaseTableManifestRequest {
+            lease_id: response.lease_id,
+        }))
+        .await
+        .expect("rpc request should succeed");
+        assert!(catalog
+            .repositories()
+            .await
+            .read_leases()
+            .get_by_id(lease_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Only files overlapping the time range are returned.
+        let response = grpc
+            .get_table_manifest(Request::new(request(Some(6), Some(15))))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner();
+        assert_eq!(response.files.len(), 1);
+        assert_eq!(
+            response.files[0].parquet_file.as_ref().unwrap().id,
+            late.id.get()
+        );
+
+        let err = grpc
+            .get_table_manifest(Request::new(GetTableManifestRequest {
+                lease_duration_seconds: 7 * 24 * 60 * 60,
+                ..request(None, None)
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
 }
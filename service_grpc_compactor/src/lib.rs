@@ -0,0 +1,393 @@
+//! Implementation of the compaction gRPC service
+
+#![deny(
+    rustdoc::broken_intra_doc_links,
+    rustdoc::bare_urls,
+    rust_2018_idioms,
+    missing_debug_implementations,
+    unreachable_pub
+)]
+#![warn(
+    missing_docs,
+    clippy::todo,
+    clippy::dbg_macro,
+    clippy::clone_on_ref_ptr,
+    clippy::future_not_send,
+    unused_crate_dependencies
+)]
+#![allow(clippy::missing_docs_in_private_items)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::sync::Arc;
+
+use compactor::{
+    compactor::Compactor,
+    namespace_merge::{self, NamespaceMerge},
+    table_rewrite::{self, TableRewrite, TagOp},
+};
+use data_types::PartitionId;
+use generated_types::influxdata::iox::compactor::v1::*;
+use iox_catalog::interface::Catalog;
+use observability_deps::tracing::{info, warn};
+use predicate::{delete_predicate::parse_delete_predicate, Predicate};
+use service_grpc_operations::tracker_to_proto;
+use tonic::{Request, Response, Status};
+
+/// Implementation of the compaction gRPC service
+#[derive(Debug)]
+pub struct CompactionService {
+    /// Catalog.
+    catalog: Arc<dyn Catalog>,
+
+    /// The compactor running table rewrites.
+    compactor: Arc<Compactor>,
+}
+
+impl CompactionService {
+    /// Create a new `CompactionService` instance
+    pub fn new(catalog: Arc<dyn Catalog>, compactor: Arc<Compactor>) -> Self {
+        Self { catalog, compactor }
+    }
+}
+
+#[tonic::async_trait]
+impl compaction_service_server::CompactionService for CompactionService {
+    async fn list_skipped_compactions(
+        &self,
+        _request: Request<ListSkippedCompactionsRequest>,
+    ) -> Result<Response<ListSkippedCompactionsResponse>, Status> {
+        let skipped_compactions = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_skipped_compactions()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(Response::new(ListSkippedCompactionsResponse {
+            skipped_compactions,
+        }))
+    }
+
+    async fn delete_skipped_compactions(
+        &self,
+        request: Request<DeleteSkippedCompactionsRequest>,
+    ) -> Result<Response<DeleteSkippedCompactionsResponse>, Status> {
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+
+        let skipped_compaction = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .delete_skipped_compactions(partition_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map(Into::into);
+
+        info!(
+            partition_id = partition_id.get(),
+            "deleted skipped compaction"
+        );
+
+        Ok(Response::new(DeleteSkippedCompactionsResponse {
+            skipped_compaction,
+        }))
+    }
+
+    async fn freeze_partition(
+        &self,
+        request: Request<FreezePartitionRequest>,
+    ) -> Result<Response<FreezePartitionResponse>, Status> {
+        let FreezePartitionRequest {
+            partition_id,
+            reason,
+        } = request.into_inner();
+        let partition_id = PartitionId::new(partition_id);
+
+        let frozen_partition = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .freeze(partition_id, &reason)
+            .await
+            .map_err(catalog_error_to_status)?;
+
+        info!(partition_id = partition_id.get(), %reason, "froze partition");
+
+        Ok(Response::new(FreezePartitionResponse {
+            frozen_partition: Some(frozen_partition.into()),
+        }))
+    }
+
+    async fn unfreeze_partition(
+        &self,
+        request: Request<UnfreezePartitionRequest>,
+    ) -> Result<Response<UnfreezePartitionResponse>, Status> {
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+
+        let frozen_partition = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .unfreeze(partition_id)
+            .await
+            .map_err(catalog_error_to_status)?
+            .map(Into::into);
+
+        info!(partition_id = partition_id.get(), "unfroze partition");
+
+        Ok(Response::new(UnfreezePartitionResponse {
+            frozen_partition,
+        }))
+    }
+
+    async fn list_frozen_partitions(
+        &self,
+        _request: Request<ListFrozenPartitionsRequest>,
+    ) -> Result<Response<ListFrozenPartitionsResponse>, Status> {
+        let frozen_partitions = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_frozen()
+            .await
+            .map_err(catalog_error_to_status)?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(Response::new(ListFrozenPartitionsResponse {
+            frozen_partitions,
+        }))
+    }
+
+    async fn rewrite_table(
+        &self,
+        request: Request<RewriteTableRequest>,
+    ) -> Result<Response<RewriteTableResponse>, Status> {
+        let rewrite = table_rewrite_from_proto(request.into_inner())?;
+
+        let tracker = self.compactor.rewrite_table(rewrite).await.map_err(|e| {
+            warn!(error=%e, "failed to start table rewrite");
+            rewrite_error_to_status(e)
+        })?;
+
+        Ok(Response::new(RewriteTableResponse {
+            operation: Some(tracker_to_proto(&tracker)),
+        }))
+    }
+
+    async fn merge_namespace(
+        &self,
+        request: Request<MergeNamespaceRequest>,
+    ) -> Result<Response<MergeNamespaceResponse>, Status> {
+        let MergeNamespaceRequest {
+            source_namespace,
+            target_namespace,
+        } = request.into_inner();
+
+        let tracker = self
+            .compactor
+            .merge_namespace(NamespaceMerge {
+                source_namespace,
+                target_namespace,
+            })
+            .await
+            .map_err(|e| {
+                warn!(error=%e, "failed to start namespace merge");
+                merge_error_to_status(e)
+            })?;
+
+        Ok(Response::new(MergeNamespaceResponse {
+            operation: Some(tracker_to_proto(&tracker)),
+        }))
+    }
+}
+
+/// Convert the request into a [`TableRewrite`], parsing its predicate.
+fn table_rewrite_from_proto(request: RewriteTableRequest) -> Result<TableRewrite, Status> {
+    let RewriteTableRequest {
+        namespace,
+        source_table,
+        target_table,
+        start,
+        stop,
+        predicate,
+        tag_ops,
+    } = request;
+
+    let predicate = if start.is_empty() && stop.is_empty() && predicate.is_empty() {
+        Predicate::default()
+    } else {
+        let start = if start.is_empty() {
+            i64::MIN.to_string()
+        } else {
+            start
+        };
+        let stop = if stop.is_empty() {
+            i64::MAX.to_string()
+        } else {
+            stop
+        };
+        parse_delete_predicate(&start, &stop, &predicate)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+            .into()
+    };
+
+    let tag_ops = tag_ops
+        .into_iter()
+        .map(|op| match op.op {
+            Some(tag_op::Op::Add(AddTag { name, value })) => Ok(TagOp::Add { name, value }),
+            Some(tag_op::Op::Remove(RemoveTag { name })) => Ok(TagOp::Remove { name }),
+            Some(tag_op::Op::Rename(RenameTag { from, to })) => Ok(TagOp::Rename { from, to }),
+            None => Err(Status::invalid_argument("tag operation is required")),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(TableRewrite {
+        namespace,
+        source_table,
+        target_table,
+        predicate,
+        tag_ops,
+    })
+}
+
+fn merge_error_to_status(e: namespace_merge::Error) -> Status {
+    use namespace_merge::Error;
+
+    match e {
+        Error::NamespaceNotFound { .. } => Status::not_found(e.to_string()),
+        Error::SameNamespace { .. } => Status::invalid_argument(e.to_string()),
+        Error::SchemaConflicts { .. } => Status::failed_precondition(e.to_string()),
+        Error::Catalog { .. } | Error::Copy { .. } => Status::internal(e.to_string()),
+    }
+}
+
+fn catalog_error_to_status(e: iox_catalog::interface::Error) -> Status {
+    match e {
+        iox_catalog::interface::Error::PartitionNotFound { .. } => Status::not_found(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    }
+}
+
+fn rewrite_error_to_status(e: table_rewrite::Error) -> Status {
+    use table_rewrite::Error;
+
+    match e {
+        Error::NamespaceNotFound { .. } | Error::TableNotFound { .. } => {
+            Status::not_found(e.to_string())
+        }
+        Error::SameTable { .. }
+        | Error::PartitionTemplateMismatch { .. }
+        | Error::TagNotFound { .. }
+        | Error::PartitionTag { .. }
+        | Error::ColumnExists { .. } => Status::invalid_argument(e.to_string()),
+        Error::Catalog { .. }
+        | Error::Planning { .. }
+        | Error::Execution { .. }
+        | Error::Upload { .. } => Status::internal(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use generated_types::influxdata::iox::compactor::v1 as proto;
+    use tonic::Code;
+
+    use super::*;
+
+    fn request() -> RewriteTableRequest {
+        RewriteTableRequest {
+            namespace: "ns".to_string(),
+            source_table: "cpu".to_string(),
+            target_table: "cpu_west".to_string(),
+            start: String::new(),
+            stop: String::new(),
+            predicate: String::new(),
+            tag_ops: vec![],
+        }
+    }
+
+    #[test]
+    fn test_table_rewrite_from_proto() {
+        let rewrite = table_rewrite_from_proto(request()).unwrap();
+        assert_eq!(rewrite.namespace, "ns");
+        assert_eq!(rewrite.source_table, "cpu");
+        assert_eq!(rewrite.target_table, "cpu_west");
+        assert_eq!(rewrite.predicate, Predicate::default());
+        assert!(rewrite.tag_ops.is_empty());
+
+        let rewrite = table_rewrite_from_proto(RewriteTableRequest {
+            start: "100".to_string(),
+            predicate: r#"region="west""#.to_string(),
+            tag_ops: vec![
+                proto::TagOp {
+                    op: Some(tag_op::Op::Add(AddTag {
+                        name: "dc".to_string(),
+                        value: "dc1".to_string(),
+                    })),
+                },
+                proto::TagOp {
+                    op: Some(tag_op::Op::Remove(RemoveTag {
+                        name: "region".to_string(),
+                    })),
+                },
+                proto::TagOp {
+                    op: Some(tag_op::Op::Rename(RenameTag {
+                        from: "host".to_string(),
+                        to: "hostname".to_string(),
+                    })),
+                },
+            ],
+            ..request()
+        })
+        .unwrap();
+        let range = rewrite.predicate.range.unwrap();
+        assert_eq!(range.start(), 100);
+        assert_eq!(rewrite.predicate.exprs.len(), 1);
+        assert_eq!(
+            rewrite.tag_ops,
+            vec![
+                TagOp::Add {
+                    name: "dc".to_string(),
+                    value: "dc1".to_string()
+                },
+                TagOp::Remove {
+                    name: "region".to_string()
+                },
+                TagOp::Rename {
+                    from: "host".to_string(),
+                    to: "hostname".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_rewrite_from_proto_invalid() {
+        let err = table_rewrite_from_proto(RewriteTableRequest {
+            predicate: "region >".to_string(),
+            ..request()
+        })
+        .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let err = table_rewrite_from_proto(RewriteTableRequest {
+            tag_ops: vec![proto::TagOp { op: None }],
+            ..request()
+        })
+        .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+}
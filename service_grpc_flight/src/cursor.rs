@@ -0,0 +1,610 @@
+//! Server-side cursors for paginated `DoGet` responses.
+//!
+//! If a `DoGet` request sets a page size, the response only contains that many rows, splitting
+//! the record batch at the end of the page if needed.
+//! The unread results are kept as a [`Cursor`] in the [`CursorStore`] and the last message of
+//! the page contains the ticket to fetch the next page. The query keeps running between pages,
+//! so every page is read from the same snapshot of the data and the query is never re-planned.
+//!
+//! Cursors that are not resumed within the TTL of the [`CursorConfig`] are dropped by a periodic
+//! expiry task, cancelling their query.
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
+use arrow_flight::{error::FlightError, FlightData};
+use datafusion::physical_plan::{accept, ExecutionPlan, ExecutionPlanVisitor};
+use futures::{ready, stream::BoxStream, Stream, StreamExt};
+use generated_types::influxdata::iox::querier::v1 as proto;
+use iox_query::{config::TagFilters, exec::PartialResults, QueryCompletedToken};
+use observability_deps::tracing::debug;
+use parking_lot::Mutex;
+use prost::Message;
+use snafu::ResultExt;
+use uuid::Uuid;
+
+use crate::{
+    request::{IoxGetRequest, RunQuery},
+    CursorMemoryExhaustedSnafu, InternalCreatingTicketSnafu, Result,
+};
+
+/// Record batches of a query result, as encoded into a `DoGet` response.
+pub(crate) type ResultStream = BoxStream<'static, Result<RecordBatch, FlightError>>;
+
+/// Configuration of the server-side cursors of paginated `DoGet` responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorConfig {
+    /// Cursors that are not resumed within this time after their last page was sent are
+    /// dropped, cancelling their query.
+    pub ttl: Duration,
+
+    /// Upper bound of the memory used by all cursors, i.e. the record batches they buffer and
+    /// the memory their query plans report as used.
+    pub max_memory_bytes: usize,
+}
+
+impl Default for CursorConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(5 * 60),
+            max_memory_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// A running query with unread results.
+pub(crate) struct Cursor {
+    /// Database the query runs against. Resuming the cursor requires the same database.
+    pub(crate) database: String,
+
+    /// Row-level tag filters the query runs with. Resuming the cursor requires the same filters.
+    pub(crate) tag_filters: TagFilters,
+
+    /// Schema of the results.
+    pub(crate) schema: SchemaRef,
+
+    /// Sent with the schema at the start of every page.
+    pub(crate) app_metadata: proto::AppMetadata,
+
+    /// The plan producing `results`, to account for the memory it uses.
+    pub(crate) plan: Arc<dyn ExecutionPlan>,
+
+    /// The unread results.
+    pub(crate) results: ResultStream,
+
+    /// Memory used by the cursor: the record batches buffered by `results` and the memory used
+    /// by `plan` when the cursor was stored.
+    pub(crate) size: usize,
+
+    /// Records the completion of the query once all results were read.
+    pub(crate) query_completed_token: QueryCompletedToken,
+
+    /// Set once `results` were truncated to the [`ResultLimits`](crate::ResultLimits), or if the
+    /// query could not read all data (e.g. a failed backend of a federated namespace).
+    pub(crate) partial: PartialResults,
+}
+
+impl Debug for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cursor")
+            .field("database", &self.database)
+            .field("tag_filters", &self.tag_filters)
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Cursor {
+    /// Take the unread results, leaving an empty stream.
+    pub(crate) fn take_results(&mut self) -> ResultStream {
+        std::mem::replace(&mut self.results, futures::stream::empty().boxed())
+    }
+
+    /// Complete the query once all results were sent.
+    ///
+    /// Returns the message flagging the results as partial, if they are.
+    pub(crate) fn complete(mut self) -> Option<FlightData> {
+        self.query_completed_token.set_success();
+
+        self.partial.get().then(|| {
+            let app_metadata = proto::AppMetadata {
+                partial: true,
+                ..self.app_metadata
+            };
+            FlightData::new().with_app_metadata(app_metadata.encode_to_vec())
+        })
+    }
+}
+
+/// Cursor waiting to be resumed.
+#[derive(Debug)]
+struct StoredCursor {
+    cursor: Cursor,
+    expires_at: Instant,
+}
+
+/// Smallest interval of the expiry task, to not wake it up constantly for very short TTLs.
+const MIN_EXPIRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The cursors of paginated `DoGet` responses, waiting for clients to fetch the next page.
+///
+/// Expired cursors are dropped whenever a cursor is stored or resumed, and periodically by the
+/// task started with [`CursorStore::spawn_expiry_task`].
+#[derive(Debug)]
+pub(crate) struct CursorStore {
+    config: CursorConfig,
+    cursors: Mutex<HashMap<Uuid, StoredCursor>>,
+}
+
+impl CursorStore {
+    pub(crate) fn new(config: CursorConfig) -> Self {
+        Self {
+            config,
+            cursors: Default::default(),
+        }
+    }
+
+    /// Start a task dropping the expired cursors of `store` every half TTL, so that abandoned
+    /// cursors release their query and memory even if no other cursor is used.
+    ///
+    /// The task exits once `store` is dropped.
+    pub(crate) fn spawn_expiry_task(store: &Arc<Self>) {
+        let interval = (store.config.ttl / 2).max(MIN_EXPIRY_INTERVAL);
+        let store = Arc::downgrade(store);
+        tokio::spawn(expire_periodically(store, interval));
+    }
+
+    /// Drop the expired cursors, returning how many were dropped.
+    pub(crate) fn remove_expired(&self) -> usize {
+        let now = Instant::now();
+        let expired: Vec<_> = {
+            let mut cursors = self.cursors.lock();
+            let ids: Vec<_> = cursors
+                .iter()
+                .filter(|(_, c)| c.expires_at <= now)
+                .map(|(id, _)| *id)
+                .collect();
+            ids.into_iter()
+                .filter_map(|id| cursors.remove(&id))
+                .collect()
+        };
+
+        // cancel the queries outside of the lock
+        let n = expired.len();
+        drop(expired);
+        n
+    }
+
+    /// Store `cursor` until it is resumed or expires, returning its ID.
+    ///
+    /// Fails if the cursor would exceed the memory limit.
+    pub(crate) fn insert(&self, cursor: Cursor) -> Result<Uuid> {
+        let now = Instant::now();
+        let mut cursors = self.cursors.lock();
+        cursors.retain(|_, c| c.expires_at > now);
+
+        let used: usize = cursors.values().map(|c| c.cursor.size).sum();
+        if used + cursor.size > self.config.max_memory_bytes {
+            return CursorMemoryExhaustedSnafu {
+                limit: self.config.max_memory_bytes,
+            }
+            .fail();
+        }
+
+        let id = Uuid::new_v4();
+        cursors.insert(
+            id,
+            StoredCursor {
+                cursor,
+                expires_at: now + self.config.ttl,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Remove and return the cursor with the given ID, if it exists and was created for
+    /// `database` and `tag_filters`.
+    pub(crate) fn take(
+        &self,
+        id: Uuid,
+        database: &str,
+        tag_filters: &TagFilters,
+    ) -> Option<Cursor> {
+        let now = Instant::now();
+        let mut cursors = self.cursors.lock();
+        cursors.retain(|_, c| c.expires_at > now);
+
+        let c = &cursors.get(&id)?.cursor;
+        if c.database != database || &c.tag_filters != tag_filters {
+            return None;
+        }
+        cursors.remove(&id).map(|c| c.cursor)
+    }
+}
+
+async fn expire_periodically(store: Weak<CursorStore>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        let Some(store) = store.upgrade() else {
+            return;
+        };
+        let n = store.remove_expired();
+        if n > 0 {
+            debug!(n, "dropped expired Flight cursors");
+        }
+    }
+}
+
+/// Memory used by the operators of `plan`, as reported by their `mem_used` metrics.
+fn plan_memory(plan: &dyn ExecutionPlan) -> usize {
+    struct MemoryVisitor(usize);
+
+    impl ExecutionPlanVisitor for MemoryVisitor {
+        type Error = std::convert::Infallible;
+
+        fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
+            self.0 += plan
+                .metrics()
+                .and_then(|metrics| metrics.sum_by_name("mem_used"))
+                .map(|m| m.as_usize())
+                .unwrap_or_default();
+            Ok(true)
+        }
+    }
+
+    let mut visitor = MemoryVisitor(0);
+    accept(plan, &mut visitor).unwrap();
+    visitor.0
+}
+
+/// A single page of a paginated `DoGet` response.
+pub(crate) struct Page {
+    cursors: Arc<CursorStore>,
+    page_size: NonZeroUsize,
+    remainder: Arc<Mutex<Option<Remainder>>>,
+}
+
+/// The unread results after a page.
+struct Remainder {
+    /// First (non-empty) batch of the next page, read to tell whether there is a next page. May
+    /// be the unread rows of a batch split at the end of the page.
+    batch: RecordBatch,
+    results: ResultStream,
+}
+
+impl Page {
+    /// Limit `results` to the first `page_size` rows.
+    pub(crate) fn new(
+        cursors: Arc<CursorStore>,
+        page_size: NonZeroUsize,
+        results: ResultStream,
+    ) -> (Self, ResultStream) {
+        let remainder = Arc::new(Mutex::new(None));
+        let page = Self {
+            cursors,
+            page_size,
+            remainder: Arc::clone(&remainder),
+        };
+        let results = PageStream {
+            results: Some(results),
+            remaining: page_size.get(),
+            remainder,
+        };
+        (page, results.boxed())
+    }
+
+    /// Complete the page once its results were sent.
+    ///
+    /// If there are unread results, `cursor` is stored with them and the message carrying the
+    /// ticket of the next page is returned. Otherwise the query is complete, see
+    /// [`Cursor::complete`].
+    pub(crate) fn finish(self, mut cursor: Cursor) -> Result<Option<FlightData>> {
+        let Some(Remainder { batch, results }) = self.remainder.lock().take() else {
+            return Ok(cursor.complete());
+        };
+
+        cursor.size = batch.get_array_memory_size() + plan_memory(cursor.plan.as_ref());
+        cursor.results = futures::stream::once(async move { Ok(batch) })
+            .chain(results)
+            .boxed();
+        let database = cursor.database.clone();
+        let app_metadata = cursor.app_metadata.clone();
+        let id = self.cursors.insert(cursor)?;
+
+        let ticket = IoxGetRequest::new(database, RunQuery::Cursor(id), false)
+            .with_page_size(Some(self.page_size))
+            .try_encode()
+            .context(InternalCreatingTicketSnafu)?;
+        let app_metadata = proto::AppMetadata {
+            next_page_ticket: ticket.ticket.to_vec(),
+            ..app_metadata
+        };
+        Ok(Some(
+            FlightData::new().with_app_metadata(app_metadata.encode_to_vec()),
+        ))
+    }
+}
+
+/// Yields the first `remaining` rows of `results`, leaving the unread results in `remainder`.
+struct PageStream {
+    results: Option<ResultStream>,
+    remaining: usize,
+    remainder: Arc<Mutex<Option<Remainder>>>,
+}
+
+impl Stream for PageStream {
+    type Item = Result<RecordBatch, FlightError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let Some(results) = self.results.as_mut() else {
+                return Poll::Ready(None);
+            };
+
+            let res = ready!(results.poll_next_unpin(cx));
+            return match res {
+                // do not start a page without rows
+                Some(Ok(batch)) if self.remaining == 0 && batch.num_rows() == 0 => continue,
+                Some(Ok(batch)) if self.remaining == 0 => {
+                    let results = self.results.take().expect("checked above");
+                    *self.remainder.lock() = Some(Remainder { batch, results });
+                    Poll::Ready(None)
+                }
+                Some(Ok(batch)) if batch.num_rows() > self.remaining => {
+                    // split the batch at the end of the page
+                    let head = batch.slice(0, self.remaining);
+                    let tail = batch.slice(self.remaining, batch.num_rows() - self.remaining);
+                    let results = self.results.take().expect("checked above");
+                    *self.remainder.lock() = Some(Remainder {
+                        batch: tail,
+                        results,
+                    });
+                    self.remaining = 0;
+                    Poll::Ready(Some(Ok(head)))
+                }
+                Some(Ok(batch)) => {
+                    self.remaining -= batch.num_rows();
+                    Poll::Ready(Some(Ok(batch)))
+                }
+                Some(Err(e)) => {
+                    self.results = None;
+                    Poll::Ready(Some(Err(e)))
+                }
+                None => {
+                    self.results = None;
+                    Poll::Ready(None)
+                }
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{ArrayRef, Int64Array};
+    use assert_matches::assert_matches;
+    use datafusion::physical_plan::empty::EmptyExec;
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pages() {
+        let cursors = Arc::new(CursorStore::new(CursorConfig::default()));
+        let mut cursor = make_cursor(&[1, 2, 3]);
+
+        // first page
+        let (page, results) = Page::new(
+            Arc::clone(&cursors),
+            NonZeroUsize::new(2).unwrap(),
+            cursor.take_results(),
+        );
+        assert_eq!(values(results).await, vec![1, 2]);
+        let next_page = page.finish(cursor).unwrap().expect("next page");
+        let id = cursor_id(next_page);
+
+        // cursors are bound to the database and tag filters
+        assert!(cursors.take(id, "other", &TagFilters::default()).is_none());
+
+        // last page
+        let mut cursor = cursors
+            .take(id, "db", &TagFilters::default())
+            .expect("cursor stored");
+        assert!(cursors.take(id, "db", &TagFilters::default()).is_none());
+        let (page, results) = Page::new(
+            Arc::clone(&cursors),
+            NonZeroUsize::new(2).unwrap(),
+            cursor.take_results(),
+        );
+        assert_eq!(values(results).await, vec![3]);
+        assert!(page.finish(cursor).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pages_split_batches() {
+        let cursors = Arc::new(CursorStore::new(CursorConfig::default()));
+        let batches: [&[i64]; 3] = [&[1, 2, 3], &[], &[4, 5]];
+        let mut cursor = make_cursor_with_batches(&batches);
+
+        let mut pages = vec![];
+        loop {
+            let (page, results) = Page::new(
+                Arc::clone(&cursors),
+                NonZeroUsize::new(2).unwrap(),
+                cursor.take_results(),
+            );
+            pages.push(values(results).await);
+            let Some(next_page) = page.finish(cursor).unwrap() else {
+                break;
+            };
+            cursor = cursors
+                .take(cursor_id(next_page), "db", &TagFilters::default())
+                .expect("cursor stored");
+        }
+
+        assert_eq!(pages, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    }
+
+    #[tokio::test]
+    async fn test_exact_page() {
+        let cursors = Arc::new(CursorStore::new(CursorConfig::default()));
+        let mut cursor = make_cursor(&[1, 2]);
+
+        let (page, results) = Page::new(
+            Arc::clone(&cursors),
+            NonZeroUsize::new(2).unwrap(),
+            cursor.take_results(),
+        );
+        assert_eq!(values(results).await, vec![1, 2]);
+        assert!(page.finish(cursor).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_partial() {
+        let cursors = Arc::new(CursorStore::new(CursorConfig::default()));
+        let mut cursor = make_cursor(&[1, 2]);
+        cursor.partial.set();
+
+        let (page, results) = Page::new(
+            Arc::clone(&cursors),
+            NonZeroUsize::new(2).unwrap(),
+            cursor.take_results(),
+        );
+        assert_eq!(values(results).await, vec![1, 2]);
+        let last = page.finish(cursor).unwrap().expect("partial flag");
+        let app_metadata = proto::AppMetadata::decode(last.app_metadata).unwrap();
+        assert!(app_metadata.partial);
+        assert!(app_metadata.next_page_ticket.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expired() {
+        let cursors = Arc::new(CursorStore::new(CursorConfig {
+            ttl: Duration::ZERO,
+            ..Default::default()
+        }));
+        let mut cursor = make_cursor(&[1, 2]);
+
+        let (page, results) = Page::new(
+            Arc::clone(&cursors),
+            NonZeroUsize::new(1).unwrap(),
+            cursor.take_results(),
+        );
+        assert_eq!(values(results).await, vec![1]);
+        let id = cursor_id(page.finish(cursor).unwrap().expect("next page"));
+        assert!(cursors.take(id, "db", &TagFilters::default()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expiry_task() {
+        let cursors = Arc::new(CursorStore::new(CursorConfig {
+            ttl: Duration::ZERO,
+            ..Default::default()
+        }));
+        let mut cursor = make_cursor(&[1, 2]);
+        let (page, results) = Page::new(
+            Arc::clone(&cursors),
+            NonZeroUsize::new(1).unwrap(),
+            cursor.take_results(),
+        );
+        assert_eq!(values(results).await, vec![1]);
+        page.finish(cursor).unwrap().expect("next page");
+        assert_eq!(cursors.cursors.lock().len(), 1);
+
+        // abandoned cursors are dropped without using any other cursor
+        CursorStore::spawn_expiry_task(&cursors);
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !cursors.cursors.lock().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("cursor expired");
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit() {
+        let cursors = Arc::new(CursorStore::new(CursorConfig {
+            max_memory_bytes: 1,
+            ..Default::default()
+        }));
+        let mut cursor = make_cursor(&[1, 2]);
+
+        let (page, results) = Page::new(
+            Arc::clone(&cursors),
+            NonZeroUsize::new(1).unwrap(),
+            cursor.take_results(),
+        );
+        assert_eq!(values(results).await, vec![1]);
+        let err = page.finish(cursor).unwrap_err();
+        assert_matches!(err, crate::Error::CursorMemoryExhausted { limit: 1 });
+    }
+
+    /// A cursor yielding a batch per value
+    fn make_cursor(values: &[i64]) -> Cursor {
+        let batches: Vec<_> = values.iter().map(std::slice::from_ref).collect();
+        make_cursor_with_batches(&batches)
+    }
+
+    /// A cursor yielding a batch per slice of values
+    fn make_cursor_with_batches(values: &[&[i64]]) -> Cursor {
+        let batches: Vec<_> = values
+            .iter()
+            .map(|v| {
+                let array = Arc::new(Int64Array::from(v.to_vec())) as ArrayRef;
+                Ok(RecordBatch::try_from_iter([("v", array)]).unwrap())
+            })
+            .collect();
+        let schema = batches[0].as_ref().unwrap().schema();
+
+        Cursor {
+            database: "db".to_string(),
+            tag_filters: TagFilters::default(),
+            plan: Arc::new(EmptyExec::new(false, Arc::clone(&schema))),
+            schema,
+            app_metadata: proto::AppMetadata::default(),
+            results: futures::stream::iter(batches).boxed(),
+            size: 0,
+            query_completed_token: QueryCompletedToken::new(|_| {}),
+            partial: Default::default(),
+        }
+    }
+
+    async fn values(results: ResultStream) -> Vec<i64> {
+        let batches: Vec<_> = results.try_collect().await.unwrap();
+        batches
+            .iter()
+            .flat_map(|b| {
+                b.column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    fn cursor_id(next_page: FlightData) -> Uuid {
+        let app_metadata = proto::AppMetadata::decode(next_page.app_metadata).unwrap();
+        let ticket = arrow_flight::Ticket {
+            ticket: app_metadata.next_page_ticket.into(),
+        };
+        let request = IoxGetRequest::try_decode(ticket).unwrap();
+        assert_eq!(request.database(), "db");
+        match request.query() {
+            RunQuery::Cursor(id) => *id,
+            query => panic!("unexpected query: {query}"),
+        }
+    }
+}
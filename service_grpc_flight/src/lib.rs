@@ -16,16 +16,31 @@
     unused_crate_dependencies
 )]
 
+use cursor::{Cursor, CursorStore, Page};
 use keep_alive::KeepAliveStream;
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+mod cursor;
 mod keep_alive;
 mod request;
+mod result_limit;
 
-use arrow::error::ArrowError;
+pub use cursor::CursorConfig;
+pub use result_limit::ResultLimits;
+
+use arrow::{
+    array::{ArrayRef, AsArray},
+    datatypes::{
+        DataType, Fields, Schema, SchemaRef, TimeUnit, TimestampMicrosecondType,
+        TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
+    },
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
 use arrow_flight::{
     encode::FlightDataEncoderBuilder,
+    error::FlightError,
     flight_descriptor::DescriptorType,
     flight_service_server::{FlightService as Flight, FlightServiceServer as FlightServer},
     Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
@@ -37,14 +52,22 @@ use datafusion::{error::DataFusionError, physical_plan::ExecutionPlan};
 use flightsql::FlightSQLCommand;
 use futures::{ready, Stream, StreamExt, TryStreamExt};
 use generated_types::influxdata::iox::querier::v1 as proto;
-use iox_query::{exec::IOxSessionContext, QueryCompletedToken, QueryNamespace};
+use iox_query::{
+    config::{IoxConfigExt, TagFilter, TagFilters, TimestampPrecision},
+    exec::IOxSessionContext,
+    QueryCompletedToken, QueryNamespace,
+};
 use observability_deps::tracing::{debug, info, warn};
 use prost::Message;
 use request::{IoxGetRequest, RunQuery};
-use service_common::{datafusion_error_to_tonic_code, planner::Planner, QueryNamespaceProvider};
+use result_limit::limit_results;
+use service_common::{
+    datafusion_error_to_tonic_code, has_debug_header, planner::Planner, QueryNamespaceProvider,
+};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{
     fmt::Debug,
+    num::NonZeroUsize,
     pin::Pin,
     sync::Arc,
     task::Poll,
@@ -57,6 +80,7 @@ use tonic::{
 use trace::{ctx::SpanContext, span::SpanExt};
 use trace_http::ctx::{RequestLogContext, RequestLogContextExt};
 use tracker::InstrumentedAsyncOwnedSemaphorePermit;
+use uuid::Uuid;
 
 /// The supported names of the grpc header that contain the target database
 /// for FlightSQL requests.
@@ -70,6 +94,19 @@ const IOX_FLIGHT_SQL_DATABASE_HEADERS: [&str; 4] = [
     "iox-namespace-name", // deprecated
 ];
 
+/// The grpc header setting the page size of the tickets returned by
+/// FlightSQL `GetFlightInfo` requests, see [`FlightService`].
+const IOX_FLIGHT_SQL_PAGE_SIZE_HEADER: &str = "iox-page-size";
+
+/// The `DoAction` type of delete requests, which are rejected explicitly
+/// rather than failing to decode as a FlightSQL action.
+///
+/// Data is not deleted in place in this version: there are no delete
+/// predicates (tombstones) to apply to persisted data, so like the
+/// `/api/v2/delete` HTTP endpoint of the router these requests are
+/// answered with `Unimplemented`.
+const DELETE_ACTION_TYPE: &str = "Delete";
+
 /// In which interval should the `DoGet` stream send empty messages as keep alive markers?
 const DO_GET_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
 
@@ -114,6 +151,13 @@ pub enum Error {
         source: tonic::metadata::errors::ToStrError,
     },
 
+    #[snafu(display(
+        "Invalid '{}' header in request: expected a positive number of rows, got '{}'",
+        IOX_FLIGHT_SQL_PAGE_SIZE_HEADER,
+        value
+    ))]
+    InvalidPageSizeHeader { value: String },
+
     #[snafu(display("Invalid database name: {}", source))]
     InvalidDatabaseName { source: NamespaceNameError },
 
@@ -147,6 +191,22 @@ pub enum Error {
 
     #[snafu(display("Authz error: {}", source))]
     Authz { source: authz::Error },
+
+    #[snafu(display(
+        "Cursor {} not found. It may have expired, run the query again",
+        cursor_id
+    ))]
+    CursorNotFound { cursor_id: Uuid },
+
+    #[snafu(display(
+        "Too much memory used by the cursors of paginated queries (limit: {} bytes), \
+        retry later or without pagination",
+        limit
+    ))]
+    CursorMemoryExhausted { limit: usize },
+
+    #[snafu(display("deletes are not supported"))]
+    DeletesUnsupported,
 }
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -166,6 +226,10 @@ impl From<Error> for tonic::Status {
             | Error::Unauthenticated { .. }
             | Error::PermissionDenied { .. }
             | Error::InvalidDatabaseName { .. }
+            | Error::InvalidPageSizeHeader { .. }
+            | Error::CursorNotFound { .. }
+            | Error::CursorMemoryExhausted { .. }
+            | Error::DeletesUnsupported
             | Error::Query { .. } => info!(e=%err, %namespace, %query, msg),
             Error::Optimize { .. }
             | Error::EncodeSchema { .. }
@@ -192,18 +256,22 @@ impl Error {
         let msg = self.to_string();
 
         let code = match self {
-            Self::DatabaseNotFound { .. } => tonic::Code::NotFound,
+            Self::DatabaseNotFound { .. } | Self::CursorNotFound { .. } => tonic::Code::NotFound,
+            Self::CursorMemoryExhausted { .. } => tonic::Code::ResourceExhausted,
             Self::InvalidTicket { .. }
             | Self::InvalidHandshake { .. }
             | Self::Deserialization { .. }
             | Self::TooManyFlightSQLDatabases { .. }
             | Self::NoFlightSQLDatabase
             | Self::InvalidDatabaseHeader { .. }
+            | Self::InvalidPageSizeHeader { .. }
             | Self::InvalidDatabaseName { .. } => tonic::Code::InvalidArgument,
             Self::Planning { source, .. } | Self::Query { source, .. } => {
                 datafusion_error_to_tonic_code(&source)
             }
-            Self::UnsupportedMessageType { .. } => tonic::Code::Unimplemented,
+            Self::UnsupportedMessageType { .. } | Self::DeletesUnsupported => {
+                tonic::Code::Unimplemented
+            }
             Self::FlightSQL { source } => match source {
                 flightsql::Error::InvalidHandle { .. }
                 | flightsql::Error::Decode { .. }
@@ -237,6 +305,7 @@ impl Error {
             | Error::TooManyFlightSQLDatabases { .. }
             | Error::NoFlightSQLDatabase
             | Error::InvalidDatabaseHeader { .. }
+            | Error::InvalidPageSizeHeader { .. }
             | Error::InvalidDatabaseName { .. }
             | Error::Optimize { .. }
             | Error::EncodeSchema { .. }
@@ -245,7 +314,10 @@ impl Error {
             | Error::UnsupportedMessageType { .. }
             | Error::Unauthenticated
             | Error::PermissionDenied
-            | Error::Authz { .. } => "<unknown>",
+            | Error::Authz { .. }
+            | Error::CursorNotFound { .. }
+            | Error::CursorMemoryExhausted { .. }
+            | Error::DeletesUnsupported => "<unknown>",
             Error::DatabaseNotFound { namespace_name } => namespace_name,
             Error::Query { namespace_name, .. } => namespace_name,
             Error::Planning { namespace_name, .. } => namespace_name,
@@ -261,6 +333,7 @@ impl Error {
             | Error::TooManyFlightSQLDatabases { .. }
             | Error::NoFlightSQLDatabase
             | Error::InvalidDatabaseHeader { .. }
+            | Error::InvalidPageSizeHeader { .. }
             | Error::InvalidDatabaseName { .. }
             | Error::Optimize { .. }
             | Error::EncodeSchema { .. }
@@ -270,6 +343,9 @@ impl Error {
             | Error::Unauthenticated
             | Error::PermissionDenied
             | Error::Authz { .. }
+            | Error::CursorNotFound { .. }
+            | Error::CursorMemoryExhausted { .. }
+            | Error::DeletesUnsupported
             | Error::DatabaseNotFound { .. } => "NONE",
             Error::Query { query, .. } => query,
             Error::Planning { query, .. } => query,
@@ -461,6 +537,31 @@ type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send
 ///     7 ┃◀ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ┃
 /// ```
 ///
+/// # Pagination
+///
+/// Tickets of the native IOx API can set a page size (see
+/// [`IoxGetRequest`]). For FlightSQL, the page size of the tickets
+/// returned by `GetFlightInfo` is set with the `iox-page-size` header.
+///
+/// The `DoGet` response then contains at most that many rows. If there
+/// are more results, the last message of the response contains no data
+/// but the ticket for the next page in its `app_metadata`
+/// (`AppMetadata.next_page_ticket`).
+///
+/// The query keeps running on the server between pages, so all pages
+/// are read from the same state of the data. If the client does not
+/// fetch the next page within the TTL of the [`CursorConfig`], the query
+/// is cancelled and the ticket becomes invalid.
+///
+/// # Result Limits
+///
+/// Results exceeding the [`ResultLimits`] are truncated instead of
+/// failing the query. The last message of such a response contains no
+/// data but flags the results as partial in its `app_metadata`
+/// (`AppMetadata.partial`). The same flag is set if the query could not
+/// read all data but partial results are allowed, e.g. if a backend of
+/// a federated namespace failed.
+///
 /// [Arrow Flight]: https://arrow.apache.org/docs/format/Flight.html
 /// [Arrow FlightSQL]: https://arrow.apache.org/docs/format/FlightSql.html
 #[derive(Debug)]
@@ -470,16 +571,28 @@ where
 {
     server: Arc<S>,
     authz: Option<Arc<dyn Authorizer>>,
+    cursors: Arc<CursorStore>,
+    result_limits: ResultLimits,
 }
 
 pub fn make_server<S>(
     server: Arc<S>,
     authz: Option<Arc<dyn Authorizer>>,
+    cursor_config: CursorConfig,
+    result_limits: ResultLimits,
 ) -> FlightServer<impl Flight>
 where
     S: QueryNamespaceProvider,
 {
-    FlightServer::new(FlightService { server, authz })
+    let cursors = Arc::new(CursorStore::new(cursor_config));
+    CursorStore::spawn_expiry_task(&cursors);
+
+    FlightServer::new(FlightService {
+        server,
+        authz,
+        cursors,
+        result_limits,
+    })
 }
 
 impl<S> FlightService<S>
@@ -487,6 +600,7 @@ where
     S: QueryNamespaceProvider,
 {
     /// Implementation of the `DoGet` method
+    #[allow(clippy::too_many_arguments)]
     async fn run_do_get(
         &self,
         span_ctx: Option<SpanContext>,
@@ -495,6 +609,12 @@ where
         query: RunQuery,
         namespace_name: String,
         is_debug: bool,
+        as_of: Option<String>,
+        sample_fraction: Option<f64>,
+        last_value_cache: bool,
+        timestamp_precision: Option<String>,
+        tag_filters: TagFilters,
+        page_size: Option<NonZeroUsize>,
     ) -> Result<Response<TonicStream<FlightData>>, tonic::Status> {
         let db = self
             .server
@@ -509,9 +629,50 @@ where
             })?;
 
         let ctx = db.new_query_context(span_ctx);
+        if let Some(as_of) = &as_of {
+            ctx.set_config_option("iox.as_of", as_of)
+                .await
+                .context(QuerySnafu {
+                    namespace_name: &namespace_name,
+                    query: query.to_string(),
+                })?;
+        }
+        if let Some(sample_fraction) = sample_fraction {
+            ctx.set_config_option("iox.sample_fraction", &sample_fraction.to_string())
+                .await
+                .context(QuerySnafu {
+                    namespace_name: &namespace_name,
+                    query: query.to_string(),
+                })?;
+        }
+        if last_value_cache {
+            ctx.set_config_option("iox.last_value_cache", "true")
+                .await
+                .context(QuerySnafu {
+                    namespace_name: &namespace_name,
+                    query: query.to_string(),
+                })?;
+        }
+        if let Some(timestamp_precision) = &timestamp_precision {
+            ctx.set_config_option("iox.timestamp_precision", timestamp_precision)
+                .await
+                .context(QuerySnafu {
+                    namespace_name: &namespace_name,
+                    query: query.to_string(),
+                })?;
+        }
+        if !tag_filters.is_empty() {
+            ctx.set_config_option("iox.tag_filters", &tag_filters.to_string())
+                .await
+                .context(QuerySnafu {
+                    namespace_name: &namespace_name,
+                    query: query.to_string(),
+                })?;
+        }
         let (query_completed_token, physical_plan) = match &query {
             RunQuery::Sql(sql_query) => {
                 let token = db.record_query(
+                    &ctx,
                     external_span_ctx.as_ref().map(RequestLogContext::ctx),
                     "sql",
                     Box::new(sql_query.clone()),
@@ -527,6 +688,7 @@ where
             }
             RunQuery::InfluxQL(sql_query) => {
                 let token = db.record_query(
+                    &ctx,
                     external_span_ctx.as_ref().map(RequestLogContext::ctx),
                     "influxql",
                     Box::new(sql_query.clone()),
@@ -542,6 +704,7 @@ where
             }
             RunQuery::FlightSQL(msg) => {
                 let token = db.record_query(
+                    &ctx,
                     external_span_ctx.as_ref().map(RequestLogContext::ctx),
                     "flightsql",
                     Box::new(msg.to_string()),
@@ -555,37 +718,79 @@ where
                     })?;
                 (token, plan)
             }
+            RunQuery::Cursor(_) => unreachable!("cursors are resumed without planning"),
         };
 
-        let output = GetStream::new(
+        let cursor = execute(
             ctx,
             physical_plan,
             namespace_name.to_string(),
             &query,
             query_completed_token,
-            permit,
+            tag_filters,
+            self.result_limits,
         )
         .await?;
+        let output = GetStream::new(cursor, permit, page_size, &self.cursors);
 
-        // Log any error that happens *during* execution (other error
-        // handling in this file happen during planning)
-        let output = output.map(move |res| {
-            if let Err(e) = &res {
-                info!(
-                    %namespace_name,
-                    %query,
-                    trace=external_span_ctx.format_jaeger().as_str(),
-                    %e,
-                    "Error executing query via DoGet",
-                );
-            }
-            res
-        });
+        Ok(log_execution_errors(
+            output,
+            namespace_name,
+            query,
+            external_span_ctx,
+        ))
+    }
 
-        Ok(Response::new(Box::pin(output) as TonicStream<FlightData>))
+    /// Implementation of the `DoGet` method for the next page of a
+    /// paginated query
+    fn resume_cursor(
+        &self,
+        external_span_ctx: Option<RequestLogContext>,
+        permit: InstrumentedAsyncOwnedSemaphorePermit,
+        namespace_name: String,
+        cursor_id: Uuid,
+        tag_filters: TagFilters,
+        page_size: Option<NonZeroUsize>,
+    ) -> Result<Response<TonicStream<FlightData>>, tonic::Status> {
+        let cursor = self
+            .cursors
+            .take(cursor_id, &namespace_name, &tag_filters)
+            .context(CursorNotFoundSnafu { cursor_id })?;
+        let output = GetStream::new(cursor, permit, page_size, &self.cursors);
+
+        Ok(log_execution_errors(
+            output,
+            namespace_name,
+            RunQuery::Cursor(cursor_id),
+            external_span_ctx,
+        ))
     }
 }
 
+/// Log any error that happens *during* execution (other error
+/// handling in this file happen during planning)
+fn log_execution_errors(
+    output: GetStream,
+    namespace_name: String,
+    query: RunQuery,
+    external_span_ctx: Option<RequestLogContext>,
+) -> Response<TonicStream<FlightData>> {
+    let output = output.map(move |res| {
+        if let Err(e) = &res {
+            info!(
+                %namespace_name,
+                %query,
+                trace=external_span_ctx.format_jaeger().as_str(),
+                %e,
+                "Error executing query via DoGet",
+            );
+        }
+        res
+    });
+
+    Response::new(Box::pin(output) as TonicStream<FlightData>)
+}
+
 #[tonic::async_trait]
 impl<S> Flight for FlightService<S>
 where
@@ -632,16 +837,29 @@ where
 
         let perms = match query {
             RunQuery::FlightSQL(cmd) => flightsql_permissions(namespace_name, cmd),
-            RunQuery::Sql(_) | RunQuery::InfluxQL(_) => vec![authz::Permission::ResourceAction(
-                authz::Resource::Database(namespace_name.to_string()),
-                authz::Action::Read,
-            )],
+            RunQuery::Sql(_) | RunQuery::InfluxQL(_) | RunQuery::Cursor(_) => {
+                vec![authz::Permission::ResourceAction(
+                    authz::Resource::Database(namespace_name.to_string()),
+                    authz::Action::Read,
+                )]
+            }
         };
-        self.authz
+        let granted = self
+            .authz
             .permissions(authz_token, &perms)
             .await
             .map_err(Error::from)?;
 
+        // row-level restrictions of the token
+        let tag_filters = TagFilters::new(
+            authz::tag_filter_policies(&granted, namespace_name)
+                .into_iter()
+                .map(|policy| TagFilter {
+                    tag: policy.tag,
+                    values: policy.values,
+                }),
+        );
+
         let permit = self
             .server
             .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
@@ -657,16 +875,33 @@ where
             "DoGet request",
         );
 
-        let response = self
-            .run_do_get(
-                span_ctx,
+        let response = match query {
+            RunQuery::Cursor(cursor_id) => self.resume_cursor(
                 external_span_ctx.clone(),
                 permit,
-                query.clone(),
                 namespace_name.to_string(),
-                is_debug,
-            )
-            .await;
+                *cursor_id,
+                tag_filters,
+                request.page_size(),
+            ),
+            _ => {
+                self.run_do_get(
+                    span_ctx,
+                    external_span_ctx.clone(),
+                    permit,
+                    query.clone(),
+                    namespace_name.to_string(),
+                    is_debug,
+                    request.as_of().map(ToString::to_string),
+                    request.sample_fraction(),
+                    request.last_value_cache(),
+                    request.timestamp_precision().map(ToString::to_string),
+                    tag_filters,
+                    request.page_size(),
+                )
+                .await
+            }
+        };
 
         if let Err(e) = &response {
             info!(
@@ -753,6 +988,7 @@ where
         let is_debug = has_debug_header(request.metadata());
 
         let namespace_name = get_flightsql_namespace(request.metadata())?;
+        let page_size = get_flightsql_page_size(request.metadata())?;
         let authz_token = get_flight_authz(request.metadata());
         let flight_descriptor = request.into_inner();
 
@@ -796,6 +1032,7 @@ where
 
         // Form the response ticket (that the client will pass back to DoGet)
         let ticket = IoxGetRequest::new(&namespace_name, RunQuery::FlightSQL(cmd), is_debug)
+            .with_page_size(page_size)
             .try_encode()
             .context(InternalCreatingTicketSnafu)?;
 
@@ -836,6 +1073,11 @@ where
             body,
         } = request.into_inner();
 
+        if action_type == DELETE_ACTION_TYPE {
+            info!(%namespace_name, %action_type, %trace, "rejecting delete DoAction request");
+            return Err(Error::DeletesUnsupported.into());
+        }
+
         // extract the FlightSQL message
         let cmd = FlightSQLCommand::try_decode(body).context(FlightSQLSnafu)?;
 
@@ -946,6 +1188,21 @@ fn get_flightsql_namespace(metadata: &MetadataMap) -> Result<String> {
     Ok(database_name.context(NoFlightSQLDatabaseSnafu)?.to_string())
 }
 
+/// The page size set by the `iox-page-size` header, if any.
+fn get_flightsql_page_size(metadata: &MetadataMap) -> Result<Option<NonZeroUsize>> {
+    let Some(v) = metadata.get(IOX_FLIGHT_SQL_PAGE_SIZE_HEADER) else {
+        return Ok(None);
+    };
+    let value = String::from_utf8_lossy(v.as_bytes());
+
+    value
+        .parse::<NonZeroUsize>()
+        .map(Some)
+        .map_err(|_| Error::InvalidPageSizeHeader {
+            value: value.into_owned(),
+        })
+}
+
 /// Retrieve the authorization token associated with the request.
 fn get_flight_authz(metadata: &MetadataMap) -> Option<Vec<u8>> {
     extract_token(metadata.get("authorization"))
@@ -972,14 +1229,82 @@ fn flightsql_permissions(namespace_name: &str, cmd: &FlightSQLCommand) -> Vec<au
     vec![authz::Permission::ResourceAction(resource, action)]
 }
 
-/// Check if request has IOx debug header set.
-fn has_debug_header(metadata: &MetadataMap) -> bool {
-    metadata
-        .get("iox-debug")
-        .and_then(|s| s.to_str().ok())
-        .map(|s| s.to_lowercase())
-        .map(|s| matches!(s.as_str(), "1" | "on" | "yes" | "y" | "true" | "t"))
-        .unwrap_or_default()
+/// Execute `physical_plan`, returning the running query.
+async fn execute(
+    ctx: IOxSessionContext,
+    physical_plan: Arc<dyn ExecutionPlan>,
+    namespace_name: String,
+    query: &RunQuery,
+    query_completed_token: QueryCompletedToken,
+    tag_filters: TagFilters,
+    result_limits: ResultLimits,
+) -> Result<Cursor, tonic::Status> {
+    // flag sampled (i.e. approximate) results
+    let sample_fraction = ctx
+        .inner()
+        .state()
+        .config()
+        .options()
+        .extensions
+        .get::<IoxConfigExt>()
+        .map(|iox_config| iox_config.sample_fraction)
+        .filter(|sample_fraction| *sample_fraction < 1.0)
+        .unwrap_or_default();
+    let app_metadata = proto::AppMetadata {
+        sample_fraction,
+        next_page_ticket: vec![],
+        partial: false,
+    };
+
+    let timestamp_precision = ctx
+        .inner()
+        .state()
+        .config()
+        .options()
+        .extensions
+        .get::<IoxConfigExt>()
+        .map(|iox_config| iox_config.timestamp_precision)
+        .unwrap_or(TimestampPrecision::Nanosecond);
+    let schema = timestamp_precision_schema(&physical_plan.schema(), timestamp_precision);
+
+    let query_results = ctx
+        .execute_stream(Arc::clone(&physical_plan))
+        .await
+        .context(QuerySnafu {
+            namespace_name: namespace_name.clone(),
+            query: query.to_string(),
+        })?
+        .map_err(|e| {
+            let code = datafusion_error_to_tonic_code(&e);
+            tonic::Status::new(code, e.to_string()).into()
+        });
+
+    // convert the timestamps to the requested precision
+    let query_results = {
+        let schema = Arc::clone(&schema);
+        query_results.map(move |res| {
+            res.and_then(|batch| {
+                convert_timestamp_precision(batch, &schema, timestamp_precision)
+                    .map_err(FlightError::Arrow)
+            })
+        })
+    };
+
+    // truncate results exceeding the limits
+    let partial = ctx.partial_results();
+    let query_results = limit_results(query_results.boxed(), result_limits, partial.clone());
+
+    Ok(Cursor {
+        database: namespace_name,
+        tag_filters,
+        plan: physical_plan,
+        schema,
+        app_metadata,
+        results: query_results,
+        size: 0,
+        query_completed_token,
+        partial,
+    })
 }
 
 /// Wrapper over a FlightDataEncodeStream that adds IOx specfic
@@ -988,50 +1313,136 @@ struct GetStream {
     inner: KeepAliveStream,
     #[allow(dead_code)]
     permit: InstrumentedAsyncOwnedSemaphorePermit,
-    query_completed_token: QueryCompletedToken,
+    /// The running query, whose results are read by `inner`
+    cursor: Option<Cursor>,
+    /// Set for paginated responses
+    page: Option<Page>,
     done: bool,
 }
 
 impl GetStream {
-    async fn new(
-        ctx: IOxSessionContext,
-        physical_plan: Arc<dyn ExecutionPlan>,
-        namespace_name: String,
-        query: &RunQuery,
-        query_completed_token: QueryCompletedToken,
+    /// Stream the results of `cursor`, or only the first `page_size`
+    /// rows if set.
+    fn new(
+        mut cursor: Cursor,
         permit: InstrumentedAsyncOwnedSemaphorePermit,
-    ) -> Result<Self, tonic::Status> {
-        let app_metadata = proto::AppMetadata {};
-
-        let schema = physical_plan.schema();
-
-        let query_results = ctx
-            .execute_stream(Arc::clone(&physical_plan))
-            .await
-            .context(QuerySnafu {
-                namespace_name: namespace_name.clone(),
-                query: query.to_string(),
-            })?
-            .map_err(|e| {
-                let code = datafusion_error_to_tonic_code(&e);
-                tonic::Status::new(code, e.to_string()).into()
-            });
+        page_size: Option<NonZeroUsize>,
+        cursors: &Arc<CursorStore>,
+    ) -> Self {
+        let results = cursor.take_results();
+        let (page, results) = match page_size {
+            Some(page_size) => {
+                let (page, results) = Page::new(Arc::clone(cursors), page_size, results);
+                (Some(page), results)
+            }
+            None => (None, results),
+        };
 
         // setup inner stream
         let inner = FlightDataEncoderBuilder::new()
-            .with_schema(schema)
-            .with_metadata(app_metadata.encode_to_vec().into())
-            .build(query_results);
+            .with_schema(Arc::clone(&cursor.schema))
+            .with_metadata(cursor.app_metadata.encode_to_vec().into())
+            .build(results);
 
         // add keep alive
         let inner = KeepAliveStream::new(inner, DO_GET_KEEP_ALIVE_INTERVAL);
 
-        Ok(Self {
+        Self {
             inner,
             permit,
-            query_completed_token,
+            cursor: Some(cursor),
+            page,
             done: false,
+        }
+    }
+
+    /// Called once all results were sent. Returns the message with the
+    /// ticket of the next page or flagging partial results, if there is
+    /// one.
+    fn finish(&mut self) -> Result<Option<FlightData>> {
+        let cursor = self.cursor.take().expect("finish is only called once");
+        match self.page.take() {
+            Some(page) => page.finish(cursor),
+            // if we get here, all is good
+            None => Ok(cursor.complete()),
+        }
+    }
+}
+
+/// Convert the nanosecond timestamp fields of `schema` to `precision`.
+fn timestamp_precision_schema(schema: &SchemaRef, precision: TimestampPrecision) -> SchemaRef {
+    if precision == TimestampPrecision::Nanosecond {
+        return Arc::clone(schema);
+    }
+
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::Timestamp(TimeUnit::Nanosecond, tz) => Arc::new(
+                field
+                    .as_ref()
+                    .clone()
+                    .with_data_type(DataType::Timestamp(time_unit(precision), tz.clone())),
+            ),
+            _ => Arc::clone(field),
+        })
+        .collect::<Fields>();
+    Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+/// Convert the nanosecond timestamp columns of `batch` to `precision`, truncating the
+/// timestamps towards negative infinity. `schema` is the converted schema, see
+/// [`timestamp_precision_schema`].
+fn convert_timestamp_precision(
+    batch: RecordBatch,
+    schema: &SchemaRef,
+    precision: TimestampPrecision,
+) -> Result<RecordBatch, ArrowError> {
+    if precision == TimestampPrecision::Nanosecond {
+        return Ok(batch);
+    }
+
+    let nanos_per_unit = precision.nanos_per_unit();
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|array| match array.data_type() {
+            DataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+                let timestamps = array.as_primitive::<TimestampNanosecondType>();
+                let convert = |ts: i64| ts.div_euclid(nanos_per_unit);
+                match precision {
+                    TimestampPrecision::Second => Arc::new(
+                        timestamps
+                            .unary::<_, TimestampSecondType>(convert)
+                            .with_timezone_opt(tz.clone()),
+                    ) as ArrayRef,
+                    TimestampPrecision::Millisecond => Arc::new(
+                        timestamps
+                            .unary::<_, TimestampMillisecondType>(convert)
+                            .with_timezone_opt(tz.clone()),
+                    ),
+                    TimestampPrecision::Microsecond => Arc::new(
+                        timestamps
+                            .unary::<_, TimestampMicrosecondType>(convert)
+                            .with_timezone_opt(tz.clone()),
+                    ),
+                    TimestampPrecision::Nanosecond => Arc::clone(array),
+                }
+            }
+            _ => Arc::clone(array),
         })
+        .collect();
+
+    RecordBatch::try_new(Arc::clone(schema), columns)
+}
+
+fn time_unit(precision: TimestampPrecision) -> TimeUnit {
+    match precision {
+        TimestampPrecision::Second => TimeUnit::Second,
+        TimestampPrecision::Millisecond => TimeUnit::Millisecond,
+        TimestampPrecision::Microsecond => TimeUnit::Microsecond,
+        TimestampPrecision::Nanosecond => TimeUnit::Nanosecond,
     }
 }
 
@@ -1051,8 +1462,11 @@ impl Stream for GetStream {
             match res {
                 None => {
                     self.done = true;
-                    // if we get here, all is good
-                    self.query_completed_token.set_success();
+                    match self.finish() {
+                        Ok(None) => {}
+                        Ok(Some(next_page)) => return Poll::Ready(Some(Ok(next_page))),
+                        Err(e) => return Poll::Ready(Some(Err(e.into()))),
+                    }
                 }
                 Some(Ok(data)) => {
                     return Poll::Ready(Some(Ok(data)));
@@ -1067,7 +1481,10 @@ impl Stream for GetStream {
 }
 #[cfg(test)]
 mod tests {
-    use arrow_flight::sql::ProstMessageExt;
+    use arrow_flight::{
+        decode::{DecodedPayload, FlightDataDecoder},
+        sql::ProstMessageExt,
+    };
     use async_trait::async_trait;
     use authz::Permission;
     use futures::Future;
@@ -1105,6 +1522,8 @@ mod tests {
         let service = FlightService {
             server: Arc::clone(&test_storage),
             authz: Option::<Arc<dyn Authorizer>>::None,
+            cursors: Arc::new(CursorStore::new(CursorConfig::default())),
+            result_limits: ResultLimits::default(),
         };
         let ticket = Ticket {
             ticket: br#"{"namespace_name": "my_db", "sql_query": "SELECT 1;"}"#
@@ -1260,6 +1679,73 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_do_action_delete_unsupported() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        test_storage.db_or_create("bananas").await;
+
+        let service = FlightService {
+            server: Arc::clone(&test_storage),
+            authz: Option::<Arc<dyn Authorizer>>::None,
+            cursors: Arc::new(CursorStore::new(CursorConfig::default())),
+            result_limits: ResultLimits::default(),
+        };
+
+        let mut req = tonic::Request::new(Action {
+            r#type: DELETE_ACTION_TYPE.to_string(),
+            body: br#"{"start": "1970-01-01T00:00:00Z", "stop": "2070-01-01T00:00:00Z"}"#
+                .to_vec()
+                .into(),
+        });
+        req.metadata_mut().insert(
+            MetadataKey::from_static("database"),
+            MetadataValue::from_static("bananas"),
+        );
+
+        let status = match service.do_action(req).await {
+            Ok(_) => panic!("delete action unexpectedly succeeded"),
+            Err(e) => e,
+        };
+        assert_eq!(status.code(), tonic::Code::Unimplemented);
+        assert_eq!(status.message(), "deletes are not supported");
+    }
+
+    #[test]
+    fn test_convert_timestamp_precision() {
+        use arrow::array::{Int64Array, TimestampNanosecondArray, TimestampSecondArray};
+
+        let time = TimestampNanosecondArray::from(vec![-1, 0, 1_999_999_999])
+            .with_timezone_opt(Some("UTC"));
+        let value = Int64Array::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_from_iter([
+            ("time", Arc::new(time) as ArrayRef),
+            ("value", Arc::new(value) as ArrayRef),
+        ])
+        .unwrap();
+
+        let schema = timestamp_precision_schema(&batch.schema(), TimestampPrecision::Nanosecond);
+        assert_eq!(schema, batch.schema());
+        let converted =
+            convert_timestamp_precision(batch.clone(), &schema, TimestampPrecision::Nanosecond)
+                .unwrap();
+        assert_eq!(converted, batch);
+
+        let schema = timestamp_precision_schema(&batch.schema(), TimestampPrecision::Second);
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Second, Some("UTC".into()))
+        );
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+        let converted =
+            convert_timestamp_precision(batch.clone(), &schema, TimestampPrecision::Second)
+                .unwrap();
+        assert_eq!(
+            converted.column(0).as_primitive::<TimestampSecondType>(),
+            &TimestampSecondArray::from(vec![-1, 0, 1]).with_timezone_opt(Some("UTC"))
+        );
+        assert_eq!(converted.column(1), batch.column(1));
+    }
+
     #[tokio::test]
     async fn do_get_authz() {
         let test_storage = Arc::new(TestDatabaseStore::default());
@@ -1268,6 +1754,8 @@ mod tests {
         let svc = FlightService {
             server: Arc::clone(&test_storage),
             authz: Some(Arc::new(MockAuthorizer {})),
+            cursors: Arc::new(CursorStore::new(CursorConfig::default())),
+            result_limits: ResultLimits::default(),
         };
 
         async fn assert_code(
@@ -1368,6 +1856,72 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn do_get_paginated() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        test_storage.db_or_create("my_db").await;
+
+        let svc = FlightService {
+            server: Arc::clone(&test_storage),
+            authz: Option::<Arc<dyn Authorizer>>::None,
+            cursors: Arc::new(CursorStore::new(CursorConfig::default())),
+            result_limits: ResultLimits::default(),
+        };
+
+        /// Returns the number of rows and the ticket of the next page
+        async fn read_page(
+            svc: &FlightService<TestDatabaseStore>,
+            ticket: Ticket,
+        ) -> (usize, Option<Ticket>) {
+            let stream = svc
+                .do_get(tonic::Request::new(ticket))
+                .await
+                .unwrap()
+                .into_inner();
+            let mut decoder = FlightDataDecoder::new(stream.map_err(FlightError::Tonic));
+
+            let mut rows = 0;
+            let mut next_page = None;
+            while let Some(data) = decoder.try_next().await.unwrap() {
+                if let DecodedPayload::RecordBatch(batch) = &data.payload {
+                    rows += batch.num_rows();
+                }
+                let app_metadata = proto::AppMetadata::decode(data.inner.app_metadata).unwrap();
+                if !app_metadata.next_page_ticket.is_empty() {
+                    assert!(next_page.is_none());
+                    next_page = Some(Ticket {
+                        ticket: app_metadata.next_page_ticket.into(),
+                    });
+                }
+            }
+            (rows, next_page)
+        }
+
+        // each SELECT is a separate partition, resulting in a batch each
+        let ticket = IoxGetRequest::new(
+            "my_db",
+            RunQuery::Sql("SELECT 1 AS x UNION ALL SELECT 2 UNION ALL SELECT 3".to_string()),
+            false,
+        )
+        .with_page_size(NonZeroUsize::new(2))
+        .try_encode()
+        .unwrap();
+
+        let (rows, next_page) = read_page(&svc, ticket).await;
+        assert_eq!(rows, 2);
+        let next_page = next_page.expect("should have a next page");
+
+        let (rows, last_page) = read_page(&svc, next_page.clone()).await;
+        assert_eq!(rows, 1);
+        assert!(last_page.is_none());
+
+        // the cursor is gone after the last page
+        let Err(e) = svc.do_get(tonic::Request::new(next_page)).await else {
+            panic!("cursor should be gone");
+        };
+        assert_eq!(e.code(), tonic::Code::NotFound);
+    }
+
     #[tokio::test]
     async fn get_flight_info_authz() {
         let test_storage = Arc::new(TestDatabaseStore::default());
@@ -1376,6 +1930,8 @@ mod tests {
         let svc = FlightService {
             server: Arc::clone(&test_storage),
             authz: Some(Arc::new(MockAuthorizer {})),
+            cursors: Arc::new(CursorStore::new(CursorConfig::default())),
+            result_limits: ResultLimits::default(),
         };
 
         async fn assert_code(
@@ -1412,4 +1968,42 @@ mod tests {
         assert_code(&svc, tonic::Code::PermissionDenied, request("Bearer BAD")).await;
         assert_code(&svc, tonic::Code::Internal, request("Bearer UGLY")).await;
     }
+
+    #[tokio::test]
+    async fn get_flight_info_page_size() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        test_storage.db_or_create("bananas").await;
+
+        let svc = FlightService {
+            server: Arc::clone(&test_storage),
+            authz: None,
+            cursors: Arc::new(CursorStore::new(CursorConfig::default())),
+            result_limits: ResultLimits::default(),
+        };
+
+        fn request(page_size: &'static str) -> tonic::Request<FlightDescriptor> {
+            let cmd = arrow_flight::sql::CommandGetCatalogs {};
+            let mut req =
+                tonic::Request::new(FlightDescriptor::new_cmd(cmd.as_any().encode_to_vec()));
+            req.metadata_mut().insert(
+                MetadataKey::from_static("database"),
+                MetadataValue::from_static("bananas"),
+            );
+            req.metadata_mut().insert(
+                MetadataKey::from_static(IOX_FLIGHT_SQL_PAGE_SIZE_HEADER),
+                MetadataValue::from_static(page_size),
+            );
+            req
+        }
+
+        let flight_info = svc.get_flight_info(request("100")).await.unwrap();
+        let ticket = flight_info.into_inner().endpoint[0].ticket.clone().unwrap();
+        let get_request = IoxGetRequest::try_decode(ticket).unwrap();
+        assert_eq!(get_request.page_size(), NonZeroUsize::new(100));
+
+        for invalid in ["0", "-1", "many"] {
+            let err = svc.get_flight_info(request(invalid)).await.unwrap_err();
+            assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        }
+    }
 }
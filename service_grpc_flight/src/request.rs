@@ -10,7 +10,11 @@ use observability_deps::tracing::trace;
 use prost::Message;
 use serde::Deserialize;
 use snafu::{ResultExt, Snafu};
-use std::fmt::{Debug, Display, Formatter};
+use std::{
+    fmt::{Debug, Display, Formatter},
+    num::NonZeroUsize,
+};
+use uuid::Uuid;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -95,6 +99,19 @@ pub struct IoxGetRequest {
     database: String,
     query: RunQuery,
     is_debug: bool,
+    /// Catalog state to query, see `iox.as_of` in [`IoxConfigExt`](iox_query::config::IoxConfigExt).
+    as_of: Option<String>,
+    /// Fraction of rows to sample, see `iox.sample_fraction` in
+    /// [`IoxConfigExt`](iox_query::config::IoxConfigExt).
+    sample_fraction: Option<f64>,
+    /// Read the ingester last value caches, see `iox.last_value_cache` in
+    /// [`IoxConfigExt`](iox_query::config::IoxConfigExt).
+    last_value_cache: bool,
+    /// Precision of the returned timestamps, see `iox.timestamp_precision` in
+    /// [`IoxConfigExt`](iox_query::config::IoxConfigExt).
+    timestamp_precision: Option<String>,
+    /// Number of rows per page of paginated responses, see [`FlightService`](crate::FlightService).
+    page_size: Option<NonZeroUsize>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -107,6 +124,9 @@ pub enum RunQuery {
     /// FlightSQL Command*. message that was received at the
     /// get_flight_info endpoint
     FlightSQL(FlightSQLCommand),
+    /// Fetch the next page of a paginated query from the server-side
+    /// cursor with this ID
+    Cursor(Uuid),
 }
 
 impl RunQuery {
@@ -115,6 +135,7 @@ impl RunQuery {
             Self::Sql(_) => "sql",
             Self::InfluxQL(_) => "influxql",
             Self::FlightSQL(_) => "flightsql",
+            Self::Cursor(_) => "cursor",
         }
     }
 }
@@ -125,6 +146,7 @@ impl Display for RunQuery {
             Self::Sql(s) => Display::fmt(s, f),
             Self::InfluxQL(s) => Display::fmt(s, f),
             Self::FlightSQL(s) => Display::fmt(s, f),
+            Self::Cursor(id) => write!(f, "cursor {id}"),
         }
     }
 }
@@ -138,9 +160,49 @@ impl IoxGetRequest {
             database: database.into(),
             query,
             is_debug,
+            as_of: None,
+            sample_fraction: None,
+            last_value_cache: false,
+            timestamp_precision: None,
+            page_size: None,
         }
     }
 
+    /// Query the catalog state at the given time (RFC3339) instead of the latest state.
+    pub fn with_as_of(self, as_of: Option<String>) -> Self {
+        Self { as_of, ..self }
+    }
+
+    /// Only read a random sample of this fraction of the rows, returning approximate results.
+    pub fn with_sample_fraction(self, sample_fraction: Option<f64>) -> Self {
+        Self {
+            sample_fraction,
+            ..self
+        }
+    }
+
+    /// Read the last values of every series from the ingester last value caches instead of the
+    /// stored data.
+    pub fn with_last_value_cache(self, last_value_cache: bool) -> Self {
+        Self {
+            last_value_cache,
+            ..self
+        }
+    }
+
+    /// Return timestamps in the given precision (`s`, `ms`, `us` or `ns`) instead of nanoseconds.
+    pub fn with_timestamp_precision(self, timestamp_precision: Option<String>) -> Self {
+        Self {
+            timestamp_precision,
+            ..self
+        }
+    }
+
+    /// Paginate the results: return at most this many record batches per page.
+    pub fn with_page_size(self, page_size: Option<NonZeroUsize>) -> Self {
+        Self { page_size, ..self }
+    }
+
     /// try to decode a ReadInfo structure from a Token
     pub fn try_decode(ticket: Ticket) -> Result<Self> {
         // decode ticket
@@ -174,7 +236,16 @@ impl IoxGetRequest {
             database,
             query,
             is_debug,
+            as_of,
+            sample_fraction,
+            last_value_cache,
+            timestamp_precision,
+            page_size,
         } = self;
+        let as_of = as_of.unwrap_or_default();
+        let timestamp_precision = timestamp_precision.unwrap_or_default();
+        let sample_fraction = sample_fraction.unwrap_or_default();
+        let page_size = page_size.map(|n| n.get() as u64).unwrap_or_default();
 
         let read_info = match query {
             RunQuery::Sql(sql_query) => proto::ReadInfo {
@@ -183,6 +254,12 @@ impl IoxGetRequest {
                 query_type: QueryType::Sql.into(),
                 flightsql_command: vec![],
                 is_debug,
+                as_of,
+                sample_fraction,
+                last_value_cache,
+                timestamp_precision,
+                page_size,
+                cursor: "".into(),
             },
             RunQuery::InfluxQL(influxql) => proto::ReadInfo {
                 database,
@@ -191,6 +268,12 @@ impl IoxGetRequest {
                 query_type: QueryType::InfluxQl.into(),
                 flightsql_command: vec![],
                 is_debug,
+                as_of,
+                sample_fraction,
+                last_value_cache,
+                timestamp_precision,
+                page_size,
+                cursor: "".into(),
             },
             RunQuery::FlightSQL(flightsql_command) => proto::ReadInfo {
                 database,
@@ -201,6 +284,25 @@ impl IoxGetRequest {
                     .context(FlightSQLSnafu)?
                     .into(),
                 is_debug,
+                as_of,
+                sample_fraction,
+                last_value_cache,
+                timestamp_precision,
+                page_size,
+                cursor: "".into(),
+            },
+            RunQuery::Cursor(cursor) => proto::ReadInfo {
+                database,
+                sql_query: "".into(),
+                query_type: QueryType::Cursor.into(),
+                flightsql_command: vec![],
+                is_debug,
+                as_of,
+                sample_fraction,
+                last_value_cache,
+                timestamp_precision,
+                page_size,
+                cursor: cursor.to_string(),
             },
         };
 
@@ -229,6 +331,16 @@ impl IoxGetRequest {
             query_type: Option<String>,
             #[serde(default = "Default::default")]
             is_debug: bool,
+            #[serde(default = "Default::default")]
+            as_of: Option<String>,
+            #[serde(default = "Default::default")]
+            sample_fraction: Option<f64>,
+            #[serde(default = "Default::default")]
+            last_value_cache: bool,
+            #[serde(default = "Default::default")]
+            timestamp_precision: Option<String>,
+            #[serde(default = "Default::default")]
+            page_size: Option<usize>,
         }
 
         let ReadInfoJson {
@@ -236,6 +348,11 @@ impl IoxGetRequest {
             sql_query,
             query_type,
             is_debug,
+            as_of,
+            sample_fraction,
+            last_value_cache,
+            timestamp_precision,
+            page_size,
         } = serde_json::from_str(&json_str).map_err(|e| format!("JSON parse error: {e}"))?;
 
         let query = if let Some(query_type) = query_type {
@@ -257,6 +374,12 @@ impl IoxGetRequest {
             database,
             query,
             is_debug,
+            as_of: as_of.filter(|s| !s.is_empty()),
+            sample_fraction: check_sample_fraction(sample_fraction.unwrap_or_default())
+                .map_err(ToString::to_string)?,
+            last_value_cache,
+            timestamp_precision: timestamp_precision.filter(|s| !s.is_empty()),
+            page_size: page_size.and_then(NonZeroUsize::new),
         })
     }
 
@@ -284,7 +407,17 @@ impl IoxGetRequest {
             query_type: _,
             flightsql_command,
             is_debug,
+            as_of,
+            sample_fraction,
+            last_value_cache,
+            timestamp_precision,
+            page_size,
+            cursor,
         } = read_info;
+        let sample_fraction = match check_sample_fraction(sample_fraction) {
+            Ok(sample_fraction) => sample_fraction,
+            Err(msg) => return InvalidContentSnafu { msg }.fail(),
+        };
 
         Ok(Self {
             database,
@@ -318,8 +451,28 @@ impl IoxGetRequest {
                         .context(FlightSQLSnafu)?;
                     RunQuery::FlightSQL(cmd)
                 }
+                QueryType::Cursor => {
+                    if !sql_query.is_empty() || !flightsql_command.is_empty() {
+                        return InvalidContentSnafu {
+                            msg: "QueryType::Cursor contained non empty sql_query or flightsql_command",
+                        }
+                        .fail();
+                    }
+                    let Ok(cursor) = Uuid::parse_str(&cursor) else {
+                        return InvalidContentSnafu {
+                            msg: "QueryType::Cursor contained invalid cursor",
+                        }
+                        .fail();
+                    };
+                    RunQuery::Cursor(cursor)
+                }
             },
             is_debug,
+            as_of: (!as_of.is_empty()).then_some(as_of),
+            sample_fraction,
+            last_value_cache,
+            timestamp_precision: (!timestamp_precision.is_empty()).then_some(timestamp_precision),
+            page_size: usize::try_from(page_size).ok().and_then(NonZeroUsize::new),
         })
     }
 
@@ -334,6 +487,37 @@ impl IoxGetRequest {
     pub fn is_debug(&self) -> bool {
         self.is_debug
     }
+
+    pub fn as_of(&self) -> Option<&str> {
+        self.as_of.as_deref()
+    }
+
+    pub fn sample_fraction(&self) -> Option<f64> {
+        self.sample_fraction
+    }
+
+    pub fn last_value_cache(&self) -> bool {
+        self.last_value_cache
+    }
+
+    pub fn timestamp_precision(&self) -> Option<&str> {
+        self.timestamp_precision.as_deref()
+    }
+
+    pub fn page_size(&self) -> Option<NonZeroUsize> {
+        self.page_size
+    }
+}
+
+/// Validate a sample fraction read from a ticket, where `0` means "no sampling".
+fn check_sample_fraction(sample_fraction: f64) -> Result<Option<f64>, &'static str> {
+    if sample_fraction == 0.0 {
+        Ok(None)
+    } else if sample_fraction > 0.0 && sample_fraction <= 1.0 {
+        Ok(Some(sample_fraction))
+    } else {
+        Err("sample_fraction must be between 0 and 1")
+    }
 }
 
 #[cfg(test)]
@@ -374,6 +558,11 @@ mod tests {
                         database: String::from(expected_database),
                         query: RunQuery::Sql(String::from(query)),
                         is_debug: false,
+                        as_of: None,
+                        sample_fraction: None,
+                        last_value_cache: false,
+                        timestamp_precision: None,
+                        page_size: None,
                     },
                 }
             }
@@ -385,6 +574,11 @@ mod tests {
                         database: String::from(expected_database),
                         query: RunQuery::InfluxQL(String::from(query)),
                         is_debug: false,
+                        as_of: None,
+                        sample_fraction: None,
+                        last_value_cache: false,
+                        timestamp_precision: None,
+                        page_size: None,
                     },
                 }
             }
@@ -564,6 +758,12 @@ mod tests {
             query_type: QueryType::Unspecified.into(),
             flightsql_command: vec![],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         // Reverts to default (unspecified) for invalid query_type enumeration, and thus SQL
@@ -580,6 +780,12 @@ mod tests {
             query_type: QueryType::Sql.into(),
             flightsql_command: vec![],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let ri = IoxGetRequest::try_decode(ticket).unwrap();
@@ -595,6 +801,12 @@ mod tests {
             query_type: QueryType::InfluxQl.into(),
             flightsql_command: vec![],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let ri = IoxGetRequest::try_decode(ticket).unwrap();
@@ -610,6 +822,12 @@ mod tests {
             query_type: 42, // not a known query type
             flightsql_command: vec![],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         // Reverts to default (unspecified) for invalid query_type enumeration, and thus SQL
@@ -627,6 +845,12 @@ mod tests {
             // can't have both sql_query and flightsql
             flightsql_command: vec![1, 2, 3],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let e = IoxGetRequest::try_decode(ticket).unwrap_err();
@@ -642,6 +866,12 @@ mod tests {
             // can't have both sql_query and flightsql
             flightsql_command: vec![1, 2, 3],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let e = IoxGetRequest::try_decode(ticket).unwrap_err();
@@ -657,6 +887,12 @@ mod tests {
             // can't have both sql_query and flightsql
             flightsql_command: vec![1, 2, 3],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let e = IoxGetRequest::try_decode(ticket).unwrap_err();
@@ -682,6 +918,12 @@ mod tests {
             query_type: QueryType::Unspecified.into(),
             flightsql_command: vec![],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         // Reverts to default (unspecified) for invalid query_type enumeration, and thus SQL
@@ -698,6 +940,12 @@ mod tests {
             query_type: QueryType::Sql.into(),
             flightsql_command: vec![],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let ri = IoxGetRequest::try_decode(ticket).unwrap();
@@ -713,6 +961,12 @@ mod tests {
             query_type: QueryType::InfluxQl.into(),
             flightsql_command: vec![],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let ri = IoxGetRequest::try_decode(ticket).unwrap();
@@ -728,6 +982,12 @@ mod tests {
             query_type: 42, // not a known query type
             flightsql_command: vec![],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         // Reverts to default (unspecified) for invalid query_type enumeration, and thus SQL
@@ -745,6 +1005,12 @@ mod tests {
             // can't have both sql_query and flightsql
             flightsql_command: vec![1, 2, 3],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let e = IoxGetRequest::try_decode(ticket).unwrap_err();
@@ -760,6 +1026,12 @@ mod tests {
             // can't have both sql_query and flightsql
             flightsql_command: vec![1, 2, 3],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let e = IoxGetRequest::try_decode(ticket).unwrap_err();
@@ -775,6 +1047,12 @@ mod tests {
             // can't have both sql_query and flightsql
             flightsql_command: vec![1, 2, 3],
             is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
         });
 
         let e = IoxGetRequest::try_decode(ticket).unwrap_err();
@@ -797,6 +1075,11 @@ mod tests {
             database: "foo_blarg".into(),
             query: RunQuery::Sql("select * from bar".into()),
             is_debug: false,
+            as_of: None,
+            sample_fraction: None,
+            last_value_cache: false,
+            timestamp_precision: None,
+            page_size: None,
         };
 
         let ticket = request.clone().try_encode().expect("encoding failed");
@@ -812,6 +1095,11 @@ mod tests {
             database: "foo_blarg".into(),
             query: RunQuery::Sql("select * from bar".into()),
             is_debug: true,
+            as_of: None,
+            sample_fraction: None,
+            last_value_cache: false,
+            timestamp_precision: None,
+            page_size: None,
         };
 
         let ticket = request.clone().try_encode().expect("encoding failed");
@@ -821,12 +1109,154 @@ mod tests {
         assert_eq!(request, roundtripped)
     }
 
+    #[test]
+    fn round_trip_sql_as_of() {
+        let request = IoxGetRequest::new(
+            "foo_blarg",
+            RunQuery::Sql("select * from bar".into()),
+            false,
+        )
+        .with_as_of(Some("2023-01-01T00:00:00Z".into()));
+
+        let ticket = request.clone().try_encode().expect("encoding failed");
+
+        let roundtripped = IoxGetRequest::try_decode(ticket).expect("decode failed");
+
+        assert_eq!(roundtripped.as_of(), Some("2023-01-01T00:00:00Z"));
+        assert_eq!(request, roundtripped)
+    }
+
+    #[test]
+    fn round_trip_sql_sample_fraction() {
+        let request = IoxGetRequest::new(
+            "foo_blarg",
+            RunQuery::Sql("select * from bar".into()),
+            false,
+        )
+        .with_sample_fraction(Some(0.25));
+
+        let ticket = request.clone().try_encode().expect("encoding failed");
+
+        let roundtripped = IoxGetRequest::try_decode(ticket).expect("decode failed");
+
+        assert_eq!(roundtripped.sample_fraction(), Some(0.25));
+        assert_eq!(request, roundtripped)
+    }
+
+    #[test]
+    fn round_trip_sql_last_value_cache() {
+        let request = IoxGetRequest::new(
+            "foo_blarg",
+            RunQuery::Sql("select * from bar".into()),
+            false,
+        )
+        .with_last_value_cache(true);
+
+        let ticket = request.clone().try_encode().expect("encoding failed");
+
+        let roundtripped = IoxGetRequest::try_decode(ticket).expect("decode failed");
+
+        assert!(roundtripped.last_value_cache());
+        assert_eq!(request, roundtripped)
+    }
+
+    #[test]
+    fn round_trip_sql_timestamp_precision() {
+        let request = IoxGetRequest::new(
+            "foo_blarg",
+            RunQuery::Sql("select * from bar".into()),
+            false,
+        )
+        .with_timestamp_precision(Some("ms".into()));
+
+        let ticket = request.clone().try_encode().expect("encoding failed");
+
+        let roundtripped = IoxGetRequest::try_decode(ticket).expect("decode failed");
+
+        assert_eq!(roundtripped.timestamp_precision(), Some("ms"));
+        assert_eq!(request, roundtripped)
+    }
+
+    #[test]
+    fn round_trip_sql_page_size() {
+        let request = IoxGetRequest::new(
+            "foo_blarg",
+            RunQuery::Sql("select * from bar".into()),
+            false,
+        )
+        .with_page_size(NonZeroUsize::new(10));
+
+        let ticket = request.clone().try_encode().expect("encoding failed");
+
+        let roundtripped = IoxGetRequest::try_decode(ticket).expect("decode failed");
+
+        assert_eq!(roundtripped.page_size(), NonZeroUsize::new(10));
+        assert_eq!(request, roundtripped)
+    }
+
+    #[test]
+    fn round_trip_cursor() {
+        let request = IoxGetRequest::new("foo_blarg", RunQuery::Cursor(Uuid::new_v4()), false)
+            .with_page_size(NonZeroUsize::new(10));
+
+        let ticket = request.clone().try_encode().expect("encoding failed");
+
+        let roundtripped = IoxGetRequest::try_decode(ticket).expect("decode failed");
+
+        assert_eq!(request, roundtripped)
+    }
+
+    #[test]
+    fn proto_ticket_decoding_invalid_cursor() {
+        let ticket = make_proto_ticket(&proto::ReadInfo {
+            database: "<foo>_<bar>".to_string(),
+            sql_query: String::new(),
+            query_type: QueryType::Cursor.into(),
+            flightsql_command: vec![],
+            is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 0.0,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: "not a uuid".to_string(),
+        });
+
+        let e = IoxGetRequest::try_decode(ticket).unwrap_err();
+        assert_matches!(e, Error::Invalid);
+    }
+
+    #[test]
+    fn proto_ticket_decoding_invalid_sample_fraction() {
+        let ticket = make_proto_ticket(&proto::ReadInfo {
+            database: "<foo>_<bar>".to_string(),
+            sql_query: "SELECT 1".to_string(),
+            query_type: QueryType::Sql.into(),
+            flightsql_command: vec![],
+            is_debug: false,
+            as_of: String::new(),
+            sample_fraction: 1.5,
+            last_value_cache: false,
+            timestamp_precision: String::new(),
+            page_size: 0,
+            cursor: String::new(),
+        });
+
+        let e = IoxGetRequest::try_decode(ticket).unwrap_err();
+        assert_matches!(e, Error::Invalid);
+    }
+
     #[test]
     fn round_trip_influxql() {
         let request = IoxGetRequest {
             database: "foo_blarg".into(),
             query: RunQuery::InfluxQL("select * from bar".into()),
             is_debug: false,
+            as_of: None,
+            sample_fraction: None,
+            last_value_cache: false,
+            timestamp_precision: None,
+            page_size: None,
         };
 
         let ticket = request.clone().try_encode().expect("encoding failed");
@@ -847,6 +1277,11 @@ mod tests {
             database: "foo_blarg".into(),
             query: RunQuery::FlightSQL(cmd),
             is_debug: false,
+            as_of: None,
+            sample_fraction: None,
+            last_value_cache: false,
+            timestamp_precision: None,
+            page_size: None,
         };
 
         let ticket = request.clone().try_encode().expect("encoding failed");
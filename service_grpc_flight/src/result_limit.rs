@@ -0,0 +1,191 @@
+//! Limits of the size of `DoGet` query results.
+//!
+//! Results exceeding the [`ResultLimits`] are truncated instead of failing the query: the client
+//! receives the results up to the limit, flagged as partial in the last message of the response
+//! (`AppMetadata.partial`). This suits dashboards, which prefer truncated data over errors.
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use arrow::record_batch::RecordBatch;
+use arrow_flight::error::FlightError;
+use futures::{ready, Stream, StreamExt};
+use iox_query::exec::PartialResults;
+
+use crate::cursor::ResultStream;
+
+/// Upper bounds of the size of the results of a single `DoGet` query.
+///
+/// The limits apply to the complete results of a query, across all pages of paginated queries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResultLimits {
+    /// Maximum number of rows. Unlimited if not set.
+    pub max_rows: Option<usize>,
+
+    /// Maximum size of the record batches, in bytes. Unlimited if not set.
+    ///
+    /// Record batches are never split to meet this limit: the results end before the first
+    /// batch that would exceed it.
+    pub max_bytes: Option<usize>,
+}
+
+/// Truncate `results` to `limits`, setting `partial` if any results were dropped.
+///
+/// The query is cancelled once the limits are hit.
+pub(crate) fn limit_results(
+    results: ResultStream,
+    limits: ResultLimits,
+    partial: PartialResults,
+) -> ResultStream {
+    if limits == ResultLimits::default() {
+        return results;
+    }
+
+    LimitStream {
+        results: Some(results),
+        limits,
+        rows: 0,
+        bytes: 0,
+        partial,
+    }
+    .boxed()
+}
+
+struct LimitStream {
+    /// The remaining results, `None` once they were exhausted or truncated.
+    results: Option<ResultStream>,
+    limits: ResultLimits,
+    /// Number of rows yielded so far.
+    rows: usize,
+    /// Size of the record batches yielded so far.
+    bytes: usize,
+    partial: PartialResults,
+}
+
+impl Stream for LimitStream {
+    type Item = Result<RecordBatch, FlightError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(results) = self.results.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        let batch = match ready!(results.poll_next_unpin(cx)) {
+            Some(Ok(batch)) if batch.num_rows() > 0 => batch,
+            Some(res) => return Poll::Ready(Some(res)),
+            None => {
+                self.results = None;
+                return Poll::Ready(None);
+            }
+        };
+
+        let size = batch.get_array_memory_size();
+        let mut rows = batch.num_rows();
+        let mut truncated = false;
+        if let Some(max_rows) = self.limits.max_rows {
+            let remaining = max_rows.saturating_sub(self.rows);
+            if rows > remaining {
+                rows = remaining;
+                truncated = true;
+            }
+        }
+        if let Some(max_bytes) = self.limits.max_bytes {
+            if self.bytes + size > max_bytes {
+                rows = 0;
+                truncated = true;
+            }
+        }
+
+        if truncated {
+            // drop the remaining results, cancelling the query
+            self.results = None;
+            self.partial.set();
+            if rows == 0 {
+                return Poll::Ready(None);
+            }
+        }
+
+        self.rows += rows;
+        self.bytes += size;
+        Poll::Ready(Some(Ok(batch.slice(0, rows))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Int64Array};
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unlimited() {
+        let (values, partial) = run(&[&[1, 2], &[3]], ResultLimits::default()).await;
+        assert_eq!(values, vec![1, 2, 3]);
+        assert!(!partial);
+    }
+
+    #[tokio::test]
+    async fn test_max_rows() {
+        let limits = ResultLimits {
+            max_rows: Some(3),
+            ..Default::default()
+        };
+        let (values, partial) = run(&[&[1, 2], &[3, 4], &[5]], limits).await;
+        assert_eq!(values, vec![1, 2, 3]);
+        assert!(partial);
+
+        // results that exactly fit are complete
+        let (values, partial) = run(&[&[1, 2], &[3]], limits).await;
+        assert_eq!(values, vec![1, 2, 3]);
+        assert!(!partial);
+    }
+
+    #[tokio::test]
+    async fn test_max_bytes() {
+        let size = batch(&[1]).get_array_memory_size();
+        let limits = ResultLimits {
+            max_bytes: Some(2 * size),
+            ..Default::default()
+        };
+        let (values, partial) = run(&[&[1], &[2], &[3]], limits).await;
+        assert_eq!(values, vec![1, 2]);
+        assert!(partial);
+    }
+
+    async fn run(batches: &[&[i64]], limits: ResultLimits) -> (Vec<i64>, bool) {
+        let results = futures::stream::iter(
+            batches
+                .iter()
+                .map(|values| Ok(batch(values)))
+                .collect::<Vec<_>>(),
+        )
+        .boxed();
+        let partial = PartialResults::default();
+
+        let batches: Vec<_> = limit_results(results, limits, partial.clone())
+            .try_collect()
+            .await
+            .unwrap();
+        let values = batches
+            .iter()
+            .flat_map(|b| {
+                b.column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        (values, partial.get())
+    }
+
+    fn batch(values: &[i64]) -> RecordBatch {
+        let array = Arc::new(Int64Array::from(values.to_vec())) as ArrayRef;
+        RecordBatch::try_from_iter([("v", array)]).unwrap()
+    }
+}
@@ -0,0 +1,154 @@
+//! Generate synthetic datasets of configurable cardinality for the storage
+//! RPC benchmarks.
+
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, DictionaryArray, Float64Array, TimestampNanosecondArray},
+    datatypes::Int32Type,
+    record_batch::RecordBatch,
+};
+use iox_query::{test::TestChunk, QueryChunk};
+
+/// The interval between two points of the same series.
+pub const POINT_INTERVAL_NS: i64 = 1_000_000_000;
+
+/// The shape of a generated dataset.
+///
+/// Every measurement has the same tags and fields, and every series has a
+/// point at each multiple of [`POINT_INTERVAL_NS`] in the
+/// [`time_range`](Self::time_range) of the dataset.
+#[derive(Debug, Clone, Copy)]
+pub struct DatasetConfig {
+    /// The number of measurements, named `m0`, `m1`, ...
+    pub measurements: usize,
+
+    /// The number of distinct values of each tag, named `tag0`, `tag1`, ...
+    ///
+    /// The number of series per measurement is the product of the
+    /// cardinalities.
+    pub tag_cardinalities: &'static [usize],
+
+    /// The number of float fields, named `field0`, `field1`, ...
+    pub fields: usize,
+
+    /// The number of points of each series.
+    pub points_per_series: usize,
+
+    /// The number of chunks each measurement is split into, each covering a
+    /// disjoint part of the time range in its own partition.
+    pub chunks_per_measurement: usize,
+}
+
+impl DatasetConfig {
+    /// The number of series in each measurement.
+    pub fn series_per_measurement(&self) -> usize {
+        self.tag_cardinalities.iter().product()
+    }
+
+    /// A name identifying this dataset in benchmark IDs.
+    pub fn name(&self) -> String {
+        format!(
+            "measurements_{}/series_{}/points_{}/chunks_{}",
+            self.measurements,
+            self.series_per_measurement(),
+            self.points_per_series,
+            self.chunks_per_measurement,
+        )
+    }
+
+    /// The time range `[start, end)` covering all points of the dataset.
+    pub fn time_range(&self) -> (i64, i64) {
+        (0, self.points_per_series as i64 * POINT_INTERVAL_NS)
+    }
+
+    /// The time range `[start, end)` covered by the first chunk of each
+    /// measurement.
+    pub fn first_chunk_time_range(&self) -> (i64, i64) {
+        (0, self.points_per_chunk() as i64 * POINT_INTERVAL_NS)
+    }
+
+    fn points_per_chunk(&self) -> usize {
+        (self.points_per_series + self.chunks_per_measurement - 1) / self.chunks_per_measurement
+    }
+
+    /// Generate the chunks of the dataset, along with the partition key of
+    /// each.
+    pub fn chunks(&self) -> Vec<(String, TestChunk)> {
+        let mut chunks = vec![];
+        for measurement in 0..self.measurements {
+            for (i, start) in (0..self.points_per_series)
+                .step_by(self.points_per_chunk())
+                .enumerate()
+            {
+                let end = (start + self.points_per_chunk()).min(self.points_per_series);
+                let id = chunks.len();
+                let chunk = self.chunk(&format!("m{measurement}"), id, start..end);
+                chunks.push((format!("partition_{i}"), chunk));
+            }
+        }
+        chunks
+    }
+
+    /// Generate a chunk of `measurement` holding the points `points` of every
+    /// series.
+    fn chunk(&self, measurement: &str, id: usize, points: std::ops::Range<usize>) -> TestChunk {
+        let min_time = points.start as i64 * POINT_INTERVAL_NS;
+        let max_time = (points.end as i64 - 1) * POINT_INTERVAL_NS;
+
+        let mut chunk = TestChunk::new(measurement)
+            .with_id(id as u128)
+            .with_partition(id as i64)
+            .with_quiet()
+            .with_time_column_with_stats(Some(min_time), Some(max_time));
+        for tag in 0..self.tag_cardinalities.len() {
+            chunk = chunk.with_tag_column(format!("tag{tag}"));
+        }
+        for field in 0..self.fields {
+            chunk = chunk.with_f64_field_column(format!("field{field}"));
+        }
+
+        // Rows are ordered by series, then time.
+        let series = self.series_per_measurement();
+        let rows = series * points.len();
+        let mut columns: HashMap<String, ArrayRef> = HashMap::new();
+
+        for (tag, cardinality) in self.tag_cardinalities.iter().enumerate() {
+            // The number of consecutive series sharing a value of this tag.
+            let stride: usize = self.tag_cardinalities[tag + 1..].iter().product();
+            let values: Vec<_> = (0..series)
+                .flat_map(|s| {
+                    let value = format!("value{}", (s / stride) % cardinality);
+                    std::iter::repeat(value).take(points.len())
+                })
+                .collect();
+            let values: DictionaryArray<Int32Type> = values.iter().map(String::as_str).collect();
+            columns.insert(format!("tag{tag}"), Arc::new(values));
+        }
+
+        for field in 0..self.fields {
+            let values = Float64Array::from_iter_values((0..series).flat_map(|s| {
+                points
+                    .clone()
+                    .map(move |p| ((s * 31 + p * 7 + field) % 100) as f64)
+            }));
+            columns.insert(format!("field{field}"), Arc::new(values));
+        }
+
+        let times = TimestampNanosecondArray::from_iter_values(
+            (0..series).flat_map(|_| points.clone().map(|p| p as i64 * POINT_INTERVAL_NS)),
+        );
+        assert_eq!(times.len(), rows);
+        columns.insert("time".to_string(), Arc::new(times));
+
+        let schema = chunk.schema().as_arrow();
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|f| Arc::clone(&columns[f.name()]))
+            .collect();
+        let batch = RecordBatch::try_new(schema, columns).expect("valid record batch");
+
+        chunk.with_record_batch(batch)
+    }
+}
@@ -0,0 +1,290 @@
+//! Benchmarks of the storage gRPC API, from request to decoded response, over
+//! synthetic datasets of varying cardinality.
+//!
+//! Each request is sent to an in-process server backed by a
+//! [`TestDatabaseStore`](service_common::test_util::TestDatabaseStore), so the
+//! benchmarks cover planning, chunk pruning, execution and the encoding of the
+//! response frames.
+
+mod generator;
+
+use std::{num::NonZeroU64, sync::Arc};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use generator::{DatasetConfig, POINT_INTERVAL_NS};
+use influxdb_storage_client::{
+    connection::Connection,
+    generated_types::{
+        aggregate::AggregateType, node, read_window_aggregate_request::Fill, Aggregate,
+        MeasurementFieldsRequest, Node, Predicate, ReadFilterRequest, ReadWindowAggregateRequest,
+        TagKeyMetaNames, TagValuesRequest, TimestampPrecision, TimestampRange,
+    },
+    Client as StorageClient, OrgAndBucket,
+};
+use service_grpc_influxrpc::test_util::Fixture;
+use tokio::runtime::Runtime;
+
+/// The datasets every benchmark is run against.
+static DATASETS: &[DatasetConfig] = &[
+    DatasetConfig {
+        measurements: 1,
+        tag_cardinalities: &[10, 10],
+        fields: 2,
+        points_per_series: 100,
+        chunks_per_measurement: 1,
+    },
+    DatasetConfig {
+        measurements: 1,
+        tag_cardinalities: &[10, 10, 10],
+        fields: 2,
+        points_per_series: 100,
+        chunks_per_measurement: 4,
+    },
+    DatasetConfig {
+        measurements: 4,
+        tag_cardinalities: &[10, 10, 100],
+        fields: 4,
+        points_per_series: 10,
+        chunks_per_measurement: 2,
+    },
+];
+
+fn org_and_bucket() -> OrgAndBucket {
+    OrgAndBucket::new(NonZeroU64::new(123).unwrap(), NonZeroU64::new(456).unwrap())
+}
+
+/// Start a test server holding the data of `config`.
+async fn init(config: &DatasetConfig) -> Fixture {
+    let fixture = Fixture::new().await.expect("starting test server");
+
+    let db = fixture
+        .test_storage
+        .db_or_create(org_and_bucket().db_name())
+        .await;
+    for (partition_key, chunk) in config.chunks() {
+        db.add_chunk(&partition_key, Arc::new(chunk));
+    }
+
+    fixture
+}
+
+fn range((start, end): (i64, i64)) -> Option<TimestampRange> {
+    Some(TimestampRange { start, end })
+}
+
+/// A predicate `tag_name = tag_value`.
+fn tag_predicate(tag_name: &str, tag_value: &str) -> Option<Predicate> {
+    use node::{Comparison, Type, Value};
+
+    let root = Node {
+        node_type: Type::ComparisonExpression as i32,
+        value: Some(Value::Comparison(Comparison::Equal as i32)),
+        children: vec![
+            Node {
+                node_type: Type::TagRef as i32,
+                value: Some(Value::TagRefValue(tag_name.as_bytes().to_vec())),
+                children: vec![],
+            },
+            Node {
+                node_type: Type::Literal as i32,
+                value: Some(Value::StringValue(tag_value.to_string())),
+                children: vec![],
+            },
+        ],
+    };
+    Some(Predicate { root: Some(root) })
+}
+
+/// Benchmark one request per iteration against every dataset.
+///
+/// `request` builds the request for a dataset, and `send` sends it using a
+/// fresh client on the fixture's connection.
+fn run_bench<R, F, Fut>(
+    c: &mut Criterion,
+    runtime: &Runtime,
+    group_name: &str,
+    request: impl Fn(&DatasetConfig) -> R,
+    send: F,
+) where
+    R: Clone,
+    F: Fn(StorageClient, R) -> Fut,
+    Fut: std::future::Future<Output = usize>,
+{
+    let mut group = c.benchmark_group(group_name);
+    group.throughput(Throughput::Elements(1)); // Queries per second
+
+    for config in DATASETS {
+        let fixture = runtime.block_on(init(config));
+        let connection: Connection = fixture.client_connection.clone();
+        let request = request(config);
+
+        group.bench_function(BenchmarkId::from_parameter(config.name()), |b| {
+            b.to_async(runtime).iter(|| {
+                let client = StorageClient::new(connection.clone());
+                send(client, request.clone())
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_read_filter(c: &mut Criterion) {
+    let runtime = runtime();
+
+    // All data of the dataset.
+    run_bench(
+        c,
+        &runtime,
+        "read_filter/all",
+        |config| ReadFilterRequest {
+            read_source: Some(StorageClient::read_source(&org_and_bucket(), 1)),
+            range: range(config.time_range()),
+            ..Default::default()
+        },
+        |mut client, request| async move {
+            client
+                .read_filter(request)
+                .await
+                .expect("read_filter")
+                .len()
+        },
+    );
+
+    // A single value of the first tag, selecting a fraction of the series.
+    run_bench(
+        c,
+        &runtime,
+        "read_filter/tag_predicate",
+        |config| ReadFilterRequest {
+            read_source: Some(StorageClient::read_source(&org_and_bucket(), 1)),
+            range: range(config.time_range()),
+            predicate: tag_predicate("tag0", "value0"),
+            ..Default::default()
+        },
+        |mut client, request| async move {
+            client
+                .read_filter(request)
+                .await
+                .expect("read_filter")
+                .len()
+        },
+    );
+
+    // Only the time range of the first chunk of each measurement, so the
+    // remaining chunks can be pruned.
+    run_bench(
+        c,
+        &runtime,
+        "read_filter/narrow_time_range",
+        |config| ReadFilterRequest {
+            read_source: Some(StorageClient::read_source(&org_and_bucket(), 1)),
+            range: range(config.first_chunk_time_range()),
+            ..Default::default()
+        },
+        |mut client, request| async move {
+            client
+                .read_filter(request)
+                .await
+                .expect("read_filter")
+                .len()
+        },
+    );
+}
+
+fn bench_tag_values(c: &mut Criterion) {
+    let runtime = runtime();
+
+    // The tag with the most distinct values.
+    run_bench(
+        c,
+        &runtime,
+        "tag_values",
+        |config| TagValuesRequest {
+            tags_source: Some(StorageClient::read_source(&org_and_bucket(), 1)),
+            range: range(config.time_range()),
+            predicate: None,
+            tag_key: format!("tag{}", config.tag_cardinalities.len() - 1).into(),
+        },
+        |mut client, request| async move {
+            let values = client.tag_values(request).await.expect("tag_values");
+            values.len()
+        },
+    );
+}
+
+fn bench_field_columns(c: &mut Criterion) {
+    let runtime = runtime();
+
+    run_bench(
+        c,
+        &runtime,
+        "field_columns",
+        |config| MeasurementFieldsRequest {
+            source: Some(StorageClient::read_source(&org_and_bucket(), 1)),
+            measurement: "m0".into(),
+            range: range(config.time_range()),
+            predicate: None,
+        },
+        |mut client, request| async move {
+            client
+                .measurement_fields(request)
+                .await
+                .expect("measurement_fields")
+                .len()
+        },
+    );
+}
+
+fn bench_read_window_aggregate(c: &mut Criterion) {
+    let runtime = runtime();
+
+    // Ten windows over the whole dataset.
+    run_bench(
+        c,
+        &runtime,
+        "read_window_aggregate/mean",
+        |config| {
+            let (start, end) = config.time_range();
+            ReadWindowAggregateRequest {
+                read_source: Some(StorageClient::read_source(&org_and_bucket(), 1)),
+                range: range((start, end)),
+                predicate: None,
+                window_every: ((end - start) / 10).max(POINT_INTERVAL_NS),
+                offset: 0,
+                aggregate: vec![Aggregate {
+                    r#type: AggregateType::Mean as i32,
+                    n: 0,
+                }],
+                window: None,
+                tag_key_meta_names: TagKeyMetaNames::Text as i32,
+                fill: Fill::None as i32,
+                timestamp_precision: TimestampPrecision::Nanoseconds as i32,
+                aggregate_expressions: vec![],
+            }
+        },
+        |mut client, request| async move {
+            client
+                .read_window_aggregate(request)
+                .await
+                .expect("read_window_aggregate")
+                .len()
+        },
+    );
+}
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("failed to initialise tokio runtime for benchmark")
+}
+
+criterion_group!(
+    benches,
+    bench_read_filter,
+    bench_tag_values,
+    bench_field_columns,
+    bench_read_window_aggregate
+);
+criterion_main!(benches);
@@ -7,7 +7,7 @@ use arrow::datatypes::DataType as ArrowDataType;
 
 use futures::{stream::BoxStream, Stream, StreamExt};
 use iox_query::exec::{
-    fieldlist::FieldList,
+    fieldlist::{FieldList, MeasurementFieldList},
     seriesset::series::{self, Batch, Either},
 };
 use predicate::rpc_predicate::{FIELD_COLUMN_NAME, MEASUREMENT_COLUMN_NAME};
@@ -18,7 +18,8 @@ use generated_types::{
         frame::Data, BooleanPointsFrame, DataType, FloatPointsFrame, Frame, GroupFrame,
         IntegerPointsFrame, SeriesFrame, StringPointsFrame, UnsignedPointsFrame,
     },
-    MeasurementFieldsResponse, Tag,
+    FieldsGroupedByMeasurementResponse, MeasurementFieldsResponse, SeriesFrameEncoding, Tag,
+    TimestampPrecision,
 };
 
 use super::{TAG_KEY_FIELD, TAG_KEY_MEASUREMENT};
@@ -94,6 +95,78 @@ where
     })
 }
 
+/// Encode the tags of the series frames in `frames` as requested by
+/// `encoding`.
+///
+/// With [`SeriesFrameEncoding::SharedTagPrefix`] each series frame omits the
+/// leading tags it has in common with the previous series frame of the
+/// stream, and instead reports their number as `shared_tag_prefix`.
+pub fn encode_series_tags<S, E>(
+    frames: S,
+    encoding: SeriesFrameEncoding,
+) -> impl Stream<Item = Result<Frame, E>>
+where
+    S: Stream<Item = Result<Frame, E>>,
+{
+    frames.scan(Vec::<Tag>::new(), move |previous, res| {
+        let res = res.map(|mut frame| {
+            if let (SeriesFrameEncoding::SharedTagPrefix, Some(Data::Series(series))) =
+                (encoding, &mut frame.data)
+            {
+                let tags = std::mem::take(&mut series.tags);
+                let shared = previous
+                    .iter()
+                    .zip(&tags)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                series.tags = tags[shared..].to_vec();
+                series.shared_tag_prefix = shared as u32;
+                *previous = tags;
+            }
+            frame
+        });
+        futures::future::ready(Some(res))
+    })
+}
+
+/// Convert the (nanosecond) point timestamps of `frames` to `precision`,
+/// truncating them towards negative infinity.
+pub fn scale_timestamps<S, E>(
+    frames: S,
+    precision: TimestampPrecision,
+) -> impl Stream<Item = Result<Frame, E>>
+where
+    S: Stream<Item = Result<Frame, E>>,
+{
+    let divisor = match precision {
+        TimestampPrecision::Nanoseconds => 1,
+        TimestampPrecision::Microseconds => 1_000,
+        TimestampPrecision::Milliseconds => 1_000_000,
+        TimestampPrecision::Seconds => 1_000_000_000,
+    };
+
+    frames.map(move |res| {
+        res.map(|mut frame| {
+            if divisor == 1 {
+                return frame;
+            }
+
+            let timestamps = match &mut frame.data {
+                Some(Data::FloatPoints(points)) => &mut points.timestamps,
+                Some(Data::IntegerPoints(points)) => &mut points.timestamps,
+                Some(Data::UnsignedPoints(points)) => &mut points.timestamps,
+                Some(Data::BooleanPoints(points)) => &mut points.timestamps,
+                Some(Data::StringPoints(points)) => &mut points.timestamps,
+                Some(Data::Series(_)) | Some(Data::Group(_)) | None => return frame,
+            };
+            for ts in timestamps.iter_mut() {
+                *ts = ts.div_euclid(divisor);
+            }
+            frame
+        })
+    })
+}
+
 /// Converts a `Series` into frames for GRPC transport
 fn series_to_frames(
     series: series::Series,
@@ -163,6 +236,7 @@ fn series_to_frames(
             data: Some(Data::Series(SeriesFrame {
                 tags: convert_tags(tags, tag_key_binary_format),
                 data_type: data_type.into(),
+                shared_tag_prefix: 0,
             })),
         })
         .chain(data_frames),
@@ -219,7 +293,30 @@ fn arcs_to_bytes(s: Vec<Arc<str>>) -> Vec<Vec<u8>> {
 pub fn fieldlist_to_measurement_fields_response(
     fieldlist: FieldList,
 ) -> Result<MeasurementFieldsResponse> {
-    let fields = fieldlist
+    let fields = fieldlist_to_message_fields(fieldlist)?;
+
+    Ok(MeasurementFieldsResponse { fields })
+}
+
+/// Translates MeasurementFieldList into the gRPC format, one response per
+/// measurement
+pub fn measurement_fieldlist_to_responses(
+    measurement_fieldlist: MeasurementFieldList,
+) -> Result<Vec<FieldsGroupedByMeasurementResponse>> {
+    measurement_fieldlist
+        .measurements
+        .into_iter()
+        .map(|(measurement, fieldlist)| {
+            Ok(FieldsGroupedByMeasurementResponse {
+                measurement,
+                fields: fieldlist_to_message_fields(fieldlist)?,
+            })
+        })
+        .collect()
+}
+
+fn fieldlist_to_message_fields(fieldlist: FieldList) -> Result<Vec<MessageField>> {
+    fieldlist
         .fields
         .into_iter()
         .map(|f| {
@@ -227,11 +324,11 @@ pub fn fieldlist_to_measurement_fields_response(
                 key: f.name,
                 r#type: datatype_to_measurement_field_enum(&f.data_type)? as i32,
                 timestamp: f.last_timestamp,
+                row_count: f.row_count,
+                null_count: f.null_count,
             })
         })
-        .collect::<Result<Vec<_>>>()?;
-
-    Ok(MeasurementFieldsResponse { fields })
+        .collect()
 }
 
 fn datatype_to_measurement_field_enum(data_type: &ArrowDataType) -> Result<FieldType> {
@@ -380,6 +477,106 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_encode_series_tags() {
+        let series_set = SeriesSet {
+            table_name: Arc::from("the_table"),
+            tags: vec![(Arc::from("tag1"), Arc::from("val1"))],
+            field_indexes: FieldIndexes::from_timestamp_and_value_indexes(5, &[0, 1]),
+            start_row: 1,
+            num_rows: 4,
+            batch: make_record_batch(),
+        };
+        let series: Vec<Either> = series_set
+            .try_into_series(3)
+            .expect("Correctly converted series set")
+            .into_iter()
+            .map(|s| s.into())
+            .collect();
+        let frames = || {
+            series_or_groups_to_frames::<_, ()>(futures::stream::iter(series.clone()).map(Ok), true)
+        };
+
+        // The full encoding leaves the frames unchanged
+        let full = frames().try_collect::<Vec<_>>().await.unwrap();
+        let encoded = encode_series_tags(frames(), SeriesFrameEncoding::Full)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(full, encoded);
+
+        let encoded = encode_series_tags(frames(), SeriesFrameEncoding::SharedTagPrefix)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let dumped_frames = dump_frames(&encoded);
+        let expected_frames = vec![
+            "SeriesFrame, tags: \0=the_table,tag1=val1,�=string_field, type: 4",
+            "StringPointsFrame, timestamps: [2000, 3000, 4000], values: bar,baz,bar",
+            "StringPointsFrame, timestamps: [5000], values: baz",
+            "SeriesFrame, shared_tag_prefix: 2, tags: �=int_field, type: 1",
+            "IntegerPointsFrame, timestamps: [2000, 3000, 4000], values: \"2,2,3\"",
+            "IntegerPointsFrame, timestamps: [5000], values: \"3\"",
+        ];
+
+        assert_eq!(
+            dumped_frames, expected_frames,
+            "Expected:\n{expected_frames:#?}\nActual:\n{dumped_frames:#?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scale_timestamps() {
+        let frames = || {
+            futures::stream::iter([
+                Frame {
+                    data: Some(Data::Series(SeriesFrame {
+                        tags: vec![],
+                        data_type: DataType::Integer.into(),
+                        shared_tag_prefix: 0,
+                    })),
+                },
+                Frame {
+                    data: Some(Data::IntegerPoints(IntegerPointsFrame {
+                        timestamps: vec![-1, 0, 1_999_999_999, 2_000_000_000],
+                        values: vec![1, 2, 3, 4],
+                    })),
+                },
+            ])
+            .map(Ok::<_, ()>)
+        };
+
+        let scaled = scale_timestamps(frames(), TimestampPrecision::Nanoseconds)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(scaled, frames().try_collect::<Vec<_>>().await.unwrap());
+
+        let scaled = scale_timestamps(frames(), TimestampPrecision::Seconds)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let dumped_frames = dump_frames(&scaled);
+        let expected_frames = vec![
+            "SeriesFrame, tags: , type: 1",
+            "IntegerPointsFrame, timestamps: [-1, 0, 1, 2], values: \"1,2,3,4\"",
+        ];
+        assert_eq!(
+            dumped_frames, expected_frames,
+            "Expected:\n{expected_frames:#?}\nActual:\n{dumped_frames:#?}"
+        );
+
+        let scaled = scale_timestamps(frames(), TimestampPrecision::Milliseconds)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let dumped_frames = dump_frames(&scaled);
+        assert_eq!(
+            dumped_frames[1],
+            "IntegerPointsFrame, timestamps: [-1, 0, 1999, 2000], values: \"1,2,3,4\""
+        );
+    }
+
     #[tokio::test]
     async fn test_group_group_conversion() {
         let group = Group {
@@ -420,26 +617,36 @@ mod tests {
                     name: "float".into(),
                     data_type: ArrowDataType::Float64,
                     last_timestamp: 1000,
+                    row_count: Some(10),
+                    null_count: Some(1),
                 },
                 Field {
                     name: "int".into(),
                     data_type: ArrowDataType::Int64,
                     last_timestamp: 2000,
+                    row_count: Some(10),
+                    null_count: Some(2),
                 },
                 Field {
                     name: "uint".into(),
                     data_type: ArrowDataType::UInt64,
                     last_timestamp: 3000,
+                    row_count: Some(10),
+                    null_count: Some(3),
                 },
                 Field {
                     name: "string".into(),
                     data_type: ArrowDataType::Utf8,
                     last_timestamp: 4000,
+                    row_count: Some(10),
+                    null_count: Some(4),
                 },
                 Field {
                     name: "bool".into(),
                     data_type: ArrowDataType::Boolean,
                     last_timestamp: 5000,
+                    row_count: Some(10),
+                    null_count: None,
                 },
             ],
         };
@@ -450,26 +657,36 @@ mod tests {
                     key: "float".into(),
                     r#type: FieldType::Float as i32,
                     timestamp: 1000,
+                    row_count: Some(10),
+                    null_count: Some(1),
                 },
                 MessageField {
                     key: "int".into(),
                     r#type: FieldType::Integer as i32,
                     timestamp: 2000,
+                    row_count: Some(10),
+                    null_count: Some(2),
                 },
                 MessageField {
                     key: "uint".into(),
                     r#type: FieldType::Unsigned as i32,
                     timestamp: 3000,
+                    row_count: Some(10),
+                    null_count: Some(3),
                 },
                 MessageField {
                     key: "string".into(),
                     r#type: FieldType::String as i32,
                     timestamp: 4000,
+                    row_count: Some(10),
+                    null_count: Some(4),
                 },
                 MessageField {
                     key: "bool".into(),
                     r#type: FieldType::Boolean as i32,
                     timestamp: 5000,
+                    row_count: Some(10),
+                    null_count: None,
                 },
             ],
         };
@@ -481,6 +698,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_measurement_field_list_conversion() {
+        let field = |name: &str, last_timestamp| Field {
+            name: name.into(),
+            data_type: ArrowDataType::Float64,
+            last_timestamp,
+            row_count: Some(2),
+            null_count: Some(1),
+        };
+        let message_field = |key: &str, timestamp| MessageField {
+            key: key.into(),
+            r#type: FieldType::Float as i32,
+            timestamp,
+            row_count: Some(2),
+            null_count: Some(1),
+        };
+
+        let input = MeasurementFieldList {
+            measurements: [
+                (
+                    "cpu".to_string(),
+                    FieldList {
+                        fields: vec![field("usage", 1000)],
+                    },
+                ),
+                (
+                    "mem".to_string(),
+                    FieldList {
+                        fields: vec![field("free", 2000), field("used", 3000)],
+                    },
+                ),
+            ]
+            .into(),
+        };
+
+        let expected = vec![
+            FieldsGroupedByMeasurementResponse {
+                measurement: "cpu".into(),
+                fields: vec![message_field("usage", 1000)],
+            },
+            FieldsGroupedByMeasurementResponse {
+                measurement: "mem".into(),
+                fields: vec![message_field("free", 2000), message_field("used", 3000)],
+            },
+        ];
+
+        let actual = measurement_fieldlist_to_responses(input).unwrap();
+        assert_eq!(
+            actual, expected,
+            "Expected:\n{expected:#?}\nActual:\n{actual:#?}"
+        );
+    }
+
     #[test]
     fn test_field_list_conversion_error() {
         let input = FieldList {
@@ -488,6 +758,8 @@ mod tests {
                 name: "unsupported".into(),
                 data_type: ArrowDataType::Int8,
                 last_timestamp: 1000,
+                row_count: None,
+                null_count: None,
             }],
         };
         let result = fieldlist_to_measurement_fields_response(input);
@@ -564,12 +836,27 @@ mod tests {
     fn format_frame(frame: &Frame, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let data = &frame.data;
         match data {
-            Some(Data::Series(SeriesFrame { tags, data_type })) => write!(
+            Some(Data::Series(SeriesFrame {
+                tags,
+                data_type,
+                shared_tag_prefix: 0,
+            })) => write!(
                 f,
                 "SeriesFrame, tags: {}, type: {:?}",
                 dump_tags(tags),
                 data_type
             ),
+            Some(Data::Series(SeriesFrame {
+                tags,
+                data_type,
+                shared_tag_prefix,
+            })) => write!(
+                f,
+                "SeriesFrame, shared_tag_prefix: {}, tags: {}, type: {:?}",
+                shared_tag_prefix,
+                dump_tags(tags),
+                data_type
+            ),
             Some(Data::FloatPoints(FloatPointsFrame { timestamps, values })) => write!(
                 f,
                 "FloatPointsFrame, timestamps: {:?}, values: {:?}",
@@ -14,20 +14,33 @@ use datafusion::logical_expr::{binary_expr, Operator};
 use datafusion::{prelude::*, scalar::ScalarValue};
 use datafusion_util::AsExpr;
 use generated_types::{
-    aggregate::AggregateType as RPCAggregateType, node::Comparison as RPCComparison,
-    node::Logical as RPCLogical, node::Value as RPCValue, read_group_request::Group as RPCGroup,
-    Aggregate as RPCAggregate, Duration as RPCDuration, Node as RPCNode, Predicate as RPCPredicate,
-    TimestampRange as RPCTimestampRange, Window as RPCWindow,
+    aggregate::AggregateType as RPCAggregateType,
+    aggregate_expression_node::{
+        FieldAggregate as RPCFieldAggregate, Operator as RPCArithmeticOperator,
+        Value as RPCAggregateExpressionValue,
+    },
+    node::Comparison as RPCComparison,
+    node::Logical as RPCLogical,
+    node::Value as RPCValue,
+    read_group_request::Group as RPCGroup,
+    read_window_aggregate_request::Fill as RPCFill,
+    Aggregate as RPCAggregate, AggregateExpression as RPCAggregateExpression,
+    AggregateExpressionNode as RPCAggregateExpressionNode, Duration as RPCDuration,
+    Node as RPCNode, Predicate as RPCPredicate, TimestampRange as RPCTimestampRange,
+    Window as RPCWindow,
 };
 
 use super::{TAG_KEY_FIELD, TAG_KEY_MEASUREMENT};
-use iox_query::{Aggregate as QueryAggregate, WindowDuration};
+use iox_query::{
+    Aggregate as QueryAggregate, AggregateExpr, ArithmeticOperator, NamedAggregateExpr,
+    WindowDuration, WindowFill,
+};
 use observability_deps::tracing::warn;
 use predicate::{
     rpc_predicate::{InfluxRpcPredicate, FIELD_COLUMN_NAME, MEASUREMENT_COLUMN_NAME},
     Predicate,
 };
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -38,6 +51,49 @@ pub enum Error {
     #[snafu(display("Error creating aggregate: Unknown aggregate type {}", aggregate_type))]
     UnknownAggregate { aggregate_type: i32 },
 
+    #[snafu(display(
+        "Error creating aggregate: {:?} requires a positive number of rows to select, got {}",
+        aggregate_type,
+        n
+    ))]
+    InvalidAggregateN {
+        aggregate_type: RPCAggregateType,
+        n: i64,
+    },
+
+    #[snafu(display(
+        "Error creating aggregate: aggregate and aggregate_expressions are mutually exclusive"
+    ))]
+    AggregateExpressionsWithAggregate {},
+
+    #[snafu(display("Error creating aggregate expression '{}': Missing expression", name))]
+    EmptyAggregateExpression { name: String },
+
+    #[snafu(display("Error creating aggregate expression: Unexpected empty node"))]
+    EmptyAggregateExpressionNode {},
+
+    #[snafu(display(
+        "Error creating aggregate expression: Aggregate {:?} not supported in expressions",
+        agg
+    ))]
+    UnsupportedExpressionAggregate { agg: QueryAggregate },
+
+    #[snafu(display("Error creating aggregate expression: Unknown operator {}", operator))]
+    UnknownAggregateExpressionOperator { operator: i32 },
+
+    #[snafu(display(
+        "Error creating aggregate expression: Unsupported number of children: {} (must be {})",
+        num_children,
+        expected
+    ))]
+    InvalidAggregateExpressionChildren {
+        num_children: usize,
+        expected: usize,
+    },
+
+    #[snafu(display("Error creating aggregate: Unknown fill type {}", fill))]
+    UnknownFill { fill: i32 },
+
     #[snafu(display("Error creating aggregate: Unknown group type: {}", group_type))]
     UnknownGroup { group_type: i32 },
 
@@ -74,8 +130,27 @@ pub enum Error {
     #[snafu(display("Internal error: incorrect number of nodes: {:?}", num_children))]
     InternalInvalidRegexExprChildren { num_children: usize },
 
-    #[snafu(display("Error creating predicate: StartsWith comparisons not supported"))]
-    StartsWithNotSupported {},
+    #[snafu(display(
+        "Error creating predicate: case-insensitive comparisons require a string value"
+    ))]
+    EqualFoldValueInvalid {},
+
+    #[snafu(display(
+        "Error creating predicate: Unsupported number of children in case-insensitive comparison: {} (must be 2)",
+        num_children
+    ))]
+    EqualFoldUnsupportedNumberOfChildren { num_children: usize },
+
+    #[snafu(display(
+        "Error creating predicate: prefix, suffix and substring comparisons require a string value"
+    ))]
+    StringMatchValueInvalid {},
+
+    #[snafu(display(
+        "Error creating predicate: Unsupported number of children in string comparison: {} (must be 2)",
+        num_children
+    ))]
+    StringMatchUnsupportedNumberOfChildren { num_children: usize },
 
     #[snafu(display(
         "Error creating predicate: Unexpected children for predicate: {:?}",
@@ -126,7 +201,7 @@ pub enum Error {
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Defines the different ways series can be grouped and aggregated
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum GroupByAndAggregate {
     /// group by a set of (Tag) columns, applying an agg to each field
     ///
@@ -161,6 +236,16 @@ pub enum GroupByAndAggregate {
         agg: QueryAggregate,
         every: WindowDuration,
         offset: WindowDuration,
+        fill: WindowFill,
+    },
+
+    /// Group by a "window" in time like `Window`, calculating each
+    /// expression from the aggregates of fields in each window
+    WindowExpressions {
+        exprs: Vec<NamedAggregateExpr>,
+        every: WindowDuration,
+        offset: WindowDuration,
+        fill: WindowFill,
     },
 }
 
@@ -329,6 +414,14 @@ fn convert_simple_node(
                         .context(FieldColumnsNotSupportedSnafu)?;
                     return Ok(builder);
                 }
+                Ok(DecodedTagKey::Normal(tag_name)) if value_list.len() > 1 => {
+                    // tag IN (<values>) rather than a chain of ORs, so
+                    // the list can be used to prune chunks
+                    let list = value_list.into_iter().map(lit).collect();
+                    let expr = build_tag_ref(tag_name.into_bytes())?.in_list(list, false);
+                    builder.inner = builder.inner.with_expr(expr);
+                    return Ok(builder);
+                }
                 _ => {}
             }
         }
@@ -587,13 +680,23 @@ fn build_comparison_node(comparison: i32, inputs: Vec<Expr>) -> Result<Expr> {
     match comparison_enum {
         Some(RPCComparison::Equal) => build_binary_expr(Operator::Eq, inputs),
         Some(RPCComparison::NotEqual) => build_binary_expr(Operator::NotEq, inputs),
-        Some(RPCComparison::StartsWith) => StartsWithNotSupportedSnafu {}.fail(),
+        Some(RPCComparison::StartsWith) => {
+            build_string_match_expr(query_functions::has_prefix_expr, inputs)
+        }
         Some(RPCComparison::Regex) => build_regex_match_expr(true, inputs),
         Some(RPCComparison::NotRegex) => build_regex_match_expr(false, inputs),
         Some(RPCComparison::Lt) => build_binary_expr(Operator::Lt, inputs),
         Some(RPCComparison::Lte) => build_binary_expr(Operator::LtEq, inputs),
         Some(RPCComparison::Gt) => build_binary_expr(Operator::Gt, inputs),
         Some(RPCComparison::Gte) => build_binary_expr(Operator::GtEq, inputs),
+        Some(RPCComparison::EqualFold) => build_equal_fold_expr(true, inputs),
+        Some(RPCComparison::NotEqualFold) => build_equal_fold_expr(false, inputs),
+        Some(RPCComparison::EndsWith) => {
+            build_string_match_expr(query_functions::has_suffix_expr, inputs)
+        }
+        Some(RPCComparison::Contains) => {
+            build_string_match_expr(query_functions::contains_str_expr, inputs)
+        }
         None => UnknownComparisonNodeSnafu { comparison }.fail(),
     }
 }
@@ -639,6 +742,46 @@ fn build_regex_match_expr(matches: bool, mut inputs: Vec<Expr>) -> Result<Expr>
     }
 }
 
+// Creates a DataFusion ScalarUDF expression that compares strings
+// case-insensitively.
+fn build_equal_fold_expr(equal: bool, mut inputs: Vec<Expr>) -> Result<Expr> {
+    let num_children = inputs.len();
+    if num_children != 2 {
+        return EqualFoldUnsupportedNumberOfChildrenSnafu { num_children }.fail();
+    }
+
+    let value = match inputs.remove(1) {
+        Expr::Literal(ScalarValue::Utf8(Some(value))) => value,
+        _ => return EqualFoldValueInvalidSnafu.fail(),
+    };
+
+    let expr = query_functions::equal_fold_expr(inputs.remove(0), value);
+    Ok(if equal {
+        expr
+    } else {
+        Expr::Not(Box::new(expr))
+    })
+}
+
+// Creates a DataFusion ScalarUDF expression that matches a prefix, suffix
+// or substring of strings, as built by `make_expr`.
+fn build_string_match_expr(
+    make_expr: fn(Expr, String) -> Expr,
+    mut inputs: Vec<Expr>,
+) -> Result<Expr> {
+    let num_children = inputs.len();
+    if num_children != 2 {
+        return StringMatchUnsupportedNumberOfChildrenSnafu { num_children }.fail();
+    }
+
+    let value = match inputs.remove(1) {
+        Expr::Literal(ScalarValue::Utf8(Some(value))) => value,
+        _ => return StringMatchValueInvalidSnafu.fail(),
+    };
+
+    Ok(make_expr(inputs.remove(0), value))
+}
+
 pub fn make_read_group_aggregate(
     aggregate: Option<RPCAggregate>,
     group: RPCGroup,
@@ -667,13 +810,60 @@ pub fn make_read_window_aggregate(
     window_every: i64,
     offset: i64,
     window: Option<RPCWindow>,
+    fill: i32,
 ) -> Result<GroupByAndAggregate> {
     // only support single aggregate for now
     if aggregates.len() != 1 {
         return AggregateNotSingletonSnafu { aggregates }.fail();
     }
     let agg = convert_aggregate(aggregates.into_iter().next())?;
+    let (every, offset) = convert_window(window, window_every, offset)?;
+    let fill = convert_fill(fill)?;
+
+    Ok(GroupByAndAggregate::Window {
+        agg,
+        every,
+        offset,
+        fill,
+    })
+}
+
+/// Builds GroupByAndAggregate::WindowExpressions
+pub fn make_read_window_aggregate_expressions(
+    aggregates: Vec<RPCAggregate>,
+    aggregate_expressions: Vec<RPCAggregateExpression>,
+    window_every: i64,
+    offset: i64,
+    window: Option<RPCWindow>,
+    fill: i32,
+) -> Result<GroupByAndAggregate> {
+    ensure!(
+        aggregates.is_empty(),
+        AggregateExpressionsWithAggregateSnafu
+    );
+
+    let exprs = aggregate_expressions
+        .into_iter()
+        .map(convert_aggregate_expression)
+        .collect::<Result<Vec<_>>>()?;
+    let (every, offset) = convert_window(window, window_every, offset)?;
+    let fill = convert_fill(fill)?;
+
+    Ok(GroupByAndAggregate::WindowExpressions {
+        exprs,
+        every,
+        offset,
+        fill,
+    })
+}
 
+/// Converts the window of a `read_window_aggregate` request into its
+/// `every` and `offset` durations
+fn convert_window(
+    window: Option<RPCWindow>,
+    window_every: i64,
+    offset: i64,
+) -> Result<(WindowDuration, WindowDuration)> {
     // Translation from these parameters to window bound
     // is defined in the Go code:
     // https://github.com/influxdata/idpe/pull/8636/files#diff-94c0a8d7e427e2d7abe49f01dced50ad776b65ec8f2c8fb2a2c8b90e2e377ed5R82
@@ -711,7 +901,7 @@ pub fn make_read_window_aggregate(
         }
     };
 
-    Ok(GroupByAndAggregate::Window { agg, every, offset })
+    Ok((every, offset))
 }
 
 enum DurationValidation {
@@ -744,6 +934,15 @@ fn convert_duration(
     }
 }
 
+fn convert_fill(fill: i32) -> Result<WindowFill> {
+    match RPCFill::from_i32(fill) {
+        Some(RPCFill::None) => Ok(WindowFill::None),
+        Some(RPCFill::Previous) => Ok(WindowFill::Previous),
+        Some(RPCFill::Linear) => Ok(WindowFill::Linear),
+        None => UnknownFillSnafu { fill }.fail(),
+    }
+}
+
 fn convert_aggregate(aggregate: Option<RPCAggregate>) -> Result<QueryAggregate> {
     let aggregate = match aggregate {
         None => return Ok(QueryAggregate::None),
@@ -762,10 +961,89 @@ fn convert_aggregate(aggregate: Option<RPCAggregate>) -> Result<QueryAggregate>
         Some(RPCAggregateType::First) => Ok(QueryAggregate::First),
         Some(RPCAggregateType::Last) => Ok(QueryAggregate::Last),
         Some(RPCAggregateType::Mean) => Ok(QueryAggregate::Mean),
+        Some(RPCAggregateType::Rate) => Ok(QueryAggregate::Rate),
+        Some(RPCAggregateType::Derivative) => Ok(QueryAggregate::Derivative),
+        Some(RPCAggregateType::NonNegativeDerivative) => Ok(QueryAggregate::NonNegativeDerivative),
+        Some(RPCAggregateType::Increase) => Ok(QueryAggregate::Increase),
+        Some(RPCAggregateType::Any) => Ok(QueryAggregate::Any),
+        Some(RPCAggregateType::All) => Ok(QueryAggregate::All),
+        Some(aggregate_type @ (RPCAggregateType::Top | RPCAggregateType::Bottom)) => {
+            let n = aggregate.n;
+            ensure!(n > 0, InvalidAggregateNSnafu { aggregate_type, n });
+
+            Ok(match aggregate_type {
+                RPCAggregateType::Top => QueryAggregate::Top(n),
+                _ => QueryAggregate::Bottom(n),
+            })
+        }
         None => UnknownAggregateSnafu { aggregate_type }.fail(),
     }
 }
 
+fn convert_aggregate_expression(expr: RPCAggregateExpression) -> Result<NamedAggregateExpr> {
+    let RPCAggregateExpression { name, expr } = expr;
+    let expr = expr.context(EmptyAggregateExpressionSnafu { name: &name })?;
+
+    Ok(NamedAggregateExpr {
+        name,
+        expr: convert_aggregate_expression_node(expr)?,
+    })
+}
+
+fn convert_aggregate_expression_node(node: RPCAggregateExpressionNode) -> Result<AggregateExpr> {
+    let RPCAggregateExpressionNode { value, children } = node;
+    let value = value.context(EmptyAggregateExpressionNodeSnafu)?;
+
+    let expected = match value {
+        RPCAggregateExpressionValue::Operator(_) => 2,
+        _ => 0,
+    };
+    ensure!(
+        children.len() == expected,
+        InvalidAggregateExpressionChildrenSnafu {
+            num_children: children.len(),
+            expected,
+        }
+    );
+
+    match value {
+        RPCAggregateExpressionValue::FieldAggregate(RPCFieldAggregate { aggregate, field }) => {
+            let agg = convert_aggregate(aggregate)?;
+            ensure!(
+                !matches!(
+                    agg,
+                    QueryAggregate::First
+                        | QueryAggregate::Last
+                        | QueryAggregate::Top(_)
+                        | QueryAggregate::Bottom(_)
+                        | QueryAggregate::None
+                ),
+                UnsupportedExpressionAggregateSnafu { agg }
+            );
+            Ok(AggregateExpr::Field { agg, field })
+        }
+        RPCAggregateExpressionValue::FloatValue(value) => Ok(AggregateExpr::Literal(value)),
+        RPCAggregateExpressionValue::Operator(operator) => {
+            let op = match RPCArithmeticOperator::from_i32(operator) {
+                Some(RPCArithmeticOperator::Add) => ArithmeticOperator::Plus,
+                Some(RPCArithmeticOperator::Subtract) => ArithmeticOperator::Minus,
+                Some(RPCArithmeticOperator::Multiply) => ArithmeticOperator::Multiply,
+                Some(RPCArithmeticOperator::Divide) => ArithmeticOperator::Divide,
+                None => return UnknownAggregateExpressionOperatorSnafu { operator }.fail(),
+            };
+
+            let mut children = children.into_iter().map(convert_aggregate_expression_node);
+            let left = children.next().expect("checked number of children")?;
+            let right = children.next().expect("checked number of children")?;
+            Ok(AggregateExpr::Binary {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            })
+        }
+    }
+}
+
 pub fn convert_group_type(group: i32) -> Result<RPCGroup> {
     RPCGroup::from_i32(group).ok_or(Error::UnknownGroup { group_type: group })
 }
@@ -902,6 +1180,10 @@ fn format_comparison(v: i32, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         Some(RPCComparison::Lte) => write!(f, "<="),
         Some(RPCComparison::Gt) => write!(f, ">"),
         Some(RPCComparison::Gte) => write!(f, ">="),
+        Some(RPCComparison::EqualFold) => write!(f, "EqualFold"),
+        Some(RPCComparison::NotEqualFold) => write!(f, "NotEqualFold"),
+        Some(RPCComparison::EndsWith) => write!(f, "EndsWith"),
+        Some(RPCComparison::Contains) => write!(f, "Contains"),
         None => write!(f, "UNKNOWN_COMPARISON:{v}"),
     }
 }
@@ -1285,6 +1567,87 @@ mod tests {
         assert!(predicate.range.is_none());
     }
 
+    #[test]
+    fn test_convert_predicate_tag_in_list() {
+        // t1 = "a" OR t1 = "b" OR t1 = "c"
+        let selection = make_or_node3(
+            make_tag_ref_node(b"t1", "a"),
+            make_tag_ref_node(b"t1", "b"),
+            make_tag_ref_node(b"t1", "c"),
+        );
+
+        let rpc_predicate = RPCPredicate {
+            root: Some(selection),
+        };
+
+        let predicate = InfluxRpcPredicateBuilder::default()
+            .rpc_predicate(Some(rpc_predicate))
+            .unwrap()
+            .build();
+
+        assert!(predicate.table_names().is_none());
+
+        let predicate = table_predicate(predicate);
+
+        let expected_expr =
+            vec![col("t1").in_list(vec![lit_dict("a"), lit_dict("b"), lit_dict("c")], false)];
+
+        assert_eq!(predicate.exprs, expected_expr);
+        assert_eq!(predicate.field_columns, None);
+        assert!(predicate.range.is_none());
+    }
+
+    #[test]
+    fn test_convert_predicate_tag_equal_fold() {
+        let mut selection = make_tag_ref_node(b"t1", "Foo");
+        selection.value = Some(RPCValue::Comparison(RPCComparison::EqualFold as i32));
+
+        let rpc_predicate = RPCPredicate {
+            root: Some(selection),
+        };
+
+        let predicate = InfluxRpcPredicateBuilder::default()
+            .rpc_predicate(Some(rpc_predicate))
+            .unwrap()
+            .build();
+
+        let predicate = table_predicate(predicate);
+
+        // compared directly against the (dictionary encoded) tag column
+        let expected_expr = vec![query_functions::equal_fold_expr(col("t1"), "Foo".into())];
+
+        assert_eq!(predicate.exprs, expected_expr);
+    }
+
+    #[test]
+    fn test_convert_predicate_tag_string_match() {
+        for (comparison, make_expr) in [
+            (
+                RPCComparison::StartsWith,
+                query_functions::has_prefix_expr as fn(Expr, String) -> Expr,
+            ),
+            (RPCComparison::EndsWith, query_functions::has_suffix_expr),
+            (RPCComparison::Contains, query_functions::contains_str_expr),
+        ] {
+            let mut selection = make_tag_ref_node(b"t1", "Foo");
+            selection.value = Some(RPCValue::Comparison(comparison as i32));
+
+            let rpc_predicate = RPCPredicate {
+                root: Some(selection),
+            };
+
+            let predicate = InfluxRpcPredicateBuilder::default()
+                .rpc_predicate(Some(rpc_predicate))
+                .unwrap()
+                .build();
+
+            let predicate = table_predicate(predicate);
+
+            let expected_expr = vec![make_expr(col("t1"), "Foo".into())];
+            assert_eq!(predicate.exprs, expected_expr, "{comparison:?}");
+        }
+    }
+
     #[test]
     fn test_convert_predicate_multiple_field_selection_flat_node2() {
         let (comparison, expected_expr) = make_host_comparison();
@@ -1680,23 +2043,23 @@ mod tests {
         let pos_3_months = WindowDuration::from_months(3, false);
         let neg_1_months = WindowDuration::from_months(1, true);
 
-        let agg = make_read_window_aggregate(vec![], 5, 10, None);
+        let agg = make_read_window_aggregate(vec![], 5, 10, None, 0);
         let expected =
             "Error creating aggregate: Exactly one aggregate is supported, but 0 were supplied: []";
         assert_eq!(agg.unwrap_err().to_string(), expected);
 
         let agg =
-            make_read_window_aggregate(vec![make_aggregate(1), make_aggregate(2)], 5, 10, None);
-        let expected = "Error creating aggregate: Exactly one aggregate is supported, but 2 were supplied: [Aggregate { r#type: Sum }, Aggregate { r#type: Count }]";
+            make_read_window_aggregate(vec![make_aggregate(1), make_aggregate(2)], 5, 10, None, 0);
+        let expected = "Error creating aggregate: Exactly one aggregate is supported, but 2 were supplied: [Aggregate { r#type: Sum, n: 0 }, Aggregate { r#type: Count, n: 0 }]";
         assert_eq!(agg.unwrap_err().to_string(), expected);
 
         // now window specified
-        let agg = make_read_window_aggregate(vec![make_aggregate(1)], 0, 0, None);
+        let agg = make_read_window_aggregate(vec![make_aggregate(1)], 0, 0, None, 0);
         let expected = "Error parsing window bounds: No window specified";
         assert_eq!(agg.unwrap_err().to_string(), expected);
 
         // correct window + window_every
-        let agg = make_read_window_aggregate(vec![make_aggregate(1)], 5, 10, None).unwrap();
+        let agg = make_read_window_aggregate(vec![make_aggregate(1)], 5, 10, None, 0).unwrap();
         let expected = make_storage_window(QueryAggregate::Sum, pos_5_ns, pos_10_ns);
         assert_eq!(agg, expected);
 
@@ -1706,6 +2069,7 @@ mod tests {
             0,
             0,
             Some(make_rpc_window(5, 0, false, 10, 0, false)),
+            0,
         )
         .unwrap();
         let expected = make_storage_window(QueryAggregate::Sum, pos_5_ns, pos_10_ns);
@@ -1717,6 +2081,7 @@ mod tests {
             0,
             0,
             Some(make_rpc_window(5, 0, false, 0, 0, false)),
+            0,
         )
         .unwrap();
         let expected = make_storage_window(QueryAggregate::Sum, pos_5_ns, WindowDuration::empty());
@@ -1728,6 +2093,7 @@ mod tests {
             0,
             0,
             Some(make_rpc_window(0, 3, false, 0, 1, true)),
+            0,
         )
         .unwrap();
         let expected = make_storage_window(QueryAggregate::Sum, pos_3_months, neg_1_months);
@@ -1739,6 +2105,7 @@ mod tests {
             0,
             0,
             Some(make_rpc_window(0, 1, true, 0, 3, false)),
+            0,
         )
         .unwrap();
         let expected = make_storage_window(QueryAggregate::Sum, neg_1_months, pos_3_months);
@@ -1751,6 +2118,7 @@ mod tests {
             5,
             10,
             Some(make_rpc_window(100, 0, false, 200, 0, false)),
+            0,
         )
         .unwrap();
         let expected = make_storage_window(QueryAggregate::Sum, pos_5_ns, pos_10_ns);
@@ -1762,6 +2130,7 @@ mod tests {
             0,
             0,
             Some(make_rpc_window(5, 1, false, 10, 0, false)),
+            0,
         );
         let expected = "Error parsing window bounds duration \'window.every\': duration used as an interval cannot mix month and nanosecond units";
         assert_eq!(agg.unwrap_err().to_string(), expected);
@@ -1772,6 +2141,7 @@ mod tests {
             0,
             0,
             Some(make_rpc_window(5, 0, false, 10, 1, false)),
+            0,
         );
         let expected = "Error parsing window bounds duration \'window.offset\': duration used as an interval cannot mix month and nanosecond units";
         assert_eq!(agg.unwrap_err().to_string(), expected);
@@ -1782,9 +2152,141 @@ mod tests {
             0,
             0,
             Some(make_rpc_window(0, 0, false, 5, 0, false)),
+            0,
         );
         let expected = "Error parsing window bounds duration \'window.every\': duration used as an interval cannot be zero";
         assert_eq!(agg.unwrap_err().to_string(), expected);
+
+        // fill
+        let agg = make_read_window_aggregate(vec![make_aggregate(1)], 5, 10, None, 2).unwrap();
+        let expected = GroupByAndAggregate::Window {
+            agg: QueryAggregate::Sum,
+            every: pos_5_ns,
+            offset: pos_10_ns,
+            fill: WindowFill::Linear,
+        };
+        assert_eq!(agg, expected);
+
+        // invalid fill
+        let agg = make_read_window_aggregate(vec![make_aggregate(1)], 5, 10, None, 100);
+        let expected = "Error creating aggregate: Unknown fill type 100";
+        assert_eq!(agg.unwrap_err().to_string(), expected);
+    }
+
+    #[test]
+    fn test_make_read_window_aggregate_expressions() {
+        let field_aggregate = |t, field: &str| RPCAggregateExpressionNode {
+            value: Some(RPCAggregateExpressionValue::FieldAggregate(
+                RPCFieldAggregate {
+                    aggregate: Some(make_aggregate(t)),
+                    field: field.to_string(),
+                },
+            )),
+            children: vec![],
+        };
+        let binary = |op: RPCArithmeticOperator, children| RPCAggregateExpressionNode {
+            value: Some(RPCAggregateExpressionValue::Operator(op as i32)),
+            children,
+        };
+        let named = |expr| RPCAggregateExpression {
+            name: "ratio".to_string(),
+            expr: Some(expr),
+        };
+
+        // sum(bytes) / sum(requests) * 100
+        let expr = binary(
+            RPCArithmeticOperator::Multiply,
+            vec![
+                binary(
+                    RPCArithmeticOperator::Divide,
+                    vec![field_aggregate(1, "bytes"), field_aggregate(1, "requests")],
+                ),
+                RPCAggregateExpressionNode {
+                    value: Some(RPCAggregateExpressionValue::FloatValue(100.0)),
+                    children: vec![],
+                },
+            ],
+        );
+        let agg = make_read_window_aggregate_expressions(vec![], vec![named(expr)], 5, 10, None, 0)
+            .unwrap();
+        let expected = GroupByAndAggregate::WindowExpressions {
+            exprs: vec![NamedAggregateExpr {
+                name: "ratio".to_string(),
+                expr: AggregateExpr::Binary {
+                    left: Box::new(AggregateExpr::Binary {
+                        left: Box::new(AggregateExpr::Field {
+                            agg: QueryAggregate::Sum,
+                            field: "bytes".to_string(),
+                        }),
+                        op: ArithmeticOperator::Divide,
+                        right: Box::new(AggregateExpr::Field {
+                            agg: QueryAggregate::Sum,
+                            field: "requests".to_string(),
+                        }),
+                    }),
+                    op: ArithmeticOperator::Multiply,
+                    right: Box::new(AggregateExpr::Literal(100.0)),
+                },
+            }],
+            every: WindowDuration::from_nanoseconds(5),
+            offset: WindowDuration::from_nanoseconds(10),
+            fill: WindowFill::None,
+        };
+        assert_eq!(agg, expected);
+
+        let agg = make_read_window_aggregate_expressions(
+            vec![make_aggregate(1)],
+            vec![named(field_aggregate(1, "bytes"))],
+            5,
+            10,
+            None,
+            0,
+        );
+        let expected =
+            "Error creating aggregate: aggregate and aggregate_expressions are mutually exclusive";
+        assert_eq!(agg.unwrap_err().to_string(), expected);
+
+        // selectors have no single value per window
+        let agg = make_read_window_aggregate_expressions(
+            vec![],
+            vec![named(field_aggregate(5, "bytes"))],
+            5,
+            10,
+            None,
+            0,
+        );
+        let expected =
+            "Error creating aggregate expression: Aggregate First not supported in expressions";
+        assert_eq!(agg.unwrap_err().to_string(), expected);
+
+        let agg = make_read_window_aggregate_expressions(
+            vec![],
+            vec![named(binary(
+                RPCArithmeticOperator::Add,
+                vec![field_aggregate(1, "bytes")],
+            ))],
+            5,
+            10,
+            None,
+            0,
+        );
+        let expected =
+            "Error creating aggregate expression: Unsupported number of children: 1 (must be 2)";
+        assert_eq!(agg.unwrap_err().to_string(), expected);
+
+        let agg = make_read_window_aggregate_expressions(
+            vec![],
+            vec![RPCAggregateExpression {
+                name: "ratio".to_string(),
+                expr: None,
+            }],
+            5,
+            10,
+            None,
+            0,
+        );
+        let expected = "Error creating aggregate expression 'ratio': Missing expression";
+        assert_eq!(agg.unwrap_err().to_string(), expected);
     }
 
     #[test]
@@ -1832,6 +2334,44 @@ mod tests {
             convert_aggregate(Some(make_aggregate(7))).unwrap(),
             QueryAggregate::Mean
         );
+        assert_eq!(
+            convert_aggregate(Some(make_aggregate(8))).unwrap(),
+            QueryAggregate::Rate
+        );
+        assert_eq!(
+            convert_aggregate(Some(make_aggregate(9))).unwrap(),
+            QueryAggregate::Derivative
+        );
+        assert_eq!(
+            convert_aggregate(Some(make_aggregate(10))).unwrap(),
+            QueryAggregate::NonNegativeDerivative
+        );
+        assert_eq!(
+            convert_aggregate(Some(make_aggregate(11))).unwrap(),
+            QueryAggregate::Increase
+        );
+        assert_eq!(
+            convert_aggregate(Some(RPCAggregate { r#type: 12, n: 3 })).unwrap(),
+            QueryAggregate::Top(3)
+        );
+        assert_eq!(
+            convert_aggregate(Some(RPCAggregate { r#type: 13, n: 1 })).unwrap(),
+            QueryAggregate::Bottom(1)
+        );
+        assert_eq!(
+            convert_aggregate(Some(make_aggregate(14))).unwrap(),
+            QueryAggregate::Any
+        );
+        assert_eq!(
+            convert_aggregate(Some(make_aggregate(15))).unwrap(),
+            QueryAggregate::All
+        );
+        assert_eq!(
+            convert_aggregate(Some(make_aggregate(12)))
+                .unwrap_err()
+                .to_string(),
+            "Error creating aggregate: Top requires a positive number of rows to select, got 0"
+        );
         assert_eq!(
             convert_aggregate(Some(make_aggregate(100)))
                 .unwrap_err()
@@ -1841,7 +2381,7 @@ mod tests {
     }
 
     fn make_aggregate(t: i32) -> RPCAggregate {
-        RPCAggregate { r#type: t }
+        RPCAggregate { r#type: t, n: 0 }
     }
 
     fn make_rpc_window(
@@ -1871,7 +2411,12 @@ mod tests {
         every: WindowDuration,
         offset: WindowDuration,
     ) -> GroupByAndAggregate {
-        GroupByAndAggregate::Window { agg, every, offset }
+        GroupByAndAggregate::Window {
+            agg,
+            every,
+            offset,
+            fill: WindowFill::None,
+        }
     }
 
     #[test]
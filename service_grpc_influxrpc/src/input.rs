@@ -1,9 +1,9 @@
 use tonic::Status;
 
 use generated_types::{
-    google::protobuf::Any, MeasurementFieldsRequest, MeasurementNamesRequest,
-    MeasurementTagKeysRequest, MeasurementTagValuesRequest, ReadFilterRequest, ReadGroupRequest,
-    ReadSource, ReadWindowAggregateRequest, TagKeysRequest,
+    google::protobuf::Any, FieldsGroupedByMeasurementRequest, MeasurementFieldsRequest,
+    MeasurementNamesRequest, MeasurementTagKeysRequest, MeasurementTagValuesRequest,
+    ReadFilterRequest, ReadGroupRequest, ReadSource, ReadWindowAggregateRequest, TagKeysRequest,
     TagValuesGroupedByMeasurementAndTagKeyRequest, TagValuesRequest,
 };
 
@@ -101,6 +101,12 @@ impl GrpcInputs for MeasurementFieldsRequest {
     }
 }
 
+impl GrpcInputs for FieldsGroupedByMeasurementRequest {
+    fn read_source_field(&self) -> Option<&Any> {
+        self.source.as_ref()
+    }
+}
+
 impl GrpcInputs for ReadWindowAggregateRequest {
     fn read_source_field(&self) -> Option<&Any> {
         self.read_source.as_ref()
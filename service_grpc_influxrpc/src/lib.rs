@@ -37,6 +37,7 @@ pub mod input;
 mod permit;
 mod query_completed_token;
 mod response_chunking;
+mod result_limit;
 pub mod service;
 
 #[cfg(any(test, feature = "test-util"))]
@@ -44,16 +45,32 @@ pub mod test_util;
 
 use generated_types::storage_server::{Storage, StorageServer};
 use service_common::QueryNamespaceProvider;
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc};
+
+pub use result_limit::{ResultLimits, PARTIAL_RESULTS_TRAILER};
 
 /// Concrete implementation of the gRPC InfluxDB Storage Service API
 #[derive(Debug)]
 struct StorageService<T: QueryNamespaceProvider> {
     pub db_store: Arc<T>,
+
+    /// The maximum number of series a single `read_filter`, `read_group` or
+    /// `read_window_aggregate` request may return.
+    pub max_series_per_query: Option<NonZeroUsize>,
+
+    /// Limits of the size of the results of `read_filter`, `read_group` and
+    /// `read_window_aggregate` requests.
+    pub result_limits: ResultLimits,
 }
 
 pub fn make_server<T: QueryNamespaceProvider + 'static>(
     db_store: Arc<T>,
+    max_series_per_query: Option<NonZeroUsize>,
+    result_limits: ResultLimits,
 ) -> StorageServer<impl Storage> {
-    StorageServer::new(StorageService { db_store })
+    StorageServer::new(StorageService {
+        db_store,
+        max_series_per_query,
+        result_limits,
+    })
 }
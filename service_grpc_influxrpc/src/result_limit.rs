@@ -0,0 +1,269 @@
+//! Limits of the size of storage read results.
+//!
+//! Results exceeding the [`ResultLimits`] are truncated instead of failing the request: the
+//! client receives the frames up to the limit, and the response ends with the
+//! [`PARTIAL_RESULTS_TRAILER`] trailer. This suits dashboards, which prefer truncated data over
+//! errors.
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{ready, Stream, StreamExt};
+use generated_types::{read_response::frame::Data, read_response::Frame};
+use iox_query::exec::PartialResults;
+use pin_project::pin_project;
+use prost::Message;
+use tonic::{metadata::MetadataMap, Code, Status};
+
+/// Trailer set to `true` at the end of responses whose results are incomplete, either because
+/// they were truncated to the [`ResultLimits`] or because a federated query skipped unavailable
+/// backends.
+pub const PARTIAL_RESULTS_TRAILER: &str = "iox-partial-results";
+
+/// Upper bounds of the size of the results of a single `read_filter`, `read_group` or
+/// `read_window_aggregate` request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResultLimits {
+    /// Maximum number of points. Unlimited if not set.
+    pub max_points: Option<usize>,
+
+    /// Maximum encoded size of the frames, in bytes. Unlimited if not set.
+    ///
+    /// Frames are never split to meet this limit: the results end before the first frame that
+    /// would exceed it.
+    pub max_bytes: Option<usize>,
+}
+
+/// Truncate `frames` to `limits`, setting `partial` if any frames were dropped.
+pub(crate) fn limit_results<S, E>(
+    frames: S,
+    limits: ResultLimits,
+    partial: PartialResults,
+) -> impl Stream<Item = Result<Frame, E>>
+where
+    S: Stream<Item = Result<Frame, E>>,
+{
+    // the (points, bytes) yielded so far, or `None` once the results were truncated
+    frames.scan(Some((0_usize, 0_usize)), move |state, res| {
+        let Some((points, bytes)) = state else {
+            return futures::future::ready(None);
+        };
+        let mut frame = match res {
+            Ok(frame) => frame,
+            Err(e) => return futures::future::ready(Some(Err(e))),
+        };
+
+        let mut truncated = false;
+        let mut keep = true;
+        if let Some(max_points) = limits.max_points {
+            let remaining = max_points.saturating_sub(*points);
+            let n = truncate_points(&mut frame, remaining);
+            if n > remaining {
+                truncated = true;
+                keep = remaining > 0;
+            }
+            *points += n.min(remaining);
+        }
+        if let Some(max_bytes) = limits.max_bytes {
+            let size = frame.encoded_len();
+            if keep && *bytes + size > max_bytes {
+                truncated = true;
+                keep = false;
+            }
+            *bytes += size;
+        }
+
+        if truncated {
+            *state = None;
+            partial.set();
+        }
+        futures::future::ready(keep.then_some(Ok(frame)))
+    })
+}
+
+/// Truncate the points of `frame` to at most `n`, returning the number of points it had.
+fn truncate_points(frame: &mut Frame, n: usize) -> usize {
+    fn truncate<T>(timestamps: &mut Vec<i64>, values: &mut Vec<T>, n: usize) -> usize {
+        let len = timestamps.len();
+        timestamps.truncate(n);
+        values.truncate(n);
+        len
+    }
+
+    match &mut frame.data {
+        Some(Data::FloatPoints(p)) => truncate(&mut p.timestamps, &mut p.values, n),
+        Some(Data::IntegerPoints(p)) => truncate(&mut p.timestamps, &mut p.values, n),
+        Some(Data::UnsignedPoints(p)) => truncate(&mut p.timestamps, &mut p.values, n),
+        Some(Data::BooleanPoints(p)) => truncate(&mut p.timestamps, &mut p.values, n),
+        Some(Data::StringPoints(p)) => truncate(&mut p.timestamps, &mut p.values, n),
+        Some(Data::Series(_)) | Some(Data::Group(_)) | None => 0,
+    }
+}
+
+/// Wraps a response stream, ending it with the [`PARTIAL_RESULTS_TRAILER`] if the results are
+/// flagged as partial once the stream is exhausted.
+///
+/// tonic sends the metadata of a [`Status`] ending a response stream as trailers, so the flag is
+/// sent as a final status with [`Code::Ok`]: clients see a successful response.
+#[derive(Debug)]
+#[pin_project]
+pub struct PartialResultsStream<S> {
+    #[pin]
+    inner: S,
+    partial: PartialResults,
+    done: bool,
+}
+
+impl<S> PartialResultsStream<S> {
+    pub fn new(inner: S, partial: PartialResults) -> Self {
+        Self {
+            inner,
+            partial,
+            done: false,
+        }
+    }
+}
+
+impl<S, T> Stream for PartialResultsStream<S>
+where
+    S: Stream<Item = Result<T, Status>>,
+{
+    type Item = Result<T, Status>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        match ready!(this.inner.poll_next(cx)) {
+            Some(res) => Poll::Ready(Some(res)),
+            None => {
+                *this.done = true;
+                if !this.partial.get() {
+                    return Poll::Ready(None);
+                }
+
+                let mut metadata = MetadataMap::new();
+                metadata.insert(PARTIAL_RESULTS_TRAILER, "true".parse().unwrap());
+                Poll::Ready(Some(Err(Status::with_metadata(Code::Ok, "", metadata))))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use generated_types::read_response::{FloatPointsFrame, SeriesFrame};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unlimited() {
+        let (frames, partial) = run(ResultLimits::default()).await;
+        assert_eq!(frames, vec![0, 2, 0, 3]);
+        assert!(!partial);
+    }
+
+    #[tokio::test]
+    async fn test_max_points() {
+        let (frames, partial) = run(ResultLimits {
+            max_points: Some(3),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(frames, vec![0, 2, 0, 1]);
+        assert!(partial);
+
+        // a frame that would be cut to no points at all is dropped
+        let (frames, partial) = run(ResultLimits {
+            max_points: Some(2),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(frames, vec![0, 2, 0]);
+        assert!(partial);
+
+        // results that exactly fit are complete
+        let (frames, partial) = run(ResultLimits {
+            max_points: Some(5),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(frames, vec![0, 2, 0, 3]);
+        assert!(!partial);
+    }
+
+    #[tokio::test]
+    async fn test_max_bytes() {
+        let size = series().encoded_len() + points(2).encoded_len();
+        let (frames, partial) = run(ResultLimits {
+            max_bytes: Some(size + series().encoded_len()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(frames, vec![0, 2, 0]);
+        assert!(partial);
+    }
+
+    #[tokio::test]
+    async fn test_trailer() {
+        let partial = PartialResults::default();
+        let stream = || futures::stream::iter([Ok::<_, Status>(1)]);
+
+        let items: Vec<_> = PartialResultsStream::new(stream(), partial.clone())
+            .collect()
+            .await;
+        assert_eq!(items.len(), 1);
+
+        partial.set();
+        let items: Vec<_> = PartialResultsStream::new(stream(), partial).collect().await;
+        assert_eq!(items.len(), 2);
+        let status = items[1].as_ref().unwrap_err();
+        assert_eq!(status.code(), Code::Ok);
+        assert_eq!(
+            status.metadata().get(PARTIAL_RESULTS_TRAILER).unwrap(),
+            "true"
+        );
+    }
+
+    /// Limit two series of 2 and 3 points, returning the number of points of each returned
+    /// frame (0 for series frames) and whether the results were flagged as partial.
+    async fn run(limits: ResultLimits) -> (Vec<usize>, bool) {
+        let frames = futures::stream::iter([
+            Ok::<_, Status>(series()),
+            Ok(points(2)),
+            Ok(series()),
+            Ok(points(3)),
+        ]);
+        let partial = PartialResults::default();
+
+        let frames: Vec<_> = limit_results(frames, limits, partial.clone())
+            .map(|frame| match frame.unwrap().data {
+                Some(Data::FloatPoints(p)) => {
+                    assert_eq!(p.timestamps.len(), p.values.len());
+                    p.timestamps.len()
+                }
+                _ => 0,
+            })
+            .collect()
+            .await;
+        (frames, partial.get())
+    }
+
+    fn series() -> Frame {
+        Frame {
+            data: Some(Data::Series(SeriesFrame::default())),
+        }
+    }
+
+    fn points(n: usize) -> Frame {
+        Frame {
+            data: Some(Data::FloatPoints(FloatPointsFrame {
+                timestamps: (0..n as i64).collect(),
+                values: (0..n).map(|v| v as f64).collect(),
+            })),
+        }
+    }
+}
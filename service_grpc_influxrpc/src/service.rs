@@ -4,46 +4,61 @@
 use super::{TAG_KEY_FIELD, TAG_KEY_MEASUREMENT};
 use crate::{
     data::{
-        fieldlist_to_measurement_fields_response, series_or_groups_to_frames, tag_keys_to_byte_vecs,
+        encode_series_tags, fieldlist_to_measurement_fields_response,
+        measurement_fieldlist_to_responses, scale_timestamps, series_or_groups_to_frames,
+        tag_keys_to_byte_vecs,
     },
     expr::{self, DecodedTagKey, GroupByAndAggregate, InfluxRpcPredicateBuilder, Loggable},
     input::GrpcInputs,
     permit::StreamWithPermit,
     query_completed_token::QueryCompletedTokenStream,
     response_chunking::ChunkReadResponses,
+    result_limit::{limit_results, PartialResultsStream},
     StorageService,
 };
 use data_types::NamespaceName;
 use datafusion::error::DataFusionError;
 use futures::{stream::BoxStream, Stream, StreamExt, TryStreamExt};
 use generated_types::{
-    google::protobuf::{Any as ProtoAny, Empty},
+    google::{
+        self,
+        protobuf::{Any as ProtoAny, Empty},
+    },
     influxdata::platform::errors::InfluxDbError,
     literal_or_regex::Value as RegexOrLiteralValue,
     offsets_response::PartitionOffsetResponse,
-    read_response::Frame,
+    read_filter_request,
+    read_response::{frame::Data, Frame},
     storage_server::Storage,
-    tag_key_predicate, CapabilitiesResponse, Capability, Int64ValuesResponse, LiteralOrRegex,
+    tag_key_predicate, CapabilitiesResponse, Capability, FieldsGroupedByMeasurementRequest,
+    FieldsGroupedByMeasurementResponse, Int64ValuesResponse, LiteralOrRegex,
     MeasurementFieldsRequest, MeasurementFieldsResponse, MeasurementNamesRequest,
     MeasurementTagKeysRequest, MeasurementTagValuesRequest, OffsetsResponse, Predicate,
     ReadFilterRequest, ReadGroupRequest, ReadResponse, ReadSeriesCardinalityRequest,
-    ReadWindowAggregateRequest, StringValuesResponse, TagKeyMetaNames, TagKeysRequest,
-    TagValuesGroupedByMeasurementAndTagKeyRequest, TagValuesRequest, TagValuesResponse,
-    TimestampRange,
+    ReadWindowAggregateRequest, SeriesFrameEncoding, StringValuesResponse, TagKeyMetaNames,
+    TagKeysRequest, TagValuesGroupedByMeasurementAndTagKeyRequest, TagValuesRequest,
+    TagValuesResponse, TimestampPrecision, TimestampRange,
 };
 use iox_query::{
     exec::{
-        fieldlist::FieldList, seriesset::converter::Error as SeriesSetError, IOxSessionContext,
+        fieldlist::{FieldList, MeasurementFieldList},
+        seriesset::converter::Error as SeriesSetError,
+        IOxSessionContext, PartialResults,
     },
     QueryCompletedToken, QueryNamespace, QueryText,
 };
 use observability_deps::tracing::{error, info, trace};
 use prost::{bytes::BytesMut, Message};
-use service_common::{datafusion_error_to_tonic_code, planner::Planner, QueryNamespaceProvider};
+use service_common::{
+    datafusion_error_to_tonic_code, has_debug_header,
+    planner::{Planner, PointOrder},
+    QueryNamespaceProvider,
+};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{
     collections::{BTreeSet, HashMap},
     fmt::{Display, Formatter, Result as FmtResult},
+    num::NonZeroUsize,
     sync::Arc,
 };
 use tokio_stream::wrappers::ReceiverStream;
@@ -62,6 +77,10 @@ const MAX_READ_RESPONSE_SIZE: usize = 4194304 - 100_000; // 4MB - <wiggle room>
 /// and is used to avoid overlarge individual gRPC messages.
 const MAX_POINTS_PER_FRAME: usize = 1000;
 
+/// How long clients are asked to wait before retrying requests that failed
+/// because the querier was temporarily out of resources.
+const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
 #[derive(Debug, Snafu)]
 pub enum Error {
     #[snafu(display("Namespace not found: {}", db_name))]
@@ -190,6 +209,13 @@ pub enum Error {
 
     #[snafu(display("Operation not yet implemented:  {}", operation))]
     NotYetImplemented { operation: String },
+
+    #[snafu(display(
+        "Query returns more than the limit of {} series. Add predicates on the measurement, \
+        tags or fields to select fewer series",
+        limit
+    ))]
+    SeriesLimitExceeded { limit: usize },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -208,6 +234,8 @@ impl Error {
     /// status
     fn into_status(self) -> Status {
         let msg = self.to_string();
+        let field = self.request_field();
+        let series_limit = matches!(self, Self::SeriesLimitExceeded { .. });
 
         let code = match self {
             Self::NamespaceNotFound { .. } => tonic::Code::NotFound,
@@ -247,6 +275,7 @@ impl Error {
                 tonic::Code::Internal
             }
             Self::NotYetImplemented { .. } => tonic::Code::Unimplemented,
+            Self::SeriesLimitExceeded { .. } => tonic::Code::ResourceExhausted,
         };
 
         // InfluxRPC clients expect an instance of InfluxDbError
@@ -276,10 +305,59 @@ impl Error {
             value: err_bytes.freeze(),
         };
 
-        let mut tonic_status = generated_types::google::encode_status(code, msg, any_err);
+        // Other clients can use the standard google.rpc error details
+        // to find the offending request field, or whether and when to
+        // retry the request, without parsing the message.
+        let details = match code {
+            tonic::Code::InvalidArgument => field
+                .map(|field| {
+                    google::encode_bad_request(vec![google::FieldViolation {
+                        field: field.to_string(),
+                        description: msg.clone(),
+                    }])
+                })
+                .transpose(),
+            tonic::Code::ResourceExhausted if series_limit => {
+                google::encode_quota_failure(vec![google::QuotaFailure {
+                    subject: "series".to_string(),
+                    description: msg.clone(),
+                }])
+                .map(Some)
+            }
+            tonic::Code::ResourceExhausted | tonic::Code::Unavailable => {
+                google::encode_retry_info(RETRY_DELAY).map(Some)
+            }
+            _ => Ok(None),
+        };
+        let details = match details {
+            Ok(details) => std::iter::once(any_err).chain(details).collect(),
+            Err(e) => return e.into(),
+        };
+
+        let mut tonic_status = google::encode_status_details(code, msg, details);
         add_headers(tonic_status.metadata_mut());
         tonic_status
     }
+
+    /// The request field that caused this error, if it is caused by
+    /// an invalid request field
+    fn request_field(&self) -> Option<&'static str> {
+        match self {
+            Self::ConvertingPredicate { .. } | Self::SettingPredicateTable { .. } => {
+                Some("predicate")
+            }
+            Self::ConvertingReadGroupType { .. } => Some("group"),
+            Self::ConvertingReadGroupAggregate { .. } | Self::ConvertingWindowAggregate { .. } => {
+                Some("aggregate")
+            }
+            Self::ConvertingTagKeyInTagValues { .. } => Some("tag_key"),
+            Self::MeasurementLiteralOrRegex { .. } => Some("MeasurementPatterns"),
+            Self::MissingTagKeyPredicate {} | Self::InvalidTagKeyRegex { .. } => {
+                Some("TagKeyPredicate")
+            }
+            _ => None,
+        }
+    }
 }
 
 /// These are the set of error codes that can appear in an InfluxDBError.
@@ -363,8 +441,7 @@ impl<T> Storage for StorageService<T>
 where
     T: QueryNamespaceProvider + 'static,
 {
-    type ReadFilterStream =
-        StreamWithPermit<QueryCompletedTokenStream<ChunkReadResponses, ReadResponse, Status>>;
+    type ReadFilterStream = ResponseStream<ChunkReadResponses, ReadResponse>;
 
     async fn read_filter(
         &self,
@@ -373,6 +450,7 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -389,30 +467,31 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "read_filter",
             defer_json(&req),
         );
 
-        let frames = read_filter_impl(Arc::clone(&db), db_name, req, &ctx)
-            .await?
-            .map_err(|e| e.into_status());
+        let frames = read_filter_impl(Arc::clone(&db), db_name, req, &ctx).await?;
+        let frames = limit_series(frames, self.max_series_per_query).map_err(|e| e.into_status());
+        let frames = limit_results(frames, self.result_limits, ctx.partial_results());
 
         make_response(
             ChunkReadResponses::new(frames, MAX_READ_RESPONSE_SIZE),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
-    type ReadGroupStream =
-        StreamWithPermit<QueryCompletedTokenStream<ChunkReadResponses, ReadResponse, Status>>;
+    type ReadGroupStream = ResponseStream<ChunkReadResponses, ReadResponse>;
 
     async fn read_group(
         &self,
@@ -420,6 +499,7 @@ where
     ) -> Result<Response<Self::ReadGroupStream>, Status> {
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -441,12 +521,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "read_group",
             defer_json(&req),
@@ -459,6 +540,7 @@ where
             group_keys,
             group,
             aggregate,
+            timestamp_precision,
         } = req;
 
         let aggregate_string =
@@ -478,21 +560,23 @@ where
             predicate,
             gby_agg,
             TagKeyMetaNames::Text,
+            TimestampPrecision::from_i32(timestamp_precision).unwrap_or_default(),
             &ctx,
         )
         .await
-        .map_err(|e| e.into_status())?
-        .map_err(|e| e.into_status());
+        .map_err(|e| e.into_status())?;
+        let frames = limit_series(frames, self.max_series_per_query).map_err(|e| e.into_status());
+        let frames = limit_results(frames, self.result_limits, ctx.partial_results());
 
         make_response(
             ChunkReadResponses::new(frames, MAX_READ_RESPONSE_SIZE),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
-    type ReadWindowAggregateStream =
-        StreamWithPermit<QueryCompletedTokenStream<ChunkReadResponses, ReadResponse, Status>>;
+    type ReadWindowAggregateStream = ResponseStream<ChunkReadResponses, ReadResponse>;
 
     async fn read_window_aggregate(
         &self,
@@ -500,6 +584,7 @@ where
     ) -> Result<Response<Self::ReadGroupStream>, Status> {
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -513,7 +598,9 @@ where
             ?req.window_every,
             ?req.offset,
             ?req.aggregate,
+            ?req.aggregate_expressions,
             ?req.window,
+            ?req.fill,
             predicate=%req.predicate.loggable(),
             trace=%external_span_ctx.format_jaeger(),
             "read_window_aggregate",
@@ -521,12 +608,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "read_window_aggregate",
             defer_json(&req),
@@ -541,14 +629,28 @@ where
             aggregate,
             window,
             tag_key_meta_names,
+            fill,
+            timestamp_precision,
+            aggregate_expressions,
         } = req;
 
         let aggregate_string = format!(
-            "aggregate: {aggregate:?}, window_every: {window_every:?}, offset: {offset:?}, window: {window:?}"
+            "aggregate: {aggregate:?}, aggregate_expressions: {aggregate_expressions:?}, window_every: {window_every:?}, offset: {offset:?}, window: {window:?}, fill: {fill:?}"
         );
 
-        let gby_agg = expr::make_read_window_aggregate(aggregate, window_every, offset, window)
-            .context(ConvertingWindowAggregateSnafu { aggregate_string })?;
+        let gby_agg = if aggregate_expressions.is_empty() {
+            expr::make_read_window_aggregate(aggregate, window_every, offset, window, fill)
+        } else {
+            expr::make_read_window_aggregate_expressions(
+                aggregate,
+                aggregate_expressions,
+                window_every,
+                offset,
+                window,
+                fill,
+            )
+        }
+        .context(ConvertingWindowAggregateSnafu { aggregate_string })?;
 
         let frames = query_group_impl(
             Arc::clone(&db),
@@ -557,25 +659,25 @@ where
             predicate,
             gby_agg,
             TagKeyMetaNames::from_i32(tag_key_meta_names).unwrap_or_default(),
+            TimestampPrecision::from_i32(timestamp_precision).unwrap_or_default(),
             &ctx,
         )
         .await
-        .map_err(|e| e.into_status())?
-        .map_err(|e| e.into_status());
+        .map_err(|e| e.into_status())?;
+        let frames = limit_series(frames, self.max_series_per_query).map_err(|e| e.into_status());
+        let frames = limit_results(frames, self.result_limits, ctx.partial_results());
 
         make_response(
             ChunkReadResponses::new(frames, MAX_READ_RESPONSE_SIZE),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
-    type TagKeysStream = StreamWithPermit<
-        QueryCompletedTokenStream<
-            BoxStream<'static, Result<StringValuesResponse, Status>>,
-            StringValuesResponse,
-            Status,
-        >,
+    type TagKeysStream = ResponseStream<
+        BoxStream<'static, Result<StringValuesResponse, Status>>,
+        StringValuesResponse,
     >;
 
     async fn tag_keys(
@@ -585,6 +687,7 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -602,12 +705,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "tag_keys",
             defer_json(&req),
@@ -636,15 +740,13 @@ where
             futures::stream::once(async move { response }).boxed(),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
-    type TagValuesStream = StreamWithPermit<
-        QueryCompletedTokenStream<
-            BoxStream<'static, Result<StringValuesResponse, Status>>,
-            StringValuesResponse,
-            Status,
-        >,
+    type TagValuesStream = ResponseStream<
+        BoxStream<'static, Result<StringValuesResponse, Status>>,
+        StringValuesResponse,
     >;
 
     async fn tag_values(
@@ -654,6 +756,7 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -674,12 +777,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "tag_values",
             defer_json(&req),
@@ -742,15 +846,13 @@ where
             futures::stream::once(async move { response }).boxed(),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
-    type TagValuesGroupedByMeasurementAndTagKeyStream = StreamWithPermit<
-        QueryCompletedTokenStream<
-            futures::stream::Iter<std::vec::IntoIter<Result<TagValuesResponse, Status>>>,
-            TagValuesResponse,
-            Status,
-        >,
+    type TagValuesGroupedByMeasurementAndTagKeyStream = ResponseStream<
+        futures::stream::Iter<std::vec::IntoIter<Result<TagValuesResponse, Status>>>,
+        TagValuesResponse,
     >;
 
     async fn tag_values_grouped_by_measurement_and_tag_key(
@@ -760,6 +862,7 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -778,12 +881,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "tag_values_grouped_by_measurement_and_tag_key",
             defer_json(&req),
@@ -801,6 +905,7 @@ where
             futures::stream::iter(results),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
@@ -826,6 +931,22 @@ where
         // For now, hard code our list of support
         let caps = [
             ("KeySortCapability", vec!["ReadFilter"]),
+            (
+                "PredicateCapability",
+                vec![
+                    "Comparison",
+                    "Regex",
+                    "NotRegex",
+                    "StartsWith",
+                    "ValueFilter",
+                    "MeasurementFilter",
+                    "FieldFilter",
+                ],
+            ),
+            (
+                "SeriesFrameEncodingCapability",
+                vec!["SharedTagPrefixReadFilter"],
+            ),
             ("Group", vec!["First", "Last", "Min", "Max"]),
             (
                 "TagKeyMetaNamesCapability",
@@ -842,6 +963,12 @@ where
             ),
         ];
 
+        // Limits are reported as `name=value` features
+        let limits = [
+            format!("MaxPointsPerFrame={MAX_POINTS_PER_FRAME}"),
+            format!("MaxReadResponseBytes={MAX_READ_RESPONSE_SIZE}"),
+        ];
+
         // Turn it into the HashMap -> Capabiltity
         let caps = caps
             .iter()
@@ -849,18 +976,21 @@ where
                 let features = features.iter().map(|f| f.to_string()).collect::<Vec<_>>();
                 (cap_name.to_string(), Capability { features })
             })
+            .chain(std::iter::once((
+                "LimitsCapability".to_string(),
+                Capability {
+                    features: limits.to_vec(),
+                },
+            )))
             .collect::<HashMap<String, Capability>>();
 
         let caps = CapabilitiesResponse { caps };
         Ok(Response::new(caps))
     }
 
-    type MeasurementNamesStream = StreamWithPermit<
-        QueryCompletedTokenStream<
-            BoxStream<'static, Result<StringValuesResponse, Status>>,
-            StringValuesResponse,
-            Status,
-        >,
+    type MeasurementNamesStream = ResponseStream<
+        BoxStream<'static, Result<StringValuesResponse, Status>>,
+        StringValuesResponse,
     >;
 
     async fn measurement_names(
@@ -870,6 +1000,7 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -887,12 +1018,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "measurement_names",
             defer_json(&req),
@@ -912,15 +1044,13 @@ where
             futures::stream::once(async move { response }).boxed(),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
-    type MeasurementTagKeysStream = StreamWithPermit<
-        QueryCompletedTokenStream<
-            BoxStream<'static, Result<StringValuesResponse, Status>>,
-            StringValuesResponse,
-            Status,
-        >,
+    type MeasurementTagKeysStream = ResponseStream<
+        BoxStream<'static, Result<StringValuesResponse, Status>>,
+        StringValuesResponse,
     >;
 
     async fn measurement_tag_keys(
@@ -930,6 +1060,7 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -948,12 +1079,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "measurement_tag_keys",
             defer_json(&req),
@@ -983,15 +1115,13 @@ where
             futures::stream::once(async move { response }).boxed(),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
-    type MeasurementTagValuesStream = StreamWithPermit<
-        QueryCompletedTokenStream<
-            BoxStream<'static, Result<StringValuesResponse, Status>>,
-            StringValuesResponse,
-            Status,
-        >,
+    type MeasurementTagValuesStream = ResponseStream<
+        BoxStream<'static, Result<StringValuesResponse, Status>>,
+        StringValuesResponse,
     >;
 
     async fn measurement_tag_values(
@@ -1001,6 +1131,7 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -1020,12 +1151,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "measurement_tag_values",
             defer_json(&req),
@@ -1057,15 +1189,13 @@ where
             futures::stream::once(async move { response }).boxed(),
             query_completed_token,
             permit,
+            ctx.partial_results(),
         )
     }
 
-    type MeasurementFieldsStream = StreamWithPermit<
-        QueryCompletedTokenStream<
-            BoxStream<'static, Result<MeasurementFieldsResponse, Status>>,
-            MeasurementFieldsResponse,
-            Status,
-        >,
+    type MeasurementFieldsStream = ResponseStream<
+        BoxStream<'static, Result<MeasurementFieldsResponse, Status>>,
+        MeasurementFieldsResponse,
     >;
 
     async fn measurement_fields(
@@ -1075,6 +1205,7 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let is_debug = has_debug_header(req.metadata());
         let req = req.into_inner();
         let permit = self
             .db_store
@@ -1093,12 +1224,13 @@ where
 
         let db = self
             .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
             .await
             .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
+            &ctx,
             external_span_ctx.as_ref().map(RequestLogContext::ctx),
             "measurement_fields",
             defer_json(&req),
@@ -1133,6 +1265,76 @@ where
             futures::stream::once(async move { response }).boxed(),
             query_completed_token,
             permit,
+            ctx.partial_results(),
+        )
+    }
+
+    type FieldsGroupedByMeasurementStream = ResponseStream<
+        futures::stream::Iter<
+            std::vec::IntoIter<Result<FieldsGroupedByMeasurementResponse, Status>>,
+        >,
+        FieldsGroupedByMeasurementResponse,
+    >;
+
+    async fn fields_grouped_by_measurement(
+        &self,
+        req: tonic::Request<FieldsGroupedByMeasurementRequest>,
+    ) -> Result<Response<Self::FieldsGroupedByMeasurementStream>, Status> {
+        let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        let is_debug = has_debug_header(req.metadata());
+        let req = req.into_inner();
+        let permit = self
+            .db_store
+            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
+            .await;
+
+        let db_name = get_namespace_name(&req)?;
+        info!(
+            %db_name,
+            ?req.range,
+            predicate=%req.predicate.loggable(),
+            trace=%external_span_ctx.format_jaeger(),
+            "fields_grouped_by_measurement",
+        );
+
+        let db = self
+            .db_store
+            .db(&db_name, span_ctx.child_span("get namespace"), is_debug)
+            .await
+            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+
+        let ctx = db.new_query_context(span_ctx);
+        let query_completed_token = db.record_query(
+            &ctx,
+            external_span_ctx.as_ref().map(RequestLogContext::ctx),
+            "fields_grouped_by_measurement",
+            defer_json(&req),
+        );
+
+        let FieldsGroupedByMeasurementRequest {
+            source: _source,
+            range,
+            predicate,
+        } = req;
+
+        let measurement_fieldlist =
+            measurement_field_names_impl(Arc::clone(&db), db_name, range, predicate, &ctx)
+                .await
+                .map_err(|e| e.into_status())?;
+        let results = measurement_fieldlist_to_responses(measurement_fieldlist)
+            .context(ConvertingFieldListSnafu)
+            .map_err(|e| e.into_status())?
+            .into_iter()
+            .map(Ok)
+            .collect::<Vec<_>>();
+
+        make_response(
+            futures::stream::iter(results),
+            query_completed_token,
+            permit,
+            ctx.partial_results(),
         )
     }
 
@@ -1395,9 +1597,15 @@ where
     // completed, but now it doesn't. We may need to revisit this in the future
     // if big queries are causing a significant latency in TTFB.
 
+    let point_order =
+        match read_filter_request::PointOrder::from_i32(req.point_order).unwrap_or_default() {
+            read_filter_request::PointOrder::Ascending => PointOrder::Ascending,
+            read_filter_request::PointOrder::Unspecified => PointOrder::Unspecified,
+        };
+
     // Build the plans
     let series_plan = Planner::new(ctx)
-        .read_filter(db, predicate)
+        .read_filter(db, predicate, point_order)
         .await
         .context(PlanningFilteringSeriesSnafu { db_name })?;
 
@@ -1420,10 +1628,17 @@ where
         });
 
     let emit_tag_keys_binary_format = req.tag_key_meta_names == TagKeyMetaNames::Binary as i32;
-
-    Ok(series_or_groups_to_frames(
-        series_or_groups,
-        emit_tag_keys_binary_format,
+    let series_frame_encoding =
+        SeriesFrameEncoding::from_i32(req.series_frame_encoding).unwrap_or_default();
+    let timestamp_precision =
+        TimestampPrecision::from_i32(req.timestamp_precision).unwrap_or_default();
+
+    Ok(scale_timestamps(
+        encode_series_tags(
+            series_or_groups_to_frames(series_or_groups, emit_tag_keys_binary_format),
+            series_frame_encoding,
+        ),
+        timestamp_precision,
     ))
 }
 
@@ -1435,6 +1650,7 @@ async fn query_group_impl<N>(
     rpc_predicate: Option<Predicate>,
     gby_agg: GroupByAndAggregate,
     tag_key_meta_names: TagKeyMetaNames,
+    timestamp_precision: TimestampPrecision,
     ctx: &IOxSessionContext,
 ) -> Result<impl Stream<Item = Result<Frame, Error>>>
 where
@@ -1457,9 +1673,24 @@ where
         GroupByAndAggregate::Columns { agg, group_columns } => {
             planner.read_group(db, predicate, agg, group_columns).await
         }
-        GroupByAndAggregate::Window { agg, every, offset } => {
+        GroupByAndAggregate::Window {
+            agg,
+            every,
+            offset,
+            fill,
+        } => {
+            planner
+                .read_window_aggregate(db, predicate, agg, every, offset, fill)
+                .await
+        }
+        GroupByAndAggregate::WindowExpressions {
+            exprs,
+            every,
+            offset,
+            fill,
+        } => {
             planner
-                .read_window_aggregate(db, predicate, agg, every, offset)
+                .read_window_aggregate_expressions(db, predicate, exprs, every, offset, fill)
                 .await
         }
     };
@@ -1490,12 +1721,43 @@ where
 
     let tag_key_binary_format = tag_key_meta_names == TagKeyMetaNames::Binary;
 
-    Ok(series_or_groups_to_frames(
-        series_or_groups,
-        tag_key_binary_format,
+    Ok(scale_timestamps(
+        series_or_groups_to_frames(series_or_groups, tag_key_binary_format),
+        timestamp_precision,
     ))
 }
 
+/// Fail `frames` with [`Error::SeriesLimitExceeded`] once it contains more
+/// than `limit` series.
+fn limit_series<S>(
+    frames: S,
+    limit: Option<NonZeroUsize>,
+) -> impl Stream<Item = Result<Frame, Error>>
+where
+    S: Stream<Item = Result<Frame, Error>>,
+{
+    // the number of series seen so far, or `None` once the limit was exceeded
+    frames.scan(Some(0_usize), move |state, res| {
+        let Some(series) = state else {
+            return futures::future::ready(None);
+        };
+
+        let res = match (res, limit) {
+            (Ok(frame), Some(limit)) if matches!(frame.data, Some(Data::Series(_))) => {
+                *series += 1;
+                if *series > limit.get() {
+                    *state = None;
+                    Err(Error::SeriesLimitExceeded { limit: limit.get() })
+                } else {
+                    Ok(frame)
+                }
+            }
+            (res, _) => res,
+        };
+        futures::future::ready(Some(res))
+    })
+}
+
 /// Return field names, restricted via optional measurement, timestamp and
 /// predicate
 async fn field_names_impl<N>(
@@ -1537,6 +1799,44 @@ where
     Ok(field_list)
 }
 
+/// Return field names grouped by measurement, restricted via optional
+/// timestamp and predicate
+async fn measurement_field_names_impl<N>(
+    db: Arc<N>,
+    db_name: NamespaceName<'static>,
+    range: Option<TimestampRange>,
+    rpc_predicate: Option<Predicate>,
+    ctx: &IOxSessionContext,
+) -> Result<MeasurementFieldList>
+where
+    N: QueryNamespace + 'static,
+{
+    let rpc_predicate_string = format!("{rpc_predicate:?}");
+
+    let predicate = InfluxRpcPredicateBuilder::default()
+        .set_range(range)
+        .rpc_predicate(rpc_predicate)
+        .context(ConvertingPredicateSnafu {
+            rpc_predicate_string,
+        })?
+        .build();
+
+    let db_name = db_name.as_str();
+
+    let plan = Planner::new(ctx)
+        .measurement_field_columns(db, predicate)
+        .await
+        .context(ListingFieldsSnafu { db_name })?;
+
+    let measurement_field_list = ctx
+        .to_measurement_field_list(plan)
+        .await
+        .context(ListingFieldsSnafu { db_name })?;
+
+    trace!(measurement_field_names=?measurement_field_list, "Measurement field names response");
+    Ok(measurement_field_list)
+}
+
 /// Materialises a collection of measurement names. Typically used as part of
 /// a plan to scope and group multiple plans by measurement name.
 async fn materialise_measurement_names<N>(
@@ -1734,18 +2034,26 @@ impl<T, E: std::error::Error> ErrorLogger for Result<T, E> {
     }
 }
 
+/// The response stream of a storage request.
+pub type ResponseStream<S, T> =
+    StreamWithPermit<PartialResultsStream<QueryCompletedTokenStream<S, T, Status>>>;
+
 /// Return the stream of results as a gRPC (tonic) response
-#[allow(clippy::type_complexity)]
-pub fn make_response<S, T, E>(
+///
+/// The response ends with the
+/// [`PARTIAL_RESULTS_TRAILER`](crate::PARTIAL_RESULTS_TRAILER) trailer if
+/// `partial` is set once the results are exhausted.
+pub fn make_response<S, T>(
     stream: S,
     token: QueryCompletedToken,
     permit: InstrumentedAsyncOwnedSemaphorePermit,
-) -> Result<Response<StreamWithPermit<QueryCompletedTokenStream<S, T, E>>>, Status>
+    partial: PartialResults,
+) -> Result<Response<ResponseStream<S, T>>, Status>
 where
-    S: Stream<Item = Result<T, E>> + Unpin + Send,
+    S: Stream<Item = Result<T, Status>> + Unpin + Send,
 {
     let mut response = Response::new(StreamWithPermit::new(
-        QueryCompletedTokenStream::new(stream, token),
+        PartialResultsStream::new(QueryCompletedTokenStream::new(stream, token), partial),
         permit,
     ));
     add_headers(response.metadata_mut());
@@ -1754,7 +2062,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::test_util::Fixture;
+    use crate::{test_util::Fixture, ResultLimits, PARTIAL_RESULTS_TRAILER};
 
     use super::*;
     use futures::Future;
@@ -1802,6 +2110,139 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_limit_series() {
+        let series = || Frame {
+            data: Some(Data::Series(read_response::SeriesFrame::default())),
+        };
+        let points = || Frame {
+            data: Some(Data::FloatPoints(read_response::FloatPointsFrame::default())),
+        };
+        let frames = || {
+            futures::stream::iter([series(), points(), series(), points(), series(), points()])
+                .map(Ok)
+        };
+
+        // No limit
+        let res = limit_series(frames(), None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 6);
+
+        // Limit not exceeded
+        let res = limit_series(frames(), NonZeroUsize::new(3))
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 6);
+
+        // Limit exceeded by the third series, which ends the stream
+        let res = limit_series(frames(), NonZeroUsize::new(2))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(res.len(), 5);
+        assert!(res[..4].iter().all(|r| r.is_ok()));
+        let status = res.into_iter().last().unwrap().unwrap_err().into_status();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_contains!(
+            status.message(),
+            "Query returns more than the limit of 2 series"
+        );
+        let quota_failures: Vec<_> = google::decode_quota_failure(&status).collect();
+        assert_eq!(quota_failures.len(), 1);
+        assert_eq!(quota_failures[0].subject, "series");
+        assert_eq!(google::decode_retry_info(&status), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_filter_result_limits() {
+        maybe_start_logging();
+        let test_storage = Arc::new(TestDatabaseStore::new());
+
+        // 5 points in 3 series
+        let db_info = org_and_bucket();
+        let chunk = TestChunk::new("TheMeasurement")
+            .with_time_column()
+            .with_tag_column("state")
+            .with_i64_field_column("field_int")
+            .with_five_rows_of_data();
+        test_storage
+            .db_or_create(db_info.db_name())
+            .await
+            .add_chunk("my_partition_key", Arc::new(chunk));
+
+        let service = StorageService {
+            db_store: Arc::clone(&test_storage),
+            max_series_per_query: None,
+            result_limits: ResultLimits {
+                max_points: Some(2),
+                ..Default::default()
+            },
+        };
+        let request = ReadFilterRequest {
+            read_source: Some(StorageClient::read_source(&db_info, 1)),
+            range: Some(make_timestamp_range(0, 10000)),
+            ..Default::default()
+        };
+        let responses: Vec<_> = service
+            .read_filter(tonic::Request::new(request))
+            .await
+            .unwrap()
+            .into_inner()
+            .collect()
+            .await;
+
+        // the results are truncated and end with the partial results trailer
+        let (trailer, responses) = responses.split_last().unwrap();
+        let points: usize = responses
+            .iter()
+            .flat_map(|r| &r.as_ref().unwrap().frames)
+            .map(|frame| match &frame.data {
+                Some(Data::IntegerPoints(p)) => p.timestamps.len(),
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(points, 2);
+
+        let status = trailer.as_ref().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Ok);
+        assert_eq!(
+            status.metadata().get(PARTIAL_RESULTS_TRAILER).unwrap(),
+            "true"
+        );
+    }
+
+    #[test]
+    fn test_error_details() {
+        let status = Error::MissingTagKeyPredicate {}.into_status();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        let violations: Vec<_> = google::decode_field_violation(&status).collect();
+        assert_eq!(
+            violations,
+            vec![google::FieldViolation {
+                field: "TagKeyPredicate".to_string(),
+                description: "Missing tag key predicate".to_string(),
+            }]
+        );
+        // the InfluxDB error is still the first detail for InfluxRPC clients
+        let rpc_status = GrpcStatus::decode(status.details()).unwrap();
+        assert_eq!(rpc_status.details.len(), 2);
+        assert_eq!(
+            rpc_status.details[0].type_url,
+            generated_types::protobuf_type_url("influxdata.platform.errors.InfluxDBError")
+        );
+
+        let status = Error::ListingTables {
+            db_name: "db".to_string(),
+            source: DataFusionError::ResourcesExhausted("out of memory".to_string()),
+        }
+        .into_status();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(google::decode_retry_info(&status), Some(RETRY_DELAY));
+        assert_eq!(google::decode_field_violation(&status).count(), 0);
+    }
+
     #[tokio::test]
     async fn test_storage_rpc_capabilities() {
         test_helpers::maybe_start_logging();
@@ -1811,6 +2252,26 @@ mod tests {
         // Test response from storage server
         let mut expected_capabilities: HashMap<String, Vec<String>> = HashMap::new();
         expected_capabilities.insert("KeySortCapability".into(), to_str_vec(&["ReadFilter"]));
+        expected_capabilities.insert(
+            "PredicateCapability".into(),
+            to_str_vec(&[
+                "Comparison",
+                "Regex",
+                "NotRegex",
+                "StartsWith",
+                "ValueFilter",
+                "MeasurementFilter",
+                "FieldFilter",
+            ]),
+        );
+        expected_capabilities.insert(
+            "LimitsCapability".into(),
+            to_str_vec(&["MaxPointsPerFrame=1000", "MaxReadResponseBytes=4094304"]),
+        );
+        expected_capabilities.insert(
+            "SeriesFrameEncodingCapability".into(),
+            to_str_vec(&["SharedTagPrefixReadFilter"]),
+        );
         expected_capabilities.insert(
             "TagKeyMetaNamesCapability".into(),
             to_str_vec(&["TagKeyMetaNamesWindowAggregate"]),
@@ -2837,7 +3298,9 @@ mod tests {
             group,
             aggregate: Some(Aggregate {
                 r#type: aggregate::AggregateType::Sum as i32,
+                n: 0,
             }),
+            timestamp_precision: TimestampPrecision::Nanoseconds as i32,
         };
 
         let frames = fixture.storage_client.read_group(request).await.unwrap();
@@ -2882,7 +3345,9 @@ mod tests {
             group,
             aggregate: Some(Aggregate {
                 r#type: aggregate::AggregateType::Sum as i32,
+                n: 0,
             }),
+            timestamp_precision: TimestampPrecision::Nanoseconds as i32,
         };
 
         // Note we don't set the response on the test namespace, so we expect an error
@@ -2931,10 +3396,14 @@ mod tests {
             offset: 15,
             aggregate: vec![Aggregate {
                 r#type: aggregate::AggregateType::Sum as i32,
+                n: 0,
             }],
             // old skool window definition
             window: None,
             tag_key_meta_names: TagKeyMetaNames::Text as i32,
+            fill: read_window_aggregate_request::Fill::None as i32,
+            timestamp_precision: TimestampPrecision::Nanoseconds as i32,
+            aggregate_expressions: vec![],
         };
 
         let frames = fixture
@@ -2985,6 +3454,7 @@ mod tests {
             offset: 0,
             aggregate: vec![Aggregate {
                 r#type: aggregate::AggregateType::Sum as i32,
+                n: 0,
             }],
             // old skool window definition
             window: Some(Window {
@@ -3000,6 +3470,9 @@ mod tests {
                 }),
             }),
             tag_key_meta_names: TagKeyMetaNames::Text as i32,
+            fill: read_window_aggregate_request::Fill::None as i32,
+            timestamp_precision: TimestampPrecision::Nanoseconds as i32,
+            aggregate_expressions: vec![],
         };
 
         let frames = fixture
@@ -3045,10 +3518,14 @@ mod tests {
             offset: 15,
             aggregate: vec![Aggregate {
                 r#type: aggregate::AggregateType::Sum as i32,
+                n: 0,
             }],
             // old skool window definition
             window: None,
             tag_key_meta_names: TagKeyMetaNames::Text as i32,
+            fill: read_window_aggregate_request::Fill::None as i32,
+            timestamp_precision: TimestampPrecision::Nanoseconds as i32,
+            aggregate_expressions: vec![],
         };
 
         let response_string = fixture
@@ -3146,6 +3623,57 @@ mod tests {
         assert_contains!(response_string, "Sugar we are going down");
     }
 
+    #[tokio::test]
+    async fn test_fields_grouped_by_measurement() {
+        test_helpers::maybe_start_logging();
+
+        // Start a test gRPC server on a randomally allocated port
+        let mut fixture = Fixture::new().await.expect("Connecting to test server");
+
+        let db_info = org_and_bucket();
+
+        // Add chunks with fields in two measurements
+        let chunk1 = TestChunk::new("TheMeasurement")
+            .with_i64_field_column("Field1")
+            .with_time_column()
+            .with_tag_column("state")
+            .with_one_row_of_data();
+        let chunk2 = TestChunk::new("TheOtherMeasurement")
+            .with_f64_field_column("Field2")
+            .with_time_column()
+            .with_tag_column("state")
+            .with_one_row_of_data();
+
+        let db = fixture.test_storage.db_or_create(db_info.db_name()).await;
+        db.add_chunk("my_partition_key", Arc::new(chunk1));
+        db.add_chunk("my_partition_key", Arc::new(chunk2));
+
+        let source = Some(StorageClient::read_source(&db_info, 1));
+
+        let request = FieldsGroupedByMeasurementRequest {
+            source: source.clone(),
+            range: Some(make_timestamp_range(0, 2000)),
+            predicate: Some(make_state_eq_ma_predicate()),
+        };
+
+        let actual_fields = fixture
+            .storage_client
+            .fields_grouped_by_measurement(request)
+            .await
+            .unwrap();
+        let expected_fields: Vec<String> = vec![
+            "measurement: TheMeasurement, key: Field1, type: 1, timestamp: 1000".into(),
+            "measurement: TheOtherMeasurement, key: Field2, type: 0, timestamp: 1000".into(),
+        ];
+
+        assert_eq!(
+            actual_fields, expected_fields,
+            "unexpected frames returned by fields_grouped_by_measurement"
+        );
+
+        grpc_request_metric_has_count(&fixture, "FieldsGroupedByMeasurement", "ok", 1);
+    }
+
     #[derive(Debug, Clone)]
     enum SemaphoredRequest {
         MeasurementFields,
@@ -3240,7 +3768,9 @@ mod tests {
                         group,
                         aggregate: Some(Aggregate {
                             r#type: aggregate::AggregateType::Sum as i32,
+                            n: 0,
                         }),
+                        timestamp_precision: TimestampPrecision::Nanoseconds as i32,
                     };
                     let streaming_resp = service
                         .read_group(tonic::Request::new(request))
@@ -3257,6 +3787,7 @@ mod tests {
                         offset: 0,
                         aggregate: vec![Aggregate {
                             r#type: aggregate::AggregateType::Sum as i32,
+                            n: 0,
                         }],
                         // old skool window definition
                         window: Some(Window {
@@ -3272,6 +3803,9 @@ mod tests {
                             }),
                         }),
                         tag_key_meta_names: TagKeyMetaNames::Text as i32,
+                        fill: read_window_aggregate_request::Fill::None as i32,
+                        timestamp_precision: TimestampPrecision::Nanoseconds as i32,
+                        aggregate_expressions: vec![],
                     };
                     let streaming_resp = service
                         .read_window_aggregate(tonic::Request::new(request))
@@ -3360,6 +3894,8 @@ mod tests {
             println!("Testing with request: {t:?}");
             let service = StorageService {
                 db_store: Arc::clone(&test_storage),
+                max_series_per_query: None,
+                result_limits: Default::default(),
             };
 
             assert_semaphore_metric(
@@ -3589,6 +4125,7 @@ mod tests {
             offset: 0,
             aggregate: vec![Aggregate {
                 r#type: aggregate::AggregateType::Mean as i32,
+                n: 0,
             }],
             window: Some(Window {
                 every: Some(Duration {
@@ -3603,6 +4140,9 @@ mod tests {
                 }),
             }),
             tag_key_meta_names: TagKeyMetaNames::Text as i32,
+            fill: read_window_aggregate_request::Fill::None as i32,
+            timestamp_precision: TimestampPrecision::Nanoseconds as i32,
+            aggregate_expressions: vec![],
         };
 
         let expected_message = "No function matches the given name and argument types 'AVG(Utf8)'";
@@ -0,0 +1,220 @@
+//! Implementation of the logging gRPC service, changing the log filter of a
+//! running server.
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::todo,
+    clippy::use_self,
+    missing_debug_implementations,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use generated_types::influxdata::iox::logging::v1::*;
+use observability_deps::tracing::info;
+use tonic::{Request, Response, Status};
+use trogging::{
+    reload::{LogFilterState, ReloadError},
+    LogFilterHandle,
+};
+
+/// Implementation of the gRPC logging service
+#[derive(Debug)]
+pub struct LoggingService {
+    handle: Option<LogFilterHandle>,
+}
+
+impl LoggingService {
+    /// Construct a service changing the filter through `handle`.
+    ///
+    /// If `handle` is [`None`] all requests fail.
+    pub fn new(handle: Option<LogFilterHandle>) -> Self {
+        Self { handle }
+    }
+
+    fn handle(&self) -> Result<&LogFilterHandle, Status> {
+        self.handle.as_ref().ok_or_else(|| {
+            Status::failed_precondition("log filter cannot be changed at runtime by this server")
+        })
+    }
+}
+
+/// Construct the logging service for the log layer installed in this process.
+///
+/// The service performs no authorization of its own, servers only mount it
+/// when explicitly enabled.
+pub fn make_server() -> logging_service_server::LoggingServiceServer<LoggingService> {
+    logging_service_server::LoggingServiceServer::new(LoggingService::new(
+        trogging::log_filter_handle(),
+    ))
+}
+
+#[tonic::async_trait]
+impl logging_service_server::LoggingService for LoggingService {
+    async fn get_log_filter(
+        &self,
+        _request: Request<GetLogFilterRequest>,
+    ) -> Result<Response<GetLogFilterResponse>, Status> {
+        let handle = self.handle()?;
+
+        Ok(Response::new(GetLogFilterResponse {
+            log_filter: Some(to_proto(handle.state())),
+        }))
+    }
+
+    async fn set_log_filter(
+        &self,
+        request: Request<SetLogFilterRequest>,
+    ) -> Result<Response<SetLogFilterResponse>, Status> {
+        let handle = self.handle()?;
+        let SetLogFilterRequest {
+            filter,
+            expiry_seconds,
+        } = request.into_inner();
+
+        let expiry = expiry_seconds.filter(|&s| s > 0).map(Duration::from_secs);
+
+        info!(%filter, ?expiry, "setting log filter");
+        handle.set(&filter, expiry).map_err(to_status)?;
+
+        Ok(Response::new(SetLogFilterResponse {
+            log_filter: Some(to_proto(handle.state())),
+        }))
+    }
+
+    async fn reset_log_filter(
+        &self,
+        _request: Request<ResetLogFilterRequest>,
+    ) -> Result<Response<ResetLogFilterResponse>, Status> {
+        let handle = self.handle()?;
+
+        info!("resetting log filter");
+        handle.reset().map_err(to_status)?;
+
+        Ok(Response::new(ResetLogFilterResponse {
+            log_filter: Some(to_proto(handle.state())),
+        }))
+    }
+}
+
+fn to_status(e: ReloadError) -> Status {
+    match e {
+        ReloadError::InvalidFilter(_) | ReloadError::InvalidExpiry(_) => {
+            Status::invalid_argument(e.to_string())
+        }
+        ReloadError::Reload(_) | ReloadError::NoRuntime => Status::internal(e.to_string()),
+    }
+}
+
+fn to_proto(state: LogFilterState) -> LogFilter {
+    LogFilter {
+        filter: state.filter,
+        default_filter: state.default_filter,
+        expires_at_ns: state.expires_at.map(|t| {
+            let ns = t.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+            i64::try_from(ns).unwrap_or(i64::MAX)
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging_service_server::LoggingService as _;
+
+    fn service() -> LoggingService {
+        LoggingService::new(Some(LogFilterHandle::new("info".to_string(), |_| Ok(()))))
+    }
+
+    #[tokio::test]
+    async fn test_set_get_reset() {
+        let service = service();
+
+        let got = service
+            .set_log_filter(Request::new(SetLogFilterRequest {
+                filter: "debug,h2=info".to_string(),
+                expiry_seconds: Some(600),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .log_filter
+            .unwrap();
+        assert_eq!(got.filter, "debug,h2=info");
+        assert_eq!(got.default_filter, "info");
+        assert!(got.expires_at_ns.is_some());
+
+        let got = service
+            .get_log_filter(Request::new(GetLogFilterRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .log_filter
+            .unwrap();
+        assert_eq!(got.filter, "debug,h2=info");
+
+        let got = service
+            .reset_log_filter(Request::new(ResetLogFilterRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .log_filter
+            .unwrap();
+        assert_eq!(got.filter, "info");
+        assert_eq!(got.expires_at_ns, None);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_filter() {
+        let err = service()
+            .set_log_filter(Request::new(SetLogFilterRequest {
+                filter: "info,[[[".to_string(),
+                expiry_seconds: None,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_expiry() {
+        let service = service();
+
+        let err = service
+            .set_log_filter(Request::new(SetLogFilterRequest {
+                filter: "debug".to_string(),
+                expiry_seconds: Some(u64::MAX),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        // The filter was not changed.
+        let got = service
+            .get_log_filter(Request::new(GetLogFilterRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .log_filter
+            .unwrap();
+        assert_eq!(got.filter, "info");
+    }
+
+    #[tokio::test]
+    async fn test_no_handle() {
+        let err = LoggingService::new(None)
+            .get_log_filter(Request::new(GetLogFilterRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+    }
+}
@@ -20,13 +20,16 @@ use std::sync::Arc;
 
 use data_types::{
     partition_template::NamespacePartitionTemplateOverride, Namespace as CatalogNamespace,
-    NamespaceName, NamespaceServiceProtectionLimitsOverride, ServiceLimitUpdate,
+    NamespaceName, NamespaceServiceProtectionLimitsOverride,
+    NamespaceTemplate as CatalogNamespaceTemplate, ServiceLimitUpdate,
 };
-use generated_types::influxdata::iox::namespace::v1::*;
+use generated_types::{google::invalid_argument, influxdata::iox::namespace::v1::*};
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use observability_deps::tracing::{debug, info, warn};
 use tonic::{Request, Response, Status};
 
+mod validate;
+
 /// Implementation of the gRPC namespace service
 #[derive(Debug)]
 pub struct NamespaceService {
@@ -73,27 +76,63 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             retention_period_ns,
             partition_template,
             service_protection_limits,
+            template,
         } = request.into_inner();
 
         // Ensure the namespace name is consistently processed within IOx - this
         // is handled by the NamespaceName type.
         let namespace_name = NamespaceName::try_from(namespace_name)
-            .map_err(|v| Status::invalid_argument(v.to_string()))?;
-
-        let retention_period_ns = map_retention_period(retention_period_ns)?;
+            .map_err(|v| invalid_argument("name", v.to_string()))?;
+
+        let partition_template = partition_template
+            .map(NamespacePartitionTemplateOverride::try_from)
+            .transpose()
+            .map_err(|v| invalid_argument("partition_template", v.to_string()))?;
+        let service_protection_limits =
+            service_protection_limits.map(NamespaceServiceProtectionLimitsOverride::from);
+
+        // Settings specified in the request take precedence over those of the
+        // template.
+        let template = match template {
+            Some(template_name) => Some(
+                repos
+                    .namespace_templates()
+                    .get_by_name(&template_name)
+                    .await
+                    .map_err(|e| Status::internal(e.to_string()))?
+                    .ok_or_else(|| {
+                        Status::not_found(format!(
+                            "A namespace template with the name `{template_name}` does not exist"
+                        ))
+                    })?,
+            ),
+            None => None,
+        };
+        let NamespaceSettings {
+            retention_period_ns,
+            partition_template,
+            service_protection_limits,
+        } = NamespaceSettings::resolve(
+            template.as_ref(),
+            retention_period_ns,
+            partition_template,
+            service_protection_limits,
+        )?;
 
-        debug!(%namespace_name, ?retention_period_ns, "Creating namespace");
+        debug!(
+            %namespace_name,
+            ?retention_period_ns,
+            template = ?template.as_ref().map(|t| &t.name),
+            "Creating namespace"
+        );
 
         let namespace = repos
             .namespaces()
             .create(
                 &namespace_name,
-                partition_template
-                    .map(NamespacePartitionTemplateOverride::try_from)
-                    .transpose()
-                    .map_err(|v| Status::invalid_argument(v.to_string()))?,
+                partition_template,
                 retention_period_ns,
-                service_protection_limits.map(NamespaceServiceProtectionLimitsOverride::from),
+                service_protection_limits,
             )
             .await
             .map_err(|e| {
@@ -106,6 +145,19 @@ impl namespace_service_server::NamespaceService for NamespaceService {
                 }
             })?;
 
+        // Record the template, so later changes to it can be propagated.
+        let namespace = match &template {
+            Some(t) => repos
+                .namespaces()
+                .update_template(&namespace_name, Some(t.id))
+                .await
+                .map_err(|e| {
+                    warn!(error=%e, %namespace_name, "failed to record namespace template");
+                    Status::internal(e.to_string())
+                })?,
+            None => namespace,
+        };
+
         info!(
             %namespace_name,
             namespace_id = %namespace.id,
@@ -197,7 +249,7 @@ impl namespace_service_server::NamespaceService for NamespaceService {
         );
 
         let new_service_limits = ServiceLimitUpdate::try_from(limit_update)
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+            .map_err(|e| invalid_argument("limit_update", e.to_string()))?;
 
         let namespace = match new_service_limits {
             ServiceLimitUpdate::MaxTables(new_max_tables) => repos
@@ -242,6 +294,351 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             },
         ))
     }
+
+    async fn get_namespace_templates(
+        &self,
+        _request: Request<GetNamespaceTemplatesRequest>,
+    ) -> Result<Response<GetNamespaceTemplatesResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let templates = repos.namespace_templates().list().await.map_err(|e| {
+            warn!(error=%e, "failed to retrieve namespace templates from catalog");
+            Status::internal(e.to_string())
+        })?;
+
+        Ok(Response::new(GetNamespaceTemplatesResponse {
+            templates: templates.iter().map(namespace_template_to_proto).collect(),
+        }))
+    }
+
+    async fn create_namespace_template(
+        &self,
+        request: Request<CreateNamespaceTemplateRequest>,
+    ) -> Result<Response<CreateNamespaceTemplateResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let NamespaceTemplateSettings {
+            name,
+            retention_period_ns,
+            partition_template,
+            service_protection_limits,
+        } = NamespaceTemplateSettings::try_from(request.into_inner().template)?;
+
+        debug!(%name, ?retention_period_ns, "Creating namespace template");
+
+        let template = repos
+            .namespace_templates()
+            .create(
+                &name,
+                partition_template,
+                retention_period_ns,
+                service_protection_limits,
+            )
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %name, "failed to create namespace template");
+                match e {
+                    iox_catalog::interface::Error::NameExists { name } => Status::already_exists(
+                        format!("A namespace template with the name `{name}` already exists"),
+                    ),
+                    other => Status::internal(other.to_string()),
+                }
+            })?;
+
+        info!(%name, template_id = %template.id, "created namespace template");
+
+        Ok(Response::new(CreateNamespaceTemplateResponse {
+            template: Some(namespace_template_to_proto(&template)),
+        }))
+    }
+
+    async fn update_namespace_template(
+        &self,
+        request: Request<UpdateNamespaceTemplateRequest>,
+    ) -> Result<Response<UpdateNamespaceTemplateResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateNamespaceTemplateRequest {
+            template,
+            propagate,
+        } = request.into_inner();
+        let NamespaceTemplateSettings {
+            name,
+            retention_period_ns,
+            partition_template,
+            service_protection_limits,
+        } = NamespaceTemplateSettings::try_from(template)?;
+
+        debug!(%name, ?retention_period_ns, propagate, "Updating namespace template");
+
+        let template = repos
+            .namespace_templates()
+            .update(
+                &name,
+                partition_template,
+                retention_period_ns,
+                service_protection_limits,
+            )
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %name, "failed to update namespace template");
+                match e {
+                    iox_catalog::interface::Error::NamespaceTemplateNotFoundByName { .. } => {
+                        Status::not_found(e.to_string())
+                    }
+                    other => Status::internal(other.to_string()),
+                }
+            })?;
+
+        info!(%name, template_id = %template.id, "updated namespace template");
+
+        // Apply the retention period and service protection limits of the
+        // template to the namespaces created from it. The partition template
+        // of a namespace cannot change once it is created.
+        let mut namespaces = vec![];
+        if propagate {
+            let members = repos
+                .namespaces()
+                .list(SoftDeletedRows::ExcludeDeleted)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .into_iter()
+                .filter(|n| n.template_id == Some(template.id));
+
+            for namespace in members {
+                let namespace_name = namespace.name;
+                repos
+                    .namespaces()
+                    .update_retention_period(&namespace_name, template.retention_period_ns)
+                    .await
+                    .map_err(status_from_catalog_namespace_error)?;
+                repos
+                    .namespaces()
+                    .update_table_limit(&namespace_name, template.max_tables.unwrap_or_default())
+                    .await
+                    .map_err(status_from_catalog_namespace_error)?;
+                let namespace = repos
+                    .namespaces()
+                    .update_column_limit(
+                        &namespace_name,
+                        template.max_columns_per_table.unwrap_or_default(),
+                    )
+                    .await
+                    .map_err(status_from_catalog_namespace_error)?;
+
+                info!(
+                    %namespace_name,
+                    template_name = %template.name,
+                    "propagated namespace template"
+                );
+                namespaces.push(namespace_to_proto(&namespace));
+            }
+        }
+
+        Ok(Response::new(UpdateNamespaceTemplateResponse {
+            template: Some(namespace_template_to_proto(&template)),
+            namespaces,
+        }))
+    }
+
+    async fn validate_namespace(
+        &self,
+        request: Request<ValidateNamespaceRequest>,
+    ) -> Result<Response<ValidateNamespaceResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let CreateNamespaceRequest {
+            name: namespace_name,
+            retention_period_ns,
+            partition_template,
+            service_protection_limits,
+            template,
+        } = request
+            .into_inner()
+            .namespace
+            .ok_or_else(|| invalid_argument("namespace", "a namespace is required"))?;
+
+        let mut errors = vec![];
+        let mut error = |field: &str, message: String| {
+            errors.push(ValidationIssue {
+                field: field.to_string(),
+                message,
+            })
+        };
+
+        match NamespaceName::try_from(namespace_name) {
+            Ok(namespace_name) => {
+                // Soft-deleted namespaces still reserve their name.
+                let existing = repos
+                    .namespaces()
+                    .get_by_name(&namespace_name, SoftDeletedRows::AllRows)
+                    .await
+                    .map_err(|e| Status::internal(e.to_string()))?;
+                if existing.is_some() {
+                    error(
+                        "name",
+                        format!("A namespace with the name `{namespace_name}` already exists"),
+                    );
+                }
+            }
+            Err(e) => error("name", e.to_string()),
+        }
+
+        let retention_period_ns = match map_retention_period(retention_period_ns) {
+            Ok(_) => retention_period_ns,
+            Err(e) => {
+                error("retention_period_ns", e.message().to_string());
+                None
+            }
+        };
+
+        let partition_template = match partition_template
+            .map(NamespacePartitionTemplateOverride::try_from)
+            .transpose()
+        {
+            Ok(v) => v,
+            Err(e) => {
+                error("partition_template", e.to_string());
+                None
+            }
+        };
+
+        let template = match template {
+            Some(template_name) => {
+                let template = repos
+                    .namespace_templates()
+                    .get_by_name(&template_name)
+                    .await
+                    .map_err(|e| Status::internal(e.to_string()))?;
+                if template.is_none() {
+                    error(
+                        "template",
+                        format!(
+                            "A namespace template with the name `{template_name}` does not exist"
+                        ),
+                    );
+                }
+                template
+            }
+            None => None,
+        };
+
+        let settings = NamespaceSettings::resolve(
+            template.as_ref(),
+            retention_period_ns,
+            partition_template,
+            service_protection_limits.map(NamespaceServiceProtectionLimitsOverride::from),
+        )?;
+        let warnings = validate::warnings(
+            settings.retention_period_ns,
+            settings
+                .partition_template
+                .as_ref()
+                .and_then(|t| t.as_proto()),
+            settings.service_protection_limits.unwrap_or(
+                NamespaceServiceProtectionLimitsOverride {
+                    max_tables: None,
+                    max_columns_per_table: None,
+                },
+            ),
+        );
+
+        debug!(
+            errors = errors.len(),
+            warnings = warnings.len(),
+            "validated namespace"
+        );
+
+        Ok(Response::new(ValidateNamespaceResponse {
+            errors,
+            warnings,
+        }))
+    }
+}
+
+/// The settings of a namespace to be created, after those of the namespace
+/// template it is created from (if any) have been applied.
+struct NamespaceSettings {
+    retention_period_ns: Option<i64>,
+    partition_template: Option<NamespacePartitionTemplateOverride>,
+    service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+}
+
+impl NamespaceSettings {
+    /// Merge the settings of a namespace creation request with those of
+    /// `template`. Settings specified in the request take precedence.
+    fn resolve(
+        template: Option<&CatalogNamespaceTemplate>,
+        retention_period_ns: Option<i64>,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<Self, Status> {
+        let Some(t) = template else {
+            return Ok(Self {
+                retention_period_ns: map_retention_period(retention_period_ns)?,
+                partition_template,
+                service_protection_limits,
+            });
+        };
+
+        let limits = t.service_protection_limits();
+        Ok(Self {
+            // An explicit (possibly infinite) retention period overrides that
+            // of the template.
+            retention_period_ns: match retention_period_ns {
+                Some(_) => map_retention_period(retention_period_ns)?,
+                None => t.retention_period_ns,
+            },
+            partition_template: partition_template.or_else(|| Some(t.partition_template.clone())),
+            service_protection_limits: Some(NamespaceServiceProtectionLimitsOverride {
+                max_tables: service_protection_limits
+                    .and_then(|l| l.max_tables)
+                    .or(limits.max_tables),
+                max_columns_per_table: service_protection_limits
+                    .and_then(|l| l.max_columns_per_table)
+                    .or(limits.max_columns_per_table),
+            }),
+        })
+    }
+}
+
+/// The validated settings of a [`NamespaceTemplate`] request.
+struct NamespaceTemplateSettings {
+    name: String,
+    retention_period_ns: Option<i64>,
+    partition_template: Option<NamespacePartitionTemplateOverride>,
+    service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+}
+
+impl TryFrom<Option<NamespaceTemplate>> for NamespaceTemplateSettings {
+    type Error = Status;
+
+    fn try_from(template: Option<NamespaceTemplate>) -> Result<Self, Self::Error> {
+        let NamespaceTemplate {
+            name,
+            retention_period_ns,
+            partition_template,
+            service_protection_limits,
+        } = template.ok_or_else(|| invalid_argument("template", "a template is required"))?;
+
+        if name.is_empty() {
+            return Err(invalid_argument(
+                "template.name",
+                "the template name must not be empty",
+            ));
+        }
+
+        Ok(Self {
+            name,
+            retention_period_ns: map_retention_period(retention_period_ns)?,
+            partition_template: partition_template
+                .map(NamespacePartitionTemplateOverride::try_from)
+                .transpose()
+                .map_err(|v| invalid_argument("template.partition_template", v.to_string()))?,
+            service_protection_limits: service_protection_limits
+                .map(NamespaceServiceProtectionLimitsOverride::from),
+        })
+    }
 }
 
 /// Convert the namespace record from the catalog into its protobuf representation.
@@ -256,6 +653,19 @@ pub fn namespace_to_proto(namespace: &CatalogNamespace) -> Namespace {
     }
 }
 
+/// Convert the namespace template record from the catalog into its protobuf representation.
+pub fn namespace_template_to_proto(template: &CatalogNamespaceTemplate) -> NamespaceTemplate {
+    NamespaceTemplate {
+        name: template.name.clone(),
+        retention_period_ns: template.retention_period_ns,
+        partition_template: template.partition_template.as_proto().cloned(),
+        service_protection_limits: Some(ServiceProtectionLimits {
+            max_tables: template.max_tables.map(|v| v.get()),
+            max_columns_per_table: template.max_columns_per_table.map(|v| v.get()),
+        }),
+    }
+}
+
 /// Map a user-submitted retention period value to the correct internal
 /// encoding.
 ///
@@ -266,7 +676,8 @@ fn map_retention_period(v: Option<i64>) -> Result<Option<i64>, Status> {
     match v {
         Some(0) => Ok(None),
         Some(v @ 1..) => Ok(Some(v)),
-        Some(_v @ ..=0) => Err(Status::invalid_argument(
+        Some(_v @ ..=0) => Err(invalid_argument(
+            "retention_period_ns",
             "invalid negative retention period",
         )),
         None => Ok(None),
@@ -310,7 +721,8 @@ mod tests {
         assert_matches!(map_retention_period(Some(1)), Ok(Some(1)));
         assert_matches!(map_retention_period(Some(42)), Ok(Some(42)));
         assert_matches!(map_retention_period(Some(-1)), Err(e) => {
-            assert_eq!(e.code(), Code::InvalidArgument)
+            assert_eq!(e.code(), Code::InvalidArgument);
+            assert_eq!(e.message(), "invalid negative retention period");
         });
         assert_matches!(map_retention_period(Some(-42)), Err(e) => {
             assert_eq!(e.code(), Code::InvalidArgument)
@@ -366,6 +778,7 @@ mod tests {
             retention_period_ns: Some(RETENTION),
             partition_template: None,
             service_protection_limits: None,
+            template: None,
         };
         let created_ns = handler
             .create_namespace(Request::new(req))
@@ -493,6 +906,7 @@ mod tests {
             retention_period_ns: Some(RETENTION),
             partition_template: None,
             service_protection_limits: None,
+            template: None,
         };
 
         let created_ns = handler
@@ -541,6 +955,7 @@ mod tests {
             retention_period_ns: None,
             partition_template: Some(PARTITION_BY_DAY_PROTO.as_ref().clone()),
             service_protection_limits: None,
+            template: None,
         };
         let created_ns = handler
             .create_namespace(Request::new(req))
@@ -579,6 +994,7 @@ mod tests {
             retention_period_ns: None,
             partition_template: Some(PartitionTemplate { parts: vec![] }),
             service_protection_limits: None,
+            template: None,
         };
 
         let error = handler
@@ -613,6 +1029,7 @@ mod tests {
             retention_period_ns: Some(RETENTION),
             partition_template: None,
             service_protection_limits: None,
+            template: None,
         };
         let created_ns = handler
             .create_namespace(Request::new(req))
@@ -670,6 +1087,7 @@ mod tests {
                 max_tables: Some(max_tables),
                 max_columns_per_table: Some(max_columns_per_table),
             }),
+            template: None,
         };
         let created_ns = handler
             .create_namespace(Request::new(req))
@@ -684,6 +1102,251 @@ mod tests {
         assert_eq!(created_ns.max_columns_per_table, max_columns_per_table);
     }
 
+    #[tokio::test]
+    async fn test_namespace_template() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = NamespaceService::new(catalog);
+
+        let template = NamespaceTemplate {
+            name: "standard".to_string(),
+            retention_period_ns: Some(RETENTION),
+            partition_template: Some(PARTITION_BY_DAY_PROTO.as_ref().clone()),
+            service_protection_limits: Some(ServiceProtectionLimits {
+                max_tables: Some(123),
+                max_columns_per_table: Some(321),
+            }),
+        };
+        let created = handler
+            .create_namespace_template(Request::new(CreateNamespaceTemplateRequest {
+                template: Some(template.clone()),
+            }))
+            .await
+            .expect("failed to create namespace template")
+            .into_inner()
+            .template
+            .expect("no template in response");
+        assert_eq!(created, template);
+
+        // Creating the same template twice fails.
+        let status = handler
+            .create_namespace_template(Request::new(CreateNamespaceTemplateRequest {
+                template: Some(template.clone()),
+            }))
+            .await
+            .expect_err("duplicate template must fail");
+        assert_eq!(status.code(), Code::AlreadyExists);
+
+        // Creating a namespace from an unknown template fails.
+        let status = handler
+            .create_namespace(Request::new(CreateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                retention_period_ns: None,
+                partition_template: None,
+                service_protection_limits: None,
+                template: Some("bananas".to_string()),
+            }))
+            .await
+            .expect_err("unknown template must fail");
+        assert_eq!(status.code(), Code::NotFound);
+
+        // A namespace created from the template uses its settings.
+        let from_template = handler
+            .create_namespace(Request::new(CreateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                retention_period_ns: None,
+                partition_template: None,
+                service_protection_limits: None,
+                template: Some("standard".to_string()),
+            }))
+            .await
+            .expect("failed to create namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(from_template.retention_period_ns, Some(RETENTION));
+        assert_eq!(from_template.max_tables, 123);
+        assert_eq!(from_template.max_columns_per_table, 321);
+        assert_eq!(
+            from_template.partition_template,
+            Some(PARTITION_BY_DAY_PROTO.as_ref().clone())
+        );
+
+        // Settings in the request override those of the template, including
+        // an explicit infinite retention period.
+        let overridden = handler
+            .create_namespace(Request::new(CreateNamespaceRequest {
+                name: "platanos".to_string(),
+                retention_period_ns: Some(0),
+                partition_template: None,
+                service_protection_limits: Some(ServiceProtectionLimits {
+                    max_tables: Some(7),
+                    max_columns_per_table: None,
+                }),
+                template: Some("standard".to_string()),
+            }))
+            .await
+            .expect("failed to create namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(overridden.retention_period_ns, None);
+        assert_eq!(overridden.max_tables, 7);
+        assert_eq!(overridden.max_columns_per_table, 321);
+
+        // Updating the template without propagating leaves the namespaces
+        // unchanged.
+        let updated_template = NamespaceTemplate {
+            retention_period_ns: Some(RETENTION * 2),
+            partition_template: None,
+            service_protection_limits: Some(ServiceProtectionLimits {
+                max_tables: Some(456),
+                max_columns_per_table: Some(654),
+            }),
+            ..template
+        };
+        let resp = handler
+            .update_namespace_template(Request::new(UpdateNamespaceTemplateRequest {
+                template: Some(updated_template.clone()),
+                propagate: false,
+            }))
+            .await
+            .expect("failed to update namespace template")
+            .into_inner();
+        assert_eq!(resp.template, Some(updated_template.clone()));
+        assert!(resp.namespaces.is_empty());
+
+        let namespaces = handler
+            .get_namespaces(Request::new(Default::default()))
+            .await
+            .expect("must return namespaces")
+            .into_inner()
+            .namespaces;
+        assert!(namespaces.contains(&from_template));
+        assert!(namespaces.contains(&overridden));
+
+        // Propagating the update applies the retention period and limits to
+        // every namespace created from the template, but not the partition
+        // template.
+        let resp = handler
+            .update_namespace_template(Request::new(UpdateNamespaceTemplateRequest {
+                template: Some(updated_template.clone()),
+                propagate: true,
+            }))
+            .await
+            .expect("failed to update namespace template")
+            .into_inner();
+        let mut namespaces = resp.namespaces;
+        namespaces.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_matches!(namespaces.as_slice(), [a, b] => {
+            for ns in [a, b] {
+                assert_eq!(ns.retention_period_ns, Some(RETENTION * 2));
+                assert_eq!(ns.max_tables, 456);
+                assert_eq!(ns.max_columns_per_table, 654);
+                assert_eq!(
+                    ns.partition_template,
+                    Some(PARTITION_BY_DAY_PROTO.as_ref().clone())
+                );
+            }
+        });
+
+        let templates = handler
+            .get_namespace_templates(Request::new(Default::default()))
+            .await
+            .expect("must return namespace templates")
+            .into_inner()
+            .templates;
+        assert_eq!(templates, vec![updated_template]);
+    }
+
+    async fn validate(
+        handler: &NamespaceService,
+        req: CreateNamespaceRequest,
+    ) -> ValidateNamespaceResponse {
+        handler
+            .validate_namespace(Request::new(ValidateNamespaceRequest {
+                namespace: Some(req),
+            }))
+            .await
+            .expect("failed to validate namespace")
+            .into_inner()
+    }
+
+    #[tokio::test]
+    async fn test_validate_namespace() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = NamespaceService::new(catalog);
+
+        let fields = |issues: Vec<ValidationIssue>| -> Vec<String> {
+            issues.into_iter().map(|i| i.field).collect()
+        };
+
+        let req = CreateNamespaceRequest {
+            name: NS_NAME.to_string(),
+            retention_period_ns: Some(RETENTION),
+            partition_template: None,
+            service_protection_limits: None,
+            template: None,
+        };
+
+        // Valid settings produce no errors or warnings.
+        let resp = validate(&handler, req.clone()).await;
+        assert!(resp.errors.is_empty());
+        assert!(resp.warnings.is_empty());
+
+        // Validating does not create the namespace.
+        let current = handler
+            .get_namespaces(Request::new(Default::default()))
+            .await
+            .expect("must return namespaces")
+            .into_inner()
+            .namespaces;
+        assert!(current.is_empty());
+
+        // Every invalid setting is reported, not only the first.
+        let resp = validate(
+            &handler,
+            CreateNamespaceRequest {
+                name: "".to_string(),
+                retention_period_ns: Some(-1),
+                partition_template: Some(PartitionTemplate { parts: vec![] }),
+                service_protection_limits: None,
+                template: Some("bananas".to_string()),
+            },
+        )
+        .await;
+        assert_eq!(
+            fields(resp.errors),
+            [
+                "name",
+                "retention_period_ns",
+                "partition_template",
+                "template"
+            ]
+        );
+
+        // Settings that are accepted but likely unintended produce warnings.
+        let resp = validate(
+            &handler,
+            CreateNamespaceRequest {
+                retention_period_ns: Some(Duration::from_secs(60).as_nanos() as _),
+                ..req.clone()
+            },
+        )
+        .await;
+        assert!(resp.errors.is_empty());
+        assert_eq!(fields(resp.warnings), ["retention_period_ns"]);
+
+        // An existing namespace is reported as an error.
+        handler
+            .create_namespace(Request::new(req.clone()))
+            .await
+            .expect("failed to create namespace");
+        let resp = validate(&handler, req).await;
+        assert_eq!(fields(resp.errors), ["name"]);
+    }
+
     macro_rules! test_create_namespace_name {
         (
             $test_name:ident,
@@ -705,6 +1368,7 @@ mod tests {
                         retention_period_ns: Some(RETENTION),
                         partition_template: None,
                         service_protection_limits: None,
+                        template: None,
                     };
 
                     let got = handler.create_namespace(Request::new(req)).await;
@@ -749,6 +1413,9 @@ mod tests {
     test_create_namespace_name!(multi_byte, name = "🍌", want = Err(e) => {
         assert_eq!(e.code(), Code::InvalidArgument);
         assert_eq!(e.message(), "namespace name '🍌' contains invalid character, character number 0 is not whitelisted");
+        assert_matches!(generated_types::google::decode_field_violation(&e).collect::<Vec<_>>().as_slice(), [v] => {
+            assert_eq!(v.field, "name");
+        });
     });
 
     test_create_namespace_name!(
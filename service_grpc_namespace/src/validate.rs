@@ -0,0 +1,253 @@
+//! Warnings about namespace settings that are accepted, but are likely to
+//! behave unexpectedly.
+
+use data_types::{
+    partition_template::PARTITION_BY_DAY_PROTO, MaxColumnsPerTable, MaxTables,
+    NamespaceServiceProtectionLimitsOverride,
+};
+use generated_types::influxdata::iox::{
+    namespace::v1::ValidationIssue, partition_template::v1 as proto,
+};
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+/// The span of time covered by a single partition, derived from the finest
+/// strftime specifier of a partition template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Granularity {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Granularity {
+    /// The (maximum) duration of a partition of this granularity.
+    fn as_nanos(&self) -> i64 {
+        let secs = match self {
+            Self::Second => 1,
+            Self::Minute => 60,
+            Self::Hour => 60 * 60,
+            Self::Day => 24 * 60 * 60,
+            Self::Week => 7 * 24 * 60 * 60,
+            Self::Month => 31 * 24 * 60 * 60,
+            Self::Year => 366 * 24 * 60 * 60,
+        };
+        secs * NANOS_PER_SECOND
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Second => "second",
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+
+    /// Return the finest granularity of the strftime specifiers in `format`,
+    /// or `None` if it contains no date or time specifiers.
+    fn from_strftime(format: &str) -> Option<Self> {
+        let mut chars = format.chars();
+        let mut finest = None;
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                continue;
+            }
+            let g = match chars.next() {
+                Some('S' | 's' | 'T' | 'X' | 'c') => Self::Second,
+                Some('M' | 'R') => Self::Minute,
+                Some('H' | 'k' | 'I' | 'l') => Self::Hour,
+                Some('d' | 'e' | 'j' | 'D' | 'F' | 'x' | 'a' | 'A' | 'u' | 'w') => Self::Day,
+                Some('U' | 'W' | 'V') => Self::Week,
+                Some('m' | 'b' | 'B' | 'h') => Self::Month,
+                Some('Y' | 'y' | 'G' | 'g' | 'C') => Self::Year,
+                _ => continue,
+            };
+            finest = Some(finest.map_or(g, |f: Self| f.min(g)));
+        }
+        finest
+    }
+}
+
+fn issue(field: &str, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Return warnings about the effective settings of a namespace, after any
+/// namespace template has been applied.
+///
+/// A `partition_template` of `None` is the default of partitioning by day.
+pub(crate) fn warnings(
+    retention_period_ns: Option<i64>,
+    partition_template: Option<&proto::PartitionTemplate>,
+    limits: NamespaceServiceProtectionLimitsOverride,
+) -> Vec<ValidationIssue> {
+    let mut warnings = vec![];
+
+    let partition_template = partition_template.unwrap_or(&PARTITION_BY_DAY_PROTO);
+    let granularity = partition_template
+        .parts
+        .iter()
+        .filter_map(|p| match &p.part {
+            Some(proto::template_part::Part::TimeFormat(f)) => Granularity::from_strftime(f),
+            _ => None,
+        })
+        .min();
+
+    match (granularity, retention_period_ns) {
+        (None, _) => warnings.push(issue(
+            "partition_template",
+            "the partition template does not partition by time, so all data for a set of \
+            tag values is written to a single partition that is never dropped by the \
+            retention period",
+        )),
+        (Some(g), Some(retention)) if retention < g.as_nanos() => warnings.push(issue(
+            "retention_period_ns",
+            format!(
+                "the retention period of {}s is shorter than the one {} span of each \
+                partition, so expired data is kept until the whole partition has expired",
+                retention / NANOS_PER_SECOND,
+                g.name()
+            ),
+        )),
+        _ => {}
+    }
+
+    if let Some(max_tables) = limits.max_tables {
+        if max_tables.get() <= 0 {
+            warnings.push(issue(
+                "service_protection_limits.max_tables",
+                "a table limit of zero or less rejects writes creating any table",
+            ));
+        } else if max_tables > MaxTables::const_default() {
+            warnings.push(issue(
+                "service_protection_limits.max_tables",
+                format!(
+                    "the table limit of {max_tables} exceeds the default of {}, \
+                    increasing the memory used by routers and ingesters",
+                    MaxTables::const_default()
+                ),
+            ));
+        }
+    }
+
+    if let Some(max_columns) = limits.max_columns_per_table {
+        if max_columns.get() <= 0 {
+            warnings.push(issue(
+                "service_protection_limits.max_columns_per_table",
+                "a column limit of zero or less rejects writes creating any column",
+            ));
+        } else if max_columns > MaxColumnsPerTable::const_default() {
+            warnings.push(issue(
+                "service_protection_limits.max_columns_per_table",
+                format!(
+                    "the column limit of {max_columns} exceeds the default of {}, \
+                    increasing the cost of persisting and querying wide tables",
+                    MaxColumnsPerTable::const_default()
+                ),
+            ));
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 60 * 60 * NANOS_PER_SECOND;
+
+    fn template(parts: &[proto::template_part::Part]) -> proto::PartitionTemplate {
+        proto::PartitionTemplate {
+            parts: parts
+                .iter()
+                .cloned()
+                .map(|part| proto::TemplatePart { part: Some(part) })
+                .collect(),
+        }
+    }
+
+    fn no_limits() -> NamespaceServiceProtectionLimitsOverride {
+        NamespaceServiceProtectionLimitsOverride {
+            max_tables: None,
+            max_columns_per_table: None,
+        }
+    }
+
+    fn fields(warnings: Vec<ValidationIssue>) -> Vec<String> {
+        warnings.into_iter().map(|w| w.field).collect()
+    }
+
+    #[test]
+    fn test_granularity() {
+        assert_eq!(
+            Granularity::from_strftime("%Y-%m-%d"),
+            Some(Granularity::Day)
+        );
+        assert_eq!(
+            Granularity::from_strftime("%Y-%m"),
+            Some(Granularity::Month)
+        );
+        assert_eq!(Granularity::from_strftime("%Y %H"), Some(Granularity::Hour));
+        assert_eq!(
+            Granularity::from_strftime("%Y-W%W"),
+            Some(Granularity::Week)
+        );
+        assert_eq!(Granularity::from_strftime("%%H"), None);
+        assert_eq!(Granularity::from_strftime("bananas"), None);
+    }
+
+    #[test]
+    fn test_default_settings() {
+        assert!(warnings(None, None, no_limits()).is_empty());
+        assert!(warnings(Some(24 * HOUR), None, no_limits()).is_empty());
+    }
+
+    #[test]
+    fn test_retention_shorter_than_partition() {
+        assert_eq!(
+            fields(warnings(Some(HOUR), None, no_limits())),
+            ["retention_period_ns"]
+        );
+
+        let hourly = template(&[proto::template_part::Part::TimeFormat(
+            "%Y-%m-%dT%H".to_string(),
+        )]);
+        assert!(warnings(Some(HOUR), Some(&hourly), no_limits()).is_empty());
+    }
+
+    #[test]
+    fn test_no_time_partitioning() {
+        let by_tag = template(&[proto::template_part::Part::TagValue("region".to_string())]);
+        assert_eq!(
+            fields(warnings(None, Some(&by_tag), no_limits())),
+            ["partition_template"]
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = NamespaceServiceProtectionLimitsOverride {
+            max_tables: Some(MaxTables::new(0)),
+            max_columns_per_table: Some(MaxColumnsPerTable::new(10_000)),
+        };
+        assert_eq!(
+            fields(warnings(None, None, limits)),
+            [
+                "service_protection_limits.max_tables",
+                "service_protection_limits.max_columns_per_table"
+            ]
+        );
+    }
+}
@@ -19,6 +19,7 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+use bytes::Bytes;
 use futures::{stream::BoxStream, StreamExt};
 use generated_types::influxdata::iox::object_store::v1::*;
 use iox_catalog::interface::Catalog;
@@ -29,6 +30,10 @@ use std::sync::Arc;
 use tonic::{Request, Response, Status};
 use uuid::Uuid;
 
+/// The maximum number of bytes sent in each response when reading a file from
+/// an offset.
+const RANGE_CHUNK_SIZE_BYTES: usize = 1024 * 1024;
+
 /// Implementation of the ObjectStore gRPC service
 #[derive(Debug)]
 pub struct ObjectStoreService {
@@ -46,6 +51,48 @@ impl ObjectStoreService {
             object_store,
         }
     }
+
+    /// Read the file at `path` from `offset` to the end of the file, as a
+    /// stream of responses of at most [`RANGE_CHUNK_SIZE_BYTES`] each.
+    async fn get_range(
+        &self,
+        path: &object_store::path::Path,
+        offset: usize,
+    ) -> Result<BoxStream<'static, Result<GetParquetFileByObjectStoreIdResponse, Status>>, Status>
+    {
+        let size = self
+            .object_store
+            .head(path)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .size;
+
+        if offset > size {
+            return Err(Status::out_of_range(format!(
+                "offset {offset} is beyond the end of the file ({size} bytes)"
+            )));
+        }
+
+        let data = if offset < size {
+            self.object_store
+                .get_range(path, offset..size)
+                .await
+                .map_err(|e| Status::unknown(e.to_string()))?
+        } else {
+            Bytes::new()
+        };
+
+        let chunks = data
+            .chunks(RANGE_CHUNK_SIZE_BYTES)
+            .map(|chunk| {
+                Ok(GetParquetFileByObjectStoreIdResponse {
+                    data: chunk.to_vec(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Box::pin(futures::stream::iter(chunks)))
+    }
 }
 
 #[tonic::async_trait]
@@ -80,6 +127,13 @@ impl object_store_service_server::ObjectStoreService for ObjectStoreService {
         );
         let path = path.object_store_path();
 
+        if req.offset > 0 {
+            return self
+                .get_range(&path, req.offset as usize)
+                .await
+                .map(Response::new);
+        }
+
         let res = self
             .object_store
             .get(&path)
@@ -100,7 +154,6 @@ impl object_store_service_server::ObjectStoreService for ObjectStoreService {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bytes::Bytes;
     use data_types::{ColumnId, ColumnSet, CompactionLevel, ParquetFileParams, Timestamp};
     use generated_types::influxdata::iox::object_store::v1::object_store_service_server::ObjectStoreService;
     use iox_catalog::{
@@ -138,6 +191,7 @@ mod tests {
                 created_at: Timestamp::new(2343),
                 column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
                 max_l0_created_at: Timestamp::new(2343),
+                content_hash: None,
             };
 
             p1 = repos.parquet_files().create(p1params).await.unwrap();
@@ -161,6 +215,7 @@ mod tests {
         let grpc = super::ObjectStoreService::new(catalog, object_store);
         let request = GetParquetFileByObjectStoreIdRequest {
             uuid: p1.object_store_id.to_string(),
+            offset: 0,
         };
 
         let tonic_response = grpc
@@ -171,5 +226,44 @@ mod tests {
         let response = response.next().await.unwrap().unwrap();
 
         assert_eq!(response.data, data);
+
+        // Resume reading from an offset
+        let request = GetParquetFileByObjectStoreIdRequest {
+            uuid: p1.object_store_id.to_string(),
+            offset: 5,
+        };
+        let tonic_response = grpc
+            .get_parquet_file_by_object_store_id(Request::new(request))
+            .await
+            .expect("rpc request should succeed");
+        let response = tonic_response
+            .into_inner()
+            .map(|res| res.unwrap().data)
+            .concat()
+            .await;
+        assert_eq!(response, b"data");
+
+        // Reading from the end of the file returns no data
+        let request = GetParquetFileByObjectStoreIdRequest {
+            uuid: p1.object_store_id.to_string(),
+            offset: data.len() as u64,
+        };
+        let tonic_response = grpc
+            .get_parquet_file_by_object_store_id(Request::new(request))
+            .await
+            .expect("rpc request should succeed");
+        assert!(tonic_response.into_inner().next().await.is_none());
+
+        // Reading past the end of the file fails
+        let request = GetParquetFileByObjectStoreIdRequest {
+            uuid: p1.object_store_id.to_string(),
+            offset: data.len() as u64 + 1,
+        };
+        let err = grpc
+            .get_parquet_file_by_object_store_id(Request::new(request))
+            .await
+            .err()
+            .expect("rpc request should fail");
+        assert_eq!(err.code(), tonic::Code::OutOfRange);
     }
 }
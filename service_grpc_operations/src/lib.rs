@@ -0,0 +1,355 @@
+//! Implementation of the `google.longrunning.Operations` gRPC service, exposing
+//! the background jobs tracked by a [`tracker::TaskRegistry`]
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::todo,
+    clippy::use_self,
+    missing_debug_implementations,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::{fmt::Debug, sync::Arc, time::Duration};
+
+use data_types::job::Job;
+use generated_types::{
+    google::{
+        longrunning::{
+            operation::Result as OperationResult, operations_server::Operations,
+            CancelOperationRequest, DeleteOperationRequest, GetOperationRequest,
+            ListOperationsRequest, ListOperationsResponse, Operation, WaitOperationRequest,
+        },
+        protobuf::{Any, Empty},
+        rpc::Status as RpcStatus,
+    },
+    influxdata::iox::operations::v1::OperationMetadata,
+    prost::Message,
+    protobuf_type_url,
+};
+use observability_deps::tracing::info;
+use parking_lot::Mutex;
+use tonic::{Request, Response, Status};
+use tracker::{AbstractTaskRegistry, TaskId, TaskResult, TaskStatus, TaskTracker};
+
+/// The protobuf type of the `metadata` of every [`Operation`]
+pub const OPERATION_METADATA: &str = "influxdata.iox.operations.v1.OperationMetadata";
+
+/// How long `WaitOperation` blocks for if the request has no timeout
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Implementation of the gRPC operations service
+#[derive(Debug)]
+pub struct OperationsService<R> {
+    jobs: Arc<Mutex<R>>,
+}
+
+impl<R> OperationsService<R>
+where
+    R: AbstractTaskRegistry<Job> + Debug + Send + 'static,
+{
+    pub fn new(jobs: Arc<Mutex<R>>) -> Self {
+        Self { jobs }
+    }
+
+    fn get_tracker(&self, name: &str) -> Result<TaskTracker<Job>, Status> {
+        let id: TaskId = name
+            .parse()
+            .map_err(|_| Status::invalid_argument(format!("invalid operation name: {name}")))?;
+
+        self.jobs
+            .lock()
+            .get(id)
+            .ok_or_else(|| Status::not_found(format!("operation {name} not found")))
+    }
+}
+
+#[tonic::async_trait]
+impl<R> Operations for OperationsService<R>
+where
+    R: AbstractTaskRegistry<Job> + Debug + Send + 'static,
+{
+    async fn list_operations(
+        &self,
+        _request: Request<ListOperationsRequest>,
+    ) -> Result<Response<ListOperationsResponse>, Status> {
+        let mut trackers = self.jobs.lock().tracked();
+        trackers.sort_unstable_by_key(|t| t.id());
+
+        Ok(Response::new(ListOperationsResponse {
+            operations: trackers.iter().map(tracker_to_proto).collect(),
+            next_page_token: Default::default(),
+        }))
+    }
+
+    async fn get_operation(
+        &self,
+        request: Request<GetOperationRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let tracker = self.get_tracker(&request.into_inner().name)?;
+        Ok(Response::new(tracker_to_proto(&tracker)))
+    }
+
+    async fn delete_operation(
+        &self,
+        _request: Request<DeleteOperationRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        Err(Status::unimplemented(
+            "operations are removed automatically once complete",
+        ))
+    }
+
+    async fn cancel_operation(
+        &self,
+        request: Request<CancelOperationRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let tracker = self.get_tracker(&request.into_inner().name)?;
+        if !tracker.metadata().is_cancellable() {
+            return Err(Status::failed_precondition(format!(
+                "{} cannot be cancelled",
+                tracker.metadata()
+            )));
+        }
+
+        info!(id = tracker.id().0, job = %tracker.metadata(), "cancelling operation");
+        tracker.cancel();
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn wait_operation(
+        &self,
+        request: Request<WaitOperationRequest>,
+    ) -> Result<Response<Operation>, Status> {
+        let WaitOperationRequest { name, timeout } = request.into_inner();
+        let tracker = self.get_tracker(&name)?;
+
+        let timeout = match timeout {
+            Some(timeout) => Duration::try_from(timeout)
+                .map_err(|_| Status::invalid_argument("invalid timeout"))?,
+            None => DEFAULT_WAIT_TIMEOUT,
+        };
+
+        // Returning before the operation completes is not an error, the
+        // caller inspects `done`
+        let _ = tokio::time::timeout(timeout, tracker.join()).await;
+
+        Ok(Response::new(tracker_to_proto(&tracker)))
+    }
+}
+
+/// Returns the [`Operation`] describing the current state of `tracker`
+pub fn tracker_to_proto(tracker: &TaskTracker<Job>) -> Operation {
+    let status = tracker.get_status();
+    let progress = tracker.progress();
+
+    let mut metadata = OperationMetadata {
+        cpu_nanos: status.cpu_nanos().unwrap_or_default() as u64,
+        wall_nanos: status.wall_nanos().unwrap_or_default() as u64,
+        items_done: progress.done as u64,
+        items_total: progress.total.map(|v| v as u64),
+        job: Some(tracker.metadata().into()),
+        ..Default::default()
+    };
+
+    match status {
+        TaskStatus::Creating => {}
+        TaskStatus::Running {
+            total_count,
+            pending_count,
+            ..
+        } => {
+            metadata.total_count = total_count as u64;
+            metadata.pending_count = pending_count as u64;
+        }
+        TaskStatus::Complete {
+            total_count,
+            success_count,
+            error_count,
+            cancelled_count,
+            dropped_count,
+            ..
+        } => {
+            metadata.total_count = total_count as u64;
+            metadata.success_count = success_count as u64;
+            metadata.error_count = error_count as u64;
+            metadata.cancelled_count = cancelled_count as u64;
+            metadata.dropped_count = dropped_count as u64;
+        }
+    }
+
+    let result = status.result().map(|result| match result {
+        TaskResult::Success => OperationResult::Response(Any {
+            type_url: protobuf_type_url("google.protobuf.Empty"),
+            value: Default::default(),
+        }),
+        TaskResult::Cancelled => OperationResult::Error(RpcStatus {
+            code: tonic::Code::Cancelled as i32,
+            message: "operation cancelled".to_string(),
+            details: vec![],
+        }),
+        TaskResult::Dropped => OperationResult::Error(RpcStatus {
+            code: tonic::Code::Internal as i32,
+            message: "operation did not run to completion".to_string(),
+            details: vec![],
+        }),
+        TaskResult::Error => OperationResult::Error(RpcStatus {
+            code: tonic::Code::Internal as i32,
+            message: "operation failed".to_string(),
+            details: vec![],
+        }),
+    });
+
+    Operation {
+        name: tracker.id().to_string(),
+        metadata: Some(Any {
+            type_url: protobuf_type_url(OPERATION_METADATA),
+            value: metadata.encode_to_vec().into(),
+        }),
+        done: result.is_some(),
+        result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use data_types::PartitionId;
+    use generated_types::influxdata::iox::operations::v1::{
+        operation_metadata::Job as ProtoJob, CompactPartition,
+    };
+    use iox_time::SystemProvider;
+    use tracker::{TaskRegistry, TrackedFutureExt};
+
+    fn service() -> OperationsService<TaskRegistry<Job>> {
+        let registry = TaskRegistry::new(Arc::new(SystemProvider::new()));
+        OperationsService::new(Arc::new(Mutex::new(registry)))
+    }
+
+    fn decode_metadata(op: &Operation) -> OperationMetadata {
+        let any = op.metadata.as_ref().unwrap();
+        assert_eq!(any.type_url, protobuf_type_url(OPERATION_METADATA));
+        OperationMetadata::decode(any.value.clone()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list_get_cancel() {
+        let service = service();
+        let (tracker, registration) = service.jobs.lock().register(Job::CompactPartition {
+            partition_id: PartitionId::new(42),
+        });
+        let reporter = registration.progress_reporter();
+        reporter.add_total(2);
+        reporter.add_done(1);
+
+        let handle = tokio::spawn(futures::future::pending::<Result<(), ()>>().track(registration));
+
+        let ops = service
+            .list_operations(Request::new(ListOperationsRequest::default()))
+            .await
+            .unwrap()
+            .into_inner()
+            .operations;
+        assert_eq!(ops.len(), 1);
+        assert!(!ops[0].done);
+
+        let metadata = decode_metadata(&ops[0]);
+        assert_eq!(metadata.items_done, 1);
+        assert_eq!(metadata.items_total, Some(2));
+        assert_eq!(
+            metadata.job,
+            Some(ProtoJob::CompactPartition(CompactPartition {
+                partition_id: 42
+            }))
+        );
+
+        service
+            .cancel_operation(Request::new(CancelOperationRequest {
+                name: tracker.id().to_string(),
+            }))
+            .await
+            .unwrap();
+        handle.await.unwrap().unwrap_err();
+
+        let op = service
+            .get_operation(Request::new(GetOperationRequest {
+                name: tracker.id().to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(op.done);
+        match op.result {
+            Some(OperationResult::Error(status)) => {
+                assert_eq!(status.code, tonic::Code::Cancelled as i32)
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+        assert_eq!(decode_metadata(&op).cancelled_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_wait() {
+        let service = service();
+        let (tracker, registration) = service.jobs.lock().register(Job::WalReplay);
+        tokio::spawn(futures::future::ready(Ok::<_, ()>(())).track(registration));
+
+        let op = service
+            .wait_operation(Request::new(WaitOperationRequest {
+                name: tracker.id().to_string(),
+                timeout: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(op.done);
+        assert!(matches!(op.result, Some(OperationResult::Response(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_not_cancellable() {
+        let service = service();
+        let (tracker, registration) = service.jobs.lock().register(Job::WalReplay);
+        let handle = tokio::spawn(futures::future::pending::<Result<(), ()>>().track(registration));
+
+        let err = service
+            .cancel_operation(Request::new(CancelOperationRequest {
+                name: tracker.id().to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+        assert!(!tracker.is_cancelled());
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_not_found() {
+        let service = service();
+
+        let err = service
+            .get_operation(Request::new(GetOperationRequest {
+                name: "42".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+
+        let err = service
+            .cancel_operation(Request::new(CancelOperationRequest {
+                name: "bananas".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+}
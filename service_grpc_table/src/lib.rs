@@ -25,8 +25,8 @@ use workspace_hack as _;
 use std::sync::Arc;
 
 use data_types::{partition_template::TablePartitionTemplateOverride, NamespaceName};
-use generated_types::influxdata::iox::table::v1::*;
-use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use generated_types::{google::invalid_argument, influxdata::iox::table::v1::*};
+use iox_catalog::interface::{Catalog, RepoCollection, SoftDeletedRows};
 use observability_deps::tracing::{debug, error, info, warn};
 use tonic::{Request, Response, Status};
 
@@ -44,6 +44,63 @@ impl TableService {
     }
 }
 
+/// Look up the table `table` of the namespace `namespace`.
+async fn get_table(
+    repos: &mut dyn RepoCollection,
+    namespace: String,
+    table: &str,
+) -> Result<data_types::Table, Status> {
+    let namespace_name = NamespaceName::try_from(namespace)
+        .map_err(|e| invalid_argument("namespace", e.to_string()))?;
+
+    let namespace = repos
+        .namespaces()
+        .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+        .ok_or_else(|| {
+            Status::not_found(format!(
+                "Could not find a namespace with name {namespace_name}"
+            ))
+        })?;
+
+    repos
+        .tables()
+        .get_by_namespace_and_name(namespace.id, table)
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+        .ok_or_else(|| {
+            Status::not_found(format!(
+                "Could not find a table with name {table} in namespace {namespace_name}"
+            ))
+        })
+}
+
+/// Map the catalog errors of column changes to a [`Status`].
+fn column_change_error(e: iox_catalog::interface::Error) -> Status {
+    use iox_catalog::interface::Error;
+
+    match e {
+        Error::ColumnNotFoundByName { .. } => Status::not_found(e.to_string()),
+        Error::ColumnNameExists { .. } => Status::already_exists(e.to_string()),
+        Error::ColumnNotChangeable { .. } => Status::failed_precondition(e.to_string()),
+        other => Status::internal(other.to_string()),
+    }
+}
+
+/// Map a retention period of 0 to [`None`], like the namespace service, and
+/// reject negative retention periods.
+fn map_retention_period(v: Option<i64>) -> Result<Option<i64>, Status> {
+    match v {
+        Some(0) | None => Ok(None),
+        Some(v @ 1..) => Ok(Some(v)),
+        Some(_) => Err(invalid_argument(
+            "retention_period_ns",
+            "invalid negative retention period",
+        )),
+    }
+}
+
 #[tonic::async_trait]
 impl table_service_server::TableService for TableService {
     // List tables for a namespace
@@ -54,7 +111,7 @@ impl table_service_server::TableService for TableService {
         let mut repos = self.catalog.repositories().await;
 
         let namespace_name = NamespaceName::try_from(request.into_inner().namespace_name)
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+            .map_err(|e| invalid_argument("namespace_name", e.to_string()))?;
 
         debug!(%namespace_name, "listing tables for namespace");
 
@@ -91,7 +148,7 @@ impl table_service_server::TableService for TableService {
         } = request.into_inner();
 
         let namespace_name = NamespaceName::try_from(namespace)
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+            .map_err(|e| invalid_argument("namespace", e.to_string()))?;
 
         debug!(%name, %namespace_name, "Creating table");
 
@@ -114,7 +171,7 @@ impl table_service_server::TableService for TableService {
                     partition_template,
                     &namespace.partition_template,
                 )
-                .map_err(|v| Status::invalid_argument(v.to_string()))?,
+                .map_err(|v| invalid_argument("partition_template", v.to_string()))?,
                 namespace.id,
             )
             .await
@@ -143,16 +200,166 @@ impl table_service_server::TableService for TableService {
             table: Some(table.into()),
         }))
     }
+
+    // rename a table
+    async fn rename_table(
+        &self,
+        request: Request<RenameTableRequest>,
+    ) -> Result<Response<RenameTableResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let RenameTableRequest {
+            namespace,
+            name,
+            new_name,
+        } = request.into_inner();
+
+        debug!(%namespace, %name, %new_name, "Renaming table");
+
+        let table = get_table(&mut *repos, namespace, &name).await?;
+
+        let table = repos
+            .tables()
+            .rename(table.id, &new_name)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, table_id=%table.id, %name, %new_name, "failed to rename table");
+                match e {
+                    iox_catalog::interface::Error::TableNameExists { .. } => {
+                        Status::already_exists(e.to_string())
+                    }
+                    e => Status::internal(e.to_string()),
+                }
+            })?;
+
+        info!(table_id = %table.id, %name, %new_name, "renamed table");
+
+        Ok(Response::new(RenameTableResponse {
+            table: Some(table.into()),
+        }))
+    }
+
+    // update the retention period of a table
+    async fn update_table_retention(
+        &self,
+        request: Request<UpdateTableRetentionRequest>,
+    ) -> Result<Response<UpdateTableRetentionResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateTableRetentionRequest {
+            namespace,
+            name,
+            retention_period_ns,
+        } = request.into_inner();
+        let retention_period_ns = map_retention_period(retention_period_ns)?;
+
+        debug!(%namespace, %name, ?retention_period_ns, "Updating table retention period");
+
+        let table = get_table(&mut *repos, namespace, &name).await?;
+
+        let table = repos
+            .tables()
+            .update_retention_period(table.id, retention_period_ns)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, table_id=%table.id, %name, "failed to update table retention period");
+                Status::internal(e.to_string())
+            })?;
+
+        info!(
+            table_id = %table.id,
+            %name,
+            ?retention_period_ns,
+            "updated table retention period"
+        );
+
+        Ok(Response::new(UpdateTableRetentionResponse {
+            table: Some(table.into()),
+        }))
+    }
+
+    // rename a column of a table
+    async fn rename_column(
+        &self,
+        request: Request<RenameColumnRequest>,
+    ) -> Result<Response<RenameColumnResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let RenameColumnRequest {
+            namespace,
+            table,
+            name,
+            new_name,
+        } = request.into_inner();
+
+        debug!(%namespace, %table, %name, %new_name, "Renaming column");
+
+        let table = get_table(&mut *repos, namespace, &table).await?;
+
+        let column = repos
+            .columns()
+            .rename(table.id, &name, &new_name)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, table_id=%table.id, %name, %new_name, "failed to rename column");
+                column_change_error(e)
+            })?;
+
+        info!(
+            table_id = %table.id,
+            column_id = column.id.get(),
+            %name,
+            %new_name,
+            "renamed column"
+        );
+
+        Ok(Response::new(RenameColumnResponse {}))
+    }
+
+    // drop a column of a table
+    async fn drop_column(
+        &self,
+        request: Request<DropColumnRequest>,
+    ) -> Result<Response<DropColumnResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let DropColumnRequest {
+            namespace,
+            table,
+            name,
+        } = request.into_inner();
+
+        debug!(%namespace, %table, %name, "Dropping column");
+
+        let table = get_table(&mut *repos, namespace, &table).await?;
+
+        let column = repos.columns().delete(table.id, &name).await.map_err(|e| {
+            warn!(error=%e, table_id=%table.id, %name, "failed to drop column");
+            column_change_error(e)
+        })?;
+
+        info!(
+            table_id = %table.id,
+            column_id = column.id.get(),
+            %name,
+            "dropped column"
+        );
+
+        Ok(Response::new(DropColumnResponse {}))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use data_types::{partition_template::NamespacePartitionTemplateOverride, TableId};
+    use data_types::{partition_template::NamespacePartitionTemplateOverride, ColumnType, TableId};
     use generated_types::influxdata::iox::{
         partition_template::v1::{template_part, PartitionTemplate, TemplatePart},
         table::v1::table_service_server::TableService as _,
     };
-    use iox_catalog::{mem::MemCatalog, test_helpers::arbitrary_namespace};
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
     use tonic::Code;
 
     use super::*;
@@ -466,4 +673,190 @@ mod tests {
         let all_tables = catalog.repositories().await.tables().list().await.unwrap();
         assert!(all_tables.is_empty());
     }
+
+    #[tokio::test]
+    async fn rename_table() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let (namespace, table) = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "grapes").await;
+            let table = arbitrary_table(&mut *repos, "varietals", &namespace).await;
+            arbitrary_table(&mut *repos, "regions", &namespace).await;
+            (namespace, table)
+        };
+
+        let renamed = handler
+            .rename_table(Request::new(RenameTableRequest {
+                namespace: namespace.name.clone(),
+                name: "varietals".into(),
+                new_name: "varieties".into(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(renamed.id, table.id.get());
+        assert_eq!(renamed.name, "varieties");
+
+        // the old name no longer exists
+        let error = handler
+            .rename_table(Request::new(RenameTableRequest {
+                namespace: namespace.name.clone(),
+                name: "varietals".into(),
+                new_name: "varieties".into(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        // the new name must not be taken
+        let error = handler
+            .rename_table(Request::new(RenameTableRequest {
+                namespace: namespace.name.clone(),
+                name: "varieties".into(),
+                new_name: "regions".into(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::AlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn update_table_retention() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let (namespace, table) = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "grapes").await;
+            let table = arbitrary_table(&mut *repos, "varietals", &namespace).await;
+            (namespace, table)
+        };
+        assert_eq!(table.retention_period_ns, None);
+
+        let update = |retention_period_ns| {
+            handler.update_table_retention(Request::new(UpdateTableRetentionRequest {
+                namespace: namespace.name.clone(),
+                name: "varietals".into(),
+                retention_period_ns,
+            }))
+        };
+
+        let updated = update(Some(42)).await.unwrap().into_inner().table.unwrap();
+        assert_eq!(updated.id, table.id.get());
+        assert_eq!(updated.retention_period_ns, Some(42));
+
+        let stored = {
+            let mut repos = catalog.repositories().await;
+            repos.tables().get_by_id(table.id).await.unwrap().unwrap()
+        };
+        assert_eq!(stored.retention_period_ns, Some(42));
+
+        // 0 removes the table retention period
+        let updated = update(Some(0)).await.unwrap().into_inner().table.unwrap();
+        assert_eq!(updated.retention_period_ns, None);
+
+        let error = update(Some(-1)).await.unwrap_err();
+        assert_eq!(error.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn rename_and_drop_column() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let (namespace, table) = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "grapes").await;
+            let table = arbitrary_table(&mut *repos, "varietals", &namespace).await;
+            for (name, column_type) in [
+                ("time", ColumnType::Time),
+                ("color", ColumnType::Tag),
+                ("tanins", ColumnType::F64),
+            ] {
+                repos
+                    .columns()
+                    .create_or_get(name, table.id, column_type)
+                    .await
+                    .unwrap();
+            }
+            (namespace, table)
+        };
+
+        handler
+            .rename_column(Request::new(RenameColumnRequest {
+                namespace: namespace.name.clone(),
+                table: table.name.clone(),
+                name: "tanins".into(),
+                new_name: "tannins".into(),
+            }))
+            .await
+            .unwrap();
+
+        // the old name no longer exists
+        let error = handler
+            .rename_column(Request::new(RenameColumnRequest {
+                namespace: namespace.name.clone(),
+                table: table.name.clone(),
+                name: "tanins".into(),
+                new_name: "tannins".into(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        // tags cannot be dropped
+        let error = handler
+            .drop_column(Request::new(DropColumnRequest {
+                namespace: namespace.name.clone(),
+                table: table.name.clone(),
+                name: "color".into(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::FailedPrecondition);
+        assert_eq!(
+            error.message(),
+            "cannot change column color: only field columns can be dropped"
+        );
+
+        handler
+            .drop_column(Request::new(DropColumnRequest {
+                namespace: namespace.name.clone(),
+                table: table.name.clone(),
+                name: "tannins".into(),
+            }))
+            .await
+            .unwrap();
+
+        let mut column_names: Vec<_> = catalog
+            .repositories()
+            .await
+            .columns()
+            .list_by_table_id(table.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        column_names.sort();
+        assert_eq!(column_names, &["color", "time"]);
+
+        // unknown tables are reported
+        let error = handler
+            .drop_column(Request::new(DropColumnRequest {
+                namespace: namespace.name.clone(),
+                table: "does_not_exist".into(),
+                name: "color".into(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+    }
 }
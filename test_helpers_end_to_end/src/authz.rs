@@ -3,7 +3,7 @@ use generated_types::influxdata::iox::authz::v1::{
     iox_authorizer_service_server::{IoxAuthorizerService, IoxAuthorizerServiceServer},
     permission::PermissionOneOf,
     resource_action_permission::{Action, ResourceType},
-    AuthorizeRequest, AuthorizeResponse, Permission, ResourceActionPermission,
+    AuthorizeRequest, AuthorizeResponse, Permission, ResourceActionPermission, TagFilterPolicy,
 };
 use observability_deps::tracing::{error, info};
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
@@ -103,6 +103,29 @@ impl Authorizer {
         token
     }
 
+    /// Attach a tag filter policy to `token`, restricting the rows of `namespace_name` it can
+    /// access to those where `tag` has one of `values`.
+    pub fn add_tag_filter(
+        &mut self,
+        token: &str,
+        namespace_name: &str,
+        tag: &str,
+        values: &[&str],
+    ) {
+        self.tokens
+            .lock()
+            .unwrap()
+            .get_mut(token.as_bytes())
+            .expect("unknown token")
+            .push(Permission {
+                permission_one_of: Some(PermissionOneOf::TagFilter(TagFilterPolicy {
+                    database: namespace_name.to_string(),
+                    tag: tag.to_string(),
+                    values: values.iter().map(ToString::to_string).collect(),
+                })),
+            });
+    }
+
     /// Get the address the server is listening at.
     pub fn addr(&self) -> String {
         format!("http://{}", self.addr)
@@ -138,6 +161,22 @@ impl IoxAuthorizerService for AuthorizerService {
         let valid = recognized.is_some();
         let perms = recognized.unwrap_or_default();
 
+        // tag filters apply to every requested database
+        let databases: Vec<_> = request
+            .permissions
+            .iter()
+            .filter_map(|p| match &p.permission_one_of {
+                Some(PermissionOneOf::ResourceAction(ra)) => ra.resource_id.as_deref(),
+                _ => None,
+            })
+            .collect();
+        let tag_filters = perms.iter().filter(|p| {
+            matches!(
+                &p.permission_one_of,
+                Some(PermissionOneOf::TagFilter(tf)) if databases.contains(&tf.database.as_str())
+            )
+        });
+
         Ok(tonic::Response::new(AuthorizeResponse {
             valid,
             subject: None,
@@ -145,6 +184,7 @@ impl IoxAuthorizerService for AuthorizerService {
                 .permissions
                 .iter()
                 .filter(|p| perms.contains(p))
+                .chain(tag_filters)
                 .cloned()
                 .collect(),
         }))
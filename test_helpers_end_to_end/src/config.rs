@@ -224,6 +224,11 @@ impl TestConfig {
             .with_env("INFLUXDB_IOX_SINGLE_TENANCY", "true")
     }
 
+    /// Serve the gRPC API to change the log filter at runtime.
+    pub fn with_log_filter_api(self) -> Self {
+        self.with_env("INFLUXDB_IOX_LOG_FILTER_API", "true")
+    }
+
     // Get the catalog DSN URL if set.
     pub fn dsn(&self) -> &Option<String> {
         &self.dsn
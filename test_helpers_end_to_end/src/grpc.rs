@@ -3,9 +3,11 @@ use generated_types::{
     aggregate::AggregateType,
     node::{Comparison, Logical, Type as NodeType, Value},
     read_group_request::Group,
-    Aggregate, MeasurementFieldsRequest, MeasurementNamesRequest, MeasurementTagKeysRequest,
-    MeasurementTagValuesRequest, Node, Predicate, ReadFilterRequest, ReadGroupRequest, ReadSource,
-    ReadWindowAggregateRequest, TagKeyMetaNames, TagKeysRequest, TagValuesRequest, TimestampRange,
+    read_window_aggregate_request::Fill,
+    Aggregate, FieldsGroupedByMeasurementRequest, MeasurementFieldsRequest,
+    MeasurementNamesRequest, MeasurementTagKeysRequest, MeasurementTagValuesRequest, Node,
+    Predicate, ReadFilterRequest, ReadGroupRequest, ReadSource, ReadWindowAggregateRequest,
+    TagKeyMetaNames, TagKeysRequest, TagValuesRequest, TimestampPrecision, TimestampRange,
 };
 use prost::Message;
 
@@ -21,9 +23,15 @@ pub struct GrpcRequestBuilder {
     group_keys: Option<Vec<String>>,
     // also used for read_window_aggregate requests
     aggregate_type: Option<AggregateType>,
+    // number of rows to select for top / bottom aggregates
+    aggregate_n: i64,
 
     window_every: Option<i64>,
     offset: Option<i64>,
+    fill: Fill,
+
+    // for read_filter, read_group and read_window_aggregate requests
+    timestamp_precision: TimestampPrecision,
 }
 
 /// Trait for converting various literal rust values to their
@@ -351,6 +359,14 @@ impl GrpcRequestBuilder {
         }
     }
 
+    /// Set the number of rows to select for a top or bottom aggregate
+    pub fn aggregate_n(self, aggregate_n: i64) -> Self {
+        Self {
+            aggregate_n,
+            ..self
+        }
+    }
+
     /// Set the window_every field for a read_window_aggregate request
     pub fn window_every(self, window_every: i64) -> Self {
         assert!(
@@ -372,11 +388,24 @@ impl GrpcRequestBuilder {
         }
     }
 
+    pub fn fill(self, fill: Fill) -> Self {
+        Self { fill, ..self }
+    }
+
+    /// Set the precision of the returned timestamps
+    pub fn timestamp_precision(self, timestamp_precision: TimestampPrecision) -> Self {
+        Self {
+            timestamp_precision,
+            ..self
+        }
+    }
+
     pub fn build_read_filter(self) -> tonic::Request<ReadFilterRequest> {
         tonic::Request::new(ReadFilterRequest {
             read_source: self.read_source,
             range: self.range,
             predicate: self.predicate,
+            timestamp_precision: self.timestamp_precision as i32,
             ..Default::default()
         })
     }
@@ -418,6 +447,16 @@ impl GrpcRequestBuilder {
         })
     }
 
+    pub fn build_fields_grouped_by_measurement(
+        self,
+    ) -> tonic::Request<FieldsGroupedByMeasurementRequest> {
+        tonic::Request::new(FieldsGroupedByMeasurementRequest {
+            source: self.read_source,
+            range: self.range,
+            predicate: self.predicate,
+        })
+    }
+
     pub fn build_measurement_tag_values(
         self,
         measurement: &str,
@@ -444,10 +483,21 @@ impl GrpcRequestBuilder {
         })
     }
 
+    pub fn build_fields_grouped_by_measurement(
+        self,
+    ) -> tonic::Request<FieldsGroupedByMeasurementRequest> {
+        tonic::Request::new(FieldsGroupedByMeasurementRequest {
+            source: self.read_source,
+            range: self.range,
+            predicate: self.predicate,
+        })
+    }
+
     /// Creates a read group request
     pub fn build_read_group(self) -> tonic::Request<ReadGroupRequest> {
         let aggregate = self.aggregate_type.map(|aggregate_type| Aggregate {
             r#type: aggregate_type.into(),
+            n: self.aggregate_n,
         });
 
         let group_keys = self.group_keys.unwrap_or_default();
@@ -463,6 +513,7 @@ impl GrpcRequestBuilder {
             group_keys,
             group,
             aggregate,
+            timestamp_precision: self.timestamp_precision as i32,
         })
     }
 
@@ -474,6 +525,7 @@ impl GrpcRequestBuilder {
             .map(|aggregate_type| {
                 vec![Aggregate {
                     r#type: aggregate_type.into(),
+                    n: self.aggregate_n,
                 }]
             })
             .expect("No aggregate specified, can't create read_window_aggregate request");
@@ -487,6 +539,9 @@ impl GrpcRequestBuilder {
             aggregate,
             window: None,
             tag_key_meta_names: TagKeyMetaNames::Text as i32,
+            fill: self.fill as i32,
+            timestamp_precision: self.timestamp_precision as i32,
+            aggregate_expressions: vec![],
         })
     }
 }
@@ -24,6 +24,15 @@ const CHANNEL_SIZE: usize = 100_000;
 #[async_trait]
 pub trait AsyncExport: Send + 'static {
     async fn export(&mut self, span: Vec<Span>);
+
+    /// The maximum number of spans passed to a single [`Self::export()`]
+    /// call.
+    ///
+    /// Spans already queued when a batch is started are included in the batch,
+    /// up to this limit.
+    fn max_batch_size(&self) -> usize {
+        1
+    }
 }
 
 /// `AsyncExporter` wraps a `AsyncExport` and sinks spans to it
@@ -34,7 +43,8 @@ pub trait AsyncExport: Send + 'static {
 /// If this worker cannot keep up, and this queue fills up, spans will
 /// be dropped and warnings logged
 ///
-/// Note: Currently this does not batch spans (#2392)
+/// Spans are only batched if the `AsyncExport` allows it (see
+/// [`AsyncExport::max_batch_size()`]).
 #[derive(Debug)]
 pub struct AsyncExporter {
     join: Shared<BoxFuture<'static, Result<(), Arc<JoinError>>>>,
@@ -105,7 +115,29 @@ async fn background_worker<T: AsyncExport>(
 ) {
     loop {
         match receiver.recv().await {
-            Some(Some(span)) => exporter.export(vec![span]).await,
+            Some(Some(span)) => {
+                let mut batch = vec![span];
+                let mut shutdown = false;
+
+                // Opportunistically add any already queued spans to the batch.
+                while batch.len() < exporter.max_batch_size() {
+                    match receiver.try_recv() {
+                        Ok(Some(span)) => batch.push(span),
+                        Ok(None) => {
+                            shutdown = true;
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                exporter.export(batch).await;
+
+                if shutdown {
+                    info!("async exporter shut down");
+                    break;
+                }
+            }
             Some(None) => {
                 info!("async exporter shut down");
                 break;
@@ -182,4 +214,41 @@ mod tests {
         assert_eq!(s2.ctx.span_id.get(), r3.ctx.span_id.get());
         assert_eq!(s2.ctx.trace_id.get(), r3.ctx.trace_id.get());
     }
+
+    #[derive(Debug)]
+    struct BatchSizeRecorder {
+        max_batch_size: usize,
+        batches: Arc<std::sync::Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl AsyncExport for BatchSizeRecorder {
+        async fn export(&mut self, batch: Vec<Span>) {
+            self.batches.lock().unwrap().push(batch.len());
+        }
+
+        fn max_batch_size(&self) -> usize {
+            self.max_batch_size
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exporter_batching() {
+        let batches = Arc::new(std::sync::Mutex::new(vec![]));
+        let exporter = AsyncExporter::new(BatchSizeRecorder {
+            max_batch_size: 2,
+            batches: Arc::clone(&batches),
+        });
+
+        let root = SpanContext::new(Arc::new(trace::LogTraceCollector::new()));
+        for _ in 0..5 {
+            exporter.export(root.child("foo"));
+        }
+
+        // The spans are all queued before the worker runs, and are batched
+        // up to the max batch size.
+        exporter.drain().await.unwrap();
+
+        assert_eq!(*batches.lock().unwrap(), [2, 2, 1]);
+    }
 }
@@ -33,6 +33,16 @@ impl JaegerTag {
             value: value.into(),
         }
     }
+
+    /// The tag key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The tag value.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
 }
 
 impl From<JaegerTag> for jaeger::Tag {
@@ -16,15 +16,18 @@ use workspace_hack as _;
 
 use crate::export::AsyncExporter;
 use crate::jaeger::JaegerAgentExporter;
+use crate::otlp::OtlpExporter;
 use iox_time::SystemProvider;
 use jaeger::JaegerTag;
 use snafu::Snafu;
 use std::num::{NonZeroU16, NonZeroU64};
 use std::sync::Arc;
+use trace_http::sampler::{Sampler, SamplingRule};
 
 pub mod export;
 
 mod jaeger;
+mod otlp;
 mod rate_limiter;
 
 /// Auto-generated thrift code
@@ -56,7 +59,7 @@ pub const DEFAULT_INFLUX_TRACE_CONTEXT_HEADER_NAME: &str = "influx-trace-id";
 pub struct TracingConfig {
     /// Tracing: exporter type
     ///
-    /// Can be one of: none, jaeger
+    /// Can be one of: none, jaeger, otlp
     #[clap(
         long = "traces-exporter",
         env = "TRACES_EXPORTER",
@@ -93,7 +96,7 @@ pub struct TracingConfig {
 
     /// Tracing: Jaeger service name.
     ///
-    /// Only used if `--traces-exporter` is "jaeger".
+    /// Only used if `--traces-exporter` is "jaeger" or "otlp".
     #[clap(
         long = "traces-exporter-jaeger-service-name",
         env = "TRACES_EXPORTER_JAEGER_SERVICE_NAME",
@@ -128,7 +131,7 @@ pub struct TracingConfig {
     ///
     /// Use a comma-delimited string to set multiple pairs: env=prod,region=eu-1
     ///
-    /// Only used if `--traces-exporter` is "jaeger".
+    /// Only used if `--traces-exporter` is "jaeger" or "otlp".
     #[clap(
         long = "traces-jaeger-tags",
         env = "TRACES_EXPORTER_JAEGER_TAGS",
@@ -139,7 +142,7 @@ pub struct TracingConfig {
 
     /// Tracing: Maximum number of message sent to a Jaeger service, per second.
     ///
-    /// Only used if `--traces-exporter` is "jaeger".
+    /// Only used if `--traces-exporter` is "jaeger" or "otlp".
     #[clap(
         long = "traces-jaeger-max-msgs-per-second",
         env = "TRACES_JAEGER_MAX_MSGS_PER_SECOND",
@@ -147,6 +150,47 @@ pub struct TracingConfig {
         action
     )]
     pub traces_jaeger_max_msgs_per_second: NonZeroU64,
+
+    /// Tracing: OpenTelemetry collector OTLP/HTTP endpoint.
+    ///
+    /// Spans are sent as JSON to the `/v1/traces` path of this endpoint.
+    ///
+    /// Only used if `--traces-exporter` is "otlp".
+    #[clap(
+        long = "traces-exporter-otlp-endpoint",
+        env = "TRACES_EXPORTER_OTLP_ENDPOINT",
+        default_value = "http://localhost:4318",
+        action
+    )]
+    pub traces_exporter_otlp_endpoint: String,
+
+    /// Tracing: sampling rules for requests that carry no trace context.
+    ///
+    /// Each rule has the form <namespace>:<rpc>=<rate>, where "*" matches
+    /// any namespace / RPC and the rate is between 0 and 1. The first
+    /// matching rule applies. Use a comma-delimited string to set multiple
+    /// rules: *:Delete=1,*:ReadFilter=0.01,my_db:*=0.5
+    ///
+    /// The namespace is only known for HTTP requests that name it in the
+    /// query string.
+    #[clap(
+        long = "traces-sampling-rules",
+        env = "TRACES_SAMPLING_RULES",
+        value_delimiter = ',',
+        action
+    )]
+    pub traces_sampling_rules: Option<Vec<SamplingRule>>,
+
+    /// Tracing: sampling rate for requests that carry no trace context and
+    /// match no sampling rule.
+    #[clap(
+        long = "traces-sampling-default-rate",
+        env = "TRACES_SAMPLING_DEFAULT_RATE",
+        default_value = "0",
+        value_parser = parse_rate,
+        action
+    )]
+    pub traces_sampling_default_rate: f64,
 }
 
 impl TracingConfig {
@@ -154,14 +198,33 @@ impl TracingConfig {
         match self.traces_exporter {
             TracesExporter::None => Ok(None),
             TracesExporter::Jaeger => Ok(Some(jaeger_exporter(self)?)),
+            TracesExporter::Otlp => Ok(Some(otlp_exporter(self)?)),
         }
     }
+
+    /// Build the [`Sampler`] for requests that carry no trace context.
+    pub fn sampler(&self) -> Sampler {
+        Sampler::new(
+            self.traces_sampling_rules.clone().unwrap_or_default(),
+            self.traces_sampling_default_rate,
+        )
+    }
+}
+
+fn parse_rate(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(v) if (0.0..=1.0).contains(&v) => Ok(v),
+        _ => Err(format!(
+            "invalid sampling rate '{s}', must be between 0 and 1"
+        )),
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum TracesExporter {
     None,
     Jaeger,
+    Otlp,
 }
 
 impl std::str::FromStr for TracesExporter {
@@ -171,8 +234,9 @@ impl std::str::FromStr for TracesExporter {
         match s.to_ascii_lowercase().as_str() {
             "none" => Ok(Self::None),
             "jaeger" => Ok(Self::Jaeger),
+            "otlp" => Ok(Self::Otlp),
             _ => Err(format!(
-                "Invalid traces exporter '{s}'. Valid options: none, jaeger"
+                "Invalid traces exporter '{s}'. Valid options: none, jaeger, otlp"
             )),
         }
     }
@@ -185,6 +249,9 @@ pub enum Error {
 
     #[snafu(context(false))]
     IOError { source: std::io::Error },
+
+    #[snafu(display("Failed to create HTTP client: {}", source))]
+    HttpClient { source: reqwest::Error },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -211,3 +278,22 @@ fn jaeger_exporter(config: &TracingConfig) -> Result<Arc<AsyncExporter>> {
 
     Ok(Arc::new(AsyncExporter::new(jaeger)))
 }
+
+fn otlp_exporter(config: &TracingConfig) -> Result<Arc<AsyncExporter>> {
+    let mut otlp = OtlpExporter::new(
+        config.traces_exporter_jaeger_service_name.clone(),
+        &config.traces_exporter_otlp_endpoint,
+        Arc::new(SystemProvider::new()),
+        config.traces_jaeger_max_msgs_per_second,
+    )?;
+
+    // Use any specified static span tags.
+    if let Some(tags) = &config.traces_jaeger_tags {
+        otlp = otlp.with_tags(
+            tags.iter()
+                .map(|t| (t.key().to_string(), t.value().to_string())),
+        );
+    }
+
+    Ok(Arc::new(AsyncExporter::new(otlp)))
+}
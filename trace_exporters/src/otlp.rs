@@ -0,0 +1,259 @@
+//! An exporter of spans to an OpenTelemetry collector using the OTLP/HTTP JSON
+//! protocol.
+//!
+//! See <https://opentelemetry.io/docs/specs/otlp/#otlphttp>.
+
+use std::{num::NonZeroU64, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use iox_time::TimeProvider;
+use observability_deps::tracing::*;
+use serde_json::{json, Value};
+use trace::span::{MetaValue, Span, SpanStatus};
+
+use crate::{export::AsyncExport, rate_limiter::RateLimiter};
+
+/// The path (relative to the collector endpoint) spans are sent to.
+const TRACES_PATH: &str = "v1/traces";
+
+/// The timeout for a single export request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The maximum number of spans sent in a single export request.
+const MAX_BATCH_SIZE: usize = 512;
+
+/// OTLP span kind "server".
+const SPAN_KIND_SERVER: u8 = 2;
+
+/// OTLP status codes.
+const STATUS_CODE_UNSET: u8 = 0;
+const STATUS_CODE_OK: u8 = 1;
+const STATUS_CODE_ERROR: u8 = 2;
+
+/// `OtlpExporter` receives span data and sends it as JSON over HTTP to an
+/// OpenTelemetry collector.
+#[derive(Debug)]
+pub struct OtlpExporter {
+    /// The name of the service
+    service_name: String,
+
+    /// The full URL spans are sent to.
+    url: String,
+
+    client: reqwest::Client,
+
+    /// Optional static attributes to annotate every span with.
+    tags: Vec<(String, String)>,
+
+    /// Rate limiter
+    rate_limiter: RateLimiter,
+}
+
+impl OtlpExporter {
+    pub fn new(
+        service_name: String,
+        endpoint: &str,
+        time_provider: Arc<dyn TimeProvider>,
+        max_msgs_per_second: NonZeroU64,
+    ) -> super::Result<Self> {
+        let url = format!("{}/{}", endpoint.trim().trim_end_matches('/'), TRACES_PATH);
+        info!(%url, %service_name, "Creating OTLP tracing exporter");
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| super::Error::HttpClient { source: e })?;
+
+        Ok(Self {
+            service_name,
+            url,
+            client,
+            tags: vec![],
+            rate_limiter: RateLimiter::new(max_msgs_per_second, time_provider),
+        })
+    }
+
+    /// Annotate all spans emitted by this exporter with the specified static
+    /// key/value attributes.
+    pub fn with_tags(self, tags: impl IntoIterator<Item = (String, String)>) -> Self {
+        let tags = tags.into_iter().collect::<Vec<_>>();
+        debug!(?tags, "setting static OTLP resource attributes");
+        Self { tags, ..self }
+    }
+
+    /// Build the `ExportTraceServiceRequest` JSON payload for `spans`.
+    fn make_request(&self, spans: Vec<Span>) -> Value {
+        let resource_attributes = std::iter::once(("service.name", self.service_name.as_str()))
+            .chain(self.tags.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .map(|(k, v)| attribute(k, &MetaValue::String(v.to_string().into())))
+            .collect::<Vec<_>>();
+
+        json!({
+            "resourceSpans": [{
+                "resource": { "attributes": resource_attributes },
+                "scopeSpans": [{
+                    "scope": { "name": "iox" },
+                    "spans": spans.iter().map(span_json).collect::<Vec<_>>(),
+                }],
+            }],
+        })
+    }
+}
+
+#[async_trait]
+impl AsyncExport for OtlpExporter {
+    async fn export(&mut self, spans: Vec<Span>) {
+        let body = self.make_request(spans);
+
+        self.rate_limiter.send().await;
+
+        match self.client.post(&self.url).json(&body).send().await {
+            Ok(resp) if !resp.status().is_success() => {
+                error!(status=%resp.status(), "OTLP collector rejected span batch")
+            }
+            Ok(_) => {}
+            Err(e) => error!(%e, "error sending span batch to OTLP collector"),
+        }
+    }
+
+    fn max_batch_size(&self) -> usize {
+        MAX_BATCH_SIZE
+    }
+}
+
+/// Convert a [`Span`] into its OTLP JSON representation.
+fn span_json(s: &Span) -> Value {
+    let nanos = |t: Option<chrono::DateTime<chrono::Utc>>| {
+        // OTLP JSON encodes 64-bit integers as strings.
+        t.and_then(|t| t.timestamp_nanos_opt())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let (code, message) = match s.status {
+        SpanStatus::Unknown => (STATUS_CODE_UNSET, ""),
+        SpanStatus::Ok => (STATUS_CODE_OK, ""),
+        SpanStatus::Err => (
+            STATUS_CODE_ERROR,
+            s.events.last().map(|e| e.msg.as_ref()).unwrap_or_default(),
+        ),
+    };
+
+    let mut v = json!({
+        "traceId": format!("{:032x}", s.ctx.trace_id.get()),
+        "spanId": format!("{:016x}", s.ctx.span_id.get()),
+        "name": s.name,
+        "kind": SPAN_KIND_SERVER,
+        "startTimeUnixNano": nanos(s.start),
+        "endTimeUnixNano": nanos(s.end.or(s.start)),
+        "attributes": s
+            .metadata
+            .iter()
+            .map(|(k, v)| attribute(k, v))
+            .collect::<Vec<_>>(),
+        "events": s
+            .events
+            .iter()
+            .map(|e| json!({ "timeUnixNano": nanos(Some(e.time)), "name": e.msg }))
+            .collect::<Vec<_>>(),
+        "links": s
+            .ctx
+            .links
+            .iter()
+            .map(|(trace_id, span_id)| json!({
+                "traceId": format!("{:032x}", trace_id.get()),
+                "spanId": format!("{:016x}", span_id.get()),
+            }))
+            .collect::<Vec<_>>(),
+        "status": { "code": code, "message": message },
+    });
+
+    if let Some(parent) = s.ctx.parent_span_id {
+        v["parentSpanId"] = format!("{:016x}", parent.get()).into();
+    }
+
+    v
+}
+
+/// Convert a key / [`MetaValue`] pair into an OTLP `KeyValue`.
+fn attribute(key: &str, value: &MetaValue) -> Value {
+    let value = match value {
+        MetaValue::String(v) => json!({ "stringValue": v }),
+        MetaValue::Float(v) => json!({ "doubleValue": v }),
+        // OTLP JSON encodes 64-bit integers as strings.
+        MetaValue::Int(v) => json!({ "intValue": v.to_string() }),
+        MetaValue::Bool(v) => json!({ "boolValue": v }),
+    };
+
+    json!({ "key": key, "value": value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use iox_time::SystemProvider;
+    use trace::ctx::{SpanContext, SpanId, TraceId};
+    use trace::span::SpanEvent;
+
+    #[test]
+    fn test_span_json() {
+        let mut ctx = SpanContext::new(Arc::new(trace::LogTraceCollector::new()));
+        ctx.trace_id = TraceId::new(0x1234).unwrap();
+        ctx.span_id = SpanId::new(0x42).unwrap();
+        ctx.parent_span_id = Some(SpanId::new(0x24).unwrap());
+
+        let mut span = ctx.child("foo");
+        span.ctx.span_id = SpanId::new(0x43).unwrap();
+        span.start = Some(Utc.timestamp_nanos(100));
+        span.end = Some(Utc.timestamp_nanos(300));
+        span.status = SpanStatus::Err;
+        span.metadata.insert("rows".into(), MetaValue::Int(42));
+        span.events.push(SpanEvent {
+            time: Utc.timestamp_nanos(200),
+            msg: "failed".into(),
+        });
+
+        let got = span_json(&span);
+        assert_eq!(
+            got,
+            json!({
+                "traceId": "00000000000000000000000000001234",
+                "spanId": "0000000000000043",
+                "parentSpanId": "0000000000000042",
+                "name": "foo",
+                "kind": 2,
+                "startTimeUnixNano": "100",
+                "endTimeUnixNano": "300",
+                "attributes": [{ "key": "rows", "value": { "intValue": "42" } }],
+                "events": [{ "timeUnixNano": "200", "name": "failed" }],
+                "links": [],
+                "status": { "code": 2, "message": "failed" },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_make_request() {
+        let exporter = OtlpExporter::new(
+            "bananas".to_string(),
+            "http://127.0.0.1:4318/",
+            Arc::new(SystemProvider::new()),
+            NonZeroU64::new(1).unwrap(),
+        )
+        .unwrap()
+        .with_tags([("env".to_string(), "prod".to_string())]);
+
+        assert_eq!(exporter.url, "http://127.0.0.1:4318/v1/traces");
+
+        let got = exporter.make_request(vec![]);
+        assert_eq!(
+            got["resourceSpans"][0]["resource"]["attributes"],
+            json!([
+                { "key": "service.name", "value": { "stringValue": "bananas" } },
+                { "key": "env", "value": { "stringValue": "prod" } },
+            ])
+        );
+        assert_eq!(got["resourceSpans"][0]["scopeSpans"][0]["spans"], json!([]));
+    }
+}
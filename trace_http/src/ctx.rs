@@ -2,13 +2,15 @@ use std::num::{NonZeroU128, NonZeroU64, ParseIntError};
 use std::str::FromStr;
 use std::sync::Arc;
 
-use http::HeaderMap;
+use http::{HeaderMap, Uri};
 use observability_deps::tracing::*;
 use snafu::Snafu;
 
 use trace::ctx::{SpanContext, SpanId, TraceId};
 use trace::TraceCollector;
 
+use crate::sampler::Sampler;
+
 const B3_FLAGS: &str = "X-B3-Flags";
 const B3_SAMPLED_HEADER: &str = "X-B3-Sampled";
 const B3_TRACE_ID_HEADER: &str = "X-B3-TraceId";
@@ -71,6 +73,8 @@ pub struct TraceHeaderParser {
     jaeger_trace_context_header_name: Option<Arc<str>>,
     /// header that forces sampling
     jaeger_debug_name: Option<Arc<str>>,
+    /// server-side sampling of requests without trace context
+    sampler: Arc<Sampler>,
 }
 
 impl TraceHeaderParser {
@@ -96,6 +100,31 @@ impl TraceHeaderParser {
         self
     }
 
+    /// specify the [`Sampler`] used to start new traces for requests that
+    /// carry no trace context
+    pub fn with_sampler(mut self, sampler: Sampler) -> Self {
+        self.sampler = Arc::new(sampler);
+        self
+    }
+
+    /// Create a new root SpanContext for a request to `uri` that carries no
+    /// trace context, if selected by the configured [`Sampler`]
+    pub fn sample(
+        &self,
+        collector: Option<&Arc<dyn TraceCollector>>,
+        uri: &Uri,
+    ) -> Option<SpanContext> {
+        let collector = collector?;
+        if self.sampler.is_disabled() || !self.sampler.sample(uri) {
+            return None;
+        }
+
+        let ctx = SpanContext::new(Arc::clone(collector));
+        let trace_id = format!("{:x}", ctx.trace_id.get());
+        trace!(%trace_id, %uri, "Sampled new trace rooted at IOx");
+        Some(ctx)
+    }
+
     /// Create a SpanContext for the trace described in the request's
     /// headers, if any
     ///
@@ -17,4 +17,6 @@ use workspace_hack as _;
 mod classify;
 pub mod ctx;
 mod metrics;
+pub mod request_id;
+pub mod sampler;
 pub mod tower;
@@ -0,0 +1,120 @@
+//! Per-request correlation identifiers.
+//!
+//! Every request handled by a [`TraceService`] is assigned a [`RequestId`],
+//! either taken from the [`REQUEST_ID_HEADER`] sent by the client or randomly
+//! generated. The ID is:
+//!
+//! - attached to the request extensions for use by service handlers
+//! - recorded as the `request_id` field of a `tracing` span wrapping the
+//!   request, so all log events emitted while handling it carry the ID
+//! - returned to the client in the [`REQUEST_ID_HEADER`] of the response
+//!   (including error responses)
+//!
+//! [`TraceService`]: crate::tower::TraceService
+
+use std::fmt::Display;
+use std::sync::Arc;
+
+use http::{HeaderMap, HeaderValue};
+use rand::Rng;
+
+/// The header carrying the request ID, both from the client and in the
+/// response.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The maximum length of a client provided request ID.
+///
+/// Longer values are ignored and a new ID generated instead.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// An opaque identifier correlating a single request with the log events and
+/// background work it causes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(Arc<str>);
+
+impl RequestId {
+    /// Generate a new, random request ID.
+    pub fn new() -> Self {
+        let v: u128 = rand::thread_rng().gen();
+        Self(format!("{v:032x}").into())
+    }
+
+    /// Return the ID from the [`REQUEST_ID_HEADER`] in `headers`, or generate
+    /// a new ID if absent or invalid.
+    ///
+    /// A client provided ID is only accepted if it is non-empty, at most 128
+    /// bytes long and consists only of visible ASCII characters.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| is_valid(v))
+            .map(|v| Self(v.into()))
+            .unwrap_or_default()
+    }
+
+    /// Return the string representation of this ID.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return this ID as a [`HeaderValue`].
+    pub(crate) fn header_value(&self) -> HeaderValue {
+        // Both generated and accepted IDs are always visible ASCII.
+        HeaderValue::from_str(&self.0).expect("request ID is a valid header value")
+    }
+}
+
+impl Default for RequestId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+fn is_valid(v: &str) -> bool {
+    !v.is_empty() && v.len() <= MAX_REQUEST_ID_LEN && v.bytes().all(|b| b.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated() {
+        let a = RequestId::new();
+        let b = RequestId::new();
+
+        assert_eq!(a.as_str().len(), 32);
+        assert_ne!(a, b);
+
+        // Generated IDs round-trip through the header
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, a.header_value());
+        assert_eq!(RequestId::from_headers(&headers), a);
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+
+        // Missing header generates a new ID
+        assert_eq!(RequestId::from_headers(&headers).as_str().len(), 32);
+
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("bananas-42"));
+        assert_eq!(RequestId::from_headers(&headers).as_str(), "bananas-42");
+
+        // Invalid values are replaced
+        for bad in ["", "has space", &"a".repeat(MAX_REQUEST_ID_LEN + 1)] {
+            headers.insert(REQUEST_ID_HEADER, HeaderValue::from_str(bad).unwrap());
+            let got = RequestId::from_headers(&headers);
+            assert_ne!(got.as_str(), bad);
+            assert_eq!(got.as_str().len(), 32);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Server-side trace sampling.
+//!
+//! Requests that carry no trace context headers are not traced by default. A
+//! [`Sampler`] allows the server to start new traces for a fraction of these
+//! requests, with the rate selected by the first matching [`SamplingRule`].
+
+use std::str::FromStr;
+
+use http::Uri;
+use rand::Rng;
+
+/// A rule matching requests by namespace and RPC name, sampling matches at
+/// the configured rate.
+///
+/// Rules are written as `<namespace>:<rpc>=<rate>`, where `*` matches any
+/// namespace / RPC and `rate` is a fraction between 0 and 1 inclusive. For
+/// example:
+///
+/// - `*:Delete=1` samples all delete requests
+/// - `*:ReadFilter=0.01` samples 1% of `read_filter` requests
+/// - `my_db:*=0.5` samples half of all requests for the `my_db` namespace
+///
+/// The RPC name is the last path segment of the request (the gRPC method name,
+/// or the final segment of the HTTP API path such as `write`), compared
+/// case-insensitively.
+///
+/// The namespace is only known for HTTP requests that specify it in the query
+/// string (the `db` parameter, or the `org` & `bucket` parameters); a rule
+/// with a specific namespace never matches other requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplingRule {
+    namespace: Option<String>,
+    rpc: Option<String>,
+    rate: f64,
+}
+
+impl SamplingRule {
+    /// Construct a new rule, with [`None`] matching any value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not within 0 and 1 inclusive.
+    pub fn new(namespace: Option<String>, rpc: Option<String>, rate: f64) -> Self {
+        assert!((0.0..=1.0).contains(&rate), "invalid sampling rate");
+        Self {
+            namespace,
+            rpc,
+            rate,
+        }
+    }
+
+    fn matches(&self, namespace: Option<&str>, rpc: &str) -> bool {
+        let namespace_matches = match (&self.namespace, namespace) {
+            (None, _) => true,
+            (Some(want), Some(got)) => want == got,
+            (Some(_), None) => false,
+        };
+        let rpc_matches = self
+            .rpc
+            .as_ref()
+            .map(|want| want.eq_ignore_ascii_case(rpc))
+            .unwrap_or(true);
+
+        namespace_matches && rpc_matches
+    }
+}
+
+impl FromStr for SamplingRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("invalid sampling rule '{s}', expected <namespace>:<rpc>=<rate>");
+
+        let (selector, rate) = s.trim().split_once('=').ok_or_else(err)?;
+        let (namespace, rpc) = selector.split_once(':').ok_or_else(err)?;
+
+        let rate = rate.parse::<f64>().map_err(|_| err())?;
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(format!(
+                "invalid sampling rate in '{s}', must be between 0 and 1"
+            ));
+        }
+
+        let wildcard = |v: &str| match v {
+            "" | "*" => None,
+            v => Some(v.to_string()),
+        };
+
+        Ok(Self::new(wildcard(namespace), wildcard(rpc), rate))
+    }
+}
+
+/// Decides whether to start a new trace for a request without trace context.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sampler {
+    rules: Vec<SamplingRule>,
+    default_rate: f64,
+}
+
+impl Sampler {
+    /// Construct a [`Sampler`] applying the first matching rule in `rules`,
+    /// or `default_rate` if none match.
+    pub fn new(rules: Vec<SamplingRule>, default_rate: f64) -> Self {
+        Self {
+            rules,
+            default_rate,
+        }
+    }
+
+    /// Returns true if this [`Sampler`] never samples any request.
+    pub fn is_disabled(&self) -> bool {
+        self.default_rate <= 0.0 && self.rules.iter().all(|r| r.rate <= 0.0)
+    }
+
+    /// Return the sampling rate that applies to a request for `uri`.
+    pub fn rate(&self, uri: &Uri) -> f64 {
+        let rpc = uri.path().rsplit('/').next().unwrap_or_default();
+        let namespace = uri.query().and_then(namespace_from_query);
+
+        self.rules
+            .iter()
+            .find(|r| r.matches(namespace.as_deref(), rpc))
+            .map(|r| r.rate)
+            .unwrap_or(self.default_rate)
+    }
+
+    /// Make a sampling decision for a request for `uri`.
+    pub fn sample(&self, uri: &Uri) -> bool {
+        match self.rate(uri) {
+            r if r <= 0.0 => false,
+            r if r >= 1.0 => true,
+            r => rand::thread_rng().gen_bool(r),
+        }
+    }
+}
+
+/// Extract the namespace name from the query string of a write / query HTTP
+/// request.
+fn namespace_from_query(query: &str) -> Option<String> {
+    let mut org = None;
+    let mut bucket = None;
+
+    for (k, v) in query.split('&').filter_map(|kv| kv.split_once('=')) {
+        match k {
+            "db" => return Some(v.to_string()),
+            "org" => org = Some(v),
+            "bucket" => bucket = Some(v),
+            _ => {}
+        }
+    }
+
+    Some(format!("{}_{}", org?, bucket?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule() {
+        assert_eq!(
+            "*:Delete=1".parse::<SamplingRule>().unwrap(),
+            SamplingRule::new(None, Some("Delete".to_string()), 1.0)
+        );
+        assert_eq!(
+            "my_db:*=0.5".parse::<SamplingRule>().unwrap(),
+            SamplingRule::new(Some("my_db".to_string()), None, 0.5)
+        );
+
+        for bad in ["bananas", "*=1", "*:*", "*:*=bananas", "*:*=1.5", "*:*=-1"] {
+            assert!(bad.parse::<SamplingRule>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_rate() {
+        let sampler = Sampler::new(
+            vec![
+                "*:Delete=1".parse().unwrap(),
+                "my_db:write=0.5".parse().unwrap(),
+                "org_bucket:*=0.25".parse().unwrap(),
+                "*:ReadFilter=0.01".parse().unwrap(),
+            ],
+            0.001,
+        );
+
+        let rate = |uri: &str| sampler.rate(&uri.parse::<Uri>().unwrap());
+
+        assert_eq!(rate("/influxdata.iox.delete.v1.DeleteService/Delete"), 1.0);
+        assert_eq!(
+            rate("/influxdata.platform.storage.Storage/ReadFilter"),
+            0.01
+        );
+        assert_eq!(rate("/write?db=my_db&precision=s"), 0.5);
+        assert_eq!(rate("/write?db=other"), 0.001);
+        assert_eq!(rate("/api/v2/write?org=org&bucket=bucket"), 0.25);
+        assert_eq!(rate("/api/v2/query?org=org&bucket=bucket"), 0.25);
+        assert_eq!(rate("/api/v2/write?org=org"), 0.001);
+
+        assert!(!sampler.is_disabled());
+        assert!(Sampler::default().is_disabled());
+        assert!(!Sampler::default().sample(&"/write".parse().unwrap()));
+    }
+}
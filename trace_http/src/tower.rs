@@ -24,12 +24,13 @@ use http_body::SizeHint;
 use pin_project::{pin_project, pinned_drop};
 use tower::{Layer, Service};
 
-use observability_deps::tracing::{error, warn};
+use observability_deps::tracing::{error, info_span, instrument::Instrumented, warn, Instrument};
 use trace::{span::SpanRecorder, TraceCollector};
 
 use crate::classify::{classify_headers, classify_response, Classification};
 use crate::ctx::{RequestLogContext, RequestLogContextExt, TraceHeaderParser};
 use crate::metrics::{MetricsCollection, MetricsRecorder};
+use crate::request_id::{RequestId, REQUEST_ID_HEADER};
 
 /// `TraceLayer` implements `tower::Layer` and can be used to decorate a
 /// `tower::Service` to collect information about requests flowing through it
@@ -37,6 +38,7 @@ use crate::metrics::{MetricsCollection, MetricsRecorder};
 /// Including:
 ///
 /// - Extracting distributed trace context and attaching span context
+/// - Assigning a [`RequestId`] to correlate log events with the request
 /// - Collecting count and duration metrics - [RED metrics][1]
 ///
 /// [1]: https://www.weave.works/blog/the-red-method-key-metrics-for-microservices-architecture/
@@ -97,7 +99,7 @@ where
 {
     type Response = Response<TracedBody<ResBody>>;
     type Error = S::Error;
-    type Future = TracedFuture<S::Future>;
+    type Future = TracedFuture<Instrumented<S::Future>>;
 
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         self.service.poll_ready(cx)
@@ -106,6 +108,12 @@ where
     fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
         let metrics_recorder = Some(self.metrics.recorder(&request));
 
+        // Assign the request ID, and record it in a span covering all log
+        // events emitted while handling this request.
+        let request_id = RequestId::from_headers(request.headers());
+        request.extensions_mut().insert(request_id.clone());
+        let log_span = info_span!("request", request_id = %request_id);
+
         let request_ctx = match self
             .trace_header_parser
             .parse(self.collector.as_ref(), request.headers())
@@ -117,7 +125,14 @@ where
 
                 Some(ctx)
             }
-            Ok(None) => None,
+            Ok(None) => self
+                .trace_header_parser
+                .sample(self.collector.as_ref(), request.uri())
+                .map(|ctx| {
+                    let ctx = RequestLogContext::new(ctx);
+                    request.extensions_mut().insert(ctx.clone());
+                    ctx
+                }),
             Err(e) => {
                 error!(%e, "error extracting trace context from request");
                 None
@@ -137,12 +152,15 @@ where
             })
         });
 
+        let inner = log_span.in_scope(|| self.service.call(request));
+
         TracedFuture {
             request_ctx,
+            request_id,
             metrics_recorder,
             span_recorder: SpanRecorder::new(span),
             was_ready: false,
-            inner: self.service.call(request),
+            inner: inner.instrument(log_span),
         }
     }
 }
@@ -153,6 +171,7 @@ where
 #[derive(Debug)]
 pub struct TracedFuture<F> {
     request_ctx: Option<RequestLogContext>,
+    request_id: RequestId,
     span_recorder: SpanRecorder,
     metrics_recorder: Option<MetricsRecorder>,
     was_ready: bool,
@@ -167,6 +186,7 @@ impl<F> PinnedDrop for TracedFuture<F> {
             let trace = self.request_ctx.format_jaeger();
             warn!(
                 %trace,
+                request_id=%self.request_id,
                 when="before returning headers",
                 "request cancelled",
             );
@@ -214,6 +234,7 @@ where
                 // add trace-id header to the response, if we have one
                 let projected = self.as_mut().project();
                 let request_ctx = projected.request_ctx.take();
+                let request_id = projected.request_id.clone();
                 let span_recorder = projected.span_recorder.take();
                 if let Some(trace_id) = span_recorder.span().map(|span| span.ctx.trace_id) {
                     // format as hex
@@ -221,8 +242,14 @@ where
                     response.headers_mut().insert("trace-id", trace_id);
                 }
 
+                // and always add the request ID
+                response
+                    .headers_mut()
+                    .insert(REQUEST_ID_HEADER, request_id.header_value());
+
                 Poll::Ready(Ok(response.map(|body| TracedBody {
                     request_ctx,
+                    request_id,
                     span_recorder,
                     was_done_data: AtomicBool::new(false),
                     was_ready_trailers: AtomicBool::new(false),
@@ -240,6 +267,7 @@ where
 #[derive(Debug)]
 pub struct TracedBody<B> {
     request_ctx: Option<RequestLogContext>,
+    request_id: RequestId,
     span_recorder: SpanRecorder,
     metrics_recorder: MetricsRecorder,
     was_done_data: AtomicBool,
@@ -255,6 +283,7 @@ impl<B> PinnedDrop for TracedBody<B> {
             let trace = self.request_ctx.format_jaeger();
             warn!(
                 %trace,
+                request_id=%self.request_id,
                 when="before fully returning body data",
                 "request cancelled",
             );
@@ -262,6 +291,7 @@ impl<B> PinnedDrop for TracedBody<B> {
             let trace = self.request_ctx.format_jaeger();
             warn!(
                 %trace,
+                request_id=%self.request_id,
                 when="before returning trailers",
                 "request cancelled",
             );
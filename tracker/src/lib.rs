@@ -17,9 +17,11 @@ use workspace_hack as _;
 mod async_semaphore;
 mod disk_metric;
 mod lock;
+mod memory;
 mod task;
 
 pub use async_semaphore::*;
 pub use disk_metric::*;
 pub use lock::*;
+pub use memory::*;
 pub use task::*;
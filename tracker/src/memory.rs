@@ -0,0 +1,416 @@
+//! Attribution of heap allocations to IOx subsystems.
+//!
+//! Each thread has a current [`MemorySubsystem`], to which all heap
+//! allocations made by the thread are attributed while the
+//! [`TrackingAllocator`] is installed as the global allocator. The current
+//! subsystem can be set for the lifetime of a thread with
+//! [`set_thread_subsystem()`], for a synchronous scope with
+//! [`enter_subsystem()`], or for every poll of a future with
+//! [`in_subsystem()`].
+//!
+//! An allocation remains attributed to the subsystem that allocated it until
+//! it is freed, regardless of which subsystem frees (or reallocates) it.
+
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    any::Any,
+    cell::Cell,
+    future::Future,
+    marker::PhantomData,
+    mem::size_of,
+    pin::Pin,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+
+use metric::{Attributes, MetricKind, Observation, Reporter};
+use pin_project::pin_project;
+
+/// A subsystem heap allocations are attributed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemorySubsystem {
+    /// Allocations made outside of any other subsystem.
+    Unattributed = 0,
+
+    /// Buffering of writes in memory before they are persisted.
+    MutableBuffer = 1,
+
+    /// Execution of query (and compaction / persist) plans.
+    QueryExec = 2,
+
+    /// Requests to, and caching of, the catalog.
+    Catalog = 3,
+}
+
+impl MemorySubsystem {
+    /// All subsystems, in discriminant order.
+    pub const ALL: [Self; 4] = [
+        Self::Unattributed,
+        Self::MutableBuffer,
+        Self::QueryExec,
+        Self::Catalog,
+    ];
+
+    /// The name of this subsystem.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Unattributed => "unattributed",
+            Self::MutableBuffer => "mutable_buffer",
+            Self::QueryExec => "query_exec",
+            Self::Catalog => "catalog",
+        }
+    }
+
+    fn from_tag(tag: usize) -> Self {
+        Self::ALL.get(tag).copied().unwrap_or(Self::Unattributed)
+    }
+}
+
+/// The number of bytes currently allocated per subsystem, indexed by
+/// discriminant.
+static ALLOCATED: [AtomicUsize; MemorySubsystem::ALL.len()] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+
+/// Set once the first allocation is made by a [`TrackingAllocator`].
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CURRENT: Cell<MemorySubsystem> = const { Cell::new(MemorySubsystem::Unattributed) };
+}
+
+/// Returns the subsystem allocations made by this thread are currently
+/// attributed to.
+pub fn current_subsystem() -> MemorySubsystem {
+    // The thread local may be inaccessible while the thread is being torn
+    // down, but allocations can still happen.
+    CURRENT
+        .try_with(Cell::get)
+        .unwrap_or(MemorySubsystem::Unattributed)
+}
+
+/// Attribute all subsequent allocations made by this thread to `subsystem`.
+pub fn set_thread_subsystem(subsystem: MemorySubsystem) {
+    let _ = CURRENT.try_with(|v| v.set(subsystem));
+}
+
+/// Attribute allocations made by this thread to `subsystem` until the
+/// returned guard is dropped.
+///
+/// The guard must not be held across an `await` point, use
+/// [`in_subsystem()`] for async code instead.
+pub fn enter_subsystem(subsystem: MemorySubsystem) -> SubsystemGuard {
+    SubsystemGuard {
+        previous: CURRENT.try_with(|v| v.replace(subsystem)).ok(),
+        _not_send: PhantomData,
+    }
+}
+
+/// A guard returned by [`enter_subsystem()`], restoring the previously
+/// current subsystem when dropped.
+#[derive(Debug)]
+#[must_use = "the subsystem is only entered while the guard is held"]
+pub struct SubsystemGuard {
+    previous: Option<MemorySubsystem>,
+
+    /// The guard is tied to the thread it was created on.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for SubsystemGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            set_thread_subsystem(previous);
+        }
+    }
+}
+
+/// Attribute allocations made while polling `fut` to `subsystem`.
+pub fn in_subsystem<F>(subsystem: MemorySubsystem, fut: F) -> InSubsystem<F>
+where
+    F: Future,
+{
+    InSubsystem {
+        subsystem,
+        inner: fut,
+    }
+}
+
+/// A future returned by [`in_subsystem()`].
+#[pin_project]
+#[derive(Debug)]
+pub struct InSubsystem<F> {
+    subsystem: MemorySubsystem,
+    #[pin]
+    inner: F,
+}
+
+impl<F> Future for InSubsystem<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = enter_subsystem(*this.subsystem);
+        this.inner.poll(cx)
+    }
+}
+
+/// Returns the number of bytes currently allocated by each subsystem, or
+/// [`None`] if no [`TrackingAllocator`] is installed.
+pub fn allocated_bytes() -> Option<[(MemorySubsystem, usize); MemorySubsystem::ALL.len()]> {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    Some(MemorySubsystem::ALL.map(|s| (s, ALLOCATED[s as usize].load(Ordering::Relaxed))))
+}
+
+/// Returns the number of bytes currently allocated by `subsystem`, or
+/// [`None`] if no [`TrackingAllocator`] is installed.
+pub fn subsystem_allocated_bytes(subsystem: MemorySubsystem) -> Option<usize> {
+    INSTALLED
+        .load(Ordering::Relaxed)
+        .then(|| ALLOCATED[subsystem as usize].load(Ordering::Relaxed))
+}
+
+/// The size of the tag recording the subsystem of an allocation.
+const TAG_SIZE: usize = size_of::<usize>();
+
+/// Returns the layout of the allocation made by the inner allocator to hold
+/// `layout` and its tag, and the offset of the user data within it.
+///
+/// The tag is stored in the `usize` immediately preceding the user data.
+fn tagged_layout(layout: Layout) -> Option<(Layout, usize)> {
+    // Both the alignment and the tag size are powers of two, so the offset is
+    // a multiple of both.
+    let offset = layout.align().max(TAG_SIZE);
+    let size = layout.size().checked_add(offset)?;
+    let layout = Layout::from_size_align(size, offset).ok()?;
+    Some((layout, offset))
+}
+
+/// A [`GlobalAlloc`] wrapping another allocator, attributing the bytes
+/// allocated to the [current subsystem](current_subsystem) of the allocating
+/// thread.
+///
+/// Every allocation is prefixed with a tag recording its subsystem, so it can
+/// be attributed correctly when freed. This costs at least a `usize` per
+/// allocation, and the shared counters add contention to every allocation,
+/// so this allocator is intended for diagnosing memory usage rather than for
+/// general use.
+#[derive(Debug)]
+pub struct TrackingAllocator<A> {
+    inner: A,
+}
+
+impl<A> TrackingAllocator<A> {
+    /// Wrap `inner`, tracking the allocations made through it.
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+impl<A> TrackingAllocator<A>
+where
+    A: GlobalAlloc,
+{
+    /// Record the allocation of `size` bytes by the current subsystem, and tag
+    /// the allocation at `base`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be a non-null allocation of a layout returned by
+    /// [`tagged_layout()`] with `offset`.
+    unsafe fn tag(base: *mut u8, offset: usize, size: usize) -> *mut u8 {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            INSTALLED.store(true, Ordering::Relaxed);
+        }
+
+        let subsystem = current_subsystem();
+        ALLOCATED[subsystem as usize].fetch_add(size, Ordering::Relaxed);
+
+        let ptr = base.add(offset);
+        (ptr as *mut usize).sub(1).write(subsystem as usize);
+        ptr
+    }
+
+    /// Read the subsystem tag of the user data at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`Self::tag()`].
+    unsafe fn read_tag(ptr: *mut u8) -> MemorySubsystem {
+        MemorySubsystem::from_tag((ptr as *mut usize).sub(1).read())
+    }
+}
+
+unsafe impl<A> GlobalAlloc for TrackingAllocator<A>
+where
+    A: GlobalAlloc,
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some((tagged, offset)) = tagged_layout(layout) else {
+            return std::ptr::null_mut();
+        };
+
+        let base = self.inner.alloc(tagged);
+        if base.is_null() {
+            return base;
+        }
+        Self::tag(base, offset, layout.size())
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let Some((tagged, offset)) = tagged_layout(layout) else {
+            return std::ptr::null_mut();
+        };
+
+        let base = self.inner.alloc_zeroed(tagged);
+        if base.is_null() {
+            return base;
+        }
+        Self::tag(base, offset, layout.size())
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let (tagged, offset) = tagged_layout(layout).expect("layout was allocated");
+
+        let subsystem = Self::read_tag(ptr);
+        ALLOCATED[subsystem as usize].fetch_sub(layout.size(), Ordering::Relaxed);
+
+        self.inner.dealloc(ptr.sub(offset), tagged);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let (tagged, offset) = tagged_layout(layout).expect("layout was allocated");
+        let Some(new_tagged_size) = new_size.checked_add(offset) else {
+            return std::ptr::null_mut();
+        };
+
+        // The tag is copied along with the user data, so the allocation stays
+        // attributed to the subsystem that made it.
+        let subsystem = Self::read_tag(ptr);
+
+        let base = self.inner.realloc(ptr.sub(offset), tagged, new_tagged_size);
+        if base.is_null() {
+            return base;
+        }
+
+        let allocated = &ALLOCATED[subsystem as usize];
+        if new_size >= layout.size() {
+            allocated.fetch_add(new_size - layout.size(), Ordering::Relaxed);
+        } else {
+            allocated.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+        }
+
+        base.add(offset)
+    }
+}
+
+/// A `metric::Instrument` that reports the heap memory allocated by each
+/// [`MemorySubsystem`], if a [`TrackingAllocator`] is installed:
+///
+/// - a u64 gauge called "heap_allocated_bytes"
+#[derive(Debug, Clone)]
+pub struct HeapTrackingMetrics {
+    attributes: Vec<Attributes>,
+}
+
+impl HeapTrackingMetrics {
+    pub fn new() -> Self {
+        Self {
+            attributes: MemorySubsystem::ALL
+                .iter()
+                .map(|s| Attributes::from(&[("subsystem", s.name())]))
+                .collect(),
+        }
+    }
+}
+
+impl Default for HeapTrackingMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl metric::Instrument for HeapTrackingMetrics {
+    fn report(&self, reporter: &mut dyn Reporter) {
+        let Some(allocated) = allocated_bytes() else {
+            return;
+        };
+
+        reporter.start_metric(
+            "heap_allocated_bytes",
+            "heap memory currently allocated, by subsystem",
+            MetricKind::U64Gauge,
+        );
+        for (attributes, (_, bytes)) in self.attributes.iter().zip(allocated) {
+            reporter.report_observation(attributes, Observation::U64Gauge(bytes as u64));
+        }
+        reporter.finish_metric();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::System;
+
+    use super::*;
+
+    // A tracking allocator not installed as the global allocator, so the
+    // tests observe only their own allocations.
+    static ALLOC: TrackingAllocator<System> = TrackingAllocator::new(System);
+
+    #[test]
+    fn test_attribution() {
+        let layout = Layout::from_size_align(100, 64).unwrap();
+        let before = ALLOCATED[MemorySubsystem::Catalog as usize].load(Ordering::SeqCst);
+
+        let ptr = {
+            let _guard = enter_subsystem(MemorySubsystem::Catalog);
+            unsafe { ALLOC.alloc(layout) }
+        };
+        assert_eq!(ptr as usize % 64, 0);
+        assert_eq!(current_subsystem(), MemorySubsystem::Unattributed);
+        assert_eq!(
+            subsystem_allocated_bytes(MemorySubsystem::Catalog),
+            Some(before + 100)
+        );
+
+        // Growing the allocation outside of the subsystem is still attributed
+        // to it.
+        let ptr = unsafe { ALLOC.realloc(ptr, layout, 150) };
+        assert_eq!(ptr as usize % 64, 0);
+        assert_eq!(
+            subsystem_allocated_bytes(MemorySubsystem::Catalog),
+            Some(before + 150)
+        );
+
+        unsafe { ALLOC.dealloc(ptr, Layout::from_size_align(150, 64).unwrap()) };
+        assert_eq!(
+            subsystem_allocated_bytes(MemorySubsystem::Catalog),
+            Some(before)
+        );
+    }
+
+    #[test]
+    fn test_nested_scopes() {
+        set_thread_subsystem(MemorySubsystem::QueryExec);
+        {
+            let _guard = enter_subsystem(MemorySubsystem::MutableBuffer);
+            assert_eq!(current_subsystem(), MemorySubsystem::MutableBuffer);
+        }
+        assert_eq!(current_subsystem(), MemorySubsystem::QueryExec);
+        set_thread_subsystem(MemorySubsystem::Unattributed);
+    }
+}
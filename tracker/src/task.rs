@@ -118,6 +118,9 @@ struct TrackerState {
     err_futures: AtomicUsize,
     cancelled_futures: AtomicUsize,
 
+    items_done: AtomicUsize,
+    items_total: AtomicUsize,
+
     notify: Notify,
 }
 
@@ -171,6 +174,34 @@ impl TrackerState {
     fn is_complete(&self) -> bool {
         matches!(self.get_status(), TaskStatus::Complete { .. })
     }
+
+    fn get_progress(&self) -> TaskProgress {
+        let total = self.items_total.load(Ordering::Relaxed);
+        TaskProgress {
+            done: self.items_done.load(Ordering::Relaxed),
+            total: (total != 0).then_some(total),
+        }
+    }
+}
+
+/// The progress a task has reported through its [`ProgressReporter`]
+///
+/// Progress is best-effort and independent of [`TaskStatus`], a task
+/// that never reports any progress is still tracked as normal
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct TaskProgress {
+    /// The number of items of work completed
+    pub done: usize,
+    /// The total number of items of work, if known
+    pub total: Option<usize>,
+}
+
+impl TaskProgress {
+    /// Returns the percentage of work completed, if the total is known
+    pub fn percent(&self) -> Option<f64> {
+        self.total
+            .map(|total| (self.done.min(total) as f64 / total as f64) * 100.0)
+    }
 }
 
 /// Returns a summary of the task execution
@@ -439,6 +470,11 @@ where
         self.state.get_status()
     }
 
+    /// Gets the progress reported by the task
+    pub fn progress(&self) -> TaskProgress {
+        self.state.get_progress()
+    }
+
     /// Returns the instant the tracker was created
     pub fn start_time(&self) -> Time {
         self.state.start_time
@@ -511,6 +547,8 @@ impl TaskRegistration {
             ok_futures: AtomicUsize::new(0),
             err_futures: AtomicUsize::new(0),
             cancelled_futures: AtomicUsize::new(0),
+            items_done: AtomicUsize::new(0),
+            items_total: AtomicUsize::new(0),
             notify: Notify::new(),
         });
 
@@ -524,6 +562,16 @@ impl TaskRegistration {
     {
         TaskTracker::new(TaskId(0), &self, metadata)
     }
+
+    /// Returns a [`ProgressReporter`] for the task
+    ///
+    /// Unlike the registration itself, holding a reporter does not prevent
+    /// the task from being considered complete
+    pub fn progress_reporter(&self) -> ProgressReporter {
+        ProgressReporter {
+            state: Arc::clone(&self.state),
+        }
+    }
 }
 
 impl Drop for TaskRegistration {
@@ -549,6 +597,34 @@ impl Drop for TaskRegistration {
     }
 }
 
+/// A handle used by a task to report how much of its work is done
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    state: Arc<TrackerState>,
+}
+
+impl ProgressReporter {
+    /// Add `n` items to the total amount of work
+    ///
+    /// Tasks that discover their work incrementally may call this many times
+    pub fn add_total(&self, n: usize) {
+        self.state.items_total.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Record that `n` more items of work are done
+    pub fn add_done(&self, n: usize) {
+        self.state.items_done.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Returns if the task has been cancelled
+    ///
+    /// Tasks that must not be interrupted at arbitrary yield points can
+    /// check this between units of work instead
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancel_token.is_cancelled()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -1099,6 +1175,39 @@ mod tests {
         assert_eq!(poll, Poll::Ready(()));
     }
 
+    #[tokio::test]
+    async fn test_progress() {
+        let mut registry = test_registry();
+        let (tracker, registration) = registry.register(());
+        let reporter = registration.progress_reporter();
+
+        assert_eq!(tracker.progress(), TaskProgress::default());
+        assert_eq!(tracker.progress().percent(), None);
+
+        reporter.add_total(4);
+        reporter.add_done(1);
+        assert_eq!(
+            tracker.progress(),
+            TaskProgress {
+                done: 1,
+                total: Some(4)
+            }
+        );
+        assert_eq!(tracker.progress().percent(), Some(25.0));
+
+        // holding a reporter does not keep the task alive
+        tokio::spawn(ready_ok().track(registration));
+        tracker.join().await;
+        assert!(tracker.is_complete());
+
+        reporter.add_done(3);
+        assert_eq!(tracker.progress().percent(), Some(100.0));
+
+        assert!(!reporter.is_cancelled());
+        tracker.cancel();
+        assert!(reporter.is_cancelled());
+    }
+
     #[test]
     fn display() {
         let state = TaskStatus::Creating;
@@ -17,8 +17,10 @@
 #[cfg(feature = "clap")]
 pub mod cli;
 pub mod config;
+pub mod reload;
 
 pub use config::*;
+pub use reload::{log_filter_handle, LogFilterHandle};
 
 // Re-export tracing_subscriber
 pub use tracing_subscriber;
@@ -199,9 +201,12 @@ where
 
     /// Returns a [`Layer`] that emits logs as specified by the configuration of
     /// `self`.
+    ///
+    /// The log filter of the first layer built in a process can be changed at
+    /// runtime through the handle returned by [`log_filter_handle()`].
     pub fn build<S>(self) -> Result<impl Layer<S> + 'static>
     where
-        S: Subscriber,
+        S: Subscriber + 'static,
         for<'a> S: LookupSpan<'a>,
     {
         let log_writer = self.make_writer;
@@ -211,6 +216,12 @@ where
 
         let log_filter = self.log_filter.unwrap_or(self.default_log_filter);
 
+        let default_filter = log_filter.to_string();
+        let (log_filter, reload_handle) = tracing_subscriber::reload::Layer::new(log_filter);
+        reload::register(LogFilterHandle::new(default_filter, move |filter| {
+            reload_handle.reload(filter).map_err(|e| e.to_string())
+        }));
+
         let res: Box<dyn Layer<S> + Send + Sync> = match log_format {
             LogFormat::Full => Box::new(
                 log_filter.and_then(
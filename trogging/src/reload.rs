@@ -0,0 +1,341 @@
+//! Runtime adjustment of the log filter.
+//!
+//! The log filter of the layer built by [`Builder::build()`] can be replaced
+//! at runtime through the [`LogFilterHandle`] returned by
+//! [`log_filter_handle()`]. An override can optionally expire, after which the
+//! filter the process was started with is restored.
+//!
+//! [`Builder::build()`]: crate::Builder::build
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, OnceLock},
+    time::{Duration, SystemTime},
+};
+
+use observability_deps::tracing::info;
+use parking_lot::Mutex;
+use thiserror::Error;
+use tokio::{runtime::Handle, task::JoinHandle};
+use tracing_subscriber::EnvFilter;
+
+/// Errors changing the log filter.
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    #[error("invalid log filter: {0}")]
+    InvalidFilter(#[from] tracing_subscriber::filter::ParseError),
+
+    #[error("cannot reload log filter: {0}")]
+    Reload(String),
+
+    #[error("log filter expiry of {0:?} is out of range")]
+    InvalidExpiry(Duration),
+
+    #[error("log filter expiry requires a tokio runtime")]
+    NoRuntime,
+}
+
+type ReloadFn = dyn Fn(EnvFilter) -> Result<(), String> + Send + Sync;
+
+/// The state of the log filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilterState {
+    /// The directives of the active filter.
+    pub filter: String,
+
+    /// The directives of the filter the process was started with, restored
+    /// when an override expires or is reset.
+    pub default_filter: String,
+
+    /// When the active override expires, if it does.
+    pub expires_at: Option<SystemTime>,
+}
+
+#[derive(Debug)]
+struct State {
+    current: String,
+    expires_at: Option<SystemTime>,
+
+    /// Incremented on each change, to identify stale expiry timers.
+    generation: u64,
+
+    /// The task restoring the default filter once the override expires.
+    ///
+    /// Aborted when the filter is changed again.
+    expiry_task: Option<JoinHandle<()>>,
+}
+
+impl State {
+    fn cancel_expiry(&mut self) {
+        if let Some(task) = self.expiry_task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// A handle to change the filter of the installed log layer.
+#[derive(Clone)]
+pub struct LogFilterHandle {
+    reload: Arc<ReloadFn>,
+    default_filter: Arc<str>,
+    state: Arc<Mutex<State>>,
+}
+
+impl Debug for LogFilterHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogFilterHandle")
+            .field("default_filter", &self.default_filter)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LogFilterHandle {
+    /// Construct a handle applying new filters with `reload`, restoring
+    /// `default_filter` when reset.
+    ///
+    /// Only needed for log layers not built with [`Builder`].
+    ///
+    /// [`Builder`]: crate::Builder
+    pub fn new(
+        default_filter: String,
+        reload: impl Fn(EnvFilter) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            reload: Arc::new(reload),
+            state: Arc::new(Mutex::new(State {
+                current: default_filter.clone(),
+                expires_at: None,
+                generation: 0,
+                expiry_task: None,
+            })),
+            default_filter: default_filter.into(),
+        }
+    }
+
+    /// Return the current filter state.
+    pub fn state(&self) -> LogFilterState {
+        let state = self.state.lock();
+        LogFilterState {
+            filter: state.current.clone(),
+            default_filter: self.default_filter.to_string(),
+            expires_at: state.expires_at,
+        }
+    }
+
+    /// Replace the log filter with `directives` (in the same syntax as the
+    /// `--log-filter` option).
+    ///
+    /// If `expiry` is specified, the default filter is restored after it
+    /// elapses, unless the filter is changed again in the meantime. The
+    /// expiry is timed by a task on the current tokio runtime.
+    ///
+    /// The filter is left unchanged if any argument is invalid.
+    pub fn set(&self, directives: &str, expiry: Option<Duration>) -> Result<(), ReloadError> {
+        let filter = EnvFilter::try_new(directives)?;
+        let expires_at = expiry
+            .map(|d| {
+                SystemTime::now()
+                    .checked_add(d)
+                    .ok_or(ReloadError::InvalidExpiry(d))
+            })
+            .transpose()?;
+        let runtime = match expiry {
+            Some(_) => Some(Handle::try_current().map_err(|_| ReloadError::NoRuntime)?),
+            None => None,
+        };
+
+        let mut state = self.state.lock();
+        (self.reload)(filter).map_err(ReloadError::Reload)?;
+
+        state.current = directives.to_string();
+        state.expires_at = expires_at;
+        state.generation += 1;
+
+        // Replace the timer of any earlier override.
+        state.cancel_expiry();
+        if let Some((expiry, runtime)) = expiry.zip(runtime) {
+            let handle = self.clone();
+            let generation = state.generation;
+            state.expiry_task = Some(runtime.spawn(async move {
+                tokio::time::sleep(expiry).await;
+                handle.expire(generation);
+            }));
+        }
+        drop(state);
+
+        info!(filter=%directives, ?expiry, "log filter changed");
+
+        Ok(())
+    }
+
+    /// Restore the filter the process was started with.
+    pub fn reset(&self) -> Result<(), ReloadError> {
+        self.restore(None)
+    }
+
+    fn expire(&self, generation: u64) {
+        match self.restore(Some(generation)) {
+            Ok(()) => {}
+            Err(e) => info!(%e, "failed to restore expired log filter"),
+        }
+    }
+
+    /// Restore the default filter, if `generation` is [`None`] or matches the
+    /// current generation.
+    fn restore(&self, generation: Option<u64>) -> Result<(), ReloadError> {
+        let mut state = self.state.lock();
+        if generation.is_some_and(|g| g != state.generation) {
+            // The filter was changed since the expiry was scheduled.
+            return Ok(());
+        }
+
+        let filter = EnvFilter::try_new(self.default_filter.as_ref())?;
+        (self.reload)(filter).map_err(ReloadError::Reload)?;
+
+        state.current = self.default_filter.to_string();
+        state.expires_at = None;
+        state.generation += 1;
+        if generation.is_none() {
+            // Expiry tasks restore the filter themselves and must not abort
+            // themselves.
+            state.cancel_expiry();
+        } else {
+            state.expiry_task = None;
+        }
+        drop(state);
+
+        info!(filter=%self.default_filter, "log filter restored");
+
+        Ok(())
+    }
+}
+
+static LOG_FILTER_HANDLE: OnceLock<LogFilterHandle> = OnceLock::new();
+
+/// Register `handle` as the process-wide log filter handle, if none is
+/// registered yet.
+pub(crate) fn register(handle: LogFilterHandle) {
+    let _ = LOG_FILTER_HANDLE.set(handle);
+}
+
+/// Return the handle to change the filter of the log layer, if one was built.
+pub fn log_filter_handle() -> Option<LogFilterHandle> {
+    LOG_FILTER_HANDLE.get().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_handle() -> (LogFilterHandle, Arc<Mutex<Vec<String>>>) {
+        let calls = Arc::new(Mutex::new(vec![]));
+        let captured = Arc::clone(&calls);
+        let handle = LogFilterHandle::new("info".to_string(), move |f| {
+            captured.lock().push(f.to_string());
+            Ok(())
+        });
+        (handle, calls)
+    }
+
+    #[test]
+    fn test_set_reset() {
+        let (handle, calls) = mock_handle();
+
+        assert_eq!(handle.state().filter, "info");
+
+        handle.set("debug,h2=info", None).unwrap();
+        let state = handle.state();
+        assert_eq!(state.filter, "debug,h2=info");
+        assert_eq!(state.default_filter, "info");
+        assert_eq!(state.expires_at, None);
+
+        handle.reset().unwrap();
+        assert_eq!(handle.state().filter, "info");
+
+        assert_eq!(calls.lock().len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_filter() {
+        let (handle, calls) = mock_handle();
+
+        let err = handle.set("info,[[[", None).unwrap_err();
+        assert!(matches!(err, ReloadError::InvalidFilter(_)));
+        assert_eq!(handle.state().filter, "info");
+        assert!(calls.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expiry() {
+        let (handle, _calls) = mock_handle();
+
+        handle
+            .set("debug", Some(Duration::from_millis(10)))
+            .unwrap();
+        assert!(handle.state().expires_at.is_some());
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while handle.state().filter != "info" {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "filter did not expire"
+            );
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(handle.state().expires_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_expiry_replaced() {
+        let (handle, _calls) = mock_handle();
+
+        handle
+            .set("debug", Some(Duration::from_millis(10)))
+            .unwrap();
+        handle.set("trace", None).unwrap();
+
+        // The timer of the first change was cancelled by the second.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(handle.state().filter, "trace");
+        assert!(handle.state.lock().expiry_task.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expiry_out_of_range() {
+        let (handle, calls) = mock_handle();
+
+        let err = handle
+            .set("debug", Some(Duration::from_secs(u64::MAX)))
+            .unwrap_err();
+        assert!(matches!(err, ReloadError::InvalidExpiry(_)));
+        assert_eq!(handle.state().filter, "info");
+        assert!(calls.lock().is_empty());
+    }
+
+    #[test]
+    fn test_expiry_without_runtime() {
+        let (handle, calls) = mock_handle();
+
+        let err = handle
+            .set("debug", Some(Duration::from_secs(1)))
+            .unwrap_err();
+        assert!(matches!(err, ReloadError::NoRuntime));
+        assert_eq!(handle.state().filter, "info");
+        assert!(calls.lock().is_empty());
+    }
+
+    #[test]
+    fn test_stale_expiry_ignored() {
+        let (handle, _calls) = mock_handle();
+
+        handle.set("debug", None).unwrap();
+        let stale = handle.state.lock().generation;
+        handle.set("trace", None).unwrap();
+
+        // An expiry scheduled for an earlier change does not revert the
+        // latest one.
+        handle.expire(stale);
+        assert_eq!(handle.state().filter, "trace");
+    }
+}
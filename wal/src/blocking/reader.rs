@@ -2,6 +2,7 @@ use crate::{FileTypeIdentifier, SegmentEntry, SegmentIdBytes, SequencedWalOp};
 use byteorder::{BigEndian, ReadBytesExt};
 use crc32fast::Hasher;
 use generated_types::influxdata::iox::wal::v1::WalOpBatch as ProtoWalOpBatch;
+use object_store_encryption::{decrypt, is_encrypted, FormatError, KeyProvider};
 use prost::Message;
 use snafu::prelude::*;
 use snap::read::FrameDecoder;
@@ -9,10 +10,16 @@ use std::{
     fs::File,
     io::{self, BufReader, Read},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 #[derive(Debug)]
-pub struct ClosedSegmentFileReader<R>(R);
+pub struct ClosedSegmentFileReader<R> {
+    inner: R,
+
+    /// The keys used to decrypt encrypted entries, if any.
+    keys: Option<Arc<dyn KeyProvider>>,
+}
 
 impl ClosedSegmentFileReader<BufReader<File>> {
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
@@ -28,12 +35,25 @@ where
     R: Read,
 {
     pub fn new(f: R) -> Self {
-        Self(f)
+        Self {
+            inner: f,
+            keys: None,
+        }
+    }
+
+    /// Decrypt encrypted entries with `keys`.
+    ///
+    /// Unencrypted entries can be read regardless of whether keys are set.
+    pub fn with_encryption_keys(self, keys: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            keys: Some(keys),
+            ..self
+        }
     }
 
     fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
         let mut data = [0u8; N];
-        self.0
+        self.inner
             .read_exact(&mut data)
             .context(UnableToReadArraySnafu { length: N })?;
         Ok(data)
@@ -44,27 +64,43 @@ where
     }
 
     fn one_entry(&mut self) -> Result<Option<SegmentEntry>> {
-        let expected_checksum = match self.0.read_u32::<BigEndian>() {
+        let expected_checksum = match self.inner.read_u32::<BigEndian>() {
             Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
             other => other.context(UnableToReadChecksumSnafu)?,
         };
 
         let expected_len = self
-            .0
+            .inner
             .read_u32::<BigEndian>()
             .context(UnableToReadLengthSnafu)?
             .into();
 
-        let compressed_read = self.0.by_ref().take(expected_len);
-        let hashing_read = CrcReader::new(compressed_read);
-        let mut decompressing_read = FrameDecoder::new(hashing_read);
+        let compressed_read = self.inner.by_ref().take(expected_len);
+        let mut hashing_read = CrcReader::new(compressed_read);
 
         let mut data = Vec::with_capacity(100);
-        decompressing_read
-            .read_to_end(&mut data)
-            .context(UnableToReadDataSnafu)?;
-
-        let (actual_compressed_len, actual_checksum) = decompressing_read.into_inner().checksum();
+        let (actual_compressed_len, actual_checksum) = match &self.keys {
+            None => {
+                let mut decompressing_read = FrameDecoder::new(hashing_read);
+                decompressing_read
+                    .read_to_end(&mut data)
+                    .context(UnableToReadDataSnafu)?;
+                decompressing_read.into_inner().checksum()
+            }
+            Some(_) => {
+                // Encrypted entries can only be authenticated and decrypted
+                // as a whole, so the entry is read before it is decompressed.
+                let mut raw = Vec::with_capacity(expected_len as usize);
+                hashing_read
+                    .read_to_end(&mut raw)
+                    .context(UnableToReadDataSnafu)?;
+                let checksum = hashing_read.checksum();
+                if checksum == (expected_len, expected_checksum) {
+                    self.decompress(&raw, &mut data)?;
+                }
+                checksum
+            }
+        };
 
         ensure!(
             expected_len == actual_compressed_len,
@@ -85,6 +121,23 @@ where
         Ok(Some(SegmentEntry { data }))
     }
 
+    /// Decompress the (possibly encrypted) raw entry data into `data`.
+    fn decompress(&self, raw: &[u8], data: &mut Vec<u8>) -> Result<()> {
+        let decrypted;
+        let compressed = match &self.keys {
+            Some(keys) if is_encrypted(raw) => {
+                decrypted = decrypt(keys.as_ref(), raw).context(UnableToDecryptDataSnafu)?;
+                decrypted.as_slice()
+            }
+            _ => raw,
+        };
+
+        FrameDecoder::new(compressed)
+            .read_to_end(data)
+            .context(UnableToReadDataSnafu)?;
+        Ok(())
+    }
+
     pub fn next_batch(&mut self) -> Result<Option<Vec<SequencedWalOp>>> {
         if let Some(entry) = self.one_entry()? {
             let decoded =
@@ -175,6 +228,10 @@ pub enum Error {
         source: snap::Error,
     },
 
+    UnableToDecryptData {
+        source: FormatError,
+    },
+
     UnableToDeserializeData {
         source: prost::DecodeError,
     },
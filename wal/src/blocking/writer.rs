@@ -1,6 +1,7 @@
 use crate::{ClosedSegment, SegmentId, WriteSummary, FILE_TYPE_IDENTIFIER};
 use byteorder::{BigEndian, WriteBytesExt};
 use crc32fast::Hasher;
+use object_store_encryption::{encrypt, FormatError, KeyProvider};
 use snafu::prelude::*;
 use std::{
     fs::{File, OpenOptions},
@@ -33,12 +34,16 @@ pub struct OpenSegmentFileWriter {
     bytes_written: usize,
 
     buffer: Vec<u8>,
+
+    /// The keys used to encrypt entries, if encryption is enabled.
+    keys: Option<Arc<dyn KeyProvider>>,
 }
 
 impl OpenSegmentFileWriter {
     pub fn new_in_directory(
         dir: impl Into<PathBuf>,
         next_id_source: Arc<AtomicU64>,
+        keys: Option<Arc<dyn KeyProvider>>,
     ) -> Result<Self> {
         let id = SegmentId::new(next_id_source.fetch_add(1, Ordering::Relaxed));
         let path = crate::build_segment_path(dir, id);
@@ -67,6 +72,7 @@ impl OpenSegmentFileWriter {
             f,
             bytes_written,
             buffer: Vec::with_capacity(8 * 1204), // 8kiB initial size
+            keys,
         })
     }
 
@@ -74,6 +80,10 @@ impl OpenSegmentFileWriter {
         self.id
     }
 
+    pub fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+
     pub fn write(&mut self, data: &[u8]) -> Result<WriteSummary> {
         // Ensure the write buffer is always empty before using it.
         self.buffer.clear();
@@ -94,14 +104,34 @@ impl OpenSegmentFileWriter {
             .write_u64::<BigEndian>(0)
             .expect("cannot fail to write to buffer");
 
-        // Compress the payload into the reused buffer, recording the crc hash
-        // as it is wrote.
-        let mut encoder = snap::write::FrameEncoder::new(HasherWrapper::new(&mut self.buffer));
-        encoder.write_all(data).context(UnableToCompressDataSnafu)?;
-        let (checksum, buf) = encoder
-            .into_inner()
-            .expect("cannot fail to flush to a Vec")
-            .finalize();
+        let (checksum, buf) = match &self.keys {
+            None => {
+                // Compress the payload into the reused buffer, recording the
+                // crc hash as it is wrote.
+                let mut encoder =
+                    snap::write::FrameEncoder::new(HasherWrapper::new(&mut self.buffer));
+                encoder.write_all(data).context(UnableToCompressDataSnafu)?;
+                encoder
+                    .into_inner()
+                    .expect("cannot fail to flush to a Vec")
+                    .finalize()
+            }
+            Some(keys) => {
+                // Compress the payload, and write the encrypted result into
+                // the reused buffer, recording the crc hash of the ciphertext.
+                let mut encoder = snap::write::FrameEncoder::new(Vec::with_capacity(data.len()));
+                encoder.write_all(data).context(UnableToCompressDataSnafu)?;
+                let compressed = encoder.into_inner().expect("cannot fail to flush to a Vec");
+                let encrypted =
+                    encrypt(keys.as_ref(), &compressed).context(UnableToEncryptDataSnafu)?;
+
+                let mut hasher = HasherWrapper::new(&mut self.buffer);
+                hasher
+                    .write_all(&encrypted)
+                    .expect("cannot fail to write to buffer");
+                hasher.finalize()
+            }
+        };
 
         // Adjust the compressed length to take into account the u64 padding
         // above.
@@ -188,6 +218,10 @@ pub enum Error {
         source: io::Error,
     },
 
+    UnableToEncryptData {
+        source: FormatError,
+    },
+
     UnableToReadFileMetadata {
         source: io::Error,
     },
@@ -34,6 +34,7 @@ use generated_types::{
 use hashbrown::HashMap;
 use mutable_batch::MutableBatch;
 use mutable_batch_pb::decode::decode_database_batch;
+use object_store_encryption::KeyProvider;
 use observability_deps::tracing::info;
 use parking_lot::Mutex;
 use snafu::prelude::*;
@@ -221,6 +222,9 @@ pub struct Wal {
     next_id_source: Arc<AtomicU64>,
     buffer: Mutex<WalBuffer>,
 
+    /// The keys used to encrypt and decrypt segment entries, if any.
+    keys: Option<Arc<dyn KeyProvider>>,
+
     /// The handle to the [`Wal::flush_buffer_background_task()`] task.
     flusher_task: Mutex<Option<JoinHandle<()>>>,
 }
@@ -236,6 +240,21 @@ impl Wal {
     /// Similarly, editing or deleting files within a `Wal`'s root directory via some other
     /// mechanism is not supported.
     pub async fn new(root: impl Into<PathBuf>) -> Result<Arc<Self>> {
+        Self::new_with_keys(root, None).await
+    }
+
+    /// Creates a `Wal` instance that manages files in the specified root directory, encrypting
+    /// entries written to segment files with the current key of `keys`, if specified.
+    ///
+    /// Segment files written before encryption was enabled (or with a key that has since been
+    /// rotated out of the current position) remain readable, as long as `keys` still contains the
+    /// key they were encrypted with. Encrypted segment files cannot be read without `keys`.
+    ///
+    /// The same constraints as [`Wal::new()`] apply.
+    pub async fn new_with_keys(
+        root: impl Into<PathBuf>,
+        keys: Option<Arc<dyn KeyProvider>>,
+    ) -> Result<Arc<Self>> {
         let root = root.into();
         info!(wal_dir=?root, "Initalizing Write Ahead Log (WAL)");
         tokio::fs::create_dir_all(&root)
@@ -291,9 +310,12 @@ impl Wal {
             .map(|id| id.get() + 1)
             .unwrap_or(0);
         let next_id_source = Arc::new(AtomicU64::new(next_id));
-        let open_segment =
-            OpenSegmentFileWriter::new_in_directory(&root, Arc::clone(&next_id_source))
-                .context(UnableToCreateSegmentFileSnafu)?;
+        let open_segment = OpenSegmentFileWriter::new_in_directory(
+            &root,
+            Arc::clone(&next_id_source),
+            keys.clone(),
+        )
+        .context(UnableToCreateSegmentFileSnafu)?;
 
         let buffer = WalBuffer::new(None);
 
@@ -306,6 +328,7 @@ impl Wal {
             })),
             next_id_source,
             buffer: Mutex::new(buffer),
+            keys,
             flusher_task: Default::default(),
         };
 
@@ -329,7 +352,21 @@ impl Wal {
     /// Open a reader to a closed segment
     pub fn reader_for_segment(&self, id: SegmentId) -> Result<ClosedSegmentFileReader> {
         let path = build_segment_path(&self.root, id);
-        ClosedSegmentFileReader::from_path(path)
+        let reader = ClosedSegmentFileReader::from_path(path)?;
+        Ok(match &self.keys {
+            Some(keys) => reader.with_encryption_keys(Arc::clone(keys)),
+            None => reader,
+        })
+    }
+
+    /// Returns the number of bytes written to the currently open segment.
+    pub fn open_segment_size(&self) -> u64 {
+        self.segments
+            .lock()
+            .open_segment
+            .bytes_written()
+            .try_into()
+            .expect("bytes_written did not fit in size type")
     }
 
     /// Writes one [`SequencedWalOp`] to the buffer and returns a watch channel
@@ -344,9 +381,12 @@ impl Wal {
     /// closed segment details, including the [`SequenceNumberSet`] containing
     /// the sequence numbers of the writes within the closed segment.
     pub fn rotate(&self) -> Result<(ClosedSegment, SequenceNumberSet)> {
-        let new_open_segment =
-            OpenSegmentFileWriter::new_in_directory(&self.root, Arc::clone(&self.next_id_source))
-                .context(UnableToCreateSegmentFileSnafu)?;
+        let new_open_segment = OpenSegmentFileWriter::new_in_directory(
+            &self.root,
+            Arc::clone(&self.next_id_source),
+            self.keys.clone(),
+        )
+        .context(UnableToCreateSegmentFileSnafu)?;
 
         let mut segments = self.segments.lock();
 
@@ -584,6 +624,14 @@ impl ClosedSegmentFileReader {
 
         Ok(Self { id, file })
     }
+
+    /// Decrypt encrypted entries in the segment file with `keys`.
+    pub fn with_encryption_keys(self, keys: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            file: self.file.with_encryption_keys(keys),
+            ..self
+        }
+    }
 }
 
 impl std::fmt::Debug for ClosedSegmentFileReader {
@@ -687,6 +735,7 @@ mod tests {
         pbdata::v1::DatabaseBatch,
     };
     use mutable_batch_lp::lines_to_batches;
+    use object_store_encryption::{EncryptionKey, StaticKeyProvider};
 
     use super::*;
 
@@ -725,7 +774,11 @@ mod tests {
         wal.write_op(op3.clone());
         wal.write_op(op4.clone()).changed().await.unwrap();
 
+        let open_size = wal.open_segment_size();
+        assert!(open_size > 16);
+
         let (closed, ids) = wal.rotate().unwrap();
+        assert_eq!(closed.size(), open_size);
 
         let ops: Vec<SequencedWalOp> = wal
             .reader_for_segment(closed.id)
@@ -769,6 +822,49 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn encrypted_segments() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let keys: Arc<dyn KeyProvider> = Arc::new(StaticKeyProvider::new(
+            (1, EncryptionKey::new([42; 32])),
+            [],
+        ));
+
+        let op = |lp: &str, seq| SequencedWalOp {
+            table_write_sequence_numbers: vec![(TableId::new(0), seq)].into_iter().collect(),
+            op: WalOp::Write(test_data(lp)),
+        };
+        let op1 = op("m1,t=foo v=1i 1", 0);
+        let op2 = op("m1,t=foo v=2i 2", 1);
+
+        // Write a segment before encryption is enabled.
+        let plain = {
+            let wal = Wal::new(dir.path()).await.unwrap();
+            wal.write_op(op1.clone()).changed().await.unwrap();
+            wal.rotate().unwrap().0
+        };
+
+        let wal = Wal::new_with_keys(dir.path(), Some(Arc::clone(&keys)))
+            .await
+            .unwrap();
+        wal.write_op(op2.clone()).changed().await.unwrap();
+        let (encrypted, _) = wal.rotate().unwrap();
+
+        // Both segments are readable with the keys.
+        let read = |id| {
+            wal.reader_for_segment(id)
+                .unwrap()
+                .flat_map(|batch| batch.expect("failed to read WAL op batch"))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(read(plain.id()), vec![op1]);
+        assert_eq!(read(encrypted.id()), vec![op2]);
+
+        // The encrypted segment cannot be read without them.
+        let mut reader = ClosedSegmentFileReader::from_path(&encrypted.path).unwrap();
+        assert_matches!(reader.next(), Some(Err(Error::UnableToReadNextOps { .. })));
+    }
+
     // open wal with files that aren't segments (should log and skip)
 
     // read segment works even if last entry is truncated
@@ -786,6 +882,9 @@ mod tests {
             "Expected empty closed segments; got {closed:?}"
         );
 
+        // The open segment contains only the file header.
+        assert_eq!(wal.open_segment_size(), 16);
+
         // No writes, but rotating is totally fine
         let (closed_segment_details, ids) = wal.rotate().unwrap();
         assert_eq!(closed_segment_details.size(), 16);